@@ -1,4 +1,5 @@
 use crate::utils::output::Output;
+use code_runner::InterpreterRun;
 use sea_orm::DatabaseConnection;
 use serde_json::Value;
 
@@ -27,6 +28,15 @@ pub async fn coverage_percent_for_attempt(
     }
 }
 
+/// Coverage percent of an isolated interpreter run, read from its first coverage task like
+/// `coverage_percent_for_attempt` reads the stored outputs.
+pub fn coverage_percent_for_run(run: &InterpreterRun) -> Result<f64, String> {
+    match run.coverage_outputs.first() {
+        Some(output) => Ok(coverage_percent_from_json(&output.output)?.clamp(0.0, 100.0)),
+        None => Ok(0.0),
+    }
+}
+
 #[inline]
 pub fn coverage_fitness(percent: f64) -> f64 {
     (percent / 100.0).clamp(0.0, 1.0)
@@ -85,4 +95,27 @@ mod tests {
         let err = coverage_percent_from_json(bad).unwrap_err();
         assert!(err.contains("Failed to parse coverage JSON"));
     }
+
+    fn coverage_output(task_id: i64, output: &str) -> code_runner::TaskRunOutput {
+        code_runner::TaskRunOutput {
+            task_id,
+            task_number: task_id,
+            output: output.to_string(),
+            full_size_bytes: output.len() as u64,
+            truncated: false,
+            metrics: None,
+        }
+    }
+
+    #[test]
+    fn coverage_percent_for_run_reads_first_coverage_output() {
+        let mut run = InterpreterRun::default();
+        assert_eq!(coverage_percent_for_run(&run).unwrap(), 0.0);
+
+        run.coverage_outputs = vec![
+            coverage_output(4, r#"{ "summary": { "coverage_percent": 120 } }"#),
+            coverage_output(5, r#"{ "summary": { "coverage_percent": 10 } }"#),
+        ];
+        assert_eq!(coverage_percent_for_run(&run).unwrap(), 100.0);
+    }
 }
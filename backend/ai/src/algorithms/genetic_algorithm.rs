@@ -1,33 +1,205 @@
 use rand::seq::SliceRandom;
 use rand::{Rng, thread_rng};
 use std::collections::HashSet;
+use std::ops::Range;
 use util::execution_config::ExecutionConfig;
 use util::execution_config::{
-    CrossoverType as ExecCrossoverType, MutationType as ExecMutationType,
+    CrossoverType as ExecCrossoverType, GeneConfig as ExecGeneConfig, GeneKind as ExecGeneKind,
+    GeneRepair as ExecGeneRepair, MutationType as ExecMutationType,
+    SelectionType as ExecSelectionType,
 };
 
+/// What a gene turns into in the interpreter payload
+///
+/// Every gene is stored as one or more integer slots in the chromosome (each `bits_per_gene`
+/// wide): integer and categorical genes use one slot, string genes use a length slot followed
+/// by `max_length` character slots.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GeneKind {
+    Integer,                  // the slot value itself
+    Categorical(Vec<String>), // the value at the slot's index
+    Text {
+        min_length: usize,
+        max_length: usize,
+        alphabet: Vec<char>, // characters the string is built from
+    },
+}
+
+impl GeneKind {
+    // how many integer slots a gene of this kind takes in the chromosome
+    pub fn slot_count(&self) -> usize {
+        match self {
+            GeneKind::Integer | GeneKind::Categorical(_) => 1,
+            GeneKind::Text { max_length, .. } => 1 + max_length,
+        }
+    }
+
+    // renders decoded slots as payload text; out-of-range slots (after crossover/mutation) wrap
+    // around the value list / alphabet, and string lengths are clamped to the bounds
+    pub fn render(&self, slots: &[i32]) -> String {
+        match self {
+            GeneKind::Integer => slots[0].to_string(),
+            GeneKind::Categorical(values) => {
+                values[slots[0].rem_euclid(values.len() as i32) as usize].clone()
+            }
+            GeneKind::Text {
+                min_length,
+                max_length,
+                alphabet,
+            } => {
+                let len = (slots[0].max(0) as usize).clamp(*min_length, *max_length);
+                slots[1..=len]
+                    .iter()
+                    .map(|&c| alphabet[c.rem_euclid(alphabet.len() as i32) as usize])
+                    .collect()
+            }
+        }
+    }
+}
+
+/// What happens to a chromosome whose gene decodes outside `[min_value, max_value]` or to one
+/// of its `invalid_values` (crossover and mutation can produce any value the bits allow)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GeneRepair {
+    None,         // keep the value as decoded
+    Resample,     // replace it with a random valid value
+    Clamp,        // replace it with the nearest valid value
+    Penalty(f64), // keep it, but subtract this from the chromosome's fitness
+}
+
 /// Gene-level configuration
 #[derive(Clone)]
 pub struct GeneConfig {
-    pub min_value: i32,               // minimum valid value
-    pub max_value: i32,               // maximum valid value
+    pub min_value: i32, // minimum valid value (string genes: minimum length)
+    pub max_value: i32, // maximum valid value (string genes: maximum length)
     pub invalid_values: HashSet<i32>, // explicitly disallowed values
+    pub kind: GeneKind, // how the gene is rendered in the payload
+    pub repair: GeneRepair, // how invalid values are handled
 }
 
 impl GeneConfig {
-    // creates a new gene config
+    // creates a new integer gene config
     pub fn new(min_value: i32, max_value: i32, invalid_values: HashSet<i32>) -> Self {
         Self {
             min_value,
             max_value,
             invalid_values,
+            kind: GeneKind::Integer,
+            repair: GeneRepair::None,
         }
     }
 
+    // a gene that picks one of `values`
+    pub fn categorical(values: Vec<String>) -> Self {
+        Self {
+            min_value: 0,
+            max_value: values.len() as i32 - 1,
+            invalid_values: HashSet::new(),
+            kind: GeneKind::Categorical(values),
+            repair: GeneRepair::None,
+        }
+    }
+
+    // a gene that builds a string of `min_length..=max_length` characters from `alphabet`
+    pub fn text(min_length: usize, max_length: usize, alphabet: Vec<char>) -> Self {
+        Self {
+            min_value: min_length as i32,
+            max_value: max_length as i32,
+            invalid_values: HashSet::new(),
+            kind: GeneKind::Text {
+                min_length,
+                max_length,
+                alphabet,
+            },
+            repair: GeneRepair::None,
+        }
+    }
+
+    // handles invalid values of this gene with `repair`
+    pub fn with_repair(mut self, repair: GeneRepair) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    // converts a gene from the assignment's ExecutionConfig
+    pub fn from_exec(gene: &ExecGeneConfig) -> Self {
+        let repair = match gene.repair {
+            ExecGeneRepair::None => GeneRepair::None,
+            ExecGeneRepair::Resample => GeneRepair::Resample,
+            ExecGeneRepair::Clamp => GeneRepair::Clamp,
+            ExecGeneRepair::Penalty => GeneRepair::Penalty(gene.penalty),
+        };
+        let config = match gene.kind {
+            ExecGeneKind::Integer => Self::new(
+                gene.min_value,
+                gene.max_value,
+                gene.invalid_values.iter().copied().collect(),
+            ),
+            ExecGeneKind::Categorical => Self::categorical(gene.values.clone()),
+            ExecGeneKind::String => {
+                Self::text(gene.min_length, gene.max_length, gene.alphabet_chars())
+            }
+        };
+        config.with_repair(repair)
+    }
+
+    // whether `value` (the gene's first slot) is in range and not explicitly disallowed
+    pub fn is_valid(&self, value: i32) -> bool {
+        (self.min_value..=self.max_value).contains(&value) && !self.invalid_values.contains(&value)
+    }
+
+    // a random valid value
+    pub fn random_valid<R: Rng>(&self, rng: &mut R) -> i32 {
+        loop {
+            let candidate = rng.gen_range(self.min_value..=self.max_value);
+            if !self.invalid_values.contains(&candidate) {
+                return candidate;
+            }
+        }
+    }
+
+    // the valid value closest to `value` (ties go to the smaller one)
+    pub fn nearest_valid(&self, value: i32) -> i32 {
+        let clamped = value.clamp(self.min_value, self.max_value) as i64;
+        (0..=self.max_value.abs_diff(self.min_value) as i64)
+            .flat_map(|d| [clamped - d, clamped + d])
+            .filter_map(|v| i32::try_from(v).ok())
+            .find(|&v| self.is_valid(v))
+            .unwrap_or(clamped as i32)
+    }
+
     // calculates the number of bits needed to represent the gene value
     pub fn bits(&self) -> usize {
         ((self.max_value.abs().max(self.min_value.abs()) as f64).log2()).ceil() as usize + 1
     }
+
+    // value range of each slot: the gene's own range first, then one per string character
+    pub fn slot_ranges(&self) -> Vec<(i32, i32)> {
+        let mut ranges = vec![(self.min_value, self.max_value)];
+        if let GeneKind::Text {
+            max_length,
+            alphabet,
+            ..
+        } = &self.kind
+        {
+            ranges.extend(std::iter::repeat_n(
+                (0, alphabet.len() as i32 - 1),
+                *max_length,
+            ));
+        }
+        ranges
+    }
+
+    // draws random slot values within the slot ranges, skipping `invalid_values`
+    fn random_slots<R: Rng>(&self, rng: &mut R) -> Vec<i32> {
+        let mut slots: Vec<i32> = self
+            .slot_ranges()
+            .into_iter()
+            .map(|(min, max)| rng.gen_range(min..=max))
+            .collect();
+        slots[0] = self.random_valid(rng);
+        slots
+    }
 }
 
 /// GA-wide configuration
@@ -41,9 +213,60 @@ pub struct GAConfig {
     pub genes: Vec<GeneConfig>,        // Configuration for each gene in the chromosome
     pub crossover_type: CrossoverType, // Which crossover operator to use (one-point, two-point, uniform)
     pub mutation_type: MutationType,   // Which mutation operator to use (bit-flip, swap, scramble)
+    pub selection_type: SelectionType, // How parents are picked (roulette, tournament, rank)
+    pub tournament_size: usize,        // Candidates per tournament for tournament selection
+    pub elitism_count: usize, // Fittest chromosomes copied unchanged into the next generation (per island)
+    pub islands: usize,       // Sub-populations evolving independently (1 = a single population)
+    pub migration_interval: usize, // Generations between migrations between islands (0 = never)
+    pub migration_count: usize, // Fittest chromosomes each island sends to the next one per migration
 }
 
 impl GAConfig {
+    /// GA parameters and genes from ExecutionConfig
+    pub fn from_execution_config(config: &ExecutionConfig) -> Self {
+        let gatlam = &config.gatlam;
+
+        let crossover_type = match gatlam.crossover_type {
+            ExecCrossoverType::OnePoint => CrossoverType::OnePoint,
+            ExecCrossoverType::TwoPoint => CrossoverType::TwoPoint,
+            ExecCrossoverType::Uniform => CrossoverType::Uniform,
+        };
+
+        let mutation_type = match gatlam.mutation_type {
+            ExecMutationType::BitFlip => MutationType::BitFlip,
+            ExecMutationType::Swap => MutationType::Swap,
+            ExecMutationType::Scramble => MutationType::Scramble,
+        };
+
+        let selection_type = match gatlam.selection_type {
+            ExecSelectionType::Roulette => SelectionType::Roulette,
+            ExecSelectionType::Tournament => SelectionType::Tournament,
+            ExecSelectionType::Rank => SelectionType::Rank,
+        };
+
+        // Convert GeneConfig (integer, categorical or string genes)
+        let genes = gatlam.genes.iter().map(GeneConfig::from_exec).collect();
+
+        GAConfig::new(
+            gatlam.population_size,
+            gatlam.number_of_generations,
+            gatlam.selection_size,
+            gatlam.reproduction_probability,
+            gatlam.crossover_probability,
+            gatlam.mutation_probability,
+            genes,
+            crossover_type,
+            mutation_type,
+        )
+        .with_selection(selection_type, gatlam.tournament_size)
+        .with_elitism(gatlam.elitism_count)
+        .with_islands(
+            gatlam.islands,
+            gatlam.migration_interval,
+            gatlam.migration_count,
+        )
+    }
+
     // constructor with above fields
     pub fn new(
         population_size: usize,
@@ -66,23 +289,127 @@ impl GAConfig {
             genes,
             crossover_type,
             mutation_type,
+            selection_type: SelectionType::Roulette,
+            tournament_size: 3,
+            elitism_count: 0,
+            islands: 1,
+            migration_interval: 0,
+            migration_count: 0,
         }
     }
 
-    // calculates the number of bits needed to represent all genes in the chromosome
-    // this is the sum of bits for each gene, it is used to determine the length of the chromosome bit string
+    // picks parents with `selection_type`; `tournament_size` only matters for tournaments
+    pub fn with_selection(mut self, selection_type: SelectionType, tournament_size: usize) -> Self {
+        self.selection_type = selection_type;
+        self.tournament_size = tournament_size;
+        self
+    }
+
+    // copies the `elitism_count` fittest chromosomes into each new generation unchanged
+    pub fn with_elitism(mut self, elitism_count: usize) -> Self {
+        self.elitism_count = elitism_count;
+        self
+    }
+
+    // splits the population into `islands` sub-populations; every `migration_interval`
+    // generations each island sends its `migration_count` fittest chromosomes to the next one
+    pub fn with_islands(
+        mut self,
+        islands: usize,
+        migration_interval: usize,
+        migration_count: usize,
+    ) -> Self {
+        self.islands = islands;
+        self.migration_interval = migration_interval;
+        self.migration_count = migration_count;
+        self
+    }
+
+    // calculates the number of bits needed to represent every slot of every gene
+    // all slots share this width, it is used to determine the length of the chromosome bit string
     pub fn bits(&self) -> usize {
         let mut min_value = i32::MAX;
         let mut max_value = i32::MIN;
 
-        // find global min and max values across all genes
+        // find global min and max values across all slots
+        for (min, max) in self.genes.iter().flat_map(GeneConfig::slot_ranges) {
+            min_value = min_value.min(min);
+            max_value = max_value.max(max);
+        }
+
+        // calculate bits needed to represent the largest absolute value,
+        // at least a sign bit and one magnitude bit
+        (((max_value.abs().max(min_value.abs()) as f64).log2()).ceil() as usize + 1).max(2)
+    }
+
+    // total integer slots in a chromosome
+    pub fn slot_count(&self) -> usize {
+        self.genes.iter().map(|g| g.kind.slot_count()).sum()
+    }
+
+    // each gene with the index of its first slot in the decoded chromosome
+    pub fn gene_offsets(&self) -> impl Iterator<Item = (usize, &GeneConfig)> {
+        self.genes.iter().scan(0, |offset, gene| {
+            let start = *offset;
+            *offset += gene.kind.slot_count();
+            Some((start, gene))
+        })
+    }
+
+    // builds the interpreter payload from a chromosome's decoded slots:
+    // each gene rendered by its kind, comma-separated
+    pub fn payload(&self, decoded: &[i32]) -> String {
+        self.gene_offsets()
+            .map(|(offset, gene)| {
+                gene.kind
+                    .render(&decoded[offset..offset + gene.kind.slot_count()])
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    // fitness to subtract for a chromosome's invalid genes (`GeneRepair::Penalty` genes only)
+    pub fn penalty(&self, decoded: &[i32]) -> f64 {
+        self.gene_offsets()
+            .filter_map(|(offset, gene)| match gene.repair {
+                GeneRepair::Penalty(p) if !gene.is_valid(decoded[offset]) => Some(p),
+                _ => None,
+            })
+            .sum()
+    }
+
+    // a random chromosome, each gene generated from its specific GeneConfig
+    // (valid values only, one or more slots per gene)
+    pub fn random_chromosome<R: Rng>(&self, rng: &mut R) -> Chromosome {
+        let bits_per_gene = self.bits();
+        let mut gene_bits = Vec::with_capacity(bits_per_gene * self.slot_count());
         for gene_config in &self.genes {
-            min_value = min_value.min(gene_config.min_value);
-            max_value = max_value.max(gene_config.max_value);
+            for slot in gene_config.random_slots(rng) {
+                gene_bits.extend(encode_gene(slot, bits_per_gene)); // encode and append bits
+            }
         }
+        Chromosome::new(gene_bits)
+    }
 
-        // calculate bits needed to represent the largest absolute value
-        ((max_value.abs().max(min_value.abs()) as f64).log2()).ceil() as usize + 1
+    // re-encodes invalid genes of `chrom` with a valid value, per gene `repair` strategy
+    pub fn repair<R: Rng>(&self, chrom: &mut Chromosome, rng: &mut R) {
+        let bits = self.bits();
+        let decoded = crate::decode_genes(chrom.genes(), bits);
+        for (offset, gene) in self.gene_offsets() {
+            let value = decoded[offset];
+            if gene.is_valid(value) {
+                continue;
+            }
+            let repaired = match gene.repair {
+                GeneRepair::Resample => gene.random_valid(rng),
+                GeneRepair::Clamp => gene.nearest_valid(value),
+                GeneRepair::None | GeneRepair::Penalty(_) => continue,
+            };
+            chrom.genes_mut().splice(
+                offset * bits..(offset + 1) * bits,
+                encode_gene(repaired, bits),
+            );
+        }
     }
 }
 
@@ -93,6 +420,13 @@ pub enum CrossoverType {
     Uniform,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SelectionType {
+    Roulette,
+    Tournament,
+    Rank,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum MutationType {
     BitFlip,
@@ -134,43 +468,7 @@ pub struct GeneticAlgorithm {
 impl GeneticAlgorithm {
     /// Create a new GA instance using parameters from ExecutionConfig
     pub fn from_execution_config(config: &ExecutionConfig) -> Self {
-        let gatlam = &config.gatlam;
-
-        let crossover_type = match gatlam.crossover_type {
-            ExecCrossoverType::OnePoint => CrossoverType::OnePoint,
-            ExecCrossoverType::TwoPoint => CrossoverType::TwoPoint,
-            ExecCrossoverType::Uniform => CrossoverType::Uniform,
-        };
-
-        let mutation_type = match gatlam.mutation_type {
-            ExecMutationType::BitFlip => MutationType::BitFlip,
-            ExecMutationType::Swap => MutationType::Swap,
-            ExecMutationType::Scramble => MutationType::Scramble,
-        };
-
-        // Convert GeneConfig if needed
-        let genes = gatlam
-            .genes
-            .iter()
-            .map(|g| {
-                let invalids = HashSet::new(); // or copy invalids if available
-                GeneConfig::new(g.min_value, g.max_value, invalids)
-            })
-            .collect();
-
-        let ga_config = GAConfig::new(
-            gatlam.population_size,
-            gatlam.number_of_generations,
-            gatlam.selection_size,
-            gatlam.reproduction_probability,
-            gatlam.crossover_probability,
-            gatlam.mutation_probability,
-            genes,
-            crossover_type,
-            mutation_type,
-        );
-
-        Self::new(ga_config)
+        Self::new(GAConfig::from_execution_config(config))
     }
 
     pub fn new(config: GAConfig) -> Self {
@@ -186,37 +484,106 @@ impl GeneticAlgorithm {
 
     /// Evolve one generation using externally computed fitness scores.
     /// `fitness_scores.len()` must equal `self.population.len()`.
+    ///
+    /// With several islands, each island evolves from its own chromosomes only, and every
+    /// `migration_interval` generations the islands exchange their fittest chromosomes.
     pub fn step_with_fitness(&mut self, fitness_scores: &[f64]) {
-        let next_gen = self.build_next_generation(fitness_scores);
-        self.population = next_gen;
-        self.generation += 1;
-    }
-
-    fn build_next_generation(&self, fitness_scores: &[f64]) -> Vec<Chromosome> {
         assert_eq!(
             fitness_scores.len(),
             self.population.len(),
             "fitness/pop size mismatch"
         );
-        let total_fitness: f64 = fitness_scores.iter().sum();
-
+        let islands = self.island_ranges();
         let mut next_gen = Vec::with_capacity(self.population.len());
+        for island in &islands {
+            next_gen.extend(self.build_next_generation(
+                &self.population[island.clone()],
+                &fitness_scores[island.clone()],
+            ));
+        }
+        self.generation += 1;
+
+        let interval = self.config.migration_interval;
+        if islands.len() > 1 && interval > 0 && self.generation.is_multiple_of(interval) {
+            self.migrate(&islands, fitness_scores, &mut next_gen);
+        }
+        self.population = next_gen;
+    }
+
+    /// Index ranges of the islands in `population()`, as equal in size as possible.
+    /// The population is always split into contiguous islands, so callers can evaluate it
+    /// as one flat list (and in parallel) whatever the island count.
+    pub fn island_ranges(&self) -> Vec<Range<usize>> {
+        let len = self.population.len();
+        let count = self.config.islands.clamp(1, len.max(1));
+        let (base, extra) = (len / count, len % count);
+        let mut start = 0;
+        (0..count)
+            .map(|i| {
+                let end = start + base + usize::from(i < extra);
+                let range = start..end;
+                start = end;
+                range
+            })
+            .collect()
+    }
+
+    // ring migration: the fittest chromosomes of each island (by the fitness of the generation
+    // just evaluated) replace the last children of the next island in `next_gen`, leaving
+    // that island's elites alone
+    fn migrate(&self, islands: &[Range<usize>], fitness: &[f64], next_gen: &mut [Chromosome]) {
+        for (i, from) in islands.iter().enumerate() {
+            let to = &islands[(i + 1) % islands.len()];
+            let count = self
+                .config
+                .migration_count
+                .min(from.len())
+                .min(to.len().saturating_sub(self.config.elitism_count));
+            let emigrants = Self::indices_by_fitness(&fitness[from.clone()])
+                .into_iter()
+                .take(count)
+                .map(|j| self.population[from.start + j].clone());
+            for (slot, chrom) in (to.end - count..to.end).zip(emigrants) {
+                next_gen[slot] = chrom;
+            }
+        }
+    }
+
+    fn build_next_generation(
+        &self,
+        population: &[Chromosome],
+        fitness_scores: &[f64],
+    ) -> Vec<Chromosome> {
+        // rank selection spins the roulette wheel over ranking positions instead of raw fitness
+        let weights = match self.config.selection_type {
+            SelectionType::Rank => Self::rank_weights(fitness_scores),
+            _ => fitness_scores.to_vec(),
+        };
+        let total_weight: f64 = weights.iter().sum();
+
+        let mut next_gen = Vec::with_capacity(population.len());
         let mut rng = thread_rng();
 
-        // generate the next generation
-        // using roulette wheel selection and crossover/mutation
-        for _ in 0..self.population.len() {
+        // elitism: the fittest chromosomes go through untouched
+        let elites = self.config.elitism_count.min(population.len());
+        for i in Self::indices_by_fitness(fitness_scores)
+            .into_iter()
+            .take(elites)
+        {
+            next_gen.push(population[i].clone());
+        }
+
+        // fill the rest of the generation with selection and crossover/mutation
+        while next_gen.len() < population.len() {
             // with a probability, select two parents and crossover
             // otherwise, clone one parent without crossover
-            // this is a form of elitism where some chromosomes are directly passed to the next generation
-            // this is done to maintain diversity in the population and to ensure that the best solutions are not lost
-            // roulette wheel selection based on fitness scores
+            // this keeps some selected chromosomes intact to maintain diversity in the population
             let mut child = if rng.gen_range(0.0..1.0) < self.config.reproduction_probability {
-                let p1 = Self::roulette(&self.population, fitness_scores, total_fitness);
-                let p2 = Self::roulette(&self.population, fitness_scores, total_fitness);
+                let p1 = self.select(population, fitness_scores, &weights, total_weight);
+                let p2 = self.select(population, fitness_scores, &weights, total_weight);
                 Self::crossover(&p1, &p2, self.config.crossover_type)
             } else {
-                let p = Self::roulette(&self.population, fitness_scores, total_fitness);
+                let p = self.select(population, fitness_scores, &weights, total_weight);
                 Chromosome::new(p.genes().clone())
             };
 
@@ -228,6 +595,8 @@ impl GeneticAlgorithm {
                     self.config.mutation_probability,
                 );
             }
+            // repair genes that now decode out of range or to invalid values
+            self.config.repair(&mut child, &mut rng);
             // add the child to the next generation
             next_gen.push(child);
         }
@@ -237,33 +606,63 @@ impl GeneticAlgorithm {
 
     fn initialize_population(config: &GAConfig) -> Vec<Chromosome> {
         let mut rng = thread_rng(); // random number generator
-        let mut pop = Vec::with_capacity(config.population_size); // allocate space for population
-
-        let num_genes = config.genes.len();
-        let bits_per_gene = config.bits();
 
         // Create each individual
-        for _ in 0..config.population_size {
-            let mut gene_bits = Vec::with_capacity(bits_per_gene * num_genes);
-
-            // Generate each gene based on its specific GeneConfig
-            for gene_config in &config.genes {
-                let gene;
-                // Retry until we get a valid gene (not in invalid_values)
-                loop {
-                    let candidate = rng.gen_range(gene_config.min_value..=gene_config.max_value);
-                    if !gene_config.invalid_values.contains(&candidate) {
-                        gene = candidate;
-                        break;
-                    }
-                }
-                gene_bits.extend(encode_gene(gene, bits_per_gene)); // encode and append bits
+        (0..config.population_size)
+            .map(|_| config.random_chromosome(&mut rng))
+            .collect()
+    }
+
+    // picks one parent with the configured selection type
+    // `weights` are the roulette weights: fitness for roulette selection, ranks for rank selection
+    fn select(
+        &self,
+        population: &[Chromosome],
+        fitness: &[f64],
+        weights: &[f64],
+        total_weight: f64,
+    ) -> Chromosome {
+        match self.config.selection_type {
+            SelectionType::Tournament => {
+                Self::tournament(population, fitness, self.config.tournament_size)
             }
+            SelectionType::Roulette | SelectionType::Rank => {
+                Self::roulette(population, weights, total_weight)
+            }
+        }
+    }
+
+    // population indices ordered from fittest to least fit
+    fn indices_by_fitness(fitness: &[f64]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..fitness.len()).collect();
+        order.sort_by(|&a, &b| fitness[b].total_cmp(&fitness[a]));
+        order
+    }
 
-            pop.push(Chromosome::new(gene_bits)); // Add to population
+    // rank weights: the least fit chromosome gets 1, the fittest gets population size
+    fn rank_weights(fitness: &[f64]) -> Vec<f64> {
+        let mut weights = vec![0.0; fitness.len()];
+        for (position, i) in Self::indices_by_fitness(fitness)
+            .into_iter()
+            .rev()
+            .enumerate()
+        {
+            weights[i] = (position + 1) as f64;
         }
+        weights
+    }
 
-        pop
+    // picks `size` chromosomes at random (with replacement) and returns the fittest of them
+    fn tournament(population: &[Chromosome], fitness: &[f64], size: usize) -> Chromosome {
+        let mut rng = thread_rng();
+        let mut best = rng.gen_range(0..population.len());
+        for _ in 1..size.max(1) {
+            let candidate = rng.gen_range(0..population.len());
+            if fitness[candidate] > fitness[best] {
+                best = candidate;
+            }
+        }
+        population[best].clone()
     }
 
     fn roulette(population: &[Chromosome], fitness: &[f64], total: f64) -> Chromosome {
@@ -575,4 +974,348 @@ mod tests {
         assert_eq!(ga.population().len(), pop_len);
         assert_eq!(ga.generation(), 1);
     }
+
+    // --- Selection, elitism & convergence
+
+    #[test]
+    fn rank_weights_follow_fitness_order() {
+        let w = GeneticAlgorithm::rank_weights(&[3.0, 10.0, -1.0, 5.0]);
+        assert_eq!(w, vec![2.0, 4.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn large_tournament_picks_the_fittest() {
+        let population: Vec<Chromosome> =
+            (0..4).map(|i| Chromosome::new(vec![i == 2; 4])).collect();
+        let fitness = [1.0, 0.5, 9.0, 2.0];
+        for _ in 0..20 {
+            let winner = GeneticAlgorithm::tournament(&population, &fitness, 200);
+            assert_eq!(winner.genes(), population[2].genes());
+        }
+    }
+
+    /// One point per set bit.
+    fn onemax(c: &Chromosome) -> f64 {
+        c.genes().iter().filter(|&&b| b).count() as f64
+    }
+
+    fn onemax_ga(
+        selection: SelectionType,
+        crossover: CrossoverType,
+        elitism: usize,
+    ) -> GeneticAlgorithm {
+        // 8 genes x 5 bits = 40 bits per chromosome
+        let genes = vec![GeneConfig::new(-15, 15, HashSet::new()); 8];
+        let cfg = GAConfig::new(
+            30,
+            30,
+            10,
+            0.9,
+            0.8,
+            0.05,
+            genes,
+            crossover,
+            MutationType::BitFlip,
+        )
+        .with_selection(selection, 3)
+        .with_elitism(elitism);
+        GeneticAlgorithm::new(cfg)
+    }
+
+    /// Mean OneMax fitness of the final population, averaged over a few runs.
+    fn onemax_after_evolving(
+        selection: SelectionType,
+        crossover: CrossoverType,
+        elitism: usize,
+    ) -> f64 {
+        let runs = 5;
+        let mut total = 0.0;
+        for _ in 0..runs {
+            let mut ga = onemax_ga(selection, crossover, elitism);
+            for _ in 0..30 {
+                let fitness: Vec<f64> = ga.population().iter().map(onemax).collect();
+                ga.step_with_fitness(&fitness);
+            }
+            let fitness: Vec<f64> = ga.population().iter().map(onemax).collect();
+            total += fitness.iter().sum::<f64>() / fitness.len() as f64;
+        }
+        total / runs as f64
+    }
+
+    #[test]
+    fn tournament_and_rank_converge_faster_than_roulette() {
+        let roulette = onemax_after_evolving(SelectionType::Roulette, CrossoverType::OnePoint, 0);
+        let tournament =
+            onemax_after_evolving(SelectionType::Tournament, CrossoverType::OnePoint, 0);
+        let rank = onemax_after_evolving(SelectionType::Rank, CrossoverType::OnePoint, 0);
+
+        // A random 40-bit chromosome scores about 20.
+        assert!(tournament > 30.0, "tournament reached {tournament}");
+        assert!(
+            tournament > roulette && rank > roulette,
+            "roulette {roulette}, tournament {tournament}, rank {rank}"
+        );
+    }
+
+    #[test]
+    fn every_crossover_type_improves_onemax() {
+        for crossover in [
+            CrossoverType::OnePoint,
+            CrossoverType::TwoPoint,
+            CrossoverType::Uniform,
+        ] {
+            let reached = onemax_after_evolving(SelectionType::Tournament, crossover, 1);
+            assert!(reached > 30.0, "{crossover:?} reached {reached}");
+        }
+    }
+
+    #[test]
+    fn elitism_never_loses_the_best_chromosome() {
+        let mut ga = onemax_ga(SelectionType::Roulette, CrossoverType::Uniform, 2);
+        let mut best = 0.0;
+        for _ in 0..20 {
+            let fitness: Vec<f64> = ga.population().iter().map(onemax).collect();
+            let generation_best = fitness.iter().cloned().fold(f64::MIN, f64::max);
+            assert!(
+                generation_best >= best,
+                "best fell from {best} to {generation_best}"
+            );
+            best = generation_best;
+
+            let elite = GeneticAlgorithm::indices_by_fitness(&fitness)[0];
+            let elite_genes = ga.population()[elite].genes().clone();
+            ga.step_with_fitness(&fitness);
+            assert_eq!(ga.population()[0].genes(), &elite_genes);
+        }
+    }
+
+    // --- Island model
+
+    /// Two islands of 4: island 0 all-zero chromosomes, island 1 all-one chromosomes.
+    /// Children are copies of their parents (no crossover or mutation).
+    fn two_island_ga(migration_interval: usize) -> GeneticAlgorithm {
+        let genes = vec![GeneConfig::new(-7, 7, HashSet::new()); 2];
+        let cfg = GAConfig::new(
+            8,
+            10,
+            4,
+            0.0,
+            0.0,
+            0.0,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        )
+        .with_selection(SelectionType::Tournament, 4)
+        .with_islands(2, migration_interval, 1);
+        let mut ga = GeneticAlgorithm::new(cfg);
+        let len = ga.population()[0].genes().len();
+        for (i, chrom) in ga.population.iter_mut().enumerate() {
+            chrom.set_genes(vec![i >= 4; len]);
+        }
+        ga
+    }
+
+    fn all_ones(c: &Chromosome) -> bool {
+        c.genes().iter().all(|&b| b)
+    }
+
+    #[test]
+    fn island_ranges_split_the_population_evenly() {
+        let genes = vec![GeneConfig::new(0, 3, HashSet::new())];
+        let cfg = GAConfig::new(
+            10,
+            1,
+            2,
+            0.5,
+            0.5,
+            0.1,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        );
+        let ga = GeneticAlgorithm::new(cfg.with_islands(3, 1, 1));
+        assert_eq!(ga.island_ranges(), vec![0..4, 4..7, 7..10]);
+
+        let genes = vec![GeneConfig::new(0, 3, HashSet::new())];
+        let cfg = GAConfig::new(
+            2,
+            1,
+            2,
+            0.5,
+            0.5,
+            0.1,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        );
+        let ga = GeneticAlgorithm::new(cfg.with_islands(5, 1, 1));
+        assert_eq!(ga.island_ranges(), vec![0..1, 1..2]);
+    }
+
+    #[test]
+    fn islands_evolve_in_isolation_without_migration() {
+        let mut ga = two_island_ga(0);
+        for _ in 0..3 {
+            let fitness: Vec<f64> = ga.population().iter().map(onemax).collect();
+            ga.step_with_fitness(&fitness);
+        }
+        let ones: Vec<bool> = ga.population().iter().map(all_ones).collect();
+        assert_eq!(ones, [false, false, false, false, true, true, true, true]);
+    }
+
+    #[test]
+    fn migration_sends_each_islands_best_to_the_next_island() {
+        let mut ga = two_island_ga(2);
+
+        let fitness: Vec<f64> = ga.population().iter().map(onemax).collect();
+        ga.step_with_fitness(&fitness);
+        let ones: Vec<bool> = ga.population().iter().map(all_ones).collect();
+        assert_eq!(ones, [false, false, false, false, true, true, true, true]);
+
+        let fitness: Vec<f64> = ga.population().iter().map(onemax).collect();
+        ga.step_with_fitness(&fitness);
+        let ones: Vec<bool> = ga.population().iter().map(all_ones).collect();
+        assert_eq!(ones, [false, false, false, true, true, true, true, false]);
+    }
+
+    // --- Categorical & string genes
+
+    fn mixed_config() -> GAConfig {
+        let genes = vec![
+            GeneConfig::new(-3, 3, HashSet::new()),
+            GeneConfig::categorical(vec!["push".into(), "pop".into(), "peek".into()]),
+            GeneConfig::text(1, 3, vec!['a', 'b']),
+        ];
+        GAConfig::new(
+            12,
+            1,
+            4,
+            0.9,
+            0.8,
+            0.05,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        )
+    }
+
+    #[test]
+    fn payload_renders_each_gene_kind() {
+        let cfg = mixed_config();
+        // integer, categorical index, string length + 3 character slots
+        assert_eq!(cfg.slot_count(), 6);
+        assert_eq!(cfg.payload(&[-2, 1, 2, 0, 1, 1]), "-2,pop,ab");
+        // out-of-range slots wrap around the values/alphabet, lengths are clamped
+        assert_eq!(cfg.payload(&[3, 4, 7, 3, -1, 0]), "3,pop,bba");
+        assert_eq!(cfg.payload(&[0, -1, -5, 1, 0, 0]), "0,peek,b");
+    }
+
+    #[test]
+    fn population_encodes_every_slot_within_range() {
+        let ga = GeneticAlgorithm::new(mixed_config());
+        let bpg = ga.bits_per_gene();
+        assert_eq!(bpg, 3); // max abs = 3 -> 2 magnitude bits + sign
+        for chrom in ga.population() {
+            assert_eq!(chrom.genes().len(), 6 * bpg);
+            let slots: Vec<i32> = chrom.genes().chunks(bpg).map(test_decode_gene).collect();
+            assert!((-3..=3).contains(&slots[0]));
+            assert!((0..=2).contains(&slots[1]));
+            assert!((1..=3).contains(&slots[2]));
+            assert!(slots[3..].iter().all(|c| (0..=1).contains(c)));
+
+            let payload = ga.config().payload(&slots);
+            let parts: Vec<&str> = payload.split(',').collect();
+            assert!(["push", "pop", "peek"].contains(&parts[1]));
+            assert_eq!(parts[2].len(), slots[2] as usize);
+        }
+    }
+
+    #[test]
+    fn ga_bits_has_room_for_a_magnitude_bit() {
+        let genes = vec![GeneConfig::categorical(vec!["on".into(), "off".into()])];
+        let cfg = GAConfig::new(
+            2,
+            1,
+            2,
+            0.9,
+            0.8,
+            0.05,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        );
+        assert_eq!(cfg.bits(), 2);
+    }
+
+    // --- Invalid-gene repair & penalty
+
+    #[test]
+    fn nearest_and_random_valid_values_skip_invalids() {
+        let g = GeneConfig::new(0, 6, hs(&[3, 4, 6]));
+        assert_eq!(g.nearest_valid(4), 5);
+        assert_eq!(g.nearest_valid(3), 2);
+        assert_eq!(g.nearest_valid(-7), 0);
+        assert_eq!(g.nearest_valid(9), 5);
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            assert!(g.is_valid(g.random_valid(&mut rng)));
+        }
+    }
+
+    #[test]
+    fn penalty_counts_only_penalized_invalid_genes() {
+        let genes = vec![
+            GeneConfig::new(0, 3, hs(&[2])).with_repair(GeneRepair::Penalty(0.25)),
+            GeneConfig::new(0, 3, HashSet::new()).with_repair(GeneRepair::Penalty(0.5)),
+            GeneConfig::new(0, 3, HashSet::new()),
+        ];
+        let cfg = GAConfig::new(
+            2,
+            1,
+            2,
+            0.9,
+            0.8,
+            0.05,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        );
+        assert_eq!(cfg.penalty(&[1, 3, 7]), 0.0);
+        assert_eq!(cfg.penalty(&[2, 3, 7]), 0.25);
+        assert_eq!(cfg.penalty(&[2, -1, 0]), 0.75);
+    }
+
+    #[test]
+    fn resample_and_clamp_keep_every_generation_valid() {
+        for repair in [GeneRepair::Resample, GeneRepair::Clamp] {
+            // 5 bits per gene can decode -15..=15, far outside the valid ranges
+            let genes = vec![
+                GeneConfig::new(0, 15, hs(&[7, 8])).with_repair(repair),
+                GeneConfig::new(-15, -10, HashSet::new()).with_repair(repair),
+            ];
+            let cfg = GAConfig::new(
+                20,
+                10,
+                5,
+                0.9,
+                0.8,
+                1.0,
+                genes,
+                CrossoverType::Uniform,
+                MutationType::BitFlip,
+            );
+            let mut ga = GeneticAlgorithm::new(cfg);
+            let bpg = ga.bits_per_gene();
+            for _ in 0..10 {
+                ga.step_with_fitness(&[1.0; 20]);
+                for chrom in ga.population() {
+                    let slots: Vec<i32> = chrom.genes().chunks(bpg).map(test_decode_gene).collect();
+                    for ((_, gene), value) in ga.config().gene_offsets().zip(&slots) {
+                        assert!(gene.is_valid(*value), "{repair:?} left {value}");
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,3 +1,4 @@
 pub mod code_coverage;
 pub mod genetic_algorithm;
+pub mod multi_objective;
 pub mod rng;
@@ -0,0 +1,155 @@
+//! Multi-objective scoring for GATLAM runs that optimize code coverage and the property
+//! score together (`GATLAM::objective` = `weighted_sum` / `pareto`).
+//!
+//! Both objectives are in `[0, 1]` and higher is better, so they can be mixed or compared
+//! directly. The GA itself still needs one scalar per chromosome; `MultiObjective::scores`
+//! produces it, and `ParetoArchive` keeps the non-dominated payloads seen across the run.
+
+use util::execution_config::{GATLAM, GaObjective};
+
+/// The two objectives of a chromosome.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Objectives {
+    /// Coverage fitness (`coverage_fitness` of the coverage percent).
+    pub coverage: f64,
+    /// Omega-weighted property score from `Components`.
+    pub properties: f64,
+}
+
+impl Objectives {
+    /// At least as good on both objectives and strictly better on one.
+    pub fn dominates(&self, other: &Objectives) -> bool {
+        self.coverage >= other.coverage
+            && self.properties >= other.properties
+            && (self.coverage > other.coverage || self.properties > other.properties)
+    }
+}
+
+/// How a population's objectives are turned into GA fitness scores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiObjective {
+    pub objective: GaObjective,
+    pub coverage_weight: f64,
+}
+
+impl MultiObjective {
+    pub fn from_gatlam(gatlam: &GATLAM) -> Self {
+        Self {
+            objective: gatlam.objective,
+            coverage_weight: gatlam.coverage_weight.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Fitness per chromosome, in population order.
+    ///
+    /// - `properties`: the property score alone
+    /// - `weighted_sum`: `coverage_weight * coverage + (1 - coverage_weight) * properties`
+    /// - `pareto`: `1 / (1 + n)` where `n` is how many chromosomes of the population dominate
+    ///   it, so the whole non-dominated front scores 1.0
+    pub fn scores(&self, points: &[Objectives]) -> Vec<f64> {
+        match self.objective {
+            GaObjective::Properties => points.iter().map(|p| p.properties).collect(),
+            GaObjective::WeightedSum => points
+                .iter()
+                .map(|p| {
+                    self.coverage_weight * p.coverage + (1.0 - self.coverage_weight) * p.properties
+                })
+                .collect(),
+            GaObjective::Pareto => points
+                .iter()
+                .map(|p| {
+                    let dominated_by = points.iter().filter(|q| q.dominates(p)).count();
+                    1.0 / (1.0 + dominated_by as f64)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Non-dominated payloads seen so far in a run.
+#[derive(Debug, Default)]
+pub struct ParetoArchive {
+    entries: Vec<(Objectives, String)>,
+}
+
+impl ParetoArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `payload` unless an archived entry dominates or equals it; archived entries it
+    /// dominates are dropped. Returns whether it was added.
+    pub fn insert(&mut self, objectives: Objectives, payload: &str) -> bool {
+        if self
+            .entries
+            .iter()
+            .any(|(o, _)| o.dominates(&objectives) || *o == objectives)
+        {
+            return false;
+        }
+        self.entries.retain(|(o, _)| !objectives.dominates(o));
+        self.entries.push((objectives, payload.to_string()));
+        true
+    }
+
+    /// The archived front, in insertion order.
+    pub fn entries(&self) -> &[(Objectives, String)] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn obj(coverage: f64, properties: f64) -> Objectives {
+        Objectives {
+            coverage,
+            properties,
+        }
+    }
+
+    #[test]
+    fn scores_follow_the_objective() {
+        let points = [obj(1.0, 0.0), obj(0.5, 0.5), obj(0.25, 0.25)];
+
+        let weighted = MultiObjective {
+            objective: GaObjective::WeightedSum,
+            coverage_weight: 0.75,
+        };
+        assert_eq!(weighted.scores(&points), vec![0.75, 0.5, 0.25]);
+
+        let pareto = MultiObjective {
+            objective: GaObjective::Pareto,
+            coverage_weight: 0.75,
+        };
+        assert_eq!(pareto.scores(&points), vec![1.0, 1.0, 0.5]);
+
+        let properties = MultiObjective {
+            objective: GaObjective::Properties,
+            coverage_weight: 0.75,
+        };
+        assert_eq!(properties.scores(&points), vec![0.0, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn archive_keeps_only_the_non_dominated_front() {
+        let mut archive = ParetoArchive::new();
+        assert!(archive.insert(obj(0.5, 0.5), "1,1"));
+        assert!(!archive.insert(obj(0.25, 0.5), "2,2"));
+        assert!(!archive.insert(obj(0.5, 0.5), "3,3"));
+        assert!(archive.insert(obj(1.0, 0.0), "4,4"));
+        assert!(archive.insert(obj(0.5, 0.75), "5,5"));
+
+        let payloads: Vec<&str> = archive.entries().iter().map(|(_, p)| p.as_str()).collect();
+        assert_eq!(payloads, ["4,4", "5,5"]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::algorithms::genetic_algorithm::GeneKind;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::collections::HashSet;
@@ -7,6 +8,8 @@ pub struct GeneConfig {
     pub min_value: i32,
     pub max_value: i32,
     pub invalid_values: HashSet<i32>,
+    /// How the drawn value is rendered; string genes draw their length from the value range.
+    pub kind: GeneKind,
 }
 
 impl GeneConfig {
@@ -29,14 +32,15 @@ impl RandomGenomeGenerator {
     pub fn generate_string(&mut self, configs: &[GeneConfig]) -> String {
         let mut values = Vec::with_capacity(configs.len());
         for cfg in configs {
-            let v = self.random_gene_value(cfg);
-            values.push(v);
+            let mut slots = vec![self.random_gene_value(cfg)];
+            if let GeneKind::Text { alphabet, .. } = &cfg.kind {
+                for _ in 0..slots[0] {
+                    slots.push(self.rng.gen_range(0..alphabet.len() as i32));
+                }
+            }
+            values.push(cfg.kind.render(&slots));
         }
-        values
-            .iter()
-            .map(|v| v.to_string())
-            .collect::<Vec<_>>()
-            .join(",")
+        values.join(",")
     }
 
     fn random_gene_value(&mut self, cfg: &GeneConfig) -> i32 {
@@ -59,6 +63,7 @@ mod tests {
             min_value: min,
             max_value: max,
             invalid_values: set,
+            kind: GeneKind::Integer,
         }
     }
 
@@ -148,6 +153,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_categorical_and_text_genes() {
+        let command = GeneConfig {
+            kind: GeneKind::Categorical(vec!["push".into(), "pop".into()]),
+            ..make_config(0, 1, &[])
+        };
+        let word = GeneConfig {
+            kind: GeneKind::Text {
+                min_length: 2,
+                max_length: 3,
+                alphabet: vec!['x', 'y'],
+            },
+            ..make_config(2, 3, &[])
+        };
+        let mut generation = RandomGenomeGenerator::new(11);
+        for _ in 0..10 {
+            let s = generation.generate_string(&[command.clone(), word.clone()]);
+            let (cmd, text) = s.split_once(',').unwrap();
+            assert!(cmd == "push" || cmd == "pop");
+            assert!((2..=3).contains(&text.len()));
+            assert!(text.chars().all(|c| c == 'x' || c == 'y'));
+        }
+    }
+
     #[test]
     fn test_deterministic_seed() {
         let cfgs = vec![make_config(0, 3, &[]), make_config(5, 7, &[6])];
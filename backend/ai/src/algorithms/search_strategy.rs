@@ -0,0 +1,282 @@
+//! Search strategies the GATLAM driver can run (`GATLAM::search_strategy`).
+//!
+//! The driver only needs candidates to evaluate and a way to hand their fitness back, so the
+//! genetic algorithm, simulated annealing and random search all plug into the same
+//! interpreter / evaluator loop. Every strategy works on the GA's chromosome encoding and
+//! evaluates `population_size` candidates per iteration, so runs with the same config spend
+//! the same interpreter budget and can be compared directly.
+
+use crate::algorithms::genetic_algorithm::{Chromosome, GAConfig, GeneticAlgorithm};
+use rand::{Rng, thread_rng};
+use util::execution_config::{ExecutionConfig, SearchStrategyKind};
+
+/// A search over chromosomes driven by externally computed fitness.
+pub trait SearchStrategy: Send {
+    /// Candidates to evaluate in the current iteration.
+    fn propose(&self) -> &[Chromosome];
+
+    /// Fitness of the candidates from `propose`, in the same order; moves to the next
+    /// iteration.
+    fn receive(&mut self, fitness: &[f64]);
+
+    /// Genes, iteration count (`number_of_generations`) and candidates per iteration.
+    fn config(&self) -> &GAConfig;
+
+    fn bits_per_gene(&self) -> usize {
+        self.config().bits()
+    }
+}
+
+/// The strategy selected by `config.gatlam.search_strategy`.
+pub fn search_strategy_for(config: &ExecutionConfig) -> Box<dyn SearchStrategy> {
+    let gatlam = &config.gatlam;
+    match gatlam.search_strategy {
+        SearchStrategyKind::Genetic => Box::new(GeneticAlgorithm::from_execution_config(config)),
+        SearchStrategyKind::SimulatedAnnealing => Box::new(SimulatedAnnealing::new(
+            GAConfig::from_execution_config(config),
+            gatlam.initial_temperature,
+            gatlam.cooling_rate,
+        )),
+        SearchStrategyKind::RandomSearch => {
+            Box::new(RandomSearch::new(GAConfig::from_execution_config(config)))
+        }
+    }
+}
+
+impl SearchStrategy for GeneticAlgorithm {
+    fn propose(&self) -> &[Chromosome] {
+        self.population()
+    }
+
+    fn receive(&mut self, fitness: &[f64]) {
+        self.step_with_fitness(fitness);
+    }
+
+    fn config(&self) -> &GAConfig {
+        GeneticAlgorithm::config(self)
+    }
+
+    fn bits_per_gene(&self) -> usize {
+        GeneticAlgorithm::bits_per_gene(self)
+    }
+}
+
+/// Simulated annealing over chromosomes.
+///
+/// The first iteration evaluates random chromosomes and starts from the fittest. Every later
+/// iteration evaluates neighbours of the current chromosome (one bit flipped, then repaired);
+/// the best neighbour replaces the current one if it is fitter, or otherwise with probability
+/// `exp(delta / temperature)`. The temperature is multiplied by `cooling_rate` every step.
+pub struct SimulatedAnnealing {
+    config: GAConfig,
+    candidates: Vec<Chromosome>,
+    current: Option<(Chromosome, f64)>,
+    temperature: f64,
+    cooling_rate: f64,
+}
+
+impl SimulatedAnnealing {
+    pub fn new(config: GAConfig, initial_temperature: f64, cooling_rate: f64) -> Self {
+        let mut rng = thread_rng();
+        let candidates = (0..config.population_size)
+            .map(|_| config.random_chromosome(&mut rng))
+            .collect();
+        Self {
+            config,
+            candidates,
+            current: None,
+            temperature: initial_temperature.max(0.0),
+            cooling_rate: cooling_rate.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Fitness of the current chromosome, once the first iteration has been evaluated.
+    pub fn current_fitness(&self) -> Option<f64> {
+        self.current.as_ref().map(|(_, fitness)| *fitness)
+    }
+
+    pub fn temperature(&self) -> f64 {
+        self.temperature
+    }
+
+    // `chrom` with one random bit flipped, repaired per gene
+    fn neighbour<R: Rng>(&self, chrom: &Chromosome, rng: &mut R) -> Chromosome {
+        let mut next = chrom.clone();
+        let len = next.genes().len();
+        if len > 0 {
+            let i = rng.gen_range(0..len);
+            next.genes_mut()[i] ^= true;
+        }
+        self.config.repair(&mut next, rng);
+        next
+    }
+}
+
+impl SearchStrategy for SimulatedAnnealing {
+    fn propose(&self) -> &[Chromosome] {
+        &self.candidates
+    }
+
+    fn receive(&mut self, fitness: &[f64]) {
+        assert_eq!(
+            fitness.len(),
+            self.candidates.len(),
+            "fitness/candidates size mismatch"
+        );
+        let mut rng = thread_rng();
+
+        if let Some(best) = (0..fitness.len()).max_by(|&a, &b| fitness[a].total_cmp(&fitness[b])) {
+            let accept = match &self.current {
+                None => true,
+                Some((_, current)) => {
+                    let delta = fitness[best] - current;
+                    delta >= 0.0
+                        || (self.temperature > 0.0
+                            && rng.r#gen::<f64>() < (delta / self.temperature).exp())
+                }
+            };
+            if accept {
+                self.current = Some((self.candidates[best].clone(), fitness[best]));
+            }
+        }
+        self.temperature *= self.cooling_rate;
+
+        if let Some((current, _)) = &self.current {
+            self.candidates = (0..self.config.population_size)
+                .map(|_| self.neighbour(current, &mut rng))
+                .collect();
+        }
+    }
+
+    fn config(&self) -> &GAConfig {
+        &self.config
+    }
+}
+
+/// Pure random search: every iteration evaluates fresh random chromosomes.
+pub struct RandomSearch {
+    config: GAConfig,
+    candidates: Vec<Chromosome>,
+}
+
+impl RandomSearch {
+    pub fn new(config: GAConfig) -> Self {
+        let candidates = Self::sample(&config);
+        Self { config, candidates }
+    }
+
+    fn sample(config: &GAConfig) -> Vec<Chromosome> {
+        let mut rng = thread_rng();
+        (0..config.population_size)
+            .map(|_| config.random_chromosome(&mut rng))
+            .collect()
+    }
+}
+
+impl SearchStrategy for RandomSearch {
+    fn propose(&self) -> &[Chromosome] {
+        &self.candidates
+    }
+
+    fn receive(&mut self, _fitness: &[f64]) {
+        self.candidates = Self::sample(&self.config);
+    }
+
+    fn config(&self) -> &GAConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithms::genetic_algorithm::{CrossoverType, GeneConfig, MutationType};
+    use crate::decode_genes;
+    use std::collections::HashSet;
+
+    fn onemax(c: &Chromosome) -> f64 {
+        c.genes().iter().filter(|&&b| b).count() as f64 / c.genes().len() as f64
+    }
+
+    fn config(genes: Vec<GeneConfig>) -> GAConfig {
+        GAConfig::new(
+            10,
+            40,
+            4,
+            0.9,
+            0.8,
+            0.05,
+            genes,
+            CrossoverType::OnePoint,
+            MutationType::BitFlip,
+        )
+    }
+
+    #[test]
+    fn cold_annealing_never_moves_to_a_worse_chromosome() {
+        let genes = vec![GeneConfig::new(-15, 15, HashSet::new()); 6];
+        let mut sa = SimulatedAnnealing::new(config(genes), 0.0, 0.9);
+
+        let mut last = 0.0;
+        for _ in 0..40 {
+            let fitness: Vec<f64> = sa.propose().iter().map(onemax).collect();
+            sa.receive(&fitness);
+            let current = sa.current_fitness().unwrap();
+            assert!(current >= last, "{current} < {last}");
+            last = current;
+        }
+        assert!(last > 0.8, "annealing should climb OneMax, reached {last}");
+    }
+
+    #[test]
+    fn annealing_cools_by_the_rate() {
+        let genes = vec![GeneConfig::new(0, 7, HashSet::new())];
+        let mut sa = SimulatedAnnealing::new(config(genes), 2.0, 0.5);
+        for _ in 0..3 {
+            let fitness: Vec<f64> = sa.propose().iter().map(onemax).collect();
+            sa.receive(&fitness);
+        }
+        assert!((sa.temperature() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn random_search_proposes_fresh_valid_candidates() {
+        let genes = vec![GeneConfig::new(1, 5, [2, 4].into_iter().collect()); 3];
+        let mut search = RandomSearch::new(config(genes));
+        let bits = search.bits_per_gene();
+
+        for _ in 0..5 {
+            assert_eq!(search.propose().len(), 10);
+            for chrom in search.propose() {
+                for v in decode_genes(chrom.genes(), bits) {
+                    assert!([1, 3, 5].contains(&v), "{v} is not a valid value");
+                }
+            }
+            let fitness = vec![0.0; search.propose().len()];
+            search.receive(&fitness);
+        }
+    }
+
+    #[test]
+    fn strategy_follows_the_config() {
+        let mut exec = ExecutionConfig::default_config();
+        exec.gatlam.population_size = 7;
+
+        for kind in [
+            SearchStrategyKind::Genetic,
+            SearchStrategyKind::SimulatedAnnealing,
+            SearchStrategyKind::RandomSearch,
+        ] {
+            exec.gatlam.search_strategy = kind;
+            let mut strategy = search_strategy_for(&exec);
+            assert_eq!(strategy.propose().len(), 7, "{kind:?}");
+            let fitness = vec![1.0; 7];
+            strategy.receive(&fitness);
+            assert_eq!(strategy.propose().len(), 7, "{kind:?}");
+            assert_eq!(
+                strategy.config().number_of_generations,
+                exec.gatlam.number_of_generations
+            );
+        }
+    }
+}
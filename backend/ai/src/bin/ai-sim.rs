@@ -0,0 +1,169 @@
+//! `ai-sim`: runs the GATLAM search against a local fitness function (no Docker, no database).
+//!
+//! Usage: `ai-sim <simulation.json>` (or `-` to read stdin). The file holds the `gatlam`
+//! section of an assignment config and the fitness to use instead of the interpreter:
+//!
+//! ```json
+//! {
+//!   "gatlam": { "population_size": 50, "genes": [{ "min_value": 0, "max_value": 9 }] },
+//!   "fitness": { "type": "target", "values": [7] }
+//! }
+//! ```
+//!
+//! - `target`: the "bug" shows on the given decoded gene values; the LTL share grows with the
+//!   genes that match and every task fails once all of them do.
+//! - `command`: runs `program` with `args` for every new payload, writing the payload and a
+//!   newline to its stdin; it prints `<ltl_milli> <fail_milli>` (each 0..=1000) on stdout.
+//!
+//! Progress goes to stderr, one line per generation; the final report is printed to stdout as
+//! JSON.
+
+use ai::simulation::{SimulationReport, simulate};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::{env, fs, io, process};
+use util::execution_config::{ExecutionConfig, GATLAM};
+
+#[derive(Debug, Deserialize)]
+struct SimulationInput {
+    #[serde(default)]
+    gatlam: GATLAM,
+    fitness: LocalFitness,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum LocalFitness {
+    Target {
+        values: Vec<i32>,
+    },
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+impl LocalFitness {
+    fn evaluate(&self, genes: &[i32], payload: &str) -> Result<(usize, usize), String> {
+        match self {
+            LocalFitness::Target { values } => {
+                if values.len() != genes.len() {
+                    return Err(format!(
+                        "Target has {} values but the genes decode to {}",
+                        values.len(),
+                        genes.len()
+                    ));
+                }
+                let hits = genes.iter().zip(values).filter(|(g, v)| g == v).count();
+                let ltl = hits * 1000 / genes.len().max(1);
+                Ok((ltl, if hits == genes.len() { 1000 } else { 0 }))
+            }
+            LocalFitness::Command { program, args } => run_command(program, args, payload),
+        }
+    }
+}
+
+fn run_command(program: &str, args: &[String], payload: &str) -> Result<(usize, usize), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "{}", payload)
+            .map_err(|e| format!("Failed to write payload to {}: {}", program, e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if !output.status.success() {
+        return Err(format!("{} exited with {}", program, output.status));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut numbers = stdout.split_whitespace().map(str::parse::<usize>);
+    match (numbers.next(), numbers.next()) {
+        (Some(Ok(ltl)), Some(Ok(fail))) => Ok((ltl, fail)),
+        _ => Err(format!(
+            "{} must print \"<ltl_milli> <fail_milli>\", got {:?}",
+            program,
+            stdout.trim()
+        )),
+    }
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Failed to read stdin: {}", e))?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+    }
+}
+
+fn run(path: &str) -> Result<SimulationReport, String> {
+    let input: SimulationInput = serde_json::from_str(&read_input(path)?)
+        .map_err(|e| format!("Invalid simulation file: {}", e))?;
+    let mut config = ExecutionConfig::default_config();
+    config.gatlam = input.gatlam;
+
+    let report = simulate(&config, |genes, payload| {
+        input.fitness.evaluate(genes, payload)
+    })?;
+    for g in &report.generations {
+        eprintln!(
+            "generation {}/{}: best {:.4}, mean {:.4}, evaluations {} ({} new)",
+            g.generation + 1,
+            g.generations,
+            g.best_fitness,
+            g.mean_fitness,
+            g.evaluated,
+            g.interpreter_runs
+        );
+    }
+    Ok(report)
+}
+
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("Usage: ai-sim <simulation.json | ->");
+        process::exit(2);
+    };
+
+    match run(&path) {
+        Ok(report) => {
+            let last = report.generations.last();
+            let summary = json!({
+                "best_fitness": report.best_fitness,
+                "best_genes": report.best_genes,
+                "best_payload": report.best_payload,
+                "generations": report.generations.len(),
+                "evaluations": last.map_or(0, |g| g.evaluated),
+                "distinct_evaluations": last.map_or(0, |g| g.interpreter_runs),
+                "best_per_generation": report
+                    .generations
+                    .iter()
+                    .map(|g| g.best_fitness)
+                    .collect::<Vec<_>>(),
+                "mean_per_generation": report
+                    .generations
+                    .iter()
+                    .map(|g| g.mean_fitness)
+                    .collect::<Vec<_>>(),
+                "elapsed_ms": report.elapsed_ms,
+            });
+            println!("{:#}", summary);
+        }
+        Err(e) => {
+            eprintln!("ai-sim: {}", e);
+            process::exit(1);
+        }
+    }
+}
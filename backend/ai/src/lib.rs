@@ -4,13 +4,17 @@
 //
 // This file wires to Genetic Algorithm to the external code interpreter, the process is as follows
 // For each code generation and each chromosome
-// 1) Decode the chromosome bits into an interpreter payload string
-// 2) Call the interpreter (runs code, writes to DB, returns per-task outputs)
+// 1) Decode the chromosome bits into an interpreter payload string (integers, categorical
+//    values and strings, see `GAConfig::payload`)
+// 2) Call the interpreter in isolation (runs the memo and the submission against the generated
+//    Main without touching the assignment's stored files or outputs, returns per-task outputs)
 /// 3) Map outputs to `(ltl_milli, fail_milli)` via `derive_props`
 // 4) Compute fitness using Components
-// 5) Evolve teh population with the fitness scores
+// 5) Hand the fitness scores back to the search strategy (the GA evolves teh population;
+//    simulated annealing and random search are baselines, see `SearchStrategy`)
 // Notes:
-// - The interpreter is called once per chromosome per generation.
+// - The interpreter is called once per distinct decoded gene vector; repeats reuse the cached
+//   interpreter results (see `FitnessCache`).
 // - The Evaluator only checks SOME properties (Safety, Proper
 //  Termination, Segfault, Exceptions, Execution Time, Illegal Output). The
 //   two “expected output” properties are evaluated elsewhere. (Presumably), not exactly sure how this should be handled
@@ -21,26 +25,40 @@
 pub mod algorithms {
     pub mod code_coverage;
     pub mod genetic_algorithm;
+    pub mod multi_objective;
     pub mod rng;
+    pub mod search_strategy;
 }
 
+pub mod simulation;
+
 pub mod utils {
     pub mod evaluator;
+    pub mod fitness_cache;
+    pub mod history;
     pub mod output;
+    pub mod progress;
 }
 
-use crate::algorithms::genetic_algorithm::{Chromosome, GeneticAlgorithm};
+use crate::algorithms::genetic_algorithm::{Chromosome, GeneConfig as GaGeneConfig};
+use crate::algorithms::multi_objective::{MultiObjective, Objectives, ParetoArchive};
+use crate::algorithms::search_strategy::{SearchStrategy, search_strategy_for};
 use crate::utils::evaluator::{Evaluator, TaskSpec};
+use crate::utils::fitness_cache::FitnessCache;
+use crate::utils::history::GaHistory;
 use crate::utils::output::Output;
-use code_runner::run_interpreter;
+use crate::utils::progress::{GaProgress, GaProgressSink};
+use code_runner::{InterpreterRun, run_interpreter, run_interpreter_isolated};
 use db::models::assignment_submission::Entity as AssignmentSubmission;
+use db::models::ga_generation::Individual;
+use db::models::ga_run::GaRunMode;
 use rand::random;
 use sea_orm::DatabaseConnection;
 use sea_orm::EntityTrait;
 use std::collections::HashMap;
-use util::execution_config::ExecutionConfig;
+use util::execution_config::{ExecutionConfig, GaObjective};
 
-use crate::algorithms::code_coverage::{coverage_fitness, coverage_percent_for_attempt};
+use crate::algorithms::code_coverage::{coverage_fitness, coverage_percent_for_run};
 use crate::algorithms::rng::{GeneConfig as RngGeneConfig, RandomGenomeGenerator as RngGen};
 
 // Public entrypoint: build GA + Evaluator + Components, then run the loop
@@ -51,16 +69,23 @@ use crate::algorithms::rng::{GeneConfig as RngGeneConfig, RandomGenomeGenerator
 /// - `db`: shared database connection
 /// - `submission_id`: which submission we are optimizing for
 /// - `config`: ExecutionConfig containing GA parameters, omegas, language, runtime limits, etc.
+/// - `progress`: optional sink that receives a `GaProgress` after every generation
 ///
 /// # Behavior
-/// - Instantiates the GA population from `config.ga_config`
+/// - Hands over to `run_multi_objective_ga_job` when `config.gatlam.objective` also asks for
+///   code coverage
+/// - Builds the search strategy from `config.gatlam.search_strategy`: the GA, or a simulated
+///   annealing / random search baseline (see `search_strategy_for`)
 /// - Instantiates `Components` using omegas from `config`
-/// - Builds a `TaskSpec` from `config` for per-task property evaluation
+/// - Builds a `TaskSpec` per task from `config` (`task_spec` may differ per task number),
+///   including the lecturer-defined rules of each task
 /// - Instantiates an `Evaluator` to check properties like Safety, ProperTermination,
 ///   SegmentationFault, Exceptions, ExecutionTime, IllegalOutput
 /// - Builds a closure `derive_props` that maps interpreter outputs into
 ///   `(num_ltl_props, num_tasks)` for fitness evaluation
-/// - Calls the generic driver `run_ga_end_to_end` which runs the GA loop
+/// - Calls the generic driver `run_ga_end_to_end` which runs the search loop
+/// - Records the run, every generation's population and the best payload in `ga_runs` /
+///   `ga_generations` (see `GaHistory`)
 ///
 /// # Returns
 /// - `Ok(())` on success, or a `String` error propagated from the interpreter or GA driver
@@ -68,56 +93,95 @@ pub async fn run_ga_job(
     db: &DatabaseConnection,
     submission_id: i64,
     config: ExecutionConfig,
-    module_id: i64,
     assignment_id: i64,
+    progress: Option<GaProgressSink>,
 ) -> Result<(), String> {
-    // Build GA from ExecutionConfig
-    let mut ga = GeneticAlgorithm::from_execution_config(&config.clone());
-    let bits_per_gene = ga.bits_per_gene();
-
-    // Fitness Components from omegas
-    let (omega1, omega2, omega3) = (
-        config.gatlam.omega1,
-        config.gatlam.omega2,
-        config.gatlam.omega3,
-    );
-    let mut comps = Components::new(omega1, omega2, omega3, bits_per_gene);
-
-    // Evaluator for property checks
-    let evaluator = Evaluator::new();
+    if config.gatlam.objective != GaObjective::Properties {
+        return run_multi_objective_ga_job(db, submission_id, &config, assignment_id, progress)
+            .await;
+    }
 
-    // TaskSpec(s) derived from ExecutionConfig
-    let base_spec = TaskSpec::from_execution_config(&config);
+    // Build the search strategy (the GA unless a baseline is configured) from ExecutionConfig
+    let mut strategy = search_strategy_for(&config);
+    let bits_per_gene = strategy.bits_per_gene();
 
-    let delimiter = config.marking.deliminator.clone();
+    // Fitness Components from omegas
+    let mut comps = components_for(&config, bits_per_gene);
 
-    let mut derive_props = {
-        let evaluator = evaluator;
-        let base_spec = base_spec.clone();
-        let delim = delimiter.clone();
-        move |outs: &[(i64, String)], memo: &[(i64, String)]| -> (usize, usize) {
-            let specs = vec![base_spec.clone(); outs.len()];
-            evaluator.derive_props(&specs, outs, memo, &delim)
-        }
-    };
+    // Evaluator + TaskSpec(s) derived from ExecutionConfig
+    let task_numbers = Output::get_task_numbers(db, assignment_id).await?;
+    let mut derive_props = props_deriver(&config, task_numbers);
 
     // Unused fetch closure for signature compatibility
     let mut unused_fetch = |_db: &DatabaseConnection,
                             _sid: i64|
      -> Result<Vec<(i64, String)>, String> { Err("unused".into()) };
 
-    // Run the GA <-> interpreter loop
-    run_ga_end_to_end(
+    let mut history = GaHistory::start(
         db,
+        assignment_id,
         submission_id,
-        &mut ga,
+        GaRunMode::Gatlam,
+        strategy.config().number_of_generations,
+        strategy.config().population_size,
+    )
+    .await;
+
+    // Run the search <-> interpreter loop
+    let result = run_ga_end_to_end(
+        db,
+        submission_id,
+        strategy.as_mut(),
         &mut comps,
         &mut derive_props,
         &mut unused_fetch,
-        module_id,
-        assignment_id,
+        progress,
+        &mut history,
     )
-    .await
+    .await;
+    let result = match result {
+        Ok(()) => store_best_run(db, submission_id, &history).await,
+        err => err,
+    };
+    history.finish(&result).await;
+    result
+}
+
+/// Fitness `Components` weighted by the omegas in `config`.
+pub(crate) fn components_for(config: &ExecutionConfig, bits_per_gene: usize) -> Components {
+    let (omega1, omega2, omega3) = (
+        config.gatlam.omega1,
+        config.gatlam.omega2,
+        config.gatlam.omega3,
+    );
+    Components::new(omega1, omega2, omega3, bits_per_gene)
+}
+
+/// Maps a submission's and the memo's per-task outputs to `(ltl_milli, fail_milli)`.
+type PropsDeriver = Box<dyn FnMut(&[(i64, String)], &[(i64, String)]) -> (usize, usize) + Send>;
+
+/// `derive_props` for `config`: checks every task output with an `Evaluator` against the
+/// `TaskSpec` of that output's task (`task_numbers` maps task id to task number).
+fn props_deriver(config: &ExecutionConfig, task_numbers: HashMap<i64, i64>) -> PropsDeriver {
+    let evaluator = Evaluator::new();
+    let task_specs: HashMap<i64, TaskSpec> = task_numbers
+        .into_iter()
+        .map(|(task_id, n)| (task_id, TaskSpec::from_execution_config(config, Some(n))))
+        .collect();
+    let unknown_task_spec = TaskSpec::from_execution_config(config, None);
+    let delim = config.marking.delimiter.clone();
+    Box::new(move |outs: &[(i64, String)], memo: &[(i64, String)]| {
+        let specs: Vec<TaskSpec> = outs
+            .iter()
+            .map(|(task_id, _)| {
+                task_specs
+                    .get(task_id)
+                    .unwrap_or(&unknown_task_spec)
+                    .clone()
+            })
+            .collect();
+        evaluator.derive_props(&specs, outs, memo, &delim)
+    })
 }
 
 pub async fn run_rng_job(
@@ -147,45 +211,223 @@ pub async fn run_rng_job(
     Ok(())
 }
 
+/// Runs the GA with code coverage as the fitness: each chromosome is scored by the coverage
+/// percent its interpreter run reaches. Reports a `GaProgress` (with the generation's best
+/// coverage percent) to `progress` after every generation, and records the run like
+/// `run_ga_job` does.
 pub async fn run_coverage_ga_job(
     db: &DatabaseConnection,
     submission_id: i64,
     config: &ExecutionConfig,
-    module_id: i64,
     assignment_id: i64,
+    progress: Option<GaProgressSink>,
 ) -> Result<(), String> {
-    let mut ga = GeneticAlgorithm::from_execution_config(&config.clone());
-    let bits_per_gene = ga.bits_per_gene();
+    let mut strategy = search_strategy_for(config);
+    let mut history = GaHistory::start(
+        db,
+        assignment_id,
+        submission_id,
+        GaRunMode::CodeCoverage,
+        strategy.config().number_of_generations,
+        strategy.config().population_size,
+    )
+    .await;
 
-    let submission = AssignmentSubmission::find_by_id(submission_id)
-        .one(db)
-        .await
-        .map_err(|e| format!("Failed to fetch submission: {}", e))?
-        .ok_or_else(|| format!("Submission {} not found", submission_id))?;
-    let user_id = submission.user_id;
-    let attempt_number = submission.attempt;
+    let result =
+        coverage_ga_loop(db, submission_id, strategy.as_mut(), progress, &mut history).await;
+    let result = match result {
+        Ok(()) => store_best_run(db, submission_id, &history).await,
+        err => err,
+    };
+    history.finish(&result).await;
+    result
+}
 
-    let gens = ga.config().number_of_generations;
+async fn coverage_ga_loop(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    strategy: &mut dyn SearchStrategy,
+    progress: Option<GaProgressSink>,
+    history: &mut GaHistory<'_>,
+) -> Result<(), String> {
+    let bits_per_gene = strategy.bits_per_gene();
 
-    for _generation in 0..gens {
-        let mut fitness_scores = Vec::with_capacity(ga.population().len());
+    let gens = strategy.config().number_of_generations;
+    let mut cache: FitnessCache<f64> = FitnessCache::new();
 
-        for chrom in ga.population().iter() {
+    for generation in 0..gens {
+        let mut fitness_scores = Vec::with_capacity(strategy.propose().len());
+        let mut population = Vec::with_capacity(strategy.propose().len());
+        let mut best_percent = 0.0f64;
+
+        for chrom in strategy.propose().iter() {
             let decoded = decode_genes(chrom.genes(), bits_per_gene);
-            let payload = decoded
-                .iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            run_interpreter(db, submission_id, &payload).await?;
-            let percent =
-                coverage_percent_for_attempt(db, module_id, assignment_id, user_id, attempt_number)
-                    .await?;
-            let score = coverage_fitness(percent);
+            let payload = strategy.config().payload(&decoded);
+            let penalty = strategy.config().penalty(&decoded);
+            let percent = match cache.get(&decoded) {
+                Some(percent) => percent,
+                None => {
+                    let run = run_interpreter_isolated(db, submission_id, &payload).await?;
+                    let percent = coverage_percent_for_run(&run)?;
+                    cache.insert(decoded, percent);
+                    percent
+                }
+            };
+            best_percent = best_percent.max(percent);
+            let score = (coverage_fitness(percent) - penalty).max(0.0);
             fitness_scores.push(score);
+            population.push(Individual {
+                payload,
+                fitness: score,
+            });
         }
 
-        ga.step_with_fitness(&fitness_scores);
+        let mut summary = GaProgress::for_generation(generation, gens, &fitness_scores, &cache);
+        summary.coverage_percent = Some(best_percent);
+        report_generation(
+            submission_id,
+            summary,
+            &population,
+            progress.as_ref(),
+            history,
+        )
+        .await;
+        strategy.receive(&fitness_scores);
+    }
+
+    Ok(())
+}
+
+/// Runs GATLAM with code coverage as a second objective (`objective` = `weighted_sum` or
+/// `pareto`), so one run optimizes both instead of separate `run_ga_job` and
+/// `run_coverage_ga_job` invocations.
+///
+/// Each distinct chromosome is interpreted once; its property counts and coverage percent are
+/// cached together. The GA evolves on the `MultiObjective` scores, and the non-dominated
+/// payloads of the whole run are kept in a `ParetoArchive` that is logged when the run ends.
+/// Progress and history are reported like `run_ga_job`.
+pub async fn run_multi_objective_ga_job(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    config: &ExecutionConfig,
+    assignment_id: i64,
+    progress: Option<GaProgressSink>,
+) -> Result<(), String> {
+    let mut strategy = search_strategy_for(config);
+    let mut history = GaHistory::start(
+        db,
+        assignment_id,
+        submission_id,
+        GaRunMode::MultiObjective,
+        strategy.config().number_of_generations,
+        strategy.config().population_size,
+    )
+    .await;
+
+    let result = multi_objective_ga_loop(
+        db,
+        submission_id,
+        config,
+        strategy.as_mut(),
+        assignment_id,
+        progress,
+        &mut history,
+    )
+    .await;
+    let result = match result {
+        Ok(()) => store_best_run(db, submission_id, &history).await,
+        err => err,
+    };
+    history.finish(&result).await;
+    result
+}
+
+async fn multi_objective_ga_loop(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    config: &ExecutionConfig,
+    strategy: &mut dyn SearchStrategy,
+    assignment_id: i64,
+    progress: Option<GaProgressSink>,
+    history: &mut GaHistory<'_>,
+) -> Result<(), String> {
+    let bits_per_gene = strategy.bits_per_gene();
+    let mut comps = components_for(config, bits_per_gene);
+    let task_numbers = Output::get_task_numbers(db, assignment_id).await?;
+    let mut derive_props = props_deriver(config, task_numbers);
+    let scoring = MultiObjective::from_gatlam(&config.gatlam);
+    let mut archive = ParetoArchive::new();
+
+    let gens = strategy.config().number_of_generations;
+    // Property counts and coverage percent per decoded gene vector
+    let mut cache: FitnessCache<((usize, usize), f64)> = FitnessCache::new();
+
+    for generation in 0..gens {
+        let mut points = Vec::with_capacity(strategy.propose().len());
+        let mut payloads = Vec::with_capacity(strategy.propose().len());
+        let mut penalties = Vec::with_capacity(strategy.propose().len());
+        let mut best_percent = 0.0f64;
+
+        for chrom in strategy.propose().iter() {
+            let decoded = decode_genes(chrom.genes(), bits_per_gene);
+            let payload = strategy.config().payload(&decoded);
+            penalties.push(strategy.config().penalty(&decoded));
+            let ((ltl_milli, fail_milli), percent) = match cache.get(&decoded) {
+                Some(result) => result,
+                None => {
+                    let run = run_interpreter_isolated(db, submission_id, &payload).await?;
+                    let props = derive_props(&task_outputs(&run), &run.memo_outputs);
+                    let percent = coverage_percent_for_run(&run)?;
+
+                    cache.insert(decoded, (props, percent));
+                    (props, percent)
+                }
+            };
+
+            best_percent = best_percent.max(percent);
+            let objectives = Objectives {
+                coverage: coverage_fitness(percent),
+                properties: comps.evaluate(chrom, generation, ltl_milli, fail_milli),
+            };
+            archive.insert(objectives, &payload);
+            points.push(objectives);
+            payloads.push(payload);
+        }
+
+        // invalid genes are penalized after scoring, so Pareto ranks see the raw objectives
+        let fitness_scores: Vec<f64> = scoring
+            .scores(&points)
+            .into_iter()
+            .zip(&penalties)
+            .map(|(score, penalty)| (score - penalty).max(0.0))
+            .collect();
+        let population: Vec<Individual> = payloads
+            .into_iter()
+            .zip(&fitness_scores)
+            .map(|(payload, &fitness)| Individual { payload, fitness })
+            .collect();
+
+        let mut summary = GaProgress::for_generation(generation, gens, &fitness_scores, &cache);
+        summary.coverage_percent = Some(best_percent);
+        report_generation(
+            submission_id,
+            summary,
+            &population,
+            progress.as_ref(),
+            history,
+        )
+        .await;
+        strategy.receive(&fitness_scores);
+    }
+
+    for (objectives, payload) in archive.entries() {
+        tracing::info!(
+            submission_id,
+            coverage = objectives.coverage,
+            properties = objectives.properties,
+            payload = payload.as_str(),
+            "GA Pareto front entry"
+        );
     }
 
     Ok(())
@@ -194,13 +436,20 @@ pub async fn run_coverage_ga_job(
 // Core driver: decode -> interpreter -> derive -> evaluate -> evolve
 /// Generic driver used by `run_ga_job`.
 ///
-/// For each generation:
-///   For each chromosome:
-///     1) Decode its bits → interpreter payload (comma-separated ints)
-///     2) Call the interpreter (async): writes to DB and returns per-task outputs
+/// For each generation (iteration of `strategy`):
+///   For each chromosome the strategy proposes:
+///     1) Decode its bits → interpreter payload (genes rendered by kind, comma-separated)
+///     2) Call the interpreter (async) in isolation: nothing is written to the assignment's
+///        memo or submission outputs, so concurrent marking is unaffected; returns the
+///        per-task memo and submission outputs
 ///     3) Map outputs to `(num_ltl_props, num_tasks)` via `derive_props`
 ///     4) Compute fitness with `Components` using those counts
-///   Then evolve one generation with the collected fitness scores.
+///   Steps 2-3 are skipped for gene vectors already seen in this run; their counts come from
+///   the `FitnessCache`. Genes with a `GeneRepair::Penalty` that decode to invalid values
+///   lower the score by their penalty (floored at 0).
+///   Then log best/mean fitness and cache statistics (also sent to `progress` as a
+///   `GaProgress`), record the generation in `history`, and hand the collected fitness
+///   scores back to `strategy` (the GA evolves one generation).
 ///
 /// The function is generic over:
 /// - `derive_props`: caller-defined mapping from interpreter outputs to counts
@@ -210,12 +459,12 @@ pub async fn run_coverage_ga_job(
 pub async fn run_ga_end_to_end<D, F>(
     db: &DatabaseConnection,
     submission_id: i64,
-    ga: &mut GeneticAlgorithm,
+    strategy: &mut dyn SearchStrategy,
     comps: &mut Components,
     mut derive_props: D,
     mut fetch_outputs: F, // kept for compatibility; unused
-    module_id: i64,
-    assignment_id: i64,
+    progress: Option<GaProgressSink>,
+    history: &mut GaHistory<'_>,
 ) -> Result<(), String>
 where
     // Given raw outputs for this chromosome, return counts the Components expect
@@ -225,78 +474,130 @@ where
 {
     let _ = &mut fetch_outputs;
 
-    let gens = ga.config().number_of_generations;
-    let bits_per_gene = ga.bits_per_gene();
+    let gens = strategy.config().number_of_generations;
+    let bits_per_gene = strategy.bits_per_gene();
+    // Interpreter results per decoded gene vector, so repeated chromosomes skip the run
+    let mut cache: FitnessCache<(usize, usize)> = FitnessCache::new();
 
     // Outer loop: generations
     for generation in 0..gens {
-        let mut fitness_scores = Vec::with_capacity(ga.population().len());
+        let mut fitness_scores = Vec::with_capacity(strategy.propose().len());
+        let mut population = Vec::with_capacity(strategy.propose().len());
 
         // Inner loop: chromosomes in the current population
-        for chrom in ga.population().iter() {
-            // payload for interpreter, decoded bits into integers into strings
+        for chrom in strategy.propose().iter() {
+            // decoded bits into integers, used as the cache key and the interpreter payload
             let decoded = decode_genes(chrom.genes(), bits_per_gene);
-            let generated_string = decoded
-                .iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-
-            // Load submission to get user_id and attempt_number
-            let submission = AssignmentSubmission::find_by_id(submission_id)
-                .one(db)
-                .await
-                .map_err(|e| format!("Failed to fetch submission: {}", e))?
-                .ok_or_else(|| format!("Submission {} not found", submission_id))?;
-
-            let user_id = submission.user_id;
-            let attempt_number = submission.attempt;
-
-            // Run interpreter: executes code for this chromosome, writes artifacts
-            //    to DB, and returns per-task outputs for *this* submission.
-            //    The interpreter is the source of truth for stdout/stderr/exit codes.
-            run_interpreter(db, submission_id, &generated_string).await?;
-
-            let task_outputs: Vec<(i64, String)> = Output::get_submission_output_no_coverage(
-                db,
-                module_id,
-                assignment_id,
-                user_id,
-                attempt_number,
-            )
-            .await
-            .map_err(|e| e.to_string())?;
-
-            let memo_task_outputs: Vec<(i64, String)> =
-                Output::get_memo_output(module_id, assignment_id).map_err(|e| e.to_string())?;
-
-            // Derive counts the Components need:
-            //    - `n_ltl_props`: total number of violated properties across tasks
-            //    - `num_tasks`  : number of tasks we evaluated
-            //    Components will internally normalize (e.g., divide by counts).
-            let (ltl_milli, fail_milli) = derive_props(&task_outputs, &memo_task_outputs);
+            let payload = strategy.config().payload(&decoded);
+            let penalty = strategy.config().penalty(&decoded);
+
+            let (ltl_milli, fail_milli) = match cache.get(&decoded) {
+                Some(props) => props,
+                None => {
+                    // Run interpreter: executes the memo and this submission against the
+                    //    Main generated for this chromosome, in isolation from the
+                    //    assignment's stored memo and submission outputs, and returns both.
+                    //    The interpreter is the source of truth for stdout/stderr/exit codes.
+                    let run = run_interpreter_isolated(db, submission_id, &payload).await?;
+
+                    // Derive counts the Components need:
+                    //    - `n_ltl_props`: total number of violated properties across tasks
+                    //    - `num_tasks`  : number of tasks we evaluated
+                    //    Components will internally normalize (e.g., divide by counts).
+                    let props = derive_props(&task_outputs(&run), &run.memo_outputs);
+                    cache.insert(decoded, props);
+                    props
+                }
+            };
 
             // Compute fitness for this chromosome in this generation.
-            //    `Components` combines sub-scores via omega weights and returns a scalar.
-            let score = comps.evaluate(chrom, generation, ltl_milli, fail_milli);
+            //    `Components` combines sub-scores via omega weights and returns a scalar;
+            //    penalized invalid genes are subtracted from it.
+            let score =
+                (comps.evaluate(chrom, generation, ltl_milli, fail_milli) - penalty).max(0.0);
             fitness_scores.push(score);
+            population.push(Individual {
+                payload,
+                fitness: score,
+            });
         }
 
+        let summary = GaProgress::for_generation(generation, gens, &fitness_scores, &cache);
+        report_generation(
+            submission_id,
+            summary,
+            &population,
+            progress.as_ref(),
+            history,
+        )
+        .await;
+
         // Evolve the population to the next generation using the scores we computed
-        ga.step_with_fitness(&fitness_scores);
+        strategy.receive(&fitness_scores);
     }
 
     Ok(())
 }
 
+/// Reruns the best payload of a finished search through `run_interpreter`, so the Main, memo
+/// and submission outputs that marking reads are the ones of the counterexample the search
+/// found. This is the only interpreter run of a GA job that writes to the assignment.
+async fn store_best_run(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    history: &GaHistory<'_>,
+) -> Result<(), String> {
+    match history.best_payload() {
+        Some(payload) => run_interpreter(db, submission_id, payload).await,
+        None => Ok(()),
+    }
+}
+
+/// `(task_id, output)` of every non-coverage task of an interpreter run, as `derive_props`
+/// expects them.
+fn task_outputs(run: &InterpreterRun) -> Vec<(i64, String)> {
+    run.submission_outputs
+        .iter()
+        .map(|o| (o.task_id, o.output.clone()))
+        .collect()
+}
+
+/// Logs a finished generation, records it with its population in `history`, and sends it to
+/// `sink`; a closed sink is ignored.
+async fn report_generation(
+    submission_id: i64,
+    progress: GaProgress,
+    population: &[Individual],
+    sink: Option<&GaProgressSink>,
+    history: &mut GaHistory<'_>,
+) {
+    tracing::info!(
+        submission_id,
+        generation = progress.generation,
+        best_fitness = progress.best_fitness,
+        mean_fitness = progress.mean_fitness,
+        coverage_percent = progress.coverage_percent,
+        interpreter_runs = progress.interpreter_runs,
+        cache_hits = progress.cache_hits,
+        cache_hit_rate = progress.cache_hits as f64 / progress.evaluated.max(1) as f64,
+        "GA generation finished"
+    );
+    history.record(&progress, population).await;
+    if let Some(sink) = sink {
+        let _ = sink.send(progress);
+    }
+}
+
 fn exec_to_rng_configs(cfg: &ExecutionConfig) -> Vec<RngGeneConfig> {
     cfg.gatlam
         .genes
         .iter()
+        .map(GaGeneConfig::from_exec)
         .map(|g| RngGeneConfig {
             min_value: g.min_value,
             max_value: g.max_value,
-            invalid_values: std::collections::HashSet::new(),
+            invalid_values: g.invalid_values,
+            kind: g.kind,
         })
         .collect()
 }
@@ -517,6 +818,7 @@ mod component_unit_tests {
     }
 
     mod rng_generator {
+        use crate::algorithms::genetic_algorithm::GeneKind;
         use crate::algorithms::rng::{
             GeneConfig as RngGeneConfig, RandomGenomeGenerator as RngGen,
         };
@@ -527,6 +829,7 @@ mod component_unit_tests {
                 min_value: min,
                 max_value: max,
                 invalid_values: invalid.iter().cloned().collect::<HashSet<_>>(),
+                kind: GeneKind::Integer,
             }
         }
 
@@ -0,0 +1,160 @@
+//! Dry-run mode: the GATLAM search loop against a local evaluator instead of the interpreter.
+//!
+//! `simulate` builds the same search strategy and fitness `Components` as `run_ga_job`, but
+//! asks a caller-supplied function for each chromosome's `(ltl_milli, fail_milli)` rather than
+//! running the submission in Docker and reading outputs from the database. A run takes
+//! milliseconds, so population sizes, omegas and operators can be tuned before spending
+//! interpreter time on them. The `ai-sim` binary drives this from a JSON file.
+
+use crate::algorithms::search_strategy::search_strategy_for;
+use crate::utils::fitness_cache::FitnessCache;
+use crate::utils::progress::GaProgress;
+use crate::{components_for, decode_genes};
+use std::time::Instant;
+use util::execution_config::ExecutionConfig;
+
+/// Outcome of a simulated run.
+#[derive(Debug, Clone)]
+pub struct SimulationReport {
+    /// One summary per generation, as a real run would report them.
+    pub generations: Vec<GaProgress>,
+    /// Fittest chromosome seen in the run, decoded, with its payload and fitness.
+    pub best_genes: Vec<i32>,
+    pub best_payload: String,
+    pub best_fitness: f64,
+    pub elapsed_ms: u128,
+}
+
+/// Runs the search configured in `config.gatlam` with `evaluate` standing in for the
+/// interpreter and evaluator.
+///
+/// `evaluate` gets the decoded genes and the payload the interpreter would receive and returns
+/// `(ltl_milli, fail_milli)`, both in 0..=1000 like `Evaluator::derive_props`. As in a real run,
+/// it is called once per distinct gene vector; fitness is computed from its result with the
+/// configured omegas and gene penalties.
+pub fn simulate<E>(config: &ExecutionConfig, mut evaluate: E) -> Result<SimulationReport, String>
+where
+    E: FnMut(&[i32], &str) -> Result<(usize, usize), String>,
+{
+    let gatlam = &config.gatlam;
+    let omega_sum = gatlam.omega1 + gatlam.omega2 + gatlam.omega3;
+    if (omega_sum - 1.0).abs() > 1e-6 {
+        return Err(format!("Omegas must sum to 1, not {}", omega_sum));
+    }
+    config.validate_genes()?;
+
+    let started = Instant::now();
+    let mut strategy = search_strategy_for(config);
+    let bits_per_gene = strategy.bits_per_gene();
+    let mut comps = components_for(config, bits_per_gene);
+    let gens = strategy.config().number_of_generations;
+    let mut cache: FitnessCache<(usize, usize)> = FitnessCache::new();
+
+    let mut generations = Vec::with_capacity(gens);
+    let mut best: Option<(Vec<i32>, String, f64)> = None;
+
+    for generation in 0..gens {
+        let mut fitness_scores = Vec::with_capacity(strategy.propose().len());
+
+        for chrom in strategy.propose().iter() {
+            let decoded = decode_genes(chrom.genes(), bits_per_gene);
+            let payload = strategy.config().payload(&decoded);
+            let penalty = strategy.config().penalty(&decoded);
+
+            let (ltl_milli, fail_milli) = match cache.get(&decoded) {
+                Some(props) => props,
+                None => {
+                    let (ltl, fail) = evaluate(&decoded, &payload)?;
+                    let props = (ltl.min(1000), fail.min(1000));
+                    cache.insert(decoded.clone(), props);
+                    props
+                }
+            };
+
+            let score =
+                (comps.evaluate(chrom, generation, ltl_milli, fail_milli) - penalty).max(0.0);
+            if best.as_ref().is_none_or(|(_, _, f)| score > *f) {
+                best = Some((decoded, payload, score));
+            }
+            fitness_scores.push(score);
+        }
+
+        generations.push(GaProgress::for_generation(
+            generation,
+            gens,
+            &fitness_scores,
+            &cache,
+        ));
+        strategy.receive(&fitness_scores);
+    }
+
+    let (best_genes, best_payload, best_fitness) = best.unwrap_or_default();
+    Ok(SimulationReport {
+        generations,
+        best_genes,
+        best_payload,
+        best_fitness,
+        elapsed_ms: started.elapsed().as_millis(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use util::execution_config::GeneConfig;
+
+    fn config() -> ExecutionConfig {
+        let mut config = ExecutionConfig::default_config();
+        config.gatlam.population_size = 30;
+        config.gatlam.number_of_generations = 25;
+        config.gatlam.genes = vec![GeneConfig::integer(0, 7); 3];
+        config.gatlam.omega1 = 0.5;
+        config.gatlam.omega2 = 0.5;
+        config.gatlam.omega3 = 0.0;
+        config
+    }
+
+    // A "bug" that only shows when every gene is 5
+    fn find_the_fives(genes: &[i32], _payload: &str) -> Result<(usize, usize), String> {
+        let hits = genes.iter().filter(|&&g| g == 5).count();
+        let ltl = hits * 1000 / genes.len();
+        Ok((ltl, if hits == genes.len() { 1000 } else { 0 }))
+    }
+
+    #[test]
+    fn reports_the_fittest_chromosome_without_an_interpreter() {
+        let report = simulate(&config(), find_the_fives).unwrap();
+
+        assert_eq!(report.generations.len(), 25);
+        let best_of_generations = report
+            .generations
+            .iter()
+            .map(|g| g.best_fitness)
+            .fold(0.0, f64::max);
+        assert!(report.best_fitness > 0.0);
+        assert!((report.best_fitness - best_of_generations).abs() < 1e-9);
+
+        let rendered: Vec<String> = report.best_genes.iter().map(|g| g.to_string()).collect();
+        assert_eq!(report.best_payload, rendered.join(","));
+        if report.best_genes == [5, 5, 5] {
+            assert!((report.best_fitness - 1.0).abs() < 1e-9);
+        }
+
+        let last = report.generations.last().unwrap();
+        assert_eq!(last.evaluated, 30 * 25);
+        assert!(last.interpreter_runs <= last.evaluated);
+    }
+
+    #[test]
+    fn rejects_unnormalised_omegas_and_propagates_evaluator_errors() {
+        let mut unnormalised = config();
+        unnormalised.gatlam.omega3 = 0.5;
+        assert_eq!(
+            simulate(&unnormalised, find_the_fives).unwrap_err(),
+            "Omegas must sum to 1, not 1.5"
+        );
+
+        let err = simulate(&config(), |_, _| Err("evaluator crashed".to_string())).unwrap_err();
+        assert_eq!(err, "evaluator crashed");
+    }
+}
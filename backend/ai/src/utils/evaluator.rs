@@ -2,7 +2,7 @@
 // I generally would not recommend editing this file unless you know what you're doing.
 
 use std::collections::HashMap;
-use util::execution_config::ExecutionConfig;
+use util::execution_config::{ExecutionConfig, PropertyCheck, PropertyRule};
 use util::languages::Language;
 mod strategy;
 use strategy::strategy_for;
@@ -17,6 +17,7 @@ pub enum Property {
     IllegalOutput,     // G(ter => ∀o∈Out ∀x∈X (x =/ o))
     ExpectedExact,     // ExpectedExact
     ExpectedContains,  // ExpectedContains
+    Rule(usize),       // lecturer-defined rule, by index into `TaskSpec::rules`
 }
 
 #[derive(Debug, Clone)]
@@ -28,6 +29,8 @@ pub struct TaskSpec {
     pub max_runtime_ms: Option<u64>,
     /// For IllegalOutput: forbidden outputs X (exact line matches after trim)
     pub forbidden_outputs: Vec<String>,
+    /// Lecturer-defined rules from `gatlam.task_spec.rules`
+    pub rules: Vec<PropertyRule>,
 }
 
 impl Default for TaskSpec {
@@ -37,17 +40,36 @@ impl Default for TaskSpec {
             valid_return_codes: Some(vec![0]),
             max_runtime_ms: None,
             forbidden_outputs: vec![],
+            rules: vec![],
         }
     }
 }
 
 impl TaskSpec {
-    pub fn from_execution_config(config: &ExecutionConfig) -> Self {
+    /// The spec of the task with `task_number` (`None` if unknown) from `gatlam.task_spec`,
+    /// which may be shared by every task or given per task.
+    pub fn from_execution_config(config: &ExecutionConfig, task_number: Option<i64>) -> Self {
+        let task_spec = config.gatlam.task_spec.for_task(task_number);
         Self {
             language: config.project.language,
-            valid_return_codes: Some(config.gatlam.task_spec.valid_return_codes.clone()),
-            max_runtime_ms: config.gatlam.task_spec.max_runtime_ms,
-            forbidden_outputs: config.gatlam.task_spec.forbidden_outputs.clone(),
+            valid_return_codes: Some(task_spec.valid_return_codes),
+            max_runtime_ms: task_spec.max_runtime_ms,
+            forbidden_outputs: task_spec.forbidden_outputs,
+            rules: task_spec.rules,
+        }
+        .for_task(task_number)
+    }
+
+    /// This spec with only the rules that apply to the task with `task_number`.
+    pub fn for_task(&self, task_number: Option<i64>) -> Self {
+        Self {
+            rules: self
+                .rules
+                .iter()
+                .filter(|r| r.applies_to(task_number))
+                .cloned()
+                .collect(),
+            ..self.clone()
         }
     }
 }
@@ -147,6 +169,13 @@ impl Evaluator {
             }
         }
 
+        // Lecturer-defined rules
+        for (i, rule) in spec.rules.iter().enumerate() {
+            if check_rule(&rule.check, view) == Some(false) {
+                violated.push(Property::Rule(i));
+            }
+        }
+
         TaskEvaluation {
             task_id: view.task_id,
             violated,
@@ -250,6 +279,15 @@ impl Evaluator {
                 }
             }
 
+            for (r, rule) in spec.rules.iter().enumerate() {
+                if check_rule(&rule.check, &view).is_some() {
+                    checks += 1;
+                    if eval.violated.contains(&Property::Rule(r)) {
+                        viols += 1;
+                    }
+                }
+            }
+
             ltl_checks += checks;
             ltl_violations += viols;
 
@@ -416,6 +454,31 @@ fn normalized_lines(s: &str) -> Vec<String> {
         .collect()
 }
 
+/// Whether `view` satisfies `check`, or `None` if it cannot be checked: every rule needs a
+/// terminated task and `MaxRuntime` also a reported runtime.
+fn check_rule(check: &PropertyCheck, view: &TaskView) -> Option<bool> {
+    if !view.terminated {
+        return None;
+    }
+    let lines = normalized_lines(&view.stdout);
+    match check {
+        PropertyCheck::MaxRuntime { ms } => view.runtime_ms.map(|r| r <= *ms),
+        PropertyCheck::Contains { value } => Some(lines.iter().any(|l| l.contains(value.trim()))),
+        PropertyCheck::Ordering { values } => {
+            let mut rest = lines.iter();
+            Some(values.iter().all(|v| rest.any(|l| l.contains(v.trim()))))
+        }
+        PropertyCheck::ForbiddenSequence { values } => {
+            let seq: Vec<&str> = values.iter().map(|v| v.trim()).collect();
+            Some(
+                !lines
+                    .windows(seq.len().max(1))
+                    .any(|w| w.iter().map(String::as_str).eq(seq.iter().copied())),
+            )
+        }
+    }
+}
+
 fn is_valid_return_code(exit: Option<i32>, valid: Option<&[i32]>) -> bool {
     match (exit, valid) {
         (Some(code), Some(list)) => list.contains(&code),
@@ -434,6 +497,7 @@ mod tests {
             valid_return_codes: Some(vec![0]),
             max_runtime_ms: None,
             forbidden_outputs: vec![],
+            rules: vec![],
         }
     }
 
@@ -457,9 +521,27 @@ mod tests {
             valid_return_codes: Some(vec![0]),
             max_runtime_ms: None,
             forbidden_outputs: vec![],
+            rules: vec![],
+        }
+    }
+
+    fn spec_cpp_rules(checks: Vec<PropertyCheck>) -> TaskSpec {
+        TaskSpec {
+            rules: checks
+                .into_iter()
+                .map(|check| PropertyRule {
+                    task_number: None,
+                    check,
+                })
+                .collect(),
+            ..spec_cpp()
         }
     }
 
+    fn strings(xs: &[&str]) -> Vec<String> {
+        xs.iter().map(|s| s.to_string()).collect()
+    }
+
     fn out(task_id: i64, blob: &str) -> (i64, String) {
         (task_id, blob.to_string())
     }
@@ -652,6 +734,137 @@ mod tests {
         assert!(!ev.contains_forbidden_output("clean\n", &[String::from("bad")]));
     }
 
+    #[test]
+    fn rules_check_contains_ordering_and_forbidden_sequences() {
+        let ev = Evaluator::new();
+        let spec = spec_cpp_rules(vec![
+            PropertyCheck::Contains {
+                value: "done".into(),
+            },
+            PropertyCheck::Ordering {
+                values: strings(&["push 1", "pop"]),
+            },
+            PropertyCheck::ForbiddenSequence {
+                values: strings(&["pop", "pop"]),
+            },
+            PropertyCheck::Ordering {
+                values: strings(&["pop", "push 1"]),
+            },
+        ]);
+        let view = ev.parse(
+            1,
+            "push 1
+pop
+pop
+all done
+
+Retcode: 0
+",
+        );
+        let eval = ev.evaluate_task(&spec, &view);
+        assert_eq!(eval.violated, [Property::Rule(2), Property::Rule(3)]);
+
+        // Output rules wait for termination
+        let view = ev.parse(
+            1,
+            "push 1
+pop
+pop
+",
+        );
+        assert!(ev.evaluate_task(&spec, &view).violated.is_empty());
+    }
+
+    #[test]
+    fn max_runtime_rule_needs_a_reported_runtime() {
+        let ev = Evaluator::new();
+        let spec = spec_cpp_rules(vec![PropertyCheck::MaxRuntime { ms: 100 }]);
+        let slow = ev.parse(
+            1,
+            "RUNTIME_MS: 150
+Retcode: 0
+",
+        );
+        assert_eq!(ev.evaluate_task(&spec, &slow).violated, [Property::Rule(0)]);
+        let unknown = ev.parse(
+            1,
+            "Retcode: 0
+",
+        );
+        assert!(ev.evaluate_task(&spec, &unknown).violated.is_empty());
+    }
+
+    #[test]
+    fn for_task_keeps_global_and_matching_rules() {
+        let mut spec = spec_cpp_rules(vec![
+            PropertyCheck::MaxRuntime { ms: 1 },
+            PropertyCheck::MaxRuntime { ms: 2 },
+        ]);
+        spec.rules[1].task_number = Some(3);
+        assert_eq!(spec.for_task(Some(3)).rules.len(), 2);
+        assert_eq!(spec.for_task(Some(1)).rules.len(), 1);
+        assert_eq!(spec.for_task(None).rules.len(), 1);
+    }
+
+    #[test]
+    fn from_execution_config_picks_the_tasks_own_spec() {
+        let config: ExecutionConfig = serde_json::from_str(
+            r#"{"gatlam": {"task_spec": {
+                "1": {"valid_return_codes": [0, 3]},
+                "2": {"max_runtime_ms": 100, "rules": [
+                    {"type": "contains", "value": "ok"},
+                    {"type": "contains", "value": "x", "task_number": 1}
+                ]}
+            }}}"#,
+        )
+        .unwrap();
+
+        let first = TaskSpec::from_execution_config(&config, Some(1));
+        assert_eq!(first.valid_return_codes, Some(vec![0, 3]));
+        assert_eq!(first.max_runtime_ms, None);
+
+        let second = TaskSpec::from_execution_config(&config, Some(2));
+        assert_eq!(second.valid_return_codes, Some(vec![0]));
+        assert_eq!(second.max_runtime_ms, Some(100));
+        assert_eq!(second.rules.len(), 1);
+
+        let ev = Evaluator::new();
+        let view = ev.parse(
+            1,
+            "Retcode: 3
+",
+        );
+        assert!(ev.evaluate_task(&first, &view).violated.is_empty());
+        assert_eq!(
+            ev.evaluate_task(&second, &view).violated,
+            [Property::ProperTermination, Property::Rule(0)]
+        );
+    }
+
+    #[test]
+    fn violated_rules_count_towards_ltl() {
+        let ev = Evaluator::new();
+        let delim = "###";
+        let specs = vec![spec_cpp_rules(vec![
+            PropertyCheck::Contains { value: "ok".into() },
+            PropertyCheck::Contains {
+                value: "missing".into(),
+            },
+        ])];
+        let outs = vec![out(
+            1,
+            "ok
+
+Retcode: 0
+",
+        )];
+
+        // checks: 4 core + 2 rules = 6; viols: 1 (missing) -> 166
+        let (ltl_milli, fail_milli) = ev.derive_props(&specs, &outs, &[], delim);
+        assert_eq!(ltl_milli, 166);
+        assert_eq!(fail_milli, 0);
+    }
+
     #[test]
     fn memo_exact_and_contains_both_pass_yield_zero_ltl() {
         let ev = Evaluator::new();
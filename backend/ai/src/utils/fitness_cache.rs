@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+/// Interpreter results for chromosomes that were already evaluated, keyed by decoded genes.
+///
+/// Different bit strings can decode to the same values (e.g. `+0`/`-0`, or magnitudes clamped
+/// to the gene width), and small gene ranges make repeats common across generations, so only
+/// the first occurrence of a gene vector runs the interpreter. Only the expensive interpreter
+/// results are cached; fitness is still computed from them every time, since components such
+/// as the memory term change as the run progresses.
+#[derive(Debug, Clone)]
+pub struct FitnessCache<T> {
+    entries: HashMap<Vec<i32>, T>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<T> Default for FitnessCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+}
+
+impl<T: Clone> FitnessCache<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up `genes`, counting a hit or a miss.
+    pub fn get(&mut self, genes: &[i32]) -> Option<T> {
+        match self.entries.get(genes) {
+            Some(value) => {
+                self.hits += 1;
+                Some(value.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    pub fn insert(&mut self, genes: Vec<i32>, value: T) {
+        self.entries.insert(genes, value);
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Distinct gene vectors evaluated so far.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Share of lookups answered from the cache, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_hits_and_misses_per_gene_vector() {
+        let mut cache = FitnessCache::new();
+        assert_eq!(cache.get(&[1, -2]), None);
+        cache.insert(vec![1, -2], (250usize, 0usize));
+
+        assert_eq!(cache.get(&[1, -2]), Some((250, 0)));
+        assert_eq!(cache.get(&[1, -2]), Some((250, 0)));
+        assert_eq!(cache.get(&[-2, 1]), None);
+
+        assert_eq!(cache.hits(), 2);
+        assert_eq!(cache.misses(), 2);
+        assert_eq!(cache.len(), 1);
+        assert!((cache.hit_rate() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_cache_has_zero_hit_rate() {
+        let cache: FitnessCache<f64> = FitnessCache::new();
+        assert!(cache.is_empty());
+        assert_eq!(cache.hit_rate(), 0.0);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::utils::progress::GaProgress;
+use db::models::ga_generation::{self, GenerationStats, Individual};
+use db::models::ga_run::{self, GaRunMode};
+use sea_orm::DatabaseConnection;
+
+/// Records a GA run in `ga_runs` / `ga_generations`.
+///
+/// Recording is best effort: a database error is logged and the GA carries on, so a run is
+/// never failed just because its history could not be stored.
+pub struct GaHistory<'a> {
+    db: &'a DatabaseConnection,
+    run_id: Option<i64>,
+    best: Option<(f64, String)>,
+}
+
+impl<'a> GaHistory<'a> {
+    /// Creates the `ga_runs` row for a run that is about to start.
+    pub async fn start(
+        db: &'a DatabaseConnection,
+        assignment_id: i64,
+        submission_id: i64,
+        mode: GaRunMode,
+        generations: usize,
+        population_size: usize,
+    ) -> Self {
+        let run = ga_run::Model::start(
+            db,
+            assignment_id,
+            submission_id,
+            mode,
+            generations as i64,
+            population_size as i64,
+        )
+        .await;
+        let run_id = match run {
+            Ok(run) => Some(run.id),
+            Err(e) => {
+                tracing::warn!(submission_id, "Failed to record GA run: {e}");
+                None
+            }
+        };
+        Self {
+            db,
+            run_id,
+            best: None,
+        }
+    }
+
+    /// Stores a finished generation and keeps track of the best individual of the run.
+    pub async fn record(&mut self, progress: &GaProgress, population: &[Individual]) {
+        if let Some(fittest) = population
+            .iter()
+            .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+            && self.best.as_ref().is_none_or(|(f, _)| fittest.fitness > *f)
+        {
+            self.best = Some((fittest.fitness, fittest.payload.clone()));
+        }
+
+        let Some(run_id) = self.run_id else { return };
+        let stats = GenerationStats {
+            generation: progress.generation as i64,
+            best_fitness: progress.best_fitness,
+            mean_fitness: progress.mean_fitness,
+            coverage_percent: progress.coverage_percent,
+            evaluated: progress.evaluated as i64,
+            interpreter_runs: progress.interpreter_runs as i64,
+            cache_hits: progress.cache_hits as i64,
+        };
+        if let Err(e) = ga_generation::Model::record(self.db, run_id, stats, population).await {
+            tracing::warn!(run_id, "Failed to record GA generation: {e}");
+        }
+    }
+
+    /// Payload of the fittest individual recorded so far.
+    pub fn best_payload(&self) -> Option<&str> {
+        self.best.as_ref().map(|(_, payload)| payload.as_str())
+    }
+
+    /// Marks the run completed, or failed with the job's error.
+    pub async fn finish(self, result: &Result<(), String>) {
+        let Some(run_id) = self.run_id else { return };
+        let error = result.as_ref().err().cloned();
+        if let Err(e) = ga_run::Model::finish(self.db, run_id, self.best, error).await {
+            tracing::warn!(run_id, "Failed to finish GA run: {e}");
+        }
+    }
+}
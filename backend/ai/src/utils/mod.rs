@@ -1,3 +1,6 @@
 pub mod attributes;
 pub mod output;
-pub mod evaluator;
\ No newline at end of file
+pub mod evaluator;
+pub mod fitness_cache;
+pub mod history;
+pub mod progress;
\ No newline at end of file
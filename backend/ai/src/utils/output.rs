@@ -1,7 +1,10 @@
 use db::models::assignment_submission_output::Entity as SubmissionOutputEntity;
-use db::models::assignment_task::{Entity as AssignmentTaskEntity, TaskType};
+use db::models::assignment_task::{
+    Column as AssignmentTaskColumn, Entity as AssignmentTaskEntity, TaskType,
+};
 
-use sea_orm::EntityTrait;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, ErrorKind};
 use util::paths::{memo_output_dir, submission_output_dir};
@@ -42,6 +45,20 @@ impl Output {
         Ok(results)
     }
 
+    /// Task number of every task of the assignment, keyed by task id (the id that
+    /// `get_submission_output_*` returns with each output).
+    pub async fn get_task_numbers(
+        db: &sea_orm::DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<HashMap<i64, i64>, String> {
+        let tasks = AssignmentTaskEntity::find()
+            .filter(AssignmentTaskColumn::AssignmentId.eq(assignment_id))
+            .all(db)
+            .await
+            .map_err(|e| format!("Failed to fetch tasks: {}", e))?;
+        Ok(tasks.into_iter().map(|t| (t.id, t.task_number)).collect())
+    }
+
     /// Get all submission output files for the given parameters,
     /// returning Vec<(task_id, file_contents_as_string)>
     #[allow(dead_code)]
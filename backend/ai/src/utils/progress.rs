@@ -0,0 +1,71 @@
+use crate::utils::fitness_cache::FitnessCache;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Summary of one finished GA generation, sent to the caller's [`GaProgressSink`] so a long run
+/// can report how far along it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaProgress {
+    /// Zero-based index of the generation that just finished.
+    pub generation: usize,
+    /// Number of generations the run will evaluate.
+    pub generations: usize,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    /// Best coverage percent in the generation; only set by the coverage GA.
+    pub coverage_percent: Option<f64>,
+    /// Chromosomes scored so far in this run, including cache hits.
+    pub evaluated: usize,
+    /// Chromosomes that needed an interpreter run.
+    pub interpreter_runs: usize,
+    pub cache_hits: usize,
+}
+
+/// Receives a [`GaProgress`] after every generation.
+pub type GaProgressSink = UnboundedSender<GaProgress>;
+
+impl GaProgress {
+    /// Builds the summary for `generation` from its fitness scores and the run's cache, which
+    /// is looked up once per scored chromosome.
+    pub fn for_generation<T: Clone>(
+        generation: usize,
+        generations: usize,
+        fitness_scores: &[f64],
+        cache: &FitnessCache<T>,
+    ) -> Self {
+        let best_fitness = fitness_scores.iter().cloned().fold(f64::MIN, f64::max);
+        let mean_fitness = fitness_scores.iter().sum::<f64>() / fitness_scores.len().max(1) as f64;
+        Self {
+            generation,
+            generations,
+            best_fitness,
+            mean_fitness,
+            coverage_percent: None,
+            evaluated: cache.hits() + cache.misses(),
+            interpreter_runs: cache.misses(),
+            cache_hits: cache.hits(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarises_scores_and_cache_counts() {
+        let mut cache = FitnessCache::new();
+        for genes in [[1], [2], [1], [1]] {
+            if cache.get(&genes).is_none() {
+                cache.insert(genes.to_vec(), 0.0);
+            }
+        }
+
+        let progress = GaProgress::for_generation(1, 10, &[0.25, 0.75, 0.5, 0.5], &cache);
+        assert_eq!(progress.best_fitness, 0.75);
+        assert_eq!(progress.mean_fitness, 0.5);
+        assert_eq!(progress.evaluated, 4);
+        assert_eq!(progress.interpreter_runs, 2);
+        assert_eq!(progress.cache_hits, 2);
+        assert_eq!(progress.coverage_percent, None);
+    }
+}
@@ -4,7 +4,7 @@
 #include <cstdlib>
 #include "LinkedList.hpp" // resolved via -I. -Imemo -Ispec
 
-// Delimiter token must match ExecutionConfig.default_deliminator() => "###"
+// Delimiter token must match ExecutionConfig.default_delimiter() => "###"
 static constexpr const char *DELIM = "###";
 
 static void print_section(const std::string &name)
@@ -1,7 +1,7 @@
 import java.util.*;
 
 public class Main {
-	// ExecutionConfig.default_deliminator() => "###"
+	// ExecutionConfig.default_delimiter() => "###"
 	private static final String DELIM = "###";
 
 	private static void printSection(String name) {
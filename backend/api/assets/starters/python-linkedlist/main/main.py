@@ -1,7 +1,7 @@
 import os
 import sys
 
-# ExecutionConfig.default_deliminator() == "###"
+# ExecutionConfig.default_delimiter() == "###"
 DELIM = "###"
 
 # Prefer student's LinkedList.py, then memo/, then spec/
@@ -4,7 +4,7 @@ use linked_list::LinkedList;
 use std::env;
 
 // Keep delimiter consistent with other languages
-const DELIM: &str = "###"; // matches ExecutionConfig.default_deliminator()
+const DELIM: &str = "###"; // matches ExecutionConfig.default_delimiter()
 
 fn section(name: &str) {
     println!("{} {}", DELIM, name);
@@ -9,3 +9,36 @@ pub struct Claims {
 
 #[derive(Debug, Clone)]
 pub struct AuthUser(pub Claims);
+
+/// Claims of a two-factor challenge token: the password was right, but a code is still owed.
+///
+/// It lacks `admin`, so it can't pass for a session token (nor a session token for it).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TwoFactorChallengeClaims {
+    pub sub: i64,
+    pub exp: usize,
+    pub purpose: String,
+    /// Whether the session it finishes into has admin rights.
+    pub session_admin: bool,
+}
+
+/// Claims of a signed link to one archived MOSS match page and its frames.
+///
+/// `sub` is the MOSS report; `page_group` is the match the link opens (e.g. `match0`), whose
+/// frames (`match0-top.html`, `match0-0.html`, ...) it also opens.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MatchPageLinkClaims {
+    pub sub: i64,
+    pub exp: usize,
+    pub purpose: String,
+    pub module_id: i64,
+    pub assignment_id: i64,
+    pub page_group: String,
+}
+
+/// Marks a request authenticated with an API token (see [`crate::auth::middleware::authenticate_api_token`])
+/// rather than a login JWT.
+#[derive(Debug, Clone)]
+pub struct ApiTokenAuth {
+    pub token_id: i64,
+}
@@ -13,7 +13,8 @@ use util::config;
 ///
 /// This middleware checks for a valid Bearer token in the `Authorization` header,
 /// verifies the JWT using the secret from `JWT_SECRET` environment variable,
-/// and extracts the user claims into an `AuthUser` instance.
+/// and extracts the user claims into an `AuthUser` instance. Requests already authenticated
+/// with an API token (see `authenticate_api_token`) carry their `AuthUser` in the extensions.
 ///
 /// # Errors
 /// - Returns `401 Unauthorized` if the header is missing, malformed, or the token is invalid or expired.
@@ -35,6 +36,10 @@ where
     type Rejection = (StatusCode, &'static str);
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(user) = parts.extensions.get::<AuthUser>() {
+            return Ok(user.clone());
+        }
+
         // Try Authorization header first
         if let Ok(TypedHeader(Authorization(bearer))) =
             TypedHeader::<Authorization<Bearer>>::from_request_parts(parts, state).await
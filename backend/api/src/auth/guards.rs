@@ -1,9 +1,9 @@
-use crate::auth::claims::AuthUser;
+use crate::auth::claims::{ApiTokenAuth, AuthUser};
 use crate::response::ApiResponse;
 use axum::{
     Json,
     body::Body,
-    extract::{FromRequestParts, Path, State},
+    extract::{ConnectInfo, FromRequestParts, Path, State},
     http::{Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
@@ -14,19 +14,25 @@ use db::models::{
     assignment_submission::{Column as SubmissionColumn, Entity as SubmissionEntity},
     assignment_task::{Column as TaskColumn, Entity as TaskEntity},
     attendance_session::{Column as AttendanceSessionColumn, Entity as AttendanceSessionEntity},
+    ga_run::{Column as GaRunColumn, Entity as GaRunEntity},
+    group::{Column as GroupColumn, Entity as GroupEntity},
     module::Entity as ModuleEntity,
     moss_report::{Column as MossReportColumn, Entity as MossReportEntity},
     plagiarism_case::{Column as PlagiarismColumn, Entity as PlagiarismEntity},
+    plagiarism_match::Entity as PlagiarismMatchEntity,
+    plagiarism_report::Entity as PlagiarismReportEntity,
+    regrade_request::{Column as RegradeColumn, Entity as RegradeEntity},
     user,
     user::Entity as UserEntity,
 };
+use db::soft_delete::SoftDelete;
 use sea_orm::ColumnTrait;
 use sea_orm::DatabaseConnection;
 use sea_orm::EntityTrait;
 use sea_orm::QueryFilter;
 use std::{
     collections::{HashMap, HashSet},
-    net::{IpAddr, Ipv4Addr},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
 };
 use util::{config, state::AppState};
 
@@ -103,6 +109,22 @@ pub async fn allow_authenticated(
     Ok(next.run(req).await)
 }
 
+/// Refuses requests authenticated with an API token, for endpoints that need a real login
+/// (e.g. issuing more tokens).
+pub async fn deny_api_tokens(
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ApiResponse<Empty>>)> {
+    if req.extensions().get::<ApiTokenAuth>().is_some() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::error("Log in to do this; API tokens can't")),
+        ));
+    }
+
+    Ok(next.run(req).await)
+}
+
 /// Admin-only guard.
 pub async fn allow_admin(
     req: Request<Body>,
@@ -301,7 +323,11 @@ pub async fn allow_ready_assignment(
         ));
     }
 
-    let assignment = match AssignmentEntity::find_by_id(assignment_id).one(db).await {
+    let assignment = match AssignmentEntity::find_active()
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .one(db)
+        .await
+    {
         Ok(Some(a)) => a,
         Ok(None) => {
             return Err((
@@ -332,10 +358,43 @@ pub async fn allow_ready_assignment(
     Ok(next.run(req).await)
 }
 
+/// Refuses requests that would change an assignment's grades once its moderation has been
+/// signed off (see [`db::models::moderation`]).
+pub async fn deny_after_moderation_sign_off(
+    State(app_state): State<AppState>,
+    Path(params): Path<HashMap<String, String>>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, (StatusCode, Json<ApiResponse<Empty>>)> {
+    let assignment_id = params
+        .get("assignment_id")
+        .and_then(|s| s.parse::<i64>().ok())
+        .ok_or((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Missing or invalid assignment_id")),
+        ))?;
+
+    match db::models::moderation::Model::is_locked(app_state.db(), assignment_id).await {
+        Ok(false) => Ok(next.run(req).await),
+        Ok(true) => Err((
+            StatusCode::CONFLICT,
+            Json(ApiResponse::error(
+                "Grades are locked: this assignment's moderation has been signed off",
+            )),
+        )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(
+                "Database error while checking moderation",
+            )),
+        )),
+    }
+}
+
 // --- Path ID Guards ---
 
 async fn check_module_exists(
-    module_id: i32,
+    module_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     let found = ModuleEntity::find_by_id(module_id)
@@ -361,7 +420,7 @@ async fn check_module_exists(
 }
 
 async fn check_user_exists(
-    user_id: i32,
+    user_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     let found = UserEntity::find_by_id(user_id).one(db).await.map_err(|_| {
@@ -381,13 +440,13 @@ async fn check_user_exists(
 }
 
 async fn check_assignment_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
+    module_id: i64,
+    assignment_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_module_exists(module_id, db).await?;
 
-    let found = AssignmentEntity::find()
+    let found = AssignmentEntity::find_active()
         .filter(AssignmentColumn::Id.eq(assignment_id))
         .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
@@ -414,9 +473,9 @@ async fn check_assignment_hierarchy(
 }
 
 async fn check_task_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    task_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    task_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_assignment_hierarchy(module_id, assignment_id, db).await?;
@@ -446,14 +505,14 @@ async fn check_task_hierarchy(
 }
 
 async fn check_submission_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    submission_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    submission_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_assignment_hierarchy(module_id, assignment_id, db).await?;
 
-    let found = SubmissionEntity::find()
+    let found = SubmissionEntity::find_active()
         .filter(SubmissionColumn::Id.eq(submission_id))
         .filter(SubmissionColumn::AssignmentId.eq(assignment_id))
         .one(db)
@@ -480,9 +539,9 @@ async fn check_submission_hierarchy(
 }
 
 async fn check_file_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    file_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    file_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_assignment_hierarchy(module_id, assignment_id, db).await?;
@@ -512,14 +571,14 @@ async fn check_file_hierarchy(
 }
 
 pub async fn check_ticket_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    ticket_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    ticket_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_assignment_hierarchy(module_id, assignment_id, db).await?;
 
-    let found = db::models::tickets::Entity::find()
+    let found = db::models::tickets::Entity::find_active()
         .filter(db::models::tickets::Column::Id.eq(ticket_id))
         .filter(db::models::tickets::Column::AssignmentId.eq(assignment_id))
         .one(db)
@@ -544,10 +603,10 @@ pub async fn check_ticket_hierarchy(
 }
 
 pub async fn check_message_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    ticket_id: i32,
-    message_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    ticket_id: i64,
+    message_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_ticket_hierarchy(module_id, assignment_id, ticket_id, db).await?;
@@ -577,9 +636,9 @@ pub async fn check_message_hierarchy(
 }
 
 pub async fn check_plagiarism_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    case_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    case_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_assignment_hierarchy(module_id, assignment_id, db).await?;
@@ -611,13 +670,13 @@ pub async fn check_plagiarism_hierarchy(
 }
 
 pub async fn check_announcement_hierarchy(
-    module_id: i32,
-    announcement_id: i32,
+    module_id: i64,
+    announcement_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     check_module_exists(module_id, db).await?;
 
-    let found = db::models::announcements::Entity::find()
+    let found = db::models::announcements::Entity::find_active()
         .filter(db::models::announcements::Column::Id.eq(announcement_id))
         .filter(db::models::announcements::Column::ModuleId.eq(module_id))
         .one(db)
@@ -644,8 +703,8 @@ pub async fn check_announcement_hierarchy(
 }
 
 async fn check_attendance_session_hierarchy(
-    module_id: i32,
-    session_id: i32,
+    module_id: i64,
+    session_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     // Ensure module exists
@@ -678,10 +737,76 @@ async fn check_attendance_session_hierarchy(
     Ok(())
 }
 
+async fn check_group_hierarchy(
+    module_id: i64,
+    assignment_id: i64,
+    group_id: i64,
+    db: &DatabaseConnection,
+) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
+    check_assignment_hierarchy(module_id, assignment_id, db).await?;
+
+    let found = GroupEntity::find()
+        .filter(GroupColumn::Id.eq(group_id))
+        .filter(GroupColumn::AssignmentId.eq(assignment_id))
+        .one(db)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("Database error while checking group")),
+            )
+        })?;
+
+    if found.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!(
+                "Group {} in Assignment {} not found.",
+                group_id, assignment_id
+            ))),
+        ));
+    }
+    Ok(())
+}
+
+async fn check_regrade_hierarchy(
+    module_id: i64,
+    assignment_id: i64,
+    regrade_id: i64,
+    db: &DatabaseConnection,
+) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
+    check_assignment_hierarchy(module_id, assignment_id, db).await?;
+
+    let found = RegradeEntity::find()
+        .filter(RegradeColumn::Id.eq(regrade_id))
+        .filter(RegradeColumn::AssignmentId.eq(assignment_id))
+        .one(db)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(
+                    "Database error while checking regrade request",
+                )),
+            )
+        })?;
+
+    if found.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!(
+                "Regrade request {} in Assignment {} not found.",
+                regrade_id, assignment_id
+            ))),
+        ));
+    }
+    Ok(())
+}
+
 async fn check_moss_report_hierarchy(
-    module_id: i32,
-    assignment_id: i32,
-    report_id: i32,
+    module_id: i64,
+    assignment_id: i64,
+    report_id: i64,
     db: &DatabaseConnection,
 ) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
     // Ensure module & assignment exist / relate
@@ -714,6 +839,83 @@ async fn check_moss_report_hierarchy(
     Ok(())
 }
 
+async fn check_ga_run_hierarchy(
+    module_id: i64,
+    assignment_id: i64,
+    run_id: i64,
+    db: &DatabaseConnection,
+) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
+    // Ensure module & assignment exist / relate
+    check_assignment_hierarchy(module_id, assignment_id, db).await?;
+
+    // Ensure the GA run belongs to the assignment
+    let found = GaRunEntity::find()
+        .filter(GaRunColumn::Id.eq(run_id))
+        .filter(GaRunColumn::AssignmentId.eq(assignment_id))
+        .one(db)
+        .await
+        .map_err(|_| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("Database error while checking GA run")),
+            )
+        })?;
+
+    if found.is_none() {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!(
+                "GA run {} in Assignment {} not found.",
+                run_id, assignment_id
+            ))),
+        ));
+    }
+    Ok(())
+}
+
+async fn check_plagiarism_match_hierarchy(
+    module_id: i64,
+    assignment_id: i64,
+    report_id: i64,
+    match_id: i64,
+    db: &DatabaseConnection,
+) -> Result<(), (StatusCode, Json<ApiResponse<Empty>>)> {
+    // Ensure the report belongs to the assignment
+    check_moss_report_hierarchy(module_id, assignment_id, report_id, db).await?;
+
+    // Ensure the pair was parsed from that report
+    let db_err = |_| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(
+                "Database error while checking plagiarism match",
+            )),
+        )
+    };
+    let pair = PlagiarismMatchEntity::find_by_id(match_id)
+        .one(db)
+        .await
+        .map_err(db_err)?;
+    let parsed = match &pair {
+        Some(p) => PlagiarismReportEntity::find_by_id(p.report_id)
+            .one(db)
+            .await
+            .map_err(db_err)?,
+        None => None,
+    };
+
+    if parsed.is_none_or(|r| r.moss_report_id != report_id) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error(format!(
+                "Match {} in report {} not found.",
+                match_id, report_id
+            ))),
+        ));
+    }
+    Ok(())
+}
+
 pub async fn validate_known_ids(
     State(app_state): State<AppState>,
     Path(params): Path<HashMap<String, String>>,
@@ -722,26 +924,33 @@ pub async fn validate_known_ids(
 ) -> Result<Response, Response> {
     let db = app_state.db();
 
-    let mut module_id: Option<i32> = None;
-    let mut assignment_id: Option<i32> = None;
-    let mut task_id: Option<i32> = None;
-    let mut submission_id: Option<i32> = None;
-    let mut file_id: Option<i32> = None;
-    let mut user_id: Option<i32> = None;
-    let mut ticket_id: Option<i32> = None;
-    let mut message_id: Option<i32> = None;
-    let mut case_id: Option<i32> = None;
-    let mut announcement_id: Option<i32> = None;
-    let mut session_id: Option<i32> = None;
-    let mut report_id: Option<i32> = None;
+    let mut module_id: Option<i64> = None;
+    let mut assignment_id: Option<i64> = None;
+    let mut task_id: Option<i64> = None;
+    let mut submission_id: Option<i64> = None;
+    let mut file_id: Option<i64> = None;
+    let mut user_id: Option<i64> = None;
+    let mut ticket_id: Option<i64> = None;
+    let mut message_id: Option<i64> = None;
+    let mut case_id: Option<i64> = None;
+    let mut announcement_id: Option<i64> = None;
+    let mut session_id: Option<i64> = None;
+    let mut report_id: Option<i64> = None;
+    let mut run_id: Option<i64> = None;
+    let mut match_id: Option<i64> = None;
+    let mut group_id: Option<i64> = None;
+    let mut regrade_id: Option<i64> = None;
 
     for (key, raw) in &params {
         match key.as_str() {
-            // numeric ids → parse i32 (existing behavior)
+            // numeric ids → parse i64
             "module_id" | "assignment_id" | "task_id" | "submission_id" | "file_id" | "user_id"
             | "ticket_id" | "case_id" | "announcement_id" | "message_id" | "session_id"
-            | "report_id" => {
-                let id = raw.parse::<i32>().map_err(|_| {
+            | "report_id" | "run_id" | "match_id" | "notification_id" | "group_id"
+            | "regrade_id" | "template_id" | "platform_id" | "deployment_id" | "key_id"
+            | "upload_id" | "part_number" | "sample_id" | "evidence_id" | "token_id"
+            | "context_id" => {
+                let id = raw.parse::<i64>().map_err(|_| {
                     (
                         StatusCode::BAD_REQUEST,
                         Json(ApiResponse::<Empty>::error(format!(
@@ -764,10 +973,20 @@ pub async fn validate_known_ids(
                     "message_id" => message_id = Some(id),
                     "session_id" => session_id = Some(id),
                     "report_id" => report_id = Some(id),
+                    "run_id" => run_id = Some(id),
+                    "match_id" => match_id = Some(id),
+                    "group_id" => group_id = Some(id),
+                    "regrade_id" => regrade_id = Some(id),
+                    // notifications and uploads are looked up scoped to the caller, and export
+                    // templates, LTI registrations, moderation samples and plagiarism evidence
+                    // checked, by the handler
                     _ => {}
                 }
             }
 
+            // file names and signed link tokens (archived MOSS match pages) → validated by the handler
+            "page" | "token" => {}
+
             // anything else → still reject
             _ => {
                 return Err((
@@ -782,6 +1001,20 @@ pub async fn validate_known_ids(
         }
     }
 
+    // Restoring targets a deleted row, which the checks below would reject; the handler looks
+    // up the innermost soft-deletable id itself.
+    if req.uri().path().ends_with("/restore") {
+        if announcement_id.is_some() {
+            announcement_id = None;
+        } else if ticket_id.is_some() {
+            ticket_id = None;
+        } else if submission_id.is_some() {
+            submission_id = None;
+        } else {
+            assignment_id = None;
+        }
+    }
+
     // existing checks (unchanged)
     if let Some(uid) = user_id {
         check_user_exists(uid, db)
@@ -845,6 +1078,28 @@ pub async fn validate_known_ids(
             .await
             .map_err(|e| e.into_response())?;
     }
+    if let (Some(mid), Some(aid), Some(rid)) = (module_id, assignment_id, run_id) {
+        check_ga_run_hierarchy(mid, aid, rid, db)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+    if let (Some(mid), Some(aid), Some(rid), Some(maid)) =
+        (module_id, assignment_id, report_id, match_id)
+    {
+        check_plagiarism_match_hierarchy(mid, aid, rid, maid, db)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+    if let (Some(mid), Some(aid), Some(gid)) = (module_id, assignment_id, group_id) {
+        check_group_hierarchy(mid, aid, gid, db)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
+    if let (Some(mid), Some(aid), Some(rid)) = (module_id, assignment_id, regrade_id) {
+        check_regrade_hierarchy(mid, aid, rid, db)
+            .await
+            .map_err(|e| e.into_response())?;
+    }
 
     Ok(next.run(req).await)
 }
@@ -871,7 +1126,8 @@ pub async fn allow_ticket_ws_access(
         ))?;
 
     // Load ticket -> get assignment_id and author
-    let ticket = db::models::tickets::Entity::find_by_id(ticket_id)
+    let ticket = db::models::tickets::Entity::find_active()
+        .filter(db::models::tickets::Column::Id.eq(ticket_id))
         .one(db)
         .await
         .map_err(|_| {
@@ -1002,7 +1258,8 @@ pub async fn allow_attendance_ws_access(
 }
 
 /// Guard that enforces assignment security **for students only**:
-/// 1) Checks client IP against allowlist (if configured)
+/// 1) Checks client IP (the peer address unless an `IpAddr` extension is set) against
+///    allowlist (if configured)
 /// 2) Then verifies PIN, if required
 ///
 /// Admin + staff (Lecturer, AssistantLecturer, Tutor) are bypassed.
@@ -1058,7 +1315,7 @@ pub async fn allow_assignment_access(
     }
 
     // Load assignment for security config
-    let assignment = AssignmentEntity::find()
+    let assignment = AssignmentEntity::find_active()
         .filter(AssignmentColumn::Id.eq(assignment_id))
         .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
@@ -1077,10 +1334,16 @@ pub async fn allow_assignment_access(
         ))?;
 
     // ---- 1) IP allowlist check (students only) ----
+    // the peer address, unless an upstream layer has set the client `IpAddr`
     let mut client_ip = req
         .extensions()
         .get::<IpAddr>()
         .cloned()
+        .or_else(|| {
+            req.extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip())
+        })
         .unwrap_or_else(|| "127.0.0.1".parse().unwrap());
 
     // Normalize IPv6 loopback (::1) to IPv4 loopback (127.0.0.1) for deterministic tests & configs
@@ -1163,7 +1426,7 @@ pub async fn allow_submission_ws_owner_only(
 
     // Validate relationship: assignment belongs to module (fail-closed on DB errors)
     use db::models::assignment::{Column as ACol, Entity as AEntity};
-    AEntity::find()
+    AEntity::find_active()
         .filter(ACol::Id.eq(assignment_id))
         .filter(ACol::ModuleId.eq(module_id))
         .one(db)
@@ -1,15 +1,26 @@
-use crate::auth::claims::AuthUser;
+use crate::auth::claims::{ApiTokenAuth, AuthUser, Claims};
+use crate::response::ApiResponse;
 use axum::{
+    Json,
     body::Body,
-    extract::{ConnectInfo, FromRequestParts},
-    http::{Method, Request, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, State},
+    http::{Extensions, HeaderValue, Method, Request, StatusCode, header},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
 use axum_extra::extract::TypedHeader;
-use headers::{Origin, UserAgent};
-use std::net::SocketAddr;
+use chrono::Utc;
+use db::models::{
+    api_token::{self, Scope},
+    user,
+};
+use headers::{Authorization, Origin, UserAgent, authorization::Bearer};
+use sea_orm::EntityTrait;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::info;
+use util::{config, rate_limit::RateLimiter, state::AppState};
 
 /// Logs method, path, IP address, user ID (if authenticated), origin, and user-agent
 /// for each incoming HTTP request. Automatically skips CORS preflight `OPTIONS` requests.
@@ -79,3 +90,233 @@ pub async fn log_request(
     let req = Request::from_parts(parts, body);
     Ok(next.run(req).await)
 }
+
+/// Authenticates requests that carry an API token (`Authorization: Bearer ffk_...`) instead of
+/// a login JWT.
+///
+/// A valid, unexpired token whose scopes allow the request's method becomes the owner's
+/// `AuthUser` (an admin only with the `admin` scope) plus an [`ApiTokenAuth`] marker, both in
+/// the request extensions, where the `AuthUser` extractor and every guard find them. Requests
+/// without an API token pass through untouched.
+///
+/// ### Responses
+/// - `401 Unauthorized` — Unknown or expired token
+/// - `403 Forbidden` — A `read` token on anything but `GET`/`HEAD`
+pub async fn authenticate_api_token(
+    State(app_state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = req.into_parts();
+    let token =
+        match TypedHeader::<Authorization<Bearer>>::from_request_parts(&mut parts, &()).await {
+            Ok(TypedHeader(Authorization(bearer))) if api_token::is_api_token(bearer.token()) => {
+                bearer.token().to_string()
+            }
+            _ => return next.run(Request::from_parts(parts, body)).await,
+        };
+
+    let reject = |status: StatusCode, message: &str| {
+        (status, Json(ApiResponse::<()>::error(message))).into_response()
+    };
+    let db = app_state.db();
+    let found = match api_token::Model::authenticate(db, &token, Utc::now()).await {
+        Ok(Some(found)) => found,
+        Ok(None) => return reject(StatusCode::UNAUTHORIZED, "Invalid or expired API token"),
+        Err(e) => {
+            tracing::warn!("Failed to check API token: {}", e);
+            return reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to check API token",
+            );
+        }
+    };
+    if !found.allows_method(parts.method.as_str()) {
+        return reject(
+            StatusCode::FORBIDDEN,
+            "This API token is read-only; it needs the write scope",
+        );
+    }
+    let owner = match user::Entity::find_by_id(found.user_id).one(db).await {
+        Ok(Some(owner)) => owner,
+        Ok(None) => return reject(StatusCode::UNAUTHORIZED, "Invalid or expired API token"),
+        Err(e) => {
+            tracing::warn!("Failed to load API token owner: {}", e);
+            return reject(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to check API token",
+            );
+        }
+    };
+
+    parts.extensions.insert(AuthUser(Claims {
+        sub: owner.id,
+        admin: owner.admin && found.has_scope(Scope::Admin),
+        exp: found.expires_at.timestamp() as usize,
+    }));
+    parts.extensions.insert(ApiTokenAuth { token_id: found.id });
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Which bucket a request is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateClass {
+    /// Logins, registration and password resets; keyed by IP, as the caller isn't signed in.
+    Auth,
+    /// New submissions (`POST /modules/{module_id}/assignments/{assignment_id}/submissions`).
+    Submission,
+    /// Everything else.
+    General,
+}
+
+impl RateClass {
+    /// Classifies a request by method and path, relative to `/api`.
+    pub fn of(method: &Method, path: &str) -> Self {
+        if method != Method::POST {
+            return RateClass::General;
+        }
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        match segments.as_slice() {
+            [
+                "auth",
+                "login"
+                | "register"
+                | "request-password-reset"
+                | "verify-reset-token"
+                | "reset-password",
+                ..,
+            ] => RateClass::Auth,
+            ["modules", _, "assignments", _, "submissions"] => RateClass::Submission,
+            _ => RateClass::General,
+        }
+    }
+}
+
+/// The token buckets behind [`rate_limit`], one per [`RateClass`], plus the per-IP bucket of
+/// [`rate_limit_api_token_lookups`].
+pub struct RateLimits {
+    general: RateLimiter,
+    auth: RateLimiter,
+    submissions: RateLimiter,
+    api_token_lookups: RateLimiter,
+}
+
+impl RateLimits {
+    /// Limits of `general`, `auth` and `submissions` requests a minute per key. API token
+    /// lookups get the `general` limit per IP.
+    pub fn new(general: u32, auth: u32, submissions: u32) -> Self {
+        Self {
+            general: RateLimiter::per_minute(general),
+            auth: RateLimiter::per_minute(auth),
+            submissions: RateLimiter::per_minute(submissions),
+            api_token_lookups: RateLimiter::per_minute(general),
+        }
+    }
+
+    /// The limits from `RATE_LIMIT_*`.
+    pub fn from_config() -> Self {
+        Self::new(
+            config::rate_limit_per_minute(),
+            config::rate_limit_auth_per_minute(),
+            config::rate_limit_submissions_per_minute(),
+        )
+    }
+
+    fn limiter(&self, class: RateClass) -> &RateLimiter {
+        match class {
+            RateClass::Auth => &self.auth,
+            RateClass::Submission => &self.submissions,
+            RateClass::General => &self.general,
+        }
+    }
+}
+
+/// Rate limits requests with a token bucket per caller, so a flood from some users (say, every
+/// student resubmitting in the minute before a deadline) can't slow the API down for everyone.
+///
+/// Signed-in callers are keyed by user id, anyone else by IP address. Auth requests are always
+/// keyed by IP, and new submissions get their own, stricter bucket; see [`RateClass`]. Must run
+/// after [`authenticate_api_token`] so token callers are keyed by their owner.
+///
+/// ### Responses
+/// - `429 Too Many Requests` — Out of tokens, with `Retry-After` in seconds
+pub async fn rate_limit(
+    State(limits): State<Arc<RateLimits>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = req.into_parts();
+    let class = RateClass::of(&parts.method, parts.uri.path());
+    let ip = client_ip(&parts.extensions);
+    let user = match class {
+        RateClass::Auth => None,
+        _ => AuthUser::from_request_parts(&mut parts, &())
+            .await
+            .ok()
+            .map(|AuthUser(claims)| claims.sub),
+    };
+    let key = match (user, ip) {
+        (Some(id), _) => format!("user:{id}"),
+        (None, Some(ip)) => format!("ip:{ip}"),
+        (None, None) => "ip:unknown".to_string(),
+    };
+
+    if let Err(wait) = limits.limiter(class).check(&key, Instant::now()) {
+        return too_many_requests(wait);
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Rate limits requests carrying an API token by IP before the token is looked up, so a flood
+/// of made-up tokens can't reach the database unthrottled. Must run before
+/// [`authenticate_api_token`].
+///
+/// ### Responses
+/// - `429 Too Many Requests` — Out of tokens, with `Retry-After` in seconds
+pub async fn rate_limit_api_token_lookups(
+    State(limits): State<Arc<RateLimits>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = req.into_parts();
+    let has_api_token =
+        match TypedHeader::<Authorization<Bearer>>::from_request_parts(&mut parts, &()).await {
+            Ok(TypedHeader(Authorization(bearer))) => api_token::is_api_token(bearer.token()),
+            Err(_) => false,
+        };
+    if has_api_token {
+        let key = match client_ip(&parts.extensions) {
+            Some(ip) => format!("ip:{ip}"),
+            None => "ip:unknown".to_string(),
+        };
+        if let Err(wait) = limits.api_token_lookups.check(&key, Instant::now()) {
+            return too_many_requests(wait);
+        }
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// The caller's IP address, as set by the server or a trusted proxy layer.
+fn client_ip(extensions: &Extensions) -> Option<IpAddr> {
+    extensions.get::<IpAddr>().cloned().or_else(|| {
+        extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    })
+}
+
+/// `429 Too Many Requests` with `Retry-After` rounded up to whole seconds.
+fn too_many_requests(wait: Duration) -> Response {
+    let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(ApiResponse::<()>::error(format!(
+            "Too many requests; try again in {secs} seconds"
+        ))),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    response
+}
@@ -1,16 +1,19 @@
 //! Authentication utilities and JWT helpers.
 //!
-//! Provides claims, guards, extractors, middleware, and a function to generate JWTs.
+//! Provides claims, guards, extractors, middleware, sign-in state cookies, and functions to
+//! generate JWTs, the short-lived challenge tokens used between a password and a two-factor
+//! code, and the signed links that open archived MOSS match pages.
 
 pub mod claims;
 pub mod extractors;
 pub mod guards;
 pub mod middleware;
+pub mod state_cookie;
 
-pub use claims::{AuthUser, Claims};
+pub use claims::{ApiTokenAuth, AuthUser, Claims, MatchPageLinkClaims, TwoFactorChallengeClaims};
 
 use chrono::{Duration, Utc};
-use jsonwebtoken::{EncodingKey, Header, encode};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
 use util::config;
 
 /// Generates a JWT and its expiry timestamp for a given user.
@@ -36,3 +39,86 @@ pub fn generate_jwt(user_id: i64, admin: bool) -> (String, String) {
 
     (token, expiry.to_rfc3339())
 }
+
+/// `purpose` of a two-factor challenge token.
+const TWO_FACTOR_PURPOSE: &str = "two_factor";
+
+/// How long the user has to enter their two-factor code after their password.
+pub const TWO_FACTOR_CHALLENGE_MINUTES: i64 = 5;
+
+/// Generates a two-factor challenge token for a user whose password checked out, finishing into
+/// a session with `admin` rights.
+pub fn generate_two_factor_challenge(user_id: i64, admin: bool) -> String {
+    let expiry = Utc::now() + Duration::minutes(TWO_FACTOR_CHALLENGE_MINUTES);
+    let claims = TwoFactorChallengeClaims {
+        sub: user_id,
+        exp: expiry.timestamp() as usize,
+        purpose: TWO_FACTOR_PURPOSE.to_string(),
+        session_admin: admin,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config::jwt_secret().as_bytes()),
+    )
+    .expect("Token encoding failed")
+}
+
+/// The user a valid, unexpired two-factor challenge token was issued to, and whether their
+/// session gets admin rights.
+pub fn decode_two_factor_challenge(token: &str) -> Option<(i64, bool)> {
+    let data = decode::<TwoFactorChallengeClaims>(
+        token,
+        &DecodingKey::from_secret(config::jwt_secret().as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .ok()?;
+    (data.claims.purpose == TWO_FACTOR_PURPOSE)
+        .then_some((data.claims.sub, data.claims.session_admin))
+}
+
+/// `purpose` of a MOSS match page link token.
+const MATCH_PAGE_LINK_PURPOSE: &str = "moss_match_page";
+
+/// How long a signed MOSS match page link stays valid.
+pub const MATCH_PAGE_LINK_MINUTES: i64 = 10;
+
+/// Signs a link token opening the pages of `page_group` (e.g. `match0`) archived for a MOSS
+/// report, and returns it with its expiry timestamp.
+pub fn generate_match_page_link(
+    module_id: i64,
+    assignment_id: i64,
+    report_id: i64,
+    page_group: &str,
+) -> (String, String) {
+    let expiry = Utc::now() + Duration::minutes(MATCH_PAGE_LINK_MINUTES);
+    let claims = MatchPageLinkClaims {
+        sub: report_id,
+        exp: expiry.timestamp() as usize,
+        purpose: MATCH_PAGE_LINK_PURPOSE.to_string(),
+        module_id,
+        assignment_id,
+        page_group: page_group.to_string(),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config::jwt_secret().as_bytes()),
+    )
+    .expect("Token encoding failed");
+
+    (token, expiry.to_rfc3339())
+}
+
+/// The claims of a valid, unexpired MOSS match page link token.
+pub fn decode_match_page_link(token: &str) -> Option<MatchPageLinkClaims> {
+    let data = decode::<MatchPageLinkClaims>(
+        token,
+        &DecodingKey::from_secret(config::jwt_secret().as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .ok()?;
+    (data.claims.purpose == MATCH_PAGE_LINK_PURPOSE).then_some(data.claims)
+}
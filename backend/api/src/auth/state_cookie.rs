@@ -0,0 +1,73 @@
+//! Cookies tying a sign-in's `state` to the browser that started it.
+//!
+//! A state row on its own proves only that *someone* started a sign-in. Without the cookie, an
+//! attacker could start one, sign in at the provider as themselves, and hand the callback URL to
+//! a victim, whose browser would then be signed in to the attacker's account (login CSRF). The
+//! callback only accepts a state that matches the cookie set when that browser started it.
+
+use axum::http::{HeaderMap, HeaderValue, header};
+
+/// How the cookie travels on cross-site requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// Sent on top-level navigations, such as a provider's redirect back.
+    Lax,
+    /// Sent on cross-site POSTs too, such as an LMS's form post; needs `Secure`.
+    None,
+}
+
+/// A `Set-Cookie` value storing `state` under `name` for requests under `path`.
+pub fn set(
+    name: &str,
+    state: &str,
+    path: &str,
+    same_site: SameSite,
+    max_age_secs: i64,
+) -> HeaderValue {
+    let (same_site, secure) = match same_site {
+        SameSite::Lax => ("Lax", util::config::app_config().is_production()),
+        SameSite::None => ("None", true),
+    };
+    let mut cookie = format!(
+        "{name}={state}; Path={path}; Max-Age={max_age_secs}; HttpOnly; SameSite={same_site}"
+    );
+    if secure {
+        cookie.push_str("; Secure");
+    }
+    HeaderValue::from_str(&cookie).expect("state cookies are plain ASCII")
+}
+
+/// A `Set-Cookie` value removing the cookie `name` under `path`.
+pub fn clear(name: &str, path: &str) -> HeaderValue {
+    HeaderValue::from_str(&format!("{name}=; Path={path}; Max-Age=0; HttpOnly"))
+        .expect("state cookies are plain ASCII")
+}
+
+/// Whether the request carries the cookie `name` holding exactly `state`.
+pub fn matches(headers: &HeaderMap, name: &str, state: &str) -> bool {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .any(|(k, v)| k == name && !v.is_empty() && v == state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_only_the_named_cookie_with_the_same_state() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("other=abc; ff_state=abc123"),
+        );
+        assert!(matches(&headers, "ff_state", "abc123"));
+        assert!(!matches(&headers, "ff_state", "abc"));
+        assert!(!matches(&headers, "other", "abc123"));
+        assert!(!matches(&HeaderMap::new(), "ff_state", "abc123"));
+    }
+}
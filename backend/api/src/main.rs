@@ -1,8 +1,11 @@
 use api::auth::guards::{SUPERUSER_IDS, validate_known_ids};
 use api::routes::routes;
+use api::services::{
+    announcement_publisher, email_outbox, ticket_escalation::escalate_unanswered_tickets,
+};
 use api::ws::system::payload::{
-    CodeManagerAdmin, CodeManagerGeneral, CpuInfo, DiskSummary, LoadAverages, MemoryInfo,
-    SystemHealthAdminPayload, SystemHealthGeneralPayload,
+    CodeManagerAdmin, CodeManagerGeneral, ContainerInfo, CpuInfo, DiskSummary, GpuInfo,
+    LoadAverages, MemoryInfo, SystemHealthAdminPayload, SystemHealthGeneralPayload,
 };
 use api::{auth::middleware::log_request, ws::ws_routes};
 use axum::{
@@ -10,8 +13,9 @@ use axum::{
     http::header::{CONTENT_DISPOSITION, CONTENT_TYPE},
     middleware::{from_fn, from_fn_with_state},
 };
-use db::connect;
+use db::{connect, connect_read_replica};
 use db::models::system_metric::ActiveModel as SystemMetricActive;
+use db::soft_delete::{purge_deleted_before, retention_cutoff};
 use sea_orm::{
     ActiveModelTrait,
     ActiveValue::{NotSet, Set},
@@ -31,7 +35,27 @@ use api::ws::system::emit::{health_admin, health_general};
 #[tokio::main]
 async fn main() {
     // Load configuration and initialize logging
-    let _log_guard = init_logging(&config::log_file(), &config::log_level());
+    let cfg = config::init().unwrap_or_else(|e| panic!("Invalid configuration:\n{e}"));
+    let _log_guard = init_logging(&cfg.log_file, &cfg.log_level);
+    tracing::info!("Loaded configuration: {:?}", cfg);
+
+    #[cfg(feature = "hot-reload")]
+    config::spawn_hot_reload(Duration::from_secs(30), |changed| {
+        if changed.contains(&"max_number_containers") {
+            tokio::spawn(async {
+                let max = config::live().max_number_containers;
+                let client = reqwest::Client::new();
+                if let Err(e) =
+                    code_runner::code_manager_client::set_max_concurrent(&client, max).await
+                {
+                    tracing::warn!(
+                        "Failed to push reloaded MAX_NUM_CONTAINERS to code_manager: {}",
+                        e
+                    );
+                }
+            });
+        }
+    });
 
     // Initialize superuser IDs
     let _ = once_cell::sync::Lazy::force(&SUPERUSER_IDS);
@@ -39,11 +63,25 @@ async fn main() {
     // Set up dependencies
     let db = connect().await;
     let ws = WebSocketManager::new();
-    let app_state = AppState::new(db, ws);
+    let mut app_state = AppState::new(db, ws);
+    if let Some(replica) = connect_read_replica().await {
+        tracing::info!("Serving read-heavy queries from the read replica");
+        app_state = app_state.with_read_replica(replica);
+    }
 
     // Spawn periodic system health broadcaster over WebSockets
     spawn_system_health_broadcaster(app_state.clone());
 
+    // Purge soft-deleted rows once their retention period is over
+    spawn_retention_sweeper(app_state.clone());
+
+    // Escalate tickets that have gone unanswered by staff to the module's lecturers
+    spawn_ticket_escalator(app_state.clone());
+    spawn_announcement_publisher(app_state.clone());
+
+    // Send queued notification emails, and digests for users in digest mode
+    spawn_email_outbox(app_state.clone());
+
     // Configure middleware
     let cors = CorsLayer::very_permissive().expose_headers([CONTENT_DISPOSITION, CONTENT_TYPE]);
 
@@ -60,15 +98,13 @@ async fn main() {
         .with_state(app_state);
 
     // Start server
-    let addr: SocketAddr = format!("{}:{}", config::host(), config::port())
+    let addr: SocketAddr = format!("{}:{}", cfg.host, cfg.port)
         .parse()
         .expect("Invalid address");
 
     println!(
         "Starting {} on http://{}:{}",
-        config::project_name(),
-        config::host(),
-        config::port()
+        cfg.project_name, cfg.host, cfg.port
     );
 
     axum::serve(
@@ -120,42 +156,108 @@ fn init_logging(log_file: &str, _log_level: &str) -> tracing_appender::non_block
     guard
 }
 
+fn spawn_retention_sweeper(app_state: AppState) {
+    let db = app_state.db_clone();
+
+    tokio::spawn(async move {
+        loop {
+            let cutoff = retention_cutoff(config::soft_delete_retention_days());
+            match purge_deleted_before(&db, cutoff).await {
+                Ok(report) if report.total() > 0 => {
+                    tracing::info!("Retention sweep purged {:?}", report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Retention sweep failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(config::retention_sweep_interval_secs()))
+                .await;
+        }
+    });
+}
+
+fn spawn_ticket_escalator(app_state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            match escalate_unanswered_tickets(&app_state, chrono::Utc::now()).await {
+                Ok(n) if n > 0 => tracing::info!("Escalated {} unanswered ticket(s)", n),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Ticket escalation failed: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(config::ticket_escalation_interval_secs()))
+                .await;
+        }
+    });
+}
+
+fn spawn_announcement_publisher(app_state: AppState) {
+    tokio::spawn(async move {
+        loop {
+            match announcement_publisher::publish_due_announcements(&app_state, chrono::Utc::now())
+                .await
+            {
+                Ok(n) if n > 0 => tracing::info!("Published {} scheduled announcement(s)", n),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Announcement publishing failed: {}", e),
+            }
+            tokio::time::sleep(announcement_publisher::PUBLISH_INTERVAL).await;
+        }
+    });
+}
+
+fn spawn_email_outbox(app_state: AppState) {
+    let db = app_state.db_clone();
+    tokio::spawn(async move {
+        loop {
+            match email_outbox::send_pending(&db).await {
+                Ok(n) if n > 0 => tracing::info!("Sent {} notification email(s)", n),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Email outbox failed: {}", e),
+            }
+            tokio::time::sleep(email_outbox::SEND_INTERVAL).await;
+        }
+    });
+
+    let db = app_state.db_clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(config::email_digest_interval_secs())).await;
+            match email_outbox::send_digests(&db).await {
+                Ok(n) if n > 0 => tracing::info!("Sent {} email digest(s)", n),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Email digest failed: {}", e),
+            }
+        }
+    });
+}
+
 fn spawn_system_health_broadcaster(app_state: AppState) {
-    let interval_ms = config::system_health_broadcast_ms();
-    let cm_host = config::code_manager_host();
-    let cm_port = config::code_manager_port();
     let ws = app_state.ws_clone();
     let db = app_state.db_clone();
 
     tokio::spawn(async move {
         let client = reqwest::Client::new();
-        let persist_interval = Duration::from_secs(config::system_health_persist_seconds());
         let mut last_persist = Instant::now();
         let mut first_persist = true;
 
         loop {
-            tokio::time::sleep(Duration::from_millis(interval_ms)).await;
-            let metrics = sample_system_metrics();
+            // Read every tick so hot-reloaded intervals take effect without a restart.
+            let live = config::live();
+            tokio::time::sleep(Duration::from_millis(live.system_health_broadcast_ms)).await;
+            let persist_interval = Duration::from_secs(live.system_health_persist_seconds);
+            // Shells out to docker/nvidia-smi and waits on CPU sampling; keep it off the runtime.
+            let Ok(metrics) = tokio::task::spawn_blocking(sample_system_metrics).await else {
+                continue;
+            };
 
             // Code manager stats
             let mut cm_running: usize = 0;
             let mut cm_waiting: usize = 0;
             let mut cm_max: Option<usize> = None;
 
-            let cm_url = format!("http://{}:{}/stats", cm_host, cm_port);
-            if let Ok(resp) = client.get(&cm_url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(v) = resp.json::<serde_json::Value>().await {
-                        cm_running =
-                            v.get("running").and_then(|x| x.as_u64()).unwrap_or(0) as usize;
-                        cm_waiting =
-                            v.get("waiting").and_then(|x| x.as_u64()).unwrap_or(0) as usize;
-                        cm_max = v
-                            .get("max_concurrent")
-                            .and_then(|x| x.as_u64())
-                            .map(|n| n as usize);
-                    }
-                }
+            if let Ok(stats) = code_runner::code_manager_client::stats(&client).await {
+                cm_running = stats.running;
+                cm_waiting = stats.waiting;
+                cm_max = Some(stats.max_concurrent);
             }
 
             // ----- build typed payloads -----
@@ -204,6 +306,29 @@ fn spawn_system_health_broadcaster(app_state: AppState) {
                         mount_point: d.mount_point.clone(),
                     })
                     .collect(),
+                gpus: metrics
+                    .gpus
+                    .iter()
+                    .map(|g| GpuInfo {
+                        index: g.index,
+                        name: g.name.clone(),
+                        utilization: g.utilization,
+                        mem_total: g.mem_total,
+                        mem_used: g.mem_used,
+                    })
+                    .collect(),
+                containers: metrics
+                    .containers
+                    .iter()
+                    .map(|c| ContainerInfo {
+                        id: c.id.clone(),
+                        name: c.name.clone(),
+                        cpu_usage: c.cpu_usage,
+                        mem_used: c.mem_used,
+                        mem_limit: c.mem_limit,
+                        job: c.job.clone(),
+                    })
+                    .collect(),
                 code_manager: CodeManagerAdmin {
                     running: cm_running,
                     waiting: cm_waiting,
@@ -231,6 +356,8 @@ fn spawn_system_health_broadcaster(app_state: AppState) {
                     created_at: Set(chrono::Utc::now()),
                     cpu_avg: Set(metrics.cpu_avg_usage as f32),
                     mem_pct: Set(mem_pct as f32),
+                    cm_running: Set(cm_running as i32),
+                    cm_waiting: Set(cm_waiting as i32),
                 };
                 let _ = rec.insert(&db).await;
                 last_persist = Instant::now();
@@ -97,4 +97,17 @@ where
             message: message.into(),
         }
     }
+
+    /// Constructs an error response that also carries `data`, e.g. a list of per-field problems.
+    ///
+    /// # Arguments
+    /// - `data`: Details of the error.
+    /// - `message`: A description of the error.
+    pub fn error_with_data(data: T, message: impl Into<String>) -> Self {
+        ApiResponse {
+            success: false,
+            data: Some(data),
+            message: message.into(),
+        }
+    }
 }
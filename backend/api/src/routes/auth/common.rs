@@ -0,0 +1,35 @@
+use crate::response::ApiResponse;
+use crate::services::sso::SsoError;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+/// Cookie holding the state of the SSO sign-in this browser started.
+pub const SSO_STATE_COOKIE: &str = "ff_sso_state";
+
+/// Path the SSO state cookie is sent on: the sign-in and its callback.
+pub const SSO_STATE_COOKIE_PATH: &str = "/api/auth/sso";
+
+pub fn sso_error(e: SsoError) -> Response {
+    let status = match &e {
+        SsoError::Disabled => StatusCode::NOT_FOUND,
+        SsoError::Invalid(_) => StatusCode::UNAUTHORIZED,
+        SsoError::Forbidden(_) => StatusCode::FORBIDDEN,
+        SsoError::Conflict(_) => StatusCode::CONFLICT,
+        SsoError::Provider(_) => StatusCode::BAD_GATEWAY,
+        SsoError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let message = match e {
+        SsoError::Database(_) => "Failed to process the sign-in".to_string(),
+        other => other.to_string(),
+    };
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+/// `redirect` if it is a path on the frontend; anything else (absolute or protocol-relative
+/// URLs) is dropped so the sign-in can't be used to bounce users to another site.
+pub fn frontend_path(redirect: Option<&str>) -> Option<&str> {
+    redirect.filter(|r| r.starts_with('/') && !r.starts_with("//") && !r.contains('\\'))
+}
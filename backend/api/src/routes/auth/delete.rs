@@ -0,0 +1,40 @@
+use super::common::sso_error;
+use crate::services::sso::SsoError;
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::sso_identity::Model as IdentityModel;
+use util::{config, state::AppState};
+
+/// DELETE /api/auth/sso/link
+///
+/// Unlinks the authenticated user's OIDC provider account; they sign in with their password
+/// from then on.
+///
+/// ### Responses
+/// - `200 OK` — `"SSO account unlinked"`
+/// - `401 Unauthorized` — Not authenticated
+/// - `404 Not Found` — SSO is not enabled, or no provider account is linked
+/// - `500 Internal Server Error` — Database error
+pub async fn unlink_sso(State(app_state): State<AppState>, AuthUser(claims): AuthUser) -> Response {
+    let Some(oidc) = config::oidc() else {
+        return sso_error(SsoError::Disabled);
+    };
+    match IdentityModel::unlink(app_state.db(), &oidc.issuer, claims.sub).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success((), "SSO account unlinked")),
+        )
+            .into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("No SSO account is linked")),
+        )
+            .into_response(),
+        Err(e) => sso_error(e.into()),
+    }
+}
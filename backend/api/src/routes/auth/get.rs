@@ -1,17 +1,21 @@
+use super::common::{SSO_STATE_COOKIE, SSO_STATE_COOKIE_PATH, frontend_path, sso_error};
+use crate::auth::state_cookie::{self, SameSite};
 use crate::routes::common::UserModule;
+use crate::services::sso::{self, SsoError};
+use crate::services::two_factor;
 use crate::{auth::claims::AuthUser, response::ApiResponse};
 use axum::{
     Json,
     extract::{Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
-    response::IntoResponse,
+    response::{IntoResponse, Redirect, Response},
 };
-use db::models::{module, user, user_module_role, user_module_role::Role};
+use db::models::{module, sso_login_state, user, user_module_role, user_module_role::Role};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use tokio::fs::File as FsFile;
 use tokio::io::AsyncReadExt;
-use util::{paths::user_profile_path, state::AppState};
+use util::{config, paths::user_profile_path, state::AppState};
 
 #[derive(Debug, Serialize)]
 pub struct MeResponse {
@@ -403,3 +407,215 @@ pub async fn get_module_role(
         )),
     )
 }
+
+#[derive(Debug, Serialize)]
+pub struct SsoConfigResponse {
+    pub enabled: bool,
+    /// Provider name for the login button; `None` when SSO is off.
+    pub name: Option<String>,
+}
+
+/// GET /api/auth/sso
+///
+/// Whether single sign-on is available, for showing the login button.
+///
+/// ### Response
+/// ```json
+/// {
+///   "success": true,
+///   "message": "SSO configuration retrieved",
+///   "data": { "enabled": true, "name": "University SSO" }
+/// }
+/// ```
+pub async fn get_sso_config() -> impl IntoResponse {
+    let oidc = config::oidc();
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            SsoConfigResponse {
+                enabled: oidc.is_some(),
+                name: oidc.map(|o| o.display_name),
+            },
+            "SSO configuration retrieved",
+        )),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SsoLoginQuery {
+    /// Frontend path to land on after signing in.
+    pub redirect: Option<String>,
+}
+
+/// GET /api/auth/sso/login
+///
+/// Starts a single sign-on: redirects the browser to the OIDC provider, which sends it back to
+/// `/api/auth/sso/callback`. The sign-in's state is also set in an HttpOnly cookie, which the
+/// callback requires, so only this browser can finish the sign-in.
+///
+/// ### Query Parameters
+/// - `redirect` (optional): Frontend path to land on afterwards, e.g. `/modules/1`
+///
+/// ### Responses
+/// - `303 See Other` — To the provider's authorization endpoint, setting the state cookie
+/// - `404 Not Found` — SSO is not enabled
+/// - `502 Bad Gateway` — The provider's discovery document could not be fetched
+pub async fn sso_login(
+    State(app_state): State<AppState>,
+    Query(query): Query<SsoLoginQuery>,
+) -> Response {
+    let Some(oidc) = config::oidc() else {
+        return sso_error(SsoError::Disabled);
+    };
+    let redirect = frontend_path(query.redirect.as_deref());
+    match sso::authorization_url(app_state.db(), &oidc, None, redirect).await {
+        Ok((url, state)) => (
+            [(
+                header::SET_COOKIE,
+                state_cookie::set(
+                    SSO_STATE_COOKIE,
+                    &state,
+                    SSO_STATE_COOKIE_PATH,
+                    SameSite::Lax,
+                    sso_login_state::STATE_TTL_MINUTES * 60,
+                ),
+            )],
+            Redirect::to(&url),
+        )
+            .into_response(),
+        Err(e) => sso_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SsoCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// GET /api/auth/sso/callback
+///
+/// Where the OIDC provider sends the browser back to. The authorization code is redeemed with
+/// the sign-in's PKCE verifier and the id token verified against the provider's key set, issuer,
+/// our client id and the sign-in's nonce.
+///
+/// The provider's user is mapped onto a FitchFork account: the one already linked to them, else
+/// a new account (username from `OIDC_USERNAME_CLAIM`) when `OIDC_AUTO_PROVISION` is on. An
+/// existing account with their email or username is never taken over; its owner links it with
+/// `POST /api/auth/sso/link`. The `OIDC_ROLE_RULES` matching the user's roles claim are then
+/// applied. The session never carries admin rights, even for an admin's account.
+///
+/// A sign-in is only finished for the browser that started it, i.e. the one holding the state
+/// cookie set by `/api/auth/sso/login`; the cookie is cleared either way. An account link started
+/// with `POST /api/auth/sso/link` is not finished here, since any browser could be the one
+/// arriving: the code and state are handed to the frontend, which finishes the link with
+/// `POST /api/auth/sso/link/complete` under the linking user's session.
+///
+/// Two-factor authentication applies as it does to a password login: a user who has it on, or
+/// is staff while it's required of staff, gets a challenge instead of a session.
+///
+/// ### Query Parameters
+/// - `code`: The authorization code
+/// - `state`: The state issued when the sign-in was started
+///
+/// ### Responses
+/// - `303 See Other` — To `{FRONTEND_URL}/sso/callback#token=...&expires_at=...&redirect=...`,
+///   or with `two_factor_required=true&setup_required=...&challenge_token=...` in place of the
+///   token; for an account link, to `{FRONTEND_URL}/sso/callback#link_state=...&code=...`
+/// - `400 Bad Request` — The provider reported an error, or `code`/`state` is missing
+/// - `401 Unauthorized` — Unknown or expired state, state not matching this browser's cookie,
+///   bad signature, wrong audience or nonce
+/// - `403 Forbidden` — No matching account and auto-provisioning is off
+/// - `404 Not Found` — SSO is not enabled
+/// - `409 Conflict` — The accounts are already linked elsewhere, or an unlinked account has the
+///   user's email or username; the user should sign in with their password and link instead
+/// - `502 Bad Gateway` — The provider could not be reached or refused the code
+pub async fn sso_callback(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SsoCallbackQuery>,
+) -> Response {
+    let Some(oidc) = config::oidc() else {
+        return sso_error(SsoError::Disabled);
+    };
+    if let Some(err) = query.error {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(format!(
+                "The SSO provider refused the sign-in: {}",
+                query.error_description.unwrap_or(err)
+            ))),
+        )
+            .into_response();
+    }
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("code and state are required")),
+        )
+            .into_response();
+    };
+
+    match sso::is_link(app_state.db(), &state).await {
+        Ok(true) => {
+            let fragment = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("link_state", &state)
+                .append_pair("code", &code)
+                .finish();
+            return Redirect::to(&format!(
+                "{}/sso/callback#{}",
+                config::frontend_url().trim_end_matches('/'),
+                fragment
+            ))
+            .into_response();
+        }
+        Ok(false) => {}
+        Err(e) => return sso_error(e),
+    }
+
+    let clear_cookie = [(
+        header::SET_COOKIE,
+        state_cookie::clear(SSO_STATE_COOKIE, SSO_STATE_COOKIE_PATH),
+    )];
+    if !state_cookie::matches(&headers, SSO_STATE_COOKIE, &state) {
+        return (
+            clear_cookie,
+            sso_error(SsoError::Invalid(
+                "This sign-in was started in another browser".into(),
+            )),
+        )
+            .into_response();
+    }
+
+    let done = match sso::complete(app_state.db(), &oidc, &state, &code, None).await {
+        Ok(done) => done,
+        Err(e) => return (clear_cookie, sso_error(e)).into_response(),
+    };
+
+    // Never an admin session: admins sign in with their password for their admin rights
+    let login = match two_factor::redirect_login(app_state.db(), &done.user, false).await {
+        Ok(login) => login,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Database error: {e}"))),
+            )
+                .into_response();
+        }
+    };
+    let mut fragment = url::form_urlencoded::Serializer::new(String::new());
+    fragment
+        .extend_pairs(login)
+        .append_pair("redirect", done.redirect.as_deref().unwrap_or("/"));
+    (
+        clear_cookie,
+        Redirect::to(&format!(
+            "{}/sso/callback#{}",
+            config::frontend_url().trim_end_matches('/'),
+            fragment.finish()
+        )),
+    )
+        .into_response()
+}
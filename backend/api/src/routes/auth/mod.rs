@@ -5,25 +5,39 @@
 //! ## Structure
 //! - `post.rs` — POST handlers (e.g., register)
 //! - `get.rs` — GET handlers (e.g., current user info)
+//! - `delete.rs` — DELETE handlers (unlinking an SSO account)
+//! - `common.rs` — SSO error responses and redirect checks shared by the handlers
+//! - `two_factor.rs` — Two-factor login challenges, setup and backup codes
 //!
 //! ## Usage
 //! The `auth_routes()` function returns a `Router` which is nested under `/auth` in the main application.
 
-use crate::auth::guards::allow_authenticated;
+use crate::auth::guards::{allow_authenticated, deny_api_tokens};
 use axum::{
     Router,
     middleware::from_fn,
     routing::{get, post},
 };
-use get::{get_avatar, get_me, get_module_role, has_role_in_module};
+use delete::unlink_sso;
+use get::{
+    get_avatar, get_me, get_module_role, get_sso_config, has_role_in_module, sso_callback,
+    sso_login,
+};
 use post::{
-    change_password, login, register, request_password_reset, reset_password,
-    upload_profile_picture, verify_reset_token,
+    change_password, complete_sso_link, link_lti, login, register, request_password_reset,
+    reset_password, start_sso_link, upload_profile_picture, verify_reset_token,
+};
+use two_factor::{
+    disable_two_factor, enable_two_factor, get_two_factor, login_two_factor,
+    login_two_factor_setup, regenerate_backup_codes, setup_two_factor,
 };
 use util::state::AppState;
 
+mod common;
+pub mod delete;
 pub mod get;
 pub mod post;
+pub mod two_factor;
 
 // # Auth Routes Module
 //
@@ -43,7 +57,23 @@ pub mod post;
 // - `GET /auth/me` — Retrieve info about the currently authenticated user.
 // - `GET /auth/avatar/{user_id}` — Retrieve a user's profile picture.
 // - `GET /auth/has-role` — Check if the current user has a role in a module.
-// -
+// - `GET /auth/sso` — Whether single sign-on is enabled.
+// - `GET /auth/sso/login` — Start an OIDC sign-in; redirects to the provider.
+// - `GET /auth/sso/callback` — The provider's redirect back; signs the user in.
+// - `POST /auth/sso/link` — Start linking the current user to their provider account.
+// - `POST /auth/sso/link/complete` — Finish that link with the code from the callback.
+// - `DELETE /auth/sso/link` — Unlink the current user's provider account.
+// - `POST /auth/lti/link` — Link the LMS user of an LTI launch to the current user.
+// - `POST /auth/login/2fa` — Finish a login with a two-factor code.
+// - `POST /auth/login/2fa/setup` — Start a two-factor setup required at login.
+// - `GET /auth/2fa` — The current user's two-factor status.
+// - `POST /auth/2fa/setup` — Start setting up two-factor authentication.
+// - `POST /auth/2fa/enable` — Confirm the setup with a code; returns backup codes.
+// - `POST /auth/2fa/disable` — Turn two-factor authentication off.
+// - `POST /auth/2fa/backup-codes` — Replace the backup codes.
+//
+// Profile pictures, passwords, SSO links and two-factor settings can't be changed with an API
+// token; see `account_routes()`.
 //
 // ## Usage
 // Use the `auth_routes()` function to mount all `/auth` endpoints under the main application router.
@@ -52,16 +82,39 @@ pub fn auth_routes() -> Router<AppState> {
     Router::new()
         .route("/register", post(register))
         .route("/login", post(login))
+        .route("/login/2fa", post(login_two_factor))
+        .route("/login/2fa/setup", post(login_two_factor_setup))
         .route("/request-password-reset", post(request_password_reset))
         .route("/verify-reset-token", post(verify_reset_token))
         .route("/reset-password", post(reset_password))
         .route("/me", get(get_me))
-        .route("/upload-profile-picture", post(upload_profile_picture))
+        .route(
+            "/upload-profile-picture",
+            post(upload_profile_picture).route_layer(from_fn(deny_api_tokens)),
+        )
         .route("/avatar/{user_id}", get(get_avatar))
         .route("/has-role", get(has_role_in_module))
         .route("/module-role", get(get_module_role))
-        .route(
-            "/change-password",
-            post(change_password).route_layer(from_fn(allow_authenticated)),
-        )
+        .route("/sso", get(get_sso_config))
+        .route("/sso/login", get(sso_login))
+        .route("/sso/callback", get(sso_callback))
+        .merge(account_routes())
+}
+
+/// Routes that change how the user signs in. They need a login session: an API token holder
+/// could otherwise link their own SSO identity, enroll two-factor or set a new password, and
+/// take the account over.
+fn account_routes() -> Router<AppState> {
+    Router::new()
+        .route("/change-password", post(change_password))
+        .route("/sso/link", post(start_sso_link).delete(unlink_sso))
+        .route("/sso/link/complete", post(complete_sso_link))
+        .route("/lti/link", post(link_lti))
+        .route("/2fa", get(get_two_factor))
+        .route("/2fa/setup", post(setup_two_factor))
+        .route("/2fa/enable", post(enable_two_factor))
+        .route("/2fa/disable", post(disable_two_factor))
+        .route("/2fa/backup-codes", post(regenerate_backup_codes))
+        .route_layer(from_fn(allow_authenticated))
+        .route_layer(from_fn(deny_api_tokens))
 }
@@ -1,10 +1,16 @@
+use super::common::{frontend_path, sso_error};
+use super::two_factor::TwoFactorChallengeResponse;
 use crate::auth::AuthUser;
+use crate::auth::generate_two_factor_challenge;
+use crate::services::lti::{self, LtiError};
+use crate::services::sso::{self, SsoError};
+use crate::services::two_factor::{self, LoginStep};
 use crate::{auth::generate_jwt, response::ApiResponse, services::email::EmailService};
 use axum::{
     Json,
     extract::{Multipart, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use chrono::{Duration, Utc};
 use db::models::{
@@ -239,6 +245,21 @@ pub struct LoginRequest {
 /// }
 /// ```
 ///
+/// - `200 OK` (two-factor authentication enabled, or required of this user but not yet set up)  
+///   No session yet: finish with `POST /api/auth/login/2fa` (after
+///   `POST /api/auth/login/2fa/setup` when `setup_required`) within 5 minutes.
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "two_factor_required": true,
+///     "setup_required": false,
+///     "challenge_token": "jwt_challenge_here"
+///   },
+///   "message": "Two-factor authentication required"
+/// }
+/// ```
+///
 /// - `401 Unauthorized` (invalid credentials)  
 /// ```json
 /// {
@@ -254,10 +275,7 @@ pub struct LoginRequest {
 ///   "message": "Database error: detailed error here"
 /// }
 /// ```
-pub async fn login(
-    State(app_state): State<AppState>,
-    Json(req): Json<LoginRequest>,
-) -> impl IntoResponse {
+pub async fn login(State(app_state): State<AppState>, Json(req): Json<LoginRequest>) -> Response {
     let db = app_state.db();
 
     if let Err(validation_errors) = req.validate() {
@@ -265,7 +283,8 @@ pub async fn login(
         return (
             StatusCode::BAD_REQUEST,
             Json(ApiResponse::<UserResponse>::error(error_message)),
-        );
+        )
+            .into_response();
     }
 
     let user = match UserModel::verify_credentials(db, &req.username, &req.password).await {
@@ -276,7 +295,8 @@ pub async fn login(
                 Json(ApiResponse::<UserResponse>::error(
                     "Invalid student number or password",
                 )),
-            );
+            )
+                .into_response();
         }
         Err(e) => {
             return (
@@ -285,24 +305,62 @@ pub async fn login(
                     "Database error: {}",
                     e
                 ))),
-            );
+            )
+                .into_response();
         }
     };
 
-    let (token, expiry) = generate_jwt(user.id, user.admin);
-    let user_response = UserResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        admin: user.admin,
-        token,
-        expires_at: expiry,
+    let setup_required = match two_factor::login_step(db, &user).await {
+        Ok(LoginStep::Done) => {
+            return (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    session_response(user.admin, user),
+                    "Login successful",
+                )),
+            )
+                .into_response();
+        }
+        Ok(LoginStep::Code) => false,
+        Ok(LoginStep::Setup) => true,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<UserResponse>::error(format!(
+                    "Database error: {}",
+                    e
+                ))),
+            )
+                .into_response();
+        }
     };
 
+    let challenge = TwoFactorChallengeResponse {
+        two_factor_required: true,
+        setup_required,
+        challenge_token: generate_two_factor_challenge(user.id, user.admin),
+    };
     (
         StatusCode::OK,
-        Json(ApiResponse::success(user_response, "Login successful")),
+        Json(ApiResponse::success(
+            challenge,
+            "Two-factor authentication required",
+        )),
     )
+        .into_response()
+}
+
+/// A session for `user`, with `admin` rights or not, as returned by a successful login.
+pub(super) fn session_response(admin: bool, user: UserModel) -> UserResponse {
+    let (token, expiry) = generate_jwt(user.id, admin);
+    UserResponse {
+        id: user.id,
+        username: user.username,
+        email: user.email,
+        admin,
+        token,
+        expires_at: expiry,
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -920,3 +978,216 @@ pub async fn change_password(
         ),
     }
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SsoLinkRequest {
+    /// Frontend path to land on once linked, e.g. `/settings`.
+    pub redirect: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SsoLinkResponse {
+    /// The provider's sign-in page; navigate the browser here.
+    pub url: String,
+}
+
+/// POST /api/auth/sso/link
+///
+/// Starts linking the authenticated user's account to their account at the OIDC provider, so
+/// they can sign in either way. The provider sends the browser back to
+/// `/api/auth/sso/callback`, which hands it to the frontend to finish with
+/// `POST /api/auth/sso/link/complete`.
+///
+/// ### Request Body (optional)
+/// ```json
+/// { "redirect": "/settings" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Continue at the SSO provider",
+///   "data": { "url": "https://idp.example/authorize?..." }
+/// }
+/// ```
+/// - `401 Unauthorized` — Not authenticated
+/// - `404 Not Found` — SSO is not enabled
+/// - `502 Bad Gateway` — The provider's discovery document could not be fetched
+pub async fn start_sso_link(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    body: Option<Json<SsoLinkRequest>>,
+) -> Response {
+    let Some(oidc) = config::oidc() else {
+        return sso_error(SsoError::Disabled);
+    };
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    let redirect = frontend_path(req.redirect.as_deref());
+    match sso::authorization_url(app_state.db(), &oidc, Some(claims.sub), redirect).await {
+        Ok((url, _)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                SsoLinkResponse { url },
+                "Continue at the SSO provider",
+            )),
+        )
+            .into_response(),
+        Err(e) => sso_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SsoLinkCompleteRequest {
+    /// `link_state` from the callback's redirect.
+    pub state: String,
+    /// `code` from the callback's redirect.
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SsoLinkCompleteResponse {
+    /// Frontend path the link was started from.
+    pub redirect: Option<String>,
+}
+
+/// POST /api/auth/sso/link/complete
+///
+/// Finishes an account link started with `POST /api/auth/sso/link`, linking the provider
+/// account to the authenticated user. Only the user who started the link can finish it, so a
+/// link URL passed to someone else can't attach their provider account to the sender's.
+///
+/// ### Request Body
+/// ```json
+/// { "state": "...", "code": "..." }
+/// ```
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "SSO account linked",
+///   "data": { "redirect": "/settings" }
+/// }
+/// ```
+/// - `401 Unauthorized` — Not authenticated, unknown or expired state, the link was started by
+///   another user, or the id token failed verification
+/// - `404 Not Found` — SSO is not enabled
+/// - `409 Conflict` — The provider account is linked to another user, or this user already has
+///   a different one linked
+/// - `502 Bad Gateway` — The provider could not be reached or refused the code
+pub async fn complete_sso_link(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(req): Json<SsoLinkCompleteRequest>,
+) -> Response {
+    let Some(oidc) = config::oidc() else {
+        return sso_error(SsoError::Disabled);
+    };
+    match sso::complete(
+        app_state.db(),
+        &oidc,
+        &req.state,
+        &req.code,
+        Some(claims.sub),
+    )
+    .await
+    {
+        Ok(done) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                SsoLinkCompleteResponse {
+                    redirect: done.redirect,
+                },
+                "SSO account linked",
+            )),
+        )
+            .into_response(),
+        Err(e) => sso_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LtiLinkRequest {
+    /// `link_token` from the LTI launch's redirect.
+    pub link_token: String,
+    /// The user's current password.
+    pub password: String,
+}
+
+/// POST /api/auth/lti/link
+///
+/// Links the LMS user an LTI launch was for to the authenticated user's account. A launch whose
+/// LMS user has the username or email of an existing account doesn't sign in as that account;
+/// it hands out a `link_token` instead, and the account's owner confirms here with their
+/// password. Their next launch from that LMS signs them in.
+///
+/// ### Request Body
+/// ```json
+/// { "link_token": "...", "password": "..." }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — `"LMS account linked"`
+/// - `401 Unauthorized` — Not authenticated, an invalid or expired link token, or the wrong
+///   password
+/// - `409 Conflict` — The LMS user is linked to another account, or this account is already
+///   linked to a different user on that LMS
+/// - `500 Internal Server Error` — Database error
+pub async fn link_lti(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(req): Json<LtiLinkRequest>,
+) -> Response {
+    let db = app_state.db();
+    let Some((platform_id, subject)) = lti::decode_link_token(&req.link_token) else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse::<()>::error("Invalid or expired link token")),
+        )
+            .into_response();
+    };
+
+    let user = match user::Entity::find_by_id(claims.sub).one(db).await {
+        Ok(Some(user)) => user,
+        Ok(None) => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(ApiResponse::<()>::error("Authentication required")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Database error: {}", e))),
+            )
+                .into_response();
+        }
+    };
+    if !user.verify_password(&req.password) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse::<()>::error("Password is incorrect")),
+        )
+            .into_response();
+    }
+
+    match lti::link_user(db, platform_id, &subject, user.id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "LMS account linked")),
+        )
+            .into_response(),
+        Err(LtiError::Conflict(msg)) => {
+            (StatusCode::CONFLICT, Json(ApiResponse::<()>::error(msg))).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response(),
+    }
+}
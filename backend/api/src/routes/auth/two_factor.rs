@@ -0,0 +1,525 @@
+//! # Two-factor Authentication Handlers
+//!
+//! TOTP two-factor authentication (see [`db::models::user_two_factor`]).
+//!
+//! Signed-in users set it up with `POST /auth/2fa/setup` (a secret and `otpauth://` URI to show
+//! as a QR code) and `POST /auth/2fa/enable` (a code from the app), which also hands out backup
+//! codes. From then on `POST /auth/login` answers with a challenge token instead of a session,
+//! and `POST /auth/login/2fa` trades it and a code for the session.
+//!
+//! When admins require two-factor authentication of staff (`/api/system/two-factor`), staff
+//! without it get a challenge with `setup_required`, and set it up through
+//! `POST /auth/login/2fa/setup` before `POST /auth/login/2fa` signs them in.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use db::models::{
+    two_factor_backup_code,
+    user::{self, Model as UserModel},
+    user_two_factor::{self, Verification},
+};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait};
+use serde::{Deserialize, Serialize};
+use util::state::AppState;
+
+use super::post::{UserResponse, session_response};
+use crate::auth::{AuthUser, decode_two_factor_challenge};
+use crate::response::ApiResponse;
+use crate::services::two_factor::{self, ISSUER};
+
+/// `POST /auth/login` when a code is still owed.
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallengeResponse {
+    pub two_factor_required: bool,
+    /// Two-factor authentication is required of the user but not set up yet.
+    pub setup_required: bool,
+    /// Hand back to `POST /auth/login/2fa` (and `/auth/login/2fa/setup`).
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    /// Whether the user is staff and admins require it of staff; it can't be disabled then.
+    pub required: bool,
+    pub backup_codes_remaining: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorSetupResponse {
+    /// Base32 secret, for entering into an authenticator app by hand.
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code.
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackupCodesResponse {
+    /// Single-use codes; shown this once.
+    pub backup_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorLoginResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    /// Present when this sign-in finished an enforced setup.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_codes: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeRequest {
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeCodeRequest {
+    pub challenge_token: String,
+    /// A TOTP code, or a backup code.
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CodeRequest {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DisableTwoFactorRequest {
+    pub password: String,
+    pub code: String,
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+fn db_error(e: DbErr) -> Response {
+    error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("Database error: {}", e),
+    )
+}
+
+fn ok<T: Serialize>(data: T, message: &str) -> Response {
+    (StatusCode::OK, Json(ApiResponse::success(data, message))).into_response()
+}
+
+/// Turns a failed [`Verification`] into its response.
+fn rejected(outcome: Verification) -> Response {
+    match outcome {
+        Verification::Locked => error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "Too many incorrect codes; try again in a few minutes",
+        ),
+        _ => error(StatusCode::UNAUTHORIZED, "Invalid two-factor code"),
+    }
+}
+
+async fn find_user(db: &DatabaseConnection, user_id: i64) -> Result<UserModel, Response> {
+    match user::Entity::find_by_id(user_id).one(db).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(error(StatusCode::UNAUTHORIZED, "Authentication required")),
+        Err(e) => Err(db_error(e)),
+    }
+}
+
+/// The user behind a challenge token, and whether their session gets admin rights.
+async fn challenged_user(
+    db: &DatabaseConnection,
+    token: &str,
+) -> Result<(UserModel, bool), Response> {
+    let Some((user_id, admin)) = decode_two_factor_challenge(token) else {
+        return Err(error(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or expired challenge; log in again",
+        ));
+    };
+    // Rights revoked since the challenge was issued aren't handed back
+    let user = find_user(db, user_id).await?;
+    let admin = admin && user.admin;
+    Ok((user, admin))
+}
+
+fn setup_response(setup: &user_two_factor::Model, user: &UserModel) -> TwoFactorSetupResponse {
+    TwoFactorSetupResponse {
+        secret: setup.secret.clone(),
+        otpauth_uri: setup.otpauth_uri(ISSUER, &user.username),
+    }
+}
+
+/// Confirms a pending setup with `code`, enabling it and issuing backup codes.
+async fn confirm_setup(
+    db: &DatabaseConnection,
+    setup: user_two_factor::Model,
+    code: &str,
+) -> Result<Vec<String>, Response> {
+    let outcome = setup.verify(db, code, Utc::now()).await.map_err(db_error)?;
+    if !outcome.is_valid() {
+        return Err(rejected(outcome));
+    }
+    let user_id = setup.user_id;
+    // Re-read to keep the step just used
+    let setup = user_two_factor::Model::find(db, user_id)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| {
+            error(
+                StatusCode::BAD_REQUEST,
+                "Set up two-factor authentication first",
+            )
+        })?;
+    setup.enable(db).await.map_err(db_error)?;
+    two_factor_backup_code::Model::regenerate(db, user_id)
+        .await
+        .map_err(db_error)
+}
+
+/// POST /api/auth/login/2fa
+///
+/// Finishes a login that needs a two-factor code. Takes a TOTP code or a backup code; for a user
+/// finishing an enforced setup, the TOTP code confirms the new secret and backup codes are
+/// returned alongside the session.
+///
+/// ### Request Body
+/// ```json
+/// { "challenge_token": "jwt_challenge_here", "code": "123456" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The session, as for `POST /api/auth/login`, plus `backup_codes` after a setup
+/// - `400 Bad Request` — Two-factor authentication isn't enabled or being set up
+/// - `401 Unauthorized` — Invalid or expired challenge, or wrong code
+/// - `429 Too Many Requests` — Too many wrong codes; locked for a few minutes
+pub async fn login_two_factor(
+    State(app_state): State<AppState>,
+    Json(req): Json<ChallengeCodeRequest>,
+) -> Response {
+    let db = app_state.db();
+    let (user, admin) = match challenged_user(db, &req.challenge_token).await {
+        Ok(challenged) => challenged,
+        Err(resp) => return resp,
+    };
+    let setup = match user_two_factor::Model::find(db, user.id).await {
+        Ok(Some(setup)) => setup,
+        Ok(None) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "Set up two-factor authentication first",
+            );
+        }
+        Err(e) => return db_error(e),
+    };
+
+    if setup.enabled {
+        return match setup.verify(db, &req.code, Utc::now()).await {
+            Ok(outcome) if outcome.is_valid() => ok(
+                TwoFactorLoginResponse {
+                    user: session_response(admin, user),
+                    backup_codes: None,
+                },
+                "Login successful",
+            ),
+            Ok(outcome) => rejected(outcome),
+            Err(e) => db_error(e),
+        };
+    }
+
+    // Only an enforced setup may be finished at login; anyone else enables it once signed in
+    match two_factor::is_required(db, &user).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "Two-factor authentication is not enabled",
+            );
+        }
+        Err(e) => return db_error(e),
+    }
+    match confirm_setup(db, setup, &req.code).await {
+        Ok(codes) => ok(
+            TwoFactorLoginResponse {
+                user: session_response(admin, user),
+                backup_codes: Some(codes),
+            },
+            "Two-factor authentication enabled",
+        ),
+        Err(resp) => resp,
+    }
+}
+
+/// POST /api/auth/login/2fa/setup
+///
+/// Starts the enforced setup of a staff member whose login answered `setup_required`.
+///
+/// ### Request Body
+/// ```json
+/// { "challenge_token": "jwt_challenge_here" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Scan the code with an authenticator app",
+///   "data": { "secret": "JBSWY3DP...", "otpauth_uri": "otpauth://totp/FitchFork:u12345678?..." }
+/// }
+/// ```
+/// - `400 Bad Request` — Setup isn't required of this user
+/// - `401 Unauthorized` — Invalid or expired challenge
+/// - `409 Conflict` — Already enabled
+pub async fn login_two_factor_setup(
+    State(app_state): State<AppState>,
+    Json(req): Json<ChallengeRequest>,
+) -> Response {
+    let db = app_state.db();
+    let user = match challenged_user(db, &req.challenge_token).await {
+        Ok((user, _)) => user,
+        Err(resp) => return resp,
+    };
+    match two_factor::is_required(db, &user).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "Two-factor authentication is not required; set it up once logged in",
+            );
+        }
+        Err(e) => return db_error(e),
+    }
+    begin_setup(db, &user).await
+}
+
+async fn begin_setup(db: &DatabaseConnection, user: &UserModel) -> Response {
+    match user_two_factor::Model::begin_setup(db, user.id).await {
+        Ok(Some(setup)) => ok(
+            setup_response(&setup, user),
+            "Scan the code with an authenticator app",
+        ),
+        Ok(None) => error(
+            StatusCode::CONFLICT,
+            "Two-factor authentication is already enabled",
+        ),
+        Err(e) => db_error(e),
+    }
+}
+
+/// GET /api/auth/2fa
+///
+/// The authenticated user's two-factor status.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Two-factor status retrieved",
+///   "data": { "enabled": true, "required": false, "backup_codes_remaining": 9 }
+/// }
+/// ```
+pub async fn get_two_factor(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> Response {
+    let db = app_state.db();
+    let user = match find_user(db, claims.sub).await {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    let status = async {
+        Ok::<_, DbErr>(TwoFactorStatusResponse {
+            enabled: user_two_factor::Model::is_enabled(db, user.id).await?,
+            required: two_factor::is_required(db, &user).await?,
+            backup_codes_remaining: two_factor_backup_code::Model::remaining(db, user.id).await?,
+        })
+    };
+    match status.await {
+        Ok(status) => ok(status, "Two-factor status retrieved"),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /api/auth/2fa/setup
+///
+/// Starts setting up two-factor authentication with a fresh secret; nothing changes at login
+/// until `POST /api/auth/2fa/enable` confirms it. Starting again replaces the secret.
+///
+/// ### Responses
+/// - `200 OK` — As for `POST /api/auth/login/2fa/setup`
+/// - `409 Conflict` — Already enabled
+pub async fn setup_two_factor(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+) -> Response {
+    let db = app_state.db();
+    match find_user(db, claims.sub).await {
+        Ok(user) => begin_setup(db, &user).await,
+        Err(resp) => resp,
+    }
+}
+
+/// POST /api/auth/2fa/enable
+///
+/// Confirms the secret from `POST /api/auth/2fa/setup` with a code from the app, enabling
+/// two-factor authentication.
+///
+/// ### Request Body
+/// ```json
+/// { "code": "123456" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Two-factor authentication enabled",
+///   "data": { "backup_codes": ["k7m2q-x9p4r", "..."] }
+/// }
+/// ```
+/// - `400 Bad Request` — Setup hasn't been started
+/// - `401 Unauthorized` — Wrong code
+/// - `409 Conflict` — Already enabled
+/// - `429 Too Many Requests` — Too many wrong codes
+pub async fn enable_two_factor(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(req): Json<CodeRequest>,
+) -> Response {
+    let db = app_state.db();
+    let setup = match user_two_factor::Model::find(db, claims.sub).await {
+        Ok(Some(setup)) if setup.enabled => {
+            return error(
+                StatusCode::CONFLICT,
+                "Two-factor authentication is already enabled",
+            );
+        }
+        Ok(Some(setup)) => setup,
+        Ok(None) => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "Set up two-factor authentication first",
+            );
+        }
+        Err(e) => return db_error(e),
+    };
+    match confirm_setup(db, setup, &req.code).await {
+        Ok(backup_codes) => ok(
+            BackupCodesResponse { backup_codes },
+            "Two-factor authentication enabled",
+        ),
+        Err(resp) => resp,
+    }
+}
+
+/// POST /api/auth/2fa/disable
+///
+/// Turns two-factor authentication off, given the password and a current (or backup) code.
+///
+/// ### Request Body
+/// ```json
+/// { "password": "strongpassword", "code": "123456" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — Disabled
+/// - `400 Bad Request` — Not enabled
+/// - `401 Unauthorized` — Wrong password or code
+/// - `403 Forbidden` — Required of this user
+/// - `429 Too Many Requests` — Too many wrong codes
+pub async fn disable_two_factor(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(req): Json<DisableTwoFactorRequest>,
+) -> Response {
+    let db = app_state.db();
+    let user = match find_user(db, claims.sub).await {
+        Ok(user) => user,
+        Err(resp) => return resp,
+    };
+    match two_factor::is_required(db, &user).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return error(
+                StatusCode::FORBIDDEN,
+                "Two-factor authentication is required for staff",
+            );
+        }
+        Err(e) => return db_error(e),
+    }
+    if !user.verify_password(&req.password) {
+        return error(StatusCode::UNAUTHORIZED, "Password is incorrect");
+    }
+    let setup = match enabled_setup(db, user.id).await {
+        Ok(setup) => setup,
+        Err(resp) => return resp,
+    };
+    match setup.verify(db, &req.code, Utc::now()).await {
+        Ok(outcome) if outcome.is_valid() => {}
+        Ok(outcome) => return rejected(outcome),
+        Err(e) => return db_error(e),
+    }
+    match user_two_factor::Model::disable(db, user.id).await {
+        Ok(_) => ok((), "Two-factor authentication disabled"),
+        Err(e) => db_error(e),
+    }
+}
+
+/// POST /api/auth/2fa/backup-codes
+///
+/// Replaces the user's backup codes, given a current (or backup) code. Old codes stop working.
+///
+/// ### Request Body
+/// ```json
+/// { "code": "123456" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The new codes, as for `POST /api/auth/2fa/enable`
+/// - `400 Bad Request` — Not enabled
+/// - `401 Unauthorized` — Wrong code
+/// - `429 Too Many Requests` — Too many wrong codes
+pub async fn regenerate_backup_codes(
+    State(app_state): State<AppState>,
+    AuthUser(claims): AuthUser,
+    Json(req): Json<CodeRequest>,
+) -> Response {
+    let db = app_state.db();
+    let setup = match enabled_setup(db, claims.sub).await {
+        Ok(setup) => setup,
+        Err(resp) => return resp,
+    };
+    match setup.verify(db, &req.code, Utc::now()).await {
+        Ok(outcome) if outcome.is_valid() => {}
+        Ok(outcome) => return rejected(outcome),
+        Err(e) => return db_error(e),
+    }
+    match two_factor_backup_code::Model::regenerate(db, claims.sub).await {
+        Ok(backup_codes) => ok(
+            BackupCodesResponse { backup_codes },
+            "Backup codes replaced",
+        ),
+        Err(e) => db_error(e),
+    }
+}
+
+async fn enabled_setup(
+    db: &DatabaseConnection,
+    user_id: i64,
+) -> Result<user_two_factor::Model, Response> {
+    match user_two_factor::Model::find(db, user_id).await {
+        Ok(Some(setup)) if setup.enabled => Ok(setup),
+        Ok(_) => Err(error(
+            StatusCode::BAD_REQUEST,
+            "Two-factor authentication is not enabled",
+        )),
+        Err(e) => Err(db_error(e)),
+    }
+}
@@ -0,0 +1,114 @@
+use crate::auth::state_cookie::{self, SameSite};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+};
+use db::models::{
+    lti_launch_state::{self, Model as LaunchStateModel},
+    lti_platform::Model as PlatformModel,
+};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use util::config;
+
+/// Cookie holding the state of the LTI login this browser started.
+pub const LTI_STATE_COOKIE: &str = "ff_lti_state";
+
+/// Path the LTI state cookie is sent on: the login and the launch.
+pub const LTI_STATE_COOKIE_PATH: &str = "/api/lti";
+
+/// OIDC third-party login initiation parameters.
+#[derive(Debug, Deserialize)]
+pub struct LoginParams {
+    pub iss: String,
+    pub login_hint: String,
+    pub target_link_uri: Option<String>,
+    pub lti_message_hint: Option<String>,
+    /// Sent by platforms that register one tool several times
+    pub client_id: Option<String>,
+}
+
+pub fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ApiResponse::<()>::error(message.into()))).into_response()
+}
+
+pub fn launch_url() -> String {
+    format!(
+        "{}/api/lti/launch",
+        config::lti_tool_url().trim_end_matches('/')
+    )
+}
+
+/// Stores a login state and sends the browser to the platform's authorization endpoint, which
+/// posts the id token back to the launch URL. The state is also set in a cookie, which the launch
+/// requires; it is `SameSite=None` since the launch is the platform's cross-site form post.
+pub async fn start_login(db: &DatabaseConnection, params: LoginParams) -> Response {
+    let platforms =
+        match PlatformModel::find_by_issuer(db, &params.iss, params.client_id.as_deref()).await {
+            Ok(p) => p,
+            Err(_) => {
+                return error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to look up the platform",
+                );
+            }
+        };
+    let platform = match platforms.as_slice() {
+        [platform] => platform,
+        [] => return error(StatusCode::BAD_REQUEST, "Unknown LTI platform"),
+        _ => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "Several registrations match this issuer; client_id is required",
+            );
+        }
+    };
+
+    let login = match LaunchStateModel::create(db, platform.id).await {
+        Ok(l) => l,
+        Err(_) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to start the login",
+            );
+        }
+    };
+
+    let redirect_uri = launch_url();
+    let mut query = vec![
+        ("scope", "openid"),
+        ("response_type", "id_token"),
+        ("response_mode", "form_post"),
+        ("prompt", "none"),
+        ("client_id", platform.client_id.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("login_hint", params.login_hint.as_str()),
+        ("state", login.state.as_str()),
+        ("nonce", login.nonce.as_str()),
+    ];
+    if let Some(hint) = params.lti_message_hint.as_deref() {
+        query.push(("lti_message_hint", hint));
+    }
+    match url::Url::parse_with_params(&platform.auth_login_url, &query) {
+        Ok(url) => (
+            [(
+                header::SET_COOKIE,
+                state_cookie::set(
+                    LTI_STATE_COOKIE,
+                    &login.state,
+                    LTI_STATE_COOKIE_PATH,
+                    SameSite::None,
+                    lti_launch_state::STATE_TTL_MINUTES * 60,
+                ),
+            )],
+            Redirect::to(url.as_str()),
+        )
+            .into_response(),
+        Err(_) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "The platform's login URL is invalid",
+        ),
+    }
+}
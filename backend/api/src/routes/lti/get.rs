@@ -0,0 +1,47 @@
+use super::common::{LoginParams, error, start_login};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use util::state::AppState;
+
+/// GET /api/lti/login
+///
+/// OIDC third-party login initiation from the platform. Redirects (`303`) to the platform's
+/// authorization endpoint with a fresh `state` and `nonce`, setting the state in an HttpOnly
+/// cookie that the launch requires.
+///
+/// ### Query Parameters
+/// - `iss` (required): The platform's issuer
+/// - `login_hint` (required): Opaque user hint, passed back to the platform
+/// - `lti_message_hint` (optional): Opaque message hint, passed back to the platform
+/// - `target_link_uri` (optional): Ignored; launches always land on `/api/lti/launch`
+/// - `client_id` (optional): Picks the registration when an issuer has several
+///
+/// ### Responses
+/// - `303 See Other` — To the platform's authorization endpoint, setting the state cookie
+/// - `400 Bad Request` — Unknown issuer, or several registrations and no `client_id`
+pub async fn login(
+    State(app_state): State<AppState>,
+    Query(params): Query<LoginParams>,
+) -> Response {
+    start_login(app_state.db(), params).await
+}
+
+/// GET /api/lti/jwks
+///
+/// The tool's active public keys, which platforms use to verify its client assertions.
+///
+/// ### Responses
+/// - `200 OK` — A bare JWK set (no `ApiResponse` envelope)
+/// ```json
+/// { "keys": [ { "kty": "RSA", "alg": "RS256", "use": "sig", "kid": "1f2e3d4c5b6a7980", "n": "...", "e": "AQAB" } ] }
+/// ```
+pub async fn jwks(State(app_state): State<AppState>) -> Response {
+    match crate::services::lti::jwks(app_state.db()).await {
+        Ok(set) => (StatusCode::OK, Json(set)).into_response(),
+        Err(_) => error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load keys"),
+    }
+}
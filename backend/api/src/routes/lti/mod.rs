@@ -0,0 +1,34 @@
+//! # LTI Routes Module
+//!
+//! Defines the public `/lti` endpoint group through which an LMS launches FitchFork as an
+//! LTI 1.3 tool. Platforms, deployments and the tool's keys are managed under
+//! `/system/lti`.
+//!
+//! ## Structure
+//! - `common.rs` — OIDC login initiation shared by the GET and POST forms
+//! - `get.rs` — GET handlers (login initiation, the tool's JWKS)
+//! - `post.rs` — POST handlers (login initiation, the launch)
+//!
+//! ## Usage
+//! Called via `routes()` as a nested router mounted under `/lti`. These routes are
+//! unauthenticated: the launch is authenticated by the platform's signed id token.
+
+use axum::{Router, routing::get, routing::post};
+use util::state::AppState;
+
+mod common;
+pub mod get;
+pub mod post;
+
+/// Builds and returns the `/lti` route group.
+///
+/// Routes:
+/// - `GET|POST /lti/login` → OIDC third-party login initiation; redirects to the platform
+/// - `POST /lti/launch`    → the platform's id token; signs the user in and redirects to the frontend
+/// - `GET /lti/jwks`       → the tool's public keys
+pub fn lti_routes() -> Router<AppState> {
+    Router::new()
+        .route("/login", get(get::login).post(post::login))
+        .route("/launch", post(post::launch))
+        .route("/jwks", get(get::jwks))
+}
@@ -0,0 +1,273 @@
+use super::common::{LTI_STATE_COOKIE, LTI_STATE_COOKIE_PATH, LoginParams, error, start_login};
+use crate::auth::state_cookie;
+use crate::services::lti::{
+    LTI_VERSION, LaunchUser, LtiError, MESSAGE_TYPE_RESOURCE_LINK, link_token, module_role,
+    resolve_user, verify_id_token,
+};
+use crate::services::two_factor;
+use axum::{
+    Form,
+    extract::State,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Redirect, Response},
+};
+use db::models::{
+    assignment,
+    lti_context::Model as ContextModel,
+    lti_deployment::Model as DeploymentModel,
+    lti_launch_state::Model as LaunchStateModel,
+    lti_platform::Entity as PlatformEntity,
+    lti_resource_link::Model as ResourceLinkModel,
+    user_module_role::{self, Column as RoleColumn, Entity as RoleEntity},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use util::{config, state::AppState};
+
+/// POST /api/lti/login
+///
+/// The form-encoded variant of `GET /api/lti/login`; same parameters and responses.
+pub async fn login(State(app_state): State<AppState>, Form(params): Form<LoginParams>) -> Response {
+    start_login(app_state.db(), params).await
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LaunchForm {
+    pub id_token: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+fn launch_error(e: LtiError) -> Response {
+    match e {
+        LtiError::Invalid(msg) => error(StatusCode::UNAUTHORIZED, msg),
+        LtiError::Platform(msg) => error(StatusCode::BAD_GATEWAY, msg),
+        LtiError::Conflict(msg) => error(StatusCode::CONFLICT, msg),
+        LtiError::Database(_) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to process the launch",
+        ),
+    }
+}
+
+/// POST /api/lti/launch
+///
+/// The platform's form post of the id token after login initiation. The token is verified
+/// against the platform's key set and the login's `state` and `nonce`, and must be an LTI 1.3
+/// resource link launch from a registered deployment. The launch is only accepted from the
+/// browser that started the login, i.e. the one holding the state cookie set by
+/// `/api/lti/login`; the cookie is cleared either way.
+///
+/// The placement is tied to a module on its first launch: the one an admin linked its course
+/// (the launch's context) to, see `POST /api/system/lti/platforms/{platform_id}/contexts`.
+/// The custom parameter `assignment_id` can narrow it to one of the module's assignments. The
+/// platform's AGS line item, when the score scope is granted, is recorded so marks flow back
+/// to the LMS gradebook.
+///
+/// On their first launch the LMS user gets a new FitchFork account (username from the LIS person
+/// sourcedid, else their email), linked to them. When that username or email is already taken,
+/// the launch doesn't sign in: it hands the frontend a `link_token`, and the account's owner
+/// links the LMS user with `POST /api/auth/lti/link` while signed in, then launches again.
+///
+/// The user is given the module role matching their LMS role if they have none in the module
+/// yet: instructors become lecturers, teaching assistants tutors and learners students. The
+/// session never carries admin rights, even for an admin's account.
+///
+/// ### Form Fields
+/// - `id_token`: The platform's signed launch JWT
+/// - `state`: The state issued by `/api/lti/login`
+///
+/// ### Responses
+/// - `303 See Other` — To `{FRONTEND_URL}/lti/launch#token=...&expires_at=...&redirect=...`,
+///   where `redirect` is the module or assignment page; a user owing a two-factor code gets
+///   `two_factor_required=true&setup_required=...&challenge_token=...` in place of the token,
+///   and an LMS user who must link an existing account `link_required=true&link_token=...`
+/// - `400 Bad Request` — The platform reported an error, or not a resource link launch
+/// - `401 Unauthorized` — Unknown or expired state, state not matching this browser's cookie,
+///   bad signature, wrong audience or nonce
+/// - `403 Forbidden` — The deployment is not registered, or a new placement's course is not
+///   linked to a module
+/// - `404 Not Found` — The assignment named by the placement is not in the module
+/// - `502 Bad Gateway` — The platform's key set could not be fetched
+pub async fn launch(
+    State(app_state): State<AppState>,
+    headers: HeaderMap,
+    Form(form): Form<LaunchForm>,
+) -> Response {
+    if let Some(err) = form.error {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "The platform refused the launch: {}",
+                form.error_description.unwrap_or(err)
+            ),
+        );
+    }
+    let (Some(id_token), Some(state)) = (form.id_token, form.state) else {
+        return error(StatusCode::BAD_REQUEST, "id_token and state are required");
+    };
+
+    let clear_cookie = [(
+        header::SET_COOKIE,
+        state_cookie::clear(LTI_STATE_COOKIE, LTI_STATE_COOKIE_PATH),
+    )];
+    if !state_cookie::matches(&headers, LTI_STATE_COOKIE, &state) {
+        return (
+            clear_cookie,
+            error(
+                StatusCode::UNAUTHORIZED,
+                "This launch was started in another browser",
+            ),
+        )
+            .into_response();
+    }
+    (
+        clear_cookie,
+        complete_launch(app_state.db(), &id_token, &state).await,
+    )
+        .into_response()
+}
+
+/// Verifies a launch whose state matched the browser's cookie and signs its user in.
+async fn complete_launch(db: &DatabaseConnection, id_token: &str, state: &str) -> Response {
+    let login = match LaunchStateModel::take(db, state).await {
+        Ok(Some(l)) => l,
+        Ok(None) => return error(StatusCode::UNAUTHORIZED, "Unknown or expired launch state"),
+        Err(e) => return launch_error(e.into()),
+    };
+    let platform = match PlatformEntity::find_by_id(login.platform_id).one(db).await {
+        Ok(Some(p)) => p,
+        Ok(None) => return error(StatusCode::UNAUTHORIZED, "Unknown LTI platform"),
+        Err(e) => return launch_error(e.into()),
+    };
+
+    let claims = match verify_id_token(&platform, id_token).await {
+        Ok(c) => c,
+        Err(e) => return launch_error(e),
+    };
+    if claims.nonce != login.nonce {
+        return error(StatusCode::UNAUTHORIZED, "Nonce mismatch");
+    }
+    if claims.message_type != MESSAGE_TYPE_RESOURCE_LINK || claims.version != LTI_VERSION {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "Only LTI 1.3 resource link launches are supported",
+        );
+    }
+    match DeploymentModel::exists(db, platform.id, &claims.deployment_id).await {
+        Ok(true) => {}
+        Ok(false) => return error(StatusCode::FORBIDDEN, "Deployment not registered"),
+        Err(e) => return launch_error(e.into()),
+    }
+
+    // The placement's target: fixed on its first launch, in the module its course is linked to
+    let link = match ResourceLinkModel::find(db, platform.id, &claims.resource_link.id).await {
+        Ok(l) => l,
+        Err(e) => return launch_error(e.into()),
+    };
+    let (module_id, assignment_id) = match &link {
+        Some(link) => (link.module_id, link.assignment_id),
+        None => {
+            let Some(context) = &claims.context else {
+                return error(
+                    StatusCode::FORBIDDEN,
+                    "This placement is not in a course linked to a module",
+                );
+            };
+            let module_id = match ContextModel::find(db, platform.id, &context.id).await {
+                Ok(Some(linked)) => linked.module_id,
+                Ok(None) => {
+                    return error(
+                        StatusCode::FORBIDDEN,
+                        "This course is not linked to a module; ask an administrator to link it",
+                    );
+                }
+                Err(e) => return launch_error(e.into()),
+            };
+            let assignment_id = claims.custom_id("assignment_id");
+            if let Some(assignment_id) = assignment_id {
+                match assignment::Entity::find_by_id(assignment_id)
+                    .filter(assignment::Column::ModuleId.eq(module_id))
+                    .one(db)
+                    .await
+                {
+                    Ok(Some(_)) => {}
+                    Ok(None) => return error(StatusCode::NOT_FOUND, "Assignment not found"),
+                    Err(e) => return launch_error(e.into()),
+                }
+            }
+            (module_id, assignment_id)
+        }
+    };
+    if let Err(e) = ResourceLinkModel::record_launch(
+        db,
+        platform.id,
+        &claims.resource_link.id,
+        claims.context.as_ref().map(|c| c.id.as_str()),
+        module_id,
+        assignment_id,
+        claims.lineitem(),
+    )
+    .await
+    {
+        return launch_error(e.into());
+    }
+
+    let target = match assignment_id {
+        Some(aid) => format!("/modules/{module_id}/assignments/{aid}"),
+        None => format!("/modules/{module_id}"),
+    };
+    let user = match resolve_user(db, platform.id, &claims).await {
+        Ok(LaunchUser::Linked(u)) => u,
+        Ok(LaunchUser::Unlinked) => {
+            let fragment = url::form_urlencoded::Serializer::new(String::new())
+                .append_pair("link_required", "true")
+                .append_pair("link_token", &link_token(platform.id, &claims.sub))
+                .append_pair("redirect", &target)
+                .finish();
+            return Redirect::to(&format!(
+                "{}/lti/launch#{}",
+                config::frontend_url().trim_end_matches('/'),
+                fragment
+            ))
+            .into_response();
+        }
+        Err(e) => return launch_error(e),
+    };
+    if let Some(role) = module_role(&claims.roles) {
+        let existing = RoleEntity::find()
+            .filter(RoleColumn::UserId.eq(user.id))
+            .filter(RoleColumn::ModuleId.eq(module_id))
+            .one(db)
+            .await;
+        match existing {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                if let Err(e) =
+                    user_module_role::Model::assign_user_to_module(db, user.id, module_id, role)
+                        .await
+                {
+                    return launch_error(e.into());
+                }
+            }
+            Err(e) => return launch_error(e.into()),
+        }
+    }
+
+    // Never an admin session: the LMS vouches for the user's course, not their FitchFork rights
+    let login = match two_factor::redirect_login(db, &user, false).await {
+        Ok(login) => login,
+        Err(e) => return launch_error(e.into()),
+    };
+    let fragment = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(login)
+        .append_pair("redirect", &target)
+        .finish();
+    Redirect::to(&format!(
+        "{}/lti/launch#{}",
+        config::frontend_url().trim_end_matches('/'),
+        fragment
+    ))
+    .into_response()
+}
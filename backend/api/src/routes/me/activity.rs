@@ -11,6 +11,7 @@ use chrono::{DateTime, Duration, SecondsFormat, Utc};
 use db::models::{
     announcements, assignment, assignment_submission, module, user, user_module_role,
 };
+use db::soft_delete::SoftDelete;
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
 use serde::{Deserialize, Serialize};
 use util::state::AppState;
@@ -240,8 +241,9 @@ pub async fn get_my_activity(
     let mut envelopes: Vec<ActivityEnvelope> = Vec::new();
 
     if include_kind(ActivityKind::Announcement) {
-        match announcements::Entity::find()
+        match announcements::Entity::find_active()
             .filter(announcements::Column::ModuleId.is_in(module_ids.clone()))
+            .filter(announcements::Model::visible_at(Utc::now()))
             .order_by_desc(announcements::Column::CreatedAt)
             .limit(fetch_limit)
             .all(db)
@@ -313,7 +315,7 @@ pub async fn get_my_activity(
 
     if include_kind(ActivityKind::AssignmentAvailable) || include_kind(ActivityKind::AssignmentDue)
     {
-        match assignment::Entity::find()
+        match assignment::Entity::find_active()
             .filter(assignment::Column::ModuleId.is_in(module_ids.clone()))
             .order_by_desc(assignment::Column::UpdatedAt)
             .limit(fetch_limit)
@@ -388,7 +390,7 @@ pub async fn get_my_activity(
     }
 
     if include_kind(ActivityKind::Submission) {
-        match assignment_submission::Entity::find()
+        match assignment_submission::Entity::find_active()
             .filter(assignment_submission::Column::UserId.eq(user_id))
             .order_by_desc(assignment_submission::Column::CreatedAt)
             .limit(fetch_limit)
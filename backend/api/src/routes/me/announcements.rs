@@ -4,7 +4,7 @@
 //!
 //! Users can retrieve a paginated list of announcements filtered by role, year, pinned status,
 //! search query, and sorted by various fields. Only announcements in modules the user
-//! is associated with are returned.
+//! is associated with are returned, and scheduled or expired ones only where they are staff.
 
 use axum::{
     Extension, Json,
@@ -12,8 +12,10 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use chrono::Utc;
 use db::models::{announcements, module, user, user_module_role};
-use migration::Expr;
+use migration::{Expr, Func};
+use db::soft_delete::SoftDelete;
 use sea_orm::{
     ColumnTrait, Condition, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder,
     QuerySelect, RelationTrait,
@@ -184,7 +186,19 @@ pub async fn get_my_announcements(
             .into_response();
     }
 
-    let mut condition = Condition::all().add(announcements::Column::ModuleId.is_in(module_ids));
+    let staff_module_ids: Vec<i64> = memberships
+        .iter()
+        .filter(|m| m.role != user_module_role::Role::Student)
+        .map(|m| m.module_id)
+        .collect();
+
+    let mut condition = Condition::all()
+        .add(announcements::Column::ModuleId.is_in(module_ids))
+        .add(
+            Condition::any()
+                .add(announcements::Column::ModuleId.is_in(staff_module_ids))
+                .add(announcements::Model::visible_at(Utc::now())),
+        );
 
     if let Some(year) = params.year {
         condition = condition.add(Expr::col((module::Entity, module::Column::Year)).eq(year));
@@ -198,13 +212,31 @@ pub async fn get_my_announcements(
         let pattern = format!("%{}%", q.to_lowercase());
         condition = condition.add(
             Condition::any()
-                .add(Expr::cust("LOWER(announcements.title)").like(&pattern))
-                .add(Expr::cust("LOWER(module.code)").like(&pattern))
-                .add(Expr::cust("LOWER(user.username)").like(&pattern)),
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        announcements::Entity,
+                        announcements::Column::Title,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        module::Entity,
+                        module::Column::Code,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        user::Entity,
+                        user::Column::Username,
+                    ))))
+                    .like(&pattern),
+                ),
         );
     }
 
-    let mut query = announcements::Entity::find()
+    let mut query = announcements::Entity::find_active()
         .join(JoinType::InnerJoin, announcements::Relation::Module.def())
         .join(JoinType::InnerJoin, announcements::Relation::User.def())
         .filter(condition);
@@ -0,0 +1,230 @@
+//! # My API Token Handlers
+//!
+//! Personal access tokens, for calling the API from scripts and CI pipelines without a password
+//! or a short-lived login JWT (see [`db::models::api_token`]). Send one as
+//! `Authorization: Bearer ffk_...`.
+//!
+//! Tokens can only be managed from a login session; a token can't list, issue or revoke tokens.
+
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{DateTime, Duration, Utc};
+use db::models::api_token::{self, Scope};
+use serde::{Deserialize, Serialize};
+use util::state::AppState;
+
+use crate::{auth::AuthUser, response::ApiResponse};
+
+/// Lifetime of a token when none is asked for.
+pub const DEFAULT_EXPIRY_DAYS: i64 = 90;
+
+/// Longest lifetime a token can have.
+pub const MAX_EXPIRY_DAYS: i64 = 365;
+
+/// Longest token name.
+const MAX_NAME_LEN: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiTokenRequest {
+    /// What the token is for, e.g. `"Marks sync"`.
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    /// Days until the token expires; [`DEFAULT_EXPIRY_DAYS`] if left out.
+    pub expires_in_days: Option<i64>,
+}
+
+impl CreateApiTokenRequest {
+    /// Checks the request, returning the token's expiry. `admin_allowed` is whether the owner
+    /// is an admin, as only their tokens may carry the `admin` scope.
+    pub fn validate(&self, admin_allowed: bool) -> Result<DateTime<Utc>, String> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(format!("name must be 1 to {MAX_NAME_LEN} characters"));
+        }
+        if self.scopes.is_empty() {
+            return Err("At least one scope is required".into());
+        }
+        if self.scopes.contains(&Scope::Admin) && !admin_allowed {
+            return Err("Only admins' tokens can have the admin scope".into());
+        }
+        let days = self.expires_in_days.unwrap_or(DEFAULT_EXPIRY_DAYS);
+        if !(1..=MAX_EXPIRY_DAYS).contains(&days) {
+            return Err(format!(
+                "expires_in_days must be between 1 and {MAX_EXPIRY_DAYS}"
+            ));
+        }
+        Ok(Utc::now() + Duration::days(days))
+    }
+}
+
+/// A token as listed; the token itself is never shown again after it is created.
+#[derive(Debug, Serialize)]
+pub struct ApiTokenResponse {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: String,
+    pub last_used_at: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+impl From<api_token::Model> for ApiTokenResponse {
+    fn from(t: api_token::Model) -> Self {
+        Self {
+            scopes: t.scope_list(),
+            id: t.id,
+            user_id: t.user_id,
+            name: t.name,
+            token_prefix: t.token_prefix,
+            expires_at: t.expires_at.to_rfc3339(),
+            last_used_at: t.last_used_at.map(|at| at.to_rfc3339()),
+            created_by: t.created_by,
+            created_at: t.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub api_token: ApiTokenResponse,
+    /// The token itself; shown this once.
+    pub token: String,
+}
+
+/// GET `/api/me/api-tokens`
+///
+/// The user's API tokens, newest first.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "API tokens retrieved",
+///   "data": [{
+///     "id": 3, "user_id": 7, "name": "Marks sync", "token_prefix": "ffk_a1B2c3D4",
+///     "scopes": ["read"], "expires_at": "2026-01-14T10:00:00+00:00",
+///     "last_used_at": null, "created_by": null, "created_at": "2025-10-16T10:00:00+00:00"
+///   }]
+/// }
+/// ```
+/// - `403 Forbidden` — Called with an API token
+pub async fn get_my_api_tokens(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> (StatusCode, Json<ApiResponse<Vec<ApiTokenResponse>>>) {
+    match api_token::Model::for_user(state.db(), claims.sub).await {
+        Ok(tokens) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                tokens.into_iter().map(Into::into).collect(),
+                "API tokens retrieved",
+            )),
+        ),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error("Failed to retrieve API tokens")),
+        ),
+    }
+}
+
+/// POST `/api/me/api-tokens`
+///
+/// Issues the user an API token.
+///
+/// ### Request Body
+/// ```json
+/// { "name": "Marks sync", "scopes": ["read"], "expires_in_days": 30 }
+/// ```
+/// Scopes:
+/// - `read` — `GET` requests only
+/// - `write` — any request
+/// - `admin` — the user's admin rights (admins only); without it the token acts as a regular user
+///
+/// ### Responses
+/// - `201 Created` — The token as listed, plus `token`, which is not shown again
+/// - `400 Bad Request` — Missing name or scopes, `admin` for a non-admin, or an expiry outside
+///   1 to 365 days
+/// - `403 Forbidden` — Called with an API token
+pub async fn create_my_api_token(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<CreateApiTokenRequest>,
+) -> (StatusCode, Json<ApiResponse<CreatedApiTokenResponse>>) {
+    let expires_at = match req.validate(claims.admin) {
+        Ok(at) => at,
+        Err(msg) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg))),
+    };
+    create_token(&state, claims.sub, &req, expires_at, None).await
+}
+
+/// Issues a token for `user_id` from a validated request.
+pub async fn create_token(
+    state: &AppState,
+    user_id: i64,
+    req: &CreateApiTokenRequest,
+    expires_at: DateTime<Utc>,
+    created_by: Option<i64>,
+) -> (StatusCode, Json<ApiResponse<CreatedApiTokenResponse>>) {
+    match api_token::Model::create(
+        state.db(),
+        user_id,
+        req.name.trim(),
+        &req.scopes,
+        expires_at,
+        created_by,
+    )
+    .await
+    {
+        Ok((model, token)) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                CreatedApiTokenResponse {
+                    api_token: model.into(),
+                    token,
+                },
+                "API token created; copy it now, it won't be shown again",
+            )),
+        ),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error("Failed to create API token")),
+        ),
+    }
+}
+
+/// DELETE `/api/me/api-tokens/{token_id}`
+///
+/// Revokes one of the user's API tokens; it stops working immediately.
+///
+/// ### Responses
+/// - `200 OK` — Revoked
+/// - `403 Forbidden` — Called with an API token
+/// - `404 Not Found` — No such token of the user's
+pub async fn revoke_my_api_token(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Path(token_id): Path<i64>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    match api_token::Model::revoke(state.db(), token_id, Some(claims.sub)).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "API token revoked")),
+        ),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error("API token not found")),
+        ),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error("Failed to revoke API token")),
+        ),
+    }
+}
@@ -22,7 +22,8 @@ use db::models::{
     module,
     user_module_role::{self, Role as ModuleRole},
 };
-use migration::Expr;
+use migration::{Expr, Func};
+use db::soft_delete::SoftDelete;
 use sea_orm::{
     ColumnTrait, Condition, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder,
     QuerySelect, RelationTrait,
@@ -239,12 +240,24 @@ pub async fn get_my_assignments(
         let pattern = format!("%{}%", q.to_lowercase());
         condition = condition.add(
             Condition::any()
-                .add(Expr::cust("LOWER(assignment.title)").like(&pattern))
-                .add(Expr::cust("LOWER(module.code)").like(&pattern)),
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        assignment::Entity,
+                        assignment::Column::Name,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        module::Entity,
+                        module::Column::Code,
+                    ))))
+                    .like(&pattern),
+                ),
         );
     }
 
-    let mut query = assignment::Entity::find()
+    let mut query = assignment::Entity::find_active()
         .join(JoinType::InnerJoin, assignment::Relation::Module.def())
         .filter(condition);
 
@@ -0,0 +1,275 @@
+//! # My Calendar Handlers
+//!
+//! A subscribable iCalendar feed of the user's deadlines and sessions, for Google Calendar,
+//! Outlook and the like.
+//!
+//! Calendar apps fetch the feed on their own schedule and can't log in, so the feed URL carries
+//! a per-user feed token ([`calendar_feed_token`]) instead of a JWT. The token only opens the
+//! feed; `POST /me/calendar/reset` replaces it when a URL has been shared by mistake.
+
+use std::collections::HashMap;
+
+use axum::{
+    Extension, Json,
+    extract::{Query, State},
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use db::models::{
+    announcements, assignment, assignment_extension, attendance_session, calendar_feed_token,
+    module, user_module_role,
+};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use util::{
+    config,
+    ical::{self, CalendarEvent},
+    state::AppState,
+};
+
+use crate::{auth::AuthUser, response::ApiResponse};
+
+/// Where the user's feed lives, to hand to a calendar app.
+#[derive(Debug, Serialize)]
+pub struct CalendarFeedResponse {
+    pub token: String,
+    /// API path of the feed, token included, e.g. `/api/me/calendar.ics?token=...`
+    pub path: String,
+}
+
+impl From<calendar_feed_token::Model> for CalendarFeedResponse {
+    fn from(t: calendar_feed_token::Model) -> Self {
+        Self {
+            path: format!("/api/me/calendar.ics?token={}", t.token),
+            token: t.token,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalendarQuery {
+    pub token: Option<String>,
+}
+
+/// GET `/api/me/calendar`
+///
+/// The user's calendar feed URL, creating its token the first time.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Calendar feed retrieved",
+///   "data": { "token": "Xb3...", "path": "/api/me/calendar.ics?token=Xb3..." }
+/// }
+/// ```
+/// - `500 Internal Server Error` — Database error
+pub async fn get_my_calendar_feed(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> (StatusCode, Json<ApiResponse<CalendarFeedResponse>>) {
+    match calendar_feed_token::Model::for_user(state.db(), claims.sub).await {
+        Ok(t) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(t.into(), "Calendar feed retrieved")),
+        ),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error("Failed to retrieve calendar feed")),
+        ),
+    }
+}
+
+/// POST `/api/me/calendar/reset`
+///
+/// Replaces the user's feed token. Calendars subscribed with the old URL stop updating.
+///
+/// ### Responses
+/// - `200 OK` — The new feed, as for `GET /api/me/calendar`
+/// - `500 Internal Server Error` — Database error
+pub async fn reset_my_calendar_feed(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> (StatusCode, Json<ApiResponse<CalendarFeedResponse>>) {
+    match calendar_feed_token::Model::regenerate(state.db(), claims.sub).await {
+        Ok(t) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(t.into(), "Calendar feed reset")),
+        ),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error("Failed to reset calendar feed")),
+        ),
+    }
+}
+
+/// GET `/api/me/calendar.ics?token=...`
+///
+/// The user's calendar as `text/calendar`, across every module they are assigned to:
+/// - assignment due dates (extended ones where the user has an extension; students don't see
+///   assignments still being set up)
+/// - attendance sessions, when they were opened
+/// - scheduled announcements, when they go live (students only see those already live)
+///
+/// **Auth**: the feed token from `GET /api/me/calendar` in `token`; no bearer token needed.
+///
+/// ### Responses
+/// - `200 OK` — `text/calendar` named `fitchfork.ics`
+/// - `401 Unauthorized` — Missing or unknown token
+/// - `500 Internal Server Error` — Database error
+pub async fn get_calendar_ics(
+    State(state): State<AppState>,
+    Query(query): Query<CalendarQuery>,
+) -> Response {
+    let db = state.db();
+    let user_id = match query.token.as_deref() {
+        Some(token) => calendar_feed_token::Model::find_user_id(db, token).await,
+        None => Ok(None),
+    };
+    let user_id = match user_id {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            return (StatusCode::UNAUTHORIZED, "Invalid calendar feed token").into_response();
+        }
+        Err(_) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load calendar").into_response();
+        }
+    };
+
+    let events = match calendar_events(db, user_id).await {
+        Ok(events) => events,
+        Err(e) => {
+            tracing::warn!("Failed to build calendar for user {}: {}", user_id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load calendar").into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/calendar; charset=utf-8"),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_static("inline; filename=\"fitchfork.ics\""),
+            ),
+        ],
+        ical::render("FitchFork", &events),
+    )
+        .into_response()
+}
+
+async fn calendar_events(
+    db: &DatabaseConnection,
+    user_id: i64,
+) -> Result<Vec<CalendarEvent>, DbErr> {
+    let memberships = user_module_role::Entity::find()
+        .filter(user_module_role::Column::UserId.eq(user_id))
+        .all(db)
+        .await?;
+    if memberships.is_empty() {
+        return Ok(Vec::new());
+    }
+    let module_ids: Vec<i64> = memberships.iter().map(|m| m.module_id).collect();
+    let staff_module_ids: Vec<i64> = memberships
+        .iter()
+        .filter(|m| m.role != user_module_role::Role::Student)
+        .map(|m| m.module_id)
+        .collect();
+    let codes: HashMap<i64, String> = module::Entity::find()
+        .filter(module::Column::Id.is_in(module_ids.clone()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|m| (m.id, m.code))
+        .collect();
+    let code = |module_id: i64| codes.get(&module_id).map(String::as_str).unwrap_or("");
+
+    let frontend = config::frontend_url();
+    let frontend = frontend.trim_end_matches('/');
+    let now = Utc::now();
+    let mut events = Vec::new();
+
+    let extended: HashMap<i64, _> = assignment_extension::Entity::find()
+        .filter(assignment_extension::Column::UserId.eq(user_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|e| (e.assignment_id, e.due_date))
+        .collect();
+    let assignments = assignment::Entity::find_active()
+        .filter(assignment::Column::ModuleId.is_in(module_ids.clone()))
+        .filter(
+            Condition::any()
+                .add(assignment::Column::ModuleId.is_in(staff_module_ids.clone()))
+                .add(assignment::Column::Status.ne(assignment::Status::Setup)),
+        )
+        .all(db)
+        .await?;
+    for a in assignments {
+        events.push(CalendarEvent {
+            uid: format!("assignment-{}@fitchfork", a.id),
+            summary: format!("{}: {} due", code(a.module_id), a.name),
+            description: a.description.clone(),
+            url: Some(format!(
+                "{frontend}/modules/{}/assignments/{}",
+                a.module_id, a.id
+            )),
+            start: extended.get(&a.id).copied().unwrap_or(a.due_date),
+            end: None,
+            stamp: a.updated_at,
+        });
+    }
+
+    let sessions = attendance_session::Entity::find()
+        .filter(attendance_session::Column::ModuleId.is_in(module_ids.clone()))
+        .all(db)
+        .await?;
+    for s in sessions {
+        events.push(CalendarEvent {
+            uid: format!("attendance-session-{}@fitchfork", s.id),
+            summary: format!("{}: {}", code(s.module_id), s.title),
+            description: Some("Attendance session".to_string()),
+            url: Some(format!("{frontend}/modules/{}/attendance", s.module_id)),
+            start: s.created_at,
+            end: None,
+            stamp: s.updated_at,
+        });
+    }
+
+    let scheduled = announcements::Entity::find_active()
+        .filter(announcements::Column::ModuleId.is_in(module_ids))
+        .filter(announcements::Column::PublishAt.is_not_null())
+        .filter(
+            Condition::any()
+                .add(announcements::Column::ModuleId.is_in(staff_module_ids))
+                .add(announcements::Model::visible_at(now)),
+        )
+        .all(db)
+        .await?;
+    for a in scheduled {
+        let Some(publish_at) = a.publish_at else {
+            continue;
+        };
+        events.push(CalendarEvent {
+            uid: format!("announcement-{}@fitchfork", a.id),
+            summary: format!("{}: {}", code(a.module_id), a.title),
+            description: Some(a.body.clone()),
+            url: Some(format!(
+                "{frontend}/modules/{}/announcements/{}",
+                a.module_id, a.id
+            )),
+            start: publish_at,
+            end: None,
+            stamp: a.updated_at,
+        });
+    }
+
+    events.sort_by_key(|e| e.start);
+    Ok(events)
+}
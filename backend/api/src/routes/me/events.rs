@@ -8,8 +8,9 @@ use axum::{
 use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
 use common::format_validation_errors;
 use db::models::{assignment, module, user_module_role};
+use db::soft_delete::SoftDelete;
 use sea_orm::{
-    ColumnTrait, Condition, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait,
+    ColumnTrait, Condition, JoinType, QueryFilter, QuerySelect, RelationTrait,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -136,7 +137,7 @@ pub async fn get_my_events(
 
     let mut events_map: HashMap<String, Vec<EventItem>> = HashMap::new();
 
-    let mut assignment_query = assignment::Entity::find()
+    let mut assignment_query = assignment::Entity::find_active()
         .join(JoinType::InnerJoin, assignment::Relation::Module.def())
         .join(JoinType::InnerJoin, module::Relation::UserModuleRole.def())
         .filter(user_module_role::Column::UserId.eq(user_id));
@@ -16,6 +16,7 @@ use db::models::{
 };
 use sea_orm::{
     ColumnTrait, Condition, EntityTrait, FromQueryResult, QueryFilter, QuerySelect, RelationTrait,
+    sea_query::{Expr, Func},
 };
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::HashSet};
@@ -163,7 +164,7 @@ pub async fn get_my_grades(
             .into_response();
     }
 
-    let db = app_state.db();
+    let db = app_state.db_read();
     let caller_id = user.0.sub;
 
     let page = query.page.unwrap_or(1);
@@ -214,9 +215,27 @@ pub async fn get_my_grades(
         let pattern = format!("%{}%", q.to_lowercase());
         condition = condition.add(
             Condition::any()
-                .add(assignment::Column::Name.like(&pattern))
-                .add(user::Column::Username.like(&pattern))
-                .add(module::Column::Code.like(&pattern)),
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        assignment::Entity,
+                        assignment::Column::Name,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        user::Entity,
+                        user::Column::Username,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        module::Entity,
+                        module::Column::Code,
+                    ))))
+                    .like(&pattern),
+                ),
         );
     }
 
@@ -4,23 +4,36 @@
 //!
 //! ## Structure
 //! - `announcements.rs` — GET handlers for fetching the user's announcements
+//! - `api_tokens.rs` — the user's API tokens (login sessions only)
 //! - `assignments.rs` — GET handlers for fetching the user's assignments
+//! - `calendar.rs` — the user's calendar feed URL and the iCalendar feed itself
 //! - `tickets.rs` — GET handlers for fetching the user's tickets
 //! - `grades.rs` — GET handlers for fetching the user's grades
 //! - `submissions.rs` — GET handlers for fetching the user's submissions
 //! - `events.rs` — GET handlers for fetching the user's events
+//! - `notifications.rs` — GET/PUT handlers for the user's notifications, notification preferences
+//!   and email settings
 //!
 //! ## Usage
 //! Call `me_routes()` to get a configured `Router` for `/me` endpoints to be mounted in the main app.
 
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    middleware::from_fn,
+    routing::{delete, get, post, put},
+};
 use util::state::AppState;
 
+use crate::auth::guards::deny_api_tokens;
+
 pub mod activity;
 pub mod announcements;
+pub mod api_tokens;
 pub mod assignments;
+pub mod calendar;
 pub mod events;
 pub mod grades;
+pub mod notifications;
 pub mod plagiarism;
 pub mod submissions;
 pub mod tickets;
@@ -36,8 +49,17 @@ pub mod tickets;
 /// - `GET /me/events`        → fetch events for the logged-in user
 /// - `GET /me/plagiarism`    → fetch plagiarism cases for lecturer/assistant lecturer roles
 /// - `GET /me/activity`      → aggregated, paginated activity feed for the logged-in user
+/// - `GET /me/notifications` → paginated notifications for the logged-in user
+/// - `PUT /me/notifications/read-all` → mark all notifications as read
+/// - `PUT /me/notifications/{notification_id}/read` → mark one notification as read
+/// - `GET|PUT /me/notifications/preferences` → per-kind in-app/email delivery preferences
+/// - `GET|PUT /me/notifications/email` → email opt-out and digest mode
+/// - `GET /me/calendar`      → the user's calendar feed URL
+/// - `POST /me/calendar/reset` → replace the calendar feed token
 ///
 /// All routes operate on the currently authenticated user and require the application state.
+/// The feed itself, `GET /me/calendar.ics`, is mounted outside this group by `routes()` since
+/// calendar apps authenticate with the feed token rather than a JWT.
 pub fn me_routes() -> Router<AppState> {
     Router::new()
         .route("/announcements", get(announcements::get_my_announcements))
@@ -48,4 +70,34 @@ pub fn me_routes() -> Router<AppState> {
         .route("/events", get(events::get_my_events))
         .route("/plagiarism", get(plagiarism::get_my_plagiarism_cases))
         .route("/activity", get(activity::get_my_activity))
+        .route("/notifications", get(notifications::get_my_notifications))
+        .route(
+            "/notifications/read-all",
+            put(notifications::mark_all_notifications_read),
+        )
+        .route(
+            "/notifications/{notification_id}/read",
+            put(notifications::mark_notification_read),
+        )
+        .route(
+            "/notifications/preferences",
+            get(notifications::get_my_notification_preferences)
+                .put(notifications::put_my_notification_preferences),
+        )
+        .route(
+            "/notifications/email",
+            get(notifications::get_my_email_settings).put(notifications::put_my_email_settings),
+        )
+        .route("/calendar", get(calendar::get_my_calendar_feed))
+        .route("/calendar/reset", post(calendar::reset_my_calendar_feed))
+        .route(
+            "/api-tokens",
+            get(api_tokens::get_my_api_tokens)
+                .post(api_tokens::create_my_api_token)
+                .route_layer(from_fn(deny_api_tokens)),
+        )
+        .route(
+            "/api-tokens/{token_id}",
+            delete(api_tokens::revoke_my_api_token).route_layer(from_fn(deny_api_tokens)),
+        )
 }
@@ -0,0 +1,400 @@
+//! # My Notifications Handlers
+//!
+//! Provides endpoints for the currently authenticated user's notifications and notification
+//! preferences.
+//!
+//! Notifications are created by `services::notifications` (submission marked, announcement
+//! posted, ticket replied) and also pushed live on the `user:{user_id}.notifications` WebSocket
+//! topic. Preferences choose, per notification kind, whether the in-app and email channels are
+//! used; email settings opt out of notification emails altogether or batch them into a digest.
+
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{
+    email_setting::Model as EmailSettingModel,
+    notification::{Model as NotificationModel, NotificationKind},
+    notification_preference::Model as PreferenceModel,
+};
+use sea_orm::DbErr;
+use serde::{Deserialize, Serialize};
+use util::state::AppState;
+
+/// Query parameters for listing notifications
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    /// Page number (default: 1)
+    pub page: Option<u64>,
+    /// Items per page (default: 20, max: 100)
+    pub per_page: Option<u64>,
+    /// Only return unread notifications (default: false)
+    pub unread: Option<bool>,
+}
+
+/// Response object for a single notification
+#[derive(Debug, Serialize)]
+pub struct NotificationResponse {
+    pub id: i64,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub link: Option<String>,
+    pub read: bool,
+    pub read_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<NotificationModel> for NotificationResponse {
+    fn from(n: NotificationModel) -> Self {
+        Self {
+            id: n.id,
+            kind: n.kind.to_string(),
+            title: n.title,
+            body: n.body,
+            link: n.link,
+            read: n.read_at.is_some(),
+            read_at: n.read_at.map(|t| t.to_rfc3339()),
+            created_at: n.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Paginated list of notifications
+#[derive(Debug, Serialize)]
+pub struct NotificationsResponse {
+    pub notifications: Vec<NotificationResponse>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+    /// Unread notifications across all pages
+    pub unread: u64,
+}
+
+/// One notification kind's delivery channels
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreferenceItem {
+    pub kind: NotificationKind,
+    pub in_app: bool,
+    pub email: bool,
+}
+
+impl From<PreferenceModel> for PreferenceItem {
+    fn from(p: PreferenceModel) -> Self {
+        Self {
+            kind: p.kind,
+            in_app: p.in_app,
+            email: p.email,
+        }
+    }
+}
+
+/// Request/response body for notification preferences
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PreferencesBody {
+    pub preferences: Vec<PreferenceItem>,
+}
+
+/// Response for marking all notifications as read
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailSettingsBody {
+    /// No notification emails at all
+    pub opted_out: bool,
+    /// One digest email every `EMAIL_DIGEST_INTERVAL_SECS` instead of one email per notification
+    pub digest: bool,
+}
+
+impl From<EmailSettingModel> for EmailSettingsBody {
+    fn from(s: EmailSettingModel) -> Self {
+        Self {
+            opted_out: s.opted_out,
+            digest: s.digest,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadAllResponse {
+    pub updated: u64,
+}
+
+fn db_error(context: &str, err: DbErr) -> axum::response::Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiResponse::<()>::error(format!("{context}: {err}"))),
+    )
+        .into_response()
+}
+
+/// GET /api/me/notifications
+///
+/// Retrieves the authenticated user's notifications, newest first.
+///
+/// ### Query Parameters
+/// - `page` (optional): Page number (default: 1)
+/// - `per_page` (optional): Items per page (default: 20, max: 100)
+/// - `unread` (optional): Only return unread notifications (default: false)
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "notifications": [
+///       {
+///         "id": 12,
+///         "kind": "submission_marked",
+///         "title": "Assignment 1: submission marked",
+///         "body": "Attempt 2 scored 45/50.",
+///         "link": "/modules/1/assignments/3/submissions/40",
+///         "read": false,
+///         "read_at": null,
+///         "created_at": "2025-10-16T10:00:00+00:00"
+///       }
+///     ],
+///     "page": 1,
+///     "per_page": 20,
+///     "total": 1,
+///     "unread": 1
+///   },
+///   "message": "Notifications retrieved"
+/// }
+/// ```
+pub async fn get_my_notifications(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Query(params): Query<NotificationsQuery>,
+) -> impl IntoResponse {
+    let db = state.db();
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+
+    let (items, total) = match NotificationModel::list_for_user(
+        db,
+        claims.sub,
+        params.unread.unwrap_or(false),
+        page,
+        per_page,
+    )
+    .await
+    {
+        Ok(r) => r,
+        Err(e) => return db_error("Failed to retrieve notifications", e),
+    };
+    let unread = match NotificationModel::unread_count(db, claims.sub).await {
+        Ok(n) => n,
+        Err(e) => return db_error("Failed to retrieve notifications", e),
+    };
+
+    let response = NotificationsResponse {
+        notifications: items.into_iter().map(NotificationResponse::from).collect(),
+        page,
+        per_page,
+        total,
+        unread,
+    };
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(response, "Notifications retrieved")),
+    )
+        .into_response()
+}
+
+/// PUT /api/me/notifications/{notification_id}/read
+///
+/// Marks one of the authenticated user's notifications as read.
+///
+/// ### Responses
+/// - `200 OK` — The updated notification
+/// - `404 Not Found` — No such notification for this user
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Path(notification_id): Path<i64>,
+) -> impl IntoResponse {
+    match NotificationModel::mark_read(state.db(), claims.sub, notification_id).await {
+        Ok(n) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                NotificationResponse::from(n),
+                "Notification marked as read",
+            )),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(msg)) => {
+            (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(msg))).into_response()
+        }
+        Err(e) => db_error("Failed to update notification", e),
+    }
+}
+
+/// PUT /api/me/notifications/read-all
+///
+/// Marks all of the authenticated user's notifications as read.
+///
+/// ### Example Response
+/// ```json
+/// { "success": true, "data": { "updated": 3 }, "message": "Notifications marked as read" }
+/// ```
+pub async fn mark_all_notifications_read(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    match NotificationModel::mark_all_read(state.db(), claims.sub).await {
+        Ok(updated) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                ReadAllResponse { updated },
+                "Notifications marked as read",
+            )),
+        )
+            .into_response(),
+        Err(e) => db_error("Failed to update notifications", e),
+    }
+}
+
+/// GET /api/me/notifications/preferences
+///
+/// Retrieves the authenticated user's channels for every notification kind. Kinds the user
+/// hasn't configured report the defaults (in-app on, email off).
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "preferences": [
+///       { "kind": "submission_marked", "in_app": true, "email": true },
+///       { "kind": "announcement_posted", "in_app": true, "email": false },
+///       { "kind": "ticket_replied", "in_app": true, "email": false },
+///       { "kind": "regrade_updated", "in_app": true, "email": false }
+///     ]
+///   },
+///   "message": "Notification preferences retrieved"
+/// }
+/// ```
+pub async fn get_my_notification_preferences(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    match PreferenceModel::for_user(state.db(), claims.sub).await {
+        Ok(prefs) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                PreferencesBody {
+                    preferences: prefs.into_iter().map(PreferenceItem::from).collect(),
+                },
+                "Notification preferences retrieved",
+            )),
+        )
+            .into_response(),
+        Err(e) => db_error("Failed to retrieve notification preferences", e),
+    }
+}
+
+/// PUT /api/me/notifications/preferences
+///
+/// Saves channels for the listed notification kinds; unlisted kinds are left unchanged.
+/// Returns the full set of preferences afterwards.
+///
+/// ### Request Body
+/// ```json
+/// { "preferences": [ { "kind": "submission_marked", "in_app": true, "email": true } ] }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — Same shape as `GET /api/me/notifications/preferences`
+/// - `422 Unprocessable Entity` — Unknown `kind` or missing fields
+pub async fn put_my_notification_preferences(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(body): Json<PreferencesBody>,
+) -> impl IntoResponse {
+    let db = state.db();
+    for item in &body.preferences {
+        if let Err(e) =
+            PreferenceModel::set(db, claims.sub, item.kind, item.in_app, item.email).await
+        {
+            return db_error("Failed to save notification preferences", e);
+        }
+    }
+
+    match PreferenceModel::for_user(db, claims.sub).await {
+        Ok(prefs) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                PreferencesBody {
+                    preferences: prefs.into_iter().map(PreferenceItem::from).collect(),
+                },
+                "Notification preferences updated",
+            )),
+        )
+            .into_response(),
+        Err(e) => db_error("Failed to retrieve notification preferences", e),
+    }
+}
+
+/// GET /api/me/notifications/email
+///
+/// Returns the authenticated user's email settings. These apply on top of the per-kind `email`
+/// preferences: an opted-out user gets no notification emails, and a user in digest mode gets
+/// them batched into one email every `EMAIL_DIGEST_INTERVAL_SECS`.
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": { "opted_out": false, "digest": true },
+///   "message": "Email settings retrieved"
+/// }
+/// ```
+pub async fn get_my_email_settings(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    match EmailSettingModel::for_user(state.db(), claims.sub).await {
+        Ok(settings) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                EmailSettingsBody::from(settings),
+                "Email settings retrieved",
+            )),
+        )
+            .into_response(),
+        Err(e) => db_error("Failed to retrieve email settings", e),
+    }
+}
+
+/// PUT /api/me/notifications/email
+///
+/// Saves the authenticated user's email settings. Emails already queued for a digest still go
+/// out in the next one.
+///
+/// ### Request Body
+/// ```json
+/// { "opted_out": false, "digest": true }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` → The saved settings (same shape as GET)
+/// - `422 Unprocessable Entity` → Missing or non-boolean fields
+pub async fn put_my_email_settings(
+    State(state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(body): Json<EmailSettingsBody>,
+) -> impl IntoResponse {
+    match EmailSettingModel::set(state.db(), claims.sub, body.opted_out, body.digest).await {
+        Ok(settings) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                EmailSettingsBody::from(settings),
+                "Email settings updated",
+            )),
+        )
+            .into_response(),
+        Err(e) => db_error("Failed to save email settings", e),
+    }
+}
@@ -14,6 +14,7 @@ use db::models::{
     user,
     user_module_role::{self, Role},
 };
+use db::soft_delete::SoftDelete;
 use sea_orm::{ColumnTrait, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use util::state::AppState;
@@ -163,7 +164,7 @@ pub async fn get_my_plagiarism_cases(
     }
 
     let mut assignments_query =
-        assignment::Entity::find().filter(assignment::Column::ModuleId.is_in(module_ids.clone()));
+        assignment::Entity::find_active().filter(assignment::Column::ModuleId.is_in(module_ids.clone()));
 
     if let Some(assignment_id) = query.assignment_id {
         assignments_query = assignments_query.filter(assignment::Column::Id.eq(assignment_id));
@@ -255,7 +256,7 @@ pub async fn get_my_plagiarism_cases(
         .flat_map(|c| [c.submission_id_1, c.submission_id_2])
         .collect();
 
-    let submissions = SubmissionEntity::find()
+    let submissions = SubmissionEntity::find_active()
         .filter(SubmissionColumn::Id.is_in(submission_ids.clone()))
         .all(db)
         .await
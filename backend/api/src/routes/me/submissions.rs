@@ -5,9 +5,10 @@ use axum::{
     response::IntoResponse,
 };
 use common::format_validation_errors;
+use db::soft_delete::SoftDelete;
 use sea_orm::{
-    ColumnTrait, Condition, EntityTrait, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder,
-    QuerySelect, RelationTrait, prelude::Expr,
+    ColumnTrait, Condition, FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, prelude::Expr, sea_query::Func,
 };
 use sea_orm_migration::prelude::Alias;
 use serde::{Deserialize, Serialize};
@@ -89,13 +90,13 @@ pub struct SubmissionWithRelations {
     pub id: i64,
     pub earned: f64,
     pub total: f64,
-    pub created_at: chrono::NaiveDateTime,
-    pub updated_at: chrono::NaiveDateTime,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
     pub user_id: i64,
     pub assignment_id: i64,
     pub assignment_name: String,
     pub assignment_description: Option<String>,
-    pub assignment_due_date: chrono::NaiveDateTime,
+    pub assignment_due_date: chrono::DateTime<chrono::Utc>,
     pub module_id: i64,
     pub module_code: String,
     pub username: String,
@@ -172,7 +173,7 @@ pub async fn get_my_submissions(
     let page = query.page.unwrap_or(1);
     let per_page = query.per_page.unwrap_or(20);
 
-    let mut query_builder = SubmissionEntity::find()
+    let mut query_builder = SubmissionEntity::find_active()
         .column_as(SubmissionColumn::Id, "id")
         .column_as(SubmissionColumn::Earned, "earned")
         .column_as(SubmissionColumn::Total, "total")
@@ -222,9 +223,27 @@ pub async fn get_my_submissions(
         let pattern = format!("%{}%", q.to_lowercase());
         condition = condition.add(
             Condition::any()
-                .add(module::Column::Code.like(&pattern))
-                .add(user::Column::Username.like(&pattern))
-                .add(assignment::Column::Name.like(&pattern)),
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        module::Entity,
+                        module::Column::Code,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        user::Entity,
+                        user::Column::Username,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        assignment::Entity,
+                        assignment::Column::Name,
+                    ))))
+                    .like(&pattern),
+                ),
         );
     }
 
@@ -301,8 +320,8 @@ pub async fn get_my_submissions(
                     earned: s.earned,
                     total: s.total,
                 },
-                created_at: s.created_at.to_string(),
-                updated_at: s.updated_at.to_string(),
+                created_at: s.created_at.naive_utc().to_string(),
+                updated_at: s.updated_at.naive_utc().to_string(),
                 is_late,
                 module: ModuleInfo {
                     id: s.module_id,
@@ -17,7 +17,8 @@ use db::models::{
     assignment, module, tickets, user,
     user_module_role::{self},
 };
-use migration::Expr;
+use migration::{Expr, Func};
+use db::soft_delete::SoftDelete;
 use sea_orm::{
     ColumnTrait, Condition, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder,
     QuerySelect, RelationTrait,
@@ -191,7 +192,7 @@ pub async fn get_my_tickets(
         .map(|m| m.module_id)
         .collect();
 
-    let assignments = assignment::Entity::find()
+    let assignments = assignment::Entity::find_active()
         .filter(assignment::Column::ModuleId.is_in(module_ids.clone()))
         .all(db)
         .await
@@ -228,12 +229,36 @@ pub async fn get_my_tickets(
         let pattern = format!("%{}%", q.to_lowercase());
         condition = condition.add(
             Condition::any()
-                .add(Expr::cust("LOWER(tickets.title)").like(&pattern))
-                .add(Expr::cust("LOWER(module.code)").like(&pattern))
-                .add(Expr::cust("LOWER(assignment.name)").like(&pattern)),
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        tickets::Entity,
+                        tickets::Column::Title,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        module::Entity,
+                        module::Column::Code,
+                    ))))
+                    .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col((
+                        assignment::Entity,
+                        assignment::Column::Name,
+                    ))))
+                    .like(&pattern),
+                ),
         );
         if requested_role != "student" {
-            condition = condition.add(Expr::cust("LOWER(user.username)").like(&pattern));
+            condition = condition.add(
+                Expr::expr(Func::lower(Expr::col((
+                    user::Entity,
+                    user::Column::Username,
+                ))))
+                .like(&pattern),
+            );
         }
     }
 
@@ -250,7 +275,7 @@ pub async fn get_my_tickets(
         }
     }
 
-    let mut query = tickets::Entity::find()
+    let mut query = tickets::Entity::find_active()
         .join(JoinType::InnerJoin, tickets::Relation::Assignment.def())
         .join(JoinType::InnerJoin, assignment::Relation::Module.def())
         .filter(condition);
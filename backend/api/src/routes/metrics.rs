@@ -0,0 +1,48 @@
+//! Prometheus metrics route.
+//!
+//! Provides `GET /metrics`, which returns the API's metrics in the Prometheus text
+//! exposition format for scraping.
+
+use crate::services::metrics;
+use axum::{
+    Router,
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use util::{config, state::AppState};
+
+/// Builds the `/metrics` route group.
+///
+/// # Returns
+/// An Axum `Router` with the `GET /metrics` route configured.
+pub fn metrics_routes() -> Router<AppState> {
+    Router::new().route("/", get(get_metrics))
+}
+
+/// GET /metrics
+///
+/// Returns the current WebSocket connection count and marking duration histograms in the
+/// Prometheus text format. When `METRICS_TOKEN` is set, the request must carry
+/// `Authorization: Bearer <METRICS_TOKEN>`.
+///
+/// ### Responses
+/// - `200 OK` with `Content-Type: text/plain; version=0.0.4`
+/// - `401 Unauthorized` if `METRICS_TOKEN` is set and the header is missing or wrong
+async fn get_metrics(headers: HeaderMap) -> Response {
+    if let Some(expected) = config::metrics_token() {
+        let provided = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        if provided != Some(expected.as_str()) {
+            return (StatusCode::UNAUTHORIZED, "Missing or invalid metrics token").into_response();
+        }
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::render(),
+    )
+        .into_response()
+}
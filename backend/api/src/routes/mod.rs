@@ -6,28 +6,52 @@
 //!
 //! Route groups include:
 //! - `/health` → Health check endpoint (public)
+//! - `/metrics` → Prometheus metrics (public unless `METRICS_TOKEN` is set)
 //! - `/auth` → Authentication endpoints (login, token handling, public)
+//! - `/lti` → LTI 1.3 login, launch and key set (public; launches are signed by the LMS)
 //! - `/users` → User management endpoints (admin-only)
 //! - `/modules` → Module management, personnel, and assignments (authenticated users)
 //! - `/me` → User-specific endpoints (announcements, tickets, assignments)
+//! - `/plagiarism/match-pages` → Archived MOSS match pages (authenticated by a signed link)
+//! - `/uploads` → Resumable chunked uploads of large files (authenticated users)
+//!
+//! Besides login JWTs, every group accepts API tokens (`Bearer ffk_...`) for scripts and CI; see
+//! [`authenticate_api_token`].
+//!
+//! When `RATE_LIMIT_ENABLED` (production by default), every group is rate limited per user or
+//! IP, more strictly for logins and new submissions; see [`rate_limit`]. API token lookups are
+//! also limited per IP, ahead of the database; see [`rate_limit_api_token_lookups`].
 
 use crate::auth::guards::{allow_admin, allow_authenticated};
+use crate::auth::middleware::{
+    RateLimits, authenticate_api_token, rate_limit, rate_limit_api_token_lookups,
+};
 use crate::routes::auth::get::get_avatar;
-use crate::routes::me::me_routes;
+use crate::routes::me::{calendar::get_calendar_ics, me_routes};
+use crate::routes::modules::assignments::plagiarism::get::get_signed_moss_match_page;
 use crate::routes::{
-    auth::auth_routes, health::health_routes, modules::modules_routes, system::system_routes,
-    test::test_routes, users::users_routes,
+    auth::auth_routes, health::health_routes, lti::lti_routes, metrics::metrics_routes,
+    modules::modules_routes, system::system_routes, test::test_routes, uploads::uploads_routes,
+    users::users_routes,
 };
-use axum::{Router, middleware::from_fn, routing::get};
+use axum::{
+    Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+};
+use std::sync::Arc;
 use util::{config, state::AppState};
 
 pub mod auth;
 pub mod common;
 pub mod health;
+pub mod lti;
 pub mod me;
+pub mod metrics;
 pub mod modules;
 pub mod system;
 pub mod test;
+pub mod uploads;
 pub mod users;
 
 /// Builds the complete application router for all HTTP endpoints.
@@ -37,13 +61,20 @@ pub mod users;
 ///
 /// # Route Structure:
 /// - `/health` → Health check endpoint (no authentication required).
+/// - `/metrics` → Prometheus metrics (bearer token only if `METRICS_TOKEN` is set).
 /// - `/auth` → Authentication endpoints (login, refresh, etc.).
+/// - `/lti` → LTI 1.3 tool endpoints for LMS launches (no authentication required).
 /// - `/users` → User management (restricted to admins via `require_admin` middleware).
 /// - `/users/{user_id}/avatar` → Publicly accessible avatar retrieval.
 /// - `/modules` → Module CRUD, personnel management, and assignments (requires authentication).
 /// - `/me` → User-specific endpoints (announcements, tickets, assignments, etc.)
+/// - `/me/calendar.ics` → The user's iCalendar feed (authenticated by its feed token).
+/// - `/plagiarism/match-pages/{token}/{page}` → An archived MOSS match page (authenticated by its signed link).
+/// - `/uploads` → Chunked uploads, sent in parts and resumable (requires authentication).
 /// - `/test` → Development/test-only routes (mounted only if `env != production`).
 ///
+/// All but `/test` are rate limited when `RATE_LIMIT_ENABLED` is on.
+///
 /// The `/test` route group is mounted **here** instead of in `main` to:
 /// 1. Keep `main` focused on server startup logic only.
 /// 2. Avoid changing the `Router` type after construction, which can cause trait bound issues.
@@ -51,7 +82,9 @@ pub mod users;
 pub fn routes(app_state: AppState) -> Router<AppState> {
     let mut router: Router<AppState> = Router::new()
         .nest("/health", health_routes())
+        .nest("/metrics", metrics_routes())
         .nest("/auth", auth_routes())
+        .nest("/lti", lti_routes())
         .nest("/users", users_routes().route_layer(from_fn(allow_admin)))
         .route("/users/{user_id}/avatar", get(get_avatar))
         .nest(
@@ -59,8 +92,31 @@ pub fn routes(app_state: AppState) -> Router<AppState> {
             modules_routes(app_state.clone()).route_layer(from_fn(allow_authenticated)),
         )
         .nest("/me", me_routes().route_layer(from_fn(allow_authenticated)))
-        .nest("/system", system_routes())
-        .with_state(app_state.clone());
+        .route("/me/calendar.ics", get(get_calendar_ics))
+        .route(
+            "/plagiarism/match-pages/{token}/{page}",
+            get(get_signed_moss_match_page),
+        )
+        .nest(
+            "/uploads",
+            uploads_routes().route_layer(from_fn(allow_authenticated)),
+        )
+        .nest("/system", system_routes());
+
+    // Added before the API token layer so that it runs after it, keying token callers by user
+    let limits = config::rate_limit_enabled().then(|| Arc::new(RateLimits::from_config()));
+    if let Some(limits) = &limits {
+        router = router.layer(from_fn_with_state(limits.clone(), rate_limit));
+    }
+    router = router.layer(from_fn_with_state(
+        app_state.clone(),
+        authenticate_api_token,
+    ));
+    // ...and this one after it, so token lookups are throttled by IP first
+    if let Some(limits) = limits {
+        router = router.layer(from_fn_with_state(limits, rate_limit_api_token_lookups));
+    }
+    router = router.with_state(app_state.clone());
 
     // Conditionally mount the `/test` route group if *not* in production.
     //
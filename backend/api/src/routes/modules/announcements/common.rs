@@ -3,6 +3,9 @@
 //! Represents the payload for creating or updating an announcement.
 //! Used in POST and PUT requests under the `/announcements` route group.
 
+use crate::auth::{Claims, guards::user_has_any_role};
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -10,4 +13,39 @@ pub struct AnnouncementRequest {
     pub title: String,
     pub body: String,
     pub pinned: bool,
+    /// When the announcement goes live; omitted or past publishes it straight away.
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the announcement stops being shown to students.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Checks that an announcement going live at `publish_at` (straight away if `None` or past)
+/// would not expire before it does.
+pub fn validate_schedule(
+    publish_at: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(), &'static str> {
+    let goes_live = publish_at.map_or(now, |at| at.max(now));
+    match expires_at {
+        Some(expires_at) if expires_at <= goes_live => {
+            Err("expires_at must be after the announcement is published")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether the caller may see announcements that are scheduled or expired: module staff
+/// (tutors and up) and admins.
+pub async fn sees_unpublished(db: &DatabaseConnection, claims: &Claims, module_id: i64) -> bool {
+    claims.admin
+        || user_has_any_role(
+            db,
+            claims.sub,
+            module_id,
+            &["Lecturer", "AssistantLecturer", "Tutor"],
+        )
+        .await
 }
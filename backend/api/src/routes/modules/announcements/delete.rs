@@ -12,11 +12,12 @@ use axum::{
     response::IntoResponse,
 };
 use db::models::announcements::Model as AnnouncementModel;
+use sea_orm::DbErr;
 use util::state::AppState;
 
 /// DELETE /api/modules/{module_id}/announcements/{announcement_id}
 ///
-/// Deletes a single announcement by ID under the given module.
+/// Soft deletes a single announcement by ID under the given module.
 ///
 /// # AuthZ / AuthN
 /// - Requires a valid `Bearer` token (JWT).
@@ -28,9 +29,11 @@ use util::state::AppState;
 /// - `announcement_id` — ID of the announcement to delete.
 ///
 /// # Behavior
-/// - Deletion is **idempotent**: attempting to delete a non-existent announcement
-///   will still return `200 OK` in this implementation (the driver returns
-///   `rows_affected = 0`, which we do not currently treat as an error).
+/// - The announcement is hidden, not removed. It can be restored with
+///   `POST /api/modules/{module_id}/announcements/{announcement_id}/restore` until the
+///   retention sweep purges it.
+/// - Unknown or already deleted announcements are rejected with `404 Not Found` by the
+///   path guard.
 ///
 /// # Example cURL
 /// ```bash
@@ -39,9 +42,10 @@ use util::state::AppState;
 /// ```
 ///
 /// # Responses
-/// - `200 OK` — Always returned on successful DB call, even if the record didn’t exist.
+/// - `200 OK` — Announcement deleted.
 /// - `401 UNAUTHORIZED` — Missing/invalid token.
 /// - `403 FORBIDDEN` — Authenticated but not lecturer/assistant on this module.
+/// - `404 NOT FOUND` — No live announcement with this ID in the module.
 /// - `500 INTERNAL SERVER ERROR` — Database error.
 ///
 /// ## 200 OK — Example
@@ -70,10 +74,10 @@ use util::state::AppState;
 /// ```
 pub async fn delete_announcement(
     State(app_state): State<AppState>,
-    Path((_, announcement_id)): Path<(i64, i64)>,
+    Path((module_id, announcement_id)): Path<(i64, i64)>,
 ) -> impl IntoResponse {
     let db = app_state.db();
-    match AnnouncementModel::delete(db, announcement_id).await {
+    match AnnouncementModel::delete(db, module_id, announcement_id).await {
         Ok(_) => (
             StatusCode::OK,
             Json(ApiResponse::success(
@@ -81,6 +85,7 @@ pub async fn delete_announcement(
                 "Announcement deleted successfully",
             )),
         ),
+        Err(DbErr::RecordNotFound(msg)) => (StatusCode::NOT_FOUND, Json(ApiResponse::error(msg))),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ApiResponse::error(format!(
@@ -2,18 +2,24 @@
 //!
 //! Provides an endpoint to retrieve a paginated list of announcements for a specific module.
 //!
-//! Supports filtering by search query, pinned status, and sorting by various fields.
+//! Supports filtering by search query, pinned status, and sorting by various fields. Scheduled
+//! and expired announcements are only shown to module staff.
 
-use crate::response::ApiResponse;
+use crate::{
+    auth::AuthUser, response::ApiResponse, routes::modules::announcements::common::sees_unpublished,
+};
 use axum::{
+    Extension,
     extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Json},
 };
+use chrono::Utc;
 use db::models::announcements::{
     Column as AnnouncementColumn, Entity as AnnouncementEntity, Model as AnnouncementModel,
 };
 use db::models::user::Entity as UserEntity;
+use db::soft_delete::SoftDelete;
 use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
 use serde::{Deserialize, Serialize};
 use util::state::AppState;
@@ -66,6 +72,9 @@ impl FilterResponse {
 /// This ensures pinned announcements always appear at the top, with the newest first.
 /// If the user explicitly includes `pinned` in the `sort` parameter, the default is overridden.
 ///
+/// Students only see announcements that are live: past their `publish_at` and not yet at their
+/// `expires_at`. Staff (tutors and up) and admins see scheduled and expired ones too.
+///
 /// # Path Parameters
 ///
 /// - `module_id`: The ID of the module to retrieve announcements for.
@@ -146,6 +155,7 @@ impl FilterResponse {
 pub async fn get_announcements(
     Path(module_id): Path<i64>,
     State(app_state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
     Query(params): Query<FilterReq>,
 ) -> impl IntoResponse {
     let db = app_state.db();
@@ -169,6 +179,10 @@ pub async fn get_announcements(
 
     let mut condition = Condition::all().add(AnnouncementColumn::ModuleId.eq(module_id));
 
+    if !sees_unpublished(db, &claims, module_id).await {
+        condition = condition.add(AnnouncementModel::visible_at(Utc::now()));
+    }
+
     if let Some(ref query) = params.query {
         let pattern = format!("%{}%", query.to_lowercase());
         condition = condition.add(
@@ -193,7 +207,7 @@ pub async fn get_announcements(
         }
     }
 
-    let mut query = AnnouncementEntity::find().filter(condition);
+    let mut query = AnnouncementEntity::find_active().filter(condition);
 
     let mut applied_pinned_sort = false;
 
@@ -299,6 +313,7 @@ pub async fn get_announcements(
 /// # Behavior
 ///
 /// - Verifies the announcement belongs to the given `module_id`.
+/// - Scheduled and expired announcements are `404 NOT FOUND` for students.
 /// - Eager-loads the related user (author) via the `belongs_to User` relation.
 /// - Returns `404 NOT FOUND` if no matching announcement is found.
 ///
@@ -344,6 +359,7 @@ pub async fn get_announcements(
 pub async fn get_announcement(
     State(app_state): State<AppState>,
     Path((module_id, announcement_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
 ) -> impl IntoResponse {
     let db = app_state.db();
 
@@ -353,6 +369,16 @@ pub async fn get_announcement(
         .one(db)
         .await;
 
+    let result = match result {
+        Ok(Some((announcement, _)))
+            if !announcement.is_visible(Utc::now())
+                && !sees_unpublished(db, &claims, module_id).await =>
+        {
+            Ok(None)
+        }
+        other => other,
+    };
+
     match result {
         Ok(Some((announcement, Some(user)))) => {
             let thin = MinimalUser {
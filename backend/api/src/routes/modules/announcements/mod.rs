@@ -3,7 +3,7 @@
 //! Defines and wires up routes for the `/api/modules/{module_id}/announcements` endpoint group.
 //!
 //! ## Structure
-//! - `post.rs` — POST handlers (e.g., create announcement)
+//! - `post.rs` — POST handlers (e.g., create or restore announcement)
 //! - `get.rs` — GET handlers (e.g., list announcements)
 //! - `put.rs` — PUT handlers (e.g., edit announcement)
 //! - `delete.rs` — DELETE handlers (e.g., remove announcement)
@@ -18,7 +18,7 @@ use axum::routing::{delete, get, post, put};
 use axum::{Router, middleware::from_fn_with_state};
 use delete::delete_announcement;
 use get::{get_announcement, get_announcements};
-use post::create_announcement;
+use post::{create_announcement, restore_announcement};
 use put::edit_announcement;
 use util::state::AppState;
 
@@ -36,6 +36,7 @@ pub mod put;
 /// - GET `/{announcement_id}`  → get single announcement (with author id & username)
 /// - PUT `/{announcement_id}`  → edit announcement (lecturer or assistant lecturer only)
 /// - DELETE `/{announcement_id}` → delete announcement (lecturer or assistant lecturer only)
+/// - POST `/{announcement_id}/restore` → restore a deleted announcement (lecturer or assistant lecturer only)
 pub fn announcement_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
         .route(
@@ -52,6 +53,13 @@ pub fn announcement_routes(app_state: AppState) -> Router<AppState> {
                 allow_assistant_lecturer,
             )),
         )
+        .route(
+            "/{announcement_id}/restore",
+            post(restore_announcement).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
         .route(
             "/{announcement_id}",
             put(edit_announcement).route_layer(from_fn_with_state(
@@ -1,12 +1,15 @@
-//! Create announcement handler.
+//! Create and restore announcement handlers.
 //!
-//! Provides an endpoint to create a new announcement for a specific module.
+//! Provides endpoints to create a new announcement for a specific module and to bring back a
+//! deleted one. Module members are notified of new announcements once they are published.
 //!
 //! **Permissions:** Only authorized users (lecturer/assistant) can create announcements.
 
 use crate::{
-    auth::AuthUser, response::ApiResponse,
-    routes::modules::announcements::common::AnnouncementRequest,
+    auth::AuthUser,
+    response::ApiResponse,
+    routes::modules::announcements::common::{AnnouncementRequest, validate_schedule},
+    services::announcement_publisher,
 };
 use axum::{
     Extension, Json,
@@ -14,13 +17,20 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use chrono::Utc;
 use db::models::announcements::Model as AnnouncementModel;
+use sea_orm::DbErr;
 use util::state::AppState;
 
 /// POST /api/modules/{module_id}/announcements
 ///
 /// Creates a new announcement for the specified module.
 ///
+/// Once the announcement is published, every other member of the module gets an
+/// `announcement_posted` notification and it is pushed to the `module:{module_id}.announcements`
+/// topic. Without a future `publish_at` that happens straight away; otherwise the announcement
+/// stays visible to staff only until the scheduler publishes it.
+///
 /// # AuthZ / AuthN
 /// - Requires a valid `Bearer` token (JWT).
 /// - Caller must be **lecturer** or **assistant_lecturer** on the target module
@@ -35,9 +45,12 @@ use util::state::AppState;
 /// {
 ///   "title": "Exam Schedule",
 ///   "body": "The exam will be held next **Friday** at 09:00.",
-///   "pinned": true
+///   "pinned": true,
+///   "publish_at": "2025-02-12T08:00:00Z",
+///   "expires_at": "2025-02-20T08:00:00Z"
 /// }
 /// ```
+/// `publish_at` and `expires_at` are optional.
 ///
 /// # Example cURL
 /// ```bash
@@ -53,7 +66,7 @@ use util::state::AppState;
 ///
 /// # Responses
 /// - `200 OK` — Announcement created successfully. Returns the created record.
-/// - `400 BAD REQUEST` — Malformed JSON.
+/// - `400 BAD REQUEST` — Malformed JSON, or `expires_at` is not after the publish time.
 /// - `401 UNAUTHORIZED` — Missing/invalid token.
 /// - `403 FORBIDDEN` — Authenticated but not lecturer/assistant on this module.
 /// - `422 UNPROCESSABLE ENTITY` — JSON is valid but required fields missing/invalid.
@@ -71,7 +84,10 @@ use util::state::AppState;
 ///     "body": "The exam will be held next **Friday** at 09:00.",
 ///     "pinned": true,
 ///     "created_at": "2025-02-10T12:34:56Z",
-///     "updated_at": "2025-02-10T12:34:56Z"
+///     "updated_at": "2025-02-10T12:34:56Z",
+///     "publish_at": "2025-02-12T08:00:00Z",
+///     "expires_at": "2025-02-20T08:00:00Z",
+///     "published_at": null
 ///   },
 ///   "message": "Announcement created successfully"
 /// }
@@ -117,19 +133,83 @@ pub async fn create_announcement(
     let db = app_state.db();
     let user_id = claims.sub;
 
-    match AnnouncementModel::create(db, module_id, user_id, &req.title, &req.body, req.pinned).await
+    if let Err(msg) = validate_schedule(req.publish_at, req.expires_at, Utc::now()) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg)));
+    }
+
+    match AnnouncementModel::create_scheduled(
+        db,
+        module_id,
+        user_id,
+        &req.title,
+        &req.body,
+        req.pinned,
+        req.publish_at,
+        req.expires_at,
+    )
+    .await
     {
-        Ok(announcement) => (
+        Ok(announcement) => {
+            if announcement.published_at.is_some() {
+                announcement_publisher::publish(&app_state, &announcement).await;
+            }
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    announcement,
+                    "Announcement created successfully",
+                )),
+            )
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!(
+                "Failed to create announcement: {}",
+                err
+            ))),
+        ),
+    }
+}
+
+/// POST /api/modules/{module_id}/announcements/{announcement_id}/restore
+///
+/// Restores a soft-deleted announcement. Only possible until the retention sweep purges it
+/// (`SOFT_DELETE_RETENTION_DAYS`).
+///
+/// # AuthZ / AuthN
+/// - Caller must be **lecturer** or **assistant_lecturer** on the module.
+///
+/// # Responses
+/// - `200 OK` — Announcement restored.
+/// - `404 NOT FOUND` — No deleted announcement with this ID in the module.
+/// - `500 INTERNAL SERVER ERROR` — Database error.
+///
+/// ## 200 OK — Example
+/// ```json
+/// {
+///   "success": true,
+///   "data": null,
+///   "message": "Announcement restored successfully"
+/// }
+/// ```
+pub async fn restore_announcement(
+    State(app_state): State<AppState>,
+    Path((module_id, announcement_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    match AnnouncementModel::restore(db, module_id, announcement_id).await {
+        Ok(()) => (
             StatusCode::OK,
             Json(ApiResponse::success(
-                announcement,
-                "Announcement created successfully",
+                (),
+                "Announcement restored successfully",
             )),
         ),
+        Err(DbErr::RecordNotFound(msg)) => (StatusCode::NOT_FOUND, Json(ApiResponse::error(msg))),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ApiResponse::error(format!(
-                "Failed to create announcement: {}",
+                "Failed to restore announcement: {}",
                 err
             ))),
         ),
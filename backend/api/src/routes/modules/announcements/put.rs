@@ -4,14 +4,23 @@
 //!
 //! **Permissions:** Only authorized users (lecturer/assistant) can edit announcements.
 
-use crate::{response::ApiResponse, routes::modules::announcements::common::AnnouncementRequest};
+use crate::{
+    response::ApiResponse,
+    routes::modules::announcements::common::{AnnouncementRequest, validate_schedule},
+    services::announcement_publisher,
+};
 use axum::{
     Json,
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
 };
-use db::models::announcements::Model as AnnouncementModel;
+use chrono::Utc;
+use db::models::announcements::{
+    Column as AnnouncementColumn, Entity as AnnouncementEntity, Model as AnnouncementModel,
+};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use util::state::AppState;
 
 /// PUT /api/modules/{module_id}/announcements/{announcement_id}
@@ -33,7 +42,9 @@ use util::state::AppState;
 /// {
 ///   "title": "New title (or empty string to keep existing)",
 ///   "body": "Updated body (or empty string to keep existing)",
-///   "pinned": true
+///   "pinned": true,
+///   "publish_at": "2025-08-18T08:00:00Z",
+///   "expires_at": "2025-08-25T08:00:00Z"
 /// }
 /// ```
 ///
@@ -41,6 +52,9 @@ use util::state::AppState;
 /// - `title`: if empty string `""`, the existing title is kept.
 /// - `body`: if empty string `""`, the existing body is kept.
 /// - `pinned`: always updated to the provided boolean.
+/// - `publish_at`, `expires_at`: optional; the existing values are kept when omitted.
+///   `publish_at` can only be changed while the announcement is still scheduled, and moving it
+///   to the past publishes the announcement straight away.
 ///
 /// # Example cURL
 /// ```bash
@@ -52,8 +66,11 @@ use util::state::AppState;
 ///
 /// # Responses
 /// - `200 OK` — Returns the updated announcement.
+/// - `400 BAD REQUEST` — `publish_at` given for a published announcement, or `expires_at` is not
+///   after the publish time.
 /// - `401 UNAUTHORIZED` — Missing/invalid token.
 /// - `403 FORBIDDEN` — Authenticated but not lecturer/assistant on this module.
+/// - `404 NOT FOUND` — No such announcement in the module.
 /// - `422 UNPROCESSABLE ENTITY` — Malformed/invalid JSON for `AnnouncementRequest`.
 /// - `500 INTERNAL SERVER ERROR` — Database error.
 ///
@@ -69,7 +86,10 @@ use util::state::AppState;
 ///     "body": "**Hall A** instead of Hall B.",
 ///     "pinned": false,
 ///     "created_at": "2025-08-16T12:00:00Z",
-///     "updated_at": "2025-08-16T12:30:00Z"
+///     "updated_at": "2025-08-16T12:30:00Z",
+///     "publish_at": "2025-08-18T08:00:00Z",
+///     "expires_at": "2025-08-25T08:00:00Z",
+///     "published_at": null
 ///   },
 ///   "message": "Announcement updated successfully"
 /// }
@@ -92,22 +112,94 @@ use util::state::AppState;
 /// ```
 pub async fn edit_announcement(
     State(app_state): State<AppState>,
-    Path((_, announcement_id)): Path<(i64, i64)>,
+    Path((module_id, announcement_id)): Path<(i64, i64)>,
     Json(req): Json<AnnouncementRequest>,
 ) -> impl IntoResponse {
     let db = app_state.db();
+    let now = Utc::now();
 
-    match AnnouncementModel::update(db, announcement_id, &req.title, &req.body, req.pinned).await {
-        Ok(updated_announcement) => (
-            StatusCode::OK,
-            Json(ApiResponse::success(
-                updated_announcement,
-                "Announcement updated successfully",
+    let existing = match AnnouncementEntity::find_active()
+        .filter(AnnouncementColumn::Id.eq(announcement_id))
+        .filter(AnnouncementColumn::ModuleId.eq(module_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(a)) => a,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error("Announcement not found")),
+            );
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("Failed to update announcement")),
+            );
+        }
+    };
+
+    if req.publish_at.is_some() && existing.published_at.is_some() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error(
+                "Announcement has already been published",
             )),
-        ),
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::error("Failed to update announcement")),
-        ),
+        );
+    }
+    if let Err(msg) = validate_schedule(
+        req.publish_at.or(existing.publish_at),
+        req.expires_at.or(existing.expires_at),
+        now,
+    ) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg)));
     }
+
+    let updated = match AnnouncementModel::update(
+        db,
+        announcement_id,
+        &req.title,
+        &req.body,
+        req.pinned,
+        req.publish_at,
+        req.expires_at,
+    )
+    .await
+    {
+        Ok(updated) => updated,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("Failed to update announcement")),
+            );
+        }
+    };
+
+    // Rescheduled into the past: publish now rather than waiting for the next sweep
+    let updated =
+        if updated.published_at.is_none() && updated.publish_at.is_some_and(|at| at <= now) {
+            match AnnouncementModel::mark_published(db, updated.id).await {
+                Ok(true) => {
+                    let published = AnnouncementEntity::find_by_id(updated.id)
+                        .one(db)
+                        .await
+                        .ok()
+                        .flatten()
+                        .unwrap_or(updated);
+                    announcement_publisher::publish(&app_state, &published).await;
+                    published
+                }
+                _ => updated,
+            }
+        } else {
+            updated
+        };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            updated,
+            "Announcement updated successfully",
+        )),
+    )
 }
@@ -0,0 +1,440 @@
+use chrono::{DateTime, Utc};
+use db::models::{
+    assignment::{self, AssignmentType, Model as AssignmentModel},
+    assignment_file::{self, FileType, Model as AssignmentFileModel},
+    assignment_overwrite_file::{self, Model as OverwriteFileModel},
+    assignment_task::{Model as TaskModel, TaskType},
+    content_blob,
+};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use util::{
+    archive::ArchiveLimits,
+    execution_config::ExecutionConfig,
+    mark_allocator::{MarkAllocator, load_allocator, save_allocator},
+};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+/// Bumped whenever the layout changes in a way older importers can't read.
+pub const BUNDLE_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+const CONFIG: &str = "config.json";
+const ALLOCATOR: &str = "allocator.json";
+const TASKS: &str = "tasks.json";
+const FILES_DIR: &str = "files/";
+const OVERWRITE_DIR: &str = "overwrite/";
+
+/// The assignment's own details; dates are kept so a straight copy needs no edits.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub name: String,
+    pub description: Option<String>,
+    pub assignment_type: AssignmentType,
+    pub available_from: DateTime<Utc>,
+    pub due_date: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleTask {
+    pub task_number: i64,
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub task_type: TaskType,
+    #[serde(default)]
+    pub artifact_patterns: Vec<String>,
+}
+
+/// Replaces the bundled name and dates when importing.
+#[derive(Debug, Default)]
+pub struct ImportOverrides {
+    pub name: Option<String>,
+    pub available_from: Option<DateTime<Utc>>,
+    pub due_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    /// The upload isn't a usable bundle; the message says why.
+    Invalid(String),
+    Internal(String),
+}
+
+impl From<DbErr> for ImportError {
+    fn from(e: DbErr) -> Self {
+        ImportError::Internal(e.to_string())
+    }
+}
+
+/// The module's assignment `assignment_id`, unless it has been deleted.
+pub async fn find_assignment(
+    db: &DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+) -> Result<Option<AssignmentModel>, DbErr> {
+    assignment::Entity::find_active()
+        .filter(assignment::Column::Id.eq(assignment_id))
+        .filter(assignment::Column::ModuleId.eq(module_id))
+        .one(db)
+        .await
+}
+
+/// Zips the assignment's setup: details, effective config, mark allocator, tasks, files
+/// (except config and allocator) and overwrite files. Memo outputs are left out; they are
+/// regenerated from the memo once the bundle is imported.
+pub async fn export_bundle(
+    db: &DatabaseConnection,
+    assignment: &AssignmentModel,
+) -> Result<Vec<u8>, String> {
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    let manifest = Manifest {
+        version: BUNDLE_VERSION,
+        name: assignment.name.clone(),
+        description: assignment.description.clone(),
+        assignment_type: assignment.assignment_type.clone(),
+        available_from: assignment.available_from,
+        due_date: assignment.due_date,
+    };
+    entries.push((MANIFEST.into(), to_json(&manifest)?));
+
+    // The effective config, so the bundle doesn't depend on the source module's defaults
+    if let Some(config) = assignment.config() {
+        entries.push((CONFIG.into(), to_json(&config)?));
+    }
+    if let Ok(allocator) = load_allocator(assignment.module_id, assignment.id) {
+        entries.push((ALLOCATOR.into(), to_json(&allocator)?));
+    }
+
+    let mut tasks = TaskModel::get_by_assignment_id(db, assignment.id)
+        .await
+        .map_err(|e| e.to_string())?;
+    tasks.sort_by_key(|t| t.task_number);
+    let bundle_tasks: Vec<BundleTask> = tasks
+        .iter()
+        .map(|t| BundleTask {
+            task_number: t.task_number,
+            name: t.name.clone(),
+            command: t.command.clone(),
+            task_type: t.task_type.clone(),
+            artifact_patterns: t.artifact_patterns(),
+        })
+        .collect();
+    entries.push((TASKS.into(), to_json(&bundle_tasks)?));
+
+    let files = assignment_file::Entity::find()
+        .filter(assignment_file::Column::AssignmentId.eq(assignment.id))
+        .filter(assignment_file::Column::FileType.is_not_in([
+            FileType::Config.to_string(),
+            FileType::MarkAllocator.to_string(),
+        ]))
+        .order_by_asc(assignment_file::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    for file in files {
+        let bytes = file
+            .load_file()
+            .await
+            .map_err(|e| format!("Failed to read {} file: {e}", file.file_type))?;
+        entries.push((
+            format!("{FILES_DIR}{}/{}", file.file_type, file.filename),
+            bytes,
+        ));
+    }
+
+    let task_numbers: HashMap<i64, i64> = tasks.iter().map(|t| (t.id, t.task_number)).collect();
+    let overwrites = assignment_overwrite_file::Entity::find()
+        .filter(assignment_overwrite_file::Column::AssignmentId.eq(assignment.id))
+        .order_by_asc(assignment_overwrite_file::Column::Id)
+        .all(db)
+        .await
+        .map_err(|e| e.to_string())?;
+    for file in overwrites {
+        let Some(task_number) = task_numbers.get(&file.task_id) else {
+            continue;
+        };
+        let bytes = file
+            .load_file()
+            .await
+            .map_err(|e| format!("Failed to read overwrite file: {e}"))?;
+        entries.push((
+            format!("{OVERWRITE_DIR}{task_number}/{}", file.filename),
+            bytes,
+        ));
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in entries {
+        zip.start_file(name, SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+    Ok(zip.finish().map_err(|e| e.to_string())?.into_inner())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+/// A bundle read back from its zip and checked, before anything is written.
+struct Bundle {
+    manifest: Manifest,
+    config: Option<ExecutionConfig>,
+    allocator: Option<MarkAllocator>,
+    tasks: Vec<BundleTask>,
+    files: Vec<(FileType, String, Vec<u8>)>,
+    overwrites: Vec<(i64, String, Vec<u8>)>,
+}
+
+fn invalid(msg: impl Into<String>) -> ImportError {
+    ImportError::Invalid(msg.into())
+}
+
+/// The last component of `path`, if it is a plain file name.
+fn file_name(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .filter(|n| !n.is_empty() && !path.ends_with('/'))
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(
+    entries: &BTreeMap<String, Vec<u8>>,
+    name: &str,
+) -> Result<Option<T>, ImportError> {
+    entries
+        .get(name)
+        .map(|bytes| serde_json::from_slice(bytes).map_err(|e| invalid(format!("{name}: {e}"))))
+        .transpose()
+}
+
+fn read_bundle(bytes: &[u8]) -> Result<Bundle, ImportError> {
+    let limits = ArchiveLimits::default();
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|_| invalid("Bundle is not a zip archive"))?;
+    if archive.len() > limits.max_files {
+        return Err(invalid("Bundle has too many files"));
+    }
+
+    let mut entries = BTreeMap::new();
+    let mut total: u64 = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|_| invalid("Bundle is not a valid zip archive"))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry
+            .enclosed_name()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+        else {
+            return Err(invalid(format!("Unsafe path in bundle: {}", entry.name())));
+        };
+        total += entry.size();
+        if total > limits.max_total_size {
+            return Err(invalid("Bundle is too large"));
+        }
+        let mut buf = Vec::new();
+        entry
+            .read_to_end(&mut buf)
+            .map_err(|_| invalid(format!("Unreadable entry in bundle: {name}")))?;
+        entries.insert(name, buf);
+    }
+
+    let manifest: Manifest =
+        parse_json(&entries, MANIFEST)?.ok_or_else(|| invalid("Bundle has no manifest.json"))?;
+    if manifest.version > BUNDLE_VERSION {
+        return Err(invalid(format!(
+            "Bundle version {} is newer than this server supports ({BUNDLE_VERSION})",
+            manifest.version
+        )));
+    }
+    if manifest.name.trim().is_empty() {
+        return Err(invalid("manifest.json: name is required"));
+    }
+
+    let config = match parse_json::<serde_json::Value>(&entries, CONFIG)? {
+        Some(value) => Some(
+            ExecutionConfig::from_json_checked(&value).map_err(|errors| {
+                let details: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                invalid(format!("config.json: {}", details.join("; ")))
+            })?,
+        ),
+        None => None,
+    };
+    let allocator: Option<MarkAllocator> = parse_json(&entries, ALLOCATOR)?;
+
+    let tasks: Vec<BundleTask> = parse_json(&entries, TASKS)?.unwrap_or_default();
+    let mut numbers = HashSet::new();
+    for t in &tasks {
+        if t.task_number <= 0 || !numbers.insert(t.task_number) {
+            return Err(invalid(format!(
+                "tasks.json: task numbers must be positive and unique (got {})",
+                t.task_number
+            )));
+        }
+        if t.name.trim().is_empty() || t.command.trim().is_empty() {
+            return Err(invalid(format!(
+                "tasks.json: task {} needs a name and a command",
+                t.task_number
+            )));
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut overwrites = Vec::new();
+    for (path, bytes) in entries {
+        if let Some(rest) = path.strip_prefix(FILES_DIR) {
+            let (kind, name) = rest.split_once('/').unwrap_or((rest, ""));
+            let file_type = kind
+                .parse::<FileType>()
+                .ok()
+                .filter(FileType::is_deduplicated)
+                .ok_or_else(|| invalid(format!("Unknown file type in bundle: {kind}")))?;
+            let name = file_name(name).ok_or_else(|| invalid(format!("Bad file path: {path}")))?;
+            if files.iter().any(|(t, _, _)| *t == file_type) {
+                return Err(invalid(format!("Bundle has more than one {kind} file")));
+            }
+            files.push((file_type, name, bytes));
+        } else if let Some(rest) = path.strip_prefix(OVERWRITE_DIR) {
+            let (number, name) = rest.split_once('/').unwrap_or((rest, ""));
+            let task_number = number
+                .parse::<i64>()
+                .ok()
+                .filter(|n| numbers.contains(n))
+                .ok_or_else(|| invalid(format!("Overwrite file for unknown task: {path}")))?;
+            let name = file_name(name).ok_or_else(|| invalid(format!("Bad file path: {path}")))?;
+            overwrites.push((task_number, name, bytes));
+        }
+    }
+
+    Ok(Bundle {
+        manifest,
+        config,
+        allocator,
+        tasks,
+        files,
+        overwrites,
+    })
+}
+
+/// Creates a new assignment in `module_id` from a bundle made by [`export_bundle`].
+///
+/// The assignment starts in `setup`; it becomes ready once its memo output is generated.
+/// Nothing is left behind if the import fails part way.
+pub async fn import_bundle(
+    db: &DatabaseConnection,
+    module_id: i64,
+    bytes: &[u8],
+    overrides: ImportOverrides,
+) -> Result<AssignmentModel, ImportError> {
+    let bundle = read_bundle(bytes)?;
+    let name = overrides
+        .name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| bundle.manifest.name.clone());
+
+    let assignment = AssignmentModel::create(
+        db,
+        module_id,
+        name.trim(),
+        bundle.manifest.description.as_deref(),
+        bundle.manifest.assignment_type.clone(),
+        overrides
+            .available_from
+            .unwrap_or(bundle.manifest.available_from),
+        overrides.due_date.unwrap_or(bundle.manifest.due_date),
+    )
+    .await
+    .map_err(|e| match e {
+        DbErr::Custom(msg) => ImportError::Invalid(msg),
+        e => ImportError::Internal(e.to_string()),
+    })?;
+
+    match populate(db, &assignment, bundle).await {
+        Ok(()) => {
+            let _ = AssignmentModel::try_transition_to_ready(db, module_id, assignment.id).await;
+            Ok(assignment::Entity::find_by_id(assignment.id)
+                .one(db)
+                .await?
+                .unwrap_or(assignment))
+        }
+        Err(e) => {
+            if assignment.purge(db).await.is_ok() {
+                let _ = content_blob::Model::prune(db).await;
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn populate(
+    db: &DatabaseConnection,
+    assignment: &AssignmentModel,
+    bundle: Bundle,
+) -> Result<(), ImportError> {
+    let (module_id, assignment_id) = (assignment.module_id, assignment.id);
+
+    if let Some(config) = bundle.config {
+        // Stored as overrides of the target module's config, like any other assignment config
+        let bytes = config
+            .overrides(module_id)
+            .and_then(|v| serde_json::to_vec_pretty(&v).map_err(|e| e.to_string()))
+            .map_err(ImportError::Internal)?;
+        AssignmentFileModel::save_file(
+            db,
+            assignment_id,
+            module_id,
+            FileType::Config,
+            CONFIG,
+            &bytes,
+        )
+        .await?;
+    }
+
+    let mut task_ids = HashMap::new();
+    for t in &bundle.tasks {
+        let task = TaskModel::create(
+            db,
+            assignment_id,
+            t.task_number,
+            &t.name,
+            &t.command,
+            t.task_type.clone(),
+        )
+        .await?;
+        if !t.artifact_patterns.is_empty() {
+            TaskModel::set_artifact_patterns(db, task.id, &t.artifact_patterns).await?;
+        }
+        task_ids.insert(t.task_number, task.id);
+    }
+
+    for (file_type, filename, bytes) in &bundle.files {
+        AssignmentFileModel::save_file(
+            db,
+            assignment_id,
+            module_id,
+            file_type.clone(),
+            filename,
+            bytes,
+        )
+        .await?;
+    }
+
+    for (task_number, filename, bytes) in &bundle.overwrites {
+        OverwriteFileModel::save_file(db, assignment_id, task_ids[task_number], filename, bytes)
+            .await?;
+    }
+
+    if let Some(allocator) = bundle.allocator {
+        save_allocator(module_id, assignment_id, &allocator).map_err(ImportError::Internal)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use super::common::{export_bundle, find_assignment};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::IntoResponse,
+};
+use util::state::AppState;
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/export
+///
+/// Downloads the assignment as a bundle zip (see the [module docs](super)) that
+/// `POST /assignments/import` accepts in any module. Assistant lecturer or higher.
+///
+/// ### Responses
+/// - `200 OK` — `application/zip` attachment named `assignment_{assignment_id}.zip`
+/// - `404 Not Found` — No such assignment in the module
+/// - `500 Internal Server Error` — A stored file could not be read
+pub async fn export_assignment(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let assignment = match find_assignment(db, module_id, assignment_id).await {
+        Ok(Some(a)) => a,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Assignment not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve assignment")),
+            )
+                .into_response();
+        }
+    };
+
+    let bytes = match export_bundle(db, &assignment).await {
+        Ok(b) => b,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to export assignment: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/zip"),
+    );
+    if let Ok(v) = HeaderValue::from_str(&format!(
+        "attachment; filename=\"assignment_{assignment_id}.zip\""
+    )) {
+        headers.insert(header::CONTENT_DISPOSITION, v);
+    }
+    (headers, Body::from(bytes)).into_response()
+}
@@ -0,0 +1,21 @@
+//! Assignment bundles: one zip holding everything needed to set an assignment up again.
+//!
+//! Used to copy an assignment into another module (e.g. next semester's) without re-uploading
+//! its archives and redoing its configuration:
+//! - `GET  /api/modules/{module_id}/assignments/{assignment_id}/export` → download a bundle
+//! - `POST /api/modules/{module_id}/assignments/import`                 → create an assignment from one
+//! - `POST /api/modules/{module_id}/assignments/{assignment_id}/clone`  → export and import in one call
+//!
+//! Bundle layout:
+//! ```text
+//! manifest.json                   name, description, type, dates, bundle version
+//! config.json                     effective execution config
+//! allocator.json                  mark allocator (if any)
+//! tasks.json                      tasks with their commands and artifact patterns
+//! files/<file_type>/<filename>    spec, main, memo and makefile archives
+//! overwrite/<task_number>/<file>  overwrite files per task
+//! ```
+
+pub mod common;
+pub mod get;
+pub mod post;
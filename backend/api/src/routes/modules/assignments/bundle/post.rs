@@ -0,0 +1,270 @@
+use super::common::{ImportError, ImportOverrides, export_bundle, find_assignment, import_bundle};
+use crate::{
+    auth::AuthUser, response::ApiResponse, routes::modules::assignments::common::AssignmentResponse,
+};
+use axum::{
+    Extension, Json,
+    extract::{Multipart, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use db::models::{module, user};
+use sea_orm::{DatabaseConnection, EntityTrait};
+use serde::Deserialize;
+use util::state::AppState;
+
+/// Parses an optional RFC 3339 date, naming `field` in the error.
+fn parse_date(value: Option<&str>, field: &str) -> Result<Option<DateTime<Utc>>, String> {
+    value
+        .filter(|v| !v.trim().is_empty())
+        .map(|v| {
+            DateTime::parse_from_rfc3339(v.trim())
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid {field} datetime"))
+        })
+        .transpose()
+}
+
+fn import_response(
+    result: Result<db::models::assignment::Model, ImportError>,
+    message: &str,
+) -> Response {
+    match result {
+        Ok(assignment) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                AssignmentResponse::from(assignment),
+                message,
+            )),
+        )
+            .into_response(),
+        Err(ImportError::Invalid(msg)) => {
+            (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(msg))).into_response()
+        }
+        Err(ImportError::Internal(msg)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to import assignment: {msg}"
+            ))),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/modules/{module_id}/assignments/import
+///
+/// Creates an assignment in the module from a bundle made by `GET /assignments/{id}/export`:
+/// its config (stored as overrides of this module's config), mark allocator, tasks, files and
+/// overwrite files. The new assignment starts in `setup`; generate its memo output to make it
+/// ready. Assistant lecturer or higher.
+///
+/// ### Request Body (Multipart Form Data)
+/// - `file` (required): The bundle zip
+/// - `name` (optional): Replaces the bundled name
+/// - `available_from`, `due_date` (optional, RFC 3339): Replace the bundled dates
+///
+/// ### Responses
+/// - `201 Created` — The new assignment (same shape as `POST /assignments`)
+/// - `400 Bad Request` — No file, an invalid date, or a file that isn't a valid bundle (the
+///   message says what is wrong with it)
+pub async fn import_assignment(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut file: Option<Vec<u8>> = None;
+    let mut name: Option<String> = None;
+    let mut available_from: Option<String> = None;
+    let mut due_date: Option<String> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(f)) => f,
+            Ok(None) => break,
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Malformed multipart payload")),
+                )
+                    .into_response();
+            }
+        };
+        let field_name = field.name().unwrap_or("").to_owned();
+        let result = match field_name.as_str() {
+            "file" => field.bytes().await.map(|b| file = Some(b.to_vec())),
+            "name" => field.text().await.map(|t| name = Some(t)),
+            "available_from" => field.text().await.map(|t| available_from = Some(t)),
+            "due_date" => field.text().await.map(|t| due_date = Some(t)),
+            _ => Ok(()),
+        };
+        if result.is_err() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!(
+                    "Unreadable field: {field_name}"
+                ))),
+            )
+                .into_response();
+        }
+    }
+
+    let Some(file) = file else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Missing file upload")),
+        )
+            .into_response();
+    };
+    let overrides = match (
+        parse_date(available_from.as_deref(), "available_from"),
+        parse_date(due_date.as_deref(), "due_date"),
+    ) {
+        (Ok(available_from), Ok(due_date)) => ImportOverrides {
+            name,
+            available_from,
+            due_date,
+        },
+        (Err(e), _) | (_, Err(e)) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(e))).into_response();
+        }
+    };
+
+    import_response(
+        import_bundle(app_state.db(), module_id, &file, overrides).await,
+        "Assignment imported successfully",
+    )
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CloneRequest {
+    /// Module to create the copy in; defaults to the assignment's own module
+    pub module_id: Option<i64>,
+    pub name: Option<String>,
+    pub available_from: Option<String>,
+    pub due_date: Option<String>,
+}
+
+/// Whether the user may set up assignments in `module_id`.
+async fn can_manage_module(db: &DatabaseConnection, user_id: i64, module_id: i64) -> bool {
+    for role in ["Lecturer", "AssistantLecturer"] {
+        if user::Model::is_in_role(db, user_id, module_id, role)
+            .await
+            .unwrap_or(false)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/clone
+///
+/// Copies the assignment's setup (as in `GET /export`) into a new assignment, in this module or
+/// another one. Needs assistant lecturer or higher here, and in the target module too.
+/// Submissions, grades and memo output are not copied.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "module_id": 12,
+///   "name": "Assignment 1",
+///   "available_from": "2026-02-01T00:00:00Z",
+///   "due_date": "2026-02-28T23:59:59Z"
+/// }
+/// ```
+/// All fields are optional; the name and dates default to the original's.
+///
+/// ### Responses
+/// - `201 Created` — The new assignment (same shape as `POST /assignments`)
+/// - `400 Bad Request` — An invalid date, or `due_date` before `available_from`
+/// - `403 Forbidden` — Not assistant lecturer or higher in the target module
+/// - `404 Not Found` — No such assignment, or no such target module
+pub async fn clone_assignment(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<CloneRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let assignment = match find_assignment(db, module_id, assignment_id).await {
+        Ok(Some(a)) => a,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Assignment not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve assignment")),
+            )
+                .into_response();
+        }
+    };
+
+    let target = req.module_id.unwrap_or(module_id);
+    if target != module_id {
+        match module::Entity::find_by_id(target).one(db).await {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiResponse::<()>::error("Target module not found")),
+                )
+                    .into_response();
+            }
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error("Failed to retrieve module")),
+                )
+                    .into_response();
+            }
+        }
+        if !claims.admin && !can_manage_module(db, claims.sub, target).await {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error(
+                    "Lecturer or assistant lecturer access required for the target module",
+                )),
+            )
+                .into_response();
+        }
+    }
+
+    let overrides = match (
+        parse_date(req.available_from.as_deref(), "available_from"),
+        parse_date(req.due_date.as_deref(), "due_date"),
+    ) {
+        (Ok(available_from), Ok(due_date)) => ImportOverrides {
+            name: req.name,
+            available_from,
+            due_date,
+        },
+        (Err(e), _) | (_, Err(e)) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(e))).into_response();
+        }
+    };
+
+    let bundle = match export_bundle(db, &assignment).await {
+        Ok(b) => b,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to export assignment: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
+
+    import_response(
+        import_bundle(db, target, &bundle, overrides).await,
+        "Assignment cloned successfully",
+    )
+}
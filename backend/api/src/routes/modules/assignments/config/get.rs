@@ -43,12 +43,13 @@ use util::{execution_config::ExecutionConfig, state::AppState};
 ///       "max_memory": 8589934592,
 ///       "max_cpus": 2,
 ///       "max_uncompressed_size": 100000000,
-///       "max_processes": 256
+///       "max_processes": 256,
+///       "max_disk_bytes": 1073741824
 ///     },
 ///     "marking": {
 ///       "marking_scheme": "exact",
 ///       "feedback_scheme": "auto",
-///       "deliminator": "###"
+///       "delimiter": "###"
 ///     }
 ///   }
 /// }
@@ -79,8 +80,8 @@ pub async fn get_assignment_config(
 
     // Verify the assignment exists
     match AssignmentEntity::find()
-        .filter(AssignmentColumn::Id.eq(assignment_id as i32))
-        .filter(AssignmentColumn::ModuleId.eq(module_id as i32))
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
         .await
     {
@@ -173,7 +174,8 @@ pub async fn get_assignment_config(
 
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/config/default
 ///
-/// Returns the default execution configuration used when no custom config file is present.
+/// Returns the default execution configuration used when no custom config file is present:
+/// the module config layered over the system defaults if the module has one.
 /// This helps clients pre-fill configuration forms or understand system defaults.
 ///
 /// ### Success Response (200 OK)
@@ -193,16 +195,17 @@ pub async fn get_assignment_config(
 //   "marking": {
 //     "marking_scheme": "exact",
 //     "feedback_scheme": "auto",
-//     "deliminator": "###"
+//     "delimiter": "###"
 //   }
 // }
 
 /// }
 /// ```
 pub async fn get_default_assignment_config(
-    Path((_module_id, _assignment_id)): Path<(i64, i64)>,
+    Path((module_id, _assignment_id)): Path<(i64, i64)>,
 ) -> impl IntoResponse {
-    let default_config = ExecutionConfig::default_config();
+    let default_config = ExecutionConfig::module_defaults(module_id)
+        .unwrap_or_else(|_| ExecutionConfig::default_config());
     (
         StatusCode::OK,
         Json(ApiResponse::success(
@@ -7,23 +7,40 @@
 ///   The format is based on the [`ExecutionConfig`] struct from `util::execution_config`.
 ///
 /// - `GET /default` → Returns the system's default [`ExecutionConfig`] used to initialize new configurations.
+///
+/// Saving or resetting the configuration can change how grades are computed, so both are refused
+/// once the assignment's moderation has been signed off.
 use axum::{
     Router,
+    middleware::from_fn_with_state,
     routing::{get, post},
 };
 use get::{get_assignment_config, get_default_assignment_config};
 use post::set_assignment_config;
 use util::state::AppState;
 
+use crate::auth::guards::deny_after_moderation_sign_off;
 use crate::routes::modules::assignments::config::post::reset_assignment_config;
 
 pub mod get;
 pub mod post;
 
-pub fn config_routes() -> Router<AppState> {
+pub fn config_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/", post(set_assignment_config))
+        .route(
+            "/",
+            post(set_assignment_config).route_layer(from_fn_with_state(
+                app_state.clone(),
+                deny_after_moderation_sign_off,
+            )),
+        )
         .route("/", get(get_assignment_config))
         .route("/default", get(get_default_assignment_config))
-        .route("/reset", post(reset_assignment_config)) // TODO Tests 
+        .route(
+            "/reset",
+            post(reset_assignment_config).route_layer(from_fn_with_state(
+                app_state,
+                deny_after_moderation_sign_off,
+            )),
+        ) // TODO Tests 
 }
@@ -2,13 +2,23 @@ use crate::response::ApiResponse;
 use axum::{
     extract::{Json, Path, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use db::models::assignment::{Column as AssignmentColumn, Entity as AssignmentEntity};
 use db::models::assignment_file::{FileType, Model as AssignmentFile};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Serialize;
 use serde_json::Value;
-use util::{execution_config::ExecutionConfig, state::AppState};
+use util::{
+    execution_config::{ConfigError, ExecutionConfig},
+    state::AppState,
+};
+
+/// `data` of a rejected config: every problem found, with the path of its field.
+#[derive(Debug, Serialize)]
+pub struct ConfigErrorsResponse {
+    pub errors: Vec<ConfigError>,
+}
 
 /// POST /api/modules/{module_id}/assignments/{assignment_id}/config
 ///
@@ -34,7 +44,7 @@ use util::{execution_config::ExecutionConfig, state::AppState};
 ///   "marking": {
 ///     "marking_scheme": "exact",
 ///     "feedback_scheme": "auto",
-///     "deliminator": "###"
+///     "delimiter": "###"
 ///   }
 /// }
 /// ```
@@ -49,19 +59,37 @@ use util::{execution_config::ExecutionConfig, state::AppState};
 /// ```
 ///
 /// ### Error Responses
-/// - **400** – Invalid JSON structure
+/// - **400** – The config has problems: unknown fields, a value of the wrong type, out-of-range
+///   values (e.g. `pass_mark` > 100, omegas not summing to 1), conflicting options (e.g.
+///   `password_enabled` without a `password_pin`), or an invalid environment variable name,
+///   `project.image`, GATLAM gene or `task_spec` property rule. `message` joins all problems;
+///   `data.errors` lists them with their field paths:
+/// ```json
+/// {
+///   "success": false,
+///   "data": {
+///     "errors": [
+///       { "path": "marking.pass_mrak", "message": "Unknown field marking.pass_mrak" },
+///       { "path": "gatlam.omega1", "message": "gatlam.omega1 + omega2 + omega3 must sum to 1 (got 1.2)" }
+///     ]
+///   },
+///   "message": "Unknown field marking.pass_mrak; gatlam.omega1 + omega2 + omega3 must sum to 1 (got 1.2)"
+/// }
+/// ```
 /// - **404** – Assignment not found
 /// - **500** – Internal error saving the file
 ///
 /// ### Notes
 /// - Configuration is saved to disk.
 /// - Only valid `ExecutionConfig` objects are accepted.
+/// - If the module has a config (`/api/modules/{module_id}/config`), fields missing from the
+///   body are inherited from it, and only the fields that differ from it are stored.
 // api route
 pub async fn set_assignment_config(
     State(app_state): State<AppState>,
     Path((module_id, assignment_id)): Path<(i64, i64)>,
     Json(config_json): Json<Value>,
-) -> impl IntoResponse {
+) -> Response {
     let db = app_state.db();
 
     if !config_json.is_object() {
@@ -70,26 +98,41 @@ pub async fn set_assignment_config(
             Json(ApiResponse::<()>::error(
                 "Configuration must be a JSON object",
             )),
-        );
+        )
+            .into_response();
     }
 
-    let config: ExecutionConfig = match serde_json::from_value(config_json) {
-        Ok(cfg) => cfg,
+    // Fields the body leaves out come from the module config, not the built-in defaults.
+    let config_json = match ExecutionConfig::inherit(module_id, &config_json) {
+        Ok(value) => value,
         Err(e) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(e))).into_response();
+        }
+    };
+
+    let config = match ExecutionConfig::from_json_checked(&config_json) {
+        Ok(cfg) => cfg,
+        Err(errors) => {
+            let message = errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
             return (
                 StatusCode::BAD_REQUEST,
-                Json(ApiResponse::<()>::error(format!(
-                    "Invalid config format: {}",
-                    e
-                ))),
-            );
+                Json(ApiResponse::error_with_data(
+                    ConfigErrorsResponse { errors },
+                    message,
+                )),
+            )
+                .into_response();
         }
     };
 
     // Ensure assignment exists
     if let Err(resp) = AssignmentEntity::find()
-        .filter(AssignmentColumn::Id.eq(assignment_id as i32))
-        .filter(AssignmentColumn::ModuleId.eq(module_id as i32))
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
         .await
         .map_err(|e| {
@@ -106,18 +149,22 @@ pub async fn set_assignment_config(
             ))
         })
     {
-        return resp;
+        return resp.into_response();
     }
 
     // Serialize and overwrite-in-place (handled inside save_file)
-    let bytes = match serde_json::to_vec_pretty(&config) {
+    let bytes = match config
+        .overrides(module_id)
+        .and_then(|v| serde_json::to_vec_pretty(&v).map_err(|e| e.to_string()))
+    {
         Ok(b) => b,
         Err(e) => {
             eprintln!("Serialization error: {:?}", e);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error("Failed to serialize config")),
-            );
+            )
+                .into_response();
         }
     };
 
@@ -134,21 +181,24 @@ pub async fn set_assignment_config(
         Ok(_) => (
             StatusCode::OK,
             Json(ApiResponse::success((), "Assignment configuration saved")),
-        ),
+        )
+            .into_response(),
         Err(e) => {
             eprintln!("File save error: {:?}", e);
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<()>::error("Failed to save config")),
             )
+                .into_response()
         }
     }
 }
 
 /// POST /api/modules/{module_id}/assignments/{assignment_id}/config/reset
 ///
-/// Overwrite the assignment's config on disk with the system defaults (`ExecutionConfig::default_config()`).
-/// Returns the default config that was saved.
+/// Overwrite the assignment's config on disk with the defaults: the module config if the module
+/// has one (`ExecutionConfig::module_defaults()`), the system defaults otherwise.
+/// Returns the config the assignment now resolves to.
 ///
 /// ### Success Response (200 OK)
 /// ```json
@@ -166,8 +216,8 @@ pub async fn reset_assignment_config(
 
     // Ensure the assignment exists and belongs to the module
     let assignment = match AssignmentEntity::find()
-        .filter(AssignmentColumn::Id.eq(assignment_id as i32))
-        .filter(AssignmentColumn::ModuleId.eq(module_id as i32))
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
         .await
     {
@@ -190,10 +240,24 @@ pub async fn reset_assignment_config(
     };
 
     // Build defaults
-    let default_cfg = ExecutionConfig::default_config();
+    let default_cfg = match ExecutionConfig::module_defaults(module_id) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            eprintln!("Module config error: {e}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<ExecutionConfig>::error(
+                    "Failed to load module config",
+                )),
+            );
+        }
+    };
 
     // Persist file
-    let bytes = match serde_json::to_vec_pretty(&default_cfg) {
+    let bytes = match default_cfg
+        .overrides(module_id)
+        .and_then(|v| serde_json::to_vec_pretty(&v).map_err(|e| e.to_string()))
+    {
         Ok(b) => b,
         Err(e) => {
             eprintln!("Serialization error: {:?}", e);
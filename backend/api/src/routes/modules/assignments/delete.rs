@@ -3,7 +3,8 @@
 //! Provides endpoints for deleting single or multiple assignments within a module.
 //!
 //! - `DELETE /api/modules/{module_id}/assignments/{assignment_id}`  
-//!   Deletes a single assignment along with its associated files and folder.
+//!   Soft deletes a single assignment; its files and folder are removed when the retention
+//!   sweep purges it.
 //!
 //! - `DELETE /api/modules/{module_id}/assignments/bulk`  
 //!   Deletes multiple assignments in a module using a JSON array of assignment IDs.
@@ -27,7 +28,9 @@ use util::state::AppState;
 
 /// DELETE /api/modules/:module_id/assignments/:assignment_id
 ///
-/// Delete a specific assignment and its associated files and folder.
+/// Soft delete a specific assignment. It can be restored with
+/// `POST /api/modules/:module_id/assignments/:assignment_id/restore` until the retention sweep
+/// removes it along with its files and folder.
 /// Only accessible by lecturers or admins assigned to the module.
 ///
 /// ### Path Parameters
@@ -65,7 +68,7 @@ pub async fn delete_assignment(
 ) -> impl IntoResponse {
     let db = app_state.db();
 
-    match assignment::Model::delete(db, assignment_id as i32, module_id as i32).await {
+    match assignment::Model::delete(db, assignment_id, module_id).await {
         Ok(()) => (
             StatusCode::OK,
             Json(json!({
@@ -131,7 +134,7 @@ pub async fn bulk_delete_assignments(
     let mut failed: Vec<JsonValue> = Vec::new();
 
     for &id in &req.assignment_ids {
-        match assignment::Model::delete(db, id as i32, module_id as i32).await {
+        match assignment::Model::delete(db, id, module_id).await {
             Ok(_) => deleted_count += 1,
             Err(e) => {
                 failed.push(json!({
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use db::models::exam_session::Model as ExamSessionModel;
+use serde::Serialize;
+use util::execution_config::ExamOptions;
+
+#[derive(Debug, Serialize)]
+pub struct ExamSessionResponse {
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub started_at: String,
+    pub ends_at: String,
+    pub remaining_seconds: i64,
+    pub running: bool,
+}
+
+impl ExamSessionResponse {
+    pub fn new(s: ExamSessionModel, username: Option<String>, now: DateTime<Utc>) -> Self {
+        Self {
+            user_id: s.user_id,
+            username,
+            started_at: s.started_at.to_rfc3339(),
+            ends_at: s.ends_at.to_rfc3339(),
+            remaining_seconds: s.remaining_seconds(now),
+            running: s.is_running(now),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExamResponse {
+    pub enabled: bool,
+    pub duration_minutes: u32,
+    pub hide_feedback: bool,
+    /// The caller's session; `None` until they start the exam.
+    pub session: Option<ExamSessionResponse>,
+}
+
+impl ExamResponse {
+    pub fn new(exam: ExamOptions, session: Option<ExamSessionResponse>) -> Self {
+        Self {
+            enabled: exam.enabled,
+            duration_minutes: exam.duration_minutes,
+            hide_feedback: exam.hide_feedback,
+            session,
+        }
+    }
+}
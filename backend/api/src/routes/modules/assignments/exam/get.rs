@@ -0,0 +1,139 @@
+use super::common::{ExamResponse, ExamSessionResponse};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use db::models::{
+    assignment::{Column as AssignmentColumn, Entity as AssignmentEntity},
+    exam_session::Model as ExamSessionModel,
+    user,
+};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use util::state::AppState;
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/exam
+///
+/// The assignment's exam settings and the caller's session. `session` is `null` until the
+/// caller starts the exam.
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "enabled": true,
+///     "duration_minutes": 90,
+///     "hide_feedback": true,
+///     "session": {
+///       "user_id": 10,
+///       "username": null,
+///       "started_at": "2025-10-16T08:00:00+00:00",
+///       "ends_at": "2025-10-16T09:30:00+00:00",
+///       "remaining_seconds": 3120,
+///       "running": true
+///     }
+///   },
+///   "message": "Exam retrieved"
+/// }
+/// ```
+pub async fn get_exam(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let assignment = match AssignmentEntity::find_active()
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(a)) => a,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Assignment not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+
+    let session = match ExamSessionModel::for_user(db, assignment_id, claims.sub).await {
+        Ok(s) => s.map(|s| ExamSessionResponse::new(s, None, Utc::now())),
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve exam session")),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            ExamResponse::new(assignment.exam(), session),
+            "Exam retrieved",
+        )),
+    )
+        .into_response()
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/exam/sessions
+///
+/// Every student's exam session, most recently started first. Tutor or higher.
+pub async fn list_exam_sessions(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let sessions = match ExamSessionModel::list_for_assignment(db, assignment_id).await {
+        Ok(s) => s,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve exam sessions")),
+            )
+                .into_response();
+        }
+    };
+
+    let usernames: HashMap<i64, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(sessions.iter().map(|s| s.user_id)))
+        .all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    let now = Utc::now();
+    let response: Vec<ExamSessionResponse> = sessions
+        .into_iter()
+        .map(|s| {
+            let username = usernames.get(&s.user_id).cloned();
+            ExamSessionResponse::new(s, username, now)
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(response, "Exam sessions retrieved")),
+    )
+        .into_response()
+}
@@ -0,0 +1,41 @@
+//! Exam routes module.
+//!
+//! Provides the `/exam` route group for timed assignments (`exam.enabled` in the config): each
+//! student starts the exam when ready and may submit only until their time runs out.
+//!
+//! Routes include:
+//! - Get the exam settings and the caller's session, and start the exam
+//! - List everyone's sessions (tutor or higher)
+//!
+//! The group sits behind the assignment's IP allowlist and PIN, so a student can only start
+//! an exam from where, and with the PIN, the lecturer allows.
+
+use crate::auth::guards::allow_tutor;
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+};
+use get::{get_exam, list_exam_sessions};
+use post::start_exam;
+use util::state::AppState;
+
+pub mod common;
+pub mod get;
+pub mod post;
+
+/// Builds and returns the `/exam` route group.
+///
+/// Routes:
+/// - `GET  /exam`           → The exam settings and the caller's session, if started
+/// - `POST /exam/start`     → Start the caller's exam
+/// - `GET  /exam/sessions`  → Every student's session (tutor or higher)
+pub fn exam_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_exam))
+        .route("/start", post(start_exam))
+        .route(
+            "/sessions",
+            get(list_exam_sessions).route_layer(from_fn_with_state(app_state, allow_tutor)),
+        )
+}
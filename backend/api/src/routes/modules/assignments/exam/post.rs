@@ -0,0 +1,120 @@
+use super::common::ExamSessionResponse;
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{Duration, Utc};
+use db::models::{
+    assignment::{Column as AssignmentColumn, Entity as AssignmentEntity},
+    exam_session::Model as ExamSessionModel,
+};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, QueryFilter};
+use util::state::AppState;
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/exam/start
+///
+/// Starts the caller's exam. They may submit for `exam.duration_minutes` from now, or until
+/// their due date if that is sooner. Starting an exam that is already started returns the
+/// existing session; the clock is never reset.
+///
+/// Like the rest of the assignment, this is subject to the config's `security.allowed_cidrs`
+/// and PIN (`x-assignment-pin`).
+///
+/// ### Responses
+/// - `200 OK` — The session (`started_at`, `ends_at`, `remaining_seconds`, `running`)
+/// - `400 Bad Request` — The assignment is not an exam
+/// - `403 Forbidden` — The assignment is not open yet, or the caller's due date has passed
+/// - `404 Not Found` — No such assignment
+pub async fn start_exam(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let assignment = match AssignmentEntity::find_active()
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(a)) => a,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Assignment not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+
+    let exam = assignment.exam();
+    if !exam.enabled {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("This assignment is not an exam")),
+        )
+            .into_response();
+    }
+
+    // an exam already started is returned as is, even once the assignment has closed
+    let existing = match ExamSessionModel::for_user(db, assignment_id, claims.sub).await {
+        Ok(s) => s,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve exam session")),
+            )
+                .into_response();
+        }
+    };
+    if existing.is_none() {
+        let now = Utc::now();
+        if now < assignment.available_from {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error("The exam has not opened yet")),
+            )
+                .into_response();
+        }
+        let due_date = assignment
+            .due_date_for(db, claims.sub)
+            .await
+            .unwrap_or(assignment.due_date);
+        if now >= due_date {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error("The exam has closed")),
+            )
+                .into_response();
+        }
+    }
+
+    let duration = Duration::minutes(exam.duration_minutes.into());
+    match ExamSessionModel::start(db, &assignment, claims.sub, duration).await {
+        Ok(session) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                ExamSessionResponse::new(session, None, Utc::now()),
+                "Exam started",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to start exam")),
+        )
+            .into_response(),
+    }
+}
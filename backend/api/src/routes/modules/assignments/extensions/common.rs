@@ -0,0 +1,31 @@
+use db::models::assignment_extension::Model as ExtensionModel;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtensionResponse {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub due_date: String,
+    pub reason: Option<String>,
+    pub granted_by: Option<i64>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl ExtensionResponse {
+    pub fn new(e: ExtensionModel, username: Option<String>) -> Self {
+        Self {
+            id: e.id,
+            assignment_id: e.assignment_id,
+            user_id: e.user_id,
+            username,
+            due_date: e.due_date.to_rfc3339(),
+            reason: e.reason,
+            granted_by: e.granted_by,
+            created_at: e.created_at.to_rfc3339(),
+            updated_at: e.updated_at.to_rfc3339(),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::assignment_extension::Model as ExtensionModel;
+use sea_orm::DbErr;
+use util::state::AppState;
+
+/// DELETE /api/modules/{module_id}/assignments/{assignment_id}/extensions/{user_id}
+///
+/// Revokes a student's extension; the assignment's due date applies to them again.
+/// Assistant lecturer or higher. Submissions already marked keep their marks.
+pub async fn revoke_extension(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id, user_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    match ExtensionModel::revoke(app_state.db(), assignment_id, user_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "Extension revoked")),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Extension not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to revoke extension")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,110 @@
+use super::common::ExtensionResponse;
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{assignment_extension::Model as ExtensionModel, user};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use util::state::AppState;
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/extensions
+///
+/// Lists the assignment's extensions, latest due date first. Tutor or higher.
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": [
+///     {
+///       "id": 3,
+///       "assignment_id": 2,
+///       "user_id": 10,
+///       "username": "u10000001",
+///       "due_date": "2025-10-20T23:59:00+00:00",
+///       "reason": "Medical certificate",
+///       "granted_by": 1,
+///       "created_at": "2025-10-16T10:00:00+00:00",
+///       "updated_at": "2025-10-16T10:00:00+00:00"
+///     }
+///   ],
+///   "message": "Extensions retrieved"
+/// }
+/// ```
+pub async fn list_extensions(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let extensions = match ExtensionModel::list_for_assignment(db, assignment_id).await {
+        Ok(e) => e,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve extensions")),
+            )
+                .into_response();
+        }
+    };
+
+    let usernames: HashMap<i64, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(extensions.iter().map(|e| e.user_id)))
+        .all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    let response: Vec<ExtensionResponse> = extensions
+        .into_iter()
+        .map(|e| {
+            let username = usernames.get(&e.user_id).cloned();
+            ExtensionResponse::new(e, username)
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(response, "Extensions retrieved")),
+    )
+        .into_response()
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/extensions/me
+///
+/// The caller's extension for the assignment, or `null` in `data` if they don't have one.
+pub async fn get_my_extension(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    match ExtensionModel::for_user(app_state.db(), assignment_id, claims.sub).await {
+        Ok(Some(e)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                Some(ExtensionResponse::new(e, None)),
+                "Extension retrieved",
+            )),
+        )
+            .into_response(),
+        Ok(None) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                None::<ExtensionResponse>,
+                "No extension for this assignment",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve extension")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,65 @@
+//! Extension routes module.
+//!
+//! Provides the `/extensions` route group for individual extensions: a student's own due date
+//! for the assignment, used instead of the assignment's by the late-acceptance gate, the late
+//! cap and `is_late` flags.
+//!
+//! Routes include:
+//! - List extensions (tutor or higher) and get the caller's own extension
+//! - Grant and revoke extensions (assistant lecturer or higher)
+
+use crate::auth::guards::{allow_assistant_lecturer, allow_tutor, deny_after_moderation_sign_off};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{delete, get, put},
+};
+use delete::revoke_extension;
+use get::{get_my_extension, list_extensions};
+use put::grant_extension;
+use util::state::AppState;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod put;
+
+/// Builds and returns the `/extensions` route group.
+///
+/// Routes:
+/// - `GET    /extensions`            → List the assignment's extensions (tutor or higher)
+/// - `GET    /extensions/me`         → The caller's extension, if any
+/// - `PUT    /extensions/{user_id}`  → Grant or replace a student's extension (assistant lecturer or higher)
+/// - `DELETE /extensions/{user_id}`  → Revoke a student's extension (assistant lecturer or higher)
+pub fn extension_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_extensions).route_layer(from_fn_with_state(app_state.clone(), allow_tutor)),
+        )
+        .route("/me", get(get_my_extension))
+        .route(
+            "/{user_id}",
+            put(grant_extension)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/{user_id}",
+            delete(revoke_extension)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+}
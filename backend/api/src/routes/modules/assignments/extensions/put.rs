@@ -0,0 +1,126 @@
+use super::common::ExtensionResponse;
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    assignment::Entity as AssignmentEntity,
+    assignment_extension::Model as ExtensionModel,
+    user,
+    user_module_role::{self, Role},
+};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::Deserialize;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct GrantExtensionRequest {
+    /// RFC 3339; must be later than the assignment's due date
+    pub due_date: String,
+    pub reason: Option<String>,
+}
+
+/// PUT /api/modules/{module_id}/assignments/{assignment_id}/extensions/{user_id}
+///
+/// Grants a student an extension, replacing any they already have. Assistant lecturer or
+/// higher.
+///
+/// ### Request Body
+/// ```json
+/// { "due_date": "2025-10-20T23:59:00Z", "reason": "Medical certificate" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The extension (same shape as the items of `GET /extensions`)
+/// - `400 Bad Request` — Invalid `due_date`, one not after the assignment's due date, or a
+///   user who isn't a student of the module
+pub async fn grant_extension(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, user_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<GrantExtensionRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let due_date = match DateTime::parse_from_rfc3339(&req.due_date) {
+        Ok(dt) => dt.with_timezone(&Utc),
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error("Invalid due_date datetime")),
+            )
+                .into_response();
+        }
+    };
+
+    let assignment = match AssignmentEntity::find_by_id(assignment_id).one(db).await {
+        Ok(Some(a)) => a,
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load assignment")),
+            )
+                .into_response();
+        }
+    };
+    if due_date <= assignment.due_date {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "An extension must be later than the assignment's due date",
+            )),
+        )
+            .into_response();
+    }
+
+    let is_student = user_module_role::Entity::find()
+        .filter(user_module_role::Column::ModuleId.eq(module_id))
+        .filter(user_module_role::Column::UserId.eq(user_id))
+        .filter(user_module_role::Column::Role.eq(Role::Student))
+        .count(db)
+        .await
+        .unwrap_or(0)
+        > 0;
+    if !is_student {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Extensions can only be granted to students of this module",
+            )),
+        )
+            .into_response();
+    }
+
+    let reason = req
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    match ExtensionModel::grant(db, assignment_id, user_id, due_date, reason, claims.sub).await {
+        Ok(e) => {
+            let username = user::Entity::find_by_id(user_id)
+                .one(db)
+                .await
+                .ok()
+                .flatten()
+                .map(|u| u.username);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    ExtensionResponse::new(e, username),
+                    "Extension granted",
+                )),
+            )
+                .into_response()
+        }
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to grant extension")),
+        )
+            .into_response(),
+    }
+}
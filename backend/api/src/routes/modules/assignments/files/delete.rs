@@ -78,16 +78,8 @@ pub async fn delete_files(
     }
 
     let found_models = match assignment_file::Entity::find()
-        .filter(assignment_file::Column::AssignmentId.eq(assignment_id as i32))
-        .filter(
-            assignment_file::Column::Id.is_in(
-                file_ids
-                    .iter()
-                    .copied()
-                    .map(|id| id as i32)
-                    .collect::<Vec<_>>(),
-            ),
-        )
+        .filter(assignment_file::Column::AssignmentId.eq(assignment_id))
+        .filter(assignment_file::Column::Id.is_in(file_ids.iter().copied()))
         .all(db)
         .await
     {
@@ -121,7 +113,8 @@ pub async fn delete_files(
     }
 
     for file in found_models {
-        let _ = file.delete_file_only();
+        let _ = file.delete_file_only().await;
+        let _ = file.release_content(db).await;
         let am: assignment_file::ActiveModel = file.into();
         let _ = am.delete(db).await;
     }
@@ -7,9 +7,7 @@ use axum::{
 };
 use db::models::assignment_file::{Column as FileColumn, Entity as FileEntity};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
-use std::path::PathBuf;
-use tokio::{fs::File as FsFile, io::AsyncReadExt};
-use util::{paths::storage_root, state::AppState};
+use util::state::AppState;
 
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/files/{file_id}
 ///
@@ -27,7 +25,7 @@ use util::{paths::storage_root, state::AppState};
 /// ```json
 /// {
 ///   "success": false,
-///   "message": "File not found" // or "File missing on disk"
+///   "message": "File not found" // or "File missing in storage"
 /// }
 /// ```
 ///
@@ -35,7 +33,7 @@ use util::{paths::storage_root, state::AppState};
 /// ```json
 /// {
 ///   "success": false,
-///   "message": "Database error" // or "Could not open file" or "Failed to read file"
+///   "message": "Database error" // or "Failed to read file"
 /// }
 /// ```
 ///
@@ -46,46 +44,32 @@ pub async fn download_file(
     let db = app_state.db();
 
     let file = FileEntity::find()
-        .filter(FileColumn::Id.eq(file_id as i32))
-        .filter(FileColumn::AssignmentId.eq(assignment_id as i32))
+        .filter(FileColumn::Id.eq(file_id))
+        .filter(FileColumn::AssignmentId.eq(assignment_id))
         .one(db)
         .await
         .unwrap()
         .unwrap();
 
-    let storage_root = storage_root();
-    let fs_path = PathBuf::from(storage_root).join(&file.path);
-
-    if tokio::fs::metadata(&fs_path).await.is_err() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error("File missing on disk")),
-        )
-            .into_response();
-    }
-
-    let mut file_handle = match FsFile::open(&fs_path).await {
-        Ok(f) => f,
+    let buffer = match file.load_file().await {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("File missing in storage")),
+            )
+                .into_response();
+        }
         Err(err) => {
-            eprintln!("File open error: {:?}", err);
+            eprintln!("File read error: {:?}", err);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error("Could not open file")),
+                Json(ApiResponse::<()>::error("Failed to read file")),
             )
                 .into_response();
         }
     };
 
-    let mut buffer = Vec::new();
-    if let Err(err) = file_handle.read_to_end(&mut buffer).await {
-        eprintln!("File read error: {:?}", err);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<()>::error("Failed to read file")),
-        )
-            .into_response();
-    }
-
     let mut headers = HeaderMap::new();
     headers.insert(
         header::CONTENT_DISPOSITION,
@@ -150,7 +134,7 @@ pub async fn list_files(
     let db = app_state.db();
 
     match FileEntity::find()
-        .filter(FileColumn::AssignmentId.eq(assignment_id as i32))
+        .filter(FileColumn::AssignmentId.eq(assignment_id))
         .all(db)
         .await
     {
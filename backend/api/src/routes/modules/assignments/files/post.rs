@@ -1,6 +1,7 @@
-use crate::response::ApiResponse;
+use crate::routes::uploads::common::read_completed_upload;
+use crate::{auth::AuthUser, response::ApiResponse};
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Multipart, Path, State},
     http::StatusCode,
     response::IntoResponse,
@@ -13,7 +14,7 @@ use db::models::{
 };
 use sea_orm::EntityTrait;
 use serde::Serialize;
-use util::state::AppState;
+use util::{config, state::AppState};
 
 #[derive(Debug, Serialize)]
 pub struct UploadedFileMetadata {
@@ -47,6 +48,8 @@ pub struct AssignmentSubmissionMetadata {
 /// ### Request Body (Multipart Form Data)
 /// - `file_type` (string, required): The type of file. Must be one of: `spec`, `main`, `memo`, etc.
 /// - `file` (file, required): The file to upload. Only one file per request is allowed.
+/// - `upload_id` (string, instead of `file`): The id of a completed chunked upload (see
+///   `/api/uploads`) holding the file; it is consumed by the request.
 ///
 /// ### Responses
 ///
@@ -70,7 +73,15 @@ pub struct AssignmentSubmissionMetadata {
 /// ```json
 /// {
 ///   "success": false,
-///   "message": "Invalid file_type" // or "Missing required field: file_type" or "Missing file upload" or "Empty file provided" or "Only one file may be uploaded per request"
+///   "message": "Invalid file_type" // or "Missing required field: file_type" or "Missing file upload" or "Empty file provided" or "Only one file may be uploaded per request" or "Upload 7 not found"
+/// }
+/// ```
+///
+/// - `413 Payload Too Large`
+/// ```json
+/// {
+///   "success": false,
+///   "message": "File too large. Max size is 100 MB"
 /// }
 /// ```
 ///
@@ -93,6 +104,7 @@ pub struct AssignmentSubmissionMetadata {
 pub async fn upload_files(
     State(app_state): State<AppState>,
     Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
     mut multipart: Multipart,
 ) -> impl IntoResponse {
     let db = app_state.db();
@@ -100,6 +112,7 @@ pub async fn upload_files(
     let mut file_type: Option<FileType> = None;
     let mut file_name: Option<String> = None;
     let mut file_bytes: Option<Vec<u8>> = None;
+    let mut upload_id: Option<String> = None;
     let mut file_count = 0;
 
     while let Some(field) = match multipart.next_field().await {
@@ -168,6 +181,19 @@ pub async fn upload_files(
                 }
                 file_count += 1;
             }
+            "upload_id" => match field.text().await {
+                Ok(id) => upload_id = Some(id.trim().to_string()),
+                Err(e) => {
+                    eprintln!("upload_id read error: {e}");
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::<UploadedFileMetadata>::error(
+                            "Unreadable upload_id",
+                        )),
+                    )
+                        .into_response();
+                }
+            },
             _ => continue,
         }
     }
@@ -185,6 +211,43 @@ pub async fn upload_files(
         }
     };
 
+    // a completed chunked upload stands in for the file
+    let mut upload = None;
+    if let Some(raw) = upload_id {
+        if file_count > 0 {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<UploadedFileMetadata>::error(
+                    "Provide either a file or an upload_id, not both",
+                )),
+            )
+                .into_response();
+        }
+        let Ok(id) = raw.parse::<i64>() else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<UploadedFileMetadata>::error(
+                    "upload_id must be an integer",
+                )),
+            )
+                .into_response();
+        };
+        match read_completed_upload(db, id, claims.sub).await {
+            Ok((found, bytes)) => {
+                file_name = Some(found.filename.clone());
+                file_bytes = Some(bytes);
+                upload = Some(found);
+            }
+            Err(msg) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<UploadedFileMetadata>::error(msg)),
+                )
+                    .into_response();
+            }
+        }
+    }
+
     let file_name = match file_name {
         Some(name) => name,
         None => {
@@ -211,6 +274,17 @@ pub async fn upload_files(
         }
     };
 
+    let max_size_mb = config::max_upload_size_mb();
+    if file_bytes.len() as u64 > max_size_mb * 1024 * 1024 {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ApiResponse::<UploadedFileMetadata>::error(format!(
+                "File too large. Max size is {max_size_mb} MB"
+            ))),
+        )
+            .into_response();
+    }
+
     match FileModel::save_file(
         db,
         assignment_id,
@@ -222,6 +296,10 @@ pub async fn upload_files(
     .await
     {
         Ok(saved) => {
+            if let Some(upload) = upload {
+                let _ = upload.discard(db).await;
+            }
+
             if file_type == FileType::Spec {
                 // recipients
                 let email_list = UserModel::get_emails_by_module_id(db, module_id).await;
@@ -0,0 +1,188 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    ga_generation::{self, Individual},
+    ga_run::{self, Entity as GaRunEntity},
+};
+use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ListGaRunsQuery {
+    pub submission_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GaRunResponse {
+    pub id: i64,
+    pub submission_id: i64,
+    pub mode: String,
+    pub status: String,
+    pub generations: i64,
+    pub population_size: i64,
+    pub best_fitness: Option<f64>,
+    pub best_payload: Option<String>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl From<ga_run::Model> for GaRunResponse {
+    fn from(run: ga_run::Model) -> Self {
+        Self {
+            id: run.id,
+            submission_id: run.submission_id,
+            mode: run.mode.to_string(),
+            status: run.status.to_string(),
+            generations: run.generations,
+            population_size: run.population_size,
+            best_fitness: run.best_fitness,
+            best_payload: run.best_payload,
+            error: run.error,
+            started_at: run.started_at,
+            finished_at: run.finished_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GaGenerationResponse {
+    pub generation: i64,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    pub coverage_percent: Option<f64>,
+    pub best_payload: String,
+    pub evaluated: i64,
+    pub interpreter_runs: i64,
+    pub cache_hits: i64,
+    pub population: Vec<Individual>,
+}
+
+impl From<ga_generation::Model> for GaGenerationResponse {
+    fn from(g: ga_generation::Model) -> Self {
+        Self {
+            population: g.individuals(),
+            generation: g.generation,
+            best_fitness: g.best_fitness,
+            mean_fitness: g.mean_fitness,
+            coverage_percent: g.coverage_percent,
+            best_payload: g.best_payload,
+            evaluated: g.evaluated,
+            interpreter_runs: g.interpreter_runs,
+            cache_hits: g.cache_hits,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GaRunDetailResponse {
+    #[serde(flatten)]
+    pub run: GaRunResponse,
+    pub history: Vec<GaGenerationResponse>,
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/ga/runs
+///
+/// Lists the assignment's GA runs, newest first, without their generations.
+///
+/// ### Query Parameters
+/// - `submission_id` (optional): only runs of this submission
+///
+/// ### Responses
+/// - `200 OK`: list of runs (may be empty)
+/// - `500 Internal Server Error`: database error
+pub async fn list_ga_runs(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id)): Path<(i64, i64)>,
+    Query(query): Query<ListGaRunsQuery>,
+) -> impl IntoResponse {
+    match ga_run::Model::list_for_assignment(app_state.db(), assignment_id, query.submission_id)
+        .await
+    {
+        Ok(runs) => {
+            let runs: Vec<GaRunResponse> = runs.into_iter().map(GaRunResponse::from).collect();
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(runs, "GA runs retrieved successfully")),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to retrieve GA runs: {}",
+                e
+            ))),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/ga/runs/{run_id}
+///
+/// Returns a GA run with every recorded generation (fitness statistics, best payload and the
+/// evaluated population), in generation order.
+///
+/// ### Responses
+/// - `200 OK`: the run and its history
+/// - `404 Not Found`: no such run for this assignment
+/// - `500 Internal Server Error`: database error
+pub async fn get_ga_run(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id, run_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let run = match GaRunEntity::find_by_id(run_id).one(db).await {
+        Ok(Some(run)) if run.assignment_id == assignment_id => run,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("GA run not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to retrieve GA run: {}",
+                    e
+                ))),
+            )
+                .into_response();
+        }
+    };
+
+    match ga_generation::Model::list_for_run(db, run.id).await {
+        Ok(generations) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                GaRunDetailResponse {
+                    run: run.into(),
+                    history: generations
+                        .into_iter()
+                        .map(GaGenerationResponse::from)
+                        .collect(),
+                },
+                "GA run retrieved successfully",
+            )),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to retrieve GA generations: {}",
+                e
+            ))),
+        )
+            .into_response(),
+    }
+}
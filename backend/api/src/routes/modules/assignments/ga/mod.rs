@@ -0,0 +1,24 @@
+//! GA Routes Module
+//!
+//! Read-only history of the GATLAM / code coverage GA runs of an assignment's submissions:
+//! per-generation fitness, the evaluated populations and the best payload found.
+
+use crate::auth::guards::allow_tutor;
+use axum::{Router, middleware::from_fn_with_state, routing::get};
+use get::{get_ga_run, list_ga_runs};
+use util::state::AppState;
+
+pub mod get;
+
+/// Registers the GA history endpoints. Access is restricted to tutors, assistant lecturers
+/// and lecturers of the module.
+///
+/// - `GET /runs`: List runs for the assignment (newest first), optionally for one submission
+///   with `?submission_id=`.
+/// - `GET /runs/{run_id}`: A run with all its generations and populations.
+pub fn ga_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/runs", get(list_ga_runs))
+        .route("/runs/{run_id}", get(get_ga_run))
+        .route_layer(from_fn_with_state(app_state.clone(), allow_tutor))
+}
@@ -38,6 +38,7 @@ use db::models::{
     },
     assignment_file, user,
 };
+use db::soft_delete::SoftDelete;
 use sea_orm::{
     ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, sea_query::Expr,
 };
@@ -45,6 +46,7 @@ use serde::{Deserialize, Serialize};
 use util::execution_config::LatePolicy;
 use util::{
     execution_config::{ExecutionConfig, GradingPolicy, SubmissionMode},
+    mark_allocator::AllocatorMismatch,
     state::AppState,
 };
 
@@ -83,6 +85,7 @@ pub struct AttemptsInfo {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AssignmentPolicy {
     pub allow_practice_submissions: bool,
+    pub reduced_practice_feedback: bool,
     pub submission_mode: SubmissionMode,
     pub grading_policy: GradingPolicy,
     pub limit_attempts: bool,
@@ -166,6 +169,7 @@ impl From<AssignmentModel> for AssignmentFileResponse {
 ///     ],
 ///     "policy": {
 ///       "allow_practice_submissions": true,
+///       "reduced_practice_feedback": false,
 ///       "submission_mode": "manual",
 ///       "grading_policy": "last",
 ///       "limit_attempts": true,
@@ -216,8 +220,8 @@ pub async fn get_assignment(
     let db = app_state.db();
 
     let assignment_res = assignment::Entity::find()
-        .filter(assignment::Column::Id.eq(assignment_id as i32))
-        .filter(assignment::Column::ModuleId.eq(module_id as i32))
+        .filter(assignment::Column::Id.eq(assignment_id))
+        .filter(assignment::Column::ModuleId.eq(module_id))
         .one(db)
         .await;
 
@@ -255,6 +259,7 @@ pub async fn get_assignment(
                     let cfg = a.config().unwrap_or_else(ExecutionConfig::default_config);
                     let policy = AssignmentPolicy {
                         allow_practice_submissions: cfg.marking.allow_practice_submissions,
+                        reduced_practice_feedback: cfg.marking.reduced_practice_feedback,
                         submission_mode: cfg.project.submission_mode,
                         grading_policy: cfg.marking.grading_policy,
                         limit_attempts: cfg.marking.limit_attempts,
@@ -497,7 +502,7 @@ pub async fn get_assignments(
         }
     }
 
-    let mut condition = Condition::all().add(AssignmentColumn::ModuleId.eq(module_id as i32));
+    let mut condition = Condition::all().add(AssignmentColumn::ModuleId.eq(module_id));
 
     if let Some(ref query) = params.query {
         let pattern = format!("%{}%", query.to_lowercase());
@@ -553,7 +558,7 @@ pub async fn get_assignments(
         }
     }
 
-    let mut query = AssignmentEntity::find().filter(condition);
+    let mut query = AssignmentEntity::find_active().filter(condition);
 
     if let Some(sort_param) = &params.sort {
         for sort in sort_param.split(',') {
@@ -671,6 +676,7 @@ pub struct AssignmentReadiness {
     pub makefile_present: bool,
     pub memo_output_present: bool,
     pub mark_allocator_present: bool,
+    pub mark_allocator_issues: Vec<AllocatorMismatch>,
     pub is_ready: bool,
 }
 
@@ -684,6 +690,10 @@ pub struct AssignmentReadiness {
 /// - If `submission_mode` is **gatlam** → an **interpreter** must be present.
 /// - Other modes (e.g., `rng`, `codecoverage`) do not require main/interpreter.
 ///
+/// `mark_allocator_issues` lists where the mark allocator disagrees with the memo output
+/// (section counts, missing outputs, values that do not add up), each tagged by `kind`.
+/// It is advisory and does not affect `is_ready`.
+///
 /// This endpoint is useful to check if an assignment is fully set up and eligible
 /// to transition from `Setup` to `Ready`.
 ///
@@ -708,6 +718,9 @@ pub struct AssignmentReadiness {
 ///     "makefile_present": true,
 ///     "memo_output_present": true,
 ///     "mark_allocator_present": true,
+///     "mark_allocator_issues": [
+///       { "kind": "subsection_count", "task_number": 2, "allocator": 3, "memo": 2 }
+///     ],
 ///     "is_ready": true
 ///   }
 /// }
@@ -753,6 +766,7 @@ pub async fn get_assignment_readiness(
                 memo_output_present: report.memo_output_present,
                 mark_allocator_present: report.mark_allocator_present,
                 is_ready: report.is_ready(),
+                mark_allocator_issues: report.mark_allocator_issues,
             };
 
             (
@@ -30,6 +30,7 @@ use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use util::execution_config::GradingPolicy;
 use util::paths::submission_report_path;
 use util::state::AppState;
 
@@ -78,6 +79,14 @@ pub struct GradeResponse {
 #[derive(Debug, Serialize)]
 pub struct PaginatedGradeResponse {
     pub grades: Vec<GradeResponse>,
+    /// The policy the scores were computed under.
+    pub grading_policy: GradingPolicy,
+    /// How many recent attempts `best_of_last_n` looks at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_of_last_n: Option<u32>,
+    /// The cap on attempts after the first under `weighted`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub later_attempt_cap: Option<f64>,
     pub page: u64,
     pub per_page: u64,
     pub total: u64,
@@ -189,8 +198,8 @@ fn read_tasks_from_report(
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/grades
 ///
 /// Returns a **paginated** list of **student** grades for an assignment, computed from
-/// `assignment_submissions` using the assignment’s grading policy (**best** or **last**)
-/// as defined in its `ExecutionConfig`.
+/// `assignment_submissions` using the assignment’s grading policy as defined in its
+/// `ExecutionConfig`, which the response reports alongside the grades.
 ///
 /// Staff submissions (lecturer/assistant_lecturer/tutor/admin) are **excluded** via the
 /// `user_module_roles` table — only users with role **student** in the given module
@@ -225,6 +234,11 @@ fn read_tasks_from_report(
 /// - **Last** — Uses the most recent submission per student (by `created_at`, then `attempt`)
 /// - **Best** — Uses the submission with the highest ratio `earned/total`
 ///   (ties broken by newer `created_at`, then higher `attempt`)
+/// - **BestOfLastN** (`best_of_last_n`) — As **Best**, among the `best_of_last_n` most recent
+///   submissions only
+/// - **Weighted** (`weighted`) — The score is the average over every submission, the k-th oldest
+///   weighing k and each after the first capped at `later_attempt_cap`; the row shows the most
+///   recent submission
 ///
 /// # Response (200 OK)
 /// ```json
@@ -249,6 +263,8 @@ fn read_tasks_from_report(
 ///         ]
 ///       }
 ///     ],
+///     "grading_policy": "best_of_last_n",
+///     "best_of_last_n": 3,
 ///     "page": 1,
 ///     "per_page": 20,
 ///     "total": 47
@@ -263,7 +279,8 @@ fn read_tasks_from_report(
 ///
 /// # Notes
 /// - If the report is missing or malformed, `tasks` is returned as an empty array for that row.
-/// - `score` is computed as `(earned / total) * 100`. Practice submissions are ignored.
+/// - `score` is computed as `(earned / total) * 100` (averaged under `weighted`). Practice
+///   submissions are ignored.
 /// - Only **students** (role `student` in `user_module_roles`) are included.
 /// - Pagination and sorting are done **after** policy selection (one row per student).
 ///
@@ -275,7 +292,7 @@ pub async fn list_grades(
     Path((module_id, assignment_id)): Path<(i64, i64)>,
     Query(params): Query<ListGradeQueryParams>,
 ) -> Response {
-    let db = state.db();
+    let db = state.db_read();
     let page = params.page.max(1);
     let per_page = params.per_page.clamp(1, 100);
 
@@ -287,7 +304,7 @@ pub async fn list_grades(
         assignment_id,
         GradeComputationOptions {
             username_filter,
-            user_id: None,
+            ..Default::default()
         },
     )
     .await
@@ -318,6 +335,7 @@ pub async fn list_grades(
         }
     };
 
+    let marking = result.execution_config.marking;
     let mut grades = result.grades;
 
     // Load task name map once
@@ -401,7 +419,7 @@ pub async fn list_grades(
             GradeResponse {
                 id: g.submission.id,
                 assignment_id,
-                user_id: g.submission.user_id,
+                user_id: g.user.id,
                 submission_id: Some(g.submission.id),
                 score: g.score_pct,
                 username: g.user.username.clone(),
@@ -414,6 +432,11 @@ pub async fn list_grades(
 
     let payload = PaginatedGradeResponse {
         grades: payload_rows,
+        grading_policy: marking.grading_policy,
+        best_of_last_n: (marking.grading_policy == GradingPolicy::BestOfLastN)
+            .then_some(marking.best_of_last_n),
+        later_attempt_cap: (marking.grading_policy == GradingPolicy::Weighted)
+            .then_some(marking.later_attempt_cap),
         page,
         per_page,
         total,
@@ -432,7 +455,7 @@ pub async fn list_grades(
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/grades/export
 ///
 /// Exports **student** grades for an assignment as a **CSV attachment**.
-/// Uses the assignment’s grading policy (**best/last**) to pick **one submission per student**,
+/// Uses the assignment’s grading policy to pick **one submission per student** and its score,
 /// then outputs:
 /// - `username`
 /// - `score` (final %)
@@ -489,7 +512,7 @@ pub async fn export_grades(
     State(state): State<AppState>,
     Path((module_id, assignment_id)): Path<(i64, i64)>,
 ) -> Response {
-    let db = state.db();
+    let db = state.db_read();
 
     // Guard: assignment exists in module
     match AssignmentEntity::find()
@@ -5,17 +5,24 @@
 //! Routes include:
 //! - List grades for an assignment (with pagination, search, and sorting)
 //! - Export grades as CSV
+//! - Send grades to the LMS over LTI
 //!
 //! Access control:
 //! - Students see only their own grade (list endpoint)
 //! - Staff-only access for export (enforced upstream as per router setup)
 
 use crate::auth::guards::allow_assistant_lecturer;
-use axum::{Router, middleware::from_fn_with_state, routing::get};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{get, post},
+};
 use get::{export_grades, list_grades};
+use post::sync_lti_grades;
 use util::state::AppState;
 
 pub mod get;
+pub mod post;
 
 /// Expects an assignment ID and module ID.
 /// Builds and returns the `/grades` route group.
@@ -23,6 +30,7 @@ pub mod get;
 /// Routes:
 /// - `GET /grades`         → List grades (students restricted to own)
 /// - `GET /grades/export`  → Export grades as CSV
+/// - `POST /grades/lti-sync` → Send grades to the LMS placements of the assignment
 pub fn grade_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
         .route(
@@ -39,4 +47,11 @@ pub fn grade_routes(app_state: AppState) -> Router<AppState> {
                 allow_assistant_lecturer,
             )),
         )
+        .route(
+            "/lti-sync",
+            post(sync_lti_grades).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
 }
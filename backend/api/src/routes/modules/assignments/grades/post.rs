@@ -0,0 +1,55 @@
+//! Assignment grade passback.
+//!
+//! - `POST /api/modules/{module_id}/assignments/{assignment_id}/grades/lti-sync` → post every
+//!   student's mark to the LMS placements of the assignment (LTI Assignment and Grade Services)
+
+use crate::response::ApiResponse;
+use crate::services::lti::{LtiError, sync_grades};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use util::state::AppState;
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/grades/lti-sync
+///
+/// Posts each student's counted mark, as a percentage, to the AGS line item of every LMS
+/// placement of the assignment. Marks are also posted automatically as submissions are graded;
+/// this resends them all, e.g. after rubric marking or a change of grading policy.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Grades sent to the LMS",
+///   "data": { "posted": 118, "unlinked": 3, "failed": 0 }
+/// }
+/// ```
+/// `unlinked` counts students who have never launched the assignment from that LMS.
+/// - `400 Bad Request` — No active LTI key, or the assignment's config could not be loaded
+/// - `500 Internal Server Error` — Database failure
+pub async fn sync_lti_grades(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> Response {
+    match sync_grades(app_state.db(), module_id, assignment_id, None).await {
+        Ok(summary) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(summary, "Grades sent to the LMS")),
+        )
+            .into_response(),
+        Err(LtiError::Database(_)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to send grades")),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(e.to_string())),
+        )
+            .into_response(),
+    }
+}
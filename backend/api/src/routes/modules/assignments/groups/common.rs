@@ -0,0 +1,142 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    group::Model as GroupModel,
+    user,
+    user_module_role::{self, Role},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupMemberResponse {
+    pub id: i64,
+    pub username: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupResponse {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub name: String,
+    pub members: Vec<GroupMemberResponse>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl GroupResponse {
+    /// Builds the response, looking up the members' usernames.
+    pub async fn load(
+        db: &DatabaseConnection,
+        group: GroupModel,
+        member_ids: Vec<i64>,
+    ) -> Result<Self, DbErr> {
+        let members = user::Entity::find()
+            .filter(user::Column::Id.is_in(member_ids))
+            .order_by_asc(user::Column::Username)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|u| GroupMemberResponse {
+                id: u.id,
+                username: u.username,
+            })
+            .collect();
+
+        Ok(Self {
+            id: group.id,
+            assignment_id: group.assignment_id,
+            name: group.name,
+            members,
+            created_at: group.created_at.to_rfc3339(),
+            updated_at: group.updated_at.to_rfc3339(),
+        })
+    }
+}
+
+fn error(status: StatusCode, message: String) -> Response {
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+/// Checks a proposed group before it is saved: the name is non-empty and unique within the
+/// assignment, and every member is a student of the module who isn't in another group.
+///
+/// `group_id` is the group being edited, if any.
+pub async fn validate_group(
+    db: &DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+    group_id: Option<i64>,
+    name: Option<&str>,
+    member_ids: Option<&[i64]>,
+) -> Result<(), Response> {
+    let db_error = |_| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error".to_string(),
+        )
+    };
+
+    if let Some(name) = name {
+        if name.trim().is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "Group name must not be empty".to_string(),
+            ));
+        }
+        let taken = GroupModel::list_for_assignment(db, assignment_id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .any(|(g, _)| g.name == name.trim() && Some(g.id) != group_id);
+        if taken {
+            return Err(error(
+                StatusCode::CONFLICT,
+                format!("A group named '{}' already exists", name.trim()),
+            ));
+        }
+    }
+
+    if let Some(member_ids) = member_ids {
+        if member_ids.is_empty() {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                "A group needs at least one member".to_string(),
+            ));
+        }
+
+        let students: HashSet<i64> = user_module_role::Entity::find()
+            .filter(user_module_role::Column::ModuleId.eq(module_id))
+            .filter(user_module_role::Column::Role.eq(Role::Student))
+            .filter(user_module_role::Column::UserId.is_in(member_ids.iter().copied()))
+            .all(db)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|r| r.user_id)
+            .collect();
+        if let Some(id) = member_ids.iter().find(|id| !students.contains(id)) {
+            return Err(error(
+                StatusCode::BAD_REQUEST,
+                format!("User {id} is not a student in this module"),
+            ));
+        }
+
+        let taken = GroupModel::conflicting_members(db, assignment_id, member_ids, group_id)
+            .await
+            .map_err(db_error)?;
+        if let Some(id) = taken.first() {
+            return Err(error(
+                StatusCode::CONFLICT,
+                format!("User {id} is already in another group for this assignment"),
+            ));
+        }
+    }
+
+    Ok(())
+}
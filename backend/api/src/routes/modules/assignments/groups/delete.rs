@@ -0,0 +1,38 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::group::Model as GroupModel;
+use sea_orm::DbErr;
+use util::state::AppState;
+
+/// DELETE /api/modules/{module_id}/assignments/{assignment_id}/groups/{group_id}
+///
+/// Deletes a group. Assistant lecturer or higher.
+///
+/// Submissions made for the group are unlinked and stay with the members who made them.
+pub async fn delete_group(
+    State(app_state): State<AppState>,
+    Path((_, _, group_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    match GroupModel::delete(app_state.db(), group_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "Group deleted")),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Group not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to delete group")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,118 @@
+use super::common::GroupResponse;
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::group::Model as GroupModel;
+use util::state::AppState;
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/groups
+///
+/// Lists the assignment's groups by name, with their members. Tutor or higher.
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": [
+///     {
+///       "id": 4,
+///       "assignment_id": 2,
+///       "name": "Team Rocket",
+///       "members": [ { "id": 10, "username": "u10000001" }, { "id": 11, "username": "u10000002" } ],
+///       "created_at": "2025-10-16T10:00:00+00:00",
+///       "updated_at": "2025-10-16T10:00:00+00:00"
+///     }
+///   ],
+///   "message": "Groups retrieved"
+/// }
+/// ```
+pub async fn list_groups(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let groups = match GroupModel::list_for_assignment(db, assignment_id).await {
+        Ok(groups) => groups,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve groups")),
+            )
+                .into_response();
+        }
+    };
+
+    let mut response = Vec::with_capacity(groups.len());
+    for (group, member_ids) in groups {
+        match GroupResponse::load(db, group, member_ids).await {
+            Ok(g) => response.push(g),
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error("Failed to retrieve groups")),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(response, "Groups retrieved")),
+    )
+        .into_response()
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/groups/me
+///
+/// The caller's group for the assignment, or `null` in `data` if they aren't in one.
+pub async fn get_my_group(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let group = match GroupModel::for_user(db, assignment_id, claims.sub).await {
+        Ok(Some(group)) => group,
+        Ok(None) => {
+            return (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    None::<GroupResponse>,
+                    "Not in a group for this assignment",
+                )),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve group")),
+            )
+                .into_response();
+        }
+    };
+
+    let response = match GroupModel::member_ids(db, group.id).await {
+        Ok(ids) => GroupResponse::load(db, group, ids).await,
+        Err(e) => Err(e),
+    };
+    match response {
+        Ok(g) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(Some(g), "Group retrieved")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve group")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,66 @@
+//! Group routes module.
+//!
+//! Provides the `/groups` route group for managing the student groups of an assignment.
+//!
+//! Groups only affect marking when the assignment's config sets `marking.group_submissions`:
+//! a member's submission is then shared by the whole group (grade, report and attempt budget).
+//!
+//! Routes include:
+//! - List groups (tutor or higher) and get the caller's own group
+//! - Create, edit and delete groups (assistant lecturer or higher)
+
+use crate::auth::guards::{allow_assistant_lecturer, allow_tutor};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{delete, get, post, put},
+};
+use delete::delete_group;
+use get::{get_my_group, list_groups};
+use post::create_group;
+use put::edit_group;
+use util::state::AppState;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod post;
+pub mod put;
+
+/// Builds and returns the `/groups` route group.
+///
+/// Routes:
+/// - `GET    /groups`             → List the assignment's groups (tutor or higher)
+/// - `GET    /groups/me`          → The caller's group, if any
+/// - `POST   /groups`             → Create a group (assistant lecturer or higher)
+/// - `PUT    /groups/{group_id}`  → Rename a group and/or replace its members (assistant lecturer or higher)
+/// - `DELETE /groups/{group_id}`  → Delete a group (assistant lecturer or higher)
+pub fn group_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_groups).route_layer(from_fn_with_state(app_state.clone(), allow_tutor)),
+        )
+        .route("/me", get(get_my_group))
+        .route(
+            "/",
+            post(create_group).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/{group_id}",
+            put(edit_group).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/{group_id}",
+            delete(delete_group).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+}
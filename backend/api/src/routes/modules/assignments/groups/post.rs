@@ -0,0 +1,80 @@
+use super::common::{GroupResponse, validate_group};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::group::Model as GroupModel;
+use serde::Deserialize;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateGroupRequest {
+    pub name: String,
+    /// User ids of the members; each must be a student of the module
+    pub member_ids: Vec<i64>,
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/groups
+///
+/// Creates a group. Assistant lecturer or higher.
+///
+/// ### Request Body
+/// ```json
+/// { "name": "Team Rocket", "member_ids": [10, 11] }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — The new group (same shape as the items of `GET /groups`)
+/// - `400 Bad Request` — Empty name, no members, or a member who isn't a student of the module
+/// - `409 Conflict` — The name is taken, or a member is already in another group
+pub async fn create_group(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Json(req): Json<CreateGroupRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if let Err(resp) = validate_group(
+        db,
+        module_id,
+        assignment_id,
+        None,
+        Some(&req.name),
+        Some(&req.member_ids),
+    )
+    .await
+    {
+        return resp;
+    }
+
+    let group =
+        match GroupModel::create(db, assignment_id, req.name.trim(), &req.member_ids).await {
+            Ok(group) => group,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error("Failed to create group")),
+                )
+                    .into_response();
+            }
+        };
+
+    let member_ids = GroupModel::member_ids(db, group.id)
+        .await
+        .unwrap_or(req.member_ids);
+    match GroupResponse::load(db, group, member_ids).await {
+        Ok(g) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(g, "Group created")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve group")),
+        )
+            .into_response(),
+    }
+}
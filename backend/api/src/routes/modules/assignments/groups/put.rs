@@ -0,0 +1,109 @@
+use super::common::{GroupResponse, validate_group};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::group::Model as GroupModel;
+use sea_orm::DbErr;
+use serde::Deserialize;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct EditGroupRequest {
+    pub name: Option<String>,
+    /// Replaces the members when given
+    pub member_ids: Option<Vec<i64>>,
+}
+
+/// PUT /api/modules/{module_id}/assignments/{assignment_id}/groups/{group_id}
+///
+/// Renames a group and/or replaces its members. Assistant lecturer or higher.
+///
+/// Submissions already made for the group stay linked to it, so removed members lose access
+/// to them and added members gain it.
+///
+/// ### Request Body
+/// ```json
+/// { "name": "Team Rocket", "member_ids": [10, 11, 12] }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The updated group
+/// - `400 Bad Request` — Nothing to change, empty name, no members, or a non-student member
+/// - `404 Not Found` — No such group for this assignment
+/// - `409 Conflict` — The name is taken, or a member is already in another group
+pub async fn edit_group(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, group_id)): Path<(i64, i64, i64)>,
+    Json(req): Json<EditGroupRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if req.name.is_none() && req.member_ids.is_none() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "At least one of 'name' or 'member_ids' must be provided",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Err(resp) = validate_group(
+        db,
+        module_id,
+        assignment_id,
+        Some(group_id),
+        req.name.as_deref(),
+        req.member_ids.as_deref(),
+    )
+    .await
+    {
+        return resp;
+    }
+
+    let group = match GroupModel::update(
+        db,
+        group_id,
+        req.name.as_deref().map(str::trim),
+        req.member_ids.as_deref(),
+    )
+    .await
+    {
+        Ok(group) => group,
+        Err(DbErr::RecordNotFound(_)) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Group not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to update group")),
+            )
+                .into_response();
+        }
+    };
+
+    let response = match GroupModel::member_ids(db, group.id).await {
+        Ok(ids) => GroupResponse::load(db, group, ids).await,
+        Err(e) => Err(e),
+    };
+    match response {
+        Ok(g) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(g, "Group updated")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve group")),
+        )
+            .into_response(),
+    }
+}
@@ -78,7 +78,7 @@ pub async fn delete_interpreter(
 
     // Delete each interpreter file and DB record
     for interpreter in interpreters {
-        let _ = interpreter.delete_file_only(); // ignore file deletion errors
+        let _ = interpreter.delete_file_only().await; // ignore file deletion errors
         let am: assignment_interpreter::ActiveModel = interpreter.into();
         let _ = am.delete(db).await; // ignore DB deletion errors for now
     }
@@ -9,9 +9,7 @@ use db::models::assignment_interpreter::{
 };
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::Serialize;
-use std::path::PathBuf;
-use tokio::{fs::File as FsFile, io::AsyncReadExt};
-use util::{paths::storage_root, state::AppState};
+use util::state::AppState;
 
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/interpreter
 ///
@@ -23,7 +21,7 @@ use util::{paths::storage_root, state::AppState};
 ///
 /// ### Responses
 /// - `200 OK`: Returns the interpreter as a binary attachment
-/// - `404 Not Found`: If no interpreter exists for the assignment, or if the file is missing on disk
+/// - `404 Not Found`: If no interpreter exists for the assignment, or if the file is missing in storage
 /// - `500 Internal Server Error`: If DB or file read fails
 ///
 pub async fn download_interpreter(
@@ -56,39 +54,27 @@ pub async fn download_interpreter(
         }
     };
 
-    let storage_root = storage_root();
-    let fs_path = PathBuf::from(storage_root).join(&interpreter.path);
-
-    if tokio::fs::metadata(&fs_path).await.is_err() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error("Interpreter file missing on disk")),
-        )
-            .into_response();
-    }
-
-    let mut file_handle = match FsFile::open(&fs_path).await {
-        Ok(f) => f,
+    let buffer = match interpreter.load_file().await {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(
+                    "Interpreter file missing in storage",
+                )),
+            )
+                .into_response();
+        }
         Err(err) => {
-            eprintln!("File open error: {:?}", err);
+            eprintln!("File read error: {:?}", err);
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error("Could not open interpreter file")),
+                Json(ApiResponse::<()>::error("Failed to read interpreter file")),
             )
                 .into_response();
         }
     };
 
-    let mut buffer = Vec::new();
-    if let Err(err) = file_handle.read_to_end(&mut buffer).await {
-        eprintln!("File read error: {:?}", err);
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<()>::error("Failed to read interpreter file")),
-        )
-            .into_response();
-    }
-
     let mut headers = HeaderMap::new();
     headers.insert(
         header::CONTENT_DISPOSITION,
@@ -1,7 +1,7 @@
 use crate::response::ApiResponse;
 use axum::{Json, extract::Path, http::StatusCode, response::IntoResponse};
-use util::mark_allocator::load_allocator;
-use util::paths::{assignment_dir, mark_allocator_path};
+use util::mark_allocator::{allocator_file, load_allocator};
+use util::paths::assignment_dir;
 
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/mark_allocator
 ///
@@ -32,10 +32,13 @@ use util::paths::{assignment_dir, mark_allocator_path};
 ///
 /// Notes:
 /// - This returns the **normalized** structure: `{ generated_at, total_value, tasks[] }`.
-/// - Each task contains `subsections[]` with `{ name, value, feedback?, regex? }`.
+/// - Each task contains `subsections[]` with `{ name, value, feedback?, regex? }`, plus the
+///   optional `comparator` (`"exact" | "percentage" | "regex"`), `weight` and nested
+///   `subsections[]`.
 /// - If the assignment’s marking scheme is **Regex**, the generator will have
 ///   created `regex` arrays where each element corresponds to a line within that subsection.
-/// - File location: `{STORAGE_ROOT}/module_{m}/assignment_{a}/mark_allocator/allocator.json`.
+/// - File location: `{STORAGE_ROOT}/module_{m}/assignment_{a}/mark_allocator/allocator.json`
+///   (or `allocator.yaml` / `allocator.yml`).
 ///
 /// Errors:
 /// - **404 Not Found**: assignment folder or allocator file is missing
@@ -52,8 +55,7 @@ pub async fn load(Path((module_id, assignment_id)): Path<(i64, i64)>) -> impl In
         )
             .into_response();
     }
-    let alloc_path = mark_allocator_path(module_id, assignment_id);
-    if !alloc_path.exists() {
+    if allocator_file(module_id, assignment_id).is_none() {
         return (
             StatusCode::NOT_FOUND,
             Json(ApiResponse::<()>::error(
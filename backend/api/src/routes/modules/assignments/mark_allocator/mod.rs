@@ -2,8 +2,10 @@
 ///
 /// This module defines HTTP routes for generating, loading, and saving mark allocator data.
 /// Each route is protected with middleware that ensures only lecturers can access them.
+use crate::auth::guards::deny_after_moderation_sign_off;
 use axum::{
     Router,
+    middleware::from_fn_with_state,
     routing::{get, post, put},
 };
 use get::load;
@@ -24,9 +26,21 @@ pub mod put;
 /// - `PUT  /` → `save` updated allocator data to disk.
 ///
 /// All routes require lecturer authentication using the `require_lecturer` middleware.
-pub fn mark_allocator_routes() -> Router<AppState> {
+pub fn mark_allocator_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/generate", post(generate))
+        .route(
+            "/generate",
+            post(generate).route_layer(from_fn_with_state(
+                app_state.clone(),
+                deny_after_moderation_sign_off,
+            )),
+        )
         .route("/", get(load))
-        .route("/", put(save))
+        .route(
+            "/",
+            put(save).route_layer(from_fn_with_state(
+                app_state,
+                deny_after_moderation_sign_off,
+            )),
+        )
 }
@@ -3,7 +3,7 @@ use axum::{Json, extract::Path, http::StatusCode, response::IntoResponse};
 use util::execution_config::{ExecutionConfig, MarkingScheme};
 use util::paths::assignment_dir;
 
-use util::mark_allocator::{MarkAllocator, load_allocator, save_allocator};
+use util::mark_allocator::{MarkAllocator, Subsection, load_allocator, save_allocator};
 
 /// PUT /api/modules/{module_id}/assignments/{assignment_id}/mark_allocator
 ///
@@ -12,16 +12,16 @@ use util::mark_allocator::{MarkAllocator, load_allocator, save_allocator};
 /// - Validates:
 ///   - `tasks` non-empty
 ///   - each task: `task_number > 0`, `name != ""`, `value >= 0`
-///   - each subsection: `name != ""`, `value >= 0`
-///   - sum(subsection.value) == task.value for every task
+///   - each subsection: `name != ""`, `value >= 0`, `weight >= 0`
+///   - each subsection memory threshold: `0 <= percent <= 100`
+///   - sum(subsection.value * weight) == task.value for every task
+///   - for nested `subsections`: the same rules, and their marks sum to the parent's `value`
 ///   - sum(task.value) == total_value
-/// - If `ExecutionConfig.marking.marking_scheme == "regex"`:
-///   - For every subsection, ensures `regex.len() == subsection.value`.
-///   - If `regex` is missing → **pad** with `""` to length = `value`.
-///   - If `regex` exists but shorter → **pad** with `""`.
-///   - If `regex` longer than `value` → **400 Bad Request**.
-/// - Persists **normalized JSON** at:
-///   `{STORAGE_ROOT}/module_{m}/assignment_{a}/mark_allocator/allocator.json`
+/// - If `ExecutionConfig.marking.marking_scheme == "regex"` (or a subsection's `comparator`
+///   is `"regex"`), a missing `regex` on a marked subsection is stored as `[]`.
+/// - Persists the **normalized** allocator in the format of the existing file
+///   (`allocator.json`, `allocator.yaml` or `allocator.yml`; JSON if there is none) under
+///   `{STORAGE_ROOT}/module_{m}/assignment_{a}/mark_allocator/`
 /// - Responds with the normalized allocator.
 pub async fn save(
     Path((module_id, assignment_id)): Path<(i64, i64)>,
@@ -82,28 +82,13 @@ pub async fn save(
         }
 
         let mut sum_sub_values: f64 = 0.0;
-        for (sidx, s) in t.subsections.iter().enumerate() {
-            if s.name.trim().is_empty() {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse::<()>::error(&format!(
-                        "tasks[{}].subsections[{}].name must be a non-empty string",
-                        tidx, sidx
-                    ))),
-                )
+        for (sidx, sub) in t.subsections.iter().enumerate() {
+            let path = format!("tasks[{}].subsections[{}]", tidx, sidx);
+            if let Err(e) = validate_subsection(&path, sub) {
+                return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(e)))
                     .into_response();
             }
-            if s.value < 0.0 {
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(ApiResponse::<()>::error(&format!(
-                        "tasks[{}].subsections[{}].value must be >= 0",
-                        tidx, sidx
-                    ))),
-                )
-                    .into_response();
-            }
-            sum_sub_values += s.value;
+            sum_sub_values += sub.marks();
         }
 
         if !t.code_coverage.unwrap_or(false) && sum_sub_values != t.value {
@@ -161,29 +146,14 @@ pub async fn save(
         Err(_) => false,
     };
 
-    // 5) If Regex scheme, accept regex arrays as-is (independent of value)
-    if want_regex {
-        for (_tidx, t) in alloc.tasks.iter_mut().enumerate() {
-            for (_sidx, s) in t.subsections.iter_mut().enumerate() {
-                match s.regex.as_mut() {
-                    Some(v) => {
-                        // normalize: ensure strings (empty "" is allowed)
-                        for r in v.iter_mut() {
-                            // nothing to do; keep user-provided string
-                            // (you could trim whitespace if you want)
-                            let _ = r;
-                        }
-                    }
-                    None => {
-                        // if omitted, store empty vec (not tied to marks)
-                        s.regex = Some(Vec::new());
-                    }
-                }
-            }
+    // 5) Marked subsections using the regex scheme get an (empty) regex list if omitted
+    for t in alloc.tasks.iter_mut() {
+        for sub in t.subsections.iter_mut() {
+            default_regex(sub, want_regex);
         }
     }
 
-    // 6) Save normalized JSON
+    // 6) Save normalized allocator
     if let Err(e) = save_allocator(module_id, assignment_id, &alloc) {
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -204,3 +174,54 @@ pub async fn save(
     )
         .into_response()
 }
+
+/// Checks one subsection and, recursively, its nested subsections.
+fn validate_subsection(path: &str, s: &Subsection) -> Result<(), String> {
+    if s.name.trim().is_empty() {
+        return Err(format!("{path}.name must be a non-empty string"));
+    }
+    if s.value < 0.0 {
+        return Err(format!("{path}.value must be >= 0"));
+    }
+    if s.weight.is_some_and(|w| w < 0.0) {
+        return Err(format!("{path}.weight must be >= 0"));
+    }
+    for (midx, m) in s.memory_thresholds.iter().flatten().enumerate() {
+        if !(0.0..=100.0).contains(&m.percent) {
+            return Err(format!(
+                "{path}.memory_thresholds[{midx}].percent must be between 0 and 100"
+            ));
+        }
+    }
+    if let Some(children) = s.children() {
+        let mut sum: f64 = 0.0;
+        for (cidx, child) in children.iter().enumerate() {
+            validate_subsection(&format!("{path}.subsections[{cidx}]"), child)?;
+            sum += child.marks();
+        }
+        if sum != s.value {
+            return Err(format!(
+                "{path}: sum of nested subsection values ({sum}) must equal its value ({})",
+                s.value
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Stores `[]` as the regex list of marked (leaf) subsections that use the regex scheme.
+fn default_regex(s: &mut Subsection, scheme_is_regex: bool) {
+    let is_regex = match &s.comparator {
+        Some(c) => *c == MarkingScheme::Regex,
+        None => scheme_is_regex,
+    };
+    match s.subsections.as_mut().filter(|c| !c.is_empty()) {
+        Some(children) => {
+            for child in children {
+                default_regex(child, is_regex);
+            }
+        }
+        None if is_regex && s.regex.is_none() => s.regex = Some(Vec::new()),
+        None => {}
+    }
+}
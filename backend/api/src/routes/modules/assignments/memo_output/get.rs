@@ -8,8 +8,7 @@ use axum::{
 use db::models::{assignment_memo_output, assignment_task};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 use serde::Serialize;
-use tokio::fs as tokio_fs;
-use util::{execution_config::ExecutionConfig, paths::storage_root, state::AppState};
+use util::{execution_config::ExecutionConfig, state::AppState, storage::storage};
 
 #[derive(Serialize)]
 struct MemoSubsection {
@@ -91,21 +90,16 @@ pub async fn get_all_memo_outputs(
 
     // Load separator from execution config (once)
     let separator = match ExecutionConfig::get_execution_config(module_id, assignment_id) {
-        Ok(config) => config.marking.deliminator,
+        Ok(config) => config.marking.delimiter,
         Err(_) => "###".to_string(),
     };
 
     let mut results = Vec::new();
 
     for memo in memo_outputs {
-        let full_path = storage_root().join(&memo.path);
-        if !full_path.is_file() {
-            continue;
-        }
-
-        let raw_content = match tokio_fs::read_to_string(&full_path).await {
-            Ok(c) => c,
-            Err(_) => continue,
+        let raw_content = match storage().read(&memo.path).await.map(String::from_utf8) {
+            Ok(Ok(c)) => c,
+            _ => continue,
         };
 
         // Parse output
@@ -1,11 +1,11 @@
-use crate::auth::guards::{allow_assistant_lecturer, allow_tutor};
+use crate::auth::guards::{allow_assistant_lecturer, allow_tutor, deny_after_moderation_sign_off};
 use axum::{
     Router,
     middleware::from_fn_with_state,
     routing::{get, post},
 };
 use get::get_all_memo_outputs;
-use post::generate_memo_output;
+use post::{generate_memo_output, generate_memo_output_for_task};
 use util::state::AppState;
 
 pub mod get;
@@ -16,15 +16,33 @@ pub mod post;
 ///
 /// Routes:
 /// - `POST /generate`      → Start async memo output generation for an assignment
+/// - `POST /generate/{task_id}` → Regenerate memo output for a single task only
 /// - `GET  /`              → Retrieve all memo outputs for an assignment
 pub fn memo_output_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
         .route(
             "/generate",
-            post(generate_memo_output).route_layer(from_fn_with_state(
-                app_state.clone(),
-                allow_assistant_lecturer,
-            )),
+            post(generate_memo_output)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/generate/{task_id}",
+            post(generate_memo_output_for_task)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
         )
         .route(
             "/",
@@ -4,7 +4,7 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
 };
-use code_runner::create_memo_outputs_for_all_tasks;
+use code_runner::{create_memo_outputs_for_all_tasks, create_memo_outputs_for_task};
 use std::fs;
 use tracing::{error, info};
 use util::{
@@ -102,33 +102,8 @@ pub async fn generate_memo_output(
 ) -> (StatusCode, Json<ApiResponse<()>>) {
     let db = app_state.db();
 
-    // Use centralized helpers for directories
-    let memo_dir = memo_dir(module_id, assignment_id);
-    let memo_valid = memo_dir.is_dir()
-        && fs::read_dir(&memo_dir)
-            .map(|mut entries| entries.any(|e| e.ok().map(|f| f.path().is_file()).unwrap_or(false)))
-            .unwrap_or(false);
-
-    if !memo_valid {
-        return (
-            StatusCode::UNPROCESSABLE_ENTITY,
-            Json(ApiResponse::<()>::error(
-                "Required memo directory is missing or empty",
-            )),
-        );
-    }
-
-    let cfg_dir = config_dir(module_id, assignment_id);
-    let config_valid = cfg_dir.is_dir()
-        && fs::read_dir(&cfg_dir)
-            .map(|mut entries| entries.any(|e| e.ok().map(|f| f.path().is_file()).unwrap_or(false)))
-            .unwrap_or(false);
-
-    if !config_valid {
-        return (
-            StatusCode::UNPROCESSABLE_ENTITY,
-            Json(ApiResponse::<()>::error("Config file not valid")),
-        );
+    if let Some(rejection) = validate_memo_inputs(module_id, assignment_id) {
+        return rejection;
     }
 
     match create_memo_outputs_for_all_tasks(db, assignment_id).await {
@@ -152,68 +127,182 @@ pub async fn generate_memo_output(
                 assignment_id, err_str
             );
 
-            // Map low-level error strings to user-friendly messages + appropriate status codes.
-            let (status, message) = if err_str.contains("Config validation failed")
-                || err_str.contains("Failed to load execution config")
-            {
-                (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "Your configuration is missing or invalid. Open the Config step and save settings before generating memo output.",
-                )
-            } else if err_str.contains("memo")
-                && (err_str.contains("Required directory")
-                    || err_str.contains("Missing directory")
-                    || err_str.contains("No .zip"))
-            {
-                (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "Memo archive (.zip) not found. Upload your Memo files under Files & Resources.",
-                )
-            } else if err_str.contains("makefile")
-                && (err_str.contains("Required directory")
-                    || err_str.contains("Missing directory")
-                    || err_str.contains("No .zip"))
-            {
-                (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "Makefile archive (.zip) not found. Upload a Makefile under Files & Resources.",
-                )
-            } else if err_str.contains("main")
-                && (err_str.contains("Required directory")
-                    || err_str.contains("Missing directory")
-                    || err_str.contains("No .zip"))
-            {
-                (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "Main files (.zip) not found. In manual mode, upload Main Files; in GATLAM mode, ensure the Interpreter is configured.",
-                )
-            } else if err_str.contains("No tasks are defined") || err_str.contains("No tasks found")
-            {
-                (
-                    StatusCode::UNPROCESSABLE_ENTITY,
-                    "No tasks are defined yet. Add at least one task and try again.",
-                )
-            } else if err_str.contains("Failed to send request to code_manager") {
-                (
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    "The runner service is unavailable. Please try again shortly or contact support if it persists.",
-                )
-            } else if err_str.contains("code_manager responded with error")
-                || err_str.contains("Failed to parse response JSON")
-                || err_str.contains("Response missing 'output'")
-            {
-                (
-                    StatusCode::BAD_GATEWAY,
-                    "The runner failed to execute your tasks. Check your build/commands and execution limits, then retry.",
-                )
-            } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Failed to generate memo output. Please retry; if it continues, contact support.",
-                )
-            };
+            let (status, message) = memo_error_response(&err_str);
+            (status, Json(ApiResponse::<()>::error(message)))
+        }
+    }
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/memo_output/generate/{task_id}
+///
+/// Regenerate the memo output of a **single task**. Accessible to users with Assistant Lecturer,
+/// Lecturer or Admin roles assigned to the module.
+///
+/// Unlike `/generate`, this leaves every other task's memo output in place. The task is run first
+/// and its previous memo output is only replaced once the new one is ready, so marking can keep
+/// using the old output while the run is in progress.
+///
+/// ### Path Parameters
+/// - `module_id` (i64): The ID of the module containing the assignment
+/// - `assignment_id` (i64): The ID of the assignment
+/// - `task_id` (i64): The ID of the task whose memo output should be regenerated
+///
+/// ### Example Request
+/// ```bash
+/// curl -X POST http://localhost:3000/api/modules/1/assignments/2/memo_output/generate/5 \
+///   -H "Authorization: Bearer <token>"
+/// ```
+///
+/// ### Success Response (200 OK)
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Memo output regenerated for task",
+///   "data": null
+/// }
+/// ```
+///
+/// ### Error Responses
+/// - **422** – Memo/config missing, or the task is a coverage task (no memo output)
+/// - **404** – Task does not belong to the assignment
+/// - **503 / 502** – Runner unavailable or failed (same mapping as `/generate`)
+pub async fn generate_memo_output_for_task(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, task_id)): Path<(i64, i64, i64)>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    let db = app_state.db();
+
+    if let Some(rejection) = validate_memo_inputs(module_id, assignment_id) {
+        return rejection;
+    }
 
+    match create_memo_outputs_for_task(db, assignment_id, task_id).await {
+        Ok(_) => {
+            info!(
+                "Memo output regenerated for task {} in assignment {}",
+                task_id, assignment_id
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::<()>::success(
+                    (),
+                    "Memo output regenerated for task",
+                )),
+            )
+        }
+        Err(err_str) => {
+            error!(
+                "Memo output regeneration failed for task {} in assignment {}: {}",
+                task_id, assignment_id, err_str
+            );
+            let (status, message) = memo_error_response(&err_str);
             (status, Json(ApiResponse::<()>::error(message)))
         }
     }
 }
+
+/// Checks that the memo and config directories are present and non-empty before a run.
+fn validate_memo_inputs(
+    module_id: i64,
+    assignment_id: i64,
+) -> Option<(StatusCode, Json<ApiResponse<()>>)> {
+    // Use centralized helpers for directories
+    let memo_dir = memo_dir(module_id, assignment_id);
+    let memo_valid = memo_dir.is_dir()
+        && fs::read_dir(&memo_dir)
+            .map(|mut entries| entries.any(|e| e.ok().map(|f| f.path().is_file()).unwrap_or(false)))
+            .unwrap_or(false);
+
+    if !memo_valid {
+        return Some((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::<()>::error(
+                "Required memo directory is missing or empty",
+            )),
+        ));
+    }
+
+    let cfg_dir = config_dir(module_id, assignment_id);
+    let config_valid = cfg_dir.is_dir()
+        && fs::read_dir(&cfg_dir)
+            .map(|mut entries| entries.any(|e| e.ok().map(|f| f.path().is_file()).unwrap_or(false)))
+            .unwrap_or(false);
+
+    if !config_valid {
+        return Some((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::<()>::error("Config file not valid")),
+        ));
+    }
+
+    None
+}
+
+/// Map low-level error strings to user-friendly messages + appropriate status codes.
+fn memo_error_response(err_str: &str) -> (StatusCode, &'static str) {
+    if err_str.contains("Config validation failed")
+        || err_str.contains("Failed to load execution config")
+    {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Your configuration is missing or invalid. Open the Config step and save settings before generating memo output.",
+        )
+    } else if err_str.contains("memo")
+        && (err_str.contains("Required directory")
+            || err_str.contains("Missing directory")
+            || err_str.contains("No .zip"))
+    {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Memo archive (.zip) not found. Upload your Memo files under Files & Resources.",
+        )
+    } else if err_str.contains("makefile")
+        && (err_str.contains("Required directory")
+            || err_str.contains("Missing directory")
+            || err_str.contains("No .zip"))
+    {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Makefile archive (.zip) not found. Upload a Makefile under Files & Resources.",
+        )
+    } else if err_str.contains("main")
+        && (err_str.contains("Required directory")
+            || err_str.contains("Missing directory")
+            || err_str.contains("No .zip"))
+    {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Main files (.zip) not found. In manual mode, upload Main Files; in GATLAM mode, ensure the Interpreter is configured.",
+        )
+    } else if err_str.contains("No tasks are defined") || err_str.contains("No tasks found") {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "No tasks are defined yet. Add at least one task and try again.",
+        )
+    } else if err_str.contains("Failed to send request to code_manager") {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The runner service is unavailable. Please try again shortly or contact support if it persists.",
+        )
+    } else if err_str.contains("code_manager responded with error")
+        || err_str.contains("Failed to parse response JSON")
+        || err_str.contains("Response missing 'output'")
+    {
+        (
+            StatusCode::BAD_GATEWAY,
+            "The runner failed to execute your tasks. Check your build/commands and execution limits, then retry.",
+        )
+    } else if err_str.contains("Coverage tasks do not produce memo output") {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Coverage tasks don't have memo output; only normal and valgrind tasks can be regenerated.",
+        )
+    } else if err_str.contains("not found in assignment") {
+        (StatusCode::NOT_FOUND, "Task not found in this assignment.")
+    } else {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to generate memo output. Please retry; if it continues, contact support.",
+        )
+    }
+}
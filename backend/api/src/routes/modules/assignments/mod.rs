@@ -5,14 +5,16 @@
 //! Routes include:
 //! - Create, read, update, delete assignments (single and bulk)
 //! - Open/close assignments
+//! - Clone, export and import assignment bundles
 //! - Assignment stats and readiness checks
-//! - Nested routes for tasks, config, memo output, mark allocation, submissions, files, interpreter, tickets, plagiarism, grades, and starter packs
+//! - Nested routes for tasks, config, memo output, mark allocation, submissions, files, interpreter, tickets, groups, extensions, exams, regrades, moderation, rubric, plagiarism, grades, starter packs, debug terminals, and GA run history
 //!
 //! Access control is enforced via middleware guards for lecturers, assistants, and assigned users.
 
 use crate::{
     auth::guards::{
         allow_assignment_access, allow_assistant_lecturer, allow_ready_assignment, allow_student,
+        allow_tutor,
     },
     routes::modules::assignments::{post::verify_assignment_pin, statistics::statistics_routes},
 };
@@ -21,40 +23,61 @@ use axum::{
     middleware::from_fn_with_state,
     routing::{delete, get, post, put},
 };
+use bundle::{
+    get::export_assignment,
+    post::{clone_assignment, import_assignment},
+};
 use config::config_routes;
 use delete::{bulk_delete_assignments, delete_assignment};
+use exam::exam_routes;
+use extensions::extension_routes;
 use files::files_routes;
+use ga::ga_routes;
 use get::{get_assignment, get_assignment_readiness, get_assignments};
 use grades::grade_routes;
+use groups::group_routes;
 use interpreter::interpreter_routes;
 use mark_allocator::mark_allocator_routes;
 use memo_output::memo_output_routes;
+use moderation::moderation_routes;
 use overwrite_files::overwrite_file_routes;
 use plagiarism::plagiarism_routes;
-use post::create_assignment;
+use post::{create_assignment, restore_assignment};
 use put::{bulk_update_assignments, close_assignment, edit_assignment, open_assignment};
+use regrades::regrade_routes;
+use rubric::rubric_routes;
 use submissions::submission_routes;
 use tasks::tasks_routes;
+use terminal::terminal_routes;
 use tickets::ticket_routes;
 use util::state::AppState;
 
+pub mod bundle;
 pub mod common;
 pub mod config;
 pub mod delete;
+pub mod exam;
+pub mod extensions;
 pub mod files;
+pub mod ga;
 pub mod get;
 pub mod grades;
+pub mod groups;
 pub mod interpreter;
 pub mod mark_allocator;
 pub mod memo_output;
+pub mod moderation;
 pub mod overwrite_files;
 pub mod plagiarism;
 pub mod post;
 pub mod put;
+pub mod regrades;
+pub mod rubric;
 pub mod starter;
 pub mod statistics;
 pub mod submissions;
 pub mod tasks;
+pub mod terminal;
 pub mod tickets;
 
 /// Expects a module ID.
@@ -72,7 +95,11 @@ pub mod tickets;
 /// - `PUT    /assignments/:assignment_id/open`           → Open assignment (requires lecturer, only if currently Ready, Closed, or Archived)
 /// - `PUT    /assignments/:assignment_id/close`          → Close assignment (requires lecturer, only if currently Open)
 /// - `DELETE /assignments/:assignment_id`                → Delete assignment (requires lecturer)
+/// - `POST   /assignments/:assignment_id/restore`        → Restore a deleted assignment (requires lecturer)
 /// - `GET    /assignments/:assignment_id/readiness`      → Assignment readiness (lecturer or admin only)
+/// - `POST   /assignments/import`                        → Create an assignment from a bundle zip (requires lecturer)
+/// - `GET    /assignments/:assignment_id/export`         → Download the assignment as a bundle zip (requires lecturer)
+/// - `POST   /assignments/:assignment_id/clone`          → Copy the assignment, optionally into another module (requires lecturer)
 ///
 /// Nested routes:
 /// - Tasks routes                  → `tasks_routes`
@@ -83,11 +110,19 @@ pub mod tickets;
 /// - Files routes                  → `files_routes`
 /// - Interpreter routes            → `interpreter_routes`
 /// - Tickets routes                → `ticket_routes`
+/// - Groups routes                 → `group_routes`
+/// - Extensions routes             → `extension_routes`
+/// - Exam routes                   → `exam_routes`
+/// - Regrade request routes        → `regrade_routes`
+/// - Moderation routes             → `moderation_routes`
+/// - Rubric routes                 → `rubric_routes`
 /// - Plagiarism routes             → `plagiarism_routes`
 /// - Grades routes                 → `grade_routes`
 /// - Overwrite files routes        → `overwrite_file_routes`
 /// - Statistics routes             → `statistics_routes`
 /// - Starter routes                → `starter_routes`
+/// - Terminal routes               → `terminal_routes`
+/// - GA routes                     → `ga_routes`
 pub fn assignment_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
         .route(
@@ -124,6 +159,34 @@ pub fn assignment_routes(app_state: AppState) -> Router<AppState> {
                 allow_assistant_lecturer,
             )),
         )
+        .route(
+            "/import",
+            post(import_assignment).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/{assignment_id}/export",
+            get(export_assignment).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/{assignment_id}/clone",
+            post(clone_assignment).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/{assignment_id}/restore",
+            post(restore_assignment).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
         .route(
             "/{assignment_id}/open",
             put(open_assignment)
@@ -180,7 +243,7 @@ pub fn assignment_routes(app_state: AppState) -> Router<AppState> {
         )
         .nest(
             "/{assignment_id}/config",
-            config_routes().layer(from_fn_with_state(
+            config_routes(app_state.clone()).layer(from_fn_with_state(
                 app_state.clone(),
                 allow_assistant_lecturer,
             )),
@@ -191,7 +254,7 @@ pub fn assignment_routes(app_state: AppState) -> Router<AppState> {
         )
         .nest(
             "/{assignment_id}/mark_allocator",
-            mark_allocator_routes().route_layer(from_fn_with_state(
+            mark_allocator_routes(app_state.clone()).route_layer(from_fn_with_state(
                 app_state.clone(),
                 allow_assistant_lecturer,
             )),
@@ -219,6 +282,56 @@ pub fn assignment_routes(app_state: AppState) -> Router<AppState> {
                     allow_assignment_access,
                 )),
         )
+        .nest(
+            "/{assignment_id}/groups",
+            group_routes(app_state.clone())
+                .route_layer(from_fn_with_state(app_state.clone(), allow_student))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assignment_access,
+                )),
+        )
+        .nest(
+            "/{assignment_id}/extensions",
+            extension_routes(app_state.clone())
+                .route_layer(from_fn_with_state(app_state.clone(), allow_student))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assignment_access,
+                )),
+        )
+        .nest(
+            "/{assignment_id}/exam",
+            exam_routes(app_state.clone())
+                .route_layer(from_fn_with_state(app_state.clone(), allow_student))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assignment_access,
+                )),
+        )
+        .nest(
+            "/{assignment_id}/regrades",
+            regrade_routes(app_state.clone())
+                .route_layer(from_fn_with_state(app_state.clone(), allow_student))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assignment_access,
+                )),
+        )
+        .nest(
+            "/{assignment_id}/moderation",
+            moderation_routes(app_state.clone())
+                .route_layer(from_fn_with_state(app_state.clone(), allow_tutor)),
+        )
+        .nest(
+            "/{assignment_id}/rubric",
+            rubric_routes(app_state.clone())
+                .route_layer(from_fn_with_state(app_state.clone(), allow_student))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assignment_access,
+                )),
+        )
         .nest(
             "/{assignment_id}/plagiarism",
             plagiarism_routes()
@@ -248,4 +361,9 @@ pub fn assignment_routes(app_state: AppState) -> Router<AppState> {
                 allow_assistant_lecturer,
             )),
         )
+        .nest(
+            "/{assignment_id}/terminal",
+            terminal_routes(app_state.clone()),
+        )
+        .nest("/{assignment_id}/ga", ga_routes(app_state.clone()))
 }
@@ -0,0 +1,122 @@
+use crate::auth::guards::user_has_any_role;
+use db::models::{
+    moderation::Model as ModerationModel,
+    moderation_sample::{DiscrepancyStats, Model as SampleModel},
+    user,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default percentage points a second mark may differ from the first by and still agree.
+pub const DEFAULT_TOLERANCE: f64 = 10.0;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SampleResponse {
+    pub id: i64,
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub submission_id: i64,
+    pub marker_id: Option<i64>,
+    pub marker_username: Option<String>,
+    /// Left out for second markers, so they mark independently.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_mark: Option<f64>,
+    pub second_mark: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difference: Option<f64>,
+    pub comment: Option<String>,
+    pub marked_at: Option<String>,
+    pub created_at: String,
+}
+
+impl SampleResponse {
+    pub fn new(s: SampleModel, usernames: &HashMap<i64, String>, reveal_first: bool) -> Self {
+        Self {
+            id: s.id,
+            user_id: s.user_id,
+            username: usernames.get(&s.user_id).cloned(),
+            submission_id: s.submission_id,
+            marker_id: s.marker_id,
+            marker_username: s.marker_id.and_then(|id| usernames.get(&id).cloned()),
+            first_mark: reveal_first.then_some(s.first_mark),
+            second_mark: s.second_mark,
+            difference: if reveal_first { s.difference() } else { None },
+            comment: s.comment,
+            marked_at: s.marked_at.map(|t| t.to_rfc3339()),
+            created_at: s.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModerationResponse {
+    pub signed_off: bool,
+    pub signed_off_by: Option<i64>,
+    pub signed_off_at: Option<String>,
+    pub samples: Vec<SampleResponse>,
+    /// Only for moderators (assistant lecturer or higher).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statistics: Option<DiscrepancyStats>,
+}
+
+impl ModerationResponse {
+    /// The assignment's moderation as `viewer` may see it: moderators see every sample with
+    /// its first mark and the discrepancy statistics; second markers see only their own
+    /// samples, without first marks.
+    pub async fn load(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        viewer_id: i64,
+        is_moderator: bool,
+        tolerance: f64,
+    ) -> Result<Self, DbErr> {
+        let moderation = ModerationModel::for_assignment(db, assignment_id).await?;
+        let mut samples = SampleModel::list_for_assignment(db, assignment_id).await?;
+        let statistics = is_moderator.then(|| DiscrepancyStats::compute(&samples, tolerance));
+        if !is_moderator {
+            samples.retain(|s| s.marker_id == Some(viewer_id));
+        }
+        let usernames = usernames(db, &samples).await?;
+        let signed_off_at = moderation.as_ref().and_then(|m| m.signed_off_at);
+        Ok(Self {
+            signed_off: signed_off_at.is_some(),
+            signed_off_by: moderation.and_then(|m| m.signed_off_by),
+            signed_off_at: signed_off_at.map(|t| t.to_rfc3339()),
+            samples: samples
+                .into_iter()
+                .map(|s| SampleResponse::new(s, &usernames, is_moderator))
+                .collect(),
+            statistics,
+        })
+    }
+}
+
+/// Usernames of the samples' students and markers, keyed by user id.
+pub async fn usernames(
+    db: &DatabaseConnection,
+    samples: &[SampleModel],
+) -> Result<HashMap<i64, String>, DbErr> {
+    let ids: Vec<i64> = samples
+        .iter()
+        .flat_map(|s| [Some(s.user_id), s.marker_id])
+        .flatten()
+        .collect();
+    Ok(user::Entity::find()
+        .filter(user::Column::Id.is_in(ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect())
+}
+
+/// Whether the user runs moderation for the module: an admin, lecturer or assistant lecturer.
+pub async fn is_moderator(
+    db: &DatabaseConnection,
+    module_id: i64,
+    user_id: i64,
+    admin: bool,
+) -> bool {
+    admin || user_has_any_role(db, user_id, module_id, &["Lecturer", "AssistantLecturer"]).await
+}
@@ -0,0 +1,96 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{
+    moderation::Model as ModerationModel,
+    moderation_sample::{Column as SampleColumn, Entity as SampleEntity},
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use util::state::AppState;
+
+/// DELETE /api/modules/{module_id}/assignments/{assignment_id}/moderation/samples/{sample_id}
+///
+/// Removes a student from the moderation sample. Requires assistant lecturer or higher, and is
+/// refused once the moderation is signed off.
+///
+/// ### Responses
+/// - `200 OK` — Removed
+/// - `404 Not Found` — No such sample in this assignment
+/// - `409 Conflict` — The moderation has been signed off
+pub async fn remove_sample(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id, sample_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    match SampleEntity::delete_many()
+        .filter(SampleColumn::Id.eq(sample_id))
+        .filter(SampleColumn::AssignmentId.eq(assignment_id))
+        .exec(app_state.db())
+        .await
+    {
+        Ok(res) if res.rows_affected > 0 => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "Sample removed")),
+        )
+            .into_response(),
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Sample not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to remove sample")),
+        )
+            .into_response(),
+    }
+}
+
+/// DELETE /api/modules/{module_id}/assignments/{assignment_id}/moderation/sign-off
+///
+/// Withdraws the moderation sign-off so the assignment's grades can change again. The samples
+/// and their marks are kept. Requires lecturer.
+///
+/// ### Responses
+/// - `200 OK` — Reopened
+/// - `409 Conflict` — The moderation is not signed off
+pub async fn reopen(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    match ModerationModel::is_locked(db, assignment_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error("Moderation is not signed off")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve moderation")),
+            )
+                .into_response();
+        }
+    }
+
+    match ModerationModel::reopen(db, assignment_id).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "Moderation reopened")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to reopen moderation")),
+        )
+            .into_response(),
+    }
+}
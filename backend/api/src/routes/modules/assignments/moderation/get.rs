@@ -0,0 +1,103 @@
+use super::common::{DEFAULT_TOLERANCE, ModerationResponse, is_moderator};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationQuery {
+    /// Percentage points two marks may differ by and still agree (default 10).
+    pub tolerance: Option<f64>,
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/moderation
+///
+/// The assignment's moderation: the sampled submissions, their first and second marks, the
+/// discrepancy statistics and whether it has been signed off.
+///
+/// Lecturers and assistant lecturers see everything. Other staff see only the samples they were
+/// asked to second-mark, without the first marks or statistics, so their marking stays
+/// independent.
+///
+/// ### Query Parameters
+/// - `tolerance` (optional, default 10): Percentage points a second mark may differ from the
+///   first by before it counts in `outside_tolerance`
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "signed_off": false,
+///     "signed_off_by": null,
+///     "signed_off_at": null,
+///     "samples": [
+///       {
+///         "id": 1,
+///         "user_id": 42,
+///         "username": "u12345678",
+///         "submission_id": 191,
+///         "marker_id": 7,
+///         "marker_username": "tutor1",
+///         "first_mark": 64.0,
+///         "second_mark": 70.0,
+///         "difference": 6.0,
+///         "comment": "Generous on task 2",
+///         "marked_at": "2025-10-16T08:00:00+00:00",
+///         "created_at": "2025-10-15T08:00:00+00:00"
+///       }
+///     ],
+///     "statistics": {
+///       "sampled": 1,
+///       "marked": 1,
+///       "mean_difference": 6.0,
+///       "mean_absolute_difference": 6.0,
+///       "max_absolute_difference": 6.0,
+///       "std_dev": 0.0,
+///       "tolerance": 10.0,
+///       "outside_tolerance": 0
+///     }
+///   },
+///   "message": "Moderation retrieved"
+/// }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The moderation
+/// - `400 Bad Request` — `tolerance` is negative
+pub async fn get_moderation(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Query(query): Query<ModerationQuery>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let tolerance = query.tolerance.unwrap_or(DEFAULT_TOLERANCE);
+    if !tolerance.is_finite() || tolerance < 0.0 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("tolerance must not be negative")),
+        )
+            .into_response();
+    }
+
+    let moderator = is_moderator(db, module_id, claims.sub, claims.admin).await;
+    match ModerationResponse::load(db, assignment_id, claims.sub, moderator, tolerance).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(response, "Moderation retrieved")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve moderation")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,91 @@
+//! Moderation routes module.
+//!
+//! Provides the `/moderation` route group: a sample of students' counted submissions is
+//! second-marked independently, the discrepancies between the first and second marks are
+//! summarised, and a lecturer signs the moderation off, which locks the assignment's grades.
+//!
+//! Routes include:
+//! - View the moderation (moderators see everything; second markers only their own samples,
+//!   without the first marks)
+//! - Sample students and remove samples (assistant lecturer or higher)
+//! - Record second marks (the sample's marker)
+//! - Sign off and reopen (lecturer)
+//!
+//! While signed off, the grade-changing submission, rubric and regrade routes are refused by
+//! the `deny_after_moderation_sign_off` guard, as are config, mark allocator, memo output and
+//! extension changes, since grades are computed from those when read.
+
+use crate::auth::guards::{
+    allow_assistant_lecturer, allow_lecturer, deny_after_moderation_sign_off,
+};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{delete, get, post, put},
+};
+use delete::{remove_sample, reopen};
+use get::get_moderation;
+use post::{add_samples, sign_off};
+use put::record_second_mark;
+use util::state::AppState;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod post;
+pub mod put;
+
+/// Builds and returns the `/moderation` route group.
+///
+/// Routes:
+/// - `GET    /moderation`                       → The moderation (tutor or higher)
+/// - `POST   /moderation/samples`               → Sample students (assistant lecturer or higher)
+/// - `PUT    /moderation/samples/{sample_id}`   → Record a second mark (the sample's marker)
+/// - `DELETE /moderation/samples/{sample_id}`   → Remove a sample (assistant lecturer or higher)
+/// - `POST   /moderation/sign-off`              → Sign off, locking grades (lecturer)
+/// - `DELETE /moderation/sign-off`              → Reopen (lecturer)
+///
+/// Changing the sample is refused once the moderation is signed off.
+pub fn moderation_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_moderation))
+        .route(
+            "/samples",
+            post(add_samples)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/samples/{sample_id}",
+            put(record_second_mark).route_layer(from_fn_with_state(
+                app_state.clone(),
+                deny_after_moderation_sign_off,
+            )),
+        )
+        .route(
+            "/samples/{sample_id}",
+            delete(remove_sample)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/sign-off",
+            post(sign_off).route_layer(from_fn_with_state(app_state.clone(), allow_lecturer)),
+        )
+        .route(
+            "/sign-off",
+            delete(reopen).route_layer(from_fn_with_state(app_state.clone(), allow_lecturer)),
+        )
+}
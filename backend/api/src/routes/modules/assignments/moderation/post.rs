@@ -0,0 +1,251 @@
+use super::common::{DEFAULT_TOLERANCE, ModerationResponse, SampleResponse, usernames};
+use crate::{auth::AuthUser, auth::guards::user_has_any_role, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::grade::{GradeComputationError, GradeComputationOptions, compute_assignment_grades};
+use db::models::{
+    moderation::Model as ModerationModel,
+    moderation_sample::{Model as SampleModel, SampleCandidate, spread_sample},
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct SampleRequest {
+    /// Staff member (tutor or higher in the module) who second-marks the sample.
+    pub marker_id: i64,
+    /// Number of students to sample. Takes precedence over `percentage`.
+    pub size: Option<usize>,
+    /// Percentage of the graded students to sample (default 10, at least one student).
+    pub percentage: Option<f64>,
+    /// Sample exactly these students instead of picking them.
+    pub user_ids: Option<Vec<i64>>,
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/moderation/samples
+///
+/// Adds students to the moderation sample for `marker_id` to second-mark. Each sample records the
+/// student's counted submission and its current grade as the first mark.
+///
+/// Unless `user_ids` is given, the students are picked from those with a grade who are not
+/// sampled yet, spread evenly from the lowest mark to the highest. Students already in the
+/// sample are skipped.
+///
+/// Requires assistant lecturer or higher, and is refused once the moderation is signed off.
+///
+/// ### Request Body
+/// ```json
+/// { "marker_id": 7, "percentage": 10 }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — The samples added
+/// - `400 Bad Request` — The marker is not staff in the module, the size or percentage is
+///   invalid, or a `user_ids` entry has no grade for the assignment
+/// - `404 Not Found` — No such assignment
+/// - `409 Conflict` — The moderation has been signed off
+pub async fn add_samples(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Json(req): Json<SampleRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if !user_has_any_role(
+        db,
+        req.marker_id,
+        module_id,
+        &["Lecturer", "AssistantLecturer", "Tutor"],
+    )
+    .await
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "The marker must be a tutor or lecturer in this module",
+            )),
+        )
+            .into_response();
+    }
+    if req.size == Some(0)
+        || req
+            .percentage
+            .is_some_and(|p| !p.is_finite() || p <= 0.0 || p > 100.0)
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "size must be positive and percentage in (0, 100]",
+            )),
+        )
+            .into_response();
+    }
+
+    let grades = match compute_assignment_grades(
+        db,
+        module_id,
+        assignment_id,
+        GradeComputationOptions::default(),
+    )
+    .await
+    {
+        Ok(result) => result.grades,
+        Err(GradeComputationError::AssignmentNotFound) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Assignment not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            eprintln!("add_samples: compute error: {e:?}");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to compute grades")),
+            )
+                .into_response();
+        }
+    };
+    let graded: Vec<SampleCandidate> = grades
+        .into_iter()
+        .map(|g| SampleCandidate {
+            user_id: g.user.id,
+            submission_id: g.submission.id,
+            mark: g.score_pct,
+        })
+        .collect();
+
+    let candidates = if let Some(user_ids) = &req.user_ids {
+        let wanted: HashSet<i64> = user_ids.iter().copied().collect();
+        let picked: Vec<SampleCandidate> = graded
+            .into_iter()
+            .filter(|c| wanted.contains(&c.user_id))
+            .collect();
+        if picked.len() != wanted.len() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "Some students have no grade for this assignment",
+                )),
+            )
+                .into_response();
+        }
+        picked
+    } else {
+        let sampled: HashSet<i64> = match SampleModel::list_for_assignment(db, assignment_id).await
+        {
+            Ok(samples) => samples.into_iter().map(|s| s.user_id).collect(),
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error("Failed to retrieve samples")),
+                )
+                    .into_response();
+            }
+        };
+        let size = req.size.unwrap_or_else(|| {
+            let pct = req.percentage.unwrap_or(10.0);
+            ((graded.len() as f64 * pct / 100.0).round() as usize).max(1)
+        });
+        let unsampled = graded
+            .into_iter()
+            .filter(|c| !sampled.contains(&c.user_id))
+            .collect();
+        spread_sample(unsampled, size)
+    };
+
+    let added = match SampleModel::add(db, assignment_id, req.marker_id, &candidates).await {
+        Ok(added) => added,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to add samples")),
+            )
+                .into_response();
+        }
+    };
+    let names = usernames(db, &added).await.unwrap_or_default();
+    let data: Vec<SampleResponse> = added
+        .into_iter()
+        .map(|s| SampleResponse::new(s, &names, true))
+        .collect();
+
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::success(data, "Samples added")),
+    )
+        .into_response()
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/moderation/sign-off
+///
+/// Signs off the assignment's moderation. From then on its grades are locked: submitting,
+/// remarking, resubmitting, deleting or ignoring submissions, rubric scoring and regrade
+/// responses are refused with `409 Conflict` until the sign-off is withdrawn.
+///
+/// Every sample must have been second-marked first. Requires lecturer.
+///
+/// ### Responses
+/// - `200 OK` — The signed-off moderation
+/// - `409 Conflict` — Already signed off, nothing sampled, or samples still to mark
+pub async fn sign_off(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let (locked, samples) = match (
+        ModerationModel::is_locked(db, assignment_id).await,
+        SampleModel::list_for_assignment(db, assignment_id).await,
+    ) {
+        (Ok(locked), Ok(samples)) => (locked, samples),
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve moderation")),
+            )
+                .into_response();
+        }
+    };
+    let refusal = if locked {
+        Some("Moderation is already signed off".to_string())
+    } else if samples.is_empty() {
+        Some("Nothing has been sampled for moderation".to_string())
+    } else {
+        let unmarked = samples.iter().filter(|s| s.second_mark.is_none()).count();
+        (unmarked > 0).then(|| format!("{unmarked} sample(s) still to be second-marked"))
+    };
+    if let Some(msg) = refusal {
+        return (StatusCode::CONFLICT, Json(ApiResponse::<()>::error(msg))).into_response();
+    }
+
+    if ModerationModel::sign_off(db, assignment_id, claims.sub)
+        .await
+        .is_err()
+    {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to sign off moderation")),
+        )
+            .into_response();
+    }
+
+    match ModerationResponse::load(db, assignment_id, claims.sub, true, DEFAULT_TOLERANCE).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(response, "Moderation signed off")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve moderation")),
+        )
+            .into_response(),
+    }
+}
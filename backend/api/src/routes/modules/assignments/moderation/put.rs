@@ -0,0 +1,105 @@
+use super::common::{SampleResponse, usernames};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::moderation_sample::{Entity as SampleEntity, Model as SampleModel};
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct SecondMarkRequest {
+    /// The second marker's percentage, 0–100.
+    pub mark: f64,
+    pub comment: Option<String>,
+}
+
+/// PUT /api/modules/{module_id}/assignments/{assignment_id}/moderation/samples/{sample_id}
+///
+/// Records the second marker's mark for a sample, replacing any earlier one. Only the sample's
+/// marker may record it, and not once the moderation is signed off.
+///
+/// ### Request Body
+/// ```json
+/// { "mark": 70, "comment": "Generous on task 2" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The updated sample (without its first mark)
+/// - `400 Bad Request` — `mark` is not between 0 and 100
+/// - `403 Forbidden` — The caller is not the sample's marker
+/// - `404 Not Found` — No such sample in this assignment
+/// - `409 Conflict` — The moderation has been signed off
+pub async fn record_second_mark(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id, sample_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<SecondMarkRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if !(0.0..=100.0).contains(&req.mark) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("mark must be between 0 and 100")),
+        )
+            .into_response();
+    }
+
+    let sample: SampleModel = match SampleEntity::find_by_id(sample_id).one(db).await {
+        Ok(Some(s)) if s.assignment_id == assignment_id => s,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Sample not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+    if sample.marker_id != Some(claims.sub) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "Only the sample's marker may second-mark it",
+            )),
+        )
+            .into_response();
+    }
+
+    let comment = req
+        .comment
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    match sample.record(db, req.mark, comment).await {
+        Ok(updated) => {
+            let names = usernames(db, std::slice::from_ref(&updated))
+                .await
+                .unwrap_or_default();
+            (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    SampleResponse::new(updated, &names, false),
+                    "Second mark recorded",
+                )),
+            )
+                .into_response()
+        }
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to record second mark")),
+        )
+            .into_response(),
+    }
+}
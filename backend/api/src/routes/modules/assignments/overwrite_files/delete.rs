@@ -56,7 +56,7 @@ pub async fn delete_task_overwrite_files(
     }
 
     for file in files {
-        if let Err(e) = file.delete_file_only() {
+        if let Err(e) = file.delete_file_only().await {
             eprintln!("Failed to delete file from disk: {:?}", e);
         }
 
@@ -41,7 +41,7 @@ pub async fn get_task_overwrite_files(
         None => return (StatusCode::NOT_FOUND, "Overwrite file not found").into_response(),
     };
 
-    let contents = match file.load_file() {
+    let contents = match file.load_file().await {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Failed to read file from disk: {:?}", e);
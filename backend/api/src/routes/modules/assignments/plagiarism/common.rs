@@ -0,0 +1,87 @@
+//! Responses shared by the plagiarism case lifecycle routes.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    plagiarism_case::Model as CaseModel, plagiarism_case_decision::Model as DecisionModel,
+    plagiarism_case_evidence::Model as EvidenceModel,
+};
+use sea_orm::{DatabaseConnection, DbErr};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct EvidenceResponse {
+    pub id: i64,
+    /// The case's stage at upload; `None` before it was reported.
+    pub stage: Option<String>,
+    pub filename: String,
+    pub size: i64,
+    pub uploaded_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<EvidenceModel> for EvidenceResponse {
+    fn from(e: EvidenceModel) -> Self {
+        Self {
+            id: e.id,
+            stage: e.stage.map(|s| s.to_string()),
+            filename: e.filename,
+            size: e.size,
+            uploaded_by: e.uploaded_by,
+            created_at: e.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecisionResponse {
+    pub id: i64,
+    pub from_stage: Option<String>,
+    pub to_stage: String,
+    pub decision: String,
+    pub outcome: Option<String>,
+    pub decided_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DecisionModel> for DecisionResponse {
+    fn from(d: DecisionModel) -> Self {
+        Self {
+            id: d.id,
+            from_stage: d.from_stage.map(|s| s.to_string()),
+            to_stage: d.to_stage.to_string(),
+            decision: d.decision,
+            outcome: d.outcome.map(|o| o.to_string()),
+            decided_by: d.decided_by,
+            created_at: d.created_at,
+        }
+    }
+}
+
+/// A case's misconduct process: its stage, the evidence attached and every decision so far.
+#[derive(Debug, Serialize)]
+pub struct CaseLifecycleResponse {
+    pub id: i64,
+    pub status: String,
+    /// `None` until the case is reported.
+    pub stage: Option<String>,
+    pub outcome: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub evidence: Vec<EvidenceResponse>,
+    pub decisions: Vec<DecisionResponse>,
+}
+
+impl CaseLifecycleResponse {
+    pub async fn load(db: &DatabaseConnection, case: CaseModel) -> Result<Self, DbErr> {
+        let evidence = EvidenceModel::list_for_case(db, case.id).await?;
+        let decisions = DecisionModel::list_for_case(db, case.id).await?;
+        Ok(Self {
+            id: case.id,
+            status: case.status.to_string(),
+            stage: case.stage.map(|s| s.to_string()),
+            outcome: case.outcome.map(|o| o.to_string()),
+            updated_at: case.updated_at,
+            evidence: evidence.into_iter().map(Into::into).collect(),
+            decisions: decisions.into_iter().map(Into::into).collect(),
+        })
+    }
+}
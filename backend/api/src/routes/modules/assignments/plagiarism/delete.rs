@@ -9,10 +9,15 @@ use axum::{
 use db::models::{
     moss_report::{Column as MossReportColumn, Entity as MossReportEntity},
     plagiarism_case::{Column as PlagiarismColumn, Entity as PlagiarismEntity},
+    plagiarism_case_evidence::{Column as EvidenceColumn, Entity as EvidenceEntity},
 };
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use sea_orm::{ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter, TransactionTrait};
 use serde::Deserialize;
-use util::{paths::moss_archive_dir, state::AppState};
+use util::{
+    paths::{moss_archive_dir, plagiarism_base_dir},
+    state::AppState,
+    storage::{delete_all, key_for, storage},
+};
 
 use crate::response::ApiResponse;
 
@@ -33,6 +38,7 @@ use crate::response::ApiResponse;
 /// - `200 OK` with success message when deletion is successful
 /// - `403 FORBIDDEN` if user lacks required permissions
 /// - `404 NOT FOUND` if specified plagiarism case doesn't exist
+/// - `409 CONFLICT` if the case has been reported (see `PATCH /{case_id}/stage`)
 /// - `500 INTERNAL SERVER ERROR` for database errors or deletion failures
 ///
 /// The response body follows a standardized JSON format with a success message.
@@ -66,16 +72,37 @@ use crate::response::ApiResponse;
 ///
 /// # Notes
 ///
-/// This operation is irreversible and permanently removes the plagiarism case record.
+/// This operation is irreversible and permanently removes the plagiarism case record, along with
+/// any evidence uploaded for it. Reported cases are kept as a record of the misconduct process.
 /// Only users with lecturer or assistant lecturer roles assigned to the module can perform this action.
 pub async fn delete_plagiarism_case(
     State(app_state): State<AppState>,
     Path((_, _, case_id)): Path<(i64, i64, i64)>,
 ) -> impl IntoResponse {
-    match PlagiarismEntity::delete_by_id(case_id)
-        .exec(app_state.db())
-        .await
-    {
+    let db = app_state.db();
+    match PlagiarismEntity::find_by_id(case_id).one(db).await {
+        Ok(Some(case)) if case.stage.is_some() => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(
+                    "Reported plagiarism cases cannot be deleted",
+                )),
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to delete plagiarism case: {}",
+                    e
+                ))),
+            );
+        }
+    }
+    let evidence = evidence_paths(db, &[case_id]).await;
+
+    match PlagiarismEntity::delete_by_id(case_id).exec(db).await {
         Ok(result) => {
             if result.rows_affected == 0 {
                 return (
@@ -83,6 +110,7 @@ pub async fn delete_plagiarism_case(
                     Json(ApiResponse::<()>::error("Plagiarism case not found")),
                 );
             }
+            remove_evidence_files(evidence).await;
 
             (
                 StatusCode::OK,
@@ -101,6 +129,25 @@ pub async fn delete_plagiarism_case(
     }
 }
 
+/// Storage keys of the evidence uploaded for `case_ids`, read before deleting the cases removes
+/// their evidence rows.
+async fn evidence_paths<C: ConnectionTrait>(db: &C, case_ids: &[i64]) -> Vec<String> {
+    EvidenceEntity::find()
+        .filter(EvidenceColumn::CaseId.is_in(case_ids.to_vec()))
+        .all(db)
+        .await
+        .map(|evidence| evidence.into_iter().map(|e| e.path).collect())
+        .unwrap_or_default()
+}
+
+async fn remove_evidence_files(paths: Vec<String>) {
+    for path in paths {
+        if let Err(e) = storage().delete(&path).await {
+            tracing::warn!("Plagiarism: failed to remove evidence file {path}: {e}");
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BulkDeletePayload {
     case_ids: Vec<i64>,
@@ -127,6 +174,7 @@ pub struct BulkDeletePayload {
 /// - `200 OK` with success message and count of deleted cases
 /// - `400 BAD REQUEST` for invalid payload or missing cases
 /// - `403 FORBIDDEN` if user lacks required permissions
+/// - `409 CONFLICT` if any of the cases has been reported
 /// - `500 INTERNAL SERVER ERROR` for database errors or deletion failures
 ///
 /// The response body follows a standardized JSON format with a success message.
@@ -178,6 +226,7 @@ pub struct BulkDeletePayload {
 ///
 /// - This operation is atomic - either all cases are deleted or none
 /// - Returns an error if any specified case doesn't exist or belongs to a different assignment
+/// - Reported cases cannot be deleted
 /// - Only users with lecturer or assistant lecturer roles assigned to the module can perform this action
 pub async fn bulk_delete_plagiarism_cases(
     State(app_state): State<AppState>,
@@ -237,6 +286,22 @@ pub async fn bulk_delete_plagiarism_cases(
         );
     }
 
+    let reported: Vec<i64> = existing_cases
+        .iter()
+        .filter(|c| c.stage.is_some())
+        .map(|c| c.id)
+        .collect();
+    if !reported.is_empty() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(format!(
+                "Reported plagiarism cases cannot be deleted: {:?}",
+                reported
+            ))),
+        );
+    }
+    let evidence = evidence_paths(&txn, &payload.case_ids).await;
+
     let delete_result = match PlagiarismEntity::delete_many()
         .filter(PlagiarismColumn::Id.is_in(payload.case_ids))
         .exec(&txn)
@@ -263,6 +328,7 @@ pub async fn bulk_delete_plagiarism_cases(
             ))),
         );
     }
+    remove_evidence_files(evidence).await;
 
     (
         StatusCode::OK,
@@ -324,16 +390,14 @@ pub async fn delete_moss_report(
     // 2) Best-effort: remove archive folder if present (we use report_id as the archive id)
     //    NOTE: this assumes archives are stored under <...>/moss_archives/{report_id}/archive.zip
     let archive_dir = moss_archive_dir(module_id, assignment_id, &report_id.to_string());
-    if archive_dir.exists() {
-        if let Err(e) = fs::remove_dir_all(&archive_dir) {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<()>::error(format!(
-                    "Failed to delete report archive: {e}"
-                ))),
-            )
-                .into_response();
-        }
+    if let Err(e) = delete_all(&key_for(&archive_dir)).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to delete report archive: {e}"
+            ))),
+        )
+            .into_response();
     }
 
     // 3) Delete the DB row
@@ -362,3 +426,34 @@ pub async fn delete_moss_report(
             .into_response(),
     }
 }
+
+/// DELETE /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/base-files
+///
+/// Removes every starter/skeleton file uploaded for plagiarism runs. Later runs only exclude
+/// the assignment's spec files again.
+///
+/// # Responses
+/// - 200 OK — base files removed (also when there were none)
+/// - 500 Internal Server Error — failed to delete the folder
+pub async fn clear_plagiarism_base_files(
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let dir = plagiarism_base_dir(module_id, assignment_id);
+    if dir.exists()
+        && let Err(e) = fs::remove_dir_all(&dir)
+    {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to delete base files: {e}"
+            ))),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::<()>::success_without_data(
+            "Base files removed successfully",
+        )),
+    )
+}
@@ -1,28 +1,44 @@
+use crate::auth::{decode_match_page_link, generate_match_page_link};
 use crate::response::ApiResponse;
 use axum::{
     Json,
     body::Body,
     extract::{Path, Query, State},
-    http::{HeaderMap, HeaderValue, StatusCode},
-    response::IntoResponse,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
 };
-use db::models::moss_report::Entity as MossReportEntity;
+use db::models::moss_report::{self, Entity as MossReportEntity};
 use db::models::{
     assignment_submission::{self, Entity as SubmissionEntity},
     plagiarism_case::{self, Entity as PlagiarismEntity, Status},
+    plagiarism_case_evidence::Entity as EvidenceEntity,
+    plagiarism_match::{self, Entity as PlagiarismMatchEntity},
+    plagiarism_report,
     user::{self, Entity as UserEntity},
 };
+use db::soft_delete::SoftDelete;
+use moss_parser::FileMatchRow;
+use moss_parser::graph::{GraphEdge, GraphExportOptions, GraphFormat, export_graph};
 use sea_orm::{
     ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
     QueryTrait,
+    sea_query::{Expr, Func},
 };
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::collections::HashMap;
+use std::io::Read;
 use std::str::FromStr;
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
-use util::{paths::moss_archive_zip_path, state::AppState};
+use util::{
+    paths::{moss_archive_zip_path, moss_matches_dir, plagiarism_base_dir},
+    state::AppState,
+    storage::{key_for, storage},
+};
+
+use super::common::CaseLifecycleResponse;
+use super::post::MOSS_MATCH_MANIFEST;
 
 #[derive(Debug, Deserialize)]
 pub struct ListPlagiarismCaseQueryParams {
@@ -45,6 +61,7 @@ pub struct UserResponse {
 #[derive(Debug, Serialize)]
 pub struct SubmissionResponse {
     id: i64,
+    assignment_id: i64,
     filename: String,
     created_at: chrono::DateTime<chrono::Utc>,
     user: UserResponse,
@@ -58,6 +75,10 @@ pub struct PlagiarismCaseResponse {
     similarity: f32,
     lines_matched: i64,
     report_id: Option<i64>,
+    historical: bool,
+    /// Misconduct process stage; `None` until reported.
+    stage: Option<String>,
+    outcome: Option<String>,
     created_at: chrono::DateTime<chrono::Utc>,
     updated_at: chrono::DateTime<chrono::Utc>,
     submission_1: SubmissionResponse,
@@ -107,6 +128,7 @@ pub struct PlagiarismCaseListResponse {
 ///         "status": "flagged",
 ///         "description": "Very similar submissions",
 ///         "similarity": 84.3,
+///         "historical": false,
 ///         "created_at": "2024-05-15T08:30:00Z",
 ///         "updated_at": "2024-05-16T10:15:00Z",
 ///         "submission_1": {
@@ -143,7 +165,7 @@ pub async fn list_plagiarism_cases(
     let per_page = params.per_page.unwrap_or(20).min(100);
 
     // Limit cases to this assignment’s submissions
-    let submission_models = SubmissionEntity::find()
+    let submission_models = SubmissionEntity::find_active()
         .filter(assignment_submission::Column::AssignmentId.eq(assignment_id))
         .all(app_state.db())
         .await
@@ -181,7 +203,10 @@ pub async fn list_plagiarism_cases(
         let user_ids_subquery = UserEntity::find()
             .select_only()
             .column(user::Column::Id)
-            .filter(user::Column::Username.like(format!("%{}%", search_query.to_lowercase())))
+            .filter(
+                Expr::expr(Func::lower(Expr::col(user::Column::Username)))
+                    .like(format!("%{}%", search_query.to_lowercase())),
+            )
             .into_query();
 
         let submission_ids_subquery = SubmissionEntity::find()
@@ -231,7 +256,7 @@ pub async fn list_plagiarism_cases(
         .flat_map(|c| [c.submission_id_1, c.submission_id_2])
         .collect();
 
-    let submissions = SubmissionEntity::find()
+    let submissions = SubmissionEntity::find_active()
         .filter(assignment_submission::Column::Id.is_in(submission_ids))
         .all(app_state.db())
         .await
@@ -263,10 +288,14 @@ pub async fn list_plagiarism_cases(
                 similarity: case.similarity,
                 lines_matched: case.lines_matched,
                 report_id: case.report_id,
+                historical: case.historical,
+                stage: case.stage.map(|s| s.to_string()),
+                outcome: case.outcome.map(|o| o.to_string()),
                 created_at: case.created_at,
                 updated_at: case.updated_at,
                 submission_1: SubmissionResponse {
                     id: s1.id,
+                    assignment_id: s1.assignment_id,
                     filename: s1.filename,
                     created_at: s1.created_at,
                     user: UserResponse {
@@ -278,6 +307,7 @@ pub async fn list_plagiarism_cases(
                 },
                 submission_2: SubmissionResponse {
                     id: s2.id,
+                    assignment_id: s2.assignment_id,
                     filename: s2.filename,
                     created_at: s2.created_at,
                     user: UserResponse {
@@ -307,6 +337,256 @@ pub async fn list_plagiarism_cases(
     )
 }
 
+/// Largest submission file returned by `get_case_diff`; bigger files come back without content.
+const MAX_DIFF_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize)]
+pub struct DiffSide {
+    submission_id: i64,
+    username: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffFile {
+    name: String,
+    /// `None` if the file is not in the stored submission or is too large.
+    content: Option<String>,
+    line_count: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffRegion {
+    start_1: i64,
+    end_1: i64,
+    start_2: i64,
+    end_2: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiffFilePair {
+    file_1: DiffFile,
+    file_2: DiffFile,
+    lines_matched: i64,
+    percent: Option<u32>,
+    regions: Vec<DiffRegion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaseDiffResponse {
+    case_id: i64,
+    report_id: Option<i64>,
+    similarity: f32,
+    lines_matched: i64,
+    submission_1: DiffSide,
+    submission_2: DiffSide,
+    files: Vec<DiffFilePair>,
+}
+
+/// Text of `name` from a stored submission: the matching entry of a `.zip` (as named in the
+/// MOSS/JPlag upload, where spaces become `_`), or the file itself for single-file submissions.
+fn read_submission_file(path: &std::path::Path, name: &str) -> Option<String> {
+    let is_zip = path.extension().and_then(|e| e.to_str()) == Some("zip");
+    if !is_zip {
+        if std::fs::metadata(path).ok()?.len() > MAX_DIFF_FILE_BYTES {
+            return None;
+        }
+        let bytes = std::fs::read(path).ok()?;
+        return Some(String::from_utf8_lossy(&bytes).into_owned());
+    }
+
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path).ok()?).ok()?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).ok()?;
+        let entry_name = entry.name().replace('\\', "/");
+        let entry_name = entry_name.trim_start_matches('/');
+        if entry.is_dir() || (entry_name != name && entry_name.replace(' ', "_") != name) {
+            continue;
+        }
+        if entry.size() > MAX_DIFF_FILE_BYTES {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).ok()?;
+        return Some(String::from_utf8_lossy(&bytes).into_owned());
+    }
+    None
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/{case_id}/diff
+///
+/// Returns the matched code of a case for a side-by-side view: for each matched file pair, both
+/// files read from the stored submissions and the aligned line ranges from the MOSS/JPlag report.
+/// `_1` / `_2` always refer to the case's `submission_1` / `submission_2`.
+///
+/// The pair is the stored report pair the case was created from (or, for older cases, the pair
+/// with the same submissions in the case's report). Regions are empty when the report gave no
+/// line ranges (MOSS reports that could not be archived); the files are still returned.
+///
+/// # Returns
+/// - `200 OK` with `{ case_id, report_id, similarity, lines_matched, submission_1, submission_2,
+///   files: [{ file_1, file_2, lines_matched, percent, regions: [{ start_1, end_1, start_2,
+///   end_2 }] }] }`. Each file has `name`, `content` and `line_count`; `content` is `null` if
+///   the file is missing from the submission or larger than 1 MiB. Lines are 1-based, inclusive.
+/// - `404 NOT FOUND` if the case, its submissions, or its report pair do not exist
+/// - `500 INTERNAL SERVER ERROR` for database failures
+pub async fn get_case_diff(
+    State(app_state): State<AppState>,
+    Path((_, _, case_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    let not_found =
+        |msg: &str| (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(msg))).into_response();
+    let db_error = |e: sea_orm::DbErr| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to load case diff: {e}"
+            ))),
+        )
+            .into_response()
+    };
+
+    let case = match PlagiarismEntity::find_by_id(case_id).one(db).await {
+        Ok(Some(c)) => c,
+        Ok(None) => return not_found("Plagiarism case not found"),
+        Err(e) => return db_error(e),
+    };
+
+    // The pair the case came from, else the same submissions in the case's report
+    let mut pair = match PlagiarismMatchEntity::find()
+        .filter(plagiarism_match::Column::CaseId.eq(case.id))
+        .one(db)
+        .await
+    {
+        Ok(p) => p,
+        Err(e) => return db_error(e),
+    };
+    if pair.is_none()
+        && let Some(report_id) = case.report_id
+    {
+        let (s1, s2) = (case.submission_id_1, case.submission_id_2);
+        let parsed = match plagiarism_report::Model::find_for_moss_report(db, report_id).await {
+            Ok(p) => p,
+            Err(e) => return db_error(e),
+        };
+        if let Some(parsed) = parsed {
+            pair = match PlagiarismMatchEntity::find()
+                .filter(plagiarism_match::Column::ReportId.eq(parsed.id))
+                .filter(
+                    Condition::any()
+                        .add(
+                            Condition::all()
+                                .add(plagiarism_match::Column::SubmissionIdA.eq(s1))
+                                .add(plagiarism_match::Column::SubmissionIdB.eq(s2)),
+                        )
+                        .add(
+                            Condition::all()
+                                .add(plagiarism_match::Column::SubmissionIdA.eq(s2))
+                                .add(plagiarism_match::Column::SubmissionIdB.eq(s1)),
+                        ),
+                )
+                .one(db)
+                .await
+            {
+                Ok(p) => p,
+                Err(e) => return db_error(e),
+            };
+        }
+    }
+    let Some(pair) = pair else {
+        return not_found("No report details stored for this case");
+    };
+
+    let (sub_1, sub_2) = match (
+        SubmissionEntity::find_by_id(case.submission_id_1)
+            .one(db)
+            .await,
+        SubmissionEntity::find_by_id(case.submission_id_2)
+            .one(db)
+            .await,
+    ) {
+        (Ok(Some(a)), Ok(Some(b))) => (a, b),
+        (Err(e), _) | (_, Err(e)) => return db_error(e),
+        _ => return not_found("Submission not found"),
+    };
+
+    // Orient the pair (`a`/`b`) to the case (`_1`/`_2`)
+    let flip = pair.submission_id_a != Some(case.submission_id_1);
+    let (user_1, user_2) = if flip {
+        (pair.user_b.clone(), pair.user_a.clone())
+    } else {
+        (pair.user_a.clone(), pair.user_b.clone())
+    };
+    let rows: Vec<FileMatchRow> = pair
+        .files
+        .clone()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let (path_1, path_2) = (sub_1.full_path(), sub_2.full_path());
+
+    let files = tokio::task::spawn_blocking(move || {
+        let load = |path: &std::path::Path, name: String| {
+            let content = read_submission_file(path, &name);
+            DiffFile {
+                line_count: content.as_ref().map(|c| c.lines().count()),
+                name,
+                content,
+            }
+        };
+        rows.into_iter()
+            .map(|row| {
+                let (name_1, name_2) = if flip {
+                    (row.b_filename, row.a_filename)
+                } else {
+                    (row.a_filename, row.b_filename)
+                };
+                DiffFilePair {
+                    file_1: load(&path_1, name_1),
+                    file_2: load(&path_2, name_2),
+                    lines_matched: row.lines_matched,
+                    percent: row.percent,
+                    regions: row
+                        .regions
+                        .into_iter()
+                        .map(|r| if flip { r.swapped() } else { r })
+                        .map(|r| DiffRegion {
+                            start_1: r.a_start,
+                            end_1: r.a_end,
+                            start_2: r.b_start,
+                            end_2: r.b_end,
+                        })
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            CaseDiffResponse {
+                case_id: case.id,
+                report_id: case.report_id,
+                similarity: case.similarity,
+                lines_matched: case.lines_matched,
+                submission_1: DiffSide {
+                    submission_id: sub_1.id,
+                    username: user_1,
+                },
+                submission_2: DiffSide {
+                    submission_id: sub_2.id,
+                    username: user_2,
+                },
+                files,
+            },
+            "Case diff retrieved successfully",
+        )),
+    )
+        .into_response()
+}
+
 #[derive(Deserialize)]
 pub struct PlagiarismQuery {
     pub status: Option<String>,
@@ -314,6 +594,8 @@ pub struct PlagiarismQuery {
     pub max_similarity: Option<f32>,
     pub user: Option<String>,
     pub report_id: Option<i64>,
+    pub min_lines: Option<i64>,
+    pub format: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -325,6 +607,7 @@ struct GraphLink {
     similarity: f32,
     lines_matched: i64,
     status: String,
+    historical: bool,
 }
 
 #[derive(Serialize)]
@@ -352,8 +635,16 @@ struct LinksResponse {
 ///   - `"reviewed"`
 /// - `min_similarity` (optional): Minimum similarity threshold (inclusive).
 /// - `max_similarity` (optional): Maximum similarity threshold (inclusive).
+/// - `min_lines` (optional): Minimum number of matched lines (inclusive).
 /// - `user` (optional): Case-insensitive substring match against usernames.  
 ///   Keeps only edges where at least one endpoint matches the query.
+/// - `format` (optional): Return the graph as a file download instead of JSON, for Gephi, yEd
+///   or Graphviz. One of:
+///   - `"dot"` (Graphviz, `text/vnd.graphviz`)
+///   - `"graphml"` (`application/graphml+xml`)
+///   - `"csv"` (edge list: `source,target,weight,lines_matched,case_id,status`)
+///
+///   Edges carry the similarity as `weight`. The filters above apply to the export as well.
 ///
 /// # Semantics
 ///
@@ -364,14 +655,18 @@ struct LinksResponse {
 ///   - `case_id`: The plagiarism case’s unique ID
 ///   - `similarity`: Similarity score (0–100) reported for the case
 ///   - `status`: Current review status of the case (`"review"`, `"flagged"`, `"reviewed"`)
+///   - `historical`: One side is an archive submission (an earlier assignment or semester)
 /// - Multiple cases between the same user pair will result in multiple edges.  
 ///   (If you prefer one edge per pair, aggregate or deduplicate in your client.)
-/// - Only cases where **both** submissions belong to the specified assignment are considered.
+/// - Cases are those of the specified assignment; in historical cases one submission belongs
+///   to the archive assignment it was compared against.
 ///
 /// # Returns
 ///
 /// - `200 OK` with a `links` array (possibly empty) on success
-/// - `400 BAD REQUEST` if `status` is provided but invalid
+/// - `200 OK` with a `plagiarism_graph_{assignment_id}.{dot|graphml|csv}` attachment when
+///   `format` is given
+/// - `400 BAD REQUEST` if `status` or `format` is provided but invalid
 /// - `500 INTERNAL SERVER ERROR` if submissions, users, or cases could not be fetched
 ///
 /// # Example Request
@@ -380,6 +675,10 @@ struct LinksResponse {
 /// GET /api/modules/12/assignments/34/plagiarism/graph?status=flagged&min_similarity=60&user=u123
 /// ```
 ///
+/// ```http
+/// GET /api/modules/12/assignments/34/plagiarism/graph?min_similarity=40&format=graphml
+/// ```
+///
 /// # Example Response (200 OK)
 ///
 /// ```json
@@ -429,9 +728,28 @@ pub async fn get_graph(
     State(app_state): State<AppState>,
     Path((_module_id, assignment_id)): Path<(i64, i64)>,
     Query(query): Query<PlagiarismQuery>,
-) -> impl IntoResponse {
+) -> Response {
     use sea_orm::{ColumnTrait, QueryFilter};
 
+    // 0) Optional export format (JSON when absent)
+    let format = match query
+        .format
+        .as_deref()
+        .map(GraphFormat::from_str)
+        .transpose()
+    {
+        Ok(f) => f,
+        Err(_) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<LinksResponse>::error(
+                    "Invalid format parameter (expected dot, graphml or csv)",
+                )),
+            )
+                .into_response();
+        }
+    };
+
     // 1) Base: assignment filter
     let mut q =
         PlagiarismEntity::find().filter(plagiarism_case::Column::AssignmentId.eq(assignment_id));
@@ -453,7 +771,8 @@ pub async fn get_graph(
                     Json(ApiResponse::<LinksResponse>::error(
                         "Invalid status parameter",
                     )),
-                );
+                )
+                    .into_response();
             }
         }
     }
@@ -465,6 +784,9 @@ pub async fn get_graph(
     if let Some(max) = query.max_similarity {
         q = q.filter(plagiarism_case::Column::Similarity.lte(max));
     }
+    if let Some(min_lines) = query.min_lines {
+        q = q.filter(plagiarism_case::Column::LinesMatched.gte(min_lines));
+    }
 
     // 4) Fetch cases
     let cases = match q.all(app_state.db()).await {
@@ -475,18 +797,20 @@ pub async fn get_graph(
                 Json(ApiResponse::<LinksResponse>::error(
                     "Failed to fetch plagiarism cases",
                 )),
-            );
+            )
+                .into_response();
         }
     };
 
-    if cases.is_empty() {
+    if cases.is_empty() && format.is_none() {
         return (
             StatusCode::OK,
             Json(ApiResponse::success(
                 LinksResponse { links: vec![] },
                 "Plagiarism graph retrieved successfully",
             )),
-        );
+        )
+            .into_response();
     }
 
     // 5) Load submissions
@@ -496,7 +820,7 @@ pub async fn get_graph(
         .flat_map(|c| [c.submission_id_1, c.submission_id_2])
         .collect();
 
-    let submissions = match SubmissionEntity::find()
+    let submissions = match SubmissionEntity::find_active()
         .filter(assignment_submission::Column::Id.is_in(all_sub_ids.clone()))
         .all(app_state.db())
         .await
@@ -508,7 +832,8 @@ pub async fn get_graph(
                 Json(ApiResponse::<LinksResponse>::error(
                     "Failed to fetch submissions for cases",
                 )),
-            );
+            )
+                .into_response();
         }
     };
     let sub_by_id: HashMap<i64, _> = submissions.into_iter().map(|s| (s.id, s)).collect();
@@ -525,7 +850,8 @@ pub async fn get_graph(
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(ApiResponse::<LinksResponse>::error("Failed to fetch users")),
-            );
+            )
+                .into_response();
         }
     };
     let user_by_id: HashMap<i64, _> = users.into_iter().map(|u| (u.id, u)).collect();
@@ -564,9 +890,46 @@ pub async fn get_graph(
             similarity: case.similarity,
             lines_matched: case.lines_matched,
             status: case.status.to_string().to_lowercase(),
+            historical: case.historical,
         });
     }
 
+    // 8) Export file, if a format was requested
+    if let Some(format) = format {
+        let edges: Vec<GraphEdge> = links
+            .into_iter()
+            .map(|l| GraphEdge {
+                source: l.source,
+                target: l.target,
+                similarity: Some(l.similarity as f64),
+                lines_matched: l.lines_matched,
+                case_id: Some(l.case_id),
+                status: Some(l.status),
+            })
+            .collect();
+        let opts = GraphExportOptions {
+            min_similarity: query.min_similarity.map(f64::from),
+            max_similarity: query.max_similarity.map(f64::from),
+            min_lines: query.min_lines.unwrap_or(0),
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            axum::http::header::CONTENT_TYPE,
+            HeaderValue::from_static(format.content_type()),
+        );
+        headers.insert(
+            axum::http::header::CONTENT_DISPOSITION,
+            HeaderValue::from_str(&format!(
+                "attachment; filename=\"plagiarism_graph_{}.{}\"",
+                assignment_id,
+                format.extension()
+            ))
+            .unwrap_or(HeaderValue::from_static("attachment")),
+        );
+        return (headers, export_graph(&edges, format, &opts)).into_response();
+    }
+
     (
         StatusCode::OK,
         Json(ApiResponse::success(
@@ -574,6 +937,7 @@ pub async fn get_graph(
             "Plagiarism graph retrieved successfully",
         )),
     )
+        .into_response()
 }
 
 /// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/moss/reports/{report_id}/download
@@ -636,15 +1000,29 @@ pub async fn download_moss_archive_by_report(
     let archive_id = report.id.to_string();
     let zip_path = moss_archive_zip_path(module_id, assignment_id, &archive_id);
 
-    if !zip_path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error("Archive file missing on disk")),
-        )
-            .into_response();
-    }
+    let local_zip = match storage().local_path(&key_for(&zip_path)).await {
+        Ok(path) => path,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(
+                    "Archive file missing from storage",
+                )),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to open archive: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
 
-    let file = match File::open(&zip_path).await {
+    let file = match File::open(&local_zip).await {
         Ok(f) => f,
         Err(e) => {
             return (
@@ -680,6 +1058,475 @@ pub async fn download_moss_archive_by_report(
     (headers, body).into_response()
 }
 
+/// Looks up `report_id` and checks it belongs to `assignment_id` (404 otherwise).
+async fn find_assignment_report(
+    app_state: &AppState,
+    assignment_id: i64,
+    report_id: i64,
+) -> Result<moss_report::Model, axum::response::Response> {
+    match MossReportEntity::find_by_id(report_id)
+        .one(app_state.db())
+        .await
+    {
+        Ok(Some(r)) if r.assignment_id == assignment_id => Ok(r),
+        Ok(_) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Report not found")),
+        )
+            .into_response()),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to fetch report: {e}"
+            ))),
+        )
+            .into_response()),
+    }
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/moss/reports/{report_id}/matches
+///
+/// Lists the MOSS match pages archived for a report when it was parsed: one entry per user
+/// pair (as in `moss_parser::UserPairReport`) with a `matches` row per file pair, whose
+/// `match_href` is the page name to request from `.../matches/{page}`. Pages MOSS would not
+/// serve at the time keep their original URL.
+///
+/// - 404 if the report does not exist, is not for this assignment, or has no archived pages.
+pub async fn list_moss_match_pages(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, report_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    let report = match find_assignment_report(&app_state, assignment_id, report_id).await {
+        Ok(r) => r,
+        Err(resp) => return resp,
+    };
+
+    let manifest = moss_matches_dir(module_id, assignment_id, &report.id.to_string())
+        .join(MOSS_MATCH_MANIFEST);
+    let pairs: serde_json::Value = match storage()
+        .read(&key_for(&manifest))
+        .await
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+    {
+        Some(v) => v,
+        None => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(
+                    "No archived match pages for this report",
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            serde_json::json!({ "pairs": pairs }),
+            "Match pages retrieved successfully",
+        )),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListReportPairsQuery {
+    page: Option<u64>,
+    per_page: Option<u64>,
+    min_percent: Option<f64>,
+    min_lines: Option<i64>,
+    user: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParsedReportResponse {
+    id: i64,
+    moss_report_id: i64,
+    title: Option<String>,
+    pair_count: i64,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportPairResponse {
+    id: i64,
+    user_a: String,
+    user_b: String,
+    submission_id_a: Option<i64>,
+    submission_id_b: Option<i64>,
+    total_percent: Option<f64>,
+    lines_matched: i64,
+    files: Option<serde_json::Value>,
+    case_id: Option<i64>,
+}
+
+impl From<plagiarism_match::Model> for ReportPairResponse {
+    fn from(m: plagiarism_match::Model) -> Self {
+        Self {
+            id: m.id,
+            user_a: m.user_a,
+            user_b: m.user_b,
+            submission_id_a: m.submission_id_a,
+            submission_id_b: m.submission_id_b,
+            total_percent: m.total_percent,
+            lines_matched: m.lines_matched,
+            files: m.files,
+            case_id: m.case_id,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportPairListResponse {
+    report: ParsedReportResponse,
+    pairs: Vec<ReportPairResponse>,
+    page: u64,
+    per_page: u64,
+    total: u64,
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/moss/reports/{report_id}/pairs
+///
+/// Lists the user pairs stored when the report was parsed (MOSS or JPlag), most matched lines
+/// first. Unlike `.../matches`, this works for every parsed run, even after MOSS has expired
+/// the report and without an archive.
+///
+/// # Query Parameters
+/// - `page`: (Optional) Page number (default: 1, min: 1)
+/// - `per_page`: (Optional) Items per page (default: 20, max: 100)
+/// - `min_percent`: (Optional) Only pairs with at least this similarity (0–100)
+/// - `min_lines`: (Optional) Only pairs with at least this many matched lines
+/// - `user`: (Optional) Case-insensitive username search on either side of the pair
+///
+/// # Returns
+/// - `200 OK` with `{ report, pairs, page, per_page, total }`. Each pair has `id`, `user_a`,
+///   `user_b`, `submission_id_a`, `submission_id_b`, `total_percent`, `lines_matched`, `files`
+///   (per-file matches) and `case_id` (the case created from it, if any).
+/// - `404 NOT FOUND` if the report does not exist, is not for this assignment, or was not parsed
+/// - `500 INTERNAL SERVER ERROR` for database failures
+pub async fn list_report_pairs(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id, report_id)): Path<(i64, i64, i64)>,
+    Query(params): Query<ListReportPairsQuery>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    if let Err(resp) = find_assignment_report(&app_state, assignment_id, report_id).await {
+        return resp;
+    }
+
+    let report = match plagiarism_report::Model::find_for_moss_report(db, report_id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Report has not been parsed")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to fetch parsed report: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
+
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+
+    let mut query =
+        PlagiarismMatchEntity::find().filter(plagiarism_match::Column::ReportId.eq(report.id));
+    if let Some(min) = params.min_percent {
+        query = query.filter(plagiarism_match::Column::TotalPercent.gte(min));
+    }
+    if let Some(min) = params.min_lines {
+        query = query.filter(plagiarism_match::Column::LinesMatched.gte(min));
+    }
+    if let Some(user) = params.user.filter(|u| !u.trim().is_empty()) {
+        let pattern = format!("%{}%", user.trim().to_lowercase());
+        query = query.filter(
+            Condition::any()
+                .add(
+                    Expr::expr(Func::lower(Expr::col(plagiarism_match::Column::UserA)))
+                        .like(&pattern),
+                )
+                .add(
+                    Expr::expr(Func::lower(Expr::col(plagiarism_match::Column::UserB)))
+                        .like(&pattern),
+                ),
+        );
+    }
+    let query = query
+        .order_by_desc(plagiarism_match::Column::LinesMatched)
+        .order_by_desc(plagiarism_match::Column::TotalPercent)
+        .order_by_asc(plagiarism_match::Column::Id);
+
+    let paginator = query.paginate(db, per_page);
+    let (total, pairs) = match (
+        paginator.num_items().await,
+        paginator.fetch_page(page - 1).await,
+    ) {
+        (Ok(total), Ok(pairs)) => (total, pairs),
+        (Err(e), _) | (_, Err(e)) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to fetch report pairs: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            ReportPairListResponse {
+                report: ParsedReportResponse {
+                    id: report.id,
+                    moss_report_id: report.moss_report_id,
+                    title: report.title,
+                    pair_count: report.pair_count,
+                    created_at: report.created_at,
+                },
+                pairs: pairs.into_iter().map(ReportPairResponse::from).collect(),
+                page,
+                per_page,
+                total,
+            },
+            "Report pairs retrieved successfully",
+        )),
+    )
+        .into_response()
+}
+
+/// The match an archived MOSS page belongs to: `match0` for `match0.html` and its frames
+/// (`match0-top.html`, `match0-0.html`, ...). `None` if `page` is not a plain `.html` file name.
+fn match_page_group(page: &str) -> Option<&str> {
+    let is_page_name = page.ends_with(".html")
+        && !page.starts_with('.')
+        && page
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !is_page_name {
+        return None;
+    }
+    let stem = page.trim_end_matches(".html");
+    Some(stem.split_once('-').map_or(stem, |(group, _)| group))
+}
+
+fn invalid_match_page() -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::<()>::error("Invalid match page name")),
+    )
+        .into_response()
+}
+
+/// Serves an archived match page as sandboxed `text/html`: the pages hold student code, so
+/// nothing in them may run scripts or reach the API origin.
+async fn serve_match_page(
+    module_id: i64,
+    assignment_id: i64,
+    report_id: i64,
+    page: &str,
+) -> Response {
+    let path = moss_matches_dir(module_id, assignment_id, &report_id.to_string()).join(page);
+    let html = match storage().read(&key_for(&path)).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Match page not archived")),
+            )
+                .into_response();
+        }
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    headers.insert(
+        header::CONTENT_SECURITY_POLICY,
+        HeaderValue::from_static("sandbox"),
+    );
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    (headers, html).into_response()
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/moss/reports/{report_id}/matches/{page}
+///
+/// Serves an archived MOSS match page (or one of its frames) as sandboxed `text/html`, so
+/// matched code can still be viewed after MOSS expires the report. To show a page in the
+/// browser, where frames can't send the `Authorization` header, open a signed link from
+/// `.../matches/{page}/link` instead.
+///
+/// - 400 if `page` is not a plain `.html` file name.
+/// - 404 if the report does not exist, is not for this assignment, or the page was not archived.
+pub async fn get_moss_match_page(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, report_id, page)): Path<(i64, i64, i64, String)>,
+) -> impl IntoResponse {
+    if match_page_group(&page).is_none() {
+        return invalid_match_page();
+    }
+    if let Err(resp) = find_assignment_report(&app_state, assignment_id, report_id).await {
+        return resp;
+    }
+    serve_match_page(module_id, assignment_id, report_id, &page).await
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchPageLinkResponse {
+    /// API path of the page, link token included, e.g. `/api/plagiarism/match-pages/{token}/match0.html`
+    pub url: String,
+    pub expires_at: String,
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/moss/reports/{report_id}/matches/{page}/link
+///
+/// A short-lived signed link to an archived match page, for viewing it in the browser. The
+/// token sits in the link's path, so the page's frames (`match0-top.html`, ...) load by
+/// relative URL with the same token; it opens no other match and no other endpoint.
+///
+/// - 200 with `{ url, expires_at }`.
+/// - 400 if `page` is not a plain `.html` file name.
+/// - 404 if the report does not exist, is not for this assignment, or the page was not archived.
+pub async fn get_moss_match_page_link(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, report_id, page)): Path<(i64, i64, i64, String)>,
+) -> impl IntoResponse {
+    let Some(group) = match_page_group(&page) else {
+        return invalid_match_page();
+    };
+    if let Err(resp) = find_assignment_report(&app_state, assignment_id, report_id).await {
+        return resp;
+    }
+
+    let path = moss_matches_dir(module_id, assignment_id, &report_id.to_string()).join(&page);
+    if !storage().exists(&key_for(&path)).await.unwrap_or(false) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Match page not archived")),
+        )
+            .into_response();
+    }
+
+    let (token, expires_at) = generate_match_page_link(module_id, assignment_id, report_id, group);
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            MatchPageLinkResponse {
+                url: format!("/api/plagiarism/match-pages/{token}/{page}"),
+                expires_at,
+            },
+            "Match page link created",
+        )),
+    )
+        .into_response()
+}
+
+/// GET /api/plagiarism/match-pages/{token}/{page}
+///
+/// Serves an archived MOSS match page through a signed link from
+/// `.../moss/reports/{report_id}/matches/{page}/link`, as sandboxed `text/html`.
+///
+/// **Auth**: the link token in the path; no bearer token needed.
+///
+/// - 400 if `page` is not a plain `.html` file name.
+/// - 401 if the link is invalid or expired.
+/// - 403 if the link is for another match.
+/// - 404 if the report has since been deleted or the page was not archived.
+pub async fn get_signed_moss_match_page(
+    State(app_state): State<AppState>,
+    Path((token, page)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let Some(group) = match_page_group(&page) else {
+        return invalid_match_page();
+    };
+    let Some(link) = decode_match_page_link(&token) else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(ApiResponse::<()>::error("Invalid or expired link")),
+        )
+            .into_response();
+    };
+    if link.page_group != group {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "This link does not open that page",
+            )),
+        )
+            .into_response();
+    }
+    if let Err(resp) = find_assignment_report(&app_state, link.assignment_id, link.sub).await {
+        return resp;
+    }
+    serve_match_page(link.module_id, link.assignment_id, link.sub, &page).await
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlagiarismBaseFile {
+    pub filename: String,
+    pub size: u64,
+    pub uploaded_at: Option<String>,
+}
+
+impl PlagiarismBaseFile {
+    pub(crate) fn from_path(path: &std::path::Path) -> Option<Self> {
+        let meta = std::fs::metadata(path).ok()?;
+        if !meta.is_file() {
+            return None;
+        }
+        Some(Self {
+            filename: path.file_name()?.to_string_lossy().into_owned(),
+            size: meta.len(),
+            uploaded_at: meta
+                .modified()
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+        })
+    }
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/base-files
+///
+/// Lists the starter/skeleton files uploaded for plagiarism runs (see `upload_plagiarism_base_file`),
+/// sorted by name. An assignment without any returns an empty list.
+pub async fn list_plagiarism_base_files(
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let mut files: Vec<PlagiarismBaseFile> =
+        std::fs::read_dir(plagiarism_base_dir(module_id, assignment_id))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| PlagiarismBaseFile::from_path(&e.path()))
+                    .collect()
+            })
+            .unwrap_or_default();
+    files.sort_by(|a, b| a.filename.cmp(&b.filename));
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            serde_json::json!({ "files": files }),
+            "Base files retrieved successfully",
+        )),
+    )
+}
+
 #[derive(Serialize)]
 pub struct MossReportItem {
     pub id: i64,
@@ -744,3 +1591,106 @@ pub async fn list_moss_reports(
         ),
     }
 }
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/{case_id}/lifecycle
+///
+/// Returns a plagiarism case's misconduct process: its stage and outcome, the evidence attached
+/// to it and every decision recorded so far, oldest first.
+///
+/// ### Responses
+/// - `200 OK` with `{ id, status, stage, outcome, updated_at, evidence, decisions }`
+/// - `404 Not Found` — no such case in this assignment
+/// - `500 Internal Server Error` — database error
+pub async fn get_case_lifecycle(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id, case_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    let case = match PlagiarismEntity::find_by_id(case_id).one(db).await {
+        Ok(Some(case)) if case.assignment_id == assignment_id => case,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Plagiarism case not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Database error: {e}"))),
+            )
+                .into_response();
+        }
+    };
+
+    match CaseLifecycleResponse::load(db, case).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                response,
+                "Plagiarism case lifecycle retrieved",
+            )),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!("Database error: {e}"))),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/{case_id}/evidence/{evidence_id}
+///
+/// Downloads a file of evidence attached to a plagiarism case.
+///
+/// ### Responses
+/// - `200 OK` with the file as an attachment
+/// - `404 Not Found` — no such evidence on a case in this assignment, or its file is missing
+pub async fn download_case_evidence(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id, case_id, evidence_id)): Path<(i64, i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    let not_found =
+        |msg: &str| (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(msg))).into_response();
+
+    let evidence = match EvidenceEntity::find_by_id(evidence_id)
+        .find_also_related(PlagiarismEntity)
+        .one(db)
+        .await
+    {
+        Ok(Some((evidence, Some(case))))
+            if evidence.case_id == case_id && case.assignment_id == assignment_id =>
+        {
+            evidence
+        }
+        Ok(_) => return not_found("Evidence not found"),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Database error: {e}"))),
+            )
+                .into_response();
+        }
+    };
+    let Ok(bytes) = evidence.load_file().await else {
+        return not_found("Evidence file missing");
+    };
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!(
+            "attachment; filename=\"{}\"",
+            evidence.filename.replace('"', "")
+        ))
+        .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    (headers, bytes).into_response()
+}
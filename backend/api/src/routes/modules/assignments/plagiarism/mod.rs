@@ -7,8 +7,12 @@
 //! - Run MOSS plagiarism checks and list MOSS reports
 //! - Flag and review plagiarism cases
 //! - Retrieve plagiarism graph for visualization
-//! - Manage versioned MOSS archives (create, delete, **download specific report**)
+//! - Side-by-side matched code of a case
+//! - Manage versioned MOSS archives (create, delete, **download specific report**, view archived match pages)
 //! - List stored MOSS reports from the database
+//! - Browse the parsed user pairs of a report and open cases from them
+//! - Take a case through the misconduct process (reported → interviewed → escalated → resolved)
+//!   with evidence attachments and decision records
 //!
 //! Access control should be enforced via middleware (not shown here) for lecturers, tutors, or assistants.
 
@@ -17,15 +21,23 @@ use axum::{
     routing::{delete, get, patch, post, put},
 };
 
-use delete::{bulk_delete_plagiarism_cases, delete_plagiarism_case};
-use get::{download_moss_archive_by_report, get_graph, list_moss_reports, list_plagiarism_cases};
-use patch::{patch_plagiarism_flag, patch_plagiarism_review};
-use post::{create_plagiarism_case, hash_scan, run_moss_check};
+use delete::{bulk_delete_plagiarism_cases, clear_plagiarism_base_files, delete_plagiarism_case};
+use get::{
+    download_case_evidence, download_moss_archive_by_report, get_case_diff, get_case_lifecycle,
+    get_graph, get_moss_match_page, get_moss_match_page_link, list_moss_match_pages, list_moss_reports,
+    list_plagiarism_base_files, list_plagiarism_cases, list_report_pairs,
+};
+use patch::{patch_plagiarism_flag, patch_plagiarism_review, patch_plagiarism_stage};
+use post::{
+    create_case_from_pair, create_plagiarism_case, hash_scan, run_moss_check, upload_case_evidence,
+    upload_plagiarism_base_file,
+};
 use put::update_plagiarism_case;
 use util::state::AppState;
 
 use crate::routes::modules::assignments::plagiarism::delete::delete_moss_report;
 
+pub mod common;
 pub mod delete;
 pub mod get;
 pub mod patch;
@@ -43,10 +55,23 @@ pub mod put;
 /// - `DELETE /assignments/plagiarism/bulk`                          → Bulk delete plagiarism cases
 /// - `PATCH  /assignments/plagiarism/{case_id}/flag`                → Flag a plagiarism case
 /// - `PATCH  /assignments/plagiarism/{case_id}/review`              → Review a plagiarism case
-/// - `POST   /assignments/plagiarism/moss`                          → Run MOSS check (also kicks off a versioned archive job)
+/// - `GET    /assignments/plagiarism/{case_id}/diff`                → Matched code of both submissions (side-by-side)
+/// - `PATCH  /assignments/plagiarism/{case_id}/stage`               → Move a case through the misconduct process
+/// - `GET    /assignments/plagiarism/{case_id}/lifecycle`           → A case's stage, evidence and decisions
+/// - `POST   /assignments/plagiarism/{case_id}/evidence`            → Attach evidence to a case (multipart)
+/// - `GET    /assignments/plagiarism/{case_id}/evidence/{evidence_id}` → Download a case's evidence
+/// - `POST   /assignments/plagiarism/moss`                          → Run MOSS (or local JPlag, `engine = "jplag"`) check (also kicks off a versioned archive job)
 /// - `GET    /assignments/plagiarism/moss/reports`                  → List stored MOSS reports (from DB)
 /// - `GET    /assignments/plagiarism/moss/reports/{report_id}/download` → Download the archive ZIP for a **specific** report
+/// - `GET    /assignments/plagiarism/moss/reports/{report_id}/matches` → List the report's archived match pages
+/// - `GET    /assignments/plagiarism/moss/reports/{report_id}/matches/{page}` → Serve an archived match page (HTML)
+/// - `GET    /assignments/plagiarism/moss/reports/{report_id}/matches/{page}/link` → Signed link to view a match page in the browser
+/// - `GET    /assignments/plagiarism/moss/reports/{report_id}/pairs` → List the report's parsed user pairs (filterable)
+/// - `POST   /assignments/plagiarism/moss/reports/{report_id}/pairs/{match_id}/case` → Create a case from a parsed pair
 /// - `DELETE /assignments/plagiarism/moss/reports/{report_id}`      → Delete a specific moss report
+/// - `GET    /assignments/plagiarism/base-files`                    → List starter code excluded from runs
+/// - `POST   /assignments/plagiarism/base-files`                    → Upload starter code to exclude (multipart)
+/// - `DELETE /assignments/plagiarism/base-files`                    → Remove all uploaded starter code
 pub fn plagiarism_routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_plagiarism_cases))
@@ -57,12 +82,43 @@ pub fn plagiarism_routes() -> Router<AppState> {
         .route("/bulk", delete(bulk_delete_plagiarism_cases))
         .route("/{case_id}/flag", patch(patch_plagiarism_flag))
         .route("/{case_id}/review", patch(patch_plagiarism_review))
+        .route("/{case_id}/diff", get(get_case_diff))
+        .route("/{case_id}/stage", patch(patch_plagiarism_stage))
+        .route("/{case_id}/lifecycle", get(get_case_lifecycle))
+        .route("/{case_id}/evidence", post(upload_case_evidence))
+        .route(
+            "/{case_id}/evidence/{evidence_id}",
+            get(download_case_evidence),
+        )
         .route("/moss", post(run_moss_check))
         .route("/moss/reports", get(list_moss_reports))
         .route(
             "/moss/reports/{report_id}/download",
             get(download_moss_archive_by_report),
         )
+        .route(
+            "/moss/reports/{report_id}/matches",
+            get(list_moss_match_pages),
+        )
+        .route(
+            "/moss/reports/{report_id}/matches/{page}",
+            get(get_moss_match_page),
+        )
+        .route(
+            "/moss/reports/{report_id}/matches/{page}/link",
+            get(get_moss_match_page_link),
+        )
+        .route("/moss/reports/{report_id}/pairs", get(list_report_pairs))
+        .route(
+            "/moss/reports/{report_id}/pairs/{match_id}/case",
+            post(create_case_from_pair),
+        )
         .route("/moss/reports/{report_id}", delete(delete_moss_report))
         .route("/hash-scan", post(hash_scan))
+        .route(
+            "/base-files",
+            get(list_plagiarism_base_files)
+                .post(upload_plagiarism_base_file)
+                .delete(clear_plagiarism_base_files),
+        )
 }
@@ -1,21 +1,52 @@
-use crate::response::ApiResponse;
+use super::common::CaseLifecycleResponse;
+use crate::{
+    auth::{AuthUser, guards::user_has_any_role},
+    response::ApiResponse,
+    services::notifications::{self, NewNotification},
+};
 use axum::{
-    Json,
+    Extension, Json,
     extract::{Path, State},
     http::StatusCode,
     response::IntoResponse,
 };
 use chrono::{DateTime, Utc};
 use db::models::{
-    assignment_submission::Model as SubmissionModel,
-    plagiarism_case::{Column as PlagiarismColumn, Entity as PlagiarismEntity, Status},
+    assignment::{Entity as AssignmentEntity, Model as AssignmentModel},
+    assignment_submission::{
+        Column as SubmissionColumn, Entity as SubmissionEntity, Model as SubmissionModel,
+    },
+    group::Model as GroupModel,
+    notification::NotificationKind,
+    plagiarism_case::{
+        Column as PlagiarismColumn, Entity as PlagiarismEntity, Model as PlagiarismModel, Outcome,
+        Stage, Status, TransitionError,
+    },
 };
+use db::soft_delete::SoftDelete;
 use sea_orm::{
-    ActiveModelTrait, ColumnTrait, EntityTrait, IntoActiveModel, QueryFilter, Set, TransactionTrait,
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel,
+    QueryFilter, QuerySelect, QueryTrait, Select, Set, TransactionTrait,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use util::state::AppState;
 
+/// The assignment's case `case_id`, unless one of its submissions has been deleted.
+fn find_case(case_id: i64, assignment_id: i64) -> Select<PlagiarismEntity> {
+    let deleted = || {
+        SubmissionEntity::find_deleted()
+            .select_only()
+            .column(SubmissionColumn::Id)
+            .into_query()
+    };
+    PlagiarismEntity::find()
+        .filter(PlagiarismColumn::Id.eq(case_id))
+        .filter(PlagiarismColumn::AssignmentId.eq(assignment_id))
+        .filter(PlagiarismColumn::SubmissionId1.not_in_subquery(deleted()))
+        .filter(PlagiarismColumn::SubmissionId2.not_in_subquery(deleted()))
+}
+
 #[derive(Debug, Serialize)]
 pub struct FlaggedCaseResponse {
     id: i64,
@@ -95,12 +126,7 @@ pub async fn patch_plagiarism_flag(
         }
     };
 
-    let case = match PlagiarismEntity::find()
-        .filter(PlagiarismColumn::Id.eq(case_id))
-        .filter(PlagiarismColumn::AssignmentId.eq(assignment_id))
-        .one(&txn)
-        .await
-    {
+    let case = match find_case(case_id, assignment_id).one(&txn).await {
         Ok(Some(case)) => case,
         Ok(None) => {
             return (
@@ -201,6 +227,7 @@ pub struct ReviewedCaseResponse {
 /// - `200 OK` with minimal case information on success
 /// - `403 FORBIDDEN` if user lacks required permissions
 /// - `404 NOT FOUND` if specified plagiarism case doesn't exist
+/// - `409 CONFLICT` if the case is reported but not yet resolved
 /// - `500 INTERNAL SERVER ERROR` for database errors or update failures
 ///
 /// The response body includes only essential fields after the status change.
@@ -242,16 +269,12 @@ pub struct ReviewedCaseResponse {
 /// - This operation updates the case status to "reviewed" and sets the current timestamp to `updated_at`
 /// - Only users with lecturer or assistant lecturer roles assigned to the module can perform this action
 /// - Typically indicates the case was investigated and determined not to be plagiarism
+/// - A reported case can only be cleared by resolving it (`PATCH /{case_id}/stage`)
 pub async fn patch_plagiarism_review(
     State(app_state): State<AppState>,
     Path((_, assignment_id, case_id)): Path<(i64, i64, i64)>,
 ) -> impl IntoResponse {
-    let case = match PlagiarismEntity::find()
-        .filter(PlagiarismColumn::Id.eq(case_id))
-        .filter(PlagiarismColumn::AssignmentId.eq(assignment_id))
-        .one(app_state.db())
-        .await
-    {
+    let case = match find_case(case_id, assignment_id).one(app_state.db()).await {
         Ok(Some(case)) => case,
         Ok(None) => {
             return (
@@ -266,6 +289,14 @@ pub async fn patch_plagiarism_review(
             );
         }
     };
+    if case.in_process() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::error(
+                "A reported case is cleared by resolving it, not by review",
+            )),
+        );
+    }
 
     let mut active_case = case.into_active_model();
     active_case.status = Set(Status::Reviewed);
@@ -298,3 +329,231 @@ pub async fn patch_plagiarism_review(
         )),
     )
 }
+
+#[derive(Debug, Deserialize)]
+pub struct StageRequest {
+    /// `reported`, `interviewed`, `escalated` or `resolved`.
+    pub stage: String,
+    /// The reasons for the decision, kept in the case's decision record.
+    pub decision: String,
+    /// Required when resolving: `cleared`, `warning`, `mark_penalty` or `disciplinary`.
+    pub outcome: Option<String>,
+}
+
+/// PATCH /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/{case_id}/stage
+///
+/// Moves a plagiarism case to the next stage of its misconduct process and records the decision.
+/// A case is `reported`, may be `interviewed` and then `escalated`, and is `resolved` from any
+/// stage with an outcome.
+///
+/// # Path Parameters
+///
+/// - `module_id`: The ID of the parent module
+/// - `assignment_id`: The ID of the assignment containing the plagiarism case
+/// - `case_id`: The ID of the plagiarism case
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "stage": "resolved",
+///   "decision": "Both students admitted sharing code in the interview.",
+///   "outcome": "mark_penalty"
+/// }
+/// ```
+///
+/// # Returns
+///
+/// - `200 OK` with the case's stage, evidence and decisions
+/// - `400 BAD REQUEST` for an unknown stage or outcome, or an empty decision
+/// - `403 FORBIDDEN` if an assistant lecturer escalates or resolves a case
+/// - `404 NOT FOUND` if the case doesn't exist in this assignment
+/// - `409 CONFLICT` if the stage doesn't follow the current one, no evidence was uploaded at the
+///   current stage, or an outcome is missing when resolving (or given otherwise)
+/// - `500 INTERNAL SERVER ERROR` for database errors
+///
+/// # Notes
+///
+/// - Reporting, interviewing and escalating need evidence uploaded while the case was at the
+///   stage being left (`POST /{case_id}/evidence`)
+/// - Escalating and resolving are reserved for lecturers
+/// - The students whose submissions in this assignment are involved (every member, for group
+///   submissions) are notified when the case reaches a stage listed in the assignment config's
+///   `plagiarism.notify_students_at` (by default `reported` and `resolved`)
+pub async fn patch_plagiarism_stage(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, case_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<StageRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let Ok(stage) = Stage::from_str(req.stage.trim()) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "stage must be one of reported, interviewed, escalated or resolved",
+            )),
+        )
+            .into_response();
+    };
+    let outcome = match req.outcome.as_deref().map(|o| Outcome::from_str(o.trim())) {
+        None => None,
+        Some(Ok(outcome)) => Some(outcome),
+        Some(Err(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "outcome must be one of cleared, warning, mark_penalty or disciplinary",
+                )),
+            )
+                .into_response();
+        }
+    };
+    let decision = req.decision.trim();
+    if decision.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("decision is required")),
+        )
+            .into_response();
+    }
+
+    if matches!(stage, Stage::Escalated | Stage::Resolved)
+        && !claims.admin
+        && !user_has_any_role(db, claims.sub, module_id, &["Lecturer"]).await
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "Only lecturers may escalate or resolve a case",
+            )),
+        )
+            .into_response();
+    }
+
+    let case = match find_case(case_id, assignment_id).one(db).await {
+        Ok(Some(case)) => case,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Plagiarism case not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Database error: {}", e))),
+            )
+                .into_response();
+        }
+    };
+
+    let case = match case
+        .transition(db, stage, decision, outcome, claims.sub)
+        .await
+    {
+        Ok((case, _)) => case,
+        Err(TransitionError::Database(e)) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to update plagiarism case: {}",
+                    e
+                ))),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(e.to_string())),
+            )
+                .into_response();
+        }
+    };
+
+    if let Ok(Some(assignment)) = AssignmentEntity::find_by_id(assignment_id).one(db).await
+        && stage.notifies_students(&assignment.plagiarism())
+    {
+        match involved_students(db, &case).await {
+            Ok(user_ids) => notify_students(&app_state, &assignment, &case, user_ids),
+            Err(e) => tracing::warn!(
+                "Plagiarism: failed to find the students of case {}: {}",
+                case.id,
+                e
+            ),
+        }
+    }
+
+    match CaseLifecycleResponse::load(db, case).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                response,
+                format!("Plagiarism case {stage}"),
+            )),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!("Database error: {}", e))),
+        )
+            .into_response(),
+    }
+}
+
+/// Owners of the case's submissions in its own assignment (every member, for group
+/// submissions). The other side of a historical case belongs to an archive and is left out.
+async fn involved_students(
+    db: &DatabaseConnection,
+    case: &PlagiarismModel,
+) -> Result<Vec<i64>, DbErr> {
+    let submissions = SubmissionEntity::find_active()
+        .filter(SubmissionColumn::Id.is_in([case.submission_id_1, case.submission_id_2]))
+        .filter(SubmissionColumn::AssignmentId.eq(case.assignment_id))
+        .all(db)
+        .await?;
+    let mut user_ids = Vec::new();
+    for submission in submissions {
+        match submission.group_id {
+            Some(group_id) => user_ids.extend(GroupModel::member_ids(db, group_id).await?),
+            None => user_ids.push(submission.user_id),
+        }
+    }
+    user_ids.sort_unstable();
+    user_ids.dedup();
+    Ok(user_ids)
+}
+
+fn notify_students(
+    app_state: &AppState,
+    assignment: &AssignmentModel,
+    case: &PlagiarismModel,
+    user_ids: Vec<i64>,
+) {
+    let Some(stage) = case.stage else {
+        return;
+    };
+    let body = match (stage, case.outcome) {
+        (Stage::Resolved, Some(outcome)) => format!(
+            "The academic integrity case involving your submission has been resolved: {}.",
+            outcome.to_string().replace('_', " ")
+        ),
+        _ => format!("The academic integrity case involving your submission has been {stage}."),
+    };
+    notifications::spawn_notify(
+        app_state,
+        user_ids,
+        NewNotification {
+            kind: NotificationKind::PlagiarismCaseUpdated,
+            title: format!("{}: academic integrity case {stage}", assignment.name),
+            body,
+            link: Some(format!(
+                "/modules/{}/assignments/{}",
+                assignment.module_id, assignment.id
+            )),
+        },
+    );
+}
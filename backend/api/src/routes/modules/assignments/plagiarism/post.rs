@@ -1,14 +1,18 @@
 use std::fs;
 use std::path::PathBuf;
 
+use super::common::EvidenceResponse;
+use super::get::PlagiarismBaseFile;
 use crate::services::moss_archiver::{ArchiveOptions, archive_moss_to_fs_and_zip};
 use crate::{
+    auth::{AuthUser, Claims},
     response::ApiResponse,
+    services::jplag::{JplagRunOptions, JplagService},
     services::moss::{MossRunOptions, MossService},
 };
 use axum::{
-    Json,
-    extract::{Path as AxumPath, State},
+    Extension, Json,
+    extract::{Multipart, Path as AxumPath, State},
     http::StatusCode,
     response::IntoResponse,
 };
@@ -20,17 +24,28 @@ use db::models::assignment_file::{
 use db::models::moss_report::{self, FilterMode as MossFilterMode};
 use db::models::{
     assignment_submission::{self, Entity as SubmissionEntity},
+    group::Model as GroupModel,
     plagiarism_case,
-    user::Entity as UserEntity,
+    plagiarism_case_evidence::Model as EvidenceModel,
+    plagiarism_match::{self, NewMatch},
+    plagiarism_report,
+    user::{self, Entity as UserEntity},
 };
-use moss_parser::{ParseOptions, parse_moss};
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use moss_parser::{ParseOptions, UserPairReport, jplag::parse_jplag_dir, parse_moss};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::{error, info};
 use util::config;
-use util::paths::{assignment_dir, ensure_parent_dir, moss_archive_zip_path};
-use util::{execution_config::ExecutionConfig, state::AppState};
+use util::paths::{
+    assignment_dir, ensure_parent_dir, moss_archive_zip_path, moss_matches_dir, plagiarism_base_dir,
+};
+use util::{
+    execution_config::{ExecutionConfig, PlagiarismOptions},
+    state::AppState,
+    storage::{key_for, storage},
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct CreatePlagiarismCasePayload {
@@ -240,6 +255,148 @@ pub async fn create_plagiarism_case(
     }
 }
 
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/moss/reports/{report_id}/pairs/{match_id}/case
+///
+/// Creates a `"review"` case from a stored report pair (see `.../pairs`), with the pair's
+/// similarity and matched lines and a generated description, linked to `report_id`. Pairs the
+/// run skipped (e.g. below a threshold, or cases deleted since) can be opened this way. The
+/// case is marked `historical` if either submission is from another assignment.
+///
+/// # Returns
+/// - `201 Created` with the new case (same shape as `POST /plagiarism`)
+/// - `400 BAD REQUEST` if the pair has no submission ids or a submission no longer exists
+/// - `404 NOT FOUND` if the report or pair does not exist (checked by the route guard)
+/// - `409 CONFLICT` if a case was already created from this pair
+/// - `500 INTERNAL SERVER ERROR` for database errors
+pub async fn create_case_from_pair(
+    State(app_state): State<AppState>,
+    AxumPath((_, assignment_id, report_id, match_id)): AxumPath<(i64, i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let pair = match plagiarism_match::Entity::find_by_id(match_id).one(db).await {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Match not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to fetch match: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
+
+    if pair.case_id.is_some() {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(
+                "A case has already been created from this match",
+            )),
+        )
+            .into_response();
+    }
+
+    let (Some(sub_a), Some(sub_b)) = (pair.submission_id_a, pair.submission_id_b) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Match is not linked to two submissions",
+            )),
+        )
+            .into_response();
+    };
+    let (a, b, ua, ub) = if sub_a <= sub_b {
+        (sub_a, sub_b, &pair.user_a, &pair.user_b)
+    } else {
+        (sub_b, sub_a, &pair.user_b, &pair.user_a)
+    };
+
+    let submissions = SubmissionEntity::find_active()
+        .filter(assignment_submission::Column::Id.is_in([a, b]))
+        .all(db)
+        .await
+        .unwrap_or_default();
+    if submissions.len() != 2 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "One or both submissions no longer exist",
+            )),
+        )
+            .into_response();
+    }
+    let historical = submissions.iter().any(|s| s.assignment_id != assignment_id);
+
+    let description = generate_description(ua, ub, a, b, pair.lines_matched, pair.total_percent);
+    let similarity = pair.total_percent.unwrap_or(0.0).clamp(0.0, 100.0) as f32;
+
+    let case = match plagiarism_case::Model::create_case(
+        db,
+        assignment_id,
+        a,
+        b,
+        &description,
+        similarity,
+        pair.lines_matched.max(0),
+        Some(report_id),
+    )
+    .await
+    {
+        Ok(c) => c,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to create plagiarism case")),
+            )
+                .into_response();
+        }
+    };
+    if historical && let Err(e) = plagiarism_case::Model::mark_historical(db, case.id).await {
+        error!(
+            "Plagiarism: failed to mark case {} historical: {e}",
+            case.id
+        );
+    }
+    if let Err(e) = plagiarism_match::Model::set_case(db, pair.id, case.id).await {
+        error!(
+            "Plagiarism: failed to link match {} to case {}: {e}",
+            pair.id, case.id
+        );
+    }
+
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::success(
+            PlagiarismCaseResponse {
+                id: case.id,
+                assignment_id,
+                submission_id_1: case.submission_id_1,
+                submission_id_2: case.submission_id_2,
+                description: case.description,
+                status: case.status.to_string(),
+                similarity: case.similarity,
+                lines_matched: case.lines_matched,
+                report_id: case.report_id,
+                created_at: case.created_at,
+                updated_at: case.updated_at,
+            },
+            "Plagiarism case created successfully",
+        )),
+    )
+        .into_response()
+}
+
+/// File in a report's `matches` directory listing the archived match pages per user pair.
+pub const MOSS_MATCH_MANIFEST: &str = "matches.json";
+
 #[allow(dead_code)]
 #[derive(Debug, Serialize, Deserialize)]
 struct ArchiveManifest {
@@ -252,11 +409,30 @@ struct ArchiveManifest {
     files: Option<usize>,
 }
 
+/// Which detector a plagiarism run uses.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlagiarismEngine {
+    /// The hosted MOSS service (default).
+    #[default]
+    Moss,
+    /// A JPlag jar run locally (`JPLAG_JAR`).
+    Jplag,
+}
+
 #[derive(Deserialize)]
 pub struct RunMossPayload {
+    #[serde(default)]
+    pub engine: PlagiarismEngine,
     pub experimental: Option<bool>,
     pub max_matches: Option<u32>,
     pub show_limit: Option<u32>,
+    /// JPlag only: minimum token match length.
+    pub min_tokens: Option<u32>,
+    /// Exclude the uploaded plagiarism base files (default `true`).
+    pub use_base_files: Option<bool>,
+    /// Earlier assignments (any module or year) whose submissions are compared as archives.
+    pub archive_assignment_ids: Option<Vec<i64>>,
     pub filter_mode: Option<MossFilterMode>,
     pub filter_patterns: Option<Vec<String>>,
     pub description: String,
@@ -283,22 +459,63 @@ pub struct RunMossPayload {
 ///
 /// # Request Body
 ///
-/// **None.** The language is read from the assignment’s execution config
-/// (`project.language`).
+/// - `description` (**required**): saved with the report.
+/// - `engine` (optional): `"moss"` (default) or `"jplag"`.
+/// - `experimental`, `max_matches`, `show_limit` (optional): MOSS options.
+/// - `min_tokens` (optional): JPlag minimum token match length.
+/// - `use_base_files` (optional, default `true`): exclude the uploaded plagiarism base files.
+/// - `archive_assignment_ids` (optional): earlier assignments, from any module or year, whose
+///   submissions are compared as archive corpora (see below).
+/// - `filter_mode`, `filter_patterns` (optional): which submission files are compared.
+///
+/// The language is read from the assignment’s execution config (`project.language`).
+///
+/// # JPlag
+///
+/// With `engine = "jplag"` the submissions are compared by a JPlag jar run on this server
+/// (`JPLAG_JAR`, run with `JAVA_BIN`) instead of the MOSS service, which is useful when MOSS
+/// is unavailable. The same submissions, base files and filters are used and cases are created
+/// the same way; the report is saved with `report_url = "jplag://local"` and JPlag's
+/// `results.zip` becomes the report's archive. Returns `400 BAD REQUEST` if `JPLAG_JAR` is
+/// not set.
+///
+/// # Archive corpora
+///
+/// Each assignment in `archive_assignment_ids` contributes one submission per student (by its
+/// own grading policy), labelled `<username>@a<assignment_id>` in the report, so students
+/// resubmitting an earlier cohort's solution are caught. Only matches between this
+/// assignment's submissions and archive submissions become cases, marked `historical: true`;
+/// archive-to-archive matches and a student's match with their own archived work are skipped.
+/// The caller must be a lecturer or assistant lecturer (or admin) on each archive assignment's
+/// module. Returns `400 BAD REQUEST` if the list contains this assignment, `404 NOT FOUND` for
+/// an unknown assignment and `403 FORBIDDEN` without access.
 ///
 /// # Behavior
 ///
 /// - **Submission selection respects the assignment’s grading policy**:
 ///   - `grading_policy = "last"` → uses each student’s **most recent** non-practice, non-ignored submission.
 ///   - `grading_policy = "best"` → uses each student’s **best-scoring** non-practice, non-ignored submission.
-/// - Base (starter) files attached to the assignment are included if present.
+///   - `grading_policy = "best_of_last_n"` → the best-scoring of their `best_of_last_n` most recent ones.
+///   - `grading_policy = "weighted"` → their most recent one, as for `last`.
+/// - Base (starter) code is excluded from matches: the assignment's spec files, plus the files
+///   uploaded via `POST .../plagiarism/base-files` unless `use_base_files = false`. MOSS gets
+///   them as base files (`-b`), JPlag as base code (`-bc`); ZIPs are expanded.
 /// - The MOSS result URL and timestamp are saved to:
 ///   `<storage_root>/module_{module_id}/assignment_{assignment_id}/reports.txt`.
 /// - A **fire-and-forget archive job** mirrors the report for offline viewing to:
 ///   - Folder: `<storage_root>/module_{module_id}/assignment_{assignment_id}/moss_archive/`
 ///   - ZIP:    `<storage_root>/module_{module_id}/assignment_{assignment_id}/moss_archive.zip`
 ///   The archive includes **all images** referenced by the report. Existing archives are overwritten.
-/// - Case creation is **deduplicated** per pair (order-independent).
+/// - While the report is parsed, every matched page (and its frames) is saved to
+///   `.../moss_archives/{report_id}/matches/` with links rewritten, so the matched code outlives
+///   MOSS's 14-day expiry. They are listed by `GET .../moss/reports/{report_id}/matches` and
+///   served by `GET .../moss/reports/{report_id}/matches/{page}`.
+/// - Case creation is **deduplicated** per pair (order-independent), also against the
+///   assignment's existing cases, which are left as they are.
+/// - Cases are only opened for pairs reaching the assignment config's
+///   `plagiarism.case_min_similarity` (%) or `plagiarism.case_min_lines`; with neither set,
+///   every pair gets one. Pairs below stay in the stored report (`.../pairs`), from which a case
+///   can still be opened.
 /// - `similarity` is stored as an `f32` percent, clamped to **0.0–100.0**.
 /// - Newly created cases start in `"review"` status and can be managed via the plagiarism APIs/UI.
 ///
@@ -330,6 +547,7 @@ pub struct RunMossPayload {
 pub async fn run_moss_check(
     State(app_state): State<AppState>,
     AxumPath((module_id, assignment_id)): AxumPath<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
     Json(body): Json<RunMossPayload>,
 ) -> impl IntoResponse {
     // 0) Load assignment config
@@ -358,31 +576,8 @@ pub async fn run_moss_check(
     };
 
     // 1) Choose one submission per user according to policy
-    let selected_submissions: Vec<assignment_submission::Model> = match SubmissionEntity::find()
-        .filter(assignment_submission::Column::AssignmentId.eq(assignment_id))
-        .all(app_state.db())
-        .await
-    {
-        Ok(all_for_assignment) => {
-            use std::collections::HashSet;
-            let mut user_ids = HashSet::<i64>::new();
-            for s in &all_for_assignment {
-                user_ids.insert(s.user_id);
-            }
-            let mut chosen = Vec::with_capacity(user_ids.len());
-            for uid in user_ids {
-                if let Ok(Some(s)) = assignment_submission::Model::get_best_for_user(
-                    app_state.db(),
-                    &assignment_model,
-                    uid,
-                )
-                .await
-                {
-                    chosen.push(s);
-                }
-            }
-            chosen
-        }
+    let selected_submissions = match select_submissions(app_state.db(), &assignment_model).await {
+        Ok(subs) => subs,
         Err(_) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -404,6 +599,101 @@ pub async fn run_moss_check(
         submission_files.push((submission.full_path(), username, Some(submission.id)));
     }
 
+    // 2.0) Archive corpora: earlier assignments / semesters to compare against
+    let mut corpus = RunCorpus {
+        case_options: cfg.plagiarism.clone(),
+        ..RunCorpus::default()
+    };
+    corpus
+        .owners
+        .extend(selected_submissions.iter().map(|s| (s.id, s.user_id)));
+    if assignment_model.group_submissions() {
+        match GroupModel::memberships(app_state.db(), assignment_id).await {
+            Ok(group_of) => {
+                corpus
+                    .groups
+                    .extend(selected_submissions.iter().filter_map(|s| {
+                        let group = s.group_id.or(group_of.get(&s.user_id).copied())?;
+                        Some((s.id, group))
+                    }))
+            }
+            Err(e) => error!("Plagiarism: failed to load groups: {e}"),
+        }
+    }
+
+    let mut archive_ids = body.archive_assignment_ids.clone().unwrap_or_default();
+    archive_ids.sort_unstable();
+    archive_ids.dedup();
+    for archive_id in archive_ids {
+        if archive_id == assignment_id {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "archive_assignment_ids must not include this assignment",
+                )),
+            )
+                .into_response();
+        }
+        let archive = match AssignmentEntity::find_by_id(archive_id)
+            .one(app_state.db())
+            .await
+        {
+            Ok(Some(a)) => a,
+            Ok(None) => {
+                return (
+                    StatusCode::NOT_FOUND,
+                    Json(ApiResponse::<()>::error(format!(
+                        "Archive assignment {archive_id} not found"
+                    ))),
+                )
+                    .into_response();
+            }
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error(
+                        "Failed to load archive assignment",
+                    )),
+                )
+                    .into_response();
+            }
+        };
+        if !is_module_staff(app_state.db(), &claims, archive.module_id).await {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error(format!(
+                    "Lecturer or assistant lecturer access required for archive assignment {archive_id}"
+                ))),
+            )
+                .into_response();
+        }
+
+        let archived = match select_submissions(app_state.db(), &archive).await {
+            Ok(subs) => subs,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error(
+                        "Failed to retrieve archive submissions",
+                    )),
+                )
+                    .into_response();
+            }
+        };
+        for submission in archived {
+            // Distinct label, so pairs never merge with this assignment's pairs for the same users
+            let label = UserEntity::find_by_id(submission.user_id)
+                .one(app_state.db())
+                .await
+                .ok()
+                .flatten()
+                .map(|u| format!("{}@a{}", u.username, archive_id));
+            submission_files.push((submission.full_path(), label, Some(submission.id)));
+            corpus.owners.insert(submission.id, submission.user_id);
+            corpus.historical.insert(submission.id);
+        }
+    }
+
     // 2.1) Gather skeleton/base from SPEC files
     let spec_files = AssignmentFileEntity::find()
         .filter(AssignmentFileCol::AssignmentId.eq(assignment_id))
@@ -423,6 +713,28 @@ pub async fn run_moss_check(
         }
     }
 
+    // 2.2) Starter code uploaded for plagiarism runs, unless this run opts out
+    if body.use_base_files.unwrap_or(true) {
+        let mut uploaded: Vec<PathBuf> =
+            fs::read_dir(plagiarism_base_dir(module_id, assignment_id))
+                .map(|entries| {
+                    entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.path())
+                        .filter(|p| p.is_file())
+                        .collect()
+                })
+                .unwrap_or_default();
+        uploaded.sort();
+        for p in uploaded {
+            if p.extension().and_then(|s| s.to_str()) == Some("zip") {
+                spec_zips.push(p);
+            } else {
+                base_files.push(p);
+            }
+        }
+    }
+
     // 3) Build MOSS run options (with filters from request)
     let mut opts = MossRunOptions::default();
     opts.language = moss_language.to_string();
@@ -476,6 +788,56 @@ pub async fn run_moss_check(
             .into_response();
     }
 
+    // 3.1) JPlag runs locally instead of going to the MOSS server
+    if body.engine == PlagiarismEngine::Jplag {
+        let Some(jar) = config::jplag_jar() else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "JPlag is not configured (set JPLAG_JAR)",
+                )),
+            )
+                .into_response();
+        };
+        let jplag_opts = JplagRunOptions {
+            language: cfg.project.language.to_jplag().to_string(),
+            min_tokens: body.min_tokens,
+            filter_mode: filter_mode.clone(),
+            filter_patterns: filter_patterns.clone(),
+            spec_zips: opts.spec_zips,
+        };
+        let jplag_service = JplagService::new(&config::java_bin(), jar);
+        let db = app_state.db().clone();
+        let started_at = Utc::now();
+
+        tokio::spawn(async move {
+            run_jplag_job(
+                db,
+                module_id,
+                assignment_id,
+                jplag_service,
+                base_files,
+                submission_files,
+                jplag_opts,
+                body.description,
+                corpus,
+            )
+            .await;
+        });
+
+        return (
+            StatusCode::ACCEPTED,
+            Json(ApiResponse::success(
+                serde_json::json!({
+                    "started_at": started_at,
+                    "message": "JPlag job started; results will be parsed and archived when ready"
+                }),
+                "Started JPlag job",
+            )),
+        )
+            .into_response();
+    }
+
     // 4) Prepare async job inputs
     let moss_user_id = config::moss_user_id();
     let moss_service = MossService::new(&moss_user_id);
@@ -526,61 +888,52 @@ pub async fn run_moss_check(
                 };
 
                 // (B) Parse → create cases (NO deletion of prior cases; link to this report)
+                //     Match pages are saved to a work dir as they are parsed, then stored
+                //     alongside the report's archive.
+                let pages_dir = report_row.as_ref().map(|m| {
+                    std::env::temp_dir().join(format!(
+                        "moss_matches_{}_{}_{}",
+                        module_id, assignment_id, m.id
+                    ))
+                });
                 let parse_opts = ParseOptions {
                     min_lines: 0,
-                    include_matches: false,
+                    include_matches: true,
+                    archive_dir: pages_dir.clone(),
+                    ..ParseOptions::default()
                 };
                 match parse_moss(&report_url, parse_opts).await {
                     Ok(parsed) => {
-                        use std::collections::HashSet;
-                        let mut seen = HashSet::<(i64, i64)>::new(); // dedupe within THIS run only
-                        let report_id_opt = report_row.as_ref().map(|m| m.id);
-
-                        for r in parsed.reports {
-                            let (Some(sub_a), Some(sub_b)) = (r.submission_id_a, r.submission_id_b)
-                            else {
-                                continue;
-                            };
-                            let (a, b, ua, ub) = if sub_a <= sub_b {
-                                (sub_a, sub_b, r.user_a, r.user_b)
-                            } else {
-                                (sub_b, sub_a, r.user_b, r.user_a)
-                            };
-                            if !seen.insert((a, b)) {
-                                continue;
-                            }
-
-                            let description = generate_description(
-                                &ua,
-                                &ub,
-                                a,
-                                b,
-                                r.total_lines_matched,
-                                r.total_percent,
-                            );
-                            let similarity: f32 =
-                                r.total_percent.unwrap_or(0.0).clamp(0.0, 100.0) as f32;
-                            let lines_matched = r.total_lines_matched.max(0);
-
-                            // NEW signature: (similarity, lines_matched, report_id)
-                            if let Err(e) = plagiarism_case::Model::create_case(
-                                &db,
-                                assignment_id,
-                                a,
-                                b,
-                                &description,
-                                similarity,
-                                lines_matched,
-                                report_id_opt,
+                        for e in &parsed.archive_errors {
+                            error!("MOSS: failed to archive match page {e}");
+                        }
+                        if let (Some(dir), Some(report)) = (&pages_dir, &report_row) {
+                            store_match_pages(
+                                dir,
+                                &moss_matches_dir(module_id, assignment_id, &report.id.to_string()),
+                                &parsed.reports,
                             )
-                            .await
-                            {
-                                error!("MOSS: failed to create case for ({a},{b}): {e}");
-                            }
+                            .await;
+                        }
+                        let report_id_opt = report_row.as_ref().map(|m| m.id);
+                        let cases = create_cases_from_reports(
+                            &db,
+                            assignment_id,
+                            &parsed.reports,
+                            report_id_opt,
+                            &corpus,
+                        )
+                        .await;
+                        if let Some(report_id) = report_id_opt {
+                            store_parsed_report(&db, report_id, assignment_id, parsed, &cases)
+                                .await;
                         }
                     }
                     Err(e) => error!("MOSS parse failed: {e}"),
                 }
+                if let Some(dir) = &pages_dir {
+                    let _ = fs::remove_dir_all(dir);
+                }
 
                 // (C) Archive (zip) if report row exists (unchanged semantics)
                 if let Some(report) = report_row {
@@ -602,11 +955,6 @@ pub async fn run_moss_check(
                         return;
                     }
 
-                    if let Err(e) = ensure_parent_dir(&final_zip) {
-                        error!("MOSS: archive prepare failed: {e}");
-                        return;
-                    }
-
                     let work_dir: PathBuf = std::env::temp_dir().join(format!(
                         "moss_arch_{}_{}_{}",
                         module_id, assignment_id, &report_id_str
@@ -615,13 +963,27 @@ pub async fn run_moss_check(
                         error!("MOSS: failed to create temp work dir: {e}");
                         return;
                     }
+                    let work_zip = work_dir.with_extension("zip");
 
                     let opts = ArchiveOptions { concurrency: 12 };
-                    match archive_moss_to_fs_and_zip(&report_url, &work_dir, &final_zip, opts).await
-                    {
-                        Ok((_manifest, _zip_abs)) => {
-                            let _ = fs::remove_dir_all(&work_dir);
+                    let archived =
+                        match archive_moss_to_fs_and_zip(&report_url, &work_dir, &work_zip, opts)
+                            .await
+                        {
+                            Ok(_) => match fs::read(&work_zip) {
+                                Ok(bytes) => storage()
+                                    .write(&key_for(&final_zip), &bytes)
+                                    .await
+                                    .map_err(anyhow::Error::from),
+                                Err(e) => Err(e.into()),
+                            },
+                            Err(e) => Err(e),
+                        };
+                    let _ = fs::remove_dir_all(&work_dir);
+                    let _ = fs::remove_file(&work_zip);
 
+                    match archived {
+                        Ok(()) => {
                             let exists_after = moss_report::Entity::find_by_id(report.id)
                                 .one(&db)
                                 .await
@@ -630,7 +992,7 @@ pub async fn run_moss_check(
                                 .is_some();
 
                             if !exists_after {
-                                if let Err(e) = fs::remove_file(&final_zip) {
+                                if let Err(e) = storage().delete(&key_for(&final_zip)).await {
                                     error!(
                                         "MOSS: report deleted during archive; failed to remove zip {}: {e}",
                                         final_zip.display()
@@ -664,13 +1026,7 @@ pub async fn run_moss_check(
                                 );
                             }
                         }
-                        Err(e) => {
-                            let _ = fs::remove_dir_all(&work_dir);
-                            if final_zip.exists() {
-                                let _ = fs::remove_file(&final_zip);
-                            }
-                            error!("MOSS archive failed: {e}");
-                        }
+                        Err(e) => error!("MOSS archive failed: {e}"),
                     }
                 } else {
                     info!("MOSS: skipping archive because the moss_report row was not created");
@@ -695,6 +1051,489 @@ pub async fn run_moss_check(
         .into_response()
 }
 
+/// Background half of a JPlag run: run → save report → create cases → keep `results.zip`.
+///
+/// The report row gets a `jplag://local` URL (there is nothing hosted to link to); the JPlag
+/// `results.zip` is stored as the report's archive, so it downloads like a MOSS archive and
+/// opens in the JPlag report viewer.
+#[allow(clippy::too_many_arguments)]
+async fn run_jplag_job(
+    db: DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+    service: JplagService,
+    base_files: Vec<PathBuf>,
+    submission_files: Vec<(PathBuf, Option<String>, Option<i64>)>,
+    opts: JplagRunOptions,
+    description: String,
+    corpus: RunCorpus,
+) {
+    let work_dir: PathBuf = std::env::temp_dir().join(format!(
+        "jplag_{}_{}_{}",
+        module_id,
+        assignment_id,
+        Utc::now().timestamp_millis()
+    ));
+    let filter_mode = opts.filter_mode.clone();
+    let filter_patterns = opts.filter_patterns.clone();
+
+    let result = match service
+        .run(&work_dir, base_files, submission_files, opts)
+        .await
+    {
+        Ok(r) => r,
+        Err(e) => {
+            error!("JPlag run failed: {e}");
+            let _ = fs::remove_dir_all(&work_dir);
+            return;
+        }
+    };
+
+    let report = match moss_report::Entity::create_report(
+        &db,
+        assignment_id,
+        "jplag://local",
+        filter_mode,
+        description,
+        filter_patterns,
+    )
+    .await
+    {
+        Ok(m) => m,
+        Err(e) => {
+            error!("JPlag: failed to save moss_report: {e}");
+            let _ = fs::remove_dir_all(&work_dir);
+            return;
+        }
+    };
+
+    let parse_opts = ParseOptions {
+        min_lines: 0,
+        include_matches: true,
+        ..ParseOptions::default()
+    };
+    match parse_jplag_dir(&result.report_dir, parse_opts) {
+        Ok(parsed) => {
+            let cases = create_cases_from_reports(
+                &db,
+                assignment_id,
+                &parsed.reports,
+                Some(report.id),
+                &corpus,
+            )
+            .await;
+            store_parsed_report(&db, report.id, assignment_id, parsed, &cases).await;
+        }
+        Err(e) => error!("JPlag parse failed: {e}"),
+    }
+
+    let final_zip = moss_archive_zip_path(module_id, assignment_id, &report.id.to_string());
+    let archived = match fs::read(&result.results_zip) {
+        Ok(bytes) => storage().write(&key_for(&final_zip), &bytes).await,
+        Err(e) => Err(e),
+    };
+    let _ = fs::remove_dir_all(&work_dir);
+    match archived {
+        Ok(_) => {
+            if let Err(e) =
+                moss_report::Entity::set_archive_state(&db, report.id, true, Some(Utc::now())).await
+            {
+                error!(
+                    "JPlag: failed to update archive state for report {}: {e}",
+                    report.id
+                );
+            } else {
+                info!(
+                    "JPlag results saved for report {}: {}",
+                    report.id,
+                    final_zip.display()
+                );
+            }
+        }
+        Err(e) => error!("JPlag: failed to store results.zip: {e}"),
+    }
+}
+
+/// Stores the match pages saved in `pages_dir` under `matches_dir`, along with the list of
+/// them per user pair (`MOSS_MATCH_MANIFEST`, served by `list_moss_match_pages`). Best
+/// effort: a page that cannot be stored is only logged.
+async fn store_match_pages(
+    pages_dir: &std::path::Path,
+    matches_dir: &std::path::Path,
+    reports: &[UserPairReport],
+) {
+    let entries = match fs::read_dir(pages_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("MOSS: failed to read saved match pages: {e}");
+            return;
+        }
+    };
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let key = key_for(&matches_dir.join(name));
+        let stored = match fs::read(&path) {
+            Ok(bytes) => storage().write(&key, &bytes).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = stored {
+            error!("MOSS: failed to store match page {key}: {e}");
+        }
+    }
+
+    let key = key_for(&matches_dir.join(MOSS_MATCH_MANIFEST));
+    match serde_json::to_vec_pretty(reports) {
+        Ok(bytes) => {
+            if let Err(e) = storage().write(&key, &bytes).await {
+                error!("MOSS: failed to write {key}: {e}");
+            }
+        }
+        Err(e) => error!("MOSS: failed to serialize match manifest: {e}"),
+    }
+}
+
+/// The submissions a run compared: who owns each, which group (with group submissions) it
+/// belongs to, and which came from archive corpora; plus the assignment's thresholds for
+/// opening cases.
+#[derive(Debug, Default)]
+struct RunCorpus {
+    owners: HashMap<i64, i64>,
+    groups: HashMap<i64, i64>,
+    historical: HashSet<i64>,
+    case_options: PlagiarismOptions,
+}
+
+/// One submission per user of `assignment`, picked by its grading policy (practice and
+/// ignored submissions excluded).
+async fn select_submissions(
+    db: &DatabaseConnection,
+    assignment: &AssignmentModel,
+) -> Result<Vec<assignment_submission::Model>, sea_orm::DbErr> {
+    let all_for_assignment = SubmissionEntity::find_active()
+        .filter(assignment_submission::Column::AssignmentId.eq(assignment.id))
+        .all(db)
+        .await?;
+    let user_ids: HashSet<i64> = all_for_assignment.iter().map(|s| s.user_id).collect();
+
+    // Group members share submissions; compare each one once
+    let mut seen = HashSet::new();
+    let mut chosen = Vec::with_capacity(user_ids.len());
+    for uid in user_ids {
+        if let Ok(Some(s)) =
+            assignment_submission::Model::get_best_for_user(db, assignment, uid).await
+            && seen.insert(s.id)
+        {
+            chosen.push(s);
+        }
+    }
+    Ok(chosen)
+}
+
+/// Admins, and lecturers or assistant lecturers of `module_id`.
+async fn is_module_staff(db: &DatabaseConnection, claims: &Claims, module_id: i64) -> bool {
+    if claims.admin {
+        return true;
+    }
+    for role in ["Lecturer", "AssistantLecturer"] {
+        if user::Model::is_in_role(db, claims.sub, module_id, role)
+            .await
+            .unwrap_or(false)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Creates one `"review"` case per matched submission pair, linked to `report_id`.
+///
+/// Pairs are deduplicated (order-independent) within this run and against the assignment's
+/// existing cases, which are kept as they are. Only pairs reaching the assignment's
+/// `plagiarism.case_min_similarity` or `plagiarism.case_min_lines` open a case (every pair when
+/// neither is set). Pairs involving an archive submission are marked `historical`; pairs of two
+/// archive submissions, of a student and their own archived work, or of two members of the same
+/// group are skipped.
+/// Used for both MOSS and JPlag results.
+///
+/// Returns the ids of the pairs' cases, created or existing, keyed by `(lower, higher)`
+/// submission id.
+async fn create_cases_from_reports(
+    db: &DatabaseConnection,
+    assignment_id: i64,
+    reports: &[UserPairReport],
+    report_id: Option<i64>,
+    corpus: &RunCorpus,
+) -> HashMap<(i64, i64), i64> {
+    let mut seen = HashSet::<(i64, i64)>::new();
+    let mut created = HashMap::new();
+    let existing: HashMap<(i64, i64), i64> = match plagiarism_case::Entity::find()
+        .filter(plagiarism_case::Column::AssignmentId.eq(assignment_id))
+        .all(db)
+        .await
+    {
+        Ok(cases) => cases
+            .into_iter()
+            .map(|c| {
+                let (a, b) = (c.submission_id_1, c.submission_id_2);
+                ((a.min(b), a.max(b)), c.id)
+            })
+            .collect(),
+        Err(e) => {
+            error!("Plagiarism: failed to load existing cases, none created: {e}");
+            return created;
+        }
+    };
+    let (mut already_open, mut below_threshold) = (0, 0);
+
+    for r in reports {
+        let (Some(sub_a), Some(sub_b)) = (r.submission_id_a, r.submission_id_b) else {
+            continue;
+        };
+        let (a, b, ua, ub) = if sub_a <= sub_b {
+            (sub_a, sub_b, &r.user_a, &r.user_b)
+        } else {
+            (sub_b, sub_a, &r.user_b, &r.user_a)
+        };
+        if !seen.insert((a, b)) {
+            continue;
+        }
+
+        let historical = match (
+            corpus.historical.contains(&a),
+            corpus.historical.contains(&b),
+        ) {
+            (true, true) => continue,
+            (false, false) => {
+                let (group_a, group_b) = (corpus.groups.get(&a), corpus.groups.get(&b));
+                if group_a.is_some() && group_a == group_b {
+                    continue;
+                }
+                false
+            }
+            _ => {
+                let (owner_a, owner_b) = (corpus.owners.get(&a), corpus.owners.get(&b));
+                if owner_a.is_some() && owner_a == owner_b {
+                    continue;
+                }
+                true
+            }
+        };
+
+        if let Some(&case_id) = existing.get(&(a, b)) {
+            created.insert((a, b), case_id);
+            already_open += 1;
+            continue;
+        }
+        let similarity: f32 = r.total_percent.unwrap_or(0.0).clamp(0.0, 100.0) as f32;
+        let lines_matched = r.total_lines_matched.max(0);
+        if !corpus
+            .case_options
+            .opens_case(f64::from(similarity), lines_matched)
+        {
+            below_threshold += 1;
+            continue;
+        }
+        let description =
+            generate_description(ua, ub, a, b, r.total_lines_matched, r.total_percent);
+
+        // NEW signature: (similarity, lines_matched, report_id)
+        match plagiarism_case::Model::create_case(
+            db,
+            assignment_id,
+            a,
+            b,
+            &description,
+            similarity,
+            lines_matched,
+            report_id,
+        )
+        .await
+        {
+            Ok(case) => {
+                if historical
+                    && let Err(e) = plagiarism_case::Model::mark_historical(db, case.id).await
+                {
+                    error!(
+                        "Plagiarism: failed to mark case {} historical: {e}",
+                        case.id
+                    );
+                }
+                created.insert((a, b), case.id);
+            }
+            Err(e) => error!("Plagiarism: failed to create case for ({a},{b}): {e}"),
+        }
+    }
+    info!(
+        "Plagiarism: assignment {assignment_id}: {} pairs, {already_open} with existing cases, \
+         {below_threshold} below the case thresholds",
+        seen.len()
+    );
+    created
+}
+
+/// Stores the parsed run as a `plagiarism_report` with one `plagiarism_match` per user pair,
+/// linking each pair to the case created for it in this run (if any). Best effort: a failure
+/// is logged and leaves the run's cases in place.
+async fn store_parsed_report(
+    db: &DatabaseConnection,
+    moss_report_id: i64,
+    assignment_id: i64,
+    parsed: moss_parser::Output,
+    cases: &HashMap<(i64, i64), i64>,
+) {
+    let matches = parsed
+        .reports
+        .into_iter()
+        .map(|r| {
+            let case_id = match (r.submission_id_a, r.submission_id_b) {
+                (Some(a), Some(b)) => cases.get(&(a.min(b), a.max(b))).copied(),
+                _ => None,
+            };
+            NewMatch {
+                files: r
+                    .matches
+                    .as_ref()
+                    .and_then(|rows| serde_json::to_value(rows).ok()),
+                user_a: r.user_a,
+                user_b: r.user_b,
+                submission_id_a: r.submission_id_a,
+                submission_id_b: r.submission_id_b,
+                total_percent: r.total_percent,
+                lines_matched: r.total_lines_matched,
+                case_id,
+            }
+        })
+        .collect();
+
+    if let Err(e) =
+        plagiarism_report::Model::store(db, moss_report_id, assignment_id, parsed.title, matches)
+            .await
+    {
+        error!("Plagiarism: failed to store parsed report {moss_report_id}: {e}");
+    }
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/base-files
+///
+/// Uploads starter/skeleton code that plagiarism runs should not count as similarity. MOSS
+/// receives it as base files (`-b`) and JPlag as base code (`-bc`), so code every student was
+/// given stops inflating similarity percentages. Unlike spec files, uploading here does not
+/// notify students.
+///
+/// ### Request Body (Multipart Form Data)
+/// - `file` (file, required): a source file or a `.zip` of the starter pack. One file per
+///   request; a file with the same name is replaced.
+///
+/// ### Responses
+/// - `201 Created` with the stored file (`filename`, `size`, `uploaded_at`)
+/// - `400 Bad Request` — missing or empty file, more than one file, an invalid file name, or a
+///   `.zip` that cannot be opened
+/// - `500 Internal Server Error` — failed to save the file
+pub async fn upload_plagiarism_base_file(
+    AxumPath((module_id, assignment_id)): AxumPath<(i64, i64)>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let mut upload: Option<(Option<String>, Vec<u8>)> = None;
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(f)) => f,
+            Ok(None) => break,
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Malformed multipart payload")),
+                )
+                    .into_response();
+            }
+        };
+        if field.name() != Some("file") {
+            continue;
+        }
+        if upload.is_some() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "Only one file may be uploaded per request",
+                )),
+            )
+                .into_response();
+        }
+        let file_name = field.file_name().map(|s| s.to_string());
+        match field.bytes().await {
+            Ok(b) => upload = Some((file_name, b.to_vec())),
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Unreadable file payload")),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let Some((file_name, bytes)) = upload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Missing file upload")),
+        )
+            .into_response();
+    };
+    if bytes.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Empty file provided")),
+        )
+            .into_response();
+    }
+
+    // Keep only the final path component; no hidden files
+    let file_name = file_name
+        .as_deref()
+        .and_then(|n| std::path::Path::new(n).file_name())
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.starts_with('.'))
+        .map(str::to_string);
+    let Some(file_name) = file_name else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Invalid file name")),
+        )
+            .into_response();
+    };
+
+    if file_name.ends_with(".zip") && zip::ZipArchive::new(std::io::Cursor::new(&bytes)).is_err() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Invalid ZIP archive")),
+        )
+            .into_response();
+    }
+
+    let path = plagiarism_base_dir(module_id, assignment_id).join(&file_name);
+    let saved = ensure_parent_dir(&path).and_then(|_| fs::write(&path, &bytes));
+    match saved
+        .ok()
+        .and_then(|_| PlagiarismBaseFile::from_path(&path))
+    {
+        Some(file) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                file,
+                "Base file uploaded successfully",
+            )),
+        )
+            .into_response(),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to save file")),
+        )
+            .into_response(),
+    }
+}
+
 fn generate_description(
     user_a: &str,
     user_b: &str,
@@ -784,6 +1623,8 @@ pub struct CreatedCases {
 /// - **Submission selection respects the assignment's grading policy**:
 ///   - `grading_policy = "last"` → uses each student's **most recent** non-practice, non-ignored submission
 ///   - `grading_policy = "best"` → uses each student's **best-scoring** non-practice, non-ignored submission
+///   - `grading_policy = "best_of_last_n"` → the best-scoring of their `best_of_last_n` most recent ones
+///   - `grading_policy = "weighted"` → their most recent one, as for `last`
 /// - Groups submissions by their SHA-256 file hash
 /// - Only includes groups with 2+ submissions (actual collisions)
 /// - Filters out submissions with empty/missing file hashes
@@ -913,10 +1754,13 @@ pub async fn hash_scan(
     let exec_config = ExecutionConfig::get_execution_config(module_id, assignment_id)
         .unwrap_or_else(|_| ExecutionConfig::default_config());
     let policy_used = match exec_config.marking.grading_policy {
-        util::execution_config::GradingPolicy::Best => "Best",
-        util::execution_config::GradingPolicy::Last => "Last",
-    }
-    .to_string();
+        util::execution_config::GradingPolicy::Best => "Best".to_string(),
+        util::execution_config::GradingPolicy::Last => "Last".to_string(),
+        util::execution_config::GradingPolicy::BestOfLastN => {
+            format!("Best of last {}", exec_config.marking.best_of_last_n)
+        }
+        util::execution_config::GradingPolicy::Weighted => "Weighted".to_string(),
+    };
 
     let selected_submissions: Vec<assignment_submission::Model> =
         match assignment_submission::Model::get_selected_submissions_for_assignment(
@@ -936,6 +1780,22 @@ pub async fn hash_scan(
             }
         };
 
+    // Group of each submission, so members of the same group aren't flagged against each other
+    let submission_group: HashMap<i64, i64> = if assignment.group_submissions() {
+        let group_of = GroupModel::memberships(app_state.db(), assignment_id)
+            .await
+            .unwrap_or_default();
+        selected_submissions
+            .iter()
+            .filter_map(|s| {
+                let group = s.group_id.or(group_of.get(&s.user_id).copied())?;
+                Some((s.id, group))
+            })
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
     let mut groups_map: HashMap<String, Vec<assignment_submission::Model>> = HashMap::new();
     for s in selected_submissions
         .into_iter()
@@ -972,6 +1832,10 @@ pub async fn hash_scan(
                     if s1.user_id == s2.user_id {
                         continue;
                     }
+                    let group_1 = submission_group.get(&s1.submission_id);
+                    if group_1.is_some() && group_1 == submission_group.get(&s2.submission_id) {
+                        continue;
+                    }
 
                     let submission_id_1 = std::cmp::min(s1.submission_id, s2.submission_id);
                     let submission_id_2 = std::cmp::max(s1.submission_id, s2.submission_id);
@@ -1074,3 +1938,140 @@ pub async fn hash_scan(
     )
         .into_response()
 }
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/plagiarism/{case_id}/evidence
+///
+/// Attaches a file of evidence (interview notes, annotated code, correspondence) to a plagiarism
+/// case. The file is tagged with the case's current stage; moving the case on needs evidence from
+/// the stage it is leaving (see `PATCH /{case_id}/stage`). Evidence cannot be removed.
+///
+/// ### Request Body (Multipart Form Data)
+/// - `file` (file, required): one file per request.
+///
+/// ### Responses
+/// - `201 Created` with the stored evidence (`id`, `stage`, `filename`, `size`, `uploaded_by`,
+///   `created_at`)
+/// - `400 Bad Request` — missing or empty file, more than one file, or an invalid file name
+/// - `404 Not Found` — no such case in this assignment
+/// - `409 Conflict` — the case is resolved
+/// - `500 Internal Server Error` — failed to save the file
+pub async fn upload_case_evidence(
+    State(app_state): State<AppState>,
+    AxumPath((module_id, assignment_id, case_id)): AxumPath<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let case = match plagiarism_case::Entity::find_by_id(case_id).one(db).await {
+        Ok(Some(case)) if case.assignment_id == assignment_id => case,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Plagiarism case not found")),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!("Database error: {e}"))),
+            )
+                .into_response();
+        }
+    };
+    if case.stage == Some(plagiarism_case::Stage::Resolved) {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(
+                "Evidence cannot be added to a resolved case",
+            )),
+        )
+            .into_response();
+    }
+
+    let mut upload: Option<(Option<String>, Vec<u8>)> = None;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(f)) => f,
+            Ok(None) => break,
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Malformed multipart payload")),
+                )
+                    .into_response();
+            }
+        };
+        if field.name() != Some("file") {
+            continue;
+        }
+        if upload.is_some() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "Only one file may be uploaded per request",
+                )),
+            )
+                .into_response();
+        }
+        let file_name = field.file_name().map(|s| s.to_string());
+        match field.bytes().await {
+            Ok(b) => upload = Some((file_name, b.to_vec())),
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Unreadable file payload")),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let Some((file_name, bytes)) = upload else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Missing file upload")),
+        )
+            .into_response();
+    };
+    if bytes.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Empty file provided")),
+        )
+            .into_response();
+    }
+    // Keep only the final path component
+    let Some(file_name) = file_name
+        .as_deref()
+        .and_then(|n| std::path::Path::new(n).file_name())
+        .and_then(|n| n.to_str())
+        .filter(|n| !n.starts_with('.'))
+        .map(str::to_string)
+    else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("Invalid file name")),
+        )
+            .into_response();
+    };
+
+    match EvidenceModel::save_file(db, module_id, &case, &file_name, &bytes, claims.sub).await {
+        Ok(evidence) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                EvidenceResponse::from(evidence),
+                "Evidence uploaded",
+            )),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to save evidence: {e}"
+            ))),
+        )
+            .into_response(),
+    }
+}
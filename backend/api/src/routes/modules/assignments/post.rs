@@ -1,7 +1,8 @@
-//! Assignment creation route.
+//! Assignment creation and restore routes.
 //!
-//! Provides an endpoint for creating a new assignment in a module:
+//! Provides endpoints for creating a new assignment in a module and bringing back a deleted one:
 //! - `POST /api/modules/{module_id}/assignments`
+//! - `POST /api/modules/{module_id}/assignments/{assignment_id}/restore`
 //!
 //! Key points:
 //! - Assignments are created in the `setup` state by default.
@@ -184,8 +185,8 @@ pub async fn verify_assignment_pin(
     let db = app_state.db();
 
     let assignment_res = AssignmentEntity::find()
-        .filter(AssignmentCol::Id.eq(assignment_id as i32))
-        .filter(AssignmentCol::ModuleId.eq(module_id as i32))
+        .filter(AssignmentCol::Id.eq(assignment_id))
+        .filter(AssignmentCol::ModuleId.eq(module_id))
         .one(db)
         .await;
 
@@ -225,3 +226,54 @@ pub async fn verify_assignment_pin(
         )),
     )
 }
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/restore
+///
+/// Restore a soft-deleted assignment, together with everything under it.
+/// Only possible until the retention sweep purges it (`SOFT_DELETE_RETENTION_DAYS`).
+/// Only accessible by lecturers or assistant lecturers assigned to the module.
+///
+/// ### Responses
+///
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Assignment 123 restored successfully"
+/// }
+/// ```
+///
+/// - `404 Not Found`
+/// ```json
+/// {
+///   "success": false,
+///   "message": "No deleted assignment 123 in module 456"
+/// }
+/// ```
+pub async fn restore_assignment(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    match AssignmentModel::restore(db, assignment_id, module_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success(
+                (),
+                format!("Assignment {} restored successfully", assignment_id),
+            )),
+        ),
+        Err(DbErr::RecordNotFound(msg)) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(msg)),
+        ),
+        Err(e) => {
+            eprintln!("DB error in restore_assignment: {e:?}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use db::models::{
+    regrade_request::Model as RegradeModel,
+    user,
+    user_module_role::{self, Role},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegradeResponse {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub submission_id: i64,
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub task_number: i64,
+    pub subsection: Option<String>,
+    pub reason: String,
+    pub status: String,
+    pub response: Option<String>,
+    pub responded_by: Option<i64>,
+    pub resolved_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl RegradeResponse {
+    pub fn new(r: RegradeModel, username: Option<String>) -> Self {
+        Self {
+            id: r.id,
+            assignment_id: r.assignment_id,
+            submission_id: r.submission_id,
+            user_id: r.user_id,
+            username,
+            task_number: r.task_number,
+            subsection: r.subsection,
+            reason: r.reason,
+            status: r.status.to_string(),
+            response: r.response,
+            responded_by: r.responded_by,
+            resolved_at: r.resolved_at.map(|t| t.to_rfc3339()),
+            created_at: r.created_at.to_rfc3339(),
+            updated_at: r.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Whether `user_id` takes the module as a student (and so only sees their own requests).
+pub async fn is_student(module_id: i64, user_id: i64, db: &DatabaseConnection) -> bool {
+    user_module_role::Entity::find()
+        .filter(user_module_role::Column::UserId.eq(user_id))
+        .filter(user_module_role::Column::ModuleId.eq(module_id))
+        .filter(user_module_role::Column::Role.eq(Role::Student))
+        .count(db)
+        .await
+        .map(|n| n > 0)
+        .unwrap_or(false)
+}
+
+pub async fn username(db: &DatabaseConnection, user_id: i64) -> Option<String> {
+    user::Entity::find_by_id(user_id)
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+        .map(|u| u.username)
+}
@@ -0,0 +1,168 @@
+use super::common::{RegradeResponse, is_student, username};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{
+    regrade_request::{Entity as RegradeEntity, Model as RegradeModel, RegradeStatus},
+    user,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::Deserialize;
+use std::collections::HashMap;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct ListRegradesQuery {
+    /// `open`, `under_review` or `resolved`
+    pub status: Option<String>,
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/regrades
+///
+/// Lists the assignment's regrade requests, newest first. Students only see their own.
+///
+/// ### Query Parameters
+/// - `status` (optional): `open`, `under_review` or `resolved`
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": [
+///     {
+///       "id": 4,
+///       "assignment_id": 2,
+///       "submission_id": 17,
+///       "user_id": 10,
+///       "username": "u10000001",
+///       "task_number": 1,
+///       "subsection": "Output",
+///       "reason": "My output matches the memo apart from trailing whitespace",
+///       "status": "under_review",
+///       "response": null,
+///       "responded_by": 3,
+///       "resolved_at": null,
+///       "created_at": "2025-10-16T10:00:00+00:00",
+///       "updated_at": "2025-10-16T11:00:00+00:00"
+///     }
+///   ],
+///   "message": "Regrade requests retrieved"
+/// }
+/// ```
+///
+/// ### Responses
+/// - `400 Bad Request` — Unknown `status`
+pub async fn list_regrades(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Query(query): Query<ListRegradesQuery>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let status = match query.status.as_deref() {
+        None => None,
+        Some(s) => match s.parse::<RegradeStatus>() {
+            Ok(status) => Some(status),
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Invalid status value")),
+                )
+                    .into_response();
+            }
+        },
+    };
+    let only_user = is_student(module_id, claims.sub, db)
+        .await
+        .then_some(claims.sub);
+
+    let requests =
+        match RegradeModel::list_for_assignment(db, assignment_id, only_user, status).await {
+            Ok(r) => r,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error(
+                        "Failed to retrieve regrade requests",
+                    )),
+                )
+                    .into_response();
+            }
+        };
+
+    let usernames: HashMap<i64, String> = user::Entity::find()
+        .filter(user::Column::Id.is_in(requests.iter().map(|r| r.user_id)))
+        .all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    let response: Vec<RegradeResponse> = requests
+        .into_iter()
+        .map(|r| {
+            let username = usernames.get(&r.user_id).cloned();
+            RegradeResponse::new(r, username)
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(response, "Regrade requests retrieved")),
+    )
+        .into_response()
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/regrades/{regrade_id}
+///
+/// Returns one regrade request (same shape as the items of `GET /regrades`) to the student who
+/// opened it or to staff.
+///
+/// ### Responses
+/// - `403 Forbidden` — Another student's request
+pub async fn get_regrade(
+    State(app_state): State<AppState>,
+    Path((module_id, _, regrade_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let request = match RegradeEntity::find_by_id(regrade_id).one(db).await {
+        Ok(Some(r)) => r,
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(
+                    "Failed to retrieve regrade request",
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    if request.user_id != claims.sub && is_student(module_id, claims.sub, db).await {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "You can only view your own regrade requests",
+            )),
+        )
+            .into_response();
+    }
+
+    let username = username(db, request.user_id).await;
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            RegradeResponse::new(request, username),
+            "Regrade request retrieved",
+        )),
+    )
+        .into_response()
+}
@@ -0,0 +1,53 @@
+//! Regrade request routes module.
+//!
+//! Provides the `/regrades` route group: students dispute the mark of one task (or subsection)
+//! of a marked submission, and staff review, answer and resolve the request, optionally
+//! remarking the submission.
+//!
+//! Routes include:
+//! - List and get requests (students see their own; staff see all)
+//! - Open a request (students)
+//! - Respond to a request (tutor or higher)
+//!
+//! Staff are told about new requests, and students about responses, on the assignment's
+//! submission WebSocket topics; students are also sent a `regrade_updated` notification.
+
+use crate::auth::guards::{allow_tutor, deny_after_moderation_sign_off};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{get, post, put},
+};
+use get::{get_regrade, list_regrades};
+use post::open_regrade;
+use put::respond_to_regrade;
+use util::state::AppState;
+
+pub mod common;
+pub mod get;
+pub mod post;
+pub mod put;
+
+/// Builds and returns the `/regrades` route group.
+///
+/// Routes:
+/// - `GET  /regrades`               → List requests (students: their own)
+/// - `POST /regrades`               → Open a request (students)
+/// - `GET  /regrades/{regrade_id}`  → Get a request (its student or staff)
+/// - `PUT  /regrades/{regrade_id}`  → Respond, change status and/or remark (tutor or higher;
+///   refused once moderation is signed off)
+pub fn regrade_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_regrades))
+        .route("/", post(open_regrade))
+        .route("/{regrade_id}", get(get_regrade))
+        .route(
+            "/{regrade_id}",
+            put(respond_to_regrade)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(app_state.clone(), allow_tutor)),
+        )
+}
@@ -0,0 +1,260 @@
+use super::common::{RegradeResponse, is_student, username};
+use crate::ws::regrades::{emit as r_emit, payload as r_payload};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{
+    assignment::Entity as AssignmentEntity,
+    assignment_submission::{self, Model as SubmissionModel, SubmissionStatus},
+    assignment_task,
+    regrade_request::Model as RegradeModel,
+};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::Deserialize;
+use serde_json::Value;
+use util::{paths::submission_report_path, state::AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct OpenRegradeRequest {
+    pub submission_id: i64,
+    pub task_number: i64,
+    /// Label of the disputed subsection, as in the submission's report; omit to dispute the
+    /// whole task
+    pub subsection: Option<String>,
+    pub reason: String,
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/regrades
+///
+/// Opens a regrade request against a task (or one of its subsections) of one of the caller's
+/// marked submissions. Students only.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "submission_id": 17,
+///   "task_number": 1,
+///   "subsection": "Output",
+///   "reason": "My output matches the memo apart from trailing whitespace"
+/// }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — The request (same shape as the items of `GET /regrades`)
+/// - `400 Bad Request` — Empty reason, a submission that isn't marked, or an unknown task or
+///   subsection
+/// - `403 Forbidden` — Not a student of the module, or someone else's submission
+/// - `404 Not Found` — Submission not found in this assignment
+/// - `409 Conflict` — A request for the same task/subsection is still pending
+pub async fn open_regrade(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<OpenRegradeRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    let user_id = claims.sub;
+
+    let reason = req.reason.trim();
+    if reason.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("A reason is required")),
+        )
+            .into_response();
+    }
+    let subsection = req
+        .subsection
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+
+    if !is_student(module_id, user_id, db).await {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "Only students can request a regrade",
+            )),
+        )
+            .into_response();
+    }
+
+    let assignment = match AssignmentEntity::find_by_id(assignment_id).one(db).await {
+        Ok(Some(a)) => a,
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load assignment")),
+            )
+                .into_response();
+        }
+    };
+
+    let submission = match assignment_submission::Entity::find_by_id(req.submission_id)
+        .filter(assignment_submission::Column::AssignmentId.eq(assignment_id))
+        .filter(assignment_submission::Column::DeletedAt.is_null())
+        .one(db)
+        .await
+    {
+        Ok(Some(s)) => s,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Submission not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load submission")),
+            )
+                .into_response();
+        }
+    };
+
+    let owned = match SubmissionModel::owner_condition(db, &assignment, user_id).await {
+        Ok(cond) => assignment_submission::Entity::find_by_id(submission.id)
+            .filter(cond)
+            .count(db)
+            .await
+            .map(|n| n > 0)
+            .unwrap_or(false),
+        Err(_) => false,
+    };
+    if !owned {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "You can only request a regrade of your own submissions",
+            )),
+        )
+            .into_response();
+    }
+
+    if submission.status != SubmissionStatus::Graded {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Only marked submissions can be regraded",
+            )),
+        )
+            .into_response();
+    }
+
+    let task_exists = assignment_task::Entity::find()
+        .filter(assignment_task::Column::AssignmentId.eq(assignment_id))
+        .filter(assignment_task::Column::TaskNumber.eq(req.task_number))
+        .count(db)
+        .await
+        .unwrap_or(0)
+        > 0;
+    if !task_exists {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(format!(
+                "Task {} does not exist",
+                req.task_number
+            ))),
+        )
+            .into_response();
+    }
+    if let Some(label) = subsection
+        && !report_has_subsection(module_id, &submission, req.task_number, label)
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(format!(
+                "Task {} has no subsection '{}'",
+                req.task_number, label
+            ))),
+        )
+            .into_response();
+    }
+
+    match RegradeModel::find_pending(db, submission.id, req.task_number, subsection).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(
+                    "A regrade request for this task is already pending",
+                )),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to open regrade request")),
+            )
+                .into_response();
+        }
+    }
+
+    let request = match RegradeModel::open(
+        db,
+        &submission,
+        user_id,
+        req.task_number,
+        subsection,
+        reason,
+    )
+    .await
+    {
+        Ok(r) => r,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to open regrade request")),
+            )
+                .into_response();
+        }
+    };
+
+    r_emit::opened(&app_state.ws_clone(), r_payload::Regrade::from(&request)).await;
+
+    let username = username(db, user_id).await;
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::success(
+            RegradeResponse::new(request, username),
+            "Regrade request opened",
+        )),
+    )
+        .into_response()
+}
+
+/// Whether the submission's report lists `label` among the subsections of `task_number`.
+/// Submissions without a readable report accept any label.
+fn report_has_subsection(
+    module_id: i64,
+    submission: &SubmissionModel,
+    task_number: i64,
+    label: &str,
+) -> bool {
+    let path = submission_report_path(
+        module_id,
+        submission.assignment_id,
+        submission.user_id,
+        submission.attempt,
+    );
+    let Some(report) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+    else {
+        return true;
+    };
+    report["tasks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|t| t["task_number"].as_i64() == Some(task_number))
+        .filter_map(|t| t["subsections"].as_array())
+        .flatten()
+        .any(|s| s["label"].as_str() == Some(label))
+}
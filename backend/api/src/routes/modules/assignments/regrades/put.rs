@@ -0,0 +1,184 @@
+use super::common::{RegradeResponse, username};
+use crate::routes::modules::assignments::submissions::post::remark_submission;
+use crate::services::lti;
+use crate::services::notifications::{self, NewNotification};
+use crate::ws::regrades::{emit as r_emit, payload as r_payload};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{
+    assignment_submission,
+    notification::NotificationKind,
+    regrade_request::{Entity as RegradeEntity, RegradeStatus},
+};
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct RespondToRegradeRequest {
+    /// `under_review` or `resolved`; requests only move forward
+    pub status: Option<String>,
+    /// Reply shown to the student
+    pub response: Option<String>,
+    /// Remark the disputed submission in the background
+    #[serde(default)]
+    pub remark: bool,
+}
+
+/// PUT /api/modules/{module_id}/assignments/{assignment_id}/regrades/{regrade_id}
+///
+/// Records a staff response to a regrade request: a status change, a reply, and/or a remark of
+/// the submission. Tutor or higher. The student is notified (`regrade_updated`) and the
+/// request is pushed to the assignment's submission topics as `regrade.updated`.
+///
+/// ### Request Body
+/// ```json
+/// { "status": "resolved", "response": "Whitespace is now ignored; remarked.", "remark": true }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The updated request (same shape as the items of `GET /regrades`)
+/// - `400 Bad Request` — Nothing to change, or an unknown `status`
+/// - `409 Conflict` — A status the request can't move to (e.g. reopening a resolved one)
+pub async fn respond_to_regrade(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, regrade_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<RespondToRegradeRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let reply = req
+        .response
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    if req.status.is_none() && reply.is_none() && !req.remark {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Provide a status, a response or remark: true",
+            )),
+        )
+            .into_response();
+    }
+    let status = match req.status.as_deref() {
+        None => None,
+        Some(s) => match s.parse::<RegradeStatus>() {
+            Ok(status) => Some(status),
+            Err(_) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error("Invalid status value")),
+                )
+                    .into_response();
+            }
+        },
+    };
+
+    let request = match RegradeEntity::find_by_id(regrade_id).one(db).await {
+        Ok(Some(r)) => r,
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(
+                    "Failed to retrieve regrade request",
+                )),
+            )
+                .into_response();
+        }
+    };
+    if let Some(next) = status
+        && !request.status.can_transition_to(next)
+    {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(format!(
+                "A regrade request cannot move from {} to {}",
+                request.status, next
+            ))),
+        )
+            .into_response();
+    }
+
+    let submission_id = request.submission_id;
+    let request = match request.respond(db, status, reply, claims.sub).await {
+        Ok(r) => r,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to update regrade request")),
+            )
+                .into_response();
+        }
+    };
+
+    if req.remark {
+        let db = app_state.db_clone();
+        tokio::spawn(async move {
+            let submission = match assignment_submission::Entity::find_by_id(submission_id)
+                .one(&db)
+                .await
+            {
+                Ok(Some(s)) => s,
+                _ => return,
+            };
+            let user_id = submission.user_id;
+            if let Err(e) = remark_submission(submission, &db).await {
+                tracing::warn!(
+                    "Remark of submission {} for regrade {} failed: {}",
+                    submission_id,
+                    regrade_id,
+                    e
+                );
+                return;
+            }
+            if let Err(e) = lti::sync_grades(&db, module_id, assignment_id, Some(&[user_id])).await
+            {
+                tracing::warn!("LTI grade sync after regrade {} failed: {}", regrade_id, e);
+            }
+        });
+    }
+
+    r_emit::updated(&app_state.ws_clone(), r_payload::Regrade::from(&request)).await;
+
+    let status_text = request.status.to_string().replace('_', " ");
+    notifications::spawn_notify(
+        &app_state,
+        vec![request.user_id],
+        NewNotification {
+            kind: NotificationKind::RegradeUpdated,
+            title: format!(
+                "Regrade request for task {}: {}",
+                request.task_number, status_text
+            ),
+            body: request
+                .response
+                .clone()
+                .unwrap_or_else(|| format!("Your regrade request is {status_text}.")),
+            link: Some(format!(
+                "/modules/{}/assignments/{}/submissions/{}",
+                module_id, assignment_id, request.submission_id
+            )),
+        },
+    );
+
+    let username = username(db, request.user_id).await;
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            RegradeResponse::new(request, username),
+            if req.remark {
+                "Regrade request updated; remark started"
+            } else {
+                "Regrade request updated"
+            },
+        )),
+    )
+        .into_response()
+}
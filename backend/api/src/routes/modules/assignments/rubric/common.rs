@@ -0,0 +1,95 @@
+use db::models::{
+    rubric::Model as RubricModel,
+    rubric_criterion::Model as CriterionModel,
+    user_module_role::{self, Role},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CriterionResponse {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub max_score: f64,
+}
+
+impl From<CriterionModel> for CriterionResponse {
+    fn from(c: CriterionModel) -> Self {
+        Self {
+            id: c.id,
+            name: c.name,
+            description: c.description,
+            max_score: c.max_score,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RubricResponse {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    /// Percentage (0–100) of the final grade taken from the rubric
+    pub weight: f64,
+    /// Sum of the criteria's `max_score`
+    pub total: f64,
+    pub criteria: Vec<CriterionResponse>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl RubricResponse {
+    pub fn new(r: RubricModel, criteria: Vec<CriterionModel>) -> Self {
+        Self {
+            id: r.id,
+            assignment_id: r.assignment_id,
+            title: r.title,
+            description: r.description,
+            weight: r.weight,
+            total: criteria.iter().map(|c| c.max_score).sum(),
+            criteria: criteria.into_iter().map(CriterionResponse::from).collect(),
+            created_at: r.created_at.to_rfc3339(),
+            updated_at: r.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScoredCriterion {
+    pub criterion_id: i64,
+    pub name: String,
+    pub max_score: f64,
+    /// `None` until a marker scores it
+    pub score: Option<f64>,
+    pub comment: Option<String>,
+    pub marked_by: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubmissionRubricResponse {
+    pub submission_id: i64,
+    pub rubric_id: i64,
+    pub weight: f64,
+    pub criteria: Vec<ScoredCriterion>,
+    pub earned: f64,
+    pub total: f64,
+    pub automated_percentage: f64,
+    /// `None` while no criterion has been scored
+    pub rubric_percentage: Option<f64>,
+    /// What the submission counts for: the automated percentage until it is scored
+    pub final_percentage: f64,
+}
+
+/// Whether `user_id` takes the module as a student (and so only sees their own submissions).
+pub async fn is_student(module_id: i64, user_id: i64, db: &DatabaseConnection) -> bool {
+    user_module_role::Entity::find()
+        .filter(user_module_role::Column::UserId.eq(user_id))
+        .filter(user_module_role::Column::ModuleId.eq(module_id))
+        .filter(user_module_role::Column::Role.eq(Role::Student))
+        .count(db)
+        .await
+        .map(|n| n > 0)
+        .unwrap_or(false)
+}
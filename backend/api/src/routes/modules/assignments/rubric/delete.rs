@@ -0,0 +1,37 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::rubric::Model as RubricModel;
+use sea_orm::DbErr;
+use util::state::AppState;
+
+/// DELETE /api/modules/{module_id}/assignments/{assignment_id}/rubric
+///
+/// Removes the assignment's rubric along with every score recorded against it; grades go back
+/// to the automated marks. Assistant lecturer or higher.
+pub async fn delete_rubric(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    match RubricModel::delete_for_assignment(app_state.db(), assignment_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "Rubric deleted")),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("This assignment has no rubric")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to delete rubric")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,240 @@
+use super::common::{RubricResponse, ScoredCriterion, SubmissionRubricResponse, is_student};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::grade::percentage;
+use db::models::{
+    assignment::Entity as AssignmentEntity,
+    assignment_submission::{self, Model as SubmissionModel},
+    rubric::Model as RubricModel,
+};
+use sea_orm::{EntityTrait, PaginatorTrait, QueryFilter};
+use util::state::AppState;
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/rubric
+///
+/// Returns the assignment's rubric with its criteria in order.
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "id": 1,
+///     "assignment_id": 2,
+///     "title": "Code quality",
+///     "description": null,
+///     "weight": 30.0,
+///     "total": 10.0,
+///     "criteria": [
+///       { "id": 1, "name": "Style", "description": "Naming and layout", "max_score": 5.0 },
+///       { "id": 2, "name": "Design", "description": null, "max_score": 5.0 }
+///     ],
+///     "created_at": "2025-10-16T10:00:00+00:00",
+///     "updated_at": "2025-10-16T10:00:00+00:00"
+///   },
+///   "message": "Rubric retrieved"
+/// }
+/// ```
+///
+/// ### Responses
+/// - `404 Not Found` — The assignment has no rubric
+pub async fn get_rubric(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let rubric = match RubricModel::for_assignment(db, assignment_id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("This assignment has no rubric")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve rubric")),
+            )
+                .into_response();
+        }
+    };
+    let criteria = match rubric.criteria(db).await {
+        Ok(c) => c,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve rubric")),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            RubricResponse::new(rubric, criteria),
+            "Rubric retrieved",
+        )),
+    )
+        .into_response()
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/rubric/submissions/{submission_id}
+///
+/// Returns a submission's rubric scores and how they blend with its automated mark. Students
+/// may only view their own (or their group's) submissions.
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "submission_id": 17,
+///     "rubric_id": 1,
+///     "weight": 30.0,
+///     "criteria": [
+///       { "criterion_id": 1, "name": "Style", "max_score": 5.0, "score": 4.0, "comment": "Tidy", "marked_by": 3 },
+///       { "criterion_id": 2, "name": "Design", "max_score": 5.0, "score": null, "comment": null, "marked_by": null }
+///     ],
+///     "earned": 4.0,
+///     "total": 10.0,
+///     "automated_percentage": 90.0,
+///     "rubric_percentage": 40.0,
+///     "final_percentage": 75.0
+///   },
+///   "message": "Rubric scores retrieved"
+/// }
+/// ```
+///
+/// ### Responses
+/// - `403 Forbidden` — Another student's submission
+/// - `404 Not Found` — The assignment has no rubric
+pub async fn get_submission_rubric(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, submission_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let submission = match assignment_submission::Entity::find_by_id(submission_id)
+        .one(db)
+        .await
+    {
+        Ok(Some(s)) => s,
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load submission")),
+            )
+                .into_response();
+        }
+    };
+
+    if is_student(module_id, claims.sub, db).await {
+        let owned = match AssignmentEntity::find_by_id(assignment_id).one(db).await {
+            Ok(Some(assignment)) => {
+                match SubmissionModel::owner_condition(db, &assignment, claims.sub).await {
+                    Ok(cond) => assignment_submission::Entity::find_by_id(submission_id)
+                        .filter(cond)
+                        .count(db)
+                        .await
+                        .map(|n| n > 0)
+                        .unwrap_or(false),
+                    Err(_) => false,
+                }
+            }
+            _ => false,
+        };
+        if !owned {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error(
+                    "You can only view your own submissions",
+                )),
+            )
+                .into_response();
+        }
+    }
+
+    let rubric = match RubricModel::for_assignment(db, assignment_id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("This assignment has no rubric")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve rubric")),
+            )
+                .into_response();
+        }
+    };
+
+    match submission_rubric(db, &rubric, &submission).await {
+        Ok(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(response, "Rubric scores retrieved")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve rubric scores")),
+        )
+            .into_response(),
+    }
+}
+
+/// Builds a submission's scored rubric, shared with the scoring endpoint's response.
+pub async fn submission_rubric(
+    db: &sea_orm::DatabaseConnection,
+    rubric: &RubricModel,
+    submission: &SubmissionModel,
+) -> Result<SubmissionRubricResponse, sea_orm::DbErr> {
+    let criteria = rubric.criteria(db).await?;
+    let mut scores = rubric.scores(db, submission.id).await?;
+
+    let total: f64 = criteria.iter().map(|c| c.max_score).sum();
+    let scored = !scores.is_empty();
+    let earned: f64 = scores.values().map(|s| s.score).sum();
+    let automated_percentage = percentage(submission.earned, submission.total);
+    let rubric_percentage = scored.then(|| percentage(earned, total));
+
+    Ok(SubmissionRubricResponse {
+        submission_id: submission.id,
+        rubric_id: rubric.id,
+        weight: rubric.weight,
+        criteria: criteria
+            .into_iter()
+            .map(|c| {
+                let score = scores.remove(&c.id);
+                ScoredCriterion {
+                    criterion_id: c.id,
+                    name: c.name,
+                    max_score: c.max_score,
+                    score: score.as_ref().map(|s| s.score),
+                    comment: score.as_ref().and_then(|s| s.comment.clone()),
+                    marked_by: score.and_then(|s| s.marked_by),
+                }
+            })
+            .collect(),
+        earned,
+        total,
+        automated_percentage,
+        rubric_percentage,
+        final_percentage: rubric_percentage
+            .map(|pct| rubric.final_percentage(automated_percentage, pct))
+            .unwrap_or(automated_percentage),
+    })
+}
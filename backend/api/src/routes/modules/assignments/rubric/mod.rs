@@ -0,0 +1,74 @@
+//! Rubric routes module.
+//!
+//! Provides the `/rubric` route group: the assignment's qualitative rubric and the manual scores
+//! staff record against it per submission. Once a submission is scored, its grade is the
+//! automated mark blended with the rubric mark by the rubric's `weight`. Changes are refused
+//! once the assignment's moderation has been signed off.
+//!
+//! Routes include:
+//! - Get the rubric (any module member)
+//! - Create/replace and delete the rubric (assistant lecturer or higher)
+//! - Get a submission's rubric scores (its owner or staff) and score it (tutor or higher)
+
+use crate::auth::guards::{allow_assistant_lecturer, allow_tutor, deny_after_moderation_sign_off};
+use axum::{
+    Router,
+    middleware::from_fn_with_state,
+    routing::{delete, get, put},
+};
+use delete::delete_rubric;
+use get::{get_rubric, get_submission_rubric};
+use put::{save_rubric, score_submission};
+use util::state::AppState;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod put;
+
+/// Builds and returns the `/rubric` route group.
+///
+/// Routes:
+/// - `GET    /rubric`                              → The rubric and its criteria
+/// - `PUT    /rubric`                              → Create or replace the rubric (assistant lecturer or higher)
+/// - `DELETE /rubric`                              → Remove the rubric and its scores (assistant lecturer or higher)
+/// - `GET    /rubric/submissions/{submission_id}`  → A submission's scores and blended mark (owner or staff)
+/// - `PUT    /rubric/submissions/{submission_id}`  → Score a submission (tutor or higher)
+pub fn rubric_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_rubric))
+        .route(
+            "/",
+            put(save_rubric)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/",
+            delete(delete_rubric)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route("/submissions/{submission_id}", get(get_submission_rubric))
+        .route(
+            "/submissions/{submission_id}",
+            put(score_submission)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(app_state.clone(), allow_tutor)),
+        )
+}
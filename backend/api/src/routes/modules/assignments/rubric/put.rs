@@ -0,0 +1,270 @@
+use super::common::RubricResponse;
+use super::get::submission_rubric;
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use db::models::{
+    assignment_submission,
+    rubric::{CriterionInput, Model as RubricModel},
+    rubric_score::Model as ScoreModel,
+};
+use sea_orm::{DbErr, EntityTrait, TransactionTrait};
+use serde::Deserialize;
+use std::collections::HashMap;
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct CriterionRequest {
+    /// An existing criterion to keep (with its scores); omit to add a new one
+    pub id: Option<i64>,
+    pub name: String,
+    pub description: Option<String>,
+    pub max_score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SaveRubricRequest {
+    pub title: String,
+    pub description: Option<String>,
+    /// Percentage (0–100) of the final grade taken from the rubric
+    pub weight: f64,
+    pub criteria: Vec<CriterionRequest>,
+}
+
+/// PUT /api/modules/{module_id}/assignments/{assignment_id}/rubric
+///
+/// Creates the assignment's rubric, or replaces it. Criteria sent with an `id` are updated and
+/// keep their scores; criteria left out are removed with their scores. Assistant lecturer or
+/// higher.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "title": "Code quality",
+///   "weight": 30,
+///   "criteria": [
+///     { "id": 1, "name": "Style", "description": "Naming and layout", "max_score": 5 },
+///     { "name": "Design", "max_score": 5 }
+///   ]
+/// }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The saved rubric (same shape as `GET /rubric`)
+/// - `400 Bad Request` — Empty title or criterion name, no criteria, a `weight` outside 0–100,
+///   a non-positive `max_score`, or an `id` that isn't one of the rubric's criteria
+pub async fn save_rubric(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id)): Path<(i64, i64)>,
+    Json(req): Json<SaveRubricRequest>,
+) -> impl IntoResponse {
+    let title = req.title.trim();
+    let error = if title.is_empty() {
+        Some("Title is required")
+    } else if !(0.0..=100.0).contains(&req.weight) {
+        Some("Weight must be between 0 and 100")
+    } else if req.criteria.is_empty() {
+        Some("A rubric needs at least one criterion")
+    } else if req.criteria.iter().any(|c| c.name.trim().is_empty()) {
+        Some("Every criterion needs a name")
+    } else if req
+        .criteria
+        .iter()
+        .any(|c| !c.max_score.is_finite() || c.max_score <= 0.0)
+    {
+        Some("Every criterion's max_score must be positive")
+    } else {
+        None
+    };
+    if let Some(message) = error {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(message)),
+        )
+            .into_response();
+    }
+
+    let criteria: Vec<CriterionInput> = req
+        .criteria
+        .into_iter()
+        .map(|c| CriterionInput {
+            id: c.id,
+            name: c.name.trim().to_owned(),
+            description: c.description.filter(|d| !d.trim().is_empty()),
+            max_score: c.max_score,
+        })
+        .collect();
+    let description = req.description.as_deref().filter(|d| !d.trim().is_empty());
+
+    match RubricModel::save(
+        app_state.db(),
+        assignment_id,
+        title,
+        description,
+        req.weight,
+        &criteria,
+    )
+    .await
+    {
+        Ok((rubric, criteria)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                RubricResponse::new(rubric, criteria),
+                "Rubric saved",
+            )),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(msg)) => {
+            (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(msg))).into_response()
+        }
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to save rubric")),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScoreRequest {
+    pub criterion_id: i64,
+    pub score: f64,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScoreSubmissionRequest {
+    pub scores: Vec<ScoreRequest>,
+}
+
+/// PUT /api/modules/{module_id}/assignments/{assignment_id}/rubric/submissions/{submission_id}
+///
+/// Records manual scores for some or all of the rubric's criteria on a submission, replacing
+/// earlier scores for those criteria. Tutor or higher.
+///
+/// ### Request Body
+/// ```json
+/// { "scores": [ { "criterion_id": 1, "score": 4, "comment": "Tidy" } ] }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The submission's scores (same shape as `GET /rubric/submissions/{submission_id}`)
+/// - `400 Bad Request` — No scores, a criterion that isn't on the rubric, or a score outside
+///   0..=`max_score`
+/// - `404 Not Found` — The assignment has no rubric
+pub async fn score_submission(
+    State(app_state): State<AppState>,
+    Path((_, assignment_id, submission_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<ScoreSubmissionRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if req.scores.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("No scores provided")),
+        )
+            .into_response();
+    }
+
+    let rubric = match RubricModel::for_assignment(db, assignment_id).await {
+        Ok(Some(r)) => r,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("This assignment has no rubric")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve rubric")),
+            )
+                .into_response();
+        }
+    };
+    let max_scores: HashMap<i64, f64> = match rubric.criteria(db).await {
+        Ok(criteria) => criteria.into_iter().map(|c| (c.id, c.max_score)).collect(),
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve rubric")),
+            )
+                .into_response();
+        }
+    };
+
+    for s in &req.scores {
+        let Some(&max) = max_scores.get(&s.criterion_id) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!(
+                    "Criterion {} is not part of this rubric",
+                    s.criterion_id
+                ))),
+            )
+                .into_response();
+        };
+        if !s.score.is_finite() || s.score < 0.0 || s.score > max {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(format!(
+                    "Score for criterion {} must be between 0 and {}",
+                    s.criterion_id, max
+                ))),
+            )
+                .into_response();
+        }
+    }
+
+    let saved = async {
+        let txn = db.begin().await?;
+        for s in &req.scores {
+            let comment = s.comment.as_deref().filter(|c| !c.trim().is_empty());
+            ScoreModel::set(
+                &txn,
+                s.criterion_id,
+                submission_id,
+                s.score,
+                comment,
+                claims.sub,
+            )
+            .await?;
+        }
+        txn.commit().await
+    }
+    .await;
+    if saved.is_err() {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to save scores")),
+        )
+            .into_response();
+    }
+
+    let response = match assignment_submission::Entity::find_by_id(submission_id)
+        .one(db)
+        .await
+    {
+        Ok(Some(submission)) => submission_rubric(db, &rubric, &submission).await.ok(),
+        _ => None,
+    };
+    match response {
+        Some(response) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(response, "Scores saved")),
+        )
+            .into_response(),
+        None => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to retrieve rubric scores")),
+        )
+            .into_response(),
+    }
+}
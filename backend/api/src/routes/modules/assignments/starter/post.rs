@@ -123,7 +123,8 @@ pub async fn create(
     println!("Starter artifacts wiped for assignment {}", assignment_id);
 
     // 4) Write config
-    let mut cfg = ExecutionConfig::default_config();
+    let mut cfg = ExecutionConfig::module_defaults(module_id)
+        .unwrap_or_else(|_| ExecutionConfig::default_config());
     cfg.project.language = pack.language;
     if cfg.save(module_id, assignment_id).is_err() {
         println!(
@@ -0,0 +1,238 @@
+use chrono::{DateTime, Utc};
+use db::grade::{GradeComputationError, GradeComputationOptions, compute_assignment_grades};
+use db::models::assignment_submission::{Column as SubmissionColumn, Entity as SubmissionEntity};
+use db::soft_delete::SoftDelete;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long a cached distribution may be served while its submissions are unchanged. Bounds how
+/// stale it gets after changes the fingerprint misses, such as a new grading policy or rubric
+/// scores.
+const DISTRIBUTION_TTL: Duration = Duration::from_secs(300);
+/// Distributions kept at most; the oldest makes room for a new one.
+const DISTRIBUTION_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Bucket {
+    /// Lower bound (inclusive), in %.
+    pub from: f64,
+    /// Upper bound (exclusive, except for the last bucket), in %.
+    pub to: f64,
+    pub count: usize,
+}
+
+/// How an assignment's counted grades are spread.
+#[derive(Debug, Clone, Serialize)]
+pub struct Distribution {
+    /// Students with a counted grade.
+    pub students: usize,
+    pub bucket_width: u32,
+    pub buckets: Vec<Bucket>,
+    pub mean: f64,
+    pub min: f64,
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+    pub max: f64,
+    pub pass_mark: u32,
+    /// % of students at or above the pass mark.
+    pub pass_rate: f64,
+}
+
+#[inline]
+fn r2(x: f64) -> f64 {
+    (x * 100.0).round() / 100.0
+}
+
+/// Linear-interpolated percentile of sorted `xs`, `p` in 0..=1.
+fn percentile(xs: &[f64], p: f64) -> f64 {
+    if xs.is_empty() {
+        return 0.0;
+    }
+    let pos = p * (xs.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    xs[lo] + (xs[hi] - xs[lo]) * (pos - lo as f64)
+}
+
+impl Distribution {
+    /// Buckets `marks` (percentages) into `bucket_width`-wide bands from 0 to 100; a mark of
+    /// 100 falls in the last band.
+    pub fn from_marks(mut marks: Vec<f64>, bucket_width: u32, pass_mark: u32) -> Self {
+        marks.sort_by(f64::total_cmp);
+        let width = f64::from(bucket_width.max(1));
+        let n_buckets = (100.0 / width).ceil() as usize;
+        let mut buckets: Vec<Bucket> = (0..n_buckets)
+            .map(|i| Bucket {
+                from: i as f64 * width,
+                to: ((i + 1) as f64 * width).min(100.0),
+                count: 0,
+            })
+            .collect();
+        for m in &marks {
+            let i = ((m.clamp(0.0, 100.0) / width) as usize).min(n_buckets - 1);
+            buckets[i].count += 1;
+        }
+        let students = marks.len();
+        let passed = marks.iter().filter(|&&m| m >= f64::from(pass_mark)).count();
+        Self {
+            students,
+            bucket_width,
+            buckets,
+            mean: if students == 0 {
+                0.0
+            } else {
+                r2(marks.iter().sum::<f64>() / students as f64)
+            },
+            min: r2(marks.first().copied().unwrap_or(0.0)),
+            q1: r2(percentile(&marks, 0.25)),
+            median: r2(percentile(&marks, 0.5)),
+            q3: r2(percentile(&marks, 0.75)),
+            max: r2(marks.last().copied().unwrap_or(0.0)),
+            pass_mark,
+            pass_rate: if students == 0 {
+                0.0
+            } else {
+                r2(passed as f64 * 100.0 / students as f64)
+            },
+        }
+    }
+}
+
+/// What an assignment's distribution was computed from: its live submission count and the
+/// latest update to any submission. A new, remarked, ignored, deleted or restored submission
+/// changes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    submissions: u64,
+    last_change: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    fingerprint: Fingerprint,
+    distribution: Distribution,
+    inserted_at: Instant,
+}
+
+/// Computed distributions by `(assignment_id, bucket_width)`.
+///
+/// Computing one scans every submission of the assignment, so a distribution is reused while the
+/// assignment's submissions are unchanged and the entry is younger than [`DISTRIBUTION_TTL`].
+#[derive(Debug, Default)]
+struct DistributionCache {
+    entries: HashMap<(i64, u32), Entry>,
+}
+
+impl DistributionCache {
+    fn get(
+        &mut self,
+        key: (i64, u32),
+        fingerprint: Fingerprint,
+        now: Instant,
+    ) -> Option<Distribution> {
+        let entry = self.entries.get(&key)?;
+        if entry.fingerprint == fingerprint
+            && now.saturating_duration_since(entry.inserted_at) < DISTRIBUTION_TTL
+        {
+            return Some(entry.distribution.clone());
+        }
+        self.entries.remove(&key);
+        None
+    }
+
+    fn insert(
+        &mut self,
+        key: (i64, u32),
+        fingerprint: Fingerprint,
+        distribution: Distribution,
+        now: Instant,
+    ) {
+        while self.entries.len() >= DISTRIBUTION_CAPACITY && !self.entries.contains_key(&key) {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.inserted_at)
+                .map(|(k, _)| *k)
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                fingerprint,
+                distribution,
+                inserted_at: now,
+            },
+        );
+    }
+}
+
+fn shared() -> &'static Mutex<DistributionCache> {
+    static CACHE: OnceLock<Mutex<DistributionCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(DistributionCache::default()))
+}
+
+async fn fingerprint(db: &DatabaseConnection, assignment_id: i64) -> Result<Fingerprint, DbErr> {
+    let submissions = SubmissionEntity::find_active()
+        .filter(SubmissionColumn::AssignmentId.eq(assignment_id))
+        .count(db)
+        .await?;
+    let last_change = SubmissionEntity::find()
+        .filter(SubmissionColumn::AssignmentId.eq(assignment_id))
+        .order_by_desc(SubmissionColumn::UpdatedAt)
+        .one(db)
+        .await?
+        .map(|s| s.updated_at);
+    Ok(Fingerprint {
+        submissions,
+        last_change,
+    })
+}
+
+/// The distribution of the assignment's counted grades (see
+/// [`db::grade::compute_assignment_grades`]), from the cache when its submissions are unchanged.
+pub async fn distribution(
+    db: &DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+    bucket_width: u32,
+) -> Result<Distribution, GradeComputationError> {
+    let key = (assignment_id, bucket_width);
+    let fingerprint = fingerprint(db, assignment_id).await?;
+    let cached =
+        shared()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key, fingerprint, Instant::now());
+    if let Some(distribution) = cached {
+        return Ok(distribution);
+    }
+
+    let result = compute_assignment_grades(
+        db,
+        module_id,
+        assignment_id,
+        GradeComputationOptions::default(),
+    )
+    .await?;
+    let marks = result.grades.iter().map(|g| g.score_pct).collect();
+    let distribution = Distribution::from_marks(
+        marks,
+        bucket_width,
+        result.execution_config.marking.pass_mark,
+    );
+    shared().lock().unwrap_or_else(|e| e.into_inner()).insert(
+        key,
+        fingerprint,
+        distribution.clone(),
+        Instant::now(),
+    );
+    Ok(distribution)
+}
@@ -1,24 +1,34 @@
-use crate::{auth::AuthUser, response::ApiResponse};
+use super::common::{self, Distribution};
+use crate::{
+    auth::{AuthUser, Claims, guards::user_has_any_role},
+    response::ApiResponse,
+};
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::IntoResponse,
 };
 use chrono::{DateTime, Utc};
+use db::grade::{GradeComputationError, GradeComputationOptions, compute_assignment_grades};
 use db::models::{
-    assignment::{Column as AssignmentColumn, Entity as AssignmentEntity},
+    assignment::{
+        Column as AssignmentColumn, Entity as AssignmentEntity, Model as AssignmentModel,
+    },
+    assignment_extension::Model as ExtensionModel,
     assignment_submission::Model as SubmissionModel,
     assignment_submission::{self, Entity as SubmissionEntity},
+    assignment_task::{Column as TaskCol, Entity as TaskEntity},
+    module::{Column as ModuleColumn, Entity as ModuleEntity, Model as ModuleModel},
     user_module_role::{Column as UMRCol, Entity as UMREntity, Role as UMRRole},
 };
+use db::soft_delete::SoftDelete;
 use sea_orm::{ColumnTrait, EntityTrait, Order, QueryFilter, QueryOrder};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use util::{
-    execution_config::{ExecutionConfig, GradingPolicy},
-    mark_allocator::load_allocator,
-    paths::submission_report_path,
-    state::AppState,
+    execution_config::ExecutionConfig, mark_allocator::load_allocator,
+    paths::submission_report_path, state::AppState,
 };
 
 // ---------- Response DTO ----------
@@ -136,7 +146,7 @@ fn is_late(created_at: DateTime<Utc>, due: Option<DateTime<Utc>>) -> bool {
 ///
 /// The response summarizes submission counts, pass/fail metrics,
 /// and distribution statistics (mean, median, percentiles, stddev)
-/// according to the assignment’s grading policy (`best` or `last`).
+/// according to the assignment’s grading policy (`best`, `last`, `best_of_last_n` or `weighted`).
 ///
 /// ### Path Parameters
 /// - `module_id` (i64): Module ID  
@@ -191,7 +201,7 @@ pub async fn get_assignment_stats(
     Path((module_id, assignment_id)): Path<(i64, i64)>,
     _auth: AuthUser, // present due to auth middleware
 ) -> axum::response::Response {
-    let db = app_state.db();
+    let db = app_state.db_read();
 
     // Validate assignment (and grab config + due_date)
     let assignment = match AssignmentEntity::find()
@@ -269,7 +279,7 @@ pub async fn get_assignment_stats(
     }
 
     // ---- Query A: all student submissions (for 'ignored' count visibility only)
-    let all_student_rows: Vec<SubmissionModel> = match SubmissionEntity::find()
+    let all_student_rows: Vec<SubmissionModel> = match SubmissionEntity::find_active()
         .filter(assignment_submission::Column::AssignmentId.eq(assignment_id))
         .filter(assignment_submission::Column::UserId.is_in(student_ids.clone()))
         .order_by(assignment_submission::Column::CreatedAt, Order::Desc)
@@ -291,7 +301,7 @@ pub async fn get_assignment_stats(
     let ignored = all_student_rows.iter().filter(|s| s.ignored).count();
 
     // ---- Query B: ONLY counted rows → students & NOT practice & NOT ignored
-    let rows: Vec<SubmissionModel> = match SubmissionEntity::find()
+    let rows: Vec<SubmissionModel> = match SubmissionEntity::find_active()
         .filter(assignment_submission::Column::AssignmentId.eq(assignment_id))
         .filter(assignment_submission::Column::UserId.is_in(student_ids.clone()))
         .filter(assignment_submission::Column::IsPractice.eq(false))
@@ -319,11 +329,20 @@ pub async fn get_assignment_stats(
     let mut late = 0usize;
     let mut on_time = 0usize;
 
-    use std::collections::{HashMap, HashSet};
+    use std::collections::HashSet;
     let mut user_marks: HashMap<i64, Vec<(DateTime<Utc>, i64)>> = HashMap::new();
 
+    // students with an extension are late only after their own due date
+    let due_dates = ExtensionModel::due_dates(db, assignment_id)
+        .await
+        .unwrap_or_default();
+
     for s in &rows {
-        if is_late(s.created_at, Some(assignment.due_date)) {
+        let due = due_dates
+            .get(&s.user_id)
+            .copied()
+            .unwrap_or(assignment.due_date);
+        if is_late(s.created_at, Some(due)) {
             late += 1;
         } else {
             on_time += 1;
@@ -356,17 +375,14 @@ pub async fn get_assignment_stats(
     let cfg = assignment
         .config()
         .unwrap_or_else(ExecutionConfig::default_config);
-    let grading_policy = cfg.marking.grading_policy;
     let pass_mark_threshold = cfg.marking.pass_mark as i64;
 
     let mut effective_marks: Vec<i64> = Vec::new();
-    for (_uid, marks) in user_marks {
-        let chosen = match grading_policy {
-            GradingPolicy::Best => marks.iter().map(|(_, p)| *p).max(),
-            GradingPolicy::Last => marks.iter().max_by_key(|(ts, _)| *ts).map(|(_, p)| *p),
-        };
-        if let Some(p) = chosen {
-            effective_marks.push(p);
+    for (_uid, mut marks) in user_marks {
+        marks.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+        let pcts: Vec<f64> = marks.iter().map(|(_, p)| *p as f64).collect();
+        if let Some((_, p)) = cfg.marking.policy_mark(&pcts) {
+            effective_marks.push(p.round() as i64);
         }
     }
 
@@ -430,3 +446,660 @@ pub async fn get_assignment_stats(
     )
         .into_response()
 }
+
+// ---------- Per-task statistics ----------
+
+/// How many of the most-failed subsections `common_failures` lists.
+const COMMON_FAILURES_LIMIT: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub struct TaskStatsResponse {
+    /// Students with a counted submission.
+    pub students: usize,
+    pub tasks: Vec<TaskStats>,
+    /// The subsections most students lost marks on, most failed first.
+    pub common_failures: Vec<FailingSubsection>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaskStats {
+    pub task_number: i64,
+    pub name: String,
+    /// Marks available for the task.
+    pub total: f64,
+    /// Students whose counted report includes the task.
+    pub students: usize,
+    pub mean_earned: f64,
+    pub median_earned: f64,
+    pub mean_pct: f64,
+    /// % of students who earned nothing for the task.
+    pub zero_rate: f64,
+    /// % of students who earned full marks for the task.
+    pub full_rate: f64,
+    /// Correlation (-1..1) between the task's mark and the total mark: low or negative values
+    /// mean the task does not separate stronger students from weaker ones. `None` with fewer
+    /// than three students or when every student scored the same.
+    pub discrimination: Option<f64>,
+    pub subsections: Vec<SubsectionStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubsectionStats {
+    pub label: String,
+    pub total: f64,
+    pub students: usize,
+    pub mean_earned: f64,
+    pub median_earned: f64,
+    pub zero_rate: f64,
+    /// % of students who lost any marks on the subsection.
+    pub fail_rate: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailingSubsection {
+    pub task_number: i64,
+    pub task_name: String,
+    pub label: String,
+    pub failed: usize,
+    pub fail_rate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportScore {
+    earned: f64,
+    total: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportSubsection {
+    #[serde(default)]
+    label: String,
+    earned: f64,
+    total: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportTask {
+    task_number: i64,
+    #[serde(default)]
+    name: String,
+    score: ReportScore,
+    #[serde(default)]
+    subsections: Vec<ReportSubsection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportRoot {
+    #[serde(default)]
+    tasks: Vec<ReportTask>,
+}
+
+fn mean_f64(xs: &[f64]) -> f64 {
+    if xs.is_empty() {
+        return 0.0;
+    }
+    xs.iter().sum::<f64>() / xs.len() as f64
+}
+
+fn median_f64(xs: &mut [f64]) -> f64 {
+    if xs.is_empty() {
+        return 0.0;
+    }
+    xs.sort_by(f64::total_cmp);
+    let n = xs.len();
+    if n % 2 == 1 {
+        xs[n / 2]
+    } else {
+        (xs[n / 2 - 1] + xs[n / 2]) / 2.0
+    }
+}
+
+/// Pearson correlation of paired samples.
+fn correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    if xs.len() < 3 || xs.len() != ys.len() {
+        return None;
+    }
+    let (mx, my) = (mean_f64(xs), mean_f64(ys));
+    let cov: f64 = xs.iter().zip(ys).map(|(x, y)| (x - mx) * (y - my)).sum();
+    let vx: f64 = xs.iter().map(|x| (x - mx).powi(2)).sum();
+    let vy: f64 = ys.iter().map(|y| (y - my).powi(2)).sum();
+    if vx == 0.0 || vy == 0.0 {
+        return None;
+    }
+    Some(cov / (vx * vy).sqrt())
+}
+
+#[inline]
+fn rate(count: usize, of: usize) -> f64 {
+    if of == 0 {
+        0.0
+    } else {
+        r2(count as f64 * 100.0 / of as f64)
+    }
+}
+
+#[derive(Default)]
+struct TaskAcc {
+    name: String,
+    total: f64,
+    earned: Vec<f64>,
+    pcts: Vec<f64>,
+    overall: Vec<f64>,
+    /// Subsections in report order, by label.
+    subsections: Vec<(String, f64, Vec<f64>)>,
+}
+
+/// Aggregates each student's counted report (their total mark % and report tasks).
+fn aggregate_tasks(
+    reports: Vec<(f64, Vec<ReportTask>)>,
+    task_names: &HashMap<i64, String>,
+) -> TaskStatsResponse {
+    let students = reports.len();
+    let mut by_task: BTreeMap<i64, TaskAcc> = BTreeMap::new();
+    for (overall, tasks) in reports {
+        for t in tasks {
+            let acc = by_task.entry(t.task_number).or_default();
+            if acc.name.is_empty() {
+                acc.name = task_names.get(&t.task_number).cloned().unwrap_or(t.name);
+            }
+            acc.total = acc.total.max(t.score.total);
+            acc.earned.push(t.score.earned);
+            acc.pcts.push(if t.score.total > 0.0 {
+                t.score.earned * 100.0 / t.score.total
+            } else {
+                0.0
+            });
+            acc.overall.push(overall);
+            for sub in t.subsections {
+                match acc.subsections.iter_mut().find(|(l, ..)| *l == sub.label) {
+                    Some((_, total, earned)) => {
+                        *total = total.max(sub.total);
+                        earned.push(sub.earned);
+                    }
+                    None => acc
+                        .subsections
+                        .push((sub.label, sub.total, vec![sub.earned])),
+                }
+            }
+        }
+    }
+
+    let mut common_failures = Vec::new();
+    let tasks = by_task
+        .into_iter()
+        .map(|(task_number, mut acc)| {
+            let n = acc.earned.len();
+            let subsections = acc
+                .subsections
+                .into_iter()
+                .map(|(label, total, mut earned)| {
+                    let m = earned.len();
+                    let failed = earned.iter().filter(|&&e| e < total).count();
+                    if failed > 0 {
+                        common_failures.push(FailingSubsection {
+                            task_number,
+                            task_name: acc.name.clone(),
+                            label: label.clone(),
+                            failed,
+                            fail_rate: rate(failed, m),
+                        });
+                    }
+                    SubsectionStats {
+                        total,
+                        students: m,
+                        mean_earned: r2(mean_f64(&earned)),
+                        median_earned: r2(median_f64(&mut earned)),
+                        zero_rate: rate(earned.iter().filter(|&&e| e <= 0.0).count(), m),
+                        fail_rate: rate(failed, m),
+                        label,
+                    }
+                })
+                .collect();
+            TaskStats {
+                task_number,
+                total: acc.total,
+                students: n,
+                mean_earned: r2(mean_f64(&acc.earned)),
+                mean_pct: r2(mean_f64(&acc.pcts)),
+                zero_rate: rate(acc.earned.iter().filter(|&&e| e <= 0.0).count(), n),
+                full_rate: rate(acc.earned.iter().filter(|&&e| e >= acc.total).count(), n),
+                discrimination: correlation(&acc.pcts, &acc.overall).map(r2),
+                median_earned: r2(median_f64(&mut acc.earned)),
+                name: acc.name,
+                subsections,
+            }
+        })
+        .collect();
+
+    common_failures.sort_by(|a, b| {
+        b.fail_rate
+            .total_cmp(&a.fail_rate)
+            .then(b.failed.cmp(&a.failed))
+            .then(a.task_number.cmp(&b.task_number))
+    });
+    common_failures.truncate(COMMON_FAILURES_LIMIT);
+
+    TaskStatsResponse {
+        students,
+        tasks,
+        common_failures,
+    }
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/stats/tasks
+///
+/// Per-task and per-subsection statistics over each student's **counted** submission (the one
+/// the grading policy picks), read from the stored submission reports, to show which tasks were
+/// too hard or did not discriminate between students. Staff, practice and ignored submissions
+/// are left out.
+///
+/// Access: **lecturer / assistant lecturer / admin**
+///
+/// ### 200 OK
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Task stats computed",
+///   "data": {
+///     "students": 40,
+///     "tasks": [
+///       {
+///         "task_number": 1,
+///         "name": "Linked list",
+///         "total": 10.0,
+///         "students": 40,
+///         "mean_earned": 6.4,
+///         "median_earned": 7.0,
+///         "mean_pct": 64.0,
+///         "zero_rate": 12.5,
+///         "full_rate": 20.0,
+///         "discrimination": 0.71,
+///         "subsections": [
+///           {
+///             "label": "Insert",
+///             "total": 5.0,
+///             "students": 40,
+///             "mean_earned": 4.1,
+///             "median_earned": 5.0,
+///             "zero_rate": 5.0,
+///             "fail_rate": 35.0
+///           }
+///         ]
+///       }
+///     ],
+///     "common_failures": [
+///       { "task_number": 1, "task_name": "Linked list", "label": "Insert", "failed": 14, "fail_rate": 35.0 }
+///     ]
+///   }
+/// }
+/// ```
+///
+/// ### Metrics
+/// - **mean_earned / median_earned / mean_pct** — marks earned for the task
+/// - **zero_rate / full_rate** — % of students earning nothing / everything for the task
+/// - **discrimination** — correlation between the task's mark and the student's total mark
+/// - **fail_rate** — % of students who lost any marks on the subsection
+/// - **common_failures** — up to 10 subsections with the highest fail rate
+///
+/// ### Errors
+/// - **404 Not Found** — Assignment not found
+/// - **500 Internal Server Error** — Database, config or read error
+pub async fn get_task_stats(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> axum::response::Response {
+    let db = app_state.db_read();
+
+    let grades = match compute_assignment_grades(
+        db,
+        module_id,
+        assignment_id,
+        GradeComputationOptions::default(),
+    )
+    .await
+    {
+        Ok(result) => result.grades,
+        Err(GradeComputationError::AssignmentNotFound) => {
+            return (
+                axum::http::StatusCode::NOT_FOUND,
+                Json(ApiResponse::<TaskStatsResponse>::error(
+                    "Assignment not found",
+                )),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            eprintln!("get_task_stats: compute error: {e}");
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<TaskStatsResponse>::error(
+                    "Failed to compute grades",
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    let task_names: HashMap<i64, String> = match TaskEntity::find()
+        .filter(TaskCol::AssignmentId.eq(assignment_id))
+        .all(db)
+        .await
+    {
+        Ok(rows) => rows.into_iter().map(|t| (t.task_number, t.name)).collect(),
+        Err(_) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<TaskStatsResponse>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+
+    let reports = grades
+        .iter()
+        .filter_map(|g| {
+            let path = submission_report_path(
+                module_id,
+                assignment_id,
+                g.submission.user_id,
+                g.submission.attempt,
+            );
+            let raw = std::fs::read_to_string(path).ok()?;
+            let report = serde_json::from_str::<ReportRoot>(&raw).ok()?;
+            Some((g.score_pct, report.tasks))
+        })
+        .collect();
+
+    (
+        axum::http::StatusCode::OK,
+        Json(ApiResponse::success(
+            aggregate_tasks(reports, &task_names),
+            "Task stats computed",
+        )),
+    )
+        .into_response()
+}
+
+// ---------- Grade distribution ----------
+
+#[derive(Debug, Deserialize)]
+pub struct DistributionQuery {
+    /// Width of each histogram bucket in percentage points, 1–50 (default 10).
+    pub bucket_width: Option<u32>,
+    /// Compare against this assignment.
+    pub compare_assignment_id: Option<i64>,
+    /// Compare against the same-named assignment in last year's offering of the module.
+    #[serde(default)]
+    pub compare_previous_year: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DistributionResponse {
+    #[serde(flatten)]
+    pub distribution: Distribution,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<CohortComparison>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CohortComparison {
+    pub assignment_id: i64,
+    pub assignment_name: String,
+    pub module_id: i64,
+    pub module_code: String,
+    pub year: i32,
+    pub distribution: Distribution,
+    /// This assignment's value minus the compared one's.
+    pub mean_difference: f64,
+    pub median_difference: f64,
+    pub pass_rate_difference: f64,
+}
+
+fn distribution_error(e: GradeComputationError) -> axum::response::Response {
+    match e {
+        GradeComputationError::AssignmentNotFound => (
+            axum::http::StatusCode::NOT_FOUND,
+            Json(ApiResponse::<DistributionResponse>::error(
+                "Assignment not found",
+            )),
+        )
+            .into_response(),
+        e => {
+            eprintln!("get_grade_distribution: compute error: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<DistributionResponse>::error(
+                    "Failed to compute grades",
+                )),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Finds the assignment to compare against, checking the caller may see its grades.
+async fn comparison_target(
+    db: &sea_orm::DatabaseConnection,
+    module_id: i64,
+    assignment: &AssignmentModel,
+    query: &DistributionQuery,
+    claims: &Claims,
+) -> Result<Option<(AssignmentModel, ModuleModel)>, (axum::http::StatusCode, String)> {
+    let db_err = |_| {
+        (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            "Database error".to_string(),
+        )
+    };
+    let target = if let Some(other_id) = query.compare_assignment_id {
+        AssignmentEntity::find_active()
+            .filter(AssignmentColumn::Id.eq(other_id))
+            .one(db)
+            .await
+            .map_err(db_err)?
+            .ok_or((
+                axum::http::StatusCode::NOT_FOUND,
+                "Comparison assignment not found".to_string(),
+            ))?
+    } else if query.compare_previous_year {
+        let module = ModuleEntity::find_by_id(module_id)
+            .one(db)
+            .await
+            .map_err(db_err)?
+            .ok_or((
+                axum::http::StatusCode::NOT_FOUND,
+                "Module not found".to_string(),
+            ))?;
+        let previous = ModuleEntity::find()
+            .filter(ModuleColumn::Code.eq(module.code.clone()))
+            .filter(ModuleColumn::Year.eq(module.year - 1))
+            .one(db)
+            .await
+            .map_err(db_err)?
+            .ok_or((
+                axum::http::StatusCode::NOT_FOUND,
+                format!("No {} module for {}", module.code, module.year - 1),
+            ))?;
+        AssignmentEntity::find_active()
+            .filter(AssignmentColumn::ModuleId.eq(previous.id))
+            .all(db)
+            .await
+            .map_err(db_err)?
+            .into_iter()
+            .find(|a| a.name.trim().eq_ignore_ascii_case(assignment.name.trim()))
+            .ok_or((
+                axum::http::StatusCode::NOT_FOUND,
+                format!(
+                    "No assignment named '{}' in {} {}",
+                    assignment.name, previous.code, previous.year
+                ),
+            ))?
+    } else {
+        return Ok(None);
+    };
+
+    if !claims.admin
+        && !user_has_any_role(
+            db,
+            claims.sub,
+            target.module_id,
+            &["Lecturer", "AssistantLecturer"],
+        )
+        .await
+    {
+        return Err((
+            axum::http::StatusCode::FORBIDDEN,
+            "You may not view the comparison assignment's grades".to_string(),
+        ));
+    }
+    let module = ModuleEntity::find_by_id(target.module_id)
+        .one(db)
+        .await
+        .map_err(db_err)?
+        .ok_or((
+            axum::http::StatusCode::NOT_FOUND,
+            "Module not found".to_string(),
+        ))?;
+    Ok(Some((target, module)))
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/stats/distribution
+///
+/// Histogram, quartiles and pass rate of the students' **counted** grades (per the grading
+/// policy, rubric included), optionally compared with another assignment or with the same
+/// assignment in last year's offering of the module (same module code, previous year, matched
+/// by assignment name).
+///
+/// Distributions are cached per assignment and bucket width, and recomputed once the
+/// assignment's submissions change or after five minutes.
+///
+/// Access: **lecturer / assistant lecturer / admin**, and for the comparison the same role in
+/// the compared assignment's module.
+///
+/// ### Query Parameters
+/// - `bucket_width` (optional, 1–50, default 10)
+/// - `compare_assignment_id` (optional): Assignment to compare against
+/// - `compare_previous_year` (optional, bool): Compare against last year's offering
+///
+/// ### 200 OK
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Grade distribution computed",
+///   "data": {
+///     "students": 4,
+///     "bucket_width": 25,
+///     "buckets": [
+///       { "from": 0.0, "to": 25.0, "count": 0 },
+///       { "from": 25.0, "to": 50.0, "count": 1 },
+///       { "from": 50.0, "to": 75.0, "count": 2 },
+///       { "from": 75.0, "to": 100.0, "count": 1 }
+///     ],
+///     "mean": 61.25, "min": 40.0, "q1": 51.25, "median": 57.5, "q3": 67.5, "max": 90.0,
+///     "pass_mark": 50, "pass_rate": 75.0,
+///     "comparison": {
+///       "assignment_id": 3,
+///       "assignment_name": "Practical 1",
+///       "module_id": 1,
+///       "module_code": "COS212",
+///       "year": 2024,
+///       "distribution": { "students": 50, "...": "..." },
+///       "mean_difference": 4.1,
+///       "median_difference": 2.5,
+///       "pass_rate_difference": -3.0
+///     }
+///   }
+/// }
+/// ```
+///
+/// ### Errors
+/// - **400 Bad Request** — `bucket_width` out of range, or both comparisons requested
+/// - **403 Forbidden** — No access to the comparison assignment's module
+/// - **404 Not Found** — Assignment, comparison assignment or previous year's module not found
+/// - **500 Internal Server Error** — Database or config error
+pub async fn get_grade_distribution(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<DistributionQuery>,
+) -> axum::response::Response {
+    let db = app_state.db_read();
+
+    let bucket_width = query.bucket_width.unwrap_or(10);
+    if !(1..=50).contains(&bucket_width) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<DistributionResponse>::error(
+                "bucket_width must be between 1 and 50",
+            )),
+        )
+            .into_response();
+    }
+    if query.compare_assignment_id.is_some() && query.compare_previous_year {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<DistributionResponse>::error(
+                "Use either compare_assignment_id or compare_previous_year",
+            )),
+        )
+            .into_response();
+    }
+
+    let assignment = match AssignmentEntity::find_active()
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(a)) => a,
+        Ok(None) => return distribution_error(GradeComputationError::AssignmentNotFound),
+        Err(e) => return distribution_error(GradeComputationError::Database(e)),
+    };
+
+    let distribution = match common::distribution(db, module_id, assignment_id, bucket_width).await
+    {
+        Ok(d) => d,
+        Err(e) => return distribution_error(e),
+    };
+
+    let comparison = match comparison_target(db, module_id, &assignment, &query, &claims).await {
+        Ok(None) => None,
+        Ok(Some((other, other_module))) => {
+            let other_distribution =
+                match common::distribution(db, other.module_id, other.id, bucket_width).await {
+                    Ok(d) => d,
+                    Err(e) => return distribution_error(e),
+                };
+            Some(CohortComparison {
+                assignment_id: other.id,
+                assignment_name: other.name,
+                module_id: other_module.id,
+                module_code: other_module.code,
+                year: other_module.year,
+                mean_difference: r2(distribution.mean - other_distribution.mean),
+                median_difference: r2(distribution.median - other_distribution.median),
+                pass_rate_difference: r2(distribution.pass_rate - other_distribution.pass_rate),
+                distribution: other_distribution,
+            })
+        }
+        Err((status, msg)) => {
+            return (
+                status,
+                Json(ApiResponse::<DistributionResponse>::error(msg)),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        axum::http::StatusCode::OK,
+        Json(ApiResponse::success(
+            DistributionResponse {
+                distribution,
+                comparison,
+            },
+            "Grade distribution computed",
+        )),
+    )
+        .into_response()
+}
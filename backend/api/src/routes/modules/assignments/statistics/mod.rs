@@ -4,21 +4,40 @@
 //!
 //! Routes:
 //! - GET /summary  — Aggregated submission summary (lecturer/assistant lecturer/admin)
+//! - GET /tasks    — Per-task and per-subsection difficulty analysis (lecturer/assistant lecturer/admin)
+//! - GET /distribution — Grade histogram, quartiles and pass rate, optionally against another
+//!   cohort (lecturer/assistant lecturer/admin)
 
 use axum::{Router, middleware::from_fn_with_state, routing::get};
 
 use crate::auth::guards::allow_assistant_lecturer;
-use get::get_assignment_stats;
+use get::{get_assignment_stats, get_grade_distribution, get_task_stats};
 use util::state::AppState;
 
+pub mod common;
 pub mod get;
 
 pub fn statistics_routes(app_state: AppState) -> Router<AppState> {
-    Router::new().route(
-        "/",
-        get(get_assignment_stats).route_layer(from_fn_with_state(
-            app_state.clone(),
-            allow_assistant_lecturer,
-        )),
-    )
+    Router::new()
+        .route(
+            "/",
+            get(get_assignment_stats).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/tasks",
+            get(get_task_stats).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/distribution",
+            get(get_grade_distribution).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
 }
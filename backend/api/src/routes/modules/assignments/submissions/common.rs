@@ -7,12 +7,14 @@
 //! - `UserResponse` → represents a user associated with a submission
 //! - `Mark` → represents earned/total marks for a submission
 //! - `SubmissionListItem` → single item in a submissions list
+//! - `ClientInfo` / `SharedIpSubmission` → staff-only upload metadata on list items
 //! - `SubmissionsListResponse` → paginated submissions list
 //! - `SubmissionResponse` → minimal response for a single submission
 //! - `MarkSummary` → summary of earned vs total marks
 //! - `SubmissionDetailResponse` → detailed response after grading a submission
 
 use serde::{Deserialize, Serialize};
+use util::scan_code_content::DisallowedMatch;
 
 /// Query parameters for submissions listing endpoints.
 #[derive(Debug, Deserialize)]
@@ -24,7 +26,11 @@ pub struct ListSubmissionsQuery {
     pub username: Option<String>,
     pub late: Option<bool>,
     pub ignored: Option<bool>,
+    /// Keep only practice (`true`) or only graded (`false`) submissions.
+    pub practice: Option<bool>,
     pub status: Option<String>,
+    /// Staff only: keep (`true`) or drop (`false`) submissions sharing an IP with another user's.
+    pub shared_ip: Option<bool>,
 }
 
 /// Represents a user associated with a submission.
@@ -56,6 +62,29 @@ pub struct SubmissionListItem {
     pub mark: Option<Mark>,
     pub ignored: bool,
     pub status: String,
+    /// Staff only: where the submission was uploaded from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<ClientInfo>,
+    /// Staff only: other users' submissions uploaded from the same IP close in time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shared_ip: Option<Vec<SharedIpSubmission>>,
+}
+
+/// Where a submission was uploaded from.
+#[derive(Debug, Serialize, Clone)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub upload_duration_ms: Option<i64>,
+}
+
+/// Another user's submission uploaded from the same IP address.
+#[derive(Debug, Serialize, Clone)]
+pub struct SharedIpSubmission {
+    pub submission_id: i64,
+    pub user_id: i64,
+    pub username: Option<String>,
+    pub seconds_apart: i64,
 }
 
 /// Paginated response of submissions list.
@@ -97,6 +126,13 @@ pub struct CodeCoverageSummary {
     pub total_lines: u32,
     pub covered_lines: u32,
     pub coverage_percent: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_percent: Option<f64>,
+    /// Percentage the coverage mark is based on (line coverage for older reports).
+    #[serde(default)]
+    pub marked_percent: Option<f64>,
 }
 
 /// Represents code coverage for a submission.
@@ -134,6 +170,39 @@ pub struct SubmissionDetailResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<serde_json::Value>,
     pub plagiarism: PlagiarismInfo,
+    /// Where disallowed code was found, for `failed_disallowed_code` submissions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disallowed_code: Option<DisallowedMatch>,
+    /// The manual rubric mark, once the submission has been scored against the rubric.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rubric: Option<RubricMark>,
+}
+
+impl SubmissionDetailResponse {
+    /// Blanks each task subsection's `feedback`, which can quote the memo output; marks stay.
+    /// Used while a timed exam with `exam.hide_feedback` is running.
+    pub fn hide_feedback(&mut self) {
+        for task in &mut self.tasks {
+            let Some(subsections) = task.get_mut("subsections").and_then(|s| s.as_array_mut())
+            else {
+                continue;
+            };
+            for sub in subsections {
+                if let Some(feedback) = sub.get_mut("feedback") {
+                    *feedback = serde_json::Value::String(String::new());
+                }
+            }
+        }
+    }
+}
+
+/// A submission's rubric mark and how it blends with the automated `mark`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RubricMark {
+    /// Percentage (0–100) of the final grade taken from the rubric.
+    pub weight: f64,
+    pub percentage: f64,
+    pub final_percentage: f64,
 }
 
 // ---- instant ACK (client will GET /submissions/{id} and attach WS) ----
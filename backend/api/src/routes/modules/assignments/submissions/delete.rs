@@ -3,12 +3,16 @@
 //! Provides endpoints for deleting single or multiple submissions within an assignment.
 //!
 //! - `DELETE /api/modules/{module_id}/assignments/{assignment_id}/submissions/{submission_id}`  
-//!   Deletes a single submission and attempts to remove its stored file.
+//!   Soft deletes a single submission; its stored file is removed when the retention sweep
+//!   purges it.
 //!
 //! - `DELETE /api/modules/{module_id}/assignments/{assignment_id}/submissions/bulk`  
-//!   Deletes multiple submissions using a JSON array of submission IDs.
+//!   Soft deletes multiple submissions using a JSON array of submission IDs.
 //!
-//! **Access Control:** Only lecturers or assistant lecturers may delete submissions.
+//! - `DELETE /api/modules/{module_id}/assignments/{assignment_id}/submissions/{submission_id}/run`  
+//!   Cancels the in-flight run (marking, GA or remark) of a submission.
+//!
+//! **Access Control:** Only lecturers or assistant lecturers may delete submissions or cancel runs.
 //!
 //! **Responses:** JSON-wrapped `ApiResponse` indicating success, number of deletions, or detailed errors.
 
@@ -22,7 +26,7 @@ use serde::Deserialize;
 use serde_json::json;
 use sqlx::types::JsonValue;
 
-use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use sea_orm::DbErr;
 
 use crate::response::ApiResponse;
 use util::state::AppState;
@@ -31,7 +35,9 @@ use db::models::assignment_submission as submission;
 
 /// DELETE /api/modules/:module_id/assignments/:assignment_id/submissions/:submission_id
 ///
-/// Delete a specific submission and (best-effort) its stored file.
+/// Soft delete a specific submission. It can be restored with
+/// `POST .../submissions/:submission_id/restore` until the retention sweep removes it and its
+/// stored file.
 /// Only accessible by lecturers or assistant lecturers.
 ///
 /// ### Path Parameters
@@ -70,54 +76,21 @@ pub async fn delete_submission(
 ) -> impl IntoResponse {
     let db = app_state.db();
 
-    // Ensure the submission exists and belongs to the assignment
-    let sub = match submission::Entity::find()
-        .filter(submission::Column::Id.eq(submission_id))
-        .filter(submission::Column::AssignmentId.eq(assignment_id))
-        .one(db)
-        .await
-    {
-        Ok(Some(s)) => s,
-        Ok(None) => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(json!({
-                    "success": false,
-                    "message": format!("No submission {} found for assignment {}", submission_id, assignment_id)
-                })),
-            );
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "success": false,
-                    "message": e.to_string(),
-                })),
-            );
-        }
-    };
-
-    // Best-effort: remove stored file first (ignore failure, log if needed)
-    if let Err(e) = sub.delete_file_only() {
-        eprintln!(
-            "delete_submission: failed to remove file for submission {}: {}",
-            submission_id, e
-        );
-    }
-
-    // Delete DB row by primary key (we already validated assignment_id)
-    match submission::Entity::delete_by_id(submission_id)
-        .exec(db)
-        .await
-    {
-        Ok(_) => (
+    match submission::Model::soft_delete(db, assignment_id, submission_id).await {
+        Ok(()) => (
             StatusCode::OK,
             Json(json!({
                 "success": true,
                 "message": format!("Submission {} deleted successfully", submission_id),
             })),
         ),
+        Err(DbErr::RecordNotFound(msg)) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "success": false,
+                "message": msg,
+            })),
+        ),
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(json!({
@@ -135,7 +108,7 @@ pub struct BulkDeleteSubmissionsRequest {
 
 /// DELETE /api/modules/:module_id/assignments/:assignment_id/submissions/bulk
 ///
-/// Bulk delete multiple submissions by ID within an assignment.
+/// Bulk soft delete multiple submissions by ID within an assignment.
 /// Only accessible by lecturers or assistant lecturers.
 ///
 /// ### Path Parameters
@@ -182,32 +155,9 @@ pub async fn bulk_delete_submissions(
     let mut failed: Vec<JsonValue> = Vec::new();
 
     for &sid in &req.submission_ids {
-        // Lookup
-        match submission::Entity::find()
-            .filter(submission::Column::Id.eq(sid))
-            .filter(submission::Column::AssignmentId.eq(assignment_id))
-            .one(db)
-            .await
-        {
-            Ok(Some(sub)) => {
-                // Remove file best-effort
-                if let Err(e) = sub.delete_file_only() {
-                    eprintln!(
-                        "bulk_delete_submissions: file removal failed for {}: {}",
-                        sid, e
-                    );
-                }
-
-                // Delete row
-                match submission::Entity::delete_by_id(sid).exec(db).await {
-                    Ok(_) => deleted_count += 1,
-                    Err(e) => failed.push(json!({ "id": sid, "error": e.to_string() })),
-                }
-            }
-            Ok(None) => failed.push(json!({
-                "id": sid,
-                "error": format!("No submission {} found for assignment {}", sid, assignment_id)
-            })),
+        match submission::Model::soft_delete(db, assignment_id, sid).await {
+            Ok(()) => deleted_count += 1,
+            Err(DbErr::RecordNotFound(msg)) => failed.push(json!({ "id": sid, "error": msg })),
             Err(e) => failed.push(json!({ "id": sid, "error": e.to_string() })),
         }
     }
@@ -227,3 +177,71 @@ pub async fn bulk_delete_submissions(
 
     (StatusCode::OK, Json(response))
 }
+
+/// DELETE /api/modules/:module_id/assignments/:assignment_id/submissions/:submission_id/run
+///
+/// Cancel whatever code execution is currently running for a submission (initial marking,
+/// a remark/resubmit, or a GATLAM/RNG/coverage job). Containers already running are stopped
+/// and no further tasks or GA iterations are started; the submission is then marked as failed
+/// by the run that was interrupted.
+/// Only accessible by lecturers or assistant lecturers.
+///
+/// ### Path Parameters
+/// - `module_id` (i64): The ID of the module containing the assignment
+/// - `assignment_id` (i64): The ID of the assignment
+/// - `submission_id` (i64): The ID of the submission whose run should be cancelled
+///
+/// ### Responses
+///
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Run for submission 987 cancelled"
+/// }
+/// ```
+///
+/// - `404 Not Found`
+/// ```json
+/// {
+///   "success": false,
+///   "message": "No run in progress for submission 987"
+/// }
+/// ```
+///
+/// - `502 Bad Gateway` — the run was flagged as cancelled (nothing new will start), but the
+///   code manager could not be told to stop the containers already running.
+pub async fn cancel_submission_run(
+    Path((_module_id, _assignment_id, submission_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    use code_runner::jobs::{cancel_job, submission_job_key};
+
+    match cancel_job(&submission_job_key(submission_id)).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success(
+                (),
+                format!("Run for submission {} cancelled", submission_id),
+            )),
+        ),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(format!(
+                "No run in progress for submission {}",
+                submission_id
+            ))),
+        ),
+        Err(e) => {
+            eprintln!(
+                "cancel_submission_run: failed to stop containers for submission {}: {}",
+                submission_id, e
+            );
+            (
+                StatusCode::BAD_GATEWAY,
+                Json(ApiResponse::<()>::error(
+                    "Run marked as cancelled, but running containers could not be stopped",
+                )),
+            )
+        }
+    }
+}
@@ -8,41 +8,57 @@
 //! code coverage.
 
 use super::common::{
-    CodeCoverage, ListSubmissionsQuery, MarkSummary, PlagiarismInfo, SubmissionDetailResponse,
-    SubmissionListItem, SubmissionsListResponse, UserResponse,
+    ClientInfo, CodeCoverage, ListSubmissionsQuery, MarkSummary, PlagiarismInfo, RubricMark,
+    SharedIpSubmission, SubmissionDetailResponse, SubmissionListItem, SubmissionsListResponse,
+    UserResponse,
 };
 use crate::{auth::AuthUser, response::ApiResponse};
 use axum::{
     Extension, Json,
+    body::Body,
     extract::{Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use bytes::Bytes;
 use chrono::{DateTime, Utc};
 use db::models::{
     assignment::{Column as AssignmentColumn, Entity as AssignmentEntity},
+    assignment_extension::Model as ExtensionModel,
     assignment_submission::{
         self, Column as SubmissionColumn, Entity as SubmissionEntity, Model as SubmissionModel,
         SubmissionStatus,
     },
     assignment_submission_output::Model as SubmissionOutput,
     assignment_task,
+    group::Model as GroupModel,
     plagiarism_case::{
         Column as PlagiarismCaseColumn, Entity as PlagiarismCaseEntity, Status as PlagiarismStatus,
     },
+    rubric::Model as RubricModel,
     user,
     user_module_role::{self, Role},
 };
+use db::{grade::percentage, soft_delete::SoftDelete};
+use futures::stream;
 use sea_orm::{
     ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
     QueryOrder, QuerySelect, QueryTrait, RelationTrait,
     sea_query::{Expr, Func},
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::cmp::Ordering;
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::{collections::HashMap, fs};
+use tokio::sync::mpsc;
+use util::scan_code_content::DisallowedMatch;
 use util::state::AppState;
+use zip::{
+    CompressionMethod, ZipWriter,
+    write::{SimpleFileOptions, StreamWriter},
+};
 
 fn is_late(submission: DateTime<Utc>, due_date: DateTime<Utc>) -> bool {
     submission > due_date
@@ -111,8 +127,8 @@ async fn get_user_submissions(
     Query(params): Query<ListSubmissionsQuery>,
 ) -> impl IntoResponse {
     let assignment = AssignmentEntity::find()
-        .filter(AssignmentColumn::Id.eq(assignment_id as i32))
-        .filter(AssignmentColumn::ModuleId.eq(module_id as i32))
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
         .await
         .unwrap()
@@ -121,9 +137,24 @@ async fn get_user_submissions(
     let page = params.page.unwrap_or(1).max(1);
     let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
 
+    // With group submissions this includes the submissions made by the student's group
+    let owner = match SubmissionModel::owner_condition(db, &assignment, user_id).await {
+        Ok(c) => c,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+    // Lateness is judged against each submitter's own (possibly extended) due date
+    let due_dates = ExtensionModel::due_dates(db, assignment_id)
+        .await
+        .unwrap_or_default();
     let mut condition = Condition::all()
-        .add(assignment_submission::Column::AssignmentId.eq(assignment_id as i32))
-        .add(assignment_submission::Column::UserId.eq(user_id));
+        .add(assignment_submission::Column::AssignmentId.eq(assignment_id))
+        .add(owner);
 
     // filename: fuzzy, case-insensitive
     if let Some(query) = &params.query {
@@ -137,17 +168,23 @@ async fn get_user_submissions(
     }
 
     if let Some(late_status) = params.late {
-        condition = if late_status {
-            condition.add(assignment_submission::Column::CreatedAt.gt(assignment.due_date))
-        } else {
-            condition.add(assignment_submission::Column::CreatedAt.lte(assignment.due_date))
-        };
+        condition = condition.add(ExtensionModel::late_condition(
+            assignment_submission::Column::UserId,
+            assignment_submission::Column::CreatedAt,
+            assignment.due_date,
+            &due_dates,
+            late_status,
+        ));
     }
 
     if let Some(ignored) = params.ignored {
         condition = condition.add(assignment_submission::Column::Ignored.eq(ignored));
     }
 
+    if let Some(practice) = params.practice {
+        condition = condition.add(assignment_submission::Column::IsPractice.eq(practice));
+    }
+
     if let Some(ref status_csv) = params.status {
         let statuses = parse_statuses_csv(status_csv);
         if !statuses.is_empty() {
@@ -155,7 +192,7 @@ async fn get_user_submissions(
         }
     }
 
-    let mut query = assignment_submission::Entity::find().filter(condition);
+    let mut query = assignment_submission::Entity::find_active().filter(condition);
 
     if let Some(ref sort) = params.sort {
         for field in sort.split(',') {
@@ -209,6 +246,31 @@ async fn get_user_submissions(
         }
     };
 
+    // Group members' submissions carry the member who made them
+    let mut submitters: HashMap<i64, UserResponse> = HashMap::new();
+    let other_ids: Vec<i64> = rows
+        .iter()
+        .map(|s| s.user_id)
+        .filter(|id| *id != user_id)
+        .collect();
+    if !other_ids.is_empty() {
+        for u in user::Entity::find()
+            .filter(user::Column::Id.is_in(other_ids))
+            .all(db)
+            .await
+            .unwrap_or_default()
+        {
+            submitters.insert(
+                u.id,
+                UserResponse {
+                    id: u.id,
+                    username: u.username,
+                    email: u.email,
+                },
+            );
+        }
+    }
+
     let mut items: Vec<SubmissionListItem> = rows
         .into_iter()
         .map(|s| {
@@ -216,36 +278,35 @@ async fn get_user_submissions(
             let report_path =
                 submission_report_path(module_id, assignment_id, s.user_id, s.attempt);
 
-            let (mark, is_practice) = match fs::read_to_string(&report_path) {
-                Ok(content) => {
-                    if let Ok(json) = serde_json::from_str::<Value>(&content) {
-                        let mark = json
-                            .get("mark")
-                            .and_then(|m| serde_json::from_value(m.clone()).ok());
-                        let is_practice = json
-                            .get("is_practice")
-                            .and_then(|p| p.as_bool())
-                            .unwrap_or(false);
-                        (mark, is_practice)
-                    } else {
-                        (None, false)
-                    }
-                }
-                Err(_) => (None, false),
-            };
+            let mark = fs::read_to_string(&report_path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .and_then(|json| json.get("mark").cloned())
+                .and_then(|m| serde_json::from_value(m).ok());
 
             SubmissionListItem {
                 id: s.id,
-                user: user_resp.clone(),
+                user: submitters
+                    .get(&s.user_id)
+                    .cloned()
+                    .unwrap_or_else(|| user_resp.clone()),
                 filename: s.filename,
                 attempt: s.attempt,
                 created_at: s.created_at.to_rfc3339(),
                 updated_at: s.updated_at.to_rfc3339(),
-                is_practice,
-                is_late: is_late(s.created_at, assignment.due_date),
+                is_practice: s.is_practice,
+                is_late: is_late(
+                    s.created_at,
+                    due_dates
+                        .get(&s.user_id)
+                        .copied()
+                        .unwrap_or(assignment.due_date),
+                ),
                 mark,
                 ignored: s.ignored,
                 status: s.status.to_string(),
+                client: None,
+                shared_ip: None,
             }
         })
         .collect();
@@ -337,8 +398,8 @@ async fn get_list_submissions(
     params: ListSubmissionsQuery,
 ) -> impl IntoResponse {
     let assignment = match AssignmentEntity::find()
-        .filter(AssignmentColumn::Id.eq(assignment_id as i32))
-        .filter(AssignmentColumn::ModuleId.eq(module_id as i32))
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
         .one(db)
         .await
     {
@@ -366,8 +427,12 @@ async fn get_list_submissions(
     let page = params.page.unwrap_or(1).max(1);
     let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
 
+    // Lateness is judged against each submitter's own (possibly extended) due date
+    let due_dates = ExtensionModel::due_dates(db, assignment_id)
+        .await
+        .unwrap_or_default();
     let mut condition =
-        Condition::all().add(assignment_submission::Column::AssignmentId.eq(assignment_id as i32));
+        Condition::all().add(assignment_submission::Column::AssignmentId.eq(assignment_id));
 
     // fuzzy, case-insensitive filter across filename + username
     if let Some(query) = &params.query {
@@ -406,17 +471,23 @@ async fn get_list_submissions(
     }
 
     if let Some(late_status) = params.late {
-        condition = if late_status {
-            condition.add(assignment_submission::Column::CreatedAt.gt(assignment.due_date))
-        } else {
-            condition.add(assignment_submission::Column::CreatedAt.lte(assignment.due_date))
-        };
+        condition = condition.add(ExtensionModel::late_condition(
+            assignment_submission::Column::UserId,
+            assignment_submission::Column::CreatedAt,
+            assignment.due_date,
+            &due_dates,
+            late_status,
+        ));
     }
 
     if let Some(ignored) = params.ignored {
         condition = condition.add(assignment_submission::Column::Ignored.eq(ignored));
     }
 
+    if let Some(practice) = params.practice {
+        condition = condition.add(assignment_submission::Column::IsPractice.eq(practice));
+    }
+
     if let Some(ref status_csv) = params.status {
         let statuses = parse_statuses_csv(status_csv);
         if !statuses.is_empty() {
@@ -424,7 +495,40 @@ async fn get_list_submissions(
         }
     }
 
-    let mut query = assignment_submission::Entity::find()
+    // submissions from an IP another user submitted from shortly before or after
+    let shared_ip = SubmissionModel::shared_ip_matches(
+        db,
+        assignment_id,
+        chrono::Duration::seconds(assignment_submission::SHARED_IP_WINDOW_SECS),
+    )
+    .await
+    .unwrap_or_default();
+    if let Some(flagged) = params.shared_ip {
+        let ids: Vec<i64> = shared_ip.keys().copied().collect();
+        condition = condition.add(if flagged {
+            assignment_submission::Column::Id.is_in(ids)
+        } else {
+            assignment_submission::Column::Id.is_not_in(ids)
+        });
+    }
+    let shared_usernames: HashMap<i64, String> = user::Entity::find()
+        .filter(
+            user::Column::Id.is_in(
+                shared_ip
+                    .values()
+                    .flatten()
+                    .map(|m| m.user_id)
+                    .collect::<Vec<_>>(),
+            ),
+        )
+        .all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|u| (u.id, u.username))
+        .collect();
+
+    let mut query = assignment_submission::Entity::find_active()
         .filter(condition)
         .find_also_related(user::Entity);
 
@@ -479,23 +583,23 @@ async fn get_list_submissions(
             let report_path =
                 submission_report_path(module_id, assignment_id, s.user_id, s.attempt);
 
-            let (mark, is_practice) = match fs::read_to_string(&report_path) {
-                Ok(content) => {
-                    if let Ok(json) = serde_json::from_str::<Value>(&content) {
-                        let mark = json
-                            .get("mark")
-                            .and_then(|m| serde_json::from_value(m.clone()).ok());
-                        let is_practice = json
-                            .get("is_practice")
-                            .and_then(|p| p.as_bool())
-                            .unwrap_or(false);
-                        (mark, is_practice)
-                    } else {
-                        (None, false)
-                    }
-                }
-                Err(_) => (None, false),
-            };
+            let mark = fs::read_to_string(&report_path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .and_then(|json| json.get("mark").cloned())
+                .and_then(|m| serde_json::from_value(m).ok());
+
+            let shared = shared_ip.get(&s.id).map(|matches| {
+                matches
+                    .iter()
+                    .map(|m| SharedIpSubmission {
+                        submission_id: m.submission_id,
+                        user_id: m.user_id,
+                        username: shared_usernames.get(&m.user_id).cloned(),
+                        seconds_apart: m.seconds_apart,
+                    })
+                    .collect()
+            });
 
             SubmissionListItem {
                 id: s.id,
@@ -504,11 +608,23 @@ async fn get_list_submissions(
                 attempt: s.attempt,
                 created_at: s.created_at.to_rfc3339(),
                 updated_at: s.updated_at.to_rfc3339(),
-                is_practice,
-                is_late: is_late(s.created_at, assignment.due_date),
+                is_practice: s.is_practice,
+                is_late: is_late(
+                    s.created_at,
+                    due_dates
+                        .get(&s.user_id)
+                        .copied()
+                        .unwrap_or(assignment.due_date),
+                ),
                 mark,
                 ignored: s.ignored,
                 status: s.status.to_string(),
+                client: Some(ClientInfo {
+                    ip: s.client_ip,
+                    user_agent: s.user_agent,
+                    upload_duration_ms: s.upload_duration_ms,
+                }),
+                shared_ip: Some(shared.unwrap_or_default()),
             }
         })
         .collect();
@@ -581,8 +697,14 @@ async fn is_student(module_id: i64, user_id: i64, db: &DatabaseConnection) -> bo
 ///
 /// ### Query Parameters
 /// - `page`, `per_page`, `query`, `username`, `sort` (see API docs above for details)
+/// - `shared_ip` (bool, staff only): `true` keeps only submissions uploaded from an IP address
+///   another user submitted from within two minutes of them, `false` drops them
 ///
 /// ### Notes
+/// - Staff also get each submission's `client` (`ip`, `user_agent`, `upload_duration_ms`) and
+///   `shared_ip`: the other users' submissions from the same IP in that window, with
+///   `seconds_apart`. These are leads for the plagiarism workflow, not evidence on their own;
+///   groups submitting together are not flagged.
 /// - Students: `username` is ignored, only their own submissions returned.
 /// - Late submissions are calculated based on `due_date`.
 pub async fn list_submissions(
@@ -657,6 +779,11 @@ pub async fn list_submissions(
 ///         "lines_matched": 15,
 ///         "description": "Similar to submission 789"
 ///     },
+///     "rubric": {
+///         "weight": 30.0,
+///         "percentage": 70.0,
+///         "final_percentage": 80.5
+///     },
 ///   }
 /// }
 /// ```
@@ -709,8 +836,14 @@ pub async fn list_submissions(
 ///
 /// ### Notes
 /// - User metadata is only included for non-student users (lecturers, tutors, admins)
+/// - `rubric` is only included once the submission has been scored against the assignment's
+///   rubric; `final_percentage` is the grade the submission counts for
 /// - The response contains the complete grading report including marks, tasks, and optional
 ///   code coverage analysis
+/// - For timed exams with `exam.hide_feedback`, students get each subsection's `feedback` as an
+///   empty string until their due date has passed
+/// - With `marking.reduced_practice_feedback`, students get the same marks-only report for
+///   their practice submissions
 /// - Access is restricted to users with appropriate permissions for the module
 
 pub async fn get_submission(
@@ -826,11 +959,6 @@ pub async fn get_submission(
             total: 0.0,
         });
 
-    let is_practice = parsed
-        .get("is_practice")
-        .and_then(|p| p.as_bool())
-        .unwrap_or(false);
-
     let mut tasks: Vec<serde_json::Value> = parsed
         .get("tasks")
         .and_then(|t| t.as_array())
@@ -841,6 +969,10 @@ pub async fn get_submission(
         .get("code_coverage")
         .and_then(|c| serde_json::from_value::<CodeCoverage>(c.clone()).ok());
 
+    let disallowed_code = parsed
+        .get("disallowed_code")
+        .and_then(|d| serde_json::from_value::<DisallowedMatch>(d.clone()).ok());
+
     // Enrich task names: replace numeric task IDs or task_numbers with real names
     let (by_id, by_num): (HashMap<i64, String>, HashMap<i64, String>) =
         match assignment_task::Entity::find()
@@ -908,19 +1040,20 @@ pub async fn get_submission(
 
     // Construct the structured response
     let mut user_info = None;
+    let requester_is_student = is_student(module_id, claims.sub, db).await;
 
     // If the requester is not a student, include user info
-    if !is_student(module_id, claims.sub, db).await {
-        if let Ok(Some(u)) = user::Entity::find_by_id(user_id).one(db).await {
-            user_info = Some(
-                serde_json::to_value(UserResponse {
-                    id: u.id,
-                    username: u.username,
-                    email: u.email,
-                })
-                .unwrap(),
-            );
-        }
+    if !requester_is_student
+        && let Ok(Some(u)) = user::Entity::find_by_id(user_id).one(db).await
+    {
+        user_info = Some(
+            serde_json::to_value(UserResponse {
+                id: u.id,
+                username: u.username,
+                email: u.email,
+            })
+            .unwrap(),
+        );
     }
 
     let plagiarism_info = match PlagiarismCaseEntity::find()
@@ -958,7 +1091,27 @@ pub async fn get_submission(
         },
     };
 
-    let response = SubmissionDetailResponse {
+    let due_date = assignment
+        .due_date_for(db, submission.user_id)
+        .await
+        .unwrap_or(assignment.due_date);
+
+    // Blend in the manual rubric mark once the submission has been scored
+    let rubric = match RubricModel::for_assignment(db, assignment_id).await {
+        Ok(Some(r)) => r
+            .percentages(db, &[submission.id])
+            .await
+            .ok()
+            .and_then(|pcts| pcts.get(&submission.id).copied())
+            .map(|pct| RubricMark {
+                weight: r.weight,
+                percentage: pct,
+                final_percentage: r.final_percentage(percentage(mark.earned, mark.total), pct),
+            }),
+        _ => None,
+    };
+
+    let mut response = SubmissionDetailResponse {
         id: submission.id,
         attempt: submission.attempt,
         filename: submission.filename.clone(),
@@ -966,16 +1119,27 @@ pub async fn get_submission(
         created_at: submission.created_at.to_rfc3339(),
         updated_at: submission.updated_at.to_rfc3339(),
         mark,
-        is_practice,
-        is_late: is_late(submission.created_at, assignment.due_date),
+        is_practice: submission.is_practice,
+        is_late: is_late(submission.created_at, due_date),
         ignored: submission.ignored,
         status: submission.status.to_string(),
         tasks,
         code_coverage,
         user: user_info,
         plagiarism: plagiarism_info,
+        disallowed_code,
+        rubric,
     };
 
+    // a timed exam's feedback is held back from students until the due date
+    if requester_is_student && assignment.exam().hides_feedback() && Utc::now() < due_date {
+        response.hide_feedback();
+    }
+    // as is practice feedback when the assignment gives practice a reduced report
+    if requester_is_student && submission.is_practice && assignment.reduced_practice_feedback() {
+        response.hide_feedback();
+    }
+
     (
         StatusCode::OK,
         Json(ApiResponse::success(
@@ -1126,8 +1290,16 @@ pub async fn download_submission_file(
         }
     };
 
-    // Authorization: owner, staff on this module, or admin
-    let is_owner = claims.sub == submission.user_id;
+    // Authorization: owner (or a member of the group it was made for), staff on this module,
+    // or admin
+    let is_owner = claims.sub == submission.user_id
+        || match submission.group_id {
+            Some(gid) => GroupModel::member_ids(db, gid)
+                .await
+                .map(|ids| ids.contains(&claims.sub))
+                .unwrap_or(false),
+            None => false,
+        };
     let is_admin = claims.admin;
     let is_staff = if is_admin {
         true
@@ -1156,20 +1328,16 @@ pub async fn download_submission_file(
             .into_response();
     }
 
-    // Resolve file path from the stored relative path (submission.full_path() joins STORAGE_ROOT)
-    let full_path: PathBuf = submission.full_path();
-
-    if tokio::fs::metadata(&full_path).await.is_err() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(ApiResponse::<()>::error("File missing on disk")),
-        )
-            .into_response();
-    }
-
-    // Read file bytes
-    let buffer = match tokio::fs::read(&full_path).await {
+    // Read file bytes from the configured storage backend
+    let buffer = match submission.load_file().await {
         Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("File missing in storage")),
+            )
+                .into_response();
+        }
         Err(_) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -1193,3 +1361,224 @@ pub async fn download_submission_file(
 
     (StatusCode::OK, headers, buffer).into_response()
 }
+
+#[derive(Debug, Deserialize)]
+pub struct ExportSubmissionsQuery {
+    /// Comma-separated usernames to export; everyone when absent.
+    pub username: Option<String>,
+    /// Consider practice attempts when picking each student's latest submission.
+    pub include_practice: Option<bool>,
+}
+
+/// Bytes written by the blocking zip writer, handed to the response stream in bounded chunks.
+struct ChannelWriter(mpsc::Sender<io::Result<Bytes>>);
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "client went away"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// One student's entry in the export: the folder name and their latest submission.
+struct ExportEntry {
+    folder: String,
+    submission: SubmissionModel,
+    report: PathBuf,
+}
+
+/// Writes every entry's submission file and report into `zip`. Files are copied from their
+/// local paths, so no more than a buffer of any of them is held in memory.
+fn write_export<W: Write>(
+    runtime: &tokio::runtime::Handle,
+    zip: &mut ZipWriter<StreamWriter<W>>,
+    entries: &[ExportEntry],
+) -> zip::result::ZipResult<()> {
+    let deflated = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    // archives are already compressed
+    let stored = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+
+    for entry in entries {
+        let submission = &entry.submission;
+        match runtime.block_on(submission.local_file()) {
+            Ok(path) => {
+                let options = if util::source_files::is_source_file(&submission.filename) {
+                    deflated
+                } else {
+                    stored
+                };
+                zip.start_file(format!("{}/{}", entry.folder, submission.filename), options)?;
+                io::copy(&mut fs::File::open(path)?, zip)?;
+            }
+            Err(e) => tracing::warn!(
+                submission_id = submission.id,
+                "Submission file missing from export: {e}"
+            ),
+        }
+        if let Ok(mut report) = fs::File::open(&entry.report) {
+            zip.start_file(format!("{}/submission_report.json", entry.folder), deflated)?;
+            io::copy(&mut report, zip)?;
+        }
+    }
+    Ok(())
+}
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/submissions/export
+///
+/// Downloads every student's latest submission as one zip, for external moderation and
+/// archiving. Each student gets a folder named after their username holding the submitted file
+/// and its `submission_report.json`. Ignored submissions are passed over, as are practice
+/// attempts unless `include_practice` is set. Only lecturers and assistant lecturers (and admins)
+/// may export.
+///
+/// The zip is generated while it is sent, one file at a time, so memory use does not grow with
+/// the size of the class.
+///
+/// ### Query Parameters
+/// - `username` (optional): Comma-separated usernames to export instead of the whole class
+/// - `include_practice` (optional, default `false`): Let a practice attempt be the latest
+///
+/// ### Example Request
+/// ```bash
+/// curl -OJ "http://localhost:3000/api/modules/1/assignments/2/submissions/export?username=u12345678,u87654321" \
+///   -H "Authorization: Bearer <token>"
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — `application/zip` attachment `submissions_module_{m}_assignment_{a}.zip`
+/// - `404 Not Found` — Assignment not found, or no submissions match
+/// - `500 Internal Server Error` — Database error
+pub async fn export_submissions(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+    Query(params): Query<ExportSubmissionsQuery>,
+) -> Response {
+    let db = app_state.db();
+
+    match AssignmentEntity::find()
+        .filter(AssignmentColumn::Id.eq(assignment_id))
+        .filter(AssignmentColumn::ModuleId.eq(module_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Assignment not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    }
+
+    let latest = match SubmissionModel::get_latest_counted_submissions_for_assignment(
+        db,
+        assignment_id,
+        params.include_practice.unwrap_or(false),
+    )
+    .await
+    {
+        Ok(s) => s,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+    let user_ids: Vec<i64> = latest.iter().map(|s| s.user_id).collect();
+    let usernames: HashMap<i64, String> = match user::Entity::find()
+        .filter(user::Column::Id.is_in(user_ids))
+        .all(db)
+        .await
+    {
+        Ok(users) => users.into_iter().map(|u| (u.id, u.username)).collect(),
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database error")),
+            )
+                .into_response();
+        }
+    };
+
+    let wanted: Option<Vec<String>> = params.username.as_deref().map(|csv| {
+        csv.split(',')
+            .map(|u| u.trim().to_ascii_lowercase())
+            .filter(|u| !u.is_empty())
+            .collect()
+    });
+    let mut entries: Vec<ExportEntry> = latest
+        .into_iter()
+        .filter_map(|submission| {
+            let username = usernames.get(&submission.user_id)?;
+            if let Some(wanted) = &wanted
+                && !wanted.contains(&username.to_ascii_lowercase())
+            {
+                return None;
+            }
+            Some(ExportEntry {
+                folder: username.replace(['/', '\\'], "_"),
+                report: submission_report_path(
+                    module_id,
+                    assignment_id,
+                    submission.user_id,
+                    submission.attempt,
+                ),
+                submission,
+            })
+        })
+        .collect();
+    if entries.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("No submissions to export")),
+        )
+            .into_response();
+    }
+    entries.sort_by(|a, b| a.folder.cmp(&b.folder));
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let runtime = tokio::runtime::Handle::current();
+    tokio::task::spawn_blocking(move || {
+        let mut zip = ZipWriter::new_stream(ChannelWriter(tx.clone()));
+        let result = write_export(&runtime, &mut zip, &entries).and_then(|_| zip.finish());
+        if let Err(e) = result {
+            tracing::error!(assignment_id, "Submission export failed: {e}");
+            let _ = tx.blocking_send(Err(io::Error::other(e)));
+        }
+    });
+    let body = Body::from_stream(stream::poll_fn(move |cx| rx.poll_recv(cx)));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/zip"),
+    );
+    headers.insert(
+        header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!(
+            "attachment; filename=\"submissions_module_{module_id}_assignment_{assignment_id}.zip\""
+        ))
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment")),
+    );
+    (StatusCode::OK, headers, body).into_response()
+}
@@ -3,8 +3,9 @@
 //! Provides the `/submissions` route group for handling assignment submissions.
 //!
 //! Routes include:
-//! - Create, resubmit, remark, get, and download submissions
+//! - Create, resubmit, remark, dry-run, get, and download submissions
 //! - List all submissions
+//! - Export everyone's latest submission as a zip
 //! - Get submission output
 //!
 //! Access control is enforced via middleware guards for students, tutors, or lecturers.
@@ -15,12 +16,17 @@ use axum::{
     routing::{delete, get, patch, post},
 };
 
-use delete::{bulk_delete_submissions, delete_submission};
-use get::{get_submission, get_submission_output, list_submissions};
+use delete::{bulk_delete_submissions, cancel_submission_run, delete_submission};
+use get::{export_submissions, get_submission, get_submission_output, list_submissions};
 use patch::set_submission_ignored;
-use post::{remark_submissions, resubmit_submissions, submit_assignment};
+use post::{
+    dry_run_submission, remark_submissions, restore_submission, resubmit_submissions,
+    submit_assignment,
+};
 
-use crate::auth::guards::{allow_assistant_lecturer, allow_ready_assignment, allow_tutor};
+use crate::auth::guards::{
+    allow_assistant_lecturer, allow_ready_assignment, allow_tutor, deny_after_moderation_sign_off,
+};
 use crate::routes::modules::assignments::submissions::get::download_submission_file;
 use util::state::AppState;
 
@@ -34,18 +40,32 @@ pub mod post;
 ///
 /// ### Routes
 /// - `GET    /`                          — List submissions (students: only their own; staff: all)
+/// - `GET    /export`                    — Zip of every student's latest submission and report (**lecturer/assistant lecturer only**)
 /// - `GET    /{submission_id}`           — Get a submission's report (role-aware extras)
 /// - `GET    /{submission_id}/output`    — Get task output (**lecturer/tutor only**)
 /// - `GET    /{submission_id}/download`  — Download original submission file (owner or staff)
 /// - `POST   /`                          — Create a submission (**student only**, guarded by assignment readiness)
 /// - `POST   /remark`                    — Remark submissions (**lecturer/assistant lecturer only**)
 /// - `POST   /resubmit`                  — Resubmit submissions (**lecturer/assistant lecturer only**)
+/// - `POST   /{submission_id}/dry_run`   — Run all tasks and return the outputs without saving them (**lecturer/assistant lecturer only**)
 /// - `PATCH  /{submission_id}/ignore`    — Toggle `ignored` flag (**lecturer/assistant lecturer only**)
 /// - `DELETE /{submission_id}`           — Delete a submission (**lecturer/assistant lecturer only**)
+/// - `POST   /{submission_id}/restore`   — Restore a deleted submission (**lecturer/assistant lecturer only**)
 /// - `DELETE /bulk`                      — Bulk delete submissions (**lecturer/assistant lecturer only**)
+/// - `DELETE /{submission_id}/run`       — Cancel the submission's in-flight run (**lecturer/assistant lecturer only**)
+///
+/// Creating, remarking, resubmitting, ignoring, deleting and restoring submissions is refused
+/// with `409 Conflict` once the assignment's moderation has been signed off.
 pub fn submission_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
         .route("/", get(list_submissions))
+        .route(
+            "/export",
+            get(export_submissions).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
         .route("/{submission_id}", get(get_submission))
         .route(
             "/{submission_id}/output",
@@ -55,44 +75,100 @@ pub fn submission_routes(app_state: AppState) -> Router<AppState> {
         .route("/{submission_id}/download", get(download_submission_file))
         .route(
             "/{submission_id}",
-            delete(delete_submission).route_layer(from_fn_with_state(
+            delete(delete_submission)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/{submission_id}/run",
+            delete(cancel_submission_run).route_layer(from_fn_with_state(
                 app_state.clone(),
                 allow_assistant_lecturer,
             )),
         )
         .route(
             "/bulk",
-            delete(bulk_delete_submissions).route_layer(from_fn_with_state(
-                app_state.clone(),
-                allow_assistant_lecturer,
-            )),
+            delete(bulk_delete_submissions)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
         )
         .route(
             "/",
-            post(submit_assignment).route_layer(from_fn_with_state(
-                app_state.clone(),
-                allow_ready_assignment,
-            )),
+            post(submit_assignment)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_ready_assignment,
+                )),
         )
         .route(
             "/remark",
-            post(remark_submissions).route_layer(from_fn_with_state(
-                app_state.clone(),
-                allow_assistant_lecturer,
-            )),
+            post(remark_submissions)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
         )
         .route(
             "/resubmit",
-            post(resubmit_submissions).route_layer(from_fn_with_state(
+            post(resubmit_submissions)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
+        .route(
+            "/{submission_id}/dry_run",
+            post(dry_run_submission).route_layer(from_fn_with_state(
                 app_state.clone(),
                 allow_assistant_lecturer,
             )),
         )
+        .route(
+            "/{submission_id}/restore",
+            post(restore_submission)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
+        )
         .route(
             "/{submission_id}/ignore",
-            patch(set_submission_ignored).route_layer(from_fn_with_state(
-                app_state.clone(),
-                allow_assistant_lecturer,
-            )),
+            patch(set_submission_ignored)
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    deny_after_moderation_sign_off,
+                ))
+                .route_layer(from_fn_with_state(
+                    app_state.clone(),
+                    allow_assistant_lecturer,
+                )),
         )
 }
@@ -1,24 +1,36 @@
 use super::common::{MarkSummary, PlagiarismInfo, SubmissionDetailResponse};
-use crate::services::email::EmailService;
+use crate::routes::uploads::common::read_completed_upload;
+use crate::services::lti;
+use crate::services::notifications::{self, NewNotification};
+use crate::services::{email::EmailService, metrics};
+use crate::ws::ga::{emit as ga_emit, payload as ga_payload};
 use crate::ws::submissions::{emit as sub_emit, payload as sub_payload};
 use crate::{auth::AuthUser, response::ApiResponse, routes::modules::assignments::get::is_late};
+use ai::utils::progress::{GaProgress, GaProgressSink};
 use axum::{
     Json,
-    extract::{Extension, Multipart, Path, Query, State},
-    http::StatusCode,
+    extract::{ConnectInfo, Extension, Multipart, Path, Query, State},
+    http::{HeaderMap, StatusCode, header::USER_AGENT},
     response::IntoResponse,
 };
 use chrono::Utc;
 use code_runner;
+use code_runner::code_manager_client::Priority;
 use db::models::assignment_submission_output;
 use db::models::assignment_task::{self, TaskType};
+use db::models::exam_session::Model as ExamSessionModel;
+use db::models::group::Model as GroupModel;
+use db::models::notification::NotificationKind;
 use db::models::user::Entity as UserEntity;
 use db::models::{
     assignment::{Column as AssignmentColumn, Entity as AssignmentEntity},
     assignment_submission::{self, Model as AssignmentSubmissionModel},
     user,
 };
-use db::models::{assignment_memo_output, assignment_submission::SubmissionStatus};
+use db::models::{
+    assignment_memo_output,
+    assignment_submission::{SubmissionRunState, SubmissionStatus},
+};
 use marker::MarkingJob;
 use marker::comparators::{
     exact_comparator::ExactComparator, percentage_comparator::PercentageComparator,
@@ -29,10 +41,12 @@ use marker::feedback::{
     ai_feedback::AiFeedback, auto_feedback::AutoFeedback, manual_feedback::ManualFeedback,
 };
 use md5;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, Order, QueryFilter, QueryOrder, TransactionTrait,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{fs, path::PathBuf};
+use std::{fs, net::SocketAddr, path::PathBuf, time::Instant};
 use tokio_util::bytes;
 use util::mark_allocator::{generate_allocator, save_allocator};
 use util::paths::{
@@ -40,11 +54,13 @@ use util::paths::{
     submission_report_path,
 };
 use util::paths::{storage_root as storage_root_path, submission_output_dir};
+use util::scan_code_content::DisallowedMatch;
 use util::{
+    archive::{self, ArchiveLimits},
     execution_config::{
         ExecutionConfig, {FeedbackScheme, MarkingScheme, SubmissionMode},
     },
-    mark_allocator, scan_code_content,
+    mark_allocator, scan_code_content, source_files,
     state::AppState,
 };
 
@@ -179,7 +195,7 @@ pub enum DisallowedCodeCheckResult {
     /// No disallowed code found - continue with normal processing
     Clean,
     /// Disallowed code found - should set mark to zero
-    DisallowedFound(SubmissionDetailResponse),
+    DisallowedFound(Box<SubmissionDetailResponse>),
     /// Error occurred during checking - continue with normal processing (best-effort)
     CheckFailed(String),
 }
@@ -188,7 +204,8 @@ pub enum DisallowedCodeCheckResult {
 fn build_disallowed_submission_response(
     submission: &AssignmentSubmissionModel,
     total_marks: f64,
-    assignment: &db::models::assignment::Model,
+    due_date: chrono::DateTime<Utc>,
+    found: DisallowedMatch,
 ) -> SubmissionDetailResponse {
     let now = Utc::now();
     SubmissionDetailResponse {
@@ -203,7 +220,7 @@ fn build_disallowed_submission_response(
             total: total_marks,
         },
         is_practice: submission.is_practice,
-        is_late: is_late(submission.created_at, assignment.due_date),
+        is_late: is_late(submission.created_at, due_date),
         ignored: submission.ignored,
         status: SubmissionStatus::FailedDisallowedCode.to_string(),
         tasks: vec![],
@@ -215,6 +232,8 @@ fn build_disallowed_submission_response(
             lines_matched: 0,
             description: "".to_string(),
         },
+        disallowed_code: Some(found),
+        rubric: None,
     }
 }
 
@@ -277,8 +296,9 @@ pub async fn check_disallowed_code_existing(
     };
 
     // Check if the file contains disallowed code
-    match scan_code_content::contains_dissalowed_code(file_bytes, config) {
-        Ok(true) => {
+    match scan_code_content::find_dissalowed_code_in_file(&submission.filename, file_bytes, config)
+    {
+        Ok(Some(found)) => {
             // Load allocator for total marks
             let allocator =
                 match mark_allocator::load_allocator(assignment.module_id, assignment.id) {
@@ -317,8 +337,16 @@ pub async fn check_disallowed_code_existing(
             };
 
             // Build response using shared helper
-            let response =
-                build_disallowed_submission_response(&updated, allocator.total_value, assignment);
+            let due_date = assignment
+                .due_date_for(db, updated.user_id)
+                .await
+                .unwrap_or(assignment.due_date);
+            let response = build_disallowed_submission_response(
+                &updated,
+                allocator.total_value,
+                due_date,
+                found,
+            );
 
             // Save report using shared helper
             if let Err(e) = save_submission_report(
@@ -331,9 +359,9 @@ pub async fn check_disallowed_code_existing(
                 eprintln!("Failed to save submission report: {}", e);
             }
 
-            DisallowedCodeCheckResult::DisallowedFound(response)
+            DisallowedCodeCheckResult::DisallowedFound(Box::new(response))
         }
-        Ok(false) => {
+        Ok(None) => {
             if submission.ignored {
                 if let Err(e) =
                     AssignmentSubmissionModel::set_ignored(db, submission.id, false).await
@@ -385,8 +413,8 @@ pub async fn check_disallowed_code_new(
     file_hash: &str,
     assignment: &db::models::assignment::Model,
 ) -> DisallowedCodeCheckResult {
-    match scan_code_content::contains_dissalowed_code(file_bytes, config) {
-        Ok(true) => {
+    match scan_code_content::find_dissalowed_code_in_file(file_name, file_bytes, config) {
+        Ok(Some(found)) => {
             let allocator =
                 match mark_allocator::load_allocator(assignment.module_id, assignment_id) {
                     Ok(a) => a,
@@ -448,8 +476,16 @@ pub async fn check_disallowed_code_new(
             };
 
             // Build response using shared helper
-            let response =
-                build_disallowed_submission_response(&updated, allocator.total_value, assignment);
+            let due_date = assignment
+                .due_date_for(db, updated.user_id)
+                .await
+                .unwrap_or(assignment.due_date);
+            let response = build_disallowed_submission_response(
+                &updated,
+                allocator.total_value,
+                due_date,
+                found,
+            );
 
             // Save report using shared helper
             if let Err(e) = save_submission_report(
@@ -462,9 +498,9 @@ pub async fn check_disallowed_code_new(
                 eprintln!("Failed to save submission report: {}", e);
             }
 
-            DisallowedCodeCheckResult::DisallowedFound(response)
+            DisallowedCodeCheckResult::DisallowedFound(Box::new(response))
         }
-        Ok(false) => DisallowedCodeCheckResult::Clean,
+        Ok(None) => DisallowedCodeCheckResult::Clean,
         Err(e) => {
             eprintln!("Disallowed scan error: {}", e);
             DisallowedCodeCheckResult::CheckFailed(format!("Scan error: {}", e))
@@ -543,17 +579,20 @@ fn get_execution_config(module_id: i64, assignment_id: i64) -> Result<ExecutionC
         .map_err(|_| "Failed to load execution config".to_string())
 }
 
+/// Rejection of an upload by [`validate_file_upload`].
+type UploadError = (StatusCode, Json<ApiResponse<Box<SubmissionDetailResponse>>>);
+
 /// Validates file upload requirements
 fn validate_file_upload(
     file_name: &Option<String>,
     file_bytes: &Option<bytes::Bytes>,
-) -> Result<(String, bytes::Bytes), (StatusCode, Json<ApiResponse<SubmissionDetailResponse>>)> {
+) -> Result<(String, bytes::Bytes), UploadError> {
     let file_name = match file_name {
         Some(name) => name.clone(),
         None => {
             return Err((
                 StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponse::<SubmissionDetailResponse>::error(
+                Json(ApiResponse::<Box<SubmissionDetailResponse>>::error(
                     "No file provided",
                 )),
             ));
@@ -565,27 +604,28 @@ fn validate_file_upload(
         None => {
             return Err((
                 StatusCode::UNPROCESSABLE_ENTITY,
-                Json(ApiResponse::<SubmissionDetailResponse>::error(
+                Json(ApiResponse::<Box<SubmissionDetailResponse>>::error(
                     "No file provided",
                 )),
             ));
         }
     };
 
-    let allowed_extensions = [".tgz", ".gz", ".tar", ".zip"];
+    let allowed_extensions = [".tgz", ".gz", ".tar", ".zip", ".7z", ".rar"];
     let file_extension = std::path::Path::new(&file_name)
         .extension()
         .and_then(|ext| ext.to_str())
         .map(|ext| format!(".{}", ext.to_lowercase()));
 
-    if !file_extension
+    // Plain source files (e.g. a lone `main.py`) are zipped up by the code runner
+    let is_archive = file_extension
         .as_ref()
-        .map_or(false, |ext| allowed_extensions.contains(&ext.as_str()))
-    {
+        .is_some_and(|ext| allowed_extensions.contains(&ext.as_str()));
+    if !is_archive && !source_files::is_source_file(&file_name) {
         return Err((
             StatusCode::UNPROCESSABLE_ENTITY,
-            Json(ApiResponse::<SubmissionDetailResponse>::error(
-                "Only .tgz, .gz, .tar, and .zip files are allowed",
+            Json(ApiResponse::<Box<SubmissionDetailResponse>>::error(
+                "Only .tgz, .gz, .tar, .zip, .7z, .rar or plain source files are allowed",
             )),
         ));
     }
@@ -593,7 +633,7 @@ fn validate_file_upload(
     if file_bytes.is_empty() {
         return Err((
             StatusCode::UNPROCESSABLE_ENTITY,
-            Json(ApiResponse::<SubmissionDetailResponse>::error(
+            Json(ApiResponse::<Box<SubmissionDetailResponse>>::error(
                 "Empty file provided",
             )),
         ));
@@ -621,8 +661,41 @@ async fn get_next_attempt(
     Ok(prev_attempt + 1)
 }
 
-/// Core grading function that can be used for initial submissions, regrading, and resubmission
+/// Core grading function that can be used for initial submissions, regrading, and resubmission.
+///
+/// Records how long marking took in the `fitchfork_marking_duration_seconds` metric. A failure
+/// marks the submission's run as failed, leaving its previous marks in place.
 async fn grade_submission(
+    submission: AssignmentSubmissionModel,
+    assignment: &db::models::assignment::Model,
+    memo_outputs: &[std::path::PathBuf],
+    config: &util::execution_config::ExecutionConfig,
+    db: &sea_orm::DatabaseConnection,
+    strict_mismatch_error: bool,
+) -> Result<SubmissionDetailResponse, String> {
+    let submission_id = submission.id;
+    let started = std::time::Instant::now();
+    let result = mark_submission(
+        submission,
+        assignment,
+        memo_outputs,
+        config,
+        db,
+        strict_mismatch_error,
+    )
+    .await;
+    metrics::observe_marking(started.elapsed(), result.is_ok());
+    if result.is_err()
+        && let Err(e) =
+            AssignmentSubmissionModel::set_run_state(db, submission_id, SubmissionRunState::Failed)
+                .await
+    {
+        eprintln!("Failed to record run state: {:?}", e);
+    }
+    result
+}
+
+async fn mark_submission(
     submission: AssignmentSubmissionModel,
     assignment: &db::models::assignment::Model,
     _memo_outputs: &[std::path::PathBuf],
@@ -818,6 +891,17 @@ async fn grade_submission(
         marking_job = marking_job.with_valgrind(valgrind_path);
     }
 
+    let massif_path = attempt_dir(
+        assignment.module_id,
+        assignment.id,
+        submission.user_id,
+        submission.attempt,
+    )
+    .join("massif_report.json");
+    if massif_path.exists() {
+        marking_job = marking_job.with_massif(massif_path);
+    }
+
     let mark_report = match marking_job.mark().await {
         Ok(report) => report,
         Err(e) => {
@@ -852,13 +936,17 @@ async fn grade_submission(
         total: mark_report.data.mark.total,
     };
 
-    // Apply late cap if applicable
-    let is_late_now = submission.created_at > assignment.due_date;
+    // Apply late cap if applicable (against the student's extension, if any)
+    let due_date = assignment
+        .due_date_for(db, submission.user_id)
+        .await
+        .unwrap_or(assignment.due_date);
+    let is_late_now = submission.created_at > due_date;
     let mut _late_capped_to: Option<f64> = None;
     if is_late_now && config.marking.late.allow_late_submissions {
         if within_late_window(
             submission.created_at,
-            assignment.due_date,
+            due_date,
             config.marking.late.late_window_minutes,
         ) {
             let (adj, capped) = cap_late_earned(
@@ -890,6 +978,9 @@ async fn grade_submission(
                     total_lines: s.total_lines as u32,
                     covered_lines: s.covered_lines as u32,
                     coverage_percent: s.coverage_percent,
+                    branch_percent: s.branch_percent,
+                    function_percent: s.function_percent,
+                    marked_percent: Some(s.marked_percent),
                 });
             let files: Vec<serde_json::Value> = cov
                 .files
@@ -909,15 +1000,21 @@ async fn grade_submission(
         })
         .and_then(|v| serde_json::from_value::<super::common::CodeCoverage>(v).ok());
 
+    // Marks, status and run state land together or not at all
+    let txn = db.begin().await.map_err(|e| e.to_string())?;
     let mut active_model: assignment_submission::ActiveModel = submission.clone().into();
     active_model.earned = sea_orm::ActiveValue::Set(mark.earned);
     active_model.total = sea_orm::ActiveValue::Set(mark.total);
     active_model.status =
         sea_orm::ActiveValue::Set(assignment_submission::SubmissionStatus::Graded);
     assignment_submission::Entity::update(active_model)
-        .exec(db)
+        .exec(&txn)
+        .await
+        .map_err(|e| e.to_string())?;
+    AssignmentSubmissionModel::set_run_state(&txn, submission.id, SubmissionRunState::Marked)
         .await
         .map_err(|e| e.to_string())?;
+    txn.commit().await.map_err(|e| e.to_string())?;
 
     let now = Utc::now();
     let resp = SubmissionDetailResponse {
@@ -929,7 +1026,7 @@ async fn grade_submission(
         updated_at: now.to_rfc3339(),
         mark,
         is_practice: submission.is_practice,
-        is_late: is_late(submission.created_at, assignment.due_date),
+        is_late: is_late(submission.created_at, due_date),
         ignored: submission.ignored,
         status: submission.status.to_string(),
         tasks,
@@ -941,6 +1038,8 @@ async fn grade_submission(
             lines_matched: 0,
             description: "".to_string(),
         },
+        disallowed_code: None,
+        rubric: None,
     };
 
     let report_path = submission_report_path(
@@ -959,30 +1058,127 @@ async fn grade_submission(
     Ok(resp)
 }
 
+/// Forwards per-generation GA progress to the submission's `ga:{submission_id}` topic.
+///
+/// The forwarder finishes once the returned sink is dropped, i.e. when the GA job returns.
+fn forward_ga_progress(
+    app: &AppState,
+    module_id: i64,
+    assignment_id: i64,
+    submission_id: i64,
+) -> (GaProgressSink, tokio::task::JoinHandle<()>) {
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel::<GaProgress>();
+    let ws = app.ws_clone();
+    let forwarder = tokio::spawn(async move {
+        while let Some(p) = progress_rx.recv().await {
+            let payload = ga_payload::GaProgressPayload {
+                module_id,
+                assignment_id,
+                submission_id,
+                generation: p.generation,
+                generations: p.generations,
+                best_fitness: p.best_fitness,
+                mean_fitness: p.mean_fitness,
+                coverage_percent: p.coverage_percent,
+                evaluated: p.evaluated,
+                interpreter_runs: p.interpreter_runs,
+                cache_hits: p.cache_hits,
+            };
+            ga_emit::progress(&ws, payload).await;
+        }
+    });
+    (progress_tx, forwarder)
+}
+
 async fn process_submission_code(
     db: &sea_orm::DatabaseConnection,
-    submission_id: i64,
+    app: &AppState,
+    submission: &AssignmentSubmissionModel,
     config: ExecutionConfig,
     module_id: i64,
     assignment_id: i64,
 ) -> Result<(), String> {
+    let submission_id = submission.id;
+
+    // One job for the whole run, so a cancel also stops GA iterations that haven't started yet.
+    // A student waiting on a manual run jumps ahead of GA runs in code_manager's queue; a job
+    // already started by a bulk resubmit keeps its bulk priority.
+    let priority = match config.project.submission_mode {
+        SubmissionMode::Manual => Priority::Interactive,
+        _ => Priority::Bulk,
+    };
+    let _job = code_runner::jobs::start_job_with_priority(
+        &code_runner::jobs::submission_job_key(submission_id),
+        priority,
+    );
+
     let res = match config.project.submission_mode {
         SubmissionMode::Manual => {
-            code_runner::create_submission_outputs_for_all_tasks(db, submission_id)
-                .await
-                .map_err(|e| format!("Code runner failed: {}", e))
+            // Forward live task output to the owner + staff submission topics while tasks run.
+            let (output_tx, mut output_rx) =
+                tokio::sync::mpsc::unbounded_channel::<code_runner::TaskOutputChunk>();
+            let ws = app.ws_clone();
+            let user_id = submission.user_id;
+            let attempt = submission.attempt;
+            let forwarder = tokio::spawn(async move {
+                while let Some(chunk) = output_rx.recv().await {
+                    let payload = sub_payload::SubmissionOutputPayload {
+                        module_id,
+                        assignment_id,
+                        submission_id,
+                        attempt,
+                        task_id: chunk.task_id,
+                        task_number: chunk.task_number,
+                        stream: chunk.stream,
+                        data: chunk.data,
+                    };
+                    sub_emit::output(&ws, assignment_id, user_id, payload).await;
+                }
+            });
+
+            let res = code_runner::create_submission_outputs_for_all_tasks_streaming(
+                db,
+                submission_id,
+                Some(output_tx),
+            )
+            .await
+            .map_err(|e| format!("Code runner failed: {}", e));
+
+            // All senders are dropped once the runner returns; drain what's left.
+            let _ = forwarder.await;
+            res
         }
 
         SubmissionMode::GATLAM => {
-            ai::run_ga_job(db, submission_id, config.clone(), module_id, assignment_id)
-                .await
-                .map_err(|e| format!("GATLAM failed: {}", e))
+            let (progress_tx, forwarder) =
+                forward_ga_progress(app, module_id, assignment_id, submission_id);
+            let res = ai::run_ga_job(
+                db,
+                submission_id,
+                config.clone(),
+                assignment_id,
+                Some(progress_tx),
+            )
+            .await
+            .map_err(|e| format!("GATLAM failed: {}", e));
+            let _ = forwarder.await;
+            res
         }
 
         SubmissionMode::CodeCoverage => {
-            ai::run_coverage_ga_job(db, submission_id, &config, module_id, assignment_id)
-                .await
-                .map_err(|e| format!("Coverage GA failed: {}", e))
+            let (progress_tx, forwarder) =
+                forward_ga_progress(app, module_id, assignment_id, submission_id);
+            let res = ai::run_coverage_ga_job(
+                db,
+                submission_id,
+                &config,
+                assignment_id,
+                Some(progress_tx),
+            )
+            .await
+            .map_err(|e| format!("Coverage GA failed: {}", e));
+            let _ = forwarder.await;
+            res
         }
 
         SubmissionMode::RNG => ai::run_rng_job(db, submission_id, &config)
@@ -1086,24 +1282,15 @@ async fn process_submission_code(
     res
 }
 
-/// Clears the submission output directory
-fn clear_submission_output(
+/// Removes the stale submission report before a rerun.
+///
+/// The outputs themselves stay until the rerun replaces them, so a failed rerun still leaves a
+/// consistent set behind.
+fn clear_submission_report(
     submission: &AssignmentSubmissionModel,
     module_id: i64,
     assignment_id: i64,
 ) -> Result<(), String> {
-    let attempt = attempt_dir(
-        module_id,
-        assignment_id,
-        submission.user_id,
-        submission.attempt,
-    );
-    let output_dir = attempt.join("submission_output");
-    if output_dir.exists() {
-        fs::remove_dir_all(&output_dir)
-            .map_err(|e| format!("Failed to clear output directory: {}", e))?;
-    }
-
     let report_path = submission_report_path(
         module_id,
         assignment_id,
@@ -1203,15 +1390,22 @@ fn parse_bool_flag(v: Option<&str>) -> bool {
 /// - `assignment_id` (i64): The ID of the assignment to submit to
 ///
 /// ### Request (multipart/form-data)
-/// - `file` (required): The assignment file to upload (`.tgz`, `.gz`, `.tar`, or `.zip` only)
-/// - `is_practice` (optional): If set to `true` or `1`, marks this as a practice submission
+/// - `file` (required): The assignment file to upload (`.tgz`, `.gz`, `.tar`, `.zip`, `.7z`,
+///   `.rar`, or a plain source file such as `main.py`)
+/// - `upload_id` (instead of `file`): The id of a completed chunked upload (see `/api/uploads`)
+///   holding the file; it is consumed once the submission is stored, so a request that fails
+///   before then can be retried with it
+/// - `is_practice` or `practice` (optional): If set to `true` or `1`, makes this a practice
+///   submission. It is run and marked like any other, but doesn't use up an attempt and never
+///   counts towards the grade. Students may only practise if `allow_practice_submissions` is on,
+///   and with `reduced_practice_feedback` they get the marks without the per-task feedback
 ///
 /// ### Example Request
 /// ```bash
 /// curl -X POST http://localhost:3000/api/modules/1/assignments/2/submissions \
 ///   -H "Authorization: Bearer <token>" \
 ///   -F "file=@solution.zip" \
-///   -F "is_practice=true"
+///   -F "practice=true"
 /// ```
 ///
 /// ### Success Response (200 OK)
@@ -1246,6 +1440,12 @@ fn parse_bool_flag(v: Option<&str>) -> bool {
 ///
 /// ### Error Responses
 ///
+/// **403 Forbidden** - Attempt limit reached, or (timed exams) the student hasn't started the
+/// exam or their time is up
+/// ```json
+/// { "success": false, "message": "Your exam time is up" }
+/// ```
+///
 /// **404 Not Found** - Assignment not found
 /// ```json
 /// { "success": false, "message": "Assignment not found" }
@@ -1257,12 +1457,21 @@ fn parse_bool_flag(v: Option<&str>) -> bool {
 /// ```
 /// or
 /// ```json
-/// { "success": false, "message": "Only .tgz, .gz, .tar, and .zip files are allowed" }
+/// { "success": false, "message": "Only .tgz, .gz, .tar, .zip, .7z, .rar or plain source files are allowed" }
 /// ```
 /// or
 /// ```json
 /// { "success": false, "message": "Empty file provided" }
 /// ```
+/// or (an `upload_id` that isn't one of the caller's completed uploads)
+/// ```json
+/// { "success": false, "message": "Upload 7 is not complete" }
+/// ```
+/// or (an archive that can't be read, escapes its directory or expands past
+/// `execution.max_uncompressed_size`)
+/// ```json
+/// { "success": false, "message": "Invalid archive: Archive contains no files" }
+/// ```
 ///
 /// **500 Internal Server Error** - Grading or system error
 /// ```json
@@ -1282,9 +1491,13 @@ fn parse_bool_flag(v: Option<&str>) -> bool {
 /// ```
 ///
 /// ### Side Effects
-/// - Saves the uploaded file and generated outputs to disk
+/// - Saves the uploaded file and generated outputs to disk; archives are rewritten as a
+///   normalized zip first (see `util::archive`), so the stored `filename` ends in `.zip`
 /// - Triggers code execution and marking
 /// - Saves a copy of the grading report as `submission_report.json` in the attempt folder
+/// - Records the client's IP address, `User-Agent` and how long the upload took (for an
+///   `upload_id`, from starting the chunked upload to completing it); staff see these in the
+///   submission list
 ///
 /// ### Notes
 /// - Each submission increments the attempt number for the user/assignment
@@ -1300,10 +1513,13 @@ pub async fn submit_assignment(
     Path((module_id, assignment_id)): Path<(i64, i64)>,
     Query(q): Query<SubmitQuery>, // async_mode from query string
     Extension(AuthUser(claims)): Extension<AuthUser>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
     mut multipart: Multipart,
 ) -> (StatusCode, Json<ApiResponse<serde_json::Value>>) {
     let db = app_state.db();
     let async_mode = parse_bool_flag(q.async_mode.as_deref());
+    let upload_started = Instant::now();
 
     // ---- load assignment ----
     let assignment = match load_assignment(module_id, assignment_id, db).await {
@@ -1323,6 +1539,7 @@ pub async fn submit_assignment(
     let mut attests_ownership = false;
     let mut file_name: Option<String> = None;
     let mut file_bytes: Option<bytes::Bytes> = None;
+    let mut upload_id: Option<String> = None;
 
     while let Some(field) = multipart.next_field().await.unwrap_or(None) {
         match field.name() {
@@ -1330,7 +1547,10 @@ pub async fn submit_assignment(
                 file_name = field.file_name().map(|s| s.to_string());
                 file_bytes = Some(field.bytes().await.unwrap_or_default());
             }
-            Some("is_practice") => {
+            Some("upload_id") => {
+                upload_id = Some(field.text().await.unwrap_or_default().trim().to_string());
+            }
+            Some("is_practice") | Some("practice") => {
                 let v = field
                     .text()
                     .await
@@ -1351,6 +1571,7 @@ pub async fn submit_assignment(
             _ => {}
         }
     }
+    let mut upload_duration_ms = upload_started.elapsed().as_millis() as i64;
 
     // ---- preconditions / validation ----
     if !attests_ownership {
@@ -1362,6 +1583,43 @@ pub async fn submit_assignment(
         );
     }
 
+    // a completed chunked upload stands in for the file
+    let mut upload = None;
+    if let Some(raw) = upload_id {
+        if file_bytes.is_some() {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::<serde_json::Value>::error(
+                    "Provide either a file or an upload_id, not both",
+                )),
+            );
+        }
+        let Ok(id) = raw.parse::<i64>() else {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::<serde_json::Value>::error(
+                    "upload_id must be an integer",
+                )),
+            );
+        };
+        match read_completed_upload(db, id, claims.sub).await {
+            Ok((found, bytes)) => {
+                if let Some(completed_at) = found.completed_at {
+                    upload_duration_ms = (completed_at - found.created_at).num_milliseconds();
+                }
+                file_name = Some(found.filename.clone());
+                file_bytes = Some(bytes::Bytes::from(bytes));
+                upload = Some(found);
+            }
+            Err(msg) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(ApiResponse::<serde_json::Value>::error(msg)),
+                );
+            }
+        }
+    }
+
     // use shared helper for file presence + extension + non-empty checks
     let (file_name, file_bytes) = match validate_file_upload(&file_name, &file_bytes) {
         Ok(v) => v,
@@ -1374,14 +1632,14 @@ pub async fn submit_assignment(
         }
     };
 
-    // size limit (mirror UI 50MB)
-    const MAX_SIZE_MB: usize = 50;
-    if file_bytes.len() > MAX_SIZE_MB * 1024 * 1024 {
+    // size limit (MAX_UPLOAD_SIZE_MB, shared with chunked uploads)
+    let max_size_mb = util::config::max_upload_size_mb();
+    if file_bytes.len() as u64 > max_size_mb * 1024 * 1024 {
         return (
             StatusCode::PAYLOAD_TOO_LARGE,
-            Json(ApiResponse::<serde_json::Value>::error(
-                "File too large. Max size is 50 MB",
-            )),
+            Json(ApiResponse::<serde_json::Value>::error(format!(
+                "File too large. Max size is {max_size_mb} MB"
+            ))),
         );
     }
 
@@ -1421,6 +1679,33 @@ pub async fn submit_assignment(
         }
     }
 
+    // timed exams: students submit only while their exam session is running
+    let submitter_is_student = user::Model::is_in_role(db, claims.sub, module_id, "Student")
+        .await
+        .unwrap_or(false);
+    let exam = assignment.exam();
+    if exam.enabled && submitter_is_student {
+        let refusal = match ExamSessionModel::for_user(db, assignment_id, claims.sub).await {
+            Ok(Some(session)) if session.is_running(Utc::now()) => None,
+            Ok(Some(_)) => Some("Your exam time is up"),
+            Ok(None) => Some("Start the exam before submitting"),
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<serde_json::Value>::error(
+                        "Failed to load exam session",
+                    )),
+                );
+            }
+        };
+        if let Some(msg) = refusal {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<serde_json::Value>::error(msg)),
+            );
+        }
+    }
+
     // load execution config
     let config = match get_execution_config(module_id, assignment_id) {
         Ok(c) => c,
@@ -1432,6 +1717,34 @@ pub async fn submit_assignment(
         }
     };
 
+    // archives are stored as a normalized zip (no macOS metadata or wrapper folders)
+    let (file_name, file_bytes) = if source_files::is_source_file(&file_name) {
+        (file_name, file_bytes)
+    } else {
+        match archive::normalize_archive(&file_bytes, &ArchiveLimits::from(&config)) {
+            Ok(bytes) => (archive::zip_name(&file_name), bytes::Bytes::from(bytes)),
+            Err(e) => {
+                return (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(ApiResponse::<serde_json::Value>::error(format!(
+                        "Invalid archive: {}",
+                        e
+                    ))),
+                );
+            }
+        }
+    };
+
+    // where the file came from, kept for staff reviewing shared-machine submissions
+    let client = assignment_submission::ClientMetadata {
+        ip: connect_info.map(|Extension(ConnectInfo(addr))| addr.ip().to_string()),
+        user_agent: headers
+            .get(USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        upload_duration_ms: Some(upload_duration_ms),
+    };
+
     // attempt/hash
     let file_hash = format!("{:x}", md5::compute(&file_bytes));
     let attempt = match get_next_attempt(assignment_id, claims.sub, db).await {
@@ -1463,6 +1776,12 @@ pub async fn submit_assignment(
     {
         DisallowedCodeCheckResult::Clean => {}
         DisallowedCodeCheckResult::DisallowedFound(response) => {
+            // stored as a failed submission; the chunked upload's copy is no longer needed
+            if let Some(upload) = upload {
+                let _ = upload.discard(db).await;
+            }
+            let _ = AssignmentSubmissionModel::set_client_metadata(db, response.id, &client).await;
+
             let username_opt = user::Entity::find_by_id(claims.sub)
                 .one(db)
                 .await
@@ -1552,6 +1871,47 @@ pub async fn submit_assignment(
         }
     };
 
+    // the chunked upload has been taken in; its copy is no longer needed
+    if let Some(upload) = upload {
+        let _ = upload.discard(db).await;
+    }
+
+    // group submissions count for every member of the submitter's group
+    let submission = if assignment.group_submissions() {
+        match GroupModel::for_user(db, assignment_id, claims.sub).await {
+            Ok(Some(group)) => {
+                match AssignmentSubmissionModel::set_group(db, submission.id, Some(group.id)).await
+                {
+                    Ok(s) => s,
+                    Err(_) => {
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(ApiResponse::<serde_json::Value>::error(
+                                "Failed to link submission to group",
+                            )),
+                        );
+                    }
+                }
+            }
+            Ok(None) => submission,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<serde_json::Value>::error(
+                        "Failed to look up submission group",
+                    )),
+                );
+            }
+        }
+    } else {
+        submission
+    };
+
+    // best effort; a submission is not refused for want of its metadata
+    let submission = AssignmentSubmissionModel::set_client_metadata(db, submission.id, &client)
+        .await
+        .unwrap_or(submission);
+
     let username_opt = user::Entity::find_by_id(claims.sub)
         .one(db)
         .await
@@ -1636,7 +1996,14 @@ pub async fn submit_assignment(
     )
     .await
     {
-        Ok(resp) => {
+        Ok(mut resp) => {
+            // the exam is still running, so its feedback is held back too
+            if exam.hides_feedback() && submitter_is_student {
+                resp.hide_feedback();
+            }
+            if is_practice && submitter_is_student && assignment.reduced_practice_feedback() {
+                resp.hide_feedback();
+            }
             let body = serde_json::to_value(&resp).unwrap_or_else(|_| json!({}));
             (
                 StatusCode::OK,
@@ -1670,9 +2037,12 @@ async fn run_submission_pipeline(
     assignment_id: i64,
     user_id: i64,
 ) -> Result<SubmissionDetailResponse, String> {
-    // --- Late acceptance gate ---
+    // --- Late acceptance gate (an extension replaces the due date) ---
     let submitted_at = submission.created_at;
-    let due = assignment.due_date;
+    let due = assignment
+        .due_date_for(db, user_id)
+        .await
+        .unwrap_or(assignment.due_date);
     let is_late_now = submitted_at > due;
     if is_late_now {
         let late = &config.marking.late;
@@ -1724,8 +2094,16 @@ async fn run_submission_pipeline(
     }
 
     // execute
-    if let Err(_) =
-        process_submission_code(db, submission.id, config.clone(), module_id, assignment_id).await
+    if process_submission_code(
+        db,
+        app,
+        submission,
+        config.clone(),
+        module_id,
+        assignment_id,
+    )
+    .await
+    .is_err()
     {
         let _ = AssignmentSubmissionModel::set_failed(
             db,
@@ -1816,6 +2194,32 @@ async fn run_submission_pipeline(
                 None,
             )
             .await;
+            // every member of the group shares a group submission's mark
+            let recipients = match submission.group_id {
+                Some(gid) => GroupModel::member_ids(db, gid)
+                    .await
+                    .ok()
+                    .filter(|ids| !ids.is_empty())
+                    .unwrap_or_else(|| vec![user_id]),
+                None => vec![user_id],
+            };
+            lti::spawn_sync(app, module_id, assignment_id, recipients.clone());
+            notifications::spawn_notify(
+                app,
+                recipients,
+                NewNotification {
+                    kind: NotificationKind::SubmissionMarked,
+                    title: format!("{}: submission marked", assignment.name),
+                    body: format!(
+                        "Attempt {} scored {}/{}.",
+                        submission.attempt, resp.mark.earned, resp.mark.total
+                    ),
+                    link: Some(format!(
+                        "/modules/{}/assignments/{}/submissions/{}",
+                        module_id, assignment_id, submission.id
+                    )),
+                },
+            );
             Ok(resp)
         }
         Err(_) => {
@@ -1961,49 +2365,7 @@ pub async fn remark_submissions(
             let assignment = assignment.clone();
             let memo_outputs = memo_outputs.clone();
             let config = config.clone();
-            async move {
-                // Extract extension from submission filename
-                let ext = std::path::PathBuf::from(&submission.filename)
-                    .extension()
-                    .map(|e| e.to_string_lossy().to_string());
-
-                if let Ok(file_bytes) = std::fs::read(util::paths::submission_file_path(
-                    assignment.module_id,
-                    assignment.id,
-                    submission.user_id,
-                    submission.attempt,
-                    submission.id,
-                    ext.as_deref(),
-                )) {
-                    match check_disallowed_code_existing(
-                        submission.id,
-                        &file_bytes,
-                        &config,
-                        db,
-                        &assignment,
-                    )
-                    .await
-                    {
-                        DisallowedCodeCheckResult::Clean => {
-                            // Continue with normal processing
-                        }
-                        DisallowedCodeCheckResult::DisallowedFound(_response) => {
-                            return Ok(());
-                        }
-                        DisallowedCodeCheckResult::CheckFailed(e) => {
-                            eprintln!("Disallowed code check failed: {}", e);
-                            return Err("Failed to scan submission for disallowed code patterns"
-                                .to_string());
-                        }
-                    }
-                } else {
-                    return Err("Failed to read submission file from disk".to_string());
-                }
-
-                grade_submission(submission, &assignment, &memo_outputs, &config, db, true)
-                    .await
-                    .map(|_| ())
-            }
+            async move { remark_one(submission, &assignment, &memo_outputs, &config, db).await }
         })
         .await;
 
@@ -2016,6 +2378,65 @@ pub async fn remark_submissions(
     )
 }
 
+/// Remarks a submission from its stored outputs, after re-checking it for disallowed code.
+async fn remark_one(
+    submission: AssignmentSubmissionModel,
+    assignment: &db::models::assignment::Model,
+    memo_outputs: &[PathBuf],
+    config: &ExecutionConfig,
+    db: &DatabaseConnection,
+) -> Result<(), String> {
+    // Extract extension from submission filename
+    let ext = std::path::PathBuf::from(&submission.filename)
+        .extension()
+        .map(|e| e.to_string_lossy().to_string());
+
+    if let Ok(file_bytes) = std::fs::read(util::paths::submission_file_path(
+        assignment.module_id,
+        assignment.id,
+        submission.user_id,
+        submission.attempt,
+        submission.id,
+        ext.as_deref(),
+    )) {
+        match check_disallowed_code_existing(submission.id, &file_bytes, config, db, assignment)
+            .await
+        {
+            DisallowedCodeCheckResult::Clean => {
+                // Continue with normal processing
+            }
+            DisallowedCodeCheckResult::DisallowedFound(_response) => {
+                return Ok(());
+            }
+            DisallowedCodeCheckResult::CheckFailed(e) => {
+                eprintln!("Disallowed code check failed: {}", e);
+                return Err("Failed to scan submission for disallowed code patterns".to_string());
+            }
+        }
+    } else {
+        return Err("Failed to read submission file from disk".to_string());
+    }
+
+    grade_submission(submission, assignment, memo_outputs, config, db, true)
+        .await
+        .map(|_| ())
+}
+
+/// Remarks a single submission, as `POST /remark` does for each of its targets.
+pub async fn remark_submission(
+    submission: AssignmentSubmissionModel,
+    db: &DatabaseConnection,
+) -> Result<(), String> {
+    let assignment = AssignmentEntity::find_by_id(submission.assignment_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("Database error: {}", e))?
+        .ok_or_else(|| "Assignment not found".to_string())?;
+    let (_, _, memo_outputs) = get_assignment_paths(assignment.module_id, assignment.id)?;
+    let config = get_execution_config(assignment.module_id, assignment.id)?;
+    remark_one(submission, &assignment, &memo_outputs, &config, db).await
+}
+
 /// POST /api/modules/{module_id}/assignments/{assignment_id}/submissions/resubmit
 ///
 /// Reprocess assignment submissions using the latest marking pipeline. Accessible to admins, module lecturers, and assistant lecturers.
@@ -2244,8 +2665,8 @@ pub async fn resubmit_submissions(
             }
         }
 
-        // clear old outputs before launching
-        if let Err(e) = clear_submission_output(&submission, assignment.module_id, assignment.id) {
+        // clear the old report before launching
+        if let Err(e) = clear_submission_report(&submission, assignment.module_id, assignment.id) {
             failed.push(FailedOperation {
                 id: Some(sid),
                 error: e,
@@ -2274,6 +2695,11 @@ pub async fn resubmit_submissions(
         let submission_bg = submission.clone();
 
         tokio::spawn(async move {
+            // Queued behind students' own submissions in code_manager.
+            let _job = code_runner::jobs::start_job_with_priority(
+                &code_runner::jobs::submission_job_key(submission_bg.id),
+                Priority::Bulk,
+            );
             // Full pipeline (exec + grade + WS statuses). Handles its own emits.
             let _ = run_submission_pipeline(
                 &db_bg,
@@ -2309,3 +2735,148 @@ pub async fn resubmit_submissions(
         Json(ApiResponse::success(response, &message)),
     )
 }
+
+#[derive(Debug, Serialize)]
+pub struct DryRunResponse {
+    pub submission_id: i64,
+    pub outputs: Vec<code_runner::TaskRunOutput>,
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/submissions/{submission_id}/dry_run
+///
+/// Run every task of the assignment against an existing submission and return the outputs,
+/// without deleting or saving any stored outputs and without re-marking. Meant for trying out
+/// config, task or makefile changes against a real submission before remarking.
+/// Only accessible by lecturers or assistant lecturers.
+///
+/// ### Path Parameters
+/// - `module_id` (i64): The ID of the module containing the assignment
+/// - `assignment_id` (i64): The ID of the assignment
+/// - `submission_id` (i64): The ID of the submission to run
+///
+/// ### Success Response (200 OK)
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Dry run completed for submission 987",
+///   "data": {
+///     "submission_id": 987,
+///     "outputs": [
+///       {
+///         "task_id": 12,
+///         "task_number": 1,
+///         "output": "&-=-&Subtask1\n...",
+///         "full_size_bytes": 2048,
+///         "truncated": false,
+///         "metrics": { "wall_time_ms": 812, "cpu_time_ms": 640, "max_rss_bytes": 10485760 }
+///       }
+///     ]
+///   }
+/// }
+/// ```
+///
+/// ### Error Responses
+/// - `404 Not Found` — no such submission for this assignment
+/// - `500 Internal Server Error` — the run failed (e.g. missing files, code manager unreachable)
+pub async fn dry_run_submission(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id, submission_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    match assignment_submission::Entity::find()
+        .filter(assignment_submission::Column::Id.eq(submission_id))
+        .filter(assignment_submission::Column::AssignmentId.eq(assignment_id))
+        .one(db)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<DryRunResponse>::error(format!(
+                    "No submission {} found for assignment {}",
+                    submission_id, assignment_id
+                ))),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<DryRunResponse>::error(format!(
+                    "DB error: {}",
+                    e
+                ))),
+            );
+        }
+    }
+
+    match code_runner::create_submission_outputs_for_all_tasks(db, submission_id, true).await {
+        Ok(outputs) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                DryRunResponse {
+                    submission_id,
+                    outputs,
+                },
+                format!("Dry run completed for submission {}", submission_id),
+            )),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<DryRunResponse>::error(format!(
+                "Dry run failed: {}",
+                e
+            ))),
+        ),
+    }
+}
+
+/// POST /api/modules/{module_id}/assignments/{assignment_id}/submissions/{submission_id}/restore
+///
+/// Restore a soft-deleted submission. Only possible until the retention sweep purges it
+/// (`SOFT_DELETE_RETENTION_DAYS`). A restored graded attempt counts toward the student's
+/// attempt limit again.
+///
+/// **Access Control:** Lecturers or assistant lecturers only.
+///
+/// ### Responses
+///
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Submission 987 restored successfully"
+/// }
+/// ```
+///
+/// - `404 Not Found`
+/// ```json
+/// {
+///   "success": false,
+///   "message": "No deleted submission 987 for assignment 123"
+/// }
+/// ```
+pub async fn restore_submission(
+    State(app_state): State<AppState>,
+    Path((_module_id, assignment_id, submission_id)): Path<(i64, i64, i64)>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    match AssignmentSubmissionModel::restore(db, assignment_id, submission_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success(
+                (),
+                format!("Submission {} restored successfully", submission_id),
+            )),
+        ),
+        Err(sea_orm::DbErr::RecordNotFound(msg)) => {
+            (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(msg)))
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(e.to_string())),
+        ),
+    }
+}
@@ -1,4 +1,5 @@
 use db::models::assignment_task::TaskType;
+use globset::Glob;
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -8,6 +9,25 @@ pub struct TaskResponse {
     pub name: String,
     pub command: String,
     pub task_type: TaskType,
+    pub artifact_patterns: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
 }
+
+/// Checks that every artifact pattern is a relative glob, e.g. `*.png` or `plots/**`.
+pub fn validate_artifact_patterns(patterns: &[String]) -> Result<(), String> {
+    for pattern in patterns {
+        let trimmed = pattern.trim();
+        if trimmed.is_empty() {
+            return Err("Artifact patterns must be non-empty".to_string());
+        }
+        if trimmed.starts_with('/') || trimmed.split('/').any(|part| part == "..") {
+            return Err(format!(
+                "Artifact pattern '{}' must be relative to /output",
+                trimmed
+            ));
+        }
+        Glob::new(trimmed).map_err(|e| format!("Invalid artifact pattern '{}': {}", trimmed, e))?;
+    }
+    Ok(())
+}
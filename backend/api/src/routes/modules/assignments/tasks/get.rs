@@ -54,6 +54,7 @@ pub struct TaskDetailResponse {
     pub name: String,
     pub command: String,
     pub task_type: TaskType,
+    pub artifact_patterns: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
     pub has_overwrite_files: bool,
@@ -83,6 +84,7 @@ pub struct TaskDetailResponse {
 ///     "name": "Compilation",
 ///     "command": "java -cp . Main",
 ///     "task_type": "normal",
+///     "artifact_patterns": [],
 ///     "has_overwrite_files": false,
 ///     "created_at": "2024-01-01T00:00:00Z",
 ///     "updated_at": "2024-01-01T00:00:00Z",
@@ -231,7 +233,7 @@ pub async fn get_task_details(
 
     // Split memo into chunks per subsection
     let separator = match ExecutionConfig::get_execution_config(module_id, assignment_id) {
-        Ok(cfg) => cfg.marking.deliminator,
+        Ok(cfg) => cfg.marking.delimiter,
         Err(_) => "###".to_string(),
     };
     let mut memo_chunks: Vec<Option<String>> = if let Some(ref memo) = memo_content {
@@ -295,6 +297,7 @@ pub async fn get_task_details(
     };
 
     let resp = TaskDetailResponse {
+        artifact_patterns: task.artifact_patterns(),
         id: task.id,
         task_id: task.id,
         name: task.name.clone(),
@@ -339,6 +342,7 @@ pub async fn get_task_details(
 ///       "name": "Compilation",
 ///       "command": "java -cp . Main",
 ///       "task_type": "normal",
+///       "artifact_patterns": [],
 ///       "created_at": "2024-01-01T00:00:00Z",
 ///       "updated_at": "2024-01-01T00:00:00Z"
 ///     }
@@ -381,6 +385,7 @@ pub async fn list_tasks(
             let data = tasks
                 .into_iter()
                 .map(|task| TaskResponse {
+                    artifact_patterns: task.artifact_patterns(),
                     id: task.id,
                     task_number: task.task_number,
                     name: task.name,
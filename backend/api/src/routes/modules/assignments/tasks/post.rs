@@ -1,5 +1,7 @@
 use crate::response::ApiResponse;
-use crate::routes::modules::assignments::tasks::common::TaskResponse;
+use crate::routes::modules::assignments::tasks::common::{
+    TaskResponse, validate_artifact_patterns,
+};
 use axum::{
     Json,
     extract::{Path, State},
@@ -7,7 +9,7 @@ use axum::{
     response::IntoResponse,
 };
 use chrono::Utc;
-use db::models::assignment_task::{ActiveModel, Column, Entity, TaskType};
+use db::models::assignment_task::{ActiveModel, Column, Entity, Model, TaskType};
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter};
 use serde::Deserialize;
 use util::state::AppState;
@@ -23,6 +25,9 @@ pub struct CreateTaskRequest {
     /// Optional; defaults to "normal". One of: "normal" | "coverage" | "valgrind".
     #[serde(default)]
     task_type: Option<TaskType>,
+    /// Optional globs of files under `/output` to keep from each run, e.g. `["*.png"]`.
+    #[serde(default)]
+    artifact_patterns: Vec<String>,
 }
 
 /// POST /api/modules/{module_id}/assignments/{assignment_id}/tasks
@@ -51,6 +56,10 @@ pub struct CreateTaskRequest {
 /// - `"coverage"`: Code coverage task (special handling)
 /// - `"valgrind"`: Memory leak test (Valgrind)
 ///
+/// #### `artifact_patterns`
+/// Optional list of globs, relative to the container's `/output` directory. Files the task
+/// writes there that match are stored with the submission (e.g. `["*.png", "results/*.csv"]`).
+///
 /// ### Example Requests
 /// Normal:
 /// ```bash
@@ -87,6 +96,7 @@ pub struct CreateTaskRequest {
 ///     "name": "Memcheck",
 ///     "command": "valgrind --leak-check=full ./app",
 ///     "task_type": "valgrind",
+///     "artifact_patterns": [],
 ///     "created_at": "2025-05-29T00:00:00Z",
 ///     "updated_at": "2025-05-29T00:00:00Z"
 ///   }
@@ -96,6 +106,7 @@ pub struct CreateTaskRequest {
 /// ### Error Responses
 /// - 422: `"Invalid task_number, name, or command"`
 /// - 422: `"task_number must be unique"`
+/// - 422: `"Invalid artifact pattern '<pattern>': <reason>"`
 /// - 500: `"Failed to create task"`
 pub async fn create_task(
     State(app_state): State<AppState>,
@@ -118,6 +129,14 @@ pub async fn create_task(
             .into_response();
     }
 
+    if let Err(e) = validate_artifact_patterns(&payload.artifact_patterns) {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::<()>::error(e)),
+        )
+            .into_response();
+    }
+
     // Ensure task_number uniqueness within the assignment
     match Entity::find()
         .filter(Column::AssignmentId.eq(assignment_id))
@@ -151,6 +170,9 @@ pub async fn create_task(
         name: sea_orm::ActiveValue::Set(payload.name.clone()),
         command: sea_orm::ActiveValue::Set(payload.command.clone()),
         task_type: sea_orm::ActiveValue::Set(task_type),
+        artifact_patterns: sea_orm::ActiveValue::Set(Model::join_artifact_patterns(
+            &payload.artifact_patterns,
+        )),
         created_at: sea_orm::ActiveValue::Set(now),
         updated_at: sea_orm::ActiveValue::Set(now),
         ..Default::default()
@@ -159,6 +181,7 @@ pub async fn create_task(
     match new_task.insert(db).await {
         Ok(task) => {
             let response = TaskResponse {
+                artifact_patterns: task.artifact_patterns(),
                 id: task.id,
                 task_number: task.task_number,
                 name: task.name,
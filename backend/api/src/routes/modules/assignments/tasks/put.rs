@@ -3,7 +3,9 @@
 //! This module provides the endpoint handler for editing the command of a specific assignment task within a module. It validates the existence and relationships of the module, assignment, and task, and updates the task's command in the database. The endpoint returns detailed information about the updated task or appropriate error responses.
 
 use crate::response::ApiResponse;
-use crate::routes::modules::assignments::tasks::common::TaskResponse;
+use crate::routes::modules::assignments::tasks::common::{
+    TaskResponse, validate_artifact_patterns,
+};
 use axum::{
     extract::{Json, Path, State},
     http::StatusCode,
@@ -22,6 +24,8 @@ pub struct EditTaskRequest {
     command: Option<String>,
     /// Optional new task type: "normal" | "coverage" | "valgrind"
     task_type: Option<TaskType>,
+    /// Optional new artifact globs; an empty list stops keeping artifacts
+    artifact_patterns: Option<Vec<String>>,
 }
 
 /// PUT /api/modules/{module_id}/assignments/{assignment_id}/tasks/{task_id}
@@ -33,6 +37,7 @@ pub struct EditTaskRequest {
 /// - `name` (label shown to users)
 /// - `command` (shell command executed by the runner)
 /// - `task_type` (one of: "normal", "coverage", "valgrind")
+/// - `artifact_patterns` (globs of `/output` files kept from each run)
 ///
 /// > Note: `code_coverage: true` marks the task as a **code coverage task**. The evaluator
 /// > may apply coverage-specific handling (e.g., expecting coverage artifacts). It does **not**
@@ -53,6 +58,8 @@ pub struct EditTaskRequest {
 /// - `name` (string, optional): New display name for the task; if provided, must be non-empty
 /// - `command` (string, optional): New command to execute; if provided, must be non-empty
 /// - `code_coverage` (boolean, optional): Set whether this task is a **code coverage task**
+/// - `artifact_patterns` (string[], optional): Globs relative to `/output`, e.g. `["*.png"]`;
+///   `[]` clears them
 ///
 /// ### Example Requests
 /// Update only the command:
@@ -87,6 +94,7 @@ pub struct EditTaskRequest {
 ///     "name": "Coverage run",
 ///     "command": "cargo llvm-cov --no-report",
 ///     "task_type": "coverage",
+///     "artifact_patterns": [],
 ///     "created_at": "2024-01-01T00:00:00Z",
 ///     "updated_at": "2024-01-01T12:30:00Z"
 ///   }
@@ -197,11 +205,15 @@ pub async fn edit_task(
     let db = app_state.db();
 
     // Must provide something to change
-    if payload.name.is_none() && payload.command.is_none() && payload.task_type.is_none() {
+    if payload.name.is_none()
+        && payload.command.is_none()
+        && payload.task_type.is_none()
+        && payload.artifact_patterns.is_none()
+    {
         return (
             StatusCode::UNPROCESSABLE_ENTITY,
             Json(ApiResponse::<()>::error(
-                "At least one of 'name', 'command', or 'task_type' must be provided",
+                "At least one of 'name', 'command', 'task_type', or 'artifact_patterns' must be provided",
             )),
         )
             .into_response();
@@ -229,15 +241,37 @@ pub async fn edit_task(
             .into_response();
     }
 
-    let updated = match assignment_task::Model::edit(
-        db,
-        task_id,
-        payload.name.as_deref(),
-        payload.command.as_deref(),
-        payload.task_type,
-    )
-    .await
+    if let Err(e) = payload
+        .artifact_patterns
+        .as_deref()
+        .map_or(Ok(()), validate_artifact_patterns)
     {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::<()>::error(e)),
+        )
+            .into_response();
+    }
+
+    let result = async {
+        let task = assignment_task::Model::edit(
+            db,
+            task_id,
+            payload.name.as_deref(),
+            payload.command.as_deref(),
+            payload.task_type,
+        )
+        .await?;
+        match &payload.artifact_patterns {
+            Some(patterns) => {
+                assignment_task::Model::set_artifact_patterns(db, task.id, patterns).await
+            }
+            None => Ok(task),
+        }
+    }
+    .await;
+
+    let updated = match result {
         Ok(t) => t,
         Err(DbErr::RecordNotFound(_)) => {
             return (
@@ -256,6 +290,7 @@ pub async fn edit_task(
     };
 
     let resp = TaskResponse {
+        artifact_patterns: updated.artifact_patterns(),
         id: updated.id,
         task_number: updated.task_number,
         name: updated.name,
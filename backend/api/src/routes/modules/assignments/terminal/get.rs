@@ -0,0 +1,153 @@
+use crate::response::ApiResponse;
+use axum::{
+    extract::{
+        Path, WebSocketUpgrade,
+        ws::{Message, WebSocket},
+    },
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use code_runner::{code_manager_client::terminal_url, load_memo_base_files};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{
+        Message as UpstreamMessage, client::IntoClientRequest, http::HeaderValue,
+        http::header::AUTHORIZATION,
+    },
+};
+use util::{config, execution_config::ExecutionConfig};
+
+type Upstream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// GET /api/modules/{module_id}/assignments/{assignment_id}/terminal
+///
+/// Opens an interactive shell for debugging the assignment's setup. The connection is upgraded
+/// to a WebSocket and bridged to code_manager's `/terminal`, which starts a container with the
+/// assignment's config (limits, image, sandbox, environment) and its memo, makefile and main
+/// archives unpacked in `/code`. The session takes a container slot until it closes.
+///
+/// Browsers can pass the JWT as `?token=...`.
+///
+/// ### Path Parameters
+/// - `module_id` (i64): The ID of the module containing the assignment
+/// - `assignment_id` (i64): The ID of the assignment
+///
+/// ### Messages
+/// Everything the client sends (text or binary) is written to the shell's stdin; there is no
+/// TTY, so send whole lines (e.g. `"make\n"`). code_manager answers with JSON text:
+/// ```json
+/// { "type": "queued" }
+/// { "type": "ready" }
+/// { "type": "output", "stream": "stdout", "data": "g++ -o main main.cpp\n" }
+/// { "type": "exit", "code": 0 }
+/// { "type": "error", "message": "Session time limit of 1800s reached" }
+/// ```
+///
+/// ### Responses
+/// - `101 Switching Protocols`: The WebSocket is open
+/// - `403 Forbidden`: The user is not a lecturer of the module, or authenticated with an API
+///   token
+/// - `404 Not Found`: The assignment has no config
+/// - `422 Unprocessable Entity`: The memo, makefile or main archive is missing
+pub async fn open_terminal(
+    ws: WebSocketUpgrade,
+    Path((module_id, assignment_id)): Path<(i64, i64)>,
+) -> Response {
+    let config = match ExecutionConfig::get_execution_config(module_id, assignment_id) {
+        Ok(config) => config,
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(format!(
+                    "Assignment config not found: {}",
+                    e
+                ))),
+            )
+                .into_response();
+        }
+    };
+    let files = match load_memo_base_files(module_id, assignment_id).await {
+        Ok(files) => files,
+        Err(e) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::<()>::error(e)),
+            )
+                .into_response();
+        }
+    };
+    let start = json!({ "config": config, "files": files }).to_string();
+
+    ws.on_upgrade(move |socket| bridge(socket, start))
+}
+
+/// Forwards messages between the client and code_manager until either side closes.
+async fn bridge(mut socket: WebSocket, start: String) {
+    let upstream = match connect_upstream().await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            let error = json!({ "type": "error", "message": e }).to_string();
+            let _ = socket.send(Message::Text(error.into())).await;
+            let _ = socket.send(Message::Close(None)).await;
+            return;
+        }
+    };
+    let (mut up_tx, mut up_rx) = upstream.split();
+    if up_tx
+        .send(UpstreamMessage::Text(start.into()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+    let (mut down_tx, mut down_rx) = socket.split();
+
+    loop {
+        tokio::select! {
+            message = down_rx.next() => {
+                let forwarded = match message {
+                    Some(Ok(Message::Text(text))) => UpstreamMessage::Text(text.to_string().into()),
+                    Some(Ok(Message::Binary(data))) => UpstreamMessage::Binary(data),
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                if up_tx.send(forwarded).await.is_err() {
+                    break;
+                }
+            }
+            message = up_rx.next() => {
+                let forwarded = match message {
+                    Some(Ok(UpstreamMessage::Text(text))) => Message::Text(text.to_string().into()),
+                    Some(Ok(UpstreamMessage::Binary(data))) => Message::Binary(data),
+                    Some(Ok(UpstreamMessage::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                if down_tx.send(forwarded).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    let _ = up_tx.send(UpstreamMessage::Close(None)).await;
+    let _ = down_tx.send(Message::Close(None)).await;
+}
+
+/// Opens code_manager's `/terminal` with `CODE_MANAGER_TOKEN`, if one is configured.
+async fn connect_upstream() -> Result<Upstream, String> {
+    let mut request = terminal_url()
+        .into_client_request()
+        .map_err(|e| format!("Invalid code_manager address: {}", e))?;
+    if let Some(token) = config::code_manager_token() {
+        let value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .map_err(|_| "CODE_MANAGER_TOKEN is not a valid header value".to_string())?;
+        request.headers_mut().insert(AUTHORIZATION, value);
+    }
+    let (upstream, _) = connect_async(request)
+        .await
+        .map_err(|e| format!("Failed to connect to code_manager: {}", e))?;
+    Ok(upstream)
+}
@@ -0,0 +1,29 @@
+//! Terminal Routes Module
+//!
+//! Lets lecturers open a shell in a sandboxed runner container loaded with the assignment's
+//! files, to debug build problems without access to the server.
+
+use crate::auth::guards::{allow_lecturer, deny_api_tokens};
+use axum::{
+    Router,
+    middleware::{from_fn, from_fn_with_state},
+    routing::get,
+};
+use get::open_terminal;
+use util::state::AppState;
+
+pub mod get;
+
+/// Registers the terminal endpoint.
+///
+/// - `GET /`: Upgrade to a WebSocket connected to a shell in a new container. Access is
+///   restricted to lecturers of the module, signed in: a shell is never opened for an API token,
+///   whatever its scopes.
+pub fn terminal_routes(app_state: AppState) -> Router<AppState> {
+    Router::new().route(
+        "/",
+        get(open_terminal)
+            .route_layer(from_fn_with_state(app_state.clone(), allow_lecturer))
+            .route_layer(from_fn(deny_api_tokens)),
+    )
+}
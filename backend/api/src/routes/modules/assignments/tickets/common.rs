@@ -4,8 +4,10 @@
 //!
 //! It includes:
 //! - `is_valid`: checks whether a user is authorized to access or modify a ticket.
+//! - `staff_role`: the user's highest staff role on a module, if any.
 //! - `TicketResponse`: a serializable response type for ticket API endpoints.
 
+use chrono::Utc;
 use db::models::user::Model as UserModel;
 use db::models::{
     UserModuleRole as Entity,
@@ -52,6 +54,23 @@ pub async fn is_valid(
         .is_some()
 }
 
+/// Returns the highest staff role (Lecturer, then AssistantLecturer, then Tutor) `user_id`
+/// holds on `module_id`, or `None` if they are not staff there.
+pub async fn staff_role(user_id: i64, module_id: i64, db: &DatabaseConnection) -> Option<Role> {
+    let roles: Vec<Role> = Entity::find()
+        .filter(Column::UserId.eq(user_id))
+        .filter(Column::ModuleId.eq(module_id))
+        .all(db)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|r| r.role)
+        .collect();
+    [Role::Lecturer, Role::AssistantLecturer, Role::Tutor]
+        .into_iter()
+        .find(|role| roles.contains(role))
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TicketResponse {
     pub id: i64,
@@ -60,12 +79,20 @@ pub struct TicketResponse {
     pub title: String,
     pub description: String,
     pub status: String,
+    pub assignee_id: Option<i64>,
+    pub priority: String,
+    pub sla_due_at: Option<String>,
+    pub first_response_at: Option<String>,
+    pub escalated_at: Option<String>,
+    /// Still open and unanswered past `sla_due_at`.
+    pub overdue: bool,
     pub created_at: String,
     pub updated_at: String,
 }
 
 impl From<TicketModel> for TicketResponse {
     fn from(ticket: TicketModel) -> Self {
+        let overdue = ticket.is_overdue(Utc::now());
         Self {
             id: ticket.id,
             assignment_id: ticket.assignment_id,
@@ -73,6 +100,12 @@ impl From<TicketModel> for TicketResponse {
             title: ticket.title,
             description: ticket.description,
             status: ticket.status.to_string(),
+            assignee_id: ticket.assignee_id,
+            priority: ticket.priority.to_string(),
+            sla_due_at: ticket.sla_due_at.map(|t| t.to_rfc3339()),
+            first_response_at: ticket.first_response_at.map(|t| t.to_rfc3339()),
+            escalated_at: ticket.escalated_at.map(|t| t.to_rfc3339()),
+            overdue,
             created_at: ticket.created_at.to_rfc3339(),
             updated_at: ticket.updated_at.to_rfc3339(),
         }
@@ -17,7 +17,8 @@ use util::state::AppState;
 
 use crate::routes::modules::assignments::tickets::common::is_valid;
 
-/// Deletes an existing ticket.
+/// Soft deletes an existing ticket. It can be restored with `POST .../tickets/{ticket_id}/restore`
+/// until the retention sweep purges it.
 ///
 /// **Endpoint:** `DELETE /modules/{module_id}/assignments/{assignment_id}/tickets/{ticket_id}`  
 /// **Permissions:** Only the ticket owner can delete their ticket.
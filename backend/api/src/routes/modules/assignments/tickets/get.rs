@@ -26,6 +26,7 @@ use db::models::{
     user_module_role::{self, Role},
 };
 use migration::Expr;
+use db::soft_delete::SoftDelete;
 use sea_orm::{
     ColumnTrait, Condition, DatabaseConnection, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
     QueryOrder, QuerySelect, RelationTrait,
@@ -298,7 +299,7 @@ pub async fn get_tickets(
         }
     }
 
-    let mut query = TicketEntity::find().filter(condition);
+    let mut query = TicketEntity::find_active().filter(condition);
 
     if let Some(sort_param) = &params.sort {
         for sort in sort_param.split(',') {
@@ -3,7 +3,8 @@
 //! Provides the `/tickets` route group with full CRUD and nested message functionality.
 //!
 //! Routes include:
-//! - Create, open, close, delete, and get tickets
+//! - Create, open, close, delete, restore, and get tickets
+//! - Assign tickets to staff and set their priority (SLA deadline)
 //! - List all tickets
 //! - Nested routes for ticket messages
 //!
@@ -22,8 +23,8 @@ pub mod put;
 pub mod ticket_messages;
 use delete::delete_ticket;
 use get::{get_ticket, get_tickets};
-use post::create_ticket;
-use put::{close_ticket, open_ticket};
+use post::{create_ticket, restore_ticket};
+use put::{assign_ticket, close_ticket, open_ticket, set_ticket_priority};
 use ticket_messages::ticket_message_routes;
 
 /// Builds and returns the `/tickets` route group for a given ticket context.
@@ -32,7 +33,10 @@ use ticket_messages::ticket_message_routes;
 /// - `POST   /tickets`                  → Create a new ticket
 /// - `PUT    /tickets/{ticket_id}/open` → Reopen a closed ticket
 /// - `PUT    /tickets/{ticket_id}/close`→ Close an open ticket
+/// - `PUT    /tickets/{ticket_id}/assign` → Assign the ticket to a staff member
+/// - `PUT    /tickets/{ticket_id}/priority` → Change the ticket's priority and SLA deadline
 /// - `DELETE /tickets/{ticket_id}`      → Delete a ticket
+/// - `POST   /tickets/{ticket_id}/restore` → Restore a deleted ticket
 /// - `GET    /tickets/{ticket_id}`      → Get details of a ticket
 /// - `GET    /tickets`                  → List all tickets
 ///
@@ -43,7 +47,10 @@ pub fn ticket_routes(app_state: AppState) -> Router<AppState> {
         .route("/", post(create_ticket))
         .route("/{ticket_id}/close", put(close_ticket))
         .route("/{ticket_id}/open", put(open_ticket))
+        .route("/{ticket_id}/assign", put(assign_ticket))
+        .route("/{ticket_id}/priority", put(set_ticket_priority))
         .route("/{ticket_id}", delete(delete_ticket))
+        .route("/{ticket_id}/restore", post(restore_ticket))
         .route("/{ticket_id}", get(get_ticket))
         .route("/", get(get_tickets))
         .nest(
@@ -1,6 +1,6 @@
-//! Ticket creation handler.
+//! Ticket creation and restore handlers.
 //!
-//! Provides an endpoint to create a new ticket for an assignment.
+//! Provides endpoints to create a new ticket for an assignment and to bring back a deleted one.
 //!
 //! Only authenticated users can create tickets, and each ticket is linked
 //! to the assignment and the user who created it.
@@ -12,12 +12,14 @@ use axum::{
     response::IntoResponse,
 };
 use db::models::tickets::Model as TicketModel;
+use sea_orm::DbErr;
 use serde::Deserialize;
 use util::state::AppState;
 
 use crate::{
-    auth::AuthUser, response::ApiResponse,
-    routes::modules::assignments::tickets::common::TicketResponse,
+    auth::AuthUser,
+    response::ApiResponse,
+    routes::modules::assignments::tickets::common::{TicketResponse, is_valid},
 };
 
 /// Request payload for creating a ticket.
@@ -98,3 +100,49 @@ pub async fn create_ticket(
         ),
     }
 }
+
+/// Restores a soft-deleted ticket.
+///
+/// **Endpoint:** `POST /modules/{module_id}/assignments/{assignment_id}/tickets/{ticket_id}/restore`
+/// **Permissions:** The ticket owner, module staff, or an admin.
+///
+/// ### Path parameters
+/// - `module_id`       → ID of the module (used for permission check)
+/// - `assignment_id`   → ID of the assignment the ticket belongs to
+/// - `ticket_id`       → ID of the ticket to be restored
+///
+/// ### Responses
+/// - `200 OK` → Ticket restored successfully
+/// - `403 Forbidden` → User does not have permission to restore this ticket
+/// - `404 Not Found` → No deleted ticket with this ID in the assignment
+pub async fn restore_ticket(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, ticket_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if !is_valid(claims.sub, ticket_id, module_id, claims.admin, db).await {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error("Forbidden")),
+        );
+    }
+
+    match TicketModel::restore(db, assignment_id, ticket_id).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success(
+                (),
+                "Ticket restored successfully",
+            )),
+        ),
+        Err(DbErr::RecordNotFound(msg)) => {
+            (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(msg)))
+        }
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to restore ticket")),
+        ),
+    }
+}
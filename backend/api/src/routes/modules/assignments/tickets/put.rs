@@ -1,13 +1,16 @@
-//! Ticket status handlers.
+//! Ticket status, assignment and priority handlers.
 //!
-//! Provides endpoints to open or close a ticket in a module.
+//! Provides endpoints to open or close a ticket in a module, assign it to a staff member, and
+//! change its priority (which moves its SLA deadline).
 //!
-//! Access control is enforced via the `is_valid` function, which ensures the
-//! user has permission to modify the ticket. Only the ticket owner or
-//! authorized users can perform these actions.
+//! Access control for opening and closing is enforced via the `is_valid` function, which ensures
+//! the user has permission to modify the ticket. Only the ticket owner or authorized users can
+//! perform these actions. Assignment and priority are staff-only.
 
 use crate::{
-    auth::AuthUser, response::ApiResponse, routes::modules::assignments::tickets::common::is_valid,
+    auth::AuthUser,
+    response::ApiResponse,
+    routes::modules::assignments::tickets::common::{TicketResponse, is_valid, staff_role},
 };
 use axum::{
     Extension,
@@ -15,10 +18,10 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Json},
 };
-use db::models::tickets::Model as TicketModel;
+use db::models::tickets::{Model as TicketModel, TicketPriority};
 use db::models::user_module_role::{self, Role as ModuleRole};
 use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use util::state::AppState;
 /// Response payload for ticket status updates.
 #[derive(Serialize)]
@@ -199,3 +202,185 @@ pub async fn close_ticket(
             .into_response(),
     }
 }
+
+/// Request body for assigning a ticket.
+#[derive(Debug, Deserialize)]
+pub struct AssignTicketReq {
+    /// Staff member to assign the ticket to; `null` unassigns it.
+    pub assignee_id: Option<i64>,
+}
+
+/// Assigns a ticket to a staff member, or unassigns it.
+///
+/// **Endpoint:** `PUT /modules/{module_id}/assignments/{assignment_id}/tickets/{ticket_id}/assign`
+/// **Permissions:** Lecturers, assistant lecturers and admins may assign anyone on the module's
+/// staff; tutors may only take a ticket themselves or give up their own.
+///
+/// ### Request Body
+/// ```json
+/// { "assignee_id": 42 }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` → The updated ticket
+/// - `400 Bad Request` → The assignee is not a lecturer, assistant lecturer or tutor on the module
+/// - `403 Forbidden` → Caller is not staff, or is a tutor assigning someone else
+/// - `404 Not Found` → Ticket not found in this assignment
+/// - `500 Internal Server Error` → Failed to assign the ticket
+pub async fn assign_ticket(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, ticket_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<AssignTicketReq>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let caller_role = staff_role(claims.sub, module_id, db).await;
+    if !claims.admin && caller_role.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error("Only staff may assign tickets")),
+        )
+            .into_response();
+    }
+
+    let ticket = match TicketModel::get_by_id(db, ticket_id).await {
+        Ok(Some(t)) if t.assignment_id == assignment_id => t,
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Ticket not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load ticket")),
+            )
+                .into_response();
+        }
+    };
+
+    if !claims.admin && caller_role == Some(ModuleRole::Tutor) {
+        let own = match req.assignee_id {
+            Some(id) => id == claims.sub,
+            None => ticket.assignee_id == Some(claims.sub),
+        };
+        if !own {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ApiResponse::<()>::error(
+                    "Tutors may only assign tickets to themselves",
+                )),
+            )
+                .into_response();
+        }
+    }
+
+    if let Some(assignee_id) = req.assignee_id
+        && staff_role(assignee_id, module_id, db).await.is_none()
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Tickets can only be assigned to staff on this module",
+            )),
+        )
+            .into_response();
+    }
+
+    match TicketModel::assign(db, ticket_id, req.assignee_id).await {
+        Ok(ticket) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                TicketResponse::from(ticket),
+                "Ticket assignment updated",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to assign ticket")),
+        )
+            .into_response(),
+    }
+}
+
+/// Request body for changing a ticket's priority.
+#[derive(Debug, Deserialize)]
+pub struct SetPriorityReq {
+    /// `low`, `normal`, `high` or `urgent`.
+    pub priority: TicketPriority,
+}
+
+/// Changes a ticket's priority and moves its SLA deadline to match.
+///
+/// **Endpoint:** `PUT /modules/{module_id}/assignments/{assignment_id}/tickets/{ticket_id}/priority`
+/// **Permissions:** Module staff (Lecturer, AssistantLecturer, Tutor) or admin.
+///
+/// The SLA deadline is counted from when the ticket was opened: 96h for `low`, 48h for `normal`,
+/// 24h for `high` and 8h for `urgent`.
+///
+/// ### Request Body
+/// ```json
+/// { "priority": "high" }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` → The updated ticket
+/// - `403 Forbidden` → Caller is not staff
+/// - `404 Not Found` → Ticket not found in this assignment
+/// - `500 Internal Server Error` → Failed to update the ticket
+pub async fn set_ticket_priority(
+    State(app_state): State<AppState>,
+    Path((module_id, assignment_id, ticket_id)): Path<(i64, i64, i64)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<SetPriorityReq>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    if !claims.admin && staff_role(claims.sub, module_id, db).await.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::<()>::error(
+                "Only staff may change ticket priority",
+            )),
+        )
+            .into_response();
+    }
+
+    match TicketModel::get_by_id(db, ticket_id).await {
+        Ok(Some(t)) if t.assignment_id == assignment_id => {}
+        Ok(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Ticket not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load ticket")),
+            )
+                .into_response();
+        }
+    }
+
+    match TicketModel::set_priority(db, ticket_id, req.priority).await {
+        Ok(ticket) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                TicketResponse::from(ticket),
+                "Ticket priority updated",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to update ticket priority")),
+        )
+            .into_response(),
+    }
+}
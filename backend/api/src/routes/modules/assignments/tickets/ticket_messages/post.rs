@@ -2,7 +2,9 @@
 //!
 //! Provides an endpoint to create a new message for a ticket in a module.
 //!
-//! Only users authorized to view the ticket (author or staff) can create messages.
+//! Only users authorized to view the ticket (author or staff) can create messages. Replies from
+//! anyone other than the author notify the ticket's author and count as the ticket's first
+//! response for SLA and escalation purposes.
 
 use axum::{
     Extension, Json,
@@ -10,6 +12,7 @@ use axum::{
     http::StatusCode,
     response::IntoResponse,
 };
+use db::models::notification::NotificationKind;
 use db::models::tickets::TicketStatus;
 use db::models::{
     ticket_messages::Model as TicketMessageModel,
@@ -26,6 +29,7 @@ use crate::{
         common::is_valid,
         ticket_messages::common::{MessageResponse, UserResponse},
     },
+    services::notifications::{self, NewNotification},
 };
 
 /// POST /api/modules/{module_id}/assignments/{assignment_id}/tickets/{ticket_id}/messages
@@ -219,6 +223,30 @@ pub async fn create_message(
     };
     t_emit::message_created(&ws, payload).await;
 
+    if user_id != ticket.user_id {
+        // A reply from anyone but the author stops the ticket from being escalated
+        if let Err(e) = db::models::tickets::Model::record_first_response(db, ticket.id).await {
+            tracing::warn!(
+                "Failed to record first response on ticket {}: {}",
+                ticket.id,
+                e
+            );
+        }
+        notifications::spawn_notify(
+            &app_state,
+            vec![ticket.user_id],
+            NewNotification {
+                kind: NotificationKind::TicketReplied,
+                title: format!("New reply on ticket: {}", ticket.title),
+                body: format!("{}: {}", user.username, message.content),
+                link: Some(format!(
+                    "/modules/{}/assignments/{}/tickets/{}",
+                    module_id, ticket.assignment_id, ticket.id
+                )),
+            },
+        );
+    }
+
     (
         StatusCode::OK,
         Json(ApiResponse::success(
@@ -1,5 +1,9 @@
+use db::models::{attendance_record::AttendanceSummary, attendance_session::GeoPoint};
 use serde::{Deserialize, Serialize};
 
+/// Smallest and largest geofence radius a session can have, in metres.
+pub const GEOFENCE_RADIUS_RANGE_M: std::ops::RangeInclusive<i32> = 10..=5000;
+
 #[derive(Debug, Serialize)]
 pub struct AttendanceSessionResponse {
     pub id: i64,
@@ -11,6 +15,9 @@ pub struct AttendanceSessionResponse {
     pub restrict_by_ip: bool,
     pub allowed_ip_cidr: Option<String>,
     pub created_from_ip: Option<String>,
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
+    pub geo_radius_m: Option<i32>,
     pub created_at: String,
     pub updated_at: String,
     pub attended_count: i64, // students who marked for this session
@@ -29,6 +36,9 @@ impl From<db::models::attendance_session::Model> for AttendanceSessionResponse {
             restrict_by_ip: m.restrict_by_ip,
             allowed_ip_cidr: m.allowed_ip_cidr,
             created_from_ip: m.created_from_ip,
+            geo_latitude: m.geo_latitude,
+            geo_longitude: m.geo_longitude,
+            geo_radius_m: m.geo_radius_m,
             created_at: m.created_at.to_rfc3339(),
             updated_at: m.updated_at.to_rfc3339(),
             attended_count: 0,
@@ -77,6 +87,10 @@ pub struct CreateSessionReq {
     pub allowed_ip_cidr: Option<String>,
     pub pin_to_creator_ip: Option<bool>,
     pub allow_manual_entry: Option<bool>,
+    /// Geofence venue and radius; all three or none.
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
+    pub geo_radius_m: Option<i32>,
 }
 
 #[derive(Deserialize)]
@@ -89,4 +103,96 @@ pub struct EditSessionReq {
     pub allowed_ip_cidr: Option<String>,
     pub created_from_ip: Option<String>,
     pub allow_manual_entry: Option<bool>,
+    /// Replaces the geofence; all three or none.
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
+    pub geo_radius_m: Option<i32>,
+    /// Removes the geofence.
+    pub clear_geofence: Option<bool>,
+}
+
+/// Validates the geofence fields of a create/edit request: `Ok(None)` when none are given.
+pub fn parse_geofence(
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    radius_m: Option<i32>,
+) -> Result<Option<(GeoPoint, i32)>, &'static str> {
+    match (latitude, longitude, radius_m) {
+        (None, None, None) => Ok(None),
+        (Some(latitude), Some(longitude), Some(radius)) => {
+            let venue = GeoPoint {
+                latitude,
+                longitude,
+            };
+            if !venue.is_valid() {
+                return Err("Invalid geofence coordinates");
+            }
+            if !GEOFENCE_RADIUS_RANGE_M.contains(&radius) {
+                return Err("Geofence radius must be between 10 and 5000 metres");
+            }
+            Ok(Some((venue, radius)))
+        }
+        _ => Err("geo_latitude, geo_longitude and geo_radius_m must be given together"),
+    }
+}
+
+/// What the projector shows: the current code, when it rotates, and the link its QR encodes.
+#[derive(Debug, Serialize)]
+pub struct ProjectorCodeResponse {
+    pub code: String,
+    pub rotation_seconds: i32,
+    pub valid_until: String,
+    pub seconds_remaining: i64,
+    /// Frontend link that marks attendance with this code when scanned.
+    pub mark_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SummaryQuery {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+    /// Username filter
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StudentAttendanceSummary {
+    pub user_id: i64,
+    pub username: String,
+    #[serde(flatten)]
+    pub summary: AttendanceSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SummaryListResponse {
+    pub students: Vec<StudentAttendanceSummary>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+}
+
+/// A CSV row that could not be imported.
+#[derive(Debug, Serialize)]
+pub struct ImportRowError {
+    /// 1-based line number in the uploaded file.
+    pub line: usize,
+    pub username: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub imported: usize,
+    /// Rows for students who already had a record for the session.
+    pub skipped: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AtRiskQuery {
+    /// Students attending less than this percentage of sessions are flagged (default 75).
+    pub min_attendance: Option<f64>,
+    /// Students averaging less than this percentage on past-due assignments are flagged
+    /// (default 50).
+    pub min_mark: Option<f64>,
 }
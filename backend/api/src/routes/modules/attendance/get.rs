@@ -1,29 +1,39 @@
 // api/src/routes/modules/attendance/get.rs
 
 //! Attendance module: read-only routes (list sessions, get session,
-//! fetch current code, list records, export records as CSV).
+//! fetch current code or the projector view of it, list records, export records as CSV,
+//! attendance summaries and the at-risk-student report).
 
 use axum::{
     Extension, Json,
     extract::{Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
 };
 use chrono::{SecondsFormat, Utc};
 use sea_orm::{ColumnTrait, Condition, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
-use util::state::AppState;
+use util::{config, csv, state::AppState};
 
 use crate::{auth::AuthUser, response::ApiResponse};
 
-use super::common::{AttendanceSessionResponse, ListQuery, ListResponse};
+use super::common::{
+    AtRiskQuery, AttendanceSessionResponse, ListQuery, ListResponse, ProjectorCodeResponse,
+    StudentAttendanceSummary, SummaryListResponse, SummaryQuery,
+};
+use db::gradebook::{self, GradebookColumn};
 use db::models::attendance_session::{
     Column as SessionCol, Entity as SessionEntity, Model as Session,
 };
 use db::models::{
     attendance_record::Column as RecordCol,
     attendance_record::Entity as RecordEntity,
+    attendance_record::{AttendanceSummary, Model as RecordModel},
     user::{Column as UserCol, Entity as UserEntity},
 };
 
+/// Students per batch when building the at-risk report.
+const AT_RISK_BATCH: u64 = 200;
+
 /// GET `/api/modules/{module_id}/attendance/sessions`
 ///
 /// List attendance sessions for a module.
@@ -202,6 +212,83 @@ pub async fn get_session_code(
     )
 }
 
+/// GET `/api/modules/{module_id}/attendance/sessions/{session_id}/projector`
+///
+/// Everything the projector view needs to show the **current rotating code**: the code, when
+/// it rotates out, and the link its QR code should encode (the frontend's
+/// `/attendance/mark?m=&s=&c=` page). Poll again at `valid_until`.
+///
+/// **Auth**: **Lecturer or AssistantLecturer**.
+///
+/// **Notes**:
+/// - Returns `400` if the session is not active.
+///
+/// **200 OK**
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "code": "042917",
+///     "rotation_seconds": 30,
+///     "valid_until": "2025-09-08T10:00:30Z",
+///     "seconds_remaining": 16,
+///     "mark_url": "https://fitchfork.example/attendance/mark?m=1&s=7&c=042917"
+///   },
+///   "message": "Current code"
+/// }
+/// ```
+pub async fn get_session_projector(
+    State(state): State<AppState>,
+    Path((module_id, session_id)): Path<(i64, i64)>,
+) -> (StatusCode, Json<ApiResponse<ProjectorCodeResponse>>) {
+    let db = state.db();
+
+    let Some(sess) = SessionEntity::find()
+        .filter(SessionCol::Id.eq(session_id))
+        .filter(SessionCol::ModuleId.eq(module_id))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error("Attendance session not found")),
+        );
+    };
+
+    if !sess.active {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("Session is not currently active")),
+        );
+    }
+
+    let now = Utc::now();
+    let code = sess.current_code(now);
+    let valid_until = sess.code_valid_until(now);
+    let mark_url = format!(
+        "{}/attendance/mark?m={}&s={}&c={}",
+        config::frontend_url().trim_end_matches('/'),
+        module_id,
+        session_id,
+        code
+    );
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            ProjectorCodeResponse {
+                code,
+                rotation_seconds: sess.rotation_seconds,
+                valid_until: valid_until.to_rfc3339_opts(SecondsFormat::Secs, true),
+                seconds_remaining: (valid_until - now).num_seconds().max(0),
+                mark_url,
+            },
+            "Current code",
+        )),
+    )
+}
+
 /// A single attendance record (DTO) for API responses.
 #[derive(serde::Serialize)]
 pub struct AttendanceRecordDto {
@@ -448,3 +535,234 @@ pub async fn export_session_records_csv(
 
     (StatusCode::OK, (headers, csv))
 }
+
+/// GET `/api/modules/{module_id}/attendance/summary`
+///
+/// A page of the module's students (by username) with their attendance across every session
+/// of the module: sessions attended out of the total, the percentage, the current streak
+/// (sessions attended in a row up to the latest) and the longest streak.
+///
+/// **Auth**: **Lecturer or AssistantLecturer**.
+///
+/// **Query**:
+/// - `query` *(optional)*: username filter
+/// - `page` *(default 1)*
+/// - `per_page` *(default 20, max 100)*
+///
+/// **Response**: `SummaryListResponse`
+/// ```json
+/// {
+///   "students": [
+///     { "user_id": 7, "username": "u12345678", "sessions_total": 10, "sessions_attended": 8,
+///       "percentage": 80.0, "current_streak": 3, "longest_streak": 5 }
+///   ],
+///   "page": 1, "per_page": 20, "total": 1
+/// }
+/// ```
+pub async fn list_attendance_summaries(
+    State(state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Query(params): Query<SummaryQuery>,
+) -> (StatusCode, Json<ApiResponse<SummaryListResponse>>) {
+    let db = state.db();
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+
+    let (students, total) =
+        match gradebook::students(db, module_id, params.query.as_deref(), page, per_page).await {
+            Ok(found) => found,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::error("Failed to load students")),
+                );
+            }
+        };
+    let ids: Vec<i64> = students.iter().map(|u| u.id).collect();
+    let summaries = match RecordModel::summaries_for(db, module_id, &ids).await {
+        Ok(s) => s,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("Failed to summarise attendance")),
+            );
+        }
+    };
+
+    let students = students
+        .into_iter()
+        .map(|u| StudentAttendanceSummary {
+            summary: summaries.get(&u.id).copied().unwrap_or_default(),
+            user_id: u.id,
+            username: u.username,
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            SummaryListResponse {
+                students,
+                page,
+                per_page,
+                total,
+            },
+            "Attendance summaries retrieved",
+        )),
+    )
+}
+
+/// GET `/api/modules/{module_id}/attendance/summary/me`
+///
+/// The caller's own attendance across every session of the module, as in
+/// `GET /attendance/summary`.
+///
+/// **Auth**: Any user assigned to the module.
+pub async fn get_my_attendance_summary(
+    State(state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> (StatusCode, Json<ApiResponse<AttendanceSummary>>) {
+    match RecordModel::summaries_for(state.db(), module_id, &[claims.sub]).await {
+        Ok(summaries) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                summaries.get(&claims.sub).copied().unwrap_or_default(),
+                "Attendance summary retrieved",
+            )),
+        ),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error("Failed to summarise attendance")),
+        ),
+    }
+}
+
+/// GET `/api/modules/{module_id}/attendance/at-risk/export`
+///
+/// At-risk-student report: every student of the module with their attendance summary next to
+/// their average mark on the assignments already past their due date (the mark that counts
+/// under each assignment's grading policy, 0 where nothing was submitted), flagged when either
+/// falls below its threshold.
+///
+/// **Auth**: **Lecturer or AssistantLecturer**.
+///
+/// **Query**:
+/// - `min_attendance` *(default 75)*: attendance percentage below which a student is at risk
+/// - `min_mark` *(default 50)*: average mark below which a student is at risk
+///
+/// ### Responses
+/// - `200 OK` — `text/csv` attachment named `at_risk_module_{module_id}.csv`
+/// ```csv
+/// username,sessions_attended,sessions_total,attendance_percentage,current_streak,longest_streak,assignments_due,assignments_submitted,average_mark,at_risk
+/// u12345678,4,10,40.00,0,2,3,2,45.50,true
+/// ```
+/// - `400 Bad Request` — A threshold outside 0–100
+/// - `500 Internal Server Error` — Database error
+pub async fn export_at_risk_csv(
+    State(state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Query(params): Query<AtRiskQuery>,
+) -> Response {
+    let db = state.db();
+    let min_attendance = params.min_attendance.unwrap_or(75.0);
+    let min_mark = params.min_mark.unwrap_or(50.0);
+    if ![min_attendance, min_mark]
+        .iter()
+        .all(|t| (0.0..=100.0).contains(t))
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Thresholds must be between 0 and 100",
+            )),
+        )
+            .into_response();
+    }
+    let failed = |msg: &str| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(msg)),
+        )
+            .into_response()
+    };
+
+    let now = Utc::now();
+    let columns: Vec<GradebookColumn> = match gradebook::columns(db, module_id).await {
+        Ok(columns) => columns
+            .into_iter()
+            .filter(|c| c.assignment.due_date <= now)
+            .collect(),
+        Err(_) => return failed("Failed to load assignments"),
+    };
+
+    let mut report = String::from(
+        "username,sessions_attended,sessions_total,attendance_percentage,current_streak,\
+         longest_streak,assignments_due,assignments_submitted,average_mark,at_risk\n",
+    );
+    let mut page = 1;
+    loop {
+        let students = match gradebook::students(db, module_id, None, page, AT_RISK_BATCH).await {
+            Ok((students, _)) => students,
+            Err(_) => return failed("Failed to load students"),
+        };
+        if students.is_empty() {
+            break;
+        }
+        let ids: Vec<i64> = students.iter().map(|u| u.id).collect();
+        let summaries = match RecordModel::summaries_for(db, module_id, &ids).await {
+            Ok(s) => s,
+            Err(_) => return failed("Failed to summarise attendance"),
+        };
+        let rows = match gradebook::rows(db, module_id, &columns, students).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("export_at_risk_csv: {e}");
+                return failed("Failed to compute grades");
+            }
+        };
+
+        for row in rows {
+            let summary = summaries.get(&row.user.id).copied().unwrap_or_default();
+            let submitted = row.cells.iter().flatten().count();
+            let average = if columns.is_empty() {
+                None
+            } else {
+                let total: f64 = row.cells.iter().flatten().map(|c| c.mark).sum();
+                Some(total / columns.len() as f64)
+            };
+            // With no sessions (or nothing due) yet there is nothing to be behind on
+            let at_risk = (summary.sessions_total > 0 && summary.percentage < min_attendance)
+                || average.is_some_and(|a| a < min_mark);
+
+            report.push_str(&format!(
+                "{},{},{},{:.2},{},{},{},{},{},{}\n",
+                csv::escape(&row.user.username),
+                summary.sessions_attended,
+                summary.sessions_total,
+                summary.percentage,
+                summary.current_streak,
+                summary.longest_streak,
+                columns.len(),
+                submitted,
+                average.map(|a| format!("{a:.2}")).unwrap_or_default(),
+                at_risk
+            ));
+        }
+        page += 1;
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("text/csv; charset=utf-8"),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        HeaderValue::from_str(&format!(
+            "attachment; filename=\"at_risk_module_{module_id}.csv\""
+        ))
+        .unwrap_or(HeaderValue::from_static("attachment")),
+    );
+    (StatusCode::OK, headers, report).into_response()
+}
@@ -13,9 +13,11 @@ mod put;
 
 pub use delete::delete_session;
 pub use get::{
-    export_session_records_csv, get_session, get_session_code, list_session_records, list_sessions,
+    export_at_risk_csv, export_session_records_csv, get_my_attendance_summary, get_session,
+    get_session_code, get_session_projector, list_attendance_summaries, list_session_records,
+    list_sessions,
 };
-pub use post::{create_session, mark_attendance};
+pub use post::{create_session, import_attendance_csv, mark_attendance};
 pub use put::edit_session;
 
 use crate::{
@@ -67,6 +69,13 @@ pub fn attendance_routes(app_state: AppState) -> Router<AppState> {
                 allow_assistant_lecturer,
             )),
         )
+        .route(
+            "/sessions/{session_id}/projector",
+            get(get_session_projector).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
         .route("/sessions/{session_id}/mark", post(mark_attendance))
         .route(
             "/sessions/{session_id}/mark/by-username",
@@ -89,5 +98,27 @@ pub fn attendance_routes(app_state: AppState) -> Router<AppState> {
                 allow_assistant_lecturer,
             )),
         )
+        .route(
+            "/sessions/{session_id}/records/import",
+            post(import_attendance_csv).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route(
+            "/summary",
+            get(list_attendance_summaries).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .route("/summary/me", get(get_my_attendance_summary))
+        .route(
+            "/at-risk/export",
+            get(export_at_risk_csv).route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
         .with_state(app_state)
 }
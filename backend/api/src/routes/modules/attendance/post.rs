@@ -3,15 +3,17 @@ use axum::{
     extract::{ConnectInfo, Path, State},
     http::StatusCode,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::net::SocketAddr;
 
 use crate::{auth::AuthUser, response::ApiResponse};
-use util::state::AppState;
+use util::{csv, state::AppState};
 
-use super::common::{AttendanceSessionResponse, CreateSessionReq};
-use db::models::attendance_session as Sess;
+use super::common::{
+    AttendanceSessionResponse, CreateSessionReq, ImportResponse, ImportRowError, parse_geofence,
+};
+use db::models::attendance_session::{self as Sess, ReportedLocation};
 use sea_orm::PaginatorTrait;
 
 use crate::ws::attendance::emit;
@@ -33,6 +35,11 @@ pub async fn create_session(
 ) -> (StatusCode, Json<ApiResponse<AttendanceSessionResponse>>) {
     let db = state.db();
 
+    let geofence = match parse_geofence(body.geo_latitude, body.geo_longitude, body.geo_radius_m) {
+        Ok(g) => g,
+        Err(msg) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg))),
+    };
+
     let active = body.active.unwrap_or(false);
     let rotation = body.rotation_seconds.unwrap_or(30).clamp(5, 300);
     let restrict = body.restrict_by_ip.unwrap_or(false);
@@ -45,7 +52,7 @@ pub async fn create_session(
     };
 
     // NOTE: Model::create no longer takes code_length/allow_manual_entry
-    match Sess::Model::create(
+    let created = match Sess::Model::create(
         db,
         module_id,
         claims.sub,
@@ -59,6 +66,11 @@ pub async fn create_session(
     )
     .await
     {
+        Ok(row) if geofence.is_some() => Sess::Model::set_geofence(db, row.id, geofence).await,
+        other => other,
+    };
+
+    match created {
         Ok(row) => (
             StatusCode::CREATED,
             Json(ApiResponse::success(
@@ -78,9 +90,15 @@ pub async fn create_session(
 #[derive(Deserialize)]
 pub struct MarkAttendanceReq {
     pub code: String,
+    /// Where the device is; required when the session is geofenced.
+    pub location: Option<ReportedLocation>,
 }
 
 /// POST /api/modules/{module_id}/attendance/sessions/{session_id}/mark
+///
+/// Students mark themselves present with the code on the projector. Besides the code, the
+/// session's IP policy and geofence (if any) must be satisfied, so a code passed on to someone
+/// who is not in the venue is of no use to them.
 pub async fn mark_attendance(
     State(state): State<AppState>,
     Path((module_id, session_id)): Path<(i64, i64)>,
@@ -139,13 +157,14 @@ pub async fn mark_attendance(
 
     let ip_txt = Some(addr.ip().to_string());
 
-    // Proceed with normal mark (validates active, IP policy, and rotating code)
+    // Proceed with normal mark (validates active, IP policy, geofence, and rotating code)
     match attendance_record::Model::mark(
         db,
         &sess,
         claims.sub,
         &body.code,
         ip_txt.as_deref(),
+        body.location,
         now,
         1, // window tolerance
     )
@@ -319,3 +338,127 @@ pub async fn mark_attendance_by_username(
         ),
     }
 }
+
+/// POST `/api/modules/{module_id}/attendance/sessions/{session_id}/records/import`
+///
+/// Bulk-imports attendance recorded outside the system (a paper register, another tool) into
+/// the session. The body is CSV with a header row naming a `username` column and, optionally,
+/// a `taken_at` column (RFC 3339; the session's creation time when absent or blank). Other
+/// columns are ignored, so a file from `GET .../records/export` can be imported as is.
+///
+/// Rows for users who are not students of the module, or with an unreadable `taken_at`, are
+/// reported back and skipped; the rest are imported. Students who already have a record for
+/// the session keep it.
+///
+/// **Auth**: **Lecturer or AssistantLecturer**.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// { "imported": 41, "skipped": 2, "errors": [ { "line": 7, "username": "u1", "message": "User not found" } ] }
+/// ```
+/// - `400 Bad Request` — Empty file or no `username` column
+/// - `404 Not Found` — No such session in the module
+pub async fn import_attendance_csv(
+    State(state): State<AppState>,
+    Path((module_id, session_id)): Path<(i64, i64)>,
+    body: String,
+) -> (StatusCode, Json<ApiResponse<ImportResponse>>) {
+    let db = state.db();
+
+    let Some(sess) = SessionEntity::find()
+        .filter(SessionCol::Id.eq(session_id))
+        .filter(SessionCol::ModuleId.eq(module_id))
+        .one(db)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error("Attendance session not found")),
+        );
+    };
+
+    let mut records = csv::parse(&body).into_iter();
+    let Some((_, header)) = records.next() else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("CSV file is empty")),
+        );
+    };
+    let Some(username_col) = csv::column(&header, &["username"]) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error("CSV must have a username column")),
+        );
+    };
+    let taken_at_col = csv::column(&header, &["taken_at"]);
+
+    let mut result = ImportResponse {
+        imported: 0,
+        skipped: 0,
+        errors: Vec::new(),
+    };
+    for (line, fields) in records {
+        let field = |col: Option<usize>| {
+            col.and_then(|c| fields.get(c))
+                .map(|f| f.trim())
+                .unwrap_or("")
+        };
+        let username = field(Some(username_col)).to_string();
+        let mut reject = |message: &str| {
+            result.errors.push(ImportRowError {
+                line,
+                username: username.clone(),
+                message: message.to_string(),
+            })
+        };
+
+        if username.is_empty() {
+            reject("Username required");
+            continue;
+        }
+        let taken_at = match field(taken_at_col) {
+            "" => sess.created_at,
+            raw => match DateTime::parse_from_rfc3339(raw) {
+                Ok(at) => at.with_timezone(&Utc),
+                Err(_) => {
+                    reject("taken_at must be an RFC 3339 timestamp");
+                    continue;
+                }
+            },
+        };
+        let student = match UserEntity::find()
+            .filter(UserCol::Username.eq(username.as_str()))
+            .one(db)
+            .await
+        {
+            Ok(Some(u)) => u,
+            Ok(None) => {
+                reject("User not found");
+                continue;
+            }
+            Err(_) => {
+                reject("Failed to look up user");
+                continue;
+            }
+        };
+        if !user::Model::is_in_role(db, student.id, module_id, "Student")
+            .await
+            .unwrap_or(false)
+        {
+            reject("User is not a student of this module");
+            continue;
+        }
+
+        match attendance_record::Model::import(db, &sess, student.id, taken_at).await {
+            Ok(true) => result.imported += 1,
+            Ok(false) => result.skipped += 1,
+            Err(_) => reject("Failed to record attendance"),
+        }
+    }
+
+    let message = format!("Imported {} attendance records", result.imported);
+    (StatusCode::OK, Json(ApiResponse::success(result, message)))
+}
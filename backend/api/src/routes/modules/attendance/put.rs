@@ -6,7 +6,7 @@ use axum::{
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
 use util::state::AppState;
 
-use super::common::{AttendanceSessionResponse, EditSessionReq};
+use super::common::{AttendanceSessionResponse, EditSessionReq, parse_geofence};
 use crate::response::ApiResponse;
 use crate::ws::attendance::emit;
 use crate::ws::attendance::payload as ap;
@@ -18,6 +18,8 @@ use db::models::attendance_session::{
 ///
 /// Edit an existing attendance session’s settings.
 /// Fields `code_length` and `allow_manual_entry` have been removed and can no longer be changed.
+/// `geo_latitude`, `geo_longitude` and `geo_radius_m` replace the geofence (all three or none);
+/// `clear_geofence: true` removes it.
 pub async fn edit_session(
     State(state): State<AppState>,
     Path((module_id, session_id)): Path<(i64, i64)>,
@@ -39,6 +41,11 @@ pub async fn edit_session(
         );
     };
 
+    let geofence = match parse_geofence(body.geo_latitude, body.geo_longitude, body.geo_radius_m) {
+        Ok(g) => g,
+        Err(msg) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg))),
+    };
+
     let mut am: SessionAM = existing.clone().into();
 
     // Apply allowed updates
@@ -61,6 +68,15 @@ pub async fn edit_session(
     if let Some(ip) = body.created_from_ip {
         am.created_from_ip = Set(Some(ip));
     }
+    if body.clear_geofence.unwrap_or(false) {
+        am.geo_latitude = Set(None);
+        am.geo_longitude = Set(None);
+        am.geo_radius_m = Set(None);
+    } else if let Some((venue, radius)) = geofence {
+        am.geo_latitude = Set(Some(venue.latitude));
+        am.geo_longitude = Set(Some(venue.longitude));
+        am.geo_radius_m = Set(Some(radius));
+    }
     // Removed: code_length and allow_manual_entry
 
     // Persist update
@@ -74,6 +90,9 @@ pub async fn edit_session(
                 restrict_by_ip: updated.restrict_by_ip,
                 allowed_ip_cidr: updated.allowed_ip_cidr.clone(),
                 created_from_ip: updated.created_from_ip.clone(),
+                geo_latitude: updated.geo_latitude,
+                geo_longitude: updated.geo_longitude,
+                geo_radius_m: updated.geo_radius_m,
             };
 
             let ws = state.ws_clone();
@@ -0,0 +1,50 @@
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use util::execution_config::ExecutionConfig;
+
+use crate::response::ApiResponse;
+
+/// DELETE /api/modules/{module_id}/config
+///
+/// Removes the module's config. Assignment configs keep their own overrides and take every other
+/// field from the system defaults again.
+///
+/// ### Success Response (200 OK)
+/// ```json
+/// { "success": true, "message": "Module configuration removed", "data": null }
+/// ```
+///
+/// ### Error Responses
+/// - **404** – The module has no config
+/// - **500** – Internal error removing the file
+pub async fn delete_module_config(Path(module_id): Path<i64>) -> Response {
+    match ExecutionConfig::delete_module_config(module_id) {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success(
+                (),
+                "Module configuration removed",
+            )),
+        )
+            .into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(
+                "No configuration set for this module",
+            )),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Module config delete error: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to remove module config")),
+            )
+                .into_response()
+        }
+    }
+}
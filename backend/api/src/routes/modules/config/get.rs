@@ -0,0 +1,59 @@
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use util::execution_config::ExecutionConfig;
+
+use crate::response::ApiResponse;
+
+/// GET /api/modules/{module_id}/config
+///
+/// Returns the module's config as stored: only the fields it sets for the module's assignments.
+/// Returns an empty object if the module has no config.
+///
+/// ### Success Response (200 OK)
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Module configuration retrieved successfully",
+///   "data": {
+///     "config_version": 2,
+///     "execution": { "timeout_secs": 30 },
+///     "security": { "allowed_cidrs": ["10.0.0.0/8"] }
+///   }
+/// }
+/// ```
+///
+/// ### Error Responses
+/// - **500** – The stored config cannot be read
+pub async fn get_module_config(Path(module_id): Path<i64>) -> Response {
+    match ExecutionConfig::module_config_json(module_id) {
+        Ok(Some(config)) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                config,
+                "Module configuration retrieved successfully",
+            )),
+        )
+            .into_response(),
+        Ok(None) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                json!({}),
+                "No configuration set for this module",
+            )),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Failed to load module config: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load module config")),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! # Module Config Routes
+//!
+//! Defines and wires up routes for the `/modules/{module_id}/config` endpoint group: the
+//! module-level default config that every assignment config in the module inherits from.
+//!
+//! ## Structure
+//! - `get.rs` — GET handlers (fetch the module config)
+//! - `put.rs` — PUT handlers (replace the module config)
+//! - `delete.rs` — DELETE handlers (remove the module config)
+//!
+//! ## Usage
+//! Called via `modules_routes()` as a nested router mounted under `/modules/{module_id}/config`.
+//! This route group is protected by `allow_lecturer` middleware in the parent router.
+
+use axum::{
+    Router,
+    routing::{delete, get, put},
+};
+use util::state::AppState;
+
+mod delete;
+mod get;
+mod put;
+
+/// Builds and returns the `/modules/{module_id}/config` route group.
+///
+/// Routes:
+/// - `GET    /config` → get the module config (only the fields it sets)
+/// - `PUT    /config` → replace the module config
+/// - `DELETE /config` → remove the module config; assignments fall back to the system defaults
+pub fn module_config_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get::get_module_config))
+        .route("/", put(put::set_module_config))
+        .route("/", delete(delete::delete_module_config))
+}
@@ -0,0 +1,98 @@
+use axum::{
+    Json,
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use util::execution_config::{ExecutionConfig, migrate};
+
+use crate::{
+    response::ApiResponse, routes::modules::assignments::config::post::ConfigErrorsResponse,
+};
+
+/// PUT /api/modules/{module_id}/config
+///
+/// Replaces the module's config. The body holds only the fields the module's assignments should
+/// inherit (any part of an assignment config); assignments override them in their own config.
+/// Fields left out come from the system defaults.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "execution": { "timeout_secs": 30, "max_processes": 64 },
+///   "security": { "allowed_cidrs": ["10.0.0.0/8"] }
+/// }
+/// ```
+///
+/// ### Success Response (200 OK)
+/// Returns the stored config (upgraded to the current `config_version`).
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Module configuration saved",
+///   "data": {
+///     "config_version": 2,
+///     "execution": { "timeout_secs": 30, "max_processes": 64 },
+///     "security": { "allowed_cidrs": ["10.0.0.0/8"] }
+///   }
+/// }
+/// ```
+///
+/// ### Error Responses
+/// - **400** – Not a JSON object, or the config has problems (same checks and `data.errors`
+///   format as `POST /api/modules/{module_id}/assignments/{assignment_id}/config`)
+/// - **500** – Internal error saving the file
+pub async fn set_module_config(
+    Path(module_id): Path<i64>,
+    Json(mut config_json): Json<Value>,
+) -> Response {
+    if !config_json.is_object() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "Configuration must be a JSON object",
+            )),
+        )
+            .into_response();
+    }
+
+    if let Err(e) = migrate(&mut config_json) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(e))).into_response();
+    }
+
+    if let Err(errors) = ExecutionConfig::from_json_checked(&config_json) {
+        let message = errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::error_with_data(
+                ConfigErrorsResponse { errors },
+                message,
+            )),
+        )
+            .into_response();
+    }
+
+    match ExecutionConfig::save_module_config(module_id, &config_json) {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                config_json,
+                "Module configuration saved",
+            )),
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Module config save error: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to save module config")),
+            )
+                .into_response()
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Helpers shared by the gradebook handlers.
+
+use axum::{
+    Json,
+    body::Body,
+    http::{
+        HeaderValue, StatusCode,
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+};
+use db::gradebook::{self, GradebookColumn, GradebookRow};
+use db::models::module;
+use sea_orm::{DatabaseConnection, EntityTrait};
+
+use crate::response::ApiResponse;
+
+/// Students per batch when exporting.
+pub const EXPORT_BATCH: u64 = 200;
+
+pub fn error(status: StatusCode, msg: &str) -> Response {
+    (status, Json(ApiResponse::<()>::error(msg))).into_response()
+}
+
+/// Loads the module and its assignment columns.
+pub async fn load_columns(
+    db: &DatabaseConnection,
+    module_id: i64,
+) -> Result<(module::Model, Vec<GradebookColumn>), Response> {
+    let module = match module::Entity::find_by_id(module_id).one(db).await {
+        Ok(Some(module)) => module,
+        Ok(None) => return Err(error(StatusCode::NOT_FOUND, "Module not found")),
+        Err(_) => {
+            return Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to retrieve module",
+            ));
+        }
+    };
+    let columns = gradebook::columns(db, module_id).await.map_err(|_| {
+        error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to load assignments",
+        )
+    })?;
+    Ok((module, columns))
+}
+
+/// Every matching student's row, computed in batches.
+pub async fn all_rows(
+    db: &DatabaseConnection,
+    module_id: i64,
+    columns: &[GradebookColumn],
+    query: Option<&str>,
+) -> Result<Vec<GradebookRow>, Response> {
+    let mut rows = Vec::new();
+    let mut page = 1;
+    loop {
+        let students = match gradebook::students(db, module_id, query, page, EXPORT_BATCH).await {
+            Ok((students, _)) => students,
+            Err(_) => {
+                return Err(error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to load students",
+                ));
+            }
+        };
+        if students.is_empty() {
+            return Ok(rows);
+        }
+        match gradebook::rows(db, module_id, columns, students).await {
+            Ok(batch) => rows.extend(batch),
+            Err(e) => {
+                eprintln!("gradebook: {e}");
+                return Err(error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Failed to compute grades",
+                ));
+            }
+        }
+        page += 1;
+    }
+}
+
+pub fn attachment(content_type: &'static str, filename: String, body: Body) -> Response {
+    let disposition = HeaderValue::from_str(&format!("attachment; filename=\"{filename}\""))
+        .unwrap_or_else(|_| HeaderValue::from_static("attachment"));
+    match Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, HeaderValue::from_static(content_type))
+        .header(CONTENT_DISPOSITION, disposition)
+        .body(body)
+    {
+        Ok(resp) => resp,
+        Err(_) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to build response",
+        ),
+    }
+}
@@ -0,0 +1,407 @@
+//! Module gradebook: read-only routes (the gradebook page, CSV/XLSX export).
+
+use axum::{
+    Json,
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::gradebook::{self, GradebookCell, GradebookColumn, GradebookRow};
+use db::models::grade_export_template::Model as TemplateModel;
+use futures::{StreamExt, stream};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+use serde::{Deserialize, Serialize};
+use util::{execution_config::GradingPolicy, state::AppState};
+
+use super::common::{EXPORT_BATCH, all_rows, attachment, error, load_columns};
+use crate::response::ApiResponse;
+
+#[derive(Debug, Deserialize)]
+pub struct GradebookQuery {
+    pub page: Option<u64>,
+    pub per_page: Option<u64>,
+    /// Username filter
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// `csv` (default) or `xlsx`
+    pub format: Option<String>,
+    /// Username filter
+    pub query: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GradebookAssignment {
+    pub id: i64,
+    pub name: String,
+    pub assignment_type: String,
+    pub due_date: String,
+    pub grading_policy: GradingPolicy,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GradebookGrade {
+    pub assignment_id: i64,
+    pub mark: f64,
+    pub best: f64,
+    pub last: f64,
+    pub attempts: usize,
+    pub late: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GradebookStudent {
+    pub user_id: i64,
+    pub username: String,
+    /// One entry per assignment, in the same order; `null` where the student has not submitted
+    pub grades: Vec<Option<GradebookGrade>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GradebookResponse {
+    pub assignments: Vec<GradebookAssignment>,
+    pub students: Vec<GradebookStudent>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+}
+
+/// GET `/api/modules/{module_id}/gradebook`
+///
+/// A page of the module's students (by username) with their marks on every live assignment of
+/// the module, in due-date order. Each grade follows the assignment's grading policy (`mark`),
+/// with the best and last marks, the number of counted attempts and whether the attempt that
+/// counts was late (after the student's due date, extensions included). Group submissions and
+/// rubric scores count as they do for the assignment grades.
+///
+/// **Auth**: Lecturer or assistant lecturer of the module (or admin).
+///
+/// **Query**:
+/// - `query` *(optional)*: username filter
+/// - `page` *(default 1)*
+/// - `per_page` *(default 20, max 100)*
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Gradebook retrieved",
+///   "data": {
+///     "assignments": [
+///       { "id": 12, "name": "Practical 1", "assignment_type": "practical", "due_date": "2025-03-01T23:59:00+00:00", "grading_policy": "best" }
+///     ],
+///     "students": [
+///       {
+///         "user_id": 7,
+///         "username": "u12345678",
+///         "grades": [ { "assignment_id": 12, "mark": 90.0, "best": 90.0, "last": 50.0, "attempts": 2, "late": false } ]
+///       }
+///     ],
+///     "page": 1,
+///     "per_page": 20,
+///     "total": 1
+///   }
+/// }
+/// ```
+/// - `404 Not Found` — No such module
+/// - `500 Internal Server Error` — Database error
+pub async fn get_gradebook(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Query(params): Query<GradebookQuery>,
+) -> Response {
+    let db = app_state.db_read();
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+
+    let (_, columns) = match load_columns(db, module_id).await {
+        Ok(loaded) => loaded,
+        Err(resp) => return resp,
+    };
+    let (students, total) =
+        match gradebook::students(db, module_id, params.query.as_deref(), page, per_page).await {
+            Ok(found) => found,
+            Err(_) => {
+                return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load students");
+            }
+        };
+    let rows = match gradebook::rows(db, module_id, &columns, students).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("get_gradebook: {e}");
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to compute grades",
+            );
+        }
+    };
+
+    let response = GradebookResponse {
+        assignments: columns
+            .iter()
+            .map(|c| GradebookAssignment {
+                id: c.assignment.id,
+                name: c.assignment.name.clone(),
+                assignment_type: c.assignment.assignment_type.to_string(),
+                due_date: c.assignment.due_date.to_rfc3339(),
+                grading_policy: c.grading_policy(),
+            })
+            .collect(),
+        students: rows
+            .into_iter()
+            .map(|row| GradebookStudent {
+                user_id: row.user.id,
+                username: row.user.username,
+                grades: row
+                    .cells
+                    .into_iter()
+                    .zip(&columns)
+                    .map(|(cell, c)| {
+                        cell.map(|cell| GradebookGrade {
+                            assignment_id: c.assignment.id,
+                            mark: cell.mark,
+                            best: cell.best,
+                            last: cell.last,
+                            attempts: cell.attempts,
+                            late: cell.late,
+                        })
+                    })
+                    .collect(),
+            })
+            .collect(),
+        page,
+        per_page,
+        total,
+    };
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(response, "Gradebook retrieved")),
+    )
+        .into_response()
+}
+
+/// Column headers: the username, then mark, best, last, attempts and late per assignment.
+fn headers(columns: &[GradebookColumn]) -> Vec<String> {
+    let mut headers = vec!["username".to_string()];
+    for c in columns {
+        let name = &c.assignment.name;
+        headers.push(name.clone());
+        for suffix in ["best", "last", "attempts", "late"] {
+            headers.push(format!("{name} ({suffix})"));
+        }
+    }
+    headers
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn csv_line(fields: impl IntoIterator<Item = String>) -> String {
+    let mut line = fields
+        .into_iter()
+        .map(|f| csv_escape(&f))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+fn csv_row(row: &GradebookRow) -> String {
+    let mut fields = vec![row.user.username.clone()];
+    for cell in &row.cells {
+        match cell {
+            Some(GradebookCell {
+                mark,
+                best,
+                last,
+                attempts,
+                late,
+            }) => fields.extend([
+                format!("{mark:.2}"),
+                format!("{best:.2}"),
+                format!("{last:.2}"),
+                attempts.to_string(),
+                late.to_string(),
+            ]),
+            None => fields.extend(std::iter::repeat_n(String::new(), 5)),
+        }
+    }
+    csv_line(fields)
+}
+
+fn build_xlsx(columns: &[GradebookColumn], rows: &[GradebookRow]) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let sheet = workbook.add_worksheet_with_constant_memory();
+    sheet.set_name("Gradebook")?;
+    sheet.set_freeze_panes(1, 1)?;
+    for (col, header) in headers(columns).into_iter().enumerate() {
+        sheet.write_string_with_format(0, col as u16, header, &bold)?;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        let r = i as u32 + 1;
+        sheet.write_string(r, 0, &row.user.username)?;
+        for (j, cell) in row.cells.iter().enumerate() {
+            let Some(cell) = cell else { continue };
+            let col = (1 + j * 5) as u16;
+            sheet.write_number(r, col, cell.mark)?;
+            sheet.write_number(r, col + 1, cell.best)?;
+            sheet.write_number(r, col + 2, cell.last)?;
+            sheet.write_number(r, col + 3, cell.attempts as f64)?;
+            sheet.write_boolean(r, col + 4, cell.late)?;
+        }
+    }
+    workbook.save_to_buffer()
+}
+
+/// GET `/api/modules/{module_id}/gradebook/export`
+///
+/// The whole gradebook as a spreadsheet: one row per student (by username) and, per assignment,
+/// the columns `<name>` (the mark that counts), `<name> (best)`, `<name> (last)`,
+/// `<name> (attempts)` and `<name> (late)`. Cells are empty where a student has not submitted.
+///
+/// Students are processed in batches; the CSV is streamed as each batch is ready.
+///
+/// **Auth**: Lecturer or assistant lecturer of the module (or admin).
+///
+/// **Query**:
+/// - `format` *(optional)*: `csv` (default) or `xlsx`
+/// - `query` *(optional)*: username filter
+///
+/// ### Responses
+/// - `200 OK` — `text/csv` or `application/vnd.openxmlformats-officedocument.spreadsheetml.sheet`
+///   attachment named `gradebook_module_{module_id}.{csv,xlsx}`
+/// ```csv
+/// username,Practical 1,Practical 1 (best),Practical 1 (last),Practical 1 (attempts),Practical 1 (late)
+/// u12345678,90.00,90.00,50.00,2,false
+/// ```
+/// - `400 Bad Request` — Unknown format
+/// - `404 Not Found` — No such module
+/// - `500 Internal Server Error` — Database error
+pub async fn export_gradebook(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Query(params): Query<ExportQuery>,
+) -> Response {
+    let xlsx = match params
+        .format
+        .as_deref()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        None | Some("csv") => false,
+        Some("xlsx") => true,
+        Some(_) => {
+            return error(StatusCode::BAD_REQUEST, "Format must be csv or xlsx");
+        }
+    };
+    let db = app_state.db_read().clone();
+    let (_, columns) = match load_columns(&db, module_id).await {
+        Ok(loaded) => loaded,
+        Err(resp) => return resp,
+    };
+
+    if !xlsx {
+        let header = csv_line(headers(&columns));
+        let batches = stream::try_unfold(
+            (db, columns, params.query, 1u64),
+            move |(db, columns, query, page)| async move {
+                let (students, _) =
+                    gradebook::students(&db, module_id, query.as_deref(), page, EXPORT_BATCH)
+                        .await
+                        .map_err(std::io::Error::other)?;
+                if students.is_empty() {
+                    return Ok(None);
+                }
+                let rows = gradebook::rows(&db, module_id, &columns, students)
+                    .await
+                    .map_err(std::io::Error::other)?;
+                let chunk: String = rows.iter().map(csv_row).collect();
+                Ok(Some((Bytes::from(chunk), (db, columns, query, page + 1))))
+            },
+        );
+        let body = Body::from_stream(
+            stream::once(async move { Ok::<_, std::io::Error>(Bytes::from(header)) })
+                .chain(batches),
+        );
+        return attachment(
+            "text/csv; charset=utf-8",
+            format!("gradebook_module_{module_id}.csv"),
+            body,
+        );
+    }
+
+    let rows = match all_rows(&db, module_id, &columns, params.query.as_deref()).await {
+        Ok(rows) => rows,
+        Err(resp) => return resp,
+    };
+
+    match tokio::task::spawn_blocking(move || build_xlsx(&columns, &rows)).await {
+        Ok(Ok(bytes)) => attachment(
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            format!("gradebook_module_{module_id}.xlsx"),
+            Body::from(bytes),
+        ),
+        _ => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to build the spreadsheet",
+        ),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecordTemplate {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// GET `/api/modules/{module_id}/gradebook/records/templates`
+///
+/// The records-system export templates available for `POST /gradebook/records` (managed by
+/// admins under `/api/system/grade-export-templates`).
+///
+/// **Auth**: Lecturer or assistant lecturer of the module (or admin).
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Export templates retrieved",
+///   "data": [ { "id": 1, "name": "Faculty records", "description": null } ]
+/// }
+/// ```
+pub async fn list_record_templates(State(app_state): State<AppState>) -> Response {
+    match TemplateModel::list(app_state.db_read()).await {
+        Ok(templates) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                templates
+                    .into_iter()
+                    .map(|t| RecordTemplate {
+                        id: t.id,
+                        name: t.name,
+                        description: t.description,
+                    })
+                    .collect::<Vec<_>>(),
+                "Export templates retrieved",
+            )),
+        )
+            .into_response(),
+        Err(_) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to retrieve export templates",
+        ),
+    }
+}
@@ -0,0 +1,38 @@
+//! # Module Gradebook Routes
+//!
+//! Defines the `/modules/{module_id}/gradebook` endpoint group: every student's marks on every
+//! assignment of the module in one matrix, instead of one grades request per assignment.
+//!
+//! ## Structure
+//! - `common.rs` — shared loading and response helpers
+//! - `get.rs` — GET handlers (the gradebook page, CSV/XLSX export, records templates)
+//! - `post.rs` — POST handlers (records-system export)
+//!
+//! ## Usage
+//! Called via `modules_routes()` as a nested router mounted under `/modules/{module_id}/gradebook`.
+//! This route group is protected by `allow_assistant_lecturer` middleware in the parent router.
+
+use axum::{
+    Router,
+    routing::{get, post},
+};
+use util::state::AppState;
+
+mod common;
+mod get;
+mod post;
+
+/// Builds and returns the `/modules/{module_id}/gradebook` route group.
+///
+/// Routes:
+/// - `GET /gradebook`        → a page of students × assignments
+/// - `GET /gradebook/export` → the whole gradebook as CSV (streamed) or XLSX
+/// - `GET /gradebook/records/templates` → records-system export templates
+/// - `POST /gradebook/records` → final marks in a records-system template's CSV layout
+pub fn gradebook_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get::get_gradebook))
+        .route("/export", get(get::export_gradebook))
+        .route("/records/templates", get(get::list_record_templates))
+        .route("/records", post(post::export_records))
+}
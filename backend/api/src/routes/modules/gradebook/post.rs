@@ -0,0 +1,129 @@
+//! Module gradebook: records-system export.
+
+use axum::{
+    Json,
+    body::Body,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::grade_export_template::Entity as TemplateEntity;
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use std::collections::HashMap;
+use util::state::AppState;
+
+use super::common::{all_rows, attachment, error, load_columns};
+use crate::response::ApiResponse;
+
+#[derive(Debug, Deserialize)]
+pub struct RecordsExportRequest {
+    pub template_id: i64,
+    /// Weight per assignment id; assignments left out do not count. Equal weights when omitted.
+    pub weights: Option<HashMap<i64, f64>>,
+    /// Count missing marks as 0 instead of refusing the export
+    #[serde(default)]
+    pub missing_as_zero: bool,
+}
+
+/// POST `/api/modules/{module_id}/gradebook/records`
+///
+/// Exports every student's final mark in the layout of a records-system template (see
+/// `/api/system/grade-export-templates`). The final mark is the weighted average of the
+/// assignment marks from the gradebook (each following its grading policy), rounded and coded
+/// pass/fail as the template says.
+///
+/// **Auth**: Lecturer or assistant lecturer of the module (or admin).
+///
+/// ### Request Body
+/// ```json
+/// { "template_id": 1, "weights": { "12": 40, "13": 60 }, "missing_as_zero": false }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — `text/csv` attachment named `records_{module_code}_{year}.csv`
+/// - `400 Bad Request` — A negative weight, no positive weight, or a weight for an assignment
+///   outside the module
+/// - `404 Not Found` — No such module or template
+/// - `422 Unprocessable Entity` — Students are missing marks; `data` lists them
+/// ```json
+/// {
+///   "success": false,
+///   "message": "2 students are missing marks",
+///   "data": [ { "user_id": 7, "username": "u12345678", "assignments": ["Practical 2"] } ]
+/// }
+/// ```
+pub async fn export_records(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Json(req): Json<RecordsExportRequest>,
+) -> Response {
+    let db = app_state.db_read();
+    let (module, columns) = match load_columns(db, module_id).await {
+        Ok(loaded) => loaded,
+        Err(resp) => return resp,
+    };
+    let definition = match TemplateEntity::find_by_id(req.template_id).one(db).await {
+        Ok(Some(template)) => match template.definition() {
+            Ok(definition) => definition,
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+        },
+        Ok(None) => return error(StatusCode::NOT_FOUND, "Export template not found"),
+        Err(_) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to retrieve export template",
+            );
+        }
+    };
+
+    if let Some(weights) = &req.weights {
+        if let Some(id) = weights
+            .keys()
+            .find(|id| !columns.iter().any(|c| c.assignment.id == **id))
+        {
+            return error(
+                StatusCode::BAD_REQUEST,
+                &format!("Assignment {id} is not in this module"),
+            );
+        }
+        if weights.values().any(|w| !w.is_finite() || *w < 0.0) {
+            return error(StatusCode::BAD_REQUEST, "Weights cannot be negative");
+        }
+        if !weights.values().any(|w| *w > 0.0) {
+            return error(
+                StatusCode::BAD_REQUEST,
+                "At least one weight must be positive",
+            );
+        }
+    }
+
+    let rows = match all_rows(db, module_id, &columns, None).await {
+        Ok(rows) => rows,
+        Err(resp) => return resp,
+    };
+    match definition.render(
+        &module,
+        &columns,
+        &rows,
+        req.weights.as_ref(),
+        req.missing_as_zero,
+    ) {
+        Ok(csv) => attachment(
+            "text/csv; charset=utf-8",
+            format!("records_{}_{}.csv", module.code, module.year),
+            Body::from(csv),
+        ),
+        Err(missing) => {
+            let message = match missing.len() {
+                1 => "1 student is missing marks".to_string(),
+                n => format!("{n} students are missing marks"),
+            };
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::error_with_data(missing, message)),
+            )
+                .into_response()
+        }
+    }
+}
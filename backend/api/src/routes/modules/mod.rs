@@ -8,16 +8,21 @@
 //! - `put.rs` — PUT handlers (e.g., edit module, edit lecturers)
 //! - `delete.rs` — DELETE handlers (e.g., remove lecturers, students, tutors)
 //! - `assignments.rs` — nested assignment routes under modules
+//! - `config/` — the module-level default assignment config
+//! - `gradebook/` — every student's marks across the module's assignments
+//! - `rollover.rs` — rolling a module over into a new year
+//! - `ticket_queue/` — the staff queue of tickets across the module's assignments
 //!
 //! ## Usage
 //! Call `modules_routes()` to get a configured `Router` for `/modules` to be mounted in the main app.
 
-use crate::auth::guards::{allow_admin, allow_lecturer};
+use crate::auth::guards::{allow_admin, allow_assistant_lecturer, allow_lecturer, allow_tutor};
 use crate::{
     auth::guards::allow_student,
     routes::modules::{
         announcements::announcement_routes, attendance::attendance_routes,
-        personnel::personnel_routes,
+        config::module_config_routes, gradebook::gradebook_routes, personnel::personnel_routes,
+        ticket_queue::ticket_queue_routes,
     },
 };
 use assignments::assignment_routes;
@@ -30,17 +35,22 @@ use delete::{bulk_delete_modules, delete_module};
 use get::{get_module, get_modules, get_my_details};
 use post::create;
 use put::{bulk_edit_modules, edit_module};
+use rollover::rollover_module;
 use util::state::AppState;
 
 pub mod announcements;
 pub mod assignments;
 pub mod attendance;
 pub mod common;
+pub mod config;
 pub mod delete;
 pub mod get;
+pub mod gradebook;
 pub mod personnel;
 pub mod post;
 pub mod put;
+pub mod rollover;
+pub mod ticket_queue;
 
 /// Builds and returns the `/modules` route group.
 ///
@@ -50,9 +60,13 @@ pub mod put;
 /// - `GET    /modules/{module_id}`     → get a single module by ID
 /// - `PUT    /modules/{module_id}`     → edit module details (admin only)
 /// - `DELETE /modules/{module_id}`     → delete a module entirely (admin only)
+/// - `POST   /modules/{module_id}/rollover` → set up the module's next offering (admin only)
 ///
 /// - Nested students routes under `/modules/{module_id}/students`
 /// - Nested personnel routes under `/modules/{module_id}/personnel`
+/// - Nested module config routes under `/modules/{module_id}/config` (lecturer only)
+/// - Nested gradebook routes under `/modules/{module_id}/gradebook` (lecturer or assistant lecturer)
+/// - Nested ticket queue routes under `/modules/{module_id}/tickets` (tutor or higher)
 ///
 /// All modifying routes are protected by `require_admin` middleware.
 pub fn modules_routes(app_state: AppState) -> Router<AppState> {
@@ -72,6 +86,10 @@ pub fn modules_routes(app_state: AppState) -> Router<AppState> {
             "/{module_id}",
             delete(delete_module).route_layer(from_fn(allow_admin)),
         )
+        .route(
+            "/{module_id}/rollover",
+            post(rollover_module).route_layer(from_fn(allow_admin)),
+        )
         .route(
             "/bulk",
             delete(bulk_delete_modules).route_layer(from_fn(allow_admin)),
@@ -88,6 +106,22 @@ pub fn modules_routes(app_state: AppState) -> Router<AppState> {
             "/{module_id}/personnel",
             personnel_routes().route_layer(from_fn_with_state(app_state.clone(), allow_lecturer)),
         )
+        .nest(
+            "/{module_id}/config",
+            module_config_routes()
+                .route_layer(from_fn_with_state(app_state.clone(), allow_lecturer)),
+        )
+        .nest(
+            "/{module_id}/gradebook",
+            gradebook_routes().route_layer(from_fn_with_state(
+                app_state.clone(),
+                allow_assistant_lecturer,
+            )),
+        )
+        .nest(
+            "/{module_id}/tickets",
+            ticket_queue_routes().route_layer(from_fn_with_state(app_state.clone(), allow_tutor)),
+        )
         .nest(
             "/{module_id}/announcements",
             announcement_routes(app_state.clone())
@@ -28,10 +28,12 @@ mod post;
 /// - `POST   /personnel`           → assign one or more users to a role
 /// - `DELETE /personnel`           → remove one or more users from a role
 /// - `GET    /personnel/eligible`  → fetch users not assigned to any role in the module
+/// - `POST   /personnel/import`    → enroll a CSV roster, creating missing users (admin only)
 pub fn personnel_routes() -> Router<AppState> {
     Router::new()
         .route("/", get(get::get_personnel))
         .route("/", post(post::assign_personnel))
         .route("/", delete(delete::remove_personnel))
         .route("/eligible", get(get::get_eligible_users_for_module))
+        .route("/import", post(post::import_roster))
 }
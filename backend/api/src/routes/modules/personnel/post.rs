@@ -7,10 +7,17 @@ use axum::{
 use sea_orm::{
     ActiveModelTrait, ColumnTrait, Condition, EntityTrait, IntoActiveModel, QueryFilter, Set,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{auth::AuthUser, response::ApiResponse};
+use crate::{
+    auth::AuthUser,
+    response::ApiResponse,
+    routes::users::common::{
+        ImportRowError, ImportedUserOutcome, find_or_create_user, read_user_csv,
+    },
+};
 use db::models::{
+    module::Entity as ModuleEntity,
     user::Entity as UserEntity,
     user_module_role::{
         ActiveModel as RoleActiveModel, Column as RoleCol, Entity as RoleEntity, Role,
@@ -226,3 +233,176 @@ pub async fn assign_personnel(
         )),
     )
 }
+
+/// Outcome of a roster import.
+#[derive(Debug, Default, Serialize)]
+pub struct RosterImportResponse {
+    /// Users that did not exist before the import.
+    pub users_created: usize,
+    /// Users newly assigned to the module.
+    pub enrolled: usize,
+    /// Users already in the module whose role changed.
+    pub role_changed: usize,
+    /// Users already in the module with the given role.
+    pub unchanged: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Reads a roster `role` cell; blank means student.
+fn parse_roster_role(role: Option<&str>) -> Option<Role> {
+    let Some(role) = role else {
+        return Some(Role::Student);
+    };
+    match role
+        .trim()
+        .to_ascii_lowercase()
+        .replace([' ', '-'], "_")
+        .as_str()
+    {
+        "student" => Some(Role::Student),
+        "tutor" => Some(Role::Tutor),
+        "assistant_lecturer" => Some(Role::AssistantLecturer),
+        "lecturer" => Some(Role::Lecturer),
+        _ => None,
+    }
+}
+
+/// POST /api/modules/{module_id}/personnel/import
+///
+/// Enrolls a whole roster in the module from a CSV, creating any users that don't exist yet.
+///
+/// ### Permissions:
+/// - Admins only.
+///
+/// ---
+///
+/// ### Request Body (`text/csv`)
+/// ```csv
+/// username,email,role
+/// u12345678,u12345678@tuks.co.za,
+/// u87654321,u87654321@tuks.co.za,tutor
+/// ```
+/// - `username` (or `student_number`) is required.
+/// - `email` is only needed for users who don't exist yet; they get a random password (or the
+///   row's `password`) and sign in after resetting it.
+/// - `role` is one of `student` (the default when blank), `tutor`, `assistant_lecturer` or
+///   `lecturer`. Users already in the module are moved to this role.
+///
+/// Problem rows are reported against their line and skipped; the rest are still imported.
+///
+/// ---
+///
+/// ### Success Response (200 OK)
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Enrolled 1 user",
+///   "data": {
+///     "users_created": 1,
+///     "enrolled": 1,
+///     "role_changed": 0,
+///     "unchanged": 0,
+///     "errors": [ { "line": 3, "username": "u87654321", "message": "Unknown role" } ]
+///   }
+/// }
+/// ```
+///
+/// ### Error Responses
+/// - **400 Bad Request** — Empty file or no username column
+/// - **403 Forbidden** — Not an admin
+/// - **404 Not Found** — No such module
+pub async fn import_roster(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    body: String,
+) -> (StatusCode, Json<ApiResponse<RosterImportResponse>>) {
+    let db = app_state.db();
+
+    if !claims.admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::error("Only admins can import module rosters")),
+        );
+    }
+    match ModuleEntity::find_by_id(module_id).one(db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error("Module not found")),
+            );
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error("Failed to retrieve module")),
+            );
+        }
+    }
+
+    let (rows, errors) = match read_user_csv(&body) {
+        Ok(read) => read,
+        Err(msg) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg))),
+    };
+
+    let mut result = RosterImportResponse {
+        errors,
+        ..Default::default()
+    };
+    for row in &rows {
+        let Some(role) = parse_roster_role(row.role.as_deref()) else {
+            result.errors.push(ImportRowError::new(row, "Unknown role"));
+            continue;
+        };
+        let user = match find_or_create_user(db, row).await {
+            Ok(ImportedUserOutcome::Created(user)) => {
+                result.users_created += 1;
+                user
+            }
+            Ok(ImportedUserOutcome::Existing(user)) => user,
+            Err(msg) => {
+                result.errors.push(ImportRowError::new(row, msg));
+                continue;
+            }
+        };
+
+        let existing = RoleEntity::find()
+            .filter(RoleCol::UserId.eq(user.id))
+            .filter(RoleCol::ModuleId.eq(module_id))
+            .one(db)
+            .await;
+        let assigned = match existing {
+            Ok(Some(existing)) if existing.role == role => {
+                result.unchanged += 1;
+                continue;
+            }
+            Ok(Some(existing)) => {
+                let mut active = existing.into_active_model();
+                active.role = Set(role);
+                active.update(db).await.map(|_| result.role_changed += 1)
+            }
+            Ok(None) => RoleActiveModel {
+                user_id: Set(user.id),
+                module_id: Set(module_id),
+                role: Set(role),
+            }
+            .insert(db)
+            .await
+            .map(|_| result.enrolled += 1),
+            Err(e) => Err(e),
+        };
+        if assigned.is_err() {
+            result
+                .errors
+                .push(ImportRowError::new(row, "Failed to assign role"));
+        }
+    }
+    result.errors.sort_by_key(|e| e.line);
+
+    let message = match result.enrolled {
+        1 => "Enrolled 1 user".to_string(),
+        n => format!("Enrolled {n} users"),
+    };
+    (StatusCode::OK, Json(ApiResponse::success(result, message)))
+}
@@ -0,0 +1,349 @@
+//! Semester rollover route.
+//!
+//! `POST /api/modules/{module_id}/rollover` sets up the module's next offering: a new module
+//! with the same metadata, module config and staff, copies of the chosen assignments (through
+//! the assignment export bundle, dates moved by the difference in years), and the old module's
+//! assignments archived. With `dry_run` it only reports what would be created.
+
+use crate::response::ApiResponse;
+use crate::routes::modules::assignments::bundle::common::{
+    ImportError, ImportOverrides, export_bundle, import_bundle,
+};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{Datelike, Utc};
+use db::models::module;
+use db::rollover::{
+    archivable_assignments, archive_assignments, create_module, shift_years, staff,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use util::state::AppState;
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RolloverRequest {
+    /// Year of the new offering
+    pub year: i32,
+    /// Defaults to the current module's code
+    pub code: Option<String>,
+    /// Defaults to the current module's description
+    pub description: Option<String>,
+    /// Assignments to copy; all live assignments when omitted
+    pub assignment_ids: Option<Vec<i64>>,
+    /// Carry over lecturers, assistant lecturers and tutors
+    #[serde(default = "default_true")]
+    pub include_personnel: bool,
+    /// Archive the current module's assignments once the new module is set up
+    #[serde(default = "default_true")]
+    pub archive: bool,
+    /// Only report what would be created
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverModule {
+    /// `None` on a dry run
+    pub id: Option<i64>,
+    pub code: String,
+    pub year: i32,
+    pub description: Option<String>,
+    pub credits: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverAssignment {
+    pub source_id: i64,
+    /// The copy's id; `None` on a dry run
+    pub id: Option<i64>,
+    pub name: String,
+    pub available_from: String,
+    pub due_date: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverPerson {
+    pub user_id: i64,
+    pub username: String,
+    pub role: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RolloverResponse {
+    pub dry_run: bool,
+    pub module: RolloverModule,
+    pub assignments: Vec<RolloverAssignment>,
+    pub personnel: Vec<RolloverPerson>,
+    /// Assignments of the current module that are (or would be) archived
+    pub archived_assignments: u64,
+}
+
+/// POST /api/modules/{module_id}/rollover
+///
+/// Rolls the module over into a new year. Admin only.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "year": 2026,
+///   "code": "COS301",
+///   "description": "Software Engineering",
+///   "assignment_ids": [12, 13],
+///   "include_personnel": true,
+///   "archive": true,
+///   "dry_run": true
+/// }
+/// ```
+/// Only `year` is required. Students are never carried over.
+///
+/// ### Responses
+/// - `200 OK` — Dry run: what would be created
+/// - `201 Created` — The new module, the assignment copies and the staff carried over
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Module rolled over",
+///   "data": {
+///     "dry_run": false,
+///     "module": { "id": 9, "code": "COS301", "year": 2026, "description": "Software Engineering", "credits": 16 },
+///     "assignments": [
+///       { "source_id": 12, "id": 40, "name": "Practical 1", "available_from": "2026-02-01T00:00:00+00:00", "due_date": "2026-02-14T23:59:00+00:00" }
+///     ],
+///     "personnel": [ { "user_id": 3, "username": "u12345678", "role": "lecturer" } ],
+///     "archived_assignments": 4
+///   }
+/// }
+/// ```
+/// - `400 Bad Request` — A year before this one, or an assignment id not in the module
+/// - `404 Not Found` — No such module
+/// - `409 Conflict` — A module with that code and year already exists
+pub async fn rollover_module(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Json(req): Json<RolloverRequest>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let source = match module::Entity::find_by_id(module_id).one(db).await {
+        Ok(Some(m)) => m,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Module not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to retrieve module")),
+            )
+                .into_response();
+        }
+    };
+
+    let current_year = Utc::now().year();
+    if req.year < current_year {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(format!(
+                "Year must be {current_year} or later"
+            ))),
+        )
+            .into_response();
+    }
+    let code = req
+        .code
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| source.code.clone());
+    let description = req.description.or_else(|| source.description.clone());
+
+    match module::Entity::find()
+        .filter(module::Column::Code.eq(&code))
+        .filter(module::Column::Year.eq(req.year))
+        .one(db)
+        .await
+    {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(format!(
+                    "Module {code} already exists for {}",
+                    req.year
+                ))),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check existing modules")),
+            )
+                .into_response();
+        }
+    }
+
+    let (live, personnel) = match (
+        archivable_assignments(db, module_id).await,
+        staff(db, module_id).await,
+    ) {
+        (Ok(live), Ok(staff)) => (live, staff),
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load the module")),
+            )
+                .into_response();
+        }
+    };
+    let selected = match &req.assignment_ids {
+        None => live.clone(),
+        Some(ids) => {
+            if let Some(missing) = ids.iter().find(|id| !live.iter().any(|a| a.id == **id)) {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<()>::error(format!(
+                        "Assignment {missing} is not a live assignment of this module"
+                    ))),
+                )
+                    .into_response();
+            }
+            live.iter()
+                .filter(|a| ids.contains(&a.id))
+                .cloned()
+                .collect()
+        }
+    };
+
+    let years = req.year - source.year;
+    let mut response = RolloverResponse {
+        dry_run: req.dry_run,
+        module: RolloverModule {
+            id: None,
+            code: code.clone(),
+            year: req.year,
+            description: description.clone(),
+            credits: source.credits,
+        },
+        assignments: selected
+            .iter()
+            .map(|a| RolloverAssignment {
+                source_id: a.id,
+                id: None,
+                name: a.name.clone(),
+                available_from: shift_years(a.available_from, years).to_rfc3339(),
+                due_date: shift_years(a.due_date, years).to_rfc3339(),
+            })
+            .collect(),
+        personnel: if req.include_personnel {
+            personnel
+                .into_iter()
+                .map(|(user, role)| RolloverPerson {
+                    user_id: user.id,
+                    username: user.username,
+                    role: role.to_string(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        },
+        archived_assignments: if req.archive { live.len() as u64 } else { 0 },
+    };
+
+    if req.dry_run {
+        return (
+            StatusCode::OK,
+            Json(ApiResponse::success(response, "Rollover preview")),
+        )
+            .into_response();
+    }
+
+    let created = match create_module(
+        db,
+        &source,
+        &code,
+        req.year,
+        description.as_deref(),
+        req.include_personnel,
+    )
+    .await
+    {
+        Ok(m) => m,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error(format!(
+                    "Failed to create module: {e}"
+                ))),
+            )
+                .into_response();
+        }
+    };
+    response.module.id = Some(created.id);
+
+    for (assignment, planned) in selected.iter().zip(response.assignments.iter_mut()) {
+        let copied = match export_bundle(db, assignment).await {
+            Ok(bundle) => import_bundle(
+                db,
+                created.id,
+                &bundle,
+                ImportOverrides {
+                    name: None,
+                    available_from: Some(shift_years(assignment.available_from, years)),
+                    due_date: Some(shift_years(assignment.due_date, years)),
+                },
+            )
+            .await
+            .map_err(|e| match e {
+                ImportError::Invalid(msg) | ImportError::Internal(msg) => msg,
+            }),
+            Err(e) => Err(e),
+        };
+        match copied {
+            Ok(copy) => planned.id = Some(copy.id),
+            Err(e) => {
+                // Leave nothing half made: the old module is untouched at this point
+                let _ = created.delete(db).await;
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error(format!(
+                        "Failed to copy assignment {}: {e}",
+                        assignment.id
+                    ))),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    if req.archive {
+        match archive_assignments(db, module_id).await {
+            Ok(n) => response.archived_assignments = n,
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error(
+                        "Module rolled over, but archiving its assignments failed",
+                    )),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    (
+        StatusCode::CREATED,
+        Json(ApiResponse::success(response, "Module rolled over")),
+    )
+        .into_response()
+}
@@ -0,0 +1,219 @@
+//! Module ticket queue: the staff view of tickets across the module's assignments.
+
+use crate::{
+    auth::AuthUser, response::ApiResponse,
+    routes::modules::assignments::tickets::common::TicketResponse,
+};
+use axum::{
+    Extension,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use chrono::Utc;
+use db::models::{
+    assignment,
+    tickets::{Column as TicketColumn, Entity as TicketEntity, TicketPriority, TicketStatus},
+};
+use db::soft_delete::SoftDelete;
+use sea_orm::{ColumnTrait, Condition, PaginatorTrait, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use util::state::AppState;
+
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    /// Page number (default: 1)
+    pub page: Option<u64>,
+    /// Items per page (default: 20, max: 100)
+    pub per_page: Option<u64>,
+    /// `open` or `closed`
+    pub status: Option<String>,
+    /// `me`, `unassigned`, or a user id
+    pub assignee: Option<String>,
+    /// `low`, `normal`, `high` or `urgent`
+    pub priority: Option<String>,
+    /// Only tickets still unanswered past their SLA deadline
+    pub overdue: Option<bool>,
+    /// Only tickets that have (or have not) been escalated
+    pub escalated: Option<bool>,
+    /// Only tickets on this assignment
+    pub assignment_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueAssignment {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueItem {
+    pub ticket: TicketResponse,
+    pub assignment: QueueAssignment,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueResponse {
+    pub tickets: Vec<QueueItem>,
+    pub page: u64,
+    pub per_page: u64,
+    pub total: u64,
+}
+
+/// GET /api/modules/{module_id}/tickets
+///
+/// The staff ticket queue: tickets from every assignment in the module, ordered by SLA deadline
+/// (soonest first) and then by age. **Tutor, assistant lecturer, lecturer or admin only.**
+///
+/// ### Query Parameters
+/// - `page`, `per_page` (optional): Pagination (defaults 1 and 20, `per_page` capped at 100)
+/// - `status` (optional): `open` or `closed`
+/// - `assignee` (optional): `me`, `unassigned`, or a user id
+/// - `priority` (optional): `low`, `normal`, `high` or `urgent`
+/// - `overdue` (optional): `true` for open tickets with no staff reply past their SLA deadline
+/// - `escalated` (optional): `true`/`false` to keep only escalated/unescalated tickets
+/// - `assignment_id` (optional): Restrict to one assignment
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "data": {
+///     "tickets": [
+///       {
+///         "ticket": {
+///           "id": 12, "assignment_id": 3, "user_id": 7, "title": "Task 2 fails",
+///           "description": "...", "status": "open", "assignee_id": 4, "priority": "high",
+///           "sla_due_at": "2025-05-02T08:00:00+00:00", "first_response_at": null,
+///           "escalated_at": null, "overdue": true,
+///           "created_at": "2025-05-01T08:00:00+00:00", "updated_at": "2025-05-01T08:00:00+00:00"
+///         },
+///         "assignment": { "id": 3, "name": "Practical 2" }
+///       }
+///     ],
+///     "page": 1,
+///     "per_page": 20,
+///     "total": 1
+///   },
+///   "message": "Ticket queue retrieved"
+/// }
+/// ```
+/// - `400 Bad Request` → Invalid `status`, `priority` or `assignee`
+/// - `500 Internal Server Error` → Database error
+pub async fn get_ticket_queue(
+    State(app_state): State<AppState>,
+    Path(module_id): Path<i64>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Query(q): Query<QueueQuery>,
+) -> impl IntoResponse {
+    let db = app_state.db();
+    let page = q.page.unwrap_or(1).max(1);
+    let per_page = q.per_page.unwrap_or(20).clamp(1, 100);
+
+    let mut condition = Condition::all().add(assignment::Column::ModuleId.eq(module_id));
+
+    if let Some(ref status) = q.status {
+        match status.parse::<TicketStatus>() {
+            Ok(status) => condition = condition.add(TicketColumn::Status.eq(status)),
+            Err(_) => return bad_request("Invalid status value"),
+        }
+    }
+
+    if let Some(ref priority) = q.priority {
+        match priority.parse::<TicketPriority>() {
+            Ok(priority) => condition = condition.add(TicketColumn::Priority.eq(priority)),
+            Err(_) => return bad_request("Invalid priority value"),
+        }
+    }
+
+    if let Some(ref assignee) = q.assignee {
+        condition = match assignee.as_str() {
+            "me" => condition.add(TicketColumn::AssigneeId.eq(claims.sub)),
+            "unassigned" => condition.add(TicketColumn::AssigneeId.is_null()),
+            other => match other.parse::<i64>() {
+                Ok(id) => condition.add(TicketColumn::AssigneeId.eq(id)),
+                Err(_) => return bad_request("Invalid assignee value"),
+            },
+        };
+    }
+
+    if q.overdue == Some(true) {
+        condition = condition
+            .add(TicketColumn::Status.eq(TicketStatus::Open))
+            .add(TicketColumn::FirstResponseAt.is_null())
+            .add(TicketColumn::SlaDueAt.lt(Utc::now()));
+    }
+
+    match q.escalated {
+        Some(true) => condition = condition.add(TicketColumn::EscalatedAt.is_not_null()),
+        Some(false) => condition = condition.add(TicketColumn::EscalatedAt.is_null()),
+        None => {}
+    }
+
+    if let Some(assignment_id) = q.assignment_id {
+        condition = condition.add(TicketColumn::AssignmentId.eq(assignment_id));
+    }
+
+    let paginator = TicketEntity::find_active()
+        .find_also_related(assignment::Entity)
+        .filter(condition)
+        .order_by_asc(TicketColumn::SlaDueAt)
+        .order_by_asc(TicketColumn::CreatedAt)
+        .order_by_asc(TicketColumn::Id)
+        .paginate(db, per_page);
+
+    let total = match paginator.num_items().await {
+        Ok(n) => n,
+        Err(e) => return db_error(e),
+    };
+    let rows = match paginator.fetch_page(page - 1).await {
+        Ok(rows) => rows,
+        Err(e) => return db_error(e),
+    };
+
+    let tickets = rows
+        .into_iter()
+        .filter_map(|(ticket, assignment)| {
+            let assignment = assignment?;
+            Some(QueueItem {
+                ticket: ticket.into(),
+                assignment: QueueAssignment {
+                    id: assignment.id,
+                    name: assignment.name,
+                },
+            })
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            QueueResponse {
+                tickets,
+                page,
+                per_page,
+                total,
+            },
+            "Ticket queue retrieved",
+        )),
+    )
+        .into_response()
+}
+
+fn bad_request(message: &str) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::<()>::error(message)),
+    )
+        .into_response()
+}
+
+fn db_error(e: sea_orm::DbErr) -> axum::response::Response {
+    tracing::error!("Failed to load ticket queue: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ApiResponse::<()>::error("Failed to retrieve ticket queue")),
+    )
+        .into_response()
+}
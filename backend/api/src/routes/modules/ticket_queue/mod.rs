@@ -0,0 +1,24 @@
+//! # Module Ticket Queue Routes
+//!
+//! Defines the `/modules/{module_id}/tickets` endpoint group: the staff view of every ticket
+//! across the module's assignments, for triaging by status, assignee, priority and SLA.
+//!
+//! ## Structure
+//! - `get.rs` — GET handlers (the queue page)
+//!
+//! ## Usage
+//! Called via `modules_routes()` as a nested router mounted under `/modules/{module_id}/tickets`.
+//! This route group is protected by `allow_tutor` middleware in the parent router.
+
+use axum::{Router, routing::get};
+use util::state::AppState;
+
+mod get;
+
+/// Builds and returns the `/modules/{module_id}/tickets` route group.
+///
+/// Routes:
+/// - `GET /tickets` → a filtered page of the module's tickets, soonest SLA deadline first
+pub fn ticket_queue_routes() -> Router<AppState> {
+    Router::new().route("/", get(get::get_ticket_queue))
+}
@@ -0,0 +1,36 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use db::models::api_token;
+use util::state::AppState;
+
+use crate::response::ApiResponse;
+
+/// DELETE /api/system/api-tokens/{token_id}
+///
+/// Revokes any user's API token; it stops working immediately.
+///
+/// ### Responses
+/// - `200 OK` — Revoked
+/// - `404 Not Found` — No such token
+pub async fn revoke_api_token(
+    State(app_state): State<AppState>,
+    Path(token_id): Path<i64>,
+) -> (StatusCode, Json<ApiResponse<()>>) {
+    match api_token::Model::revoke(app_state.db(), token_id, None).await {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse::success((), "API token revoked")),
+        ),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::error("API token not found")),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Database error: {e}"))),
+        ),
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use db::models::api_token;
+use serde::Deserialize;
+use util::state::AppState;
+
+use crate::response::ApiResponse;
+use crate::routes::me::api_tokens::ApiTokenResponse;
+
+#[derive(Debug, Deserialize)]
+pub struct ListApiTokensQuery {
+    pub user_id: Option<i64>,
+}
+
+/// GET /api/system/api-tokens
+///
+/// API tokens, newest first: every user's, or only `user_id`'s.
+///
+/// ### Responses
+/// - `200 OK` — Tokens as listed by `GET /api/me/api-tokens`
+pub async fn list_api_tokens(
+    State(app_state): State<AppState>,
+    Query(query): Query<ListApiTokensQuery>,
+) -> (StatusCode, Json<ApiResponse<Vec<ApiTokenResponse>>>) {
+    let db = app_state.db();
+    let tokens = match query.user_id {
+        Some(user_id) => api_token::Model::for_user(db, user_id).await,
+        None => api_token::Model::all(db).await,
+    };
+    match tokens {
+        Ok(tokens) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                tokens.into_iter().map(Into::into).collect(),
+                "API tokens retrieved",
+            )),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Database error: {e}"))),
+        ),
+    }
+}
@@ -0,0 +1,40 @@
+//! # API Token Administration Routes
+//!
+//! Defines the `/system/api-tokens` endpoint group: every user's API tokens (see
+//! `/me/api-tokens`), and tokens issued to service accounts — users set up for a CI pipeline or
+//! departmental script rather than a person.
+//!
+//! ## Structure
+//! - `get.rs` — GET handlers (list tokens)
+//! - `post.rs` — POST handlers (issue a token to a user)
+//! - `delete.rs` — DELETE handlers (revoke a token)
+//!
+//! ## Usage
+//! Mounted by `system_routes()`, which is admin only. Like `/me/api-tokens`, these need a login
+//! session; API tokens can't manage tokens.
+
+use axum::{
+    Router,
+    middleware::from_fn,
+    routing::{delete, get},
+};
+use util::state::AppState;
+
+use crate::auth::guards::deny_api_tokens;
+
+pub mod delete;
+pub mod get;
+pub mod post;
+
+/// Builds and returns the `/system/api-tokens` route group.
+///
+/// Routes:
+/// - `GET    /api-tokens`              → list tokens, optionally of one user
+/// - `POST   /api-tokens`              → issue a token to a user
+/// - `DELETE /api-tokens/{token_id}`   → revoke a token
+pub fn api_token_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get::list_api_tokens).post(post::issue_api_token))
+        .route("/{token_id}", delete(delete::revoke_api_token))
+        .route_layer(from_fn(deny_api_tokens))
+}
@@ -0,0 +1,68 @@
+use axum::{Extension, Json, extract::State, http::StatusCode};
+use db::models::user;
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use util::state::AppState;
+
+use crate::auth::AuthUser;
+use crate::response::ApiResponse;
+use crate::routes::me::api_tokens::{CreateApiTokenRequest, CreatedApiTokenResponse, create_token};
+
+#[derive(Debug, Deserialize)]
+pub struct IssueApiTokenRequest {
+    /// The service account (or other user) the token acts as.
+    pub user_id: i64,
+    #[serde(flatten)]
+    pub token: CreateApiTokenRequest,
+}
+
+/// POST /api/system/api-tokens
+///
+/// Issues an API token to a user, typically a service account for a CI pipeline or script.
+/// The issuing admin is recorded as `created_by`.
+///
+/// ### Request Body
+/// ```json
+/// { "user_id": 42, "name": "Departmental marks export", "scopes": ["read"], "expires_in_days": 365 }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — As for `POST /api/me/api-tokens`
+/// - `400 Bad Request` — As for `POST /api/me/api-tokens`; `admin` needs an admin user
+/// - `404 Not Found` — No such user
+pub async fn issue_api_token(
+    State(app_state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<IssueApiTokenRequest>,
+) -> (StatusCode, Json<ApiResponse<CreatedApiTokenResponse>>) {
+    let owner = match user::Entity::find_by_id(req.user_id)
+        .one(app_state.db())
+        .await
+    {
+        Ok(Some(owner)) => owner,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::error("User not found")),
+            );
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Database error: {e}"))),
+            );
+        }
+    };
+    let expires_at = match req.token.validate(owner.admin) {
+        Ok(at) => at,
+        Err(msg) => return (StatusCode::BAD_REQUEST, Json(ApiResponse::error(msg))),
+    };
+    create_token(
+        &app_state,
+        owner.id,
+        &req.token,
+        expires_at,
+        Some(claims.sub),
+    )
+    .await
+}
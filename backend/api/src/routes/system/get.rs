@@ -1,8 +1,8 @@
 pub mod code_manager {
     use crate::response::ApiResponse;
     use axum::{Json, http::StatusCode};
+    use code_runner::code_manager_client::{http_base_url, with_auth};
     use serde::Deserialize;
-    use util::config;
 
     #[derive(Deserialize)]
     struct CodeManagerMaxConcurrentResp {
@@ -10,13 +10,9 @@ pub mod code_manager {
     }
 
     pub async fn get_max_concurrent_handler() -> (StatusCode, Json<ApiResponse<usize>>) {
-        let url = format!(
-            "http://{}:{}/max_concurrent",
-            config::code_manager_host(),
-            config::code_manager_port()
-        );
+        let url = format!("{}/max_concurrent", http_base_url());
         let client = reqwest::Client::new();
-        match client.get(url).send().await {
+        match with_auth(client.get(url)).send().await {
             Ok(resp) if resp.status().is_success() => {
                 match resp.json::<CodeManagerMaxConcurrentResp>().await {
                     Ok(body) => (
@@ -51,12 +47,13 @@ pub mod code_manager {
 pub use code_manager::get_max_concurrent_handler;
 
 pub mod metrics {
+    use crate::response::ApiResponse;
     use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
     use axum::{
         Json,
         extract::{Query, State},
-        http::{HeaderMap, HeaderValue},
-        response::IntoResponse,
+        http::{HeaderMap, HeaderValue, StatusCode},
+        response::{IntoResponse, Response},
     };
     use chrono::{Datelike, Duration, TimeZone, Timelike, Utc};
     use db::models::system_metric::{
@@ -79,13 +76,26 @@ pub mod metrics {
         }
     }
 
+    fn round1(v: f64) -> f64 {
+        if !v.is_finite() {
+            return 0.0;
+        }
+        (v * 10.0).round() / 10.0
+    }
+
     #[derive(Debug, Deserialize)]
     pub struct MetricsQuery {
         pub start: Option<String>,
         pub end: Option<String>,
         pub bucket: Option<String>, // day|week|month|year
+        /// Fixed-width downsampling (`1m`, `5m` or `1h`). Overrides the grouping implied by
+        /// `bucket`, which then only picks the default range.
+        pub interval: Option<String>,
     }
 
+    /// Most points one query may return: a week at `1m`.
+    const MAX_POINTS: i64 = 7 * 24 * 60;
+
     fn parse_time(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
         chrono::DateTime::parse_from_rfc3339(s)
             .ok()
@@ -112,8 +122,32 @@ pub mod metrics {
         }
     }
 
+    fn interval_unit(interval: &str) -> Option<&'static str> {
+        match interval {
+            "1m" => Some("1m"),
+            "5m" => Some("5m"),
+            "1h" => Some("hour"),
+            _ => None,
+        }
+    }
+
+    /// Width of a fixed-size unit in seconds; `None` for calendar units.
+    fn unit_seconds(unit: &str) -> Option<i64> {
+        match unit {
+            "1m" => Some(60),
+            "5m" => Some(300),
+            "hour" => Some(3600),
+            _ => None,
+        }
+    }
+
     fn floor_to_unit(ts: chrono::DateTime<Utc>, unit: &str) -> chrono::DateTime<Utc> {
         match unit {
+            "1m" | "5m" => {
+                let secs = unit_seconds(unit).unwrap();
+                let t = ts.timestamp();
+                Utc.timestamp_opt(t - t.rem_euclid(secs), 0).unwrap()
+            }
             "hour" => ts
                 .date_naive()
                 .and_hms_opt(ts.hour(), 0, 0)
@@ -177,6 +211,8 @@ pub mod metrics {
 
     fn step_next(dt: chrono::DateTime<Utc>, unit: &str) -> chrono::DateTime<Utc> {
         match unit {
+            "1m" => dt + Duration::minutes(1),
+            "5m" => dt + Duration::minutes(5),
             "hour" => dt + Duration::hours(1),
             "day" => dt + Duration::days(1),
             "month" => {
@@ -188,11 +224,16 @@ pub mod metrics {
         }
     }
 
-    #[derive(Debug)]
+    #[derive(Debug, Default)]
     struct MetricPoint {
         ts: chrono::DateTime<Utc>,
+        /// Rows averaged into this point; 0 for buckets with no data.
+        samples: u64,
         cpu_avg: f64,
         mem_pct: f64,
+        cm_running: f64,
+        cm_waiting: f64,
+        cm_waiting_max: i32,
     }
 
     fn aggregate_points(
@@ -208,6 +249,9 @@ pub mod metrics {
             n: u64,
             cpu: f64,
             mem_pct: f64,
+            cm_running: f64,
+            cm_waiting: f64,
+            cm_waiting_max: i32,
         }
 
         let mut map: BTreeMap<i64, Agg> = BTreeMap::new();
@@ -217,6 +261,9 @@ pub mod metrics {
             entry.n += 1;
             entry.cpu += r.cpu_avg as f64;
             entry.mem_pct += r.mem_pct as f64;
+            entry.cm_running += r.cm_running as f64;
+            entry.cm_waiting += r.cm_waiting as f64;
+            entry.cm_waiting_max = entry.cm_waiting_max.max(r.cm_waiting);
         }
 
         let mut points = Vec::new();
@@ -227,14 +274,17 @@ pub mod metrics {
                 let n = a.n.max(1) as f64;
                 points.push(MetricPoint {
                     ts: t,
+                    samples: a.n,
                     cpu_avg: a.cpu / n,
                     mem_pct: a.mem_pct / n,
+                    cm_running: a.cm_running / n,
+                    cm_waiting: a.cm_waiting / n,
+                    cm_waiting_max: a.cm_waiting_max,
                 });
             } else {
                 points.push(MetricPoint {
                     ts: t,
-                    cpu_avg: 0.0,
-                    mem_pct: 0.0,
+                    ..Default::default()
                 });
             }
             t = step_next(t, unit);
@@ -243,12 +293,14 @@ pub mod metrics {
         points
     }
 
-    pub async fn get_metrics(
-        State(state): State<AppState>,
-        Query(q): Query<MetricsQuery>,
-    ) -> Json<serde_json::Value> {
+    /// Resolves the range and grouping unit of a query and loads its points.
+    async fn load_points(state: &AppState, q: &MetricsQuery) -> Result<Vec<MetricPoint>, String> {
         let bucket = validate_bucket(q.bucket.as_deref().unwrap_or("day"));
-        let unit = group_unit(bucket);
+        let unit = match q.interval.as_deref() {
+            Some(interval) => interval_unit(interval)
+                .ok_or_else(|| format!("Invalid interval '{interval}': expected 1m, 5m or 1h"))?,
+            None => group_unit(bucket),
+        };
 
         let (mut start, mut end) = match (
             q.start.as_deref().and_then(parse_time),
@@ -260,54 +312,73 @@ pub mod metrics {
         start = floor_to_unit(start, unit);
         end = floor_to_unit(end, unit);
 
+        if let Some(secs) = unit_seconds(unit)
+            && (end - start).num_seconds() / secs >= MAX_POINTS
+        {
+            return Err(format!(
+                "Range too large for this interval: at most {MAX_POINTS} points per query"
+            ));
+        }
+
         let rows = SystemMetric::find()
             .filter(MetCol::CreatedAt.gte(start))
-            .filter(MetCol::CreatedAt.lte(end))
+            .filter(MetCol::CreatedAt.lt(step_next(end, unit)))
             .all(state.db())
             .await
             .unwrap_or_default();
 
-        let points = aggregate_points(rows, start, end, unit);
+        Ok(aggregate_points(rows, start, end, unit))
+    }
+
+    fn bad_request(message: String) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(message)),
+        )
+            .into_response()
+    }
+
+    /// GET /api/system/metrics
+    ///
+    /// CPU, memory and code manager queue history, averaged per bucket. Buckets are hours,
+    /// days or months depending on `bucket`, or fixed 1m/5m/1h steps when `interval` is set.
+    /// Buckets without samples are zero-filled and have `samples: 0`.
+    pub async fn get_metrics(
+        State(state): State<AppState>,
+        Query(q): Query<MetricsQuery>,
+    ) -> Response {
+        let points = match load_points(&state, &q).await {
+            Ok(points) => points,
+            Err(e) => return bad_request(e),
+        };
         let payload: Vec<_> = points
             .into_iter()
             .map(|p| {
                 json!({
                     "ts": p.ts.to_rfc3339(),
+                    "samples": p.samples,
                     "cpu_avg": round_pct(p.cpu_avg),
                     "mem_pct": round_pct(p.mem_pct),
+                    "cm_running": round1(p.cm_running),
+                    "cm_waiting": round1(p.cm_waiting),
+                    "cm_waiting_max": p.cm_waiting_max,
                 })
             })
             .collect();
 
-        Json(json!({ "points": payload }))
+        Json(json!({ "points": payload })).into_response()
     }
 
     pub async fn get_metrics_csv(
         State(state): State<AppState>,
         Query(q): Query<MetricsQuery>,
-    ) -> impl IntoResponse {
-        let bucket = validate_bucket(q.bucket.as_deref().unwrap_or("day"));
-        let unit = group_unit(bucket);
-
-        let (mut start, mut end) = match (
-            q.start.as_deref().and_then(parse_time),
-            q.end.as_deref().and_then(parse_time),
-        ) {
-            (Some(s), Some(e)) => (s, e),
-            _ => default_bounds(Utc::now(), bucket),
+    ) -> Response {
+        let points = match load_points(&state, &q).await {
+            Ok(points) => points,
+            Err(e) => return bad_request(e),
         };
-        start = floor_to_unit(start, unit);
-        end = floor_to_unit(end, unit);
-
-        let rows = SystemMetric::find()
-            .filter(MetCol::CreatedAt.gte(start))
-            .filter(MetCol::CreatedAt.lte(end))
-            .all(state.db())
-            .await
-            .unwrap_or_default();
-
-        let points = aggregate_points(rows, start, end, unit);
-        let mut csv = String::from("timestamp,cpu_avg,mem_pct\n");
+        let mut csv =
+            String::from("timestamp,cpu_avg,mem_pct,cm_running,cm_waiting,cm_waiting_max\n");
         for p in points {
             let cpu = round_pct(p.cpu_avg);
             let mem = round_pct(p.mem_pct);
@@ -322,7 +393,15 @@ pub mod metrics {
             } else {
                 format!("{:.1}", mem)
             };
-            csv.push_str(&format!("{},{},{}\n", p.ts.to_rfc3339(), cpu_s, mem_s));
+            csv.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                p.ts.to_rfc3339(),
+                cpu_s,
+                mem_s,
+                round1(p.cm_running),
+                round1(p.cm_waiting),
+                p.cm_waiting_max
+            ));
         }
 
         let mut headers = HeaderMap::new();
@@ -332,7 +411,7 @@ pub mod metrics {
             HeaderValue::from_static("attachment; filename=system_metrics.csv"),
         );
 
-        (headers, csv)
+        (headers, csv).into_response()
     }
 }
 
@@ -576,3 +655,43 @@ pub mod submissions {
 }
 
 pub use submissions::{SubmissionsQuery, submissions_over_time, submissions_over_time_export};
+
+pub mod two_factor {
+    use crate::response::ApiResponse;
+    use crate::services::two_factor::staff_required;
+    use axum::{Json, extract::State, http::StatusCode};
+    use serde::{Deserialize, Serialize};
+    use util::state::AppState;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct TwoFactorPolicy {
+        /// Whether admins, lecturers, assistant lecturers and tutors must use two-factor
+        /// authentication.
+        pub require_staff: bool,
+    }
+
+    /// GET /api/system/two-factor
+    ///
+    /// The instance's two-factor authentication policy.
+    ///
+    /// - `200 OK` with `{ "require_staff": false }`
+    pub async fn get_two_factor_policy(
+        State(app_state): State<AppState>,
+    ) -> (StatusCode, Json<ApiResponse<TwoFactorPolicy>>) {
+        match staff_required(app_state.db()).await {
+            Ok(require_staff) => (
+                StatusCode::OK,
+                Json(ApiResponse::success(
+                    TwoFactorPolicy { require_staff },
+                    "OK",
+                )),
+            ),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Database error: {e}"))),
+            ),
+        }
+    }
+}
+
+pub use two_factor::{TwoFactorPolicy, get_two_factor_policy};
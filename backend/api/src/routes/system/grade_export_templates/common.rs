@@ -0,0 +1,67 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::grade_export_template::{Definition, Model as TemplateModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub definition: Definition,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateResponse {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub definition: serde_json::Value,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<TemplateModel> for TemplateResponse {
+    fn from(t: TemplateModel) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            description: t.description,
+            definition: t.definition,
+            created_at: t.created_at.to_rfc3339(),
+            updated_at: t.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Checks the name and definition, returning the trimmed name or the problems found.
+pub fn validate(req: &TemplateRequest) -> Result<String, Vec<String>> {
+    let name = req.name.trim().to_string();
+    let mut errors = match req.definition.validate() {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors,
+    };
+    if name.is_empty() {
+        errors.insert(0, "Name is required".to_string());
+    }
+    if errors.is_empty() {
+        Ok(name)
+    } else {
+        Err(errors)
+    }
+}
+
+/// `400` listing the problems with a template.
+pub fn invalid(errors: Vec<String>) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::error_with_data(
+            errors,
+            "Invalid export template",
+        )),
+    )
+        .into_response()
+}
@@ -0,0 +1,45 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::grade_export_template::Entity as TemplateEntity;
+use sea_orm::EntityTrait;
+use util::state::AppState;
+
+/// DELETE `/api/system/grade-export-templates/{template_id}`
+///
+/// Deletes a template. Admin only.
+///
+/// ### Responses
+/// - `200 OK` — Deleted
+/// - `404 Not Found` — No such template
+pub async fn delete_template(
+    State(app_state): State<AppState>,
+    Path(template_id): Path<i64>,
+) -> Response {
+    match TemplateEntity::delete_by_id(template_id)
+        .exec(app_state.db())
+        .await
+    {
+        Ok(res) if res.rows_affected > 0 => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_without_data(
+                "Export template deleted",
+            )),
+        )
+            .into_response(),
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Export template not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to delete export template")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,67 @@
+use super::common::TemplateResponse;
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::grade_export_template::Model as TemplateModel;
+use util::state::AppState;
+
+/// GET `/api/system/grade-export-templates`
+///
+/// All grade export templates by name. Admin only.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Export templates retrieved",
+///   "data": [
+///     {
+///       "id": 1,
+///       "name": "Faculty records",
+///       "description": null,
+///       "definition": {
+///         "columns": [
+///           { "header": "STUDENT_NO", "source": "student_number" },
+///           { "header": "MARK", "source": "final_mark" },
+///           { "header": "RESULT", "source": "result_code" }
+///         ],
+///         "rounding": { "mode": "half_up", "decimals": 0 },
+///         "pass_mark": 50.0,
+///         "pass_code": "P",
+///         "fail_code": "F",
+///         "delimiter": ",",
+///         "header": true
+///       },
+///       "created_at": "2025-10-16T08:00:00+00:00",
+///       "updated_at": "2025-10-16T08:00:00+00:00"
+///     }
+///   ]
+/// }
+/// ```
+pub async fn list_templates(State(app_state): State<AppState>) -> Response {
+    match TemplateModel::list(app_state.db()).await {
+        Ok(templates) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                templates
+                    .into_iter()
+                    .map(TemplateResponse::from)
+                    .collect::<Vec<_>>(),
+                "Export templates retrieved",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(
+                "Failed to retrieve export templates",
+            )),
+        )
+            .into_response(),
+    }
+}
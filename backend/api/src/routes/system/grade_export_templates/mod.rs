@@ -0,0 +1,43 @@
+//! # Grade Export Template Routes
+//!
+//! Defines the `/system/grade-export-templates` endpoint group: the CSV layouts of the
+//! university records system that module staff export final marks in (see
+//! `GET/POST /modules/{module_id}/gradebook/records`).
+//!
+//! ## Structure
+//! - `common.rs` — request/response bodies
+//! - `get.rs` — GET handlers (list templates)
+//! - `post.rs` — POST handlers (create a template)
+//! - `put.rs` — PUT handlers (replace a template)
+//! - `delete.rs` — DELETE handlers (remove a template)
+//!
+//! ## Usage
+//! Mounted by `system_routes()`, which is admin only.
+
+use axum::{
+    Router,
+    routing::{get, put},
+};
+use util::state::AppState;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod post;
+pub mod put;
+
+/// Builds and returns the `/system/grade-export-templates` route group.
+///
+/// Routes:
+/// - `GET    /grade-export-templates`                 → list templates
+/// - `POST   /grade-export-templates`                 → create a template
+/// - `PUT    /grade-export-templates/{template_id}`   → replace a template
+/// - `DELETE /grade-export-templates/{template_id}`   → delete a template
+pub fn grade_export_template_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get::list_templates).post(post::create_template))
+        .route(
+            "/{template_id}",
+            put(put::update_template).delete(delete::delete_template),
+        )
+}
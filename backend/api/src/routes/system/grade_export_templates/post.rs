@@ -0,0 +1,89 @@
+use super::common::{TemplateRequest, TemplateResponse, invalid, validate};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::grade_export_template::Model as TemplateModel;
+use util::state::AppState;
+
+/// POST `/api/system/grade-export-templates`
+///
+/// Creates a grade export template. Admin only.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "name": "Faculty records",
+///   "description": "Semester mark upload",
+///   "definition": {
+///     "columns": [
+///       { "header": "STUDENT_NO", "source": "student_number" },
+///       { "header": "MODULE", "source": "module_code" },
+///       { "header": "TERM", "source": "literal", "value": "S1" },
+///       { "header": "MARK", "source": "final_mark" },
+///       { "header": "RESULT", "source": "result_code" }
+///     ],
+///     "rounding": { "mode": "half_up", "decimals": 0 },
+///     "pass_mark": 50,
+///     "pass_code": "P",
+///     "fail_code": "F"
+///   }
+/// }
+/// ```
+/// Column sources: `username`, `student_number` (the username's digits), `email`,
+/// `module_code`, `year`, `final_mark`, `result_code` and `literal` (with a `value`).
+/// Rounding modes: `half_up` (default), `half_even`, `down`, `up`. `delimiter` defaults to `,`
+/// and `header` to `true`.
+///
+/// ### Responses
+/// - `201 Created` — The template
+/// - `400 Bad Request` — The problems with the template, in `data`
+/// - `409 Conflict` — A template with that name already exists
+pub async fn create_template(
+    State(app_state): State<AppState>,
+    Json(req): Json<TemplateRequest>,
+) -> Response {
+    let db = app_state.db();
+    let name = match validate(&req) {
+        Ok(name) => name,
+        Err(errors) => return invalid(errors),
+    };
+    match TemplateModel::find_by_name(db, &name).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(
+                    "An export template with this name already exists",
+                )),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check export templates")),
+            )
+                .into_response();
+        }
+    }
+
+    match TemplateModel::create(db, &name, req.description.as_deref(), &req.definition).await {
+        Ok(template) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                TemplateResponse::from(template),
+                "Export template created",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to create export template")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,79 @@
+use super::common::{TemplateRequest, TemplateResponse, invalid, validate};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::grade_export_template::Model as TemplateModel;
+use sea_orm::DbErr;
+use util::state::AppState;
+
+/// PUT `/api/system/grade-export-templates/{template_id}`
+///
+/// Replaces a template's name, description and definition. Admin only. The body is the same as
+/// for creating one.
+///
+/// ### Responses
+/// - `200 OK` — The updated template
+/// - `400 Bad Request` — The problems with the template, in `data`
+/// - `404 Not Found` — No such template
+/// - `409 Conflict` — Another template has that name
+pub async fn update_template(
+    State(app_state): State<AppState>,
+    Path(template_id): Path<i64>,
+    Json(req): Json<TemplateRequest>,
+) -> Response {
+    let db = app_state.db();
+    let name = match validate(&req) {
+        Ok(name) => name,
+        Err(errors) => return invalid(errors),
+    };
+    match TemplateModel::find_by_name(db, &name).await {
+        Ok(Some(other)) if other.id != template_id => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(
+                    "An export template with this name already exists",
+                )),
+            )
+                .into_response();
+        }
+        Ok(_) => {}
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check export templates")),
+            )
+                .into_response();
+        }
+    }
+
+    match TemplateModel::update(
+        db,
+        template_id,
+        &name,
+        req.description.as_deref(),
+        &req.definition,
+    )
+    .await
+    {
+        Ok(template) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                TemplateResponse::from(template),
+                "Export template updated",
+            )),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(msg)) => {
+            (StatusCode::NOT_FOUND, Json(ApiResponse::<()>::error(msg))).into_response()
+        }
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to update export template")),
+        )
+            .into_response(),
+    }
+}
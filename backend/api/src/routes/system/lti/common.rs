@@ -0,0 +1,157 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    lti_context::Model as ContextModel,
+    lti_deployment::Model as DeploymentModel,
+    lti_key::Model as KeyModel,
+    lti_platform::{Model as PlatformModel, PlatformFields},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct PlatformRequest {
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub auth_login_url: String,
+    pub auth_token_url: String,
+    pub keyset_url: String,
+}
+
+impl PlatformRequest {
+    /// Trimmed fields, or the problems found.
+    pub fn validate(&self) -> Result<PlatformFields<'_>, Vec<String>> {
+        let fields = PlatformFields {
+            name: self.name.trim(),
+            issuer: self.issuer.trim(),
+            client_id: self.client_id.trim(),
+            auth_login_url: self.auth_login_url.trim(),
+            auth_token_url: self.auth_token_url.trim(),
+            keyset_url: self.keyset_url.trim(),
+        };
+        let mut errors = Vec::new();
+        for (field, value) in [
+            ("name", fields.name),
+            ("issuer", fields.issuer),
+            ("client_id", fields.client_id),
+        ] {
+            if value.is_empty() {
+                errors.push(format!("{field} is required"));
+            }
+        }
+        for (field, value) in [
+            ("auth_login_url", fields.auth_login_url),
+            ("auth_token_url", fields.auth_token_url),
+            ("keyset_url", fields.keyset_url),
+        ] {
+            match url::Url::parse(value) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+                _ => errors.push(format!("{field} must be an http(s) URL")),
+            }
+        }
+        if errors.is_empty() {
+            Ok(fields)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// `400` listing the problems with a platform.
+pub fn invalid(errors: Vec<String>) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::error_with_data(errors, "Invalid LTI platform")),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeploymentResponse {
+    pub id: i64,
+    pub deployment_id: String,
+    pub created_at: String,
+}
+
+impl From<DeploymentModel> for DeploymentResponse {
+    fn from(d: DeploymentModel) -> Self {
+        Self {
+            id: d.id,
+            deployment_id: d.deployment_id,
+            created_at: d.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContextResponse {
+    pub id: i64,
+    pub context_id: String,
+    pub module_id: i64,
+    pub created_at: String,
+}
+
+impl From<ContextModel> for ContextResponse {
+    fn from(c: ContextModel) -> Self {
+        Self {
+            id: c.id,
+            context_id: c.context_id,
+            module_id: c.module_id,
+            created_at: c.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlatformResponse {
+    pub id: i64,
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub auth_login_url: String,
+    pub auth_token_url: String,
+    pub keyset_url: String,
+    pub deployments: Vec<DeploymentResponse>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl PlatformResponse {
+    pub fn new(p: PlatformModel, deployments: Vec<DeploymentModel>) -> Self {
+        Self {
+            id: p.id,
+            name: p.name,
+            issuer: p.issuer,
+            client_id: p.client_id,
+            auth_login_url: p.auth_login_url,
+            auth_token_url: p.auth_token_url,
+            keyset_url: p.keyset_url,
+            deployments: deployments.into_iter().map(Into::into).collect(),
+            created_at: p.created_at.to_rfc3339(),
+            updated_at: p.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyResponse {
+    pub id: i64,
+    pub kid: String,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl From<KeyModel> for KeyResponse {
+    fn from(k: KeyModel) -> Self {
+        Self {
+            id: k.id,
+            kid: k.kid,
+            active: k.active,
+            created_at: k.created_at.to_rfc3339(),
+        }
+    }
+}
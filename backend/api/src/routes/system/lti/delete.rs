@@ -0,0 +1,125 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    lti_context::{Column as ContextColumn, Entity as ContextEntity},
+    lti_deployment::{Column as DeploymentColumn, Entity as DeploymentEntity},
+    lti_platform::Entity as PlatformEntity,
+};
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use util::state::AppState;
+
+/// DELETE `/api/system/lti/platforms/{platform_id}`
+///
+/// Removes a platform with its deployments, user links and placements. Admin only.
+///
+/// ### Responses
+/// - `200 OK` — Deleted
+/// - `404 Not Found` — No such platform
+pub async fn delete_platform(
+    State(app_state): State<AppState>,
+    Path(platform_id): Path<i64>,
+) -> Response {
+    match PlatformEntity::delete_by_id(platform_id)
+        .exec(app_state.db())
+        .await
+    {
+        Ok(res) if res.rows_affected > 0 => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_without_data(
+                "LTI platform deleted",
+            )),
+        )
+            .into_response(),
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("LTI platform not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to delete LTI platform")),
+        )
+            .into_response(),
+    }
+}
+
+/// DELETE `/api/system/lti/platforms/{platform_id}/deployments/{deployment_id}`
+///
+/// Stops launches from a deployment. `deployment_id` is the id returned when it was
+/// registered. Admin only.
+///
+/// ### Responses
+/// - `200 OK` — Deleted
+/// - `404 Not Found` — No such deployment on the platform
+pub async fn delete_deployment(
+    State(app_state): State<AppState>,
+    Path((platform_id, deployment_id)): Path<(i64, i64)>,
+) -> Response {
+    match DeploymentEntity::delete_many()
+        .filter(DeploymentColumn::Id.eq(deployment_id))
+        .filter(DeploymentColumn::PlatformId.eq(platform_id))
+        .exec(app_state.db())
+        .await
+    {
+        Ok(res) if res.rows_affected > 0 => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_without_data(
+                "Deployment deleted",
+            )),
+        )
+            .into_response(),
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("Deployment not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to delete deployment")),
+        )
+            .into_response(),
+    }
+}
+
+/// DELETE `/api/system/lti/platforms/{platform_id}/contexts/{context_id}`
+///
+/// Unlinks a course from its module; placements already opened keep their module. `context_id`
+/// is the id returned when it was linked. Admin only.
+///
+/// ### Responses
+/// - `200 OK` — Deleted
+/// - `404 Not Found` — No such context on the platform
+pub async fn delete_context(
+    State(app_state): State<AppState>,
+    Path((platform_id, context_id)): Path<(i64, i64)>,
+) -> Response {
+    match ContextEntity::delete_many()
+        .filter(ContextColumn::Id.eq(context_id))
+        .filter(ContextColumn::PlatformId.eq(platform_id))
+        .exec(app_state.db())
+        .await
+    {
+        Ok(res) if res.rows_affected > 0 => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_without_data(
+                "LTI context unlinked",
+            )),
+        )
+            .into_response(),
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("LTI context not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to unlink LTI context")),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,143 @@
+use super::common::{ContextResponse, KeyResponse, PlatformResponse};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    lti_context::Model as ContextModel, lti_deployment::Model as DeploymentModel,
+    lti_key::Model as KeyModel, lti_platform::Model as PlatformModel,
+};
+use util::{config, state::AppState};
+
+/// GET `/api/system/lti/platforms`
+///
+/// Lists the registered platforms with their deployments, and the URLs to configure the tool
+/// with in the LMS. Admin only.
+///
+/// ### Responses
+/// - `200 OK`
+/// ```json
+/// {
+///   "success": true,
+///   "message": "LTI platforms retrieved",
+///   "data": {
+///     "tool": {
+///       "login_url": "https://fitchfork.co.za/api/lti/login",
+///       "launch_url": "https://fitchfork.co.za/api/lti/launch",
+///       "jwks_url": "https://fitchfork.co.za/api/lti/jwks"
+///     },
+///     "platforms": [
+///       {
+///         "id": 1, "name": "ClickUP", "issuer": "https://clickup.up.ac.za",
+///         "client_id": "170000000000123",
+///         "auth_login_url": "https://clickup.up.ac.za/api/lti/authorize_redirect",
+///         "auth_token_url": "https://clickup.up.ac.za/login/oauth2/token",
+///         "keyset_url": "https://clickup.up.ac.za/api/lti/security/jwks",
+///         "deployments": [ { "id": 1, "deployment_id": "12:8865aa05b4b79b64", "created_at": "..." } ],
+///         "created_at": "...", "updated_at": "..."
+///       }
+///     ]
+///   }
+/// }
+/// ```
+pub async fn list_platforms(State(app_state): State<AppState>) -> Response {
+    let db = app_state.db();
+    let platforms = match PlatformModel::list(db).await {
+        Ok(p) => p,
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load LTI platforms")),
+            )
+                .into_response();
+        }
+    };
+    let mut out = Vec::with_capacity(platforms.len());
+    for platform in platforms {
+        match DeploymentModel::for_platform(db, platform.id).await {
+            Ok(deployments) => out.push(PlatformResponse::new(platform, deployments)),
+            Err(_) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ApiResponse::<()>::error("Failed to load LTI deployments")),
+                )
+                    .into_response();
+            }
+        }
+    }
+
+    let base = config::lti_tool_url();
+    let base = base.trim_end_matches('/');
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            serde_json::json!({
+                "tool": {
+                    "login_url": format!("{base}/api/lti/login"),
+                    "launch_url": format!("{base}/api/lti/launch"),
+                    "jwks_url": format!("{base}/api/lti/jwks"),
+                },
+                "platforms": out,
+            }),
+            "LTI platforms retrieved",
+        )),
+    )
+        .into_response()
+}
+
+/// GET `/api/system/lti/keys`
+///
+/// Lists the tool's signing keys, newest first. Private keys are never returned. Admin only.
+///
+/// ### Responses
+/// - `200 OK` — `[ { "id": 2, "kid": "1f2e3d4c5b6a7980", "active": true, "created_at": "..." } ]`
+pub async fn list_keys(State(app_state): State<AppState>) -> Response {
+    match KeyModel::list(app_state.db()).await {
+        Ok(keys) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                keys.into_iter().map(KeyResponse::from).collect::<Vec<_>>(),
+                "LTI keys retrieved",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to load LTI keys")),
+        )
+            .into_response(),
+    }
+}
+
+/// GET `/api/system/lti/platforms/{platform_id}/contexts`
+///
+/// Lists the platform's courses linked to modules. Admin only.
+///
+/// ### Responses
+/// - `200 OK` — `[ { "id": 1, "context_id": "c-301", "module_id": 4, "created_at": "..." } ]`
+pub async fn list_contexts(
+    State(app_state): State<AppState>,
+    Path(platform_id): Path<i64>,
+) -> Response {
+    match ContextModel::for_platform(app_state.db(), platform_id).await {
+        Ok(contexts) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                contexts
+                    .into_iter()
+                    .map(ContextResponse::from)
+                    .collect::<Vec<_>>(),
+                "LTI contexts retrieved",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to load LTI contexts")),
+        )
+            .into_response(),
+    }
+}
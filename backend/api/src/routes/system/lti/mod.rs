@@ -0,0 +1,77 @@
+//! # LTI Administration Routes
+//!
+//! Defines the `/system/lti` endpoint group: the LMS platforms and deployments allowed to
+//! launch FitchFork over LTI 1.3 (see `/lti`), the LMS courses linked to modules, and the
+//! tool's signing keys.
+//!
+//! ## Structure
+//! - `common.rs` — request/response bodies
+//! - `get.rs` — GET handlers (list platforms, courses or keys)
+//! - `post.rs` — POST handlers (register a platform or deployment, link a course, generate a key)
+//! - `put.rs` — PUT handlers (edit a platform, (de)activate a key)
+//! - `delete.rs` — DELETE handlers (remove a platform or deployment, unlink a course)
+//!
+//! ## Usage
+//! Mounted by `system_routes()`, which is admin only. Platforms decide who can sign in, so these
+//! need a login session; API tokens can't use them.
+
+use axum::{
+    Router,
+    middleware::from_fn,
+    routing::{delete, get, post, put},
+};
+use util::state::AppState;
+
+use crate::auth::guards::deny_api_tokens;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod post;
+pub mod put;
+
+/// Builds and returns the `/system/lti` route group.
+///
+/// Routes:
+/// - `GET    /lti/platforms`                                        → list platforms
+/// - `POST   /lti/platforms`                                        → register a platform
+/// - `PUT    /lti/platforms/{platform_id}`                          → edit a platform
+/// - `DELETE /lti/platforms/{platform_id}`                          → remove a platform
+/// - `POST   /lti/platforms/{platform_id}/deployments`              → register a deployment
+/// - `DELETE /lti/platforms/{platform_id}/deployments/{deployment_id}` → remove a deployment
+/// - `GET    /lti/platforms/{platform_id}/contexts`                 → list linked courses
+/// - `POST   /lti/platforms/{platform_id}/contexts`                 → link a course to a module
+/// - `DELETE /lti/platforms/{platform_id}/contexts/{context_id}`    → unlink a course
+/// - `GET    /lti/keys`                                             → list the tool's keys
+/// - `POST   /lti/keys`                                             → generate a key
+/// - `PUT    /lti/keys/{key_id}`                                    → (de)activate a key
+pub fn lti_admin_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/platforms",
+            get(get::list_platforms).post(post::create_platform),
+        )
+        .route(
+            "/platforms/{platform_id}",
+            put(put::update_platform).delete(delete::delete_platform),
+        )
+        .route(
+            "/platforms/{platform_id}/deployments",
+            post(post::add_deployment),
+        )
+        .route(
+            "/platforms/{platform_id}/deployments/{deployment_id}",
+            delete(delete::delete_deployment),
+        )
+        .route(
+            "/platforms/{platform_id}/contexts",
+            get(get::list_contexts).post(post::add_context),
+        )
+        .route(
+            "/platforms/{platform_id}/contexts/{context_id}",
+            delete(delete::delete_context),
+        )
+        .route("/keys", get(get::list_keys).post(post::create_key))
+        .route("/keys/{key_id}", put(put::update_key))
+        .route_layer(from_fn(deny_api_tokens))
+}
@@ -0,0 +1,296 @@
+use super::common::{
+    ContextResponse, DeploymentResponse, KeyResponse, PlatformRequest, PlatformResponse, invalid,
+};
+use crate::response::ApiResponse;
+use crate::services::lti::generate_key;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    lti_context::Model as ContextModel,
+    lti_deployment::Model as DeploymentModel,
+    lti_platform::{Entity as PlatformEntity, Model as PlatformModel},
+    module::Entity as ModuleEntity,
+};
+use sea_orm::EntityTrait;
+use serde::Deserialize;
+use util::state::AppState;
+
+/// POST `/api/system/lti/platforms`
+///
+/// Registers an LMS as an LTI 1.3 platform. Admin only.
+///
+/// ### Request Body
+/// ```json
+/// {
+///   "name": "ClickUP",
+///   "issuer": "https://clickup.up.ac.za",
+///   "client_id": "170000000000123",
+///   "auth_login_url": "https://clickup.up.ac.za/api/lti/authorize_redirect",
+///   "auth_token_url": "https://clickup.up.ac.za/login/oauth2/token",
+///   "keyset_url": "https://clickup.up.ac.za/api/lti/security/jwks"
+/// }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — The platform
+/// - `400 Bad Request` — The problems with the platform, in `data`
+/// - `409 Conflict` — The issuer and client id are already registered
+pub async fn create_platform(
+    State(app_state): State<AppState>,
+    Json(req): Json<PlatformRequest>,
+) -> Response {
+    let db = app_state.db();
+    let fields = match req.validate() {
+        Ok(f) => f,
+        Err(errors) => return invalid(errors),
+    };
+    match PlatformModel::find_by_issuer(db, fields.issuer, Some(fields.client_id)).await {
+        Ok(existing) if existing.is_empty() => {}
+        Ok(_) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(
+                    "This issuer and client id are already registered",
+                )),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check LTI platforms")),
+            )
+                .into_response();
+        }
+    }
+
+    match PlatformModel::create(db, &fields).await {
+        Ok(platform) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                PlatformResponse::new(platform, Vec::new()),
+                "LTI platform registered",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to register LTI platform")),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeploymentRequest {
+    pub deployment_id: String,
+}
+
+/// POST `/api/system/lti/platforms/{platform_id}/deployments`
+///
+/// Allows launches from one of the platform's deployments of the tool. Admin only.
+///
+/// ### Request Body
+/// ```json
+/// { "deployment_id": "12:8865aa05b4b79b64" }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — The deployment
+/// - `400 Bad Request` — Empty deployment id
+/// - `404 Not Found` — No such platform
+/// - `409 Conflict` — Already registered
+pub async fn add_deployment(
+    State(app_state): State<AppState>,
+    Path(platform_id): Path<i64>,
+    Json(req): Json<DeploymentRequest>,
+) -> Response {
+    let db = app_state.db();
+    let deployment_id = req.deployment_id.trim();
+    if deployment_id.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("deployment_id is required")),
+        )
+            .into_response();
+    }
+    match PlatformEntity::find_by_id(platform_id).one(db).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("LTI platform not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load LTI platform")),
+            )
+                .into_response();
+        }
+    }
+    match DeploymentModel::exists(db, platform_id, deployment_id).await {
+        Ok(false) => {}
+        Ok(true) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error("Deployment already registered")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check deployments")),
+            )
+                .into_response();
+        }
+    }
+
+    match DeploymentModel::create(db, platform_id, deployment_id).await {
+        Ok(deployment) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                DeploymentResponse::from(deployment),
+                "Deployment registered",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to register deployment")),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContextRequest {
+    pub context_id: String,
+    pub module_id: i64,
+}
+
+/// POST `/api/system/lti/platforms/{platform_id}/contexts`
+///
+/// Links one of the platform's courses to a module. A placement's first launch must come from
+/// a linked course, and opens its module; LMS instructors can't choose the module themselves.
+/// Admin only.
+///
+/// ### Request Body
+/// ```json
+/// { "context_id": "c-301", "module_id": 4 }
+/// ```
+///
+/// ### Responses
+/// - `201 Created` — The link
+/// - `400 Bad Request` — Empty context id
+/// - `404 Not Found` — No such platform or module
+/// - `409 Conflict` — The course is already linked
+pub async fn add_context(
+    State(app_state): State<AppState>,
+    Path(platform_id): Path<i64>,
+    Json(req): Json<ContextRequest>,
+) -> Response {
+    let db = app_state.db();
+    let context_id = req.context_id.trim();
+    if context_id.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error("context_id is required")),
+        )
+            .into_response();
+    }
+    let platform = PlatformEntity::find_by_id(platform_id).one(db).await;
+    let module = ModuleEntity::find_by_id(req.module_id).one(db).await;
+    match (platform, module) {
+        (Ok(Some(_)), Ok(Some(_))) => {}
+        (Ok(None), _) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("LTI platform not found")),
+            )
+                .into_response();
+        }
+        (_, Ok(None)) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("Module not found")),
+            )
+                .into_response();
+        }
+        _ => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to load LTI platform")),
+            )
+                .into_response();
+        }
+    }
+    match ContextModel::find(db, platform_id, context_id).await {
+        Ok(None) => {}
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error("Context already linked")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check LTI contexts")),
+            )
+                .into_response();
+        }
+    }
+
+    match ContextModel::create(db, platform_id, context_id, req.module_id).await {
+        Ok(context) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                ContextResponse::from(context),
+                "LTI context linked",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to link LTI context")),
+        )
+            .into_response(),
+    }
+}
+
+/// POST `/api/system/lti/keys`
+///
+/// Generates a 2048-bit RSA signing key. The newest active key signs; older keys stay on the
+/// JWKS endpoint until deactivated, so platforms can refresh before a rotation completes.
+/// Admin only.
+///
+/// ### Responses
+/// - `201 Created` — `{ "id": 2, "kid": "1f2e3d4c5b6a7980", "active": true, "created_at": "..." }`
+pub async fn create_key(State(app_state): State<AppState>) -> Response {
+    match generate_key(app_state.db()).await {
+        Ok(key) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(
+                KeyResponse::from(key),
+                "LTI key generated",
+            )),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error(format!(
+                "Failed to generate LTI key: {e}"
+            ))),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,134 @@
+use super::common::{KeyResponse, PlatformRequest, PlatformResponse, invalid};
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::{
+    lti_deployment::Model as DeploymentModel, lti_key::Model as KeyModel,
+    lti_platform::Model as PlatformModel,
+};
+use sea_orm::DbErr;
+use serde::Deserialize;
+use util::state::AppState;
+
+/// PUT `/api/system/lti/platforms/{platform_id}`
+///
+/// Replaces a platform's registration. Same body as `POST /api/system/lti/platforms`. Admin
+/// only.
+///
+/// ### Responses
+/// - `200 OK` — The platform
+/// - `400 Bad Request` — The problems with the platform, in `data`
+/// - `404 Not Found` — No such platform
+/// - `409 Conflict` — Another platform has this issuer and client id
+pub async fn update_platform(
+    State(app_state): State<AppState>,
+    Path(platform_id): Path<i64>,
+    Json(req): Json<PlatformRequest>,
+) -> Response {
+    let db = app_state.db();
+    let fields = match req.validate() {
+        Ok(f) => f,
+        Err(errors) => return invalid(errors),
+    };
+    match PlatformModel::find_by_issuer(db, fields.issuer, Some(fields.client_id)).await {
+        Ok(existing) if existing.iter().all(|p| p.id == platform_id) => {}
+        Ok(_) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::<()>::error(
+                    "This issuer and client id are already registered",
+                )),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to check LTI platforms")),
+            )
+                .into_response();
+        }
+    }
+
+    let platform = match PlatformModel::update(db, platform_id, &fields).await {
+        Ok(p) => p,
+        Err(DbErr::RecordNotFound(_)) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error("LTI platform not found")),
+            )
+                .into_response();
+        }
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to update LTI platform")),
+            )
+                .into_response();
+        }
+    };
+    match DeploymentModel::for_platform(db, platform_id).await {
+        Ok(deployments) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                PlatformResponse::new(platform, deployments),
+                "LTI platform updated",
+            )),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to load LTI deployments")),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct KeyUpdateRequest {
+    pub active: bool,
+}
+
+/// PUT `/api/system/lti/keys/{key_id}`
+///
+/// Activates or deactivates a signing key. Deactivated keys are no longer published or used.
+/// Admin only.
+///
+/// ### Request Body
+/// ```json
+/// { "active": false }
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The key
+/// - `404 Not Found` — No such key
+pub async fn update_key(
+    State(app_state): State<AppState>,
+    Path(key_id): Path<i64>,
+    Json(req): Json<KeyUpdateRequest>,
+) -> Response {
+    match KeyModel::set_active(app_state.db(), key_id, req.active).await {
+        Ok(key) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(
+                KeyResponse::from(key),
+                "LTI key updated",
+            )),
+        )
+            .into_response(),
+        Err(DbErr::RecordNotFound(_)) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error("LTI key not found")),
+        )
+            .into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<()>::error("Failed to update LTI key")),
+        )
+            .into_response(),
+    }
+}
@@ -1,9 +1,16 @@
-use axum::{Router, middleware::from_fn, routing::get};
+use axum::{
+    Router,
+    middleware::from_fn,
+    routing::{get, post},
+};
 use util::state::AppState;
 
-use crate::auth::guards::allow_admin;
+use crate::auth::guards::{allow_admin, deny_api_tokens};
 
+pub mod api_tokens;
 pub mod get;
+pub mod grade_export_templates;
+pub mod lti;
 pub mod post;
 
 pub fn system_routes() -> Router<AppState> {
@@ -12,12 +19,26 @@ pub fn system_routes() -> Router<AppState> {
             "/code-manager/max-concurrent",
             get(get::get_max_concurrent_handler).post(post::set_max_concurrent_handler),
         )
+        .route("/config/reload", post(post::reload_config_handler))
         .route("/metrics", get(get::get_metrics))
         .route("/metrics/export", get(get::get_metrics_csv))
         .route("/submissions", get(get::submissions_over_time))
+        .route(
+            "/two-factor",
+            // Staff two-factor enforcement is login policy; API tokens can't touch it
+            get(get::get_two_factor_policy)
+                .post(post::set_two_factor_policy)
+                .route_layer(from_fn(deny_api_tokens)),
+        )
         .route(
             "/submissions/export",
             get(get::submissions_over_time_export),
         )
+        .nest(
+            "/grade-export-templates",
+            grade_export_templates::grade_export_template_routes(),
+        )
+        .nest("/lti", lti::lti_admin_routes())
+        .nest("/api-tokens", api_tokens::api_token_admin_routes())
         .route_layer(from_fn(allow_admin))
 }
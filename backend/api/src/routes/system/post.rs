@@ -1,7 +1,11 @@
+use super::get::TwoFactorPolicy;
 use crate::response::ApiResponse;
-use axum::{Json, http::StatusCode};
+use axum::{Json, extract::State, http::StatusCode};
+use code_runner::code_manager_client::{self, http_base_url, with_auth};
+use db::models::system_setting;
 use serde::{Deserialize, Serialize};
-use util::config;
+use util::config::{self, LiveConfig};
+use util::state::AppState;
 
 #[derive(Deserialize)]
 pub struct SetMaxConcurrentRequest {
@@ -23,16 +27,12 @@ pub async fn set_max_concurrent_handler(
         );
     }
 
-    let url = format!(
-        "http://{}:{}/max_concurrent",
-        config::code_manager_host(),
-        config::code_manager_port()
-    );
+    let url = format!("{}/max_concurrent", http_base_url());
     let body = CodeManagerSetReq {
         max_concurrent: req.max_concurrent,
     };
     let client = reqwest::Client::new();
-    match client.post(url).json(&body).send().await {
+    match with_auth(client.post(url)).json(&body).send().await {
         Ok(resp) if resp.status().is_success() => (
             StatusCode::OK,
             Json(ApiResponse::success(req.max_concurrent, "Updated")),
@@ -52,3 +52,84 @@ pub async fn set_max_concurrent_handler(
         ),
     }
 }
+
+#[derive(Serialize)]
+pub struct ConfigReloadResponse {
+    /// Live settings whose value changed.
+    pub changed: Vec<&'static str>,
+    /// Live settings now in effect.
+    pub config: LiveConfig,
+}
+
+/// POST /api/system/config/reload
+///
+/// Re-reads the configuration and applies the reloadable values (code_manager host/port,
+/// `MAX_NUM_CONTAINERS`, health broadcast/persist intervals) without a restart; see
+/// [`util::config::reload`]. A changed `MAX_NUM_CONTAINERS` is pushed to code_manager.
+///
+/// - `200 OK` with the changed fields and current values. If code_manager could not be
+///   updated the message says so; the new limit applies once it is set there.
+/// - `500 Internal Server Error` if the config is invalid; nothing is changed.
+pub async fn reload_config_handler() -> (StatusCode, Json<ApiResponse<ConfigReloadResponse>>) {
+    let changed = match config::reload() {
+        Ok(changed) => changed,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::error(format!("Config reload failed: {e}"))),
+            );
+        }
+    };
+    let live = config::live();
+    tracing::info!("Config reloaded via API; changed: {:?}", changed);
+
+    let mut message = "Config reloaded".to_string();
+    if changed.contains(&"max_number_containers") {
+        let client = reqwest::Client::new();
+        if let Err(e) =
+            code_manager_client::set_max_concurrent(&client, live.max_number_containers).await
+        {
+            message = format!("Config reloaded, but code_manager was not updated: {e}");
+        }
+    }
+
+    let body = ConfigReloadResponse {
+        changed,
+        config: (*live).clone(),
+    };
+    (StatusCode::OK, Json(ApiResponse::success(body, &message)))
+}
+
+/// POST /api/system/two-factor
+///
+/// Sets the two-factor authentication policy. With `require_staff` on, staff without two-factor
+/// authentication must set it up at their next login, and staff can no longer disable it.
+/// Sessions already issued are unaffected.
+///
+/// ### Request Body
+/// ```json
+/// { "require_staff": true }
+/// ```
+///
+/// - `200 OK` with the policy now in effect
+pub async fn set_two_factor_policy(
+    State(app_state): State<AppState>,
+    Json(req): Json<TwoFactorPolicy>,
+) -> (StatusCode, Json<ApiResponse<TwoFactorPolicy>>) {
+    match system_setting::Model::set_flag(
+        app_state.db(),
+        system_setting::REQUIRE_STAFF_TWO_FACTOR,
+        req.require_staff,
+    )
+    .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(req, "Two-factor policy updated")),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::error(format!("Database error: {e}"))),
+        ),
+    }
+}
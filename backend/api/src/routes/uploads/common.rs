@@ -0,0 +1,82 @@
+use crate::response::ApiResponse;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::upload_session::Model as UploadModel;
+use sea_orm::{DatabaseConnection, DbErr};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct UploadResponse {
+    pub id: i64,
+    pub filename: String,
+    pub size: i64,
+    pub sha256: String,
+    pub chunk_size: i64,
+    pub total_parts: i32,
+    /// Part numbers received so far; empty once the upload is complete.
+    pub received_parts: Vec<i32>,
+    pub completed: bool,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+impl UploadResponse {
+    pub async fn load(db: &DatabaseConnection, upload: &UploadModel) -> Result<Self, DbErr> {
+        Ok(Self {
+            id: upload.id,
+            filename: upload.filename.clone(),
+            size: upload.size,
+            sha256: upload.sha256.clone(),
+            chunk_size: upload.chunk_size,
+            total_parts: upload.total_parts(),
+            received_parts: upload.received_parts(db).await?,
+            completed: upload.completed_at.is_some(),
+            expires_at: upload.expires_at.to_rfc3339(),
+            created_at: upload.created_at.to_rfc3339(),
+        })
+    }
+}
+
+pub fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ApiResponse::<()>::error(message))).into_response()
+}
+
+/// The caller's unexpired upload, or the error response to send.
+pub async fn find_upload(
+    db: &DatabaseConnection,
+    upload_id: i64,
+    user_id: i64,
+) -> Result<UploadModel, Response> {
+    match UploadModel::find_for_user(db, upload_id, user_id).await {
+        Ok(Some(upload)) => Ok(upload),
+        Ok(None) => Err(error(StatusCode::NOT_FOUND, "Upload not found")),
+        Err(_) => Err(error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to load upload",
+        )),
+    }
+}
+
+/// The filename and bytes of the caller's completed upload `upload_id`, for handlers that take
+/// an `upload_id` in place of a file. The error is the message to reject the request with.
+///
+/// The upload is left in place; call [`UploadModel::discard`] once it has been used.
+pub async fn read_completed_upload(
+    db: &DatabaseConnection,
+    upload_id: i64,
+    user_id: i64,
+) -> Result<(UploadModel, Vec<u8>), String> {
+    let upload = match UploadModel::find_for_user(db, upload_id, user_id).await {
+        Ok(Some(upload)) if upload.completed_at.is_some() => upload,
+        Ok(Some(_)) => return Err(format!("Upload {upload_id} is not complete")),
+        Ok(None) => return Err(format!("Upload {upload_id} not found")),
+        Err(_) => return Err("Failed to load upload".to_string()),
+    };
+    match upload.read().await {
+        Ok(bytes) => Ok((upload, bytes)),
+        Err(_) => Err("Failed to read upload".to_string()),
+    }
+}
@@ -0,0 +1,39 @@
+use super::common::{error, find_upload};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use util::state::AppState;
+
+/// DELETE /api/uploads/{upload_id}
+///
+/// Abandons one of the caller's chunked uploads and deletes what it stored.
+///
+/// ### Responses
+/// - `200 OK` — Discarded
+/// - `404 Not Found` — No such upload of the caller's, or it has expired
+pub async fn delete_upload(
+    State(app_state): State<AppState>,
+    Path(upload_id): Path<i64>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> Response {
+    let db = app_state.db();
+    let upload = match find_upload(db, upload_id, claims.sub).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    match upload.discard(db).await {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(ApiResponse::<()>::success_without_data("Upload discarded")),
+        )
+            .into_response(),
+        Err(_) => error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to discard upload",
+        ),
+    }
+}
@@ -0,0 +1,37 @@
+use super::common::{UploadResponse, error, find_upload};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use util::state::AppState;
+
+/// GET /api/uploads/{upload_id}
+///
+/// The state of one of the caller's chunked uploads; `received_parts` tells a client resuming
+/// an interrupted upload which parts still need to be sent.
+///
+/// ### Responses
+/// - `200 OK` — The upload
+/// - `404 Not Found` — No such upload of the caller's, or it has expired
+pub async fn get_upload(
+    State(app_state): State<AppState>,
+    Path(upload_id): Path<i64>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> Response {
+    let db = app_state.db();
+    let upload = match find_upload(db, upload_id, claims.sub).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    match UploadResponse::load(db, &upload).await {
+        Ok(body) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(body, "Upload retrieved")),
+        )
+            .into_response(),
+        Err(_) => error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load upload"),
+    }
+}
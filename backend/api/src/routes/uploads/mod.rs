@@ -0,0 +1,52 @@
+//! # Upload Routes
+//!
+//! Defines the `/uploads` endpoint group: resumable chunked uploads for files too large to send
+//! reliably in one request. A completed upload's id is accepted as `upload_id` in place of
+//! `file` by the submission and assignment-file uploads.
+//!
+//! ## Structure
+//! - `common.rs` — response body and lookup helpers
+//! - `get.rs` — GET handlers (upload state, for resuming)
+//! - `post.rs` — POST handlers (start and complete an upload)
+//! - `put.rs` — PUT handlers (send a part)
+//! - `delete.rs` — DELETE handlers (abandon an upload)
+//!
+//! ## Usage
+//! Mounted by `routes()` behind `allow_authenticated`; every upload belongs to the user who
+//! started it.
+
+use axum::{
+    Router,
+    extract::DefaultBodyLimit,
+    routing::{get, post, put},
+};
+use db::models::upload_session::CHUNK_SIZE;
+use util::state::AppState;
+
+pub mod common;
+pub mod delete;
+pub mod get;
+pub mod post;
+pub mod put;
+
+/// Builds and returns the `/uploads` route group.
+///
+/// Routes:
+/// - `POST   /uploads`                                   → start an upload
+/// - `GET    /uploads/{upload_id}`                       → upload state and received parts
+/// - `DELETE /uploads/{upload_id}`                       → abandon an upload
+/// - `PUT    /uploads/{upload_id}/parts/{part_number}`   → send a part
+/// - `POST   /uploads/{upload_id}/complete`              → assemble and verify the file
+pub fn uploads_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", post(post::create_upload))
+        .route(
+            "/{upload_id}",
+            get(get::get_upload).delete(delete::delete_upload),
+        )
+        .route(
+            "/{upload_id}/parts/{part_number}",
+            put(put::upload_part).layer(DefaultBodyLimit::max(CHUNK_SIZE as usize)),
+        )
+        .route("/{upload_id}/complete", post(post::complete_upload))
+}
@@ -0,0 +1,139 @@
+use super::common::{UploadResponse, error, find_upload};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use db::models::upload_session::{CompleteError, Model as UploadModel};
+use serde::Deserialize;
+use util::{config, state::AppState};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUploadRequest {
+    pub filename: String,
+    pub size: i64,
+    pub sha256: String,
+}
+
+/// POST /api/uploads
+///
+/// Starts a chunked upload for a file too large to send reliably in one request. The file is
+/// then sent in `chunk_size` parts with `PUT /api/uploads/{upload_id}/parts/{part_number}` and
+/// assembled with `POST /api/uploads/{upload_id}/complete`, after which `upload_id` can be sent
+/// in place of `file` to the submission and assignment-file uploads.
+///
+/// ### Request Body
+/// ```json
+/// { "filename": "solution.zip", "size": 83886080, "sha256": "9f86d0...0a08" }
+/// ```
+/// - `sha256`: hex SHA-256 of the whole file, checked on completion
+///
+/// ### Responses
+/// - `201 Created` — The upload (`id`, `chunk_size`, `total_parts`, `received_parts`, ...)
+/// - `400 Bad Request` — Missing filename, non-positive size or malformed checksum
+/// - `413 Payload Too Large` — Larger than `MAX_UPLOAD_SIZE_MB`
+pub async fn create_upload(
+    State(app_state): State<AppState>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    Json(req): Json<CreateUploadRequest>,
+) -> Response {
+    let db = app_state.db();
+
+    // keep the final path component only; the name is reused when the file is stored
+    let filename = req.filename.rsplit(['/', '\\']).next().unwrap_or("").trim();
+    if filename.is_empty() {
+        return error(StatusCode::BAD_REQUEST, "filename is required");
+    }
+    if req.size <= 0 {
+        return error(StatusCode::BAD_REQUEST, "size must be positive");
+    }
+    let sha256 = req.sha256.trim();
+    if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+        return error(
+            StatusCode::BAD_REQUEST,
+            "sha256 must be a hex SHA-256 checksum",
+        );
+    }
+    let max_mb = config::max_upload_size_mb();
+    if req.size as u64 > max_mb * 1024 * 1024 {
+        return error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("File too large. Max size is {max_mb} MB"),
+        );
+    }
+
+    let upload = match UploadModel::create(db, claims.sub, filename, req.size, sha256).await {
+        Ok(u) => u,
+        Err(_) => {
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to start upload");
+        }
+    };
+    match UploadResponse::load(db, &upload).await {
+        Ok(body) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::success(body, "Upload started")),
+        )
+            .into_response(),
+        Err(_) => error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load upload"),
+    }
+}
+
+/// POST /api/uploads/{upload_id}/complete
+///
+/// Assembles the received parts and checks the file against the size and checksum given when
+/// the upload started. Completing a completed upload returns it unchanged.
+///
+/// ### Responses
+/// - `200 OK` — The completed upload
+/// - `404 Not Found` — No such upload of the caller's, or it has expired
+/// - `409 Conflict` — Parts are missing; their numbers are in `data.missing_parts`
+/// - `422 Unprocessable Entity` — The assembled file does not match its checksum. The parts
+///   are dropped and must be sent again.
+pub async fn complete_upload(
+    State(app_state): State<AppState>,
+    Path(upload_id): Path<i64>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+) -> Response {
+    let db = app_state.db();
+    let upload = match find_upload(db, upload_id, claims.sub).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+
+    let upload = match upload.complete(db).await {
+        Ok(u) => u,
+        Err(CompleteError::MissingParts(missing)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(ApiResponse::error_with_data(
+                    serde_json::json!({ "missing_parts": missing }),
+                    "Upload is missing parts",
+                )),
+            )
+                .into_response();
+        }
+        Err(CompleteError::ChecksumMismatch) => {
+            return error(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "The assembled file does not match its checksum; send the parts again",
+            );
+        }
+        Err(CompleteError::Db(_)) => {
+            return error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to complete upload",
+            );
+        }
+    };
+
+    match UploadResponse::load(db, &upload).await {
+        Ok(body) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(body, "Upload complete")),
+        )
+            .into_response(),
+        Err(_) => error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load upload"),
+    }
+}
@@ -0,0 +1,99 @@
+use super::common::{UploadResponse, error, find_upload};
+use crate::{auth::AuthUser, response::ApiResponse};
+use axum::{
+    Extension, Json,
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use db::models::upload_session::sha256_hex;
+use util::state::AppState;
+
+/// Header carrying a part's hex SHA-256.
+pub const PART_CHECKSUM_HEADER: &str = "x-part-sha256";
+
+/// PUT /api/uploads/{upload_id}/parts/{part_number}
+///
+/// Sends one part of a chunked upload as the raw request body. Parts are numbered from 0; each
+/// is `chunk_size` bytes except the last, which holds the rest. Parts may arrive in any order,
+/// and sending a part again replaces it, so a client resumes an interrupted upload by sending
+/// the parts missing from `GET /api/uploads/{upload_id}`.
+///
+/// ### Headers
+/// - `X-Part-SHA256` (required): hex SHA-256 of the part
+///
+/// ### Example Request
+/// ```bash
+/// curl -X PUT http://localhost:3000/api/uploads/7/parts/0 \
+///   -H "Authorization: Bearer <token>" \
+///   -H "X-Part-SHA256: $(sha256sum part0 | cut -d' ' -f1)" \
+///   --data-binary @part0
+/// ```
+///
+/// ### Responses
+/// - `200 OK` — The upload, with the part in `received_parts`
+/// - `400 Bad Request` — No such part number, wrong part size or missing checksum header
+/// - `404 Not Found` — No such upload of the caller's, or it has expired
+/// - `409 Conflict` — The upload is already complete
+/// - `422 Unprocessable Entity` — The part does not match its checksum
+pub async fn upload_part(
+    State(app_state): State<AppState>,
+    Path((upload_id, part_number)): Path<(i64, i32)>,
+    Extension(AuthUser(claims)): Extension<AuthUser>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let db = app_state.db();
+    let upload = match find_upload(db, upload_id, claims.sub).await {
+        Ok(u) => u,
+        Err(resp) => return resp,
+    };
+    if upload.completed_at.is_some() {
+        return error(StatusCode::CONFLICT, "Upload already completed");
+    }
+
+    let Some(expected_size) = upload.part_size(part_number) else {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Part {part_number} is out of range; this upload has parts 0 to {}",
+                upload.total_parts() - 1
+            ),
+        );
+    };
+    let Some(checksum) = headers
+        .get(PART_CHECKSUM_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase())
+    else {
+        return error(StatusCode::BAD_REQUEST, "Missing X-Part-SHA256 header");
+    };
+    if body.len() as i64 != expected_size {
+        return error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Part {part_number} must be {expected_size} bytes, got {}",
+                body.len()
+            ),
+        );
+    }
+    if sha256_hex(&body) != checksum {
+        return error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Part {part_number} does not match its checksum"),
+        );
+    }
+
+    if upload.save_part(db, part_number, &body).await.is_err() {
+        return error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to store part");
+    }
+    match UploadResponse::load(db, &upload).await {
+        Ok(body) => (
+            StatusCode::OK,
+            Json(ApiResponse::success(body, "Part received")),
+        )
+            .into_response(),
+        Err(_) => error(StatusCode::INTERNAL_SERVER_ERROR, "Failed to load upload"),
+    }
+}
@@ -1,5 +1,12 @@
+use std::collections::HashSet;
+
+use db::models::user::{Column as UserCol, Entity as UserEntity, Model as UserModel};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use util::csv;
+use validator::{Validate, ValidateEmail};
 
 #[derive(Debug, Deserialize, Serialize, Validate)]
 pub struct CreateUserRequest {
@@ -42,3 +49,148 @@ impl From<db::models::user::Model> for UserResponse {
         }
     }
 }
+
+/// One row of an uploaded class list or module roster.
+#[derive(Debug, Clone)]
+pub struct ImportedUser {
+    /// 1-based line number in the uploaded file.
+    pub line: usize,
+    pub username: String,
+    pub email: Option<String>,
+    pub password: Option<String>,
+    /// Module role, for rosters.
+    pub role: Option<String>,
+}
+
+/// A CSV row that could not be imported.
+#[derive(Debug, Serialize)]
+pub struct ImportRowError {
+    pub line: usize,
+    pub username: String,
+    pub message: String,
+}
+
+impl ImportRowError {
+    pub fn new(row: &ImportedUser, message: impl Into<String>) -> Self {
+        Self {
+            line: row.line,
+            username: row.username.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Reads an uploaded class list: a header row, then one user per row. The username column may
+/// be called `username` or `student_number` (usernames are student numbers); `email`,
+/// `password` and `role` are optional columns and anything else is ignored.
+///
+/// Rows without a username, or repeating one from an earlier row, come back as errors. `Err`
+/// means the file itself is unusable.
+pub fn read_user_csv(text: &str) -> Result<(Vec<ImportedUser>, Vec<ImportRowError>), &'static str> {
+    let mut records = csv::parse(text).into_iter();
+    let Some((_, header)) = records.next() else {
+        return Err("CSV file is empty");
+    };
+    let username_col = csv::column(&header, &["username"]);
+    let number_col = csv::column(&header, &["student_number", "student number"]);
+    if username_col.is_none() && number_col.is_none() {
+        return Err("CSV must have a username or student_number column");
+    }
+    let email_col = csv::column(&header, &["email"]);
+    let password_col = csv::column(&header, &["password"]);
+    let role_col = csv::column(&header, &["role"]);
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen = HashSet::new();
+    for (line, fields) in records {
+        let field = |col: Option<usize>| {
+            col.and_then(|c| fields.get(c))
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+        };
+        let (username, number) = (field(username_col), field(number_col));
+        let row = ImportedUser {
+            line,
+            username: username.clone().or(number.clone()).unwrap_or_default(),
+            email: field(email_col),
+            password: field(password_col),
+            role: field(role_col),
+        };
+
+        if row.username.is_empty() {
+            errors.push(ImportRowError::new(&row, "Username required"));
+        } else if matches!((&username, &number), (Some(u), Some(n)) if u != n) {
+            errors.push(ImportRowError::new(
+                &row,
+                "username and student_number must match",
+            ));
+        } else if !seen.insert(row.username.clone()) {
+            errors.push(ImportRowError::new(&row, "Username repeated in the file"));
+        } else {
+            rows.push(row);
+        }
+    }
+    Ok((rows, errors))
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserImportResponse {
+    pub created: usize,
+    /// Rows for users that already existed.
+    pub existing: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// Whether [`find_or_create_user`] found the user or had to create them.
+pub enum ImportedUserOutcome {
+    Created(UserModel),
+    Existing(UserModel),
+}
+
+/// Finds the row's user by username, creating a non-admin user when there is none. New users
+/// need an `email`; without a `password` they get a random one and sign in after resetting it.
+pub async fn find_or_create_user(
+    db: &DatabaseConnection,
+    row: &ImportedUser,
+) -> Result<ImportedUserOutcome, String> {
+    let lookup_failed = |_| "Failed to look up user".to_string();
+    if let Some(existing) = UserEntity::find()
+        .filter(UserCol::Username.eq(row.username.as_str()))
+        .one(db)
+        .await
+        .map_err(lookup_failed)?
+    {
+        return Ok(ImportedUserOutcome::Existing(existing));
+    }
+
+    let Some(email) = row.email.as_deref() else {
+        return Err("Email required to create a user".into());
+    };
+    if !email.validate_email() {
+        return Err("Invalid email".into());
+    }
+    if UserEntity::find()
+        .filter(UserCol::Email.eq(email))
+        .one(db)
+        .await
+        .map_err(lookup_failed)?
+        .is_some()
+    {
+        return Err("Email already belongs to another user".into());
+    }
+    let password = match &row.password {
+        Some(p) if p.len() < 6 => return Err("Password must be at least 6 characters".into()),
+        Some(p) => p.clone(),
+        None => thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(24)
+            .map(char::from)
+            .collect(),
+    };
+
+    UserModel::create(db, &row.username, email, &password, false)
+        .await
+        .map(ImportedUserOutcome::Created)
+        .map_err(|e| format!("Failed to create user: {e}"))
+}
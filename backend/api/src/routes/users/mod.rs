@@ -4,6 +4,7 @@
 //!
 //! ## Structure
 //! - `get.rs` — GET handlers (e.g., list users)
+//! - `post.rs` — POST handlers (create users, one at a time, in bulk or from a CSV)
 //! - `put.rs` — PUT handlers (e.g., update user)
 //! - `delete.rs` — DELETE handlers (e.g., delete user)
 //!
@@ -20,7 +21,7 @@ use axum::{
 };
 use delete::delete_user;
 use get::{get_user, get_user_modules, list_users};
-use post::{bulk_create_users, create_user};
+use post::{bulk_create_users, create_user, import_users};
 use put::update_user;
 use util::state::AppState;
 
@@ -35,6 +36,7 @@ pub mod put;
 /// - `GET /users` → `list_users` (admin only)
 /// - `POST /users` → `create_user` (admin only)
 /// - `POST /users/bulk` → `bulk_create_users` (admin only)
+/// - `POST /users/import` → `import_users` from a CSV class list (admin only)
 /// - `GET /users/{user_id}/modules` → `get_user_modules` (admin only)
 /// - `GET /users/{user_id}` → `get_user` (admin only)
 /// - `PUT /users/{user_id}` → `update_user` (admin only)
@@ -49,6 +51,7 @@ pub fn users_routes() -> Router<AppState> {
         .route("/", get(list_users))
         .route("/", post(create_user))
         .route("/bulk", post(bulk_create_users))
+        .route("/import", post(import_users))
         .route("/{user_id}/modules", get(get_user_modules))
         .route("/{user_id}", get(get_user))
         .route("/{user_id}", put(update_user))
@@ -2,11 +2,15 @@
 //!
 //! - `POST /api/users`: Create a single non-admin user
 //! - `POST /api/users/bulk`: Create multiple non-admin users
+//! - `POST /api/users/import`: Create non-admin users from a CSV class list
 //!
 //! All routes require admin privileges.
 
 use crate::response::ApiResponse;
-use crate::routes::users::common::{BulkCreateUsersRequest, CreateUserRequest, UserResponse};
+use crate::routes::users::common::{
+    BulkCreateUsersRequest, CreateUserRequest, ImportRowError, ImportedUserOutcome,
+    UserImportResponse, UserResponse, find_or_create_user, read_user_csv,
+};
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
 use db::models::user::Model as UserModel;
 use util::state::AppState;
@@ -136,3 +140,80 @@ pub async fn bulk_create_users(
     )
         .into_response()
 }
+
+/// POST /api/users/import
+///
+/// Creates **non-admin** users from a CSV class list, for cohorts too large to add one at a
+/// time. Admin-only access.
+///
+/// ### Request Body (`text/csv`)
+/// A header row, then one user per row:
+/// ```csv
+/// username,email,password
+/// u12345678,u12345678@tuks.co.za,
+/// u87654321,u87654321@tuks.co.za,hunter22
+/// ```
+/// - `username` (or `student_number`) and `email` are required; other columns are ignored.
+/// - `password` is optional; users without one get a random password and sign in after
+///   resetting it.
+///
+/// Users whose username already exists (with the same email) are left alone. Every other
+/// problem is reported against its line and the row skipped; the rest are still created.
+///
+/// ### Response: 200 OK
+/// ```json
+/// {
+///   "success": true,
+///   "message": "Created 1 user",
+///   "data": {
+///     "created": 1,
+///     "existing": 0,
+///     "errors": [ { "line": 3, "username": "u87654321", "message": "Invalid email" } ]
+///   }
+/// }
+/// ```
+///
+/// ### Errors:
+/// - 400 Bad Request — Empty file or no username column
+pub async fn import_users(State(app_state): State<AppState>, body: String) -> impl IntoResponse {
+    let db = app_state.db();
+
+    let (rows, mut errors) = match read_user_csv(&body) {
+        Ok(read) => read,
+        Err(msg) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::<()>::error(msg))).into_response();
+        }
+    };
+
+    let (mut created, mut existing) = (0, 0);
+    for row in &rows {
+        match find_or_create_user(db, row).await {
+            Ok(ImportedUserOutcome::Created(_)) => created += 1,
+            Ok(ImportedUserOutcome::Existing(user)) => match &row.email {
+                Some(email) if !email.eq_ignore_ascii_case(&user.email) => errors.push(
+                    ImportRowError::new(row, "Username already exists with a different email"),
+                ),
+                _ => existing += 1,
+            },
+            Err(msg) => errors.push(ImportRowError::new(row, msg)),
+        }
+    }
+    errors.sort_by_key(|e| e.line);
+
+    let message = match created {
+        1 => "Created 1 user".to_string(),
+        n => format!("Created {n} users"),
+    };
+    (
+        StatusCode::OK,
+        Json(ApiResponse::success(
+            UserImportResponse {
+                created,
+                existing,
+                errors,
+            },
+            message,
+        )),
+    )
+        .into_response()
+}
@@ -0,0 +1,110 @@
+//! Announcement publishing.
+//!
+//! [`publish`] tells a module's members that an announcement has gone live: an
+//! `announcement.published` event on the `module:{id}.announcements` topic and an
+//! `announcement_posted` notification for everyone but the author. Announcements are published
+//! when they are created unless they have a future `publish_at`; those are picked up by
+//! [`publish_due_announcements`], which `main` runs every [`PUBLISH_INTERVAL`].
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    announcements::Model as AnnouncementModel, module, notification::NotificationKind,
+    user_module_role,
+};
+use sea_orm::{ColumnTrait, DbErr, EntityTrait, QueryFilter, QuerySelect};
+use util::state::AppState;
+
+use crate::services::notifications::{self, NewNotification};
+use crate::ws::announcements::{emit as a_emit, payload as a_payload};
+
+/// How often scheduled announcements are checked for.
+pub const PUBLISH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Publishes every scheduled announcement whose time has come as of `now`, returning how many
+/// were published.
+pub async fn publish_due_announcements(app: &AppState, now: DateTime<Utc>) -> Result<usize, DbErr> {
+    let db = app.db();
+    let due = AnnouncementModel::due_for_publishing(db, now).await?;
+
+    let mut published = 0;
+    for announcement in due {
+        // Another sweep may have got there first
+        if !AnnouncementModel::mark_published(db, announcement.id).await? {
+            continue;
+        }
+        published += 1;
+        publish(app, &announcement).await;
+    }
+
+    Ok(published)
+}
+
+/// Broadcasts the announcement to the module's topic and notifies its members.
+pub async fn publish(app: &AppState, announcement: &AnnouncementModel) {
+    a_emit::published(
+        app.ws(),
+        a_payload::Announcement {
+            id: announcement.id,
+            module_id: announcement.module_id,
+            user_id: announcement.user_id,
+            title: announcement.title.clone(),
+            body: announcement.body.clone(),
+            pinned: announcement.pinned,
+            published_at: announcement
+                .published_at
+                .unwrap_or_else(Utc::now)
+                .to_rfc3339(),
+            expires_at: announcement.expires_at.map(|at| at.to_rfc3339()),
+        },
+    )
+    .await;
+
+    notify_module_members(app, announcement).await;
+}
+
+/// Queues an `announcement_posted` notification for everyone in the module except the author.
+async fn notify_module_members(app_state: &AppState, announcement: &AnnouncementModel) {
+    let db = app_state.db();
+    let module_code = match module::Entity::find_by_id(announcement.module_id)
+        .one(db)
+        .await
+    {
+        Ok(Some(m)) => m.code,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to load module for announcement notification: {}", e);
+            return;
+        }
+    };
+    let recipients: Vec<i64> = match user_module_role::Entity::find()
+        .select_only()
+        .column(user_module_role::Column::UserId)
+        .filter(user_module_role::Column::ModuleId.eq(announcement.module_id))
+        .filter(user_module_role::Column::UserId.ne(announcement.user_id))
+        .into_tuple()
+        .all(db)
+        .await
+    {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::warn!("Failed to load announcement recipients: {}", e);
+            return;
+        }
+    };
+
+    notifications::spawn_notify(
+        app_state,
+        recipients,
+        NewNotification {
+            kind: NotificationKind::AnnouncementPosted,
+            title: format!("{}: {}", module_code, announcement.title),
+            body: announcement.body.clone(),
+            link: Some(format!(
+                "/modules/{}/announcements/{}",
+                announcement.module_id, announcement.id
+            )),
+        },
+    );
+}
@@ -1,8 +1,8 @@
 //! Email service module for handling email-related functionality.
 //!
-//! This module provides functionality for sending various types of emails using SMTP,
-//! specifically configured for Gmail. It uses the `lettre` crate for email handling
-//! and supports both plain text and HTML email formats.
+//! This module builds the account and marking emails (plain text and HTML) with the `lettre`
+//! crate and sends them through the shared SMTP client in `util::mail` (`SMTP_HOST`,
+//! `SMTP_PORT`; Gmail by default). Notification emails go through `services::email_outbox`.
 //!
 //! # Environment Variables Required
 //! - `GMAIL_USERNAME`: Gmail address to send emails from
@@ -10,34 +10,24 @@
 //! - `FRONTEND_URL`: Base URL of the frontend application
 //! - `EMAIL_FROM_NAME`: Display name for the sender
 
-use lettre::transport::smtp::client::{Tls, TlsParameters};
-use lettre::{
-    AsyncTransport, Tokio1Executor,
-    message::{Message, MultiPart, SinglePart, header},
-    transport::smtp::{AsyncSmtpTransport, authentication::Credentials},
-};
-use once_cell::sync::Lazy;
-use util::config;
+use lettre::message::{Message, MultiPart, SinglePart, header};
+use util::{config, mail};
 
-/// Global SMTP client instance configured for Gmail.
-///
-/// This is initialized lazily when first used, using environment variables
-/// for configuration. The client is configured to use TLS and requires
-/// authentication.
-static SMTP_CLIENT: Lazy<AsyncSmtpTransport<Tokio1Executor>> = Lazy::new(|| {
-    let username = config::gmail_username();
-    let password = config::gmail_app_password();
-
-    let tls_parameters =
-        TlsParameters::new("smtp.gmail.com".to_string()).expect("Failed to create TLS parameters");
-
-    AsyncSmtpTransport::<Tokio1Executor>::relay("smtp.gmail.com")
-        .expect("Failed to create SMTP transport")
-        .port(587)
-        .tls(Tls::Required(tls_parameters))
-        .credentials(Credentials::new(username, password))
-        .build()
-});
+/// Tiny helper to avoid extra crates
+pub(crate) fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for ch in input.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
 
 /// Service for handling email-related operations.
 pub struct EmailService;
@@ -130,10 +120,9 @@ impl EmailService {
                     ),
             )?;
 
-        match SMTP_CLIENT.send(email).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Box::new(e) as Box<dyn std::error::Error>),
-        }
+        mail::send_message(email)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
     }
 
     /// Sends a password change confirmation email to the specified email address.
@@ -192,10 +181,9 @@ impl EmailService {
                     ),
             )?;
 
-        match SMTP_CLIENT.send(email).await {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Box::new(e) as Box<dyn std::error::Error>),
-        }
+        mail::send_message(email)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)
     }
 
     /// Notify users when an assignment specification file changes.
@@ -212,22 +200,6 @@ impl EmailService {
         spec_filename: &str,
         change_summary: Option<&str>,
     ) {
-        // Tiny local helper to avoid extra crates
-        fn escape_html(input: &str) -> String {
-            let mut out = String::with_capacity(input.len());
-            for ch in input.chars() {
-                match ch {
-                    '&' => out.push_str("&amp;"),
-                    '<' => out.push_str("&lt;"),
-                    '>' => out.push_str("&gt;"),
-                    '"' => out.push_str("&quot;"),
-                    '\'' => out.push_str("&#39;"),
-                    _ => out.push(ch),
-                }
-            }
-            out
-        }
-
         if to_emails.is_empty() {
             eprintln!("send_spec_change_email: empty recipient list; skipping send");
             return;
@@ -350,7 +322,7 @@ impl EmailService {
             }
         };
 
-        if let Err(e) = SMTP_CLIENT.send(msg).await {
+        if let Err(e) = mail::send_message(msg).await {
             eprintln!("Failed to send spec-change email: {}", e);
         }
     }
@@ -401,10 +373,8 @@ impl EmailService {
                 ),
         )?;
 
-        SMTP_CLIENT
-            .send(email)
+        mail::send_message(email)
             .await
-            .map(|_| ())
             .map_err(|e| Box::new(e) as _)
     }
 }
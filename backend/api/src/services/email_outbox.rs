@@ -0,0 +1,207 @@
+//! Notification email outbox.
+//!
+//! [`enqueue`] records the email channel of a notification in `email_deliveries` for every
+//! recipient who hasn't opted out of email (see `db::models::email_setting`), marked for their
+//! digest if they are in digest mode. [`send_pending`] and [`send_digests`] then deliver them
+//! through `util::mail`; `main` runs both on a timer. Each row keeps its delivery status.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use db::models::{
+    email_delivery::Model as DeliveryModel,
+    email_setting::Model as EmailSettingModel,
+    user::{Column as UserColumn, Entity as UserEntity},
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use util::{
+    config,
+    mail::{self, OutboundEmail},
+};
+
+use crate::services::{email::escape_html, notifications::NewNotification};
+
+/// How often emails that aren't waiting for a digest are sent.
+pub const SEND_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Deliveries picked up per round; the rest wait for the next one.
+const BATCH_SIZE: u64 = 200;
+const DIGEST_BATCH_SIZE: u64 = 5000;
+
+/// Queues `notification` for each of `user_ids` who gets notification emails. Returns how many
+/// were queued.
+pub async fn enqueue(
+    db: &DatabaseConnection,
+    user_ids: &[i64],
+    notification: &NewNotification,
+) -> Result<u64, DbErr> {
+    let recipients: Vec<(i64, bool)> = EmailSettingModel::for_users(db, user_ids)
+        .await?
+        .into_iter()
+        .filter(|s| !s.opted_out)
+        .map(|s| (s.user_id, s.digest))
+        .collect();
+    DeliveryModel::queue(
+        db,
+        &recipients,
+        notification.kind,
+        &notification.title,
+        &notification.body,
+        &mail::markdown_to_html(&notification.body),
+        notification.link.as_deref(),
+    )
+    .await
+}
+
+/// Sends the pending emails that aren't waiting for a digest. Returns how many were sent.
+pub async fn send_pending(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let pending = DeliveryModel::pending(db, false, BATCH_SIZE).await?;
+    let addresses = addresses_of(db, pending.iter().map(|d| d.user_id)).await?;
+
+    let mut sent = 0;
+    for delivery in pending {
+        let Some(to) = addresses.get(&delivery.user_id) else {
+            continue;
+        };
+        match mail::send(&notification_email(to, &delivery)).await {
+            Ok(()) => {
+                DeliveryModel::mark_sent(db, &[delivery.id]).await?;
+                sent += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Email delivery {} failed: {}", delivery.id, e);
+                DeliveryModel::record_failure(db, &[delivery.id], &e.to_string()).await?;
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// Sends each user in digest mode one email with everything queued for them since their last
+/// digest. Returns how many digests were sent.
+pub async fn send_digests(db: &DatabaseConnection) -> Result<usize, DbErr> {
+    let mut by_user: BTreeMap<i64, Vec<DeliveryModel>> = BTreeMap::new();
+    for delivery in DeliveryModel::pending(db, true, DIGEST_BATCH_SIZE).await? {
+        by_user.entry(delivery.user_id).or_default().push(delivery);
+    }
+    let addresses = addresses_of(db, by_user.keys().copied()).await?;
+
+    let mut sent = 0;
+    for (user_id, deliveries) in by_user {
+        let Some(to) = addresses.get(&user_id) else {
+            continue;
+        };
+        let ids: Vec<i64> = deliveries.iter().map(|d| d.id).collect();
+        match mail::send(&digest_email(to, &deliveries)).await {
+            Ok(()) => {
+                DeliveryModel::mark_sent(db, &ids).await?;
+                sent += 1;
+            }
+            Err(e) => {
+                tracing::warn!("Email digest for user {} failed: {}", user_id, e);
+                DeliveryModel::record_failure(db, &ids, &e.to_string()).await?;
+            }
+        }
+    }
+    Ok(sent)
+}
+
+/// The email for a single notification.
+pub fn notification_email(to: &str, delivery: &DeliveryModel) -> OutboundEmail {
+    let from_name = config::email_from_name();
+    let url = delivery.link.as_deref().map(absolute_link);
+
+    let text_link = url
+        .as_deref()
+        .map(|u| format!("Open in FitchFork: {}\n\n", u))
+        .unwrap_or_default();
+    let html_link = url
+        .as_deref()
+        .map(|u| {
+            format!(
+                "<p><a href=\"{}\">Open in FitchFork</a></p>",
+                escape_html(u)
+            )
+        })
+        .unwrap_or_default();
+
+    OutboundEmail {
+        to: to.to_string(),
+        subject: delivery.subject.clone(),
+        text: format!(
+            "{}\n\n{}\n\n{}Best regards,\n{}",
+            delivery.subject, delivery.body_text, text_link, from_name
+        ),
+        html: format!(
+            "<html><body><h3>{}</h3>{}{}<p>Best regards,<br>{}</p></body></html>",
+            escape_html(&delivery.subject),
+            delivery.body_html,
+            html_link,
+            escape_html(&from_name)
+        ),
+    }
+}
+
+/// One email listing several notifications, oldest first.
+pub fn digest_email(to: &str, deliveries: &[DeliveryModel]) -> OutboundEmail {
+    let from_name = config::email_from_name();
+    let subject = match deliveries.len() {
+        1 => "Your FitchFork digest: 1 update".to_string(),
+        n => format!("Your FitchFork digest: {n} updates"),
+    };
+
+    let mut text = String::new();
+    let mut html = format!("<html><body><h2>{}</h2>", escape_html(&subject));
+    for delivery in deliveries {
+        let url = delivery.link.as_deref().map(absolute_link);
+        text.push_str(&format!(
+            "## {}\n\n{}\n\n",
+            delivery.subject, delivery.body_text
+        ));
+        html.push_str(&format!(
+            "<h3>{}</h3>{}",
+            escape_html(&delivery.subject),
+            delivery.body_html
+        ));
+        if let Some(url) = url {
+            text.push_str(&format!("Open in FitchFork: {}\n\n", url));
+            html.push_str(&format!(
+                "<p><a href=\"{}\">Open in FitchFork</a></p>",
+                escape_html(&url)
+            ));
+        }
+    }
+    text.push_str(&format!("Best regards,\n{}", from_name));
+    html.push_str(&format!(
+        "<p>Best regards,<br>{}</p></body></html>",
+        escape_html(&from_name)
+    ));
+
+    OutboundEmail {
+        to: to.to_string(),
+        subject,
+        text,
+        html,
+    }
+}
+
+fn absolute_link(path: &str) -> String {
+    format!("{}{}", config::frontend_url(), path)
+}
+
+async fn addresses_of(
+    db: &DatabaseConnection,
+    user_ids: impl Iterator<Item = i64>,
+) -> Result<HashMap<i64, String>, DbErr> {
+    let ids: Vec<i64> = user_ids.collect();
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+    Ok(UserEntity::find()
+        .filter(UserColumn::Id.is_in(ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u.email))
+        .collect())
+}
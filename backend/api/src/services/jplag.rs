@@ -0,0 +1,242 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use db::models::moss_report::FilterMode;
+use globset::GlobSet;
+use tokio::process::Command;
+use zip::ZipArchive;
+
+use crate::services::moss::{build_globset, normalize_path, sanitize, should_include};
+
+/// Options for a single JPlag run.
+#[derive(Clone, Debug)]
+pub struct JplagRunOptions {
+    /// JPlag language identifier (`-l`), see `Language::to_jplag`.
+    pub language: String,
+    /// Minimum token match length (`-t`); JPlag's language default when `None`.
+    pub min_tokens: Option<u32>,
+    pub filter_mode: FilterMode,
+    pub filter_patterns: Option<Vec<String>>, // glob patterns
+    /// Optional spec ZIPs (skeleton code) used as base code (`-bc`).
+    pub spec_zips: Vec<PathBuf>,
+}
+
+impl Default for JplagRunOptions {
+    fn default() -> Self {
+        Self {
+            language: "text".to_string(),
+            min_tokens: None,
+            filter_mode: FilterMode::All,
+            filter_patterns: None,
+            spec_zips: vec![],
+        }
+    }
+}
+
+/// Where a finished JPlag run left its report.
+#[derive(Debug)]
+pub struct JplagReport {
+    /// `results.zip` as written by JPlag (loadable in the JPlag report viewer).
+    pub results_zip: PathBuf,
+    /// The same report, extracted for `moss_parser::jplag::parse_jplag_dir`.
+    pub report_dir: PathBuf,
+}
+
+/// Runs a locally installed JPlag jar as an alternative to the MOSS service.
+pub struct JplagService {
+    java_bin: String,
+    jar: PathBuf,
+}
+
+impl JplagService {
+    pub fn new(java_bin: &str, jar: impl Into<PathBuf>) -> Self {
+        Self {
+            java_bin: java_bin.to_string(),
+            jar: jar.into(),
+        }
+    }
+
+    /// Lays the submissions out in `work_dir` (one `<username>_<submission_id>` directory each,
+    /// the same names MOSS gets), runs JPlag on them and extracts the report.
+    ///
+    /// `submission_files` are `(path, username, submission_id)` like for `MossService`; ZIPs are
+    /// expanded (with filtering), other files are copied as-is.
+    pub async fn run(
+        &self,
+        work_dir: &Path,
+        base_files: Vec<PathBuf>,
+        submission_files: Vec<(PathBuf, Option<String>, Option<i64>)>,
+        opts: JplagRunOptions,
+    ) -> Result<JplagReport, String> {
+        if submission_files.len() < 2 {
+            return Err("JPlag requires at least 2 submission files to compare".to_string());
+        }
+        if !self.jar.is_file() {
+            return Err(format!("JPlag jar not found: {}", self.jar.display()));
+        }
+
+        let globset = build_globset(opts.filter_patterns.as_ref()).map_err(|e| e.to_string())?;
+
+        // ---- Submissions ----
+        let submissions_dir = work_dir.join("submissions");
+        let mut laid_out = 0;
+        for (path, username, submission_id) in &submission_files {
+            let dir_tag = match (username, submission_id) {
+                (Some(u), Some(id)) => format!("{}_{}", u, id),
+                (Some(u), None) => u.to_string(),
+                (None, Some(id)) => id.to_string(),
+                (None, None) => "submission".to_string(),
+            };
+            let target = submissions_dir.join(sanitize(&dir_tag));
+
+            let written = if path.extension().and_then(|s| s.to_str()) == Some("zip") {
+                extract_zip_filtered(path, &target, &opts.filter_mode, globset.as_ref()).await?
+            } else {
+                let fname = path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default();
+                if should_include(fname, &opts.filter_mode, globset.as_ref()) {
+                    copy_file(path, &target.join(sanitize(fname))).await?;
+                    1
+                } else {
+                    0
+                }
+            };
+            if written > 0 {
+                laid_out += 1;
+            }
+        }
+        if laid_out < 2 {
+            return Err("Fewer than 2 submissions contain files after filtering".to_string());
+        }
+
+        // ---- Base code ----
+        let base_dir = work_dir.join("base");
+        let mut base_count = 0;
+        for p in &base_files {
+            let fname = p.file_name().and_then(|s| s.to_str()).unwrap_or("base");
+            copy_file(p, &base_dir.join(sanitize(fname))).await?;
+            base_count += 1;
+        }
+        for zip in &opts.spec_zips {
+            base_count += extract_zip_filtered(zip, &base_dir, &FilterMode::All, None).await?;
+        }
+
+        // ---- Run ----
+        let results = work_dir.join("results");
+        let mut cmd = Command::new(&self.java_bin);
+        cmd.arg("-jar")
+            .arg(&self.jar)
+            .args(["-l", &opts.language])
+            .arg("-r")
+            .arg(&results)
+            // Report every comparison, not only the top ones
+            .args(["-n", "-1"]);
+        if let Some(t) = opts.min_tokens {
+            cmd.args(["-t", &t.to_string()]);
+        }
+        if base_count > 0 {
+            cmd.arg("-bc").arg(&base_dir);
+        }
+        cmd.arg(&submissions_dir);
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| format!("Failed to start JPlag ({}): {e}", self.java_bin))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!(
+                "JPlag exited with {}: {}",
+                output.status,
+                stderr.trim()
+            ));
+        }
+
+        // JPlag appends ".zip" to the result name
+        let results_zip = [results.with_extension("zip"), results]
+            .into_iter()
+            .find(|p| p.is_file())
+            .ok_or_else(|| "JPlag did not write a results.zip".to_string())?;
+
+        let report_dir = work_dir.join("report");
+        let zip_data = tokio::fs::read(&results_zip)
+            .await
+            .map_err(|e| format!("Failed to read {}: {e}", results_zip.display()))?;
+        ZipArchive::new(Cursor::new(zip_data))
+            .and_then(|mut archive| archive.extract(&report_dir))
+            .map_err(|e| format!("Failed to extract JPlag results: {e}"))?;
+
+        Ok(JplagReport {
+            results_zip,
+            report_dir,
+        })
+    }
+}
+
+// ---------------- small helpers ----------------
+
+async fn copy_file(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    tokio::fs::copy(from, to)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy {}: {e}", from.display()))
+}
+
+/// Expands the entries of `zip_path` that pass the filter into `target`; returns how many.
+async fn extract_zip_filtered(
+    zip_path: &Path,
+    target: &Path,
+    filter_mode: &FilterMode,
+    globset: Option<&GlobSet>,
+) -> Result<usize, String> {
+    let zip_data = tokio::fs::read(zip_path)
+        .await
+        .map_err(|e| format!("Failed to read ZIP {}: {e}", zip_path.display()))?;
+    let mut archive = ZipArchive::new(Cursor::new(zip_data))
+        .map_err(|e| format!("Failed to open ZIP {}: {e}", zip_path.display()))?;
+
+    // Read everything first; zip entries must not be held across an await
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read ZIP entry {i}: {e}"))?;
+        if file.is_dir() {
+            continue;
+        }
+        // Entries escaping the archive root are skipped
+        let Some(internal) = file.enclosed_name() else {
+            continue;
+        };
+        let internal = normalize_path(&internal.to_string_lossy());
+        if internal.is_empty() || !should_include(&internal, filter_mode, globset) {
+            continue;
+        }
+
+        let mut contents = Vec::new();
+        std::io::copy(&mut file, &mut contents)
+            .map_err(|e| format!("Failed to read entry bytes: {e}"))?;
+        entries.push((internal, contents));
+    }
+
+    let written = entries.len();
+    for (internal, contents) in entries {
+        let dest = target.join(&internal);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        tokio::fs::write(&dest, contents)
+            .await
+            .map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+    }
+    Ok(written)
+}
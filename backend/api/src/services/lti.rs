@@ -0,0 +1,651 @@
+//! LTI 1.3 tool support.
+//!
+//! FitchFork acts as an LTI 1.3 tool for the platforms (LMSes) registered in
+//! `db::models::lti_platform`:
+//! - **Launch** — [`verify_id_token`] checks the platform's signed id token against the
+//!   platform's JWKS, and [`resolve_user`] / [`module_role`] map the launching LMS user onto a
+//!   FitchFork user and module role. An LMS user whose username or email is already taken is
+//!   linked by the account's owner instead, signed in, with a [`link_token`].
+//! - **Assignment and Grade Services** — [`sync_grades`] posts students' marks to the line items
+//!   of the assignment's LMS placements, authenticating with a client assertion signed by the
+//!   tool's key (`db::models::lti_key`), which [`jwks`] publishes.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::Utc;
+use db::grade::{GradeComputationError, GradeComputationOptions, compute_assignment_grades};
+use db::models::{
+    lti_key::Model as LtiKeyModel,
+    lti_platform::{Entity as PlatformEntity, Model as PlatformModel},
+    lti_resource_link::Model as ResourceLinkModel,
+    lti_user_link::Model as UserLinkModel,
+    user::{Column as UserColumn, Entity as UserEntity, Model as UserModel},
+    user_module_role::Role,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use reqwest::Client;
+use rsa::RsaPrivateKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+use rsa::traits::PublicKeyParts;
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::Duration;
+use util::{config, state::AppState};
+
+pub const MESSAGE_TYPE_RESOURCE_LINK: &str = "LtiResourceLinkRequest";
+pub const LTI_VERSION: &str = "1.3.0";
+const AGS_SCORE_SCOPE: &str = "https://purl.imsglobal.org/spec/lti-ags/scope/score";
+const SCORE_CONTENT_TYPE: &str = "application/vnd.ims.lis.v1.score+json";
+const RSA_KEY_BITS: usize = 2048;
+
+#[derive(Debug)]
+pub enum LtiError {
+    /// The launch or request is malformed, unsigned, or not for us.
+    Invalid(String),
+    /// The platform could not be reached or refused the request.
+    Platform(String),
+    /// The accounts are already linked to others.
+    Conflict(String),
+    Database(DbErr),
+}
+
+impl std::fmt::Display for LtiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LtiError::Invalid(e) => write!(f, "{e}"),
+            LtiError::Platform(e) => write!(f, "LTI platform error: {e}"),
+            LtiError::Conflict(e) => write!(f, "{e}"),
+            LtiError::Database(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for LtiError {}
+
+impl From<DbErr> for LtiError {
+    fn from(e: DbErr) -> Self {
+        LtiError::Database(e)
+    }
+}
+
+impl From<GradeComputationError> for LtiError {
+    fn from(e: GradeComputationError) -> Self {
+        match e {
+            GradeComputationError::Database(e) => LtiError::Database(e),
+            other => LtiError::Invalid(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResourceLinkClaim {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContextClaim {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LisClaim {
+    pub person_sourcedid: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AgsEndpointClaim {
+    pub lineitem: Option<String>,
+    #[serde(default)]
+    pub scope: Vec<String>,
+}
+
+/// The claims of a resource link launch that FitchFork uses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LaunchClaims {
+    pub sub: String,
+    pub nonce: String,
+    pub email: Option<String>,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/message_type")]
+    pub message_type: String,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/version")]
+    pub version: String,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/deployment_id")]
+    pub deployment_id: String,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/resource_link")]
+    pub resource_link: ResourceLinkClaim,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/context")]
+    pub context: Option<ContextClaim>,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/roles", default)]
+    pub roles: Vec<String>,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/custom", default)]
+    pub custom: HashMap<String, Value>,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti/claim/lis", default)]
+    pub lis: LisClaim,
+    #[serde(rename = "https://purl.imsglobal.org/spec/lti-ags/claim/endpoint")]
+    pub ags: Option<AgsEndpointClaim>,
+}
+
+impl LaunchClaims {
+    /// A numeric custom parameter; LMSes send custom values as strings.
+    pub fn custom_id(&self, name: &str) -> Option<i64> {
+        match self.custom.get(name)? {
+            Value::Number(n) => n.as_i64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The line item to post marks to, if the platform granted the score scope.
+    pub fn lineitem(&self) -> Option<&str> {
+        let ags = self.ags.as_ref()?;
+        if !ags.scope.iter().any(|s| s == AGS_SCORE_SCOPE) {
+            return None;
+        }
+        ags.lineitem.as_deref()
+    }
+}
+
+/// The module role for LIS membership roles: instructors lecture, teaching assistants tutor
+/// and learners are students. Other roles (administrators, mentors, ...) get none.
+pub fn module_role(roles: &[String]) -> Option<Role> {
+    let names: Vec<&str> = roles
+        .iter()
+        .filter_map(|r| r.rsplit(['#', '/']).next())
+        .collect();
+    if names.contains(&"TeachingAssistant") {
+        Some(Role::Tutor)
+    } else if names.contains(&"Instructor") {
+        Some(Role::Lecturer)
+    } else if names.contains(&"Learner") {
+        Some(Role::Student)
+    } else {
+        None
+    }
+}
+
+fn http_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+fn random_token(len: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct PlatformJwk {
+    kid: Option<String>,
+    n: String,
+    e: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlatformJwks {
+    keys: Vec<PlatformJwk>,
+}
+
+/// Verifies a launch's id token against `platform`: the signature by the platform's published
+/// key, the issuer and our client id as audience, and expiry.
+pub async fn verify_id_token(
+    platform: &PlatformModel,
+    id_token: &str,
+) -> Result<LaunchClaims, LtiError> {
+    let header = jsonwebtoken::decode_header(id_token)
+        .map_err(|e| LtiError::Invalid(format!("Malformed id token: {e}")))?;
+    if header.alg != Algorithm::RS256 {
+        return Err(LtiError::Invalid(
+            "id token must be signed with RS256".into(),
+        ));
+    }
+
+    let jwks: PlatformJwks = http_client()
+        .get(&platform.keyset_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| LtiError::Platform(format!("Failed to fetch the platform's keys: {e}")))?
+        .json()
+        .await
+        .map_err(|e| LtiError::Platform(format!("Invalid platform key set: {e}")))?;
+    let jwk = match &header.kid {
+        Some(kid) => jwks.keys.iter().find(|k| k.kid.as_ref() == Some(kid)),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(|| LtiError::Invalid("id token signed with an unknown key".into()))?;
+    let key = DecodingKey::from_rsa_components(&jwk.n, &jwk.e)
+        .map_err(|e| LtiError::Platform(format!("Invalid platform key: {e}")))?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&[&platform.issuer]);
+    validation.set_audience(&[&platform.client_id]);
+    decode::<LaunchClaims>(id_token, &key, &validation)
+        .map(|data| data.claims)
+        .map_err(|e| LtiError::Invalid(format!("Invalid id token: {e}")))
+}
+
+/// Who a launch signs in as.
+#[derive(Debug)]
+pub enum LaunchUser {
+    /// The FitchFork user linked to the LMS user.
+    Linked(UserModel),
+    /// The LMS user isn't linked, and an existing account already has their username or email.
+    /// Nothing in the launch proves they own it, so its owner links it while signed in, with a
+    /// [`link_token`] for the LMS user.
+    Unlinked,
+}
+
+/// The FitchFork user for the launching LMS user, creating and linking one on their first
+/// launch. Existing accounts are never linked here, only accounts the launch creates: the
+/// platform's sourcedid and email claims are not proof of owning an account with that username
+/// or email.
+pub async fn resolve_user(
+    db: &DatabaseConnection,
+    platform_id: i64,
+    claims: &LaunchClaims,
+) -> Result<LaunchUser, LtiError> {
+    if let Some(link) = UserLinkModel::find(db, platform_id, &claims.sub).await?
+        && let Some(user) = UserEntity::find_by_id(link.user_id).one(db).await?
+    {
+        return Ok(LaunchUser::Linked(user));
+    }
+
+    let sourcedid = claims
+        .lis
+        .person_sourcedid
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let email = claims
+        .email
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| LtiError::Invalid("The platform did not share the user's email".into()))?;
+    let username = sourcedid
+        .or_else(|| email.split('@').next())
+        .unwrap_or(email);
+
+    let taken = UserEntity::find()
+        .filter(
+            Condition::any()
+                .add(UserColumn::Username.eq(username))
+                .add(UserColumn::Email.eq(email)),
+        )
+        .one(db)
+        .await?
+        .is_some();
+    if taken {
+        return Ok(LaunchUser::Unlinked);
+    }
+
+    let user = UserModel::create(db, username, email, &random_token(32), false)
+        .await
+        .map_err(|e| LtiError::Invalid(format!("Failed to create the user: {e}")))?;
+    UserLinkModel::create(db, platform_id, &claims.sub, user.id).await?;
+    Ok(LaunchUser::Linked(user))
+}
+
+/// `purpose` of an account link token.
+const LINK_PURPOSE: &str = "lti_link";
+/// How long the user has to sign in and link after the launch.
+const LINK_TOKEN_MINUTES: i64 = 15;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct LinkClaims {
+    platform_id: i64,
+    subject: String,
+    exp: usize,
+    purpose: String,
+}
+
+/// A short-lived token naming the LMS user `subject` on `platform_id`, which lets a signed-in
+/// user link that LMS user to their own account with [`link_user`].
+pub fn link_token(platform_id: i64, subject: &str) -> String {
+    let claims = LinkClaims {
+        platform_id,
+        subject: subject.to_string(),
+        exp: (Utc::now() + chrono::Duration::minutes(LINK_TOKEN_MINUTES)).timestamp() as usize,
+        purpose: LINK_PURPOSE.to_string(),
+    };
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(config::jwt_secret().as_bytes()),
+    )
+    .expect("Token encoding failed")
+}
+
+/// The platform and LMS user of a valid, unexpired link token.
+pub fn decode_link_token(token: &str) -> Option<(i64, String)> {
+    let data = decode::<LinkClaims>(
+        token,
+        &DecodingKey::from_secret(config::jwt_secret().as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .ok()?;
+    (data.claims.purpose == LINK_PURPOSE).then_some((data.claims.platform_id, data.claims.subject))
+}
+
+/// Links the LMS user `subject` on `platform_id` to `user_id`, so their launches sign in as
+/// that user.
+pub async fn link_user(
+    db: &DatabaseConnection,
+    platform_id: i64,
+    subject: &str,
+    user_id: i64,
+) -> Result<(), LtiError> {
+    match UserLinkModel::find(db, platform_id, subject).await? {
+        Some(link) if link.user_id == user_id => return Ok(()),
+        Some(_) => {
+            return Err(LtiError::Conflict(
+                "This LMS account is already linked to another user".into(),
+            ));
+        }
+        None => {}
+    }
+    if UserLinkModel::for_user(db, platform_id, user_id)
+        .await?
+        .is_some()
+    {
+        return Err(LtiError::Conflict(
+            "A different account on this LMS is already linked".into(),
+        ));
+    }
+    UserLinkModel::create(db, platform_id, subject, user_id).await?;
+    Ok(())
+}
+
+/// The tool's public keys as a JWK set.
+pub async fn jwks(db: &DatabaseConnection) -> Result<Value, DbErr> {
+    let mut keys = Vec::new();
+    for key in LtiKeyModel::active(db).await? {
+        let private = match RsaPrivateKey::from_pkcs8_pem(&key.private_key_pem) {
+            Ok(k) => k,
+            Err(e) => {
+                tracing::warn!("Skipping unreadable LTI key {}: {}", key.kid, e);
+                continue;
+            }
+        };
+        keys.push(json!({
+            "kty": "RSA",
+            "alg": "RS256",
+            "use": "sig",
+            "kid": key.kid,
+            "n": URL_SAFE_NO_PAD.encode(private.n().to_bytes_be()),
+            "e": URL_SAFE_NO_PAD.encode(private.e().to_bytes_be()),
+        }));
+    }
+    Ok(json!({ "keys": keys }))
+}
+
+/// Generates and stores a new signing key. Its kid is derived from the public modulus.
+pub async fn generate_key(db: &DatabaseConnection) -> Result<LtiKeyModel, String> {
+    let (kid, pem) = tokio::task::spawn_blocking(|| {
+        let key =
+            RsaPrivateKey::new(&mut rand::rngs::OsRng, RSA_KEY_BITS).map_err(|e| e.to_string())?;
+        let pem = key
+            .to_pkcs8_pem(LineEnding::LF)
+            .map_err(|e| e.to_string())?
+            .to_string();
+        let kid = hex::encode(&Sha256::digest(key.n().to_bytes_be())[..8]);
+        Ok::<_, String>((kid, pem))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    LtiKeyModel::create(db, &kid, &pem)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Debug, Serialize)]
+struct ClientAssertion<'a> {
+    iss: &'a str,
+    sub: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+    jti: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+/// An AGS score-scope access token from the platform (OAuth2 client credentials with a signed
+/// JWT client assertion).
+async fn access_token(
+    client: &Client,
+    platform: &PlatformModel,
+    key: &LtiKeyModel,
+) -> Result<String, LtiError> {
+    let encoding = EncodingKey::from_rsa_pem(key.private_key_pem.as_bytes())
+        .map_err(|e| LtiError::Invalid(format!("Unreadable LTI key {}: {e}", key.kid)))?;
+    let now = Utc::now().timestamp();
+    let assertion = ClientAssertion {
+        iss: &platform.client_id,
+        sub: &platform.client_id,
+        aud: &platform.auth_token_url,
+        iat: now,
+        exp: now + 300,
+        jti: random_token(24),
+    };
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(key.kid.clone());
+    let assertion = encode(&header, &assertion, &encoding)
+        .map_err(|e| LtiError::Invalid(format!("Failed to sign the client assertion: {e}")))?;
+
+    let token: TokenResponse = client
+        .post(&platform.auth_token_url)
+        .form(&[
+            ("grant_type", "client_credentials"),
+            (
+                "client_assertion_type",
+                "urn:ietf:params:oauth:client-assertion-type:jwt-bearer",
+            ),
+            ("client_assertion", assertion.as_str()),
+            ("scope", AGS_SCORE_SCOPE),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| LtiError::Platform(format!("Token request failed: {e}")))?
+        .json()
+        .await
+        .map_err(|e| LtiError::Platform(format!("Invalid token response: {e}")))?;
+    Ok(token.access_token)
+}
+
+/// `{lineitem}/scores`, keeping any query string of the line item URL.
+fn scores_url(lineitem: &str) -> Result<String, LtiError> {
+    let mut url = url::Url::parse(lineitem)
+        .map_err(|e| LtiError::Invalid(format!("Invalid line item URL: {e}")))?;
+    let path = format!("{}/scores", url.path().trim_end_matches('/'));
+    url.set_path(&path);
+    Ok(url.to_string())
+}
+
+async fn post_score(
+    client: &Client,
+    token: &str,
+    lineitem: &str,
+    subject: &str,
+    score_pct: f64,
+) -> Result<(), LtiError> {
+    let body = json!({
+        "userId": subject,
+        "scoreGiven": (score_pct * 100.0).round() / 100.0,
+        "scoreMaximum": 100,
+        "activityProgress": "Completed",
+        "gradingProgress": "FullyGraded",
+        "timestamp": Utc::now().to_rfc3339(),
+    });
+    client
+        .post(scores_url(lineitem)?)
+        .bearer_auth(token)
+        .header(reqwest::header::CONTENT_TYPE, SCORE_CONTENT_TYPE)
+        .body(body.to_string())
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| LtiError::Platform(format!("Score post failed: {e}")))?;
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SyncSummary {
+    /// Scores accepted by the platforms
+    pub posted: usize,
+    /// Students with a mark who have never launched from that placement's platform
+    pub unlinked: usize,
+    /// Scores the platforms rejected or could not be sent
+    pub failed: usize,
+}
+
+/// Posts the counted mark of each student (all graded students when `user_ids` is `None`) to
+/// every LMS placement of the assignment that has a line item.
+pub async fn sync_grades(
+    db: &DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+    user_ids: Option<&[i64]>,
+) -> Result<SyncSummary, LtiError> {
+    let mut summary = SyncSummary::default();
+    let links = ResourceLinkModel::graded_for_assignment(db, assignment_id).await?;
+    if links.is_empty() {
+        return Ok(summary);
+    }
+    let Some(key) = LtiKeyModel::signing(db).await? else {
+        return Err(LtiError::Invalid(
+            "No active LTI key to sign grade requests with".into(),
+        ));
+    };
+
+    let grades = compute_assignment_grades(
+        db,
+        module_id,
+        assignment_id,
+        GradeComputationOptions {
+            user_ids,
+            ..Default::default()
+        },
+    )
+    .await?
+    .grades;
+    if grades.is_empty() {
+        return Ok(summary);
+    }
+
+    let client = http_client();
+    for link in links {
+        let Some(lineitem) = link.lineitem.as_deref() else {
+            continue;
+        };
+        let Some(platform) = PlatformEntity::find_by_id(link.platform_id).one(db).await? else {
+            continue;
+        };
+        let token = match access_token(&client, &platform, &key).await {
+            Ok(token) => token,
+            Err(e) => {
+                tracing::warn!("LTI grade sync to {} failed: {}", platform.issuer, e);
+                summary.failed += grades.len();
+                continue;
+            }
+        };
+        for grade in &grades {
+            let Some(user_link) = UserLinkModel::for_user(db, platform.id, grade.user.id).await?
+            else {
+                summary.unlinked += 1;
+                continue;
+            };
+            match post_score(
+                &client,
+                &token,
+                lineitem,
+                &user_link.subject,
+                grade.score_pct,
+            )
+            .await
+            {
+                Ok(()) => summary.posted += 1,
+                Err(e) => {
+                    tracing::warn!(
+                        "LTI score for user {} on {} failed: {}",
+                        grade.user.id,
+                        lineitem,
+                        e
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// [`sync_grades`] for `user_ids` in the background, so marking doesn't wait on the LMS.
+pub fn spawn_sync(app: &AppState, module_id: i64, assignment_id: i64, user_ids: Vec<i64>) {
+    if user_ids.is_empty() {
+        return;
+    }
+    let db = app.db_clone();
+    tokio::spawn(async move {
+        if let Err(e) = sync_grades(&db, module_id, assignment_id, Some(&user_ids)).await {
+            tracing::warn!(
+                "LTI grade sync for assignment {} failed: {}",
+                assignment_id,
+                e
+            );
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_role_maps_lis_roles() {
+        let roles = |rs: &[&str]| rs.iter().map(|r| r.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            module_role(&roles(&[
+                "http://purl.imsglobal.org/vocab/lis/v2/membership#Instructor",
+                "http://purl.imsglobal.org/vocab/lis/v2/membership/Instructor#TeachingAssistant",
+            ])),
+            Some(Role::Tutor)
+        );
+        assert_eq!(
+            module_role(&roles(&[
+                "http://purl.imsglobal.org/vocab/lis/v2/membership#Instructor"
+            ])),
+            Some(Role::Lecturer)
+        );
+        assert_eq!(module_role(&roles(&["Learner"])), Some(Role::Student));
+        assert_eq!(
+            module_role(&roles(&[
+                "http://purl.imsglobal.org/vocab/lis/v2/institution/person#Administrator"
+            ])),
+            None
+        );
+    }
+
+    #[test]
+    fn scores_url_keeps_the_query() {
+        assert_eq!(
+            scores_url("https://lms.example/api/lti/courses/4/line_items/9?type_id=2").unwrap(),
+            "https://lms.example/api/lti/courses/4/line_items/9/scores?type_id=2"
+        );
+    }
+}
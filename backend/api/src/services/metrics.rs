@@ -0,0 +1,106 @@
+//! Prometheus metrics for the API.
+//!
+//! Tracks open WebSocket connections and how long marking takes. The registry is
+//! rendered in the Prometheus text format by `GET /api/metrics`.
+
+use once_cell::sync::Lazy;
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntGauge, Registry, TextEncoder};
+use std::time::Duration;
+
+/// Bucket bounds in seconds, from a trivial output diff up to a large coverage run.
+const MARKING_BUCKETS: &[f64] = &[
+    0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0,
+];
+
+struct Metrics {
+    registry: Registry,
+    ws_connections: IntGauge,
+    marking_duration: HistogramVec,
+}
+
+static METRICS: Lazy<Metrics> = Lazy::new(|| {
+    let ws_connections =
+        IntGauge::new("fitchfork_ws_connections", "Open WebSocket connections").unwrap();
+    let marking_duration = HistogramVec::new(
+        HistogramOpts::new(
+            "fitchfork_marking_duration_seconds",
+            "Time taken to mark one submission, by outcome",
+        )
+        .buckets(MARKING_BUCKETS.to_vec()),
+        &["outcome"],
+    )
+    .unwrap();
+
+    let registry = Registry::new();
+    registry.register(Box::new(ws_connections.clone())).unwrap();
+    registry
+        .register(Box::new(marking_duration.clone()))
+        .unwrap();
+
+    Metrics {
+        registry,
+        ws_connections,
+        marking_duration,
+    }
+});
+
+/// Records how long marking one submission took and whether it succeeded.
+pub fn observe_marking(duration: Duration, ok: bool) {
+    let outcome = if ok { "ok" } else { "error" };
+    METRICS
+        .marking_duration
+        .with_label_values(&[outcome])
+        .observe(duration.as_secs_f64());
+}
+
+/// Counts a WebSocket connection as open for as long as the guard is alive.
+pub struct WsConnectionGuard(());
+
+impl WsConnectionGuard {
+    pub fn new() -> Self {
+        METRICS.ws_connections.inc();
+        Self(())
+    }
+}
+
+impl Default for WsConnectionGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for WsConnectionGuard {
+    fn drop(&mut self) {
+        METRICS.ws_connections.dec();
+    }
+}
+
+/// Renders every metric in the Prometheus text exposition format.
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&METRICS.registry.gather(), &mut buffer) {
+        tracing::error!("Failed to encode metrics: {}", e);
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guard_tracks_open_connections() {
+        let before = METRICS.ws_connections.get();
+        let guard = WsConnectionGuard::new();
+        assert_eq!(METRICS.ws_connections.get(), before + 1);
+        drop(guard);
+        assert_eq!(METRICS.ws_connections.get(), before);
+    }
+
+    #[test]
+    fn marking_durations_are_labelled_by_outcome() {
+        observe_marking(Duration::from_millis(300), false);
+        let text = render();
+        assert!(text.contains("fitchfork_marking_duration_seconds_count{outcome=\"error\"}"));
+    }
+}
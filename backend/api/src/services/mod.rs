@@ -1,7 +1,20 @@
 //! External service integrations.
 //!
-//! Provides modules for sending emails and interacting with MOSS plagiarism detection.
+//! Provides modules for sending emails (directly or through the notification email outbox) and
+//! user notifications, interacting with MOSS plagiarism detection (or a locally-run JPlag),
+//! acting as an LTI 1.3 tool, single sign-on through an OpenID Connect provider, the two-factor
+//! authentication policy, exporting Prometheus metrics, escalating unanswered tickets, and
+//! publishing scheduled announcements.
 
+pub mod announcement_publisher;
 pub mod email;
+pub mod email_outbox;
+pub mod jplag;
+pub mod lti;
+pub mod metrics;
 pub mod moss;
 pub mod moss_archiver;
+pub mod notifications;
+pub mod sso;
+pub mod ticket_escalation;
+pub mod two_factor;
@@ -395,15 +395,15 @@ impl MossService {
 
 // ---------------- small pure helpers ----------------
 
-fn sanitize(s: &str) -> String {
+pub(crate) fn sanitize(s: &str) -> String {
     s.replace('\\', "_").replace('/', "_").replace(' ', "_")
 }
 
-fn normalize_path(p: &str) -> String {
+pub(crate) fn normalize_path(p: &str) -> String {
     p.replace('\\', "/").trim_start_matches('/').to_string()
 }
 
-fn build_globset(patterns: Option<&Vec<String>>) -> Result<Option<GlobSet>, globset::Error> {
+pub(crate) fn build_globset(patterns: Option<&Vec<String>>) -> Result<Option<GlobSet>, globset::Error> {
     let Some(list) = patterns else {
         return Ok(None);
     };
@@ -416,7 +416,7 @@ fn build_globset(patterns: Option<&Vec<String>>) -> Result<Option<GlobSet>, glob
     Ok(Some(set))
 }
 
-fn should_include(path_like: &str, mode: &FilterMode, set: Option<&GlobSet>) -> bool {
+pub(crate) fn should_include(path_like: &str, mode: &FilterMode, set: Option<&GlobSet>) -> bool {
     match *mode {
         FilterMode::All => true,
         FilterMode::Whitelist => {
@@ -0,0 +1,95 @@
+//! Notification delivery.
+//!
+//! [`notify`] fans one event out to its recipients over the channels each of them has enabled
+//! (see `db::models::notification_preference`):
+//! - **in-app** — a stored notification, pushed live to the `user:{id}.notifications` topic
+//! - **email** — queued in the email outbox (see [`email_outbox`]) unless the user has opted out
+//!   of email, and sent on its own or in their next digest
+//!
+//! Delivery is best-effort: failures are logged and never reach the request that caused them.
+
+use db::models::{
+    notification::{Model as NotificationModel, NotificationKind},
+    notification_preference::Model as PreferenceModel,
+};
+use util::state::AppState;
+
+use crate::services::email_outbox;
+use crate::ws::notifications::{emit as n_emit, payload as n_payload};
+
+/// What to tell the recipients.
+#[derive(Debug, Clone)]
+pub struct NewNotification {
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    /// Frontend path, e.g. `/modules/1/assignments/2/submissions/3`.
+    pub link: Option<String>,
+}
+
+/// Delivers `notification` to each of `user_ids` on their enabled channels.
+pub async fn notify(app: &AppState, user_ids: &[i64], notification: &NewNotification) {
+    let db = app.db();
+    let prefs = match PreferenceModel::for_users(db, user_ids, notification.kind).await {
+        Ok(prefs) => prefs,
+        Err(e) => {
+            tracing::warn!("Failed to load notification preferences: {}", e);
+            return;
+        }
+    };
+
+    let mut email_to = Vec::new();
+    for pref in prefs {
+        if pref.email {
+            email_to.push(pref.user_id);
+        }
+        if !pref.in_app {
+            continue;
+        }
+        match NotificationModel::create(
+            db,
+            pref.user_id,
+            notification.kind,
+            &notification.title,
+            &notification.body,
+            notification.link.as_deref(),
+        )
+        .await
+        {
+            Ok(saved) => {
+                let payload = n_payload::Notification {
+                    id: saved.id,
+                    kind: saved.kind.to_string(),
+                    title: saved.title,
+                    body: saved.body,
+                    link: saved.link,
+                    created_at: saved.created_at.to_rfc3339(),
+                };
+                n_emit::created(app.ws(), pref.user_id, payload).await;
+            }
+            Err(e) => tracing::warn!(
+                "Failed to store notification for user {}: {}",
+                pref.user_id,
+                e
+            ),
+        }
+    }
+
+    if email_to.is_empty() {
+        return;
+    }
+    if let Err(e) = email_outbox::enqueue(db, &email_to, notification).await {
+        tracing::warn!("Failed to queue notification emails: {}", e);
+    }
+}
+
+/// [`notify`] in the background, so the caller doesn't wait on delivery.
+pub fn spawn_notify(app: &AppState, user_ids: Vec<i64>, notification: NewNotification) {
+    if user_ids.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tokio::spawn(async move {
+        notify(&app, &user_ids, &notification).await;
+    });
+}
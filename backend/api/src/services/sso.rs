@@ -0,0 +1,516 @@
+//! OpenID Connect single sign-on.
+//!
+//! With a provider configured (`util::config::oidc`), users can sign in through it instead of
+//! with a password, using the authorization code flow with PKCE:
+//! - [`authorization_url`] stores a `db::models::sso_login_state` and points the browser at the
+//!   provider.
+//! - [`complete`] redeems the code the provider sends back, checks the id token against the
+//!   provider's JWKS and maps the provider's user onto a FitchFork user through
+//!   `db::models::sso_identity`, then applies the configured role mapping rules.
+//!
+//! A provider user without an identity yet is given a new account when auto-provisioning is on.
+//! Existing accounts are never linked by email or username, however the provider vouches for
+//! them: their owners link explicitly by starting the flow while signed in, and the link is only
+//! made when they finish it under the same session, so a link URL sent to someone else can't
+//! attach their provider account.
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::Utc;
+use db::models::{
+    module::{Column as ModuleColumn, Entity as ModuleEntity},
+    sso_identity::Model as IdentityModel,
+    sso_login_state::Model as LoginStateModel,
+    user::{self, Column as UserColumn, Entity as UserEntity, Model as UserModel},
+    user_module_role::{self, Role},
+};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use reqwest::Client;
+use sea_orm::{
+    ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use util::config::{OidcConfig, OidcGrant, OidcRoleRule};
+
+#[derive(Debug)]
+pub enum SsoError {
+    /// No provider is configured.
+    Disabled,
+    /// The callback or id token is malformed, unsigned, expired or not for us.
+    Invalid(String),
+    /// The provider's user may not sign in here.
+    Forbidden(String),
+    /// The provider account or FitchFork account is already linked elsewhere.
+    Conflict(String),
+    /// The provider could not be reached or refused the request.
+    Provider(String),
+    Database(DbErr),
+}
+
+impl std::fmt::Display for SsoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SsoError::Disabled => write!(f, "Single sign-on is not enabled"),
+            SsoError::Invalid(e) | SsoError::Forbidden(e) | SsoError::Conflict(e) => {
+                write!(f, "{e}")
+            }
+            SsoError::Provider(e) => write!(f, "SSO provider error: {e}"),
+            SsoError::Database(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for SsoError {}
+
+impl From<DbErr> for SsoError {
+    fn from(e: DbErr) -> Self {
+        SsoError::Database(e)
+    }
+}
+
+fn http_client() -> Client {
+    Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+fn random_token(len: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// The parts of the provider's discovery document FitchFork uses.
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+async fn discover(client: &Client, cfg: &OidcConfig) -> Result<Discovery, SsoError> {
+    let discovery: Discovery = client
+        .get(format!("{}/.well-known/openid-configuration", cfg.issuer))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| SsoError::Provider(format!("Failed to fetch the discovery document: {e}")))?
+        .json()
+        .await
+        .map_err(|e| SsoError::Provider(format!("Invalid discovery document: {e}")))?;
+    if discovery.issuer.trim_end_matches('/') != cfg.issuer {
+        return Err(SsoError::Provider(format!(
+            "Discovery document is for issuer {}",
+            discovery.issuer
+        )));
+    }
+    Ok(discovery)
+}
+
+/// The PKCE `S256` challenge for `verifier`.
+fn code_challenge(verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+/// Starts a sign-in, or an account link for `link_user_id`, and returns the provider URL to
+/// send the browser to along with the sign-in's state. `redirect` is the frontend path to land
+/// on afterwards.
+pub async fn authorization_url(
+    db: &DatabaseConnection,
+    cfg: &OidcConfig,
+    link_user_id: Option<i64>,
+    redirect: Option<&str>,
+) -> Result<(String, String), SsoError> {
+    let discovery = discover(&http_client(), cfg).await?;
+    let login = LoginStateModel::create(db, link_user_id, redirect).await?;
+    let challenge = code_challenge(&login.code_verifier);
+    let url = url::Url::parse_with_params(
+        &discovery.authorization_endpoint,
+        [
+            ("response_type", "code"),
+            ("client_id", cfg.client_id.as_str()),
+            ("redirect_uri", cfg.redirect_url.as_str()),
+            ("scope", cfg.scopes.as_str()),
+            ("state", login.state.as_str()),
+            ("nonce", login.nonce.as_str()),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ],
+    )
+    .map_err(|e| SsoError::Provider(format!("Invalid authorization endpoint: {e}")))?;
+    Ok((url.to_string(), login.state))
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderJwk {
+    kid: Option<String>,
+    n: String,
+    e: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderJwks {
+    keys: Vec<ProviderJwk>,
+}
+
+/// The claims of a verified id token.
+#[derive(Debug, Clone)]
+pub struct IdClaims(Map<String, Value>);
+
+impl IdClaims {
+    pub fn str(&self, name: &str) -> Option<&str> {
+        self.0
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+
+    pub fn subject(&self) -> Option<&str> {
+        self.str("sub")
+    }
+
+    /// The values of a roles claim sent as a single string or a list of strings.
+    pub fn values(&self, name: &str) -> Vec<String> {
+        match self.0.get(name) {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Redeems the authorization `code` and returns the verified claims of the id token: signed by
+/// one of the provider's published keys, issued by the provider to our client, unexpired, and
+/// carrying the sign-in's `nonce`.
+async fn redeem_code(
+    client: &Client,
+    cfg: &OidcConfig,
+    discovery: &Discovery,
+    login: &LoginStateModel,
+    code: &str,
+) -> Result<IdClaims, SsoError> {
+    let token: TokenResponse = client
+        .post(&discovery.token_endpoint)
+        .basic_auth(&cfg.client_id, Some(&cfg.client_secret))
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", cfg.redirect_url.as_str()),
+            ("client_id", cfg.client_id.as_str()),
+            ("code_verifier", login.code_verifier.as_str()),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| SsoError::Provider(format!("Token request failed: {e}")))?
+        .json()
+        .await
+        .map_err(|e| SsoError::Provider(format!("Invalid token response: {e}")))?;
+
+    let header = jsonwebtoken::decode_header(&token.id_token)
+        .map_err(|e| SsoError::Invalid(format!("Malformed id token: {e}")))?;
+    if header.alg != Algorithm::RS256 {
+        return Err(SsoError::Invalid(
+            "id token must be signed with RS256".into(),
+        ));
+    }
+    let jwks: ProviderJwks = client
+        .get(&discovery.jwks_uri)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| SsoError::Provider(format!("Failed to fetch the provider's keys: {e}")))?
+        .json()
+        .await
+        .map_err(|e| SsoError::Provider(format!("Invalid provider key set: {e}")))?;
+    let jwk = match &header.kid {
+        Some(kid) => jwks.keys.iter().find(|k| k.kid.as_ref() == Some(kid)),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(|| SsoError::Invalid("id token signed with an unknown key".into()))?;
+    let key = DecodingKey::from_rsa_components(&jwk.n, &jwk.e)
+        .map_err(|e| SsoError::Provider(format!("Invalid provider key: {e}")))?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_issuer(&[&discovery.issuer]);
+    validation.set_audience(&[&cfg.client_id]);
+    let claims = decode::<Map<String, Value>>(&token.id_token, &key, &validation)
+        .map(|data| IdClaims(data.claims))
+        .map_err(|e| SsoError::Invalid(format!("Invalid id token: {e}")))?;
+    if claims.str("nonce") != Some(login.nonce.as_str()) {
+        return Err(SsoError::Invalid("Nonce mismatch".into()));
+    }
+    Ok(claims)
+}
+
+/// A finished sign-in or account link.
+#[derive(Debug)]
+pub struct Completed {
+    pub user: UserModel,
+    /// Frontend path the sign-in was started from.
+    pub redirect: Option<String>,
+}
+
+/// Whether `state` is an account link in progress, which only [`complete`] with the linking
+/// user's session may finish.
+pub async fn is_link(db: &DatabaseConnection, state: &str) -> Result<bool, SsoError> {
+    Ok(LoginStateModel::find_unexpired(db, state)
+        .await?
+        .is_some_and(|login| login.link_user_id.is_some()))
+}
+
+/// Finishes the sign-in (or account link) started with `state`, using the authorization `code`
+/// the provider sent back.
+///
+/// `session_user` is the signed-in user finishing the flow. A sign-in is finished without one;
+/// an account link only by the user who started it.
+pub async fn complete(
+    db: &DatabaseConnection,
+    cfg: &OidcConfig,
+    state: &str,
+    code: &str,
+    session_user: Option<i64>,
+) -> Result<Completed, SsoError> {
+    let Some(login) = LoginStateModel::take(db, state).await? else {
+        return Err(SsoError::Invalid("Unknown or expired sign-in state".into()));
+    };
+    if login.link_user_id != session_user {
+        return Err(SsoError::Invalid(
+            "This sign-in was started by someone else".into(),
+        ));
+    }
+    let client = http_client();
+    let discovery = discover(&client, cfg).await?;
+    let claims = redeem_code(&client, cfg, &discovery, &login, code).await?;
+    let subject = claims
+        .subject()
+        .ok_or_else(|| SsoError::Invalid("id token has no subject".into()))?;
+
+    let user = match login.link_user_id {
+        Some(user_id) => link_identity(db, cfg, subject, user_id).await?,
+        None => resolve_user(db, cfg, subject, &claims).await?,
+    };
+    let user =
+        apply_role_rules(db, &cfg.role_rules, &claims.values(&cfg.roles_claim), user).await?;
+
+    Ok(Completed {
+        user,
+        redirect: login.redirect,
+    })
+}
+
+/// Links the provider's `subject` to the signed-in `user_id`.
+async fn link_identity(
+    db: &DatabaseConnection,
+    cfg: &OidcConfig,
+    subject: &str,
+    user_id: i64,
+) -> Result<UserModel, SsoError> {
+    match IdentityModel::find(db, &cfg.issuer, subject).await? {
+        Some(identity) if identity.user_id != user_id => {
+            return Err(SsoError::Conflict(
+                "This SSO account is already linked to another user".into(),
+            ));
+        }
+        Some(_) => {}
+        None => {
+            if IdentityModel::for_user(db, &cfg.issuer, user_id)
+                .await?
+                .is_some()
+            {
+                return Err(SsoError::Conflict(
+                    "A different SSO account is already linked; unlink it first".into(),
+                ));
+            }
+            IdentityModel::create(db, &cfg.issuer, subject, user_id).await?;
+        }
+    }
+    UserEntity::find_by_id(user_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| SsoError::Invalid("User not found".into()))
+}
+
+/// The FitchFork user for the provider's `subject`, creating one on their first sign-in. A
+/// provider user whose email or username belongs to an existing account gets a conflict: its
+/// owner links it from their account instead.
+async fn resolve_user(
+    db: &DatabaseConnection,
+    cfg: &OidcConfig,
+    subject: &str,
+    claims: &IdClaims,
+) -> Result<UserModel, SsoError> {
+    if let Some(identity) = IdentityModel::find(db, &cfg.issuer, subject).await?
+        && let Some(user) = UserEntity::find_by_id(identity.user_id).one(db).await?
+    {
+        return Ok(user);
+    }
+
+    let email = claims.str("email");
+    let existing = match email {
+        Some(email) => {
+            UserEntity::find()
+                .filter(UserColumn::Email.eq(email))
+                .one(db)
+                .await?
+        }
+        None => None,
+    };
+    if existing.is_some() {
+        return Err(SsoError::Conflict(
+            "An account with this email already exists; sign in with your password and link \
+             your SSO account from there"
+                .into(),
+        ));
+    }
+    if !cfg.auto_provision {
+        return Err(SsoError::Forbidden(
+            "No FitchFork account matches this SSO account".into(),
+        ));
+    }
+
+    let email = email
+        .ok_or_else(|| SsoError::Invalid("The provider did not share the user's email".into()))?;
+    let username = claims.str(&cfg.username_claim).ok_or_else(|| {
+        SsoError::Invalid(format!(
+            "The provider did not share the {} claim",
+            cfg.username_claim
+        ))
+    })?;
+    let taken = UserEntity::find()
+        .filter(UserColumn::Username.eq(username))
+        .one(db)
+        .await?;
+    if taken.is_some() {
+        return Err(SsoError::Conflict(format!(
+            "An account with username {username} already exists; sign in with your password \
+             and link your SSO account from there"
+        )));
+    }
+    let user = UserModel::create(db, username, email, &random_token(32), false).await?;
+
+    IdentityModel::create(db, &cfg.issuer, subject, user.id).await?;
+    Ok(user)
+}
+
+/// The grants of the rules whose value appears in the user's roles claim.
+pub fn matching_grants<'a>(rules: &'a [OidcRoleRule], values: &[String]) -> Vec<&'a OidcGrant> {
+    rules
+        .iter()
+        .filter(|rule| values.contains(&rule.value))
+        .map(|rule| &rule.grant)
+        .collect()
+}
+
+/// Applies the role mapping rules matching the user's roles claim `values`. Rules only grant:
+/// they make the user an admin, or give them a role in the latest offering of a module they
+/// are not in yet. Nothing is taken away when a value disappears from the claim.
+async fn apply_role_rules(
+    db: &DatabaseConnection,
+    rules: &[OidcRoleRule],
+    values: &[String],
+    mut user: UserModel,
+) -> Result<UserModel, DbErr> {
+    for grant in matching_grants(rules, values) {
+        match grant {
+            OidcGrant::Admin => {
+                if !user.admin {
+                    let mut active: user::ActiveModel = user.into();
+                    active.admin = Set(true);
+                    active.updated_at = Set(Utc::now());
+                    user = active.update(db).await?;
+                }
+            }
+            OidcGrant::Module { code, role } => {
+                let Ok(role) = serde_json::from_value::<Role>(Value::String(role.clone())) else {
+                    continue;
+                };
+                let Some(module) = ModuleEntity::find()
+                    .filter(ModuleColumn::Code.eq(code.as_str()))
+                    .order_by_desc(ModuleColumn::Year)
+                    .one(db)
+                    .await?
+                else {
+                    tracing::warn!("OIDC role rule names unknown module {}", code);
+                    continue;
+                };
+                let existing = user_module_role::Entity::find()
+                    .filter(user_module_role::Column::UserId.eq(user.id))
+                    .filter(user_module_role::Column::ModuleId.eq(module.id))
+                    .one(db)
+                    .await?;
+                if existing.is_none() {
+                    user_module_role::Model::assign_user_to_module(db, user.id, module.id, role)
+                        .await?;
+                }
+            }
+        }
+    }
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn code_challenge_matches_rfc_7636() {
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn roles_claim_may_be_a_string_or_a_list() {
+        let claims = IdClaims(
+            json!({ "groups": ["staff", "cos301-tutors"], "role": "student" })
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        assert_eq!(claims.values("groups"), ["staff", "cos301-tutors"]);
+        assert_eq!(claims.values("role"), ["student"]);
+        assert!(claims.values("missing").is_empty());
+
+        let rules = [
+            OidcRoleRule {
+                value: "staff".into(),
+                grant: OidcGrant::Admin,
+            },
+            OidcRoleRule {
+                value: "cos212-tutors".into(),
+                grant: OidcGrant::Module {
+                    code: "COS212".into(),
+                    role: "tutor".into(),
+                },
+            },
+        ];
+        assert_eq!(
+            matching_grants(&rules, &claims.values("groups")),
+            [&OidcGrant::Admin]
+        );
+    }
+}
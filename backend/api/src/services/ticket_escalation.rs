@@ -0,0 +1,73 @@
+//! Ticket escalation.
+//!
+//! [`escalate_unanswered_tickets`] finds open tickets nobody but their author has replied to
+//! within `TICKET_ESCALATION_HOURS` and notifies the module's lecturers about each of them,
+//! once. `main` runs it every `TICKET_ESCALATION_INTERVAL_SECS`.
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    assignment::Entity as AssignmentEntity,
+    notification::NotificationKind,
+    tickets::Model as TicketModel,
+    user_module_role::{Model as UserModuleRole, Role},
+};
+use sea_orm::{DbErr, EntityTrait};
+use util::{config, state::AppState};
+
+use crate::services::notifications::{self, NewNotification};
+
+/// Escalates every ticket that has gone unanswered for too long as of `now`, returning how many
+/// were escalated.
+pub async fn escalate_unanswered_tickets(
+    app: &AppState,
+    now: DateTime<Utc>,
+) -> Result<usize, DbErr> {
+    let db = app.db();
+    let hours = config::ticket_escalation_hours() as i64;
+    let due = TicketModel::due_for_escalation(db, now, hours).await?;
+
+    let mut escalated = 0;
+    for ticket in due {
+        // Another sweep may have got there first
+        if !TicketModel::mark_escalated(db, ticket.id).await? {
+            continue;
+        }
+        escalated += 1;
+
+        let Some(assignment) = AssignmentEntity::find_by_id(ticket.assignment_id)
+            .one(db)
+            .await?
+        else {
+            continue;
+        };
+        let lecturers: Vec<i64> =
+            UserModuleRole::get_users_by_module_role(db, assignment.module_id, Role::Lecturer)
+                .await?
+                .into_iter()
+                .map(|r| r.user_id)
+                .collect();
+        if lecturers.is_empty() {
+            continue;
+        }
+
+        notifications::notify(
+            app,
+            &lecturers,
+            &NewNotification {
+                kind: NotificationKind::TicketEscalated,
+                title: format!("Ticket unanswered for {hours}h: {}", ticket.title),
+                body: format!(
+                    "A {} priority ticket on {} has had no staff reply since it was opened.",
+                    ticket.priority, assignment.name
+                ),
+                link: Some(format!(
+                    "/modules/{}/assignments/{}/tickets/{}",
+                    assignment.module_id, ticket.assignment_id, ticket.id
+                )),
+            },
+        )
+        .await;
+    }
+
+    Ok(escalated)
+}
@@ -0,0 +1,90 @@
+//! Two-factor authentication policy.
+//!
+//! Decides what a user owes after a correct password ([`login_step`]): nothing, a code (once
+//! they have enabled two-factor authentication), or setting it up first — the last when an admin
+//! has switched on [`REQUIRE_STAFF_TWO_FACTOR`] and the user is staff. Staff are admins and
+//! anyone with a lecturer, assistant lecturer or tutor role in a module.
+//!
+//! SSO and LTI logins owe the same: they finish with [`redirect_login`] rather than a session
+//! straight away, since a second factor at the identity provider or LMS can't be relied on.
+
+use db::models::{
+    system_setting::{self, REQUIRE_STAFF_TWO_FACTOR},
+    user, user_module_role, user_two_factor,
+};
+use sea_orm::{ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::auth::{generate_jwt, generate_two_factor_challenge};
+
+/// Issuer shown next to the account in authenticator apps.
+pub const ISSUER: &str = "FitchFork";
+
+/// What stands between a correct password and a session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoginStep {
+    /// Nothing; sign the user in.
+    Done,
+    /// A TOTP or backup code.
+    Code,
+    /// Staff must set up two-factor authentication before signing in.
+    Setup,
+}
+
+/// Whether staff must use two-factor authentication.
+pub async fn staff_required(db: &DatabaseConnection) -> Result<bool, DbErr> {
+    system_setting::Model::get_flag(db, REQUIRE_STAFF_TWO_FACTOR).await
+}
+
+pub async fn is_staff(db: &DatabaseConnection, user: &user::Model) -> Result<bool, DbErr> {
+    if user.admin {
+        return Ok(true);
+    }
+    let roles = user_module_role::Entity::find()
+        .filter(user_module_role::Column::UserId.eq(user.id))
+        .filter(user_module_role::Column::Role.ne(user_module_role::Role::Student))
+        .count(db)
+        .await?;
+    Ok(roles > 0)
+}
+
+/// Whether the user may not go without two-factor authentication.
+pub async fn is_required(db: &DatabaseConnection, user: &user::Model) -> Result<bool, DbErr> {
+    Ok(staff_required(db).await? && is_staff(db, user).await?)
+}
+
+pub async fn login_step(db: &DatabaseConnection, user: &user::Model) -> Result<LoginStep, DbErr> {
+    if user_two_factor::Model::is_enabled(db, user.id).await? {
+        Ok(LoginStep::Code)
+    } else if is_required(db, user).await? {
+        Ok(LoginStep::Setup)
+    } else {
+        Ok(LoginStep::Done)
+    }
+}
+
+/// The fragment parameters a browser login (SSO, LTI) redirects to the frontend with: `token`
+/// and `expires_at` for a session with `admin` rights, or, when [`login_step`] asks for more,
+/// `two_factor_required`, `setup_required` and a `challenge_token` to finish with at
+/// `POST /api/auth/login/2fa` like a password login.
+pub async fn redirect_login(
+    db: &DatabaseConnection,
+    user: &user::Model,
+    admin: bool,
+) -> Result<Vec<(&'static str, String)>, DbErr> {
+    let setup_required = match login_step(db, user).await? {
+        LoginStep::Done => {
+            let (token, expires_at) = generate_jwt(user.id, admin);
+            return Ok(vec![("token", token), ("expires_at", expires_at)]);
+        }
+        LoginStep::Code => false,
+        LoginStep::Setup => true,
+    };
+    Ok(vec![
+        ("two_factor_required", "true".to_string()),
+        ("setup_required", setup_required.to_string()),
+        (
+            "challenge_token",
+            generate_two_factor_challenge(user.id, admin),
+        ),
+    ])
+}
@@ -0,0 +1,34 @@
+// api/src/ws/announcements/emit.rs
+use serde::Serialize;
+use util::ws::WebSocketManager;
+
+use crate::ws::core::{envelope, event::Event};
+use crate::ws::types::ClientTopic;
+
+use super::payload;
+
+/* ------------ Events (typed, stable names) ------------ */
+
+#[derive(Debug, Serialize)]
+pub struct AnnouncementPublished {
+    #[serde(flatten)]
+    pub payload: payload::Announcement,
+}
+impl Event for AnnouncementPublished {
+    const NAME: &'static str = "announcement.published";
+    fn topic_path(&self) -> String {
+        ClientTopic::ModuleAnnouncements {
+            module_id: self.payload.module_id,
+        }
+        .path()
+    }
+}
+
+/* ------------ One-liner emit helpers ------------ */
+
+pub async fn published(ws: &WebSocketManager, announcement: payload::Announcement) {
+    let ev = AnnouncementPublished {
+        payload: announcement,
+    };
+    envelope::emit(ws, &ev).await;
+}
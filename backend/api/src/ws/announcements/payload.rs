@@ -0,0 +1,15 @@
+// api/src/ws/announcements/payload.rs
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Announcement {
+    pub id: i64,
+    pub module_id: i64,
+    pub user_id: i64,
+    pub title: String,
+    pub body: String,
+    pub pinned: bool,
+    pub published_at: String, // RFC3339
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>, // RFC3339
+}
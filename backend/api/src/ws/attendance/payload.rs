@@ -9,6 +9,9 @@ pub struct SessionUpdated {
     pub restrict_by_ip: bool,
     pub allowed_ip_cidr: Option<String>,
     pub created_from_ip: Option<String>,
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
+    pub geo_radius_m: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -5,12 +5,14 @@ use crate::auth::guards::{is_superuser, user_has_any_role};
 use crate::ws::types::ClientTopic;
 
 use db::models::assignment::{Column as AssCol, Entity as AssEntity};
+use db::models::assignment_submission::{Column as SubCol, Entity as SubEntity};
 use db::models::attendance_session::{Column as SessCol, Entity as SessEntity};
 use db::models::tickets::{Column as TicCol, Entity as TicEntity};
 
 /// staff sets reused below
 const STAFF_ROLES: &[&str] = &["Lecturer", "AssistantLecturer"];
 const STAFF_ROLES_WITH_TUTORS: &[&str] = &["Lecturer", "AssistantLecturer", "Tutor"];
+const MEMBER_ROLES: &[&str] = &["Lecturer", "AssistantLecturer", "Tutor", "Student"];
 
 /// Result type that lets the caller send a specific reason back to the client.
 pub enum TopicAuth {
@@ -130,6 +132,53 @@ pub async fn authorize_topic(
                 TopicAuth::Denied("not_owner")
             }
         }
+
+        // ------------------------
+        // GA progress: submission owner OR module staff (incl. tutors) OR admin/superuser
+        // ------------------------
+        ClientTopic::GaRun { submission_id } => {
+            let sid = *submission_id;
+            match submission_owner_and_module(db, sid).await {
+                Some((owner_id, module_id)) => {
+                    if user.0.admin
+                        || user.0.sub == owner_id
+                        || is_superuser(user.0.sub).await
+                        || user_has_any_role(db, user.0.sub, module_id, STAFF_ROLES_WITH_TUTORS)
+                            .await
+                    {
+                        TopicAuth::Allowed
+                    } else {
+                        TopicAuth::Denied("not_allowed_for_submission")
+                    }
+                }
+                None => TopicAuth::Denied("submission_not_found"),
+            }
+        }
+
+        // ------------------------
+        // User notifications (owner-only)
+        // ------------------------
+        ClientTopic::UserNotifications { user_id } => {
+            if user.0.sub == *user_id {
+                TopicAuth::Allowed
+            } else {
+                TopicAuth::Denied("not_owner")
+            }
+        }
+
+        // ------------------------
+        // Module announcements: any module member OR admin/superuser
+        // ------------------------
+        ClientTopic::ModuleAnnouncements { module_id } => {
+            if user.0.admin
+                || is_superuser(user.0.sub).await
+                || user_has_any_role(db, user.0.sub, *module_id, MEMBER_ROLES).await
+            {
+                TopicAuth::Allowed
+            } else {
+                TopicAuth::Denied("not_module_member")
+            }
+        }
     }
 }
 
@@ -193,3 +242,22 @@ async fn ticket_owner_and_module(
     let module_id = module_id_for_assignment(db, assignment_id).await?;
     Some((owner_id, module_id))
 }
+
+/// returns (submission_owner_id, module_id)
+async fn submission_owner_and_module(
+    db: &sea_orm::DatabaseConnection,
+    submission_id: i64,
+) -> Option<(i64, i64)> {
+    let (owner_id, assignment_id) = SubEntity::find()
+        .select_only()
+        .column(SubCol::UserId)
+        .column(SubCol::AssignmentId)
+        .filter(SubCol::Id.eq(submission_id))
+        .into_tuple::<(i64, i64)>()
+        .one(db)
+        .await
+        .ok()??;
+
+    let module_id = module_id_for_assignment(db, assignment_id).await?;
+    Some((owner_id, module_id))
+}
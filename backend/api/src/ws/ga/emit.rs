@@ -0,0 +1,37 @@
+// api/src/ws/ga/emit.rs
+use serde::Serialize;
+use util::ws::WebSocketManager;
+
+use super::payload::GaProgressPayload;
+use crate::ws::core::{envelope, event::Event};
+use crate::ws::types::ClientTopic;
+
+/* =========================
+EVENTS
+========================= */
+
+#[derive(Debug, Serialize)]
+pub struct GaProgressEvent {
+    #[serde(flatten)]
+    pub payload: GaProgressPayload,
+}
+
+impl Event for GaProgressEvent {
+    const NAME: &'static str = "ga.progress";
+    fn topic_path(&self) -> String {
+        ClientTopic::GaRun {
+            submission_id: self.payload.submission_id,
+        }
+        .path()
+    }
+}
+
+/* =========================
+HELPERS
+========================= */
+
+/// Emit a finished generation to the submission's GA topic.
+pub async fn progress(ws: &WebSocketManager, payload: GaProgressPayload) {
+    let ev = GaProgressEvent { payload };
+    envelope::emit(ws, &ev).await;
+}
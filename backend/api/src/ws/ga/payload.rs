@@ -0,0 +1,22 @@
+// api/src/ws/ga/payload.rs
+use serde::Serialize;
+
+/// Progress of a GATLAM / code coverage GA run, sent after every generation.
+#[derive(Debug, Clone, Serialize)]
+pub struct GaProgressPayload {
+    pub module_id: i64,
+    pub assignment_id: i64,
+    pub submission_id: i64,
+    /// Zero-based index of the generation that just finished.
+    pub generation: usize,
+    pub generations: usize,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    /// Best coverage percent in the generation (code coverage runs only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coverage_percent: Option<f64>,
+    /// Chromosomes scored so far, including ones answered from the fitness cache.
+    pub evaluated: usize,
+    pub interpreter_runs: usize,
+    pub cache_hits: usize,
+}
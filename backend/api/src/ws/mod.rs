@@ -8,8 +8,12 @@ pub mod auth;
 mod mux;
 pub mod types;
 
+pub mod announcements;
 pub mod attendance;
 pub mod core;
+pub mod ga;
+pub mod notifications;
+pub mod regrades;
 pub mod submissions;
 pub mod system;
 pub mod tickets;
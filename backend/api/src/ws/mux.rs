@@ -16,7 +16,7 @@ use super::{
     auth::authorize_topic,
     types::{WsIn, WsOut},
 };
-use crate::{auth::claims::AuthUser, ws::auth::TopicAuth};
+use crate::{auth::claims::AuthUser, services::metrics::WsConnectionGuard, ws::auth::TopicAuth};
 
 pub async fn ws_multiplex_entry(
     ws: WebSocketUpgrade,
@@ -38,6 +38,7 @@ impl Subs {
 }
 
 async fn serve(socket: WebSocket, app: AppState, user: AuthUser) {
+    let _connection = WsConnectionGuard::new();
     let (mut sink, mut rx) = socket.split();
 
     let (tx_out, mut rx_out) = tokio::sync::mpsc::channel::<Message>(256);
@@ -0,0 +1,37 @@
+// api/src/ws/notifications/emit.rs
+use serde::Serialize;
+use util::ws::WebSocketManager;
+
+use crate::ws::core::{envelope, event::Event};
+use crate::ws::types::ClientTopic;
+
+use super::payload;
+
+/* ------------ Events (typed, stable names) ------------ */
+
+#[derive(Debug, Serialize)]
+pub struct NotificationCreated {
+    #[serde(flatten)]
+    pub payload: payload::Notification,
+    #[serde(skip)]
+    pub user_id: i64,
+}
+impl Event for NotificationCreated {
+    const NAME: &'static str = "notification.created";
+    fn topic_path(&self) -> String {
+        ClientTopic::UserNotifications {
+            user_id: self.user_id,
+        }
+        .path()
+    }
+}
+
+/* ------------ One-liner emit helpers ------------ */
+
+pub async fn created(ws: &WebSocketManager, user_id: i64, notification: payload::Notification) {
+    let ev = NotificationCreated {
+        user_id,
+        payload: notification,
+    };
+    envelope::emit(ws, &ev).await;
+}
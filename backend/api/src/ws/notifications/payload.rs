@@ -0,0 +1,13 @@
+// api/src/ws/notifications/payload.rs
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub id: i64,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    pub created_at: String, // RFC3339
+}
@@ -0,0 +1,75 @@
+// api/src/ws/regrades/emit.rs
+use serde::Serialize;
+use util::ws::WebSocketManager;
+
+use crate::ws::core::{envelope, event::Event};
+use crate::ws::types::ClientTopic;
+
+use super::payload;
+
+/* ------------ Events (typed, stable names) ------------ */
+
+#[derive(Debug, Serialize)]
+pub struct RegradeOpenedStaff {
+    #[serde(flatten)]
+    pub payload: payload::Regrade,
+}
+impl Event for RegradeOpenedStaff {
+    const NAME: &'static str = "regrade.opened";
+    fn topic_path(&self) -> String {
+        ClientTopic::AssignmentSubmissionsStaff {
+            assignment_id: self.payload.assignment_id,
+        }
+        .path()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegradeUpdatedStaff {
+    #[serde(flatten)]
+    pub payload: payload::Regrade,
+}
+impl Event for RegradeUpdatedStaff {
+    const NAME: &'static str = "regrade.updated";
+    fn topic_path(&self) -> String {
+        ClientTopic::AssignmentSubmissionsStaff {
+            assignment_id: self.payload.assignment_id,
+        }
+        .path()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RegradeUpdatedOwner {
+    #[serde(flatten)]
+    pub payload: payload::Regrade,
+}
+impl Event for RegradeUpdatedOwner {
+    const NAME: &'static str = "regrade.updated";
+    fn topic_path(&self) -> String {
+        ClientTopic::AssignmentSubmissionsOwner {
+            assignment_id: self.payload.assignment_id,
+            user_id: self.payload.user_id,
+        }
+        .path()
+    }
+}
+
+/* ------------ One-liner emit helpers ------------ */
+
+/// A student opened a request: tell the assignment's staff.
+pub async fn opened(ws: &WebSocketManager, regrade: payload::Regrade) {
+    envelope::emit(ws, &RegradeOpenedStaff { payload: regrade }).await;
+}
+
+/// Staff responded: tell the student and the other staff.
+pub async fn updated(ws: &WebSocketManager, regrade: payload::Regrade) {
+    envelope::emit(
+        ws,
+        &RegradeUpdatedOwner {
+            payload: regrade.clone(),
+        },
+    )
+    .await;
+    envelope::emit(ws, &RegradeUpdatedStaff { payload: regrade }).await;
+}
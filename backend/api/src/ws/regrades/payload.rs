@@ -0,0 +1,32 @@
+// api/src/ws/regrades/payload.rs
+use db::models::regrade_request::Model as RegradeModel;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Regrade {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub submission_id: i64,
+    pub user_id: i64,
+    pub task_number: i64,
+    pub subsection: Option<String>,
+    pub status: String,
+    pub response: Option<String>,
+    pub updated_at: String, // RFC3339
+}
+
+impl From<&RegradeModel> for Regrade {
+    fn from(r: &RegradeModel) -> Self {
+        Self {
+            id: r.id,
+            assignment_id: r.assignment_id,
+            submission_id: r.submission_id,
+            user_id: r.user_id,
+            task_number: r.task_number,
+            subsection: r.subsection.clone(),
+            status: r.status.to_string(),
+            response: r.response.clone(),
+            updated_at: r.updated_at.to_rfc3339(),
+        }
+    }
+}
@@ -4,7 +4,7 @@ use util::ws::WebSocketManager;
 
 use super::payload::SubmissionStatusPayload;
 use crate::ws::core::{envelope, event::Event};
-use crate::ws::submissions::payload::SubmissionNewPayload;
+use crate::ws::submissions::payload::{SubmissionNewPayload, SubmissionOutputPayload};
 use crate::ws::types::ClientTopic;
 
 /* =========================
@@ -69,6 +69,45 @@ impl Event for SubmissionNewStaffEvent {
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct SubmissionOutputOwnerEvent {
+    #[serde(flatten)]
+    pub payload: SubmissionOutputPayload,
+    #[serde(skip)]
+    pub assignment_id: i64,
+    #[serde(skip)]
+    pub user_id: i64,
+}
+
+impl Event for SubmissionOutputOwnerEvent {
+    const NAME: &'static str = "submission.output";
+    fn topic_path(&self) -> String {
+        ClientTopic::AssignmentSubmissionsOwner {
+            assignment_id: self.assignment_id,
+            user_id: self.user_id,
+        }
+        .path()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmissionOutputStaffEvent {
+    #[serde(flatten)]
+    pub payload: SubmissionOutputPayload,
+    #[serde(skip)]
+    pub assignment_id: i64,
+}
+
+impl Event for SubmissionOutputStaffEvent {
+    const NAME: &'static str = "submission.output";
+    fn topic_path(&self) -> String {
+        ClientTopic::AssignmentSubmissionsStaff {
+            assignment_id: self.assignment_id,
+        }
+        .path()
+    }
+}
+
 /* =========================
 HELPERS
 ========================= */
@@ -134,3 +173,24 @@ pub async fn new_submission_staff(
     };
     envelope::emit(ws, &ev).await;
 }
+
+/// Emit a live output chunk to both the owner and staff topics.
+pub async fn output(
+    ws: &WebSocketManager,
+    assignment_id: i64,
+    user_id: i64,
+    payload: SubmissionOutputPayload,
+) {
+    let ev_owner = SubmissionOutputOwnerEvent {
+        payload: payload.clone(),
+        assignment_id,
+        user_id,
+    };
+    envelope::emit(ws, &ev_owner).await;
+
+    let ev_staff = SubmissionOutputStaffEvent {
+        payload,
+        assignment_id,
+    };
+    envelope::emit(ws, &ev_staff).await;
+}
@@ -31,3 +31,17 @@ pub struct SubmissionNewPayload {
     /// RFC3339
     pub created_at: String,
 }
+
+/// Live stdout/stderr produced by a task while the submission is still running.
+#[derive(Debug, Clone, Serialize)]
+pub struct SubmissionOutputPayload {
+    pub module_id: i64,
+    pub assignment_id: i64,
+    pub submission_id: i64,
+    pub attempt: i64,
+    pub task_id: i64,
+    pub task_number: i64,
+    /// "stdout" | "stderr"
+    pub stream: String,
+    pub data: String,
+}
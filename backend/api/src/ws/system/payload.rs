@@ -49,6 +49,30 @@ pub struct DiskSummary {
     pub mount_point: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuInfo {
+    pub index: u32,
+    pub name: String,
+    /// Percent busy.
+    pub utilization: f32,
+    pub mem_total: u64,
+    pub mem_used: u64,
+}
+
+/// A running container; `job` is the code_manager job id (e.g. `submission-42`) for run
+/// containers.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    /// Percent of one core.
+    pub cpu_usage: f32,
+    pub mem_used: u64,
+    pub mem_limit: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<String>,
+}
+
 /* =========================
 GENERAL PAYLOAD
 ========================= */
@@ -79,5 +103,9 @@ pub struct SystemHealthAdminPayload {
     pub cpu: CpuInfo,
     pub memory: MemoryInfo,
     pub disks: Vec<DiskSummary>,
+    /// Empty when the host has no (NVIDIA) GPU.
+    pub gpus: Vec<GpuInfo>,
+    /// Empty when Docker is not reachable from the API host.
+    pub containers: Vec<ContainerInfo>,
     pub code_manager: CodeManagerAdmin,
 }
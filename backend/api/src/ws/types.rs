@@ -72,6 +72,15 @@ pub enum ClientTopic {
     // Submissions
     AssignmentSubmissionsStaff { assignment_id: i64 },
     AssignmentSubmissionsOwner { assignment_id: i64, user_id: i64 },
+
+    // GA progress for one submission's GATLAM / code coverage run
+    GaRun { submission_id: i64 }, // "ga:{submission_id}"
+
+    // Notifications for one user
+    UserNotifications { user_id: i64 }, // "user:{user_id}.notifications"
+
+    // Announcements going live in a module
+    ModuleAnnouncements { module_id: i64 }, // "module:{module_id}.announcements"
 }
 
 impl ClientTopic {
@@ -90,6 +99,11 @@ impl ClientTopic {
                 assignment_id,
                 user_id,
             } => format!("assignment:{assignment_id}.submissions:user:{user_id}"),
+            ClientTopic::GaRun { submission_id } => format!("ga:{submission_id}"),
+            ClientTopic::UserNotifications { user_id } => format!("user:{user_id}.notifications"),
+            ClientTopic::ModuleAnnouncements { module_id } => {
+                format!("module:{module_id}.announcements")
+            }
         }
     }
 }
@@ -13,6 +13,26 @@ pub async fn make_test_app() -> (
     AppState,
 ) {
     let db: DatabaseConnection = db::test_utils::setup_test_db().await;
+    make_test_app_with_db(db)
+}
+
+/// Same as [`make_test_app`], but backed by a multi-connection in-memory database so
+/// concurrent requests (or handlers that spawn their own DB work) don't queue on one connection.
+pub async fn make_pooled_test_app() -> (
+    BoxCloneService<Request<Body>, Response, Infallible>,
+    AppState,
+) {
+    let db = db::test_utils::setup_pooled_test_db(8).await;
+    make_test_app_with_db(db)
+}
+
+/// Builds the full API + WS router on top of an already-prepared database.
+pub fn make_test_app_with_db(
+    db: DatabaseConnection,
+) -> (
+    BoxCloneService<Request<Body>, Response, Infallible>,
+    AppState,
+) {
     let ws = WebSocketManager::new();
     let app_state = AppState::new(db, ws);
 
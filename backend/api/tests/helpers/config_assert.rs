@@ -3,22 +3,26 @@ use serde_json::Value;
 /// Assert that a config JSON equals the library defaults.
 /// Pass the **object under "data"** from the /config response: `assert_default_config(&json["data"])`.
 pub fn assert_default_config(d: &Value) {
+    assert_eq!(d["config_version"], util::execution_config::CONFIG_VERSION);
+
     // ---------- execution ----------
     assert_eq!(d["execution"]["timeout_secs"], 30);
     assert_eq!(d["execution"]["max_memory"], 8_589_934_592u64);
     assert_eq!(d["execution"]["max_cpus"], 2);
     assert_eq!(d["execution"]["max_uncompressed_size"], 100_000_000u64);
     assert_eq!(d["execution"]["max_processes"], 256);
+    assert_eq!(d["execution"]["max_disk_bytes"], 1_073_741_824u64);
 
     // ---------- marking ----------
     assert_eq!(d["marking"]["marking_scheme"], "exact");
     assert_eq!(d["marking"]["feedback_scheme"], "auto");
-    assert_eq!(d["marking"]["deliminator"], "###");
+    assert_eq!(d["marking"]["delimiter"], "###");
     assert_eq!(d["marking"]["grading_policy"], "last");
     assert_eq!(d["marking"]["max_attempts"], 10);
     assert_eq!(d["marking"]["limit_attempts"], true);
     assert_eq!(d["marking"]["pass_mark"], 50);
     assert_eq!(d["marking"]["allow_practice_submissions"], false);
+    assert_eq!(d["marking"]["reduced_practice_feedback"], false);
     assert!(
         d["marking"]["dissalowed_code"]
             .as_array()
@@ -58,6 +62,9 @@ pub fn assert_default_config(d: &Value) {
 
     assert_eq!(d["gatlam"]["crossover_type"], "onepoint");
     assert_eq!(d["gatlam"]["mutation_type"], "bitflip");
+    assert_eq!(d["gatlam"]["selection_type"], "roulette");
+    assert_eq!(d["gatlam"]["tournament_size"], 3);
+    assert_eq!(d["gatlam"]["elitism_count"], 0);
     approx(&d["gatlam"]["omega1"], 0.5, "gatlam.omega1");
     approx(&d["gatlam"]["omega2"], 0.3, "gatlam.omega2");
     approx(&d["gatlam"]["omega3"], 0.2, "gatlam.omega3");
@@ -93,6 +100,8 @@ pub fn assert_default_config(d: &Value) {
     assert!(d["security"]["password_pin"].is_null());
     assert_eq!(d["security"]["cookie_ttl_minutes"], 480);
     assert_eq!(d["security"]["bind_cookie_to_user"], true);
+    assert_eq!(d["security"]["seccomp_profile"], "strict");
+    assert!(d["security"]["apparmor_profile"].is_null());
     assert!(
         d["security"]["allowed_cidrs"]
             .as_array()
@@ -109,6 +118,16 @@ pub fn assert_default_config(d: &Value) {
             .is_empty(),
         "code_coverage.whitelist should default to an empty array"
     );
+
+    // ---------- output ----------
+    assert_eq!(d["output"]["max_output_bytes"], 4_194_304);
+    assert_eq!(d["output"]["max_artifact_bytes"], 20_971_520);
+
+    // ---------- environment ----------
+    assert!(
+        d["environment"].as_object().unwrap().is_empty(),
+        "environment should default to an empty object"
+    );
 }
 fn approx(v: &Value, expected: f64, path: &str) {
     let got = v.as_f64().unwrap();
@@ -1,5 +1,7 @@
 pub mod app;
 pub mod config_assert;
+pub mod lti;
+pub mod oidc;
 pub mod ws;
 
 pub use app::{make_test_app, make_test_app_with_storage};
@@ -0,0 +1,84 @@
+//! A stand-in OpenID Connect provider for the SSO tests: it serves a discovery document and
+//! [`super::lti::PLATFORM_KEY_PEM`]'s public key, and its token endpoint answers every code with
+//! an id token carrying the claims the test last gave it.
+
+use axum::{
+    Json, Router,
+    extract::{Form, State},
+    routing::{get, post},
+};
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+
+use super::lti::{PLATFORM_KEY_PEM, PLATFORM_KID, platform_jwks};
+
+pub const CLIENT_ID: &str = "fitchfork-sso";
+
+#[derive(Clone)]
+pub struct Provider {
+    pub issuer: String,
+    /// Claims of the next id token; `iss`, `aud`, `iat` and `exp` are filled in.
+    pub claims: Arc<Mutex<Value>>,
+    pub token_requests: Arc<Mutex<Vec<HashMap<String, String>>>>,
+}
+
+impl Provider {
+    pub fn next_claims(&self, claims: Value) {
+        *self.claims.lock().unwrap() = claims;
+    }
+}
+
+async fn token(
+    State(p): State<Provider>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Json<Value> {
+    p.token_requests.lock().unwrap().push(form);
+    let mut claims = p.claims.lock().unwrap().clone();
+    let now = Utc::now().timestamp();
+    claims["iss"] = json!(p.issuer);
+    claims["aud"] = json!(CLIENT_ID);
+    claims["iat"] = json!(now);
+    claims["exp"] = json!(now + 300);
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(PLATFORM_KID.to_string());
+    let id_token = encode(
+        &header,
+        &claims,
+        &EncodingKey::from_rsa_pem(PLATFORM_KEY_PEM.as_bytes()).unwrap(),
+    )
+    .unwrap();
+    Json(json!({ "access_token": "idp-token", "token_type": "Bearer", "id_token": id_token }))
+}
+
+/// Serves the provider on a free local port; its issuer is its base URL.
+pub async fn spawn_provider() -> Provider {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let issuer = format!("http://{}", listener.local_addr().unwrap());
+    let provider = Provider {
+        issuer: issuer.clone(),
+        claims: Arc::new(Mutex::new(json!({}))),
+        token_requests: Arc::default(),
+    };
+    let discovery = json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{issuer}/authorize"),
+        "token_endpoint": format!("{issuer}/token"),
+        "jwks_uri": format!("{issuer}/jwks"),
+    });
+    let router = Router::new()
+        .route(
+            "/.well-known/openid-configuration",
+            get(move || async move { Json(discovery) }),
+        )
+        .route("/jwks", get(|| async { Json(platform_jwks()) }))
+        .route("/token", post(token))
+        .with_state(provider.clone());
+    tokio::spawn(async move {
+        axum::serve(listener, router).await.unwrap();
+    });
+    provider
+}
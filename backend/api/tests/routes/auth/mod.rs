@@ -1,2 +1,4 @@
 pub mod get_test;
 pub mod post_test;
+pub mod sso_test;
+pub mod two_factor_test;
@@ -0,0 +1,449 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{
+            Request, StatusCode,
+            header::{COOKIE, LOCATION, SET_COOKIE},
+        },
+    };
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use db::models::{
+        module,
+        sso_identity::Model as IdentityModel,
+        user::{Column as UserColumn, Entity as UserEntity, Model as UserModel},
+        user_module_role::{Column as RoleColumn, Entity as RoleEntity, Role},
+        user_two_factor::Model as TwoFactorModel,
+    };
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use sha2::{Digest, Sha256};
+    use std::collections::HashMap;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+    use crate::helpers::oidc::{CLIENT_ID, Provider, spawn_provider};
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    const OIDC_VARS: &[&str] = &[
+        "OIDC_ISSUER",
+        "OIDC_CLIENT_ID",
+        "OIDC_CLIENT_SECRET",
+        "OIDC_REDIRECT_URL",
+        "OIDC_ROLE_RULES",
+    ];
+
+    fn enable_sso(p: &Provider) {
+        unsafe {
+            std::env::set_var("OIDC_ISSUER", &p.issuer);
+            std::env::set_var("OIDC_CLIENT_ID", CLIENT_ID);
+            std::env::set_var("OIDC_CLIENT_SECRET", "sso-secret");
+            std::env::set_var("OIDC_REDIRECT_URL", "http://api.test/api/auth/sso/callback");
+            std::env::set_var("OIDC_ROLE_RULES", "staff=admin;cos301-tutors=COS301:tutor");
+        }
+    }
+
+    fn disable_sso() {
+        for k in OIDC_VARS {
+            unsafe { std::env::remove_var(k) };
+        }
+    }
+
+    fn query_params(location: &str) -> HashMap<String, String> {
+        url::Url::parse(location)
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect()
+    }
+
+    fn fragment_params(location: &str) -> HashMap<String, String> {
+        let fragment = location.split_once('#').unwrap().1;
+        url::form_urlencoded::parse(fragment.as_bytes())
+            .into_owned()
+            .collect()
+    }
+
+    async fn get(app: &App, uri: &str) -> axum::response::Response {
+        let req = Request::builder().uri(uri).body(AxumBody::empty()).unwrap();
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    /// Starts a sign-in and returns the provider authorization URL's query.
+    async fn start_sign_in(app: &App) -> HashMap<String, String> {
+        let response = get(app, "/api/auth/sso/login?redirect=%2Fmodules").await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let params = query_params(response.headers()[LOCATION].to_str().unwrap());
+        let cookie = response.headers()[SET_COOKIE].to_str().unwrap();
+        assert!(cookie.starts_with(&format!("ff_sso_state={};", params["state"])));
+        assert!(cookie.contains("HttpOnly") && cookie.contains("SameSite=Lax"));
+        params
+    }
+
+    /// The provider's redirect back to the browser that started the sign-in.
+    async fn callback(app: &App, state: &str) -> axum::response::Response {
+        callback_with_cookie(app, state, Some(state)).await
+    }
+
+    async fn callback_with_cookie(
+        app: &App,
+        state: &str,
+        cookie: Option<&str>,
+    ) -> axum::response::Response {
+        let mut req = Request::builder().uri(format!(
+            "/api/auth/sso/callback?code=auth-code&state={state}"
+        ));
+        if let Some(cookie) = cookie {
+            req = req.header(COOKIE, format!("theme=dark; ff_sso_state={cookie}"));
+        }
+        app.clone()
+            .oneshot(req.body(AxumBody::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    /// Starts an account link as the holder of `token` and returns the provider authorization
+    /// URL's query.
+    async fn start_link(app: &App, token: &str) -> HashMap<String, String> {
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/auth/sso/link")
+            .header("Authorization", format!("Bearer {token}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        query_params(json["data"]["url"].as_str().unwrap())
+    }
+
+    /// Finishes an account link with the callback's `fragment` as the holder of `token`.
+    async fn complete_link(
+        app: &App,
+        token: &str,
+        fragment: &HashMap<String, String>,
+    ) -> axum::response::Response {
+        let body = json!({ "state": fragment["link_state"], "code": fragment["code"] });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/auth/sso/link/complete")
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .body(AxumBody::from(body.to_string()))
+            .unwrap();
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn sign_in_provisions_links_and_maps_roles() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let provider = spawn_provider().await;
+        enable_sso(&provider);
+        let cos301 = module::Model::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+
+        let response = get(&app, "/api/auth/sso").await;
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["enabled"], true);
+
+        // A new user gets an account, and a tutor role from the role rules
+        let params = start_sign_in(&app).await;
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["response_type"], "code");
+        assert_eq!(params["code_challenge_method"], "S256");
+        provider.next_claims(json!({
+            "sub": "idp-1",
+            "nonce": params["nonce"],
+            "email": "u30000001@tuks.test",
+            "email_verified": true,
+            "preferred_username": "u30000001",
+            "groups": ["cos301-tutors"],
+        }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let location = response.headers()[LOCATION].to_str().unwrap().to_string();
+        assert!(location.contains("/sso/callback#token="));
+        let fragment = fragment_params(&location);
+        assert_eq!(fragment["redirect"], "/modules");
+
+        let token_request = provider.token_requests.lock().unwrap()[0].clone();
+        assert_eq!(token_request["grant_type"], "authorization_code");
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(Sha256::digest(token_request["code_verifier"].as_bytes())),
+            params["code_challenge"]
+        );
+
+        let user = UserEntity::find()
+            .filter(UserColumn::Username.eq("u30000001"))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let role = RoleEntity::find()
+            .filter(RoleColumn::UserId.eq(user.id))
+            .filter(RoleColumn::ModuleId.eq(cos301.id))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(role.role, Role::Tutor);
+
+        // A state is good for one sign-in only
+        let replay = callback(&app, &params["state"]).await;
+        assert_eq!(replay.status(), StatusCode::UNAUTHORIZED);
+
+        // An existing password user isn't taken over on their email, even a verified one...
+        let lecturer = UserModel::create(db, "lecturer1", "lecturer1@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let staff_claims = |nonce: &str| {
+            json!({
+                "sub": "idp-2",
+                "nonce": nonce,
+                "email": "lecturer1@tuks.test",
+                "email_verified": true,
+                "groups": ["staff"],
+            })
+        };
+        let params = start_sign_in(&app).await;
+        provider.next_claims(staff_claims(&params["nonce"]));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(
+            IdentityModel::find(db, &provider.issuer, "idp-2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // ...they link it from their account, and are made admin by the role rules, but an SSO
+        // sign-in never hands out an admin session
+        let (session, _) = generate_jwt(lecturer.id, false);
+        let params = start_link(&app, &session).await;
+        provider.next_claims(staff_claims(&params["nonce"]));
+        let response = callback(&app, &params["state"]).await;
+        let fragment = fragment_params(response.headers()[LOCATION].to_str().unwrap());
+        let response = complete_link(&app, &session, &fragment).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let lecturer = UserEntity::find_by_id(lecturer.id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(lecturer.admin);
+
+        let params = start_sign_in(&app).await;
+        provider.next_claims(staff_claims(&params["nonce"]));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let fragment = fragment_params(response.headers()[LOCATION].to_str().unwrap());
+        let payload = fragment["token"].split('.').nth(1).unwrap();
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap();
+        assert_eq!(claims["sub"], lecturer.id);
+        assert_eq!(claims["admin"], false);
+
+        // Nor is one taken over on their username
+        UserModel::create(db, "student1", "student1@home.test", "pw", false)
+            .await
+            .unwrap();
+        let params = start_sign_in(&app).await;
+        provider.next_claims(json!({
+            "sub": "idp-3",
+            "nonce": params["nonce"],
+            "email": "student1@tuks.test",
+            "email_verified": true,
+            "preferred_username": "student1",
+        }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Nor can a sign-in be finished in a browser other than the one that started it, which
+        // would sign that browser in to the starter's account
+        let redeemed = provider.token_requests.lock().unwrap().len();
+        let params = start_sign_in(&app).await;
+        let response = callback_with_cookie(&app, &params["state"], None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let other = start_sign_in(&app).await;
+        let response = callback_with_cookie(&app, &params["state"], Some(&other["state"])).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(provider.token_requests.lock().unwrap().len(), redeemed);
+
+        // Nor do id tokens minted for another sign-in
+        let params = start_sign_in(&app).await;
+        provider.next_claims(json!({ "sub": "idp-1", "nonce": "not-the-nonce" }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        disable_sso();
+        let response = get(&app, "/api/auth/sso/login").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn password_users_can_link_and_unlink() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let provider = spawn_provider().await;
+        enable_sso(&provider);
+
+        let user = UserModel::create(db, "u40000001", "u40000001@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let (token, _) = generate_jwt(user.id, false);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/auth/sso/link")
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json")
+            .body(AxumBody::from(r#"{"redirect":"/settings"}"#))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let params = query_params(json["data"]["url"].as_str().unwrap());
+
+        // The provider's email differs from FitchFork's; the link doesn't depend on it
+        provider.next_claims(json!({
+            "sub": "idp-9",
+            "nonce": params["nonce"],
+            "email": "someone.else@idp.test",
+        }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let fragment = fragment_params(response.headers()[LOCATION].to_str().unwrap());
+        assert!(!fragment.contains_key("token"));
+        assert_eq!(fragment["link_state"], params["state"]);
+        assert!(
+            IdentityModel::find(db, &provider.issuer, "idp-9")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // The linking user's own session finishes it
+        let response = complete_link(&app, &token, &fragment).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["redirect"], "/settings");
+        let identity = IdentityModel::find(db, &provider.issuer, "idp-9")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(identity.user_id, user.id);
+
+        // Signing in through the provider now lands on the same account
+        let params = start_sign_in(&app).await;
+        provider.next_claims(json!({ "sub": "idp-9", "nonce": params["nonce"] }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+
+        // ...and still owes a two-factor code once they've turned it on
+        TwoFactorModel::begin_setup(db, user.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .enable(db)
+            .await
+            .unwrap();
+        let params = start_sign_in(&app).await;
+        provider.next_claims(json!({ "sub": "idp-9", "nonce": params["nonce"] }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let fragment = fragment_params(response.headers()[LOCATION].to_str().unwrap());
+        assert!(!fragment.contains_key("token"));
+        assert_eq!(fragment["two_factor_required"], "true");
+        assert_eq!(fragment["setup_required"], "false");
+        assert!(fragment.contains_key("challenge_token"));
+
+        let unlink = || {
+            Request::builder()
+                .method("DELETE")
+                .uri("/api/auth/sso/link")
+                .header("Authorization", format!("Bearer {token}"))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+        let response = app.clone().oneshot(unlink()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(unlink()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        disable_sso();
+    }
+    #[tokio::test]
+    #[serial]
+    async fn link_urls_cannot_be_finished_by_someone_else() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let provider = spawn_provider().await;
+        enable_sso(&provider);
+
+        let attacker = UserModel::create(db, "u40000002", "u40000002@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let victim = UserModel::create(db, "u40000003", "u40000003@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let (attacker_token, _) = generate_jwt(attacker.id, false);
+        let (victim_token, _) = generate_jwt(victim.id, false);
+
+        // The attacker starts a link and sends the URL on; the victim signs in at the provider
+        let params = start_link(&app, &attacker_token).await;
+        provider.next_claims(json!({ "sub": "idp-victim", "nonce": params["nonce"] }));
+        let response = callback(&app, &params["state"]).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let fragment = fragment_params(response.headers()[LOCATION].to_str().unwrap());
+        // The victim's browser is not signed in to the attacker's account...
+        assert!(!fragment.contains_key("token"));
+        assert!(!fragment.contains_key("challenge_token"));
+
+        // ...and the victim's session can't finish the attacker's link
+        let response = complete_link(&app, &victim_token, &fragment).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(
+            IdentityModel::find(db, &provider.issuer, "idp-victim")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // A plain sign-in can't be finished as a link either
+        let params = start_sign_in(&app).await;
+        provider.next_claims(json!({ "sub": "idp-victim", "nonce": params["nonce"] }));
+        let fragment = HashMap::from([
+            ("link_state".to_string(), params["state"].clone()),
+            ("code".to_string(), "auth-code".to_string()),
+        ]);
+        let response = complete_link(&app, &attacker_token, &fragment).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(
+            IdentityModel::find(db, &provider.issuer, "idp-victim")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        disable_sso();
+    }
+}
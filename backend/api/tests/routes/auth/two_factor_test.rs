@@ -0,0 +1,243 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        module,
+        user::Model as UserModel,
+        user_module_role::{Model as RoleModel, Role},
+        user_two_factor::Model as TwoFactorModel,
+    };
+    use sea_orm::DatabaseConnection;
+    use serde_json::{Value, json};
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn post(app: &App, uri: &str, token: Option<&str>, body: Value) -> (StatusCode, Value) {
+        let mut req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            req = req.header("Authorization", format!("Bearer {token}"));
+        }
+        let req = req.body(AxumBody::from(body.to_string())).unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    async fn login(app: &App, username: &str) -> Value {
+        let (status, json) = post(
+            app,
+            "/api/auth/login",
+            None,
+            json!({ "username": username, "password": "password123" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        json["data"].clone()
+    }
+
+    /// A code the user's authenticator would show `offset_steps` steps from now.
+    async fn code(db: &DatabaseConnection, user_id: i64, offset_steps: i64) -> String {
+        TwoFactorModel::find(db, user_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .code_at(Utc::now() + Duration::seconds(30 * offset_steps))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn enabled_two_factor_gates_login() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let user = UserModel::create(db, "u50000001", "u5@tuks.test", "password123", false)
+            .await
+            .unwrap();
+        let (token, _) = generate_jwt(user.id, false);
+
+        let (status, json) = post(&app, "/api/auth/2fa/setup", Some(&token), json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        let uri = json["data"]["otpauth_uri"].as_str().unwrap();
+        assert!(uri.starts_with("otpauth://totp/FitchFork:u50000001?secret="));
+
+        // Nothing changes until a code confirms the secret
+        assert!(login(&app, "u50000001").await["token"].is_string());
+        let (status, _) = post(
+            &app,
+            "/api/auth/2fa/enable",
+            Some(&token),
+            json!({ "code": "000000" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, json) = post(
+            &app,
+            "/api/auth/2fa/enable",
+            Some(&token),
+            json!({ "code": code(db, user.id, 0).await }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let backup_codes: Vec<String> =
+            serde_json::from_value(json["data"]["backup_codes"].clone()).unwrap();
+        assert_eq!(backup_codes.len(), 10);
+
+        // Now a password only buys a challenge, which isn't a session
+        let challenge = login(&app, "u50000001").await;
+        assert_eq!(challenge["two_factor_required"], true);
+        assert_eq!(challenge["setup_required"], false);
+        assert!(challenge.get("token").is_none());
+        let challenge_token = challenge["challenge_token"].as_str().unwrap();
+        let req = Request::builder()
+            .uri("/api/auth/me")
+            .header("Authorization", format!("Bearer {challenge_token}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let login_2fa = |code: String| {
+            post(
+                &app,
+                "/api/auth/login/2fa",
+                None,
+                json!({ "challenge_token": challenge_token, "code": code }),
+            )
+        };
+        let (status, _) = login_2fa("000000".into()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, json) = login_2fa(backup_codes[0].clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["token"].is_string());
+        let (status, _) = login_2fa(backup_codes[0].clone()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, json) = login_2fa(code(db, user.id, 1).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["username"], "u50000001");
+
+        let req = Request::builder()
+            .uri("/api/auth/2fa")
+            .header("Authorization", format!("Bearer {token}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["enabled"], true);
+        assert_eq!(json["data"]["required"], false);
+        assert_eq!(json["data"]["backup_codes_remaining"], 9);
+
+        let (status, _) = post(
+            &app,
+            "/api/auth/2fa/disable",
+            Some(&token),
+            json!({ "password": "wrong", "code": backup_codes[1] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = post(
+            &app,
+            "/api/auth/2fa/disable",
+            Some(&token),
+            json!({ "password": "password123", "code": backup_codes[1] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(login(&app, "u50000001").await["token"].is_string());
+    }
+
+    #[tokio::test]
+    async fn admins_can_require_two_factor_of_staff() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let admin = UserModel::create(db, "admin1", "admin1@tuks.test", "pw", true)
+            .await
+            .unwrap();
+        let tutor = UserModel::create(db, "u60000001", "u6@tuks.test", "password123", false)
+            .await
+            .unwrap();
+        UserModel::create(db, "u60000002", "u7@tuks.test", "password123", false)
+            .await
+            .unwrap();
+        let cos301 = module::Model::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        RoleModel::assign_user_to_module(db, tutor.id, cos301.id, Role::Tutor)
+            .await
+            .unwrap();
+
+        let (admin_token, _) = generate_jwt(admin.id, true);
+        let (tutor_token, _) = generate_jwt(tutor.id, false);
+        let (status, _) = post(
+            &app,
+            "/api/system/two-factor",
+            Some(&tutor_token),
+            json!({ "require_staff": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, json) = post(
+            &app,
+            "/api/system/two-factor",
+            Some(&admin_token),
+            json!({ "require_staff": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["require_staff"], true);
+
+        // Students are unaffected
+        assert!(login(&app, "u60000002").await["token"].is_string());
+
+        // Staff have to set it up before they get in
+        let challenge = login(&app, "u60000001").await;
+        assert_eq!(challenge["setup_required"], true);
+        let challenge_token = challenge["challenge_token"].as_str().unwrap();
+        let (status, json) = post(
+            &app,
+            "/api/auth/login/2fa/setup",
+            None,
+            json!({ "challenge_token": challenge_token }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["secret"].is_string());
+        let (status, json) = post(
+            &app,
+            "/api/auth/login/2fa",
+            None,
+            json!({ "challenge_token": challenge_token, "code": code(db, tutor.id, 0).await }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["token"].is_string());
+        assert_eq!(json["data"]["backup_codes"].as_array().unwrap().len(), 10);
+
+        // ...and can't turn it off while it's required
+        let (status, _) = post(
+            &app,
+            "/api/auth/2fa/disable",
+            Some(&tutor_token),
+            json!({ "password": "password123", "code": code(db, tutor.id, 1).await }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(login(&app, "u60000001").await["setup_required"], false);
+    }
+}
@@ -0,0 +1,501 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{
+            Request, StatusCode,
+            header::{COOKIE, LOCATION, SET_COOKIE},
+        },
+    };
+    use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{self, AssignmentType},
+        assignment_submission, lti_context, lti_deployment, lti_key,
+        lti_platform::{self, PlatformFields},
+        lti_resource_link, module,
+        user::{Column as UserColumn, Entity as UserEntity, Model as UserModel},
+        user_module_role::{Column as RoleColumn, Entity as RoleEntity, Role},
+    };
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
+    use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::collections::HashMap;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::execution_config::ExecutionConfig;
+
+    use crate::helpers::app::make_test_app_with_storage;
+    use crate::helpers::lti::{PLATFORM_KEY_PEM, PLATFORM_KID, Recorded, spawn_platform};
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    const ISSUER: &str = "https://lms.test";
+    const CLIENT_ID: &str = "fitchfork-client";
+    const DEPLOYMENT: &str = "dep-1";
+
+    struct Setup {
+        platform: lti_platform::Model,
+        module: module::Model,
+        assignment: assignment::Model,
+        base: String,
+        recorded: Recorded,
+    }
+
+    async fn setup(db: &DatabaseConnection) -> Setup {
+        let (base, recorded) = spawn_platform().await;
+        let platform = lti_platform::Model::create(
+            db,
+            &PlatformFields {
+                name: "LMS",
+                issuer: ISSUER,
+                client_id: CLIENT_ID,
+                auth_login_url: &format!("{base}/authorize"),
+                auth_token_url: &format!("{base}/token"),
+                keyset_url: &format!("{base}/jwks"),
+            },
+        )
+        .await
+        .unwrap();
+        lti_deployment::Model::create(db, platform.id, DEPLOYMENT)
+            .await
+            .unwrap();
+        let module = module::Model::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        lti_context::Model::create(db, platform.id, "course-1", module.id)
+            .await
+            .unwrap();
+        let assignment = assignment::Model::create(
+            db,
+            module.id,
+            "Practical 1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(1),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        Setup {
+            platform,
+            module,
+            assignment,
+            base,
+            recorded,
+        }
+    }
+
+    /// Runs the OIDC login and returns the `state` and `nonce` sent to the platform.
+    async fn login(app: &App) -> (String, String) {
+        let req = Request::builder()
+            .uri(format!("/api/lti/login?iss={ISSUER}&login_hint=hint-1"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let location = url::Url::parse(response.headers()[LOCATION].to_str().unwrap()).unwrap();
+        let params: HashMap<String, String> = location.query_pairs().into_owned().collect();
+        assert_eq!(params["client_id"], CLIENT_ID);
+        assert_eq!(params["login_hint"], "hint-1");
+        assert_eq!(params["response_mode"], "form_post");
+        assert!(params["redirect_uri"].ends_with("/api/lti/launch"));
+        let cookie = response.headers()[SET_COOKIE].to_str().unwrap();
+        assert!(cookie.starts_with(&format!("ff_lti_state={};", params["state"])));
+        assert!(cookie.contains("SameSite=None") && cookie.contains("Secure"));
+        (params["state"].clone(), params["nonce"].clone())
+    }
+
+    fn id_token(s: &Setup, nonce: &str, deployment: &str) -> String {
+        sign(&claims(s, nonce, deployment))
+    }
+
+    fn claims(s: &Setup, nonce: &str, deployment: &str) -> Value {
+        let now = Utc::now().timestamp();
+        json!({
+            "iss": ISSUER,
+            "aud": CLIENT_ID,
+            "iat": now,
+            "exp": now + 300,
+            "nonce": nonce,
+            "sub": "lms-user-1",
+            "email": "u12345678@tuks.test",
+            "https://purl.imsglobal.org/spec/lti/claim/message_type": "LtiResourceLinkRequest",
+            "https://purl.imsglobal.org/spec/lti/claim/version": "1.3.0",
+            "https://purl.imsglobal.org/spec/lti/claim/deployment_id": deployment,
+            "https://purl.imsglobal.org/spec/lti/claim/resource_link": { "id": "link-1" },
+            "https://purl.imsglobal.org/spec/lti/claim/context": { "id": "course-1" },
+            "https://purl.imsglobal.org/spec/lti/claim/roles": [
+                "http://purl.imsglobal.org/vocab/lis/v2/membership#Learner"
+            ],
+            "https://purl.imsglobal.org/spec/lti/claim/lis": { "person_sourcedid": "u12345678" },
+            "https://purl.imsglobal.org/spec/lti/claim/custom": {
+                "assignment_id": s.assignment.id.to_string()
+            },
+            "https://purl.imsglobal.org/spec/lti-ags/claim/endpoint": {
+                "scope": ["https://purl.imsglobal.org/spec/lti-ags/scope/score"],
+                "lineitem": format!("{}/lineitems/1", s.base)
+            }
+        })
+    }
+
+    fn sign(claims: &Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(PLATFORM_KID.to_string());
+        encode(
+            &header,
+            claims,
+            &EncodingKey::from_rsa_pem(PLATFORM_KEY_PEM.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    /// The platform's form post to the browser that started the login.
+    async fn launch(app: &App, id_token: &str, state: &str) -> axum::response::Response {
+        launch_with_cookie(app, id_token, state, Some(state)).await
+    }
+
+    async fn launch_with_cookie(
+        app: &App,
+        id_token: &str,
+        state: &str,
+        cookie: Option<&str>,
+    ) -> axum::response::Response {
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("id_token", id_token)
+            .append_pair("state", state)
+            .finish();
+        let mut req = Request::builder()
+            .method("POST")
+            .uri("/api/lti/launch")
+            .header("Content-Type", "application/x-www-form-urlencoded");
+        if let Some(cookie) = cookie {
+            req = req.header(COOKIE, format!("ff_lti_state={cookie}"));
+        }
+        app.clone()
+            .oneshot(req.body(AxumBody::from(body)).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn launch_signs_in_and_links_the_placement() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+
+        let (state, nonce) = login(&app).await;
+        let token = id_token(&s, &nonce, DEPLOYMENT);
+        let response = launch(&app, &token, &state).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let location = response.headers()[LOCATION].to_str().unwrap().to_string();
+        assert!(location.contains("/lti/launch#token="));
+        assert!(location.contains(&format!(
+            "redirect=%2Fmodules%2F{}%2Fassignments%2F{}",
+            s.module.id, s.assignment.id
+        )));
+
+        // The LMS learner is a FitchFork student of the module now
+        let user = UserEntity::find()
+            .filter(UserColumn::Username.eq("u12345678"))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let role = RoleEntity::find()
+            .filter(RoleColumn::UserId.eq(user.id))
+            .filter(RoleColumn::ModuleId.eq(s.module.id))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(role.role, Role::Student);
+
+        let link = lti_resource_link::Model::find(db, s.platform.id, "link-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.assignment_id, Some(s.assignment.id));
+        assert_eq!(link.context_id.as_deref(), Some("course-1"));
+        assert_eq!(link.lineitem, Some(format!("{}/lineitems/1", s.base)));
+
+        // A state is good for one launch only
+        let replay = launch(&app, &token, &state).await;
+        assert_eq!(replay.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn launch_rejects_wrong_nonce_and_unknown_deployments() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+
+        let (state, _) = login(&app).await;
+        let response = launch(&app, &id_token(&s, "not-the-nonce", DEPLOYMENT), &state).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &id_token(&s, &nonce, "dep-unknown"), &state).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Signed by someone else: the platform's published key doesn't verify it
+        let (state, nonce) = login(&app).await;
+        let mut forged = id_token(&s, &nonce, DEPLOYMENT);
+        forged.push_str("AA");
+        let response = launch(&app, &forged, &state).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // A launch posted to a browser other than the one that started the login, which would
+        // sign that browser in to the LMS user's account, is refused and leaves the state unspent
+        let (state, nonce) = login(&app).await;
+        let token = id_token(&s, &nonce, DEPLOYMENT);
+        let response = launch_with_cookie(&app, &token, &state, None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let (other, _) = login(&app).await;
+        let response = launch_with_cookie(&app, &token, &state, Some(&other)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(
+            response.headers()[SET_COOKIE]
+                .to_str()
+                .unwrap()
+                .contains("Max-Age=0")
+        );
+        let response = launch(&app, &token, &state).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn instructors_cannot_pick_the_module_of_a_new_placement() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        let other = module::Model::create(db, "COS333", 2025, None, 16)
+            .await
+            .unwrap();
+        let instructor = |nonce: &str, context: &str| {
+            let mut c = claims(&s, nonce, DEPLOYMENT);
+            c["https://purl.imsglobal.org/spec/lti/claim/context"] = json!({ "id": context });
+            c["https://purl.imsglobal.org/spec/lti/claim/resource_link"] =
+                json!({ "id": "link-2" });
+            c["https://purl.imsglobal.org/spec/lti/claim/roles"] =
+                json!(["http://purl.imsglobal.org/vocab/lis/v2/membership#Instructor"]);
+            c["https://purl.imsglobal.org/spec/lti/claim/custom"] =
+                json!({ "module_id": other.id.to_string() });
+            sign(&c)
+        };
+
+        // An unlinked course gets nowhere, whatever module it asks for
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &instructor(&nonce, "course-2"), &state).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(
+            lti_resource_link::Model::find(db, s.platform.id, "link-2")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        // A linked one opens its own module, not the one asked for
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &instructor(&nonce, "course-1"), &state).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let link = lti_resource_link::Model::find(db, s.platform.id, "link-2")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.module_id, s.module.id);
+        let roles = RoleEntity::find()
+            .filter(RoleColumn::ModuleId.eq(other.id))
+            .all(db)
+            .await
+            .unwrap();
+        assert!(roles.is_empty());
+    }
+
+    fn fragment_params(response: &axum::response::Response) -> HashMap<String, String> {
+        let location = response.headers()[LOCATION].to_str().unwrap();
+        let fragment = location.split_once('#').unwrap().1;
+        url::form_urlencoded::parse(fragment.as_bytes())
+            .into_owned()
+            .collect()
+    }
+
+    async fn link(app: &App, session: Option<&str>, body: Value) -> StatusCode {
+        let mut req = Request::builder()
+            .method("POST")
+            .uri("/api/auth/lti/link")
+            .header("Content-Type", "application/json");
+        if let Some(session) = session {
+            req = req.header("Authorization", format!("Bearer {session}"));
+        }
+        let req = req.body(AxumBody::from(body.to_string())).unwrap();
+        app.clone().oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn existing_accounts_are_only_linked_by_their_owner() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        // Only the email matches; the launch's sourcedid is u12345678
+        let owner = UserModel::create(db, "u87654321", "u12345678@tuks.test", "pw", false)
+            .await
+            .unwrap();
+
+        // The launch doesn't sign in as the account with the LMS user's email...
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &id_token(&s, &nonce, DEPLOYMENT), &state).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let params = fragment_params(&response);
+        assert!(!params.contains_key("token"));
+        assert!(!params.contains_key("challenge_token"));
+        assert_eq!(params["link_required"], "true");
+        let roles = RoleEntity::find()
+            .filter(RoleColumn::UserId.eq(owner.id))
+            .all(db)
+            .await
+            .unwrap();
+        assert!(roles.is_empty());
+
+        // ...and only its owner, signed in and with their password, can link it
+        let link_token = &params["link_token"];
+        let (session, _) = generate_jwt(owner.id, false);
+        let body = json!({ "link_token": link_token, "password": "pw" });
+        assert_eq!(
+            link(&app, None, body.clone()).await,
+            StatusCode::UNAUTHORIZED
+        );
+        let wrong = json!({ "link_token": link_token, "password": "guess" });
+        assert_eq!(
+            link(&app, Some(&session), wrong).await,
+            StatusCode::UNAUTHORIZED
+        );
+        let forged = json!({ "link_token": "not-a-token", "password": "pw" });
+        assert_eq!(
+            link(&app, Some(&session), forged).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(link(&app, Some(&session), body).await, StatusCode::OK);
+
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &id_token(&s, &nonce, DEPLOYMENT), &state).await;
+        let params = fragment_params(&response);
+        let payload = params["token"].split('.').nth(1).unwrap();
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap();
+        assert_eq!(claims["sub"], owner.id);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn launches_never_sign_in_as_admin() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        let admin = UserModel::create(db, "u12345678", "admin@test.com", "pw", true)
+            .await
+            .unwrap();
+
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &id_token(&s, &nonce, DEPLOYMENT), &state).await;
+        let params = fragment_params(&response);
+        let (session, _) = generate_jwt(admin.id, true);
+        let body = json!({ "link_token": params["link_token"], "password": "pw" });
+        assert_eq!(link(&app, Some(&session), body).await, StatusCode::OK);
+
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &id_token(&s, &nonce, DEPLOYMENT), &state).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let params = fragment_params(&response);
+        let payload = params["token"].split('.').nth(1).unwrap();
+        let claims: Value =
+            serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).unwrap()).unwrap();
+        assert_eq!(claims["sub"], admin.id);
+        assert_eq!(claims["admin"], false);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn grades_are_posted_to_the_line_item() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        lti_key::Model::create(db, "tool-key", PLATFORM_KEY_PEM)
+            .await
+            .unwrap();
+        ExecutionConfig::default_config()
+            .save(s.module.id, s.assignment.id)
+            .unwrap();
+
+        let (state, nonce) = login(&app).await;
+        let response = launch(&app, &id_token(&s, &nonce, DEPLOYMENT), &state).await;
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let student = UserEntity::find()
+            .filter(UserColumn::Username.eq("u12345678"))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        assignment_submission::Model::save_file(
+            db,
+            s.assignment.id,
+            student.id,
+            1,
+            8.0,
+            10.0,
+            false,
+            "s.zip",
+            "hash1",
+            b"zip",
+        )
+        .await
+        .unwrap();
+
+        let lecturer = UserModel::create(db, "lecturer", "lecturer@test.com", "pw", false)
+            .await
+            .unwrap();
+        db::models::user_module_role::Model::assign_user_to_module(
+            db,
+            lecturer.id,
+            s.module.id,
+            Role::Lecturer,
+        )
+        .await
+        .unwrap();
+        let (token, _) = generate_jwt(lecturer.id, false);
+        let req = Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/modules/{}/assignments/{}/grades/lti-sync",
+                s.module.id, s.assignment.id
+            ))
+            .header("Authorization", format!("Bearer {token}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["posted"], 1);
+
+        let requests = s.recorded.token_requests.lock().unwrap().clone();
+        assert_eq!(requests[0]["grant_type"], "client_credentials");
+        assert_eq!(
+            requests[0]["scope"],
+            "https://purl.imsglobal.org/spec/lti-ags/scope/score"
+        );
+        let scores = s.recorded.scores.lock().unwrap().clone();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0]["userId"], "lms-user-1");
+        assert_eq!(scores[0]["scoreGiven"], 80.0);
+        assert_eq!(scores[0]["scoreMaximum"], 100);
+        assert_eq!(scores[0]["gradingProgress"], "FullyGraded");
+    }
+}
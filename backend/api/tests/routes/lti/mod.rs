@@ -0,0 +1 @@
+pub mod launch_test;
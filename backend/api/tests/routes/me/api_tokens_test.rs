@@ -0,0 +1,255 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        api_token::{Model as ApiTokenModel, Scope},
+        user::Model as UserModel,
+    };
+    use serde_json::{Value, json};
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn call(
+        app: &App,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {token}"))
+            .header("Content-Type", "application/json");
+        let req = match body {
+            Some(body) => req.body(AxumBody::from(body.to_string())).unwrap(),
+            None => req.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn tokens_act_as_their_owner_within_their_scopes() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let user = UserModel::create(db, "u70000001", "u7@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let (jwt, _) = generate_jwt(user.id, false);
+
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/api/me/api-tokens",
+            &jwt,
+            Some(json!({ "name": "CI", "scopes": ["admin"] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, json) = call(
+            &app,
+            "POST",
+            "/api/me/api-tokens",
+            &jwt,
+            Some(json!({ "name": "CI", "scopes": ["read"], "expires_in_days": 30 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let read_token = json["data"]["token"].as_str().unwrap().to_string();
+        let read_id = json["data"]["id"].as_i64().unwrap();
+        assert!(read_token.starts_with(json["data"]["token_prefix"].as_str().unwrap()));
+
+        // A read token can look but not touch
+        let (status, json) = call(&app, "GET", "/api/auth/me", &read_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["id"], user.id);
+        let (status, _) = call(&app, "POST", "/api/me/calendar/reset", &read_token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // ...nor manage tokens, even to list them
+        let (status, _) = call(&app, "GET", "/api/me/api-tokens", &read_token, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, json) = call(&app, "GET", "/api/me/api-tokens", &jwt, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"][0]["scopes"], json!(["read"]));
+        assert!(json["data"][0].get("token").is_none());
+        assert!(json["data"][0]["last_used_at"].is_string());
+
+        let (status, _) = call(
+            &app,
+            "DELETE",
+            &format!("/api/me/api-tokens/{read_id}"),
+            &jwt,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "GET", "/api/auth/me", &read_token, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Expired tokens stop working
+        let (_, expired) = ApiTokenModel::create(
+            db,
+            user.id,
+            "old",
+            &[Scope::Write],
+            Utc::now() - Duration::minutes(1),
+            None,
+        )
+        .await
+        .unwrap();
+        let (status, _) = call(&app, "GET", "/api/auth/me", &expired, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn tokens_cannot_change_how_the_owner_signs_in() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let admin = UserModel::create(db, "admin1", "admin1@tuks.test", "pw", true)
+            .await
+            .unwrap();
+        let (_, token) = ApiTokenModel::create(
+            db,
+            admin.id,
+            "everything",
+            &[Scope::Write, Scope::Admin],
+            Utc::now() + Duration::days(1),
+            None,
+        )
+        .await
+        .unwrap();
+
+        for (method, uri) in [
+            ("POST", "/api/auth/change-password"),
+            ("POST", "/api/auth/upload-profile-picture"),
+            ("POST", "/api/auth/sso/link"),
+            ("DELETE", "/api/auth/sso/link"),
+            ("GET", "/api/auth/2fa"),
+            ("POST", "/api/auth/2fa/setup"),
+            ("POST", "/api/auth/2fa/enable"),
+            ("POST", "/api/auth/2fa/disable"),
+            ("POST", "/api/auth/2fa/backup-codes"),
+        ] {
+            let (status, _) = call(&app, method, uri, &token, Some(json!({}))).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn admins_issue_service_account_tokens() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let admin = UserModel::create(db, "admin1", "admin1@tuks.test", "pw", true)
+            .await
+            .unwrap();
+        let service = UserModel::create(db, "svc-marks", "svc@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let (jwt, _) = generate_jwt(admin.id, true);
+
+        let (status, json) = call(
+            &app,
+            "POST",
+            "/api/system/api-tokens",
+            &jwt,
+            Some(json!({ "user_id": service.id, "name": "Marks export", "scopes": ["write"] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["data"]["user_id"], service.id);
+        assert_eq!(json["data"]["created_by"], admin.id);
+        let service_token = json["data"]["token"].as_str().unwrap().to_string();
+        let (status, json) = call(&app, "GET", "/api/auth/me", &service_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["username"], "svc-marks");
+
+        // An admin's own token only carries admin rights with the admin scope
+        let (_, plain) = ApiTokenModel::create(
+            db,
+            admin.id,
+            "plain",
+            &[Scope::Write],
+            Utc::now() + Duration::days(1),
+            None,
+        )
+        .await
+        .unwrap();
+        let (status, _) = call(&app, "GET", "/api/system/api-tokens", &plain, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (_, admin_token) = ApiTokenModel::create(
+            db,
+            admin.id,
+            "admin",
+            &[Scope::Read, Scope::Admin],
+            Utc::now() + Duration::days(1),
+            None,
+        )
+        .await
+        .unwrap();
+        let (status, _) = call(
+            &app,
+            "GET",
+            "/api/system/grade-export-templates",
+            &admin_token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        // ...but not the ones deciding who can sign in and how
+        for uri in [
+            "/api/system/api-tokens",
+            "/api/system/two-factor",
+            "/api/system/lti/platforms",
+        ] {
+            let (status, _) = call(&app, "GET", uri, &admin_token, None).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{uri}");
+        }
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/api/system/two-factor",
+            &admin_token,
+            Some(json!({ "require_staff": false })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, json) = call(
+            &app,
+            "GET",
+            &format!("/api/system/api-tokens?user_id={}", service.id),
+            &jwt,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let id = json["data"][0]["id"].as_i64().unwrap();
+        let (status, _) = call(
+            &app,
+            "DELETE",
+            &format!("/api/system/api-tokens/{id}"),
+            &jwt,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "GET", "/api/auth/me", &service_token, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
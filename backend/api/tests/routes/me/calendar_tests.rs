@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, TimeZone, Utc};
+    use db::models::{
+        announcements::Model as AnnouncementModel,
+        assignment::{self, AssignmentType, Model as AssignmentModel},
+        assignment_extension::Model as ExtensionModel,
+        attendance_session::Model as SessionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use sea_orm::{ActiveModelTrait, IntoActiveModel, Set};
+    use serde_json::Value;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn feed_token(app: &App, user_id: i64, reset: bool) -> String {
+        let (jwt, _) = generate_jwt(user_id, false);
+        let (method, uri) = if reset {
+            ("POST", "/api/me/calendar/reset")
+        } else {
+            ("GET", "/api/me/calendar")
+        };
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let token = json["data"]["token"].as_str().unwrap().to_string();
+        assert_eq!(
+            json["data"]["path"],
+            format!("/api/me/calendar.ics?token={token}")
+        );
+        token
+    }
+
+    /// Fetches the feed the way a calendar app would: no Authorization header.
+    async fn fetch_feed(app: &App, token: &str) -> (StatusCode, String) {
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("/api/me/calendar.ics?token={token}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        if status == StatusCode::OK {
+            assert_eq!(
+                resp.headers()["content-type"].to_str().unwrap(),
+                "text/calendar; charset=utf-8"
+            );
+        }
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn calendar_feed_lists_deadlines_sessions_and_scheduled_announcements() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, Some("SE"), 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "cal_lect", "cal_lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        let student = UserModel::create(db, "cal_stud", "cal_stud@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, student.id, module.id, Role::Student)
+            .await
+            .unwrap();
+
+        let due = Utc.with_ymd_and_hms(2030, 3, 1, 21, 59, 0).unwrap();
+        let ready = AssignmentModel::create(
+            db,
+            module.id,
+            "Practical 1",
+            None,
+            AssignmentType::Practical,
+            due - Duration::days(7),
+            due,
+        )
+        .await
+        .unwrap();
+        let mut am = ready.clone().into_active_model();
+        am.status = Set(assignment::Status::Ready);
+        am.update(db).await.unwrap();
+        let setup = AssignmentModel::create(
+            db,
+            module.id,
+            "Practical 2",
+            None,
+            AssignmentType::Practical,
+            due,
+            due + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        ExtensionModel::grant(
+            db,
+            ready.id,
+            student.id,
+            due + Duration::days(2),
+            None,
+            lecturer.id,
+        )
+        .await
+        .unwrap();
+        let session = SessionModel::create(
+            db,
+            module.id,
+            lecturer.id,
+            "Lecture 1",
+            true,
+            30,
+            false,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let scheduled = AnnouncementModel::create_scheduled(
+            db,
+            module.id,
+            lecturer.id,
+            "Exam venue",
+            "Hall A, bring ID",
+            false,
+            Some(due),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Lecturer: everything, including what students can't see yet
+        let token = feed_token(&app, lecturer.id, false).await;
+        let (status, ics) = fetch_feed(&app, &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains(&format!("UID:assignment-{}@fitchfork", ready.id)));
+        assert!(ics.contains(&format!("UID:assignment-{}@fitchfork", setup.id)));
+        assert!(ics.contains("SUMMARY:COS301: Practical 1 due\r\n"));
+        assert!(ics.contains("DTSTART:20300301T215900Z\r\n"));
+        assert!(ics.contains(&format!("UID:attendance-session-{}@fitchfork", session.id)));
+        assert!(ics.contains(&format!("UID:announcement-{}@fitchfork", scheduled.id)));
+        assert!(ics.contains("DESCRIPTION:Hall A\\, bring ID\r\n"));
+
+        // Student: their extended due date, nothing still in setup or not yet announced
+        let token = feed_token(&app, student.id, false).await;
+        assert_eq!(feed_token(&app, student.id, false).await, token);
+        let (status, ics) = fetch_feed(&app, &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(ics.contains(&format!("UID:assignment-{}@fitchfork", ready.id)));
+        assert!(ics.contains("DTSTART:20300303T215900Z\r\n"));
+        assert!(!ics.contains(&format!("UID:assignment-{}@fitchfork", setup.id)));
+        assert!(ics.contains(&format!("UID:attendance-session-{}@fitchfork", session.id)));
+        assert!(!ics.contains("UID:announcement-"));
+
+        // Resetting the token retires the old URL
+        let new_token = feed_token(&app, student.id, true).await;
+        assert_ne!(new_token, token);
+        assert_eq!(fetch_feed(&app, &token).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(fetch_feed(&app, &new_token).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn calendar_feed_requires_a_feed_token() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let user = UserModel::create(app_state.db(), "cal_u", "cal_u@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        // A login token is not a feed token
+        let (jwt, _) = generate_jwt(user.id, false);
+        assert_eq!(fetch_feed(&app, &jwt).await.0, StatusCode::UNAUTHORIZED);
+        let req = Request::builder()
+            .method("GET")
+            .uri("/api/me/calendar.ics")
+            .header("Authorization", format!("Bearer {}", jwt))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = Request::builder()
+            .method("GET")
+            .uri("/api/me/calendar")
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,4 +1,7 @@
+pub mod api_tokens_test;
+pub mod calendar_tests;
 pub mod events_test;
 pub mod grades_tests;
+pub mod notifications_tests;
 pub mod plagiarism_tests;
 pub mod submissions_tests;
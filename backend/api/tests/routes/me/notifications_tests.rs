@@ -0,0 +1,402 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use api::services::email_outbox;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use db::models::{
+        email_delivery::{DeliveryStatus, Model as DeliveryModel},
+        module::Model as ModuleModel,
+        notification::{Model as NotificationModel, NotificationKind},
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(app: &App, req: Request<AxumBody>) -> (StatusCode, Value) {
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn request(method: &str, uri: &str, token: &str, body: Option<Value>) -> Request<AxumBody> {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn list_and_mark_notifications_read() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let alice = UserModel::create(db, "alice", "alice@test.com", "pw", false)
+            .await
+            .unwrap();
+        let bob = UserModel::create(db, "bob", "bob@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let first = NotificationModel::create(
+            db,
+            alice.id,
+            NotificationKind::AnnouncementPosted,
+            "COS301: Exam",
+            "Friday",
+            Some("/modules/1/announcements/1"),
+        )
+        .await
+        .unwrap();
+        NotificationModel::create(
+            db,
+            alice.id,
+            NotificationKind::TicketReplied,
+            "New reply",
+            "bob: hi",
+            None,
+        )
+        .await
+        .unwrap();
+        let bobs = NotificationModel::create(
+            db,
+            bob.id,
+            NotificationKind::TicketReplied,
+            "New reply",
+            "alice: hi",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let (token, _) = generate_jwt(alice.id, false);
+        let (status, json) =
+            send(&app, request("GET", "/api/me/notifications", &token, None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["total"], 2);
+        assert_eq!(json["data"]["unread"], 2);
+        assert_eq!(json["data"]["notifications"][1]["id"], first.id);
+        assert_eq!(
+            json["data"]["notifications"][1]["kind"],
+            "announcement_posted"
+        );
+        assert_eq!(json["data"]["notifications"][1]["read"], false);
+
+        // Someone else's notification is not found
+        let (status, _) = send(
+            &app,
+            request(
+                "PUT",
+                &format!("/api/me/notifications/{}/read", bobs.id),
+                &token,
+                None,
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, json) = send(
+            &app,
+            request(
+                "PUT",
+                &format!("/api/me/notifications/{}/read", first.id),
+                &token,
+                None,
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["read"], true);
+
+        let (_, json) = send(
+            &app,
+            request("GET", "/api/me/notifications?unread=true", &token, None),
+        )
+        .await;
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["unread"], 1);
+
+        let (status, json) = send(
+            &app,
+            request("PUT", "/api/me/notifications/read-all", &token, None),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["updated"], 1);
+        assert_eq!(
+            NotificationModel::unread_count(db, alice.id).await.unwrap(),
+            0
+        );
+        assert_eq!(
+            NotificationModel::unread_count(db, bob.id).await.unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn preferences_default_and_update() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let user = UserModel::create(app_state.db(), "alice", "alice@test.com", "pw", false)
+            .await
+            .unwrap();
+        let (token, _) = generate_jwt(user.id, false);
+
+        let (status, json) = send(
+            &app,
+            request("GET", "/api/me/notifications/preferences", &token, None),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let prefs = json["data"]["preferences"].as_array().unwrap();
+        assert_eq!(prefs.len(), 6);
+        // Announcements and ticket replies are emailed unless the user turns it off
+        assert!(prefs.iter().all(|p| p["in_app"] == true));
+        for p in prefs {
+            let emailed = p["kind"] == "announcement_posted" || p["kind"] == "ticket_replied";
+            assert_eq!(p["email"], emailed, "{}", p["kind"]);
+        }
+
+        let (status, json) = send(
+            &app,
+            request(
+                "PUT",
+                "/api/me/notifications/preferences",
+                &token,
+                Some(json!({
+                    "preferences": [
+                        { "kind": "announcement_posted", "in_app": false, "email": true }
+                    ]
+                })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let updated = json["data"]["preferences"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["kind"] == "announcement_posted")
+            .unwrap()
+            .clone();
+        assert_eq!(updated["in_app"], false);
+        assert_eq!(updated["email"], true);
+
+        let (status, _) = send(
+            &app,
+            request(
+                "PUT",
+                "/api/me/notifications/preferences",
+                &token,
+                Some(json!({
+                    "preferences": [ { "kind": "nonsense", "in_app": true, "email": true } ]
+                })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn posting_an_announcement_notifies_module_members() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        let student = UserModel::create(db, "student", "stud@test.com", "pw", false)
+            .await
+            .unwrap();
+        let outsider = UserModel::create(db, "outsider", "out@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, student.id, module.id, Role::Student)
+            .await
+            .unwrap();
+
+        let (token, _) = generate_jwt(lecturer.id, false);
+        let (status, _) = send(
+            &app,
+            request(
+                "POST",
+                &format!("/api/modules/{}/announcements", module.id),
+                &token,
+                Some(json!({ "title": "Exam", "body": "Friday 09:00", "pinned": false })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Delivery runs in the background
+        let mut delivered = 0;
+        for _ in 0..50 {
+            delivered = NotificationModel::unread_count(db, student.id)
+                .await
+                .unwrap();
+            if delivered > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(delivered, 1);
+        let (items, _) = NotificationModel::list_for_user(db, student.id, false, 1, 10)
+            .await
+            .unwrap();
+        assert_eq!(items[0].kind, NotificationKind::AnnouncementPosted);
+        assert_eq!(items[0].title, "COS301: Exam");
+
+        assert_eq!(
+            NotificationModel::unread_count(db, lecturer.id)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            NotificationModel::unread_count(db, outsider.id)
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn announcement_emails_honour_opt_out_and_digest() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut users = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("now", Role::Student),
+            ("digest", Role::Student),
+            ("quiet", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            users.push(u);
+        }
+        let (lecturer, now, digest, quiet) = (&users[0], &users[1], &users[2], &users[3]);
+
+        // Defaults, then each student picks a mode
+        let (token, _) = generate_jwt(digest.id, false);
+        let (status, json) = send(
+            &app,
+            request("GET", "/api/me/notifications/email", &token, None),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"], json!({ "opted_out": false, "digest": false }));
+        let (status, json) = send(
+            &app,
+            request(
+                "PUT",
+                "/api/me/notifications/email",
+                &token,
+                Some(json!({ "opted_out": false, "digest": true })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["digest"], true);
+        let (token, _) = generate_jwt(quiet.id, false);
+        let (status, _) = send(
+            &app,
+            request(
+                "PUT",
+                "/api/me/notifications/email",
+                &token,
+                Some(json!({ "opted_out": true, "digest": false })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (token, _) = generate_jwt(lecturer.id, false);
+        let (status, _) = send(
+            &app,
+            request(
+                "POST",
+                &format!("/api/modules/{}/announcements", module.id),
+                &token,
+                Some(json!({ "title": "Exam", "body": "Friday **09:00**", "pinned": false })),
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Delivery runs in the background
+        let mut queued = Vec::new();
+        for _ in 0..50 {
+            queued = DeliveryModel::list_for_user(db, digest.id).await.unwrap();
+            if !queued.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(queued.len(), 1);
+        assert!(queued[0].digest);
+        assert_eq!(queued[0].status, DeliveryStatus::Pending);
+
+        let immediate = DeliveryModel::list_for_user(db, now.id).await.unwrap();
+        assert_eq!(immediate.len(), 1);
+        assert!(!immediate[0].digest);
+        assert_eq!(immediate[0].subject, "COS301: Exam");
+        assert!(immediate[0].body_html.contains("<strong>09:00</strong>"));
+        assert!(
+            DeliveryModel::list_for_user(db, quiet.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            DeliveryModel::list_for_user(db, lecturer.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let email = email_outbox::digest_email(&digest.email, &queued);
+        assert_eq!(email.to, "digest@test.com");
+        assert_eq!(email.subject, "Your FitchFork digest: 1 update");
+        assert!(email.html.contains("<h3>COS301: Exam</h3>"));
+        assert!(email.html.contains("<strong>09:00</strong>"));
+        assert!(
+            email
+                .text
+                .contains(&format!("/modules/{}/announcements/", module.id))
+        );
+    }
+}
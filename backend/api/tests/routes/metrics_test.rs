@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode, header},
+    };
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn metrics_returns_prometheus_text() {
+        let (app, _app_state, _tmp) = make_test_app_with_storage().await;
+
+        let req = Request::builder()
+            .method("GET")
+            .uri("/api/metrics")
+            .body(AxumBody::empty())
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; version=0.0.4"
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("fitchfork_ws_connections"));
+    }
+}
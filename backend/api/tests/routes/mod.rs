@@ -1,6 +1,10 @@
 pub mod auth;
 pub mod health_test;
+pub mod lti;
 pub mod me;
+pub mod metrics_test;
 pub mod modules;
+pub mod rate_limit_test;
 pub mod system;
+pub mod uploads;
 pub mod users;
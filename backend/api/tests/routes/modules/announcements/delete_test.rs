@@ -153,4 +153,38 @@ mod tests {
         let response = app.oneshot(req).await.unwrap();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn deleted_announcement_is_hidden_until_restored() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.lecturer.id, data.lecturer.admin);
+        let uri = format!(
+            "/api/modules/{}/announcements/{}",
+            data.module.id, data.announcement.id
+        );
+        let request = |method: &str, uri: String| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("DELETE", uri.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(request("GET", uri.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .clone()
+            .oneshot(request("POST", format!("{}/restore", uri)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(request("GET", uri)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 }
@@ -2,18 +2,24 @@
 mod tests {
     use crate::helpers::app::make_test_app_with_storage;
     use api::auth::generate_jwt;
+    use api::services::announcement_publisher::publish_due_announcements;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
     };
+    use chrono::{Duration, Utc};
     use db::models::{
-        announcements::Model as AnnouncementModel,
+        announcements::{ActiveModel as AnnouncementActiveModel, Model as AnnouncementModel},
         module::Model as ModuleModel,
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRole, Role},
     };
+    use sea_orm::{ActiveModelTrait, Set};
     use serde_json::Value;
-    use tower::ServiceExt;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    type App = BoxCloneService<Request<Body>, axum::response::Response, Infallible>;
 
     struct TestData {
         student: UserModel,
@@ -352,4 +358,95 @@ mod tests {
         let response = app.oneshot(req).await.unwrap();
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
+
+    // ─────────────────────────────────────────────────────────────
+    // Scheduled / expired announcements
+    // ─────────────────────────────────────────────────────────────
+
+    async fn list_titles(app: &App, module_id: i64, user: &UserModel) -> Vec<String> {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!(
+                "/api/modules/{}/announcements?per_page=100",
+                module_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = read_json_body(response).await;
+        json["data"]["announcements"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["title"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn scheduled_and_expired_announcements_are_staff_only() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+        let now = Utc::now();
+
+        let scheduled = AnnouncementModel::create_scheduled(
+            db,
+            data.module.id,
+            data.lecturer.id,
+            "Exam venue",
+            "Hall A",
+            false,
+            Some(now + Duration::hours(1)),
+            None,
+        )
+        .await
+        .unwrap();
+        AnnouncementModel::create_scheduled(
+            db,
+            data.module.id,
+            data.lecturer.id,
+            "Old news",
+            "Gone",
+            false,
+            None,
+            Some(now - Duration::minutes(1)),
+        )
+        .await
+        .unwrap();
+
+        let student_titles = list_titles(&app, data.module.id, &data.student).await;
+        assert_eq!(student_titles.len(), 3);
+        assert!(!student_titles.contains(&"Exam venue".to_string()));
+        assert!(!student_titles.contains(&"Old news".to_string()));
+        let staff_titles = list_titles(&app, data.module.id, &data.lecturer).await;
+        assert_eq!(staff_titles.len(), 5);
+
+        let (token, _) = generate_jwt(data.student.id, data.student.admin);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!(
+                "/api/modules/{}/announcements/{}",
+                data.module.id, scheduled.id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Nothing is due yet; once the publish time passes the publisher picks it up, once
+        assert_eq!(publish_due_announcements(&app_state, now).await.unwrap(), 0);
+        let mut due: AnnouncementActiveModel = scheduled.clone().into();
+        due.publish_at = Set(Some(now - Duration::minutes(1)));
+        due.update(db).await.unwrap();
+        assert_eq!(publish_due_announcements(&app_state, now).await.unwrap(), 1);
+        assert_eq!(publish_due_announcements(&app_state, now).await.unwrap(), 0);
+
+        let student_titles = list_titles(&app, data.module.id, &data.student).await;
+        assert!(student_titles.contains(&"Exam venue".to_string()));
+        assert!(!student_titles.contains(&"Old news".to_string()));
+    }
 }
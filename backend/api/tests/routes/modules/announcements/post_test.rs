@@ -226,4 +226,54 @@ mod tests {
 
         assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
+
+    #[tokio::test]
+    async fn create_announcement_scheduled_and_expiry_validated() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let (token, _) = generate_jwt(data.lecturer.id, data.lecturer.admin);
+        let uri = format!("/api/modules/{}/announcements", data.module.id);
+        let publish_at = chrono::Utc::now() + chrono::Duration::days(1);
+
+        let post = |body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post(json!({
+                "title": "Exam venue",
+                "body": "Hall A",
+                "pinned": false,
+                "publish_at": publish_at,
+                "expires_at": publish_at - chrono::Duration::hours(1),
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(post(json!({
+                "title": "Exam venue",
+                "body": "Hall A",
+                "pinned": false,
+                "publish_at": publish_at,
+                "expires_at": publish_at + chrono::Duration::days(7),
+            })))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert!(json["data"]["publish_at"].is_string());
+        assert!(json["data"]["published_at"].is_null());
+    }
 }
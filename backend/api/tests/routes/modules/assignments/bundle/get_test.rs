@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_file::{FileType, Model as AssignmentFileModel},
+        assignment_overwrite_file::Model as OverwriteFileModel,
+        assignment_task::{Model as AssignmentTaskModel, TaskType},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serial_test::serial;
+    use std::io::{Cursor, Read};
+    use tower::ServiceExt;
+    use util::{
+        execution_config::ExecutionConfig,
+        mark_allocator::{MarkAllocator, Task, save_allocator},
+    };
+    use zip::ZipArchive;
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    /// A lecturer's module with an assignment that has a custom timeout, two tasks (one with
+    /// artifact patterns and an overwrite file), main and memo archives and an allocator.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> (i64, AssignmentModel) {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "Linked lists",
+            Some("Build one"),
+            AssignmentType::Practical,
+            Utc::now() - Duration::days(1),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+
+        let mut config = ExecutionConfig::get_execution_config(module.id, assignment.id).unwrap();
+        config.execution.timeout_secs = 42;
+        config.save(module.id, assignment.id).unwrap();
+
+        let t1 = AssignmentTaskModel::create(
+            db,
+            assignment.id,
+            1,
+            "Build",
+            "make build",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+        AssignmentTaskModel::set_artifact_patterns(db, t1.id, &["*.log".to_string()])
+            .await
+            .unwrap();
+        AssignmentTaskModel::create(
+            db,
+            assignment.id,
+            2,
+            "Leaks",
+            "make leaks",
+            TaskType::Valgrind,
+        )
+        .await
+        .unwrap();
+        for (file_type, name, bytes) in [
+            (FileType::Main, "main.zip", b"main".as_slice()),
+            (FileType::Memo, "memo.zip", b"memo".as_slice()),
+        ] {
+            AssignmentFileModel::save_file(db, assignment.id, module.id, file_type, name, bytes)
+                .await
+                .unwrap();
+        }
+        OverwriteFileModel::save_file(db, assignment.id, t1.id, "stub.zip", b"stub")
+            .await
+            .unwrap();
+        save_allocator(
+            module.id,
+            assignment.id,
+            &MarkAllocator::new_now(vec![Task {
+                task_number: 1,
+                name: "Build".into(),
+                value: 10.0,
+                code_coverage: None,
+                valgrind: None,
+                subsections: vec![],
+            }]),
+        )
+        .unwrap();
+
+        (lecturer.id, assignment)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn export_contains_the_assignment_setup() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let (lecturer, assignment) = setup(app_state.db()).await;
+
+        let (token, _) = generate_jwt(lecturer, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!(
+                "/api/modules/{}/assignments/{}/export",
+                assignment.module_id, assignment.id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/zip");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        let mut zip = ZipArchive::new(Cursor::new(bytes.to_vec())).unwrap();
+        let mut names: Vec<String> = zip.file_names().map(str::to_owned).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "allocator.json",
+                "config.json",
+                "files/main/main.zip",
+                "files/memo/memo.zip",
+                "manifest.json",
+                "overwrite/1/stub.zip",
+                "tasks.json",
+            ]
+        );
+        let mut manifest = String::new();
+        zip.by_name("manifest.json")
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+        assert_eq!(manifest["name"], "Linked lists");
+        assert_eq!(manifest["version"], 1);
+
+        // Students can't export
+        let student = UserModel::create(app_state.db(), "student", "s@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(
+            app_state.db(),
+            student.id,
+            assignment.module_id,
+            Role::Student,
+        )
+        .await
+        .unwrap();
+        let (token, _) = generate_jwt(student.id, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!(
+                "/api/modules/{}/assignments/{}/export",
+                assignment.module_id, assignment.id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
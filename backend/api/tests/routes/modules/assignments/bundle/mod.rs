@@ -0,0 +1,2 @@
+pub mod get_test;
+pub mod post_test;
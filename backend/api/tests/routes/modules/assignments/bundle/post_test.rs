@@ -0,0 +1,302 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_file::{FileType, Model as AssignmentFileModel},
+        assignment_overwrite_file::Model as OverwriteFileModel,
+        assignment_task::{Model as AssignmentTaskModel, TaskType},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use db::models::{assignment_file, assignment_overwrite_file};
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::mark_allocator::load_allocator;
+    use util::{
+        execution_config::ExecutionConfig,
+        mark_allocator::{MarkAllocator, Task, save_allocator},
+    };
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    /// A lecturer's module with an assignment that has a custom timeout, two tasks (one with
+    /// artifact patterns and an overwrite file), main and memo archives and an allocator.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> (i64, AssignmentModel) {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "Linked lists",
+            Some("Build one"),
+            AssignmentType::Practical,
+            Utc::now() - Duration::days(1),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+
+        let mut config = ExecutionConfig::get_execution_config(module.id, assignment.id).unwrap();
+        config.execution.timeout_secs = 42;
+        config.save(module.id, assignment.id).unwrap();
+
+        let t1 = AssignmentTaskModel::create(
+            db,
+            assignment.id,
+            1,
+            "Build",
+            "make build",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+        AssignmentTaskModel::set_artifact_patterns(db, t1.id, &["*.log".to_string()])
+            .await
+            .unwrap();
+        AssignmentTaskModel::create(
+            db,
+            assignment.id,
+            2,
+            "Leaks",
+            "make leaks",
+            TaskType::Valgrind,
+        )
+        .await
+        .unwrap();
+        for (file_type, name, bytes) in [
+            (FileType::Main, "main.zip", b"main".as_slice()),
+            (FileType::Memo, "memo.zip", b"memo".as_slice()),
+        ] {
+            AssignmentFileModel::save_file(db, assignment.id, module.id, file_type, name, bytes)
+                .await
+                .unwrap();
+        }
+        OverwriteFileModel::save_file(db, assignment.id, t1.id, "stub.zip", b"stub")
+            .await
+            .unwrap();
+        save_allocator(
+            module.id,
+            assignment.id,
+            &MarkAllocator::new_now(vec![Task {
+                task_number: 1,
+                name: "Build".into(),
+                value: 10.0,
+                code_coverage: None,
+                valgrind: None,
+                subsections: vec![],
+            }]),
+        )
+        .unwrap();
+
+        (lecturer.id, assignment)
+    }
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> (StatusCode, Vec<u8>) {
+        let (token, _) = generate_jwt(user_id, false);
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", content_type)
+            .body(AxumBody::from(body))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    async fn send_json(app: &App, uri: &str, user_id: i64, body: Value) -> (StatusCode, Value) {
+        let (status, bytes) = send(
+            app,
+            "POST",
+            uri,
+            user_id,
+            "application/json",
+            body.to_string().into_bytes(),
+        )
+        .await;
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    fn multipart_body(bundle: &[u8], name: &str) -> (String, Vec<u8>) {
+        let boundary = "----BoundaryTest";
+        let mut body = Vec::new();
+        body.extend(format!("--{boundary}\r\n").as_bytes());
+        body.extend(b"Content-Disposition: form-data; name=\"name\"\r\n\r\n");
+        body.extend(name.as_bytes());
+        body.extend(b"\r\n");
+        body.extend(format!("--{boundary}\r\n").as_bytes());
+        body.extend(b"Content-Disposition: form-data; name=\"file\"; filename=\"bundle.zip\"\r\n");
+        body.extend(b"Content-Type: application/zip\r\n\r\n");
+        body.extend(bundle);
+        body.extend(b"\r\n");
+        body.extend(format!("--{boundary}--\r\n").as_bytes());
+        (format!("multipart/form-data; boundary={boundary}"), body)
+    }
+
+    /// Checks the copy `assignment_id` in `module_id` has the setup from [`setup`].
+    async fn assert_copied(db: &sea_orm::DatabaseConnection, module_id: i64, assignment_id: i64) {
+        let config = ExecutionConfig::get_execution_config(module_id, assignment_id).unwrap();
+        assert_eq!(config.execution.timeout_secs, 42);
+
+        let mut tasks = AssignmentTaskModel::get_by_assignment_id(db, assignment_id)
+            .await
+            .unwrap();
+        tasks.sort_by_key(|t| t.task_number);
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].command, "make build");
+        assert_eq!(tasks[0].artifact_patterns(), ["*.log"]);
+        assert_eq!(tasks[1].task_type, TaskType::Valgrind);
+
+        let files = assignment_file::Entity::find()
+            .filter(assignment_file::Column::AssignmentId.eq(assignment_id))
+            .filter(assignment_file::Column::FileType.eq(FileType::Memo))
+            .all(db)
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].load_file().await.unwrap(), b"memo");
+
+        let overwrites = assignment_overwrite_file::Entity::find()
+            .filter(assignment_overwrite_file::Column::AssignmentId.eq(assignment_id))
+            .all(db)
+            .await
+            .unwrap();
+        assert_eq!(overwrites.len(), 1);
+        assert_eq!(overwrites[0].task_id, tasks[0].id);
+        assert_eq!(overwrites[0].load_file().await.unwrap(), b"stub");
+
+        let allocator = load_allocator(module_id, assignment_id).unwrap();
+        assert_eq!(allocator.total_value, 10.0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn clone_copies_into_another_module() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let (lecturer, assignment) = setup(db).await;
+        let next_year = ModuleModel::create(db, "COS301", 2026, None, 16)
+            .await
+            .unwrap();
+        let elsewhere = ModuleModel::create(db, "COS212", 2026, None, 16)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer, next_year.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/clone",
+            assignment.module_id, assignment.id
+        );
+
+        // Not staff in the target module
+        let (status, _) =
+            send_json(&app, &uri, lecturer, json!({ "module_id": elsewhere.id })).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send_json(&app, &uri, lecturer, json!({ "module_id": 999999 })).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send_json(
+            &app,
+            &uri,
+            lecturer,
+            json!({ "module_id": next_year.id, "due_date": "not a date" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let due = (Utc::now() + Duration::days(365)).to_rfc3339();
+        let (status, json) = send_json(
+            &app,
+            &uri,
+            lecturer,
+            json!({ "module_id": next_year.id, "name": "Linked lists 2026", "due_date": due }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["data"]["module_id"], next_year.id);
+        assert_eq!(json["data"]["name"], "Linked lists 2026");
+        assert_eq!(json["data"]["assignment_type"], "practical");
+        assert_eq!(json["data"]["description"], "Build one");
+        let copy_id = json["data"]["id"].as_i64().unwrap();
+        assert_ne!(copy_id, assignment.id);
+        assert_copied(db, next_year.id, copy_id).await;
+
+        // Same module by default
+        let (status, json) = send_json(&app, &uri, lecturer, json!({})).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["data"]["module_id"], assignment.module_id);
+        assert_eq!(json["data"]["name"], "Linked lists");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn import_recreates_an_exported_assignment() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let (lecturer, assignment) = setup(db).await;
+        let module_id = assignment.module_id;
+
+        let (status, bundle) = send(
+            &app,
+            "GET",
+            &format!(
+                "/api/modules/{module_id}/assignments/{}/export",
+                assignment.id
+            ),
+            lecturer,
+            "application/json",
+            Vec::new(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let uri = format!("/api/modules/{module_id}/assignments/import");
+        let (content_type, body) = multipart_body(&bundle, "Imported");
+        let (status, bytes) = send(&app, "POST", &uri, lecturer, &content_type, body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let json: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["data"]["name"], "Imported");
+        assert_eq!(json["data"]["status"], "setup");
+        assert_copied(db, module_id, json["data"]["id"].as_i64().unwrap()).await;
+
+        // Not a bundle
+        let (content_type, body) = multipart_body(b"not a zip", "Broken");
+        let (status, bytes) = send(&app, "POST", &uri, lecturer, &content_type, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let json: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["message"], "Bundle is not a zip archive");
+    }
+}
@@ -16,6 +16,7 @@ mod tests {
     };
     use serde_json::Value;
     use tower::ServiceExt;
+    use util::execution_config::CONFIG_VERSION;
 
     struct TestData {
         admin_user: UserModel,
@@ -141,6 +142,10 @@ mod tests {
         assert_eq!(json["success"], true);
         assert_eq!(json["data"]["execution"]["timeout_secs"], 123);
         assert_eq!(json["data"]["marking"]["marking_scheme"], "exact");
+        // Saved without a version, so upgraded on load
+        assert_eq!(json["data"]["config_version"], CONFIG_VERSION);
+        assert_eq!(json["data"]["marking"]["delimiter"], "###");
+        assert!(json["data"]["marking"]["deliminator"].is_null());
     }
 
     #[tokio::test]
@@ -94,7 +94,7 @@ mod tests {
             "/api/modules/{}/assignments/{}/config",
             data.module.id, data.assignments[0].id
         );
-        let body = json!({"test_timeout": 300, "allowed_languages": ["java", "python"]});
+        let body = json!({"execution": {"timeout_secs": 300}, "project": {"language": "java"}});
         let req = Request::builder()
             .method("POST")
             .uri(&uri)
@@ -124,7 +124,7 @@ mod tests {
             "/api/modules/{}/assignments/{}/config",
             data.module.id, data.assignments[1].id
         );
-        let body = json!({"max_memory": "1GB", "grading_criteria": {"test_weight": 0.7}});
+        let body = json!({"execution": {"max_memory": 1_073_741_824u64}, "marking": {"pass_mark": 70}});
         let req = Request::builder()
             .method("POST")
             .uri(&uri)
@@ -265,6 +265,284 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_post_config_invalid_environment_name() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "environment": { "LC_ALL": "C.UTF-8", "BAD-NAME": "1" }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], false);
+        assert!(json["message"].as_str().unwrap().contains("BAD-NAME"));
+    }
+
+    #[tokio::test]
+    async fn test_post_config_invalid_image() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "project": { "language": "cpp", "image": "--privileged" }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], false);
+        assert!(json["message"].as_str().unwrap().contains("Invalid Docker image"));
+    }
+
+    #[tokio::test]
+    async fn test_post_config_unconfined_apparmor() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "security": { "apparmor_profile": "unconfined" }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert!(
+            json["message"]
+                .as_str()
+                .unwrap()
+                .contains("Invalid AppArmor profile")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_config_invalid_gene() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "gatlam": {
+                "genes": [
+                    { "kind": "categorical", "values": ["push", "pop"] },
+                    { "kind": "string", "min_length": 5, "max_length": 2 }
+                ]
+            }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["message"],
+            "Invalid gene 2: min_length 5 is greater than max_length 2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_config_invalid_property_rule() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "gatlam": {
+                "task_spec": {
+                    "rules": [
+                        { "type": "max_runtime", "ms": 200 },
+                        { "type": "contains", "value": "  ", "task_number": 1 }
+                    ]
+                }
+            }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["message"],
+            "Invalid property rule 2: value must not be empty"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_config_reports_every_problem() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "marking": { "pass_mark": 120, "pass_mrak": 50 },
+            "gatlam": { "omega1": 0.6, "omega2": 0.3, "omega3": 0.3 },
+            "security": { "password_enabled": true }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], false);
+        let paths: Vec<&str> = json["data"]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "marking.pass_mrak",
+                "marking.pass_mark",
+                "gatlam.omega1",
+                "security.password_pin"
+            ]
+        );
+        let message = json["message"].as_str().unwrap();
+        assert!(message.starts_with("Unknown field marking.pass_mrak; "));
+        assert!(message.contains("must sum to 1"));
+    }
+
+    #[tokio::test]
+    async fn test_post_config_per_task_task_spec() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignments[0].id
+        );
+        let body = json!({
+            "gatlam": {
+                "task_spec": {
+                    "1": { "valid_return_codes": [0, 2] },
+                    "2": { "max_runtime_ms": 500, "forbidden_outputs": ["BAD"] }
+                }
+            }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let get_req = Request::builder()
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let get_response = app.oneshot(get_req).await.unwrap();
+        assert_eq!(get_response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(get_response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let task_spec = &json["data"]["gatlam"]["task_spec"];
+        assert_eq!(task_spec["1"]["valid_return_codes"], json!([0, 2]));
+        assert_eq!(task_spec["2"]["max_runtime_ms"], 500);
+        assert_eq!(task_spec["2"]["valid_return_codes"], json!([0]));
+    }
+
     #[tokio::test]
     async fn test_post_config_overwrites_existing() {
         let (app, app_state, _tmp) = make_test_app_with_storage().await;
@@ -6,7 +6,7 @@ mod tests {
         body::Body,
         http::{Request, StatusCode},
     };
-    use chrono::{TimeZone, Utc};
+    use chrono::{Duration, TimeZone, Utc};
     use db::models::{
         assignment::{AssignmentType, Model as AssignmentModel},
         assignment_file::{FileType, Model as AssignmentFileModel},
@@ -17,6 +17,7 @@ mod tests {
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRoleModel, Role},
     };
+    use db::soft_delete::purge_deleted_before;
     use sea_orm::{ActiveModelTrait, EntityTrait, Set};
     use serde_json::{Value, json};
     use tower::ServiceExt;
@@ -202,8 +203,9 @@ mod tests {
         let found = db::models::assignment::Entity::find_by_id(data.assignments[0].id)
             .one(app_state.db())
             .await
+            .unwrap()
             .unwrap();
-        assert!(found.is_none());
+        assert!(found.deleted_at.is_some());
     }
 
     #[tokio::test]
@@ -234,8 +236,9 @@ mod tests {
         let found = db::models::assignment::Entity::find_by_id(data.assignments[1].id)
             .one(app_state.db())
             .await
+            .unwrap()
             .unwrap();
-        assert!(found.is_none());
+        assert!(found.deleted_at.is_some());
     }
 
     #[tokio::test]
@@ -380,6 +383,18 @@ mod tests {
         let response = app.clone().oneshot(req).await.unwrap();
         assert_eq!(response.status(), StatusCode::OK);
 
+        // Soft deleted: everything stays until the retention sweep
+        let sub = db::models::assignment_submission::Entity::find_by_id(data.submission_id)
+            .one(db)
+            .await
+            .unwrap();
+        assert!(sub.is_some());
+
+        let report = purge_deleted_before(db, Utc::now() + Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(report.assignments, 1);
+
         let found = db::models::assignment::Entity::find_by_id(data.assignments[0].id)
             .one(db)
             .await
@@ -407,6 +422,55 @@ mod tests {
         assert!(sub.is_none());
     }
 
+    #[tokio::test]
+    async fn test_restore_deleted_assignment() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}",
+            data.module.id, data.assignments[0].id
+        );
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let get = |uri: String| {
+            Request::builder()
+                .method("GET")
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = app.clone().oneshot(get(uri.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let restore = || {
+            Request::builder()
+                .method("POST")
+                .uri(format!("{}/restore", uri))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let response = app.clone().oneshot(restore()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.clone().oneshot(get(uri.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Nothing left to restore
+        let response = app.clone().oneshot(restore()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     #[tokio::test]
     async fn test_delete_assignment_already_deleted() {
         let (app, app_state, _tmp) = make_test_app_with_storage().await;
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        exam_session::Model as ExamSessionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::Value;
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::execution_config::ExecutionConfig;
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn get(app: &App, uri: &str, user_id: i64) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn students_see_their_own_session_and_staff_see_everyones() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let tutor = UserModel::create(db, "tutor", "tutor@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, tutor.id, module.id, Role::Tutor)
+            .await
+            .unwrap();
+        let mut students = Vec::new();
+        for i in 1..=2 {
+            let s = UserModel::create(db, &format!("s{i}"), &format!("s{i}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, s.id, module.id, Role::Student)
+                .await
+                .unwrap();
+            students.push(s);
+        }
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "Exam",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(1),
+            Utc::now() + Duration::days(1),
+        )
+        .await
+        .unwrap();
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.exam.enabled = true;
+        cfg.exam.duration_minutes = 45;
+        cfg.save(module.id, assignment.id).unwrap();
+        ExamSessionModel::start(db, &assignment, students[0].id, Duration::minutes(45))
+            .await
+            .unwrap();
+
+        let base = format!(
+            "/api/modules/{}/assignments/{}/exam",
+            module.id, assignment.id
+        );
+        let (status, body) = get(&app, &base, students[0].id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["enabled"], true);
+        assert_eq!(body["data"]["duration_minutes"], 45);
+        assert_eq!(body["data"]["session"]["user_id"], students[0].id);
+        assert_eq!(body["data"]["session"]["running"], true);
+
+        let (_, body) = get(&app, &base, students[1].id).await;
+        assert!(body["data"]["session"].is_null());
+
+        let sessions = format!("{base}/sessions");
+        let (status, body) = get(&app, &sessions, tutor.id).await;
+        assert_eq!(status, StatusCode::OK);
+        let list = body["data"].as_array().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0]["username"], "s1");
+
+        let (status, _) = get(&app, &sessions, students[0].id).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
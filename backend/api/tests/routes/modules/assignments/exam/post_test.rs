@@ -0,0 +1,143 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{DateTime, Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::Value;
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::execution_config::ExecutionConfig;
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn start(app: &App, uri: &str, user_id: i64, pin: Option<&str>) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let mut req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        if let Some(pin) = pin {
+            req = req.header("x-assignment-pin", pin);
+        }
+        let response = app
+            .clone()
+            .oneshot(req.body(AxumBody::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    /// An open assignment with one student; `configure` adjusts its config.
+    async fn setup(
+        db: &sea_orm::DatabaseConnection,
+        due_date: DateTime<Utc>,
+        configure: impl FnOnce(&mut ExecutionConfig),
+    ) -> (String, UserModel) {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let student = UserModel::create(db, "student", "student@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, student.id, module.id, Role::Student)
+            .await
+            .unwrap();
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "Exam",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(1),
+            due_date,
+        )
+        .await
+        .unwrap();
+        let mut cfg = ExecutionConfig::default_config();
+        configure(&mut cfg);
+        cfg.save(module.id, assignment.id).unwrap();
+
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/exam/start",
+            module.id, assignment.id
+        );
+        (uri, student)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn starting_twice_keeps_the_first_start() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let (uri, student) = setup(app_state.db(), Utc::now() + Duration::days(1), |cfg| {
+            cfg.exam.enabled = true;
+            cfg.exam.duration_minutes = 90;
+        })
+        .await;
+
+        let (status, body) = start(&app, &uri, student.id, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let session = &body["data"];
+        assert_eq!(session["running"], true);
+        let remaining = session["remaining_seconds"].as_i64().unwrap();
+        assert!((90 * 60 - 5..=90 * 60).contains(&remaining));
+
+        let (status, again) = start(&app, &uri, student.id, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(again["data"]["started_at"], session["started_at"]);
+        assert_eq!(again["data"]["ends_at"], session["ends_at"]);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn only_open_exams_can_be_started() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let (uri, student) = setup(app_state.db(), Utc::now() + Duration::days(1), |_| {}).await;
+        let (status, body) = start(&app, &uri, student.id, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["message"], "This assignment is not an exam");
+
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let (uri, student) = setup(app_state.db(), Utc::now() - Duration::hours(1), |cfg| {
+            cfg.exam.enabled = true;
+        })
+        .await;
+        let (status, body) = start(&app, &uri, student.id, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["message"], "The exam has closed");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn starting_requires_the_assignment_pin() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let (uri, student) = setup(app_state.db(), Utc::now() + Duration::days(1), |cfg| {
+            cfg.exam.enabled = true;
+            cfg.security.password_enabled = true;
+            cfg.security.password_pin = Some("4321".into());
+        })
+        .await;
+
+        let (status, body) = start(&app, &uri, student.id, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["message"], "PIN required");
+
+        let (status, _) = start(&app, &uri, student.id, Some("4321")).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_extension::Model as ExtensionModel,
+        assignment_submission::Model as AssignmentSubmissionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::Value;
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn get(app: &App, uri: &str, user_id: i64) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn extended_students_submissions_are_not_late() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let mut students = Vec::new();
+        for i in 1..=2 {
+            let s = UserModel::create(db, &format!("s{i}"), &format!("s{i}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, s.id, module.id, Role::Student)
+                .await
+                .unwrap();
+            students.push(s);
+        }
+        let [extended, regular] = [&students[0], &students[1]];
+
+        // Due yesterday; both submit now
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            Utc::now() - Duration::days(1),
+        )
+        .await
+        .unwrap();
+        for s in [extended, regular] {
+            AssignmentSubmissionModel::save_file(
+                db,
+                assignment.id,
+                s.id,
+                1,
+                8.0,
+                10.0,
+                false,
+                "main.zip",
+                "hash",
+                b"code",
+            )
+            .await
+            .unwrap();
+        }
+        ExtensionModel::grant(
+            db,
+            assignment.id,
+            extended.id,
+            Utc::now() + Duration::days(2),
+            None,
+            lecturer.id,
+        )
+        .await
+        .unwrap();
+
+        let base = format!(
+            "/api/modules/{}/assignments/{}/submissions",
+            module.id, assignment.id
+        );
+
+        let (status, json) = get(&app, &base, extended.id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["submissions"][0]["is_late"], false);
+        let (_, json) = get(&app, &base, regular.id).await;
+        assert_eq!(json["data"]["submissions"][0]["is_late"], true);
+
+        let (status, json) = get(&app, &format!("{base}?late=true"), lecturer.id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["submissions"][0]["user"]["id"], regular.id);
+        let (_, json) = get(&app, &format!("{base}?late=false"), lecturer.id).await;
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["submissions"][0]["user"]["id"], extended.id);
+    }
+}
@@ -0,0 +1,180 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_extension::Model as ExtensionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn grant_list_and_revoke_extension() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut users = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor", Role::Tutor),
+            ("student", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            users.push(u);
+        }
+        let [lecturer, tutor, student] = [&users[0], &users[1], &users[2]];
+        let due = Utc::now() + Duration::days(1);
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            due,
+        )
+        .await
+        .unwrap();
+
+        let base = format!(
+            "/api/modules/{}/assignments/{}/extensions",
+            module.id, assignment.id
+        );
+        let extended = (due + Duration::days(3)).to_rfc3339();
+
+        // Only assistant lecturers and up may grant, only to students, only past the due date
+        let body = json!({ "due_date": extended, "reason": "Medical certificate" });
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/{}", student.id),
+            tutor.id,
+            Some(body.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/{}", tutor.id),
+            lecturer.id,
+            Some(body.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/{}", student.id),
+            lecturer.id,
+            Some(json!({ "due_date": (due - Duration::hours(1)).to_rfc3339() })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &format!("{base}/{}", student.id),
+            lecturer.id,
+            Some(body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["user_id"], student.id);
+        assert_eq!(json["data"]["username"], "student");
+        assert_eq!(json["data"]["granted_by"], lecturer.id);
+        assert_eq!(json["data"]["reason"], "Medical certificate");
+
+        let (status, json) = send(&app, "GET", &base, tutor.id, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"].as_array().unwrap().len(), 1);
+        let (status, _) = send(&app, "GET", &base, student.id, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, json) = send(&app, "GET", &format!("{base}/me"), student.id, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["user_id"], student.id);
+        assert_eq!(
+            assignment.due_date_for(db, student.id).await.unwrap(),
+            ExtensionModel::for_user(db, assignment.id, student.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .due_date
+        );
+
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            &format!("{base}/{}", student.id),
+            lecturer.id,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            &format!("{base}/{}", student.id),
+            lecturer.id,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, json) = send(&app, "GET", &format!("{base}/me"), student.id, None).await;
+        assert!(json["data"].is_null());
+        assert_eq!(
+            assignment.due_date_for(db, student.id).await.unwrap(),
+            assignment.due_date
+        );
+    }
+}
@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        response::Response,
+    };
+    use chrono::Utc;
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as SubmissionModel,
+        ga_generation::{self, GenerationStats, Individual},
+        ga_run::{self, GaRunMode},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::Value;
+    use std::convert::Infallible;
+    use tower::ServiceExt;
+    use tower::util::BoxCloneService;
+
+    use crate::helpers::app::make_test_app_with_storage;
+    use api::auth::generate_jwt;
+
+    struct TestData {
+        tutor: UserModel,
+        student: UserModel,
+        module: ModuleModel,
+        assignment: AssignmentModel,
+        submission: SubmissionModel,
+        run: ga_run::Model,
+    }
+
+    /// A module with a tutor and a student, and one finished two-generation GA run of the
+    /// student's submission.
+    async fn setup_test_data(db: &sea_orm::DatabaseConnection) -> TestData {
+        let module = ModuleModel::create(db, "COS301", 2025, Some("GA"), 16)
+            .await
+            .unwrap();
+        let tutor = UserModel::create(db, "ga_tutor", "ga_tutor@test.com", "password", false)
+            .await
+            .unwrap();
+        let student = UserModel::create(db, "ga_student", "ga_student@test.com", "password", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, tutor.id, module.id, Role::Tutor)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, student.id, module.id, Role::Student)
+            .await
+            .unwrap();
+
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "GATLAM",
+            None,
+            AssignmentType::Practical,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let submission = SubmissionModel::save_file(
+            db,
+            assignment.id,
+            student.id,
+            1,
+            0.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash123#",
+            b"test",
+        )
+        .await
+        .unwrap();
+
+        let run = ga_run::Model::start(
+            db,
+            assignment.id,
+            submission.id,
+            GaRunMode::CodeCoverage,
+            2,
+            2,
+        )
+        .await
+        .unwrap();
+        for generation in 0..2 {
+            let population = [
+                Individual {
+                    payload: format!("{generation},0"),
+                    fitness: 0.1,
+                },
+                Individual {
+                    payload: format!("{generation},9"),
+                    fitness: 0.9,
+                },
+            ];
+            let stats = GenerationStats {
+                generation,
+                best_fitness: 0.9,
+                mean_fitness: 0.5,
+                coverage_percent: Some(90.0),
+                evaluated: 2 * (generation + 1),
+                interpreter_runs: generation + 2,
+                cache_hits: generation,
+            };
+            ga_generation::Model::record(db, run.id, stats, &population)
+                .await
+                .unwrap();
+        }
+        let run = ga_run::Model::finish(db, run.id, Some((0.9, "1,9".into())), None)
+            .await
+            .unwrap();
+
+        TestData {
+            tutor,
+            student,
+            module,
+            assignment,
+            submission,
+            run,
+        }
+    }
+
+    async fn get_json(
+        app: &BoxCloneService<Request<Body>, Response, Infallible>,
+        uri: &str,
+        user: &UserModel,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn tutor_lists_runs_for_assignment_and_submission() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let base = format!(
+            "/api/modules/{}/assignments/{}/ga/runs",
+            data.module.id, data.assignment.id
+        );
+
+        let (status, json) = get_json(&app, &base, &data.tutor).await;
+        assert_eq!(status, StatusCode::OK);
+        let runs = json["data"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0]["id"], data.run.id);
+        assert_eq!(runs[0]["mode"], "code_coverage");
+        assert_eq!(runs[0]["status"], "completed");
+        assert_eq!(runs[0]["best_payload"], "1,9");
+
+        let uri = format!("{}?submission_id={}", base, data.submission.id + 1);
+        let (status, json) = get_json(&app, &uri, &data.tutor).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn tutor_gets_run_history() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/ga/runs/{}",
+            data.module.id, data.assignment.id, data.run.id
+        );
+
+        let (status, json) = get_json(&app, &uri, &data.tutor).await;
+        assert_eq!(status, StatusCode::OK);
+        let history = json["data"]["history"].as_array().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1]["generation"], 1);
+        assert_eq!(history[1]["best_payload"], "1,9");
+        assert_eq!(history[1]["coverage_percent"], 90.0);
+        assert_eq!(history[1]["population"].as_array().unwrap().len(), 2);
+        assert_eq!(json["data"]["best_fitness"], 0.9);
+
+        let missing = format!(
+            "/api/modules/{}/assignments/{}/ga/runs/{}",
+            data.module.id,
+            data.assignment.id,
+            data.run.id + 1
+        );
+        let (status, _) = get_json(&app, &missing, &data.tutor).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn student_is_forbidden() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/ga/runs",
+            data.module.id, data.assignment.id
+        );
+
+        let (status, _) = get_json(&app, &uri, &data.student).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
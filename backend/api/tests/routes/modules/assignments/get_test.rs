@@ -856,6 +856,78 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_get_assignment_readiness_reports_allocator_mismatches() {
+        use db::models::{
+            assignment_memo_output::Model as MemoOutputModel,
+            assignment_task::{Model as TaskModel, TaskType},
+        };
+
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let (module_id, assignment_id) = (data.module.id, data.assignments[0].id);
+
+        let task = TaskModel::create(
+            app_state.db(),
+            assignment_id,
+            1,
+            "Task 1",
+            "make task1",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+        MemoOutputModel::save_file(
+            app_state.db(),
+            assignment_id,
+            task.id,
+            "task1.txt",
+            b"Task 1\n###A\na\n###B\nb\n",
+        )
+        .await
+        .unwrap();
+        let dir = util::paths::mark_allocator_dir(module_id, assignment_id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("allocator.json"),
+            serde_json::json!({
+                "generated_at": "2025-01-01T00:00:00Z",
+                "total_value": 1.0,
+                "tasks": [{
+                    "task_number": 1,
+                    "name": "Task 1",
+                    "value": 1.0,
+                    "valgrind": false,
+                    "subsections": [{ "name": "A", "value": 1.0 }]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!("/api/modules/{module_id}/assignments/{assignment_id}/readiness");
+        let req = Request::builder()
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["mark_allocator_present"], true);
+        assert_eq!(
+            json["data"]["mark_allocator_issues"],
+            serde_json::json!([
+                { "kind": "subsection_count", "task_number": 1, "allocator": 1, "memo": 2 }
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn test_get_assignment_readiness_not_found() {
         let (app, _app_state, _tmp) = make_test_app_with_storage().await;
@@ -16,6 +16,7 @@ mod tests {
     use serde_json::{Value, json};
     use std::{collections::HashMap, fs};
     use tower::ServiceExt;
+    use util::execution_config::{ExecutionConfig, GradingPolicy};
     use util::paths::{config_dir, submission_report_path};
 
     use crate::helpers::app::make_test_app_with_storage;
@@ -750,6 +751,73 @@ mod tests {
         assert!(!names.contains("Sorting"));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn list_grades_best_of_last_n_and_weighted_policies() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+
+        // oldest to newest: 90%, 50%, 60%
+        for (attempt, earned, offset) in [(1, 18.0, -300), (2, 10.0, -200), (3, 12.0, -100)] {
+            seed_submission(
+                db,
+                data.module.id,
+                &data.assignment_last,
+                &data.student1,
+                attempt,
+                earned,
+                20.0,
+                offset,
+                vec![(data.t_ids_by_num[&1], 1, earned / 2.0, 10.0)],
+            )
+            .await;
+        }
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let grades = |cfg: ExecutionConfig| {
+            cfg.save(data.module.id, data.assignment_last.id).unwrap();
+
+            let req = Request::builder()
+                .uri(format!(
+                    "/api/modules/{}/assignments/{}/grades",
+                    data.module.id, data.assignment_last.id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                assert_eq!(resp.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&body).unwrap()["data"].clone()
+            }
+        };
+
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.marking.grading_policy = GradingPolicy::BestOfLastN;
+        cfg.marking.best_of_last_n = 2;
+        let data_best = grades(cfg.clone()).await;
+        assert_eq!(data_best["grading_policy"], "best_of_last_n");
+        assert_eq!(data_best["best_of_last_n"], 2);
+        let score = data_best["grades"][0]["score"].as_f64().unwrap();
+        assert!((score - 60.0).abs() < 1e-6, "expected 60, got {score}");
+
+        // (90*1 + 50*2 + min(60, 55)*3) / 6
+        cfg.marking.grading_policy = GradingPolicy::Weighted;
+        cfg.marking.later_attempt_cap = 55.0;
+        let data_weighted = grades(cfg).await;
+        assert_eq!(data_weighted["grading_policy"], "weighted");
+        assert_eq!(data_weighted["later_attempt_cap"], 55.0);
+        assert!(data_weighted.get("best_of_last_n").is_none());
+        let score = data_weighted["grades"][0]["score"].as_f64().unwrap();
+        assert!((score - 355.0 / 6.0).abs() < 1e-6, "got {score}");
+        assert_eq!(data_weighted["grades"][0]["tasks"][0]["earned"], 6.0);
+    }
+
     #[tokio::test]
     #[serial]
     async fn list_grades_invalid_sort_returns_400() {
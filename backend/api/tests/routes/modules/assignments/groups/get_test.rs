@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::Utc;
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as AssignmentSubmissionModel,
+        group::Model as GroupModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::Value;
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::execution_config::ExecutionConfig;
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn get(app: &App, uri: &str, user_id: i64) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn group_submission_is_shared_by_members() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let mut students = Vec::new();
+        for i in 1..=3 {
+            let s = UserModel::create(db, &format!("s{i}"), &format!("s{i}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, s.id, module.id, Role::Student)
+                .await
+                .unwrap();
+            students.push(s);
+        }
+        let [s1, s2, s3] = [&students[0], &students[1], &students[2]];
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now() + chrono::Duration::days(7),
+        )
+        .await
+        .unwrap();
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.marking.group_submissions = true;
+        cfg.save(module.id, assignment.id).unwrap();
+
+        let group = GroupModel::create(db, assignment.id, "Team A", &[s1.id, s2.id])
+            .await
+            .unwrap();
+        let submission = AssignmentSubmissionModel::save_file(
+            db,
+            assignment.id,
+            s1.id,
+            1,
+            8.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash",
+            b"code",
+        )
+        .await
+        .unwrap();
+        AssignmentSubmissionModel::set_group(db, submission.id, Some(group.id))
+            .await
+            .unwrap();
+
+        let base = format!("/api/modules/{}/assignments/{}", module.id, assignment.id);
+
+        // The other member sees (and can download) the submission; outsiders don't
+        let (status, json) = get(&app, &format!("{base}/submissions"), s2.id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["submissions"][0]["id"], submission.id);
+        assert_eq!(json["data"]["submissions"][0]["user"]["id"], s1.id);
+        let (_, json) = get(&app, &format!("{base}/submissions"), s3.id).await;
+        assert_eq!(json["data"]["total"], 0);
+
+        let download = format!("{base}/submissions/{}/download", submission.id);
+        let (status, _) = get(&app, &download, s2.id).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = get(&app, &download, s3.id).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Members share the attempt budget
+        assert_eq!(assignment.attempts_used_by_user(db, s2.id).await.unwrap(), 1);
+        assert_eq!(assignment.attempts_used_by_user(db, s3.id).await.unwrap(), 0);
+
+        // Every member gets the grade
+        let (status, json) = get(&app, &format!("{base}/grades"), lecturer.id).await;
+        assert_eq!(status, StatusCode::OK);
+        let grades = json["data"]["grades"].as_array().unwrap();
+        assert_eq!(grades.len(), 2);
+        for (grade, member) in grades.iter().zip([s1, s2]) {
+            assert_eq!(grade["user_id"], member.id);
+            assert_eq!(grade["submission_id"], submission.id);
+            assert_eq!(grade["score"], 80.0);
+        }
+
+        let (status, json) = get(&app, &format!("{base}/groups/me"), s2.id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["id"], group.id);
+        let (status, json) = get(&app, &format!("{base}/groups/me"), s3.id).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"].is_null());
+        let (status, _) = get(&app, &format!("{base}/groups"), s2.id).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,264 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::Utc;
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        group::Model as GroupModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    struct TestData {
+        lecturer: UserModel,
+        tutor: UserModel,
+        students: Vec<UserModel>,
+        module: ModuleModel,
+        assignment: AssignmentModel,
+        other_assignment: AssignmentModel,
+    }
+
+    async fn setup(db: &sea_orm::DatabaseConnection) -> TestData {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        let tutor = UserModel::create(db, "tutor", "tutor@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, tutor.id, module.id, Role::Tutor)
+            .await
+            .unwrap();
+        let mut students = Vec::new();
+        for i in 1..=3 {
+            let s = UserModel::create(db, &format!("s{i}"), &format!("s{i}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, s.id, module.id, Role::Student)
+                .await
+                .unwrap();
+            students.push(s);
+        }
+        let mut assignments = Vec::new();
+        for name in ["A1", "A2"] {
+            assignments.push(
+                AssignmentModel::create(
+                    db,
+                    module.id,
+                    name,
+                    None,
+                    AssignmentType::Assignment,
+                    Utc::now(),
+                    Utc::now(),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        let other_assignment = assignments.pop().unwrap();
+        let assignment = assignments.pop().unwrap();
+        TestData {
+            lecturer,
+            tutor,
+            students,
+            module,
+            assignment,
+            other_assignment,
+        }
+    }
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn create_group_validates_members() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup(app_state.db()).await;
+        let (token, _) = generate_jwt(data.lecturer.id, false);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/groups",
+            data.module.id, data.assignment.id
+        );
+        let [s1, s2, s3] = [&data.students[0], &data.students[1], &data.students[2]];
+
+        let (status, json) = send(
+            &app,
+            "POST",
+            &uri,
+            &token,
+            Some(json!({ "name": "Team A", "member_ids": [s1.id, s2.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["data"]["name"], "Team A");
+        assert_eq!(json["data"]["members"].as_array().unwrap().len(), 2);
+
+        // Already in Team A
+        let (status, _) = send(
+            &app,
+            "POST",
+            &uri,
+            &token,
+            Some(json!({ "name": "Team B", "member_ids": [s2.id, s3.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // Name taken
+        let (status, _) = send(
+            &app,
+            "POST",
+            &uri,
+            &token,
+            Some(json!({ "name": "Team A", "member_ids": [s3.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // Staff can't be members
+        let (status, _) = send(
+            &app,
+            "POST",
+            &uri,
+            &token,
+            Some(json!({ "name": "Team B", "member_ids": [s3.id, data.tutor.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Students can't create groups
+        let (student_token, _) = generate_jwt(s3.id, false);
+        let (status, _) = send(
+            &app,
+            "POST",
+            &uri,
+            &student_token,
+            Some(json!({ "name": "Team B", "member_ids": [s3.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // The same student may be grouped on another assignment
+        let (status, _) = send(
+            &app,
+            "POST",
+            &format!(
+                "/api/modules/{}/assignments/{}/groups",
+                data.module.id, data.other_assignment.id
+            ),
+            &token,
+            Some(json!({ "name": "Team A", "member_ids": [s1.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // Tutors can list
+        let (tutor_token, _) = generate_jwt(data.tutor.id, false);
+        let (status, json) = send(&app, "GET", &uri, &tutor_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn edit_and_delete_group() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup(db).await;
+        let (token, _) = generate_jwt(data.lecturer.id, false);
+        let [s1, s2, s3] = [&data.students[0], &data.students[1], &data.students[2]];
+        let group = GroupModel::create(db, data.assignment.id, "Team A", &[s1.id, s2.id])
+            .await
+            .unwrap();
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/groups/{}",
+            data.module.id, data.assignment.id, group.id
+        );
+
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &uri,
+            &token,
+            Some(json!({ "name": "Renamed", "member_ids": [s2.id, s3.id] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["name"], "Renamed");
+        assert_eq!(
+            GroupModel::member_ids(db, group.id).await.unwrap(),
+            [s2.id, s3.id]
+        );
+
+        let (status, _) = send(&app, "PUT", &uri, &token, Some(json!({}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // The group doesn't belong to the other assignment
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            &format!(
+                "/api/modules/{}/assignments/{}/groups/{}",
+                data.module.id, data.other_assignment.id, group.id
+            ),
+            &token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = send(&app, "DELETE", &uri, &token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            GroupModel::list_for_assignment(db, data.assignment.id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let (status, _) = send(&app, "DELETE", &uri, &token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -152,6 +152,127 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_put_mark_allocator_rejects_memory_threshold_percent_out_of_range() {
+        use serde_json::json;
+
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/mark_allocator",
+            data.module.id, data.assignment.id
+        );
+
+        let bad_payload = json!({
+            "generated_at": Utc::now().to_rfc3339(),
+            "tasks": [
+                {
+                    "task_number": 1,
+                    "name": "Task 1",
+                    "value": 5,
+                    "subsections": [
+                        {
+                            "name": "Memory usage",
+                            "value": 5,
+                            "memory_thresholds": [{ "max_peak_bytes": 1024, "percent": 150 }]
+                        }
+                    ]
+                }
+            ],
+            "total_value": 5
+        });
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(bad_payload.to_string()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["message"],
+            "tasks[0].subsections[0].memory_thresholds[0].percent must be between 0 and 100"
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_put_mark_allocator_validates_nested_subsections() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/mark_allocator",
+            data.module.id, data.assignment.id
+        );
+
+        let payload = |second_value: f64| {
+            json!({
+                "generated_at": Utc::now().to_rfc3339(),
+                "tasks": [
+                    {
+                        "task_number": 1,
+                        "name": "Task 1",
+                        "value": 4,
+                        "subsections": [
+                            {
+                                "name": "Output",
+                                "value": 4,
+                                "comparator": "percentage",
+                                "subsections": [
+                                    { "name": "First", "value": 2 },
+                                    { "name": "Second", "value": second_value, "weight": 2 }
+                                ]
+                            }
+                        ]
+                    }
+                ],
+                "total_value": 4
+            })
+        };
+        let put = |body: serde_json::Value| {
+            Request::builder()
+                .method("PUT")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(put(payload(2.0))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["message"],
+            "tasks[0].subsections[0]: sum of nested subsection values (6) must equal its value (4)"
+        );
+
+        let response = app.oneshot(put(payload(1.0))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let output = &json["data"]["tasks"][0]["subsections"][0];
+        assert_eq!(output["comparator"], "percentage");
+        assert_eq!(output["subsections"][1]["weight"], 2.0);
+    }
+
     #[tokio::test]
     #[serial]
     async fn test_put_mark_allocator_not_found_on_nonexistent() {
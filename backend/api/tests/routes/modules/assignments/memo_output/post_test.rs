@@ -49,7 +49,7 @@ mod tests {
         let mut cfg = ExecutionConfig::default_config();
         cfg.project.language = Language::Java; // we’re providing Main.java
         cfg.project.submission_mode = SubmissionMode::Manual; // matches your route expectations
-        cfg.marking.deliminator = "###".to_string(); // keep existing spelling
+        cfg.marking.delimiter = "###".to_string(); // keep existing spelling
 
         cfg.save(module_id, assignment_id)
             .expect("write config.json");
@@ -328,4 +328,114 @@ mod tests {
         let response = app.oneshot(req).await.unwrap();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_post_memo_output_for_task_forbidden_for_student() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let task = AssignmentTaskModel::create(
+            app_state.db(),
+            data.assignment.id,
+            2,
+            "Task 2",
+            "make task2",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+
+        let (token, _) = generate_jwt(data.student_user.id, data.student_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/memo_output/generate/{}",
+            data.module.id, data.assignment.id, task.id
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_post_memo_output_for_task_rejects_coverage_task() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        setup_input_dirs(data.module.id, data.assignment.id);
+        let task = AssignmentTaskModel::create(
+            app_state.db(),
+            data.assignment.id,
+            2,
+            "Coverage",
+            "make coverage",
+            TaskType::Coverage,
+        )
+        .await
+        .unwrap();
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/memo_output/generate/{}",
+            data.module.id, data.assignment.id, task.id
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_post_memo_output_for_task_not_in_assignment() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        setup_input_dirs(data.module.id, data.assignment.id);
+
+        let other = AssignmentModel::create(
+            app_state.db(),
+            data.module.id,
+            "Assignment 2",
+            None,
+            db::models::assignment::AssignmentType::Assignment,
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 31, 23, 59, 59).unwrap(),
+        )
+        .await
+        .unwrap();
+        let foreign_task = AssignmentTaskModel::create(
+            app_state.db(),
+            other.id,
+            1,
+            "Other Task",
+            "make task1",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/memo_output/generate/{}",
+            data.module.id, data.assignment.id, foreign_task.id
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
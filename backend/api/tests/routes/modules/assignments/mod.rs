@@ -1,14 +1,22 @@
 pub mod assignment_access_test;
+pub mod bundle;
 pub mod config;
 pub mod delete_test;
+pub mod exam;
+pub mod extensions;
 pub mod files;
+pub mod ga;
 pub mod get_test;
 pub mod grades;
+pub mod groups;
 pub mod mark_allocator;
 pub mod memo_output;
+pub mod moderation;
 pub mod plagiarism;
 pub mod post_test;
 pub mod put_test;
+pub mod regrades;
+pub mod rubric;
 pub mod starter;
 pub mod statistics;
 pub mod submissions;
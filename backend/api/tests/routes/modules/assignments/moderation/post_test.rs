@@ -0,0 +1,268 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as AssignmentSubmissionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::execution_config::ExecutionConfig;
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn moderation_samples_second_marks_and_locks_grades_on_sign_off() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut staff = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor1", Role::Tutor),
+            ("tutor2", Role::Tutor),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            staff.push(u.id);
+        }
+        let (lecturer, tutor1, tutor2) = (staff[0], staff[1], staff[2]);
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        ExecutionConfig::default_config()
+            .save(module.id, assignment.id)
+            .unwrap();
+
+        // ten graded students, marked 10%..100%
+        let mut submissions = Vec::new();
+        for i in 1..=10 {
+            let name = format!("u{i:08}");
+            let u = UserModel::create(db, &name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, Role::Student)
+                .await
+                .unwrap();
+            let s = AssignmentSubmissionModel::save_file(
+                db,
+                assignment.id,
+                u.id,
+                1,
+                i as f64,
+                10.0,
+                false,
+                "main.zip",
+                "hash",
+                b"code",
+            )
+            .await
+            .unwrap();
+            AssignmentSubmissionModel::set_graded(db, s.id)
+                .await
+                .unwrap();
+            submissions.push(s.id);
+        }
+
+        let base = format!(
+            "/api/modules/{}/assignments/{}/moderation",
+            module.id, assignment.id
+        );
+
+        // tutors may not sample
+        let (status, _) = send(
+            &app,
+            "POST",
+            &format!("{base}/samples"),
+            tutor1,
+            Some(json!({ "marker_id": tutor1 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // 30% of ten students, spread over the marks
+        let (status, body) = send(
+            &app,
+            "POST",
+            &format!("{base}/samples"),
+            lecturer,
+            Some(json!({ "marker_id": tutor1, "percentage": 30 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let samples = body["data"].as_array().unwrap().clone();
+        let firsts: Vec<f64> = samples
+            .iter()
+            .map(|s| s["first_mark"].as_f64().unwrap())
+            .collect();
+        assert_eq!(firsts, [20.0, 60.0, 90.0]);
+
+        // nothing marked yet, so sign-off is refused
+        let (status, _) = send(&app, "POST", &format!("{base}/sign-off"), lecturer, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // the second marker sees their samples without the first marks
+        let (status, body) = send(&app, "GET", &base, tutor1, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["samples"].as_array().unwrap().len(), 3);
+        assert!(body["data"]["samples"][0].get("first_mark").is_none());
+        assert!(body["data"].get("statistics").is_none());
+        let (_, body) = send(&app, "GET", &base, tutor2, None).await;
+        assert!(body["data"]["samples"].as_array().unwrap().is_empty());
+
+        // only the assigned marker records marks
+        let first_id = samples[0]["id"].as_i64().unwrap();
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/samples/{first_id}"),
+            tutor2,
+            Some(json!({ "mark": 30 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/samples/{first_id}"),
+            tutor1,
+            Some(json!({ "mark": 120 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        for (sample, mark) in samples.iter().zip([30.0, 60.0, 70.0]) {
+            let (status, body) = send(
+                &app,
+                "PUT",
+                &format!("{base}/samples/{}", sample["id"]),
+                tutor1,
+                Some(json!({ "mark": mark, "comment": "checked" })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK, "{body}");
+            assert_eq!(body["data"]["second_mark"], json!(mark));
+        }
+
+        // differences of +10, 0 and -20
+        let (_, body) = send(&app, "GET", &format!("{base}?tolerance=15"), lecturer, None).await;
+        let stats = &body["data"]["statistics"];
+        assert_eq!(stats["marked"], 3);
+        assert!((stats["mean_difference"].as_f64().unwrap() + 10.0 / 3.0).abs() < 1e-9);
+        assert_eq!(stats["max_absolute_difference"], 20.0);
+        assert_eq!(stats["outside_tolerance"], 1);
+        assert_eq!(body["data"]["samples"][2]["difference"], -20.0);
+
+        // signing off locks grades
+        let (status, body) = send(&app, "POST", &format!("{base}/sign-off"), lecturer, None).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["data"]["signed_off"], true);
+        let ignore_uri = format!(
+            "/api/modules/{}/assignments/{}/submissions/{}/ignore",
+            module.id, assignment.id, submissions[0]
+        );
+        let (status, _) = send(
+            &app,
+            "PATCH",
+            &ignore_uri,
+            lecturer,
+            Some(json!({ "ignored": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("{base}/samples/{first_id}"),
+            tutor1,
+            Some(json!({ "mark": 40 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // ...including everything grades are computed from when read
+        let assignment_uri = format!("/api/modules/{}/assignments/{}", module.id, assignment.id);
+        for (method, path) in [
+            ("POST", "config".to_string()),
+            ("POST", "config/reset".to_string()),
+            ("PUT", "mark_allocator".to_string()),
+            ("POST", "mark_allocator/generate".to_string()),
+            ("POST", "memo_output/generate".to_string()),
+            ("PUT", format!("extensions/{tutor2}")),
+            ("DELETE", format!("extensions/{tutor2}")),
+        ] {
+            let uri = format!("{assignment_uri}/{path}");
+            let (status, _) = send(&app, method, &uri, lecturer, Some(json!({}))).await;
+            assert_eq!(status, StatusCode::CONFLICT, "{method} {path}");
+        }
+
+        // reopening unlocks them
+        let (status, _) = send(&app, "DELETE", &format!("{base}/sign-off"), lecturer, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "DELETE", &format!("{base}/sign-off"), lecturer, None).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = send(
+            &app,
+            "PATCH",
+            &ignore_uri,
+            lecturer,
+            Some(json!({ "ignored": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -2,6 +2,7 @@
 mod plagiarism_tests {
     use crate::helpers::app::make_test_app_with_storage;
     use api::auth::generate_jwt;
+    use api::routes::modules::assignments::plagiarism::post::MOSS_MATCH_MANIFEST;
     use axum::{
         body::Body as AxumBody,
         http::{Request, StatusCode},
@@ -11,13 +12,17 @@ mod plagiarism_tests {
         assignment::{AssignmentType, Model as AssignmentModel},
         assignment_submission::Model as SubmissionModel,
         module::Model as ModuleModel,
+        moss_report::{Entity as MossReportEntity, FilterMode},
         plagiarism_case::{Model as PlagiarismCaseModel, Status},
+        plagiarism_match::NewMatch,
+        plagiarism_report::Model as PlagiarismReportModel,
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRoleModel, Role},
     };
     use sea_orm::{ActiveModelTrait, DatabaseConnection, IntoActiveModel, Set};
     use serde_json::Value;
     use tower::ServiceExt;
+    use util::paths::moss_matches_dir;
 
     struct TestData {
         admin_user: UserModel,
@@ -728,4 +733,443 @@ mod plagiarism_tests {
 
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
+
+    /// Test Case: Archived MOSS match pages are listed and served
+    #[tokio::test]
+    async fn test_moss_match_pages_served_from_archive() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let report = MossReportEntity::create_report(
+            app_state.db(),
+            data.assignment.id,
+            "http://moss.stanford.edu/results/1/42",
+            FilterMode::All,
+            "Week 1".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let base = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/moss/reports/{}/matches",
+            data.module.id, data.assignment.id, report.id
+        );
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let get = |uri: String| {
+            Request::builder()
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+
+        // Nothing archived yet
+        let response = app.clone().oneshot(get(base.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let dir = moss_matches_dir(data.module.id, data.assignment.id, &report.id.to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("match0.html"),
+            r#"<FRAME SRC="match0-top.html" NAME="top"><A HREF="match0-0.html#1">x</A><IMG SRC="http://moss.stanford.edu/bitmaps/tm_0_5.gif">"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join(MOSS_MATCH_MANIFEST),
+            r#"[{"user_a":"student1","user_b":"student2","matches":[{"match_href":"match0.html"}]}]"#,
+        )
+        .unwrap();
+
+        let response = app.clone().oneshot(get(base.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["data"]["pairs"][0]["matches"][0]["match_href"],
+            "match0.html"
+        );
+
+        // Served as-is, sandboxed
+        let page = r#"<FRAME SRC="match0-top.html" NAME="top"><A HREF="match0-0.html#1">x</A><IMG SRC="http://moss.stanford.edu/bitmaps/tm_0_5.gif">"#;
+        let response = app
+            .clone()
+            .oneshot(get(format!("{base}/match0.html")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(response.headers()["content-security-policy"], "sandbox");
+        assert_eq!(response.headers()["x-content-type-options"], "nosniff");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(body.to_vec()).unwrap(), page);
+
+        // A signed link opens the match and its frames without a bearer token
+        let response = app
+            .clone()
+            .oneshot(get(format!("{base}/match0.html/link")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let url = json["data"]["url"].as_str().unwrap().to_string();
+        assert!(url.starts_with("/api/plagiarism/match-pages/") && url.ends_with("/match0.html"));
+        assert!(!url.contains(&token));
+
+        std::fs::write(dir.join("match0-top.html"), "top").unwrap();
+        std::fs::write(dir.join("match1.html"), "other").unwrap();
+        let anonymous = |uri: String| Request::builder().uri(uri).body(AxumBody::empty()).unwrap();
+        let response = app.clone().oneshot(anonymous(url.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-security-policy"], "sandbox");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(String::from_utf8(body.to_vec()).unwrap(), page);
+
+        let frame = url.replace("/match0.html", "/match0-top.html");
+        let response = app.clone().oneshot(anonymous(frame)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let other = url.replace("/match0.html", "/match1.html");
+        let response = app.clone().oneshot(anonymous(other)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Session tokens don't pass for links
+        let response = app
+            .clone()
+            .oneshot(anonymous(format!(
+                "/api/plagiarism/match-pages/{token}/match0.html"
+            )))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .clone()
+            .oneshot(get(format!("{base}/match9.html/link")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .clone()
+            .oneshot(get(format!("{base}/match9.html")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = app
+            .oneshot(get(format!("{base}/{MOSS_MATCH_MANIFEST}")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test Case: Graph export as CSV / DOT, with thresholds and format validation
+    #[tokio::test]
+    async fn test_get_graph_export_formats() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let base = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/graph",
+            data.module.id, data.assignment.id
+        );
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let get = |uri: String| {
+            Request::builder()
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(get(format!("{base}?format=csv")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/csv; charset=utf-8"
+        );
+        assert_eq!(
+            response.headers()["content-disposition"],
+            format!(
+                "attachment; filename=\"plagiarism_graph_{}.csv\"",
+                data.assignment.id
+            )
+            .as_str()
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            format!(
+                "source,target,weight,lines_matched,case_id,status\nstudent1,student2,0,0,{},review\n",
+                data.plagiarism_case.id
+            )
+        );
+
+        // Thresholds drop the edge, leaving an empty graph
+        let response = app
+            .clone()
+            .oneshot(get(format!("{base}?format=dot&min_lines=5")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            "graph plagiarism {\n}\n"
+        );
+
+        let response = app
+            .oneshot(get(format!("{base}?format=png")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    /// Test Case: Parsed report pairs are listed with percent / lines / user filters
+    #[tokio::test]
+    async fn test_list_report_pairs_filters() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let report = MossReportEntity::create_report(
+            app_state.db(),
+            data.assignment.id,
+            "http://moss.stanford.edu/results/1/43",
+            FilterMode::All,
+            "Week 2".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let base = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/moss/reports/{}/pairs",
+            data.module.id, data.assignment.id, report.id
+        );
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let get = |uri: String| {
+            Request::builder()
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+
+        // Not parsed yet
+        let response = app.clone().oneshot(get(base.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let pair = |user_a: &str, user_b: &str, percent: f64, lines: i64| NewMatch {
+            user_a: user_a.into(),
+            user_b: user_b.into(),
+            submission_id_a: None,
+            submission_id_b: None,
+            total_percent: Some(percent),
+            lines_matched: lines,
+            files: None,
+            case_id: None,
+        };
+        PlagiarismReportModel::store(
+            app_state.db(),
+            report.id,
+            data.assignment.id,
+            Some("moss results".into()),
+            vec![
+                pair("student1", "student2", 80.0, 40),
+                pair("student1", "student3", 30.0, 90),
+                pair("student3", "student4", 10.0, 5),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let list = |query: &str| {
+            let app = app.clone();
+            let req = get(format!("{base}{query}"));
+            async move {
+                let response = app.oneshot(req).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&body).unwrap()["data"].clone()
+            }
+        };
+
+        let all = list("").await;
+        assert_eq!(all["total"], 3);
+        assert_eq!(all["report"]["pair_count"], 3);
+        assert_eq!(all["report"]["title"], "moss results");
+        assert_eq!(all["pairs"][0]["lines_matched"], 90);
+
+        let strong = list("?min_percent=50").await;
+        assert_eq!(strong["total"], 1);
+        assert_eq!(strong["pairs"][0]["user_b"], "student2");
+
+        assert_eq!(list("?min_lines=10").await["total"], 2);
+        assert_eq!(list("?user=STUDENT3").await["total"], 2);
+        assert_eq!(list("?user=student4&min_lines=10").await["total"], 0);
+
+        let paged = list("?per_page=2&page=2").await;
+        assert_eq!(paged["pairs"].as_array().unwrap().len(), 1);
+        assert_eq!(paged["total"], 3);
+    }
+
+    /// Test Case: Case diff returns both files with regions oriented to the case
+    #[tokio::test]
+    async fn test_get_case_diff() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        // student1 submitted a zip, student2 a single file
+        let mut zipped = std::io::Cursor::new(Vec::new());
+        {
+            let mut w = zip::ZipWriter::new(&mut zipped);
+            w.start_file("src/my main.cpp", zip::write::SimpleFileOptions::default())
+                .unwrap();
+            std::io::Write::write_all(&mut w, b"int a;\nint b;\nint c;\n").unwrap();
+            w.finish().unwrap();
+        }
+        let zip_sub = SubmissionModel::save_file(
+            app_state.db(),
+            data.assignment.id,
+            data.student_user1.id,
+            2,
+            10.0,
+            10.0,
+            false,
+            "sub.zip",
+            "hash-zip",
+            zipped.get_ref(),
+        )
+        .await
+        .unwrap();
+        let file_sub = SubmissionModel::save_file(
+            app_state.db(),
+            data.assignment.id,
+            data.student_user2.id,
+            2,
+            10.0,
+            10.0,
+            false,
+            "main.cpp",
+            "hash-file",
+            b"// header\nint a;\nint b;\n",
+        )
+        .await
+        .unwrap();
+
+        let report = MossReportEntity::create_report(
+            app_state.db(),
+            data.assignment.id,
+            "jplag://local",
+            FilterMode::All,
+            "Week 5".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let case = PlagiarismCaseModel::create_case(
+            app_state.db(),
+            data.assignment.id,
+            zip_sub.id.min(file_sub.id),
+            zip_sub.id.max(file_sub.id),
+            "High similarity detected",
+            70.0,
+            2,
+            Some(report.id),
+        )
+        .await
+        .unwrap();
+
+        // Stored with the plain file on side `a`
+        PlagiarismReportModel::store(
+            app_state.db(),
+            report.id,
+            data.assignment.id,
+            None,
+            vec![NewMatch {
+                user_a: "student2".into(),
+                user_b: "student1".into(),
+                submission_id_a: Some(file_sub.id),
+                submission_id_b: Some(zip_sub.id),
+                total_percent: Some(70.0),
+                lines_matched: 2,
+                files: Some(serde_json::json!([{
+                    "a_filename": "main.cpp",
+                    "b_filename": "src/my_main.cpp",
+                    "percent": null,
+                    "lines_matched": 2,
+                    "match_href": "x.json",
+                    "regions": [{ "a_start": 2, "a_end": 3, "b_start": 1, "b_end": 2 }]
+                }])),
+                case_id: None,
+            }],
+        )
+        .await
+        .unwrap();
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let get = |case_id: i64| {
+            Request::builder()
+                .uri(format!(
+                    "/api/modules/{}/assignments/{}/plagiarism/{}/diff",
+                    data.module.id, data.assignment.id, case_id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(get(case.id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let diff = &json["data"];
+
+        // Side 1 is the lower submission id
+        let zip_first = zip_sub.id < file_sub.id;
+        let (zip_side, file_side) = if zip_first { ("1", "2") } else { ("2", "1") };
+        assert_eq!(
+            diff[format!("submission_{zip_side}")]["username"],
+            "student1"
+        );
+
+        let pair = &diff["files"][0];
+        let zip_file = &pair[format!("file_{zip_side}")];
+        assert_eq!(zip_file["name"], "src/my_main.cpp");
+        assert_eq!(zip_file["content"], "int a;\nint b;\nint c;\n");
+        assert_eq!(zip_file["line_count"], 3);
+        assert_eq!(
+            pair[format!("file_{file_side}")]["content"],
+            "// header\nint a;\nint b;\n"
+        );
+        let region = &pair["regions"][0];
+        assert_eq!(region[format!("start_{zip_side}")], 1);
+        assert_eq!(region[format!("end_{file_side}")], 3);
+
+        // A case without a stored report pair
+        let response = app.oneshot(get(data.plagiarism_case.id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -8,13 +8,17 @@ mod patch_plagiarism_tests {
     use chrono::{Datelike, TimeZone, Utc};
     use db::models::{
         assignment::{AssignmentType, Model as AssignmentModel},
-        assignment_submission::{Entity as AssignmentSubmissionEntity, Model as SubmissionModel},
+        assignment_submission::{
+            Column as SubmissionColumn, Entity as AssignmentSubmissionEntity,
+            Model as SubmissionModel,
+        },
         module::Model as ModuleModel,
         plagiarism_case::{Entity as PlagiarismCaseEntity, Model as PlagiarismCaseModel, Status},
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRoleModel, Role},
     };
-    use sea_orm::{DatabaseConnection, EntityTrait};
+    use db::soft_delete::SoftDelete;
+    use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
     use serde_json::Value;
     use tower::ServiceExt;
 
@@ -259,6 +263,34 @@ mod patch_plagiarism_tests {
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_flag_plagiarism_case_of_deleted_submission_not_found() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        AssignmentSubmissionEntity::mark_deleted()
+            .filter(SubmissionColumn::Id.eq(data.plagiarism_case.submission_id_2))
+            .exec(app_state.db())
+            .await
+            .unwrap();
+
+        let req = make_patch_request(
+            &data.lecturer_user,
+            data.module.id,
+            data.assignment.id,
+            data.plagiarism_case.id,
+        );
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let case = PlagiarismCaseEntity::find_by_id(data.plagiarism_case.id)
+            .one(app_state.db())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(case.status, data.plagiarism_case.status);
+    }
+
     #[tokio::test]
     async fn test_flag_plagiarism_case_unauthorized() {
         let (app, app_state, _tmp) = make_test_app_with_storage().await;
@@ -510,3 +542,217 @@ mod review_plagiarism_tests {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     }
 }
+
+#[cfg(test)]
+mod stage_plagiarism_tests {
+    use super::patch_plagiarism_tests::setup_test_data;
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use db::models::{
+        notification::{Model as NotificationModel, NotificationKind},
+        user::Model as UserModel,
+    };
+    use serde_json::{Value, json};
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send_stage(
+        app: &App,
+        user: &UserModel,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method("PATCH")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(AxumBody::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    async fn upload_evidence(
+        app: &App,
+        user: &UserModel,
+        uri: &str,
+        filename: &str,
+        content: &[u8],
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let boundary = "----BoundaryTest";
+        let mut body = Vec::new();
+        body.extend(format!("--{}\r\n", boundary).as_bytes());
+        body.extend(format!("Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n", filename).as_bytes());
+        body.extend(content);
+        body.extend(format!("\r\n--{}--\r\n", boundary).as_bytes());
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .body(AxumBody::from(body))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_plagiarism_case_lifecycle() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+        let base = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/{}",
+            data.module.id, data.assignment.id, data.plagiarism_case.id
+        );
+        let stage_uri = format!("{base}/stage");
+        let evidence_uri = format!("{base}/evidence");
+        let (al, lecturer) = (&data.assistant_user, &data.lecturer_user);
+
+        // reporting needs evidence
+        let report = json!({ "stage": "reported", "decision": "MOSS match of 92%" });
+        let (status, _) = send_stage(&app, al, &stage_uri, report.clone()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, json) =
+            upload_evidence(&app, al, &evidence_uri, "moss.html", b"<html/>").await;
+        assert_eq!(status, StatusCode::CREATED, "{json}");
+        assert_eq!(json["data"]["stage"], Value::Null);
+        let first_evidence = json["data"]["id"].as_i64().unwrap();
+
+        // stages are taken in order
+        let (status, _) = send_stage(
+            &app,
+            al,
+            &stage_uri,
+            json!({ "stage": "interviewed", "decision": "Met both students" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, json) = send_stage(&app, al, &stage_uri, report).await;
+        assert_eq!(status, StatusCode::OK, "{json}");
+        assert_eq!(json["data"]["stage"], "reported");
+
+        // a reported case is neither deleted nor cleared by review
+        let (token, _) = generate_jwt(lecturer.id, lecturer.admin);
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(&base)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let (status, _) = send_stage(&app, lecturer, &format!("{base}/review"), json!({})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // the interview needs evidence from the reported stage
+        let interview = json!({ "stage": "interviewed", "decision": "Met both students" });
+        let (status, _) = send_stage(&app, al, &stage_uri, interview.clone()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, json) =
+            upload_evidence(&app, al, &evidence_uri, "interview.txt", b"notes").await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["data"]["stage"], "reported");
+        let (status, _) = send_stage(&app, al, &stage_uri, interview).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // only lecturers resolve, and resolving needs an outcome
+        let resolve = json!({
+            "stage": "resolved",
+            "decision": "Admitted sharing code",
+            "outcome": "warning"
+        });
+        let (status, _) = send_stage(&app, al, &stage_uri, resolve.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send_stage(
+            &app,
+            lecturer,
+            &stage_uri,
+            json!({ "stage": "resolved", "decision": "Admitted sharing code" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, json) = send_stage(&app, lecturer, &stage_uri, resolve).await;
+        assert_eq!(status, StatusCode::OK, "{json}");
+        assert_eq!(json["data"]["outcome"], "warning");
+        let (status, _) = upload_evidence(&app, al, &evidence_uri, "late.txt", b"x").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // the decisions and evidence are kept
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("{base}/lifecycle"))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let stages: Vec<&str> = json["data"]["decisions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["to_stage"].as_str().unwrap())
+            .collect();
+        assert_eq!(stages, ["reported", "interviewed", "resolved"]);
+        assert_eq!(json["data"]["evidence"].as_array().unwrap().len(), 2);
+
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("{base}/evidence/{first_evidence}"))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"<html/>");
+
+        // by default the student hears about reporting and resolution only
+        let mut delivered = 0;
+        for _ in 0..40 {
+            delivered = NotificationModel::unread_count(db, data.student_user.id)
+                .await
+                .unwrap();
+            if delivered >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(delivered, 2);
+        let (items, _) = NotificationModel::list_for_user(db, data.student_user.id, false, 1, 10)
+            .await
+            .unwrap();
+        assert!(
+            items
+                .iter()
+                .all(|n| n.kind == NotificationKind::PlagiarismCaseUpdated)
+        );
+    }
+}
@@ -12,7 +12,10 @@ mod create_plagiarism_tests {
         assignment::{AssignmentType, Model as AssignmentModel},
         assignment_submission::Model as SubmissionModel,
         module::Model as ModuleModel,
+        moss_report::{Entity as MossReportEntity, FilterMode},
         plagiarism_case::{Entity as PlagiarismCaseEntity, Status},
+        plagiarism_match::{Entity as PlagiarismMatchEntity, NewMatch},
+        plagiarism_report::Model as PlagiarismReportModel,
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRoleModel, Role},
     };
@@ -652,6 +655,336 @@ mod create_plagiarism_tests {
         let v = json["data"]["similarity"].as_f64().unwrap();
         assert!(approx_eq_f64(v, sim as f64, 1e-3)); // allow small float error
     }
+    /// Test Case: Starter code uploaded for plagiarism runs can be listed and cleared
+    #[tokio::test]
+    async fn test_plagiarism_base_files_round_trip() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/base-files",
+            data.module.id, data.assignment.id
+        );
+        let upload = |filename: &str, content: &[u8]| {
+            let boundary = "----BoundaryTest";
+            let mut body = Vec::new();
+            body.extend(format!("--{}\r\n", boundary).as_bytes());
+            body.extend(format!("Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n", filename).as_bytes());
+            body.extend(content);
+            body.extend(format!("\r\n--{}--\r\n", boundary).as_bytes());
+            Request::builder()
+                .method("POST")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header(
+                    "Content-Type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .body(AxumBody::from(body))
+                .unwrap()
+        };
+        let request = |method: &str| {
+            Request::builder()
+                .method(method)
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+        let listed = |json: &Value| -> Vec<String> {
+            json["data"]["files"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|f| f["filename"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(upload("../skeleton.cpp", b"int main() {}"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["filename"], "skeleton.cpp");
+        assert_eq!(json["data"]["size"], 13);
+
+        let response = app
+            .clone()
+            .oneshot(upload("starter.zip", b"not a zip"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app.clone().oneshot(request("GET")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listed(&json), ["skeleton.cpp"]);
+
+        let response = app.clone().oneshot(request("DELETE")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.oneshot(request("GET")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert!(listed(&json).is_empty());
+    }
+
+    /// Test Case: JPlag selected but no jar configured
+    #[tokio::test]
+    async fn test_run_check_jplag_requires_jar() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        unsafe {
+            std::env::remove_var("JPLAG_JAR");
+        }
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/moss",
+            data.module.id, data.assignment.id
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(AxumBody::from(
+                json!({ "engine": "jplag", "description": "MOSS is down" }).to_string(),
+            ))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], false);
+        assert_eq!(json["message"], "JPlag is not configured (set JPLAG_JAR)");
+    }
+
+    /// Test Case: Archive assignments are validated before a run starts
+    #[tokio::test]
+    async fn test_run_check_archive_assignments_validated() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        unsafe {
+            std::env::remove_var("JPLAG_JAR");
+        }
+
+        let last_year = ModuleModel::create(
+            app_state.db(),
+            "CS101",
+            Utc::now().year() - 1,
+            Some("Intro to CS"),
+            5,
+        )
+        .await
+        .unwrap();
+        let archive = AssignmentModel::create(
+            app_state.db(),
+            last_year.id,
+            "Assignment 1",
+            Some("Last year"),
+            AssignmentType::Assignment,
+            Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 31, 23, 59, 59).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/plagiarism/moss",
+            data.module.id, data.assignment.id
+        );
+        let run = |archive_ids: Vec<i64>| {
+            Request::builder()
+                .method("POST")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(
+                    json!({
+                        "engine": "jplag",
+                        "description": "Compare with last year",
+                        "archive_assignment_ids": archive_ids,
+                    })
+                    .to_string(),
+                ))
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(run(vec![data.assignment.id]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app.clone().oneshot(run(vec![9999])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Not staff on last year's module
+        let response = app.clone().oneshot(run(vec![archive.id])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // With access, the run gets as far as the engine check
+        UserModuleRoleModel::assign_user_to_module(
+            app_state.db(),
+            data.lecturer_user.id,
+            last_year.id,
+            Role::Lecturer,
+        )
+        .await
+        .unwrap();
+        let response = app.oneshot(run(vec![archive.id])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["message"], "JPlag is not configured (set JPLAG_JAR)");
+    }
+
+    /// Test Case: A case is created from a parsed report pair exactly once
+    #[tokio::test]
+    async fn test_create_case_from_report_pair() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let report = MossReportEntity::create_report(
+            app_state.db(),
+            data.assignment.id,
+            "jplag://local",
+            FilterMode::All,
+            "Week 3".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        PlagiarismReportModel::store(
+            app_state.db(),
+            report.id,
+            data.assignment.id,
+            None,
+            vec![
+                NewMatch {
+                    user_a: "student2".into(),
+                    user_b: "student1".into(),
+                    submission_id_a: Some(data.submission2.id),
+                    submission_id_b: Some(data.submission1.id),
+                    total_percent: Some(64.5),
+                    lines_matched: 27,
+                    files: None,
+                    case_id: None,
+                },
+                NewMatch {
+                    user_a: "ghost".into(),
+                    user_b: "student1".into(),
+                    submission_id_a: None,
+                    submission_id_b: Some(data.submission1.id),
+                    total_percent: None,
+                    lines_matched: 3,
+                    files: None,
+                    case_id: None,
+                },
+            ],
+        )
+        .await
+        .unwrap();
+        let pairs = PlagiarismMatchEntity::find()
+            .all(app_state.db())
+            .await
+            .unwrap();
+        let linked = pairs.iter().find(|p| p.user_a == "student2").unwrap();
+        let unlinked = pairs.iter().find(|p| p.user_a == "ghost").unwrap();
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, data.lecturer_user.admin);
+        let post = |match_id: i64, report_id: i64| {
+            Request::builder()
+                .method("POST")
+                .uri(format!(
+                    "/api/modules/{}/assignments/{}/plagiarism/moss/reports/{}/pairs/{}/case",
+                    data.module.id, data.assignment.id, report_id, match_id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(post(linked.id, report.id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let case = &json["data"];
+        assert_eq!(
+            case["submission_id_1"],
+            data.submission1.id.min(data.submission2.id)
+        );
+        assert_eq!(case["lines_matched"], 27);
+        assert_eq!(case["report_id"], report.id);
+        assert!(approx_eq_f64(
+            case["similarity"].as_f64().unwrap(),
+            64.5,
+            1e-4
+        ));
+
+        let stored = PlagiarismMatchEntity::find_by_id(linked.id)
+            .one(app_state.db())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.case_id, case["id"].as_i64());
+
+        // Already has a case
+        let response = app
+            .clone()
+            .oneshot(post(linked.id, report.id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        // Pair without both submissions
+        let response = app
+            .clone()
+            .oneshot(post(unlinked.id, report.id))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Pair from another report
+        let other = MossReportEntity::create_report(
+            app_state.db(),
+            data.assignment.id,
+            "jplag://local",
+            FilterMode::All,
+            "Week 4".to_string(),
+            None,
+        )
+        .await
+        .unwrap();
+        let response = app.oneshot(post(linked.id, other.id)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
 }
 
 #[cfg(test)]
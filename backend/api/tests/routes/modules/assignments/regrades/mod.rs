@@ -0,0 +1,2 @@
+pub mod post_test;
+pub mod put_test;
@@ -0,0 +1,218 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as AssignmentSubmissionModel,
+        assignment_task::{Model as AssignmentTaskModel, TaskType},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    struct Setup {
+        module_id: i64,
+        assignment_id: i64,
+        tutor: i64,
+        s1: i64,
+        s2: i64,
+        submission_id: i64,
+    }
+
+    /// A module with a tutor and two students, and one task; `s1` has a marked submission.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> Setup {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for (name, role) in [
+            ("tutor", Role::Tutor),
+            ("s1", Role::Student),
+            ("s2", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            ids.push(u.id);
+        }
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        AssignmentTaskModel::create(
+            db,
+            assignment.id,
+            1,
+            "Task 1",
+            "make task1",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+        let submission = AssignmentSubmissionModel::save_file(
+            db,
+            assignment.id,
+            ids[1],
+            1,
+            5.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash",
+            b"code",
+        )
+        .await
+        .unwrap();
+        AssignmentSubmissionModel::set_graded(db, submission.id)
+            .await
+            .unwrap();
+        Setup {
+            module_id: module.id,
+            assignment_id: assignment.id,
+            tutor: ids[0],
+            s1: ids[1],
+            s2: ids[2],
+            submission_id: submission.id,
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn students_open_requests_against_their_marked_submissions() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        let base = format!(
+            "/api/modules/{}/assignments/{}/regrades",
+            s.module_id, s.assignment_id
+        );
+        let body = |task: i64| {
+            json!({
+                "submission_id": s.submission_id,
+                "task_number": task,
+                "subsection": "Output",
+                "reason": "My output matches the memo"
+            })
+        };
+
+        // Someone else's submission, staff, unknown task, empty reason
+        let (status, _) = send(&app, "POST", &base, s.s2, Some(body(1))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&app, "POST", &base, s.tutor, Some(body(1))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&app, "POST", &base, s.s1, Some(body(9))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            "POST",
+            &base,
+            s.s1,
+            Some(json!({ "submission_id": s.submission_id, "task_number": 1, "reason": " " })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = send(&app, "POST", &base, s.s1, Some(body(1))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(json["data"]["status"], "open");
+        assert_eq!(json["data"]["subsection"], "Output");
+        assert_eq!(json["data"]["username"], "s1");
+
+        // One pending request per task/subsection
+        let (status, _) = send(&app, "POST", &base, s.s1, Some(body(1))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // Students see only their own; staff see all
+        let (status, json) = send(&app, "GET", &base, s.s1, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"].as_array().unwrap().len(), 1);
+        let (_, json) = send(&app, "GET", &base, s.s2, None).await;
+        assert!(json["data"].as_array().unwrap().is_empty());
+        let (_, json) = send(&app, "GET", &format!("{base}?status=open"), s.tutor, None).await;
+        assert_eq!(json["data"].as_array().unwrap().len(), 1);
+        let (status, _) = send(&app, "GET", &format!("{base}?status=bogus"), s.tutor, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn unmarked_submissions_cannot_be_disputed() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        AssignmentSubmissionModel::set_failed(
+            db,
+            s.submission_id,
+            db::models::assignment_submission::SubmissionStatus::FailedCompile,
+        )
+        .await
+        .unwrap();
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            &format!(
+                "/api/modules/{}/assignments/{}/regrades",
+                s.module_id, s.assignment_id
+            ),
+            s.s1,
+            Some(json!({
+                "submission_id": s.submission_id,
+                "task_number": 1,
+                "reason": "It compiles on my machine"
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
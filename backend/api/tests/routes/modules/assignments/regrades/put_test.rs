@@ -0,0 +1,240 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as AssignmentSubmissionModel,
+        assignment_task::{Model as AssignmentTaskModel, TaskType},
+        module::Model as ModuleModel,
+        notification::{Model as NotificationModel, NotificationKind},
+        regrade_request::Model as RegradeModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use sea_orm::EntityTrait;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    struct Setup {
+        module_id: i64,
+        assignment_id: i64,
+        tutor: i64,
+        s1: i64,
+        s2: i64,
+        submission_id: i64,
+    }
+
+    /// A module with a tutor and two students, and one task; `s1` has a marked submission.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> Setup {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for (name, role) in [
+            ("tutor", Role::Tutor),
+            ("s1", Role::Student),
+            ("s2", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            ids.push(u.id);
+        }
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        AssignmentTaskModel::create(
+            db,
+            assignment.id,
+            1,
+            "Task 1",
+            "make task1",
+            TaskType::Normal,
+        )
+        .await
+        .unwrap();
+        let submission = AssignmentSubmissionModel::save_file(
+            db,
+            assignment.id,
+            ids[1],
+            1,
+            5.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash",
+            b"code",
+        )
+        .await
+        .unwrap();
+        AssignmentSubmissionModel::set_graded(db, submission.id)
+            .await
+            .unwrap();
+        Setup {
+            module_id: module.id,
+            assignment_id: assignment.id,
+            tutor: ids[0],
+            s1: ids[1],
+            s2: ids[2],
+            submission_id: submission.id,
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn staff_review_and_resolve_requests() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+        let submission = db::models::assignment_submission::Entity::find_by_id(s.submission_id)
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        let request = RegradeModel::open(db, &submission, s.s1, 1, None, "Task 1 is correct")
+            .await
+            .unwrap();
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/regrades/{}",
+            s.module_id, s.assignment_id, request.id
+        );
+
+        // Only staff respond; other students can't even look
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.s1,
+            Some(json!({ "status": "resolved" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&app, "GET", &uri, s.s2, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&app, "PUT", &uri, s.tutor, Some(json!({}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.tutor,
+            Some(json!({ "status": "under_review" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["status"], "under_review");
+        assert_eq!(json["data"]["responded_by"], s.tutor);
+        assert!(json["data"]["resolved_at"].is_null());
+
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.tutor,
+            Some(json!({ "status": "resolved", "response": "Marks adjusted" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["status"], "resolved");
+        assert_eq!(json["data"]["response"], "Marks adjusted");
+        assert!(json["data"]["resolved_at"].is_string());
+
+        // Resolved requests stay resolved
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.tutor,
+            Some(json!({ "status": "open" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, json) = send(&app, "GET", &uri, s.s1, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["status"], "resolved");
+
+        // The student is notified of each response (delivery runs in the background)
+        let mut delivered = 0;
+        for _ in 0..50 {
+            delivered = NotificationModel::unread_count(db, s.s1).await.unwrap();
+            if delivered >= 2 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(delivered, 2);
+        let (items, _) = NotificationModel::list_for_user(db, s.s1, false, 1, 10)
+            .await
+            .unwrap();
+        assert!(
+            items
+                .iter()
+                .all(|n| n.kind == NotificationKind::RegradeUpdated)
+        );
+        assert!(items.iter().any(|n| n.body == "Marks adjusted"));
+
+        let (status, _) = send(
+            &app,
+            "GET",
+            &format!(
+                "/api/modules/{}/assignments/{}/regrades/999999",
+                s.module_id, s.assignment_id
+            ),
+            s.tutor,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as AssignmentSubmissionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    struct Setup {
+        module_id: i64,
+        assignment_id: i64,
+        lecturer: i64,
+        tutor: i64,
+        s1: i64,
+        s2: i64,
+        submission_id: i64,
+    }
+
+    /// A module with a lecturer, a tutor and two students; `s1` has a submission marked 60%.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> Setup {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor", Role::Tutor),
+            ("s1", Role::Student),
+            ("s2", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            ids.push(u.id);
+        }
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        let submission = AssignmentSubmissionModel::save_file(
+            db,
+            assignment.id,
+            ids[2],
+            1,
+            6.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash",
+            b"code",
+        )
+        .await
+        .unwrap();
+        AssignmentSubmissionModel::set_graded(db, submission.id)
+            .await
+            .unwrap();
+        Setup {
+            module_id: module.id,
+            assignment_id: assignment.id,
+            lecturer: ids[0],
+            tutor: ids[1],
+            s1: ids[2],
+            s2: ids[3],
+            submission_id: submission.id,
+        }
+    }
+
+    fn rubric_uri(s: &Setup) -> String {
+        format!(
+            "/api/modules/{}/assignments/{}/rubric",
+            s.module_id, s.assignment_id
+        )
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn students_only_see_their_own_scores() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+        let uri = rubric_uri(&s);
+        let score_uri = format!("{}/submissions/{}", uri, s.submission_id);
+
+        let (status, _) = send(&app, "GET", &score_uri, s.s1, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        send(
+            &app,
+            "PUT",
+            &uri,
+            s.lecturer,
+            Some(json!({
+                "title": "Code quality",
+                "weight": 25,
+                "criteria": [ { "name": "Style", "max_score": 10 } ]
+            })),
+        )
+        .await;
+
+        // Unscored: the automated mark stands
+        let (status, json) = send(&app, "GET", &score_uri, s.s1, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["criteria"][0]["score"], Value::Null);
+        assert_eq!(json["data"]["rubric_percentage"], Value::Null);
+        assert_eq!(json["data"]["final_percentage"], 60.0);
+
+        let (status, _) = send(&app, "GET", &score_uri, s.s2, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = send(&app, "GET", &score_uri, s.tutor, None).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,329 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as AssignmentSubmissionModel,
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use std::fs;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::paths::submission_report_path;
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user_id: i64,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user_id, false);
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    struct Setup {
+        module_id: i64,
+        assignment_id: i64,
+        lecturer: i64,
+        tutor: i64,
+        s1: i64,
+        s2: i64,
+        submission_id: i64,
+    }
+
+    /// A module with a lecturer, a tutor and two students; `s1` has a submission marked 60%.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> Setup {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut ids = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor", Role::Tutor),
+            ("s1", Role::Student),
+            ("s2", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            ids.push(u.id);
+        }
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now() - Duration::days(7),
+            Utc::now() + Duration::days(7),
+        )
+        .await
+        .unwrap();
+        let submission = AssignmentSubmissionModel::save_file(
+            db,
+            assignment.id,
+            ids[2],
+            1,
+            6.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash",
+            b"code",
+        )
+        .await
+        .unwrap();
+        AssignmentSubmissionModel::set_graded(db, submission.id)
+            .await
+            .unwrap();
+        Setup {
+            module_id: module.id,
+            assignment_id: assignment.id,
+            lecturer: ids[0],
+            tutor: ids[1],
+            s1: ids[2],
+            s2: ids[3],
+            submission_id: submission.id,
+        }
+    }
+
+    fn rubric_uri(s: &Setup) -> String {
+        format!(
+            "/api/modules/{}/assignments/{}/rubric",
+            s.module_id, s.assignment_id
+        )
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn save_rubric_validates_and_replaces_criteria() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+        let uri = rubric_uri(&s);
+        let body = json!({
+            "title": "Code quality",
+            "weight": 40,
+            "criteria": [
+                { "name": "Style", "max_score": 5 },
+                { "name": "Design", "description": "Structure", "max_score": 5 }
+            ]
+        });
+
+        // Tutors can mark but not define the rubric
+        let (status, _) = send(&app, "PUT", &uri, s.tutor, Some(body.clone())).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.lecturer,
+            Some(json!({ "title": "Bad", "weight": 120, "criteria": [ { "name": "A", "max_score": 1 } ] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.lecturer,
+            Some(json!({ "title": "Bad", "weight": 10, "criteria": [ { "name": "A", "max_score": 0 } ] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = send(&app, "PUT", &uri, s.lecturer, Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["total"], 10.0);
+        let style_id = json["data"]["criteria"][0]["id"].as_i64().unwrap();
+
+        // Keep "Style", drop "Design", add "Tests"
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.lecturer,
+            Some(json!({
+                "title": "Code quality",
+                "weight": 40,
+                "criteria": [
+                    { "id": style_id, "name": "Style", "max_score": 4 },
+                    { "name": "Tests", "max_score": 6 }
+                ]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let criteria = json["data"]["criteria"].as_array().unwrap();
+        assert_eq!(criteria.len(), 2);
+        assert_eq!(criteria[0]["id"], style_id);
+        assert_eq!(criteria[1]["name"], "Tests");
+
+        // An id from nowhere is rejected
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.lecturer,
+            Some(json!({
+                "title": "Code quality",
+                "weight": 40,
+                "criteria": [ { "id": 999999, "name": "Ghost", "max_score": 1 } ]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = send(&app, "GET", &uri, s.s1, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["weight"], 40.0);
+
+        let (status, _) = send(&app, "DELETE", &uri, s.lecturer, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "GET", &uri, s.s1, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn scores_blend_into_the_submission_grade() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+        let uri = rubric_uri(&s);
+        let score_uri = format!("{}/submissions/{}", uri, s.submission_id);
+
+        // No rubric yet
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &score_uri,
+            s.tutor,
+            Some(json!({ "scores": [ { "criterion_id": 1, "score": 1 } ] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, json) = send(
+            &app,
+            "PUT",
+            &uri,
+            s.lecturer,
+            Some(json!({
+                "title": "Code quality",
+                "weight": 50,
+                "criteria": [
+                    { "name": "Style", "max_score": 5 },
+                    { "name": "Design", "max_score": 5 }
+                ]
+            })),
+        )
+        .await;
+        let style = json["data"]["criteria"][0]["id"].as_i64().unwrap();
+        let design = json["data"]["criteria"][1]["id"].as_i64().unwrap();
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &score_uri,
+            s.tutor,
+            Some(json!({ "scores": [ { "criterion_id": style, "score": 6 } ] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &score_uri,
+            s.s2,
+            Some(json!({ "scores": [ { "criterion_id": style, "score": 5 } ] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &score_uri,
+            s.tutor,
+            Some(json!({
+                "scores": [
+                    { "criterion_id": style, "score": 5, "comment": "Tidy" },
+                    { "criterion_id": design, "score": 3 }
+                ]
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        // 60% automated, 80% rubric, half each
+        assert_eq!(json["data"]["automated_percentage"], 60.0);
+        assert_eq!(json["data"]["rubric_percentage"], 80.0);
+        assert_eq!(json["data"]["final_percentage"], 70.0);
+        assert_eq!(json["data"]["criteria"][0]["comment"], "Tidy");
+        assert_eq!(json["data"]["criteria"][0]["marked_by"], s.tutor);
+
+        // The submission detail carries the blended grade too
+        let report = submission_report_path(s.module_id, s.assignment_id, s.s1, 1);
+        fs::create_dir_all(report.parent().unwrap()).unwrap();
+        fs::write(
+            &report,
+            json!({
+                "id": s.submission_id,
+                "attempt": 1,
+                "filename": "main.zip",
+                "created_at": Utc::now().to_rfc3339(),
+                "updated_at": Utc::now().to_rfc3339(),
+                "is_practice": false,
+                "is_late": false,
+                "mark": { "earned": 6, "total": 10 },
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let (status, json) = send(
+            &app,
+            "GET",
+            &format!(
+                "/api/modules/{}/assignments/{}/submissions/{}",
+                s.module_id, s.assignment_id, s.submission_id
+            ),
+            s.s1,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["rubric"]["final_percentage"], 70.0);
+    }
+}
@@ -1005,4 +1005,234 @@ mod tests {
         assert_eq!(d["total_marks"], 0.0);
         assert_eq!(d["num_students_submitted"], 3);
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_task_stats_difficulty_analysis() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+        let a = &data.assignment_best;
+
+        // (student, task 1 earned, [task 2 subsection A, subsection B]) out of 10 + 5 + 5
+        for (user, t1, [sa, sb]) in [
+            (&data.student1, 10.0, [5.0, 3.0]),
+            (&data.student2, 5.0, [5.0, 0.0]),
+            (&data.student3, 0.0, [4.0, 0.0]),
+        ] {
+            let earned = t1 + sa + sb;
+            AssignmentSubmissionModel::save_file(
+                db, a.id, user.id, 1, earned, 20.0, false, "main.zip", "hash", b"code",
+            )
+            .await
+            .unwrap();
+            let path = submission_report_path(data.module.id, a.id, user.id, 1);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let report = json!({
+                "mark": { "earned": earned, "total": 20.0 },
+                "tasks": [
+                    {
+                        "task_number": 1,
+                        "name": "Task 1",
+                        "score": { "earned": t1, "total": 10.0 },
+                        "subsections": []
+                    },
+                    {
+                        "task_number": 2,
+                        "name": "Task 2",
+                        "score": { "earned": sa + sb, "total": 10.0 },
+                        "subsections": [
+                            { "label": "A", "earned": sa, "total": 5.0, "feedback": "" },
+                            { "label": "B", "earned": sb, "total": 5.0, "feedback": "" }
+                        ]
+                    }
+                ]
+            });
+            fs::write(&path, report.to_string()).unwrap();
+        }
+
+        let (token, _) = generate_jwt(data.lecturer_user.id, false);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/stats/tasks",
+            data.module.id, a.id
+        );
+        let req = Request::builder()
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let d = &json["data"];
+
+        assert_eq!(d["students"], 3);
+        let t1 = &d["tasks"][0];
+        assert_eq!(t1["task_number"], 1);
+        assert_eq!(t1["mean_earned"], 5.0);
+        assert_eq!(t1["median_earned"], 5.0);
+        assert_eq!(t1["zero_rate"], 33.33);
+        assert_eq!(t1["full_rate"], 33.33);
+        assert!(t1["discrimination"].as_f64().unwrap() > 0.9);
+
+        let t2 = &d["tasks"][1];
+        assert_eq!(t2["mean_pct"], 56.67);
+        assert_eq!(t2["subsections"][1]["label"], "B");
+        assert_eq!(t2["subsections"][1]["zero_rate"], 66.67);
+        assert_eq!(t2["subsections"][1]["fail_rate"], 100.0);
+
+        // B is failed by everyone, A by one student; task 1 has no subsections
+        let failures: Vec<(&str, f64)> = d["common_failures"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| {
+                (
+                    f["label"].as_str().unwrap(),
+                    f["fail_rate"].as_f64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(failures, [("B", 100.0), ("A", 33.33)]);
+
+        // students may not see it
+        let (token, _) = generate_jwt(data.student1.id, false);
+        let req = Request::builder()
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_grade_distribution_and_cohort_comparison() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+        let (m, a) = (data.module.id, &data.assignment_best);
+
+        seed_for_user(db, m, a, &data.student1, &[(90.0, 100.0)], &[-10]).await;
+        seed_for_user(db, m, a, &data.student2, &[(50.0, 100.0)], &[-10]).await;
+        seed_for_user(db, m, a, &data.student3, &[(40.0, 100.0)], &[-10]).await;
+        let last = &data.assignment_last;
+        seed_for_user(db, m, last, &data.student1, &[(100.0, 100.0)], &[-10]).await;
+
+        let get = |user_id: i64, query: String| {
+            let app = app.clone();
+            async move {
+                let (token, _) = generate_jwt(user_id, false);
+                let req = Request::builder()
+                    .uri(format!(
+                        "/api/modules/{m}/assignments/{}/stats/distribution{query}",
+                        a.id
+                    ))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.oneshot(req).await.unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status, serde_json::from_slice::<Value>(&body).unwrap())
+            }
+        };
+        let lecturer = data.lecturer_user.id;
+
+        let (status, json) = get(lecturer, "?bucket_width=25".into()).await;
+        assert_eq!(status, StatusCode::OK);
+        let d = &json["data"];
+        assert_eq!(d["students"], 3);
+        let counts: Vec<i64> = d["buckets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| b["count"].as_i64().unwrap())
+            .collect();
+        assert_eq!(counts, [0, 1, 1, 1]);
+        assert_eq!(d["q1"], 45.0);
+        assert_eq!(d["median"], 50.0);
+        assert_eq!(d["q3"], 70.0);
+        assert_eq!(d["pass_rate"], 66.67);
+        assert!(d.get("comparison").is_none());
+
+        let (status, _) = get(lecturer, "?bucket_width=0".into()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get(data.student1.id, String::new()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // against another assignment
+        let (status, json) = get(lecturer, format!("?compare_assignment_id={}", last.id)).await;
+        assert_eq!(status, StatusCode::OK);
+        let c = &json["data"]["comparison"];
+        assert_eq!(c["assignment_id"], last.id);
+        assert_eq!(c["distribution"]["students"], 1);
+        assert_eq!(c["mean_difference"], -40.0);
+
+        // against last year's offering, once the lecturer may see it
+        let (status, _) = get(lecturer, "?compare_previous_year=true".into()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let previous = ModuleModel::create(db, "COS101", 2023, None, 16)
+            .await
+            .unwrap();
+        let old = AssignmentModel::create(
+            db,
+            previous.id,
+            "a best",
+            None,
+            AssignmentType::Assignment,
+            Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2023, 1, 31, 23, 59, 59).unwrap(),
+        )
+        .await
+        .unwrap();
+        write_config_json(previous.id, old.id, GradingPolicy::Best, 50);
+        UserModuleRoleModel::assign_user_to_module(
+            db,
+            data.student2.id,
+            previous.id,
+            Role::Student,
+        )
+        .await
+        .unwrap();
+        seed_for_user(
+            db,
+            previous.id,
+            &old,
+            &data.student2,
+            &[(30.0, 100.0)],
+            &[-10],
+        )
+        .await;
+        let (status, _) = get(lecturer, "?compare_previous_year=true".into()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        UserModuleRoleModel::assign_user_to_module(db, lecturer, previous.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let (status, json) = get(lecturer, "?compare_previous_year=true".into()).await;
+        assert_eq!(status, StatusCode::OK);
+        let c = &json["data"]["comparison"];
+        assert_eq!(c["assignment_id"], old.id);
+        assert_eq!(c["year"], 2023);
+        assert_eq!(c["pass_rate_difference"], 66.67);
+
+        // a new submission invalidates the cached distribution
+        seed_for_user(
+            db,
+            m,
+            a,
+            &data.student3,
+            &[(40.0, 100.0), (100.0, 100.0)],
+            &[-10, -5],
+        )
+        .await;
+        let (_, json) = get(lecturer, "?bucket_width=25".into()).await;
+        assert_eq!(json["data"]["max"], 100.0);
+    }
 }
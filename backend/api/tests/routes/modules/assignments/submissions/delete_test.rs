@@ -15,12 +15,15 @@ mod delete_submission_tests {
 
     use db::models::{
         assignment::{AssignmentType, Model as AssignmentModel},
-        assignment_submission::{Entity as SubmissionEntity, Model as SubmissionModel},
+        assignment_submission::{
+            Column as SubmissionColumn, Entity as SubmissionEntity, Model as SubmissionModel,
+        },
         module::Model as ModuleModel,
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRoleModel, Role},
     };
-    use sea_orm::EntityTrait;
+    use db::soft_delete::SoftDelete;
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
 
     use crate::helpers::app::make_test_app_with_storage;
 
@@ -182,9 +185,10 @@ mod delete_submission_tests {
         let v: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(v["success"], true);
 
-        // gone
+        // hidden until restored or purged
         assert!(
-            SubmissionEntity::find_by_id(data.sub1.id)
+            SubmissionEntity::find_active()
+                .filter(SubmissionColumn::Id.eq(data.sub1.id))
                 .one(app_state.db())
                 .await
                 .unwrap()
@@ -214,9 +218,10 @@ mod delete_submission_tests {
         let resp = app.clone().oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
 
-        // gone
+        // hidden until restored or purged
         assert!(
-            SubmissionEntity::find_by_id(data.sub2.id)
+            SubmissionEntity::find_active()
+                .filter(SubmissionColumn::Id.eq(data.sub2.id))
                 .one(app_state.db())
                 .await
                 .unwrap()
@@ -374,7 +379,8 @@ mod delete_submission_tests {
 
         for id in [data.sub1.id, data.sub2.id, sub3.id] {
             assert!(
-                SubmissionEntity::find_by_id(id)
+                SubmissionEntity::find_active()
+                    .filter(SubmissionColumn::Id.eq(id))
                     .one(app_state.db())
                     .await
                     .unwrap()
@@ -448,7 +454,8 @@ mod delete_submission_tests {
 
         // sub1 removed, wrong_sub remains (belongs to other assignment)
         assert!(
-            SubmissionEntity::find_by_id(data.sub1.id)
+            SubmissionEntity::find_active()
+                .filter(SubmissionColumn::Id.eq(data.sub1.id))
                 .one(app_state.db())
                 .await
                 .unwrap()
@@ -556,4 +563,119 @@ mod delete_submission_tests {
                 .is_some()
         );
     }
+
+    // ---------------- RESTORE: /submissions/{id}/restore ----------------
+
+    #[tokio::test]
+    #[serial]
+    async fn lecturer_can_restore_deleted_submission() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.lecturer.id, data.lecturer.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/submissions/{}",
+            data.module.id, data.assignment.id, data.sub1.id
+        );
+        let request = |method: &str, uri: String| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(request("DELETE", uri.clone())).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let restore_uri = format!("{}/restore", uri);
+        let resp = app
+            .clone()
+            .oneshot(request("POST", restore_uri.clone()))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert!(
+            SubmissionEntity::find_active()
+                .filter(SubmissionColumn::Id.eq(data.sub1.id))
+                .one(app_state.db())
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        // Live submissions cannot be restored
+        let resp = app.clone().oneshot(request("POST", restore_uri)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    // ---------------- CANCEL RUN: /submissions/{id}/run ----------------
+
+    fn cancel_run_request(data: &TestData, user: &UserModel) -> Request<Body> {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/submissions/{}/run",
+            data.module.id, data.assignment.id, data.sub1.id
+        );
+        Request::builder()
+            .method("DELETE")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cancel_run_without_active_run_not_found() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_data(app_state.db()).await;
+
+        let resp = app
+            .clone()
+            .oneshot(cancel_run_request(&data, &data.lecturer))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cancel_run_flags_active_job() {
+        use code_runner::jobs::{start_job, submission_job_key};
+
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_data(app_state.db()).await;
+
+        // Stands in for a marking run that is still in progress.
+        let job = start_job(&submission_job_key(data.sub1.id));
+
+        let resp = app
+            .clone()
+            .oneshot(cancel_run_request(&data, &data.assistant))
+            .await
+            .unwrap();
+        // 502 when no code manager is reachable; the run is flagged either way.
+        assert!(
+            resp.status() == StatusCode::OK || resp.status() == StatusCode::BAD_GATEWAY,
+            "unexpected status {}",
+            resp.status()
+        );
+        assert!(job.is_cancelled());
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn tutor_cannot_cancel_run_forbidden() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_data(app_state.db()).await;
+
+        let resp = app
+            .clone()
+            .oneshot(cancel_run_request(&data, &data.tutor))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -9,7 +9,7 @@ mod tests {
     use chrono::{Duration, Utc};
     use db::models::{
         assignment::Model as AssignmentModel,
-        assignment_submission::Model as AssignmentSubmissionModel,
+        assignment_submission::{ClientMetadata, Model as AssignmentSubmissionModel},
         module::Model as ModuleModel,
         plagiarism_case::{self, Entity as PlagiarismCaseEntity, Status},
         user::Model as UserModel,
@@ -20,6 +20,7 @@ mod tests {
     use serial_test::serial;
     use std::fs;
     use tower::ServiceExt;
+    use util::execution_config::ExecutionConfig;
     use util::paths::submission_report_path;
 
     struct TestData {
@@ -980,4 +981,322 @@ mod tests {
             "Plagiarism case 2 (higher similarity)"
         );
     }
+
+    // --- GET /api/modules/{module_id}/assignments/{assignment_id}/submissions/export ---
+
+    fn export_request(data: &TestData, user: &UserModel, query: &str) -> Request<Body> {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        Request::builder()
+            .uri(format!(
+                "/api/modules/{}/assignments/{}/submissions/export{}",
+                data.module.id, data.assignment.id, query
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn zip_entries(bytes: &[u8]) -> Vec<(String, String)> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut contents = String::new();
+            std::io::Read::read_to_string(&mut file, &mut contents).unwrap();
+            entries.push((file.name().to_string(), contents));
+        }
+        entries
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_export_zips_each_students_latest_counted_submission() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let response = app
+            .clone()
+            .oneshot(export_request(&data, &data.lecturer_user, ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-type"], "application/zip");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries = zip_entries(&body);
+        let names: Vec<&str> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        // student1's ignored attempt 2 is passed over for attempt 3
+        assert_eq!(
+            names,
+            [
+                "forbidden/forbidden.txt",
+                "forbidden/submission_report.json",
+                "student1/practice.txt",
+                "student1/submission_report.json",
+            ]
+        );
+        assert_eq!(entries[2].1, "practice");
+        let report: Value = serde_json::from_str(&entries[3].1).unwrap();
+        assert_eq!(report["id"], data.submissions[2].id);
+
+        let response = app
+            .clone()
+            .oneshot(export_request(
+                &data,
+                &data.lecturer_user,
+                "?username=Student1",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(zip_entries(&body).len(), 2);
+
+        let response = app
+            .clone()
+            .oneshot(export_request(
+                &data,
+                &data.lecturer_user,
+                "?username=nobody",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_export_forbidden_for_student() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let response = app
+            .oneshot(export_request(&data, &data.student_user, ""))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_shared_ip_submissions_are_flagged_for_staff_only() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+
+        // sub1 and sub4 were made by different users at the same time; sub3 is the student's
+        // own earlier attempt, which is never a match
+        let lab = ClientMetadata {
+            ip: Some("10.0.0.7".into()),
+            user_agent: Some("Mozilla/5.0".into()),
+            upload_duration_ms: Some(1200),
+        };
+        for i in [0, 2, 3] {
+            AssignmentSubmissionModel::set_client_metadata(db, data.submissions[i].id, &lab)
+                .await
+                .unwrap();
+        }
+
+        let list = |user: &UserModel, query: &str| {
+            let (token, _) = generate_jwt(user.id, user.admin);
+            Request::builder()
+                .uri(format!(
+                    "/api/modules/{}/assignments/{}/submissions{query}",
+                    data.module.id, data.assignment.id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(list(&data.lecturer_user, "?shared_ip=true"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let subs = json["data"]["submissions"].as_array().unwrap();
+        assert_eq!(subs.len(), 2);
+        let sub1 = subs
+            .iter()
+            .find(|s| s["id"] == data.submissions[0].id)
+            .unwrap();
+        assert_eq!(sub1["client"]["ip"], "10.0.0.7");
+        assert_eq!(sub1["client"]["upload_duration_ms"], 1200);
+        assert_eq!(
+            sub1["shared_ip"][0]["submission_id"],
+            data.submissions[3].id
+        );
+        assert_eq!(sub1["shared_ip"][0]["username"], "forbidden");
+        assert_eq!(sub1["shared_ip"][0]["seconds_apart"], 0);
+
+        let response = app
+            .clone()
+            .oneshot(list(&data.lecturer_user, "?shared_ip=false"))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["total"], 2);
+
+        let response = app.oneshot(list(&data.student_user, "")).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let subs = json["data"]["submissions"].as_array().unwrap();
+        assert!(!subs.is_empty());
+        assert!(
+            subs.iter()
+                .all(|s| s.get("client").is_none() && s.get("shared_ip").is_none())
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_exam_feedback_is_hidden_from_students_until_due() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.exam.enabled = true;
+        cfg.save(data.module.id, data.assignment.id).unwrap();
+
+        let sub = &data.submissions[0];
+        let path = submission_report_path(
+            data.module.id,
+            data.assignment.id,
+            data.student_user.id,
+            sub.attempt,
+        );
+        let mut report: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        report["tasks"] = json!([{
+            "task_number": 1,
+            "name": "Task 1",
+            "score": { "earned": 5, "total": 10 },
+            "subsections": [
+                { "label": "Output", "earned": 5, "total": 10, "feedback": "Missing lines: 42" }
+            ]
+        }]);
+        fs::write(&path, report.to_string()).unwrap();
+
+        let detail = |user: &UserModel| {
+            let (token, _) = generate_jwt(user.id, user.admin);
+            Request::builder()
+                .uri(format!(
+                    "/api/modules/{}/assignments/{}/submissions/{}",
+                    data.module.id, data.assignment.id, sub.id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let subsection = |json: &Value| json["data"]["tasks"][0]["subsections"][0].clone();
+
+        let response = app
+            .clone()
+            .oneshot(detail(&data.student_user))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(subsection(&json)["feedback"], "");
+        assert_eq!(subsection(&json)["earned"], 5);
+
+        let response = app.oneshot(detail(&data.lecturer_user)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(subsection(&json)["feedback"], "Missing lines: 42");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_practice_submissions_are_labelled_filtered_and_get_reduced_feedback() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.marking.allow_practice_submissions = true;
+        cfg.marking.reduced_practice_feedback = true;
+        cfg.save(data.module.id, data.assignment.id).unwrap();
+
+        // the label comes from the submission itself, not its report
+        let practice = &data.submissions[2];
+        let mut active = practice.clone().into_active_model();
+        active.is_practice = Set(true);
+        active.update(db).await.unwrap();
+        let path = submission_report_path(
+            data.module.id,
+            data.assignment.id,
+            data.student_user.id,
+            practice.attempt,
+        );
+        let mut report: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        report["is_practice"] = json!(false);
+        report["tasks"] = json!([{
+            "task_number": 1,
+            "name": "Task 1",
+            "score": { "earned": 5, "total": 10 },
+            "subsections": [
+                { "label": "Output", "earned": 5, "total": 10, "feedback": "Missing lines: 42" }
+            ]
+        }]);
+        fs::write(&path, report.to_string()).unwrap();
+
+        let send = |user: &UserModel, uri: String| {
+            let app = app.clone();
+            let (token, _) = generate_jwt(user.id, user.admin);
+            async move {
+                let req = Request::builder()
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.oneshot(req).await.unwrap();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&body).unwrap()
+            }
+        };
+        let base = format!(
+            "/api/modules/{}/assignments/{}/submissions",
+            data.module.id, data.assignment.id
+        );
+
+        let json = send(&data.student_user, format!("{base}?practice=true")).await;
+        let items = json["data"]["submissions"].as_array().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], practice.id);
+        assert_eq!(items[0]["is_practice"], true);
+
+        let json = send(&data.lecturer_user, format!("{base}?practice=false")).await;
+        let items = json["data"]["submissions"].as_array().unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|s| s["is_practice"] == false));
+
+        let subsection = |json: &Value| json["data"]["tasks"][0]["subsections"][0].clone();
+        let detail = format!("{base}/{}", practice.id);
+        let json = send(&data.student_user, detail.clone()).await;
+        assert_eq!(json["data"]["is_practice"], true);
+        assert_eq!(subsection(&json)["feedback"], "");
+        assert_eq!(subsection(&json)["earned"], 5);
+
+        let json = send(&data.lecturer_user, detail).await;
+        assert_eq!(subsection(&json)["feedback"], "Missing lines: 42");
+    }
 }
@@ -18,6 +18,7 @@ mod tests {
         assignment_submission::{self, Model as AssignmentSubmissionModel},
         assignment_submission_output, assignment_task,
         assignment_task::Model as AssignmentTaskModel,
+        exam_session::{self, Model as ExamSessionModel},
         module::Model as ModuleModel,
         user::Model as UserModel,
         user_module_role::{Model as UserModuleRoleModel, Role},
@@ -169,7 +170,7 @@ mod tests {
         // Only override what the tests require:
         cfg.project.language = Language::Java; // because we ship .java files
         cfg.project.submission_mode = SubmissionMode::Manual;
-        cfg.marking.deliminator = "###".to_string(); // your parser expects this exact token
+        cfg.marking.delimiter = "###".to_string(); // your parser expects this exact token
         // (Other defaults: pass_mark=50, grading_policy=Last, etc.)
 
         cfg.save(module_id, assignment_id)
@@ -404,7 +405,7 @@ mod tests {
         cfg.marking.allow_practice_submissions = allow_practice;
         cfg.marking.pass_mark = 50;
         cfg.marking.grading_policy = GradingPolicy::Last; // same as your previous JSON
-        cfg.marking.deliminator = "###".to_string();
+        cfg.marking.delimiter = "###".to_string();
 
         cfg.save(module_id, assignment_id)
             .expect("write config.json");
@@ -504,7 +505,8 @@ mod tests {
             .unwrap();
         let json: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(json["success"], true);
-        assert_eq!(json["data"]["filename"], "solution.tar");
+        // Archives are stored as a normalized zip
+        assert_eq!(json["data"]["filename"], "solution.zip");
     }
 
     #[tokio::test]
@@ -539,7 +541,8 @@ mod tests {
             .unwrap();
         let json: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(json["success"], true);
-        assert_eq!(json["data"]["filename"], "solution.tgz");
+        // Archives are stored as a normalized zip
+        assert_eq!(json["data"]["filename"], "solution.zip");
     }
 
     #[tokio::test]
@@ -574,7 +577,42 @@ mod tests {
             .unwrap();
         let json: Value = serde_json::from_slice(&body).unwrap();
         assert_eq!(json["success"], true);
-        assert_eq!(json["data"]["filename"], "solution.gz");
+        // Archives are stored as a normalized zip
+        assert_eq!(json["data"]["filename"], "solution.zip");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_valid_submission_plain_source_file() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (boundary, body) = multipart_body("main.py", b"print('Hello')\n", None, Some("true"));
+        let (token, _) = generate_jwt(data.student_user.id, data.student_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/submissions",
+            data.module.id, data.assignment.id
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", boundary),
+            )
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], true);
+        assert_eq!(json["data"]["filename"], "main.py");
     }
 
     #[tokio::test]
@@ -1131,10 +1169,11 @@ mod tests {
             .await
             .unwrap_or_default();
 
+        // Rejected on upload: it expands past execution.max_uncompressed_size
         assert_eq!(
             status,
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "expected 500, got {}",
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "expected 422, got {}",
             status
         );
 
@@ -1150,9 +1189,7 @@ mod tests {
             .and_then(|v| v.as_str())
             .unwrap_or_default();
         assert!(
-            msg.eq_ignore_ascii_case("Failed to run code for submission")
-                || msg.to_ascii_lowercase().contains("failed")
-                || msg.to_ascii_lowercase().contains("code"),
+            msg.starts_with("Invalid archive: Archive expands to more than"),
             "unexpected error message: {msg}"
         );
 
@@ -1272,7 +1309,7 @@ mod tests {
         assert_eq!(json["success"], false);
         assert_eq!(
             json["message"],
-            "Only .tgz, .gz, .tar, and .zip files are allowed"
+            "Only .tgz, .gz, .tar, .zip, .7z, .rar or plain source files are allowed"
         );
     }
 
@@ -1301,7 +1338,7 @@ mod tests {
             .unwrap();
 
         let response = app.oneshot(req).await.unwrap();
-        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
             .await
@@ -2069,6 +2106,132 @@ mod tests {
         assert_eq!(json["message"], "Module 9999 not found.");
     }
 
+    async fn send_dry_run_request(
+        app: &BoxCloneService<Request<Body>, Response, Infallible>,
+        token: &str,
+        module_id: i64,
+        assignment_id: i64,
+        submission_id: i64,
+    ) -> (StatusCode, Value) {
+        let req = Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/modules/{}/assignments/{}/submissions/{}/dry_run",
+                module_id, assignment_id, submission_id
+            ))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        (status, response_body_to_json(response).await)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_dry_run_student_forbidden() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(app_state.db()).await;
+
+        let submission = create_remarkable_submission(
+            db,
+            data.assignment.module_id,
+            data.assignment.id,
+            data.student_user.id,
+            1,
+        )
+        .await;
+
+        let (token, _) = generate_jwt(data.student_user.id, data.student_user.admin);
+        let (status, json) = send_dry_run_request(
+            &app,
+            &token,
+            data.module.id,
+            data.assignment.id,
+            submission.id,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(json["success"], false);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_dry_run_submission_not_found() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(app_state.db()).await;
+
+        let lecturer = UserModel::create(db, "lecturer1", "lecturer@test.com", "password", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, data.module.id, Role::Lecturer)
+            .await
+            .unwrap();
+
+        let (token, _) = generate_jwt(lecturer.id, lecturer.admin);
+        let (status, json) =
+            send_dry_run_request(&app, &token, data.module.id, data.assignment.id, 9999).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(json["success"], false);
+        assert_eq!(
+            json["message"],
+            format!(
+                "Submission 9999 in Assignment {} not found.",
+                data.assignment.id
+            )
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_dry_run_keeps_existing_outputs() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(app_state.db()).await;
+
+        let lecturer = UserModel::create(db, "lecturer1", "lecturer@test.com", "password", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, data.module.id, Role::Lecturer)
+            .await
+            .unwrap();
+
+        let submission = create_remarkable_submission(
+            db,
+            data.assignment.module_id,
+            data.assignment.id,
+            data.student_user.id,
+            1,
+        )
+        .await;
+
+        // No code manager runs in tests, so the run itself fails; the stored outputs must survive.
+        let (token, _) = generate_jwt(lecturer.id, lecturer.admin);
+        let (status, json) = send_dry_run_request(
+            &app,
+            &token,
+            data.module.id,
+            data.assignment.id,
+            submission.id,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(json["success"], false);
+
+        let outputs = assignment_submission_output::Entity::find()
+            .filter(assignment_submission_output::Column::SubmissionId.eq(submission.id))
+            .all(db)
+            .await
+            .unwrap();
+        assert_eq!(outputs.len(), 1);
+    }
+
     async fn send_resubmit_request(
         app: &BoxCloneService<Request<Body>, Response, Infallible>,
         token: &str,
@@ -2670,9 +2833,11 @@ mod tests {
         let db = app_state.db();
         let data = setup_test_data(db).await;
 
-        // Use corrupted zip file to trigger execution failure
-        let corrupted_file = create_corrupted_zip();
-        let (boundary, body) = multipart_body("corrupted.zip", &corrupted_file, None, Some("true"));
+        // Remove the makefile archive to trigger execution failure (a corrupted upload is now
+        // rejected before a submission is created)
+        fs::remove_dir_all(makefile_dir(data.module.id, data.assignment.id)).ok();
+        let file = create_submission_zip();
+        let (boundary, body) = multipart_body("solution.zip", &file, None, Some("true"));
         let (token, _) = generate_jwt(data.student_user.id, data.student_user.admin);
         let uri = format!(
             "/api/modules/{}/assignments/{}/submissions",
@@ -2690,7 +2855,7 @@ mod tests {
             .unwrap();
 
         let response = app.oneshot(req).await.unwrap();
-        // Corrupted file should cause internal server error during processing
+        // Missing makefile should cause internal server error during processing
         assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
 
         let body = axum::body::to_bytes(response.into_body(), usize::MAX)
@@ -2983,7 +3148,7 @@ mod tests {
 
         // Make sure your runner path is Manual and your delimiter matches memo files
         cfg.project.submission_mode = SubmissionMode::Manual;
-        cfg.marking.deliminator = "###".to_string();
+        cfg.marking.delimiter = "###".to_string();
 
         // Allow late submissions and make the window permissive for the tests
         cfg.marking.late.allow_late_submissions = true;
@@ -3509,4 +3674,69 @@ mod tests {
             "You must confirm the ownership attestation before submitting"
         );
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_exam_submission_requires_a_running_session() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+
+        let mut cfg = ExecutionConfig::get_execution_config(data.module.id, data.assignment.id)
+            .unwrap();
+        cfg.exam.enabled = true;
+        cfg.save(data.module.id, data.assignment.id).unwrap();
+
+        let (token, _) = generate_jwt(data.student_user.id, data.student_user.admin);
+        let base = format!(
+            "/api/modules/{}/assignments/{}",
+            data.module.id, data.assignment.id
+        );
+        let submit = || {
+            let (boundary, body) =
+                multipart_body("solution.zip", &create_submission_zip(), None, Some("true"));
+            Request::builder()
+                .method("POST")
+                .uri(format!("{base}/submissions"))
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header(
+                    CONTENT_TYPE,
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let message = |resp: Response| async move {
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<Value>(&bytes).unwrap()["message"].clone()
+        };
+
+        let resp = app.clone().oneshot(submit()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(message(resp).await, "Start the exam before submitting");
+
+        let start = Request::builder()
+            .method("POST")
+            .uri(format!("{base}/exam/start"))
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(start).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // the clock runs out
+        let session = ExamSessionModel::for_user(db, data.assignment.id, data.student_user.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut session: exam_session::ActiveModel = session.into();
+        session.ends_at = Set(Utc::now() - Duration::minutes(1));
+        session.update(db).await.unwrap();
+
+        let resp = app.clone().oneshot(submit()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(message(resp).await, "Your exam time is up");
+    }
 }
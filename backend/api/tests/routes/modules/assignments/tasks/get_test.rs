@@ -112,12 +112,20 @@ mod tests {
                             value: 10.0,
                             regex: None,
                             feedback: None,
+                            memory_thresholds: None,
+                            comparator: None,
+                            weight: None,
+                            subsections: None,
                         },
                         Subsection {
                             name: "Subsection B".to_string(),
                             value: 15.0,
                             regex: None,
                             feedback: None,
+                            memory_thresholds: None,
+                            comparator: None,
+                            weight: None,
+                            subsections: None,
                         },
                     ],
                 },
@@ -133,12 +141,20 @@ mod tests {
                             value: 20.0,
                             regex: None,
                             feedback: None,
+                            memory_thresholds: None,
+                            comparator: None,
+                            weight: None,
+                            subsections: None,
                         },
                         Subsection {
                             name: "Part 2".to_string(),
                             value: 10.0,
                             regex: None,
                             feedback: None,
+                            memory_thresholds: None,
+                            comparator: None,
+                            weight: None,
+                            subsections: None,
                         },
                     ],
                 },
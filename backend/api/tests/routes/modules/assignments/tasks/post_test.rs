@@ -467,4 +467,80 @@ mod tests {
         assert_eq!(json["success"], false);
         assert_eq!(json["message"], "task_number must be unique");
     }
+
+    /// Test Case: Artifact patterns are stored and returned
+    #[tokio::test]
+    #[serial]
+    async fn test_create_task_with_artifact_patterns() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let payload = json!({
+            "task_number": 1,
+            "name": "Plot",
+            "command": "python plot.py",
+            "artifact_patterns": ["*.png", "results/*.csv"]
+        });
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/tasks",
+            data.module.id, data.assignment.id
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header(CONTENT_TYPE, "application/json")
+            .body(AxumBody::from(serde_json::to_vec(&payload).unwrap()))
+            .unwrap();
+
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["data"]["artifact_patterns"],
+            json!(["*.png", "results/*.csv"])
+        );
+    }
+
+    /// Test Case: Artifact patterns that are not valid relative globs are rejected
+    #[tokio::test]
+    #[serial]
+    async fn test_create_task_invalid_artifact_pattern() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/tasks",
+            data.module.id, data.assignment.id
+        );
+        for pattern in ["[oops", "/etc/passwd", "../secret"] {
+            let payload = json!({
+                "task_number": 1,
+                "name": "Plot",
+                "command": "python plot.py",
+                "artifact_patterns": [pattern]
+            });
+            let req = Request::builder()
+                .method("POST")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header(CONTENT_TYPE, "application/json")
+                .body(AxumBody::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap();
+
+            let response = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "pattern {:?} was accepted",
+                pattern
+            );
+        }
+    }
 }
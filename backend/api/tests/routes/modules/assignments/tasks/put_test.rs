@@ -439,4 +439,52 @@ mod tests {
             "'name' and 'command' must be non-empty strings"
         );
     }
+
+    /// Test Case: Setting and then clearing artifact patterns
+    #[tokio::test]
+    #[serial]
+    async fn test_edit_task_artifact_patterns() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/tasks/{}",
+            data.module.id, data.assignment.id, data.task1.id
+        );
+        for (patterns, expected) in [
+            (json!(["out/*.png"]), json!(["out/*.png"])),
+            (json!([]), json!([])),
+        ] {
+            let payload = json!({ "artifact_patterns": patterns });
+            let req = Request::builder()
+                .method("PUT")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header(CONTENT_TYPE, "application/json")
+                .body(AxumBody::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap();
+
+            let response = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json["data"]["artifact_patterns"], expected);
+            assert_eq!(json["data"]["name"], data.task1.name);
+        }
+
+        let payload = json!({ "artifact_patterns": ["../up"] });
+        let req = Request::builder()
+            .method("PUT")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header(CONTENT_TYPE, "application/json")
+            .body(AxumBody::from(serde_json::to_vec(&payload).unwrap()))
+            .unwrap();
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
 }
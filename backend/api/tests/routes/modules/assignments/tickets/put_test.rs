@@ -15,7 +15,11 @@ mod tests {
         body::Body,
         http::{Request, StatusCode},
     };
-    use tower::ServiceExt;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    type App = BoxCloneService<Request<Body>, axum::response::Response, Infallible>;
+
     struct TestData {
         user: UserModel,
         invalid_user: UserModel,
@@ -142,4 +146,174 @@ mod tests {
         let response = app.oneshot(req).await.unwrap();
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
+
+    async fn put_json(
+        app: App,
+        user: &UserModel,
+        uri: String,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method("PUT")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn assign_ticket_respects_staff_roles() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+        let lecturer = UserModel::create(db, "lect", "lect@example.com", "pass", false)
+            .await
+            .unwrap();
+        let tutor = UserModel::create(db, "tut", "tut@example.com", "pass", false)
+            .await
+            .unwrap();
+        let other_tutor = UserModel::create(db, "tut2", "tut2@example.com", "pass", false)
+            .await
+            .unwrap();
+        UserModuleRole::assign_user_to_module(db, lecturer.id, data.module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        for t in [&tutor, &other_tutor] {
+            UserModuleRole::assign_user_to_module(db, t.id, data.module.id, Role::Tutor)
+                .await
+                .unwrap();
+        }
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/tickets/{}/assign",
+            data.module.id, data.assignment.id, data.ticket.id
+        );
+
+        // Students cannot assign, not even to staff
+        let (status, _) = put_json(
+            app.clone(),
+            &data.user,
+            uri.clone(),
+            serde_json::json!({ "assignee_id": tutor.id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Tutors may take a ticket themselves but not hand it to someone else
+        let (status, _) = put_json(
+            app.clone(),
+            &tutor,
+            uri.clone(),
+            serde_json::json!({ "assignee_id": other_tutor.id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, json) = put_json(
+            app.clone(),
+            &tutor,
+            uri.clone(),
+            serde_json::json!({ "assignee_id": tutor.id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["assignee_id"], tutor.id);
+
+        // Only staff on the module can be assignees
+        let (status, _) = put_json(
+            app.clone(),
+            &lecturer,
+            uri.clone(),
+            serde_json::json!({ "assignee_id": data.user.id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = put_json(
+            app.clone(),
+            &lecturer,
+            uri.clone(),
+            serde_json::json!({ "assignee_id": other_tutor.id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["assignee_id"], other_tutor.id);
+
+        // The first tutor no longer holds it, so cannot unassign it
+        let (status, _) = put_json(
+            app.clone(),
+            &tutor,
+            uri.clone(),
+            serde_json::json!({ "assignee_id": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, json) = put_json(
+            app,
+            &other_tutor,
+            uri,
+            serde_json::json!({ "assignee_id": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["assignee_id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn set_priority_moves_sla_deadline() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let data = setup_test_data(db).await;
+        let tutor = UserModel::create(db, "tut", "tut@example.com", "pass", false)
+            .await
+            .unwrap();
+        UserModuleRole::assign_user_to_module(db, tutor.id, data.module.id, Role::Tutor)
+            .await
+            .unwrap();
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/tickets/{}/priority",
+            data.module.id, data.assignment.id, data.ticket.id
+        );
+
+        let (status, _) = put_json(
+            app.clone(),
+            &data.user,
+            uri.clone(),
+            serde_json::json!({ "priority": "urgent" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, json) = put_json(
+            app.clone(),
+            &tutor,
+            uri.clone(),
+            serde_json::json!({ "priority": "urgent" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["priority"], "urgent");
+        let due =
+            chrono::DateTime::parse_from_rfc3339(json["data"]["sla_due_at"].as_str().unwrap())
+                .unwrap();
+        assert_eq!(due, data.ticket.created_at + chrono::Duration::hours(8));
+
+        let (status, _) = put_json(
+            app,
+            &tutor,
+            uri,
+            serde_json::json!({ "priority": "critical" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
 }
@@ -491,6 +491,54 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_session_projector_as_lecturer_ok() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let ctx = setup(app_state.db()).await;
+
+        let (token, _) = generate_jwt(ctx.lecturer.id, ctx.lecturer.admin);
+        let uri = format!(
+            "/api/modules/{}/attendance/sessions/{}/projector",
+            ctx.module.id, ctx.sess_active.id
+        );
+
+        let req = Request::builder()
+            .method("GET")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let data = &json["data"];
+        let code = data["code"].as_str().unwrap();
+        assert_eq!(code.len(), 6);
+        assert_eq!(data["rotation_seconds"], ctx.sess_active.rotation_seconds);
+        let remaining = data["seconds_remaining"].as_i64().unwrap();
+        assert!((0..=i64::from(ctx.sess_active.rotation_seconds)).contains(&remaining));
+        assert!(data["mark_url"].as_str().unwrap().ends_with(&format!(
+            "/attendance/mark?m={}&s={}&c={}",
+            ctx.module.id, ctx.sess_active.id, code
+        )));
+
+        // Students can't read the code off the projector endpoint either
+        let (token, _) = generate_jwt(ctx.student1.id, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
     // ---------------------------
     // list_session_records (lecturer/assistant only; must also be assigned)
     // ---------------------------
@@ -633,4 +681,131 @@ mod tests {
             assert_eq!(resp.status(), StatusCode::FORBIDDEN);
         }
     }
+
+    // ---------------------------
+    // attendance summaries & at-risk report
+    // ---------------------------
+
+    #[tokio::test]
+    async fn test_attendance_summaries_for_lecturer_and_self() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let ctx = setup(app_state.db()).await;
+
+        let (token, _) = generate_jwt(ctx.lecturer.id, ctx.lecturer.admin);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!(
+                "/api/modules/{}/attendance/summary?query=student1",
+                ctx.module.id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["total"], 1);
+        let s = &json["data"]["students"][0];
+        assert_eq!(s["username"], "att_student1");
+        // attended the first of the two sessions only
+        assert_eq!(s["sessions_total"], 2);
+        assert_eq!(s["sessions_attended"], 1);
+        assert_eq!(s["percentage"], 50.0);
+        assert_eq!(s["current_streak"], 0);
+        assert_eq!(s["longest_streak"], 1);
+
+        // students see their own summary but not the class list
+        let (token, _) = generate_jwt(ctx.student1.id, false);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!(
+                "/api/modules/{}/attendance/summary/me",
+                ctx.module.id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["sessions_attended"], 1);
+
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("/api/modules/{}/attendance/summary", ctx.module.id))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_export_at_risk_csv_flags_low_attendance() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let ctx = setup(app_state.db()).await;
+
+        let (token, _) = generate_jwt(ctx.lecturer.id, ctx.lecturer.admin);
+        let export = |query: &str| {
+            Request::builder()
+                .method("GET")
+                .uri(format!(
+                    "/api/modules/{}/attendance/at-risk/export{}",
+                    ctx.module.id, query
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(AxumBody::empty())
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(export("")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let cd = resp
+            .headers()
+            .get(axum::http::header::CONTENT_DISPOSITION)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(cd.contains(&format!("at_risk_module_{}.csv", ctx.module.id)));
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(body.to_vec()).unwrap();
+        let mut lines = csv.lines();
+        assert!(
+            lines
+                .next()
+                .unwrap()
+                .starts_with("username,sessions_attended")
+        );
+        let rows: Vec<&str> = lines.collect();
+        assert_eq!(
+            rows,
+            [
+                "att_student1,1,2,50.00,0,1,0,0,,true",
+                "att_student2,1,2,50.00,0,1,0,0,,true",
+            ]
+        );
+
+        let resp = app
+            .clone()
+            .oneshot(export("?min_attendance=50"))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(body.to_vec()).unwrap();
+        assert!(csv.lines().skip(1).all(|l| l.ends_with(",false")));
+
+        let resp = app.oneshot(export("?min_mark=120")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
 }
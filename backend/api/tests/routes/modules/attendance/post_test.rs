@@ -13,6 +13,7 @@ mod tests {
     use tower::ServiceExt;
 
     use db::models::{
+        attendance_record,
         attendance_session::{
             Column as SessionCol, Entity as SessionEntity, Model as SessionModel,
         },
@@ -397,6 +398,103 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_mark_attendance_geofenced_requires_nearby_location() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let ctx = setup(app_state.db()).await;
+
+        // Lecturer creates a session fenced to 100m around the venue
+        let (token, _) = generate_jwt(ctx.lecturer.id, ctx.lecturer.admin);
+        let uri = format!("/api/modules/{}/attendance/sessions", ctx.module.id);
+        let create = |body: Value| {
+            let req = Request::builder()
+                .method("POST")
+                .uri(&uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(body.to_string()))
+                .unwrap();
+            with_connect_info(req, [198, 51, 100, 9])
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(create(serde_json::json!({
+                "title": "Half a fence",
+                "geo_latitude": -25.7557,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = app
+            .clone()
+            .oneshot(create(serde_json::json!({
+                "title": "Fenced",
+                "active": true,
+                "geo_latitude": -25.7557,
+                "geo_longitude": 28.2333,
+                "geo_radius_m": 100,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["data"]["geo_radius_m"], 100);
+        let sess = SessionEntity::find()
+            .filter(SessionCol::Id.eq(json["data"]["id"].as_i64().unwrap()))
+            .one(app_state.db())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (token, _) = generate_jwt(ctx.student.id, ctx.student.admin);
+        let mark_uri = format!(
+            "/api/modules/{}/attendance/sessions/{}/mark",
+            ctx.module.id, sess.id
+        );
+        let mark = |body: Value| {
+            let req = Request::builder()
+                .method("POST")
+                .uri(&mark_uri)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(body.to_string()))
+                .unwrap();
+            with_connect_info(req, [198, 51, 100, 10])
+        };
+        let code = sess.current_code(Utc::now());
+
+        // No location, then a location 1km away (the code got shared)
+        for body in [
+            serde_json::json!({ "code": code }),
+            serde_json::json!({
+                "code": code,
+                "location": { "latitude": -25.7457, "longitude": 28.2333, "accuracy_m": 20.0 },
+            }),
+        ] {
+            let resp = app.clone().oneshot(mark(body)).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: Value = serde_json::from_slice(&bytes).unwrap();
+            assert!(json["message"].as_str().unwrap().contains("Location"));
+        }
+
+        let resp = app
+            .oneshot(mark(serde_json::json!({
+                "code": code,
+                "location": { "latitude": -25.7552, "longitude": 28.2333 },
+            })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_mark_attendance_duplicate_bad_request() {
         let (app, app_state, _tmp) = make_test_app_with_storage().await;
@@ -1403,4 +1501,78 @@ mod tests {
         assert_eq!(v["payload"]["method"], "admin_manual");
         assert_eq!(v["payload"]["count"], 1);
     }
+
+    // ---------------------------
+    // CSV import
+    // ---------------------------
+
+    #[tokio::test]
+    async fn test_import_attendance_csv_reports_bad_rows() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let ctx = setup(app_state.db()).await;
+
+        let (token, _) = generate_jwt(ctx.lecturer.id, ctx.lecturer.admin);
+        let uri = format!(
+            "/api/modules/{}/attendance/sessions/{}/records/import",
+            ctx.module.id, ctx.sess_active.id
+        );
+        let csv = format!(
+            "Username,taken_at,note\n\
+             {},2025-09-08T10:00:00Z,\"late, by 5 min\"\n\
+             \n\
+             {},,tutor\n\
+             nobody,,\n\
+             {},yesterday,\n",
+            ctx.student.username, ctx.student.username, ctx.student.username
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "text/csv")
+            .body(AxumBody::from(csv))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["imported"], 1);
+        assert_eq!(json["data"]["skipped"], 1);
+        let errors = json["data"]["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0]["line"], 5);
+        assert_eq!(errors[0]["message"], "User not found");
+        assert_eq!(errors[1]["line"], 6);
+
+        let taken_at = attendance_record::Entity::find_by_id((ctx.sess_active.id, ctx.student.id))
+            .one(app_state.db())
+            .await
+            .unwrap()
+            .unwrap()
+            .taken_at;
+        assert_eq!(taken_at.to_rfc3339(), "2025-09-08T10:00:00+00:00");
+
+        // no username column
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::from("user,taken_at\nx,\n"))
+            .unwrap();
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // staff only
+        let (token, _) = generate_jwt(ctx.student.id, false);
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::from("username\nx\n"))
+            .unwrap();
+        let resp = app.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use chrono::{TimeZone, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use util::execution_config::ExecutionConfig;
+
+    #[tokio::test]
+    async fn test_delete_module_config_falls_back_to_defaults() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS101", 2024, Some("Test Module"), 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer1", "lecturer1@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+
+        ExecutionConfig::save_module_config(
+            module.id,
+            &json!({ "config_version": 2, "execution": { "timeout_secs": 42 } }),
+        )
+        .unwrap();
+
+        // Created while the module config exists: inherits everything.
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "Assignment 1",
+            None,
+            AssignmentType::Assignment,
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 31, 23, 59, 59).unwrap(),
+        )
+        .await
+        .unwrap();
+        let cfg = ExecutionConfig::get_execution_config(module.id, assignment.id).unwrap();
+        assert_eq!(cfg.execution.timeout_secs, 42);
+
+        let (token, _) = generate_jwt(lecturer.id, lecturer.admin);
+        let uri = format!("/api/modules/{}/config", module.id);
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], true);
+
+        let cfg = ExecutionConfig::get_execution_config(module.id, assignment.id).unwrap();
+        assert_eq!(
+            cfg.execution.timeout_secs,
+            ExecutionConfig::default_config().execution.timeout_secs
+        );
+
+        // Nothing left to delete.
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
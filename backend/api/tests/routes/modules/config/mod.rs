@@ -0,0 +1,2 @@
+pub mod delete_test;
+pub mod put_test;
@@ -0,0 +1,210 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        response::Response,
+    };
+    use chrono::{TimeZone, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    type App = BoxCloneService<Request<Body>, Response, Infallible>;
+
+    struct TestData {
+        lecturer_user: UserModel,
+        assistant_user: UserModel,
+        module: ModuleModel,
+        assignment: AssignmentModel,
+    }
+
+    async fn setup_test_data(db: &sea_orm::DatabaseConnection) -> TestData {
+        let module = ModuleModel::create(db, "COS101", 2024, Some("Test Module"), 16)
+            .await
+            .unwrap();
+        let lecturer_user =
+            UserModel::create(db, "lecturer1", "lecturer1@test.com", "password1", false)
+                .await
+                .unwrap();
+        let assistant_user =
+            UserModel::create(db, "assistant1", "assistant1@test.com", "password2", false)
+                .await
+                .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer_user.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(
+            db,
+            assistant_user.id,
+            module.id,
+            Role::AssistantLecturer,
+        )
+        .await
+        .unwrap();
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "Assignment 1",
+            Some("Desc 1"),
+            AssignmentType::Assignment,
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 31, 23, 59, 59).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        TestData {
+            lecturer_user,
+            assistant_user,
+            module,
+            assignment,
+        }
+    }
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        user: &UserModel,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_assignment_configs_inherit_module_config() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let module_uri = format!("/api/modules/{}/config", data.module.id);
+        let assignment_uri = format!(
+            "/api/modules/{}/assignments/{}/config",
+            data.module.id, data.assignment.id
+        );
+
+        let module_config = json!({
+            "execution": { "timeout_secs": 42, "max_processes": 64 },
+            "security": { "allowed_cidrs": ["10.0.0.0/8"] }
+        });
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &module_uri,
+            &data.lecturer_user,
+            Some(module_config),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["execution"]["timeout_secs"], 42);
+
+        let (status, json) = send(&app, "GET", &module_uri, &data.lecturer_user, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            json["data"]["security"]["allowed_cidrs"],
+            json!(["10.0.0.0/8"])
+        );
+
+        // The assignment was created with the full defaults; once it saves only its own change,
+        // the rest comes from the module.
+        let (status, _) = send(
+            &app,
+            "POST",
+            &assignment_uri,
+            &data.lecturer_user,
+            Some(json!({ "execution": { "timeout_secs": 90 } })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, json) = send(&app, "GET", &assignment_uri, &data.lecturer_user, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let cfg = &json["data"];
+        assert_eq!(cfg["execution"]["timeout_secs"], 90);
+        assert_eq!(cfg["execution"]["max_processes"], 64);
+        assert_eq!(cfg["security"]["allowed_cidrs"], json!(["10.0.0.0/8"]));
+
+        // Later module changes reach the fields the assignment does not override.
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &module_uri,
+            &data.lecturer_user,
+            Some(json!({ "execution": { "timeout_secs": 42, "max_processes": 32 } })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, json) = send(&app, "GET", &assignment_uri, &data.lecturer_user, None).await;
+        let cfg = &json["data"];
+        assert_eq!(cfg["execution"]["timeout_secs"], 90);
+        assert_eq!(cfg["execution"]["max_processes"], 32);
+        assert_eq!(cfg["security"]["allowed_cidrs"], json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_put_module_config_rejects_invalid_config() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let uri = format!("/api/modules/{}/config", data.module.id);
+
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &uri,
+            &data.lecturer_user,
+            Some(json!({ "marking": { "pass_mark": 150, "pass_mrak": 1 } })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let paths: Vec<&str> = json["data"]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["path"].as_str().unwrap())
+            .collect();
+        assert!(paths.contains(&"marking.pass_mrak"));
+        assert!(paths.contains(&"marking.pass_mark"));
+
+        let (status, json) = send(&app, "GET", &uri, &data.lecturer_user, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"], json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_put_module_config_forbidden_for_assistant_lecturer() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+        let uri = format!("/api/modules/{}/config", data.module.id);
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &uri,
+            &data.assistant_user,
+            Some(json!({ "execution": { "timeout_secs": 42 } })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
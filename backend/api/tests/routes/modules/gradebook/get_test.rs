@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode, header::CONTENT_TYPE},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::{self, Model as SubmissionModel},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use sea_orm::{ActiveModelTrait, Set};
+    use serde_json::Value;
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    struct Setup {
+        module_id: i64,
+        lecturer: UserModel,
+        tutor: UserModel,
+    }
+
+    /// Two assignments; `bob` submits twice to the first (the second late), `alice` never.
+    async fn setup(db: &sea_orm::DatabaseConnection) -> Setup {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut users = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor", Role::Tutor),
+            ("bob", Role::Student),
+            ("alice", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            users.push(u);
+        }
+        let due = Utc::now() - Duration::days(1);
+        let mut assignments = Vec::new();
+        for name in ["Practical 1", "Practical 2"] {
+            assignments.push(
+                AssignmentModel::create(
+                    db,
+                    module.id,
+                    name,
+                    None,
+                    AssignmentType::Practical,
+                    due - Duration::days(7),
+                    due,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        for (attempt, earned, at) in [
+            (1, 8.0, due - Duration::days(1)),
+            (2, 6.0, due + Duration::hours(2)),
+        ] {
+            let sub = SubmissionModel::save_file(
+                db,
+                assignments[0].id,
+                users[2].id,
+                attempt,
+                earned,
+                10.0,
+                false,
+                "s.zip",
+                &format!("hash{attempt}"),
+                b"zip",
+            )
+            .await
+            .unwrap();
+            let mut am: assignment_submission::ActiveModel = sub.into();
+            am.created_at = Set(at);
+            am.update(db).await.unwrap();
+        }
+        let mut users = users.into_iter();
+        Setup {
+            module_id: module.id,
+            lecturer: users.next().unwrap(),
+            tutor: users.next().unwrap(),
+        }
+    }
+
+    async fn get(app: &App, user: &UserModel, uri: &str) -> (StatusCode, String, Vec<u8>) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method("GET")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, content_type, body.to_vec())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn gradebook_lists_students_by_assignments() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+        let uri = format!("/api/modules/{}/gradebook", s.module_id);
+
+        let (status, _, _) = get(&app, &s.tutor, &uri).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _, body) = get(&app, &s.lecturer, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let data = &json["data"];
+        assert_eq!(data["total"], 2);
+        assert_eq!(data["assignments"].as_array().unwrap().len(), 2);
+        assert_eq!(data["assignments"][0]["grading_policy"], "last");
+
+        let students = data["students"].as_array().unwrap();
+        assert_eq!(students[0]["username"], "alice");
+        assert!(students[0]["grades"][0].is_null());
+        let bob = &students[1]["grades"];
+        assert_eq!(bob[0]["attempts"], 2);
+        assert_eq!(bob[0]["best"], 80.0);
+        assert_eq!(bob[0]["last"], 60.0);
+        assert_eq!(bob[0]["mark"], 60.0);
+        assert_eq!(bob[0]["late"], true);
+        assert!(bob[1].is_null());
+
+        let (_, _, body) = get(&app, &s.lecturer, &format!("{uri}?query=ali&per_page=1")).await;
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["total"], 1);
+        assert_eq!(json["data"]["students"][0]["username"], "alice");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn gradebook_exports_csv_and_xlsx() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+        let uri = format!("/api/modules/{}/gradebook/export", s.module_id);
+
+        let (status, content_type, body) = get(&app, &s.lecturer, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("text/csv"));
+        let csv = String::from_utf8(body).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("username,Practical 1,Practical 1 (best),"));
+        assert_eq!(lines[1], "alice,,,,,,,,,,");
+        assert_eq!(lines[2], "bob,60.00,80.00,60.00,2,true,,,,,");
+
+        let (status, content_type, body) =
+            get(&app, &s.lecturer, &format!("{uri}?format=xlsx")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.contains("spreadsheetml"));
+        assert_eq!(&body[..2], b"PK");
+
+        let (status, _, _) = get(&app, &s.lecturer, &format!("{uri}?format=pdf")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
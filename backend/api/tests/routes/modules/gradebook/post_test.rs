@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as SubmissionModel,
+        grade_export_template::{Definition, Model as TemplateModel},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn post(app: &App, token: &str, uri: &str, body: Value) -> (StatusCode, Vec<u8>) {
+        let req = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(AxumBody::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn records_export_follows_the_template() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let lecturer = UserModel::create(db, "lecturer", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let due = Utc::now() + Duration::days(1);
+        let mut assignments = Vec::new();
+        for name in ["P1", "P2"] {
+            assignments.push(
+                AssignmentModel::create(
+                    db,
+                    module.id,
+                    name,
+                    None,
+                    AssignmentType::Practical,
+                    due - Duration::days(7),
+                    due,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        // u11111111: 70 and 30; u22222222: 90 on P1 only
+        for (username, marks) in [
+            ("u11111111", vec![Some(7.0), Some(3.0)]),
+            ("u22222222", vec![Some(9.0), None]),
+        ] {
+            let u = UserModel::create(db, username, &format!("{username}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, Role::Student)
+                .await
+                .unwrap();
+            for (a, earned) in assignments.iter().zip(marks) {
+                if let Some(earned) = earned {
+                    SubmissionModel::save_file(
+                        db,
+                        a.id,
+                        u.id,
+                        1,
+                        earned,
+                        10.0,
+                        false,
+                        "s.zip",
+                        &format!("{username}{}", a.id),
+                        b"zip",
+                    )
+                    .await
+                    .unwrap();
+                }
+            }
+        }
+        let definition: Definition = serde_json::from_value(json!({
+            "columns": [
+                { "header": "STUDENT_NO", "source": "student_number" },
+                { "header": "MODULE", "source": "module_code" },
+                { "header": "MARK", "source": "final_mark" },
+                { "header": "RESULT", "source": "result_code" }
+            ],
+            "delimiter": ";"
+        }))
+        .unwrap();
+        let template = TemplateModel::create(db, "Faculty", None, &definition)
+            .await
+            .unwrap();
+
+        let (token, _) = generate_jwt(lecturer.id, false);
+        let uri = format!("/api/modules/{}/gradebook/records", module.id);
+
+        let (status, body) = post(&app, &token, &uri, json!({ "template_id": template.id })).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"][0]["username"], "u22222222");
+        assert_eq!(json["data"][0]["assignments"], json!(["P2"]));
+
+        let (status, body) = post(
+            &app,
+            &token,
+            &uri,
+            json!({ "template_id": template.id, "missing_as_zero": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "STUDENT_NO;MODULE;MARK;RESULT\r\n\
+             11111111;COS301;50;P\r\n\
+             22222222;COS301;45;F\r\n"
+        );
+
+        // P1 counts three times as much as P2
+        let weights = json!({ assignments[0].id.to_string(): 3, assignments[1].id.to_string(): 1 });
+        let (status, body) = post(
+            &app,
+            &token,
+            &uri,
+            json!({ "template_id": template.id, "weights": weights, "missing_as_zero": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            String::from_utf8(body)
+                .unwrap()
+                .contains("11111111;COS301;60;P")
+        );
+
+        let (status, _) = post(
+            &app,
+            &token,
+            &uri,
+            json!({ "template_id": template.id, "weights": { "999999": 1 } }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = post(&app, &token, &uri, json!({ "template_id": 999999 })).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
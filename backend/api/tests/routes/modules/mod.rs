@@ -1,8 +1,12 @@
 pub mod announcements;
 pub mod assignments;
 pub mod attendance;
+pub mod config;
 pub mod delete_test;
 pub mod get_test;
+pub mod gradebook;
 pub mod personnel;
 pub mod post_test;
 pub mod put_test;
+pub mod rollover_test;
+pub mod ticket_queue;
@@ -215,4 +215,98 @@ mod tests {
         let res = app.oneshot(req).await.unwrap();
         assert_eq!(res.status(), StatusCode::BAD_REQUEST);
     }
+
+    #[tokio::test]
+    async fn import_roster_enrolls_creates_and_updates_roles() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_data(app_state.db()).await;
+        let uri = format!("/api/modules/{}/personnel/import", data.module.id);
+        let csv = "username,email,role\n\
+                   stud1,,\n\
+                   outsider,,Assistant Lecturer\n\
+                   u20000001,u20000001@tuks.co.za,\n\
+                   u20000002,,\n\
+                   lect1,,dean\n";
+
+        // Lecturers of the module can't import rosters
+        let (token, _) = generate_jwt(data.lecturer.id, data.lecturer.admin);
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::from(csv))
+            .unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let (token, _) = generate_jwt(data.admin.id, data.admin.admin);
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "text/csv")
+            .body(Body::from(csv))
+            .unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), 1024 * 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let report = &json["data"];
+        assert_eq!(report["users_created"], 1);
+        assert_eq!(report["enrolled"], 2);
+        assert_eq!(report["role_changed"], 0);
+        assert_eq!(report["unchanged"], 1);
+        let errors = report["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0]["line"], 5);
+        assert_eq!(errors[0]["message"], "Email required to create a user");
+        assert_eq!(errors[1]["line"], 6);
+        assert_eq!(errors[1]["message"], "Unknown role");
+
+        let db = app_state.db();
+        let new_user = UserModel::get_by_username(db, "u20000001")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(
+            UserModel::is_in_role(db, new_user.id, data.module.id, "Student")
+                .await
+                .unwrap()
+        );
+        assert!(
+            UserModel::is_in_role(db, data.outsider.id, data.module.id, "AssistantLecturer")
+                .await
+                .unwrap()
+        );
+
+        // Re-importing with a new role moves the student
+        let req = Request::builder()
+            .method("POST")
+            .uri(&uri)
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::from("student_number,role\nstud1,tutor\n"))
+            .unwrap();
+        let res = app.clone().oneshot(req).await.unwrap();
+        let body = axum::body::to_bytes(res.into_body(), 1024 * 1024)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["data"]["role_changed"], 1);
+        assert!(
+            UserModel::is_in_role(db, data.student.id, data.module.id, "Tutor")
+                .await
+                .unwrap()
+        );
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/modules/99999/personnel/import")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::from(csv))
+            .unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
 }
@@ -0,0 +1,172 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use chrono::{DateTime, Datelike, Duration, Utc};
+    use db::models::{
+        assignment::{self, AssignmentType, Model as AssignmentModel, Status},
+        module::{self, Model as ModuleModel},
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    type App = BoxCloneService<Request<Body>, axum::response::Response, Infallible>;
+
+    async fn rollover(
+        app: &App,
+        user: &UserModel,
+        module_id: i64,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method("POST")
+            .uri(format!("/api/modules/{module_id}/rollover"))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn rollover_previews_then_creates_next_offering() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let year = Utc::now().year();
+        let source = ModuleModel::create(db, "COS301", year - 1, Some("SE"), 16)
+            .await
+            .unwrap();
+        let admin = UserModel::create(db, "admin", "admin@test.com", "pw", true)
+            .await
+            .unwrap();
+        let mut people = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor", Role::Tutor),
+            ("student", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, source.id, role)
+                .await
+                .unwrap();
+            people.push(u);
+        }
+        let due = Utc::now() - Duration::days(30);
+        let mut assignments = Vec::new();
+        for name in ["Practical 1", "Practical 2"] {
+            assignments.push(
+                AssignmentModel::create(
+                    db,
+                    source.id,
+                    name,
+                    None,
+                    AssignmentType::Practical,
+                    due - Duration::days(7),
+                    due,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        let body = json!({ "year": year, "assignment_ids": [assignments[0].id] });
+
+        // Admin only
+        let (status, _) = rollover(&app, &people[0], source.id, body.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = rollover(
+            &app,
+            &admin,
+            source.id,
+            json!({ "year": year, "assignment_ids": [999999] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let mut preview = body.clone();
+        preview["dry_run"] = json!(true);
+        let (status, json) = rollover(&app, &admin, source.id, preview).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["module"]["id"], Value::Null);
+        assert_eq!(json["data"]["assignments"].as_array().unwrap().len(), 1);
+        assert_eq!(json["data"]["personnel"].as_array().unwrap().len(), 2);
+        assert_eq!(json["data"]["archived_assignments"], 2);
+        assert!(
+            module::Entity::find()
+                .filter(module::Column::Year.eq(year))
+                .one(db)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let (status, json) = rollover(&app, &admin, source.id, body.clone()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let new_id = json["data"]["module"]["id"].as_i64().unwrap();
+        assert_eq!(json["data"]["module"]["description"], "SE");
+        assert_eq!(json["data"]["archived_assignments"], 2);
+
+        // Staff carried over, students not
+        assert!(
+            UserModuleRoleModel::get_users_by_module_role(db, new_id, Role::Lecturer)
+                .await
+                .unwrap()
+                .iter()
+                .any(|r| r.user_id == people[0].id)
+        );
+        assert!(
+            UserModuleRoleModel::get_users_by_module_role(db, new_id, Role::Student)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // One copy, a year later
+        let copies = assignment::Entity::find()
+            .filter(assignment::Column::ModuleId.eq(new_id))
+            .all(db)
+            .await
+            .unwrap();
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].name, "Practical 1");
+        assert_eq!(copies[0].due_date.year(), due.year() + 1);
+        assert_eq!(
+            json["data"]["assignments"][0]["due_date"]
+                .as_str()
+                .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                .map(|d| d.with_timezone(&Utc)),
+            Some(copies[0].due_date)
+        );
+
+        // The old offering is archived
+        for a in &assignments {
+            let a = assignment::Entity::find_by_id(a.id)
+                .one(db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(a.status, Status::Archived);
+        }
+
+        let (status, _) = rollover(&app, &admin, source.id, body).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
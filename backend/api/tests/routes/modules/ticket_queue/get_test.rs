@@ -0,0 +1,242 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use api::services::ticket_escalation::escalate_unanswered_tickets;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        module::Model as ModuleModel,
+        notification::{Model as NotificationModel, NotificationKind},
+        tickets::{ActiveModel as TicketActiveModel, Model as TicketModel, TicketPriority},
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use sea_orm::{ActiveModelTrait, Set};
+    use serde_json::Value;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    struct Setup {
+        module_id: i64,
+        lecturer: UserModel,
+        tutor: UserModel,
+        student: UserModel,
+        /// Practical 1: assigned to the tutor, urgent
+        urgent: TicketModel,
+        /// Practical 1: unassigned, answered by the tutor
+        answered: TicketModel,
+        /// Practical 2: unassigned, closed
+        closed: TicketModel,
+    }
+
+    async fn setup(db: &sea_orm::DatabaseConnection) -> Setup {
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let mut users = Vec::new();
+        for (name, role) in [
+            ("lecturer", Role::Lecturer),
+            ("tutor", Role::Tutor),
+            ("bob", Role::Student),
+        ] {
+            let u = UserModel::create(db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            UserModuleRoleModel::assign_user_to_module(db, u.id, module.id, role)
+                .await
+                .unwrap();
+            users.push(u);
+        }
+        let mut assignments = Vec::new();
+        for name in ["Practical 1", "Practical 2"] {
+            assignments.push(
+                AssignmentModel::create(
+                    db,
+                    module.id,
+                    name,
+                    None,
+                    AssignmentType::Practical,
+                    Utc::now(),
+                    Utc::now() + Duration::days(7),
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        let student = users.pop().unwrap();
+        let tutor = users.pop().unwrap();
+        let lecturer = users.pop().unwrap();
+
+        let urgent = TicketModel::create(db, assignments[0].id, student.id, "Urgent", "u")
+            .await
+            .unwrap();
+        TicketModel::assign(db, urgent.id, Some(tutor.id))
+            .await
+            .unwrap();
+        let urgent = TicketModel::set_priority(db, urgent.id, TicketPriority::Urgent)
+            .await
+            .unwrap();
+        let answered = TicketModel::create(db, assignments[0].id, student.id, "Answered", "a")
+            .await
+            .unwrap();
+        TicketModel::record_first_response(db, answered.id)
+            .await
+            .unwrap();
+        let closed = TicketModel::create(db, assignments[1].id, student.id, "Closed", "c")
+            .await
+            .unwrap();
+        TicketModel::set_closed(db, closed.id).await.unwrap();
+
+        Setup {
+            module_id: module.id,
+            lecturer,
+            tutor,
+            student,
+            urgent,
+            answered,
+            closed,
+        }
+    }
+
+    async fn queue(
+        app: &App,
+        user: &UserModel,
+        module_id: i64,
+        query: &str,
+    ) -> (StatusCode, Value) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let req = Request::builder()
+            .method("GET")
+            .uri(format!("/api/modules/{module_id}/tickets?{query}"))
+            .header("Authorization", format!("Bearer {token}"))
+            .body(AxumBody::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn ids(json: &Value) -> Vec<i64> {
+        json["data"]["tickets"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["ticket"]["id"].as_i64().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn queue_filters_by_status_and_assignee() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let s = setup(app_state.db()).await;
+
+        let (status, _) = queue(&app, &s.student, s.module_id, "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Soonest SLA deadline first: the urgent ticket, then the normal ones by age
+        let (status, json) = queue(&app, &s.tutor, s.module_id, "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["total"], 3);
+        assert_eq!(ids(&json), vec![s.urgent.id, s.answered.id, s.closed.id]);
+        assert_eq!(json["data"]["tickets"][0]["ticket"]["priority"], "urgent");
+        assert_eq!(
+            json["data"]["tickets"][2]["assignment"]["name"],
+            "Practical 2"
+        );
+
+        let (_, json) = queue(&app, &s.tutor, s.module_id, "assignee=me").await;
+        assert_eq!(ids(&json), vec![s.urgent.id]);
+        let (_, json) = queue(&app, &s.lecturer, s.module_id, "assignee=me").await;
+        assert!(ids(&json).is_empty());
+        let (_, json) = queue(
+            &app,
+            &s.lecturer,
+            s.module_id,
+            &format!("assignee={}", s.tutor.id),
+        )
+        .await;
+        assert_eq!(ids(&json), vec![s.urgent.id]);
+        let (_, json) = queue(
+            &app,
+            &s.lecturer,
+            s.module_id,
+            "assignee=unassigned&status=open",
+        )
+        .await;
+        assert_eq!(ids(&json), vec![s.answered.id]);
+        let (_, json) = queue(&app, &s.lecturer, s.module_id, "status=closed").await;
+        assert_eq!(ids(&json), vec![s.closed.id]);
+        let (_, json) = queue(&app, &s.lecturer, s.module_id, "priority=urgent").await;
+        assert_eq!(ids(&json), vec![s.urgent.id]);
+
+        // Push the urgent ticket's deadline into the past
+        let mut urgent: TicketActiveModel = s.urgent.clone().into();
+        urgent.sla_due_at = Set(Some(Utc::now() - Duration::hours(1)));
+        urgent.update(app_state.db()).await.unwrap();
+        let (_, json) = queue(&app, &s.lecturer, s.module_id, "overdue=true").await;
+        assert_eq!(ids(&json), vec![s.urgent.id]);
+        assert_eq!(json["data"]["tickets"][0]["ticket"]["overdue"], true);
+
+        let (status, _) = queue(&app, &s.lecturer, s.module_id, "assignee=someone").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = queue(&app, &s.lecturer, s.module_id, "status=pending").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn unanswered_tickets_escalate_to_lecturers() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let s = setup(db).await;
+
+        // Nothing has been waiting 48 hours yet
+        assert_eq!(
+            escalate_unanswered_tickets(&app_state, Utc::now())
+                .await
+                .unwrap(),
+            0
+        );
+
+        // Only the open ticket nobody has answered escalates, and only once
+        let later = Utc::now() + Duration::hours(49);
+        assert_eq!(
+            escalate_unanswered_tickets(&app_state, later)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            escalate_unanswered_tickets(&app_state, later)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let (items, _) = NotificationModel::list_for_user(db, s.lecturer.id, false, 1, 10)
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, NotificationKind::TicketEscalated);
+        let (items, _) = NotificationModel::list_for_user(db, s.tutor.id, false, 1, 10)
+            .await
+            .unwrap();
+        assert!(items.is_empty());
+
+        let (_, json) = queue(&app, &s.lecturer, s.module_id, "escalated=true").await;
+        assert_eq!(ids(&json), vec![s.urgent.id]);
+        assert!(json["data"]["tickets"][0]["ticket"]["escalated_at"].is_string());
+        let (_, json) = queue(&app, &s.lecturer, s.module_id, "escalated=false").await;
+        assert_eq!(ids(&json), vec![s.answered.id, s.closed.id]);
+    }
+}
@@ -0,0 +1,168 @@
+#[cfg(test)]
+mod tests {
+    use api::{
+        auth::{
+            generate_jwt,
+            guards::validate_known_ids,
+            middleware::{RateLimits, rate_limit, rate_limit_api_token_lookups},
+        },
+        routes::routes,
+    };
+    use axum::{
+        Router,
+        body::Body as AxumBody,
+        http::{Request, StatusCode, header},
+        middleware::from_fn_with_state,
+    };
+    use chrono::{Duration, Utc};
+    use db::models::{assignment::Model as AssignmentModel, module, user::Model as UserModel};
+    use std::{convert::Infallible, net::IpAddr, sync::Arc};
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::{state::AppState, test_helpers::setup_test_storage_root, ws::WebSocketManager};
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    /// The API with tight limits, leaving the shared test app unlimited.
+    async fn make_limited_app(limits: RateLimits) -> (App, AppState) {
+        let db = db::test_utils::setup_test_db().await;
+        let app_state = AppState::new(db, WebSocketManager::new());
+        let router = Router::new()
+            .nest(
+                "/api",
+                routes(app_state.clone())
+                    .layer(from_fn_with_state(Arc::new(limits), rate_limit))
+                    .layer(from_fn_with_state(app_state.clone(), validate_known_ids)),
+            )
+            .with_state(app_state.clone());
+        (router.into_service().boxed_clone(), app_state)
+    }
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+        ip: &str,
+    ) -> axum::response::Response {
+        let mut req = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            req = req.header("Authorization", format!("Bearer {token}"));
+        }
+        let mut req = req.body(AxumBody::from("{}")).unwrap();
+        req.extensions_mut().insert(ip.parse::<IpAddr>().unwrap());
+        app.clone().oneshot(req).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn logins_are_limited_per_ip() {
+        let (app, _) = make_limited_app(RateLimits::new(100, 2, 100)).await;
+
+        for _ in 0..2 {
+            let response = send(&app, "POST", "/api/auth/login", None, "10.0.0.1").await;
+            assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        }
+        let response = send(&app, "POST", "/api/auth/login/2fa", None, "10.0.0.1").await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((1..=30).contains(&retry_after));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["success"], false);
+
+        // Another IP, and the rest of the API, are unaffected
+        let response = send(&app, "POST", "/api/auth/login", None, "10.0.0.2").await;
+        assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let response = send(&app, "GET", "/api/health", None, "10.0.0.1").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn submissions_are_limited_per_user() {
+        let _tmp = setup_test_storage_root();
+        let (app, app_state) = make_limited_app(RateLimits::new(100, 100, 1)).await;
+        let db = app_state.db();
+        let alice = UserModel::create(db, "u80000001", "u8@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let bob = UserModel::create(db, "u80000002", "u9@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let cos301 = module::Model::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let assignment = AssignmentModel::create(
+            db,
+            cos301.id,
+            "Assignment 1",
+            None,
+            db::models::assignment::AssignmentType::Assignment,
+            Utc::now() - Duration::days(1),
+            Utc::now() + Duration::days(1),
+        )
+        .await
+        .unwrap();
+        let uri = format!(
+            "/api/modules/{}/assignments/{}/submissions",
+            cos301.id, assignment.id
+        );
+        let (alice_token, _) = generate_jwt(alice.id, false);
+        let (bob_token, _) = generate_jwt(bob.id, false);
+
+        // Alice and Bob share an IP (say, a lab), but not a bucket
+        let response = send(&app, "POST", &uri, Some(&alice_token), "10.0.0.1").await;
+        assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let response = send(&app, "POST", &uri, Some(&alice_token), "10.0.0.1").await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        let response = send(&app, "POST", &uri, Some(&bob_token), "10.0.0.1").await;
+        assert_ne!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Other requests come out of the general bucket
+        let response = send(&app, "GET", "/api/auth/me", Some(&alice_token), "10.0.0.1").await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn api_token_lookups_are_limited_per_ip() {
+        let db = db::test_utils::setup_test_db().await;
+        let app_state = AppState::new(db, WebSocketManager::new());
+        let limits = Arc::new(RateLimits::new(2, 100, 100));
+        let app: App = Router::new()
+            .nest(
+                "/api",
+                routes(app_state.clone())
+                    .layer(from_fn_with_state(limits, rate_limit_api_token_lookups)),
+            )
+            .with_state(app_state.clone())
+            .into_service()
+            .boxed_clone();
+        let user = UserModel::create(app_state.db(), "u80000003", "u10@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let (jwt, _) = generate_jwt(user.id, false);
+
+        // Made-up tokens are turned away before the database once the IP runs out
+        for _ in 0..2 {
+            let response = send(&app, "GET", "/api/auth/me", Some("ffk_bogus"), "10.0.0.3").await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+        let response = send(&app, "GET", "/api/auth/me", Some("ffk_bogus"), "10.0.0.3").await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+        // Login sessions from the same IP, and tokens from others, aren't counted
+        let response = send(&app, "GET", "/api/auth/me", Some(&jwt), "10.0.0.3").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = send(&app, "GET", "/api/auth/me", Some("ffk_bogus"), "10.0.0.4").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -195,6 +195,8 @@ mod tests {
             created_at: Set(base_ts),
             cpu_avg: Set(42.0_f32),
             mem_pct: Set(75.0_f32),
+            cm_running: Set(2),
+            cm_waiting: Set(6),
         };
         metric.insert(db).await.unwrap();
 
@@ -238,6 +240,108 @@ mod tests {
         assert!(csv_str.contains("2024-01-01T12:00:00+00:00,42,75"));
     }
 
+    #[serial]
+    #[tokio::test]
+    async fn metrics_downsample_by_interval_with_queue_series() {
+        ensure_jwt_env();
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+
+        let admin = UserModel::create(db, "interval_admin", "interval_admin@test.com", "pw", true)
+            .await
+            .unwrap();
+        let (token, _) = generate_jwt(admin.id, admin.admin);
+
+        // Three samples in 10:00-10:05 and one in 10:05-10:10.
+        for (minute, second, cpu, waiting) in [
+            (0, 10, 20.0_f32, 2),
+            (1, 10, 40.0, 6),
+            (4, 59, 60.0, 10),
+            (7, 0, 80.0, 1),
+        ] {
+            SystemMetricActiveModel {
+                id: NotSet,
+                created_at: Set(Utc
+                    .with_ymd_and_hms(2024, 1, 1, 10, minute, second)
+                    .unwrap()),
+                cpu_avg: Set(cpu),
+                mem_pct: Set(50.0),
+                cm_running: Set(4),
+                cm_waiting: Set(waiting),
+            }
+            .insert(db)
+            .await
+            .unwrap();
+        }
+
+        let range = "start=2024-01-01T10:00:00Z&end=2024-01-01T10:14:00Z";
+        let resp = app
+            .clone()
+            .oneshot(authed_get(
+                &format!("/api/system/metrics?{range}&interval=5m"),
+                &token,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        let points = json["points"].as_array().unwrap();
+        let ts: Vec<&str> = points.iter().map(|p| p["ts"].as_str().unwrap()).collect();
+        assert_eq!(
+            ts,
+            [
+                "2024-01-01T10:00:00+00:00",
+                "2024-01-01T10:05:00+00:00",
+                "2024-01-01T10:10:00+00:00",
+            ]
+        );
+        assert_eq!(points[0]["samples"], 3);
+        assert_eq!(points[0]["cpu_avg"].as_f64().unwrap(), 40.0);
+        assert_eq!(points[0]["cm_running"].as_f64().unwrap(), 4.0);
+        assert_eq!(points[0]["cm_waiting"].as_f64().unwrap(), 6.0);
+        assert_eq!(points[0]["cm_waiting_max"], 10);
+        assert_eq!(points[1]["samples"], 1);
+        assert_eq!(points[1]["cpu_avg"].as_f64().unwrap(), 80.0);
+        assert_eq!(points[2]["samples"], 0);
+        assert_eq!(points[2]["cm_waiting"].as_f64().unwrap(), 0.0);
+
+        // 1m buckets split the first five minutes.
+        let resp = app
+            .clone()
+            .oneshot(authed_get(
+                &format!("/api/system/metrics/export?{range}&interval=1m"),
+                &token,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let csv = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(csv.to_vec()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "timestamp,cpu_avg,mem_pct,cm_running,cm_waiting,cm_waiting_max"
+        );
+        assert_eq!(lines.len(), 1 + 15);
+        assert_eq!(lines[1], "2024-01-01T10:00:00+00:00,20,50,4,2,2");
+        assert_eq!(lines[5], "2024-01-01T10:04:00+00:00,60,50,4,10,10");
+
+        // Unknown intervals and ranges with too many points are rejected.
+        for uri in [
+            format!("/api/system/metrics?{range}&interval=2m"),
+            "/api/system/metrics?start=2024-01-01T00:00:00Z&end=2024-02-01T00:00:00Z&interval=1m"
+                .to_string(),
+        ] {
+            let resp = app.clone().oneshot(authed_get(&uri, &token)).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
     #[serial]
     #[tokio::test]
     async fn admin_can_fetch_submissions_and_export() {
@@ -285,11 +389,20 @@ mod tests {
             filename: Set("submission.zip".into()),
             file_hash: Set("hash".into()),
             path: Set("path/to/file".into()),
+            content_hash: Set(None),
             is_practice: Set(false),
             ignored: Set(false),
             status: Set(db::models::assignment_submission::SubmissionStatus::Graded),
+            submission_run_state: Set(
+                db::models::assignment_submission::SubmissionRunState::Marked,
+            ),
             created_at: Set(submission_time),
             updated_at: Set(submission_time),
+            deleted_at: Set(None),
+            group_id: Set(None),
+            client_ip: Set(None),
+            user_agent: Set(None),
+            upload_duration_ms: Set(None),
         };
         submission.insert(db).await.unwrap();
 
@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use db::models::user::Model as UserModel;
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn template(name: &str) -> Value {
+        json!({
+            "name": name,
+            "definition": {
+                "columns": [
+                    { "header": "STUDENT_NO", "source": "student_number" },
+                    { "header": "MARK", "source": "final_mark" },
+                    { "header": "RESULT", "source": "result_code" }
+                ],
+                "rounding": { "mode": "half_even", "decimals": 1 }
+            }
+        })
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn admins_manage_export_templates() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let admin = UserModel::create(db, "admin", "admin@test.com", "pw", true)
+            .await
+            .unwrap();
+        let user = UserModel::create(db, "user", "user@test.com", "pw", false)
+            .await
+            .unwrap();
+        let (admin_token, _) = generate_jwt(admin.id, true);
+        let (user_token, _) = generate_jwt(user.id, false);
+        let uri = "/api/system/grade-export-templates";
+
+        let (status, _) = send(&app, "POST", uri, &user_token, Some(template("Faculty"))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, json) = send(&app, "POST", uri, &admin_token, Some(template("Faculty"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = json["data"]["id"].as_i64().unwrap();
+        assert_eq!(json["data"]["definition"]["pass_code"], "P");
+        assert_eq!(json["data"]["definition"]["rounding"]["mode"], "half_even");
+
+        let (status, _) = send(&app, "POST", uri, &admin_token, Some(template("Faculty"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let mut bad = template("Broken");
+        bad["definition"]["columns"] = json!([]);
+        bad["definition"]["pass_mark"] = json!(120);
+        let (status, json) = send(&app, "POST", uri, &admin_token, Some(bad)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["data"].as_array().unwrap().len(), 2);
+
+        let mut renamed = template("Faculty v2");
+        renamed["definition"]["fail_code"] = json!("FL");
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &format!("{uri}/{id}"),
+            &admin_token,
+            Some(renamed),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{json}");
+        assert_eq!(json["data"]["name"], "Faculty v2");
+        assert_eq!(json["data"]["definition"]["fail_code"], "FL");
+
+        let (status, json) = send(&app, "GET", uri, &admin_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"].as_array().unwrap().len(), 1);
+
+        let (status, _) = send(&app, "DELETE", &format!("{uri}/{id}"), &admin_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "DELETE", &format!("{uri}/{id}"), &admin_token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
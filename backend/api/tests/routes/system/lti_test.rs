@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests {
+    use api::auth::generate_jwt;
+    use axum::{
+        body::Body as AxumBody,
+        http::{Request, StatusCode},
+    };
+    use db::models::{lti_key, module, user::Model as UserModel};
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+
+    use crate::helpers::app::make_test_app_with_storage;
+    use crate::helpers::lti::PLATFORM_KEY_PEM;
+
+    type App = BoxCloneService<Request<AxumBody>, axum::response::Response, Infallible>;
+
+    async fn send(
+        app: &App,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let req = match body {
+            Some(b) => builder
+                .header("Content-Type", "application/json")
+                .body(AxumBody::from(b.to_string()))
+                .unwrap(),
+            None => builder.body(AxumBody::empty()).unwrap(),
+        };
+        let response = app.clone().oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn platform(client_id: &str) -> Value {
+        json!({
+            "name": "ClickUP",
+            "issuer": "https://clickup.test",
+            "client_id": client_id,
+            "auth_login_url": "https://clickup.test/api/lti/authorize_redirect",
+            "auth_token_url": "https://clickup.test/login/oauth2/token",
+            "keyset_url": "https://clickup.test/api/lti/security/jwks"
+        })
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn admins_manage_platforms_and_deployments() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let admin = UserModel::create(db, "admin", "admin@test.com", "pw", true)
+            .await
+            .unwrap();
+        let user = UserModel::create(db, "user", "user@test.com", "pw", false)
+            .await
+            .unwrap();
+        let (admin_token, _) = generate_jwt(admin.id, true);
+        let (user_token, _) = generate_jwt(user.id, false);
+        let uri = "/api/system/lti/platforms";
+
+        let (status, _) = send(&app, "POST", uri, &user_token, Some(platform("1"))).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, json) = send(&app, "POST", uri, &admin_token, Some(platform("1"))).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = json["data"]["id"].as_i64().unwrap();
+
+        let (status, _) = send(&app, "POST", uri, &admin_token, Some(platform("1"))).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let mut bad = platform("2");
+        bad["keyset_url"] = json!("not a url");
+        let (status, json) = send(&app, "POST", uri, &admin_token, Some(bad)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["data"][0], "keyset_url must be an http(s) URL");
+
+        let deployments = format!("{uri}/{id}/deployments");
+        let dep = json!({ "deployment_id": "12:abc" });
+        let (status, json) =
+            send(&app, "POST", &deployments, &admin_token, Some(dep.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let dep_id = json["data"]["id"].as_i64().unwrap();
+        let (status, _) = send(&app, "POST", &deployments, &admin_token, Some(dep)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let module = module::Model::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let contexts = format!("{uri}/{id}/contexts");
+        let ctx = json!({ "context_id": "course-1", "module_id": module.id });
+        let (status, _) = send(&app, "POST", &contexts, &user_token, Some(ctx.clone())).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, json) = send(&app, "POST", &contexts, &admin_token, Some(ctx.clone())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let ctx_id = json["data"]["id"].as_i64().unwrap();
+        let (status, _) = send(&app, "POST", &contexts, &admin_token, Some(ctx)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = send(
+            &app,
+            "POST",
+            &contexts,
+            &admin_token,
+            Some(json!({ "context_id": "course-2", "module_id": module.id + 1 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, json) = send(&app, "GET", &contexts, &admin_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"][0]["module_id"], module.id);
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            &format!("{contexts}/{ctx_id}"),
+            &admin_token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, json) = send(&app, "GET", uri, &admin_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            json["data"]["tool"]["launch_url"]
+                .as_str()
+                .unwrap()
+                .ends_with("/api/lti/launch")
+        );
+        assert_eq!(
+            json["data"]["platforms"][0]["deployments"][0]["deployment_id"],
+            "12:abc"
+        );
+
+        let mut renamed = platform("1");
+        renamed["name"] = json!("ClickUP Prod");
+        let (status, json) = send(
+            &app,
+            "PUT",
+            &format!("{uri}/{id}"),
+            &admin_token,
+            Some(renamed),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["data"]["name"], "ClickUP Prod");
+        assert_eq!(json["data"]["deployments"].as_array().unwrap().len(), 1);
+
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            &format!("{deployments}/{dep_id}"),
+            &admin_token,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "DELETE", &format!("{uri}/{id}"), &admin_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(&app, "DELETE", &format!("{uri}/{id}"), &admin_token, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn active_keys_are_published_on_the_jwks_endpoint() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let admin = UserModel::create(db, "admin", "admin@test.com", "pw", true)
+            .await
+            .unwrap();
+        let (admin_token, _) = generate_jwt(admin.id, true);
+        let key = lti_key::Model::create(db, "tool-key", PLATFORM_KEY_PEM)
+            .await
+            .unwrap();
+
+        let (status, json) = send(&app, "GET", "/api/lti/jwks", "", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["keys"][0]["kid"], "tool-key");
+        assert_eq!(json["keys"][0]["e"], "AQAB");
+
+        let (status, json) = send(&app, "GET", "/api/system/lti/keys", &admin_token, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"][0].get("private_key_pem").is_none());
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            &format!("/api/system/lti/keys/{}", key.id),
+            &admin_token,
+            Some(json!({ "active": false })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, json) = send(&app, "GET", "/api/lti/jwks", "", None).await;
+        assert!(json["keys"].as_array().unwrap().is_empty());
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            "/api/system/lti/keys/9999",
+            &admin_token,
+            Some(json!({ "active": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,2 +1,4 @@
 pub mod get_test;
+pub mod grade_export_templates_test;
+pub mod lti_test;
 pub mod post_test;
@@ -185,4 +185,63 @@ mod tests {
         let resp = app.clone().oneshot(req).await.unwrap();
         assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
     }
+
+    /// Config reload: admin gets the live values; reloading an unchanged config changes nothing.
+    #[serial]
+    #[tokio::test]
+    async fn test_admin_can_reload_config() {
+        ensure_jwt_env();
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+
+        let admin = UserModel::create(
+            app_state.db(),
+            "admin_reload",
+            "admin_reload@test.com",
+            "password",
+            true,
+        )
+        .await
+        .unwrap();
+        let (token, _) = generate_jwt(admin.id, admin.admin);
+
+        for _ in 0..2 {
+            let req = authed_post_json("/api/system/config/reload", &token, json!({}));
+            let response = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(json["success"], true);
+            assert_eq!(json["data"]["changed"], json!([]));
+            assert_eq!(
+                json["data"]["config"]["code_manager_port"],
+                util::config::code_manager_port()
+            );
+        }
+    }
+
+    /// Config reload is admin-only.
+    #[serial]
+    #[tokio::test]
+    async fn test_non_admin_cannot_reload_config() {
+        ensure_jwt_env();
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+
+        let user = UserModel::create(
+            app_state.db(),
+            "lecturer_reload",
+            "lecturer_reload@test.com",
+            "password",
+            false,
+        )
+        .await
+        .unwrap();
+        let (token, _) = generate_jwt(user.id, user.admin);
+
+        let req = authed_post_json("/api/system/config/reload", &token, json!({}));
+        let resp = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
 }
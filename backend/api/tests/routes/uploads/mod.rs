@@ -0,0 +1 @@
+pub mod uploads_test;
@@ -0,0 +1,266 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use api::auth::generate_jwt;
+    use axum::{
+        body::{Body, to_bytes},
+        http::{
+            Request, StatusCode,
+            header::{AUTHORIZATION, CONTENT_TYPE},
+        },
+        response::Response,
+    };
+    use chrono::{TimeZone, Utc};
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_file::{Column as FileColumn, Entity as FileEntity, FileType},
+        module::Model as ModuleModel,
+        upload_session::{CHUNK_SIZE, sha256_hex},
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role},
+    };
+    use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+    use serde_json::{Value, json};
+    use serial_test::serial;
+    use std::convert::Infallible;
+    use tower::{ServiceExt, util::BoxCloneService};
+    use util::storage::storage;
+
+    type App = BoxCloneService<Request<Body>, Response, Infallible>;
+
+    async fn send(app: &App, req: Request<Body>) -> (StatusCode, Value) {
+        let res: Response = app.clone().oneshot(req).await.unwrap();
+        let status = res.status();
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn start(token: &str, filename: &str, size: usize, sha256: &str) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri("/api/uploads")
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                json!({ "filename": filename, "size": size, "sha256": sha256 }).to_string(),
+            ))
+            .unwrap()
+    }
+
+    fn part(token: &str, upload_id: i64, part_number: i32, bytes: &[u8]) -> Request<Body> {
+        Request::builder()
+            .method("PUT")
+            .uri(format!("/api/uploads/{upload_id}/parts/{part_number}"))
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header("X-Part-SHA256", sha256_hex(bytes))
+            .body(Body::from(bytes.to_vec()))
+            .unwrap()
+    }
+
+    fn bare(method: &str, uri: String, token: &str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    /// A file of one full part and a short last part.
+    fn two_part_file() -> Vec<u8> {
+        (0..CHUNK_SIZE as usize + 100)
+            .map(|i| (i % 251) as u8)
+            .collect()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn parts_resume_in_any_order_and_the_upload_replaces_a_file() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let module = ModuleModel::create(db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2025, 12, 31, 23, 59, 59).unwrap(),
+        )
+        .await
+        .unwrap();
+        let lecturer = UserModel::create(db, "lect", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, module.id, Role::Lecturer)
+            .await
+            .unwrap();
+        let (token, _) = generate_jwt(lecturer.id, false);
+
+        let file = two_part_file();
+        let (status, body) = send(
+            &app,
+            start(&token, "memo.zip", file.len(), &sha256_hex(&file)),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["data"]["total_parts"], 2);
+        let upload_id = body["data"]["id"].as_i64().unwrap();
+
+        let tail = &file[CHUNK_SIZE as usize..];
+        let (status, body) = send(&app, part(&token, upload_id, 1, tail)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["received_parts"], json!([1]));
+
+        // an interrupted client asks what is still missing
+        let (_, body) = send(
+            &app,
+            bare("GET", format!("/api/uploads/{upload_id}"), &token),
+        )
+        .await;
+        assert_eq!(body["data"]["received_parts"], json!([1]));
+        let (status, body) = send(
+            &app,
+            bare("POST", format!("/api/uploads/{upload_id}/complete"), &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["data"]["missing_parts"], json!([0]));
+
+        let head = &file[..CHUNK_SIZE as usize];
+        let (status, _) = send(&app, part(&token, upload_id, 0, head)).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = send(
+            &app,
+            bare("POST", format!("/api/uploads/{upload_id}/complete"), &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["completed"], true);
+
+        let boundary = "----BoundaryTest";
+        let form = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file_type\"\r\n\r\nmemo\r\n\
+             --{boundary}\r\nContent-Disposition: form-data; name=\"upload_id\"\r\n\r\n{upload_id}\r\n\
+             --{boundary}--\r\n"
+        );
+        let req = Request::builder()
+            .method("POST")
+            .uri(format!(
+                "/api/modules/{}/assignments/{}/files",
+                module.id, assignment.id
+            ))
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .header(
+                CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(form))
+            .unwrap();
+        let (status, body) = send(&app, req).await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        assert_eq!(body["data"]["filename"], "memo.zip");
+
+        let saved = FileEntity::find()
+            .filter(FileColumn::AssignmentId.eq(assignment.id))
+            .filter(FileColumn::FileType.eq(FileType::Memo))
+            .one(db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(storage().read(&saved.path).await.unwrap() == file);
+
+        // the upload was consumed
+        let (status, _) = send(
+            &app,
+            bare("GET", format!("/api/uploads/{upload_id}"), &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn bad_parts_and_checksums_are_rejected() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let db = app_state.db();
+        let owner = UserModel::create(db, "owner", "owner@test.com", "pw", false)
+            .await
+            .unwrap();
+        let other = UserModel::create(db, "other", "other@test.com", "pw", false)
+            .await
+            .unwrap();
+        let (token, _) = generate_jwt(owner.id, false);
+        let (other_token, _) = generate_jwt(other.id, false);
+
+        let (status, _) = send(&app, start(&token, "huge.zip", 1 << 40, &sha256_hex(b"x"))).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        let (status, _) = send(&app, start(&token, "a.zip", 10, "not-a-checksum")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let file = two_part_file();
+        let (_, body) = send(
+            &app,
+            start(&token, "a.zip", file.len(), &sha256_hex(b"something else")),
+        )
+        .await;
+        let upload_id = body["data"]["id"].as_i64().unwrap();
+
+        // other users can't see or touch it
+        let (status, _) = send(
+            &app,
+            bare("GET", format!("/api/uploads/{upload_id}"), &other_token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let tail = &file[CHUNK_SIZE as usize..];
+        let (status, _) = send(&app, part(&token, upload_id, 2, tail)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(&app, part(&token, upload_id, 0, tail)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let mut corrupted = part(&token, upload_id, 1, tail);
+        corrupted
+            .headers_mut()
+            .insert("X-Part-SHA256", sha256_hex(b"nope").parse().unwrap());
+        let (status, _) = send(&app, corrupted).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // every part checks out, but the whole file doesn't match: the parts must be resent
+        send(
+            &app,
+            part(&token, upload_id, 0, &file[..CHUNK_SIZE as usize]),
+        )
+        .await;
+        send(&app, part(&token, upload_id, 1, tail)).await;
+        let (status, _) = send(
+            &app,
+            bare("POST", format!("/api/uploads/{upload_id}/complete"), &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (_, body) = send(
+            &app,
+            bare("GET", format!("/api/uploads/{upload_id}"), &token),
+        )
+        .await;
+        assert_eq!(body["data"]["received_parts"], json!([]));
+
+        let (status, _) = send(
+            &app,
+            bare("DELETE", format!("/api/uploads/{upload_id}"), &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(
+            &app,
+            bare("GET", format!("/api/uploads/{upload_id}"), &token),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
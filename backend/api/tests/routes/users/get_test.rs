@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::helpers::app::make_test_app_with_storage;
+    use crate::helpers::app::{make_pooled_test_app, make_test_app_with_storage};
     use api::auth::generate_jwt;
     use axum::{
         body::Body as AxumBody,
@@ -13,6 +13,7 @@ mod tests {
     };
     use serde_json::Value;
     use tower::ServiceExt;
+    use util::test_helpers::setup_test_storage_root;
 
     struct TestData {
         admin_user: UserModel,
@@ -115,6 +116,34 @@ mod tests {
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
     }
 
+    /// Test Case: Concurrent Listing Requests on a Pooled Database
+    #[tokio::test]
+    async fn test_list_users_concurrent_requests_on_pooled_db() {
+        let _tmp = setup_test_storage_root();
+        let (app, app_state) = make_pooled_test_app().await;
+        let data = setup_test_data(app_state.db()).await;
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let app = app.clone();
+            let token = token.clone();
+            handles.push(tokio::spawn(async move {
+                let req = Request::builder()
+                    .method("GET")
+                    .uri("/api/users")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(AxumBody::empty())
+                    .unwrap();
+                app.oneshot(req).await.unwrap().status()
+            }));
+        }
+
+        for h in handles {
+            assert_eq!(h.await.unwrap(), StatusCode::OK);
+        }
+    }
+
     /// Test Case: Listing Users without Authentication
     #[tokio::test]
     async fn test_list_users_missing_auth() {
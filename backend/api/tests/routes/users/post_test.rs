@@ -206,4 +206,86 @@ mod tests {
         assert_eq!(json["success"], false);
         assert!(json["message"].as_str().unwrap().contains("dupe"));
     }
+
+    /// Test Case: CSV class list import creates new users and reports bad rows
+    #[tokio::test]
+    async fn test_import_users_csv_creates_users_and_reports_errors() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let data = setup_test_data(app_state.db()).await;
+
+        let (token, _) = generate_jwt(data.admin_user.id, data.admin_user.admin);
+        let csv = "Student Number,Email,Password\n\
+                   u10000001,u10000001@tuks.co.za,\n\
+                   u10000002,u10000002@tuks.co.za,secret22\n\
+                   normal_user,user@test.com,\n\
+                   normal_user,other@test.com,\n\
+                   u10000003,not-an-email,\n\
+                   u10000004,user@test.com,\n\
+                   u10000001,again@tuks.co.za,\n\
+                   ,nobody@tuks.co.za,\n";
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/users/import")
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "text/csv")
+            .body(AxumBody::from(csv))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = get_json_body(response).await;
+        assert_eq!(json["message"], "Created 2 users");
+        assert_eq!(json["data"]["created"], 2);
+        assert_eq!(json["data"]["existing"], 1);
+        let errors: Vec<(u64, &str)> = json["data"]["errors"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| (e["line"].as_u64().unwrap(), e["message"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (5, "Username repeated in the file"),
+                (6, "Invalid email"),
+                (7, "Email already belongs to another user"),
+                (8, "Username repeated in the file"),
+                (9, "Username required"),
+            ]
+        );
+
+        // The given password works; the others were randomised
+        let db = app_state.db();
+        assert!(
+            UserModel::verify_credentials(db, "u10000002", "secret22")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            UserModel::get_by_username(db, "u10000001")
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        // Only admins, and only with a username column
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/users/import")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::from("email\na@b.co\n"))
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let (token, _) = generate_jwt(data.non_admin_user.id, data.non_admin_user.admin);
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/users/import")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(AxumBody::from(csv))
+            .unwrap();
+        let response = app.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
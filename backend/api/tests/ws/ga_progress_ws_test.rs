@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use crate::helpers::{connect_ws, spawn_server};
+
+    use api::auth::generate_jwt;
+    use api::ws::ga::{emit as ga_emit, payload::GaProgressPayload};
+    use chrono::Utc;
+    use db::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as SubmissionModel,
+        module,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role as ModuleRole},
+    };
+    use futures_util::{SinkExt, StreamExt};
+    use sea_orm::{ActiveModelTrait, Set};
+    use tokio::net::TcpStream;
+    use tokio::time::{Duration, timeout};
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::protocol::Message};
+
+    fn ga_topic_json(submission_id: i64) -> serde_json::Value {
+        serde_json::json!({ "kind": "ga_run", "submission_id": submission_id })
+    }
+
+    fn subscribe_frame(topics: Vec<serde_json::Value>) -> String {
+        serde_json::json!({
+            "type": "subscribe",
+            "topics": topics,
+            "since": serde_json::Value::Null
+        })
+        .to_string()
+    }
+
+    struct Seed {
+        module: module::Model,
+        assignment: AssignmentModel,
+        submission: SubmissionModel,
+        owner: UserModel,
+        other: UserModel,
+        tutor: UserModel,
+    }
+
+    /// seed: one module and assignment, a submission by `owner`, another student and a tutor
+    async fn seed(db: &sea_orm::DatabaseConnection) -> Seed {
+        let owner = UserModel::create(db, "ga-owner", "ga-owner@test.com", "pw", false)
+            .await
+            .unwrap();
+        let other = UserModel::create(db, "ga-other", "ga-other@test.com", "pw", false)
+            .await
+            .unwrap();
+        let tutor = UserModel::create(db, "ga-tutor", "ga-tutor@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let module = module::ActiveModel {
+            code: Set("COS997".into()),
+            year: Set(2025),
+            description: Set(Some("GA Progress Test".into())),
+            credits: Set(16),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        for (user, role) in [
+            (&owner, ModuleRole::Student),
+            (&other, ModuleRole::Student),
+            (&tutor, ModuleRole::Tutor),
+        ] {
+            UserModuleRoleModel::assign_user_to_module(db, user.id, module.id, role)
+                .await
+                .unwrap();
+        }
+
+        let assignment = AssignmentModel::create(
+            db,
+            module.id,
+            "GA Progress",
+            Some("ga progress tests"),
+            AssignmentType::Practical,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let submission = SubmissionModel::save_file(
+            db,
+            assignment.id,
+            owner.id,
+            1,
+            0.0,
+            10.0,
+            false,
+            "main.zip",
+            "hash123#",
+            b"test",
+        )
+        .await
+        .unwrap();
+
+        Seed {
+            module,
+            assignment,
+            submission,
+            owner,
+            other,
+            tutor,
+        }
+    }
+
+    /// Connects as `user`, subscribes to `topics` and returns the socket with the subscribe_ok.
+    async fn subscribe(
+        addr: std::net::SocketAddr,
+        user: &UserModel,
+        topics: Vec<serde_json::Value>,
+    ) -> (
+        WebSocketStream<MaybeTlsStream<TcpStream>>,
+        serde_json::Value,
+    ) {
+        let (token, _) = generate_jwt(user.id, user.admin);
+        let (mut ws, _) = connect_ws(&addr.to_string(), Some(&token))
+            .await
+            .expect("connect");
+        // consume ready
+        let _ = ws.next().await;
+
+        ws.send(Message::Text(subscribe_frame(topics).into()))
+            .await
+            .unwrap();
+        let msg = ws.next().await.expect("subscribe_ok msg").expect("ok");
+        let Message::Text(txt) = msg else {
+            panic!("expected text");
+        };
+        let v: serde_json::Value = serde_json::from_str(&txt).unwrap();
+        assert_eq!(v["type"], "subscribe_ok");
+        (ws, v)
+    }
+
+    fn rejected_with(v: &serde_json::Value, path: &str, code: &str) -> bool {
+        v["rejected"].as_array().is_some_and(|r| {
+            r.iter().any(|r| {
+                r.get(0).and_then(|s| s.as_str()) == Some(path)
+                    && r.get(1).and_then(|s| s.as_str()) == Some(code)
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn owner_and_tutor_are_accepted_other_students_are_not() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let s = seed(app_state.db()).await;
+        let path = format!("ga:{}", s.submission.id);
+
+        for user in [&s.owner, &s.tutor] {
+            let (mut ws, v) = subscribe(addr, user, vec![ga_topic_json(s.submission.id)]).await;
+            assert_eq!(
+                v["accepted"][0],
+                path.as_str(),
+                "{} not accepted",
+                user.username
+            );
+            ws.close(None).await.unwrap();
+        }
+
+        let (mut ws, v) = subscribe(
+            addr,
+            &s.other,
+            vec![ga_topic_json(s.submission.id), ga_topic_json(9999)],
+        )
+        .await;
+        assert!(rejected_with(&v, &path, "not_allowed_for_submission"));
+        assert!(rejected_with(&v, "ga:9999", "submission_not_found"));
+        ws.close(None).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn owner_receives_generation_progress() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let s = seed(app_state.db()).await;
+
+        let (mut ws, _) = subscribe(addr, &s.owner, vec![ga_topic_json(s.submission.id)]).await;
+
+        ga_emit::progress(
+            app_state.ws(),
+            GaProgressPayload {
+                module_id: s.module.id,
+                assignment_id: s.assignment.id,
+                submission_id: s.submission.id,
+                generation: 3,
+                generations: 10,
+                best_fitness: 0.75,
+                mean_fitness: 0.5,
+                coverage_percent: Some(62.5),
+                evaluated: 40,
+                interpreter_runs: 31,
+                cache_hits: 9,
+            },
+        )
+        .await;
+
+        let msg = timeout(Duration::from_millis(500), ws.next())
+            .await
+            .expect("not timed out")
+            .expect("stream closed")
+            .expect("ws error");
+        let Message::Text(txt) = msg else {
+            panic!("expected text");
+        };
+        let v: serde_json::Value = serde_json::from_str(&txt).unwrap();
+        assert_eq!(v["type"], "event");
+        assert_eq!(v["event"], "ga.progress");
+        assert_eq!(v["topic"], format!("ga:{}", s.submission.id));
+        assert_eq!(v["payload"]["generation"], 3);
+        assert_eq!(v["payload"]["generations"], 10);
+        assert_eq!(v["payload"]["best_fitness"], 0.75);
+        assert_eq!(v["payload"]["coverage_percent"], 62.5);
+        assert_eq!(v["payload"]["cache_hits"], 9);
+
+        ws.close(None).await.unwrap();
+    }
+}
@@ -1,3 +1,5 @@
+pub mod ga_progress_ws_test;
 pub mod submission_status_ws_test;
 pub mod system_test;
+pub mod terminal_test;
 pub mod ws_tests;
@@ -331,4 +331,61 @@ mod tests {
 
         ws.close(None).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn owner_receives_live_output_chunks() {
+        use api::ws::submissions::{emit as sub_emit, payload::SubmissionOutputPayload};
+        use tokio::time::{Duration, timeout};
+
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let (m, a, owner, _other, _admin) = seed(app_state.db()).await;
+
+        let (token, _) = generate_jwt(owner.id, owner.admin);
+        let (mut ws, _) = connect_ws(&addr.to_string(), Some(&token))
+            .await
+            .expect("connect");
+        // consume ready
+        let _ = ws.next().await;
+
+        let sub = subscribe_frame(vec![owner_topic_json(a.id, owner.id)]);
+        ws.send(Message::Text(sub.into())).await.unwrap();
+        // consume subscribe_ok
+        let _ = ws.next().await;
+
+        sub_emit::output(
+            app_state.ws(),
+            a.id,
+            owner.id,
+            SubmissionOutputPayload {
+                module_id: m.id,
+                assignment_id: a.id,
+                submission_id: 1,
+                attempt: 1,
+                task_id: 7,
+                task_number: 1,
+                stream: "stdout".into(),
+                data: "Hello, live!\n".into(),
+            },
+        )
+        .await;
+
+        let msg = timeout(Duration::from_millis(500), ws.next())
+            .await
+            .expect("not timed out")
+            .expect("stream closed")
+            .expect("ws error");
+        let Message::Text(txt) = msg else {
+            panic!("expected text");
+        };
+        let v: serde_json::Value = serde_json::from_str(&txt).unwrap();
+        assert_eq!(v["type"], "event");
+        assert_eq!(v["event"], "submission.output");
+        assert_eq!(v["topic"], owner_topic_path(a.id, owner.id));
+        assert_eq!(v["payload"]["task_number"], 1);
+        assert_eq!(v["payload"]["stream"], "stdout");
+        assert_eq!(v["payload"]["data"], "Hello, live!\n");
+
+        ws.close(None).await.unwrap();
+    }
 }
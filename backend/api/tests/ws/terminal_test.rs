@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::helpers::app::make_test_app_with_storage;
+    use crate::helpers::spawn_server;
+
+    use api::auth::generate_jwt;
+    use chrono::{Duration, Utc};
+    use db::models::{
+        api_token::{Model as ApiTokenModel, Scope},
+        assignment::{AssignmentType, Model as AssignmentModel},
+        module,
+        user::Model as UserModel,
+        user_module_role::{Model as UserModuleRoleModel, Role as ModuleRole},
+    };
+    use sea_orm::{ActiveModelTrait, Set};
+    use tokio_tungstenite::{
+        connect_async,
+        tungstenite::{
+            Error,
+            client::IntoClientRequest,
+            http::{HeaderValue, header::AUTHORIZATION},
+        },
+    };
+    use util::paths::config_dir;
+
+    /// seed: one module, one assignment (with the default config), a lecturer and a student
+    async fn seed(
+        db: &sea_orm::DatabaseConnection,
+    ) -> (module::Model, AssignmentModel, UserModel, UserModel) {
+        let lecturer = UserModel::create(db, "t-lecturer", "lecturer@test.com", "pw", false)
+            .await
+            .unwrap();
+        let student = UserModel::create(db, "t-student", "student@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let m = module::ActiveModel {
+            code: Set("COS998".into()),
+            year: Set(2025),
+            description: Set(Some("Terminal Test".into())),
+            credits: Set(16),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+
+        UserModuleRoleModel::assign_user_to_module(db, lecturer.id, m.id, ModuleRole::Lecturer)
+            .await
+            .unwrap();
+        UserModuleRoleModel::assign_user_to_module(db, student.id, m.id, ModuleRole::Student)
+            .await
+            .unwrap();
+
+        let a = AssignmentModel::create(
+            db,
+            m.id,
+            "Terminal",
+            Some("terminal tests"),
+            AssignmentType::Practical,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        (m, a, lecturer, student)
+    }
+
+    /// Opens the terminal socket and returns the status of the rejected upgrade.
+    async fn rejected_status(
+        addr: std::net::SocketAddr,
+        module_id: i64,
+        assignment_id: i64,
+        token: &str,
+    ) -> (u16, serde_json::Value) {
+        let url = format!(
+            "ws://{}/api/modules/{}/assignments/{}/terminal",
+            addr, module_id, assignment_id
+        );
+        let mut req = url.into_client_request().unwrap();
+        let hv = HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+        req.headers_mut().insert(AUTHORIZATION, hv);
+
+        match connect_async(req).await {
+            Ok(_) => panic!("terminal upgrade should have been rejected"),
+            Err(Error::Http(resp)) => {
+                let body = std::str::from_utf8(resp.body().as_ref().unwrap()).unwrap();
+                (resp.status().as_u16(), serde_json::from_str(body).unwrap())
+            }
+            Err(e) => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn student_is_forbidden() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let (m, a, _lecturer, student) = seed(app_state.db()).await;
+        let (token, _) = generate_jwt(student.id, student.admin);
+
+        let (status, _) = rejected_status(addr, m.id, a.id, &token).await;
+        assert_eq!(status, 403);
+    }
+
+    #[tokio::test]
+    async fn api_tokens_are_forbidden() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let db = app_state.db();
+        let (m, a, lecturer, _student) = seed(db).await;
+        let (_, token) = ApiTokenModel::create(
+            db,
+            lecturer.id,
+            "CI",
+            &[Scope::Read],
+            Utc::now() + Duration::days(1),
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The lecturer's own session would get past the guards (and fail on the missing memo)
+        let (status, json) = rejected_status(addr, m.id, a.id, &token).await;
+        assert_eq!(status, 403);
+        assert_eq!(json["success"], false);
+    }
+
+    #[tokio::test]
+    async fn lecturer_without_memo_archives_is_unprocessable() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let (m, a, lecturer, _student) = seed(app_state.db()).await;
+        let (token, _) = generate_jwt(lecturer.id, lecturer.admin);
+
+        let (status, json) = rejected_status(addr, m.id, a.id, &token).await;
+        assert_eq!(status, 422);
+        assert_eq!(json["success"], false);
+    }
+
+    #[tokio::test]
+    async fn lecturer_without_config_is_not_found() {
+        let (app, app_state, _tmp) = make_test_app_with_storage().await;
+        let addr = spawn_server(app).await;
+        let (m, a, lecturer, _student) = seed(app_state.db()).await;
+        std::fs::remove_dir_all(config_dir(m.id, a.id)).unwrap();
+        let (token, _) = generate_jwt(lecturer.id, lecturer.admin);
+
+        let (status, json) = rejected_status(addr, m.id, a.id, &token).await;
+        assert_eq!(status, 404);
+        assert!(
+            json["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Assignment config not found")
+        );
+    }
+}
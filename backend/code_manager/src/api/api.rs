@@ -1,9 +1,21 @@
 //api/api.rs
-use crate::manager::manager::ContainerManager;
-use axum::{extract::Json, http::StatusCode, response::IntoResponse};
+use crate::backend::ExecutionBackend;
+use crate::container::container::{OutputChunk, RunCancelled};
+use crate::container::metrics::CommandMetrics;
+use crate::manager::manager::{ContainerManager, RunOptions};
+use crate::manager::queue::{Priority, WaitingByPriority};
+use crate::manager::runs::RunStatus;
+use crate::metrics::metrics;
+use axum::{
+    body::Body,
+    extract::{Json, Path, Query},
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 use util::{execution_config::ExecutionConfig, paths};
 
 #[derive(Debug, Deserialize)]
@@ -13,17 +25,43 @@ pub struct RunRequest {
     pub files: Vec<(String, Vec<u8>)>,
     #[serde(default)]
     pub interpreter: bool,
+    /// If true, `/run` also returns `/code` as a tar once all commands finished.
+    #[serde(default)]
+    pub return_artifacts: bool,
+    /// If true, `/run` and `/run/stream` also return `/output` as a tar once all commands
+    /// finished.
+    #[serde(default)]
+    pub return_output_files: bool,
+    /// Caller-chosen id so the run can be stopped with `DELETE /run/{job_id}`.
+    /// Several runs may share one id.
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// `interactive`, `normal` (default) or `bulk`; higher priorities leave the queue first.
+    #[serde(default)]
+    pub priority: Priority,
 }
 
 #[derive(Debug, Serialize)]
 pub struct RunResponse {
     pub output: Vec<String>,
+    /// Wall time, CPU time and peak memory per command, in the same order as `output`.
+    pub metrics: Vec<CommandMetrics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<Vec<u8>>,
+    /// Files written to `/output`, as a tar.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_files: Option<Vec<u8>>,
 }
 
 // Hold ContainerManager in a global static for shared access
 use once_cell::sync::OnceCell;
 static MANAGER: OnceCell<ContainerManager> = OnceCell::new();
 
+/// The manager created by [`init_manager`], shared with the gRPC service.
+pub fn manager() -> &'static ContainerManager {
+    MANAGER.get().expect("Manager not initialized")
+}
+
 pub async fn health() -> impl IntoResponse {
     (StatusCode::OK, "code_manager is running")
 }
@@ -43,16 +81,33 @@ pub async fn run_code(Json(payload): Json<RunRequest>) -> impl IntoResponse {
     };
 
     match manager
-        .run(
+        .run_with(
             &execution_config,
             payload.commands,
             payload.files,
             //defaults to false if it doesn't exist
             payload.interpreter,
+            RunOptions {
+                collect_artifacts: payload.return_artifacts,
+                collect_output_files: payload.return_output_files,
+                job_id: payload.job_id,
+                priority: payload.priority,
+                ..Default::default()
+            },
         )
         .await
     {
-        Ok(output) => (StatusCode::OK, axum::Json(RunResponse { output })).into_response(),
+        Ok(run) => (
+            StatusCode::OK,
+            axum::Json(RunResponse {
+                output: run.outputs,
+                metrics: run.metrics,
+                artifacts: run.artifacts,
+                output_files: run.output_files,
+            }),
+        )
+            .into_response(),
+        Err(e) if e.is::<RunCancelled>() => (StatusCode::CONFLICT, e.to_string()).into_response(),
         Err(e) => {
             let msg = format!("Error running container: {}", e);
             tracing::error!("{}", msg);
@@ -61,8 +116,206 @@ pub async fn run_code(Json(payload): Json<RunRequest>) -> impl IntoResponse {
     }
 }
 
+#[derive(Debug, Serialize)]
+pub struct SubmitRunResponse {
+    /// Id to poll with `GET /jobs/{id}`.
+    pub id: String,
+}
+
+/// `POST /run/async`: same body as [`run_code`], but queues the run and answers `202` with its
+/// id straight away instead of waiting for it to finish.
+pub async fn run_code_async(Json(payload): Json<RunRequest>) -> impl IntoResponse {
+    let manager = MANAGER.get().expect("Manager not initialized");
+
+    let config_json = Value::Object(payload.config.into_iter().collect());
+
+    let execution_config: ExecutionConfig = match serde_json::from_value(config_json) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            let msg = format!("Invalid config: {}", e);
+            tracing::error!("{}", msg);
+            return (StatusCode::BAD_REQUEST, msg).into_response();
+        }
+    };
+
+    let id = manager.submit(
+        execution_config,
+        payload.commands,
+        payload.files,
+        payload.interpreter,
+        RunOptions {
+            job_id: payload.job_id,
+            priority: payload.priority,
+            ..Default::default()
+        },
+    );
+    (StatusCode::ACCEPTED, axum::Json(SubmitRunResponse { id })).into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListJobsQuery {
+    pub status: Option<RunStatus>,
+}
+
+/// `GET /jobs`: every queued, running and recently finished run, newest first. Filter with
+/// `?status=queued|running|completed|failed|cancelled`.
+pub async fn list_jobs(Query(query): Query<ListJobsQuery>) -> impl IntoResponse {
+    let manager = MANAGER.get().expect("Manager not initialized");
+    (StatusCode::OK, axum::Json(manager.list_runs(query.status))).into_response()
+}
+
+/// `GET /jobs/{id}`: status of one run. Runs submitted through `/run/async` also carry their
+/// outputs under `result` once completed.
+pub async fn get_job(Path(id): Path<String>) -> impl IntoResponse {
+    let manager = MANAGER.get().expect("Manager not initialized");
+    match manager.run_status(&id) {
+        Some(record) => (StatusCode::OK, axum::Json(record)).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("No run with id {}", id)).into_response(),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CancelRunResponse {
+    pub job_id: String,
+    /// Number of queued or running runs that were signalled.
+    pub runs: usize,
+}
+
+/// `DELETE /run/{job_id}`: stops every queued or running run registered under `job_id`.
+///
+/// Cancelled runs answer their own request with `409` (`/run`) or an `error` line
+/// (`/run/stream`). Returns `404` if no run is using the id.
+pub async fn cancel_run(Path(job_id): Path<String>) -> impl IntoResponse {
+    let manager = MANAGER.get().expect("Manager not initialized");
+    match manager.cancel_job(&job_id) {
+        Some(runs) => (
+            StatusCode::OK,
+            axum::Json(CancelRunResponse { job_id, runs }),
+        )
+            .into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("No run in progress for job {}", job_id),
+        )
+            .into_response(),
+    }
+}
+
+/// One line of the `/run/stream` NDJSON response.
+///
+/// Zero or more `chunk` lines are followed by exactly one `done` or `error` line.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RunStreamEvent {
+    Chunk(OutputChunk),
+    Done {
+        output: Vec<String>,
+        metrics: Vec<CommandMetrics>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output_files: Option<Vec<u8>>,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl RunStreamEvent {
+    fn to_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_else(|e| {
+            format!(
+                r#"{{"type":"error","message":"Failed to encode event: {}"}}"#,
+                e
+            )
+        });
+        line.push('\n');
+        line
+    }
+}
+
+/// Same as [`run_code`], but streams stdout/stderr back as newline-delimited JSON while the
+/// commands run. The final line carries the full outputs in the same shape as `/run`.
+pub async fn run_code_stream(Json(payload): Json<RunRequest>) -> impl IntoResponse {
+    let manager = MANAGER.get().expect("Manager not initialized").clone();
+
+    let config_json = Value::Object(payload.config.into_iter().collect());
+
+    let execution_config: ExecutionConfig = match serde_json::from_value(config_json) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            let msg = format!("Invalid config: {}", e);
+            tracing::error!("{}", msg);
+            return (StatusCode::BAD_REQUEST, msg).into_response();
+        }
+    };
+
+    let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel::<RunStreamEvent>();
+    let (chunk_tx, mut chunk_rx) = tokio::sync::mpsc::unbounded_channel::<OutputChunk>();
+
+    let forward_tx = event_tx.clone();
+    let forwarder = tokio::spawn(async move {
+        while let Some(chunk) = chunk_rx.recv().await {
+            if forward_tx.send(RunStreamEvent::Chunk(chunk)).is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let result = manager
+            .run_with(
+                &execution_config,
+                payload.commands,
+                payload.files,
+                payload.interpreter,
+                RunOptions {
+                    sink: Some(chunk_tx),
+                    collect_output_files: payload.return_output_files,
+                    job_id: payload.job_id,
+                    priority: payload.priority,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // Flush any chunks still in flight before the terminal event.
+        let _ = forwarder.await;
+
+        let last = match result {
+            Ok(run) => RunStreamEvent::Done {
+                output: run.outputs,
+                metrics: run.metrics,
+                output_files: run.output_files,
+            },
+            Err(e) if e.is::<RunCancelled>() => RunStreamEvent::Error {
+                message: e.to_string(),
+            },
+            Err(e) => {
+                let message = format!("Error running container: {}", e);
+                tracing::error!("{}", message);
+                RunStreamEvent::Error { message }
+            }
+        };
+        let _ = event_tx.send(last);
+    });
+
+    let body = futures::stream::unfold(event_rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|event| (Ok::<_, std::convert::Infallible>(event.to_line()), rx))
+    });
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
 /// Initialize global container manager - called once at startup
-pub fn init_manager(default_max_concurrent: usize) {
+///
+/// Runs go to `backend`.
+pub fn init_manager(default_max_concurrent: usize, backend: Arc<dyn ExecutionBackend>) {
     let resolved = match load_persisted_max_concurrent() {
         Some(value) => {
             tracing::info!(
@@ -73,7 +326,8 @@ pub fn init_manager(default_max_concurrent: usize) {
         }
         None => default_max_concurrent,
     };
-    if MANAGER.set(ContainerManager::new(resolved)).is_err() {
+    let manager = ContainerManager::new(resolved).with_backend(backend);
+    if MANAGER.set(manager).is_err() {
         tracing::warn!("ContainerManager was already initialized");
     } else {
         tracing::info!(max_concurrent = resolved, "Initialized ContainerManager");
@@ -92,17 +346,36 @@ pub struct StatsResponse {
     pub running: usize,
     pub waiting: usize,
     pub max_concurrent: usize,
+    /// Breakdown of `waiting`.
+    pub waiting_by_priority: WaitingByPriority,
+}
+
+/// `GET /metrics`: run counters, durations and queue depth in the Prometheus text format.
+pub async fn prometheus_metrics() -> impl IntoResponse {
+    let manager = MANAGER.get().expect("Manager not initialized");
+    let (running, _, max_concurrent) = manager.get_stats().await;
+    let waiting = manager.get_waiting_by_priority().await;
+    let metrics = metrics();
+    metrics.set_queue(running, waiting, max_concurrent);
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics.render(),
+    )
+        .into_response()
 }
 
 pub async fn stats() -> impl IntoResponse {
     let manager = MANAGER.get().expect("Manager not initialized");
     let (running, waiting, max_concurrent) = manager.get_stats().await;
+    let waiting_by_priority = manager.get_waiting_by_priority().await;
     (
         StatusCode::OK,
         axum::Json(StatsResponse {
             running,
             waiting,
             max_concurrent,
+            waiting_by_priority,
         }),
     )
         .into_response()
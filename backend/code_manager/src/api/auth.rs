@@ -0,0 +1,125 @@
+//api/auth.rs
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tonic::service::Interceptor;
+use tonic::Status;
+
+/// Shared secret every caller must send, or `None` when auth is disabled.
+pub type SharedToken = Option<Arc<str>>;
+
+/// Compares without short-circuiting so timing doesn't reveal how much of the token matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// True if `authorization` is `Bearer <expected>`.
+pub fn is_authorized(expected: &str, authorization: Option<&str>) -> bool {
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+        .unwrap_or(false)
+}
+
+/// Rejects HTTP requests without the shared token with `401`. Does nothing when `token` is `None`.
+pub async fn require_token(State(token): State<SharedToken>, req: Request, next: Next) -> Response {
+    if let Some(expected) = token.as_deref() {
+        let authorization = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok());
+        if !is_authorized(expected, authorization) {
+            return (
+                StatusCode::UNAUTHORIZED,
+                "Missing or invalid code_manager token",
+            )
+                .into_response();
+        }
+    }
+    next.run(req).await
+}
+
+/// Same check as [`require_token`] for gRPC, on the `authorization` metadata.
+#[derive(Clone)]
+pub struct TokenInterceptor {
+    token: SharedToken,
+}
+
+impl TokenInterceptor {
+    pub fn new(token: SharedToken) -> Self {
+        Self { token }
+    }
+}
+
+impl Interceptor for TokenInterceptor {
+    fn call(&mut self, req: tonic::Request<()>) -> Result<tonic::Request<()>, Status> {
+        if let Some(expected) = self.token.as_deref() {
+            let authorization = req
+                .metadata()
+                .get("authorization")
+                .and_then(|v| v.to_str().ok());
+            if !is_authorized(expected, authorization) {
+                return Err(Status::unauthenticated(
+                    "Missing or invalid code_manager token",
+                ));
+            }
+        }
+        Ok(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware::from_fn_with_state, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[test]
+    fn accepts_only_the_exact_bearer_token() {
+        assert!(is_authorized("s3cret", Some("Bearer s3cret")));
+        assert!(!is_authorized("s3cret", Some("Bearer s3cre")));
+        assert!(!is_authorized("s3cret", Some("Bearer s3cret2")));
+        assert!(!is_authorized("s3cret", Some("s3cret")));
+        assert!(!is_authorized("s3cret", None));
+    }
+
+    async fn status_for(token: SharedToken, authorization: Option<&str>) -> StatusCode {
+        let app = Router::new()
+            .route("/run", get(|| async { "ok" }))
+            .route_layer(from_fn_with_state(token, require_token));
+        let mut req = axum::http::Request::builder().uri("/run");
+        if let Some(value) = authorization {
+            req = req.header(header::AUTHORIZATION, value);
+        }
+        app.oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn middleware_rejects_missing_or_wrong_token() {
+        let token: SharedToken = Some(Arc::from("s3cret"));
+        assert_eq!(
+            status_for(token.clone(), None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_for(token.clone(), Some("Bearer nope")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_for(token, Some("Bearer s3cret")).await,
+            StatusCode::OK
+        );
+        // No token configured: everything is let through.
+        assert_eq!(status_for(None, None).await, StatusCode::OK);
+    }
+}
@@ -0,0 +1,191 @@
+//api/grpc.rs
+use crate::api::auth::{SharedToken, TokenInterceptor};
+use crate::container::container::{OutputChunk, RunCancelled};
+use crate::container::metrics::CommandMetrics;
+use crate::manager::manager::{ContainerManager, RunOptions};
+use crate::manager::queue::Priority;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tonic::service::interceptor::InterceptedService;
+use tonic::{Request, Response, Status};
+use util::execution_config::ExecutionConfig;
+
+pub mod proto {
+    tonic::include_proto!("code_manager");
+}
+
+use proto::code_manager_server::{CodeManager, CodeManagerServer};
+use proto::run_event::Event;
+
+/// Largest request or response the gRPC server accepts; submissions and build artifacts can
+/// easily exceed tonic's 4 MiB default.
+pub const MAX_MESSAGE_BYTES: usize = 256 * 1024 * 1024;
+
+/// gRPC counterpart of the HTTP handlers in [`super::api`], sharing the same [`ContainerManager`].
+pub struct CodeManagerService {
+    manager: ContainerManager,
+}
+
+impl CodeManagerService {
+    pub fn new(manager: ContainerManager) -> Self {
+        Self { manager }
+    }
+
+    /// Wraps the service in a tonic server with [`MAX_MESSAGE_BYTES`] applied both ways and
+    /// every call checked against `token`.
+    pub fn into_server(
+        self,
+        token: SharedToken,
+    ) -> InterceptedService<CodeManagerServer<Self>, TokenInterceptor> {
+        let server = CodeManagerServer::new(self)
+            .max_decoding_message_size(MAX_MESSAGE_BYTES)
+            .max_encoding_message_size(MAX_MESSAGE_BYTES);
+        InterceptedService::new(server, TokenInterceptor::new(token))
+    }
+}
+
+impl From<OutputChunk> for proto::OutputChunk {
+    fn from(chunk: OutputChunk) -> Self {
+        Self {
+            command: chunk.command as u32,
+            stream: chunk.stream.to_string(),
+            data: chunk.data,
+        }
+    }
+}
+
+impl From<CommandMetrics> for proto::CommandMetrics {
+    fn from(m: CommandMetrics) -> Self {
+        Self {
+            wall_time_ms: m.wall_time_ms,
+            cpu_time_ms: m.cpu_time_ms,
+            max_rss_bytes: m.max_rss_bytes,
+            disk_quota_exceeded: m.disk_quota_exceeded,
+        }
+    }
+}
+
+impl From<proto::Priority> for Priority {
+    fn from(priority: proto::Priority) -> Self {
+        match priority {
+            proto::Priority::Normal => Priority::Normal,
+            proto::Priority::Interactive => Priority::Interactive,
+            proto::Priority::Bulk => Priority::Bulk,
+        }
+    }
+}
+
+fn event(event: Event) -> proto::RunEvent {
+    proto::RunEvent { event: Some(event) }
+}
+
+type RunStream = UnboundedReceiverStream<Result<proto::RunEvent, Status>>;
+
+#[tonic::async_trait]
+impl CodeManager for CodeManagerService {
+    type RunStream = RunStream;
+
+    async fn run(
+        &self,
+        request: Request<proto::RunRequest>,
+    ) -> Result<Response<Self::RunStream>, Status> {
+        let req = request.into_inner();
+
+        let execution_config: ExecutionConfig = serde_json::from_str(&req.config_json)
+            .map_err(|e| Status::invalid_argument(format!("Invalid config: {}", e)))?;
+
+        let priority = req.priority().into();
+        let files = req.files.into_iter().map(|f| (f.name, f.content)).collect();
+        let job_id = Some(req.job_id).filter(|id| !id.is_empty());
+
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+        let manager = self.manager.clone();
+        tokio::spawn(async move {
+            let (sink, forwarder) = if req.stream_output {
+                let (chunk_tx, mut chunk_rx) = mpsc::unbounded_channel::<OutputChunk>();
+                let forward_tx = event_tx.clone();
+                let forwarder = tokio::spawn(async move {
+                    while let Some(chunk) = chunk_rx.recv().await {
+                        if forward_tx
+                            .send(Ok(event(Event::Chunk(chunk.into()))))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                });
+                (Some(chunk_tx), Some(forwarder))
+            } else {
+                (None, None)
+            };
+
+            let result = manager
+                .run_with(
+                    &execution_config,
+                    req.commands,
+                    files,
+                    req.interpreter,
+                    RunOptions {
+                        sink,
+                        collect_artifacts: req.return_artifacts,
+                        collect_output_files: req.return_output_files,
+                        job_id,
+                        priority,
+                        ..Default::default()
+                    },
+                )
+                .await;
+
+            // Flush any chunks still in flight before the terminal event.
+            if let Some(forwarder) = forwarder {
+                let _ = forwarder.await;
+            }
+
+            let last = match result {
+                Ok(run) => Ok(event(Event::Done(proto::RunDone {
+                    output: run.outputs,
+                    metrics: run.metrics.into_iter().map(Into::into).collect(),
+                    artifacts: run.artifacts,
+                    output_files: run.output_files,
+                }))),
+                Err(e) if e.is::<RunCancelled>() => Err(Status::aborted(e.to_string())),
+                Err(e) => {
+                    let message = format!("Error running container: {}", e);
+                    tracing::error!("{}", message);
+                    Err(Status::internal(message))
+                }
+            };
+            let _ = event_tx.send(last);
+        });
+
+        Ok(Response::new(UnboundedReceiverStream::new(event_rx)))
+    }
+
+    async fn health(
+        &self,
+        _request: Request<proto::HealthRequest>,
+    ) -> Result<Response<proto::HealthResponse>, Status> {
+        Ok(Response::new(proto::HealthResponse {
+            status: "code_manager is running".to_string(),
+        }))
+    }
+
+    async fn stats(
+        &self,
+        _request: Request<proto::StatsRequest>,
+    ) -> Result<Response<proto::StatsResponse>, Status> {
+        let (running, waiting, max_concurrent) = self.manager.get_stats().await;
+        let by_priority = self.manager.get_waiting_by_priority().await;
+        Ok(Response::new(proto::StatsResponse {
+            running: running as u64,
+            waiting: waiting as u64,
+            max_concurrent: max_concurrent as u64,
+            waiting_by_priority: Some(proto::QueueDepth {
+                interactive: by_priority.interactive as u64,
+                normal: by_priority.normal as u64,
+                bulk: by_priority.bulk as u64,
+            }),
+        }))
+    }
+}
@@ -1,2 +1,5 @@
 //api/mod.rs
 pub mod api;
+pub mod auth;
+pub mod grpc;
+pub mod terminal;
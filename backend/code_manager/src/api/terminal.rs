@@ -0,0 +1,232 @@
+//api/terminal.rs
+use crate::api::api::manager;
+use crate::container::terminal::{TerminalEnd, TerminalSession};
+use crate::manager::queue::Priority;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::IntoResponse;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use util::config;
+use util::execution_config::ExecutionConfig;
+
+/// Largest first message accepted; it carries the session's files.
+const MAX_START_MESSAGE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Files unpacked into `/code`, as `(name, contents)` pairs.
+type Files = Vec<(String, Vec<u8>)>;
+
+/// First message a client sends on `/terminal`, as JSON text.
+#[derive(Debug, Deserialize)]
+pub struct TerminalRequest {
+    pub config: HashMap<String, Value>,
+    /// Unpacked into `/code` before the shell starts, like the files of a run.
+    #[serde(default)]
+    pub files: Files,
+}
+
+/// Messages code_manager sends on `/terminal`, as JSON text tagged with `type`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TerminalEvent {
+    /// Waiting for a container slot.
+    Queued,
+    /// The shell is running; input is forwarded from now on.
+    Ready,
+    Output {
+        stream: &'static str,
+        data: String,
+    },
+    /// The shell exited; `code` is `None` if it was killed by a signal.
+    Exit {
+        code: Option<i32>,
+    },
+    /// The session could not start or was stopped; the socket closes after this.
+    Error {
+        message: String,
+    },
+}
+
+/// `GET /terminal`: an interactive shell over a WebSocket.
+///
+/// The client first sends a [`TerminalRequest`]. Once a slot is free (terminals wait at
+/// `interactive` priority and hold the slot until they close), every text or binary message is
+/// written to the shell's stdin, and output comes back as [`TerminalEvent::Output`]. Sessions
+/// end when the shell exits, the socket closes, `TERMINAL_SESSION_SECS` pass or the mounts
+/// outgrow `execution.max_disk_bytes`.
+pub async fn terminal(ws: WebSocketUpgrade) -> impl IntoResponse {
+    ws.max_message_size(MAX_START_MESSAGE_BYTES)
+        .max_frame_size(MAX_START_MESSAGE_BYTES)
+        .on_upgrade(serve_terminal)
+}
+
+/// Sends `event`; false once the client is gone.
+async fn send(socket: &mut WebSocket, event: &TerminalEvent) -> bool {
+    let text = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(text.into())).await.is_ok()
+}
+
+async fn send_error(socket: &mut WebSocket, message: String) {
+    send(socket, &TerminalEvent::Error { message }).await;
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// Reads the [`TerminalRequest`] and its config from the first message.
+fn parse_request(text: &str) -> Result<(ExecutionConfig, Files), String> {
+    let request: TerminalRequest =
+        serde_json::from_str(text).map_err(|e| format!("Invalid terminal request: {}", e))?;
+    let config: ExecutionConfig =
+        serde_json::from_value(Value::Object(request.config.into_iter().collect()))
+            .map_err(|e| format!("Invalid config: {}", e))?;
+    Ok((config, request.files))
+}
+
+async fn serve_terminal(mut socket: WebSocket) {
+    let (config, files) = match socket.recv().await {
+        Some(Ok(Message::Text(text))) => match parse_request(text.as_str()) {
+            Ok(request) => request,
+            Err(e) => return send_error(&mut socket, e).await,
+        },
+        Some(Ok(_)) => {
+            let message = "Expected a JSON terminal request first".to_string();
+            return send_error(&mut socket, message).await;
+        }
+        _ => return,
+    };
+
+    let manager = manager();
+    // Sessions always start a Docker container; the local backend has nothing to isolate them.
+    if manager.backend_name() != "docker" {
+        let message = format!(
+            "Terminals need the docker backend (EXECUTION_BACKEND is {})",
+            manager.backend_name()
+        );
+        return send_error(&mut socket, message).await;
+    }
+
+    if !send(&mut socket, &TerminalEvent::Queued).await {
+        return;
+    }
+    manager.acquire_slot(Priority::Interactive).await;
+    tracing::info!("Starting terminal session");
+    serve_session(&mut socket, &config, files).await;
+    manager.release_slot().await;
+}
+
+async fn serve_session(socket: &mut WebSocket, config: &ExecutionConfig, files: Files) {
+    let session = match TerminalSession::start(config, files).await {
+        Ok(session) => session,
+        Err(e) => return send_error(socket, e).await,
+    };
+    if !send(socket, &TerminalEvent::Ready).await {
+        return;
+    }
+
+    let (input, input_rx) = mpsc::unbounded_channel();
+    let (sink, mut output) = mpsc::unbounded_channel();
+    let limit = Duration::from_secs(config::terminal_session_secs());
+    let mut run = Box::pin(session.run(input_rx, sink, limit, config.execution.max_disk_bytes));
+
+    let end = loop {
+        tokio::select! {
+            end = &mut run => break end,
+            Some(chunk) = output.recv() => {
+                let event = TerminalEvent::Output {
+                    stream: chunk.stream,
+                    data: chunk.data,
+                };
+                if !send(socket, &event).await {
+                    break TerminalEnd::Closed;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let _ = input.send(text.as_str().as_bytes().to_vec());
+                }
+                Some(Ok(Message::Binary(data))) => {
+                    let _ = input.send(data.to_vec());
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break TerminalEnd::Closed,
+                Some(Ok(_)) => {}
+            },
+        }
+    };
+    // Dropping the session (inside `run`) removes the container.
+    drop(run);
+
+    while let Ok(chunk) = output.try_recv() {
+        let event = TerminalEvent::Output {
+            stream: chunk.stream,
+            data: chunk.data,
+        };
+        if !send(socket, &event).await {
+            return;
+        }
+    }
+    match end {
+        TerminalEnd::Exited(code) => {
+            send(socket, &TerminalEvent::Exit { code }).await;
+            let _ = socket.send(Message::Close(None)).await;
+        }
+        TerminalEnd::Closed => {}
+        TerminalEnd::TimeLimit => {
+            let message = format!("Session time limit of {}s reached", limit.as_secs());
+            send_error(socket, message).await;
+        }
+        TerminalEnd::OverDiskQuota(used) => {
+            let message = format!(
+                "Disk quota exceeded ({} bytes used, limit {} bytes)",
+                used, config.execution.max_disk_bytes
+            );
+            send_error(socket, message).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_the_start_message() {
+        let text = json!({
+            "config": { "execution": { "timeout_secs": 5 } },
+            "files": [["main.zip", [80, 75]]]
+        })
+        .to_string();
+        let (config, files) = parse_request(&text).unwrap();
+        assert_eq!(config.execution.timeout_secs, 5);
+        assert_eq!(files, vec![("main.zip".to_string(), vec![80, 75])]);
+
+        let (_, files) = parse_request(r#"{"config":{}}"#).unwrap();
+        assert!(files.is_empty());
+
+        let err = parse_request(r#"{"config":{"execution":{"timeout_secs":"x"}}}"#).unwrap_err();
+        assert!(err.starts_with("Invalid config"), "{}", err);
+        let err = parse_request("ls").unwrap_err();
+        assert!(err.starts_with("Invalid terminal request"), "{}", err);
+    }
+
+    #[test]
+    fn events_are_tagged_by_type() {
+        let output = TerminalEvent::Output {
+            stream: "stdout",
+            data: "make: ok\n".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&output).unwrap(),
+            json!({ "type": "output", "stream": "stdout", "data": "make: ok\n" })
+        );
+        assert_eq!(
+            serde_json::to_value(TerminalEvent::Exit { code: Some(2) }).unwrap(),
+            json!({ "type": "exit", "code": 2 })
+        );
+        assert_eq!(
+            serde_json::to_value(TerminalEvent::Queued).unwrap(),
+            json!({ "type": "queued" })
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//backend/docker.rs
+use super::{BackendError, ExecutionBackend};
+use crate::container::container::{run_container_with, Collect, ContainerRun, OutputSink};
+use crate::container::pool::ContainerPool;
+use crate::container::sandbox::security_opts;
+use crate::manager::jobs::CancelToken;
+use async_trait::async_trait;
+use std::sync::Arc;
+use util::execution_config::ExecutionConfig;
+
+/// Runs each command in a Docker container (see [`run_container_with`]), taking a pre-started
+/// one from the warm pool when it has one ready.
+#[derive(Default)]
+pub struct DockerBackend {
+    pool: Option<Arc<ContainerPool>>,
+}
+
+impl DockerBackend {
+    pub fn new(pool: Option<Arc<ContainerPool>>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ExecutionBackend for DockerBackend {
+    fn name(&self) -> &'static str {
+        "docker"
+    }
+
+    async fn run(
+        &self,
+        config: &ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        sink: Option<OutputSink>,
+        collect: Collect,
+        cancel: Option<CancelToken>,
+        job_id: Option<&str>,
+    ) -> Result<ContainerRun, BackendError> {
+        // An invalid sandbox config is reported by `run_container_with`.
+        let warm = match (&self.pool, security_opts(config)) {
+            (Some(pool), Ok(opts)) => pool.take(&config.runner_image(), &opts),
+            _ => None,
+        };
+        run_container_with(
+            config,
+            commands,
+            files,
+            interpreter,
+            sink,
+            collect,
+            cancel,
+            warm,
+            job_id,
+        )
+        .await
+    }
+}
@@ -0,0 +1,323 @@
+//backend/local.rs
+use super::{BackendError, ExecutionBackend};
+use crate::container::container::{
+    cancelled, collect_reader, command_output, spawn_reader, write_files, Collect, ContainerRun,
+    Ended, OutputSink, RunCancelled,
+};
+use crate::container::disk::DiskQuota;
+use crate::container::metrics::CommandMetrics;
+use crate::manager::jobs::CancelToken;
+use crate::utils::compression::pack_directory_tar;
+use async_trait::async_trait;
+use std::io;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tempdir::TempDir;
+use tokio::process::Command;
+use tokio::time::timeout;
+use util::execution_config::ExecutionConfig;
+
+/// Runs commands as plain processes on the code_manager host, for CI and developer machines
+/// without Docker.
+///
+/// Each run gets temporary `code` and `output` directories. Commands start in the code
+/// directory with a cleared environment: `PATH`, `HOME` (the code directory),
+/// `FITCHFORK_CODE_DIR`, `FITCHFORK_OUTPUT_DIR` and the assignment's `environment`. There is
+/// no `/code` or `/output`, so commands must use relative paths or those variables.
+///
+/// Limits are rlimits set before `sh` starts: address space (`max_memory`), CPU time
+/// (`timeout_secs`) and file size (`max_disk_bytes`), with core dumps off. Each command runs in
+/// its own process group, which is killed once it finishes. The image, CPU share, process
+/// count, network and sandbox settings are not applied, and CPU time and peak memory are not
+/// reported, so this backend must never run untrusted code.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalBackend;
+
+#[async_trait]
+impl ExecutionBackend for LocalBackend {
+    fn name(&self) -> &'static str {
+        "local"
+    }
+
+    async fn run(
+        &self,
+        config: &ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        sink: Option<OutputSink>,
+        collect: Collect,
+        mut cancel: Option<CancelToken>,
+        _job_id: Option<&str>,
+    ) -> Result<ContainerRun, BackendError> {
+        let code_dir = TempDir::new("code")?;
+        let output_dir = TempDir::new("output")?;
+        write_files(
+            files,
+            config.execution.max_uncompressed_size,
+            code_dir.path(),
+        )?;
+
+        let path = std::env::var("PATH").unwrap_or_else(|_| "/usr/bin:/bin".to_string());
+        let limits = Limits::from_config(config);
+
+        let mut outputs = Vec::new();
+        let mut metrics = Vec::new();
+
+        for (index, cmd) in commands.into_iter().enumerate() {
+            if cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                return Err(Box::new(RunCancelled));
+            }
+
+            let mut command = Command::new("sh");
+            command
+                .arg("-c")
+                .arg(&cmd)
+                .current_dir(code_dir.path())
+                .env_clear()
+                .env("PATH", &path)
+                .env("HOME", code_dir.path())
+                .env("FITCHFORK_CODE_DIR", code_dir.path())
+                .env("FITCHFORK_OUTPUT_DIR", output_dir.path())
+                .envs(
+                    config
+                        .environment_pairs()
+                        .iter()
+                        .filter_map(|pair| pair.split_once('=')),
+                )
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+            // SAFETY: `apply` only makes async-signal-safe calls (setsid, setrlimit).
+            unsafe {
+                command.pre_exec(move || limits.apply());
+            }
+
+            let started = Instant::now();
+            let mut child = command.spawn()?;
+            let group = child.id();
+
+            let stdout_reader = child
+                .stdout
+                .take()
+                .map(|out| spawn_reader(out, index, "stdout", sink.clone()));
+            let stderr_reader = child
+                .stderr
+                .take()
+                .map(|err| spawn_reader(err, index, "stderr", sink.clone()));
+
+            let mut quota = DiskQuota::watch(
+                vec![
+                    code_dir.path().to_path_buf(),
+                    output_dir.path().to_path_buf(),
+                ],
+                config.execution.max_disk_bytes,
+            );
+            let ended = tokio::select! {
+                result = timeout(Duration::from_secs(config.execution.timeout_secs), child.wait()) => {
+                    match result {
+                        Ok(status) => Ended::Exited(status),
+                        Err(_) => Ended::TimedOut,
+                    }
+                }
+                used = quota.exceeded() => Ended::OverDiskQuota(used),
+                _ = cancelled(&mut cancel) => Ended::Cancelled,
+            };
+            drop(quota);
+
+            // Stops the command if it is still running, and anything it left in the background
+            // (which would otherwise keep the pipes open).
+            if let Some(group) = group {
+                kill_group(group);
+            }
+            let _ = child.wait().await;
+
+            let stdout = collect_reader(stdout_reader).await;
+            let stderr = collect_reader(stderr_reader).await;
+            if let Ended::Cancelled = ended {
+                return Err(Box::new(RunCancelled));
+            }
+
+            metrics.push(CommandMetrics {
+                wall_time_ms: started.elapsed().as_millis() as u64,
+                cpu_time_ms: None,
+                max_rss_bytes: None,
+                disk_quota_exceeded: matches!(ended, Ended::OverDiskQuota(_)),
+            });
+            outputs.push(command_output(
+                ended,
+                &stdout,
+                &stderr,
+                interpreter,
+                config.execution.max_disk_bytes,
+            ));
+        }
+
+        let artifacts = if collect.artifacts {
+            Some(pack_directory_tar(code_dir.path())?)
+        } else {
+            None
+        };
+        let output_files = if collect.output_files {
+            Some(pack_directory_tar(output_dir.path())?)
+        } else {
+            None
+        };
+
+        Ok(ContainerRun {
+            outputs,
+            artifacts,
+            output_files,
+            metrics,
+        })
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type Resource = libc::c_int;
+
+/// rlimits for one command; 0 leaves a limit unset.
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    address_space: u64,
+    cpu_secs: u64,
+    file_size: u64,
+}
+
+impl Limits {
+    fn from_config(config: &ExecutionConfig) -> Self {
+        Self {
+            address_space: config.execution.max_memory,
+            cpu_secs: config.execution.timeout_secs,
+            file_size: config.execution.max_disk_bytes,
+        }
+    }
+
+    /// Runs in the forked child before `exec`, so it must not allocate.
+    fn apply(&self) -> io::Result<()> {
+        // A new process group, so the command and its children can be killed together.
+        if unsafe { libc::setsid() } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        let limits = [
+            (libc::RLIMIT_AS, self.address_space),
+            (libc::RLIMIT_CPU, self.cpu_secs),
+            (libc::RLIMIT_FSIZE, self.file_size),
+        ];
+        for (resource, value) in limits {
+            if value > 0 {
+                set_rlimit(resource, value)?;
+            }
+        }
+        set_rlimit(libc::RLIMIT_CORE, 0)
+    }
+}
+
+fn set_rlimit(resource: Resource, value: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: value as libc::rlim_t,
+        rlim_max: value as libc::rlim_t,
+    };
+    if unsafe { libc::setrlimit(resource, &limit) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// SIGKILLs every process in the group led by `pid`; a group that is already gone is ignored.
+fn kill_group(pid: u32) {
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run(config: &ExecutionConfig, commands: &[&str]) -> ContainerRun {
+        LocalBackend
+            .run(
+                config,
+                commands.iter().map(|c| c.to_string()).collect(),
+                vec![("script.sh".to_string(), b"echo from script".to_vec())],
+                false,
+                None,
+                Collect {
+                    artifacts: false,
+                    output_files: true,
+                },
+                None,
+                None,
+            )
+            .await
+            .expect("local run failed")
+    }
+
+    #[tokio::test]
+    async fn runs_commands_in_the_code_directory() {
+        let mut config = ExecutionConfig::default_config();
+        config
+            .environment
+            .insert("FITCHFORK_FLAG".to_string(), "on".to_string());
+
+        let run = run(
+            &config,
+            &[
+                "sh script.sh",
+                "echo flag=$FITCHFORK_FLAG; echo done > \"$FITCHFORK_OUTPUT_DIR/result.txt\"",
+                "exit 3",
+            ],
+        )
+        .await;
+
+        assert_eq!(run.outputs.len(), 3);
+        assert!(run.outputs[0].starts_with("from script\n"));
+        assert!(run.outputs[1].contains("flag=on"));
+        assert!(run.outputs[2].ends_with("Retcode: 3"));
+        assert_eq!(run.metrics.len(), 3);
+
+        let output_files = run.output_files.unwrap();
+        let mut archive = tar::Archive::new(output_files.as_slice());
+        let names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert!(
+            names.iter().any(|n| n.ends_with("result.txt")),
+            "{:?}",
+            names
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_rlimits() {
+        let mut config = ExecutionConfig::default_config();
+        config.execution.max_memory = 256 * 1024 * 1024;
+        config.execution.timeout_secs = 7;
+
+        let run = run(&config, &["ulimit -v; ulimit -t; ulimit -c"]).await;
+        assert!(
+            run.outputs[0].starts_with("262144\n7\n0\n"),
+            "{}",
+            run.outputs[0]
+        );
+    }
+
+    #[tokio::test]
+    async fn timeout_kills_background_processes() {
+        let mut config = ExecutionConfig::default_config();
+        config.execution.timeout_secs = 1;
+
+        let started = Instant::now();
+        let run = run(&config, &["sleep 30 & sleep 30", "echo next"]).await;
+
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert!(run.outputs[0].contains("Command timed out"));
+        assert!(run.outputs[1].starts_with("next\n"));
+    }
+}
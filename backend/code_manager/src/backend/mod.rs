@@ -0,0 +1,43 @@
+//backend/mod.rs
+pub mod docker;
+pub mod local;
+
+use crate::container::container::{Collect, ContainerRun, OutputSink};
+use crate::manager::jobs::CancelToken;
+use async_trait::async_trait;
+use util::execution_config::ExecutionConfig;
+
+pub use docker::DockerBackend;
+pub use local::LocalBackend;
+
+pub type BackendError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Runs a request's commands once [`ContainerManager`] gave it a slot.
+///
+/// Every backend puts the files in a fresh `/code` (extracting archives), runs the commands
+/// one by one with `sh -c`, stops each after `execution.timeout_secs` or once the run's
+/// directories outgrow `execution.max_disk_bytes`, and reports outputs in the same format.
+/// code_manager uses one backend for all runs, chosen with `EXECUTION_BACKEND`.
+///
+/// [`ContainerManager`]: crate::manager::manager::ContainerManager
+#[async_trait]
+pub trait ExecutionBackend: Send + Sync {
+    /// Short name for logs, e.g. `"docker"`.
+    fn name(&self) -> &'static str;
+
+    /// Runs `commands` against `files`. If `cancel` fires, the remaining commands are skipped
+    /// and [`RunCancelled`](crate::container::container::RunCancelled) is returned. `job_id`
+    /// is the caller's job the run belongs to, if any.
+    #[allow(clippy::too_many_arguments)]
+    async fn run(
+        &self,
+        config: &ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        sink: Option<OutputSink>,
+        collect: Collect,
+        cancel: Option<CancelToken>,
+        job_id: Option<&str>,
+    ) -> Result<ContainerRun, BackendError>;
+}
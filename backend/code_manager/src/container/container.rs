@@ -1,15 +1,76 @@
 //container/container.rs
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
 use std::process::Stdio;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 // use tempfile::tempdir;
 use tempdir::TempDir;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio::process::Command;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
 use tokio::time::timeout;
-use util::execution_config::ExecutionConfig;
+use util::execution_config::{is_valid_image, ExecutionConfig};
+use util::system_health::JOB_LABEL;
 
-use crate::utils::compression::{extract_archive_contents, is_supported_archive};
+use super::disk::DiskQuota;
+use super::metrics::{read_container_stats, CgroupSampler, CommandMetrics};
+use super::pool::WarmContainer;
+use super::sandbox::security_opts;
+use crate::manager::jobs::CancelToken;
+use crate::utils::compression::{
+    extract_archive_contents, is_supported_archive, pack_directory_tar,
+};
+
+/// A piece of live output read from a running command.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputChunk {
+    /// Index of the command (within the run request) that produced this chunk.
+    pub command: usize,
+    /// Either `"stdout"` or `"stderr"`.
+    pub stream: &'static str,
+    pub data: String,
+}
+
+/// Receives [`OutputChunk`]s while a container is running.
+pub type OutputSink = UnboundedSender<OutputChunk>;
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Returned when a run is stopped through its job id before all commands finished.
+#[derive(Debug)]
+pub struct RunCancelled;
+
+impl std::fmt::Display for RunCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Run cancelled")
+    }
+}
+
+impl std::error::Error for RunCancelled {}
+
+/// What to pack up once the last command finished.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Collect {
+    /// `/code`: sources plus anything the commands built.
+    pub artifacts: bool,
+    /// `/output`: files the commands wrote there (plots, CSVs, binaries, ...).
+    pub output_files: bool,
+}
+
+/// Result of a container run.
+#[derive(Debug, Default)]
+pub struct ContainerRun {
+    /// One entry per command, in the same format as [`run_container`].
+    pub outputs: Vec<String>,
+    /// Tar of `/code` after the last command, if artifacts were requested.
+    pub artifacts: Option<Vec<u8>>,
+    /// Tar of `/output` after the last command, if output files were requested.
+    pub output_files: Option<Vec<u8>>,
+    /// Resource usage, one entry per command that ran.
+    pub metrics: Vec<CommandMetrics>,
+}
 
 pub async fn run_container(
     config: &ExecutionConfig,
@@ -17,106 +78,419 @@ pub async fn run_container(
     files: Vec<(String, Vec<u8>)>,
     interpreter: bool,
 ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    let temp_code_dir = TempDir::new("code")?;
-    let temp_output_dir = TempDir::new("output")?;
-
-    let code_path = temp_code_dir.path().to_path_buf();
-    let output_path = temp_output_dir.path().to_path_buf();
+    run_container_streaming(config, commands, files, interpreter, None).await
+}
 
-    for (file_name, contents) in files {
-        let file_path = code_path.join(&file_name);
+/// Same as [`run_container`], but forwards stdout/stderr to `sink` as it is produced.
+///
+/// The returned outputs are identical to the non-streaming variant; the sink only
+/// receives a live copy. A send failure (receiver dropped) is ignored so the run
+/// still completes.
+pub async fn run_container_streaming(
+    config: &ExecutionConfig,
+    commands: Vec<String>,
+    files: Vec<(String, Vec<u8>)>,
+    interpreter: bool,
+    sink: Option<OutputSink>,
+) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    run_container_with(
+        config,
+        commands,
+        files,
+        interpreter,
+        sink,
+        Collect::default(),
+        None,
+        None,
+        None,
+    )
+    .await
+    .map(|run| run.outputs)
+}
 
-        if is_supported_archive(Path::new(&file_name)) {
-            extract_archive_contents(
-                Path::new(&file_name),
-                &contents,
-                config.execution.max_uncompressed_size,
-                &code_path,
-            )?;
-        } else {
-            fs::write(&file_path, &contents)?;
-        }
+/// Runs the commands and packs up what `collect` asks for: the resulting `/code` directory
+/// (sources plus anything the commands built) so it can be reattached to later runs, and/or
+/// whatever the commands wrote to `/output`.
+///
+/// If `cancel` fires, the running container is removed, the remaining commands are skipped and
+/// [`RunCancelled`] is returned.
+///
+/// The image comes from [`ExecutionConfig::runner_image`]. With a `warm` container of that image
+/// from the pool, the commands are `docker exec`ed in it instead of each getting a new
+/// container. If the run's limits can't be applied to it, or it was started with a different
+/// sandbox (see [`security_opts`]), it is dropped and the run goes cold. Cold containers are
+/// labelled with `job_id` ([`JOB_LABEL`]) so system health can attribute their usage.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_container_with(
+    config: &ExecutionConfig,
+    commands: Vec<String>,
+    files: Vec<(String, Vec<u8>)>,
+    interpreter: bool,
+    sink: Option<OutputSink>,
+    collect: Collect,
+    mut cancel: Option<CancelToken>,
+    warm: Option<WarmContainer>,
+    job_id: Option<&str>,
+) -> Result<ContainerRun, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let image = config.runner_image();
+    if !is_valid_image(&image) {
+        return Err(format!("Invalid runner image: {:?}", image).into());
     }
 
+    let security_opts = security_opts(config)?;
+
+    let warm = match warm.filter(|warm| warm.image == image && warm.security_opts == security_opts)
+    {
+        Some(warm) => match warm.apply_limits(config).await {
+            Ok(()) => Some(warm),
+            Err(e) => {
+                tracing::warn!(
+                    container = %warm.name,
+                    error = %e,
+                    "Warm container unusable, starting a cold one"
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    // A warm container brings its own mounts; cold runs get fresh ones.
+    let cold_dirs = match warm {
+        Some(_) => None,
+        None => Some((TempDir::new("code")?, TempDir::new("output")?)),
+    };
+    // Holds `--cidfile`s; kept outside the mounted dirs so the container can't see them.
+    let temp_meta_dir = TempDir::new("meta")?;
+
+    let (code_path, output_path) = match (&warm, &cold_dirs) {
+        (Some(warm), _) => (
+            warm.code_path().to_path_buf(),
+            warm.output_path().to_path_buf(),
+        ),
+        (None, Some((code, output))) => (code.path().to_path_buf(), output.path().to_path_buf()),
+        (None, None) => unreachable!("cold runs always get mounts"),
+    };
+
+    write_files(files, config.execution.max_uncompressed_size, &code_path)?;
+
     let memory_arg = format!("--memory={}b", config.execution.max_memory);
     let cpus_arg = format!("--cpus={}", config.execution.max_cpus);
     let pids_arg = format!("--pids-limit={}", config.execution.max_processes);
+    let env_args: Vec<String> = config
+        .environment_pairs()
+        .into_iter()
+        .flat_map(|pair| ["-e".to_string(), pair])
+        .collect();
+
+    // Named so a timed-out or cancelled container can be removed, not just its docker client.
+    let run_name = code_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("code")
+        .to_string();
 
     let mut outputs = Vec::new();
+    let mut metrics = Vec::new();
 
-    for cmd in commands {
-        let docker_output = Command::new("docker")
-            .arg("run")
-            .arg("--rm")
-            .arg("--network=none")
-            .arg(&memory_arg)
-            .arg(&cpus_arg)
-            .arg(&pids_arg)
-            .arg("--security-opt=no-new-privileges")
-            .arg("-v")
-            .arg(format!("{}:/code:rw", code_path.display()))
-            .arg("-v")
-            .arg(format!("{}:/output", output_path.display()))
-            .arg("universal-runner")
+    for (index, cmd) in commands.into_iter().enumerate() {
+        if cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            return Err(Box::new(RunCancelled));
+        }
+
+        let container_name = format!("fitchfork-{}-{}", run_name, index);
+        let cidfile = temp_meta_dir.path().join(format!("{}.cid", index));
+        let mut docker = Command::new("docker");
+        // CPU time in a warm container's cgroup includes the commands before this one.
+        let mut cpu_baseline_usec = 0;
+        match &warm {
+            Some(warm) => {
+                // The sampler finds the cgroup through the cidfile, as for a cold run.
+                fs::write(&cidfile, &warm.id)?;
+                cpu_baseline_usec = read_container_stats(&warm.id).cpu_usec.unwrap_or(0);
+                docker.arg("exec").args(&env_args).arg(&warm.name);
+            }
+            None => {
+                docker
+                    .arg("run")
+                    .arg("--rm")
+                    .arg("--name")
+                    .arg(&container_name)
+                    .arg("--cidfile")
+                    .arg(&cidfile)
+                    .args(job_id.map(|job| format!("--label={JOB_LABEL}={job}")))
+                    .arg("--network=none")
+                    .arg(&memory_arg)
+                    .arg(&cpus_arg)
+                    .arg(&pids_arg)
+                    .args(
+                        security_opts
+                            .iter()
+                            .map(|opt| format!("--security-opt={}", opt)),
+                    )
+                    .args(&env_args)
+                    .arg("-v")
+                    .arg(format!("{}:/code:rw", code_path.display()))
+                    .arg("-v")
+                    .arg(format!("{}:/output", output_path.display()))
+                    .arg(&image);
+            }
+        }
+        let started = Instant::now();
+        let mut child = docker
             .arg("sh")
             .arg("-c")
             .arg(&cmd)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()?;
+        let sampler = CgroupSampler::start(cidfile);
 
-        let output_result = timeout(
-            Duration::from_secs(config.execution.timeout_secs),
-            docker_output.wait_with_output(),
-        )
-        .await;
+        let stdout_reader = child
+            .stdout
+            .take()
+            .map(|out| spawn_reader(out, index, "stdout", sink.clone()));
+        let stderr_reader = child
+            .stderr
+            .take()
+            .map(|err| spawn_reader(err, index, "stderr", sink.clone()));
 
-        let combined_output = match output_result {
-            Ok(Ok(output)) => {
-                let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
-                let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
-                let retcode = output.status.code().unwrap_or(-1);
-
-                if interpreter {
-                    // For interpreters: return raw stdout only
-                    stdout
-                } else {
-                    // For normal execution: include markers
-                    let mut combined = String::new();
-                    combined.push_str(&stdout);
-                    combined.push_str("&FITCHFORK&StandardError\n");
-                    if !combined.is_empty() {
-                        combined.push('\n');
-                    }
-                    combined.push_str(&stderr);
-                    combined.push_str("&FITCHFORK&ReturnCode\n");
-                    if !combined.is_empty() {
-                        combined.push('\n');
-                    }
-                    combined.push_str(&format!("Retcode: {}", retcode));
-                    combined
+        let mut quota = DiskQuota::watch(
+            vec![code_path.clone(), output_path.clone()],
+            config.execution.max_disk_bytes,
+        );
+        let ended = tokio::select! {
+            result = timeout(Duration::from_secs(config.execution.timeout_secs), child.wait()) => {
+                match result {
+                    Ok(status) => Ended::Exited(status),
+                    Err(_) => Ended::TimedOut,
                 }
             }
-            Ok(Err(e)) => {
-                if interpreter {
-                    format!("Interpreter failed: {}", e)
-                } else {
-                    format!("&FITCHFORK&Error\nCommand failed: {}", e)
-                }
+            used = quota.exceeded() => Ended::OverDiskQuota(used),
+            _ = cancelled(&mut cancel) => Ended::Cancelled,
+        };
+        drop(quota);
+
+        if let Ended::Cancelled = ended {
+            let _ = child.kill().await;
+            // A warm container is removed when it is dropped on return.
+            if warm.is_none() {
+                remove_container(&container_name).await;
             }
-            Err(_) => {
-                if interpreter {
-                    "Interpreter timed out (possible infinite loop)".to_string()
-                } else {
-                    "&FITCHFORK&Error\nCommand timed out (possible infinite loop)".to_string()
+            collect_reader(stdout_reader).await;
+            collect_reader(stderr_reader).await;
+            return Err(Box::new(RunCancelled));
+        }
+
+        if matches!(ended, Ended::TimedOut | Ended::OverDiskQuota(_)) {
+            // Don't leave the container, its docker client (and its output readers) running
+            // past the deadline or the quota.
+            let _ = child.kill().await;
+            match &warm {
+                // Killing `docker exec` leaves the command running inside; restarting stops it
+                // but keeps the mounts for the next command.
+                Some(warm) => warm.restart().await,
+                None => remove_container(&container_name).await,
+            }
+        }
+
+        let stdout = collect_reader(stdout_reader).await;
+        let stderr = collect_reader(stderr_reader).await;
+
+        let stats = sampler.finish().await;
+        metrics.push(CommandMetrics {
+            wall_time_ms: started.elapsed().as_millis() as u64,
+            cpu_time_ms: stats
+                .cpu_usec
+                .map(|usec| usec.saturating_sub(cpu_baseline_usec) / 1_000),
+            // The peak can't be reset between commands, so in a warm container this is the
+            // highest usage of the run so far.
+            max_rss_bytes: stats.peak_bytes,
+            disk_quota_exceeded: matches!(ended, Ended::OverDiskQuota(_)),
+        });
+
+        outputs.push(command_output(
+            ended,
+            &stdout,
+            &stderr,
+            interpreter,
+            config.execution.max_disk_bytes,
+        ));
+    }
+
+    let artifacts = if collect.artifacts {
+        Some(pack_directory_tar(&code_path)?)
+    } else {
+        None
+    };
+    let output_files = if collect.output_files {
+        Some(pack_directory_tar(&output_path)?)
+    } else {
+        None
+    };
+
+    Ok(ContainerRun {
+        outputs,
+        artifacts,
+        output_files,
+        metrics,
+    })
+}
+
+/// Puts the run's files into `code_path`, extracting archives in place.
+pub(crate) fn write_files(
+    files: Vec<(String, Vec<u8>)>,
+    max_uncompressed_size: u64,
+    code_path: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    for (file_name, contents) in files {
+        if is_supported_archive(Path::new(&file_name)) {
+            extract_archive_contents(
+                Path::new(&file_name),
+                &contents,
+                max_uncompressed_size,
+                code_path,
+            )?;
+        } else {
+            fs::write(code_path.join(&file_name), &contents)?;
+        }
+    }
+    Ok(())
+}
+
+/// The entry for one command in a run's outputs.
+///
+/// Interpreter runs get raw stdout; everything else gets stdout, stderr and the return code
+/// separated by `&FITCHFORK&` markers, or a `&FITCHFORK&Error` message if the command didn't
+/// exit on its own.
+pub(crate) fn command_output(
+    ended: Ended,
+    stdout: &[u8],
+    stderr: &[u8],
+    interpreter: bool,
+    max_disk_bytes: u64,
+) -> String {
+    match ended {
+        Ended::Exited(Ok(status)) => {
+            let stdout = String::from_utf8_lossy(stdout).into_owned();
+            let stderr = String::from_utf8_lossy(stderr).into_owned();
+            let retcode = status.code().unwrap_or(-1);
+
+            if interpreter {
+                // For interpreters: return raw stdout only
+                stdout
+            } else {
+                // For normal execution: include markers
+                let mut combined = String::new();
+                combined.push_str(&stdout);
+                combined.push_str("&FITCHFORK&StandardError\n");
+                if !combined.is_empty() {
+                    combined.push('\n');
+                }
+                combined.push_str(&stderr);
+                combined.push_str("&FITCHFORK&ReturnCode\n");
+                if !combined.is_empty() {
+                    combined.push('\n');
                 }
+                combined.push_str(&format!("Retcode: {}", retcode));
+                combined
             }
-        };
+        }
+        Ended::Exited(Err(e)) => {
+            if interpreter {
+                format!("Interpreter failed: {}", e)
+            } else {
+                format!("&FITCHFORK&Error\nCommand failed: {}", e)
+            }
+        }
+        Ended::TimedOut => {
+            if interpreter {
+                "Interpreter timed out (possible infinite loop)".to_string()
+            } else {
+                "&FITCHFORK&Error\nCommand timed out (possible infinite loop)".to_string()
+            }
+        }
+        Ended::OverDiskQuota(used) => {
+            let message = format!(
+                "exceeded the disk quota ({} bytes used, limit {} bytes)",
+                used, max_disk_bytes
+            );
+            if interpreter {
+                format!("Interpreter {}", message)
+            } else {
+                format!("&FITCHFORK&Error\nCommand {}", message)
+            }
+        }
+        Ended::Cancelled => unreachable!("cancelled runs return early"),
+    }
+}
+
+/// How waiting for a command ended.
+pub(crate) enum Ended {
+    Exited(std::io::Result<std::process::ExitStatus>),
+    TimedOut,
+    /// `/code` and `/output` together grew past `max_disk_bytes`; holds the bytes in use.
+    OverDiskQuota(u64),
+    Cancelled,
+}
 
-        outputs.push(combined_output);
+/// Resolves once `cancel` fires; never without a token.
+pub(crate) async fn cancelled(cancel: &mut Option<CancelToken>) {
+    match cancel {
+        Some(token) => token.cancelled().await,
+        None => std::future::pending().await,
     }
+}
 
-    Ok(outputs)
+/// Force-removes a container by name; errors (e.g. it already exited) are ignored.
+pub(crate) async fn remove_container(name: &str) {
+    let _ = Command::new("docker")
+        .arg("rm")
+        .arg("-f")
+        .arg(name)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+}
+
+/// Reads a child pipe to completion, forwarding each read to `sink` and returning all bytes.
+pub(crate) fn spawn_reader<R>(
+    mut reader: R,
+    command: usize,
+    stream: &'static str,
+    sink: Option<OutputSink>,
+) -> JoinHandle<Vec<u8>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut collected = Vec::new();
+        let mut buf = [0u8; READ_BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    collected.extend_from_slice(&buf[..n]);
+                    if let Some(sink) = &sink {
+                        let _ = sink.send(OutputChunk {
+                            command,
+                            stream,
+                            data: String::from_utf8_lossy(&buf[..n]).into_owned(),
+                        });
+                    }
+                }
+            }
+        }
+        collected
+    })
+}
+
+pub(crate) async fn collect_reader(handle: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    match handle {
+        Some(handle) => handle.await.unwrap_or_default(),
+        None => Vec::new(),
+    }
 }
 
 #[cfg(test)]
@@ -184,6 +558,25 @@ mod tests {
         assert!(outputs[2].contains("line2"));
     }
 
+    #[tokio::test]
+    async fn test_run_container_exports_environment() {
+        let mut config = ExecutionConfig::default_config();
+        config
+            .environment
+            .insert("FITCHFORK_FLAG".to_string(), "on".to_string());
+
+        let outputs = run_container(
+            &config,
+            vec!["echo flag=$FITCHFORK_FLAG".to_string()],
+            vec![],
+            false,
+        )
+        .await
+        .expect("run_container failed");
+
+        assert!(outputs[0].contains("flag=on"));
+    }
+
     #[tokio::test]
     async fn test_run_container_with_zip_file() {
         let config = ExecutionConfig::default_config();
@@ -204,4 +597,34 @@ mod tests {
         assert_eq!(outputs.len(), 1);
         assert!(outputs[0].contains("Hello, Zip!"));
     }
+
+    #[tokio::test]
+    async fn test_run_container_streaming_forwards_chunks() {
+        let config = ExecutionConfig::default_config();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let outputs = run_container_streaming(
+            &config,
+            vec!["echo streamed; echo oops 1>&2".to_string()],
+            vec![],
+            false,
+            Some(tx),
+        )
+        .await
+        .expect("run_container_streaming failed");
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        while let Some(chunk) = rx.recv().await {
+            assert_eq!(chunk.command, 0);
+            match chunk.stream {
+                "stdout" => stdout.push_str(&chunk.data),
+                _ => stderr.push_str(&chunk.data),
+            }
+        }
+
+        assert!(stdout.contains("streamed"));
+        assert!(stderr.contains("oops"));
+        assert!(outputs[0].contains("streamed"));
+    }
 }
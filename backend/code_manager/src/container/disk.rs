@@ -0,0 +1,136 @@
+//container/disk.rs
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Bytes allocated on disk for everything under `path`; symlinks are not followed.
+///
+/// Uses allocated blocks rather than file lengths, so a sparse file only counts for what it
+/// actually occupies. Entries that vanish while walking are skipped.
+pub(crate) fn disk_usage(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    let own = meta.blocks() * 512;
+    if !meta.is_dir() {
+        return own;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return own;
+    };
+    entries
+        .flatten()
+        .map(|entry| disk_usage(&entry.path()))
+        .fold(own, u64::saturating_add)
+}
+
+/// Polls the run's mounted directories while a command runs.
+///
+/// Docker can't cap the size of a bind mount, so the usage of `/code` and `/output` is checked
+/// every [`CHECK_INTERVAL`]; a fast writer can go somewhat past the limit before it is stopped.
+pub(crate) struct DiskQuota {
+    handle: Option<JoinHandle<u64>>,
+}
+
+impl DiskQuota {
+    /// Starts watching `dirs`. A `limit` of 0 disables the quota.
+    pub fn watch(dirs: Vec<PathBuf>, limit: u64) -> Self {
+        if limit == 0 {
+            return Self { handle: None };
+        }
+        let handle = tokio::spawn(async move {
+            loop {
+                let dirs = dirs.clone();
+                let used = tokio::task::spawn_blocking(move || {
+                    dirs.iter().map(|dir| disk_usage(dir)).sum::<u64>()
+                })
+                .await
+                .unwrap_or(0);
+                if used > limit {
+                    return used;
+                }
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        });
+        Self {
+            handle: Some(handle),
+        }
+    }
+
+    /// Resolves with the usage in bytes once it went over the limit; never resolves when the
+    /// quota is disabled.
+    pub async fn exceeded(&mut self) -> u64 {
+        if let Some(handle) = &mut self.handle {
+            let result = handle.await;
+            // A finished handle can't be polled again.
+            self.handle = None;
+            if let Ok(used) = result {
+                return used;
+            }
+        }
+        std::future::pending().await
+    }
+}
+
+impl Drop for DiskQuota {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn disk_usage_counts_nested_files_but_not_sparse_holes() {
+        let dir = TempDir::new().unwrap();
+        let empty = disk_usage(dir.path());
+        fs::create_dir(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("nested/data.bin"), vec![1u8; 64 * 1024]).unwrap();
+        let sparse = fs::File::create(dir.path().join("sparse.bin")).unwrap();
+        sparse.set_len(1 << 30).unwrap();
+
+        let used = disk_usage(dir.path()) - empty;
+        assert!(used >= 64 * 1024, "{} bytes", used);
+        assert!(used < 1 << 20, "{} bytes", used);
+    }
+
+    #[tokio::test]
+    async fn quota_fires_once_the_directories_grow_past_the_limit() {
+        let code = TempDir::new().unwrap();
+        let output = TempDir::new().unwrap();
+        let mut quota = DiskQuota::watch(
+            vec![code.path().to_path_buf(), output.path().to_path_buf()],
+            256 * 1024,
+        );
+
+        let write = tokio::time::sleep(Duration::from_millis(300));
+        tokio::select! {
+            _ = quota.exceeded() => panic!("quota fired before anything was written"),
+            _ = write => {}
+        }
+        fs::write(output.path().join("big.bin"), vec![0u8; 512 * 1024]).unwrap();
+
+        let used = tokio::time::timeout(Duration::from_secs(5), quota.exceeded())
+            .await
+            .expect("quota did not fire");
+        assert!(used > 256 * 1024);
+    }
+
+    #[tokio::test]
+    async fn zero_disables_the_quota() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("big.bin"), vec![0u8; 4096]).unwrap();
+        let mut quota = DiskQuota::watch(vec![dir.path().to_path_buf()], 0);
+        let fired = tokio::time::timeout(Duration::from_millis(300), quota.exceeded()).await;
+        assert!(fired.is_err());
+    }
+}
@@ -0,0 +1,220 @@
+//container/metrics.rs
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Resource usage of one command, returned alongside its output.
+///
+/// `cpu_time_ms` and `max_rss_bytes` come from the container's cgroup and are `None` when it
+/// could not be read (e.g. the command exited before the first sample).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CommandMetrics {
+    pub wall_time_ms: u64,
+    pub cpu_time_ms: Option<u64>,
+    pub max_rss_bytes: Option<u64>,
+    /// The command was killed because `/code` and `/output` outgrew `max_disk_bytes`.
+    pub disk_quota_exceeded: bool,
+}
+
+/// Latest cgroup readings for a container.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CgroupStats {
+    pub cpu_usec: Option<u64>,
+    pub peak_bytes: Option<u64>,
+}
+
+impl CgroupStats {
+    /// Keeps the highest value seen for each counter; both only grow while the container runs.
+    fn merge(&mut self, other: CgroupStats) {
+        self.cpu_usec = self.cpu_usec.max(other.cpu_usec);
+        self.peak_bytes = self.peak_bytes.max(other.peak_bytes);
+    }
+}
+
+/// `usage_usec` from a cgroup v2 `cpu.stat` file.
+fn parse_cpu_stat_usage_usec(text: &str) -> Option<u64> {
+    text.lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Reads CPU time and peak memory for `container_id` under `root`.
+///
+/// Covers cgroup v2 with the systemd driver (`system.slice/docker-<id>.scope`) or the cgroupfs
+/// driver (`docker/<id>`), and cgroup v1 (`cpuacct/docker/<id>`, `memory/docker/<id>`).
+pub(crate) fn read_cgroup_stats(root: &Path, container_id: &str) -> CgroupStats {
+    let v2_dirs = [
+        root.join("system.slice")
+            .join(format!("docker-{}.scope", container_id)),
+        root.join("docker").join(container_id),
+    ];
+    for dir in v2_dirs {
+        if let Ok(cpu_stat) = fs::read_to_string(dir.join("cpu.stat")) {
+            return CgroupStats {
+                cpu_usec: parse_cpu_stat_usage_usec(&cpu_stat),
+                // memory.peak needs Linux 5.19+; memory.current is the best we can do before that.
+                peak_bytes: read_u64(&dir.join("memory.peak"))
+                    .or_else(|| read_u64(&dir.join("memory.current"))),
+            };
+        }
+    }
+
+    CgroupStats {
+        cpu_usec: read_u64(
+            &root
+                .join("cpuacct/docker")
+                .join(container_id)
+                .join("cpuacct.usage"),
+        )
+        .map(|ns| ns / 1_000),
+        peak_bytes: read_u64(
+            &root
+                .join("memory/docker")
+                .join(container_id)
+                .join("memory.max_usage_in_bytes"),
+        ),
+    }
+}
+
+/// One reading of a running container's cgroup.
+pub(crate) fn read_container_stats(container_id: &str) -> CgroupStats {
+    read_cgroup_stats(Path::new(CGROUP_ROOT), container_id)
+}
+
+/// Samples a container's cgroup until told to stop.
+///
+/// The container's id is read from `cidfile` (written by `docker run --cidfile`). The cgroup
+/// disappears as soon as the container exits, so the last sample taken while it was alive is
+/// what gets reported.
+pub(crate) struct CgroupSampler {
+    stop: Option<oneshot::Sender<()>>,
+    handle: JoinHandle<CgroupStats>,
+}
+
+impl CgroupSampler {
+    pub fn start(cidfile: PathBuf) -> Self {
+        Self::start_at(PathBuf::from(CGROUP_ROOT), cidfile)
+    }
+
+    fn start_at(root: PathBuf, cidfile: PathBuf) -> Self {
+        let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+        let handle = tokio::spawn(async move {
+            let mut stats = CgroupStats::default();
+            let mut container_id: Option<String> = None;
+            loop {
+                if container_id.is_none() {
+                    container_id = fs::read_to_string(&cidfile)
+                        .ok()
+                        .map(|id| id.trim().to_string())
+                        .filter(|id| !id.is_empty());
+                }
+                if let Some(id) = &container_id {
+                    stats.merge(read_cgroup_stats(&root, id));
+                }
+
+                tokio::select! {
+                    _ = &mut stop_rx => break,
+                    _ = tokio::time::sleep(SAMPLE_INTERVAL) => {}
+                }
+            }
+            stats
+        });
+
+        Self {
+            stop: Some(stop_tx),
+            handle,
+        }
+    }
+
+    /// Stops sampling and returns the highest readings seen.
+    pub async fn finish(mut self) -> CgroupStats {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        (&mut self.handle).await.unwrap_or_default()
+    }
+}
+
+impl Drop for CgroupSampler {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_usage_from_cpu_stat() {
+        let text = "usage_usec 123456\nuser_usec 100000\nsystem_usec 23456\n";
+        assert_eq!(parse_cpu_stat_usage_usec(text), Some(123456));
+        assert_eq!(parse_cpu_stat_usage_usec("user_usec 1\n"), None);
+    }
+
+    #[test]
+    fn reads_v2_and_v1_layouts() {
+        let root = tempfile::tempdir().unwrap();
+
+        let v2 = root.path().join("system.slice/docker-abc.scope");
+        fs::create_dir_all(&v2).unwrap();
+        fs::write(v2.join("cpu.stat"), "usage_usec 5000\n").unwrap();
+        fs::write(v2.join("memory.peak"), "1048576\n").unwrap();
+        assert_eq!(
+            read_cgroup_stats(root.path(), "abc"),
+            CgroupStats {
+                cpu_usec: Some(5000),
+                peak_bytes: Some(1_048_576),
+            }
+        );
+
+        let cpu = root.path().join("cpuacct/docker/def");
+        let mem = root.path().join("memory/docker/def");
+        fs::create_dir_all(&cpu).unwrap();
+        fs::create_dir_all(&mem).unwrap();
+        fs::write(cpu.join("cpuacct.usage"), "7000000\n").unwrap();
+        fs::write(mem.join("memory.max_usage_in_bytes"), "2048\n").unwrap();
+        assert_eq!(
+            read_cgroup_stats(root.path(), "def"),
+            CgroupStats {
+                cpu_usec: Some(7000),
+                peak_bytes: Some(2048),
+            }
+        );
+
+        assert_eq!(
+            read_cgroup_stats(root.path(), "missing"),
+            CgroupStats::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn sampler_keeps_readings_after_the_cgroup_is_gone() {
+        let root = tempfile::tempdir().unwrap();
+        let cidfile = root.path().join("run.cid");
+        let cg = root.path().join("docker/xyz");
+        fs::create_dir_all(&cg).unwrap();
+        fs::write(cg.join("cpu.stat"), "usage_usec 9000\n").unwrap();
+        fs::write(cg.join("memory.peak"), "4096\n").unwrap();
+        fs::write(&cidfile, "xyz\n").unwrap();
+
+        let sampler = CgroupSampler::start_at(root.path().to_path_buf(), cidfile);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        fs::remove_dir_all(&cg).unwrap();
+        tokio::time::sleep(SAMPLE_INTERVAL * 2).await;
+
+        let stats = sampler.finish().await;
+        assert_eq!(stats.cpu_usec, Some(9000));
+        assert_eq!(stats.peak_bytes, Some(4096));
+    }
+}
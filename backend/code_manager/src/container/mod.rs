@@ -1,2 +1,7 @@
 //container/mod.rs
 pub mod container;
+pub mod disk;
+pub mod metrics;
+pub mod pool;
+pub mod sandbox;
+pub mod terminal;
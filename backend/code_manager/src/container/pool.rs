@@ -0,0 +1,362 @@
+//container/pool.rs
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tempdir::TempDir;
+use tokio::process::Command;
+use tokio::sync::Notify;
+use util::execution_config::ExecutionConfig;
+
+use super::container::remove_container;
+use super::sandbox::security_opts;
+use crate::metrics::metrics;
+
+/// Label put on every warm container so leftovers from an earlier process can be found.
+const POOL_LABEL: &str = "fitchfork.pool=warm";
+
+/// How often the pool is checked for expired containers when nothing was taken.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Sizing of the warm pool, from `CONTAINER_POOL_*` in the env.
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Idle containers kept ready per image.
+    pub size: usize,
+    /// An idle container older than this is replaced with a fresh one.
+    pub idle_ttl: Duration,
+    pub images: Vec<String>,
+}
+
+/// A started container idling on `sleep infinity`, with its own `/code` and `/output` mounts.
+///
+/// It is created without resource limits and with no network; [`WarmContainer::apply_limits`]
+/// sets the limits of the run that takes it. Seccomp and AppArmor can't be changed once the
+/// container is running, so it only serves runs with the default sandbox. Each one serves a
+/// single run: dropping it removes the container.
+pub struct WarmContainer {
+    pub name: String,
+    pub image: String,
+    /// Full docker id, used to find the container's cgroup.
+    pub id: String,
+    /// `--security-opt` values it was started with.
+    pub security_opts: Vec<String>,
+    code_dir: TempDir,
+    output_dir: TempDir,
+    created: Instant,
+}
+
+impl WarmContainer {
+    fn new(image: &str) -> std::io::Result<Self> {
+        let code_dir = TempDir::new("code")?;
+        let output_dir = TempDir::new("output")?;
+        Ok(Self {
+            name: format!("fitchfork-warm-{}", uuid::Uuid::new_v4()),
+            image: image.to_string(),
+            id: String::new(),
+            security_opts: Vec::new(),
+            code_dir,
+            output_dir,
+            created: Instant::now(),
+        })
+    }
+
+    /// Host directory mounted at `/code`.
+    pub fn code_path(&self) -> &Path {
+        self.code_dir.path()
+    }
+
+    /// Host directory mounted at `/output`.
+    pub fn output_path(&self) -> &Path {
+        self.output_dir.path()
+    }
+
+    fn is_expired(&self, now: Instant, ttl: Duration) -> bool {
+        now.duration_since(self.created) >= ttl
+    }
+
+    async fn start(image: &str) -> Result<Self, String> {
+        let mut warm = Self::new(image).map_err(|e| format!("Failed to create mounts: {}", e))?;
+        warm.security_opts = security_opts(&ExecutionConfig::default_config())?;
+        let output = Command::new("docker")
+            .arg("run")
+            .arg("-d")
+            .arg("--name")
+            .arg(&warm.name)
+            .arg("--label")
+            .arg(POOL_LABEL)
+            .arg("--network=none")
+            .args(
+                warm.security_opts
+                    .iter()
+                    .map(|opt| format!("--security-opt={}", opt)),
+            )
+            .arg("-v")
+            .arg(format!("{}:/code:rw", warm.code_dir.path().display()))
+            .arg("-v")
+            .arg(format!("{}:/output", warm.output_dir.path().display()))
+            .arg(image)
+            .arg("sleep")
+            .arg("infinity")
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run docker: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "docker run failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        warm.id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(warm)
+    }
+
+    /// Applies the run's memory, CPU and process limits, matching what a cold `docker run` gets.
+    pub async fn apply_limits(&self, config: &ExecutionConfig) -> Result<(), String> {
+        let output = Command::new("docker")
+            .arg("update")
+            .arg(format!("--memory={}b", config.execution.max_memory))
+            // `docker run --memory` defaults swap to twice the memory limit; keep that.
+            .arg(format!(
+                "--memory-swap={}b",
+                config.execution.max_memory.saturating_mul(2)
+            ))
+            .arg(format!("--cpus={}", config.execution.max_cpus))
+            .arg(format!("--pids-limit={}", config.execution.max_processes))
+            .arg(&self.name)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run docker: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "docker update failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    /// Kills everything running in the container but keeps it (and its mounts) for the next
+    /// command. Used after a command times out.
+    pub async fn restart(&self) {
+        let _ = Command::new("docker")
+            .arg("restart")
+            .arg("-t")
+            .arg("0")
+            .arg(&self.name)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+    }
+}
+
+impl Drop for WarmContainer {
+    fn drop(&mut self) {
+        // Only containers that were actually started have an id.
+        if self.id.is_empty() {
+            return;
+        }
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let name = self.name.clone();
+            runtime.spawn(async move { remove_container(&name).await });
+        }
+    }
+}
+
+/// Keeps [`PoolConfig::size`] started containers per image so a run can skip container
+/// creation.
+///
+/// Idle containers don't count towards `max_concurrent`; a run only takes one once it holds a
+/// slot. The pool is topped up in the background by [`ContainerPool::spawn_maintenance`].
+pub struct ContainerPool {
+    config: PoolConfig,
+    idle: Mutex<HashMap<String, VecDeque<WarmContainer>>>,
+    refill: Notify,
+}
+
+impl ContainerPool {
+    pub fn new(config: PoolConfig) -> Self {
+        Self {
+            config,
+            idle: Mutex::new(HashMap::new()),
+            refill: Notify::new(),
+        }
+    }
+
+    /// Takes the newest idle container for `image`, if one is ready and was started with
+    /// `security_opts`.
+    pub fn take(&self, image: &str, security_opts: &[String]) -> Option<WarmContainer> {
+        let warm = {
+            let mut idle = self.idle.lock().unwrap();
+            idle.get_mut(image)
+                .filter(|containers| {
+                    containers
+                        .back()
+                        .is_some_and(|warm| warm.security_opts == security_opts)
+                })
+                .and_then(VecDeque::pop_back)
+        };
+        metrics().warm_pool_take(image, warm.is_some());
+        self.refill.notify_one();
+        warm
+    }
+
+    /// Idle containers per configured image.
+    pub fn idle_counts(&self) -> HashMap<String, usize> {
+        let idle = self.idle.lock().unwrap();
+        self.config
+            .images
+            .iter()
+            .map(|image| (image.clone(), idle.get(image).map_or(0, VecDeque::len)))
+            .collect()
+    }
+
+    fn put(&self, warm: WarmContainer) {
+        let mut idle = self.idle.lock().unwrap();
+        idle.entry(warm.image.clone()).or_default().push_back(warm);
+    }
+
+    /// Removes idle containers older than the TTL from the bookkeeping and returns them.
+    fn drain_expired(&self, now: Instant) -> Vec<WarmContainer> {
+        let mut idle = self.idle.lock().unwrap();
+        let mut expired = Vec::new();
+        for containers in idle.values_mut() {
+            // Oldest first, so stop at the first one still inside the TTL.
+            while containers
+                .front()
+                .is_some_and(|warm| warm.is_expired(now, self.config.idle_ttl))
+            {
+                expired.extend(containers.pop_front());
+            }
+        }
+        expired
+    }
+
+    /// How many containers `image` is short of the configured size.
+    fn missing(&self, image: &str) -> usize {
+        let idle = self.idle.lock().unwrap();
+        let have = idle.get(image).map_or(0, VecDeque::len);
+        self.config.size.saturating_sub(have)
+    }
+
+    /// Removes leftover warm containers from an earlier process, then keeps the pool filled
+    /// and recycles containers past the TTL until the process exits.
+    pub fn spawn_maintenance(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            remove_leftovers().await;
+            loop {
+                // Dropping them removes the containers.
+                drop(pool.drain_expired(Instant::now()));
+                for image in &pool.config.images {
+                    for _ in 0..pool.missing(image) {
+                        match WarmContainer::start(image).await {
+                            Ok(warm) => pool.put(warm),
+                            Err(e) => {
+                                tracing::warn!(image, error = %e, "Failed to start warm container");
+                                // Try again on the next pass rather than spinning on docker.
+                                break;
+                            }
+                        }
+                    }
+                }
+                metrics().set_warm_pool(&pool.idle_counts());
+
+                tokio::select! {
+                    _ = pool.refill.notified() => {}
+                    _ = tokio::time::sleep(MAINTENANCE_INTERVAL) => {}
+                }
+            }
+        })
+    }
+}
+
+/// Force-removes every container carrying [`POOL_LABEL`].
+async fn remove_leftovers() {
+    let Ok(output) = Command::new("docker")
+        .arg("ps")
+        .arg("-aq")
+        .arg("--filter")
+        .arg(format!("label={}", POOL_LABEL))
+        .stdin(Stdio::null())
+        .output()
+        .await
+    else {
+        return;
+    };
+    for id in String::from_utf8_lossy(&output.stdout).split_whitespace() {
+        remove_container(id).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(size: usize, ttl: Duration) -> ContainerPool {
+        ContainerPool::new(PoolConfig {
+            size,
+            idle_ttl: ttl,
+            images: vec!["universal-runner".to_string()],
+        })
+    }
+
+    #[test]
+    fn take_hands_out_the_newest_container() {
+        let pool = pool(2, Duration::from_secs(60));
+        assert!(pool.take("universal-runner", &[]).is_none());
+        assert_eq!(pool.missing("universal-runner"), 2);
+
+        let older = WarmContainer::new("universal-runner").unwrap();
+        let newer = WarmContainer::new("universal-runner").unwrap();
+        let newer_name = newer.name.clone();
+        pool.put(older);
+        pool.put(newer);
+        assert_eq!(pool.missing("universal-runner"), 0);
+        assert_eq!(pool.idle_counts()["universal-runner"], 2);
+
+        assert_eq!(pool.take("universal-runner", &[]).unwrap().name, newer_name);
+        assert!(pool.take("other-image", &[]).is_none());
+        assert_eq!(pool.idle_counts()["universal-runner"], 1);
+    }
+
+    #[test]
+    fn take_skips_containers_with_another_sandbox() {
+        let pool = pool(1, Duration::from_secs(60));
+        let mut warm = WarmContainer::new("universal-runner").unwrap();
+        warm.security_opts = vec!["no-new-privileges".to_string()];
+        pool.put(warm);
+
+        let custom = vec![
+            "no-new-privileges".to_string(),
+            "apparmor=fitchfork-runner".to_string(),
+        ];
+        assert!(pool.take("universal-runner", &custom).is_none());
+        assert_eq!(pool.idle_counts()["universal-runner"], 1);
+        assert!(pool
+            .take("universal-runner", &["no-new-privileges".to_string()])
+            .is_some());
+    }
+
+    #[test]
+    fn containers_past_the_ttl_are_drained() {
+        let pool = pool(2, Duration::from_secs(60));
+        let old = WarmContainer::new("universal-runner").unwrap();
+        let old_name = old.name.clone();
+        pool.put(old);
+        pool.put(WarmContainer::new("universal-runner").unwrap());
+
+        assert!(pool.drain_expired(Instant::now()).is_empty());
+
+        let later = Instant::now() + Duration::from_secs(61);
+        let expired = pool.drain_expired(later);
+        assert_eq!(expired.len(), 2);
+        assert_eq!(expired[0].name, old_name);
+        assert_eq!(pool.missing("universal-runner"), 2);
+    }
+}
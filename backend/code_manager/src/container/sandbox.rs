@@ -0,0 +1,113 @@
+//container/sandbox.rs
+use once_cell::sync::OnceCell;
+use std::path::PathBuf;
+use util::config;
+use util::execution_config::{ExecutionConfig, SeccompProfile};
+
+/// Seccomp allowlist used for [`SeccompProfile::Strict`] unless `SECCOMP_PROFILE` replaces it.
+///
+/// It starts from Docker's default profile and additionally blocks `ptrace`, extended
+/// attribute writes, `mknod`, `sync`/`syncfs`, kernel AIO, file handle lookups and clock
+/// adjustments. Namespace-creating `clone` flags are refused and `clone3` reports `ENOSYS` so
+/// libc falls back to `clone`.
+pub const STRICT_SECCOMP_PROFILE: &str = include_str!("../../profiles/seccomp.json");
+
+/// [`STRICT_SECCOMP_PROFILE`] written out once per process; docker reads profiles from a file.
+fn strict_profile_path() -> Result<&'static PathBuf, String> {
+    static PATH: OnceCell<PathBuf> = OnceCell::new();
+    PATH.get_or_try_init(|| {
+        let path =
+            std::env::temp_dir().join(format!("fitchfork-seccomp-{}.json", std::process::id()));
+        std::fs::write(&path, STRICT_SECCOMP_PROFILE)
+            .map_err(|e| format!("Failed to write seccomp profile to {:?}: {}", path, e))?;
+        Ok(path)
+    })
+}
+
+/// `--security-opt` values for a task container running under `config`.
+///
+/// Every container gets `no-new-privileges`. The seccomp profile follows
+/// `security.seccomp_profile`; the AppArmor profile comes from
+/// [`ExecutionConfig::apparmor_profile`] and is left to Docker when neither names one.
+pub fn security_opts(config: &ExecutionConfig) -> Result<Vec<String>, String> {
+    let mut opts = vec!["no-new-privileges".to_string()];
+    match config.security.seccomp_profile {
+        SeccompProfile::Strict => {
+            let profile = match config::seccomp_profile() {
+                Some(custom) => custom,
+                None => strict_profile_path()?.display().to_string(),
+            };
+            opts.push(format!("seccomp={}", profile));
+        }
+        // Docker applies its own default when no profile is given.
+        SeccompProfile::Docker => {}
+    }
+    if let Some(profile) = config.apparmor_profile() {
+        opts.push(format!("apparmor={}", profile));
+    }
+    Ok(opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allowed_syscalls() -> Vec<String> {
+        let profile: serde_json::Value = serde_json::from_str(STRICT_SECCOMP_PROFILE).unwrap();
+        assert_eq!(profile["defaultAction"], "SCMP_ACT_ERRNO");
+        profile["syscalls"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|rule| rule["action"] == "SCMP_ACT_ALLOW")
+            .flat_map(|rule| rule["names"].as_array().unwrap().clone())
+            .map(|name| name.as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn strict_profile_blocks_escape_hatches() {
+        let allowed = allowed_syscalls();
+        for needed in ["execve", "clone", "mmap", "openat", "futex", "wait4"] {
+            assert!(allowed.iter().any(|s| s == needed), "{} is blocked", needed);
+        }
+        for blocked in [
+            "ptrace",
+            "mount",
+            "unshare",
+            "setns",
+            "bpf",
+            "keyctl",
+            "perf_event_open",
+            "kexec_load",
+            "init_module",
+        ] {
+            assert!(
+                !allowed.iter().any(|s| s == blocked),
+                "{} is allowed",
+                blocked
+            );
+        }
+    }
+
+    #[test]
+    fn security_opts_follow_the_assignment() {
+        let mut config = ExecutionConfig::default_config();
+        let opts = security_opts(&config).unwrap();
+        assert_eq!(opts[0], "no-new-privileges");
+        let seccomp = opts[1].strip_prefix("seccomp=").unwrap();
+        if config::seccomp_profile().is_none() {
+            assert_eq!(
+                std::fs::read_to_string(seccomp).unwrap(),
+                STRICT_SECCOMP_PROFILE
+            );
+        }
+
+        config.security.seccomp_profile = SeccompProfile::Docker;
+        config.security.apparmor_profile = Some("fitchfork-runner".to_string());
+        assert_eq!(
+            security_opts(&config).unwrap(),
+            vec!["no-new-privileges", "apparmor=fitchfork-runner"]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//container/terminal.rs
+use std::process::Stdio;
+use std::time::Duration;
+use tempdir::TempDir;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use util::execution_config::{is_valid_image, ExecutionConfig};
+
+use super::container::{remove_container, write_files, OutputChunk, OutputSink};
+use super::disk::DiskQuota;
+use super::sandbox::security_opts;
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// How long output still in the pipes is collected after the shell exits.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Why a terminal session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalEnd {
+    /// The shell exited with this code (`None` if it was killed by a signal).
+    Exited(Option<i32>),
+    /// The input channel closed, i.e. the client went away.
+    Closed,
+    /// The session was open for longer than its time limit.
+    TimeLimit,
+    /// `/code` and `/output` grew past `max_disk_bytes`; holds the bytes in use.
+    OverDiskQuota(u64),
+}
+
+/// An interactive `sh` in a fresh container, for staff debugging an assignment's setup.
+///
+/// The container gets the same limits, sandbox and mounts as a cold run, with the session's
+/// files unpacked in `/code`. There is no TTY: input is passed to the shell's stdin as-is and
+/// stdout/stderr come back as [`OutputChunk`]s (command index 0). Dropping the session removes
+/// the container.
+pub struct TerminalSession {
+    name: String,
+    shell: Child,
+    stdin: ChildStdin,
+    output: UnboundedReceiver<OutputChunk>,
+    code_dir: TempDir,
+    output_dir: TempDir,
+}
+
+impl TerminalSession {
+    /// Starts the container and a shell in `/code`.
+    pub async fn start(
+        config: &ExecutionConfig,
+        files: Vec<(String, Vec<u8>)>,
+    ) -> Result<Self, String> {
+        let image = config.runner_image();
+        if !is_valid_image(&image) {
+            return Err(format!("Invalid runner image: {:?}", image));
+        }
+        let security_opts = security_opts(config)?;
+
+        let code_dir =
+            TempDir::new("code").map_err(|e| format!("Failed to create mounts: {}", e))?;
+        let output_dir =
+            TempDir::new("output").map_err(|e| format!("Failed to create mounts: {}", e))?;
+        write_files(
+            files,
+            config.execution.max_uncompressed_size,
+            code_dir.path(),
+        )
+        .map_err(|e| format!("Failed to write files: {}", e))?;
+
+        let env_args: Vec<String> = config
+            .environment_pairs()
+            .into_iter()
+            .flat_map(|pair| ["-e".to_string(), pair])
+            .collect();
+        let name = format!("fitchfork-terminal-{}", uuid::Uuid::new_v4());
+        let started = Command::new("docker")
+            .arg("run")
+            .arg("-d")
+            .arg("--rm")
+            .arg("--name")
+            .arg(&name)
+            .arg("--network=none")
+            .arg(format!("--memory={}b", config.execution.max_memory))
+            .arg(format!("--cpus={}", config.execution.max_cpus))
+            .arg(format!("--pids-limit={}", config.execution.max_processes))
+            .args(
+                security_opts
+                    .iter()
+                    .map(|opt| format!("--security-opt={}", opt)),
+            )
+            .args(&env_args)
+            .arg("-v")
+            .arg(format!("{}:/code:rw", code_dir.path().display()))
+            .arg("-v")
+            .arg(format!("{}:/output", output_dir.path().display()))
+            .arg(&image)
+            .arg("sleep")
+            .arg("infinity")
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run docker: {}", e))?;
+        if !started.status.success() {
+            return Err(format!(
+                "docker run failed: {}",
+                String::from_utf8_lossy(&started.stderr).trim()
+            ));
+        }
+
+        let shell = Command::new("docker")
+            .arg("exec")
+            .arg("-i")
+            .arg("-w")
+            .arg("/code")
+            .args(&env_args)
+            .arg(&name)
+            .arg("sh")
+            .arg("-i")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn();
+        let mut shell = match shell {
+            Ok(shell) => shell,
+            Err(e) => {
+                remove_container(&name).await;
+                return Err(format!("Failed to start shell: {}", e));
+            }
+        };
+
+        let Some(stdin) = shell.stdin.take() else {
+            remove_container(&name).await;
+            return Err("Shell has no stdin".to_string());
+        };
+        let (sink, output) = mpsc::unbounded_channel();
+        if let Some(stdout) = shell.stdout.take() {
+            forward(stdout, "stdout", sink.clone());
+        }
+        if let Some(stderr) = shell.stderr.take() {
+            forward(stderr, "stderr", sink);
+        }
+
+        Ok(Self {
+            name,
+            shell,
+            stdin,
+            output,
+            code_dir,
+            output_dir,
+        })
+    }
+
+    /// Feeds `input` to the shell and forwards its output to `sink` until the shell exits,
+    /// `input` closes, `limit` passes or the mounts outgrow `max_disk_bytes` (0 for no cap).
+    pub async fn run(
+        mut self,
+        mut input: UnboundedReceiver<Vec<u8>>,
+        sink: OutputSink,
+        limit: Duration,
+        max_disk_bytes: u64,
+    ) -> TerminalEnd {
+        let mut quota = DiskQuota::watch(
+            vec![
+                self.code_dir.path().to_path_buf(),
+                self.output_dir.path().to_path_buf(),
+            ],
+            max_disk_bytes,
+        );
+        let deadline = tokio::time::sleep(limit);
+        tokio::pin!(deadline);
+        let mut output_open = true;
+
+        loop {
+            tokio::select! {
+                chunk = self.output.recv(), if output_open => match chunk {
+                    Some(chunk) => {
+                        let _ = sink.send(chunk);
+                    }
+                    None => output_open = false,
+                },
+                status = self.shell.wait() => {
+                    // Pick up what the shell printed right before exiting.
+                    while let Ok(Some(chunk)) =
+                        tokio::time::timeout(DRAIN_TIMEOUT, self.output.recv()).await
+                    {
+                        let _ = sink.send(chunk);
+                    }
+                    return TerminalEnd::Exited(status.ok().and_then(|s| s.code()));
+                }
+                data = input.recv() => match data {
+                    Some(data) => {
+                        // A failed write means the shell is gone; `wait` reports that.
+                        if self.stdin.write_all(&data).await.is_ok() {
+                            let _ = self.stdin.flush().await;
+                        }
+                    }
+                    None => return TerminalEnd::Closed,
+                },
+                _ = &mut deadline => return TerminalEnd::TimeLimit,
+                used = quota.exceeded() => return TerminalEnd::OverDiskQuota(used),
+            }
+        }
+    }
+}
+
+impl Drop for TerminalSession {
+    fn drop(&mut self) {
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let name = self.name.clone();
+            runtime.spawn(async move { remove_container(&name).await });
+        }
+    }
+}
+
+/// Sends everything read from `reader` to `sink` until the pipe closes.
+fn forward<R>(mut reader: R, stream: &'static str, sink: OutputSink)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut buf = [0u8; READ_BUFFER_SIZE];
+        loop {
+            match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let chunk = OutputChunk {
+                        command: 0,
+                        stream,
+                        data: String::from_utf8_lossy(&buf[..n]).into_owned(),
+                    };
+                    if sink.send(chunk).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
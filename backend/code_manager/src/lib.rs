@@ -1,5 +1,7 @@
 //code_manager/src/lib.rs
 pub mod api;
+pub mod backend;
 pub mod container;
 pub mod manager;
+pub mod metrics;
 pub mod utils;
@@ -1,13 +1,21 @@
 //main.rs
-use axum::{routing::get, Router};
+use axum::{middleware::from_fn_with_state, routing::get, Router};
 use code_manager::api::api::{
-    get_max_concurrent, health, init_manager, run_code, set_max_concurrent, stats,
+    cancel_run, get_job, get_max_concurrent, health, init_manager, list_jobs, manager,
+    prometheus_metrics, run_code, run_code_async, run_code_stream, set_max_concurrent, stats,
 };
+use code_manager::api::auth::{require_token, SharedToken};
+use code_manager::api::grpc::CodeManagerService;
+use code_manager::api::terminal::terminal;
+use code_manager::backend::{DockerBackend, ExecutionBackend, LocalBackend};
+use code_manager::container::pool::{ContainerPool, PoolConfig};
 use dotenv::dotenv;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpListener;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use util::config;
+use util::config::{self, ExecutionBackendKind};
 
 #[tokio::main]
 async fn main() {
@@ -21,17 +29,44 @@ async fn main() {
 
     // Initialize the global ContainerManager
     let max_containers: usize = config::max_number_containers();
-    init_manager(max_containers);
+
+    let backend: Arc<dyn ExecutionBackend> = match config::execution_backend() {
+        ExecutionBackendKind::Docker => Arc::new(DockerBackend::new(start_pool())),
+        ExecutionBackendKind::Local => {
+            tracing::warn!(
+                "EXECUTION_BACKEND=local: commands run as plain processes on this host; \
+                 only use it for trusted code"
+            );
+            Arc::new(LocalBackend)
+        }
+    };
+    init_manager(max_containers, backend);
+
+    // Every route except /health needs the shared token, when one is configured
+    let token: SharedToken = config::code_manager_token().map(Arc::from);
+    if token.is_none() {
+        tracing::warn!(
+            "CODE_MANAGER_TOKEN is not set; code_manager accepts unauthenticated requests"
+        );
+    }
 
     // Build API routes
     let app = Router::new()
-        .route("/health", get(health))
         .route("/run", axum::routing::post(run_code))
+        .route("/run/stream", axum::routing::post(run_code_stream))
+        .route("/run/async", axum::routing::post(run_code_async))
+        .route("/run/{job_id}", axum::routing::delete(cancel_run))
+        .route("/jobs", get(list_jobs))
+        .route("/jobs/{id}", get(get_job))
         .route("/stats", get(stats))
+        .route("/metrics", get(prometheus_metrics))
+        .route("/terminal", get(terminal))
         .route(
             "/max_concurrent",
             get(get_max_concurrent).post(set_max_concurrent),
-        );
+        )
+        .route_layer(from_fn_with_state(token.clone(), require_token))
+        .route("/health", get(health));
 
     // Define address to listen on
     let host = config::code_manager_host();
@@ -41,7 +76,46 @@ async fn main() {
         .expect("Invalid address");
     tracing::info!("Listening on {}", addr);
 
+    // gRPC runs next to the HTTP API on its own port, sharing the same queue
+    let grpc_addr: SocketAddr = format!("{}:{}", host, config::code_manager_grpc_port())
+        .parse()
+        .expect("Invalid gRPC address");
+    let grpc_service = CodeManagerService::new(manager().clone()).into_server(token);
+    tokio::spawn(async move {
+        tracing::info!("Serving gRPC on {}", grpc_addr);
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(grpc_service)
+            .serve(grpc_addr)
+            .await
+        {
+            tracing::error!("gRPC server stopped: {}", e);
+        }
+    });
+
     // Create TCP listener and run server
     let listener = TcpListener::bind(&addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
+
+/// Keeps containers started ahead of time, when a pool size is configured.
+fn start_pool() -> Option<Arc<ContainerPool>> {
+    let pool_size = config::container_pool_size();
+    (pool_size > 0).then(|| {
+        let mut images = config::container_pool_images();
+        if images.is_empty() {
+            // Every image a run can get without an assignment override
+            images.push(config::runner_image());
+            images.extend(config::runner_images().into_values());
+            images.sort();
+            images.dedup();
+        }
+        tracing::info!(size = pool_size, ?images, "Starting warm container pool");
+        let pool = Arc::new(ContainerPool::new(PoolConfig {
+            size: pool_size,
+            idle_ttl: Duration::from_secs(config::container_pool_idle_ttl_secs()),
+            images,
+        }));
+        pool.spawn_maintenance();
+        pool
+    })
+}
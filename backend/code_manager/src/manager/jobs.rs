@@ -0,0 +1,144 @@
+//manager/jobs.rs
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Cancellation flag shared by every run registered under the same job id.
+#[derive(Clone)]
+pub struct CancelToken(watch::Receiver<bool>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the job is cancelled.
+    pub async fn cancelled(&mut self) {
+        // The sender lives as long as any run is registered, so this only errors if the
+        // registry entry vanished underneath us; never treat that as a cancel.
+        if self.0.wait_for(|cancelled| *cancelled).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+struct Job {
+    cancel: watch::Sender<bool>,
+    runs: usize,
+}
+
+/// Tracks in-flight runs by caller-supplied job id so they can be cancelled.
+///
+/// Several runs may share one id (e.g. every task of a submission); cancelling the id stops
+/// all of them. The entry is dropped once its last run finishes.
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+/// A run's membership in a job; unregisters the run when dropped.
+pub struct RegisteredRun {
+    registry: Arc<JobRegistry>,
+    job_id: String,
+    token: CancelToken,
+}
+
+impl RegisteredRun {
+    pub fn token(&self) -> CancelToken {
+        self.token.clone()
+    }
+}
+
+impl Drop for RegisteredRun {
+    fn drop(&mut self) {
+        self.registry.finish(&self.job_id);
+    }
+}
+
+impl JobRegistry {
+    pub fn register(self: &Arc<Self>, job_id: &str) -> RegisteredRun {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.entry(job_id.to_string()).or_insert_with(|| Job {
+            cancel: watch::channel(false).0,
+            runs: 0,
+        });
+        job.runs += 1;
+        let token = CancelToken(job.cancel.subscribe());
+
+        RegisteredRun {
+            registry: Arc::clone(self),
+            job_id: job_id.to_string(),
+            token,
+        }
+    }
+
+    fn finish(&self, job_id: &str) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.get_mut(job_id) {
+            job.runs = job.runs.saturating_sub(1);
+            if job.runs == 0 {
+                jobs.remove(job_id);
+            }
+        }
+    }
+
+    /// Signals every run registered under `job_id`.
+    ///
+    /// Returns how many runs were signalled, or `None` if no run is using that id.
+    pub fn cancel(&self, job_id: &str) -> Option<usize> {
+        let jobs = self.jobs.lock().unwrap();
+        let job = jobs.get(job_id)?;
+        job.cancel.send_replace(true);
+        Some(job.runs)
+    }
+
+    /// Number of distinct job ids with at least one run in flight.
+    pub fn active(&self) -> usize {
+        self.jobs.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_signals_every_run_sharing_the_id() {
+        let registry = Arc::new(JobRegistry::default());
+        let a = registry.register("submission-1");
+        let b = registry.register("submission-1");
+        let other = registry.register("submission-2");
+
+        assert_eq!(registry.cancel("submission-1"), Some(2));
+        assert!(a.token().is_cancelled());
+        assert!(b.token().is_cancelled());
+        assert!(!other.token().is_cancelled());
+    }
+
+    #[test]
+    fn entry_is_dropped_with_its_last_run() {
+        let registry = Arc::new(JobRegistry::default());
+        let a = registry.register("job");
+        let b = registry.register("job");
+
+        drop(a);
+        assert_eq!(registry.active(), 1);
+        drop(b);
+        assert_eq!(registry.active(), 0);
+        assert_eq!(registry.cancel("job"), None);
+    }
+
+    #[tokio::test]
+    async fn cancelled_resolves_after_cancel() {
+        let registry = Arc::new(JobRegistry::default());
+        let run = registry.register("job");
+        let mut token = run.token();
+
+        let waiter = tokio::spawn(async move { token.cancelled().await });
+        registry.cancel("job");
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+            .await
+            .expect("cancelled() should resolve")
+            .unwrap();
+    }
+}
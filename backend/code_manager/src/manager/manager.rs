@@ -1,27 +1,75 @@
 // manager/manager.rs
-use crate::container::container::run_container;
-use crate::manager::queue::Queue;
+use crate::backend::{DockerBackend, ExecutionBackend};
+use crate::container::container::{Collect, ContainerRun, OutputSink, RunCancelled};
+use crate::manager::jobs::JobRegistry;
+use crate::manager::queue::{Priority, Queue, WaitingByPriority};
+use crate::manager::runs::{RunOutcome, RunRecord, RunStatus, RunTracker};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
 use util::execution_config::ExecutionConfig;
 
+/// Optional extras for [`ContainerManager::run_with`].
+#[derive(Default)]
+pub struct RunOptions {
+    /// Receives live stdout/stderr while the commands run.
+    pub sink: Option<OutputSink>,
+    /// Return `/code` as a tar once all commands finished.
+    pub collect_artifacts: bool,
+    /// Return `/output` as a tar once all commands finished.
+    pub collect_output_files: bool,
+    /// Registers the run under this id so it can be stopped with [`ContainerManager::cancel_job`].
+    pub job_id: Option<String>,
+    /// Where the run waits in the queue while all slots are taken.
+    pub priority: Priority,
+    /// Id the run is tracked under in [`ContainerManager::run_status`]; generated when unset.
+    pub run_id: Option<String>,
+    /// Keep the outputs in the run's status record once it finishes.
+    pub keep_result: bool,
+}
+
+type RunError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+fn new_run_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
 pub struct ContainerManager {
     queue: Arc<Mutex<Queue>>,
+    jobs: Arc<JobRegistry>,
+    runs: Arc<RunTracker>,
+    backend: Arc<dyn ExecutionBackend>,
 }
 
 impl ContainerManager {
     pub fn new(max_concurrent: usize) -> Self {
         Self {
             queue: Arc::new(Mutex::new(Queue::new(max_concurrent))),
+            jobs: Arc::new(JobRegistry::default()),
+            runs: Arc::new(RunTracker::default()),
+            backend: Arc::new(DockerBackend::default()),
         }
     }
 
+    /// Runs go to `backend` instead of a [`DockerBackend`] without a warm pool.
+    pub fn with_backend(mut self, backend: Arc<dyn ExecutionBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Name of the backend runs go to, e.g. `"docker"`.
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
     #[allow(dead_code)]
     pub fn clone(&self) -> Self {
         Self {
             queue: Arc::clone(&self.queue),
+            jobs: Arc::clone(&self.jobs),
+            runs: Arc::clone(&self.runs),
+            backend: Arc::clone(&self.backend),
         }
     }
 
@@ -33,19 +81,153 @@ impl ContainerManager {
         files: Vec<(String, Vec<u8>)>,
         interpreter: bool,
     ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        self.run_streaming(config, commands, files, interpreter, None)
+            .await
+    }
+
+    /// Like [`ContainerManager::run`], forwarding live output to `sink` while the container runs.
+    pub async fn run_streaming(
+        &self,
+        config: &ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        sink: Option<OutputSink>,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        let options = RunOptions {
+            sink,
+            ..Default::default()
+        };
+        self.run_with(config, commands, files, interpreter, options)
+            .await
+            .map(|run| run.outputs)
+    }
+
+    /// Queued run that can also stream output, return the built `/code` directory and/or be
+    /// cancelled through its job id (while queued or while running).
+    ///
+    /// Every run is tracked from queued to finished; see [`ContainerManager::run_status`].
+    pub async fn run_with(
+        &self,
+        config: &ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        mut options: RunOptions,
+    ) -> Result<ContainerRun, RunError> {
+        let run_id = options.run_id.take().unwrap_or_else(new_run_id);
+        let keep_result = options.keep_result;
+        self.runs
+            .queued(&run_id, options.job_id.as_deref(), options.priority);
+
+        let result = self
+            .run_queued(&run_id, config, commands, files, interpreter, options)
+            .await;
+
+        let (status, error) = match &result {
+            Ok(_) => (RunStatus::Completed, None),
+            Err(e) if e.is::<RunCancelled>() => (RunStatus::Cancelled, Some(e.to_string())),
+            Err(e) => (RunStatus::Failed, Some(e.to_string())),
+        };
+        let outcome = match &result {
+            Ok(run) if keep_result => Some(RunOutcome {
+                output: run.outputs.clone(),
+                metrics: run.metrics.clone(),
+            }),
+            _ => None,
+        };
+        self.runs.finished(&run_id, status, error, outcome);
+
+        result
+    }
+
+    /// Queues a run in the background and returns its id straight away. Its status, and its
+    /// outputs once it finished, are available from [`ContainerManager::run_status`].
+    pub fn submit(
+        &self,
+        config: ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        mut options: RunOptions,
+    ) -> String {
+        let run_id = options.run_id.get_or_insert_with(new_run_id).clone();
+        options.keep_result = true;
+        // Recorded before returning so the id can be polled right away.
+        self.runs
+            .queued(&run_id, options.job_id.as_deref(), options.priority);
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = manager
+                .run_with(&config, commands, files, interpreter, options)
+                .await
+            {
+                tracing::warn!(error = %e, "Async run failed");
+            }
+        });
+        run_id
+    }
+
+    async fn run_queued(
+        &self,
+        run_id: &str,
+        config: &ExecutionConfig,
+        commands: Vec<String>,
+        files: Vec<(String, Vec<u8>)>,
+        interpreter: bool,
+        options: RunOptions,
+    ) -> Result<ContainerRun, RunError> {
+        let registered = options.job_id.as_deref().map(|id| self.jobs.register(id));
+        let cancel = registered.as_ref().map(|run| run.token());
+
         let maybe_notify = {
             let mut queue = self.queue.lock().await;
-            queue.try_acquire_slot()
+            queue.try_acquire_slot(options.priority)
         };
 
         if let Some(notify) = maybe_notify {
-            notify.notified().await;
+            match cancel.clone() {
+                Some(mut token) => {
+                    tokio::select! {
+                        _ = notify.notified() => {}
+                        _ = token.cancelled() => {
+                            let mut queue = self.queue.lock().await;
+                            if !queue.remove_waiting(&notify) {
+                                // Handed a slot just as we were cancelled; give it back.
+                                queue.release_slot();
+                            }
+                            return Err(Box::new(RunCancelled));
+                        }
+                    }
+                }
+                None => notify.notified().await,
+            }
         }
+        self.runs.started(run_id);
 
-        tracing::info!("Running container with commands: {:?}", commands);
+        tracing::info!(
+            backend = self.backend.name(),
+            "Running commands: {:?}",
+            commands
+        );
 
-        // Actually run the container
-        let result = run_container(config, commands, files, interpreter).await;
+        let result = self
+            .backend
+            .run(
+                config,
+                commands,
+                files,
+                interpreter,
+                options.sink,
+                Collect {
+                    artifacts: options.collect_artifacts,
+                    output_files: options.collect_output_files,
+                },
+                cancel,
+                options.job_id.as_deref(),
+            )
+            .await;
 
         // Release slot after run finishes
         {
@@ -56,6 +238,25 @@ impl ContainerManager {
         result
     }
 
+    /// Waits for a container slot at `priority`, for work that manages its own container (e.g.
+    /// an interactive terminal). The slot must be handed back with
+    /// [`ContainerManager::release_slot`].
+    pub async fn acquire_slot(&self, priority: Priority) {
+        let maybe_notify = {
+            let mut queue = self.queue.lock().await;
+            queue.try_acquire_slot(priority)
+        };
+        if let Some(notify) = maybe_notify {
+            notify.notified().await;
+        }
+    }
+
+    /// Gives back a slot taken with [`ContainerManager::acquire_slot`].
+    pub async fn release_slot(&self) {
+        let mut queue = self.queue.lock().await;
+        queue.release_slot();
+    }
+
     /// A mock run method specifically for testing concurrency.
     #[allow(dead_code)]
     pub async fn run_mock(
@@ -67,7 +268,7 @@ impl ContainerManager {
     ) -> String {
         let maybe_notify = {
             let mut queue = self.queue.lock().await;
-            queue.try_acquire_slot()
+            queue.try_acquire_slot(Priority::Normal)
         };
 
         if let Some(notify) = maybe_notify {
@@ -92,11 +293,37 @@ impl ContainerManager {
         )
     }
 
+    /// Cancels every queued or running run registered under `job_id`.
+    ///
+    /// Returns the number of runs signalled, or `None` if nothing is using that id.
+    pub fn cancel_job(&self, job_id: &str) -> Option<usize> {
+        let cancelled = self.jobs.cancel(job_id);
+        if let Some(runs) = cancelled {
+            tracing::info!(job_id, runs, "Cancelling job");
+        }
+        cancelled
+    }
+
     pub async fn get_stats(&self) -> (usize, usize, usize) {
         let q = self.queue.lock().await;
         q.stats()
     }
 
+    /// Status of a queued, running or recently finished run.
+    pub fn run_status(&self, run_id: &str) -> Option<RunRecord> {
+        self.runs.get(run_id)
+    }
+
+    /// Every tracked run, newest first, optionally only those with `status`.
+    pub fn list_runs(&self, status: Option<RunStatus>) -> Vec<RunRecord> {
+        self.runs.list(status)
+    }
+
+    pub async fn get_waiting_by_priority(&self) -> WaitingByPriority {
+        let q = self.queue.lock().await;
+        q.waiting_by_priority()
+    }
+
     pub async fn set_max_concurrent(&self, new_max: usize) {
         let mut q = self.queue.lock().await;
         q.set_max_concurrent(new_max);
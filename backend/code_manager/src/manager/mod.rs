@@ -1,3 +1,5 @@
 //manager/mod.rs
+pub mod jobs;
 pub mod manager;
 pub mod queue;
+pub mod runs;
@@ -1,12 +1,41 @@
 //manager/queue.rs
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::Arc;
 use tokio::sync::Notify;
 
+/// Scheduling class of a run. Waiting runs are started strictly in this order (FIFO within a
+/// class); runs that already hold a slot are never interrupted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// A student waiting on their own submission.
+    Interactive,
+    #[default]
+    Normal,
+    /// Remarks, resubmits and GA runs.
+    Bulk,
+}
+
+impl Priority {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Number of runs waiting for a slot, per [`Priority`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WaitingByPriority {
+    pub interactive: usize,
+    pub normal: usize,
+    pub bulk: usize,
+}
+
 pub struct Queue {
     max_concurrent: usize,
     running: usize,
-    waiting: VecDeque<Arc<Notify>>,
+    /// One FIFO per priority, indexed by [`Priority::index`].
+    waiting: [VecDeque<Arc<Notify>>; 3],
 }
 
 impl Queue {
@@ -14,36 +43,73 @@ impl Queue {
         Self {
             max_concurrent,
             running: 0,
-            waiting: VecDeque::new(),
+            waiting: Default::default(),
         }
     }
 
     /// This methods is called when a job begins
-    /// It tries to aquire a slot, if it cannot it waits
-    pub fn try_acquire_slot(&mut self) -> Option<Arc<Notify>> {
+    /// It tries to aquire a slot, if it cannot it waits behind every job of the same or
+    /// higher priority
+    pub fn try_acquire_slot(&mut self, priority: Priority) -> Option<Arc<Notify>> {
         if self.running < self.max_concurrent {
             self.running += 1;
             None // Run instantly
         } else {
             let notify = Arc::new(Notify::new());
-            self.waiting.push_back(notify.clone());
+            self.waiting[priority.index()].push_back(notify.clone());
             Some(notify)
         }
     }
 
+    /// Hands a slot to the highest-priority waiter, if any.
+    fn wake_next(&mut self) -> bool {
+        let next = self
+            .waiting
+            .iter_mut()
+            .find_map(|waiting| waiting.pop_front());
+        match next {
+            Some(waiting_task) => {
+                self.running += 1;
+                waiting_task.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
     /// This method is called when a job completes
     pub fn release_slot(&mut self) {
         self.running = self.running.saturating_sub(1);
+        self.wake_next();
+    }
 
-        if let Some(waiting_task) = self.waiting.pop_front() {
-            self.running += 1;
-            waiting_task.notify_one();
+    /// Drops a waiter that gave up before being woken.
+    ///
+    /// Returns false if it was already handed a slot, in which case the caller owns that
+    /// slot and must release it.
+    pub fn remove_waiting(&mut self, notify: &Arc<Notify>) -> bool {
+        for waiting in &mut self.waiting {
+            if let Some(pos) = waiting.iter().position(|n| Arc::ptr_eq(n, notify)) {
+                waiting.remove(pos);
+                return true;
+            }
         }
+        false
     }
 
     /// Returns current queue statistics
     pub fn stats(&self) -> (usize, usize, usize) {
-        (self.running, self.waiting.len(), self.max_concurrent)
+        let waiting = self.waiting.iter().map(VecDeque::len).sum();
+        (self.running, waiting, self.max_concurrent)
+    }
+
+    /// Returns how many jobs are waiting at each priority
+    pub fn waiting_by_priority(&self) -> WaitingByPriority {
+        WaitingByPriority {
+            interactive: self.waiting[Priority::Interactive.index()].len(),
+            normal: self.waiting[Priority::Normal.index()].len(),
+            bulk: self.waiting[Priority::Bulk.index()].len(),
+        }
     }
 
     /// Updates the maximum concurrent slots allowed. If the new limit is higher
@@ -52,13 +118,66 @@ impl Queue {
     pub fn set_max_concurrent(&mut self, new_max: usize) {
         self.max_concurrent = new_max.max(1);
         // Wake up waiting tasks if we have spare capacity now
-        while self.running < self.max_concurrent {
-            if let Some(waiting_task) = self.waiting.pop_front() {
-                self.running += 1;
-                waiting_task.notify_one();
-            } else {
-                break;
+        while self.running < self.max_concurrent && self.wake_next() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn higher_priority_waiters_are_woken_first() {
+        let mut queue = Queue::new(1);
+        assert!(queue.try_acquire_slot(Priority::Bulk).is_none());
+
+        let bulk = queue.try_acquire_slot(Priority::Bulk).unwrap();
+        let normal = queue.try_acquire_slot(Priority::Normal).unwrap();
+        let first = queue.try_acquire_slot(Priority::Interactive).unwrap();
+        let second = queue.try_acquire_slot(Priority::Interactive).unwrap();
+        assert_eq!(
+            queue.waiting_by_priority(),
+            WaitingByPriority {
+                interactive: 2,
+                normal: 1,
+                bulk: 1,
             }
+        );
+        assert_eq!(queue.stats(), (1, 4, 1));
+
+        let order = [&first, &second, &normal, &bulk];
+        for (i, expected) in order.iter().enumerate() {
+            queue.release_slot();
+            // A waiter leaves the queue when it is handed the slot.
+            assert!(
+                !queue.remove_waiting(expected),
+                "waiter {} was not woken",
+                i
+            );
         }
+        assert_eq!(queue.stats(), (1, 0, 1));
+    }
+
+    #[test]
+    fn raising_the_limit_wakes_by_priority() {
+        let mut queue = Queue::new(1);
+        queue.try_acquire_slot(Priority::Normal);
+        let bulk = queue.try_acquire_slot(Priority::Bulk).unwrap();
+        let interactive = queue.try_acquire_slot(Priority::Interactive).unwrap();
+
+        queue.set_max_concurrent(2);
+        assert!(!queue.remove_waiting(&interactive));
+        assert!(queue.remove_waiting(&bulk));
+        assert_eq!(queue.stats(), (2, 0, 2));
+    }
+
+    #[test]
+    fn priority_defaults_to_normal_and_uses_lowercase_names() {
+        assert_eq!(Priority::default(), Priority::Normal);
+        assert_eq!(
+            serde_json::from_str::<Priority>("\"interactive\"").unwrap(),
+            Priority::Interactive
+        );
+        assert_eq!(serde_json::to_string(&Priority::Bulk).unwrap(), "\"bulk\"");
     }
 }
@@ -0,0 +1,247 @@
+//manager/runs.rs
+use crate::container::metrics::CommandMetrics;
+use crate::manager::queue::Priority;
+use crate::metrics::metrics;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// How many finished runs are remembered for `/jobs` before the oldest are dropped.
+pub const MAX_FINISHED_RUNS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl RunStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            RunStatus::Completed | RunStatus::Failed | RunStatus::Cancelled
+        )
+    }
+}
+
+/// Outputs kept for runs submitted through `/run/async`, in the same shape as `/run`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOutcome {
+    pub output: Vec<String>,
+    pub metrics: Vec<CommandMetrics>,
+}
+
+/// Status of one run as reported by `/jobs`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRecord {
+    pub id: String,
+    /// Caller-chosen id used by `DELETE /run/{job_id}`, if any.
+    pub job_id: Option<String>,
+    pub priority: Priority,
+    pub status: RunStatus,
+    pub submitted_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// Time since the run got a slot: up to now while running, up to the end once finished.
+    pub duration_ms: Option<u64>,
+    /// Why the run failed or was cancelled.
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<RunOutcome>,
+}
+
+impl RunRecord {
+    /// Fills in `duration_ms` for a run that is still going.
+    fn snapshot(&self) -> RunRecord {
+        let mut record = self.clone();
+        if record.finished_at.is_none() {
+            record.duration_ms = record.started_at.map(|start| elapsed_ms(start, Utc::now()));
+        }
+        record
+    }
+}
+
+fn elapsed_ms(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    (to - from).num_milliseconds().max(0) as u64
+}
+
+#[derive(Default)]
+struct Runs {
+    records: HashMap<String, RunRecord>,
+    /// Ids of finished runs, oldest first.
+    finished: VecDeque<String>,
+}
+
+/// Status of every queued or running run, plus the last [`MAX_FINISHED_RUNS`] finished ones.
+///
+/// Lives in memory only, so history starts over when code_manager restarts.
+#[derive(Default)]
+pub struct RunTracker {
+    runs: Mutex<Runs>,
+}
+
+impl RunTracker {
+    /// Records a new run as queued. Does nothing if `id` is already known.
+    pub fn queued(&self, id: &str, job_id: Option<&str>, priority: Priority) {
+        let mut runs = self.runs.lock().unwrap();
+        runs.records
+            .entry(id.to_string())
+            .or_insert_with(|| RunRecord {
+                id: id.to_string(),
+                job_id: job_id.map(str::to_string),
+                priority,
+                status: RunStatus::Queued,
+                submitted_at: Utc::now(),
+                started_at: None,
+                finished_at: None,
+                duration_ms: None,
+                error: None,
+                result: None,
+            });
+    }
+
+    /// Marks a queued run as holding a slot.
+    pub fn started(&self, id: &str) {
+        let mut runs = self.runs.lock().unwrap();
+        if let Some(record) = runs.records.get_mut(id) {
+            let now = Utc::now();
+            record.status = RunStatus::Running;
+            record.started_at = Some(now);
+            let waited = (now - record.submitted_at).to_std().unwrap_or_default();
+            metrics().run_started(record.priority, waited);
+        }
+    }
+
+    /// Marks a run as finished with `status`, evicting the oldest finished runs past the limit.
+    pub fn finished(
+        &self,
+        id: &str,
+        status: RunStatus,
+        error: Option<String>,
+        result: Option<RunOutcome>,
+    ) {
+        let mut runs = self.runs.lock().unwrap();
+        let Some(record) = runs.records.get_mut(id) else {
+            return;
+        };
+        if record.status.is_finished() {
+            return;
+        }
+        let now = Utc::now();
+        record.status = status;
+        record.finished_at = Some(now);
+        record.duration_ms = record.started_at.map(|start| elapsed_ms(start, now));
+        record.error = error;
+        record.result = result;
+        let ran_for = record
+            .started_at
+            .map(|start| (now - start).to_std().unwrap_or_default());
+        metrics().run_finished(status, ran_for);
+
+        runs.finished.push_back(id.to_string());
+        while runs.finished.len() > MAX_FINISHED_RUNS {
+            if let Some(old) = runs.finished.pop_front() {
+                runs.records.remove(&old);
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<RunRecord> {
+        let runs = self.runs.lock().unwrap();
+        runs.records.get(id).map(RunRecord::snapshot)
+    }
+
+    /// All known runs, newest first, optionally only those with `status`. Outputs are left
+    /// out; fetch a single run for those.
+    pub fn list(&self, status: Option<RunStatus>) -> Vec<RunRecord> {
+        let runs = self.runs.lock().unwrap();
+        let mut records: Vec<RunRecord> = runs
+            .records
+            .values()
+            .filter(|record| status.is_none_or(|s| record.status == s))
+            .map(|record| RunRecord {
+                result: None,
+                ..record.snapshot()
+            })
+            .collect();
+        records.sort_by_key(|record| std::cmp::Reverse(record.submitted_at));
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_each_status_transition() {
+        let tracker = RunTracker::default();
+        tracker.queued("a", Some("submission-1"), Priority::Interactive);
+        let queued = tracker.get("a").unwrap();
+        assert_eq!(queued.status, RunStatus::Queued);
+        assert_eq!(queued.job_id.as_deref(), Some("submission-1"));
+        assert!(queued.started_at.is_none());
+        assert!(queued.duration_ms.is_none());
+
+        tracker.started("a");
+        let running = tracker.get("a").unwrap();
+        assert_eq!(running.status, RunStatus::Running);
+        assert!(running.duration_ms.is_some());
+
+        let outcome = RunOutcome {
+            output: vec!["hi".to_string()],
+            metrics: Vec::new(),
+        };
+        tracker.finished("a", RunStatus::Completed, None, Some(outcome));
+        let done = tracker.get("a").unwrap();
+        assert_eq!(done.status, RunStatus::Completed);
+        assert!(done.finished_at.is_some());
+        assert_eq!(done.result.unwrap().output, vec!["hi".to_string()]);
+
+        // A finished run can't be finished again.
+        tracker.finished("a", RunStatus::Failed, Some("late".to_string()), None);
+        assert_eq!(tracker.get("a").unwrap().status, RunStatus::Completed);
+    }
+
+    #[test]
+    fn list_filters_by_status_and_leaves_out_results() {
+        let tracker = RunTracker::default();
+        tracker.queued("a", None, Priority::Normal);
+        tracker.queued("b", None, Priority::Bulk);
+        tracker.started("b");
+        let outcome = RunOutcome {
+            output: vec!["x".to_string()],
+            metrics: Vec::new(),
+        };
+        tracker.finished("b", RunStatus::Completed, None, Some(outcome));
+
+        assert_eq!(tracker.list(None).len(), 2);
+        let completed = tracker.list(Some(RunStatus::Completed));
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].id, "b");
+        assert!(completed[0].result.is_none());
+        assert_eq!(tracker.list(Some(RunStatus::Queued))[0].id, "a");
+    }
+
+    #[test]
+    fn oldest_finished_runs_are_evicted() {
+        let tracker = RunTracker::default();
+        tracker.queued("pending", None, Priority::Normal);
+        for i in 0..=MAX_FINISHED_RUNS {
+            let id = format!("run-{}", i);
+            tracker.queued(&id, None, Priority::Normal);
+            tracker.finished(&id, RunStatus::Failed, Some("boom".to_string()), None);
+        }
+
+        assert!(tracker.get("run-0").is_none());
+        assert!(tracker.get("run-1").is_some());
+        // Unfinished runs are never evicted.
+        assert!(tracker.get("pending").is_some());
+        assert_eq!(tracker.list(None).len(), MAX_FINISHED_RUNS + 1);
+    }
+}
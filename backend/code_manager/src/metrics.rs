@@ -0,0 +1,249 @@
+//metrics.rs
+use crate::manager::queue::{Priority, WaitingByPriority};
+use crate::manager::runs::RunStatus;
+use once_cell::sync::Lazy;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Bucket bounds in seconds, from a quick `echo` up to a slow GA generation.
+const DURATION_BUCKETS: &[f64] = &[
+    0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0,
+];
+
+/// Prometheus metrics served on `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    runs_started: IntCounterVec,
+    runs_finished: IntCounterVec,
+    run_duration: HistogramVec,
+    queue_wait: HistogramVec,
+    running: IntGauge,
+    waiting: IntGaugeVec,
+    max_concurrent: IntGauge,
+    warm_containers: IntGaugeVec,
+    warm_pool_takes: IntCounterVec,
+}
+
+static METRICS: Lazy<Metrics> = Lazy::new(Metrics::new);
+
+pub fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+fn priority_label(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Interactive => "interactive",
+        Priority::Normal => "normal",
+        Priority::Bulk => "bulk",
+    }
+}
+
+fn status_label(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Queued => "queued",
+        RunStatus::Running => "running",
+        RunStatus::Completed => "completed",
+        RunStatus::Failed => "failed",
+        RunStatus::Cancelled => "cancelled",
+    }
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let runs_started = IntCounterVec::new(
+            Opts::new(
+                "code_manager_runs_started_total",
+                "Runs that got a container slot",
+            ),
+            &["priority"],
+        )
+        .unwrap();
+        let runs_finished = IntCounterVec::new(
+            Opts::new(
+                "code_manager_runs_finished_total",
+                "Runs that finished, by outcome",
+            ),
+            &["status"],
+        )
+        .unwrap();
+        let run_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "code_manager_run_duration_seconds",
+                "Time from getting a slot to finishing",
+            )
+            .buckets(DURATION_BUCKETS.to_vec()),
+            &["status"],
+        )
+        .unwrap();
+        let queue_wait = HistogramVec::new(
+            HistogramOpts::new(
+                "code_manager_queue_wait_seconds",
+                "Time spent waiting for a slot",
+            )
+            .buckets(DURATION_BUCKETS.to_vec()),
+            &["priority"],
+        )
+        .unwrap();
+        let running =
+            IntGauge::new("code_manager_running", "Runs currently holding a slot").unwrap();
+        let waiting = IntGaugeVec::new(
+            Opts::new("code_manager_queue_waiting", "Runs waiting for a slot"),
+            &["priority"],
+        )
+        .unwrap();
+        let max_concurrent = IntGauge::new(
+            "code_manager_max_concurrent",
+            "Configured number of container slots",
+        )
+        .unwrap();
+
+        let warm_containers = IntGaugeVec::new(
+            Opts::new(
+                "code_manager_warm_containers",
+                "Idle pre-started containers, by image",
+            ),
+            &["image"],
+        )
+        .unwrap();
+        let warm_pool_takes = IntCounterVec::new(
+            Opts::new(
+                "code_manager_warm_pool_takes_total",
+                "Runs that asked the warm pool for a container, by whether one was ready",
+            ),
+            &["image", "result"],
+        )
+        .unwrap();
+
+        let registry = Registry::new();
+        registry.register(Box::new(runs_started.clone())).unwrap();
+        registry.register(Box::new(runs_finished.clone())).unwrap();
+        registry.register(Box::new(run_duration.clone())).unwrap();
+        registry.register(Box::new(queue_wait.clone())).unwrap();
+        registry.register(Box::new(running.clone())).unwrap();
+        registry.register(Box::new(waiting.clone())).unwrap();
+        registry.register(Box::new(max_concurrent.clone())).unwrap();
+        registry
+            .register(Box::new(warm_containers.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(warm_pool_takes.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            runs_started,
+            runs_finished,
+            run_duration,
+            queue_wait,
+            running,
+            waiting,
+            max_concurrent,
+            warm_containers,
+            warm_pool_takes,
+        }
+    }
+
+    /// A run got a slot after waiting `waited` in the queue.
+    pub fn run_started(&self, priority: Priority, waited: Duration) {
+        let label = priority_label(priority);
+        self.runs_started.with_label_values(&[label]).inc();
+        self.queue_wait
+            .with_label_values(&[label])
+            .observe(waited.as_secs_f64());
+    }
+
+    /// A run finished with `status`; `ran_for` is `None` if it never got a slot.
+    pub fn run_finished(&self, status: RunStatus, ran_for: Option<Duration>) {
+        let label = status_label(status);
+        self.runs_finished.with_label_values(&[label]).inc();
+        if let Some(ran_for) = ran_for {
+            self.run_duration
+                .with_label_values(&[label])
+                .observe(ran_for.as_secs_f64());
+        }
+    }
+
+    /// Copies the queue counters into the gauges; called right before each scrape.
+    pub fn set_queue(&self, running: usize, waiting: WaitingByPriority, max_concurrent: usize) {
+        self.running.set(running as i64);
+        self.max_concurrent.set(max_concurrent as i64);
+        for (priority, count) in [
+            (Priority::Interactive, waiting.interactive),
+            (Priority::Normal, waiting.normal),
+            (Priority::Bulk, waiting.bulk),
+        ] {
+            self.waiting
+                .with_label_values(&[priority_label(priority)])
+                .set(count as i64);
+        }
+    }
+
+    /// A run asked the warm pool for an `image` container; `hit` if one was ready.
+    pub fn warm_pool_take(&self, image: &str, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.warm_pool_takes
+            .with_label_values(&[image, result])
+            .inc();
+    }
+
+    /// Idle warm containers per image, after the pool was topped up.
+    pub fn set_warm_pool(&self, idle: &HashMap<String, usize>) {
+        for (image, count) in idle {
+            self.warm_containers
+                .with_label_values(&[image.as_str()])
+                .set(*count as i64);
+        }
+    }
+
+    /// Everything in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_runs_and_queue_depth() {
+        let metrics = Metrics::new();
+        metrics.run_started(Priority::Interactive, Duration::from_millis(250));
+        metrics.run_finished(RunStatus::Completed, Some(Duration::from_secs(3)));
+        metrics.run_finished(RunStatus::Cancelled, None);
+        metrics.set_queue(
+            2,
+            WaitingByPriority {
+                interactive: 1,
+                normal: 0,
+                bulk: 4,
+            },
+            3,
+        );
+        metrics.warm_pool_take("universal-runner", false);
+        metrics.set_warm_pool(&HashMap::from([("universal-runner".to_string(), 2)]));
+
+        let text = metrics.render();
+        assert!(text.contains("code_manager_runs_started_total{priority=\"interactive\"} 1"));
+        assert!(text.contains("code_manager_runs_finished_total{status=\"completed\"} 1"));
+        assert!(text.contains("code_manager_runs_finished_total{status=\"cancelled\"} 1"));
+        assert!(text.contains("code_manager_run_duration_seconds_count{status=\"completed\"} 1"));
+        assert!(!text.contains("code_manager_run_duration_seconds_count{status=\"cancelled\"}"));
+        assert!(text.contains("code_manager_queue_wait_seconds_sum{priority=\"interactive\"} 0.25"));
+        assert!(text.contains("code_manager_running 2"));
+        assert!(text.contains("code_manager_queue_waiting{priority=\"bulk\"} 4"));
+        assert!(text.contains("code_manager_max_concurrent 3"));
+        assert!(text.contains(
+            "code_manager_warm_pool_takes_total{image=\"universal-runner\",result=\"miss\"} 1"
+        ));
+        assert!(text.contains("code_manager_warm_containers{image=\"universal-runner\"} 2"));
+    }
+}
@@ -2,11 +2,11 @@
 use flate2::read::GzDecoder;
 use std::fs::{self, File};
 use std::io::{Cursor, Read};
-use std::path::Path;
+use std::path::{Component, Path};
 use tar::Archive;
 use zip::read::ZipArchive;
 
-/// Extracts supported archives (`.zip`, `.tar`, `.gz`, `.tgz`) into the destination directory.
+/// Extracts supported archives (`.zip`, `.tar`, `.gz`, `.tgz`, `.7z`, `.rar`) into the destination directory.
 pub fn extract_archive_contents(
     file_path: &Path,
     archive_bytes: &[u8],
@@ -29,15 +29,30 @@ pub fn extract_archive_contents(
             }
         }
         Some("tgz") => extract_tgz(archive_bytes, max_uncompressed_size, destination_dir),
+        Some("7z") => extract_7z(archive_bytes, max_uncompressed_size, destination_dir),
+        Some("rar") => extract_rar(archive_bytes, max_uncompressed_size, destination_dir),
         Some(ext) => Err(format!("Unsupported archive type: .{}", ext).into()),
         None => Err("File has no extension".into()),
     }
 }
 
+/// Packs every file under `source_dir` into an uncompressed tar (paths relative to the dir).
+///
+/// Tar keeps modification times, so build outputs packed here stay newer than their sources
+/// once extracted again, and `make` won't rebuild them.
+pub fn pack_directory_tar(
+    source_dir: &Path,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", source_dir)?;
+    Ok(builder.into_inner()?)
+}
+
 /// Checks if the file has a supported archive extension
 pub fn is_supported_archive(path: &Path) -> bool {
     match path.extension().and_then(|ext| ext.to_str()) {
-        Some("zip") | Some("tar") | Some("gz") | Some("tgz") => true,
+        Some("zip") | Some("tar") | Some("gz") | Some("tgz") | Some("7z") | Some("rar") => true,
         _ => false,
     }
 }
@@ -135,6 +150,77 @@ fn extract_gz(
     Ok(())
 }
 
+/// 7z extraction
+fn extract_7z(
+    archive_bytes: &[u8],
+    max_uncompressed_size: u64,
+    destination_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let mut archive = sevenz_rust::SevenZReader::new(
+        Cursor::new(archive_bytes),
+        archive_bytes.len() as u64,
+        sevenz_rust::Password::empty(),
+    )?;
+
+    // Sizes are in the archive header, so check them before decompressing anything
+    let total_size: u64 = archive.archive().files.iter().map(|f| f.size()).sum();
+    if total_size > max_uncompressed_size {
+        return Err("Uncompressed 7z size exceeds allowed maximum".into());
+    }
+
+    archive.for_each_entries(|entry, reader| {
+        let name = Path::new(entry.name());
+        if name.is_absolute() || name.components().any(|c| c == Component::ParentDir) {
+            return Err(sevenz_rust::Error::other(
+                "7z archive contains invalid path (zip slip attack?)",
+            ));
+        }
+
+        let outpath = destination_dir.join(name);
+        if entry.is_directory() {
+            fs::create_dir_all(&outpath)?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                fs::create_dir_all(p)?;
+            }
+            let mut outfile = File::create(&outpath)?;
+            std::io::copy(reader, &mut outfile)?;
+        }
+        Ok(true)
+    })?;
+
+    Ok(())
+}
+
+/// RAR extraction (via `bsdtar`, see [`util::rar::extract_rar`])
+fn extract_rar(
+    archive_bytes: &[u8],
+    max_uncompressed_size: u64,
+    destination_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    util::rar::extract_rar(archive_bytes, destination_dir)?;
+
+    // bsdtar can't enforce a size cap up front, so check what it wrote
+    if dir_size(destination_dir)? > max_uncompressed_size {
+        return Err("Uncompressed rar size exceeds allowed maximum".into());
+    }
+    Ok(())
+}
+
+fn dir_size(dir: &Path) -> std::io::Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        total += if meta.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            meta.len()
+        };
+    }
+    Ok(total)
+}
+
 fn validate_tar_size<R: Read>(
     archive: &mut Archive<R>,
     max_uncompressed_size: u64,
@@ -151,3 +237,80 @@ fn validate_tar_size<R: Read>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn packed_directory_round_trips_with_mtimes() {
+        let src = tempfile::tempdir().unwrap();
+        fs::create_dir_all(src.path().join("build")).unwrap();
+        fs::write(src.path().join("main.cpp"), b"int main() {}").unwrap();
+        fs::write(src.path().join("build/app"), b"\x7fELF").unwrap();
+
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        File::options()
+            .write(true)
+            .open(src.path().join("main.cpp"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let tar_bytes = pack_directory_tar(src.path()).unwrap();
+
+        let dest = tempfile::tempdir().unwrap();
+        extract_archive_contents(Path::new("build.tar"), &tar_bytes, u64::MAX, dest.path())
+            .unwrap();
+
+        assert_eq!(fs::read(dest.path().join("build/app")).unwrap(), b"\x7fELF");
+        let source_mtime = fs::metadata(dest.path().join("main.cpp"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        let artifact_mtime = fs::metadata(dest.path().join("build/app"))
+            .unwrap()
+            .modified()
+            .unwrap();
+        assert!(source_mtime < artifact_mtime);
+    }
+
+    #[test]
+    fn seven_z_archive_is_extracted() {
+        let src = tempfile::tempdir().unwrap();
+        fs::create_dir_all(src.path().join("src")).unwrap();
+        fs::write(src.path().join("main.cpp"), b"int main() {}").unwrap();
+        fs::write(src.path().join("src/util.h"), b"#pragma once").unwrap();
+        let bytes = sevenz_rust::compress(src.path(), Cursor::new(Vec::new()))
+            .unwrap()
+            .into_inner();
+
+        let dest = tempfile::tempdir().unwrap();
+        extract_archive_contents(Path::new("submission.7z"), &bytes, u64::MAX, dest.path())
+            .unwrap();
+
+        assert_eq!(
+            fs::read(dest.path().join("main.cpp")).unwrap(),
+            b"int main() {}"
+        );
+        assert_eq!(
+            fs::read(dest.path().join("src/util.h")).unwrap(),
+            b"#pragma once"
+        );
+    }
+
+    #[test]
+    fn oversized_seven_z_archive_is_rejected() {
+        let src = tempfile::tempdir().unwrap();
+        fs::write(src.path().join("big.txt"), vec![b'a'; 4096]).unwrap();
+        let bytes = sevenz_rust::compress(src.path(), Cursor::new(Vec::new()))
+            .unwrap()
+            .into_inner();
+
+        let dest = tempfile::tempdir().unwrap();
+        let err =
+            extract_archive_contents(Path::new("big.7z"), &bytes, 1024, dest.path()).unwrap_err();
+        assert!(err.to_string().contains("exceeds allowed maximum"));
+    }
+}
@@ -0,0 +1,87 @@
+// tests/grpc.rs
+use code_manager::api::grpc::proto::code_manager_client::CodeManagerClient;
+use code_manager::api::grpc::proto::{HealthRequest, RunRequest, StatsRequest};
+use code_manager::api::grpc::CodeManagerService;
+use code_manager::manager::manager::ContainerManager;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::transport::{Channel, Server};
+use tonic::{Code, Request};
+
+async fn start_server(
+    manager: ContainerManager,
+    token: Option<&str>,
+) -> CodeManagerClient<Channel> {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(CodeManagerService::new(manager).into_server(token.map(Arc::from)))
+            .serve_with_incoming(TcpListenerStream::new(listener)),
+    );
+    CodeManagerClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_grpc_health_and_stats() {
+    let mut client = start_server(ContainerManager::new(3), None).await;
+
+    let health = client.health(HealthRequest {}).await.unwrap().into_inner();
+    assert_eq!(health.status, "code_manager is running");
+
+    let stats = client.stats(StatsRequest {}).await.unwrap().into_inner();
+    assert_eq!(stats.running, 0);
+    assert_eq!(stats.waiting, 0);
+    assert_eq!(stats.max_concurrent, 3);
+    let by_priority = stats.waiting_by_priority.unwrap();
+    assert_eq!(
+        (by_priority.interactive, by_priority.normal, by_priority.bulk),
+        (0, 0, 0)
+    );
+}
+
+#[tokio::test]
+async fn test_grpc_run_rejects_invalid_config() {
+    let mut client = start_server(ContainerManager::new(1), None).await;
+
+    let status = client
+        .run(RunRequest {
+            config_json: "not json".to_string(),
+            commands: vec!["echo hi".to_string()],
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(status.message().starts_with("Invalid config"));
+}
+
+#[tokio::test]
+async fn test_grpc_rejects_missing_or_wrong_token() {
+    let mut client = start_server(ContainerManager::new(1), Some("s3cret")).await;
+
+    let status = client.stats(StatsRequest {}).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let mut request = Request::new(RunRequest::default());
+    request
+        .metadata_mut()
+        .insert("authorization", "Bearer wrong".parse().unwrap());
+    let status = client.run(request).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
+
+#[tokio::test]
+async fn test_grpc_accepts_correct_token() {
+    let mut client = start_server(ContainerManager::new(2), Some("s3cret")).await;
+
+    let mut request = Request::new(StatsRequest {});
+    request
+        .metadata_mut()
+        .insert("authorization", "Bearer s3cret".parse().unwrap());
+    let stats = client.stats(request).await.unwrap().into_inner();
+    assert_eq!(stats.max_concurrent, 2);
+}
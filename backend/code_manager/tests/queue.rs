@@ -276,3 +276,160 @@ async fn test_stats_reports_waiting_jobs() {
     job1.await.expect("job1 should finish");
     job2.await.expect("job2 should finish");
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_cancel_removes_queued_run() {
+    use code_manager::container::container::RunCancelled;
+    use code_manager::manager::manager::RunOptions;
+    use util::execution_config::ExecutionConfig;
+
+    let manager = ContainerManager::new(1);
+    let running_count = Arc::new(AtomicUsize::new(0));
+    let max_observed = Arc::new(AtomicUsize::new(0));
+
+    // Occupy the only slot so the real run below has to queue.
+    let blocker = {
+        let mgr = manager.clone();
+        let rc = Arc::clone(&running_count);
+        let mo = Arc::clone(&max_observed);
+        tokio::spawn(async move {
+            let files = vec!["blocker.rs".to_string()];
+            mgr.run_mock("rust", &files, rc, mo).await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let queued = {
+        let mgr = manager.clone();
+        tokio::spawn(async move {
+            let config = ExecutionConfig::default_config();
+            mgr.run_with(
+                &config,
+                vec!["echo never".to_string()],
+                Vec::new(),
+                false,
+                RunOptions {
+                    job_id: Some("submission-7".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(manager.get_stats().await, (1, 1, 1));
+    assert_eq!(manager.cancel_job("submission-7"), Some(1));
+
+    let err = tokio::time::timeout(Duration::from_secs(1), queued)
+        .await
+        .expect("cancelled run should return promptly")
+        .unwrap()
+        .expect_err("cancelled run should fail");
+    assert!(err.is::<RunCancelled>());
+
+    let (running, waiting, _) = manager.get_stats().await;
+    assert_eq!(
+        (running, waiting),
+        (1, 0),
+        "cancelled run must leave the queue"
+    );
+    assert_eq!(manager.cancel_job("submission-7"), None);
+
+    blocker.await.expect("blocker should finish");
+    assert_eq!(manager.get_stats().await, (0, 0, 1));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_submitted_run_status_is_tracked() {
+    use code_manager::manager::manager::RunOptions;
+    use code_manager::manager::runs::RunStatus;
+    use util::execution_config::ExecutionConfig;
+
+    let manager = ContainerManager::new(1);
+    let running_count = Arc::new(AtomicUsize::new(0));
+    let max_observed = Arc::new(AtomicUsize::new(0));
+
+    // Occupy the only slot so the submitted run has to queue.
+    let blocker = {
+        let mgr = manager.clone();
+        let rc = Arc::clone(&running_count);
+        let mo = Arc::clone(&max_observed);
+        tokio::spawn(async move {
+            let files = vec!["blocker.rs".to_string()];
+            mgr.run_mock("rust", &files, rc, mo).await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let id = manager.submit(
+        ExecutionConfig::default_config(),
+        vec!["echo never".to_string()],
+        Vec::new(),
+        false,
+        RunOptions {
+            job_id: Some("submission-8".to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Visible as soon as submit returns, before the background task got to run.
+    let record = manager
+        .run_status(&id)
+        .expect("submitted run should be tracked");
+    assert_eq!(record.status, RunStatus::Queued);
+    assert_eq!(record.job_id.as_deref(), Some("submission-8"));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(manager.cancel_job("submission-8"), Some(1));
+
+    let deadline = Instant::now() + Duration::from_secs(1);
+    let record = loop {
+        let record = manager.run_status(&id).unwrap();
+        if record.status != RunStatus::Queued || Instant::now() > deadline {
+            break record;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    };
+    assert_eq!(record.status, RunStatus::Cancelled);
+    assert!(record.started_at.is_none(), "run never got a slot");
+    assert!(record.finished_at.is_some());
+    assert!(record.result.is_none());
+
+    let cancelled = manager.list_runs(Some(RunStatus::Cancelled));
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].id, id);
+    assert!(manager.list_runs(Some(RunStatus::Running)).is_empty());
+
+    blocker.await.expect("blocker should finish");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_held_slot_blocks_runs_until_released() {
+    use code_manager::manager::queue::Priority;
+
+    let manager = ContainerManager::new(1);
+    manager.acquire_slot(Priority::Interactive).await;
+
+    let job = {
+        let mgr = manager.clone();
+        tokio::spawn(async move {
+            let files = vec!["job.rs".to_string()];
+            mgr.run_mock(
+                "rust",
+                &files,
+                Arc::new(AtomicUsize::new(0)),
+                Arc::new(AtomicUsize::new(0)),
+            )
+            .await
+        })
+    };
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(manager.get_stats().await, (1, 1, 1));
+
+    manager.release_slot().await;
+    job.await
+        .expect("job should finish once the slot is released");
+    assert_eq!(manager.get_stats().await, (0, 0, 1));
+}
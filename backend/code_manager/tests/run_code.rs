@@ -1,6 +1,8 @@
 // tests/run_code.rs
 
+use code_manager::backend::LocalBackend;
 use code_manager::manager::manager::ContainerManager;
+use std::sync::Arc;
 use util::execution_config::ExecutionConfig;
 
 #[tokio::test]
@@ -54,3 +56,31 @@ async fn test_run_java_and_cpp_code() {
         Err(e) => panic!("Run failed with error: {}", e),
     }
 }
+
+#[tokio::test]
+async fn test_run_code_with_local_backend() {
+    let config = ExecutionConfig::default_config();
+    let manager = ContainerManager::new(1).with_backend(Arc::new(LocalBackend));
+    assert_eq!(manager.backend_name(), "local");
+
+    let outputs = manager
+        .run(
+            &config,
+            vec!["sh hello.sh".to_string(), "cat greeting.txt".to_string()],
+            vec![(
+                "hello.sh".to_string(),
+                b"echo Hello from the host > greeting.txt".to_vec(),
+            )],
+            false,
+        )
+        .await
+        .expect("local run failed");
+
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs[0].ends_with("Retcode: 0"), "{:?}", outputs);
+    assert!(
+        outputs[1].starts_with("Hello from the host"),
+        "{:?}",
+        outputs
+    );
+}
@@ -0,0 +1,82 @@
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::code_manager_client::{self, RunRequest};
+use crate::jobs::JobHandle;
+
+/// Name the packed build output is attached under when sent back to code_manager.
+/// The `.tar` extension matters: code_manager extracts it like any other archive and
+/// tar keeps modification times, so prebuilt targets stay up to date.
+pub const BUILD_ARTIFACTS_FILENAME: &str = "build_artifacts.tar";
+
+/// Returns true if a code_manager output (with `&FITCHFORK&` markers) reports a clean exit.
+fn build_succeeded(output: &str) -> bool {
+    if output.contains("&FITCHFORK&Error") {
+        return false;
+    }
+    output
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("Retcode:"))
+        .and_then(|code| code.trim().parse::<i32>().ok())
+        == Some(0)
+}
+
+/// Runs `build_command` once against `files` and returns the packed `/code` directory.
+///
+/// Returns `None` if the request fails or the build exits non-zero, so callers fall back to
+/// building inside every task (which also surfaces compile errors in each task's output).
+pub(crate) async fn build_once(
+    client: &Client,
+    config_value: &Value,
+    build_command: &str,
+    files: &[(String, Vec<u8>)],
+    job: &JobHandle,
+) -> Option<Vec<u8>> {
+    let request = RunRequest {
+        config: config_value.clone(),
+        commands: vec![build_command.to_string()],
+        files: files.to_vec(),
+        return_artifacts: true,
+        job_id: Some(job.job_id().to_string()),
+        priority: job.priority(),
+        ..Default::default()
+    };
+
+    let run = match code_manager_client::run(client, request).await {
+        Ok(run) => run,
+        Err(e) => {
+            println!("Build step request failed: {}", e);
+            return None;
+        }
+    };
+
+    let output = run.output.first().map(String::as_str).unwrap_or_default();
+    if !build_succeeded(output) {
+        println!("Build step failed; falling back to building per task");
+        return None;
+    }
+
+    run.artifacts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clean_exit_counts_as_success() {
+        let out = "built\n&FITCHFORK&StandardError\n\n&FITCHFORK&ReturnCode\n\nRetcode: 0";
+        assert!(build_succeeded(out));
+    }
+
+    #[test]
+    fn non_zero_exit_or_error_marker_is_failure() {
+        let out = "&FITCHFORK&StandardError\n\nerror: x\n&FITCHFORK&ReturnCode\n\nRetcode: 2";
+        assert!(!build_succeeded(out));
+        assert!(!build_succeeded(
+            "&FITCHFORK&Error\nCommand timed out (possible infinite loop)"
+        ));
+        assert!(!build_succeeded(""));
+    }
+}
@@ -0,0 +1,482 @@
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tonic::transport::Channel;
+use util::config::{self, CodeManagerTransport};
+
+use crate::metrics::{CommandMetrics, parse_metrics};
+use crate::output_stream::{self, TaskOutputChunk, TaskOutputSink};
+
+mod proto {
+    tonic::include_proto!("code_manager");
+}
+
+use proto::code_manager_client::CodeManagerClient;
+use proto::run_event::Event;
+
+/// Largest message sent or accepted over gRPC; matches code_manager's limit.
+const MAX_GRPC_MESSAGE_BYTES: usize = 256 * 1024 * 1024;
+
+/// Queue priority of a run; mirrors code_manager's `Priority`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    /// A student waiting on their own submission.
+    Interactive,
+    #[default]
+    Normal,
+    /// Remarks, resubmits and GA runs.
+    Bulk,
+}
+
+impl From<Priority> for proto::Priority {
+    fn from(priority: Priority) -> Self {
+        match priority {
+            Priority::Interactive => proto::Priority::Interactive,
+            Priority::Normal => proto::Priority::Normal,
+            Priority::Bulk => proto::Priority::Bulk,
+        }
+    }
+}
+
+/// One run sent to code_manager: JSON for `/run`, or protobuf when `CODE_MANAGER_TRANSPORT=grpc`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RunRequest {
+    /// Serialized `ExecutionConfig`.
+    pub config: Value,
+    pub commands: Vec<String>,
+    pub files: Vec<(String, Vec<u8>)>,
+    pub interpreter: bool,
+    pub return_artifacts: bool,
+    /// Also return whatever the commands wrote to `/output`.
+    pub return_output_files: bool,
+    pub job_id: Option<String>,
+    pub priority: Priority,
+}
+
+/// A finished run: one output (and usually one metrics entry) per command.
+#[derive(Debug, Default)]
+pub struct RunResult {
+    pub output: Vec<String>,
+    pub metrics: Vec<CommandMetrics>,
+    /// `/code` as a tar, if [`RunRequest::return_artifacts`] was set.
+    pub artifacts: Option<Vec<u8>>,
+    /// `/output` as a tar, if [`RunRequest::return_output_files`] was set.
+    pub output_files: Option<Vec<u8>>,
+}
+
+/// Queue counters reported by code_manager.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct CodeManagerStats {
+    pub running: usize,
+    pub waiting: usize,
+    pub max_concurrent: usize,
+}
+
+/// Adds `CODE_MANAGER_TOKEN` as a bearer token, if one is configured.
+pub fn with_auth(builder: RequestBuilder) -> RequestBuilder {
+    match config::code_manager_token() {
+        Some(token) => builder.bearer_auth(token),
+        None => builder,
+    }
+}
+
+/// Wraps `message` with the same bearer token in its `authorization` metadata.
+fn grpc_request<T>(message: T) -> Result<tonic::Request<T>, String> {
+    let mut request = tonic::Request::new(message);
+    if let Some(token) = config::code_manager_token() {
+        let value = format!("Bearer {}", token)
+            .parse()
+            .map_err(|_| "CODE_MANAGER_TOKEN is not a valid header value".to_string())?;
+        request.metadata_mut().insert("authorization", value);
+    }
+    Ok(request)
+}
+
+/// Base URL of code_manager's HTTP API, from the live (reloadable) host and port.
+pub fn http_base_url() -> String {
+    let live = config::live();
+    format!(
+        "http://{}:{}",
+        live.code_manager_host, live.code_manager_port
+    )
+}
+
+/// WebSocket URL of code_manager's interactive `/terminal`.
+pub fn terminal_url() -> String {
+    let live = config::live();
+    format!(
+        "ws://{}:{}/terminal",
+        live.code_manager_host, live.code_manager_port
+    )
+}
+
+async fn grpc_client() -> Result<CodeManagerClient<Channel>, String> {
+    let url = format!(
+        "http://{}:{}",
+        config::live().code_manager_host,
+        config::code_manager_grpc_port()
+    );
+    let client = CodeManagerClient::connect(url)
+        .await
+        .map_err(|e| format!("Failed to connect to code_manager over gRPC: {}", e))?;
+    Ok(client
+        .max_decoding_message_size(MAX_GRPC_MESSAGE_BYTES)
+        .max_encoding_message_size(MAX_GRPC_MESSAGE_BYTES))
+}
+
+fn status_error(status: tonic::Status) -> String {
+    format!(
+        "code_manager error: {:?} {}",
+        status.code(),
+        status.message()
+    )
+}
+
+/// Runs `request` and waits for all of its commands to finish.
+pub async fn run(client: &Client, request: RunRequest) -> Result<RunResult, String> {
+    match config::code_manager_transport() {
+        CodeManagerTransport::Http => run_http(client, &request).await,
+        CodeManagerTransport::Grpc => run_grpc(request, None).await,
+    }
+}
+
+/// Like [`run`], but forwards stdout/stderr to `sink` while the commands are still running.
+pub(crate) async fn run_streamed(
+    client: &Client,
+    request: RunRequest,
+    task_id: i64,
+    task_number: i64,
+    sink: &TaskOutputSink,
+) -> Result<RunResult, String> {
+    match config::code_manager_transport() {
+        CodeManagerTransport::Http => {
+            let url = format!("{}/run/stream", http_base_url());
+            output_stream::run_streamed(client, &url, &request, task_id, task_number, sink).await
+        }
+        CodeManagerTransport::Grpc => run_grpc(request, Some((task_id, task_number, sink))).await,
+    }
+}
+
+async fn run_http(client: &Client, request: &RunRequest) -> Result<RunResult, String> {
+    let response = with_auth(client.post(format!("{}/run", http_base_url())))
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("code_manager error: {} {}", status, text));
+    }
+
+    let resp_json: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse code_manager response: {}", e))?;
+
+    let output = resp_json
+        .get("output")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Response missing 'output' array".to_string())?
+        .iter()
+        .map(|val| val.as_str().unwrap_or("").to_string())
+        .collect();
+
+    Ok(RunResult {
+        output,
+        metrics: parse_metrics(&resp_json),
+        artifacts: bytes_field(&resp_json, "artifacts"),
+        output_files: bytes_field(&resp_json, "output_files"),
+    })
+}
+
+/// Reads a `Vec<u8>` that serde_json wrote as an array of numbers.
+pub(crate) fn bytes_field(value: &Value, name: &str) -> Option<Vec<u8>> {
+    value.get(name).and_then(|v| v.as_array()).and_then(|arr| {
+        arr.iter()
+            .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+            .collect()
+    })
+}
+
+async fn run_grpc(
+    request: RunRequest,
+    stream: Option<(i64, i64, &TaskOutputSink)>,
+) -> Result<RunResult, String> {
+    let config_json = serde_json::to_string(&request.config)
+        .map_err(|e| format!("Failed to serialize execution config: {}", e))?;
+    let message = proto::RunRequest {
+        config_json,
+        commands: request.commands,
+        files: request
+            .files
+            .into_iter()
+            .map(|(name, content)| proto::File { name, content })
+            .collect(),
+        interpreter: request.interpreter,
+        return_artifacts: request.return_artifacts,
+        return_output_files: request.return_output_files,
+        job_id: request.job_id.unwrap_or_default(),
+        stream_output: stream.is_some(),
+        priority: proto::Priority::from(request.priority).into(),
+    };
+
+    let mut events = grpc_client()
+        .await?
+        .run(grpc_request(message)?)
+        .await
+        .map_err(status_error)?
+        .into_inner();
+
+    while let Some(event) = events.message().await.map_err(status_error)? {
+        match event.event {
+            Some(Event::Chunk(chunk)) => {
+                if let Some((task_id, task_number, sink)) = stream {
+                    // The receiver going away must not abort the run.
+                    let _ = sink.send(TaskOutputChunk {
+                        task_id,
+                        task_number,
+                        stream: chunk.stream,
+                        data: chunk.data,
+                    });
+                }
+            }
+            Some(Event::Done(done)) => {
+                return Ok(RunResult {
+                    output: done.output,
+                    metrics: done
+                        .metrics
+                        .into_iter()
+                        .map(|m| CommandMetrics {
+                            wall_time_ms: m.wall_time_ms,
+                            cpu_time_ms: m.cpu_time_ms,
+                            max_rss_bytes: m.max_rss_bytes,
+                            disk_quota_exceeded: m.disk_quota_exceeded,
+                        })
+                        .collect(),
+                    artifacts: done.artifacts,
+                    output_files: done.output_files,
+                });
+            }
+            None => {}
+        }
+    }
+    Err("Stream ended before the run completed".to_string())
+}
+
+/// Where a run submitted with [`submit`] is at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// One run as reported by code_manager's `GET /jobs/{id}`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobStatus {
+    pub id: String,
+    pub job_id: Option<String>,
+    pub status: JobState,
+    pub submitted_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+    /// `{ output, metrics }` once a submitted run completed.
+    #[serde(default)]
+    result: Option<Value>,
+}
+
+impl JobStatus {
+    /// The run's outputs, if it completed.
+    pub fn result(&self) -> Option<RunResult> {
+        let result = self.result.as_ref()?;
+        let output = result
+            .get("output")?
+            .as_array()?
+            .iter()
+            .map(|val| val.as_str().unwrap_or("").to_string())
+            .collect();
+        Some(RunResult {
+            output,
+            metrics: parse_metrics(result),
+            ..Default::default()
+        })
+    }
+}
+
+/// Queues `request` on code_manager's `/run/async` and returns the run's id without waiting
+/// for it; poll [`job_status`] for the outcome. Always goes over HTTP.
+pub async fn submit(client: &Client, request: &RunRequest) -> Result<String, String> {
+    #[derive(Deserialize)]
+    struct Submitted {
+        id: String,
+    }
+
+    let response = with_auth(client.post(format!("{}/run/async", http_base_url())))
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("code_manager error: {} {}", status, text));
+    }
+    response
+        .json::<Submitted>()
+        .await
+        .map(|submitted| submitted.id)
+        .map_err(|e| format!("Failed to parse code_manager response: {}", e))
+}
+
+/// Fetches the status of a run from code_manager's `/jobs/{id}`. Always goes over HTTP.
+pub async fn job_status(client: &Client, id: &str) -> Result<JobStatus, String> {
+    let response = with_auth(client.get(format!("{}/jobs/{}", http_base_url(), id)))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("code_manager error: {} {}", status, text));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse code_manager response: {}", e))
+}
+
+/// Fetches code_manager's queue counters over the configured transport.
+pub async fn stats(client: &Client) -> Result<CodeManagerStats, String> {
+    match config::code_manager_transport() {
+        CodeManagerTransport::Http => {
+            let response = with_auth(client.get(format!("{}/stats", http_base_url())))
+                .send()
+                .await
+                .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("code_manager error: {}", response.status()));
+            }
+            response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse code_manager response: {}", e))
+        }
+        CodeManagerTransport::Grpc => {
+            let stats = grpc_client()
+                .await?
+                .stats(grpc_request(proto::StatsRequest {})?)
+                .await
+                .map_err(status_error)?
+                .into_inner();
+            Ok(CodeManagerStats {
+                running: stats.running as usize,
+                waiting: stats.waiting as usize,
+                max_concurrent: stats.max_concurrent as usize,
+            })
+        }
+    }
+}
+
+/// Sets code_manager's concurrency limit (e.g. to a reloaded `MAX_NUM_CONTAINERS`).
+pub async fn set_max_concurrent(client: &Client, max_concurrent: usize) -> Result<(), String> {
+    let response = with_auth(client.post(format!("{}/max_concurrent", http_base_url())))
+        .json(&serde_json::json!({ "max_concurrent": max_concurrent }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("code_manager error: {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Returns code_manager's health message, or an error if it can't be reached.
+pub async fn health(client: &Client) -> Result<String, String> {
+    match config::code_manager_transport() {
+        CodeManagerTransport::Http => {
+            let response = with_auth(client.get(format!("{}/health", http_base_url())))
+                .send()
+                .await
+                .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("code_manager error: {}", response.status()));
+            }
+            response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read code_manager response: {}", e))
+        }
+        CodeManagerTransport::Grpc => Ok(grpc_client()
+            .await?
+            .health(grpc_request(proto::HealthRequest {})?)
+            .await
+            .map_err(status_error)?
+            .into_inner()
+            .status),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn http_body_matches_code_manager_run_request() {
+        let request = RunRequest {
+            config: json!({ "execution": {} }),
+            commands: vec!["make task1".to_string()],
+            files: vec![("main.zip".to_string(), vec![1, 2])],
+            job_id: Some("submission-1-abc".to_string()),
+            priority: Priority::Interactive,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "config": { "execution": {} },
+                "commands": ["make task1"],
+                "files": [["main.zip", [1, 2]]],
+                "interpreter": false,
+                "return_artifacts": false,
+                "return_output_files": false,
+                "job_id": "submission-1-abc",
+                "priority": "interactive",
+            })
+        );
+    }
+
+    #[test]
+    fn job_status_exposes_the_result_of_a_completed_run() {
+        let status: JobStatus = serde_json::from_value(json!({
+            "id": "3f2c",
+            "job_id": null,
+            "priority": "normal",
+            "status": "completed",
+            "submitted_at": "2026-10-16T10:00:00Z",
+            "started_at": "2026-10-16T10:00:01Z",
+            "finished_at": "2026-10-16T10:00:03Z",
+            "duration_ms": 2000,
+            "error": null,
+            "result": {
+                "output": ["hello"],
+                "metrics": [{ "wall_time_ms": 1500, "cpu_time_ms": 900, "max_rss_bytes": null }],
+            },
+        }))
+        .unwrap();
+        assert_eq!(status.status, JobState::Completed);
+        assert_eq!(status.duration_ms, Some(2000));
+        let result = status.result().unwrap();
+        assert_eq!(result.output, vec!["hello".to_string()]);
+        assert_eq!(result.metrics[0].wall_time_ms, 1500);
+    }
+}
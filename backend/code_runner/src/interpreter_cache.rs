@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Identifies one interpreter run: the assignment, the interpreter row it ran and the payload.
+///
+/// Uploading a new interpreter replaces the row, so entries of the old one are never hit again
+/// and simply expire.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InterpreterCacheKey {
+    pub assignment_id: i64,
+    pub interpreter_id: i64,
+    pub payload: String,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    source: String,
+    inserted_at: Instant,
+}
+
+/// Sources generated by the interpreter for earlier payloads.
+///
+/// RNG jobs and GA runs regenerate the same payloads often, and the generated source only
+/// depends on the interpreter and the payload, so a repeat skips the code_manager run and
+/// reuses the stored source. Entries older than the TTL are dropped on lookup; when full, the
+/// oldest entry makes room for a new one.
+#[derive(Debug, Default)]
+pub struct InterpreterCache {
+    entries: HashMap<InterpreterCacheKey, Entry>,
+    hits: usize,
+    misses: usize,
+}
+
+impl InterpreterCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Looks up `key`, counting a hit or a miss. An entry older than `ttl` counts as a miss
+    /// and is removed.
+    pub fn get(
+        &mut self,
+        key: &InterpreterCacheKey,
+        ttl: Duration,
+        now: Instant,
+    ) -> Option<String> {
+        let fresh = self
+            .entries
+            .get(key)
+            .map(|e| now.saturating_duration_since(e.inserted_at) < ttl);
+        match fresh {
+            Some(true) => {
+                self.hits += 1;
+                self.entries.get(key).map(|e| e.source.clone())
+            }
+            Some(false) => {
+                self.entries.remove(key);
+                self.misses += 1;
+                None
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores `source` for `key`, evicting the oldest entry when `capacity` is reached.
+    /// A `capacity` of 0 stores nothing.
+    pub fn insert(
+        &mut self,
+        key: InterpreterCacheKey,
+        source: String,
+        capacity: usize,
+        now: Instant,
+    ) {
+        if capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= capacity && !self.entries.contains_key(&key) {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.inserted_at)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                source,
+                inserted_at: now,
+            },
+        );
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Share of lookups answered from the cache, between 0 and 1.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// The process-wide cache shared by every interpreter run.
+pub fn shared() -> &'static Mutex<InterpreterCache> {
+    static CACHE: OnceLock<Mutex<InterpreterCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(InterpreterCache::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(assignment_id: i64, payload: &str) -> InterpreterCacheKey {
+        InterpreterCacheKey {
+            assignment_id,
+            interpreter_id: 1,
+            payload: payload.to_string(),
+        }
+    }
+
+    #[test]
+    fn hits_only_the_same_assignment_and_payload() {
+        let ttl = Duration::from_secs(60);
+        let now = Instant::now();
+        let mut cache = InterpreterCache::new();
+
+        assert_eq!(cache.get(&key(1, "1,2"), ttl, now), None);
+        cache.insert(key(1, "1,2"), "int main() {}".into(), 8, now);
+
+        assert_eq!(
+            cache.get(&key(1, "1,2"), ttl, now).as_deref(),
+            Some("int main() {}")
+        );
+        assert_eq!(cache.get(&key(2, "1,2"), ttl, now), None);
+        assert_eq!(cache.get(&key(1, "2,1"), ttl, now), None);
+
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 3);
+        assert!((cache.hit_rate() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn expires_after_ttl_and_evicts_oldest_when_full() {
+        let ttl = Duration::from_secs(60);
+        let start = Instant::now();
+        let mut cache = InterpreterCache::new();
+
+        cache.insert(key(1, "a"), "a".into(), 2, start);
+        cache.insert(key(1, "b"), "b".into(), 2, start + Duration::from_secs(1));
+        cache.insert(key(1, "c"), "c".into(), 2, start + Duration::from_secs(2));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&key(1, "a"), ttl, start), None);
+
+        let later = start + Duration::from_secs(61);
+        assert_eq!(cache.get(&key(1, "b"), ttl, later), None);
+        assert_eq!(cache.get(&key(1, "c"), ttl, later).as_deref(), Some("c"));
+        assert_eq!(cache.len(), 1);
+
+        cache.insert(key(1, "d"), "d".into(), 0, later);
+        assert_eq!(cache.get(&key(1, "d"), ttl, later), None);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+use reqwest::{Client, StatusCode};
+
+use crate::code_manager_client::{self, Priority, with_auth};
+
+struct JobInner {
+    job_id: String,
+    priority: Priority,
+    cancelled: AtomicBool,
+}
+
+/// Handle for one logical run (e.g. everything a submission triggers), sent to code_manager as
+/// `job_id` so the in-flight containers can be stopped.
+///
+/// Cheap to clone; the job can be cancelled for as long as any clone is alive.
+#[derive(Clone)]
+pub struct JobHandle(Arc<JobInner>);
+
+impl JobHandle {
+    pub fn job_id(&self) -> &str {
+        &self.0.job_id
+    }
+
+    /// Queue priority code_manager gives every run of this job.
+    pub fn priority(&self) -> Priority {
+        self.0.priority
+    }
+
+    /// True once [`cancel_job`] was called; callers should stop sending new runs.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Weak<JobInner>>> {
+    static JOBS: OnceLock<Mutex<HashMap<String, Weak<JobInner>>>> = OnceLock::new();
+    JOBS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Key under which every run for a submission is tracked.
+pub fn submission_job_key(submission_id: i64) -> String {
+    format!("submission-{}", submission_id)
+}
+
+/// Returns the active job for `key`, or starts a new one with [`Priority::Normal`].
+///
+/// Nested callers (e.g. a GA loop and each interpreter run inside it) share one handle, so a
+/// single cancel stops all of them. A cancelled job is never reused.
+pub fn start_job(key: &str) -> JobHandle {
+    start_job_with_priority(key, Priority::Normal)
+}
+
+/// Like [`start_job`], but a newly started job gets `priority`. A job that is already active
+/// keeps the priority it was started with.
+pub fn start_job_with_priority(key: &str, priority: Priority) -> JobHandle {
+    let mut jobs = registry().lock().unwrap();
+    jobs.retain(|_, job| job.strong_count() > 0);
+
+    if let Some(inner) = jobs.get(key).and_then(Weak::upgrade)
+        && !inner.cancelled.load(Ordering::SeqCst)
+    {
+        return JobHandle(inner);
+    }
+
+    let inner = Arc::new(JobInner {
+        job_id: format!("{}-{}", key, uuid::Uuid::new_v4().simple()),
+        priority,
+        cancelled: AtomicBool::new(false),
+    });
+    jobs.insert(key.to_string(), Arc::downgrade(&inner));
+    JobHandle(inner)
+}
+
+/// Cancels the active job for `key`.
+///
+/// Marks it cancelled (so nothing new is sent) and asks code_manager to stop whatever is queued
+/// or running under its id. Returns `Ok(false)` if no job is active for `key`.
+pub async fn cancel_job(key: &str) -> Result<bool, String> {
+    let inner = registry().lock().unwrap().get(key).and_then(Weak::upgrade);
+    let Some(inner) = inner else {
+        return Ok(false);
+    };
+    inner.cancelled.store(true, Ordering::SeqCst);
+
+    let url = format!(
+        "{}/run/{}",
+        code_manager_client::http_base_url(),
+        inner.job_id
+    );
+    let response = with_auth(Client::new().delete(&url))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send cancel request to code_manager: {}", e))?;
+
+    // 404 only means nothing is in flight right now (e.g. between GA generations).
+    if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "code_manager responded with error: {} {}",
+            status, text
+        ));
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_starts_share_a_job_until_it_is_dropped() {
+        let key = "test-nested";
+        let outer = start_job(key);
+        let inner = start_job(key);
+        assert_eq!(outer.job_id(), inner.job_id());
+
+        drop(outer);
+        drop(inner);
+        let fresh = start_job(key);
+        assert!(fresh.job_id().starts_with("test-nested-"));
+        assert!(!fresh.is_cancelled());
+    }
+
+    #[test]
+    fn nested_starts_keep_the_outer_priority() {
+        let key = "test-priority";
+        let outer = start_job_with_priority(key, Priority::Bulk);
+        let inner = start_job_with_priority(key, Priority::Interactive);
+        assert_eq!(inner.priority(), Priority::Bulk);
+
+        drop(outer);
+        drop(inner);
+        assert_eq!(start_job(key).priority(), Priority::Normal);
+    }
+
+    #[test]
+    fn cancelled_job_is_not_reused() {
+        let key = "test-cancelled";
+        let old = start_job(key);
+        old.0.cancelled.store(true, Ordering::SeqCst);
+
+        let new = start_job(key);
+        assert_ne!(old.job_id(), new.job_id());
+        assert!(old.is_cancelled());
+        assert!(!new.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn cancel_without_active_job_is_a_no_op() {
+        assert_eq!(cancel_job("test-missing").await, Ok(false));
+    }
+}
@@ -1,14 +1,15 @@
-use std::path::Path;
 // Core dependencies
-use std::{fs, path::PathBuf};
+use std::fs;
 
 // use db::models::AssignmentSubmissionOutput;
 // External crates
 use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 use util::paths::{
     attempt_dir, main_dir, makefile_dir, memo_dir, memo_output_dir, overwrite_task_dir,
+    submission_artifacts_dir, submission_task_artifacts_dir,
 };
 // Your own modules
+use crate::stored_files::{apply_overwrites, first_archive_in};
 use crate::validate_files::validate_memo_files;
 
 // Models
@@ -16,35 +17,27 @@ use db::models::assignment::Entity as Assignment;
 use db::models::assignment_memo_output::{Column as MemoOutputColumn, Entity as MemoOutputEntity};
 use db::models::assignment_task::{Model as AssignmentTask, TaskType};
 use reqwest::Client;
-use serde_json::json;
+use serde::Serialize;
+use util::archive::ArchiveLimits;
 use util::code_coverage_report::CoverageProcessor;
-use util::config;
 use util::execution_config::ExecutionConfig;
+use util::massif_report::MassifProcessor;
 use util::valgrind_report::ValgrindProcessor;
+pub mod build_cache;
+pub mod code_manager_client;
+pub mod interpreter_cache;
+pub mod jobs;
+pub mod metrics;
+pub mod output_limit;
+pub mod output_stream;
+mod stored_files;
+pub mod submission_files;
+pub mod task_artifacts;
 pub mod validate_files;
 
-/// Returns the first archive file (".zip", ".tar", ".tgz", ".gz") found in the given directory.
-/// Returns an error if the directory does not exist or if no supported archive file is found.
-fn first_archive_in<P: AsRef<Path>>(dir: P) -> Result<PathBuf, String> {
-    let allowed_exts = ["zip", "tar", "tgz", "gz"];
-    let dir = dir.as_ref();
-    std::fs::read_dir(dir)
-        .map_err(|_| format!("Missing directory: {}", dir.display()))?
-        .filter_map(|e| e.ok())
-        .map(|e| e.path())
-        .find(|p| {
-            p.extension()
-                .and_then(|e| e.to_str())
-                .map(|ext| allowed_exts.contains(&ext.to_ascii_lowercase().as_str()))
-                .unwrap_or(false)
-        })
-        .ok_or_else(|| {
-            format!(
-                "No .zip, .tar, .tgz, or .gz file found in {}",
-                dir.display()
-            )
-        })
-}
+pub use output_stream::{TaskOutputChunk, TaskOutputSink};
+
+use code_manager_client::RunRequest;
 
 /// Runs all configured tasks for a given assignment ID by:
 /// 1. Validating memo files
@@ -65,7 +58,7 @@ pub async fn create_memo_outputs_for_all_tasks(
     let module_id = assignment.module_id;
 
     // Validate required input files
-    validate_memo_files(module_id, assignment_id)?;
+    validate_memo_files(module_id, assignment_id).await?;
 
     // Load config
     let config = ExecutionConfig::get_execution_config(module_id, assignment_id)
@@ -87,13 +80,6 @@ pub async fn create_memo_outputs_for_all_tasks(
         .await
         .map_err(|e| format!("Failed to delete old memo outputs: {}", e))?;
 
-    // Load archives with helpers
-    let archive_paths = vec![
-        first_archive_in(memo_dir(module_id, assignment_id))?,
-        first_archive_in(makefile_dir(module_id, assignment_id))?,
-        first_archive_in(main_dir(module_id, assignment_id))?,
-    ];
-
     let tasks = AssignmentTask::get_by_assignment_id(db, assignment_id)
         .await
         .map_err(|e| format!("DB error loading tasks: {}", e))?;
@@ -105,29 +91,15 @@ pub async fn create_memo_outputs_for_all_tasks(
     // Prepare HTTP client
     let client = Client::new();
 
-    let host = config::code_manager_host();
-
-    let port = config::code_manager_port();
-
-    let code_manager_url = format!("http://{}:{}", host, port);
+    // Read common archives once to avoid repeated storage reads
+    let base_files = load_memo_base_files(module_id, assignment_id).await?;
 
-    // Read common archives once to avoid repeated disk IO
-    let mut base_files: Vec<(String, Vec<u8>)> = Vec::new();
-    for archive_path in &archive_paths {
-        let content = std::fs::read(archive_path)
-            .map_err(|e| format!("Failed to read archive file {:?}: {}", archive_path, e))?;
-        let file_name = archive_path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| format!("Invalid archive filename: {:?}", archive_path))?
-            .to_string();
-        base_files.push((file_name, content));
-    }
+    let config_value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize ExecutionConfig: {}", e))?;
 
     use std::sync::Arc;
     use tokio::sync::Semaphore;
     use tokio::task::JoinSet;
-    use tokio::time::{Duration, sleep};
 
     let max_concurrency = std::cmp::max(
         1,
@@ -144,141 +116,25 @@ pub async fn create_memo_outputs_for_all_tasks(
             continue;
         }
 
-        let filename = format!("task_{}_output.txt", task.task_number);
         let task_files_base = base_files.clone();
         let client_cloned = client.clone();
-        let cm_url = code_manager_url.clone();
-        let config_value = serde_json::to_value(&config)
-            .map_err(|e| format!("Failed to serialize ExecutionConfig: {}", e))?;
+        let config_value = config_value.clone();
         let db_cloned = db.clone();
         let sem = semaphore.clone();
         join_set.spawn(async move {
             let _permit = sem.acquire_owned().await.ok();
-            // Apply overwrites for this task
-            let mut files = task_files_base.clone();
-            let overwrite_dir = overwrite_task_dir(module_id, assignment_id, task.task_number);
-            if overwrite_dir.exists() {
-                if let Ok(entries) = std::fs::read_dir(&overwrite_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() {
-                            if let Ok(content) = std::fs::read(&path) {
-                                if let Some(file_name) = path
-                                    .file_name()
-                                    .and_then(|s| s.to_str())
-                                    .map(|s| s.to_string())
-                                {
-                                    files.retain(|(name, _)| name != &file_name);
-                                    files.push((file_name, content));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Ensure makefile.zip is always included last
-            let makefile_archive_path = first_archive_in(makefile_dir(module_id, assignment_id))?;
-            let makefile_content = std::fs::read(&makefile_archive_path)
-                .map_err(|e| format!("Failed to read makefile archive: {}", e))?;
-            let makefile_filename = makefile_archive_path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| {
-                    format!(
-                        "Invalid makefile archive filename: {:?}",
-                        makefile_archive_path
-                    )
-                })?
-                .to_string();
-
-            files.retain(|(name, _)| name != &makefile_filename); // remove any overwrite copy
-            files.push((makefile_filename, makefile_content));
-
-            let request_body = serde_json::json!({
-                "config": config_value,
-                "commands": [task.command.clone()],
-                "files": files,
-            });
-
-            // Fire request
-            let resp_res = client_cloned
-                .post(format!("{}/run", cm_url))
-                .json(&request_body)
-                .send()
-                .await;
-            let response = match resp_res {
-                Ok(r) => r,
-                Err(e) => {
-                    return Err(format!(
-                        "Failed to send request to code_manager (task {}): {}",
-                        task.task_number, e
-                    ));
-                }
-            };
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format!(
-                    "code_manager error for task {}: {} {}",
-                    task.task_number, status, text
-                ));
-            }
-
-            let resp_json: serde_json::Value = match response.json().await {
-                Ok(v) => v,
-                Err(e) => {
-                    return Err(format!(
-                        "Failed to parse response JSON for task {}: {}",
-                        task.task_number, e
-                    ));
-                }
-            };
+            let output_combined = run_memo_task(
+                &client_cloned,
+                &config_value,
+                module_id,
+                assignment_id,
+                &task,
+                task_files_base,
+                None,
+            )
+            .await?;
 
-            let output_vec = resp_json
-                .get("output")
-                .and_then(|v| v.as_array())
-                .ok_or_else(|| {
-                    format!(
-                        "Response missing 'output' array for task {}",
-                        task.task_number
-                    )
-                })?
-                .iter()
-                .map(|val| val.as_str().unwrap_or("").to_string())
-                .collect::<Vec<String>>();
-            let output_combined = output_vec.join("\n");
-
-            // Save with retries to mitigate transient locks
-            for attempt in 0..5 {
-                match db::models::assignment_memo_output::Model::save_file(
-                    &db_cloned,
-                    assignment_id,
-                    task.id,
-                    &filename,
-                    output_combined.as_bytes(),
-                )
-                .await
-                {
-                    Ok(_) => return Ok::<(), String>(()),
-                    Err(e) => {
-                        let backoff_ms = 20u64 * (1 << attempt);
-                        println!(
-                            "Retry {}/5 saving memo output for task {} ({} ms): {}",
-                            attempt + 1,
-                            task.task_number,
-                            backoff_ms,
-                            e
-                        );
-                        sleep(Duration::from_millis(backoff_ms)).await;
-                    }
-                }
-            }
-            Err(format!(
-                "Failed to save memo output for task {} after retries",
-                task.task_number
-            ))
+            save_memo_output_with_retries(&db_cloned, assignment_id, &task, &output_combined).await
         });
     }
 
@@ -307,6 +163,158 @@ pub async fn create_memo_outputs_for_all_tasks(
     Ok(())
 }
 
+/// Regenerates the memo output of a single task, leaving every other task's memo output untouched.
+///
+/// The task is run first and its previous memo output is only replaced once the new output
+/// is available, so marking against the old memo keeps working while the run is in progress.
+pub async fn create_memo_outputs_for_task(
+    db: &DatabaseConnection,
+    assignment_id: i64,
+    task_id: i64,
+) -> Result<(), String> {
+    use db::models::assignment_memo_output::Model as MemoOutputModel;
+    use db::models::assignment_task::Entity as AssignmentTaskEntity;
+
+    let assignment = Assignment::find_by_id(assignment_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("Failed to fetch assignment: {}", e))?
+        .ok_or_else(|| format!("Assignment {} not found", assignment_id))?;
+
+    let module_id = assignment.module_id;
+
+    let task = AssignmentTaskEntity::find_by_id(task_id)
+        .one(db)
+        .await
+        .map_err(|e| format!("DB error loading task: {}", e))?
+        .filter(|t| t.assignment_id == assignment_id)
+        .ok_or_else(|| format!("Task {} not found in assignment {}", task_id, assignment_id))?;
+
+    if task.task_type == TaskType::Coverage {
+        return Err(format!(
+            "Coverage tasks do not produce memo output (task {})",
+            task.task_number
+        ));
+    }
+
+    validate_memo_files(module_id, assignment_id).await?;
+
+    let config = ExecutionConfig::get_execution_config(module_id, assignment_id)
+        .map_err(|e| format!("Failed to load execution config: {}", e))?;
+    let config_value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize ExecutionConfig: {}", e))?;
+
+    let base_files = load_memo_base_files(module_id, assignment_id).await?;
+
+    let output = run_memo_task(
+        &Client::new(),
+        &config_value,
+        module_id,
+        assignment_id,
+        &task,
+        base_files,
+        None,
+    )
+    .await?;
+
+    MemoOutputModel::delete_for_task(db, assignment_id, task.id)
+        .await
+        .map_err(|e| format!("Failed to delete old memo output for task {}: {}", task.task_number, e))?;
+
+    save_memo_output_with_retries(db, assignment_id, &task, &output).await
+}
+
+/// Reads the memo, makefile and main archives that every memo task run starts from.
+pub async fn load_memo_base_files(
+    module_id: i64,
+    assignment_id: i64,
+) -> Result<Vec<(String, Vec<u8>)>, String> {
+    Ok(vec![
+        first_archive_in(&memo_dir(module_id, assignment_id)).await?,
+        first_archive_in(&makefile_dir(module_id, assignment_id)).await?,
+        first_archive_in(&main_dir(module_id, assignment_id)).await?,
+    ])
+}
+
+/// Runs one task's command against the memo files (with the task's overwrite files applied)
+/// and returns the combined output of the run.
+async fn run_memo_task(
+    client: &Client,
+    config_value: &serde_json::Value,
+    module_id: i64,
+    assignment_id: i64,
+    task: &AssignmentTask,
+    mut files: Vec<(String, Vec<u8>)>,
+    job: Option<&jobs::JobHandle>,
+) -> Result<String, String> {
+    // Apply overwrites for this task
+    apply_overwrites(
+        &overwrite_task_dir(module_id, assignment_id, task.task_number),
+        &mut files,
+    )
+    .await?;
+
+    // Ensure makefile.zip is always included last
+    let (makefile_filename, makefile_content) =
+        first_archive_in(&makefile_dir(module_id, assignment_id)).await?;
+    files.retain(|(name, _)| name != &makefile_filename); // remove any overwrite copy
+    files.push((makefile_filename, makefile_content));
+
+    let request = RunRequest {
+        config: config_value.clone(),
+        commands: vec![task.command.clone()],
+        files,
+        job_id: job.map(|j| j.job_id().to_string()),
+        priority: job.map(|j| j.priority()).unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let run = code_manager_client::run(client, request)
+        .await
+        .map_err(|e| format!("Task {}: {}", task.task_number, e))?;
+    Ok(run.output.join("\n"))
+}
+
+/// Saves a task's memo output, retrying to mitigate transient locks.
+async fn save_memo_output_with_retries(
+    db: &DatabaseConnection,
+    assignment_id: i64,
+    task: &AssignmentTask,
+    output: &str,
+) -> Result<(), String> {
+    use tokio::time::{Duration, sleep};
+
+    let filename = format!("task_{}_output.txt", task.task_number);
+    for attempt in 0..5 {
+        match db::models::assignment_memo_output::Model::save_file(
+            db,
+            assignment_id,
+            task.id,
+            &filename,
+            output.as_bytes(),
+        )
+        .await
+        {
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                let backoff_ms = 20u64 * (1 << attempt);
+                println!(
+                    "Retry {}/5 saving memo output for task {} ({} ms): {}",
+                    attempt + 1,
+                    task.task_number,
+                    backoff_ms,
+                    e
+                );
+                sleep(Duration::from_millis(backoff_ms)).await;
+            }
+        }
+    }
+    Err(format!(
+        "Failed to save memo output for task {} after retries",
+        task.task_number
+    ))
+}
+
 pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
     db: &DatabaseConnection,
     assignment_id: i64,
@@ -322,7 +330,7 @@ pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
     let module_id = assignment.module_id;
 
     // Validate required input files
-    validate_memo_files(module_id, assignment_id)?;
+    validate_memo_files(module_id, assignment_id).await?;
 
     // Load config
     let config = ExecutionConfig::get_execution_config(module_id, assignment_id)
@@ -331,6 +339,9 @@ pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
     // Base and subdirs via helpers
     let memo_out_dir = memo_output_dir(module_id, assignment_id);
 
+    // Interpreter-driven memo runs belong to the submission's job so they can be cancelled
+    let job = submission_id.map(|id| jobs::start_job(&jobs::submission_job_key(id)));
+
     if submission_id.is_some() {
         // Delete old files on disk
         if memo_out_dir.exists() {
@@ -346,13 +357,6 @@ pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
             .map_err(|e| format!("Failed to delete old memo outputs: {}", e))?;
     }
 
-    // Load archives with helpers
-    let archive_paths = vec![
-        first_archive_in(memo_dir(module_id, assignment_id))?,
-        first_archive_in(makefile_dir(module_id, assignment_id))?,
-        first_archive_in(main_dir(module_id, assignment_id))?,
-    ];
-
     let tasks = AssignmentTask::get_by_assignment_id(db, assignment_id)
         .await
         .map_err(|e| format!("DB error loading tasks: {}", e))?;
@@ -364,29 +368,15 @@ pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
     // Prepare HTTP client
     let client = Client::new();
 
-    let host = config::code_manager_host();
-
-    let port = config::code_manager_port();
+    // Read common archives once to avoid repeated storage reads
+    let base_files = load_memo_base_files(module_id, assignment_id).await?;
 
-    let code_manager_url = format!("http://{}:{}", host, port);
-
-    // Read common archives once to avoid repeated disk IO
-    let mut base_files: Vec<(String, Vec<u8>)> = Vec::new();
-    for archive_path in &archive_paths {
-        let content = std::fs::read(archive_path)
-            .map_err(|e| format!("Failed to read archive file {:?}: {}", archive_path, e))?;
-        let file_name = archive_path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| format!("Invalid archive filename: {:?}", archive_path))?
-            .to_string();
-        base_files.push((file_name, content));
-    }
+    let config_value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize ExecutionConfig: {}", e))?;
 
     use std::sync::Arc;
     use tokio::sync::Semaphore;
     use tokio::task::JoinSet;
-    use tokio::time::{Duration, sleep};
 
     let max_concurrency = std::cmp::max(
         1,
@@ -402,141 +392,29 @@ pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
         if task.task_type == TaskType::Coverage {
             continue;
         }
-        let filename = format!("task_{}_output.txt", task.task_number);
         let task_files_base = base_files.clone();
         let client_cloned = client.clone();
-        let cm_url = code_manager_url.clone();
-        let config_value = serde_json::to_value(&config)
-            .map_err(|e| format!("Failed to serialize ExecutionConfig: {}", e))?;
+        let config_value = config_value.clone();
         let db_cloned = db.clone();
+        let job_cloned = job.clone();
         let sem = semaphore.clone();
         join_set.spawn(async move {
             let _permit = sem.acquire_owned().await.ok();
-            // Apply overwrites for this task
-            let mut files = task_files_base.clone();
-            let overwrite_dir = overwrite_task_dir(module_id, assignment_id, task.task_number);
-            if overwrite_dir.exists() {
-                if let Ok(entries) = std::fs::read_dir(&overwrite_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() {
-                            if let Ok(content) = std::fs::read(&path) {
-                                if let Some(file_name) = path
-                                    .file_name()
-                                    .and_then(|s| s.to_str())
-                                    .map(|s| s.to_string())
-                                {
-                                    files.retain(|(name, _)| name != &file_name);
-                                    files.push((file_name, content));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Ensure makefile.zip is always included last
-            let makefile_archive_path = first_archive_in(makefile_dir(module_id, assignment_id))?;
-            let makefile_content = std::fs::read(&makefile_archive_path)
-                .map_err(|e| format!("Failed to read makefile archive: {}", e))?;
-            let makefile_filename = makefile_archive_path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| {
-                    format!(
-                        "Invalid makefile archive filename: {:?}",
-                        makefile_archive_path
-                    )
-                })?
-                .to_string();
-
-            files.retain(|(name, _)| name != &makefile_filename); // remove any overwrite copy
-            files.push((makefile_filename, makefile_content));
-
-            let request_body = serde_json::json!({
-                "config": config_value,
-                "commands": [task.command.clone()],
-                "files": files,
-            });
-
-            // Fire request
-            let resp_res = client_cloned
-                .post(format!("{}/run", cm_url))
-                .json(&request_body)
-                .send()
-                .await;
-            let response = match resp_res {
-                Ok(r) => r,
-                Err(e) => {
-                    return Err(format!(
-                        "Failed to send request to code_manager (task {}): {}",
-                        task.task_number, e
-                    ));
-                }
-            };
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let text = response.text().await.unwrap_or_default();
-                return Err(format!(
-                    "code_manager error for task {}: {} {}",
-                    task.task_number, status, text
-                ));
+            if job_cloned.as_ref().is_some_and(|j| j.is_cancelled()) {
+                return Err("Run cancelled".to_string());
             }
+            let output_combined = run_memo_task(
+                &client_cloned,
+                &config_value,
+                module_id,
+                assignment_id,
+                &task,
+                task_files_base,
+                job_cloned.as_ref(),
+            )
+            .await?;
 
-            let resp_json: serde_json::Value = match response.json().await {
-                Ok(v) => v,
-                Err(e) => {
-                    return Err(format!(
-                        "Failed to parse response JSON for task {}: {}",
-                        task.task_number, e
-                    ));
-                }
-            };
-
-            let output_vec = resp_json
-                .get("output")
-                .and_then(|v| v.as_array())
-                .ok_or_else(|| {
-                    format!(
-                        "Response missing 'output' array for task {}",
-                        task.task_number
-                    )
-                })?
-                .iter()
-                .map(|val| val.as_str().unwrap_or("").to_string())
-                .collect::<Vec<String>>();
-            let output_combined = output_vec.join("\n");
-
-            // Save with retries to mitigate transient locks
-            for attempt in 0..5 {
-                match db::models::assignment_memo_output::Model::save_file(
-                    &db_cloned,
-                    assignment_id,
-                    task.id,
-                    &filename,
-                    output_combined.as_bytes(),
-                )
-                .await
-                {
-                    Ok(_) => return Ok::<(), String>(()),
-                    Err(e) => {
-                        let backoff_ms = 20u64 * (1 << attempt);
-                        println!(
-                            "Retry {}/5 saving memo output for task {} ({} ms): {}",
-                            attempt + 1,
-                            task.task_number,
-                            backoff_ms,
-                            e
-                        );
-                        sleep(Duration::from_millis(backoff_ms)).await;
-                    }
-                }
-            }
-            Err(format!(
-                "Failed to save memo output for task {} after retries",
-                task.task_number
-            ))
+            save_memo_output_with_retries(&db_cloned, assignment_id, &task, &output_combined).await
         });
     }
 
@@ -565,28 +443,140 @@ pub async fn create_memo_outputs_for_all_tasks_with_submission_id(
     Ok(())
 }
 
-use db::models::assignment_submission_output::Model as SubmissionOutputModel;
+use db::models::assignment_submission::{Model as SubmissionModel, SubmissionRunState};
+use db::models::assignment_submission_output::{
+    Model as SubmissionOutputModel, NewOutput as NewSubmissionOutput,
+};
+
+/// Output of one task from a submission run, as returned to the caller.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskRunOutput {
+    pub task_id: i64,
+    pub task_number: i64,
+    /// What was (or, in a dry run, would have been) stored; for coverage tasks this is the
+    /// processed coverage report.
+    pub output: String,
+    pub full_size_bytes: u64,
+    pub truncated: bool,
+    pub metrics: Option<metrics::CommandMetrics>,
+}
 
 /// Runs all configured tasks for a given assignment ID and student attempt by:
 /// 1. Validating submission files
 /// 2. Extracting archive files (submission, makefile, main)
 /// 3. Running the configured commands inside Docker
-/// 4. Saving the output to disk and database as `assignment_submission_output`
+/// 4. Saving the output to disk and database as `assignment_submission_output`, plus any
+///    `/output` files matching the task's artifact patterns under the attempt's `artifacts` dir
+///
+/// The old outputs are only replaced once every task has finished, in one transaction, so a
+/// failed run leaves the previous outputs intact. Progress is tracked in the submission's
+/// `submission_run_state`.
+///
+/// With `dry_run` set, step 4 is skipped: existing outputs are left untouched, no coverage,
+/// valgrind or massif report or artifact is written, and the outputs are only returned (sorted
+/// by task number).
 pub async fn create_submission_outputs_for_all_tasks(
     db: &DatabaseConnection,
     submission_id: i64,
+    dry_run: bool,
+) -> Result<Vec<TaskRunOutput>, String> {
+    run_submission_tasks(db, submission_id, None, dry_run, None).await
+}
+
+/// Same as [`create_submission_outputs_for_all_tasks`], but when `output_sink` is provided each
+/// task is run through code_manager's `/run/stream` endpoint and its stdout/stderr is forwarded
+/// to the sink as [`TaskOutputChunk`]s while the command is still running.
+///
+/// The saved outputs are identical either way; the sink only receives a live copy.
+pub async fn create_submission_outputs_for_all_tasks_streaming(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    output_sink: Option<TaskOutputSink>,
+) -> Result<(), String> {
+    run_submission_tasks(db, submission_id, output_sink, false, None)
+        .await
+        .map(|_| ())
+}
+
+/// `main_archive` replaces the assignment's stored Main archive for this run when given.
+async fn run_submission_tasks(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    output_sink: Option<TaskOutputSink>,
+    dry_run: bool,
+    main_archive: Option<(String, Vec<u8>)>,
+) -> Result<Vec<TaskRunOutput>, String> {
+    if dry_run {
+        return run_and_save_submission_tasks(db, submission_id, output_sink, true, main_archive)
+            .await;
+    }
+
+    if let Err(e) =
+        SubmissionModel::set_run_state(db, submission_id, SubmissionRunState::RunningTasks).await
+    {
+        println!("Failed to record run state for submission {}: {}", submission_id, e);
+    }
+    let result =
+        run_and_save_submission_tasks(db, submission_id, output_sink, false, main_archive).await;
+    let state = match &result {
+        Ok(_) => SubmissionRunState::OutputsSaved,
+        Err(_) => SubmissionRunState::Failed,
+    };
+    if let Err(e) = SubmissionModel::set_run_state(db, submission_id, state).await {
+        println!("Failed to record run state for submission {}: {}", submission_id, e);
+    }
+    result
+}
+
+/// Saves a run's outputs in place of the old ones, retrying a few times so a briefly locked
+/// database doesn't throw the whole run away.
+async fn replace_submission_outputs(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    outputs: Vec<NewSubmissionOutput>,
 ) -> Result<(), String> {
+    use tokio::time::{Duration, sleep};
+
+    let mut last_err = None;
+    for attempt in 0..5 {
+        match SubmissionOutputModel::replace_for_submission(db, submission_id, outputs.clone())
+            .await
+        {
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                let backoff_ms = 20u64 * (1 << attempt);
+                println!(
+                    "Retry {}/5 saving outputs for submission {} ({} ms): {}",
+                    attempt + 1,
+                    submission_id,
+                    backoff_ms,
+                    e
+                );
+                last_err = Some(e);
+                sleep(Duration::from_millis(backoff_ms)).await;
+            }
+        }
+    }
+    Err(format!(
+        "Failed to save submission outputs: {}",
+        last_err.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
+
+async fn run_and_save_submission_tasks(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    output_sink: Option<TaskOutputSink>,
+    dry_run: bool,
+    main_archive: Option<(String, Vec<u8>)>,
+) -> Result<Vec<TaskRunOutput>, String> {
     use crate::validate_files::validate_submission_files;
     use db::models::assignment::Entity as Assignment;
     use db::models::assignment_submission::Entity as AssignmentSubmission;
     use reqwest::Client;
     use sea_orm::EntityTrait;
-    use serde_json::json;
-    use tokio::fs::read;
 
-    SubmissionOutputModel::delete_for_submission(db, submission_id)
-        .await
-        .map_err(|e| format!("Failed to clear old submission outputs: {}", e))?;
+    let job = jobs::start_job(&jobs::submission_job_key(submission_id));
 
     // Fetch submission
     let submission = AssignmentSubmission::find_by_id(submission_id)
@@ -609,7 +599,7 @@ pub async fn create_submission_outputs_for_all_tasks(
     let module_id = assignment.module_id;
 
     // Validate files (unchanged)
-    validate_submission_files(module_id, assignment_id, user_id, attempt_number)?;
+    validate_submission_files(module_id, assignment_id, user_id, attempt_number).await?;
 
     // Load config (unchanged)
     let config = ExecutionConfig::get_execution_config(module_id, assignment_id)
@@ -618,12 +608,21 @@ pub async fn create_submission_outputs_for_all_tasks(
     // Paths via helpers
     let submission_path = attempt_dir(module_id, assignment_id, user_id, attempt_number);
 
-    // Standard archive paths (for non-code-coverage tasks)
-    let archive_paths = vec![
-        first_archive_in(&submission_path)?,
-        first_archive_in(makefile_dir(module_id, assignment_id))?,
-        first_archive_in(main_dir(module_id, assignment_id))?,
-    ];
+    // Archived as uploaded, or plain source files zipped on the fly
+    let submission_file = submission_files::load_submission_file(
+        &submission_path,
+        submission_id,
+        &submission.filename,
+        &ArchiveLimits::from(&config),
+    )
+    .await?;
+
+    // Standard archives (for non-code-coverage tasks)
+    let makefile_archive = first_archive_in(&makefile_dir(module_id, assignment_id)).await?;
+    let main_archive = match main_archive {
+        Some(main) => main,
+        None => first_archive_in(&main_dir(module_id, assignment_id)).await?,
+    };
 
     // // Code coverage archive paths (submission + memo + makefile, no main)
     // let code_coverage_archive_paths = vec![
@@ -639,65 +638,62 @@ pub async fn create_submission_outputs_for_all_tasks(
 
     if tasks.is_empty() {
         println!("No tasks found for assignment {}", assignment_id);
-        return Ok(());
+        if !dry_run {
+            replace_submission_outputs(db, submission_id, Vec::new()).await?;
+        }
+        return Ok(Vec::new());
     }
 
     // Load standard files
-    let mut files = Vec::new();
-    for path in &archive_paths {
-        let content = read(path)
-            .await
-            .map_err(|e| format!("Failed to read file {:?}: {}", path, e))?;
-        let filename = path
-            .file_name()
-            .and_then(|s| s.to_str())
-            .ok_or_else(|| format!("Invalid filename: {:?}", path))?
-            .to_string();
-        files.push((filename, content));
-    }
+    let files = vec![
+        submission_file.clone(),
+        makefile_archive.clone(),
+        main_archive,
+    ];
 
     // Only build coverage files if at least one task needs it
     let mut code_coverage_files = Vec::new();
     if tasks.iter().any(|t| t.task_type == TaskType::Coverage) {
-        let code_coverage_archive_paths = vec![
-            first_archive_in(&submission_path)?,
-            first_archive_in(makefile_dir(module_id, assignment_id))?,
-            first_archive_in(memo_dir(module_id, assignment_id))?,
+        code_coverage_files = vec![
+            submission_file,
+            makefile_archive.clone(),
+            first_archive_in(&memo_dir(module_id, assignment_id)).await?,
         ];
-
-        for path in &code_coverage_archive_paths {
-            let content = read(path)
-                .await
-                .map_err(|e| format!("Failed to read file {:?}: {}", path, e))?;
-            let filename = path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| format!("Invalid filename: {:?}", path))?
-                .to_string();
-            code_coverage_files.push((filename, content));
-        }
     }
 
     // HTTP client setup
-    let host = config::code_manager_host();
-    let port = config::code_manager_port();
-    let code_manager_url = format!("http://{}:{}/run", host, port);
     let client = Client::new();
 
     // Serialize config
     let config_value = serde_json::to_value(&config)
         .map_err(|e| format!("Failed to serialize execution config: {}", e))?;
 
+    // Build once up front so normal tasks can reuse the compiled output instead of rebuilding
+    let build_artifacts = match config.project.build_command.as_deref() {
+        Some(cmd) if !cmd.trim().is_empty() => {
+            build_cache::build_once(
+                &client,
+                &config_value,
+                cmd,
+                &files,
+                &job,
+            )
+            .await
+        }
+        _ => None,
+    };
+
     // Run tasks concurrently
     use std::sync::Arc;
     use tokio::sync::{Mutex, Semaphore};
     use tokio::task::JoinSet;
-    use tokio::time::{Duration, sleep};
     let mut join_set = JoinSet::new();
 
     let valgrind_outputs = Arc::new(Mutex::new(Vec::<(i64, String)>::new()));
+    // Rows to store once every task is done
+    let new_outputs = Arc::new(Mutex::new(Vec::<NewSubmissionOutput>::new()));
 
-    // Bounded concurrency to avoid DB write contention
+    // Bounded concurrency to avoid overloading code_manager
     let max_concurrency = std::cmp::max(
         1,
         std::thread::available_parallelism()
@@ -706,6 +702,20 @@ pub async fn create_submission_outputs_for_all_tasks(
             / 2,
     );
     let semaphore = Arc::new(Semaphore::new(max_concurrency));
+    let max_output_bytes = config.output.max_output_bytes;
+    let max_artifact_bytes = config.output.max_artifact_bytes;
+
+    if !dry_run {
+        // Artifacts of an earlier run of this attempt are replaced, not merged
+        let artifacts_dir =
+            submission_artifacts_dir(module_id, assignment_id, user_id, attempt_number);
+        match std::fs::remove_dir_all(&artifacts_dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                println!("Failed to clear old artifacts: {}", e);
+            }
+            _ => {}
+        }
+    }
 
     for task in tasks {
         let filename = format!(
@@ -716,209 +726,221 @@ pub async fn create_submission_outputs_for_all_tasks(
         // Choose appropriate file set based on whether this is a code coverage task
         let task_files_base = if task.task_type == TaskType::Coverage {
             code_coverage_files.clone()
+        } else if let Some(artifacts) = &build_artifacts {
+            // Prebuilt /code already holds the extracted sources
+            vec![(
+                build_cache::BUILD_ARTIFACTS_FILENAME.to_string(),
+                artifacts.clone(),
+            )]
         } else {
             files.clone()
         };
 
-        let cm_url = code_manager_url.clone();
         let client_cloned = client.clone();
         let config_value_cloned = config_value.clone();
-        let db_cloned = db.clone();
+        let new_outputs_cloned = new_outputs.clone();
         let module_id_cloned = module_id;
         let assignment_id_cloned = assignment_id;
         let submission_path_cloned = submission_path.clone();
         let whitelist_cloned = config.code_coverage.whitelist.clone();
         let whitelist = whitelist_cloned.clone();
         let valgrind_outputs_cloned = valgrind_outputs.clone();
+        let output_sink_cloned = output_sink.clone();
+        let job_cloned = job.clone();
+        let makefile_archive_cloned = makefile_archive.clone();
 
         let sem = semaphore.clone();
         join_set.spawn(async move {
             let _permit = sem.acquire_owned().await.ok();
+            if job_cloned.is_cancelled() {
+                return None;
+            }
             // Prepare task-specific files (apply overwrites)
             let mut task_files = task_files_base.clone();
             let overwrite_dir =
                 overwrite_task_dir(module_id_cloned, assignment_id_cloned, task.task_number);
-            if overwrite_dir.exists() {
-                if let Ok(entries) = std::fs::read_dir(&overwrite_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() {
-                            if let Ok(content) = std::fs::read(&path) {
-                                if let Some(file_name) = path
-                                    .file_name()
-                                    .and_then(|s| s.to_str())
-                                    .map(|s| s.to_string())
-                                {
-                                    task_files.retain(|(name, _)| name != &file_name);
-                                    task_files.push((file_name, content));
-                                }
-                            }
-                        }
-                    }
-                }
+            if let Err(e) = apply_overwrites(&overwrite_dir, &mut task_files).await {
+                println!(
+                    "Failed to load overwrite files for task {}: {}",
+                    task.task_number, e
+                );
+                return None;
             }
 
             // Ensure makefile.zip is always included last
-            match first_archive_in(makefile_dir(module_id_cloned, assignment_id_cloned)) {
-                Ok(makefile_archive_path) => {
-                    match std::fs::read(&makefile_archive_path) {
-                        Ok(makefile_content) => {
-                            if let Some(makefile_filename) = makefile_archive_path
-                                .file_name()
-                                .and_then(|s| s.to_str())
-                                .map(|s| s.to_string())
-                            {
-                                task_files.retain(|(name, _)| name != &makefile_filename);
-                                task_files.push((makefile_filename, makefile_content));
-                            }
-                        }
-                        Err(e) => {
-                            println!("Failed to read makefile archive for task {}: {}", task.task_number, e);
-                            return false;
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("Failed to find makefile archive for task {}: {}", task.task_number, e);
-                    return false;
-                }
-            }
-
+            let (makefile_filename, makefile_content) = makefile_archive_cloned;
+            task_files.retain(|(name, _)| name != &makefile_filename);
+            task_files.push((makefile_filename, makefile_content));
 
             // Compose request
-            let request_body = json!({
-                "config": config_value_cloned,
-                "commands": [task.command.clone()],
-                "files": task_files,
-            });
-
-            let response_res = client_cloned.post(&cm_url).json(&request_body).send().await;
-            match response_res {
+            let artifact_patterns = task.artifact_patterns();
+            let request = RunRequest {
+                config: config_value_cloned,
+                commands: vec![task.command.clone()],
+                files: task_files,
+                return_output_files: !artifact_patterns.is_empty() && !dry_run,
+                job_id: Some(job_cloned.job_id().to_string()),
+                priority: job_cloned.priority(),
+                ..Default::default()
+            };
+
+            let run_result = match &output_sink_cloned {
+                Some(sink) => {
+                    code_manager_client::run_streamed(
+                        &client_cloned,
+                        request,
+                        task.id,
+                        task.task_number,
+                        sink,
+                    )
+                    .await
+                }
+                None => code_manager_client::run(&client_cloned, request).await,
+            };
+            let run = match run_result {
+                Ok(run) => run,
                 Err(e) => {
-                    println!("HTTP request failed for task {}: {}", task.task_number, e);
-                    return false;
+                    println!("Run failed for task {}: {}", task.task_number, e);
+                    return None;
                 }
-                Ok(response) => {
-                    if !response.status().is_success() {
-                        let text = response.text().await.unwrap_or_default();
-                        println!("Code manager error for task {}: {}", task.task_number, text);
-                        return false;
-                    }
+            };
 
-                    let resp_json: serde_json::Value = match response.json().await {
-                        Ok(v) => v,
-                        Err(e) => {
+            if let Some(output_files) = &run.output_files {
+                let dest = submission_task_artifacts_dir(
+                    module_id_cloned,
+                    assignment_id_cloned,
+                    user_id,
+                    attempt_number,
+                    task.task_number,
+                );
+                match task_artifacts::save_artifacts(
+                    output_files,
+                    &artifact_patterns,
+                    max_artifact_bytes,
+                    &dest,
+                ) {
+                    Ok(artifacts) => {
+                        if !artifacts.skipped.is_empty() {
                             println!(
-                                "Failed to parse response JSON for task {}: {}",
-                                task.task_number, e
+                                "Skipped {} artifact(s) for task {} over the {} byte limit",
+                                artifacts.skipped.len(),
+                                task.task_number,
+                                max_artifact_bytes
                             );
-                            return false;
                         }
-                    };
+                    }
+                    Err(e) => {
+                        println!("Failed to save artifacts for task {}: {}", task.task_number, e);
+                    }
+                }
+            }
 
-                    let output_vec = match resp_json.get("output").and_then(|v| v.as_array()) {
-                        Some(arr) => arr
-                            .iter()
-                            .map(|val| val.as_str().unwrap_or("").to_string())
-                            .collect::<Vec<String>>(),
-                        None => {
-                            println!(
-                                "Response missing 'output' array for task {}",
-                                task.task_number
-                            );
-                            Vec::new()
+            let output_combined = run.output.join("\n");
+            // One command per task, so its metrics are the first entry
+            let task_metrics = run.metrics.into_iter().next();
+
+            if task.task_type == TaskType::Coverage {
+                match CoverageProcessor::process_report(config.project.language, &output_combined, &whitelist) {
+                    Ok(coverage_json) => {
+                        let run_output = TaskRunOutput {
+                            task_id: task.id,
+                            task_number: task.task_number,
+                            full_size_bytes: coverage_json.len() as u64,
+                            output: coverage_json,
+                            truncated: false,
+                            metrics: task_metrics,
+                        };
+                        if dry_run {
+                            return Some(run_output);
                         }
-                    };
-
-                    let output_combined = output_vec.join("\n");
-
-                    if task.task_type == TaskType::Coverage {
-                        match CoverageProcessor::process_report(config.project.language, &output_combined, &whitelist) {
-                            Ok(coverage_json) => {
-                                let coverage_report_path = submission_path_cloned.join("coverage_report.json");
-                                match std::fs::write(&coverage_report_path, &coverage_json) {
-                                    Ok(_) => {
-                                        return true;
-                                    }
-                                    Err(e) => {
-                                        println!("Failed to save coverage report to attempt directory: {}", e);
-                                    }
-                                }
+                        let coverage_report_path = submission_path_cloned.join("coverage_report.json");
+                        match std::fs::write(&coverage_report_path, &run_output.output) {
+                            Ok(_) => {
+                                return Some(run_output);
                             }
                             Err(e) => {
-                                println!("Failed to process coverage report for task {}: {}", task.task_number, e);
+                                println!("Failed to save coverage report to attempt directory: {}", e);
                             }
                         }
-                    } else {
-                        let mut task_saved = false;
-                        for attempt in 0..5 {
-                            match SubmissionOutputModel::save_file(
-                                &db_cloned,
-                                task.id,
-                                submission_id,
-                                &filename,
-                                output_combined.as_bytes(),
-                            )
-                            .await
-                            {
-                                Ok(_) => {
-                                    task_saved = true;
-                                    break;
-                                }
-                                Err(e) => {
-                                    let backoff_ms = 20u64 * (1 << attempt);
-                                    println!(
-                                        "Retry {}/5 saving output for task {} ({} ms): {}",
-                                        attempt + 1,
-                                        task.task_number,
-                                        backoff_ms,
-                                        e
-                                    );
-                                    sleep(Duration::from_millis(backoff_ms)).await;
-                                }
-                            }
-                        }
-
-                        if !task_saved {
-                            println!(
-                                "Failed to save submission output for task {} after retries",
-                                task.task_number
-                            );
-                            return false;
-                        }
+                    }
+                    Err(e) => {
+                        println!("Failed to process coverage report for task {}: {}", task.task_number, e);
+                    }
+                }
+            } else {
+                // Cap what is stored so a runaway print loop can't bloat the output row
+                let stored = output_limit::limit_output(output_combined, max_output_bytes);
+                if stored.is_truncated() {
+                    println!(
+                        "Truncated output for task {} ({} of {} bytes dropped)",
+                        task.task_number, stored.truncated_bytes, stored.full_size
+                    );
+                }
 
-                        if task.task_type == TaskType::Valgrind {
-                            let task_number = task.task_number;
-                            let output_for_valgrind = output_combined.clone();
-                            let mut outputs = valgrind_outputs_cloned.lock().await;
-                            outputs.push((task_number, output_for_valgrind));
-                            drop(outputs);
+                if !dry_run {
+                    let (wall_time_ms, cpu_time_ms, max_rss_bytes) = match &task_metrics {
+                        Some(m) => {
+                            let (wall, cpu, rss) = m.as_columns();
+                            (Some(wall), cpu, rss)
                         }
+                        None => (None, None, None),
+                    };
+                    new_outputs_cloned.lock().await.push(NewSubmissionOutput {
+                        task_id: task.id,
+                        filename: filename.clone(),
+                        bytes: stored.text.as_bytes().to_vec(),
+                        full_size: stored.full_size,
+                        wall_time_ms,
+                        cpu_time_ms,
+                        max_rss_bytes,
+                    });
+                }
 
-                        return true;
-                    }
-                    false
+                if task.task_type == TaskType::Valgrind {
+                    let task_number = task.task_number;
+                    let output_for_valgrind = stored.text.clone();
+                    let mut outputs = valgrind_outputs_cloned.lock().await;
+                    outputs.push((task_number, output_for_valgrind));
+                    drop(outputs);
                 }
+
+                return Some(TaskRunOutput {
+                    task_id: task.id,
+                    task_number: task.task_number,
+                    truncated: stored.is_truncated(),
+                    full_size_bytes: stored.full_size,
+                    output: stored.text,
+                    metrics: task_metrics,
+                });
             }
+            None
         });
     }
 
     // Drain all tasks
-    let mut saved_any = 0usize;
+    let mut task_outputs = Vec::new();
     while let Some(res) = join_set.join_next().await {
-        if let Ok(saved) = res {
-            if saved {
-                saved_any += 1;
-            }
+        if let Ok(Some(output)) = res {
+            task_outputs.push(output);
         }
     }
+    task_outputs.sort_by_key(|o| o.task_number);
 
-    if saved_any == 0 {
+    if job.is_cancelled() {
+        return Err("Run cancelled".to_string());
+    }
+
+    if task_outputs.is_empty() {
         return Err("No submission outputs were generated".to_string());
     }
 
+    if !dry_run {
+        let outputs = std::mem::take(&mut *new_outputs.lock().await);
+        replace_submission_outputs(db, submission_id, outputs).await?;
+    }
+
     let collected_outputs = valgrind_outputs.lock().await;
-    if !collected_outputs.is_empty() {
+    if !dry_run && !collected_outputs.is_empty() {
         match ValgrindProcessor::process_report(&collected_outputs) {
             Ok(valgrind_json) => {
                 let valgrind_report_path = submission_path.join("valgrind_report.json");
@@ -933,9 +955,100 @@ pub async fn create_submission_outputs_for_all_tasks(
                 println!("Failed to process combined valgrind report: {}", e);
             }
         }
+
+        match MassifProcessor::process_report(&collected_outputs) {
+            Ok(Some(massif_json)) => {
+                let massif_report_path = submission_path.join("massif_report.json");
+                if let Err(e) = std::fs::write(&massif_report_path, &massif_json) {
+                    println!("Failed to save combined massif report: {}", e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                println!("Failed to process combined massif report: {}", e);
+            }
+        }
     }
 
-    Ok(())
+    Ok(task_outputs)
+}
+
+/// Runs the interpreter with `generated_string` on code_manager and returns the source it
+/// generated, without the trailing `Retcode:` lines.
+async fn generate_main_source(
+    submission_id: i64,
+    interpreter: &db::models::assignment_interpreter::Model,
+    config: &ExecutionConfig,
+    generated_string: &str,
+) -> Result<String, String> {
+    use std::env;
+    use util::languages::LanguageExt;
+
+    // --- GENERATOR BRANCH (original intent) ---
+    // The interpreter is a true generator: run it and expect source code on stdout.
+    let lang = config.project.language;
+    let interpreter_bytes = interpreter
+        .load_file()
+        .await
+        .map_err(|e| format!("Failed to load interpreter file from storage: {}", e))?;
+
+    // Combine the interpreter command with the GA-produced string.
+    // e.g., "python3 interpreter.py <args>"
+    let command = format!("{} \"{}\"", interpreter.command, generated_string);
+
+    let config_value = serde_json::to_value(config)
+        .map_err(|e| format!("Failed to serialize execution config: {}", e))?;
+
+    // Send interpreter.zip + command to the code manager
+    let client = Client::new();
+    let job = jobs::start_job(&jobs::submission_job_key(submission_id));
+    let request = RunRequest {
+        config: config_value,
+        commands: vec![command],
+        files: vec![("interpreter.zip".to_string(), interpreter_bytes)],
+        interpreter: true,
+        job_id: Some(job.job_id().to_string()),
+        priority: job.priority(),
+        ..Default::default()
+    };
+
+    let run = code_manager_client::run(&client, request).await?;
+
+    let mut combined_output = run.output.join("\n");
+
+    if env::var("GA_DEBUG_PRINT").ok().as_deref() == Some("1") {
+        eprintln!(
+            "[DEBUG] generator output preview = {}",
+            &combined_output.chars().take(20000).collect::<String>()
+        );
+    }
+
+    // Sanity-check: generator should produce plausible source
+    let looks_like_source = lang.looks_like_source(&combined_output);
+
+    if !looks_like_source {
+        println!(
+            "[DEBUG] generator output does not look like source code: {}",
+            combined_output
+        );
+        return Err("Interpreter did not return plausible source code".to_string());
+    }
+
+    combined_output = combined_output
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("Retcode:"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(combined_output)
+}
+
+/// Main archive generated by the interpreter for one payload, not yet saved anywhere.
+struct InterpretedMain {
+    module_id: i64,
+    assignment_id: i64,
+    file_name: String,
+    data: Vec<u8>,
 }
 
 pub async fn create_main_from_interpreter(
@@ -943,18 +1056,39 @@ pub async fn create_main_from_interpreter(
     submission_id: i64,
     generated_string: &str,
 ) -> Result<(), String> {
-    use db::models::assignment::Entity as AssignmentEntity;
     use db::models::assignment_file::{FileType, Model as AssignmentFileModel};
+
+    let main = build_main_from_interpreter(db, submission_id, generated_string).await?;
+
+    AssignmentFileModel::save_file(
+        db,
+        main.assignment_id,
+        main.module_id,
+        FileType::Main,
+        &main.file_name,
+        &main.data,
+    )
+    .await
+    .map_err(|e| format!("Failed to save zipped main file: {}", e))?;
+
+    Ok(())
+}
+
+/// Runs the interpreter for `generated_string` (or reuses its cached source) and zips the
+/// generated source as the assignment's Main file, returning the archive in memory.
+async fn build_main_from_interpreter(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    generated_string: &str,
+) -> Result<InterpretedMain, String> {
+    use db::models::assignment::Entity as AssignmentEntity;
     use db::models::assignment_interpreter::{
         Column as InterpreterColumn, Entity as AssignmentInterpreterEntity,
     };
     use db::models::assignment_submission::Entity as AssignmentSubmissionEntity;
 
-    use reqwest::Client;
-    use serde_json::json;
-    use std::env;
     use std::io::Write;
-    use util::execution_config::ExecutionConfig;
+    use std::time::{Duration, Instant};
     use util::languages::LanguageExt;
     use zip::write::{FileOptions, ZipWriter};
 
@@ -1060,83 +1194,46 @@ pub async fn create_main_from_interpreter(
     //     return Ok(());
     // }
 
-    // --- GENERATOR BRANCH (original intent) ---
-    // The interpreter is a true generator: run it and expect source code on stdout.
-    let interpreter_bytes = interpreter
-        .load_file()
-        .map_err(|e| format!("Failed to load interpreter file from disk: {}", e))?;
-
-    // Combine the interpreter command with the GA-produced string.
-    // e.g., "python3 interpreter.py <args>"
-    let command = format!("{} \"{}\"", interpreter.command, generated_string);
-
-    let host = config::code_manager_host();
-    let port = config::code_manager_port();
-    let url = format!("http://{}:{}/run", host, port);
-
-    let config_value = serde_json::to_value(&config)
-        .map_err(|e| format!("Failed to serialize execution config: {}", e))?;
-
-    // Send interpreter.zip + command to the code manager
-    let client = Client::new();
-    let payload = json!({
-        "config": config_value,
-        "commands": [command],
-        "files": [("interpreter.zip", interpreter_bytes)],
-        "interpreter":true,
-    });
-
-    let resp = client
-        .post(&url)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to send request to code_manager: {}", e))?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!(
-            "code_manager returned error status {}: Response body: {}",
-            status, body
-        ));
-    }
-
-    #[derive(serde::Deserialize)]
-    struct RunResponse {
-        output: Vec<String>,
-    }
-
-    let run_resp: RunResponse = resp
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse code_manager response: {}", e))?;
-
-    let mut combined_output = run_resp.output.join("\n");
-
-    if env::var("GA_DEBUG_PRINT").ok().as_deref() == Some("1") {
-        eprintln!(
-            "[DEBUG] generator output preview = {}",
-            &combined_output.chars().take(20000).collect::<String>()
-        );
-    }
-
-    // Sanity-check: generator should produce plausible source
-    let looks_like_source = lang.looks_like_source(&combined_output);
-
-    if !looks_like_source {
+    // Reuse the source an earlier run generated for this payload, if still cached
+    let cache_key = interpreter_cache::InterpreterCacheKey {
+        assignment_id,
+        interpreter_id: interpreter.id,
+        payload: generated_string.to_string(),
+    };
+    let cache_ttl = Duration::from_secs(config.gatlam.interpreter_cache_ttl_secs);
+    let cached = {
+        let mut cache = interpreter_cache::shared()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let source = cache.get(&cache_key, cache_ttl, Instant::now());
         println!(
-            "[DEBUG] generator output does not look like source code: {}",
-            combined_output
+            "Interpreter cache {} for assignment {} ({} hits / {} lookups, {:.0}% hit rate)",
+            if source.is_some() { "hit" } else { "miss" },
+            assignment_id,
+            cache.hits(),
+            cache.hits() + cache.misses(),
+            cache.hit_rate() * 100.0
         );
-        return Err("Interpreter did not return plausible source code".to_string());
-    }
-
-    combined_output = combined_output
-        .lines()
-        .filter(|line| !line.trim_start().starts_with("Retcode:"))
-        .collect::<Vec<_>>()
-        .join("\n");
+        source
+    };
+    let combined_output = match cached {
+        Some(source) => source,
+        None => {
+            let source =
+                generate_main_source(submission_id, &interpreter, &config, generated_string)
+                    .await?;
+            interpreter_cache::shared()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(
+                    cache_key,
+                    source.clone(),
+                    config.gatlam.interpreter_cache_size,
+                    Instant::now(),
+                );
+            source
+        }
+    };
 
     // Zip the generated source as Main.*
     let zip_ext = std::path::Path::new(main_file_name)
@@ -1159,18 +1256,12 @@ pub async fn create_main_from_interpreter(
             .map_err(|e| format!("Failed to finish zip: {}", e))?;
     }
 
-    AssignmentFileModel::save_file(
-        db,
-        assignment_id,
+    Ok(InterpretedMain {
         module_id,
-        FileType::Main,
-        &zip_filename,
-        &zip_data,
-    )
-    .await
-    .map_err(|e| format!("Failed to save zipped main file: {}", e))?;
-
-    Ok(())
+        assignment_id,
+        file_name: zip_filename,
+        data: zip_data,
+    })
 }
 
 /// Runs the interpreter for a given submission, generating and processing
@@ -1214,15 +1305,159 @@ pub async fn run_interpreter(
 
     let assignment_id = submission.assignment_id;
 
+    // Held across all steps so a cancel also stops the steps that haven't started yet
+    let job = jobs::start_job(&jobs::submission_job_key(submission_id));
+    let check_cancelled = || {
+        if job.is_cancelled() {
+            Err("Run cancelled".to_string())
+        } else {
+            Ok(())
+        }
+    };
+
     // Step 1
+    check_cancelled()?;
     create_main_from_interpreter(db, submission_id, generated_string).await?;
 
     // Step 2
+    check_cancelled()?;
     create_memo_outputs_for_all_tasks_with_submission_id(db, assignment_id, Some(submission_id))
         .await?;
 
     // Step 3
-    create_submission_outputs_for_all_tasks(db, submission_id).await?;
+    check_cancelled()?;
+    create_submission_outputs_for_all_tasks(db, submission_id, false).await?;
 
     Ok(())
 }
+
+/// Outputs of an isolated interpreter run (see [`run_interpreter_isolated`]).
+#[derive(Debug, Clone, Default)]
+pub struct InterpreterRun {
+    /// `(task_number, output)` of every non-coverage task run against the memo, sorted by
+    /// task number.
+    pub memo_outputs: Vec<(i64, String)>,
+    /// Submission outputs of the non-coverage tasks.
+    pub submission_outputs: Vec<TaskRunOutput>,
+    /// Submission outputs of the coverage tasks (the processed coverage reports).
+    pub coverage_outputs: Vec<TaskRunOutput>,
+}
+
+/// Same pipeline as [`run_interpreter`], but nothing is written to the assignment's stores:
+/// the generated Main archive is kept in memory, memo outputs are returned instead of
+/// replacing the assignment's memo output, and the submission is run as a dry run.
+///
+/// This is what GA runs use for every chromosome, so concurrent marking, memo generation and
+/// other GA runs on the same assignment never see (or clobber) a chromosome's files.
+pub async fn run_interpreter_isolated(
+    db: &DatabaseConnection,
+    submission_id: i64,
+    generated_string: &str,
+) -> Result<InterpreterRun, String> {
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+    use tokio::task::JoinSet;
+
+    let job = jobs::start_job(&jobs::submission_job_key(submission_id));
+    let check_cancelled = || {
+        if job.is_cancelled() {
+            Err("Run cancelled".to_string())
+        } else {
+            Ok(())
+        }
+    };
+
+    // Step 1
+    check_cancelled()?;
+    let main = build_main_from_interpreter(db, submission_id, generated_string).await?;
+    let (module_id, assignment_id) = (main.module_id, main.assignment_id);
+    let main_archive = (main.file_name, main.data);
+
+    // Step 2
+    check_cancelled()?;
+    let config = ExecutionConfig::get_execution_config(module_id, assignment_id)
+        .map_err(|e| format!("Failed to load execution config: {}", e))?;
+    let config_value = serde_json::to_value(&config)
+        .map_err(|e| format!("Failed to serialize ExecutionConfig: {}", e))?;
+
+    let tasks = AssignmentTask::get_by_assignment_id(db, assignment_id)
+        .await
+        .map_err(|e| format!("DB error loading tasks: {}", e))?;
+    if tasks.is_empty() {
+        return Err("No tasks are defined for this assignment. Add at least one task before generating memo output.".to_string());
+    }
+    let coverage_task_ids: Vec<i64> = tasks
+        .iter()
+        .filter(|t| t.task_type == TaskType::Coverage)
+        .map(|t| t.id)
+        .collect();
+
+    let base_files = vec![
+        first_archive_in(&memo_dir(module_id, assignment_id)).await?,
+        first_archive_in(&makefile_dir(module_id, assignment_id)).await?,
+        main_archive.clone(),
+    ];
+
+    let client = Client::new();
+    let max_concurrency = std::cmp::max(
+        1,
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            / 2,
+    );
+    let semaphore = Arc::new(Semaphore::new(max_concurrency));
+    let mut join_set = JoinSet::new();
+
+    for task in tasks.into_iter() {
+        if task.task_type == TaskType::Coverage {
+            continue;
+        }
+        let files = base_files.clone();
+        let client = client.clone();
+        let config_value = config_value.clone();
+        let job = job.clone();
+        let sem = semaphore.clone();
+        join_set.spawn(async move {
+            let _permit = sem.acquire_owned().await.ok();
+            if job.is_cancelled() {
+                return Err("Run cancelled".to_string());
+            }
+            let output = run_memo_task(
+                &client,
+                &config_value,
+                module_id,
+                assignment_id,
+                &task,
+                files,
+                Some(&job),
+            )
+            .await?;
+            Ok((task.task_number, output))
+        });
+    }
+
+    let mut memo_outputs = Vec::new();
+    while let Some(res) = join_set.join_next().await {
+        match res {
+            Ok(Ok(output)) => memo_outputs.push(output),
+            Ok(Err(e)) => return Err(e),
+            Err(e) => return Err(format!("Join error: {}", e)),
+        }
+    }
+    memo_outputs.sort_by_key(|(task_number, _)| *task_number);
+
+    // Step 3
+    check_cancelled()?;
+    let (coverage_outputs, submission_outputs) =
+        run_submission_tasks(db, submission_id, None, true, Some(main_archive))
+            .await?
+            .into_iter()
+            .partition(|o| coverage_task_ids.contains(&o.task_id));
+
+    Ok(InterpreterRun {
+        memo_outputs,
+        submission_outputs,
+        coverage_outputs,
+    })
+}
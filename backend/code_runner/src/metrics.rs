@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Resource usage code_manager measured for one command.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    pub wall_time_ms: u64,
+    #[serde(default)]
+    pub cpu_time_ms: Option<u64>,
+    #[serde(default)]
+    pub max_rss_bytes: Option<u64>,
+    /// Killed for writing more than `max_disk_bytes` to `/code` and `/output`.
+    #[serde(default)]
+    pub disk_quota_exceeded: bool,
+}
+
+/// Reads the `metrics` array from a code_manager `/run` response or `done` stream line.
+///
+/// Missing or malformed entries are skipped, so an older code_manager just yields nothing.
+pub(crate) fn parse_metrics(response: &Value) -> Vec<CommandMetrics> {
+    response
+        .get("metrics")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|m| serde_json::from_value(m.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+impl CommandMetrics {
+    /// `(wall_time_ms, cpu_time_ms, max_rss_bytes)` in the shape the output table stores them.
+    pub(crate) fn as_columns(&self) -> (i64, Option<i64>, Option<i64>) {
+        (
+            to_i64(self.wall_time_ms),
+            self.cpu_time_ms.map(to_i64),
+            self.max_rss_bytes.map(to_i64),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_metrics_array() {
+        let response = json!({
+            "output": ["a", "b"],
+            "metrics": [
+                { "wall_time_ms": 120, "cpu_time_ms": 80, "max_rss_bytes": 4096 },
+                {
+                    "wall_time_ms": 5,
+                    "cpu_time_ms": null,
+                    "max_rss_bytes": null,
+                    "disk_quota_exceeded": true
+                }
+            ]
+        });
+
+        let metrics = parse_metrics(&response);
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].as_columns(), (120, Some(80), Some(4096)));
+        assert_eq!(metrics[1].as_columns(), (5, None, None));
+        assert!(!metrics[0].disk_quota_exceeded);
+        assert!(metrics[1].disk_quota_exceeded);
+    }
+
+    #[test]
+    fn missing_metrics_yield_nothing() {
+        assert!(parse_metrics(&json!({ "output": [] })).is_empty());
+        assert!(parse_metrics(&json!({ "metrics": [{ "bogus": 1 }] })).is_empty());
+    }
+}
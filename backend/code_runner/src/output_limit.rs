@@ -0,0 +1,109 @@
+/// Section header code_manager puts before the exit status; kept intact when truncating so the
+/// return code can still be read from stored output.
+const RETURN_CODE_MARKER: &str = "&FITCHFORK&ReturnCode";
+
+/// Task output cut down to fit the configured storage limit.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LimitedOutput {
+    pub text: String,
+    /// Size of the output before truncation, in bytes.
+    pub full_size: u64,
+    pub truncated_bytes: u64,
+}
+
+impl LimitedOutput {
+    pub fn is_truncated(&self) -> bool {
+        self.truncated_bytes > 0
+    }
+}
+
+/// Truncates `output` to roughly `max_bytes`, appending a `…truncated N bytes` marker.
+///
+/// The trailing return-code section is always preserved, and the cut never splits a UTF-8
+/// character. A limit of 0 disables truncation.
+pub fn limit_output(output: String, max_bytes: u64) -> LimitedOutput {
+    let full_size = output.len() as u64;
+    if max_bytes == 0 || full_size <= max_bytes {
+        return LimitedOutput {
+            text: output,
+            full_size,
+            truncated_bytes: 0,
+        };
+    }
+
+    let (body, trailer) = match output.rfind(RETURN_CODE_MARKER) {
+        Some(idx) => output.split_at(idx),
+        None => (output.as_str(), ""),
+    };
+
+    let budget = usize::try_from(max_bytes)
+        .unwrap_or(usize::MAX)
+        .saturating_sub(trailer.len());
+    let mut keep = budget.min(body.len());
+    while !body.is_char_boundary(keep) {
+        keep -= 1;
+    }
+
+    let truncated_bytes = (body.len() - keep) as u64;
+    let text = format!(
+        "{}\n…truncated {} bytes\n{}",
+        &body[..keep],
+        truncated_bytes,
+        trailer
+    );
+
+    LimitedOutput {
+        text,
+        full_size,
+        truncated_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_within_limit_is_untouched() {
+        let out = limit_output("hello".to_string(), 5);
+        assert_eq!(out.text, "hello");
+        assert_eq!(out.full_size, 5);
+        assert!(!out.is_truncated());
+
+        let unlimited = limit_output("x".repeat(100), 0);
+        assert_eq!(unlimited.text.len(), 100);
+    }
+
+    #[test]
+    fn oversized_output_is_cut_and_marked() {
+        let out = limit_output("a".repeat(100), 10);
+        assert_eq!(out.full_size, 100);
+        assert_eq!(out.truncated_bytes, 90);
+        assert_eq!(
+            out.text,
+            format!("{}\n…truncated 90 bytes\n", "a".repeat(10))
+        );
+    }
+
+    #[test]
+    fn return_code_section_is_kept() {
+        let trailer = "&FITCHFORK&ReturnCode\n\nRetcode: 0";
+        let raw = format!(
+            "{}&FITCHFORK&StandardError\n\n{}",
+            "y\n".repeat(50),
+            trailer
+        );
+        let out = limit_output(raw, 40);
+        assert!(out.is_truncated());
+        assert!(out.text.ends_with(trailer));
+        assert!(out.text.contains("…truncated"));
+    }
+
+    #[test]
+    fn cut_respects_char_boundaries() {
+        // 'é' is two bytes; a limit of 3 lands in the middle of the second one.
+        let out = limit_output("éééé".to_string(), 3);
+        assert!(out.text.starts_with("é\n"));
+        assert_eq!(out.truncated_bytes, 6);
+    }
+}
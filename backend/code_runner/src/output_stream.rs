@@ -0,0 +1,220 @@
+use reqwest::Client;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::code_manager_client::{RunRequest, RunResult, bytes_field};
+use crate::metrics::{CommandMetrics, parse_metrics};
+
+/// A piece of live output produced by a task while code_manager is still running it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskOutputChunk {
+    pub task_id: i64,
+    pub task_number: i64,
+    /// Either `"stdout"` or `"stderr"`.
+    pub stream: String,
+    pub data: String,
+}
+
+/// Receives [`TaskOutputChunk`]s as they are read from code_manager.
+pub type TaskOutputSink = UnboundedSender<TaskOutputChunk>;
+
+/// A parsed line of the `/run/stream` NDJSON body.
+#[derive(Debug, PartialEq, Eq)]
+enum StreamLine {
+    Chunk {
+        stream: String,
+        data: String,
+    },
+    Done {
+        output: Vec<String>,
+        metrics: Vec<CommandMetrics>,
+        output_files: Option<Vec<u8>>,
+    },
+    Error(String),
+}
+
+/// Parses one NDJSON line emitted by code_manager's `/run/stream` endpoint.
+///
+/// Returns `Ok(None)` for blank lines and unknown event types so newer servers
+/// can add events without breaking older runners.
+fn parse_stream_line(line: &str) -> Result<Option<StreamLine>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+
+    let value: Value =
+        serde_json::from_str(line).map_err(|e| format!("Invalid stream line: {}", e))?;
+
+    let field = |name: &str| {
+        value
+            .get(name)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    match value.get("type").and_then(|v| v.as_str()) {
+        Some("chunk") => Ok(Some(StreamLine::Chunk {
+            stream: field("stream"),
+            data: field("data"),
+        })),
+        Some("done") => {
+            let output = value
+                .get("output")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .map(|val| val.as_str().unwrap_or("").to_string())
+                        .collect()
+                })
+                .unwrap_or_default();
+            Ok(Some(StreamLine::Done {
+                output,
+                metrics: parse_metrics(&value),
+                output_files: bytes_field(&value, "output_files"),
+            }))
+        }
+        Some("error") => Ok(Some(StreamLine::Error(field("message")))),
+        _ => Ok(None),
+    }
+}
+
+/// POSTs `body` to code_manager's streaming endpoint, forwarding every output chunk to
+/// `sink` and returning the final outputs, metrics and `/output` files (same shape as the
+/// response from `/run`).
+pub(crate) async fn run_streamed(
+    client: &Client,
+    url: &str,
+    body: &RunRequest,
+    task_id: i64,
+    task_number: i64,
+    sink: &TaskOutputSink,
+) -> Result<RunResult, String> {
+    let mut response = crate::code_manager_client::with_auth(client.post(url))
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+    if !response.status().is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Code manager error: {}", text));
+    }
+
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        let bytes = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read stream: {}", e))?;
+        let at_end = bytes.is_none();
+        if let Some(bytes) = bytes {
+            pending.extend_from_slice(&bytes);
+        } else if !pending.is_empty() {
+            // Tolerate a final line without a trailing newline.
+            pending.push(b'\n');
+        }
+
+        while let Some(pos) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            match parse_stream_line(&String::from_utf8_lossy(&line))? {
+                Some(StreamLine::Chunk { stream, data }) => {
+                    // The receiver going away must not abort the run.
+                    let _ = sink.send(TaskOutputChunk {
+                        task_id,
+                        task_number,
+                        stream,
+                        data,
+                    });
+                }
+                Some(StreamLine::Done {
+                    output,
+                    metrics,
+                    output_files,
+                }) => {
+                    return Ok(RunResult {
+                        output,
+                        metrics,
+                        output_files,
+                        ..Default::default()
+                    });
+                }
+                Some(StreamLine::Error(message)) => return Err(message),
+                None => {}
+            }
+        }
+
+        if at_end {
+            return Err("Stream ended before the run completed".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_chunk_line() {
+        let line = r#"{"type":"chunk","command":0,"stream":"stderr","data":"boom\n"}"#;
+        assert_eq!(
+            parse_stream_line(line).unwrap(),
+            Some(StreamLine::Chunk {
+                stream: "stderr".into(),
+                data: "boom\n".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_done_and_error_lines() {
+        assert_eq!(
+            parse_stream_line(r#"{"type":"done","output":["a","b"]}"#).unwrap(),
+            Some(StreamLine::Done {
+                output: vec!["a".into(), "b".into()],
+                metrics: vec![],
+                output_files: None,
+            })
+        );
+        assert_eq!(
+            parse_stream_line(
+                r#"{"type":"done","output":["a"],"metrics":[{"wall_time_ms":7,"cpu_time_ms":3,"max_rss_bytes":null}]}"#
+            )
+            .unwrap(),
+            Some(StreamLine::Done {
+                output: vec!["a".into()],
+                metrics: vec![CommandMetrics {
+                    wall_time_ms: 7,
+                    cpu_time_ms: Some(3),
+                    max_rss_bytes: None,
+                    disk_quota_exceeded: false,
+                }],
+                output_files: None,
+            })
+        );
+        assert_eq!(
+            parse_stream_line(r#"{"type":"error","message":"nope"}"#).unwrap(),
+            Some(StreamLine::Error("nope".into()))
+        );
+    }
+
+    #[test]
+    fn parses_output_files_on_done_line() {
+        assert_eq!(
+            parse_stream_line(r#"{"type":"done","output":[],"output_files":[104,105]}"#).unwrap(),
+            Some(StreamLine::Done {
+                output: vec![],
+                metrics: vec![],
+                output_files: Some(b"hi".to_vec()),
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_blank_and_unknown_lines() {
+        assert_eq!(parse_stream_line("   ").unwrap(), None);
+        assert_eq!(parse_stream_line(r#"{"type":"heartbeat"}"#).unwrap(), None);
+        assert!(parse_stream_line("not json").is_err());
+    }
+}
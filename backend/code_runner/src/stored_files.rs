@@ -0,0 +1,121 @@
+//! Assignment and submission files read through the configured storage backend.
+//!
+//! Uploads are written with [`util::storage::storage`], so with S3 storage the only local copy
+//! is the cache on the machine that took the upload. Runs read the files back through the
+//! backend, which lets any machine sharing the store generate memos and mark submissions.
+
+use std::path::Path;
+
+use util::storage::{file_name, key_for, storage};
+
+/// Archive extensions accepted for memo, makefile, main and submission archives.
+const ARCHIVE_EXTENSIONS: [&str; 6] = ["zip", "tar", "tgz", "gz", "7z", "rar"];
+
+/// Whether `name` has one of [`ARCHIVE_EXTENSIONS`].
+fn is_archive(name: &str) -> bool {
+    name.rsplit_once('.')
+        .is_some_and(|(_, ext)| ARCHIVE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Keys of the files directly in `dir` (not in its subdirectories), sorted.
+pub(crate) async fn files_in(dir: &Path) -> Result<Vec<String>, String> {
+    let prefix = key_for(dir);
+    let mut keys: Vec<String> = storage()
+        .list(&prefix)
+        .await
+        .map_err(|e| format!("Failed to list {}: {}", dir.display(), e))?
+        .into_iter()
+        .filter(|key| {
+            key.strip_prefix(prefix.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+                .is_some_and(|name| !name.is_empty() && !name.contains('/'))
+        })
+        .collect();
+    keys.sort();
+    Ok(keys)
+}
+
+/// Reads the stored file `key` as a `(file name, contents)` pair.
+pub(crate) async fn read_file(key: &str) -> Result<(String, Vec<u8>), String> {
+    let content = storage()
+        .read(key)
+        .await
+        .map_err(|e| format!("Failed to read file {}: {}", key, e))?;
+    Ok((file_name(key).to_string(), content))
+}
+
+/// Key of the first archive (".zip", ".tar", ".tgz", ".gz", ".7z", ".rar") stored directly in
+/// `dir`. Returns an error if there is none.
+pub(crate) async fn first_archive_key(dir: &Path) -> Result<String, String> {
+    files_in(dir)
+        .await?
+        .into_iter()
+        .find(|key| is_archive(file_name(key)))
+        .ok_or_else(|| {
+            format!(
+                "No .zip, .tar, .tgz, .gz, .7z, or .rar file found in {}",
+                dir.display()
+            )
+        })
+}
+
+/// Reads the first archive stored directly in `dir` as a `(file name, contents)` pair.
+pub(crate) async fn first_archive_in(dir: &Path) -> Result<(String, Vec<u8>), String> {
+    read_file(&first_archive_key(dir).await?).await
+}
+
+/// Applies the files stored in `dir` (a task's overwrite directory) to `files`, replacing
+/// any file of the same name.
+pub(crate) async fn apply_overwrites(
+    dir: &Path,
+    files: &mut Vec<(String, Vec<u8>)>,
+) -> Result<(), String> {
+    for key in files_in(dir).await? {
+        let (file_name, content) = read_file(&key).await?;
+        files.retain(|(name, _)| name != &file_name);
+        files.push((file_name, content));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[tokio::test]
+    async fn archives_and_overwrites_come_from_storage() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("memo");
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("notes.txt"), b"notes").unwrap();
+        fs::write(dir.join("memo.ZIP"), b"memo").unwrap();
+        fs::write(dir.join("nested").join("old.zip"), b"old").unwrap();
+
+        assert_eq!(
+            first_archive_in(&dir).await.unwrap(),
+            ("memo.ZIP".to_string(), b"memo".to_vec())
+        );
+        assert!(first_archive_in(&root.path().join("main")).await.is_err());
+
+        let overwrites = root.path().join("overwrite_files").join("task_1");
+        fs::create_dir_all(&overwrites).unwrap();
+        fs::write(overwrites.join("memo.ZIP"), b"patched").unwrap();
+        let mut files = vec![
+            ("memo.ZIP".to_string(), b"memo".to_vec()),
+            ("makefile.zip".to_string(), b"make".to_vec()),
+        ];
+        apply_overwrites(&overwrites, &mut files).await.unwrap();
+        assert_eq!(
+            files,
+            [
+                ("makefile.zip".to_string(), b"make".to_vec()),
+                ("memo.ZIP".to_string(), b"patched".to_vec()),
+            ]
+        );
+        apply_overwrites(&root.path().join("task_9"), &mut files)
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 2);
+    }
+}
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use util::archive::{self, ArchiveLimits};
+use util::source_files;
+use util::storage::file_name;
+
+use crate::stored_files;
+
+/// Name plain-file submissions are zipped under before being sent to code_manager.
+const PLAIN_SUBMISSION_ARCHIVE: &str = "submission.zip";
+
+/// Loads the submission stored in `dir` (read through the storage backend) as a `(filename, bytes)` pair for code_manager.
+///
+/// Uses the uploaded archive if there is one, normalized to a clean zip (submissions stored
+/// before uploads were normalized may still be tar/7z/rar or carry macOS metadata). Otherwise every plain source file in `dir` is
+/// wrapped into an in-memory zip; the stored upload (`{submission_id}.ext`) goes back under
+/// `original_name`, so e.g. `main.py` keeps the name the makefile expects.
+pub(crate) async fn load_submission_file(
+    dir: &Path,
+    submission_id: i64,
+    original_name: &str,
+    limits: &ArchiveLimits,
+) -> Result<(String, Vec<u8>), String> {
+    if let Ok((filename, content)) = stored_files::first_archive_in(dir).await {
+        let content = archive::normalize_archive(&content, limits)
+            .map_err(|e| format!("Invalid submission archive {}: {}", filename, e))?;
+        return Ok((archive::zip_name(&filename), content));
+    }
+
+    let stored_stem = submission_id.to_string();
+    let mut sources = Vec::new();
+    for key in stored_files::files_in(dir).await? {
+        let name = file_name(&key);
+        if !source_files::is_source_file(name) {
+            continue;
+        }
+        let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+        let name = if stem == stored_stem {
+            original_name
+        } else {
+            name
+        };
+        let (_, content) = stored_files::read_file(&key).await?;
+        sources.push((name.to_string(), content));
+    }
+
+    if sources.is_empty() {
+        return Err(format!(
+            "No archive or source files found in {}",
+            dir.display()
+        ));
+    }
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok((
+        PLAIN_SUBMISSION_ARCHIVE.to_string(),
+        source_files::zip_source_files(&sources)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    #[tokio::test]
+    async fn uploaded_archive_is_normalized_to_zip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut tar = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(11);
+        header.set_cksum();
+        tar.append_data(&mut header, "solution/main.py", &b"print('hi')"[..])
+            .unwrap();
+        fs::write(dir.path().join("7.tar"), tar.into_inner().unwrap()).unwrap();
+        fs::write(dir.path().join("coverage_report.json"), b"{}").unwrap();
+
+        let (name, bytes) =
+            load_submission_file(dir.path(), 7, "solution.tar", &ArchiveLimits::default())
+                .await
+                .unwrap();
+        assert_eq!(name, "7.zip");
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 1);
+        assert!(archive.by_name("main.py").is_ok());
+
+        fs::write(dir.path().join("7.tar"), b"not an archive").unwrap();
+        assert!(
+            load_submission_file(dir.path(), 7, "solution.tar", &ArchiveLimits::default())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn plain_source_file_is_zipped_under_its_original_name() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("7.py"), b"print('hi')").unwrap();
+        fs::write(dir.path().join("coverage_report.json"), b"{}").unwrap();
+        fs::create_dir(dir.path().join("submission_output")).unwrap();
+
+        let (name, bytes) =
+            load_submission_file(dir.path(), 7, "main.py", &ArchiveLimits::default())
+                .await
+                .unwrap();
+        assert_eq!(name, PLAIN_SUBMISSION_ARCHIVE);
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 1);
+        let mut contents = String::new();
+        archive
+            .by_name("main.py")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "print('hi')");
+    }
+
+    #[tokio::test]
+    async fn multiple_source_files_are_zipped_together() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("3.java"), b"class Main {}").unwrap();
+        fs::write(dir.path().join("Helper.java"), b"class Helper {}").unwrap();
+
+        let (_, bytes) =
+            load_submission_file(dir.path(), 3, "Main.java", &ArchiveLimits::default())
+                .await
+                .unwrap();
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert!(archive.by_name("Main.java").is_ok());
+        assert!(archive.by_name("Helper.java").is_ok());
+    }
+
+    #[tokio::test]
+    async fn directory_without_submission_files_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("notes.json"), b"{}").unwrap();
+        assert!(
+            load_submission_file(dir.path(), 1, "main.py", &ArchiveLimits::default())
+                .await
+                .is_err()
+        );
+    }
+}
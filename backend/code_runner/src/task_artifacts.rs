@@ -0,0 +1,164 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::io::Cursor;
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, EntryType};
+
+/// Files kept from one task's `/output`, as paths relative to `/output`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SavedArtifacts {
+    pub saved: Vec<String>,
+    /// Matching files left out because they would have gone over the size cap.
+    pub skipped: Vec<String>,
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern.trim())
+            .map_err(|e| format!("Invalid artifact pattern '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Failed to build artifact patterns: {}", e))
+}
+
+/// Path of a tar entry relative to `/output`, or `None` if it could escape the destination.
+fn relative_path(path: &Path) -> Option<PathBuf> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::Normal(part) => relative.push(part),
+            _ => return None,
+        }
+    }
+    (!relative.as_os_str().is_empty()).then_some(relative)
+}
+
+/// Unpacks the regular files in `output_tar` (code_manager's tar of `/output`) whose path
+/// matches one of `patterns` into `dest`, replacing whatever an earlier run left there.
+///
+/// Files are kept in archive order until `max_bytes` would be exceeded; later matches are
+/// reported in [`SavedArtifacts::skipped`]. A limit of 0 disables the cap.
+pub fn save_artifacts(
+    output_tar: &[u8],
+    patterns: &[String],
+    max_bytes: u64,
+    dest: &Path,
+) -> Result<SavedArtifacts, String> {
+    let globs = build_glob_set(patterns)?;
+
+    if dest.exists() {
+        std::fs::remove_dir_all(dest)
+            .map_err(|e| format!("Failed to clear old artifacts in {:?}: {}", dest, e))?;
+    }
+
+    let mut result = SavedArtifacts::default();
+    let mut total: u64 = 0;
+    let mut archive = Archive::new(Cursor::new(output_tar));
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read output files: {}", e))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read output files: {}", e))?;
+        if entry.header().entry_type() != EntryType::Regular {
+            continue;
+        }
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid path in output files: {}", e))?;
+        let Some(relative) = relative_path(&path) else {
+            continue;
+        };
+        if !globs.is_match(&relative) {
+            continue;
+        }
+
+        let name = relative.to_string_lossy().into_owned();
+        let size = entry.header().size().unwrap_or(0);
+        if max_bytes > 0 && total.saturating_add(size) > max_bytes {
+            result.skipped.push(name);
+            continue;
+        }
+
+        let target = dest.join(&relative);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        entry
+            .unpack(&target)
+            .map_err(|e| format!("Failed to save artifact {}: {}", name, e))?;
+        total += size;
+        result.saved.push(name);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn output_tar(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("./{}", path), *contents)
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn keeps_only_matching_files() {
+        let tar = output_tar(&[
+            ("plot.png", b"png"),
+            ("results/scores.csv", b"a,b"),
+            ("scratch.tmp", b"junk"),
+        ]);
+        let dest = TempDir::new().unwrap();
+        let target = dest.path().join("task_1");
+
+        let saved =
+            save_artifacts(&tar, &patterns(&["*.png", "results/*.csv"]), 0, &target).unwrap();
+
+        assert_eq!(saved.saved, vec!["plot.png", "results/scores.csv"]);
+        assert!(saved.skipped.is_empty());
+        assert_eq!(std::fs::read(target.join("plot.png")).unwrap(), b"png");
+        assert!(target.join("results/scores.csv").exists());
+        assert!(!target.join("scratch.tmp").exists());
+    }
+
+    #[test]
+    fn stops_at_the_size_cap_and_replaces_old_artifacts() {
+        let dest = TempDir::new().unwrap();
+        let target = dest.path().join("task_2");
+        std::fs::create_dir_all(&target).unwrap();
+        std::fs::write(target.join("stale.png"), b"old").unwrap();
+
+        let tar = output_tar(&[("a.bin", b"1234"), ("b.bin", b"5678"), ("c.bin", b"9")]);
+        let saved = save_artifacts(&tar, &patterns(&["*.bin"]), 5, &target).unwrap();
+
+        assert_eq!(saved.saved, vec!["a.bin", "c.bin"]);
+        assert_eq!(saved.skipped, vec!["b.bin"]);
+        assert!(!target.join("stale.png").exists());
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        let dest = TempDir::new().unwrap();
+        let err =
+            save_artifacts(&output_tar(&[]), &patterns(&["[oops"]), 0, dest.path()).unwrap_err();
+        assert!(err.starts_with("Invalid artifact pattern"));
+    }
+}
@@ -1,16 +1,17 @@
-use std::fs;
+use crate::stored_files::files_in;
 use util::execution_config::ExecutionConfig;
 use util::paths::{attempt_dir, main_dir, makefile_dir, memo_dir};
+use util::storage::file_name;
 
 /// Validate that an assignment has a readable execution config and that each of
 /// the required directories (`memo`, `makefile`, `main`) contains at least one
-/// `.zip` file. Paths are resolved via `util::paths::*`
+/// `.zip` file. Paths are resolved via `util::paths::*` and listed through the storage backend.
 ///
 /// # Errors
 /// Returns an error if:
 /// - the execution config cannot be loaded/parsed, or
 /// - any required directory is missing or lacks a `.zip` file.
-pub fn validate_memo_files(module_id: i64, assignment_id: i64) -> Result<(), String> {
+pub async fn validate_memo_files(module_id: i64, assignment_id: i64) -> Result<(), String> {
     // Validate config first
     ExecutionConfig::get_execution_config(module_id, assignment_id)
         .map_err(|e| format!("Config validation failed: {}", e))?;
@@ -21,12 +22,10 @@ pub fn validate_memo_files(module_id: i64, assignment_id: i64) -> Result<(), Str
         makefile_dir(module_id, assignment_id),
         main_dir(module_id, assignment_id),
     ] {
-        let entries =
-            fs::read_dir(&dir).map_err(|_| format!("Failed to read directory {:?}", dir))?;
-
-        let has_zip = entries
-            .filter_map(Result::ok)
-            .any(|e| e.path().extension().and_then(|s| s.to_str()) == Some("zip"));
+        let has_zip = files_in(&dir)
+            .await?
+            .iter()
+            .any(|key| file_name(key).ends_with(".zip"));
 
         if !has_zip {
             // Figure out which name to show (memo/makefile/main)
@@ -46,14 +45,14 @@ pub fn validate_memo_files(module_id: i64, assignment_id: i64) -> Result<(), Str
 
 /// Validate that a specific student's submission attempt is present and the
 /// assignment has the required `.zip` inputs in `makefile` and `main`.
-/// Paths are resolved via `util::paths::*`
+/// Paths are resolved via `util::paths::*` and listed through the storage backend.
 ///
 /// # Errors
 /// Returns an error if:
 /// - the execution config cannot be loaded/parsed, or
 /// - `makefile`/`main` lack a `.zip`, or
 /// - the submission attempt directory has no files.
-pub fn validate_submission_files(
+pub async fn validate_submission_files(
     module_id: i64,
     assignment_id: i64,
     user_id: i64,
@@ -68,12 +67,10 @@ pub fn validate_submission_files(
         makefile_dir(module_id, assignment_id),
         main_dir(module_id, assignment_id),
     ] {
-        let entries =
-            fs::read_dir(&dir).map_err(|_| format!("Failed to read directory {:?}", dir))?;
-
-        let has_zip = entries
-            .filter_map(Result::ok)
-            .any(|e| e.path().extension().and_then(|s| s.to_str()) == Some("zip"));
+        let has_zip = files_in(&dir)
+            .await?
+            .iter()
+            .any(|key| file_name(key).ends_with(".zip"));
 
         if !has_zip {
             let name = dir
@@ -89,11 +86,7 @@ pub fn validate_submission_files(
 
     // submission directory must contain at least one file
     let submission_dir = attempt_dir(module_id, assignment_id, user_id, attempt_number);
-    let entries = fs::read_dir(&submission_dir)
-        .map_err(|_| format!("Failed to read submission directory {:?}", submission_dir))?;
-
-    let has_any_file = entries.filter_map(Result::ok).any(|e| e.path().is_file());
-    if !has_any_file {
+    if files_in(&submission_dir).await?.is_empty() {
         return Err(format!("No submission file found at {:?}", submission_dir));
     }
 
@@ -132,8 +125,8 @@ mod tests {
         fs::write(dir.join(filename), b"dummy").unwrap();
     }
 
-    #[test]
-    fn test_validate_all_files_exist() {
+    #[tokio::test]
+    async fn test_validate_all_files_exist() {
         let _tmp = setup_test_storage_root();
 
         let module_id = 5;
@@ -145,12 +138,12 @@ mod tests {
         write_required_file_zip(module_id, assignment_id, "makefile", "makefile.zip");
         write_required_file_zip(module_id, assignment_id, "main", "main.zip");
 
-        let result = validate_memo_files(module_id, assignment_id);
+        let result = validate_memo_files(module_id, assignment_id).await;
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_missing_file_causes_error() {
+    #[tokio::test]
+    async fn test_missing_file_causes_error() {
         let _tmp = setup_test_storage_root();
 
         let module_id = 1;
@@ -164,15 +157,15 @@ mod tests {
         // Ensure makefile dir exists but contains no zip to trigger the error
         fs::create_dir_all(makefile_dir(module_id, assignment_id)).unwrap();
 
-        let result = validate_memo_files(module_id, assignment_id);
+        let result = validate_memo_files(module_id, assignment_id).await;
         println!("{:?}", result);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("makefile"));
     }
 
-    #[test]
-    fn test_invalid_config_causes_error() {
+    #[tokio::test]
+    async fn test_invalid_config_causes_error() {
         let _tmp = setup_test_storage_root();
 
         let module_id = 2;
@@ -187,15 +180,15 @@ mod tests {
         write_required_file_zip(module_id, assignment_id, "makefile", "makefile.zip");
         write_required_file_zip(module_id, assignment_id, "main", "main.zip");
 
-        let result = validate_memo_files(module_id, assignment_id);
+        let result = validate_memo_files(module_id, assignment_id).await;
         println!("{:?}", result);
 
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Config validation failed"));
     }
 
-    #[test]
-    fn test_validate_submission_success() {
+    #[tokio::test]
+    async fn test_validate_submission_success() {
         let _tmp = setup_test_storage_root();
 
         let module_id = 7;
@@ -212,13 +205,14 @@ mod tests {
         fs::create_dir_all(&submission_dir).unwrap();
         fs::write(submission_dir.join("482.txt"), b"submission").unwrap();
 
-        let result = validate_submission_files(module_id, assignment_id, user_id, attempt_number);
+        let result =
+            validate_submission_files(module_id, assignment_id, user_id, attempt_number).await;
 
         assert!(result.is_ok());
     }
 
-    #[test]
-    fn test_validate_submission_missing_file() {
+    #[tokio::test]
+    async fn test_validate_submission_missing_file() {
         let _tmp = setup_test_storage_root();
 
         let module_id = 7;
@@ -232,13 +226,10 @@ mod tests {
         write_required_file_zip(module_id, assignment_id, "main", "main.zip");
 
         // Missing submission dir (or empty) → should error
-        let result = validate_submission_files(module_id, assignment_id, user_id, attempt_number);
+        let result =
+            validate_submission_files(module_id, assignment_id, user_id, attempt_number).await;
 
         assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .contains("Failed to read submission directory")
-        );
+        assert!(result.unwrap_err().contains("No submission file found"));
     }
 }
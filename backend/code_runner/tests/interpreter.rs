@@ -1,5 +1,5 @@
 use chrono::Utc;
-use code_runner::run_interpreter;
+use code_runner::{run_interpreter, run_interpreter_isolated};
 use db::models::assignment::AssignmentType;
 use db::models::assignment::{ActiveModel as AssignmentActiveModel, Entity as AssignmentEntity};
 use db::models::assignment_submission::{
@@ -13,7 +13,10 @@ use sea_orm::ColumnTrait;
 use sea_orm::QueryFilter;
 use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
 use util::execution_config::ExecutionConfig;
-use util::paths::{interpreter_dir, makefile_dir, memo_dir, storage_root, submission_file_path};
+use util::paths::{
+    interpreter_dir, main_dir, makefile_dir, memo_dir, memo_output_dir, storage_root,
+    submission_file_path,
+};
 use util::test_helpers::setup_test_storage_root;
 
 fn write_zip(path: &std::path::Path, entries: &[(&str, &[u8])]) -> std::io::Result<()> {
@@ -312,3 +315,39 @@ async fn test_run_interpreter_9998_cpp() {
 
     // keep `tmp` in scope until here
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_run_interpreter_isolated_leaves_assignment_untouched() {
+    let _tmp = setup_test_storage_root();
+
+    let assignment_id = 9997;
+    let module_id = 9997;
+    let interpreter_id = 20;
+
+    let (db, submission_id) =
+        setup_test_db_for_run_interpreter(assignment_id, module_id, interpreter_id).await;
+
+    let run = run_interpreter_isolated(&db, submission_id, "01234")
+        .await
+        .expect("run_interpreter_isolated failed");
+
+    assert_eq!(
+        run.memo_outputs
+            .iter()
+            .map(|(task_number, _)| *task_number)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert_eq!(run.submission_outputs.len(), 3);
+    assert!(run.coverage_outputs.is_empty());
+
+    // Nothing of the chromosome's run is stored
+    assert!(!main_dir(module_id, assignment_id).exists());
+    assert!(!memo_output_dir(module_id, assignment_id).exists());
+    let stored = db::models::assignment_submission_output::Entity::find()
+        .all(&db)
+        .await
+        .expect("DB error");
+    assert!(stored.is_empty());
+}
@@ -187,7 +187,7 @@ async fn test_create_submission_outputs_for_all_tasks_9999_java() {
 
     let submission_id = submission.id;
 
-    match create_submission_outputs_for_all_tasks(&db, submission_id).await {
+    match create_submission_outputs_for_all_tasks(&db, submission_id, false).await {
         Ok(_) => {}
         Err(e) => panic!("Failed to generate submission outputs: {}", e),
     }
@@ -215,7 +215,7 @@ async fn test_create_submission_outputs_for_all_tasks_9998_cpp() {
 
     let submission_id = submission.id;
 
-    match create_submission_outputs_for_all_tasks(&db, submission_id).await {
+    match create_submission_outputs_for_all_tasks(&db, submission_id, false).await {
         Ok(_) => {}
         Err(e) => panic!(
             "Failed to generate submission outputs for C++ assignment: {}",
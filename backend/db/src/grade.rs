@@ -1,4 +1,4 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::collections::HashMap;
 
 use sea_orm::{
     ColumnTrait, DatabaseConnection, EntityTrait, JoinType, PaginatorTrait, QueryFilter,
@@ -10,17 +10,24 @@ use crate::models::{
     assignment_submission::{
         Column as SubCol, Entity as SubmissionEntity, Model as SubmissionModel, Relation as SubRel,
     },
+    group,
+    rubric,
     user::{Column as UserCol, Entity as UserEntity, Model as UserModel},
     user_module_role::{Column as UmrCol, Entity as UmrEntity, Role as ModuleRole},
 };
 
-use util::execution_config::{ExecutionConfig, GradingPolicy};
+use crate::soft_delete::SoftDelete;
+use util::execution_config::{ExecutionConfig, MarkingOptions};
 
 #[derive(Debug, Clone)]
 pub struct GradeSelection {
     pub submission: SubmissionModel,
     pub user: UserModel,
+    /// Final percentage: the automated mark, blended with the rubric mark when the submission
+    /// has been scored against the assignment's rubric.
     pub score_pct: f64,
+    /// The rubric percentage, if the submission has been scored against the rubric.
+    pub rubric_pct: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +40,8 @@ pub struct GradeComputationResult {
 pub struct GradeComputationOptions<'a> {
     pub username_filter: Option<&'a str>,
     pub user_id: Option<i64>,
+    /// Only these students
+    pub user_ids: Option<&'a [i64]>,
 }
 
 #[derive(Debug)]
@@ -69,31 +78,20 @@ pub fn percentage(earned: f64, total: f64) -> f64 {
     }
 }
 
-fn apply_policy(
-    policy: GradingPolicy,
+/// Picks the attempt that counts under the assignment's grading policy from a student's
+/// attempts, newest first, along with the percentage it counts for: its own mark, or under
+/// `weighted` the weighted average of every attempt.
+pub fn apply_policy(
+    marking: &MarkingOptions,
     attempts: Vec<(SubmissionModel, UserModel)>,
-) -> Option<(SubmissionModel, UserModel)> {
-    match policy {
-        GradingPolicy::Last => attempts.into_iter().next(),
-        GradingPolicy::Best => {
-            attempts
-                .into_iter()
-                .max_by(|(a_submission, _), (b_submission, _)| {
-                    let a_ratio = a_submission.earned as f64 / a_submission.total.max(1.0) as f64;
-                    let b_ratio = b_submission.earned as f64 / b_submission.total.max(1.0) as f64;
-
-                    match a_ratio.partial_cmp(&b_ratio).unwrap_or(Ordering::Equal) {
-                        Ordering::Equal => {
-                            match a_submission.created_at.cmp(&b_submission.created_at) {
-                                Ordering::Equal => a_submission.attempt.cmp(&b_submission.attempt),
-                                ord => ord,
-                            }
-                        }
-                        ord => ord,
-                    }
-                })
-        }
-    }
+) -> Option<(SubmissionModel, UserModel, f64)> {
+    let marks: Vec<f64> = attempts
+        .iter()
+        .map(|(s, _)| percentage(s.earned, s.total))
+        .collect();
+    let (index, pct) = marking.policy_mark(&marks)?;
+    let (submission, user) = attempts.into_iter().nth(index)?;
+    Some((submission, user, pct))
 }
 
 async fn load_execution_config(
@@ -104,34 +102,90 @@ async fn load_execution_config(
         .map_err(GradeComputationError::ExecutionConfig)
 }
 
-/// Compute grades for an assignment based on the execution config policy.
-///
-/// Returns the execution config along with the chosen submission per student (respecting policy).
-pub async fn compute_assignment_grades(
+/// Adds the submissions made for each student's group to that student's attempts, keeping each
+/// list ordered newest first like the main query.
+async fn add_group_submissions(
     db: &DatabaseConnection,
     module_id: i64,
     assignment_id: i64,
     options: GradeComputationOptions<'_>,
-) -> Result<GradeComputationResult, GradeComputationError> {
-    let assignment_exists = assignment::Entity::find()
-        .filter(assignment::Column::Id.eq(assignment_id))
-        .filter(assignment::Column::ModuleId.eq(module_id))
-        .count(db)
+    per_user: &mut HashMap<i64, Vec<(SubmissionModel, UserModel)>>,
+) -> Result<(), GradeComputationError> {
+    let group_of = group::Model::memberships(db, assignment_id).await?;
+    if group_of.is_empty() {
+        return Ok(());
+    }
+
+    let group_subs = SubmissionEntity::find_active()
+        .filter(SubCol::AssignmentId.eq(assignment_id))
+        .filter(SubCol::IsPractice.eq(false))
+        .filter(SubCol::Ignored.eq(false))
+        .filter(SubCol::GroupId.is_not_null())
+        .all(db)
         .await?;
+    if group_subs.is_empty() {
+        return Ok(());
+    }
 
-    if assignment_exists == 0 {
-        return Err(GradeComputationError::AssignmentNotFound);
+    let student_ids_subq = UmrEntity::find()
+        .select_only()
+        .column(UmrCol::UserId)
+        .filter(UmrCol::ModuleId.eq(module_id))
+        .filter(UmrCol::Role.eq(ModuleRole::Student));
+    let mut members = UserEntity::find()
+        .filter(UserCol::Id.is_in(group_of.keys().copied()))
+        .filter(UserCol::Id.in_subquery(student_ids_subq.as_query().to_owned()));
+    if let Some(user_id) = options.user_id {
+        members = members.filter(UserCol::Id.eq(user_id));
+    }
+    if let Some(user_ids) = options.user_ids {
+        members = members.filter(UserCol::Id.is_in(user_ids.iter().copied()));
+    }
+    if let Some(username_filter) = options.username_filter {
+        let filter = username_filter.trim();
+        if !filter.is_empty() {
+            members = members.filter(UserCol::Username.contains(filter));
+        }
     }
 
-    let exec_cfg = load_execution_config(module_id, assignment_id).await?;
+    for member in members.all(db).await? {
+        let group_id = group_of[&member.id];
+        let attempts = per_user.entry(member.id).or_default();
+        for sub in &group_subs {
+            // the member's own submissions are already listed
+            if sub.group_id == Some(group_id) && sub.user_id != member.id {
+                attempts.push((sub.clone(), member.clone()));
+            }
+        }
+        attempts.sort_by(|(a, _), (b, _)| {
+            b.created_at
+                .cmp(&a.created_at)
+                .then(b.attempt.cmp(&a.attempt))
+        });
+    }
+    per_user.retain(|_, attempts| !attempts.is_empty());
+
+    Ok(())
+}
 
+/// Each student's counted attempts at the assignment (their own and, when the assignment takes
+/// group submissions, their group's), keyed by user id and ordered newest first.
+///
+/// Practice, ignored and staff submissions are left out.
+pub async fn student_attempts(
+    db: &DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+    exec_cfg: &ExecutionConfig,
+    options: GradeComputationOptions<'_>,
+) -> Result<HashMap<i64, Vec<(SubmissionModel, UserModel)>>, GradeComputationError> {
     let student_ids_subq = UmrEntity::find()
         .select_only()
         .column(UmrCol::UserId)
         .filter(UmrCol::ModuleId.eq(module_id))
         .filter(UmrCol::Role.eq(ModuleRole::Student));
 
-    let mut query = SubmissionEntity::find()
+    let mut query = SubmissionEntity::find_active()
         .filter(SubCol::AssignmentId.eq(assignment_id))
         .filter(SubCol::IsPractice.eq(false))
         .filter(SubCol::Ignored.eq(false))
@@ -145,6 +199,9 @@ pub async fn compute_assignment_grades(
     if let Some(user_id) = options.user_id {
         query = query.filter(SubCol::UserId.eq(user_id));
     }
+    if let Some(user_ids) = options.user_ids {
+        query = query.filter(SubCol::UserId.is_in(user_ids.iter().copied()));
+    }
 
     if let Some(username_filter) = options.username_filter {
         let filter = username_filter.trim();
@@ -165,18 +222,60 @@ pub async fn compute_assignment_grades(
         }
     }
 
+    if exec_cfg.marking.group_submissions {
+        add_group_submissions(db, module_id, assignment_id, options, &mut per_user).await?;
+    }
+
+    Ok(per_user)
+}
+
+/// Compute grades for an assignment based on the execution config policy.
+///
+/// Returns the execution config along with the chosen submission per student (respecting policy).
+pub async fn compute_assignment_grades(
+    db: &DatabaseConnection,
+    module_id: i64,
+    assignment_id: i64,
+    options: GradeComputationOptions<'_>,
+) -> Result<GradeComputationResult, GradeComputationError> {
+    let assignment_exists = assignment::Entity::find_active()
+        .filter(assignment::Column::Id.eq(assignment_id))
+        .filter(assignment::Column::ModuleId.eq(module_id))
+        .count(db)
+        .await?;
+
+    if assignment_exists == 0 {
+        return Err(GradeComputationError::AssignmentNotFound);
+    }
+
+    let exec_cfg = load_execution_config(module_id, assignment_id).await?;
+
+    let per_user = student_attempts(db, module_id, assignment_id, &exec_cfg, options).await?;
+
     let mut grades = Vec::with_capacity(per_user.len());
 
     for (_user_id, attempts) in per_user.into_iter() {
-        if let Some((submission, user)) = apply_policy(exec_cfg.marking.grading_policy, attempts) {
+        if let Some((submission, user, pct)) = apply_policy(&exec_cfg.marking, attempts) {
             grades.push(GradeSelection {
-                score_pct: percentage(submission.earned, submission.total),
+                score_pct: pct,
+                rubric_pct: None,
                 submission,
                 user,
             });
         }
     }
 
+    if let Some(rubric) = rubric::Model::for_assignment(db, assignment_id).await? {
+        let ids: Vec<i64> = grades.iter().map(|g| g.submission.id).collect();
+        let rubric_pcts = rubric.percentages(db, &ids).await?;
+        for g in &mut grades {
+            if let Some(&pct) = rubric_pcts.get(&g.submission.id) {
+                g.rubric_pct = Some(pct);
+                g.score_pct = rubric.final_percentage(g.score_pct, pct);
+            }
+        }
+    }
+
     grades.sort_by(|a, b| a.user.id.cmp(&b.user.id));
 
     Ok(GradeComputationResult {
@@ -199,6 +298,7 @@ pub async fn compute_assignment_grade_for_student(
         GradeComputationOptions {
             username_filter: None,
             user_id: Some(student_id),
+            user_ids: None,
         },
     )
     .await?;
@@ -0,0 +1,348 @@
+//! Module gradebook: every student's standing on every assignment of a module.
+//!
+//! Built on the same attempt selection as the per-assignment grades ([`crate::grade`]): each
+//! cell holds the mark that counts under the assignment's [`GradingPolicy`] alongside the best
+//! and last marks, the number of counted attempts and whether the counted attempt was late.
+//! Students are loaded a page at a time so large cohorts can be exported in batches.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, QueryTrait,
+};
+use util::execution_config::{ExecutionConfig, GradingPolicy, MarkingOptions};
+
+use crate::grade::{
+    GradeComputationError, GradeComputationOptions, apply_policy, percentage, student_attempts,
+};
+use crate::models::{
+    assignment, assignment_extension,
+    assignment_submission::Model as SubmissionModel,
+    rubric,
+    user::{Column as UserCol, Entity as UserEntity, Model as UserModel},
+    user_module_role::{Column as UmrCol, Entity as UmrEntity, Role as ModuleRole},
+};
+use crate::soft_delete::SoftDelete;
+
+/// An assignment column of the gradebook.
+#[derive(Debug, Clone)]
+pub struct GradebookColumn {
+    pub assignment: assignment::Model,
+    /// The assignment's config, or the defaults when it has none yet.
+    pub config: ExecutionConfig,
+    rubric: Option<rubric::Model>,
+    /// Extended due dates, keyed by user id.
+    due_dates: HashMap<i64, DateTime<Utc>>,
+}
+
+impl GradebookColumn {
+    pub fn grading_policy(&self) -> GradingPolicy {
+        self.config.marking.grading_policy
+    }
+}
+
+/// One student's standing on one assignment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradebookCell {
+    /// The percentage that counts under the grading policy.
+    pub mark: f64,
+    pub best: f64,
+    pub last: f64,
+    pub attempts: usize,
+    /// Whether the attempt that counts was made after the student's due date.
+    pub late: bool,
+}
+
+/// A student's row: one cell per column, `None` where they have not submitted.
+#[derive(Debug, Clone)]
+pub struct GradebookRow {
+    pub user: UserModel,
+    pub cells: Vec<Option<GradebookCell>>,
+}
+
+/// The module's live assignments in due-date order.
+pub async fn columns(
+    db: &DatabaseConnection,
+    module_id: i64,
+) -> Result<Vec<GradebookColumn>, DbErr> {
+    let assignments = assignment::Entity::find_active()
+        .filter(assignment::Column::ModuleId.eq(module_id))
+        .order_by_asc(assignment::Column::DueDate)
+        .order_by_asc(assignment::Column::Id)
+        .all(db)
+        .await?;
+
+    let mut columns = Vec::with_capacity(assignments.len());
+    for assignment in assignments {
+        let config = ExecutionConfig::get_execution_config(module_id, assignment.id)
+            .unwrap_or_else(|_| ExecutionConfig::default_config());
+        let rubric = rubric::Model::for_assignment(db, assignment.id).await?;
+        let due_dates = assignment_extension::Model::due_dates(db, assignment.id).await?;
+        columns.push(GradebookColumn {
+            assignment,
+            config,
+            rubric,
+            due_dates,
+        });
+    }
+    Ok(columns)
+}
+
+/// A page of the module's students by username, with the total matching `query`.
+pub async fn students(
+    db: &DatabaseConnection,
+    module_id: i64,
+    query: Option<&str>,
+    page: u64,
+    per_page: u64,
+) -> Result<(Vec<UserModel>, u64), DbErr> {
+    let student_ids_subq = UmrEntity::find()
+        .select_only()
+        .column(UmrCol::UserId)
+        .filter(UmrCol::ModuleId.eq(module_id))
+        .filter(UmrCol::Role.eq(ModuleRole::Student));
+
+    let mut select = UserEntity::find()
+        .filter(UserCol::Id.in_subquery(student_ids_subq.as_query().to_owned()))
+        .order_by_asc(UserCol::Username);
+    if let Some(q) = query.map(str::trim).filter(|q| !q.is_empty()) {
+        select = select.filter(UserCol::Username.contains(q));
+    }
+
+    let paginator = select.paginate(db, per_page.max(1));
+    let total = paginator.num_items().await?;
+    let users = paginator.fetch_page(page.saturating_sub(1)).await?;
+    Ok((users, total))
+}
+
+/// The gradebook rows of `students`, in the order given.
+pub async fn rows(
+    db: &DatabaseConnection,
+    module_id: i64,
+    columns: &[GradebookColumn],
+    students: Vec<UserModel>,
+) -> Result<Vec<GradebookRow>, GradeComputationError> {
+    let ids: Vec<i64> = students.iter().map(|u| u.id).collect();
+    let mut rows: Vec<GradebookRow> = students
+        .into_iter()
+        .map(|user| GradebookRow {
+            user,
+            cells: vec![None; columns.len()],
+        })
+        .collect();
+    if ids.is_empty() {
+        return Ok(rows);
+    }
+    let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    for (col, column) in columns.iter().enumerate() {
+        let per_user = student_attempts(
+            db,
+            module_id,
+            column.assignment.id,
+            &column.config,
+            GradeComputationOptions {
+                user_ids: Some(&ids),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        // the best attempt is shown next to the counted one whatever the policy
+        let best_only = MarkingOptions {
+            grading_policy: GradingPolicy::Best,
+            ..column.config.marking.clone()
+        };
+        let mut picks = Vec::new();
+        for (user_id, attempts) in per_user {
+            let count = attempts.len();
+            let Some((last, _)) = attempts.first().cloned() else {
+                continue;
+            };
+            let Some((best, _, _)) = apply_policy(&best_only, attempts.clone()) else {
+                continue;
+            };
+            if let Some((counted, _, auto)) = apply_policy(&column.config.marking, attempts) {
+                picks.push((user_id, count, (counted, auto), best, last));
+            }
+        }
+
+        let rubric_pcts = match &column.rubric {
+            Some(rubric) => {
+                let sub_ids: Vec<i64> = picks
+                    .iter()
+                    .flat_map(|(_, _, (counted, _), best, last)| [counted.id, best.id, last.id])
+                    .collect();
+                rubric.percentages(db, &sub_ids).await?
+            }
+            None => HashMap::new(),
+        };
+        // `auto` is the automated percentage the attempt counts for under the policy
+        let blended =
+            |s: &SubmissionModel, auto: f64| match (&column.rubric, rubric_pcts.get(&s.id)) {
+                (Some(rubric), Some(&pct)) => rubric.final_percentage(auto, pct),
+                _ => auto,
+            };
+        let mark_of = |s: &SubmissionModel| blended(s, percentage(s.earned, s.total));
+
+        for (user_id, attempts, (counted, auto), best, last) in picks {
+            let due = column
+                .due_dates
+                .get(&user_id)
+                .copied()
+                .unwrap_or(column.assignment.due_date);
+            let cell = GradebookCell {
+                mark: blended(&counted, auto),
+                best: mark_of(&best),
+                last: mark_of(&last),
+                attempts,
+                late: counted.created_at > due,
+            };
+            if let Some(&i) = index.get(&user_id) {
+                rows[i].cells[col] = Some(cell);
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        assignment::AssignmentType, assignment_submission, module, user_module_role,
+    };
+    use crate::test_utils::setup_test_db;
+    use chrono::Duration;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    async fn submit(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+        attempt: i64,
+        earned: f64,
+        at: DateTime<Utc>,
+    ) {
+        let sub = assignment_submission::Model::save_file(
+            db,
+            assignment_id,
+            user_id,
+            attempt,
+            earned,
+            10.0,
+            false,
+            "s.zip",
+            &format!("hash{user_id}{attempt}"),
+            b"zip",
+        )
+        .await
+        .unwrap();
+        let mut am: assignment_submission::ActiveModel = sub.into();
+        am.created_at = Set(at);
+        am.update(db).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn rows_hold_best_last_attempts_and_late_flags() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let due = Utc::now();
+        let mut assignments = Vec::new();
+        for name in ["A1", "A2"] {
+            assignments.push(
+                assignment::Model::create(
+                    &db,
+                    module.id,
+                    name,
+                    None,
+                    AssignmentType::Assignment,
+                    due - Duration::days(7),
+                    due,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+        let mut users = Vec::new();
+        for name in ["bob", "alice", "tutor"] {
+            let u = UserModel::create(&db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            let role = if name == "tutor" {
+                ModuleRole::Tutor
+            } else {
+                ModuleRole::Student
+            };
+            user_module_role::Model::assign_user_to_module(&db, u.id, module.id, role)
+                .await
+                .unwrap();
+            users.push(u);
+        }
+        let (bob, alice, tutor) = (&users[0], &users[1], &users[2]);
+
+        submit(
+            &db,
+            assignments[0].id,
+            bob.id,
+            1,
+            9.0,
+            due - Duration::days(2),
+        )
+        .await;
+        submit(
+            &db,
+            assignments[0].id,
+            bob.id,
+            2,
+            5.0,
+            due + Duration::hours(1),
+        )
+        .await;
+        submit(&db, assignments[0].id, tutor.id, 1, 10.0, due).await;
+
+        let columns = columns(&db, module.id).await.unwrap();
+        assert_eq!(columns.len(), 2);
+
+        let (students, total) = students(&db, module.id, None, 1, 10).await.unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(students[0].id, alice.id);
+
+        let rows = rows(&db, module.id, &columns, students).await.unwrap();
+        assert!(rows[0].cells.iter().all(Option::is_none));
+        let cell = rows[1].cells[0].clone().unwrap();
+        assert_eq!(cell.attempts, 2);
+        assert_eq!(cell.best, 90.0);
+        assert_eq!(cell.last, 50.0);
+        // No config on disk: the default policy (last) counts
+        assert_eq!(cell.mark, 50.0);
+        assert!(cell.late);
+        assert!(rows[1].cells[1].is_none());
+
+        // An extension moves the late line
+        assignment_extension::Model::grant(
+            &db,
+            assignments[0].id,
+            bob.id,
+            due + Duration::days(1),
+            None,
+            tutor.id,
+        )
+        .await
+        .unwrap();
+        let columns = super::columns(&db, module.id).await.unwrap();
+        let (students, _) = super::students(&db, module.id, Some("bob"), 1, 10)
+            .await
+            .unwrap();
+        let rows = super::rows(&db, module.id, &columns, students)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(!rows[0].cells[0].as_ref().unwrap().late);
+    }
+}
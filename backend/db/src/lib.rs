@@ -1,28 +1,123 @@
 pub mod grade;
+pub mod gradebook;
 pub mod models;
+pub mod rollover;
+pub mod soft_delete;
 pub mod test_utils;
 
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseBackend, DatabaseConnection, DbErr,
+    Statement,
+};
 use std::path::Path;
+use std::time::Duration;
 use util::config;
 
+/// Connects to `DATABASE_PATH`: a SQLite file path or a `sqlite:`/`postgres://` URL.
 pub async fn connect() -> DatabaseConnection {
-    let path_or_url = config::database_path(); // your env var
-    // If it's already a DSN, use it as-is; otherwise treat it as a SQLite file path.
-    let url = if path_or_url.starts_with("sqlite:")
-        || path_or_url.starts_with("postgres://")
-        || path_or_url.starts_with("mysql://")
-    {
-        path_or_url
-    } else {
+    let path_or_url = config::database_path();
+    if let Some(path) = config::sqlite_database_file(&path_or_url) {
         // Ensure parent directory exists (SQLite won't create intermediate dirs).
-        if let Some(parent) = Path::new(&path_or_url).parent() {
+        if let Some(parent) = Path::new(path).parent() {
             let _ = std::fs::create_dir_all(parent);
         }
-        format!("sqlite://{path_or_url}") // yields sqlite:///abs/path for absolute paths
-    };
+    }
 
-    Database::connect(&url)
+    Database::connect(pool_options(config::database_url_for(&path_or_url)))
         .await
         .expect("Failed to connect to database")
 }
+
+/// Connects to `DATABASE_REPLICA_PATH`, or returns `None` when no replica is configured.
+///
+/// A SQLite replica is opened read-only; it is expected to be kept in sync externally
+/// (e.g. by Litestream).
+pub async fn connect_read_replica() -> Option<DatabaseConnection> {
+    let path_or_url = config::database_replica_path()?;
+    let url = match config::sqlite_database_file(&path_or_url) {
+        Some(path) => format!("sqlite://{path}?mode=ro"),
+        None => path_or_url,
+    };
+
+    Some(
+        Database::connect(pool_options(url))
+            .await
+            .expect("Failed to connect to read replica"),
+    )
+}
+
+/// Pool limits and timeouts from the `DB_*` settings.
+pub fn pool_options(url: String) -> ConnectOptions {
+    let mut opts = ConnectOptions::new(url);
+    opts.max_connections(config::db_max_connections())
+        .min_connections(config::db_min_connections())
+        .connect_timeout(Duration::from_secs(config::db_connect_timeout_secs()))
+        .acquire_timeout(Duration::from_secs(config::db_acquire_timeout_secs()))
+        .idle_timeout(Duration::from_secs(config::db_idle_timeout_secs()));
+    opts
+}
+
+/// Moves every PostgreSQL id sequence past the largest id in its table.
+///
+/// Rows inserted with explicit ids (the seeder's fixed modules and assignments, data copied in
+/// from SQLite) do not advance the sequence, so the next generated id would collide. SQLite
+/// picks `max(rowid) + 1` by itself; this is a no-op there.
+pub async fn sync_id_sequences(db: &DatabaseConnection) -> Result<(), DbErr> {
+    if db.get_database_backend() != DatabaseBackend::Postgres {
+        return Ok(());
+    }
+
+    let serial_columns = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Postgres,
+            "SELECT table_name, column_name FROM information_schema.columns \
+             WHERE table_schema = current_schema() AND column_default LIKE 'nextval(%'",
+        ))
+        .await?;
+    for row in serial_columns {
+        let table: String = row.try_get("", "table_name")?;
+        let column: String = row.try_get("", "column_name")?;
+        db.execute(Statement::from_string(
+            DatabaseBackend::Postgres,
+            format!(
+                "SELECT setval(pg_get_serial_sequence('\"{table}\"', '{column}'), \
+                 COALESCE(MAX(\"{column}\"), 0) + 1, false) FROM \"{table}\""
+            ),
+        ))
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::module;
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+    use sea_orm::{ActiveModelTrait, Set};
+
+    #[tokio::test]
+    async fn generated_ids_follow_explicit_ones_after_sync() {
+        let db = setup_test_db().await;
+        module::ActiveModel {
+            id: Set(9999),
+            code: Set("COS999".into()),
+            year: Set(2025),
+            description: Set(None),
+            credits: Set(16),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        sync_id_sequences(&db).await.unwrap();
+
+        let next = module::Model::create(&db, "COS111", 2025, None, 16)
+            .await
+            .unwrap();
+        assert_eq!(next.id, 10000);
+    }
+}
@@ -1,6 +1,9 @@
+use crate::soft_delete::SoftDelete;
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{Condition, QueryOrder};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Deserialize, Serialize)]
@@ -18,6 +21,16 @@ pub struct Model {
 
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the announcement goes live; `None` publishes it as soon as it is created. Until
+    /// then only module staff can see it.
+    pub publish_at: Option<DateTime<Utc>>,
+    /// When the announcement stops being shown to students, if ever.
+    pub expires_at: Option<DateTime<Utc>>,
+    /// When members were notified that it went live; `None` while a scheduled announcement is
+    /// still waiting for the publisher.
+    pub published_at: Option<DateTime<Utc>>,
+    /// When the announcement was soft deleted; `None` while it is live.
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -54,6 +67,7 @@ impl Related<super::user::Entity> for Entity {
 impl ActiveModelBehavior for ActiveModel {}
 
 impl Model {
+    /// Creates an announcement that is published straight away.
     pub async fn create(
         db: &DbConn,
         module_id: i64,
@@ -61,8 +75,28 @@ impl Model {
         title: &str,
         body: &str,
         pinned: bool,
+    ) -> Result<Model, DbErr> {
+        Self::create_scheduled(db, module_id, user_id, title, body, pinned, None, None).await
+    }
+
+    /// Creates an announcement that goes live at `publish_at` (straight away if that is `None`
+    /// or already past) and comes down at `expires_at`.
+    ///
+    /// Announcements published straight away are stamped `published_at` here; scheduled ones
+    /// are left for [`Model::due_for_publishing`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_scheduled(
+        db: &DbConn,
+        module_id: i64,
+        user_id: i64,
+        title: &str,
+        body: &str,
+        pinned: bool,
+        publish_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<Model, DbErr> {
         let now = Utc::now();
+        let publish_at = publish_at.filter(|at| *at > now);
         let announcement = ActiveModel {
             module_id: Set(module_id),
             user_id: Set(user_id),
@@ -71,23 +105,98 @@ impl Model {
             pinned: Set(pinned),
             created_at: Set(now),
             updated_at: Set(now),
+            publish_at: Set(publish_at),
+            expires_at: Set(expires_at),
+            published_at: Set(publish_at.is_none().then_some(now)),
             ..Default::default()
         };
 
         announcement.insert(db).await
     }
 
-    pub async fn delete(db: &DbConn, id: i64) -> Result<(), DbErr> {
-        Entity::delete_by_id(id).exec(db).await?;
+    /// Condition matching announcements students can see at `now`: live and not yet expired.
+    pub fn visible_at(now: DateTime<Utc>) -> Condition {
+        Condition::all()
+            .add(
+                Condition::any()
+                    .add(Column::PublishAt.is_null())
+                    .add(Column::PublishAt.lte(now)),
+            )
+            .add(
+                Condition::any()
+                    .add(Column::ExpiresAt.is_null())
+                    .add(Column::ExpiresAt.gt(now)),
+            )
+    }
+
+    /// Whether students can see the announcement at `now`.
+    pub fn is_visible(&self, now: DateTime<Utc>) -> bool {
+        self.publish_at.is_none_or(|at| at <= now) && self.expires_at.is_none_or(|at| at > now)
+    }
+
+    /// Scheduled announcements whose publish time has come but whose members have not been told
+    /// yet, oldest first.
+    pub async fn due_for_publishing(db: &DbConn, now: DateTime<Utc>) -> Result<Vec<Model>, DbErr> {
+        Entity::find_active()
+            .filter(Column::PublishAt.lte(now))
+            .filter(Column::PublishedAt.is_null())
+            .order_by_asc(Column::PublishAt)
+            .all(db)
+            .await
+    }
+
+    /// Records that the announcement was published, returning `false` if it already had been.
+    pub async fn mark_published(db: &DbConn, id: i64) -> Result<bool, DbErr> {
+        let res = Entity::update_many()
+            .col_expr(Column::PublishedAt, Expr::value(Utc::now()))
+            .filter(Column::Id.eq(id))
+            .filter(Column::PublishedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Soft deletes the announcement; it can be restored until the retention sweep purges it.
+    pub async fn delete(db: &DbConn, module_id: i64, id: i64) -> Result<(), DbErr> {
+        let res = Entity::mark_deleted()
+            .filter(Column::Id.eq(id))
+            .filter(Column::ModuleId.eq(module_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "Announcement {id} in module {module_id} not found"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Brings back a soft-deleted announcement.
+    pub async fn restore(db: &DbConn, module_id: i64, id: i64) -> Result<(), DbErr> {
+        let res = Entity::mark_restored()
+            .filter(Column::Id.eq(id))
+            .filter(Column::ModuleId.eq(module_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "No deleted announcement {id} in module {module_id}"
+            )));
+        }
         Ok(())
     }
 
+    /// Updates the announcement. Empty `title`/`body` keep the existing ones; `publish_at` and
+    /// `expires_at` are only changed when given.
+    #[allow(clippy::too_many_arguments)]
     pub async fn update(
         db: &DbConn,
         id: i64,
         title: &str,
         body: &str,
         pinned: bool,
+        publish_at: Option<DateTime<Utc>>,
+        expires_at: Option<DateTime<Utc>>,
     ) -> Result<Model, DbErr> {
         let mut announcement = ActiveModel {
             id: Set(id),
@@ -102,7 +211,85 @@ impl Model {
             announcement.body = Set(body.to_owned());
         }
         announcement.pinned = Set(pinned);
+        if let Some(at) = publish_at {
+            announcement.publish_at = Set(Some(at));
+        }
+        if let Some(at) = expires_at {
+            announcement.expires_at = Set(Some(at));
+        }
 
         announcement.update(db).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{module, user};
+    use crate::test_utils::setup_test_db;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn scheduled_announcements_are_published_once() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let author = user::Model::create(&db, "lecturer", "l@test.com", "pw", false)
+            .await
+            .unwrap();
+        let now = Utc::now();
+
+        let live = Model::create(&db, module.id, author.id, "Live", "b", false)
+            .await
+            .unwrap();
+        assert!(live.published_at.is_some());
+        assert!(live.is_visible(now));
+
+        let scheduled = Model::create_scheduled(
+            &db,
+            module.id,
+            author.id,
+            "Exam venue",
+            "b",
+            false,
+            Some(now + Duration::hours(1)),
+            Some(now + Duration::hours(3)),
+        )
+        .await
+        .unwrap();
+        assert!(scheduled.published_at.is_none());
+        assert!(!scheduled.is_visible(now));
+        assert!(scheduled.is_visible(now + Duration::hours(2)));
+        assert!(!scheduled.is_visible(now + Duration::hours(4)));
+
+        assert!(
+            Model::due_for_publishing(&db, now)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let later = now + Duration::hours(2);
+        let due = Model::due_for_publishing(&db, later).await.unwrap();
+        assert_eq!(
+            due.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![scheduled.id]
+        );
+
+        let visible = Entity::find()
+            .filter(Model::visible_at(later))
+            .all(&db)
+            .await
+            .unwrap();
+        assert_eq!(visible.len(), 2);
+
+        assert!(Model::mark_published(&db, scheduled.id).await.unwrap());
+        assert!(!Model::mark_published(&db, scheduled.id).await.unwrap());
+        assert!(
+            Model::due_for_publishing(&db, later)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! API tokens.
+//!
+//! Personal access tokens let scripts and CI pipelines call the API as a user without their
+//! password or a short-lived login JWT. A token is `ffk_` followed by random characters, is
+//! shown once when created, and is kept only as a SHA-256 hash. Every token expires and carries
+//! [`Scope`]s limiting what it may do; admins can also issue tokens for service accounts.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// What every token starts with, so it can be told apart from a JWT.
+pub const TOKEN_PREFIX: &str = "ffk_";
+
+/// Random characters after [`TOKEN_PREFIX`].
+const TOKEN_LEN: usize = 40;
+
+/// Characters of the token kept in the clear, for listing tokens.
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// How often `last_used_at` is refreshed, to spare a write per request.
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+/// What a token may do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// `GET` and `HEAD` requests.
+    Read,
+    /// Any request.
+    Write,
+    /// The owner's admin rights; without it, a token for an admin acts as a regular user.
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "read" => Some(Scope::Read),
+            "write" => Some(Scope::Write),
+            "admin" => Some(Scope::Admin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "api_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    /// The token's first characters, e.g. `ffk_a1B2c3D4`.
+    pub token_prefix: String,
+    #[serde(skip_serializing)]
+    #[sea_orm(unique)]
+    pub token_hash: String,
+    /// Space-separated [`Scope`]s.
+    pub scopes: String,
+    pub expires_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// The admin who issued it, when not the owner.
+    pub created_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Whether a bearer token looks like an API token rather than a JWT.
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+impl Model {
+    /// The token's scopes, ignoring any unknown ones.
+    pub fn scope_list(&self) -> Vec<Scope> {
+        self.scopes
+            .split_whitespace()
+            .filter_map(Scope::parse)
+            .collect()
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scope_list().contains(&scope)
+    }
+
+    /// Whether the token may make a request with `method`.
+    pub fn allows_method(&self, method: &str) -> bool {
+        match method {
+            "GET" | "HEAD" | "OPTIONS" => {
+                self.has_scope(Scope::Read) || self.has_scope(Scope::Write)
+            }
+            _ => self.has_scope(Scope::Write),
+        }
+    }
+
+    /// Creates a token for `user_id`, returning it with the token itself, which isn't stored.
+    pub async fn create(
+        db: &DatabaseConnection,
+        user_id: i64,
+        name: &str,
+        scopes: &[Scope],
+        expires_at: DateTime<Utc>,
+        created_by: Option<i64>,
+    ) -> Result<(Self, String), DbErr> {
+        let random: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(TOKEN_LEN)
+            .map(char::from)
+            .collect();
+        let token = format!("{TOKEN_PREFIX}{random}");
+
+        let mut scope_names: Vec<&str> = Vec::new();
+        for scope in scopes {
+            if !scope_names.contains(&scope.as_str()) {
+                scope_names.push(scope.as_str());
+            }
+        }
+
+        let model = ActiveModel {
+            user_id: Set(user_id),
+            name: Set(name.to_string()),
+            token_prefix: Set(token[..DISPLAY_PREFIX_LEN].to_string()),
+            token_hash: Set(hash_token(&token)),
+            scopes: Set(scope_names.join(" ")),
+            expires_at: Set(expires_at),
+            last_used_at: Set(None),
+            created_by: Set(created_by),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        Ok((model, token))
+    }
+
+    /// The unexpired token matching `token`, noting that it was used.
+    pub async fn authenticate(
+        db: &DatabaseConnection,
+        token: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>, DbErr> {
+        let Some(found) = Entity::find()
+            .filter(Column::TokenHash.eq(hash_token(token)))
+            .one(db)
+            .await?
+        else {
+            return Ok(None);
+        };
+        if found.expires_at <= now {
+            return Ok(None);
+        }
+        let stale = found
+            .last_used_at
+            .is_none_or(|at| now - at >= Duration::seconds(LAST_USED_RESOLUTION_SECS));
+        if !stale {
+            return Ok(Some(found));
+        }
+        let mut am = found.into_active_model();
+        am.last_used_at = Set(Some(now));
+        am.update(db).await.map(Some)
+    }
+
+    /// The user's tokens, newest first.
+    pub async fn for_user(db: &DatabaseConnection, user_id: i64) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Every token, newest first.
+    pub async fn all(db: &DatabaseConnection) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Revokes token `id`, only if it belongs to `owner` when one is given.
+    pub async fn revoke(
+        db: &DatabaseConnection,
+        id: i64,
+        owner: Option<i64>,
+    ) -> Result<bool, DbErr> {
+        let mut delete = Entity::delete_many().filter(Column::Id.eq(id));
+        if let Some(user_id) = owner {
+            delete = delete.filter(Column::UserId.eq(user_id));
+        }
+        Ok(delete.exec(db).await?.rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user::Model as UserModel;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn tokens_are_hashed_and_expire() {
+        let db = setup_test_db().await;
+        let user = UserModel::create(&db, "ci", "ci@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let now = Utc::now();
+        let (model, token) = Model::create(
+            &db,
+            user.id,
+            "CI",
+            &[Scope::Read, Scope::Read],
+            now + Duration::days(1),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(is_api_token(&token));
+        assert!(token.starts_with(&model.token_prefix));
+        assert_ne!(model.token_hash, token);
+        assert_eq!(model.scopes, "read");
+        assert!(model.allows_method("GET"));
+        assert!(!model.allows_method("POST"));
+
+        let used = Model::authenticate(&db, &token, now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(used.last_used_at, Some(now));
+        assert!(
+            Model::authenticate(&db, "ffk_not-a-token", now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            Model::authenticate(&db, &token, now + Duration::days(2))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(
+            !Model::revoke(&db, model.id, Some(user.id + 1))
+                .await
+                .unwrap()
+        );
+        assert!(Model::revoke(&db, model.id, Some(user.id)).await.unwrap());
+        assert!(
+            Model::authenticate(&db, &token, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
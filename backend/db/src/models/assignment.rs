@@ -3,10 +3,17 @@
 //! This module defines the `Assignment` model, its relations, and
 //! methods for creating, editing, and filtering assignments.
 
+use crate::models::assignment_extension;
 use crate::models::assignment_file::{FileType, Model as AssignmentFileModel};
-use crate::models::assignment_submission::{Column as SubmissionCol, Entity as SubmissionEntity};
+use crate::models::assignment_memo_output::{
+    Column as MemoOutputColumn, Entity as MemoOutputEntity,
+};
+use crate::models::assignment_submission::{
+    Column as SubmissionCol, Entity as SubmissionEntity, Model as SubmissionModel,
+};
 use crate::models::assignment_task::{Column as TaskColumn, Entity as TaskEntity};
 use crate::models::moss_report;
+use crate::soft_delete::SoftDelete;
 use crate::models::user_module_role::{
     Column as UserModuleRoleCol, Entity as UserModuleRoleEntity, Role as ModuleRole,
 };
@@ -19,12 +26,15 @@ use sea_orm::{
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::net::IpAddr;
+use std::path::PathBuf;
 use strum::{Display, EnumIter, EnumString};
-use util::execution_config::ExecutionConfig;
+use util::execution_config::{ExamOptions, ExecutionConfig, PlagiarismOptions};
 use util::execution_config::SubmissionMode;
-use util::paths::{assignment_dir, interpreter_dir, memo_output_dir};
+use util::mark_allocator::{AllocatorMismatch, load_allocator, validate_against_outputs};
+use util::paths::{assignment_dir, interpreter_dir, memo_output_dir, storage_root};
 
 /// Assignment model representing the `assignments` table in the database.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -41,6 +51,8 @@ pub struct Model {
     pub due_date: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the assignment was soft deleted; `None` while it is live.
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 /// Defines the relationship between `Assignment` and `Module`.
@@ -68,11 +80,7 @@ impl ActiveModelBehavior for ActiveModel {}
 #[derive(
     Debug, Clone, PartialEq, Display, EnumIter, EnumString, Serialize, Deserialize, DeriveActiveEnum,
 )]
-#[sea_orm(
-    rs_type = "String",
-    db_type = "Enum",
-    enum_name = "assignment_type_enum"
-)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 #[strum(serialize_all = "lowercase", ascii_case_insensitive)]
 pub enum AssignmentType {
     #[sea_orm(string_value = "assignment")]
@@ -85,11 +93,7 @@ pub enum AssignmentType {
 #[derive(
     Debug, Clone, PartialEq, Display, EnumIter, EnumString, Serialize, Deserialize, DeriveActiveEnum,
 )]
-#[sea_orm(
-    rs_type = "String",
-    db_type = "Enum",
-    enum_name = "assignment_status_enum"
-)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 #[strum(serialize_all = "lowercase", ascii_case_insensitive)]
 pub enum Status {
     #[sea_orm(string_value = "setup")]
@@ -116,6 +120,10 @@ pub struct ReadinessReport {
     pub makefile_present: bool,
     pub memo_output_present: bool,
     pub mark_allocator_present: bool,
+    /// Where the allocator disagrees with the memo output. Advisory: does not affect
+    /// [`ReadinessReport::is_ready`].
+    #[serde(default)]
+    pub mark_allocator_issues: Vec<AllocatorMismatch>,
 }
 
 impl ReadinessReport {
@@ -178,9 +186,14 @@ impl Model {
 
         let created = active.insert(db).await?;
 
-        // auto-create default config.json (mirror on disk + DB record)
-        let default_config = ExecutionConfig::default_config();
-        match serde_json::to_vec(&default_config) {
+        // auto-create default config.json (mirror on disk + DB record); with a module config
+        // this holds no overrides, so the assignment inherits everything from it
+        let default_config = ExecutionConfig::module_defaults(module_id)
+            .unwrap_or_else(|_| ExecutionConfig::default_config());
+        match default_config
+            .overrides(module_id)
+            .and_then(|v| serde_json::to_vec(&v).map_err(|e| e.to_string()))
+        {
             Ok(bytes) => {
                 if let Err(e) = AssignmentFileModel::save_file(
                     db,
@@ -235,7 +248,7 @@ impl Model {
     ) -> Result<Self, DbErr> {
         Self::validate_dates(available_from, due_date)?;
 
-        let mut assignment = Entity::find()
+        let mut assignment = Entity::find_active()
             .filter(Column::Id.eq(id))
             .filter(Column::ModuleId.eq(module_id))
             .one(db)
@@ -253,22 +266,42 @@ impl Model {
         assignment.update(db).await
     }
 
-    pub async fn delete(db: &DatabaseConnection, id: i32, module_id: i32) -> Result<(), DbErr> {
-        let Some(model) = Entity::find()
+    /// Soft deletes the assignment. Its files stay on disk until the retention sweep purges it.
+    pub async fn delete(db: &DatabaseConnection, id: i64, module_id: i64) -> Result<(), DbErr> {
+        let res = Entity::mark_deleted()
             .filter(Column::Id.eq(id))
             .filter(Column::ModuleId.eq(module_id))
-            .one(db)
-            .await?
-        else {
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
             return Err(DbErr::RecordNotFound(format!(
                 "Assignment {id} in module {module_id} not found"
             )));
-        };
+        }
+        Ok(())
+    }
+
+    /// Brings back a soft-deleted assignment.
+    pub async fn restore(db: &DatabaseConnection, id: i64, module_id: i64) -> Result<(), DbErr> {
+        let res = Entity::mark_restored()
+            .filter(Column::Id.eq(id))
+            .filter(Column::ModuleId.eq(module_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "No deleted assignment {id} in module {module_id}"
+            )));
+        }
+        Ok(())
+    }
 
-        let active = model.into_active_model();
-        active.delete(db).await?;
+    /// Removes the assignment row and its directory for good. Files, submissions and tickets
+    /// go with the cascade; the caller prunes content blobs afterwards.
+    pub async fn purge(self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        let dir = assignment_dir(self.module_id, self.id);
+        self.into_active_model().delete(db).await?;
 
-        let dir = assignment_dir(module_id as i64, id as i64);
         if dir.exists() {
             if let Err(e) = fs::remove_dir_all(&dir) {
                 eprintln!(
@@ -277,7 +310,6 @@ impl Model {
                 );
             }
         }
-
         Ok(())
     }
 
@@ -288,7 +320,7 @@ impl Model {
         sort_by: Option<String>,
         query: Option<String>,
     ) -> Result<Vec<Self>, DbErr> {
-        let mut query_builder = Entity::find();
+        let mut query_builder = Entity::find_active();
 
         if let Some(q) = query {
             let pattern = format!("%{}%", q.to_lowercase());
@@ -357,7 +389,7 @@ impl Model {
     /// - A **memo** file exists.
     /// - A **makefile** exists.
     /// - At least one **memo output** file exists.
-    /// - A JSON **mark allocator** file exists.
+    /// - A **mark allocator** file (JSON or YAML) exists.
     /// - **Main** or **Interpreter** presence is required **conditionally**:
     ///     - If `SubmissionMode::Manual`  → **main** file must be present.
     ///     - If `SubmissionMode::GATLAM`  → **interpreter** must be present.
@@ -366,6 +398,7 @@ impl Model {
     /// The returned [`ReadinessReport`] includes:
     /// - `submission_mode`: the mode resolved from `config.json` (or default if missing/invalid).
     /// - Boolean flags for each component, including `main_present` and `interpreter_present`.
+    /// - `mark_allocator_issues`: mismatches between the allocator and the memo outputs.
     /// - `is_ready()` that applies the conditional rule above.
     ///
     /// This function only checks readiness — it does **not** modify the assignment's status.
@@ -442,15 +475,25 @@ impl Model {
         )
         .read_dir()
         .map(|it| {
-            it.flatten()
-                .any(|f| f.path().extension().map(|e| e == "json").unwrap_or(false))
+            it.flatten().any(|f| {
+                f.path()
+                    .extension()
+                    .is_some_and(|e| e == "json" || e == "yaml" || e == "yml")
+            })
         })
         .unwrap_or(false);
 
         // Determine submission mode: prefer on-disk config.json; fallback to default
-        let submission_mode = ExecutionConfig::get_execution_config(module_id, assignment_id)
-            .map(|c| c.project.submission_mode)
-            .unwrap_or_else(|_| ExecutionConfig::default_config().project.submission_mode);
+        let config = ExecutionConfig::get_execution_config(module_id, assignment_id)
+            .unwrap_or_else(|_| ExecutionConfig::default_config());
+        let submission_mode = config.project.submission_mode;
+
+        let mark_allocator_issues = if mark_allocator_present {
+            Self::mark_allocator_issues(db, module_id, assignment_id, &config.marking.delimiter)
+                .await?
+        } else {
+            Vec::new()
+        };
 
         Ok(ReadinessReport {
             submission_mode,
@@ -462,9 +505,41 @@ impl Model {
             makefile_present,
             memo_output_present,
             mark_allocator_present,
+            mark_allocator_issues,
         })
     }
 
+    /// Checks the assignment's allocator against its memo outputs (see
+    /// [`validate_against_outputs`]).
+    async fn mark_allocator_issues(
+        db: &DatabaseConnection,
+        module_id: i64,
+        assignment_id: i64,
+        delimiter: &str,
+    ) -> Result<Vec<AllocatorMismatch>, DbErr> {
+        let alloc = match load_allocator(module_id, assignment_id) {
+            Ok(alloc) => alloc,
+            Err(message) => return Ok(vec![AllocatorMismatch::InvalidAllocator { message }]),
+        };
+
+        let task_numbers: HashMap<i64, i64> = TaskEntity::find()
+            .filter(TaskColumn::AssignmentId.eq(assignment_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|t| (t.id, t.task_number))
+            .collect();
+        let memo_outputs: Vec<(i64, PathBuf)> = MemoOutputEntity::find()
+            .filter(MemoOutputColumn::AssignmentId.eq(assignment_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|m| Some((*task_numbers.get(&m.task_id)?, storage_root().join(m.path))))
+            .collect();
+
+        Ok(validate_against_outputs(&alloc, &memo_outputs, delimiter))
+    }
+
     /// Attempts to transition an assignment to `Ready` state if all readiness conditions are met.
     ///
     /// This function:
@@ -550,17 +625,55 @@ impl Model {
             .unwrap_or(10)
     }
 
+    /// The due date that applies to `user_id`: their extension's, if they have one.
+    pub async fn due_date_for(
+        &self,
+        db: &DatabaseConnection,
+        user_id: i64,
+    ) -> Result<DateTime<Utc>, DbErr> {
+        Ok(assignment_extension::Model::for_user(db, self.id, user_id)
+            .await?
+            .map(|e| e.due_date)
+            .unwrap_or(self.due_date))
+    }
+
+    /// The assignment's timed-exam settings (not an exam if config missing).
+    pub fn exam(&self) -> ExamOptions {
+        self.config().map(|cfg| cfg.exam).unwrap_or_default()
+    }
+
+    /// The assignment's plagiarism case settings (defaults if config missing).
+    pub fn plagiarism(&self) -> PlagiarismOptions {
+        self.config().map(|cfg| cfg.plagiarism).unwrap_or_default()
+    }
+
+    /// Whether students get marks without feedback on practice submissions (default false if
+    /// config missing).
+    pub fn reduced_practice_feedback(&self) -> bool {
+        self.config()
+            .map(|cfg| cfg.marking.reduced_practice_feedback)
+            .unwrap_or(false)
+    }
+
+    /// Whether a member's submission counts for their whole group (default false if config missing).
+    pub fn group_submissions(&self) -> bool {
+        self.config()
+            .map(|cfg| cfg.marking.group_submissions)
+            .unwrap_or(false)
+    }
+
     /// Count the number of used attempts for a user on this assignment.
     ///
-    /// Counts only **non-practice** and **non-ignored** submissions.
+    /// Counts only **non-practice**, **non-ignored** and **non-deleted** submissions. With group
+    /// submissions, the group's submissions count too, so members share one attempt budget.
     pub async fn attempts_used_by_user(
         &self,
         db: &DatabaseConnection,
         user_id: i64,
     ) -> Result<u32, DbErr> {
-        let count = SubmissionEntity::find()
+        let count = SubmissionEntity::find_active()
             .filter(SubmissionCol::AssignmentId.eq(self.id))
-            .filter(SubmissionCol::UserId.eq(user_id))
+            .filter(SubmissionModel::owner_condition(db, self, user_id).await?)
             .filter(SubmissionCol::IsPractice.eq(false))
             .filter(SubmissionCol::Ignored.eq(false))
             .count(db)
@@ -0,0 +1,256 @@
+//! Individual extensions: a student's own due date for one assignment.
+//!
+//! The late-acceptance gate, the late cap and `is_late` flags use a student's extended due date
+//! (see [`super::assignment::Model::due_date_for`]) instead of the assignment's.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Condition, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "assignment_extensions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub assignment_id: i64,
+    pub user_id: i64,
+    /// Replaces the assignment's due date for this student.
+    pub due_date: DateTime<Utc>,
+    pub reason: Option<String>,
+    /// The staff member who granted it; `None` once they are deleted.
+    pub granted_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Grants `user_id` an extension, replacing any existing one for the assignment.
+    pub async fn grant(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+        due_date: DateTime<Utc>,
+        reason: Option<&str>,
+        granted_by: i64,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        match Self::for_user(db, assignment_id, user_id).await? {
+            Some(existing) => {
+                let mut am: ActiveModel = existing.into();
+                am.due_date = Set(due_date);
+                am.reason = Set(reason.map(str::to_owned));
+                am.granted_by = Set(Some(granted_by));
+                am.updated_at = Set(now);
+                am.update(db).await
+            }
+            None => {
+                ActiveModel {
+                    assignment_id: Set(assignment_id),
+                    user_id: Set(user_id),
+                    due_date: Set(due_date),
+                    reason: Set(reason.map(str::to_owned)),
+                    granted_by: Set(Some(granted_by)),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+
+    /// Removes the student's extension, if they have one.
+    pub async fn revoke(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+    ) -> Result<(), DbErr> {
+        let res = Entity::delete_many()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound("Extension not found".into()));
+        }
+        Ok(())
+    }
+
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await
+    }
+
+    /// The assignment's extensions, latest due date first.
+    pub async fn list_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .order_by_desc(Column::DueDate)
+            .order_by_asc(Column::UserId)
+            .all(db)
+            .await
+    }
+
+    /// Extended due dates of the assignment, keyed by user id.
+    pub async fn due_dates(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<HashMap<i64, DateTime<Utc>>, DbErr> {
+        Ok(Self::list_for_assignment(db, assignment_id)
+            .await?
+            .into_iter()
+            .map(|e| (e.user_id, e.due_date))
+            .collect())
+    }
+
+    /// Matches submissions made after (`late`) or by (`!late`) the submitter's own due date,
+    /// given the assignment's `due_date` and its extended `due_dates`.
+    pub fn late_condition<C: ColumnTrait>(
+        user_col: C,
+        created_at_col: C,
+        due_date: DateTime<Utc>,
+        due_dates: &HashMap<i64, DateTime<Utc>>,
+        late: bool,
+    ) -> Condition {
+        let compare = |due: DateTime<Utc>| {
+            if late {
+                created_at_col.gt(due)
+            } else {
+                created_at_col.lte(due)
+            }
+        };
+        let mut cond = Condition::any().add(
+            Condition::all()
+                .add(user_col.is_not_in(due_dates.keys().copied()))
+                .add(compare(due_date)),
+        );
+        for (user_id, due) in due_dates {
+            cond = cond.add(
+                Condition::all()
+                    .add(user_col.eq(*user_id))
+                    .add(compare(*due)),
+            );
+        }
+        cond
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{assignment, module, user};
+    use crate::test_utils::setup_test_db;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn grant_replaces_and_revoke_removes() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let due = Utc::now();
+        let a = assignment::Model::create(
+            &db,
+            module.id,
+            "A1",
+            None,
+            assignment::AssignmentType::Assignment,
+            due - Duration::days(7),
+            due,
+        )
+        .await
+        .unwrap();
+        let lecturer = user::Model::create(&db, "lect", "lect@test.com", "pw", false)
+            .await
+            .unwrap();
+        let student = user::Model::create(&db, "stud", "stud@test.com", "pw", false)
+            .await
+            .unwrap();
+        let other = user::Model::create(&db, "other", "other@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        assert_eq!(a.due_date_for(&db, student.id).await.unwrap(), a.due_date);
+
+        Model::grant(
+            &db,
+            a.id,
+            student.id,
+            due + Duration::days(1),
+            None,
+            lecturer.id,
+        )
+        .await
+        .unwrap();
+        let ext = Model::grant(
+            &db,
+            a.id,
+            student.id,
+            due + Duration::days(3),
+            Some("Medical"),
+            lecturer.id,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            Model::list_for_assignment(&db, a.id).await.unwrap().len(),
+            1
+        );
+        assert_eq!(ext.reason.as_deref(), Some("Medical"));
+        assert_eq!(a.due_date_for(&db, student.id).await.unwrap(), ext.due_date);
+        assert_eq!(a.due_date_for(&db, other.id).await.unwrap(), a.due_date);
+
+        Model::revoke(&db, a.id, student.id).await.unwrap();
+        assert_eq!(a.due_date_for(&db, student.id).await.unwrap(), a.due_date);
+        assert!(matches!(
+            Model::revoke(&db, a.id, student.id).await,
+            Err(DbErr::RecordNotFound(_))
+        ));
+    }
+}
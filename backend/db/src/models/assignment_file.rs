@@ -1,13 +1,14 @@
+use super::content_blob;
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use sea_orm::{ActiveValue::Set, DatabaseConnection, DbErr};
-use std::fs;
 use std::path::PathBuf;
 use strum::{Display, EnumIter, EnumString};
 use util::execution_config::ExecutionConfig;
 use util::paths::{
     config_dir, main_dir, makefile_dir, mark_allocator_dir, memo_dir, spec_dir, storage_root,
 };
+use util::storage::{key_for, storage};
 
 /// Represents a file associated with an assignment, such as a spec, main file, memo, or submission.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -28,6 +29,10 @@ pub struct Model {
     /// Type of the file (spec, main, memo, submission).
     pub file_type: FileType,
 
+    /// SHA-256 of the contents if the file is stored through a shared
+    /// [`content_blob`](super::content_blob).
+    pub content_hash: Option<String>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -35,11 +40,7 @@ pub struct Model {
 /// Enum representing the type/category of an assignment file.
 #[derive(Debug, Clone, PartialEq, EnumIter, EnumString, Display, DeriveActiveEnum)]
 #[strum(ascii_case_insensitive)]
-#[sea_orm(
-    rs_type = "String",
-    db_type = "Enum",
-    enum_name = "assignment_file_type"
-)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 pub enum FileType {
     #[strum(serialize = "spec")]
     #[sea_orm(string_value = "spec")]
@@ -73,6 +74,16 @@ pub enum Relation {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl FileType {
+    /// Whether files of this type are deduplicated through content blobs.
+    ///
+    /// Configs and mark allocators are excluded: they are rewritten in place after upload,
+    /// which would change every file sharing the blob.
+    pub fn is_deduplicated(&self) -> bool {
+        !matches!(self, FileType::Config | FileType::MarkAllocator)
+    }
+}
+
 impl Model {
     /// Loads and returns the `ExecutionConfig` if the file type is `Config`.
     /// Requires `module_id` because it's not stored in the DB.
@@ -126,26 +137,19 @@ impl Model {
             .one(db)
             .await?
         {
-            let tracked_full = storage_root().join(&existing.path);
-            if let Some(parent) = tracked_full.parent() {
-                fs::create_dir_all(parent)
-                    .map_err(|e| DbErr::Custom(format!("Failed to create directory: {e}")))?;
+            let content_hash = Self::store(db, &file_type, &existing.path, bytes).await?;
+            if let Some(old) = &existing.content_hash {
+                content_blob::Model::release(db, old).await?;
             }
-            fs::write(&tracked_full, bytes)
-                .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
 
             // Mirror to canonical config.json if needed
             if file_type == FileType::Config {
-                let canonical =
-                    Self::full_directory_path(module_id, assignment_id, &FileType::Config)
-                        .join("config.json");
-                if canonical != tracked_full {
-                    if let Some(parent) = canonical.parent() {
-                        fs::create_dir_all(parent).map_err(|e| {
-                            DbErr::Custom(format!("Failed to create directory: {e}"))
-                        })?;
-                    }
-                    fs::write(&canonical, bytes).map_err(|e| {
+                let canonical = key_for(
+                    &Self::full_directory_path(module_id, assignment_id, &FileType::Config)
+                        .join("config.json"),
+                );
+                if canonical != existing.path {
+                    storage().write(&canonical, bytes).await.map_err(|e| {
                         DbErr::Custom(format!("Failed to write canonical file: {e}"))
                     })?;
                 }
@@ -153,14 +157,13 @@ impl Model {
 
             let mut am: ActiveModel = existing.into();
             am.filename = Set(filename.to_string());
+            am.content_hash = Set(content_hash);
             am.updated_at = Set(now);
             return am.update(db).await;
         }
 
-        // No existing row: create directory and choose a canonical stored filename
+        // No existing row: choose a canonical stored filename
         let dir_path = Self::full_directory_path(module_id, assignment_id, &file_type);
-        fs::create_dir_all(&dir_path)
-            .map_err(|e| DbErr::Custom(format!("Failed to create directory: {e}")))?;
 
         let stored_filename: String = if file_type == FileType::Config {
             // canonical name for configs
@@ -173,21 +176,16 @@ impl Model {
                 .to_string()
         };
 
-        let file_path = dir_path.join(&stored_filename);
-        fs::write(&file_path, bytes)
-            .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
-
-        // In some test envs the temp storage root may differ; fall back to absolute.
-        let relative_path = match file_path.strip_prefix(storage_root()) {
-            Ok(p) => p.to_string_lossy().to_string(),
-            Err(_) => file_path.to_string_lossy().to_string(),
-        };
+        // In some test envs the temp storage root may differ; then the key is absolute.
+        let relative_path = key_for(&dir_path.join(&stored_filename));
+        let content_hash = Self::store(db, &file_type, &relative_path, bytes).await?;
 
         let partial = ActiveModel {
             assignment_id: Set(assignment_id),
             filename: Set(stored_filename),
             path: Set(relative_path),
             file_type: Set(file_type),
+            content_hash: Set(content_hash),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -196,16 +194,41 @@ impl Model {
         partial.insert(db).await
     }
 
-    /// Loads the file contents from disk based on the path stored in the model.
-    pub fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
-        let full_path = storage_root().join(&self.path);
-        fs::read(full_path)
+    /// Writes `bytes` to `key`, through a content blob for deduplicated types. Returns the
+    /// blob's hash, if one was used.
+    async fn store(
+        db: &DatabaseConnection,
+        file_type: &FileType,
+        key: &str,
+        bytes: &[u8],
+    ) -> Result<Option<String>, DbErr> {
+        if file_type.is_deduplicated() {
+            let blob = content_blob::Model::store_at(db, key, bytes).await?;
+            return Ok(Some(blob.hash));
+        }
+        storage()
+            .write(key, bytes)
+            .await
+            .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
+        Ok(None)
+    }
+
+    /// Drops this file's reference to its content blob. Call when deleting the record.
+    pub async fn release_content(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        match &self.content_hash {
+            Some(hash) => content_blob::Model::release(db, hash).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Loads the file contents from storage based on the path stored in the model.
+    pub async fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
+        storage().read(&self.path).await
     }
 
-    /// Deletes the file from disk (but not the DB record).
-    pub fn delete_file_only(&self) -> Result<(), std::io::Error> {
-        let full_path = storage_root().join(&self.path);
-        fs::remove_file(full_path)
+    /// Deletes the file from storage (but not the DB record).
+    pub async fn delete_file_only(&self) -> Result<(), std::io::Error> {
+        storage().delete(&self.path).await
     }
 
     pub async fn get_base_files(
@@ -289,11 +312,11 @@ mod tests {
         assert!(full_path.exists());
 
         // Load contents
-        let bytes = saved.load_file().unwrap();
+        let bytes = saved.load_file().await.unwrap();
         assert_eq!(bytes, content);
 
         // Delete file only
-        saved.delete_file_only().unwrap();
+        saved.delete_file_only().await.unwrap();
         assert!(!full_path.exists());
     }
 }
@@ -1,10 +1,9 @@
 use chrono::{DateTime, Utc};
 use sea_orm::ActiveValue::Set;
 use sea_orm::entity::prelude::*;
-use std::fs;
 use std::path::PathBuf;
 use util::paths::interpreter_dir;
-use util::paths::storage_root;
+use util::storage::{key_for, storage};
 
 /// Represents an interpreter file associated with an assignment,
 /// including the command used to run it.
@@ -61,8 +60,7 @@ impl Model {
             .await?;
 
         for record in existing {
-            let existing_path = storage_root().join(&record.path);
-            let _ = fs::remove_file(existing_path);
+            let _ = storage().delete(&record.path).await;
             record.delete(db).await?;
         }
 
@@ -89,20 +87,13 @@ impl Model {
             None => inserted.id.to_string(),
         };
 
-        let dir_path = interpreter_dir(module_id, assignment_id);
-        fs::create_dir_all(&dir_path)
-            .map_err(|e| sea_orm::DbErr::Custom(format!("Failed to create directory: {e}")))?;
-
-        let file_path = dir_path.join(&stored_filename);
-        fs::write(&file_path, bytes)
+        let file_path = interpreter_dir(module_id, assignment_id).join(&stored_filename);
+        let relative_path = key_for(&file_path);
+        storage()
+            .write(&relative_path, bytes)
+            .await
             .map_err(|e| sea_orm::DbErr::Custom(format!("Failed to write file: {e}")))?;
 
-        let relative_path = file_path
-            .strip_prefix(storage_root())
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-
         let mut model: ActiveModel = inserted.into();
         model.path = Set(relative_path);
         model.updated_at = Set(Utc::now());
@@ -110,16 +101,14 @@ impl Model {
         model.update(db).await
     }
 
-    /// Load interpreter file content from disk.
-    pub fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
-        let full_path = storage_root().join(&self.path);
-        fs::read(full_path)
+    /// Load interpreter file content from storage.
+    pub async fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
+        storage().read(&self.path).await
     }
 
-    /// Delete the interpreter file from disk (but not DB record).
-    pub fn delete_file_only(&self) -> Result<(), std::io::Error> {
-        let full_path = storage_root().join(&self.path);
-        fs::remove_file(full_path)
+    /// Delete the interpreter file from storage (but not DB record).
+    pub async fn delete_file_only(&self) -> Result<(), std::io::Error> {
+        storage().delete(&self.path).await
     }
 }
 
@@ -192,11 +181,11 @@ impl Model {
 //         );
 //
 //         // Load contents
-//         let bytes = saved.load_file().unwrap();
+//         let bytes = saved.load_file().await.unwrap();
 //         assert_eq!(bytes, content);
 //
 //         // Delete file only
-//         saved.delete_file_only().unwrap();
+//         saved.delete_file_only().await.unwrap();
 //         assert!(
 //             !expected_path.exists(),
 //             "file should be removed at {:?}",
@@ -1,9 +1,9 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use sea_orm::{ActiveValue::Set, DatabaseConnection, EntityTrait};
-use std::fs;
 use std::path::PathBuf;
-use util::paths::{ensure_dir, memo_output_dir, storage_root};
+use util::paths::memo_output_dir;
+use util::storage::{key_for, storage};
 
 /// Represents the output generated by the interpreter for an assignment memo.
 ///
@@ -49,7 +49,7 @@ pub enum Relation {
 impl ActiveModelBehavior for ActiveModel {}
 
 impl Model {
-    /// Saves a memo output file to disk and creates or updates its metadata in the database.
+    /// Saves a memo output file to storage and creates or updates its metadata in the database.
     pub async fn save_file(
         db: &DatabaseConnection,
         assignment_id: i64,
@@ -90,23 +90,14 @@ impl Model {
 
         let module_id = assignment.module_id;
 
-        // Ensure directory exists (inline path util)
-        let dir_path = memo_output_dir(module_id, assignment_id);
-        ensure_dir(&dir_path)
-            .map_err(|e| DbErr::Custom(format!("Failed to create directory: {e}")))?;
-
-        // Write file
-        let file_path = dir_path.join(&stored_filename);
-        fs::write(&file_path, bytes)
+        // Store under a key relative to the storage root
+        let file_path = memo_output_dir(module_id, assignment_id).join(&stored_filename);
+        let relative_path = key_for(&file_path);
+        storage()
+            .write(&relative_path, bytes)
+            .await
             .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
 
-        // Store relative path from storage root (inline)
-        let relative_path = file_path
-            .strip_prefix(storage_root())
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-
         let mut model: ActiveModel = inserted.into();
         model.path = Set(relative_path);
         model.updated_at = Set(Utc::now());
@@ -114,17 +105,45 @@ impl Model {
         model.update(db).await
     }
 
-    /// Reads the contents of a memo output file from disk,
+    /// Deletes every memo output (DB rows and stored files) belonging to a single task.
+    ///
+    /// Returns the number of rows removed. Missing files are ignored.
+    pub async fn delete_for_task(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        task_id: i64,
+    ) -> Result<u64, DbErr> {
+        let existing = Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::TaskId.eq(task_id))
+            .all(db)
+            .await?;
+
+        for output in &existing {
+            if !output.path.is_empty() {
+                let _ = storage().delete(&output.path).await;
+            }
+        }
+
+        let res = Entity::delete_many()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::TaskId.eq(task_id))
+            .exec(db)
+            .await?;
+
+        Ok(res.rows_affected)
+    }
+
+    /// Reads the contents of a memo output file from storage,
     /// given the module_id, assignment_id, and the file id (filename base).
     ///
     /// NOTE: Preserves the original behavior of joining only `{file_id}` without a forced extension.
-    pub fn read_memo_output_file(
+    pub async fn read_memo_output_file(
         module_id: i64,
         assignment_id: i64,
         file_id: i64,
     ) -> Result<Vec<u8>, std::io::Error> {
-        let dir_path = memo_output_dir(module_id, assignment_id);
-        let file_path = dir_path.join(file_id.to_string());
-        std::fs::read(file_path)
+        let file_path = memo_output_dir(module_id, assignment_id).join(file_id.to_string());
+        storage().read(&key_for(&file_path)).await
     }
 }
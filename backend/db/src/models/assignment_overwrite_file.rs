@@ -1,9 +1,9 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
 use sea_orm::{ActiveValue::Set, DatabaseConnection, EntityTrait};
-use std::fs;
 use std::path::PathBuf;
-use util::paths::{ensure_dir, overwrite_task_dir, storage_root};
+use util::paths::overwrite_task_dir;
+use util::storage::{key_for, storage};
 
 /// Represents a file used to overwrite specific parts of an assignment during evaluation.
 /// Includes metadata such as its related assignment, task, filename, and storage path.
@@ -86,18 +86,13 @@ impl Model {
 
         let task_number = task.task_number;
 
-        let dir_path = overwrite_task_dir(module_id, assignment_id, task_number);
-        ensure_dir(&dir_path)
-            .map_err(|e| DbErr::Custom(format!("Failed to create directory: {e}")))?;
+        let file_path =
+            overwrite_task_dir(module_id, assignment_id, task_number).join(&stored_filename);
+        let relative_path = key_for(&file_path);
 
-        let file_path = dir_path.join(&stored_filename);
-        let relative_path = file_path
-            .strip_prefix(storage_root())
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-
-        fs::write(&file_path, bytes)
+        storage()
+            .write(&relative_path, bytes)
+            .await
             .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
 
         let mut model: ActiveModel = inserted.into();
@@ -107,15 +102,13 @@ impl Model {
         model.update(db).await
     }
 
-    /// Loads the file contents from disk based on the path stored in the model.
-    pub fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
-        let full_path = storage_root().join(&self.path);
-        fs::read(full_path)
+    /// Loads the file contents from storage based on the path stored in the model.
+    pub async fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
+        storage().read(&self.path).await
     }
 
-    /// Deletes the file from disk (but not the DB record).
-    pub fn delete_file_only(&self) -> Result<(), std::io::Error> {
-        let full_path = storage_root().join(&self.path);
-        fs::remove_file(full_path)
+    /// Deletes the file from storage (but not the DB record).
+    pub async fn delete_file_only(&self) -> Result<(), std::io::Error> {
+        storage().delete(&self.path).await
     }
 }
@@ -1,23 +1,23 @@
 use crate::models::assignment;
 use crate::models::assignment::Model as AssignmentModel;
+use crate::models::content_blob;
+use crate::models::group;
 use crate::models::user;
+use crate::soft_delete::SoftDelete;
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
-use sea_orm::{ActiveValue::Set, ConnectionTrait, DatabaseConnection, EntityTrait, QueryOrder};
-use std::collections::HashSet;
-use std::fs;
+use sea_orm::{
+    ActiveValue::Set, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, QueryOrder,
+};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use util::execution_config::ExecutionConfig;
-use util::execution_config::GradingPolicy;
-use util::paths::{ensure_parent_dir, storage_root};
+use util::paths::storage_root;
+use util::storage::{key_for, storage};
 
 /// Represents the status of a submission throughout its lifecycle
 #[derive(Debug, Clone, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
-#[sea_orm(
-    rs_type = "String",
-    db_type = "Enum",
-    enum_name = "submission_status_enum"
-)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 pub enum SubmissionStatus {
     /// Waiting for execution/marking
     #[sea_orm(string_value = "queued")]
@@ -75,6 +75,31 @@ impl std::fmt::Display for SubmissionStatus {
     }
 }
 
+/// How far the run → save outputs → mark pipeline got for a submission.
+///
+/// Each step commits on its own, so after a crash the outputs and marks on record are those of
+/// the last step that reached its state here; nothing is ever half replaced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+pub enum SubmissionRunState {
+    /// Never run.
+    #[default]
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    /// Tasks are running; outputs of any earlier run are still in place.
+    #[sea_orm(string_value = "running_tasks")]
+    RunningTasks,
+    /// This run's outputs replaced the old ones; marking has not finished.
+    #[sea_orm(string_value = "outputs_saved")]
+    OutputsSaved,
+    /// Marks from this run's outputs are saved.
+    #[sea_orm(string_value = "marked")]
+    Marked,
+    /// The run stopped early; outputs and marks are those of the last completed step.
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
 /// Represents a user's submission for a specific assignment.
 ///
 /// Each submission is linked to one assignment and one user.
@@ -89,6 +114,8 @@ pub struct Model {
     pub assignment_id: i64,
     /// ID of the user who submitted the assignment.
     pub user_id: i64,
+    /// The group this was submitted for, when the assignment takes group submissions.
+    pub group_id: Option<i64>,
     /// Attempt number
     pub attempt: i64,
     /// The score earned by the user.
@@ -101,16 +128,29 @@ pub struct Model {
     pub file_hash: String,
     /// Relative file path from the storage root.
     pub path: String,
+    /// SHA-256 of the stored file's shared [`content_blob`], if it has one.
+    pub content_hash: Option<String>,
     /// Is this submission a practice submission?
     pub is_practice: bool,
     /// Whether this submission should be ignored for grading/analytics.
     pub ignored: bool,
     /// Current status of the submission in the lifecycle.
     pub status: SubmissionStatus,
+    /// Progress of the latest run through the marking pipeline.
+    pub submission_run_state: SubmissionRunState,
     /// Timestamp when the submission was created.
     pub created_at: DateTime<Utc>,
     /// Timestamp when the submission was last updated.
     pub updated_at: DateTime<Utc>,
+    /// When the submission was soft deleted; `None` while it is live.
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Address the file was uploaded from, when known.
+    pub client_ip: Option<String>,
+    /// `User-Agent` of the uploading client, when sent.
+    pub user_agent: Option<String>,
+    /// How long the upload took, in milliseconds: the request body for a direct upload, or
+    /// from opening to completing a chunked upload.
+    pub upload_duration_ms: Option<i64>,
 }
 
 /// Defines relationships between `assignment_submissions` and other tables.
@@ -136,6 +176,26 @@ pub enum Relation {
 /// Custom behavior for the active model (currently using default behavior).
 impl ActiveModelBehavior for ActiveModel {}
 
+/// How close together two users' submissions from one IP address must be to be flagged.
+pub const SHARED_IP_WINDOW_SECS: i64 = 120;
+
+/// Where and how a submission was uploaded, as captured by the submit endpoint.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientMetadata {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub upload_duration_ms: Option<i64>,
+}
+
+/// Another user's submission uploaded from the same address close in time; see
+/// [`Model::shared_ip_matches`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct SharedIpMatch {
+    pub submission_id: i64,
+    pub user_id: i64,
+    pub seconds_apart: i64,
+}
+
 impl Related<user::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::User.def()
@@ -157,12 +217,12 @@ impl Model {
         storage_root().join(&self.path)
     }
 
-    /// Saves a file to disk and creates or updates its metadata in the database.
+    /// Saves a file to storage and creates or updates its metadata in the database.
     ///
     /// This method:
     /// 1. Creates a temporary DB entry.
     /// 2. Looks up the associated assignment and module.
-    /// 3. Saves the file with a generated name on disk.
+    /// 3. Saves the file with a generated name in storage.
     /// 4. Updates the DB entry with the file path (relative to STORAGE_ROOT).
     pub async fn save_file(
         db: &DatabaseConnection,
@@ -197,6 +257,7 @@ impl Model {
             file_hash: Set(file_hash.to_string()),
             path: Set(String::new()),
             status: Set(SubmissionStatus::Queued),
+            submission_run_state: Set(SubmissionRunState::Pending),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -225,43 +286,102 @@ impl Model {
             inserted.id,
             ext.as_deref(),
         );
-        ensure_parent_dir(&file_path)
-            .map_err(|e| DbErr::Custom(format!("Failed to create directory: {e}")))?;
-
-        fs::write(&file_path, bytes)
-            .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
-
-        // Compute relative path from STORAGE_ROOT and persist
-        let relative_path = file_path
-            .strip_prefix(storage_root())
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
+        // Path relative to STORAGE_ROOT, which is also the storage key. Resubmitted
+        // archives share one stored copy.
+        let relative_path = key_for(&file_path);
+        let blob = content_blob::Model::store_at(db, &relative_path, bytes).await?;
 
         // Step 5: Update DB with path
         let mut model: ActiveModel = inserted.into();
         model.path = Set(relative_path);
+        model.content_hash = Set(Some(blob.hash));
         model.updated_at = Set(Utc::now());
 
         model.update(db).await
     }
 
-    /// Loads the contents of the stored file from disk.
+    /// Loads the contents of the stored file from storage.
     ///
     /// # Returns
     /// - `Ok(Vec<u8>)`: The file contents as bytes.
     /// - `Err(std::io::Error)`: If reading the file fails.
-    pub fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
-        fs::read(self.full_path())
+    pub async fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
+        storage().read(&self.path).await
+    }
+
+    /// A local copy of the stored file, for tools that need a real path. With local storage
+    /// this is [`Self::full_path`]; with S3 the file is downloaded into the cache first.
+    pub async fn local_file(&self) -> Result<PathBuf, std::io::Error> {
+        storage().local_path(&self.path).await
     }
 
-    /// Deletes the file from disk without removing the database record.
+    /// Deletes the file from storage without removing the database record.
     ///
     /// # Returns
     /// - `Ok(())`: If the file was successfully deleted.
     /// - `Err(std::io::Error)`: If the file deletion failed.
-    pub fn delete_file_only(&self) -> Result<(), std::io::Error> {
-        fs::remove_file(self.full_path())
+    pub async fn delete_file_only(&self) -> Result<(), std::io::Error> {
+        storage().delete(&self.path).await
+    }
+
+    /// Drops this submission's reference to its content blob. Call when deleting the record.
+    pub async fn release_content(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        match &self.content_hash {
+            Some(hash) => content_blob::Model::release(db, hash).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Soft deletes the submission. Its file stays in storage until the retention sweep
+    /// purges it.
+    pub async fn soft_delete(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        submission_id: i64,
+    ) -> Result<(), DbErr> {
+        let res = Entity::mark_deleted()
+            .filter(Column::Id.eq(submission_id))
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "No submission {submission_id} found for assignment {assignment_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Brings back a soft-deleted submission.
+    pub async fn restore(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        submission_id: i64,
+    ) -> Result<(), DbErr> {
+        let res = Entity::mark_restored()
+            .filter(Column::Id.eq(submission_id))
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "No deleted submission {submission_id} for assignment {assignment_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Removes the submission row and its stored file for good. Outputs go with the cascade.
+    pub async fn purge(self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        if let Err(e) = self.delete_file_only().await {
+            eprintln!(
+                "Warning: Failed to remove file for submission {}: {}",
+                self.id, e
+            );
+        }
+        self.release_content(db).await?;
+        Entity::delete_by_id(self.id).exec(db).await?;
+        Ok(())
     }
 
     /// Find all submission IDs for a given assignment
@@ -269,8 +389,8 @@ impl Model {
         assignment_id: i64,
         db: &DatabaseConnection,
     ) -> Result<Vec<i64>, DbErr> {
-        let submissions = Entity::find()
-            .filter(Column::AssignmentId.eq(assignment_id as i32))
+        let submissions = Entity::find_active()
+            .filter(Column::AssignmentId.eq(assignment_id))
             .all(db)
             .await?;
 
@@ -281,7 +401,7 @@ impl Model {
         db: &DatabaseConnection,
         assignment_id: i64,
     ) -> Result<Vec<Self>, DbErr> {
-        let all = Entity::find()
+        let all = Entity::find_active()
             .filter(Column::AssignmentId.eq(assignment_id))
             .order_by_asc(Column::UserId)
             .order_by_desc(Column::Attempt)
@@ -299,6 +419,29 @@ impl Model {
         Ok(latest)
     }
 
+    /// Each user's latest submission that isn't ignored, passing over practice attempts unless
+    /// `include_practice` is set.
+    pub async fn get_latest_counted_submissions_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        include_practice: bool,
+    ) -> Result<Vec<Self>, DbErr> {
+        let mut query = Entity::find_active()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::Ignored.eq(false));
+        if !include_practice {
+            query = query.filter(Column::IsPractice.eq(false));
+        }
+        let all = query
+            .order_by_asc(Column::UserId)
+            .order_by_desc(Column::Attempt)
+            .all(db)
+            .await?;
+
+        let mut seen = HashSet::new();
+        Ok(all.into_iter().filter(|s| seen.insert(s.user_id)).collect())
+    }
+
     /// Set the `ignored` flag for a submission by id and return the updated model.
     pub async fn set_ignored(
         db: &DatabaseConnection,
@@ -317,15 +460,116 @@ impl Model {
         am.update(db).await
     }
 
+    /// Matches the submissions that count as `user_id`'s on the assignment: their own, plus
+    /// those made for their group when the assignment takes group submissions.
+    pub async fn owner_condition(
+        db: &DatabaseConnection,
+        assignment: &AssignmentModel,
+        user_id: i64,
+    ) -> Result<Condition, DbErr> {
+        let mut cond = Condition::any().add(Column::UserId.eq(user_id));
+        if assignment.group_submissions()
+            && let Some(g) = group::Model::for_user(db, assignment.id, user_id).await?
+        {
+            cond = cond.add(Column::GroupId.eq(g.id));
+        }
+        Ok(cond)
+    }
+
+    /// Links a submission to the group it was made for.
+    pub async fn set_group(
+        db: &DatabaseConnection,
+        submission_id: i64,
+        group_id: Option<i64>,
+    ) -> Result<Self, DbErr> {
+        let existing = Entity::find_by_id(submission_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::Custom(format!("Submission {submission_id} not found")))?;
+
+        let mut am: ActiveModel = existing.into();
+        am.group_id = Set(group_id);
+        am.update(db).await
+    }
+
+    /// Records where and how a submission was uploaded.
+    pub async fn set_client_metadata(
+        db: &DatabaseConnection,
+        submission_id: i64,
+        client: &ClientMetadata,
+    ) -> Result<Self, DbErr> {
+        let existing = Entity::find_by_id(submission_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::Custom(format!("Submission {submission_id} not found")))?;
+
+        let mut am: ActiveModel = existing.into();
+        am.client_ip = Set(client.ip.clone());
+        am.user_agent = Set(client.user_agent.clone());
+        am.upload_duration_ms = Set(client.upload_duration_ms);
+        am.update(db).await
+    }
+
+    /// Submissions to the assignment made from the same address as another user's within
+    /// `window` of it, keyed by submission id. Members of the same group are not matched with
+    /// each other; they are expected to share a machine.
+    pub async fn shared_ip_matches(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        window: chrono::Duration,
+    ) -> Result<HashMap<i64, Vec<SharedIpMatch>>, DbErr> {
+        let submissions = Entity::find_active()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::ClientIp.is_not_null())
+            .order_by_asc(Column::CreatedAt)
+            .all(db)
+            .await?;
+
+        let mut by_ip: HashMap<&str, Vec<&Self>> = HashMap::new();
+        for s in &submissions {
+            if let Some(ip) = s.client_ip.as_deref() {
+                by_ip.entry(ip).or_default().push(s);
+            }
+        }
+
+        let mut matches: HashMap<i64, Vec<SharedIpMatch>> = HashMap::new();
+        for group in by_ip.values() {
+            for (i, a) in group.iter().enumerate() {
+                for b in &group[i + 1..] {
+                    let apart = b.created_at - a.created_at;
+                    if apart > window {
+                        break;
+                    }
+                    let same_group = a.group_id.is_some() && a.group_id == b.group_id;
+                    if a.user_id == b.user_id || same_group {
+                        continue;
+                    }
+                    let seconds_apart = apart.num_seconds();
+                    matches.entry(a.id).or_default().push(SharedIpMatch {
+                        submission_id: b.id,
+                        user_id: b.user_id,
+                        seconds_apart,
+                    });
+                    matches.entry(b.id).or_default().push(SharedIpMatch {
+                        submission_id: a.id,
+                        user_id: a.user_id,
+                        seconds_apart,
+                    });
+                }
+            }
+        }
+        Ok(matches)
+    }
+
     pub async fn get_best_for_user(
         db: &DatabaseConnection,
         assignment: &AssignmentModel,
         user_id: i64,
     ) -> Result<Option<Self>, DbErr> {
-        // fetch all non-ignored, non-practice submissions for this user
-        let mut subs = Entity::find()
+        // fetch all non-ignored, non-practice submissions for this user (or their group)
+        let mut subs = Entity::find_active()
             .filter(Column::AssignmentId.eq(assignment.id))
-            .filter(Column::UserId.eq(user_id))
+            .filter(Self::owner_condition(db, assignment, user_id).await?)
             .filter(Column::Ignored.eq(false))
             .filter(Column::IsPractice.eq(false))
             .all(db)
@@ -339,29 +583,23 @@ impl Model {
             .config()
             .unwrap_or_else(ExecutionConfig::default_config);
 
-        match cfg.marking.grading_policy {
-            GradingPolicy::Best => {
-                subs.sort_by(|a, b| {
-                    let score_a = a.earned / a.total;
-                    let score_b = b.earned / b.total;
-                    score_b
-                        .partial_cmp(&score_a)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-                Ok(subs.into_iter().next())
-            }
-            GradingPolicy::Last => {
-                subs.sort_by_key(|s| std::cmp::Reverse(s.created_at));
-                Ok(subs.into_iter().next())
-            }
-        }
+        // newest first, as the grading policy expects
+        subs.sort_by_key(|s| std::cmp::Reverse((s.created_at, s.attempt)));
+        let marks: Vec<f64> = subs
+            .iter()
+            .map(|s| crate::grade::percentage(s.earned, s.total))
+            .collect();
+        Ok(cfg
+            .marking
+            .policy_mark(&marks)
+            .map(|(index, _)| subs.swap_remove(index)))
     }
 
     pub async fn get_selected_submissions_for_assignment(
         db: &DatabaseConnection,
         assignment: &AssignmentModel,
     ) -> Result<Vec<Self>, DbErr> {
-        let all_for_assignment = Entity::find()
+        let all_for_assignment = Entity::find_active()
             .filter(Column::AssignmentId.eq(assignment.id))
             .all(db)
             .await?;
@@ -371,9 +609,13 @@ impl Model {
             user_ids.insert(s.user_id);
         }
 
+        // Group members share submissions, so the same one can be chosen for several users
+        let mut seen = HashSet::<i64>::new();
         let mut chosen = Vec::with_capacity(user_ids.len());
         for uid in user_ids {
-            if let Ok(Some(s)) = Model::get_best_for_user(db, assignment, uid).await {
+            if let Ok(Some(s)) = Model::get_best_for_user(db, assignment, uid).await
+                && seen.insert(s.id)
+            {
                 chosen.push(s);
             }
         }
@@ -399,6 +641,24 @@ impl Model {
         active_model.update(db).await
     }
 
+    /// Records how far the marking pipeline got, on `db` or inside a caller's transaction.
+    pub async fn set_run_state<C>(
+        db: &C,
+        submission_id: i64,
+        state: SubmissionRunState,
+    ) -> Result<(), DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        Entity::update_many()
+            .col_expr(Column::SubmissionRunState, Expr::value(state))
+            .col_expr(Column::UpdatedAt, Expr::value(Utc::now()))
+            .filter(Column::Id.eq(submission_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
     /// Set the status to running
     pub async fn set_running(db: &DatabaseConnection, submission_id: i64) -> Result<Self, DbErr> {
         Self::update_status(db, submission_id, SubmissionStatus::Running).await
@@ -584,11 +844,13 @@ mod tests {
         assert!(full_path.exists());
 
         // Load content and verify
-        let loaded = file.load_file().expect("Failed to load file");
+        let loaded = file.load_file().await.expect("Failed to load file");
         assert_eq!(loaded, content);
 
         // Delete file
-        file.delete_file_only().expect("Failed to delete file");
+        file.delete_file_only()
+            .await
+            .expect("Failed to delete file");
         assert!(!full_path.exists());
     }
 
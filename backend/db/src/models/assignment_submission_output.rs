@@ -1,13 +1,13 @@
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
-use sea_orm::{ActiveValue::Set, DatabaseConnection, EntityTrait};
-use std::fs;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
 use std::io;
 use std::io::ErrorKind;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::models::assignment_submission;
-use util::paths::{ensure_dir, storage_root, submission_output_dir};
+use util::paths::{storage_root, submission_output_dir};
+use util::storage::{key_for, storage};
 
 /// Represents the output generated by a student's submission for an assignment task.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -18,6 +18,15 @@ pub struct Model {
     pub task_id: i64,
     pub submission_id: i64,
     pub path: String,
+    /// Size of the output before it was truncated for storage (`None` for rows saved before
+    /// sizes were recorded).
+    pub full_size_bytes: Option<i64>,
+    /// True if the stored file holds only part of the output.
+    pub truncated: bool,
+    /// Resource usage reported by code_manager for the task's command (`None` if unknown).
+    pub wall_time_ms: Option<i64>,
+    pub cpu_time_ms: Option<i64>,
+    pub max_rss_bytes: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -41,6 +50,20 @@ pub enum Relation {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// One task's output from a run, to be stored by [`Model::replace_for_submission`].
+#[derive(Debug, Clone)]
+pub struct NewOutput {
+    pub task_id: i64,
+    /// Only its extension is kept; the stored file is named after the new row's id.
+    pub filename: String,
+    pub bytes: Vec<u8>,
+    /// Size of the output before truncation.
+    pub full_size: u64,
+    pub wall_time_ms: Option<i64>,
+    pub cpu_time_ms: Option<i64>,
+    pub max_rss_bytes: Option<i64>,
+}
+
 impl Model {
     /// Absolute disk path from the stored relative `path`.
     pub fn full_path(&self) -> PathBuf {
@@ -52,19 +75,16 @@ impl Model {
         db: &DatabaseConnection,
         submission_id: i64,
     ) -> Result<(), DbErr> {
-        use sea_orm::QueryFilter;
-
         let outputs = Entity::find()
             .filter(Column::SubmissionId.eq(submission_id))
             .all(db)
             .await?;
 
         for output in outputs {
-            let path = output.full_path();
-            if path.exists() {
-                if let Err(e) = fs::remove_file(&path) {
-                    eprintln!("Failed to delete file {path:?}: {e}");
-                }
+            if let Err(e) = storage().delete(&output.path).await
+                && e.kind() != ErrorKind::NotFound
+            {
+                eprintln!("Failed to delete file {:?}: {e}", output.path);
             }
             let am: ActiveModel = output.into();
             am.delete(db).await?;
@@ -79,6 +99,29 @@ impl Model {
         submission_id: i64,
         filename: &str,
         bytes: &[u8],
+    ) -> Result<Self, DbErr> {
+        Self::save_file_with_full_size(
+            db,
+            task_id,
+            submission_id,
+            filename,
+            bytes,
+            bytes.len() as u64,
+        )
+        .await
+    }
+
+    /// Like [`Model::save_file`], for output that was cut down before storing.
+    ///
+    /// `full_size` is the size of the original output; the row is flagged as truncated if
+    /// it is larger than `bytes`.
+    pub async fn save_file_with_full_size(
+        db: &DatabaseConnection,
+        task_id: i64,
+        submission_id: i64,
+        filename: &str,
+        bytes: &[u8],
+        full_size: u64,
     ) -> Result<Self, DbErr> {
         let now = Utc::now();
 
@@ -86,6 +129,8 @@ impl Model {
             task_id: Set(task_id),
             submission_id: Set(submission_id),
             path: Set(String::new()),
+            full_size_bytes: Set(Some(i64::try_from(full_size).unwrap_or(i64::MAX))),
+            truncated: Set(full_size > bytes.len() as u64),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -119,19 +164,12 @@ impl Model {
             submission.user_id,
             submission.attempt,
         );
-        ensure_dir(&dir_path)
-            .map_err(|e| DbErr::Custom(format!("Failed to create directory: {e}")))?;
-
-        let file_path = dir_path.join(&stored_filename);
-        fs::write(&file_path, bytes)
+        let relative_path = key_for(&dir_path.join(&stored_filename));
+        storage()
+            .write(&relative_path, bytes)
+            .await
             .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
 
-        let relative_path = file_path
-            .strip_prefix(storage_root())
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-
         let mut model: ActiveModel = inserted.into();
         model.path = Set(relative_path);
         model.updated_at = Set(Utc::now());
@@ -139,6 +177,136 @@ impl Model {
         model.update(db).await
     }
 
+    /// Swaps a submission's outputs for `outputs` in one transaction.
+    ///
+    /// Either every old row is replaced or, on any error, the old rows stay as they were. New
+    /// files get fresh names (the new row ids), so the old files are only deleted once the
+    /// transaction has committed, and new files are deleted again if it rolls back.
+    pub async fn replace_for_submission(
+        db: &DatabaseConnection,
+        submission_id: i64,
+        outputs: Vec<NewOutput>,
+    ) -> Result<Vec<Self>, DbErr> {
+        let submission = assignment_submission::Entity::find_by_id(submission_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::Custom("Submission not found".to_string()))?;
+        let assignment = super::assignment::Entity::find_by_id(submission.assignment_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::Custom("Assignment not found".to_string()))?;
+        let dir_path = submission_output_dir(
+            assignment.module_id,
+            assignment.id,
+            submission.user_id,
+            submission.attempt,
+        );
+
+        let txn = db.begin().await?;
+        let old = Entity::find()
+            .filter(Column::SubmissionId.eq(submission_id))
+            .all(&txn)
+            .await?;
+        Entity::delete_many()
+            .filter(Column::SubmissionId.eq(submission_id))
+            .exec(&txn)
+            .await?;
+
+        let mut written = Vec::with_capacity(outputs.len());
+        let mut saved = Vec::with_capacity(outputs.len());
+        let mut result = Ok(());
+        for output in outputs {
+            let now = Utc::now();
+            let inserted = match (ActiveModel {
+                task_id: Set(output.task_id),
+                submission_id: Set(submission_id),
+                path: Set(String::new()),
+                full_size_bytes: Set(Some(i64::try_from(output.full_size).unwrap_or(i64::MAX))),
+                truncated: Set(output.full_size > output.bytes.len() as u64),
+                wall_time_ms: Set(output.wall_time_ms),
+                cpu_time_ms: Set(output.cpu_time_ms),
+                max_rss_bytes: Set(output.max_rss_bytes),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            })
+            .insert(&txn)
+            .await
+            {
+                Ok(inserted) => inserted,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+
+            let stored_filename = match Path::new(&output.filename).extension() {
+                Some(ext) => format!("{}.{}", inserted.id, ext.to_string_lossy()),
+                None => inserted.id.to_string(),
+            };
+            let relative_path = key_for(&dir_path.join(&stored_filename));
+            if let Err(e) = storage().write(&relative_path, &output.bytes).await {
+                result = Err(DbErr::Custom(format!("Failed to write file: {e}")));
+                break;
+            }
+            written.push(relative_path.clone());
+
+            let mut model: ActiveModel = inserted.into();
+            model.path = Set(relative_path);
+            match model.update(&txn).await {
+                Ok(model) => saved.push(model),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        let outcome = match result {
+            Ok(()) => txn.commit().await,
+            Err(e) => {
+                let _ = txn.rollback().await;
+                Err(e)
+            }
+        };
+        if let Err(e) = outcome {
+            for path in &written {
+                let _ = storage().delete(path).await;
+            }
+            return Err(e);
+        }
+
+        for output in old {
+            if let Err(e) = storage().delete(&output.path).await
+                && e.kind() != ErrorKind::NotFound
+            {
+                eprintln!("Failed to delete file {:?}: {e}", output.path);
+            }
+        }
+        Ok(saved)
+    }
+
+    /// Records the resource usage measured while producing this output.
+    pub async fn set_metrics(
+        db: &DatabaseConnection,
+        id: i64,
+        wall_time_ms: i64,
+        cpu_time_ms: Option<i64>,
+        max_rss_bytes: Option<i64>,
+    ) -> Result<Self, DbErr> {
+        let output = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("Submission output {id} not found")))?;
+
+        let mut model: ActiveModel = output.into();
+        model.wall_time_ms = Set(Some(wall_time_ms));
+        model.cpu_time_ms = Set(cpu_time_ms);
+        model.max_rss_bytes = Set(max_rss_bytes);
+        model.updated_at = Set(Utc::now());
+        model.update(db).await
+    }
+
     /// Read all output files for a submission id, returning (task_id, content).
     pub async fn get_output(
         db: &DatabaseConnection,
@@ -159,7 +327,8 @@ impl Model {
             submission.attempt,
         );
 
-        if !base_dir_path.exists() {
+        let keys = storage().list(&key_for(&base_dir_path)).await?;
+        if keys.is_empty() {
             return Err(io::Error::new(
                 ErrorKind::NotFound,
                 format!("Submission output directory {base_dir_path:?} does not exist"),
@@ -167,25 +336,109 @@ impl Model {
         }
 
         let mut results = Vec::new();
-        for entry in fs::read_dir(&base_dir_path)? {
-            let entry = entry?;
-            if entry.file_type()?.is_file() {
-                let file_path = entry.path();
-                if let Some(stem) = file_path.file_stem().and_then(|n| n.to_str()) {
-                    if let Ok(output_id) = stem.parse::<i64>() {
-                        if let Some(output) = Entity::find_by_id(output_id)
-                            .one(db)
-                            .await
-                            .map_err(|e| io::Error::other(format!("DB error: {e}")))?
-                        {
-                            let content = fs::read_to_string(&file_path)?;
-                            results.push((output.task_id, content));
-                        }
-                    }
-                }
+        for key in keys {
+            if let Some(stem) = Path::new(&key).file_stem().and_then(|n| n.to_str())
+                && let Ok(output_id) = stem.parse::<i64>()
+                && let Some(output) = Entity::find_by_id(output_id)
+                    .one(db)
+                    .await
+                    .map_err(|e| io::Error::other(format!("DB error: {e}")))?
+            {
+                let bytes = storage().read(&key).await?;
+                let content = String::from_utf8(bytes)
+                    .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+                results.push((output.task_id, content));
             }
         }
 
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Entity, Model, NewOutput};
+    use crate::models::{
+        assignment::{AssignmentType, Model as AssignmentModel},
+        assignment_submission::Model as SubmissionModel,
+        assignment_task::{Model as TaskModel, TaskType},
+        module::Model as ModuleModel,
+        user::Model as UserModel,
+    };
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+    use sea_orm::EntityTrait;
+    use util::test_helpers::setup_test_storage_root;
+
+    fn output(task_id: i64, text: &str) -> NewOutput {
+        NewOutput {
+            task_id,
+            filename: "task.txt".to_string(),
+            bytes: text.as_bytes().to_vec(),
+            full_size: text.len() as u64,
+            wall_time_ms: Some(12),
+            cpu_time_ms: None,
+            max_rss_bytes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn replace_for_submission_swaps_rows_and_files() {
+        let _tmp = setup_test_storage_root();
+        let db = setup_test_db().await;
+
+        let user = UserModel::create(&db, "u1", "u1@example.com", "pw", false)
+            .await
+            .unwrap();
+        let module = ModuleModel::create(&db, "COS101", 2025, None, 16)
+            .await
+            .unwrap();
+        let assignment = AssignmentModel::create(
+            &db,
+            module.id,
+            "A1",
+            None,
+            AssignmentType::Practical,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let task = TaskModel::create(&db, assignment.id, 1, "Task 1", "make t1", TaskType::Normal)
+            .await
+            .unwrap();
+        let submission = SubmissionModel::save_file(
+            &db,
+            assignment.id,
+            user.id,
+            1,
+            0.0,
+            10.0,
+            false,
+            "s.zip",
+            "hash",
+            b"PK",
+        )
+        .await
+        .unwrap();
+
+        let old = Model::save_file(&db, task.id, submission.id, "task.txt", b"old")
+            .await
+            .unwrap();
+
+        let saved =
+            Model::replace_for_submission(&db, submission.id, vec![output(task.id, "new")])
+                .await
+                .unwrap();
+
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].wall_time_ms, Some(12));
+        assert!(!saved[0].truncated);
+        assert_eq!(std::fs::read(saved[0].full_path()).unwrap(), b"new");
+        assert!(!old.full_path().exists());
+
+        let rows = Entity::find().all(&db).await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].id, saved[0].id);
+    }
+}
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 #[derive(Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
-#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "task_type_enum")]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 #[serde(rename_all = "lowercase")]
 pub enum TaskType {
     #[sea_orm(string_value = "normal")]
@@ -36,6 +36,8 @@ pub struct Model {
     pub name: String,
     pub command: String,
     pub task_type: TaskType,
+    /// Newline-separated globs (relative to `/output`) of files kept from each run of the task.
+    pub artifact_patterns: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -126,6 +128,45 @@ impl Model {
         Self::edit(db, id, Some(new_name), Some(new_command), None).await
     }
 
+    /// Glob patterns of `/output` files to keep from each run; empty if none were set.
+    pub fn artifact_patterns(&self) -> Vec<String> {
+        self.artifact_patterns
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Stored form of `patterns`: one per line, `None` when there are none.
+    pub fn join_artifact_patterns(patterns: &[String]) -> Option<String> {
+        let joined = patterns
+            .iter()
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        (!joined.is_empty()).then_some(joined)
+    }
+
+    /// Replaces the task's artifact patterns; an empty list clears them.
+    pub async fn set_artifact_patterns(
+        db: &DatabaseConnection,
+        id: i64,
+        patterns: &[String],
+    ) -> Result<Self, DbErr> {
+        let Some(task) = Self::get_by_id(db, id).await? else {
+            return Err(DbErr::RecordNotFound("Task not found".into()));
+        };
+
+        let mut active = task.into_active_model();
+        active.artifact_patterns = Set(Self::join_artifact_patterns(patterns));
+        active.updated_at = Set(Utc::now());
+        active.update(db).await
+    }
+
     /// Delete a task by ID.
     pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<(), DbErr> {
         if let Some(task) = Self::get_by_id(db, id).await? {
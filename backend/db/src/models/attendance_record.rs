@@ -1,6 +1,9 @@
+use super::attendance_session::ReportedLocation;
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
-use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, QueryFilter, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveModelTrait, ColumnTrait, ConnectionTrait, QueryFilter, QueryOrder, Set};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, serde::Serialize)]
 #[sea_orm(table_name = "attendance_records")]
@@ -51,14 +54,54 @@ impl Related<super::user::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+/// A student's attendance across a module's sessions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize)]
+pub struct AttendanceSummary {
+    pub sessions_total: usize,
+    pub sessions_attended: usize,
+    /// `sessions_attended` as a percentage of `sessions_total`; 0 when there are no sessions.
+    pub percentage: f64,
+    /// Sessions attended in a row, counting back from the latest one.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+}
+
+impl AttendanceSummary {
+    /// Summarises attendance over `session_ids`, oldest first.
+    pub fn from_sessions(session_ids: &[i64], attended: &HashSet<i64>) -> Self {
+        let mut summary = Self {
+            sessions_total: session_ids.len(),
+            ..Default::default()
+        };
+        let mut run = 0;
+        for id in session_ids {
+            if attended.contains(id) {
+                summary.sessions_attended += 1;
+                run += 1;
+                summary.longest_streak = summary.longest_streak.max(run);
+            } else {
+                run = 0;
+            }
+        }
+        summary.current_streak = run;
+        if summary.sessions_total > 0 {
+            summary.percentage =
+                summary.sessions_attended as f64 * 100.0 / summary.sessions_total as f64;
+        }
+        summary
+    }
+}
+
 impl Model {
-    /// Mark attendance after verifying code/IP and that the session is active.
+    /// Mark attendance after verifying code/IP/location and that the session is active.
+    #[allow(clippy::too_many_arguments)]
     pub async fn mark<C>(
         db: &C,
         session: &super::attendance_session::Model,
         user_id: i64,
         submitted_code: &str,
         client_ip: Option<&str>,
+        location: Option<ReportedLocation>,
         now: DateTime<Utc>,
         window_tolerance: i64,
     ) -> Result<Self, DbErr>
@@ -73,6 +116,9 @@ impl Model {
         if !session.ip_permitted(client_ip) {
             return Err(DbErr::Custom("IP not permitted for this session".into()));
         }
+        session
+            .location_permitted(location)
+            .map_err(|e| DbErr::Custom(e.into()))?;
 
         // Verify rotating code with tolerance
         let w = session.window(now);
@@ -111,6 +157,82 @@ impl Model {
         };
         am.insert(db).await
     }
+
+    /// Records attendance taken outside the system (a paper register, another tool) at
+    /// `taken_at`. Returns `false` when the user already has a record for the session.
+    pub async fn import<C>(
+        db: &C,
+        session: &super::attendance_session::Model,
+        user_id: i64,
+        taken_at: DateTime<Utc>,
+    ) -> Result<bool, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let am = ActiveModel {
+            session_id: Set(session.id),
+            user_id: Set(user_id),
+            taken_at: Set(taken_at),
+            ip_address: Set(None),
+            token_window: Set(session.window(taken_at)),
+        };
+        let inserted = Entity::insert(am)
+            .on_conflict(
+                OnConflict::columns([Column::SessionId, Column::UserId])
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+        Ok(inserted > 0)
+    }
+
+    /// Attendance summaries of `user_ids` across every session of the module, in the order the
+    /// sessions were created. Every user gets an entry, attended or not.
+    pub async fn summaries_for<C>(
+        db: &C,
+        module_id: i64,
+        user_ids: &[i64],
+    ) -> Result<HashMap<i64, AttendanceSummary>, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        use super::attendance_session::{Column as SessionCol, Entity as SessionEntity};
+
+        let session_ids: Vec<i64> = SessionEntity::find()
+            .filter(SessionCol::ModuleId.eq(module_id))
+            .order_by_asc(SessionCol::CreatedAt)
+            .order_by_asc(SessionCol::Id)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|s| s.id)
+            .collect();
+
+        let mut attended: HashMap<i64, HashSet<i64>> = HashMap::new();
+        if !session_ids.is_empty() && !user_ids.is_empty() {
+            let records = Entity::find()
+                .filter(Column::SessionId.is_in(session_ids.iter().copied()))
+                .filter(Column::UserId.is_in(user_ids.iter().copied()))
+                .all(db)
+                .await?;
+            for r in records {
+                attended.entry(r.user_id).or_default().insert(r.session_id);
+            }
+        }
+
+        let none = HashSet::new();
+        Ok(user_ids
+            .iter()
+            .map(|id| {
+                let sessions = attended.get(id).unwrap_or(&none);
+                (
+                    *id,
+                    AttendanceSummary::from_sessions(&session_ids, sessions),
+                )
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -155,13 +277,104 @@ mod tests {
         let code = sess.current_code(now);
 
         // use real student.id — not 42
-        let rec = Model::mark(&db, &sess, student.id, &code, Some("203.0.113.5"), now, 1)
-            .await
-            .unwrap();
+        let rec = Model::mark(
+            &db,
+            &sess,
+            student.id,
+            &code,
+            Some("203.0.113.5"),
+            None,
+            now,
+            1,
+        )
+        .await
+        .unwrap();
         assert_eq!(rec.session_id, sess.id);
         assert_eq!(rec.user_id, student.id);
 
-        let dup = Model::mark(&db, &sess, student.id, &code, Some("203.0.113.5"), now, 1).await;
+        let dup = Model::mark(
+            &db,
+            &sess,
+            student.id,
+            &code,
+            Some("203.0.113.5"),
+            None,
+            now,
+            1,
+        )
+        .await;
         assert!(dup.is_err());
     }
+
+    #[test]
+    fn summary_counts_percentage_and_streaks() {
+        let sessions = [1, 2, 3, 4, 5, 6];
+        let attended: HashSet<i64> = [1, 2, 3, 5, 6].into_iter().collect();
+        let summary = AttendanceSummary::from_sessions(&sessions, &attended);
+        assert_eq!(summary.sessions_total, 6);
+        assert_eq!(summary.sessions_attended, 5);
+        assert!((summary.percentage - 83.333).abs() < 0.01);
+        assert_eq!(summary.longest_streak, 3);
+        assert_eq!(summary.current_streak, 2);
+
+        let missed_last = AttendanceSummary::from_sessions(&[1, 2], &[1].into_iter().collect());
+        assert_eq!(missed_last.current_streak, 0);
+        assert_eq!(
+            AttendanceSummary::from_sessions(&[], &HashSet::new()),
+            AttendanceSummary::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn imported_records_count_towards_summaries() {
+        let db = setup_test_db().await;
+        let lecturer = user::Model::create(&db, "lect1", "lect1@test.com", "pw", false)
+            .await
+            .unwrap();
+        let student = user::Model::create(&db, "stud1", "stud1@test.com", "pw", false)
+            .await
+            .unwrap();
+        let m = module::Model::create(&db, "COS101", 2025, Some("Test Module"), 16)
+            .await
+            .unwrap();
+        let mut sessions = Vec::new();
+        for title in ["Lec 1", "Lec 2"] {
+            sessions.push(
+                attendance_session::Model::create(
+                    &db,
+                    m.id,
+                    lecturer.id,
+                    title,
+                    false,
+                    30,
+                    false,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap(),
+            );
+        }
+
+        let at = Utc.with_ymd_and_hms(2025, 9, 8, 10, 0, 0).unwrap();
+        assert!(
+            Model::import(&db, &sessions[1], student.id, at)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !Model::import(&db, &sessions[1], student.id, at)
+                .await
+                .unwrap()
+        );
+
+        let summaries = Model::summaries_for(&db, m.id, &[student.id, lecturer.id])
+            .await
+            .unwrap();
+        let s = summaries[&student.id];
+        assert_eq!((s.sessions_total, s.sessions_attended), (2, 1));
+        assert_eq!(s.current_streak, 1);
+        assert_eq!(summaries[&lecturer.id].sessions_attended, 0);
+    }
 }
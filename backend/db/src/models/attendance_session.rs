@@ -18,6 +18,44 @@ use std::net::IpAddr;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Mean Earth radius used for great-circle distances.
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Most of a device's reported GPS inaccuracy that is given the benefit of the doubt.
+pub const MAX_ACCURACY_SLACK_M: f64 = 100.0;
+
+/// A point on the Earth's surface, in degrees.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GeoPoint {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint {
+    /// Whether the coordinates are real latitude/longitude values.
+    pub fn is_valid(&self) -> bool {
+        (-90.0..=90.0).contains(&self.latitude) && (-180.0..=180.0).contains(&self.longitude)
+    }
+
+    /// Great-circle (haversine) distance to `other`, in metres.
+    pub fn distance_m(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.latitude.to_radians(), other.latitude.to_radians());
+        let dlat = lat2 - lat1;
+        let dlng = (other.longitude - self.longitude).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlng / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+    }
+}
+
+/// Where a student's device says it is when marking attendance.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Deserialize)]
+pub struct ReportedLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// The device's accuracy estimate, in metres.
+    pub accuracy_m: Option<f64>,
+}
+
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, serde::Serialize)]
 #[sea_orm(table_name = "attendance_sessions")]
 pub struct Model {
@@ -32,6 +70,10 @@ pub struct Model {
     pub restrict_by_ip: bool,
     pub allowed_ip_cidr: Option<String>,
     pub created_from_ip: Option<String>,
+    /// Venue the session is geofenced to, if any; see [`Model::location_permitted`].
+    pub geo_latitude: Option<f64>,
+    pub geo_longitude: Option<f64>,
+    pub geo_radius_m: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -110,6 +152,12 @@ impl Model {
         self.code_for_window(self.window(now))
     }
 
+    /// When the code shown at `now` rotates out (it is still accepted for one window after).
+    pub fn code_valid_until(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let r = i64::from(self.rotation_seconds.max(1));
+        DateTime::from_timestamp((self.window(now) + 1) * r, 0).unwrap_or(now)
+    }
+
     pub async fn create<C>(
         db: &C,
         module_id: i64,
@@ -173,6 +221,70 @@ impl Model {
         false
     }
 
+    /// The venue and radius (metres) the session is geofenced to, if it is.
+    pub fn geofence(&self) -> Option<(GeoPoint, i32)> {
+        match (self.geo_latitude, self.geo_longitude, self.geo_radius_m) {
+            (Some(latitude), Some(longitude), Some(radius)) => Some((
+                GeoPoint {
+                    latitude,
+                    longitude,
+                },
+                radius,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Checks a student's reported location against the session's geofence. Without a
+    /// geofence anything goes; with one, a location is required and must be within the radius,
+    /// widened by the device's reported accuracy up to [`MAX_ACCURACY_SLACK_M`].
+    pub fn location_permitted(
+        &self,
+        location: Option<ReportedLocation>,
+    ) -> Result<(), &'static str> {
+        let Some((venue, radius)) = self.geofence() else {
+            return Ok(());
+        };
+        let Some(location) = location else {
+            return Err("Location is required for this session");
+        };
+        let point = GeoPoint {
+            latitude: location.latitude,
+            longitude: location.longitude,
+        };
+        if !point.is_valid() {
+            return Err("Location is required for this session");
+        }
+        let slack = location
+            .accuracy_m
+            .filter(|a| a.is_finite() && *a > 0.0)
+            .map_or(0.0, |a| a.min(MAX_ACCURACY_SLACK_M));
+        if venue.distance_m(&point) <= f64::from(radius) + slack {
+            Ok(())
+        } else {
+            Err("Location is too far from the session venue")
+        }
+    }
+
+    /// Sets or (with `None`) removes the session's geofence.
+    pub async fn set_geofence<C>(
+        db: &C,
+        id: i64,
+        geofence: Option<(GeoPoint, i32)>,
+    ) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        let am = ActiveModel {
+            id: Set(id),
+            geo_latitude: Set(geofence.map(|(p, _)| p.latitude)),
+            geo_longitude: Set(geofence.map(|(p, _)| p.longitude)),
+            geo_radius_m: Set(geofence.map(|(_, r)| r)),
+            ..Default::default()
+        };
+        am.update(db).await
+    }
+
     // --- utilities unchanged (student counts) ---
     pub async fn student_count_for_module<C>(db: &C, module_id: i64) -> Result<i64, DbErr>
     where
@@ -291,5 +403,67 @@ mod tests {
         let t1 = Utc.with_ymd_and_hms(2025, 9, 8, 10, 0, 14).unwrap(); // window N
         let t2 = Utc.with_ymd_and_hms(2025, 9, 8, 10, 0, 31).unwrap(); // window N+1
         assert_ne!(s.current_code(t1), s.current_code(t2));
+        assert_eq!(
+            s.code_valid_until(t1),
+            Utc.with_ymd_and_hms(2025, 9, 8, 10, 0, 30).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_geofence_limits_where_attendance_is_marked() {
+        let db = setup_test_db().await;
+        let lecturer = user::Model::create(&db, "lect1", "lect1@test.com", "pw", false)
+            .await
+            .unwrap();
+        let m = module::Model::create(&db, "COS101", 2025, None, 16)
+            .await
+            .unwrap();
+        let s = Model::create(
+            &db,
+            m.id,
+            lecturer.id,
+            "Lec",
+            true,
+            30,
+            false,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(s.location_permitted(None).is_ok());
+
+        // IT building, University of Pretoria
+        let venue = GeoPoint {
+            latitude: -25.7557,
+            longitude: 28.2333,
+        };
+        let s = Model::set_geofence(&db, s.id, Some((venue, 100)))
+            .await
+            .unwrap();
+        // ~55m north, ~1.1km north
+        let at = |latitude: f64, accuracy_m: Option<f64>| {
+            Some(ReportedLocation {
+                latitude,
+                longitude: 28.2333,
+                accuracy_m,
+            })
+        };
+        let far = GeoPoint {
+            latitude: -25.7457,
+            longitude: 28.2333,
+        };
+        assert!((venue.distance_m(&far) - 1112.0).abs() < 5.0);
+
+        assert!(s.location_permitted(None).is_err());
+        assert!(s.location_permitted(at(-25.7552, None)).is_ok());
+        assert!(s.location_permitted(at(-25.7457, None)).is_err());
+        // A huge reported inaccuracy only buys MAX_ACCURACY_SLACK_M
+        assert!(s.location_permitted(at(-25.7457, Some(5000.0))).is_err());
+        assert!(s.location_permitted(at(-25.7541, Some(150.0))).is_ok());
+
+        let s = Model::set_geofence(&db, s.id, None).await.unwrap();
+        assert!(s.location_permitted(at(-25.7457, None)).is_ok());
     }
 }
@@ -0,0 +1,124 @@
+//! Calendar feed tokens.
+//!
+//! Calendar apps subscribe to `/api/me/calendar.ics` without being able to log in, so each
+//! user's feed URL carries a secret token instead. It only opens the feed, lasts until the user
+//! asks for a new one, and is created the first time the user asks for their feed URL.
+
+use chrono::{DateTime, Utc};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel};
+use serde::Serialize;
+
+/// Length of a generated token.
+const TOKEN_LEN: usize = 40;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "calendar_feed_tokens")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    #[sea_orm(unique)]
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn new_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
+
+impl Model {
+    /// The user's token, created on first use.
+    pub async fn for_user(db: &DatabaseConnection, user_id: i64) -> Result<Self, DbErr> {
+        if let Some(existing) = Entity::find_by_id(user_id).one(db).await? {
+            return Ok(existing);
+        }
+        ActiveModel {
+            user_id: Set(user_id),
+            token: Set(new_token()),
+            created_at: Set(Utc::now()),
+        }
+        .insert(db)
+        .await
+    }
+
+    /// Replaces the user's token, so feed URLs handed out before stop working.
+    pub async fn regenerate(db: &DatabaseConnection, user_id: i64) -> Result<Self, DbErr> {
+        match Entity::find_by_id(user_id).one(db).await? {
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.token = Set(new_token());
+                am.created_at = Set(Utc::now());
+                am.update(db).await
+            }
+            None => Self::for_user(db, user_id).await,
+        }
+    }
+
+    /// The user a feed token belongs to.
+    pub async fn find_user_id(db: &DatabaseConnection, token: &str) -> Result<Option<i64>, DbErr> {
+        if token.len() != TOKEN_LEN {
+            return Ok(None);
+        }
+        Ok(Entity::find()
+            .filter(Column::Token.eq(token))
+            .one(db)
+            .await?
+            .map(|t| t.user_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn tokens_are_stable_until_regenerated() {
+        let db = setup_test_db().await;
+        let alice = user::Model::create(&db, "alice", "a@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let first = Model::for_user(&db, alice.id).await.unwrap();
+        assert_eq!(first.token.len(), TOKEN_LEN);
+        assert_eq!(Model::for_user(&db, alice.id).await.unwrap(), first);
+        assert_eq!(
+            Model::find_user_id(&db, &first.token).await.unwrap(),
+            Some(alice.id)
+        );
+
+        let second = Model::regenerate(&db, alice.id).await.unwrap();
+        assert_ne!(second.token, first.token);
+        assert_eq!(Model::find_user_id(&db, &first.token).await.unwrap(), None);
+        assert_eq!(
+            Model::find_user_id(&db, &second.token).await.unwrap(),
+            Some(alice.id)
+        );
+    }
+}
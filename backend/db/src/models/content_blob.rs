@@ -0,0 +1,243 @@
+//! Deduplicated file contents.
+//!
+//! Identical uploads (the same makefile/main archive across assignments, a student resubmitting
+//! an unchanged zip) are stored once under [`util::paths::content_blob_path`], keyed by their
+//! SHA-256. The file at a record's own `path` is a link to that blob, so code that reads the
+//! canonical directories keeps working. `ref_count` is the number of records linked to a blob;
+//! the blob is removed when it drops to zero.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveValue::Set, ConnectionTrait, DatabaseConnection, QueryFilter, Statement, TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use util::paths::content_blob_path;
+use util::storage::{key_for, storage};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "content_blobs")]
+pub struct Model {
+    /// Hex SHA-256 of the contents.
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub hash: String,
+    pub size_bytes: i64,
+    /// Records currently linked to this blob.
+    pub ref_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Tables whose `content_hash` column references a blob.
+const REFERENCING_TABLES: &[&str] = &["assignment_files", "assignment_submissions"];
+
+impl Model {
+    /// Hex SHA-256 of `bytes`.
+    pub fn hash_bytes(bytes: &[u8]) -> String {
+        hex::encode(Sha256::digest(bytes))
+    }
+
+    /// Storage key of the blob.
+    pub fn key(&self) -> String {
+        key_for(&content_blob_path(&self.hash))
+    }
+
+    /// Stores `bytes` at `key` through the blob for its contents, adding a reference to it
+    /// (and writing the blob if these contents are new). Returns the blob.
+    ///
+    /// The reference is added with a single upsert, so concurrent stores of the same contents
+    /// neither lose counts nor collide on the primary key.
+    pub async fn store_at(db: &DatabaseConnection, key: &str, bytes: &[u8]) -> Result<Self, DbErr> {
+        let hash = Self::hash_bytes(bytes);
+        Entity::insert(ActiveModel {
+            hash: Set(hash.clone()),
+            size_bytes: Set(bytes.len() as i64),
+            ref_count: Set(1),
+            created_at: Set(Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::column(Column::Hash)
+                .value(
+                    Column::RefCount,
+                    Expr::col((Entity, Column::RefCount)).add(1),
+                )
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+        let blob = Entity::find_by_id(hash)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Content blob vanished while storing".into()))?;
+
+        // (Re)write the blob if it is missing: these contents are new, or an earlier store failed.
+        let blob_key = blob.key();
+        let present = storage()
+            .exists(&blob_key)
+            .await
+            .map_err(|e| DbErr::Custom(format!("Failed to check blob: {e}")))?;
+        if !present {
+            storage()
+                .write(&blob_key, bytes)
+                .await
+                .map_err(|e| DbErr::Custom(format!("Failed to write blob: {e}")))?;
+        }
+        storage()
+            .link(&blob_key, key)
+            .await
+            .map_err(|e| DbErr::Custom(format!("Failed to write file: {e}")))?;
+
+        Ok(blob)
+    }
+
+    /// Drops one reference to the blob `hash`, deleting it once nothing refers to it.
+    ///
+    /// The decrement and the deletion share a transaction, so a concurrent [`Model::store_at`]
+    /// of the same contents waits for it and then writes the blob afresh.
+    pub async fn release(db: &DatabaseConnection, hash: &str) -> Result<(), DbErr> {
+        let txn = db.begin().await?;
+        let decremented = Entity::update_many()
+            .col_expr(Column::RefCount, Expr::col(Column::RefCount).sub(1))
+            .filter(Column::Hash.eq(hash))
+            .exec(&txn)
+            .await?;
+        if decremented.rows_affected == 0 {
+            return Ok(());
+        }
+        Self::remove_unreferenced(&txn, hash).await?;
+        txn.commit().await
+    }
+
+    /// Recounts references from the referencing tables and removes blobs nothing refers to.
+    ///
+    /// Needed after rows go away without [`Model::release`], e.g. when deleting an assignment
+    /// cascades to its files and submissions. Returns the number of blobs removed.
+    pub async fn prune(db: &DatabaseConnection) -> Result<u64, DbErr> {
+        let backend = db.get_database_backend();
+        let union = REFERENCING_TABLES
+            .iter()
+            .map(|t| format!("SELECT content_hash FROM {t} WHERE content_hash IS NOT NULL"))
+            .collect::<Vec<_>>()
+            .join(" UNION ALL ");
+        db.execute(Statement::from_string(
+            backend,
+            format!(
+                "UPDATE content_blobs SET ref_count = \
+                 (SELECT COUNT(*) FROM ({union}) r WHERE r.content_hash = content_blobs.hash)"
+            ),
+        ))
+        .await?;
+
+        let unreferenced = Entity::find()
+            .filter(Column::RefCount.lte(0))
+            .all(db)
+            .await?;
+        let mut removed = 0;
+        for blob in unreferenced {
+            let txn = db.begin().await?;
+            if Self::remove_unreferenced(&txn, &blob.hash).await? {
+                removed += 1;
+            }
+            txn.commit().await?;
+        }
+        Ok(removed)
+    }
+
+    /// Deletes the blob `hash` if no references to it remain. Returns whether it was deleted.
+    async fn remove_unreferenced<C: ConnectionTrait>(db: &C, hash: &str) -> Result<bool, DbErr> {
+        let deleted = Entity::delete_many()
+            .filter(Column::Hash.eq(hash))
+            .filter(Column::RefCount.lte(0))
+            .exec(db)
+            .await?;
+        if deleted.rows_affected == 0 {
+            return Ok(false);
+        }
+        if let Err(e) = storage().delete(&key_for(&content_blob_path(hash))).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            return Err(DbErr::Custom(format!("Failed to delete blob: {e}")));
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+    use util::paths::storage_root;
+    use util::test_helpers::setup_test_storage_root;
+
+    #[tokio::test]
+    async fn identical_contents_share_one_blob_until_released() {
+        let _tmp = setup_test_storage_root();
+        let db = setup_test_db().await;
+
+        let a = Model::store_at(&db, "module_1/a.zip", b"same")
+            .await
+            .unwrap();
+        let b = Model::store_at(&db, "module_2/b.zip", b"same")
+            .await
+            .unwrap();
+        assert_eq!(a.hash, b.hash);
+        assert_eq!(b.ref_count, 2);
+        assert_eq!(storage().read("module_2/b.zip").await.unwrap(), b"same");
+        assert_eq!(Entity::find().all(&db).await.unwrap().len(), 1);
+
+        Model::release(&db, &a.hash).await.unwrap();
+        assert!(storage_root().join(a.key()).is_file());
+
+        Model::release(&db, &a.hash).await.unwrap();
+        assert!(!storage_root().join(a.key()).exists());
+        assert!(Entity::find().all(&db).await.unwrap().is_empty());
+        // The linked copies outlive the blob.
+        assert_eq!(storage().read("module_1/a.zip").await.unwrap(), b"same");
+
+        // No row refers to this one, so a prune removes it.
+        let orphan = Model::store_at(&db, "module_1/c.zip", b"orphan")
+            .await
+            .unwrap();
+        assert_eq!(Model::prune(&db).await.unwrap(), 1);
+        assert!(
+            Entity::find_by_id(orphan.hash)
+                .one(&db)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_stores_count_every_reference() {
+        let _tmp = setup_test_storage_root();
+        let db = setup_test_db().await;
+
+        let (a, b, c) = tokio::join!(
+            Model::store_at(&db, "module_1/a.zip", b"race"),
+            Model::store_at(&db, "module_1/b.zip", b"race"),
+            Model::store_at(&db, "module_1/c.zip", b"race"),
+        );
+        let hash = a.unwrap().hash;
+        b.unwrap();
+        c.unwrap();
+        let blob = Entity::find_by_id(hash.clone())
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(blob.ref_count, 3);
+
+        let (x, y) = tokio::join!(Model::release(&db, &hash), Model::release(&db, &hash));
+        x.unwrap();
+        y.unwrap();
+        let blob = Entity::find_by_id(hash).one(&db).await.unwrap().unwrap();
+        assert_eq!(blob.ref_count, 1);
+        assert!(storage_root().join(blob.key()).is_file());
+    }
+}
@@ -0,0 +1,238 @@
+//! The notification email outbox.
+//!
+//! Every notification email is queued here first and sent by the outbox worker, either on its
+//! own or, for users in digest mode (see [`super::email_setting`]), batched into a digest. Rows
+//! stay behind as the delivery record: `sent`, or `failed` after [`MAX_ATTEMPTS`] tries.
+
+use super::notification::NotificationKind;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryFilter, QueryOrder, QuerySelect};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+/// Sends tried before a delivery is given up on.
+pub const MAX_ATTEMPTS: i32 = 3;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "email_deliveries")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Recipient.
+    pub user_id: i64,
+    pub kind: NotificationKind,
+    pub subject: String,
+    pub body_text: String,
+    /// `body_text` rendered from markdown.
+    pub body_html: String,
+    /// Frontend path the email points at, e.g. `/modules/1/announcements/2`.
+    pub link: Option<String>,
+    /// Waits for the recipient's next digest instead of being sent on its own.
+    pub digest: bool,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DeliveryStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "sent")]
+    Sent,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Queues one email per `(user_id, digest)` recipient. Returns how many were queued.
+    pub async fn queue(
+        db: &DatabaseConnection,
+        recipients: &[(i64, bool)],
+        kind: NotificationKind,
+        subject: &str,
+        body_text: &str,
+        body_html: &str,
+        link: Option<&str>,
+    ) -> Result<u64, DbErr> {
+        if recipients.is_empty() {
+            return Ok(0);
+        }
+        let now = Utc::now();
+        let rows = recipients.iter().map(|&(user_id, digest)| ActiveModel {
+            user_id: Set(user_id),
+            kind: Set(kind),
+            subject: Set(subject.to_owned()),
+            body_text: Set(body_text.to_owned()),
+            body_html: Set(body_html.to_owned()),
+            link: Set(link.map(str::to_owned)),
+            digest: Set(digest),
+            status: Set(DeliveryStatus::Pending),
+            attempts: Set(0),
+            last_error: Set(None),
+            created_at: Set(now),
+            sent_at: Set(None),
+            ..Default::default()
+        });
+        Entity::insert_many(rows).exec(db).await?;
+        Ok(recipients.len() as u64)
+    }
+
+    /// Up to `limit` pending deliveries, oldest first: the ones sent on their own, or the ones
+    /// waiting for a digest.
+    pub async fn pending(
+        db: &DatabaseConnection,
+        digest: bool,
+        limit: u64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::Status.eq(DeliveryStatus::Pending))
+            .filter(Column::Digest.eq(digest))
+            .order_by_asc(Column::Id)
+            .limit(limit)
+            .all(db)
+            .await
+    }
+
+    pub async fn mark_sent(db: &DatabaseConnection, ids: &[i64]) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Status, Expr::value(DeliveryStatus::Sent))
+            .col_expr(Column::SentAt, Expr::value(Utc::now()))
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Records a failed send; deliveries that have now used up [`MAX_ATTEMPTS`] are marked
+    /// `failed`, the rest stay pending for the next round.
+    pub async fn record_failure(
+        db: &DatabaseConnection,
+        ids: &[i64],
+        error: &str,
+    ) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Attempts, Expr::col(Column::Attempts).add(1))
+            .col_expr(Column::LastError, Expr::value(error))
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .exec(db)
+            .await?;
+        Entity::update_many()
+            .col_expr(Column::Status, Expr::value(DeliveryStatus::Failed))
+            .filter(Column::Id.is_in(ids.iter().copied()))
+            .filter(Column::Attempts.gte(MAX_ATTEMPTS))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// All of the user's deliveries, newest first.
+    pub async fn list_for_user(db: &DatabaseConnection, user_id: i64) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn deliveries_are_retried_then_given_up_on() {
+        let db = setup_test_db().await;
+        let alice = user::Model::create(&db, "alice", "a@test.com", "pw", false)
+            .await
+            .unwrap();
+        let bob = user::Model::create(&db, "bob", "b@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let queued = Model::queue(
+            &db,
+            &[(alice.id, false), (bob.id, true)],
+            NotificationKind::AnnouncementPosted,
+            "COS301: Exam",
+            "Friday",
+            "<p>Friday</p>",
+            Some("/modules/1/announcements/1"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(queued, 2);
+
+        let now = Model::pending(&db, false, 10).await.unwrap();
+        assert_eq!(now.len(), 1);
+        assert_eq!(now[0].user_id, alice.id);
+        let later = Model::pending(&db, true, 10).await.unwrap();
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].user_id, bob.id);
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            Model::record_failure(&db, &[now[0].id], "relay down")
+                .await
+                .unwrap();
+            let row = Entity::find_by_id(now[0].id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(row.attempts, attempt);
+            assert_eq!(row.last_error.as_deref(), Some("relay down"));
+            let expected = if attempt < MAX_ATTEMPTS {
+                DeliveryStatus::Pending
+            } else {
+                DeliveryStatus::Failed
+            };
+            assert_eq!(row.status, expected);
+        }
+        assert!(Model::pending(&db, false, 10).await.unwrap().is_empty());
+
+        Model::mark_sent(&db, &[later[0].id]).await.unwrap();
+        let sent = Model::list_for_user(&db, bob.id).await.unwrap();
+        assert_eq!(sent[0].status, DeliveryStatus::Sent);
+        assert!(sent[0].sent_at.is_some());
+    }
+}
@@ -0,0 +1,108 @@
+//! Per-user switches over every notification email.
+//!
+//! Which kinds are emailed at all is decided per kind by
+//! [`super::notification_preference`]; these settings apply on top of that. Users without a row
+//! get [`Model::default_for`] (emails on, each sent as it happens).
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel, QueryFilter};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "email_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    /// No notification emails at all, whatever the per-kind preferences say.
+    pub opted_out: bool,
+    /// Batch notification emails into one digest every `EMAIL_DIGEST_INTERVAL_SECS`.
+    pub digest: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The settings used when the user hasn't saved any.
+    pub fn default_for(user_id: i64) -> Self {
+        Self {
+            user_id,
+            opted_out: false,
+            digest: false,
+            updated_at: Utc::now(),
+        }
+    }
+
+    pub async fn for_user(db: &DatabaseConnection, user_id: i64) -> Result<Self, DbErr> {
+        Ok(Entity::find_by_id(user_id)
+            .one(db)
+            .await?
+            .unwrap_or_else(|| Self::default_for(user_id)))
+    }
+
+    /// The settings of each of `user_ids`, defaults included, in the same order.
+    pub async fn for_users(db: &DatabaseConnection, user_ids: &[i64]) -> Result<Vec<Self>, DbErr> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let saved = Entity::find()
+            .filter(Column::UserId.is_in(user_ids.iter().copied()))
+            .all(db)
+            .await?;
+        Ok(user_ids
+            .iter()
+            .map(|&user_id| {
+                saved
+                    .iter()
+                    .find(|s| s.user_id == user_id)
+                    .cloned()
+                    .unwrap_or_else(|| Self::default_for(user_id))
+            })
+            .collect())
+    }
+
+    pub async fn set(
+        db: &DatabaseConnection,
+        user_id: i64,
+        opted_out: bool,
+        digest: bool,
+    ) -> Result<Self, DbErr> {
+        match Entity::find_by_id(user_id).one(db).await? {
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.opted_out = Set(opted_out);
+                am.digest = Set(digest);
+                am.updated_at = Set(Utc::now());
+                am.update(db).await
+            }
+            None => {
+                ActiveModel {
+                    user_id: Set(user_id),
+                    opted_out: Set(opted_out),
+                    digest: Set(digest),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+}
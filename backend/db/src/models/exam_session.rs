@@ -0,0 +1,164 @@
+//! Exam sessions: when a student started a timed assignment (`exam.enabled` in its config).
+//!
+//! A student may submit to an exam only while their session is running, i.e. from starting it
+//! until `ends_at`: `exam.duration_minutes` later, or their due date if that comes first.
+
+use crate::models::assignment;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "exam_sessions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub assignment_id: i64,
+    pub user_id: i64,
+    pub started_at: DateTime<Utc>,
+    /// Submissions are refused from this moment on.
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Starts the exam for `user_id`, giving them `duration` or until their due date, whichever
+    /// ends first. Starting again returns the existing session unchanged, so the clock can't
+    /// be reset.
+    pub async fn start(
+        db: &DatabaseConnection,
+        assignment: &assignment::Model,
+        user_id: i64,
+        duration: Duration,
+    ) -> Result<Self, DbErr> {
+        if let Some(existing) = Self::for_user(db, assignment.id, user_id).await? {
+            return Ok(existing);
+        }
+        let now = Utc::now();
+        let due_date = assignment.due_date_for(db, user_id).await?;
+        ActiveModel {
+            assignment_id: Set(assignment.id),
+            user_id: Set(user_id),
+            started_at: Set(now),
+            ends_at: Set((now + duration).min(due_date)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await
+    }
+
+    /// The assignment's sessions, most recently started first.
+    pub async fn list_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .order_by_desc(Column::StartedAt)
+            .order_by_asc(Column::UserId)
+            .all(db)
+            .await
+    }
+
+    /// Whether the student may still submit at `now`.
+    pub fn is_running(&self, now: DateTime<Utc>) -> bool {
+        now < self.ends_at
+    }
+
+    /// Whole seconds the student has left at `now`; 0 once time is up.
+    pub fn remaining_seconds(&self, now: DateTime<Utc>) -> i64 {
+        (self.ends_at - now).num_seconds().max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{module, user};
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn start_is_capped_by_due_date_and_only_happens_once() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let due = Utc::now() + Duration::minutes(30);
+        let a = assignment::Model::create(
+            &db,
+            module.id,
+            "Exam",
+            None,
+            assignment::AssignmentType::Assignment,
+            due - Duration::days(1),
+            due,
+        )
+        .await
+        .unwrap();
+        let student = user::Model::create(&db, "stud", "stud@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let session = Model::start(&db, &a, student.id, Duration::hours(2))
+            .await
+            .unwrap();
+        assert_eq!(session.ends_at, a.due_date);
+        assert!(session.is_running(Utc::now()));
+        assert!(!session.is_running(due));
+        assert_eq!(session.remaining_seconds(due + Duration::minutes(1)), 0);
+
+        let again = Model::start(&db, &a, student.id, Duration::hours(5))
+            .await
+            .unwrap();
+        assert_eq!(again, session);
+        assert_eq!(
+            Model::list_for_assignment(&db, a.id).await.unwrap().len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,120 @@
+//! One finished generation of a [`super::ga_run`]: fitness statistics, the best individual and
+//! the whole evaluated population.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "ga_generations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub run_id: i64,
+    /// Zero-based generation index.
+    pub generation: i64,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    /// Best coverage percent in the generation (code coverage runs only).
+    pub coverage_percent: Option<f64>,
+    /// Decoded interpreter payload of the generation's fittest chromosome.
+    pub best_payload: String,
+    /// Every chromosome of the generation as a JSON array of [`Individual`]s.
+    #[sea_orm(column_type = "JsonBinary")]
+    pub population: serde_json::Value,
+    /// Chromosomes scored so far in the run, how many needed an interpreter run, and how many
+    /// were answered from the fitness cache.
+    pub evaluated: i64,
+    pub interpreter_runs: i64,
+    pub cache_hits: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A chromosome of a stored population.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Individual {
+    /// Decoded genes, comma-separated (the interpreter payload).
+    pub payload: String,
+    pub fitness: f64,
+}
+
+/// Statistics of a finished generation, as passed to [`Model::record`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationStats {
+    pub generation: i64,
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    pub coverage_percent: Option<f64>,
+    pub evaluated: i64,
+    pub interpreter_runs: i64,
+    pub cache_hits: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::ga_run::Entity",
+        from = "Column::RunId",
+        to = "super::ga_run::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Run,
+}
+
+impl Related<super::ga_run::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Run.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Stores a finished generation; `best_payload` is taken from the fittest individual.
+    pub async fn record(
+        db: &DatabaseConnection,
+        run_id: i64,
+        stats: GenerationStats,
+        population: &[Individual],
+    ) -> Result<Self, DbErr> {
+        let best_payload = population
+            .iter()
+            .max_by(|a, b| a.fitness.total_cmp(&b.fitness))
+            .map(|i| i.payload.clone())
+            .unwrap_or_default();
+        let population =
+            serde_json::to_value(population).map_err(|e| DbErr::Custom(e.to_string()))?;
+
+        ActiveModel {
+            run_id: Set(run_id),
+            generation: Set(stats.generation),
+            best_fitness: Set(stats.best_fitness),
+            mean_fitness: Set(stats.mean_fitness),
+            coverage_percent: Set(stats.coverage_percent),
+            best_payload: Set(best_payload),
+            population: Set(population),
+            evaluated: Set(stats.evaluated),
+            interpreter_runs: Set(stats.interpreter_runs),
+            cache_hits: Set(stats.cache_hits),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// A run's generations in order.
+    pub async fn list_for_run(db: &DatabaseConnection, run_id: i64) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::RunId.eq(run_id))
+            .order_by_asc(Column::Generation)
+            .all(db)
+            .await
+    }
+
+    /// The stored population, decoded.
+    pub fn individuals(&self) -> Vec<Individual> {
+        serde_json::from_value(self.population.clone()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,300 @@
+//! GATLAM / code coverage / multi-objective GA runs of a submission, with the best individual
+//! found. Per-generation history lives in [`super::ga_generation`].
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel, QueryFilter, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "ga_runs")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub assignment_id: i64,
+    pub submission_id: i64,
+    pub mode: GaRunMode,
+    pub status: GaRunStatus,
+    /// Number of generations the run was configured for.
+    pub generations: i64,
+    pub population_size: i64,
+    /// Best fitness seen in any generation, and the decoded interpreter payload that scored it.
+    pub best_fitness: Option<f64>,
+    pub best_payload: Option<String>,
+    /// Why the run failed, if it did.
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum GaRunMode {
+    #[sea_orm(string_value = "gatlam")]
+    Gatlam,
+    #[sea_orm(string_value = "code_coverage")]
+    CodeCoverage,
+    /// GATLAM with coverage optimized alongside the property score.
+    #[sea_orm(string_value = "multi_objective")]
+    MultiObjective,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum GaRunStatus {
+    #[sea_orm(string_value = "running")]
+    Running,
+    #[sea_orm(string_value = "completed")]
+    Completed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+impl fmt::Display for GaRunMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            GaRunMode::Gatlam => "gatlam",
+            GaRunMode::CodeCoverage => "code_coverage",
+            GaRunMode::MultiObjective => "multi_objective",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl fmt::Display for GaRunStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            GaRunStatus::Running => "running",
+            GaRunStatus::Completed => "completed",
+            GaRunStatus::Failed => "failed",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(
+        belongs_to = "super::assignment_submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::assignment_submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+
+    #[sea_orm(has_many = "super::ga_generation::Entity")]
+    Generations,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::assignment_submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl Related<super::ga_generation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Generations.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Records the start of a run.
+    pub async fn start(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        submission_id: i64,
+        mode: GaRunMode,
+        generations: i64,
+        population_size: i64,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            assignment_id: Set(assignment_id),
+            submission_id: Set(submission_id),
+            mode: Set(mode),
+            status: Set(GaRunStatus::Running),
+            generations: Set(generations),
+            population_size: Set(population_size),
+            best_fitness: Set(None),
+            best_payload: Set(None),
+            error: Set(None),
+            started_at: Set(Utc::now()),
+            finished_at: Set(None),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// Marks the run as finished: completed, or failed with `error`.
+    pub async fn finish(
+        db: &DatabaseConnection,
+        run_id: i64,
+        best: Option<(f64, String)>,
+        error: Option<String>,
+    ) -> Result<Self, DbErr> {
+        let run = Entity::find_by_id(run_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound(format!("GA run {run_id} not found")))?;
+
+        let (best_fitness, best_payload) = best.unzip();
+        let mut am = run.into_active_model();
+        am.status = Set(if error.is_some() {
+            GaRunStatus::Failed
+        } else {
+            GaRunStatus::Completed
+        });
+        am.best_fitness = Set(best_fitness);
+        am.best_payload = Set(best_payload);
+        am.error = Set(error);
+        am.finished_at = Set(Some(Utc::now()));
+        am.update(db).await
+    }
+
+    /// Runs for an assignment (optionally only one submission's), newest first.
+    pub async fn list_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        submission_id: Option<i64>,
+    ) -> Result<Vec<Self>, DbErr> {
+        let mut query = Entity::find().filter(Column::AssignmentId.eq(assignment_id));
+        if let Some(submission_id) = submission_id {
+            query = query.filter(Column::SubmissionId.eq(submission_id));
+        }
+        query
+            .order_by_desc(Column::StartedAt)
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ga_generation::{self, GenerationStats, Individual};
+    use crate::models::{assignment, assignment_submission, module, user};
+    use crate::test_utils::setup_test_db;
+    use sea_orm::ActiveModelTrait;
+
+    #[tokio::test]
+    async fn records_generations_and_finishes_run() {
+        let _tmp = util::test_helpers::setup_test_storage_root();
+        let db = setup_test_db().await;
+
+        let student = user::Model::create(&db, "ga-student", "ga@test.com", "pw", false)
+            .await
+            .unwrap();
+        let m = module::Model::create(&db, "COS301", 2025, Some("GA"), 16)
+            .await
+            .unwrap();
+        let a = assignment::Model::create(
+            &db,
+            m.id,
+            "GA",
+            None,
+            assignment::AssignmentType::Practical,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let s = assignment_submission::ActiveModel {
+            assignment_id: Set(a.id),
+            user_id: Set(student.id),
+            attempt: Set(1),
+            earned: Set(0.0),
+            total: Set(10.0),
+            filename: Set("main.zip".into()),
+            file_hash: Set("hash".into()),
+            path: Set("main.zip".into()),
+            is_practice: Set(false),
+            ignored: Set(false),
+            status: Set(assignment_submission::SubmissionStatus::Running),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+
+        let run = Model::start(&db, a.id, s.id, GaRunMode::Gatlam, 2, 2)
+            .await
+            .unwrap();
+        assert_eq!(run.status, GaRunStatus::Running);
+
+        for generation in [1, 0] {
+            let population = [
+                Individual {
+                    payload: format!("{generation},1"),
+                    fitness: 0.25,
+                },
+                Individual {
+                    payload: format!("{generation},2"),
+                    fitness: 0.75,
+                },
+            ];
+            let stats = GenerationStats {
+                generation,
+                best_fitness: 0.75,
+                mean_fitness: 0.5,
+                coverage_percent: None,
+                evaluated: 2 * (generation + 1),
+                interpreter_runs: 2,
+                cache_hits: 0,
+            };
+            ga_generation::Model::record(&db, run.id, stats, &population)
+                .await
+                .unwrap();
+        }
+
+        let finished = Model::finish(&db, run.id, Some((0.75, "1,2".into())), None)
+            .await
+            .unwrap();
+        assert_eq!(finished.status, GaRunStatus::Completed);
+        assert_eq!(finished.best_payload.as_deref(), Some("1,2"));
+        assert!(finished.finished_at.is_some());
+
+        let generations = ga_generation::Model::list_for_run(&db, run.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            generations.iter().map(|g| g.generation).collect::<Vec<_>>(),
+            [0, 1]
+        );
+        assert_eq!(generations[0].best_payload, "0,2");
+        assert_eq!(generations[1].individuals().len(), 2);
+
+        let runs = Model::list_for_assignment(&db, a.id, Some(s.id))
+            .await
+            .unwrap();
+        assert_eq!(runs.len(), 1);
+        assert!(
+            Model::list_for_assignment(&db, a.id, Some(s.id + 1))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
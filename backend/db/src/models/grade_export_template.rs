@@ -0,0 +1,451 @@
+//! Grade export templates: the CSV layout a records system ingests.
+//!
+//! A template's [`Definition`] maps columns to values (student number, final mark, result code,
+//! fixed text, ...), and sets how the final mark is rounded and which codes mean pass and fail.
+//! [`Definition::render`] turns gradebook rows into that CSV, or lists the students whose marks
+//! are missing.
+
+use crate::gradebook::{GradebookColumn, GradebookRow};
+use crate::models::module;
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "grade_export_templates")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub name: String,
+    pub description: Option<String>,
+    /// A [`Definition`] as JSON.
+    pub definition: Json,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Where a column's value comes from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum ColumnSource {
+    Username,
+    /// The username's digits (`u12345678` → `12345678`)
+    StudentNumber,
+    Email,
+    ModuleCode,
+    Year,
+    /// The weighted final mark, rounded
+    FinalMark,
+    /// `pass_code` or `fail_code`
+    ResultCode,
+    /// The same text on every row
+    Literal {
+        value: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportColumn {
+    pub header: String,
+    #[serde(flatten)]
+    pub source: ColumnSource,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    #[default]
+    HalfUp,
+    HalfEven,
+    Down,
+    Up,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Rounding {
+    #[serde(default)]
+    pub mode: RoundingMode,
+    /// Decimal places, 0–4
+    #[serde(default)]
+    pub decimals: u32,
+}
+
+impl Rounding {
+    pub fn apply(&self, value: f64) -> f64 {
+        let factor = 10f64.powi(self.decimals as i32);
+        // Scale with a little slack so 64.5 stored as 64.4999… still rounds up
+        let scaled = value * factor;
+        let nudged = (scaled * 1e9).round() / 1e9;
+        let rounded = match self.mode {
+            RoundingMode::HalfUp => nudged.round(),
+            RoundingMode::HalfEven => {
+                let floor = nudged.floor();
+                if nudged - floor == 0.5 {
+                    if floor % 2.0 == 0.0 {
+                        floor
+                    } else {
+                        floor + 1.0
+                    }
+                } else {
+                    nudged.round()
+                }
+            }
+            RoundingMode::Down => nudged.floor(),
+            RoundingMode::Up => nudged.ceil(),
+        };
+        rounded / factor
+    }
+
+    fn format(&self, value: f64) -> String {
+        format!("{:.*}", self.decimals as usize, self.apply(value))
+    }
+}
+
+fn default_pass_mark() -> f64 {
+    50.0
+}
+
+fn default_pass_code() -> String {
+    "P".into()
+}
+
+fn default_fail_code() -> String {
+    "F".into()
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A template's layout and rules.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Definition {
+    pub columns: Vec<ExportColumn>,
+    #[serde(default)]
+    pub rounding: Rounding,
+    /// The rounded final mark needed for `pass_code`
+    #[serde(default = "default_pass_mark")]
+    pub pass_mark: f64,
+    #[serde(default = "default_pass_code")]
+    pub pass_code: String,
+    #[serde(default = "default_fail_code")]
+    pub fail_code: String,
+    #[serde(default = "default_delimiter")]
+    pub delimiter: char,
+    /// Whether the first line holds the column headers
+    #[serde(default = "default_true")]
+    pub header: bool,
+}
+
+/// A student who has no mark for some of the weighted assignments.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingMarks {
+    pub user_id: i64,
+    pub username: String,
+    /// Names of the assignments without a mark
+    pub assignments: Vec<String>,
+}
+
+impl Definition {
+    /// Checks the definition is usable, returning every problem found.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        if self.columns.is_empty() {
+            errors.push("At least one column is required".to_string());
+        }
+        for (i, c) in self.columns.iter().enumerate() {
+            if c.header.trim().is_empty() && self.header {
+                errors.push(format!("Column {} needs a header", i + 1));
+            }
+        }
+        if self.rounding.decimals > 4 {
+            errors.push("Rounding decimals must be between 0 and 4".to_string());
+        }
+        if !(0.0..=100.0).contains(&self.pass_mark) {
+            errors.push("Pass mark must be between 0 and 100".to_string());
+        }
+        if matches!(self.delimiter, '"' | '\n' | '\r') {
+            errors.push("Delimiter cannot be a quote or a line break".to_string());
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn escape(&self, s: &str) -> String {
+        if s.contains([self.delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn line(&self, fields: impl IntoIterator<Item = String>) -> String {
+        let mut line = fields
+            .into_iter()
+            .map(|f| self.escape(&f))
+            .collect::<Vec<_>>()
+            .join(&self.delimiter.to_string());
+        line.push_str("\r\n");
+        line
+    }
+
+    /// The CSV for `rows`. The final mark is the `weights`-weighted average (by assignment id)
+    /// of the assignment marks, every column counting equally when `weights` is `None`.
+    ///
+    /// Students without a mark for a weighted assignment are returned as errors, unless
+    /// `missing_as_zero` counts those marks as 0.
+    pub fn render(
+        &self,
+        module: &module::Model,
+        columns: &[GradebookColumn],
+        rows: &[GradebookRow],
+        weights: Option<&HashMap<i64, f64>>,
+        missing_as_zero: bool,
+    ) -> Result<String, Vec<MissingMarks>> {
+        let weight_of = |c: &GradebookColumn| match weights {
+            Some(w) => w.get(&c.assignment.id).copied().unwrap_or(0.0),
+            None => 1.0,
+        };
+        let total_weight: f64 = columns.iter().map(weight_of).filter(|w| *w > 0.0).sum();
+
+        let mut out = String::new();
+        if self.header {
+            out.push_str(&self.line(self.columns.iter().map(|c| c.header.clone())));
+        }
+        let mut missing = Vec::new();
+        for row in rows {
+            let mut earned = 0.0;
+            let mut absent = Vec::new();
+            for (column, cell) in columns.iter().zip(&row.cells) {
+                let w = weight_of(column);
+                if w <= 0.0 {
+                    continue;
+                }
+                match cell {
+                    Some(cell) => earned += cell.mark * w,
+                    None => absent.push(column.assignment.name.clone()),
+                }
+            }
+            if !absent.is_empty() && !missing_as_zero {
+                missing.push(MissingMarks {
+                    user_id: row.user.id,
+                    username: row.user.username.clone(),
+                    assignments: absent,
+                });
+                continue;
+            }
+
+            let final_mark = if total_weight > 0.0 {
+                earned / total_weight
+            } else {
+                0.0
+            };
+            let rounded = self.rounding.apply(final_mark);
+            let fields = self.columns.iter().map(|c| match &c.source {
+                ColumnSource::Username => row.user.username.clone(),
+                ColumnSource::StudentNumber => row
+                    .user
+                    .username
+                    .chars()
+                    .filter(char::is_ascii_digit)
+                    .collect(),
+                ColumnSource::Email => row.user.email.clone(),
+                ColumnSource::ModuleCode => module.code.clone(),
+                ColumnSource::Year => module.year.to_string(),
+                ColumnSource::FinalMark => self.rounding.format(final_mark),
+                ColumnSource::ResultCode => if rounded >= self.pass_mark {
+                    &self.pass_code
+                } else {
+                    &self.fail_code
+                }
+                .clone(),
+                ColumnSource::Literal { value } => value.clone(),
+            });
+            out.push_str(&self.line(fields));
+        }
+
+        if missing.is_empty() {
+            Ok(out)
+        } else {
+            Err(missing)
+        }
+    }
+}
+
+impl Model {
+    /// The stored definition; an unreadable one is reported as a custom error.
+    pub fn definition(&self) -> Result<Definition, DbErr> {
+        serde_json::from_value(self.definition.clone())
+            .map_err(|e| DbErr::Custom(format!("Invalid export template: {e}")))
+    }
+
+    pub async fn create(
+        db: &DatabaseConnection,
+        name: &str,
+        description: Option<&str>,
+        definition: &Definition,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        ActiveModel {
+            name: Set(name.to_string()),
+            description: Set(description.map(str::to_owned)),
+            definition: Set(
+                serde_json::to_value(definition).map_err(|e| DbErr::Custom(e.to_string()))?
+            ),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn update(
+        db: &DatabaseConnection,
+        id: i64,
+        name: &str,
+        description: Option<&str>,
+        definition: &Definition,
+    ) -> Result<Self, DbErr> {
+        let existing = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Export template not found".into()))?;
+        let mut am: ActiveModel = existing.into();
+        am.name = Set(name.to_string());
+        am.description = Set(description.map(str::to_owned));
+        am.definition =
+            Set(serde_json::to_value(definition).map_err(|e| DbErr::Custom(e.to_string()))?);
+        am.updated_at = Set(Utc::now());
+        am.update(db).await
+    }
+
+    /// All templates by name.
+    pub async fn list(db: &DatabaseConnection) -> Result<Vec<Self>, DbErr> {
+        Entity::find().order_by_asc(Column::Name).all(db).await
+    }
+
+    pub async fn find_by_name(db: &DatabaseConnection, name: &str) -> Result<Option<Self>, DbErr> {
+        Entity::find().filter(Column::Name.eq(name)).one(db).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gradebook::GradebookCell;
+    use crate::models::{assignment, user};
+    use crate::test_utils::setup_test_db;
+
+    fn definition() -> Definition {
+        serde_json::from_value(serde_json::json!({
+            "columns": [
+                { "header": "STUDENT_NO", "source": "student_number" },
+                { "header": "MODULE", "source": "module_code" },
+                { "header": "TERM", "source": "literal", "value": "S1" },
+                { "header": "MARK", "source": "final_mark" },
+                { "header": "RESULT", "source": "result_code" }
+            ],
+            "pass_code": "PS",
+            "fail_code": "FL"
+        }))
+        .unwrap()
+    }
+
+    fn cell(mark: f64) -> Option<GradebookCell> {
+        Some(GradebookCell {
+            mark,
+            best: mark,
+            last: mark,
+            attempts: 1,
+            late: false,
+        })
+    }
+
+    #[test]
+    fn rounding_modes() {
+        let r = |mode, decimals| Rounding { mode, decimals };
+        assert_eq!(r(RoundingMode::HalfUp, 0).apply(49.5), 50.0);
+        assert_eq!(r(RoundingMode::HalfEven, 0).apply(48.5), 48.0);
+        assert_eq!(r(RoundingMode::HalfEven, 0).apply(49.5), 50.0);
+        assert_eq!(r(RoundingMode::Down, 0).apply(49.9), 49.0);
+        assert_eq!(r(RoundingMode::Up, 1).apply(49.91), 50.0);
+        assert_eq!(r(RoundingMode::HalfUp, 2).apply(64.445), 64.45);
+    }
+
+    #[tokio::test]
+    async fn renders_records_csv_and_reports_missing_marks() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        for name in ["A1", "A2"] {
+            assignment::Model::create(
+                &db,
+                module.id,
+                name,
+                None,
+                assignment::AssignmentType::Assignment,
+                Utc::now(),
+                Utc::now(),
+            )
+            .await
+            .unwrap();
+        }
+        let columns = crate::gradebook::columns(&db, module.id).await.unwrap();
+        let mut rows = Vec::new();
+        for (username, cells) in [
+            ("u11111111", vec![cell(60.0), cell(39.0)]),
+            ("u22222222", vec![cell(80.0), None]),
+        ] {
+            let user =
+                user::Model::create(&db, username, &format!("{username}@test.com"), "pw", false)
+                    .await
+                    .unwrap();
+            rows.push(GradebookRow { user, cells });
+        }
+        let def = definition();
+        assert!(def.validate().is_ok());
+
+        let missing = def
+            .render(&module, &columns, &rows, None, false)
+            .unwrap_err();
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].username, "u22222222");
+        assert_eq!(missing[0].assignments, ["A2"]);
+
+        let csv = def.render(&module, &columns, &rows, None, true).unwrap();
+        assert_eq!(
+            csv,
+            "STUDENT_NO,MODULE,TERM,MARK,RESULT\r\n\
+             11111111,COS301,S1,50,PS\r\n\
+             22222222,COS301,S1,40,FL\r\n"
+        );
+
+        // Only A1 counts
+        let weights = HashMap::from([(columns[0].assignment.id, 1.0)]);
+        let csv = def
+            .render(&module, &columns, &rows, Some(&weights), false)
+            .unwrap();
+        assert!(csv.ends_with("22222222,COS301,S1,80,PS\r\n"));
+
+        let stored = Model::create(&db, "Faculty", None, &def).await.unwrap();
+        assert_eq!(stored.definition().unwrap(), def);
+        assert!(Model::create(&db, "Faculty", None, &def).await.is_err());
+    }
+}
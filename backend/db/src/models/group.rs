@@ -0,0 +1,340 @@
+//! Groups (teams) of students that submit together for one assignment.
+//!
+//! Groups only change how submissions are attributed when the assignment's config sets
+//! `marking.group_submissions`: a member's submission is then linked to their group and counts
+//! as every member's (see [`super::assignment_submission::Model::owner_condition`]).
+
+use super::{assignment_submission, group_member};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveValue::Set, ConnectionTrait, DatabaseConnection, IntoActiveModel, QueryFilter,
+    QueryOrder, TransactionTrait,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "groups")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub assignment_id: i64,
+    /// Unique within the assignment.
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(has_many = "super::group_member::Entity")]
+    Members,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::group_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Members.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Creates a group with the given members.
+    ///
+    /// Fails if any of them is already in another group for the assignment; check with
+    /// [`Self::conflicting_members`] first for a friendlier error.
+    pub async fn create(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        name: &str,
+        member_ids: &[i64],
+    ) -> Result<Self, DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now();
+        let group = ActiveModel {
+            assignment_id: Set(assignment_id),
+            name: Set(name.to_owned()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        replace_members(&txn, group.id, assignment_id, member_ids).await?;
+        txn.commit().await?;
+        Ok(group)
+    }
+
+    /// Renames the group and/or replaces its members.
+    pub async fn update(
+        db: &DatabaseConnection,
+        group_id: i64,
+        name: Option<&str>,
+        member_ids: Option<&[i64]>,
+    ) -> Result<Self, DbErr> {
+        let txn = db.begin().await?;
+        let group = Entity::find_by_id(group_id)
+            .one(&txn)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Group not found".into()))?;
+        let assignment_id = group.assignment_id;
+
+        let mut am = group.into_active_model();
+        if let Some(name) = name {
+            am.name = Set(name.to_owned());
+        }
+        am.updated_at = Set(Utc::now());
+        let group = am.update(&txn).await?;
+
+        if let Some(member_ids) = member_ids {
+            replace_members(&txn, group_id, assignment_id, member_ids).await?;
+        }
+        txn.commit().await?;
+        Ok(group)
+    }
+
+    /// Deletes the group. Its submissions stay with the members who made them.
+    pub async fn delete(db: &DatabaseConnection, group_id: i64) -> Result<(), DbErr> {
+        let txn = db.begin().await?;
+        assignment_submission::Entity::update_many()
+            .col_expr(
+                assignment_submission::Column::GroupId,
+                Expr::value(Option::<i64>::None),
+            )
+            .filter(assignment_submission::Column::GroupId.eq(group_id))
+            .exec(&txn)
+            .await?;
+        let res = Entity::delete_by_id(group_id).exec(&txn).await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound("Group not found".into()));
+        }
+        txn.commit().await
+    }
+
+    /// User ids of the group's members.
+    pub async fn member_ids(db: &DatabaseConnection, group_id: i64) -> Result<Vec<i64>, DbErr> {
+        Ok(group_member::Entity::find()
+            .filter(group_member::Column::GroupId.eq(group_id))
+            .order_by_asc(group_member::Column::UserId)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| m.user_id)
+            .collect())
+    }
+
+    /// The user's group for the assignment, if they are in one.
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        let Some(membership) = group_member::Entity::find()
+            .filter(group_member::Column::AssignmentId.eq(assignment_id))
+            .filter(group_member::Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Entity::find_by_id(membership.group_id).one(db).await
+    }
+
+    /// The assignment's groups by name, each with its member ids.
+    pub async fn list_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Vec<(Self, Vec<i64>)>, DbErr> {
+        let groups = Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .order_by_asc(Column::Name)
+            .all(db)
+            .await?;
+        let mut members: HashMap<i64, Vec<i64>> = HashMap::new();
+        for m in group_member::Entity::find()
+            .filter(group_member::Column::AssignmentId.eq(assignment_id))
+            .order_by_asc(group_member::Column::UserId)
+            .all(db)
+            .await?
+        {
+            members.entry(m.group_id).or_default().push(m.user_id);
+        }
+        Ok(groups
+            .into_iter()
+            .map(|g| {
+                let ids = members.remove(&g.id).unwrap_or_default();
+                (g, ids)
+            })
+            .collect())
+    }
+
+    /// Group id of every grouped user of the assignment, keyed by user id.
+    pub async fn memberships(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<HashMap<i64, i64>, DbErr> {
+        Ok(group_member::Entity::find()
+            .filter(group_member::Column::AssignmentId.eq(assignment_id))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| (m.user_id, m.group_id))
+            .collect())
+    }
+
+    /// Those of `member_ids` that are already in a group for the assignment other than
+    /// `except_group`.
+    pub async fn conflicting_members(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        member_ids: &[i64],
+        except_group: Option<i64>,
+    ) -> Result<Vec<i64>, DbErr> {
+        let mut query = group_member::Entity::find()
+            .filter(group_member::Column::AssignmentId.eq(assignment_id))
+            .filter(group_member::Column::UserId.is_in(member_ids.iter().copied()));
+        if let Some(group_id) = except_group {
+            query = query.filter(group_member::Column::GroupId.ne(group_id));
+        }
+        Ok(query
+            .order_by_asc(group_member::Column::UserId)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|m| m.user_id)
+            .collect())
+    }
+}
+
+async fn replace_members<C: ConnectionTrait>(
+    db: &C,
+    group_id: i64,
+    assignment_id: i64,
+    member_ids: &[i64],
+) -> Result<(), DbErr> {
+    group_member::Entity::delete_many()
+        .filter(group_member::Column::GroupId.eq(group_id))
+        .exec(db)
+        .await?;
+    let mut ids = member_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    for user_id in ids {
+        group_member::ActiveModel {
+            group_id: Set(group_id),
+            user_id: Set(user_id),
+            assignment_id: Set(assignment_id),
+        }
+        .insert(db)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{assignment, module, user};
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn members_belong_to_one_group_per_assignment() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let a1 = assignment::Model::create(
+            &db,
+            module.id,
+            "A1",
+            None,
+            assignment::AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let a2 = assignment::Model::create(
+            &db,
+            module.id,
+            "A2",
+            None,
+            assignment::AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let mut users = Vec::new();
+        for name in ["u1", "u2", "u3"] {
+            users.push(
+                user::Model::create(&db, name, &format!("{name}@test.com"), "pw", false)
+                    .await
+                    .unwrap()
+                    .id,
+            );
+        }
+
+        let team = Model::create(&db, a1.id, "Team A", &[users[0], users[1], users[0]])
+            .await
+            .unwrap();
+        assert_eq!(
+            Model::member_ids(&db, team.id).await.unwrap(),
+            [users[0], users[1]]
+        );
+
+        // Same student, other assignment: fine
+        Model::create(&db, a2.id, "Team A", &[users[0]]).await.unwrap();
+
+        // Same student, same assignment: rejected
+        assert_eq!(
+            Model::conflicting_members(&db, a1.id, &[users[1], users[2]], None)
+                .await
+                .unwrap(),
+            [users[1]]
+        );
+        assert!(Model::create(&db, a1.id, "Team B", &[users[1]]).await.is_err());
+        assert!(
+            Model::conflicting_members(&db, a1.id, &[users[1]], Some(team.id))
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let team = Model::update(&db, team.id, Some("Renamed"), Some(&[users[1], users[2]]))
+            .await
+            .unwrap();
+        assert_eq!(team.name, "Renamed");
+        assert!(Model::for_user(&db, a1.id, users[0]).await.unwrap().is_none());
+        assert_eq!(
+            Model::for_user(&db, a1.id, users[2]).await.unwrap().unwrap().id,
+            team.id
+        );
+        let memberships = Model::memberships(&db, a1.id).await.unwrap();
+        assert_eq!(memberships.len(), 2);
+
+        Model::delete(&db, team.id).await.unwrap();
+        assert!(Model::list_for_assignment(&db, a1.id).await.unwrap().is_empty());
+        assert!(matches!(
+            Model::delete(&db, team.id).await,
+            Err(DbErr::RecordNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+//! LTI contexts: LMS courses an admin has linked to a module.
+//!
+//! A placement's module is never taken from the launch itself, which any instructor on the
+//! platform can edit: its first launch must come from a linked course, and opens that course's
+//! module.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_contexts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub platform_id: i64,
+    /// The platform's id for the course.
+    pub context_id: String,
+    pub module_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::lti_platform::Entity",
+        from = "Column::PlatformId",
+        to = "super::lti_platform::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Platform,
+    #[sea_orm(
+        belongs_to = "super::module::Entity",
+        from = "Column::ModuleId",
+        to = "super::module::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Module,
+}
+
+impl Related<super::lti_platform::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Platform.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        context_id: &str,
+        module_id: i64,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            platform_id: Set(platform_id),
+            context_id: Set(context_id.to_string()),
+            module_id: Set(module_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn find(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        context_id: &str,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .filter(Column::ContextId.eq(context_id))
+            .one(db)
+            .await
+    }
+
+    pub async fn for_platform(
+        db: &DatabaseConnection,
+        platform_id: i64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .order_by_asc(Column::ContextId)
+            .all(db)
+            .await
+    }
+}
@@ -0,0 +1,77 @@
+//! LTI 1.3 deployments: the deployment ids of a platform that may launch FitchFork.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_deployments")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub platform_id: i64,
+    pub deployment_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::lti_platform::Entity",
+        from = "Column::PlatformId",
+        to = "super::lti_platform::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Platform,
+}
+
+impl Related<super::lti_platform::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Platform.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        deployment_id: &str,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            platform_id: Set(platform_id),
+            deployment_id: Set(deployment_id.to_string()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn for_platform(
+        db: &DatabaseConnection,
+        platform_id: i64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .order_by_asc(Column::DeploymentId)
+            .all(db)
+            .await
+    }
+
+    pub async fn exists(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        deployment_id: &str,
+    ) -> Result<bool, DbErr> {
+        Ok(Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .filter(Column::DeploymentId.eq(deployment_id))
+            .one(db)
+            .await?
+            .is_some())
+    }
+}
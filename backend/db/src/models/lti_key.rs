@@ -0,0 +1,79 @@
+//! The tool's LTI 1.3 signing keys.
+//!
+//! Every active key is published on the tool's JWKS endpoint; the newest active key signs the
+//! client assertions sent to platforms. Keys are rotated by adding a new key and deactivating
+//! the old one once platforms have refreshed their copy of the key set.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_keys")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub kid: String,
+    /// PKCS#8 PEM; never serialised.
+    #[serde(skip_serializing)]
+    pub private_key_pem: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        kid: &str,
+        private_key_pem: &str,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            kid: Set(kid.to_string()),
+            private_key_pem: Set(private_key_pem.to_string()),
+            active: Set(true),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn list(db: &DatabaseConnection) -> Result<Vec<Self>, DbErr> {
+        Entity::find().order_by_desc(Column::Id).all(db).await
+    }
+
+    /// Active keys, newest first.
+    pub async fn active(db: &DatabaseConnection) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::Active.eq(true))
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// The key that signs: the newest active one.
+    pub async fn signing(db: &DatabaseConnection) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::Active.eq(true))
+            .order_by_desc(Column::Id)
+            .one(db)
+            .await
+    }
+
+    pub async fn set_active(db: &DatabaseConnection, id: i64, active: bool) -> Result<Self, DbErr> {
+        let existing = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("LTI key not found".into()))?;
+        let mut am: ActiveModel = existing.into();
+        am.active = Set(active);
+        am.update(db).await
+    }
+}
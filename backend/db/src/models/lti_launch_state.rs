@@ -0,0 +1,88 @@
+//! OIDC login state for LTI 1.3 launches.
+//!
+//! The login initiation stores a random `state` and `nonce`; the launch that follows must
+//! present the same `state`, and the platform's id token must carry the same `nonce`. Each state
+//! is used once.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use serde::Serialize;
+
+/// How long the platform has to come back with the launch.
+pub const STATE_TTL_MINUTES: i64 = 10;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_launch_states")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub state: String,
+    pub nonce: String,
+    pub platform_id: i64,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::lti_platform::Entity",
+        from = "Column::PlatformId",
+        to = "super::lti_platform::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Platform,
+}
+
+impl Related<super::lti_platform::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Platform.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn random_token() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(32)
+        .map(char::from)
+        .collect()
+}
+
+impl Model {
+    /// Starts a login for `platform_id`, clearing out expired states on the way.
+    pub async fn create(db: &DatabaseConnection, platform_id: i64) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        Entity::delete_many()
+            .filter(Column::ExpiresAt.lt(now))
+            .exec(db)
+            .await?;
+        ActiveModel {
+            state: Set(random_token()),
+            nonce: Set(random_token()),
+            platform_id: Set(platform_id),
+            expires_at: Set(now + Duration::minutes(STATE_TTL_MINUTES)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// Removes and returns the unexpired login with `state`.
+    pub async fn take(db: &DatabaseConnection, state: &str) -> Result<Option<Self>, DbErr> {
+        let Some(found) = Entity::find()
+            .filter(Column::State.eq(state))
+            .one(db)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Entity::delete_by_id(found.id).exec(db).await?;
+        Ok((found.expires_at > Utc::now()).then_some(found))
+    }
+}
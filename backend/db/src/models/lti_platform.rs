@@ -0,0 +1,110 @@
+//! LTI 1.3 platforms: the LMS registrations allowed to launch FitchFork.
+//!
+//! A platform is identified by its `issuer` and the `client_id` it issued the tool; the
+//! `auth_login_url`, `auth_token_url` and `keyset_url` are the platform's OIDC authorization,
+//! OAuth2 token and JWKS endpoints.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_platforms")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub name: String,
+    pub issuer: String,
+    pub client_id: String,
+    pub auth_login_url: String,
+    pub auth_token_url: String,
+    pub keyset_url: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "super::lti_deployment::Entity")]
+    Deployments,
+}
+
+impl Related<super::lti_deployment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Deployments.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// The editable fields of a platform.
+#[derive(Debug, Clone)]
+pub struct PlatformFields<'a> {
+    pub name: &'a str,
+    pub issuer: &'a str,
+    pub client_id: &'a str,
+    pub auth_login_url: &'a str,
+    pub auth_token_url: &'a str,
+    pub keyset_url: &'a str,
+}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        fields: &PlatformFields<'_>,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        ActiveModel {
+            name: Set(fields.name.to_string()),
+            issuer: Set(fields.issuer.to_string()),
+            client_id: Set(fields.client_id.to_string()),
+            auth_login_url: Set(fields.auth_login_url.to_string()),
+            auth_token_url: Set(fields.auth_token_url.to_string()),
+            keyset_url: Set(fields.keyset_url.to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn update(
+        db: &DatabaseConnection,
+        id: i64,
+        fields: &PlatformFields<'_>,
+    ) -> Result<Self, DbErr> {
+        let existing = Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("LTI platform not found".into()))?;
+        let mut am: ActiveModel = existing.into();
+        am.name = Set(fields.name.to_string());
+        am.issuer = Set(fields.issuer.to_string());
+        am.client_id = Set(fields.client_id.to_string());
+        am.auth_login_url = Set(fields.auth_login_url.to_string());
+        am.auth_token_url = Set(fields.auth_token_url.to_string());
+        am.keyset_url = Set(fields.keyset_url.to_string());
+        am.updated_at = Set(Utc::now());
+        am.update(db).await
+    }
+
+    pub async fn list(db: &DatabaseConnection) -> Result<Vec<Self>, DbErr> {
+        Entity::find().order_by_asc(Column::Name).all(db).await
+    }
+
+    /// The platforms registered for `issuer`, narrowed to `client_id` when the platform sent
+    /// one.
+    pub async fn find_by_issuer(
+        db: &DatabaseConnection,
+        issuer: &str,
+        client_id: Option<&str>,
+    ) -> Result<Vec<Self>, DbErr> {
+        let mut select = Entity::find().filter(Column::Issuer.eq(issuer));
+        if let Some(client_id) = client_id {
+            select = select.filter(Column::ClientId.eq(client_id));
+        }
+        select.all(db).await
+    }
+}
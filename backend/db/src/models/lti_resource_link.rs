@@ -0,0 +1,124 @@
+//! LTI resource links: an LMS placement and the FitchFork module or assignment it opens.
+//!
+//! The module comes from the placement's course on its first launch (see
+//! [`super::lti_context`]), the assignment from its custom parameters, and both stick after
+//! that. `lineitem` is the Assignment and Grade Services line item the platform sent with the
+//! launch; marks for the assignment are posted to it.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_resource_links")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub platform_id: i64,
+    pub resource_link_id: String,
+    pub context_id: Option<String>,
+    pub module_id: i64,
+    pub assignment_id: Option<i64>,
+    pub lineitem: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::lti_platform::Entity",
+        from = "Column::PlatformId",
+        to = "super::lti_platform::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Platform,
+    #[sea_orm(
+        belongs_to = "super::module::Entity",
+        from = "Column::ModuleId",
+        to = "super::module::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Module,
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+}
+
+impl Related<super::lti_platform::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Platform.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn find(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        resource_link_id: &str,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .filter(Column::ResourceLinkId.eq(resource_link_id))
+            .one(db)
+            .await
+    }
+
+    /// Records a launch of the placement: creates the link on its first launch, and afterwards
+    /// only refreshes the line item, which platforms may move.
+    pub async fn record_launch(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        resource_link_id: &str,
+        context_id: Option<&str>,
+        module_id: i64,
+        assignment_id: Option<i64>,
+        lineitem: Option<&str>,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        if let Some(existing) = Self::find(db, platform_id, resource_link_id).await? {
+            if lineitem.is_none() || existing.lineitem.as_deref() == lineitem {
+                return Ok(existing);
+            }
+            let mut am: ActiveModel = existing.into();
+            am.lineitem = Set(lineitem.map(str::to_owned));
+            am.updated_at = Set(now);
+            return am.update(db).await;
+        }
+        ActiveModel {
+            platform_id: Set(platform_id),
+            resource_link_id: Set(resource_link_id.to_string()),
+            context_id: Set(context_id.map(str::to_owned)),
+            module_id: Set(module_id),
+            assignment_id: Set(assignment_id),
+            lineitem: Set(lineitem.map(str::to_owned)),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// Placements of the assignment that have a line item to post marks to.
+    pub async fn graded_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .filter(Column::Lineitem.is_not_null())
+            .all(db)
+            .await
+    }
+}
@@ -0,0 +1,89 @@
+//! The FitchFork user behind an LTI platform's user id (`sub`).
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "lti_user_links")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub platform_id: i64,
+    pub subject: String,
+    pub user_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::lti_platform::Entity",
+        from = "Column::PlatformId",
+        to = "super::lti_platform::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Platform,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        subject: &str,
+        user_id: i64,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            platform_id: Set(platform_id),
+            subject: Set(subject.to_string()),
+            user_id: Set(user_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn find(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        subject: &str,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .filter(Column::Subject.eq(subject))
+            .one(db)
+            .await
+    }
+
+    /// The user's link on `platform_id`, if they have launched from it.
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        platform_id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::PlatformId.eq(platform_id))
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await
+    }
+}
@@ -1,5 +1,7 @@
 pub mod announcements;
+pub mod api_token;
 pub mod assignment;
+pub mod assignment_extension;
 pub mod assignment_file;
 pub mod assignment_interpreter;
 pub mod assignment_memo_output;
@@ -9,18 +11,56 @@ pub mod assignment_submission_output;
 pub mod assignment_task;
 pub mod attendance_record;
 pub mod attendance_session;
+pub mod calendar_feed_token;
+pub mod content_blob;
+pub mod email_delivery;
+pub mod email_setting;
+pub mod exam_session;
+pub mod ga_generation;
+pub mod ga_run;
+pub mod grade_export_template;
+pub mod group;
+pub mod group_member;
+pub mod lti_context;
+pub mod lti_deployment;
+pub mod lti_key;
+pub mod lti_launch_state;
+pub mod lti_platform;
+pub mod lti_resource_link;
+pub mod lti_user_link;
+pub mod moderation;
+pub mod moderation_sample;
 pub mod module;
 pub mod moss_report;
+pub mod notification;
+pub mod notification_preference;
 pub mod password_reset_token;
 pub mod plagiarism_case;
+pub mod plagiarism_case_decision;
+pub mod plagiarism_case_evidence;
+pub mod plagiarism_match;
+pub mod plagiarism_report;
+pub mod regrade_request;
+pub mod rubric;
+pub mod rubric_criterion;
+pub mod rubric_score;
+pub mod sso_identity;
+pub mod sso_login_state;
 pub mod system_metric;
+pub mod system_setting;
 pub mod ticket_messages;
 pub mod tickets;
+pub mod two_factor_backup_code;
+pub mod upload_part;
+pub mod upload_session;
 pub mod user;
 pub mod user_module_role;
+pub mod user_two_factor;
 
 pub use announcements::Entity as Announcements;
+pub use api_token::Entity as ApiToken;
 pub use assignment::Entity as Assignment;
+pub use assignment_extension::Entity as AssignmentExtension;
 pub use assignment_file::Entity as AssignmentFile;
 pub use assignment_interpreter::Entity as AssignmentInterpreter;
 pub use assignment_memo_output::Entity as AssignmentMemoOutput;
@@ -30,11 +70,47 @@ pub use assignment_submission_output::Entity as AssignmentSubmissionOutput;
 pub use assignment_task::Entity as AssignmentTask;
 pub use attendance_record::Entity as AttendanceRecord;
 pub use attendance_session::Entity as AttendanceSession;
+pub use calendar_feed_token::Entity as CalendarFeedToken;
+pub use content_blob::Entity as ContentBlob;
+pub use email_delivery::Entity as EmailDelivery;
+pub use email_setting::Entity as EmailSetting;
+pub use exam_session::Entity as ExamSession;
+pub use ga_generation::Entity as GaGeneration;
+pub use ga_run::Entity as GaRun;
+pub use grade_export_template::Entity as GradeExportTemplate;
+pub use group::Entity as Group;
+pub use group_member::Entity as GroupMember;
+pub use lti_context::Entity as LtiContext;
+pub use lti_deployment::Entity as LtiDeployment;
+pub use lti_key::Entity as LtiKey;
+pub use lti_launch_state::Entity as LtiLaunchState;
+pub use lti_platform::Entity as LtiPlatform;
+pub use lti_resource_link::Entity as LtiResourceLink;
+pub use lti_user_link::Entity as LtiUserLink;
+pub use moderation::Entity as Moderation;
+pub use moderation_sample::Entity as ModerationSample;
 pub use module::Entity as Module;
+pub use notification::Entity as Notification;
+pub use notification_preference::Entity as NotificationPreference;
 pub use password_reset_token::Entity as PasswordResetToken;
 pub use plagiarism_case::Entity as PlagiarismCase;
+pub use plagiarism_case_decision::Entity as PlagiarismCaseDecision;
+pub use plagiarism_case_evidence::Entity as PlagiarismCaseEvidence;
+pub use plagiarism_match::Entity as PlagiarismMatch;
+pub use plagiarism_report::Entity as PlagiarismReport;
+pub use regrade_request::Entity as RegradeRequest;
+pub use rubric::Entity as Rubric;
+pub use rubric_criterion::Entity as RubricCriterion;
+pub use rubric_score::Entity as RubricScore;
+pub use sso_identity::Entity as SsoIdentity;
+pub use sso_login_state::Entity as SsoLoginState;
 pub use system_metric::Entity as SystemMetric;
+pub use system_setting::Entity as SystemSetting;
 pub use ticket_messages::Entity as TicketMessages;
 pub use tickets::Entity as Tickets;
+pub use two_factor_backup_code::Entity as TwoFactorBackupCode;
+pub use upload_part::Entity as UploadPart;
+pub use upload_session::Entity as UploadSession;
 pub use user::Entity as User;
 pub use user_module_role::Entity as UserModuleRole;
+pub use user_two_factor::Entity as UserTwoFactor;
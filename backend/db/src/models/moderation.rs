@@ -0,0 +1,112 @@
+//! Moderation sign-off: once a lecturer signs off an assignment's moderation (see
+//! [`super::moderation_sample`]), its grades are final and may not change until it is reopened.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "moderations")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub assignment_id: i64,
+    /// The lecturer who signed off; `None` while open, or once they are deleted.
+    pub signed_off_by: Option<i64>,
+    /// Set while the assignment's grades are locked.
+    pub signed_off_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::SignedOffBy",
+        to = "super::user::Column::Id",
+        on_delete = "SetNull"
+    )]
+    SignedOffBy,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .one(db)
+            .await
+    }
+
+    /// Whether the assignment's grades are locked by a moderation sign-off.
+    pub async fn is_locked(db: &DatabaseConnection, assignment_id: i64) -> Result<bool, DbErr> {
+        Ok(Self::for_assignment(db, assignment_id)
+            .await?
+            .is_some_and(|m| m.signed_off_at.is_some()))
+    }
+
+    /// Signs off the assignment's moderation, locking its grades.
+    pub async fn sign_off(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: i64,
+    ) -> Result<Self, DbErr> {
+        Self::set_signed_off(db, assignment_id, Some(user_id)).await
+    }
+
+    /// Withdraws the sign-off so grades can change again.
+    pub async fn reopen(db: &DatabaseConnection, assignment_id: i64) -> Result<Self, DbErr> {
+        Self::set_signed_off(db, assignment_id, None).await
+    }
+
+    async fn set_signed_off(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: Option<i64>,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        let signed_off_at = user_id.map(|_| now);
+        match Self::for_assignment(db, assignment_id).await? {
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.signed_off_by = Set(user_id);
+                am.signed_off_at = Set(signed_off_at);
+                am.updated_at = Set(now);
+                am.update(db).await
+            }
+            None => {
+                ActiveModel {
+                    assignment_id: Set(assignment_id),
+                    signed_off_by: Set(user_id),
+                    signed_off_at: Set(signed_off_at),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! Moderation samples: students' counted submissions picked for a second marker to mark
+//! independently, so the first marking can be checked against it before grades are signed off
+//! (see [`super::moderation`]).
+//!
+//! Marks are final percentages. `first_mark` is the grade the submission counted for when it
+//! was sampled, and `second_mark` the second marker's, once recorded.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel, QueryOrder};
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "moderation_samples")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub assignment_id: i64,
+    /// The student whose grade is being moderated.
+    pub user_id: i64,
+    pub submission_id: i64,
+    /// The second marker; `None` once they are deleted.
+    pub marker_id: Option<i64>,
+    pub first_mark: f64,
+    pub second_mark: Option<f64>,
+    pub comment: Option<String>,
+    pub marked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+
+    #[sea_orm(
+        belongs_to = "super::assignment_submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::assignment_submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::MarkerId",
+        to = "super::user::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Marker,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::assignment_submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// A student's counted grade, as a candidate for the sample.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleCandidate {
+    pub user_id: i64,
+    pub submission_id: i64,
+    pub mark: f64,
+}
+
+impl Model {
+    /// The assignment's samples in the order they were taken.
+    pub async fn list_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+
+    /// Adds `candidates` to the sample for `marker_id` to mark. Students already in the sample
+    /// are left as they are.
+    pub async fn add(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        marker_id: i64,
+        candidates: &[SampleCandidate],
+    ) -> Result<Vec<Self>, DbErr> {
+        let sampled: HashSet<i64> = Self::list_for_assignment(db, assignment_id)
+            .await?
+            .into_iter()
+            .map(|s| s.user_id)
+            .collect();
+        let now = Utc::now();
+        let mut added = Vec::new();
+        for c in candidates.iter().filter(|c| !sampled.contains(&c.user_id)) {
+            added.push(
+                ActiveModel {
+                    assignment_id: Set(assignment_id),
+                    user_id: Set(c.user_id),
+                    submission_id: Set(c.submission_id),
+                    marker_id: Set(Some(marker_id)),
+                    first_mark: Set(c.mark),
+                    created_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await?,
+            );
+        }
+        Ok(added)
+    }
+
+    /// Records the second marker's mark, replacing any earlier one.
+    pub async fn record(
+        self,
+        db: &DatabaseConnection,
+        mark: f64,
+        comment: Option<&str>,
+    ) -> Result<Self, DbErr> {
+        let mut am = self.into_active_model();
+        am.second_mark = Set(Some(mark));
+        am.comment = Set(comment.map(str::to_owned));
+        am.marked_at = Set(Some(Utc::now()));
+        am.update(db).await
+    }
+
+    /// Second mark minus first, once the second marker has marked.
+    pub fn difference(&self) -> Option<f64> {
+        self.second_mark.map(|m| m - self.first_mark)
+    }
+}
+
+/// `size` candidates spread evenly over the range of marks: the candidates are ordered by mark
+/// and split into `size` equal bands, and the middle of each band is picked.
+pub fn spread_sample(mut candidates: Vec<SampleCandidate>, size: usize) -> Vec<SampleCandidate> {
+    let n = candidates.len();
+    let size = size.min(n);
+    if size == 0 {
+        return Vec::new();
+    }
+    candidates.sort_by(|a, b| a.mark.total_cmp(&b.mark).then(a.user_id.cmp(&b.user_id)));
+    (0..size)
+        .map(|k| candidates[(2 * k + 1) * n / (2 * size)].clone())
+        .collect()
+}
+
+/// How far the second marks are from the first, over the samples marked so far.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiscrepancyStats {
+    pub sampled: usize,
+    pub marked: usize,
+    /// Mean of second minus first mark: positive when the second marker is more generous.
+    pub mean_difference: f64,
+    pub mean_absolute_difference: f64,
+    pub max_absolute_difference: f64,
+    /// Standard deviation of the differences.
+    pub std_dev: f64,
+    /// The percentage points two marks may differ by and still agree.
+    pub tolerance: f64,
+    /// Samples whose marks differ by more than `tolerance`.
+    pub outside_tolerance: usize,
+}
+
+impl DiscrepancyStats {
+    pub fn compute(samples: &[Model], tolerance: f64) -> Self {
+        let diffs: Vec<f64> = samples.iter().filter_map(Model::difference).collect();
+        let marked = diffs.len();
+        let mean = |values: Vec<f64>| {
+            if values.is_empty() {
+                0.0
+            } else {
+                values.iter().sum::<f64>() / values.len() as f64
+            }
+        };
+        let mean_difference = mean(diffs.clone());
+        Self {
+            sampled: samples.len(),
+            marked,
+            mean_difference,
+            mean_absolute_difference: mean(diffs.iter().map(|d| d.abs()).collect()),
+            max_absolute_difference: diffs.iter().map(|d| d.abs()).fold(0.0, f64::max),
+            std_dev: mean(
+                diffs
+                    .iter()
+                    .map(|d| (d - mean_difference).powi(2))
+                    .collect(),
+            )
+            .sqrt(),
+            tolerance,
+            outside_tolerance: diffs.iter().filter(|d| d.abs() > tolerance).count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(user_id: i64, mark: f64) -> SampleCandidate {
+        SampleCandidate {
+            user_id,
+            submission_id: user_id * 10,
+            mark,
+        }
+    }
+
+    fn sample(first_mark: f64, second_mark: Option<f64>) -> Model {
+        Model {
+            id: 0,
+            assignment_id: 1,
+            user_id: 1,
+            submission_id: 1,
+            marker_id: None,
+            first_mark,
+            second_mark,
+            comment: None,
+            marked_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn sample_is_spread_over_the_range_of_marks() {
+        let candidates: Vec<_> = (1..=9)
+            .map(|i| candidate(i, (10 - i) as f64 * 10.0))
+            .collect();
+        let picked: Vec<f64> = spread_sample(candidates.clone(), 3)
+            .iter()
+            .map(|c| c.mark)
+            .collect();
+        assert_eq!(picked, [20.0, 50.0, 80.0]);
+        assert_eq!(spread_sample(candidates.clone(), 20).len(), 9);
+        assert!(spread_sample(candidates, 0).is_empty());
+    }
+
+    #[test]
+    fn discrepancies_count_only_marked_samples() {
+        let samples = [
+            sample(60.0, Some(70.0)),
+            sample(80.0, Some(78.0)),
+            sample(50.0, None),
+        ];
+        let stats = DiscrepancyStats::compute(&samples, 5.0);
+        assert_eq!((stats.sampled, stats.marked), (3, 2));
+        assert_eq!(stats.mean_difference, 4.0);
+        assert_eq!(stats.mean_absolute_difference, 6.0);
+        assert_eq!(stats.max_absolute_difference, 10.0);
+        assert_eq!(stats.std_dev, 6.0);
+        assert_eq!(stats.outside_tolerance, 1);
+    }
+}
@@ -94,6 +94,11 @@ impl Model {
         Entity::delete_by_id(self.id).exec(db).await?;
         info!("Deleted module {}", self.id);
 
+        // Step 4: Drop blobs only the module's files and submissions used
+        if let Err(e) = super::content_blob::Model::prune(db).await {
+            warn!("Failed to prune content blobs: {}", e);
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,238 @@
+//! In-app notifications ("your submission was marked", "new announcement", ...).
+//!
+//! Rows are only written for users who want the in-app channel for that kind; see
+//! [`super::notification_preference`].
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, IntoActiveModel, PaginatorTrait, QueryFilter,
+    QueryOrder,
+};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "notifications")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Recipient.
+    pub user_id: i64,
+    pub kind: NotificationKind,
+    pub title: String,
+    pub body: String,
+    /// Frontend path the notification points at, e.g. `/modules/1/assignments/2`.
+    pub link: Option<String>,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The events users can be notified about.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    EnumIter,
+    DeriveActiveEnum,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationKind {
+    /// One of the user's submissions finished marking.
+    #[sea_orm(string_value = "submission_marked")]
+    SubmissionMarked,
+    /// An announcement was posted in one of the user's modules.
+    #[sea_orm(string_value = "announcement_posted")]
+    AnnouncementPosted,
+    /// Someone else replied on one of the user's tickets.
+    #[sea_orm(string_value = "ticket_replied")]
+    TicketReplied,
+    /// Staff responded to one of the user's regrade requests.
+    #[sea_orm(string_value = "regrade_updated")]
+    RegradeUpdated,
+    /// A plagiarism case involving one of the user's submissions reached a new stage.
+    #[sea_orm(string_value = "plagiarism_case_updated")]
+    PlagiarismCaseUpdated,
+    /// An open ticket in one of the user's modules went unanswered past the escalation window.
+    #[sea_orm(string_value = "ticket_escalated")]
+    TicketEscalated,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        user_id: i64,
+        kind: NotificationKind,
+        title: &str,
+        body: &str,
+        link: Option<&str>,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            user_id: Set(user_id),
+            kind: Set(kind),
+            title: Set(title.to_owned()),
+            body: Set(body.to_owned()),
+            link: Set(link.map(str::to_owned)),
+            read_at: Set(None),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// One page (1-based) of the user's notifications, newest first, with the total matching.
+    pub async fn list_for_user(
+        db: &DatabaseConnection,
+        user_id: i64,
+        unread_only: bool,
+        page: u64,
+        per_page: u64,
+    ) -> Result<(Vec<Self>, u64), DbErr> {
+        let mut query = Entity::find().filter(Column::UserId.eq(user_id));
+        if unread_only {
+            query = query.filter(Column::ReadAt.is_null());
+        }
+        let paginator = query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .paginate(db, per_page.max(1));
+        let total = paginator.num_items().await?;
+        let items = paginator.fetch_page(page.saturating_sub(1)).await?;
+        Ok((items, total))
+    }
+
+    pub async fn unread_count(db: &DatabaseConnection, user_id: i64) -> Result<u64, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ReadAt.is_null())
+            .count(db)
+            .await
+    }
+
+    /// Marks one of the user's notifications as read. Already-read notifications keep their
+    /// original `read_at`.
+    pub async fn mark_read(db: &DatabaseConnection, user_id: i64, id: i64) -> Result<Self, DbErr> {
+        let notification = Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Notification not found".into()))?;
+        if notification.read_at.is_some() {
+            return Ok(notification);
+        }
+        let mut am = notification.into_active_model();
+        am.read_at = Set(Some(Utc::now()));
+        am.update(db).await
+    }
+
+    /// Marks all of the user's unread notifications as read. Returns how many were updated.
+    pub async fn mark_all_read(db: &DatabaseConnection, user_id: i64) -> Result<u64, DbErr> {
+        Ok(Entity::update_many()
+            .col_expr(Column::ReadAt, Expr::value(Utc::now()))
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ReadAt.is_null())
+            .exec(db)
+            .await?
+            .rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn lists_newest_first_and_tracks_read_state() {
+        let db = setup_test_db().await;
+        let alice = user::Model::create(&db, "alice", "a@test.com", "pw", false)
+            .await
+            .unwrap();
+        let bob = user::Model::create(&db, "bob", "b@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let first = Model::create(
+            &db,
+            alice.id,
+            NotificationKind::AnnouncementPosted,
+            "First",
+            "b",
+            None,
+        )
+        .await
+        .unwrap();
+        let second = Model::create(
+            &db,
+            alice.id,
+            NotificationKind::SubmissionMarked,
+            "Second",
+            "b",
+            Some("/modules/1/assignments/1"),
+        )
+        .await
+        .unwrap();
+        Model::create(&db, bob.id, NotificationKind::TicketReplied, "Bob's", "b", None)
+            .await
+            .unwrap();
+
+        let (items, total) = Model::list_for_user(&db, alice.id, false, 1, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(
+            items.iter().map(|n| n.id).collect::<Vec<_>>(),
+            [second.id, first.id]
+        );
+        assert_eq!(Model::unread_count(&db, alice.id).await.unwrap(), 2);
+
+        // Bob can't read Alice's notifications
+        assert!(matches!(
+            Model::mark_read(&db, bob.id, first.id).await,
+            Err(DbErr::RecordNotFound(_))
+        ));
+
+        let read = Model::mark_read(&db, alice.id, first.id).await.unwrap();
+        assert!(read.read_at.is_some());
+        let (unread, total) = Model::list_for_user(&db, alice.id, true, 1, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(unread[0].id, second.id);
+
+        assert_eq!(Model::mark_all_read(&db, alice.id).await.unwrap(), 1);
+        assert_eq!(Model::unread_count(&db, alice.id).await.unwrap(), 0);
+        assert_eq!(Model::unread_count(&db, bob.id).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,180 @@
+//! Per-user delivery channels for each [`NotificationKind`].
+//!
+//! Only explicitly saved preferences have a row; everything else falls back to
+//! [`Model::default_for`] (in-app on; email on only for announcements and ticket replies).
+
+use super::notification::NotificationKind;
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel, Iterable, QueryFilter};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "notification_preferences")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub kind: NotificationKind,
+    /// Store a notification and push it over the user's WebSocket topic.
+    pub in_app: bool,
+    /// Also send an email.
+    pub email: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The preference used when the user hasn't saved one for `kind`.
+    pub fn default_for(user_id: i64, kind: NotificationKind) -> Self {
+        Self {
+            user_id,
+            kind,
+            in_app: true,
+            email: matches!(
+                kind,
+                NotificationKind::AnnouncementPosted | NotificationKind::TicketReplied
+            ),
+        }
+    }
+
+    /// The user's preference for every kind, defaults included, in [`NotificationKind`] order.
+    pub async fn for_user(db: &DatabaseConnection, user_id: i64) -> Result<Vec<Self>, DbErr> {
+        let saved = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .all(db)
+            .await?;
+        Ok(NotificationKind::iter()
+            .map(|kind| {
+                saved
+                    .iter()
+                    .find(|p| p.kind == kind)
+                    .cloned()
+                    .unwrap_or_else(|| Self::default_for(user_id, kind))
+            })
+            .collect())
+    }
+
+    /// The preference for `kind` of each of `user_ids`, defaults included, in the same order.
+    pub async fn for_users(
+        db: &DatabaseConnection,
+        user_ids: &[i64],
+        kind: NotificationKind,
+    ) -> Result<Vec<Self>, DbErr> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let saved = Entity::find()
+            .filter(Column::UserId.is_in(user_ids.iter().copied()))
+            .filter(Column::Kind.eq(kind))
+            .all(db)
+            .await?;
+        Ok(user_ids
+            .iter()
+            .map(|&user_id| {
+                saved
+                    .iter()
+                    .find(|p| p.user_id == user_id)
+                    .cloned()
+                    .unwrap_or_else(|| Self::default_for(user_id, kind))
+            })
+            .collect())
+    }
+
+    /// Saves the user's channels for `kind`.
+    pub async fn set(
+        db: &DatabaseConnection,
+        user_id: i64,
+        kind: NotificationKind,
+        in_app: bool,
+        email: bool,
+    ) -> Result<Self, DbErr> {
+        match Entity::find_by_id((user_id, kind)).one(db).await? {
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.in_app = Set(in_app);
+                am.email = Set(email);
+                am.update(db).await
+            }
+            None => {
+                ActiveModel {
+                    user_id: Set(user_id),
+                    kind: Set(kind),
+                    in_app: Set(in_app),
+                    email: Set(email),
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn missing_preferences_fall_back_to_defaults() {
+        let db = setup_test_db().await;
+        let alice = user::Model::create(&db, "alice", "a@test.com", "pw", false)
+            .await
+            .unwrap();
+        let bob = user::Model::create(&db, "bob", "b@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        Model::set(&db, alice.id, NotificationKind::TicketReplied, false, true)
+            .await
+            .unwrap();
+        // Saving again updates rather than duplicating
+        Model::set(&db, alice.id, NotificationKind::TicketReplied, true, true)
+            .await
+            .unwrap();
+
+        let prefs = Model::for_user(&db, alice.id).await.unwrap();
+        assert_eq!(prefs.len(), NotificationKind::iter().count());
+        let ticket = prefs
+            .iter()
+            .find(|p| p.kind == NotificationKind::TicketReplied)
+            .unwrap();
+        assert!(ticket.in_app && ticket.email);
+        let marked = prefs
+            .iter()
+            .find(|p| p.kind == NotificationKind::SubmissionMarked)
+            .unwrap();
+        assert_eq!(
+            *marked,
+            Model::default_for(alice.id, NotificationKind::SubmissionMarked)
+        );
+
+        let both = Model::for_users(&db, &[bob.id, alice.id], NotificationKind::TicketReplied)
+            .await
+            .unwrap();
+        assert_eq!(
+            both,
+            [
+                Model::default_for(bob.id, NotificationKind::TicketReplied),
+                ticket.clone()
+            ]
+        );
+    }
+}
@@ -1,8 +1,14 @@
 //! Entity and business logic for managing plagiarism cases.
 
+use super::plagiarism_case_decision::{ActiveModel as DecisionActiveModel, Model as DecisionModel};
+use super::plagiarism_case_evidence::{Column as EvidenceColumn, Entity as EvidenceEntity};
 use chrono::{DateTime, Utc};
 use sea_orm::entity::prelude::*;
-use sea_orm::{ActiveValue::Set, DatabaseConnection, DbErr, EntityTrait};
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, DbErr, EntityTrait, IntoActiveModel, PaginatorTrait,
+    TransactionTrait,
+};
+use util::execution_config::{PlagiarismOptions, PlagiarismStage};
 
 /// Represents a detected plagiarism case between two submissions.
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
@@ -37,6 +43,16 @@ pub struct Model {
     /// Total lines matched across the pair (parsed from MOSS).
     pub lines_matched: i64,
 
+    /// True when one side is a submission from an archive corpus (another assignment or an
+    /// earlier semester) that the run compared against.
+    pub historical: bool,
+
+    /// Where the case is in the misconduct process; `None` until it is reported.
+    pub stage: Option<Stage>,
+
+    /// How the case was resolved; set once `stage` is [`Stage::Resolved`].
+    pub outcome: Option<Outcome>,
+
     /// Timestamp when the case was created.
     pub created_at: DateTime<Utc>,
 
@@ -69,6 +85,147 @@ pub enum Status {
     Reviewed,
 }
 
+/// The stages of a case's misconduct process, in order. A case is reported, may be taken to an
+/// interview with the students and then escalated, and is resolved from any stage.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    sea_orm::strum::Display,
+    sea_orm::strum::EnumString,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Stage {
+    /// Formally reported as suspected misconduct.
+    #[sea_orm(string_value = "reported")]
+    Reported,
+    /// The students have been interviewed.
+    #[sea_orm(string_value = "interviewed")]
+    Interviewed,
+    /// Referred beyond the module (e.g. to a faculty committee).
+    #[sea_orm(string_value = "escalated")]
+    Escalated,
+    /// Decided; see the case's `outcome`.
+    #[sea_orm(string_value = "resolved")]
+    Resolved,
+}
+
+impl Stage {
+    /// Whether a case at `from` (`None` before it is reported) may move to this stage.
+    pub fn follows(self, from: Option<Stage>) -> bool {
+        matches!(
+            (from, self),
+            (None, Stage::Reported)
+                | (Some(Stage::Reported), Stage::Interviewed)
+                | (Some(Stage::Interviewed), Stage::Escalated)
+                | (
+                    Some(Stage::Reported | Stage::Interviewed | Stage::Escalated),
+                    Stage::Resolved
+                )
+        )
+    }
+
+    /// Whether moving to this stage needs evidence uploaded at the stage being left. A case can
+    /// be resolved without new evidence, e.g. when it is dropped after reporting.
+    pub fn requires_evidence(self) -> bool {
+        self != Stage::Resolved
+    }
+
+    /// Whether the assignment's `plagiarism.notify_students_at` includes this stage.
+    pub fn notifies_students(self, options: &PlagiarismOptions) -> bool {
+        let stage = match self {
+            Stage::Reported => PlagiarismStage::Reported,
+            Stage::Interviewed => PlagiarismStage::Interviewed,
+            Stage::Escalated => PlagiarismStage::Escalated,
+            Stage::Resolved => PlagiarismStage::Resolved,
+        };
+        options.notify_students_at.contains(&stage)
+    }
+}
+
+/// How a resolved case ended.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    sea_orm::strum::Display,
+    sea_orm::strum::EnumString,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum Outcome {
+    /// No misconduct was found.
+    #[sea_orm(string_value = "cleared")]
+    Cleared,
+    /// A formal warning, without a penalty.
+    #[sea_orm(string_value = "warning")]
+    Warning,
+    /// The students' marks were penalised.
+    #[sea_orm(string_value = "mark_penalty")]
+    MarkPenalty,
+    /// Handed to the university's disciplinary process.
+    #[sea_orm(string_value = "disciplinary")]
+    Disciplinary,
+}
+
+/// Why a case could not move to another stage.
+#[derive(Debug)]
+pub enum TransitionError {
+    /// The stage does not follow the case's current one.
+    InvalidTransition {
+        from: Option<Stage>,
+        to: Stage,
+    },
+    /// No evidence was uploaded while the case was at its current stage.
+    EvidenceRequired,
+    /// Resolving needs an outcome, and only resolving takes one.
+    Outcome,
+    Database(DbErr),
+}
+
+impl From<DbErr> for TransitionError {
+    fn from(value: DbErr) -> Self {
+        TransitionError::Database(value)
+    }
+}
+
+impl std::fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionError::InvalidTransition { from: None, to } => {
+                write!(f, "A case that has not been reported cannot be {to}")
+            }
+            TransitionError::InvalidTransition {
+                from: Some(from),
+                to,
+            } => {
+                write!(f, "A {from} case cannot be {to}")
+            }
+            TransitionError::EvidenceRequired => {
+                write!(f, "Upload evidence for the current stage first")
+            }
+            TransitionError::Outcome => {
+                write!(
+                    f,
+                    "An outcome is required when, and only when, resolving a case"
+                )
+            }
+            TransitionError::Database(e) => write!(f, "Database error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
 /// Defines relationships to assignment submissions and the report.
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
@@ -93,6 +250,24 @@ pub enum Relation {
         // no cascade here; the FK behavior is enforced in the migration (SET NULL)
     )]
     Report,
+
+    #[sea_orm(has_many = "super::plagiarism_case_evidence::Entity")]
+    Evidence,
+
+    #[sea_orm(has_many = "super::plagiarism_case_decision::Entity")]
+    Decisions,
+}
+
+impl Related<super::plagiarism_case_evidence::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Evidence.def()
+    }
+}
+
+impl Related<super::plagiarism_case_decision::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Decisions.def()
+    }
 }
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -135,4 +310,78 @@ impl Model {
         };
         active.insert(db).await
     }
+
+    /// Marks a case as historical (see `Model::historical`).
+    pub async fn mark_historical(db: &DatabaseConnection, case_id: i64) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::Historical, Expr::value(true))
+            .filter(Column::Id.eq(case_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the case is in an unfinished misconduct process (reported but not resolved).
+    pub fn in_process(&self) -> bool {
+        self.stage.is_some_and(|s| s != Stage::Resolved)
+    }
+
+    /// Moves the case to `to` and records the decision, in one transaction.
+    ///
+    /// The stage must follow the current one (see [`Stage::follows`]); unless resolving, evidence
+    /// must have been uploaded while the case was at its current stage; and `outcome` must be
+    /// given exactly when resolving.
+    pub async fn transition(
+        self,
+        db: &DatabaseConnection,
+        to: Stage,
+        decision: &str,
+        outcome: Option<Outcome>,
+        decided_by: i64,
+    ) -> Result<(Self, DecisionModel), TransitionError> {
+        let from = self.stage;
+        if !to.follows(from) {
+            return Err(TransitionError::InvalidTransition { from, to });
+        }
+        if (to == Stage::Resolved) != outcome.is_some() {
+            return Err(TransitionError::Outcome);
+        }
+        if to.requires_evidence() {
+            let at_stage = match from {
+                Some(stage) => EvidenceColumn::Stage.eq(stage),
+                None => EvidenceColumn::Stage.is_null(),
+            };
+            let evidence = EvidenceEntity::find()
+                .filter(EvidenceColumn::CaseId.eq(self.id))
+                .filter(at_stage)
+                .count(db)
+                .await?;
+            if evidence == 0 {
+                return Err(TransitionError::EvidenceRequired);
+            }
+        }
+
+        let txn = db.begin().await?;
+        let now = Utc::now();
+        let case_id = self.id;
+        let mut active = self.into_active_model();
+        active.stage = Set(Some(to));
+        active.outcome = Set(outcome);
+        active.updated_at = Set(now);
+        let case = active.update(&txn).await?;
+        let record = DecisionActiveModel {
+            case_id: Set(case_id),
+            from_stage: Set(from),
+            to_stage: Set(to),
+            decision: Set(decision.to_string()),
+            outcome: Set(outcome),
+            decided_by: Set(Some(decided_by)),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok((case, record))
+    }
 }
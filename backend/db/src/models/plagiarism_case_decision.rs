@@ -0,0 +1,63 @@
+//! Decision records of plagiarism cases: one per stage change of a case's misconduct process
+//! (see [`super::plagiarism_case::Model::transition`]), with who made it and why.
+
+use super::plagiarism_case::{Outcome, Stage};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{DatabaseConnection, QueryOrder};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "plagiarism_case_decisions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub case_id: i64,
+    /// The stage the case left; `None` when it was reported.
+    pub from_stage: Option<Stage>,
+    pub to_stage: Stage,
+    /// The reasons for the decision.
+    pub decision: String,
+    /// Set on the record that resolved the case.
+    pub outcome: Option<Outcome>,
+    /// `None` once the deciding user is deleted.
+    pub decided_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plagiarism_case::Entity",
+        from = "Column::CaseId",
+        to = "super::plagiarism_case::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Case,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::DecidedBy",
+        to = "super::user::Column::Id",
+        on_delete = "SetNull"
+    )]
+    DecidedBy,
+}
+
+impl Related<super::plagiarism_case::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Case.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The case's decisions, oldest first.
+    pub async fn list_for_case(db: &DatabaseConnection, case_id: i64) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::CaseId.eq(case_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+}
@@ -0,0 +1,115 @@
+//! Evidence attached to plagiarism cases: files (interview notes, annotated code, letters)
+//! stored under the assignment's `plagiarism_evidence` directory.
+//!
+//! Each file is tagged with the stage the case was at when it was uploaded, since moving a case
+//! on needs evidence from its current stage (see [`super::plagiarism_case::Model::transition`]).
+
+use super::plagiarism_case::{Model as CaseModel, Stage};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use std::path::Path;
+use util::paths::plagiarism_evidence_dir;
+use util::storage::{key_for, storage};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "plagiarism_case_evidence")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub case_id: i64,
+    /// The case's stage at upload; `None` before it was reported.
+    pub stage: Option<Stage>,
+    /// The uploaded file name.
+    pub filename: String,
+    /// Storage key of the stored file.
+    pub path: String,
+    /// Size in bytes.
+    pub size: i64,
+    /// `None` once the uploader is deleted.
+    pub uploaded_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plagiarism_case::Entity",
+        from = "Column::CaseId",
+        to = "super::plagiarism_case::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Case,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UploadedBy",
+        to = "super::user::Column::Id",
+        on_delete = "SetNull"
+    )]
+    UploadedBy,
+}
+
+impl Related<super::plagiarism_case::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Case.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Stores `bytes` as evidence for `case` at its current stage.
+    pub async fn save_file(
+        db: &DatabaseConnection,
+        module_id: i64,
+        case: &CaseModel,
+        filename: &str,
+        bytes: &[u8],
+        uploaded_by: i64,
+    ) -> Result<Self, DbErr> {
+        let inserted = ActiveModel {
+            case_id: Set(case.id),
+            stage: Set(case.stage),
+            filename: Set(filename.to_string()),
+            path: Set(String::new()),
+            size: Set(bytes.len() as i64),
+            uploaded_by: Set(Some(uploaded_by)),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        // Stored by id, so uploads with the same name never collide
+        let stored_filename = match Path::new(filename).extension() {
+            Some(ext) => format!("{}.{}", inserted.id, ext.to_string_lossy()),
+            None => inserted.id.to_string(),
+        };
+        let key = key_for(
+            &plagiarism_evidence_dir(module_id, case.assignment_id, case.id).join(stored_filename),
+        );
+        if let Err(e) = storage().write(&key, bytes).await {
+            Entity::delete_by_id(inserted.id).exec(db).await?;
+            return Err(DbErr::Custom(format!("Failed to write file: {e}")));
+        }
+
+        let mut active: ActiveModel = inserted.into();
+        active.path = Set(key);
+        active.update(db).await
+    }
+
+    /// Loads the file contents from storage.
+    pub async fn load_file(&self) -> Result<Vec<u8>, std::io::Error> {
+        storage().read(&self.path).await
+    }
+
+    /// The case's evidence, oldest first.
+    pub async fn list_for_case(db: &DatabaseConnection, case_id: i64) -> Result<Vec<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::CaseId.eq(case_id))
+            .order_by_asc(Column::Id)
+            .all(db)
+            .await
+    }
+}
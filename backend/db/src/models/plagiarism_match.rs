@@ -0,0 +1,179 @@
+//! One user pair of a [`super::plagiarism_report`]: overall similarity, matched lines and the
+//! per-file matches, plus the case created from it (if any).
+
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "plagiarism_matches")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// The [`super::plagiarism_report`] this pair belongs to.
+    pub report_id: i64,
+    pub user_a: String,
+    pub user_b: String,
+    pub submission_id_a: Option<i64>,
+    pub submission_id_b: Option<i64>,
+    /// Weighted similarity percent (0–100), if the engine reported one.
+    pub total_percent: Option<f64>,
+    pub lines_matched: i64,
+    /// Per-file matches as a JSON array (`a_filename`, `b_filename`, `percent`,
+    /// `lines_matched`, `match_href`).
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub files: Option<serde_json::Value>,
+    /// The plagiarism case created from this pair. Cleared if the case is deleted.
+    pub case_id: Option<i64>,
+}
+
+/// A pair to store with [`super::plagiarism_report::Model::store`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewMatch {
+    pub user_a: String,
+    pub user_b: String,
+    pub submission_id_a: Option<i64>,
+    pub submission_id_b: Option<i64>,
+    pub total_percent: Option<f64>,
+    pub lines_matched: i64,
+    pub files: Option<serde_json::Value>,
+    pub case_id: Option<i64>,
+}
+
+impl NewMatch {
+    pub(crate) fn into_active_model(self, report_id: i64) -> ActiveModel {
+        ActiveModel {
+            report_id: Set(report_id),
+            user_a: Set(self.user_a),
+            user_b: Set(self.user_b),
+            submission_id_a: Set(self.submission_id_a),
+            submission_id_b: Set(self.submission_id_b),
+            total_percent: Set(self.total_percent),
+            lines_matched: Set(self.lines_matched),
+            files: Set(self.files),
+            case_id: Set(self.case_id),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::plagiarism_report::Entity",
+        from = "Column::ReportId",
+        to = "super::plagiarism_report::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Report,
+    #[sea_orm(
+        belongs_to = "super::plagiarism_case::Entity",
+        from = "Column::CaseId",
+        to = "super::plagiarism_case::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Case,
+}
+
+impl Related<super::plagiarism_report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Report.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Links the pair to the case created from it.
+    pub async fn set_case(db: &DatabaseConnection, id: i64, case_id: i64) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::CaseId, Expr::value(case_id))
+            .filter(Column::Id.eq(id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{assignment, module, moss_report, plagiarism_report};
+    use crate::test_utils::setup_test_db;
+    use chrono::Utc;
+
+    fn pair(user_a: &str, user_b: &str, lines: i64) -> NewMatch {
+        NewMatch {
+            user_a: user_a.into(),
+            user_b: user_b.into(),
+            submission_id_a: None,
+            submission_id_b: None,
+            total_percent: Some(50.0),
+            lines_matched: lines,
+            files: None,
+            case_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn store_replaces_an_earlier_parse_and_cascades_with_the_run() {
+        let db = setup_test_db().await;
+        let m = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let a = assignment::Model::create(
+            &db,
+            m.id,
+            "A1",
+            None,
+            assignment::AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let run = moss_report::Entity::create_report(
+            &db,
+            a.id,
+            "http://moss.stanford.edu/results/1/2",
+            moss_report::FilterMode::All,
+            "Week 1".into(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        plagiarism_report::Model::store(&db, run.id, a.id, None, vec![pair("a", "b", 3)])
+            .await
+            .unwrap();
+        let report = plagiarism_report::Model::store(
+            &db,
+            run.id,
+            a.id,
+            Some("moss results".into()),
+            vec![pair("a", "b", 10), pair("a", "c", 4)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.pair_count, 2);
+
+        let found = plagiarism_report::Model::find_for_moss_report(&db, run.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, report.id);
+        assert_eq!(Entity::find().all(&db).await.unwrap().len(), 2);
+
+        moss_report::Entity::delete_by_id(run.id)
+            .exec(&db)
+            .await
+            .unwrap();
+        assert!(
+            plagiarism_report::Entity::find()
+                .all(&db)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(Entity::find().all(&db).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Parsed contents of a [`super::moss_report`] (MOSS or JPlag): the report title and its
+//! user-pair matches, stored as [`super::plagiarism_match`] rows so they can be filtered and
+//! turned into cases after the hosted report has expired.
+
+use super::plagiarism_match::{self, NewMatch};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryFilter};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "plagiarism_reports")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// The run this was parsed from (one parse per run).
+    #[sea_orm(unique)]
+    pub moss_report_id: i64,
+    pub assignment_id: i64,
+    /// Report title, if the engine gave one.
+    pub title: Option<String>,
+    /// Number of stored pairs.
+    pub pair_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::moss_report::Entity",
+        from = "Column::MossReportId",
+        to = "super::moss_report::Column::Id",
+        on_delete = "Cascade"
+    )]
+    MossReport,
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+    #[sea_orm(has_many = "super::plagiarism_match::Entity")]
+    Matches,
+}
+
+impl Related<super::moss_report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MossReport.def()
+    }
+}
+
+impl Related<super::plagiarism_match::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Matches.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Stores a parsed run and its pairs, replacing an earlier parse of the same run.
+    pub async fn store(
+        db: &DatabaseConnection,
+        moss_report_id: i64,
+        assignment_id: i64,
+        title: Option<String>,
+        matches: Vec<NewMatch>,
+    ) -> Result<Self, DbErr> {
+        Entity::delete_many()
+            .filter(Column::MossReportId.eq(moss_report_id))
+            .exec(db)
+            .await?;
+
+        let report = ActiveModel {
+            moss_report_id: Set(moss_report_id),
+            assignment_id: Set(assignment_id),
+            title: Set(title),
+            pair_count: Set(matches.len() as i64),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        if !matches.is_empty() {
+            let rows = matches.into_iter().map(|m| m.into_active_model(report.id));
+            plagiarism_match::Entity::insert_many(rows).exec(db).await?;
+        }
+        Ok(report)
+    }
+
+    /// The parse of a run, if it has been stored.
+    pub async fn find_for_moss_report(
+        db: &DatabaseConnection,
+        moss_report_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::MossReportId.eq(moss_report_id))
+            .one(db)
+            .await
+    }
+}
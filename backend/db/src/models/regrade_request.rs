@@ -0,0 +1,328 @@
+//! Regrade requests: a student disputing the mark of one task (or one of its subsections) of a
+//! marked submission.
+//!
+//! Requests move forward only: `open` → `under_review` → `resolved` (staff may also resolve an
+//! open request directly).
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel, QueryOrder};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "regrade_requests")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub assignment_id: i64,
+    pub submission_id: i64,
+    /// The student who opened the request.
+    pub user_id: i64,
+    pub task_number: i64,
+    /// Label of the disputed subsection; `None` disputes the whole task.
+    pub subsection: Option<String>,
+    pub reason: String,
+    pub status: RegradeStatus,
+    /// Staff's reply to the student.
+    pub response: Option<String>,
+    /// The staff member who last responded; `None` once they are deleted.
+    pub responded_by: Option<i64>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    EnumIter,
+    DeriveActiveEnum,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum RegradeStatus {
+    #[sea_orm(string_value = "open")]
+    Open,
+    #[sea_orm(string_value = "under_review")]
+    UnderReview,
+    #[sea_orm(string_value = "resolved")]
+    Resolved,
+}
+
+impl RegradeStatus {
+    /// Whether a request in this status can move to `next`.
+    pub fn can_transition_to(self, next: RegradeStatus) -> bool {
+        matches!(
+            (self, next),
+            (RegradeStatus::Open, RegradeStatus::UnderReview)
+                | (RegradeStatus::Open, RegradeStatus::Resolved)
+                | (RegradeStatus::UnderReview, RegradeStatus::Resolved)
+        )
+    }
+
+    /// Open and under-review requests are still awaiting an outcome.
+    pub fn is_pending(self) -> bool {
+        self != RegradeStatus::Resolved
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(
+        belongs_to = "super::assignment_submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::assignment_submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::assignment_submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn open(
+        db: &DatabaseConnection,
+        submission: &super::assignment_submission::Model,
+        user_id: i64,
+        task_number: i64,
+        subsection: Option<&str>,
+        reason: &str,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        ActiveModel {
+            assignment_id: Set(submission.assignment_id),
+            submission_id: Set(submission.id),
+            user_id: Set(user_id),
+            task_number: Set(task_number),
+            subsection: Set(subsection.map(str::to_owned)),
+            reason: Set(reason.to_owned()),
+            status: Set(RegradeStatus::Open),
+            response: Set(None),
+            responded_by: Set(None),
+            resolved_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// The still-pending request for the same task/subsection of the submission, if any.
+    pub async fn find_pending(
+        db: &DatabaseConnection,
+        submission_id: i64,
+        task_number: i64,
+        subsection: Option<&str>,
+    ) -> Result<Option<Self>, DbErr> {
+        let subsection_cond = match subsection {
+            Some(label) => Column::Subsection.eq(label),
+            None => Column::Subsection.is_null(),
+        };
+        Entity::find()
+            .filter(Column::SubmissionId.eq(submission_id))
+            .filter(Column::TaskNumber.eq(task_number))
+            .filter(subsection_cond)
+            .filter(Column::Status.ne(RegradeStatus::Resolved))
+            .one(db)
+            .await
+    }
+
+    /// Records a staff response: a new status and/or a reply. The caller checks the status
+    /// transition with [`RegradeStatus::can_transition_to`].
+    pub async fn respond(
+        self,
+        db: &DatabaseConnection,
+        status: Option<RegradeStatus>,
+        response: Option<&str>,
+        responded_by: i64,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        let mut am = self.into_active_model();
+        if let Some(status) = status {
+            am.status = Set(status);
+            if status == RegradeStatus::Resolved {
+                am.resolved_at = Set(Some(now));
+            }
+        }
+        if let Some(response) = response {
+            am.response = Set(Some(response.to_owned()));
+        }
+        am.responded_by = Set(Some(responded_by));
+        am.updated_at = Set(now);
+        am.update(db).await
+    }
+
+    /// The assignment's requests, newest first, optionally only `user_id`'s and/or those in
+    /// `status`.
+    pub async fn list_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        user_id: Option<i64>,
+        status: Option<RegradeStatus>,
+    ) -> Result<Vec<Self>, DbErr> {
+        let mut query = Entity::find().filter(Column::AssignmentId.eq(assignment_id));
+        if let Some(user_id) = user_id {
+            query = query.filter(Column::UserId.eq(user_id));
+        }
+        if let Some(status) = status {
+            query = query.filter(Column::Status.eq(status));
+        }
+        query
+            .order_by_desc(Column::CreatedAt)
+            .order_by_desc(Column::Id)
+            .all(db)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{assignment, assignment_submission, module, user};
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn status_only_moves_forward() {
+        use RegradeStatus::*;
+        assert!(Open.can_transition_to(UnderReview));
+        assert!(Open.can_transition_to(Resolved));
+        assert!(UnderReview.can_transition_to(Resolved));
+        assert!(!UnderReview.can_transition_to(Open));
+        assert!(!Resolved.can_transition_to(UnderReview));
+        assert!(!Open.can_transition_to(Open));
+        assert_eq!(
+            "under_review".parse::<RegradeStatus>().unwrap(),
+            UnderReview
+        );
+    }
+
+    #[tokio::test]
+    async fn open_respond_and_resolve() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let a = assignment::Model::create(
+            &db,
+            module.id,
+            "A1",
+            None,
+            assignment::AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let student = user::Model::create(&db, "stud", "stud@test.com", "pw", false)
+            .await
+            .unwrap();
+        let tutor = user::Model::create(&db, "tutor", "tutor@test.com", "pw", false)
+            .await
+            .unwrap();
+        let submission = assignment_submission::Model::save_file(
+            &db, a.id, student.id, 1, 5.0, 10.0, false, "main.zip", "hash", b"code",
+        )
+        .await
+        .unwrap();
+
+        let req = Model::open(
+            &db,
+            &submission,
+            student.id,
+            1,
+            Some("Output"),
+            "Matches memo",
+        )
+        .await
+        .unwrap();
+        assert_eq!(req.status, RegradeStatus::Open);
+        assert!(
+            Model::find_pending(&db, submission.id, 1, Some("Output"))
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert!(
+            Model::find_pending(&db, submission.id, 1, None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let req = req
+            .respond(&db, Some(RegradeStatus::UnderReview), None, tutor.id)
+            .await
+            .unwrap();
+        assert!(req.resolved_at.is_none());
+        let req = req
+            .respond(
+                &db,
+                Some(RegradeStatus::Resolved),
+                Some("Remarked"),
+                tutor.id,
+            )
+            .await
+            .unwrap();
+        assert!(req.resolved_at.is_some());
+        assert_eq!(req.response.as_deref(), Some("Remarked"));
+        assert!(
+            Model::find_pending(&db, submission.id, 1, Some("Output"))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let all = Model::list_for_assignment(&db, a.id, Some(student.id), None)
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 1);
+        let open = Model::list_for_assignment(&db, a.id, None, Some(RegradeStatus::Open))
+            .await
+            .unwrap();
+        assert!(open.is_empty());
+    }
+}
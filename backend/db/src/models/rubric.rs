@@ -0,0 +1,334 @@
+//! Rubrics: qualitative criteria staff score by hand, alongside the automated mark.
+//!
+//! An assignment has at most one rubric. Once a submission has at least one criterion scored,
+//! its final percentage is `weight`% rubric and the rest automated (see
+//! [`Model::final_percentage`]); unscored submissions keep their automated mark.
+
+use super::{rubric_criterion, rubric_score};
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{
+    ActiveValue::Set, DatabaseConnection, IntoActiveModel, QueryOrder, TransactionTrait,
+    TryIntoModel,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "rubrics")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub assignment_id: i64,
+    pub title: String,
+    pub description: Option<String>,
+    /// Percentage (0–100) of the final grade taken from the rubric.
+    pub weight: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::assignment::Entity",
+        from = "Column::AssignmentId",
+        to = "super::assignment::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Assignment,
+
+    #[sea_orm(has_many = "super::rubric_criterion::Entity")]
+    Criteria,
+}
+
+impl Related<super::assignment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Assignment.def()
+    }
+}
+
+impl Related<super::rubric_criterion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Criteria.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// A criterion as given to [`Model::save`]; `id` keeps (and updates) an existing criterion and
+/// its scores, `None` adds a new one.
+#[derive(Debug, Clone)]
+pub struct CriterionInput {
+    pub id: Option<i64>,
+    pub name: String,
+    pub description: Option<String>,
+    pub max_score: f64,
+}
+
+impl Model {
+    pub async fn for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .one(db)
+            .await
+    }
+
+    /// Creates or replaces the assignment's rubric. Criteria not listed in `criteria` are
+    /// removed along with their scores.
+    ///
+    /// Fails with [`DbErr::RecordNotFound`] if an `id` isn't one of the rubric's criteria.
+    pub async fn save(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+        title: &str,
+        description: Option<&str>,
+        weight: f64,
+        criteria: &[CriterionInput],
+    ) -> Result<(Self, Vec<rubric_criterion::Model>), DbErr> {
+        let txn = db.begin().await?;
+        let now = Utc::now();
+        let existing = Entity::find()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .one(&txn)
+            .await?;
+        let rubric = match existing {
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.title = Set(title.to_owned());
+                am.description = Set(description.map(str::to_owned));
+                am.weight = Set(weight);
+                am.updated_at = Set(now);
+                am.update(&txn).await?
+            }
+            None => {
+                ActiveModel {
+                    assignment_id: Set(assignment_id),
+                    title: Set(title.to_owned()),
+                    description: Set(description.map(str::to_owned)),
+                    weight: Set(weight),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await?
+            }
+        };
+
+        let current: HashMap<i64, rubric_criterion::Model> = rubric_criterion::Entity::find()
+            .filter(rubric_criterion::Column::RubricId.eq(rubric.id))
+            .all(&txn)
+            .await?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
+        let kept: Vec<i64> = criteria.iter().filter_map(|c| c.id).collect();
+        if let Some(unknown) = kept.iter().find(|id| !current.contains_key(id)) {
+            return Err(DbErr::RecordNotFound(format!(
+                "Criterion {unknown} is not part of this rubric"
+            )));
+        }
+        rubric_criterion::Entity::delete_many()
+            .filter(rubric_criterion::Column::RubricId.eq(rubric.id))
+            .filter(rubric_criterion::Column::Id.is_not_in(kept))
+            .exec(&txn)
+            .await?;
+
+        let mut saved = Vec::with_capacity(criteria.len());
+        for (position, input) in criteria.iter().enumerate() {
+            let mut am = match input.id.and_then(|id| current.get(&id)) {
+                Some(c) => c.clone().into_active_model(),
+                None => rubric_criterion::ActiveModel {
+                    rubric_id: Set(rubric.id),
+                    ..Default::default()
+                },
+            };
+            am.name = Set(input.name.clone());
+            am.description = Set(input.description.clone());
+            am.max_score = Set(input.max_score);
+            am.position = Set(position as i32);
+            saved.push(am.save(&txn).await?.try_into_model()?);
+        }
+
+        txn.commit().await?;
+        Ok((rubric, saved))
+    }
+
+    /// Removes the assignment's rubric and every score recorded against it.
+    pub async fn delete_for_assignment(
+        db: &DatabaseConnection,
+        assignment_id: i64,
+    ) -> Result<(), DbErr> {
+        let res = Entity::delete_many()
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound("Rubric not found".into()));
+        }
+        Ok(())
+    }
+
+    /// The rubric's criteria in order.
+    pub async fn criteria(
+        &self,
+        db: &DatabaseConnection,
+    ) -> Result<Vec<rubric_criterion::Model>, DbErr> {
+        rubric_criterion::Entity::find()
+            .filter(rubric_criterion::Column::RubricId.eq(self.id))
+            .order_by_asc(rubric_criterion::Column::Position)
+            .all(db)
+            .await
+    }
+
+    /// The submission's scores against this rubric, keyed by criterion id.
+    pub async fn scores(
+        &self,
+        db: &DatabaseConnection,
+        submission_id: i64,
+    ) -> Result<HashMap<i64, rubric_score::Model>, DbErr> {
+        let criterion_ids = self.criteria(db).await?.into_iter().map(|c| c.id);
+        Ok(rubric_score::Entity::find()
+            .filter(rubric_score::Column::SubmissionId.eq(submission_id))
+            .filter(rubric_score::Column::CriterionId.is_in(criterion_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|s| (s.criterion_id, s))
+            .collect())
+    }
+
+    /// Rubric percentage of each of `submission_ids` that has at least one criterion scored.
+    /// Unscored criteria count as zero.
+    pub async fn percentages(
+        &self,
+        db: &DatabaseConnection,
+        submission_ids: &[i64],
+    ) -> Result<HashMap<i64, f64>, DbErr> {
+        let criteria = self.criteria(db).await?;
+        let total: f64 = criteria.iter().map(|c| c.max_score).sum();
+        let mut earned: HashMap<i64, f64> = HashMap::new();
+        for s in rubric_score::Entity::find()
+            .filter(rubric_score::Column::SubmissionId.is_in(submission_ids.iter().copied()))
+            .filter(rubric_score::Column::CriterionId.is_in(criteria.iter().map(|c| c.id)))
+            .all(db)
+            .await?
+        {
+            *earned.entry(s.submission_id).or_default() += s.score;
+        }
+        Ok(earned
+            .into_iter()
+            .map(|(id, e)| (id, crate::grade::percentage(e, total)))
+            .collect())
+    }
+
+    /// Blends an automated percentage with a rubric percentage by the rubric's weight.
+    pub fn final_percentage(&self, automated_pct: f64, rubric_pct: f64) -> f64 {
+        let w = self.weight.clamp(0.0, 100.0) / 100.0;
+        automated_pct * (1.0 - w) + rubric_pct * w
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{assignment, assignment_submission, module, user};
+    use crate::test_utils::setup_test_db;
+
+    fn criterion(id: Option<i64>, name: &str, max_score: f64) -> CriterionInput {
+        CriterionInput {
+            id,
+            name: name.to_owned(),
+            description: None,
+            max_score,
+        }
+    }
+
+    #[tokio::test]
+    async fn save_scores_and_blend() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let a = assignment::Model::create(
+            &db,
+            module.id,
+            "A1",
+            None,
+            assignment::AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let student = user::Model::create(&db, "stud", "stud@test.com", "pw", false)
+            .await
+            .unwrap();
+        let sub = assignment_submission::Model::save_file(
+            &db, a.id, student.id, 1, 6.0, 10.0, false, "main.zip", "hash", b"code",
+        )
+        .await
+        .unwrap();
+
+        let (rubric, criteria) = Model::save(
+            &db,
+            a.id,
+            "Code quality",
+            None,
+            40.0,
+            &[
+                criterion(None, "Style", 5.0),
+                criterion(None, "Design", 5.0),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(criteria.len(), 2);
+
+        rubric_score::Model::set(&db, criteria[0].id, sub.id, 4.0, None, student.id)
+            .await
+            .unwrap();
+        let pcts = rubric.percentages(&db, &[sub.id]).await.unwrap();
+        assert_eq!(pcts[&sub.id], 40.0);
+        // 60% automated, 40% rubric at a 40% weighting
+        assert_eq!(rubric.final_percentage(60.0, pcts[&sub.id]), 52.0);
+
+        // Dropping a criterion drops its scores; keeping one keeps them
+        let (rubric, criteria) = Model::save(
+            &db,
+            a.id,
+            "Code quality",
+            None,
+            40.0,
+            &[criterion(Some(criteria[0].id), "Style", 4.0)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(criteria.len(), 1);
+        assert_eq!(rubric.scores(&db, sub.id).await.unwrap().len(), 1);
+        assert_eq!(
+            rubric.percentages(&db, &[sub.id]).await.unwrap()[&sub.id],
+            100.0
+        );
+        assert!(matches!(
+            Model::save(
+                &db,
+                a.id,
+                "X",
+                None,
+                10.0,
+                &[criterion(Some(9999), "Y", 1.0)]
+            )
+            .await,
+            Err(DbErr::RecordNotFound(_))
+        ));
+
+        Model::delete_for_assignment(&db, a.id).await.unwrap();
+        assert!(Model::for_assignment(&db, a.id).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,44 @@
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+/// One row of a [`super::rubric`]: something markers score out of `max_score`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "rubric_criteria")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub rubric_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub max_score: f64,
+    /// Order within the rubric, from 0.
+    pub position: i32,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::rubric::Entity",
+        from = "Column::RubricId",
+        to = "super::rubric::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Rubric,
+
+    #[sea_orm(has_many = "super::rubric_score::Entity")]
+    Scores,
+}
+
+impl Related<super::rubric::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Rubric.def()
+    }
+}
+
+impl Related<super::rubric_score::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Scores.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, ConnectionTrait};
+use serde::Serialize;
+
+/// A marker's score for one [`super::rubric_criterion`] of one submission.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "rubric_scores")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub criterion_id: i64,
+    pub submission_id: i64,
+    pub score: f64,
+    pub comment: Option<String>,
+    /// The staff member who last scored it; `None` once they are deleted.
+    pub marked_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::rubric_criterion::Entity",
+        from = "Column::CriterionId",
+        to = "super::rubric_criterion::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Criterion,
+
+    #[sea_orm(
+        belongs_to = "super::assignment_submission::Entity",
+        from = "Column::SubmissionId",
+        to = "super::assignment_submission::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Submission,
+}
+
+impl Related<super::rubric_criterion::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Criterion.def()
+    }
+}
+
+impl Related<super::assignment_submission::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Submission.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Records `score` for the criterion on the submission, replacing any earlier score.
+    pub async fn set<C: ConnectionTrait>(
+        db: &C,
+        criterion_id: i64,
+        submission_id: i64,
+        score: f64,
+        comment: Option<&str>,
+        marked_by: i64,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        let existing = Entity::find()
+            .filter(Column::CriterionId.eq(criterion_id))
+            .filter(Column::SubmissionId.eq(submission_id))
+            .one(db)
+            .await?;
+        match existing {
+            Some(existing) => {
+                let mut am: ActiveModel = existing.into();
+                am.score = Set(score);
+                am.comment = Set(comment.map(str::to_owned));
+                am.marked_by = Set(Some(marked_by));
+                am.updated_at = Set(now);
+                am.update(db).await
+            }
+            None => {
+                ActiveModel {
+                    criterion_id: Set(criterion_id),
+                    submission_id: Set(submission_id),
+                    score: Set(score),
+                    comment: Set(comment.map(str::to_owned)),
+                    marked_by: Set(Some(marked_by)),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+}
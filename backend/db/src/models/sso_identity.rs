@@ -0,0 +1,98 @@
+//! The FitchFork user behind an OIDC provider's user id (`sub`), for single sign-on.
+//!
+//! Identities are created on a user's first SSO sign-in, or when a user who signs in with a
+//! password links their provider account. A user has at most one identity per provider.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "sso_identities")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub issuer: String,
+    pub subject: String,
+    pub user_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    pub async fn create(
+        db: &DatabaseConnection,
+        issuer: &str,
+        subject: &str,
+        user_id: i64,
+    ) -> Result<Self, DbErr> {
+        ActiveModel {
+            issuer: Set(issuer.to_string()),
+            subject: Set(subject.to_string()),
+            user_id: Set(user_id),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    pub async fn find(
+        db: &DatabaseConnection,
+        issuer: &str,
+        subject: &str,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::Issuer.eq(issuer))
+            .filter(Column::Subject.eq(subject))
+            .one(db)
+            .await
+    }
+
+    /// The user's identity with `issuer`, if they have linked one.
+    pub async fn for_user(
+        db: &DatabaseConnection,
+        issuer: &str,
+        user_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::Issuer.eq(issuer))
+            .filter(Column::UserId.eq(user_id))
+            .one(db)
+            .await
+    }
+
+    /// Removes the user's identity with `issuer`; `false` if there was none.
+    pub async fn unlink(
+        db: &DatabaseConnection,
+        issuer: &str,
+        user_id: i64,
+    ) -> Result<bool, DbErr> {
+        let res = Entity::delete_many()
+            .filter(Column::Issuer.eq(issuer))
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected > 0)
+    }
+}
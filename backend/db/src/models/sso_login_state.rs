@@ -0,0 +1,131 @@
+//! State of an OIDC single sign-on in progress.
+//!
+//! Starting a sign-in stores a random `state`, `nonce` and PKCE `code_verifier`; the provider's
+//! callback must present the same `state`, and its id token must carry the same `nonce`. Each
+//! state is used once.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::distributions::Alphanumeric;
+use rand::{Rng, thread_rng};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use serde::Serialize;
+
+/// How long the user has to finish signing in at the provider.
+pub const STATE_TTL_MINUTES: i64 = 10;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "sso_login_states")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub state: String,
+    pub nonce: String,
+    #[serde(skip_serializing)]
+    pub code_verifier: String,
+    /// The signed-in user linking their provider account; `None` for a sign-in.
+    pub link_user_id: Option<i64>,
+    /// Frontend path to land on afterwards.
+    pub redirect: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::LinkUserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn random_token(len: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+impl Model {
+    /// Starts a sign-in (or, with `link_user_id`, an account link), clearing out expired states
+    /// on the way.
+    pub async fn create(
+        db: &DatabaseConnection,
+        link_user_id: Option<i64>,
+        redirect: Option<&str>,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        Entity::delete_many()
+            .filter(Column::ExpiresAt.lt(now))
+            .exec(db)
+            .await?;
+        ActiveModel {
+            state: Set(random_token(32)),
+            nonce: Set(random_token(32)),
+            // PKCE verifiers are 43 to 128 characters
+            code_verifier: Set(random_token(64)),
+            link_user_id: Set(link_user_id),
+            redirect: Set(redirect.map(str::to_string)),
+            expires_at: Set(now + Duration::minutes(STATE_TTL_MINUTES)),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// The unexpired sign-in with `state`, left in place.
+    pub async fn find_unexpired(
+        db: &DatabaseConnection,
+        state: &str,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find()
+            .filter(Column::State.eq(state))
+            .filter(Column::ExpiresAt.gt(Utc::now()))
+            .one(db)
+            .await
+    }
+
+    /// Removes and returns the unexpired sign-in with `state`.
+    pub async fn take(db: &DatabaseConnection, state: &str) -> Result<Option<Self>, DbErr> {
+        let Some(found) = Entity::find()
+            .filter(Column::State.eq(state))
+            .one(db)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Entity::delete_by_id(found.id).exec(db).await?;
+        Ok((found.expires_at > Utc::now()).then_some(found))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn states_are_used_once() {
+        let db = setup_test_db().await;
+        let login = Model::create(&db, None, Some("/modules/1")).await.unwrap();
+        assert_eq!(login.code_verifier.len(), 64);
+
+        let taken = Model::take(&db, &login.state).await.unwrap().unwrap();
+        assert_eq!(taken.nonce, login.nonce);
+        assert_eq!(taken.redirect.as_deref(), Some("/modules/1"));
+        assert!(Model::take(&db, &login.state).await.unwrap().is_none());
+    }
+}
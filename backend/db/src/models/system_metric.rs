@@ -10,6 +10,10 @@ pub struct Model {
     pub created_at: DateTime<Utc>,
     pub cpu_avg: f32, // 0..100
     pub mem_pct: f32, // 0..100  <-- only percentage
+    /// Jobs running on the code manager when sampled.
+    pub cm_running: i32,
+    /// Jobs queued on the code manager when sampled.
+    pub cm_waiting: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
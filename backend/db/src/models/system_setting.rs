@@ -0,0 +1,67 @@
+//! Instance-wide settings admins change at runtime.
+//!
+//! Deployment configuration lives in the environment (see `util::config`); this table holds the
+//! few switches that belong to admins instead, as string values under well-known keys. A key
+//! with no row has its default.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, IntoActiveModel};
+use serde::Serialize;
+
+/// Whether lecturers, assistant lecturers, tutors and admins must use two-factor authentication.
+pub const REQUIRE_STAFF_TWO_FACTOR: &str = "require_staff_two_factor";
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "system_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub key: String,
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// The value under `key`, if it has been set.
+    pub async fn get(db: &DatabaseConnection, key: &str) -> Result<Option<String>, DbErr> {
+        Ok(Entity::find_by_id(key.to_string())
+            .one(db)
+            .await?
+            .map(|s| s.value))
+    }
+
+    /// Sets `key` to `value`.
+    pub async fn set(db: &DatabaseConnection, key: &str, value: &str) -> Result<Self, DbErr> {
+        match Entity::find_by_id(key.to_string()).one(db).await? {
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.value = Set(value.to_string());
+                am.updated_at = Set(Utc::now());
+                am.update(db).await
+            }
+            None => {
+                ActiveModel {
+                    key: Set(key.to_string()),
+                    value: Set(value.to_string()),
+                    updated_at: Set(Utc::now()),
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+
+    /// The flag under `key`, `false` until set.
+    pub async fn get_flag(db: &DatabaseConnection, key: &str) -> Result<bool, DbErr> {
+        Ok(Self::get(db, key).await?.as_deref() == Some("true"))
+    }
+
+    pub async fn set_flag(db: &DatabaseConnection, key: &str, on: bool) -> Result<Self, DbErr> {
+        Self::set(db, key, if on { "true" } else { "false" }).await
+    }
+}
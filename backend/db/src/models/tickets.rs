@@ -1,4 +1,5 @@
-use chrono::{DateTime, Utc};
+use crate::soft_delete::SoftDelete;
+use chrono::{DateTime, Duration, Utc};
 use sea_orm::ActiveValue::Set;
 use sea_orm::DeriveActiveEnum;
 use sea_orm::entity::prelude::*;
@@ -19,15 +20,27 @@ pub struct Model {
 
     pub status: TicketStatus,
 
+    /// Staff member the ticket is assigned to, if any.
+    pub assignee_id: Option<i64>,
+    pub priority: TicketPriority,
+    /// When a staff reply is due, derived from the creation time and the priority.
+    pub sla_due_at: Option<DateTime<Utc>>,
+    /// When someone other than the author first replied.
+    pub first_response_at: Option<DateTime<Utc>>,
+    /// When the ticket was escalated to the module's lecturers for going unanswered.
+    pub escalated_at: Option<DateTime<Utc>>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the ticket was soft deleted; `None` while it is live.
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(
     Debug, Clone, PartialEq, EnumIter, DeriveActiveEnum, Display, EnumString, Serialize, Deserialize,
 )]
 #[serde(rename_all = "lowercase")]
-#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "ticket_status")]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 #[strum(serialize_all = "lowercase", ascii_case_insensitive)]
 pub enum TicketStatus {
     #[sea_orm(string_value = "open")]
@@ -37,6 +50,52 @@ pub enum TicketStatus {
     Closed,
 }
 
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    EnumIter,
+    DeriveActiveEnum,
+    Display,
+    EnumString,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum TicketPriority {
+    #[sea_orm(string_value = "low")]
+    Low,
+
+    #[sea_orm(string_value = "normal")]
+    Normal,
+
+    #[sea_orm(string_value = "high")]
+    High,
+
+    #[sea_orm(string_value = "urgent")]
+    Urgent,
+}
+
+impl TicketPriority {
+    /// Hours staff have to reply to a ticket of this priority.
+    pub fn sla_hours(self) -> i64 {
+        match self {
+            TicketPriority::Low => 96,
+            TicketPriority::Normal => 48,
+            TicketPriority::High => 24,
+            TicketPriority::Urgent => 8,
+        }
+    }
+
+    /// The SLA deadline for a ticket of this priority opened at `created_at`.
+    pub fn sla_due_at(self, created_at: DateTime<Utc>) -> DateTime<Utc> {
+        created_at + Duration::hours(self.sla_hours())
+    }
+}
+
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
     #[sea_orm(
@@ -84,6 +143,8 @@ impl Model {
             title: Set(title.to_owned()),
             description: Set(description.to_owned()),
             status: Set(TicketStatus::Open),
+            priority: Set(TicketPriority::Normal),
+            sla_due_at: Set(Some(TicketPriority::Normal.sla_due_at(now))),
             created_at: Set(now),
             updated_at: Set(now),
             ..Default::default()
@@ -126,11 +187,116 @@ impl Model {
         Entity::find_by_id(ticket_id).one(db).await
     }
 
+    /// Soft deletes the ticket; it can be restored until the retention sweep purges it.
     pub async fn delete(db: &DbConn, ticket_id: i64) -> Result<(), DbErr> {
-        Entity::delete_by_id(ticket_id).exec(db).await?;
+        let res = Entity::mark_deleted()
+            .filter(Column::Id.eq(ticket_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "Ticket {ticket_id} not found"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Brings back a soft-deleted ticket.
+    pub async fn restore(db: &DbConn, assignment_id: i64, ticket_id: i64) -> Result<(), DbErr> {
+        let res = Entity::mark_restored()
+            .filter(Column::Id.eq(ticket_id))
+            .filter(Column::AssignmentId.eq(assignment_id))
+            .exec(db)
+            .await?;
+        if res.rows_affected == 0 {
+            return Err(DbErr::RecordNotFound(format!(
+                "No deleted ticket {ticket_id} in assignment {assignment_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Assigns the ticket to `assignee_id`, or unassigns it when `None`.
+    pub async fn assign(
+        db: &DbConn,
+        ticket_id: i64,
+        assignee_id: Option<i64>,
+    ) -> Result<Model, DbErr> {
+        let model = Entity::find_by_id(ticket_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Ticket not found".to_string()))?;
+
+        let mut active_model: ActiveModel = model.into();
+        active_model.assignee_id = Set(assignee_id);
+        active_model.updated_at = Set(Utc::now());
+        active_model.update(db).await
+    }
+
+    /// Changes the priority and moves the SLA deadline to match it.
+    pub async fn set_priority(
+        db: &DbConn,
+        ticket_id: i64,
+        priority: TicketPriority,
+    ) -> Result<Model, DbErr> {
+        let model = Entity::find_by_id(ticket_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| DbErr::RecordNotFound("Ticket not found".to_string()))?;
+
+        let created_at = model.created_at;
+        let mut active_model: ActiveModel = model.into();
+        active_model.priority = Set(priority);
+        active_model.sla_due_at = Set(Some(priority.sla_due_at(created_at)));
+        active_model.updated_at = Set(Utc::now());
+        active_model.update(db).await
+    }
+
+    /// Stamps the first reply from someone other than the author; later replies leave it alone.
+    pub async fn record_first_response(db: &DbConn, ticket_id: i64) -> Result<(), DbErr> {
+        Entity::update_many()
+            .col_expr(Column::FirstResponseAt, Expr::value(Utc::now()))
+            .filter(Column::Id.eq(ticket_id))
+            .filter(Column::FirstResponseAt.is_null())
+            .exec(db)
+            .await?;
         Ok(())
     }
 
+    /// Open tickets nobody but the author has replied to within `hours` of being opened, and
+    /// that have not been escalated yet.
+    pub async fn due_for_escalation(
+        db: &DbConn,
+        now: DateTime<Utc>,
+        hours: i64,
+    ) -> Result<Vec<Model>, DbErr> {
+        Entity::find_active()
+            .filter(Column::Status.eq(TicketStatus::Open))
+            .filter(Column::FirstResponseAt.is_null())
+            .filter(Column::EscalatedAt.is_null())
+            .filter(Column::CreatedAt.lte(now - Duration::hours(hours)))
+            .all(db)
+            .await
+    }
+
+    /// Records that the ticket was escalated, returning `false` if it already had been.
+    pub async fn mark_escalated(db: &DbConn, ticket_id: i64) -> Result<bool, DbErr> {
+        let res = Entity::update_many()
+            .col_expr(Column::EscalatedAt, Expr::value(Utc::now()))
+            .filter(Column::Id.eq(ticket_id))
+            .filter(Column::EscalatedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Whether the ticket is still open and unanswered past its SLA deadline.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.status == TicketStatus::Open
+            && self.first_response_at.is_none()
+            && self.sla_due_at.is_some_and(|due| due < now)
+    }
+
     pub async fn is_author(ticket_id: i64, user_id: i64, db: &DbConn) -> bool {
         let ticket = Entity::find_by_id(ticket_id).one(db).await;
         match ticket {
@@ -139,3 +305,79 @@ impl Model {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{assignment, module, user};
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn unanswered_tickets_are_escalated_once() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let assignment = assignment::Model::create(
+            &db,
+            module.id,
+            "A1",
+            None,
+            assignment::AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+        let student = user::Model::create(&db, "u1", "u1@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let answered = Model::create(&db, assignment.id, student.id, "A", "a")
+            .await
+            .unwrap();
+        let unanswered = Model::create(&db, assignment.id, student.id, "B", "b")
+            .await
+            .unwrap();
+        assert_eq!(unanswered.priority, TicketPriority::Normal);
+        assert_eq!(
+            unanswered.sla_due_at,
+            Some(unanswered.created_at + Duration::hours(48))
+        );
+
+        Model::record_first_response(&db, answered.id)
+            .await
+            .unwrap();
+
+        let later = Utc::now() + Duration::hours(49);
+        assert!(
+            Model::due_for_escalation(&db, Utc::now(), 48)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let due = Model::due_for_escalation(&db, later, 48).await.unwrap();
+        assert_eq!(
+            due.iter().map(|t| t.id).collect::<Vec<_>>(),
+            vec![unanswered.id]
+        );
+        assert!(due[0].is_overdue(later));
+
+        assert!(Model::mark_escalated(&db, unanswered.id).await.unwrap());
+        assert!(!Model::mark_escalated(&db, unanswered.id).await.unwrap());
+        assert!(
+            Model::due_for_escalation(&db, later, 48)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        let urgent = Model::set_priority(&db, unanswered.id, TicketPriority::Urgent)
+            .await
+            .unwrap();
+        assert_eq!(
+            urgent.sla_due_at,
+            Some(urgent.created_at + Duration::hours(8))
+        );
+    }
+}
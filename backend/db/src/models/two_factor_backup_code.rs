@@ -0,0 +1,164 @@
+//! Two-factor backup codes.
+//!
+//! Enabling two-factor authentication hands the user [`CODE_COUNT`] single-use codes to get in
+//! with when their authenticator app is unavailable. Only their SHA-256 hashes are kept, so the
+//! codes are shown once; regenerating them replaces the whole set.
+
+use chrono::{DateTime, Utc};
+use rand::{Rng, thread_rng};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, PaginatorTrait};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Codes in a set.
+pub const CODE_COUNT: usize = 10;
+
+/// Characters per half of a code (`xxxxx-xxxxx`).
+const HALF_LEN: usize = 5;
+
+/// Code alphabet, without look-alikes (`0`/`o`, `1`/`l`/`i`).
+const ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "two_factor_backup_codes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    #[serde(skip_serializing)]
+    pub code_hash: String,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn new_code() -> String {
+    let mut rng = thread_rng();
+    let mut half = || -> String {
+        (0..HALF_LEN)
+            .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())] as char)
+            .collect()
+    };
+    format!("{}-{}", half(), half())
+}
+
+/// Hash of a code as typed: case, spaces and dashes don't matter.
+fn hash_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+impl Model {
+    /// Replaces the user's codes with a new set, returning the codes in plain text.
+    pub async fn regenerate(db: &DatabaseConnection, user_id: i64) -> Result<Vec<String>, DbErr> {
+        Self::delete_for_user(db, user_id).await?;
+        let now = Utc::now();
+        let codes: Vec<String> = (0..CODE_COUNT).map(|_| new_code()).collect();
+        Entity::insert_many(codes.iter().map(|code| ActiveModel {
+            user_id: Set(user_id),
+            code_hash: Set(hash_code(code)),
+            used_at: Set(None),
+            created_at: Set(now),
+            ..Default::default()
+        }))
+        .exec(db)
+        .await?;
+        Ok(codes)
+    }
+
+    /// Spends one of the user's unused codes, if `code` is one.
+    pub async fn redeem(
+        db: &DatabaseConnection,
+        user_id: i64,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool, DbErr> {
+        let Some(found) = Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::CodeHash.eq(hash_code(code)))
+            .filter(Column::UsedAt.is_null())
+            .one(db)
+            .await?
+        else {
+            return Ok(false);
+        };
+        // Conditional, so the same code redeemed twice at once is only accepted once
+        let res = Entity::update_many()
+            .col_expr(Column::UsedAt, Expr::value(now))
+            .filter(Column::Id.eq(found.id))
+            .filter(Column::UsedAt.is_null())
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// How many of the user's codes are still unused.
+    pub async fn remaining(db: &DatabaseConnection, user_id: i64) -> Result<u64, DbErr> {
+        Entity::find()
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::UsedAt.is_null())
+            .count(db)
+            .await
+    }
+
+    pub async fn delete_for_user(db: &DatabaseConnection, user_id: i64) -> Result<(), DbErr> {
+        Entity::delete_many()
+            .filter(Column::UserId.eq(user_id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user::Model as UserModel;
+    use crate::test_utils::setup_test_db;
+
+    #[tokio::test]
+    async fn codes_are_single_use_and_forgiving_of_format() {
+        let db = setup_test_db().await;
+        let user = UserModel::create(&db, "u1", "u1@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let codes = Model::regenerate(&db, user.id).await.unwrap();
+        assert_eq!(codes.len(), CODE_COUNT);
+        assert_eq!(codes[0].len(), HALF_LEN * 2 + 1);
+
+        let now = Utc::now();
+        let typed = codes[0].to_uppercase().replace('-', " ");
+        assert!(Model::redeem(&db, user.id, &typed, now).await.unwrap());
+        assert!(!Model::redeem(&db, user.id, &codes[0], now).await.unwrap());
+        assert_eq!(
+            Model::remaining(&db, user.id).await.unwrap(),
+            CODE_COUNT as u64 - 1
+        );
+
+        Model::regenerate(&db, user.id).await.unwrap();
+        assert!(!Model::redeem(&db, user.id, &codes[1], now).await.unwrap());
+    }
+}
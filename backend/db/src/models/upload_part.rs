@@ -0,0 +1,37 @@
+//! A received part of a chunked upload; its bytes live in storage under the upload's folder.
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "upload_parts")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub upload_id: i64,
+    pub part_number: i32,
+    pub size: i64,
+    pub sha256: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::upload_session::Entity",
+        from = "Column::UploadId",
+        to = "super::upload_session::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    Upload,
+}
+
+impl Related<super::upload_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Upload.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,278 @@
+//! Chunked uploads of large files.
+//!
+//! A client that can't get a big archive through in one request opens a session with the file's
+//! size and SHA-256, sends it in [`CHUNK_SIZE`] parts (each with its own checksum, and in any
+//! order or again after a dropped connection), then completes the session. Completing assembles
+//! the parts and checks the whole file against its checksum; the finished upload can then be
+//! named by id (`upload_id`) in place of a file on the submission and assignment-file uploads,
+//! which consume it.
+//!
+//! Parts and the assembled file live in storage under [`util::paths::upload_dir`]. Sessions
+//! expire after a day, finished or not, and are cleared out when new ones are opened.
+
+use super::upload_part;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, DatabaseConnection, QueryOrder};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use util::paths::{upload_dir, upload_file_path, upload_part_path};
+use util::storage::{key_for, storage};
+
+/// Size of every part but the last.
+pub const CHUNK_SIZE: i64 = 5 * 1024 * 1024;
+
+/// How long a session stays usable after it is opened.
+const SESSION_TTL_HOURS: i64 = 24;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "upload_sessions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub user_id: i64,
+    pub filename: String,
+    pub size: i64,
+    pub chunk_size: i64,
+    /// Hex SHA-256 of the whole file.
+    pub sha256: String,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_update = "NoAction",
+        on_delete = "Cascade"
+    )]
+    User,
+    #[sea_orm(has_many = "super::upload_part::Entity")]
+    Parts,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl Related<super::upload_part::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Parts.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Why a session could not be completed.
+#[derive(Debug)]
+pub enum CompleteError {
+    /// These parts have not been received yet.
+    MissingParts(Vec<i32>),
+    /// The assembled file does not match the session's size and checksum. The received parts
+    /// are dropped so the client can send them again.
+    ChecksumMismatch,
+    Db(DbErr),
+}
+
+impl From<DbErr> for CompleteError {
+    fn from(e: DbErr) -> Self {
+        CompleteError::Db(e)
+    }
+}
+
+/// Lowercase hex SHA-256 of `bytes`, the form checksums are given and stored in.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn storage_err(e: std::io::Error) -> DbErr {
+    DbErr::Custom(format!("Upload storage error: {e}"))
+}
+
+impl Model {
+    /// Opens a session for `user_id`, clearing out expired sessions on the way.
+    pub async fn create(
+        db: &DatabaseConnection,
+        user_id: i64,
+        filename: &str,
+        size: i64,
+        sha256: &str,
+    ) -> Result<Self, DbErr> {
+        let now = Utc::now();
+        let expired = Entity::find()
+            .filter(Column::ExpiresAt.lt(now))
+            .all(db)
+            .await?;
+        for session in expired {
+            session.discard(db).await?;
+        }
+
+        ActiveModel {
+            user_id: Set(user_id),
+            filename: Set(filename.to_string()),
+            size: Set(size),
+            chunk_size: Set(CHUNK_SIZE),
+            sha256: Set(sha256.to_ascii_lowercase()),
+            completed_at: Set(None),
+            expires_at: Set(now + Duration::hours(SESSION_TTL_HOURS)),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+    }
+
+    /// The user's unexpired session with this id.
+    pub async fn find_for_user(
+        db: &DatabaseConnection,
+        id: i64,
+        user_id: i64,
+    ) -> Result<Option<Self>, DbErr> {
+        Entity::find_by_id(id)
+            .filter(Column::UserId.eq(user_id))
+            .filter(Column::ExpiresAt.gt(Utc::now()))
+            .one(db)
+            .await
+    }
+
+    /// How many parts make up the file.
+    pub fn total_parts(&self) -> i32 {
+        ((self.size + self.chunk_size - 1) / self.chunk_size) as i32
+    }
+
+    /// The size part `part_number` (from 0) must have, or `None` if the file has no such part.
+    pub fn part_size(&self, part_number: i32) -> Option<i64> {
+        if part_number < 0 || part_number >= self.total_parts() {
+            return None;
+        }
+        let start = part_number as i64 * self.chunk_size;
+        Some((self.size - start).min(self.chunk_size))
+    }
+
+    /// Numbers of the parts received so far, ascending.
+    pub async fn received_parts(&self, db: &DatabaseConnection) -> Result<Vec<i32>, DbErr> {
+        Ok(upload_part::Entity::find()
+            .filter(upload_part::Column::UploadId.eq(self.id))
+            .order_by_asc(upload_part::Column::PartNumber)
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|p| p.part_number)
+            .collect())
+    }
+
+    /// Stores a part, replacing any earlier copy of it. The caller checks its number, size and
+    /// checksum first.
+    pub async fn save_part(
+        &self,
+        db: &DatabaseConnection,
+        part_number: i32,
+        bytes: &[u8],
+    ) -> Result<upload_part::Model, DbErr> {
+        storage()
+            .write(&key_for(&upload_part_path(self.id, part_number)), bytes)
+            .await
+            .map_err(storage_err)?;
+
+        let existing = upload_part::Entity::find()
+            .filter(upload_part::Column::UploadId.eq(self.id))
+            .filter(upload_part::Column::PartNumber.eq(part_number))
+            .one(db)
+            .await?;
+        match existing {
+            Some(part) => {
+                let mut am: upload_part::ActiveModel = part.into();
+                am.size = Set(bytes.len() as i64);
+                am.sha256 = Set(sha256_hex(bytes));
+                am.created_at = Set(Utc::now());
+                am.update(db).await
+            }
+            None => {
+                upload_part::ActiveModel {
+                    upload_id: Set(self.id),
+                    part_number: Set(part_number),
+                    size: Set(bytes.len() as i64),
+                    sha256: Set(sha256_hex(bytes)),
+                    created_at: Set(Utc::now()),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await
+            }
+        }
+    }
+
+    /// Assembles the parts into the finished file and checks it against the session's size and
+    /// checksum. Completing a completed session does nothing.
+    pub async fn complete(self, db: &DatabaseConnection) -> Result<Self, CompleteError> {
+        if self.completed_at.is_some() {
+            return Ok(self);
+        }
+
+        let received = self.received_parts(db).await?;
+        let missing: Vec<i32> = (0..self.total_parts())
+            .filter(|n| received.binary_search(n).is_err())
+            .collect();
+        if !missing.is_empty() {
+            return Err(CompleteError::MissingParts(missing));
+        }
+
+        let mut file = Vec::with_capacity(self.size as usize);
+        for part_number in 0..self.total_parts() {
+            let bytes = storage()
+                .read(&key_for(&upload_part_path(self.id, part_number)))
+                .await
+                .map_err(storage_err)?;
+            file.extend_from_slice(&bytes);
+        }
+        let intact = file.len() as i64 == self.size && sha256_hex(&file) == self.sha256;
+
+        for part_number in 0..self.total_parts() {
+            let _ = storage()
+                .delete(&key_for(&upload_part_path(self.id, part_number)))
+                .await;
+        }
+        upload_part::Entity::delete_many()
+            .filter(upload_part::Column::UploadId.eq(self.id))
+            .exec(db)
+            .await?;
+        if !intact {
+            return Err(CompleteError::ChecksumMismatch);
+        }
+
+        storage()
+            .write(&key_for(&upload_file_path(self.id)), &file)
+            .await
+            .map_err(storage_err)?;
+        let mut am: ActiveModel = self.into();
+        am.completed_at = Set(Some(Utc::now()));
+        Ok(am.update(db).await?)
+    }
+
+    /// The assembled file of a completed session.
+    pub async fn read(&self) -> Result<Vec<u8>, DbErr> {
+        storage()
+            .read(&key_for(&upload_file_path(self.id)))
+            .await
+            .map_err(storage_err)
+    }
+
+    /// Deletes the session with everything it has stored.
+    pub async fn discard(self, db: &DatabaseConnection) -> Result<(), DbErr> {
+        let keys = storage()
+            .list(&key_for(&upload_dir(self.id)))
+            .await
+            .unwrap_or_default();
+        for key in keys {
+            let _ = storage().delete(&key).await;
+        }
+        Entity::delete_by_id(self.id).exec(db).await?;
+        Ok(())
+    }
+}
@@ -26,11 +26,7 @@ pub struct Model {
     Debug, Clone, PartialEq, EnumIter, DeriveActiveEnum, Display, EnumString, Deserialize, Serialize,
 )]
 #[serde(rename_all = "snake_case")]
-#[sea_orm(
-    rs_type = "String",
-    db_type = "Enum",
-    enum_name = "user_module_role_type"
-)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
 #[strum(serialize_all = "lowercase", ascii_case_insensitive)]
 pub enum Role {
     #[sea_orm(string_value = "lecturer")]
@@ -117,7 +113,7 @@ impl Model {
     /// Get all users assigned to a module under a specific role.
     pub async fn get_users_by_module_role(
         db: &DatabaseConnection,
-        module_id: i32,
+        module_id: i64,
         role: Role,
     ) -> Result<Vec<Self>, DbErr> {
         Entity::find()
@@ -130,7 +126,7 @@ impl Model {
     /// Get all modules a user is assigned to under a specific role.
     pub async fn get_modules_by_user_role(
         db: &DatabaseConnection,
-        user_id: i32,
+        user_id: i64,
         role: Role,
     ) -> Result<Vec<Self>, DbErr> {
         Entity::find()
@@ -0,0 +1,421 @@
+//! TOTP two-factor authentication.
+//!
+//! A user sets up two-factor authentication by scanning the `otpauth://` URI of a fresh secret
+//! into an authenticator app, then proving it with a code; only then is it `enabled` and asked
+//! for at login. Codes are RFC 6238 TOTP: HMAC-SHA1, 6 digits, 30-second steps, with one step of
+//! clock drift allowed either way. Each step's code is accepted once, and
+//! [`MAX_FAILED_ATTEMPTS`] wrong codes in a row lock verification for [`LOCKOUT_MINUTES`].
+//!
+//! Lost devices are covered by single-use backup codes (see [`super::two_factor_backup_code`]),
+//! which [`Model::verify`] accepts in place of a TOTP code.
+
+use chrono::{DateTime, Duration, Utc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{RngCore, thread_rng};
+use sea_orm::entity::prelude::*;
+use sea_orm::{ActiveValue::Set, Condition, DatabaseConnection, IntoActiveModel};
+use serde::Serialize;
+use sha1::Sha1;
+
+use super::two_factor_backup_code;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// Seconds each code is shown for.
+pub const STEP_SECONDS: i64 = 30;
+
+/// Digits in a code.
+pub const DIGITS: u32 = 6;
+
+/// Steps either side of the current one whose codes are still accepted.
+const ALLOWED_DRIFT: i64 = 1;
+
+/// Random bytes in a secret (160 bits, as RFC 4226 recommends).
+const SECRET_BYTES: usize = 20;
+
+/// Wrong codes in a row before verification is locked.
+pub const MAX_FAILED_ATTEMPTS: i32 = 5;
+
+/// How long verification stays locked.
+pub const LOCKOUT_MINUTES: i64 = 5;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize)]
+#[sea_orm(table_name = "user_two_factors")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub user_id: i64,
+    /// Base32 secret shared with the authenticator app.
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Whether the user has confirmed the secret; until then it isn't asked for at login.
+    pub enabled: bool,
+    /// The last step a code was accepted for.
+    pub last_used_step: Option<i64>,
+    pub failed_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub enabled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::UserId",
+        to = "super::user::Column::Id",
+        on_delete = "Cascade"
+    )]
+    User,
+}
+
+impl Related<super::user::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::User.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Outcome of checking a code with [`Model::verify`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verification {
+    /// A current TOTP code.
+    Totp,
+    /// An unused backup code, now spent.
+    BackupCode,
+    Invalid,
+    /// Too many wrong codes; nothing is checked until `locked_until`.
+    Locked,
+}
+
+impl Verification {
+    pub fn is_valid(self) -> bool {
+        matches!(self, Verification::Totp | Verification::BackupCode)
+    }
+}
+
+/// A new random secret, base32-encoded.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; SECRET_BYTES];
+    thread_rng().fill_bytes(&mut bytes);
+    BASE32_NOPAD.encode(&bytes)
+}
+
+/// The step `at` falls in.
+pub fn step_at(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(STEP_SECONDS)
+}
+
+/// The code for `step` under a raw (decoded) secret.
+pub fn code_for_step(secret: &[u8], step: i64) -> String {
+    let mut mac = HmacSha1::new_from_slice(secret).expect("HMAC key");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[19] & 0x0f) as usize;
+    let slice = &digest[offset..offset + 4];
+    let val = u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]) & 0x7fff_ffff;
+
+    format!(
+        "{:0width$}",
+        val % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+/// Percent-encodes `s` for a URI path segment or query value.
+fn uri_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+impl Model {
+    pub async fn find(db: &DatabaseConnection, user_id: i64) -> Result<Option<Self>, DbErr> {
+        Entity::find_by_id(user_id).one(db).await
+    }
+
+    /// Whether the user has to give a code at login.
+    pub async fn is_enabled(db: &DatabaseConnection, user_id: i64) -> Result<bool, DbErr> {
+        Ok(Self::find(db, user_id).await?.is_some_and(|t| t.enabled))
+    }
+
+    /// Starts (or restarts) setup with a fresh secret. `None` when two-factor authentication is
+    /// already enabled; it has to be disabled before the secret can change.
+    pub async fn begin_setup(db: &DatabaseConnection, user_id: i64) -> Result<Option<Self>, DbErr> {
+        let now = Utc::now();
+        match Self::find(db, user_id).await? {
+            Some(existing) if existing.enabled => Ok(None),
+            Some(existing) => {
+                let mut am = existing.into_active_model();
+                am.secret = Set(generate_secret());
+                am.last_used_step = Set(None);
+                am.created_at = Set(now);
+                am.update(db).await.map(Some)
+            }
+            None => ActiveModel {
+                user_id: Set(user_id),
+                secret: Set(generate_secret()),
+                enabled: Set(false),
+                last_used_step: Set(None),
+                failed_attempts: Set(0),
+                locked_until: Set(None),
+                created_at: Set(now),
+                enabled_at: Set(None),
+            }
+            .insert(db)
+            .await
+            .map(Some),
+        }
+    }
+
+    /// URI for authenticator apps, usually shown as a QR code.
+    pub fn otpauth_uri(&self, issuer: &str, account: &str) -> String {
+        format!(
+            "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+            uri_encode(issuer),
+            uri_encode(account),
+            self.secret,
+            uri_encode(issuer),
+            DIGITS,
+            STEP_SECONDS
+        )
+    }
+
+    /// The code an authenticator app shows at `at`.
+    pub fn code_at(&self, at: DateTime<Utc>) -> Option<String> {
+        let secret = BASE32_NOPAD.decode(self.secret.as_bytes()).ok()?;
+        Some(code_for_step(&secret, step_at(at)))
+    }
+
+    /// The step a TOTP `code` is valid for at `now`, ignoring steps already used.
+    fn matching_step(&self, code: &str, now: DateTime<Utc>) -> Option<i64> {
+        let secret = BASE32_NOPAD.decode(self.secret.as_bytes()).ok()?;
+        let current = step_at(now);
+        (current - ALLOWED_DRIFT..=current + ALLOWED_DRIFT)
+            .filter(|step| self.last_used_step.is_none_or(|last| *step > last))
+            .find(|step| code_for_step(&secret, *step) == code)
+    }
+
+    /// Checks a TOTP or backup code, recording the attempt.
+    ///
+    /// The attempt is recorded with conditional updates rather than from `self`, so parallel
+    /// requests can neither share one step's code nor undercount wrong guesses.
+    pub async fn verify(
+        &self,
+        db: &DatabaseConnection,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> Result<Verification, DbErr> {
+        if self.locked_until.is_some_and(|until| until > now) {
+            return Ok(Verification::Locked);
+        }
+
+        let code = code.trim();
+        let outcome = if code.len() == DIGITS as usize && code.bytes().all(|b| b.is_ascii_digit()) {
+            match self.matching_step(code, now) {
+                Some(step) if self.claim_step(db, step, now).await? => Verification::Totp,
+                _ => Verification::Invalid,
+            }
+        } else if self.enabled
+            && two_factor_backup_code::Model::redeem(db, self.user_id, code, now).await?
+        {
+            Verification::BackupCode
+        } else {
+            Verification::Invalid
+        };
+
+        if outcome.is_valid() {
+            Entity::update_many()
+                .col_expr(Column::FailedAttempts, Expr::value(0))
+                .col_expr(
+                    Column::LockedUntil,
+                    Expr::value(Option::<DateTime<Utc>>::None),
+                )
+                .filter(Column::UserId.eq(self.user_id))
+                .exec(db)
+                .await?;
+            return Ok(outcome);
+        }
+
+        Entity::update_many()
+            .col_expr(
+                Column::FailedAttempts,
+                Expr::col(Column::FailedAttempts).add(1),
+            )
+            .filter(Column::UserId.eq(self.user_id))
+            .exec(db)
+            .await?;
+        Entity::update_many()
+            .col_expr(Column::FailedAttempts, Expr::value(0))
+            .col_expr(
+                Column::LockedUntil,
+                Expr::value(now + Duration::minutes(LOCKOUT_MINUTES)),
+            )
+            .filter(Column::UserId.eq(self.user_id))
+            .filter(Column::FailedAttempts.gte(MAX_FAILED_ATTEMPTS))
+            .exec(db)
+            .await?;
+        Ok(outcome)
+    }
+
+    /// Marks `step` used unless it, or a later step, already was (or verification is locked).
+    /// `false` when another request got there first.
+    async fn claim_step(
+        &self,
+        db: &DatabaseConnection,
+        step: i64,
+        now: DateTime<Utc>,
+    ) -> Result<bool, DbErr> {
+        let res = Entity::update_many()
+            .col_expr(Column::LastUsedStep, Expr::value(step))
+            .filter(Column::UserId.eq(self.user_id))
+            .filter(
+                Condition::any()
+                    .add(Column::LastUsedStep.is_null())
+                    .add(Column::LastUsedStep.lt(step)),
+            )
+            .filter(
+                Condition::any()
+                    .add(Column::LockedUntil.is_null())
+                    .add(Column::LockedUntil.lte(now)),
+            )
+            .exec(db)
+            .await?;
+        Ok(res.rows_affected > 0)
+    }
+
+    /// Turns two-factor authentication on once setup has been confirmed.
+    pub async fn enable(self, db: &DatabaseConnection) -> Result<Self, DbErr> {
+        let mut am = self.into_active_model();
+        am.enabled = Set(true);
+        am.enabled_at = Set(Some(Utc::now()));
+        am.update(db).await
+    }
+
+    /// Turns two-factor authentication off, dropping the secret and any backup codes.
+    pub async fn disable(db: &DatabaseConnection, user_id: i64) -> Result<bool, DbErr> {
+        two_factor_backup_code::Model::delete_for_user(db, user_id).await?;
+        let res = Entity::delete_by_id(user_id).exec(db).await?;
+        Ok(res.rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user::Model as UserModel;
+    use crate::test_utils::setup_test_db;
+
+    #[test]
+    fn codes_match_the_rfc_6238_vectors() {
+        let secret = b"12345678901234567890";
+        let at = |secs: i64| step_at(DateTime::from_timestamp(secs, 0).unwrap());
+        assert_eq!(code_for_step(secret, at(59)), "287082");
+        assert_eq!(code_for_step(secret, at(1_111_111_109)), "081804");
+        assert_eq!(code_for_step(secret, at(1_234_567_890)), "005924");
+        assert_eq!(code_for_step(secret, at(2_000_000_000)), "279037");
+    }
+
+    #[test]
+    fn otpauth_uri_escapes_labels() {
+        let model = Model {
+            user_id: 1,
+            secret: "JBSWY3DPEHPK3PXP".into(),
+            enabled: false,
+            last_used_step: None,
+            failed_attempts: 0,
+            locked_until: None,
+            created_at: Utc::now(),
+            enabled_at: None,
+        };
+        assert_eq!(
+            model.otpauth_uri("Fitch Fork", "u1@tuks.test"),
+            "otpauth://totp/Fitch%20Fork:u1%40tuks.test?secret=JBSWY3DPEHPK3PXP&issuer=Fitch%20Fork&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[tokio::test]
+    async fn codes_are_used_once_and_guessing_locks_verification() {
+        let db = setup_test_db().await;
+        let user = UserModel::create(&db, "u1", "u1@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let setup = Model::begin_setup(&db, user.id).await.unwrap().unwrap();
+        let secret = BASE32_NOPAD.decode(setup.secret.as_bytes()).unwrap();
+        let now = Utc::now();
+        let code = code_for_step(&secret, step_at(now));
+
+        assert_eq!(
+            setup.verify(&db, &code, now).await.unwrap(),
+            Verification::Totp
+        );
+        let enabled = Model::find(&db, user.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .enable(&db)
+            .await
+            .unwrap();
+        assert!(Model::begin_setup(&db, user.id).await.unwrap().is_none());
+        assert_eq!(
+            enabled.verify(&db, &code, now).await.unwrap(),
+            Verification::Invalid
+        );
+
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            let t = Model::find(&db, user.id).await.unwrap().unwrap();
+            t.verify(&db, "000000", now).await.unwrap();
+        }
+        let t = Model::find(&db, user.id).await.unwrap().unwrap();
+        let next = code_for_step(&secret, step_at(now) + 1);
+        assert_eq!(
+            t.verify(&db, &next, now).await.unwrap(),
+            Verification::Locked
+        );
+        let later = now + Duration::minutes(LOCKOUT_MINUTES + 1);
+        let code = code_for_step(&secret, step_at(later));
+        assert_eq!(
+            t.verify(&db, &code, later).await.unwrap(),
+            Verification::Totp
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_snapshots_neither_replay_codes_nor_dodge_the_lockout() {
+        let db = setup_test_db().await;
+        let user = UserModel::create(&db, "u1", "u1@tuks.test", "pw", false)
+            .await
+            .unwrap();
+        let setup = Model::begin_setup(&db, user.id).await.unwrap().unwrap();
+        let now = Utc::now();
+        let code = setup.code_at(now).unwrap();
+
+        // Parallel requests read the row before either records its attempt
+        assert_eq!(
+            setup.verify(&db, &code, now).await.unwrap(),
+            Verification::Totp
+        );
+        assert_eq!(
+            setup.verify(&db, &code, now).await.unwrap(),
+            Verification::Invalid
+        );
+
+        let stale = Model::find(&db, user.id).await.unwrap().unwrap();
+        for _ in 0..MAX_FAILED_ATTEMPTS {
+            stale.verify(&db, "000000", now).await.unwrap();
+        }
+        let t = Model::find(&db, user.id).await.unwrap().unwrap();
+        assert_eq!(
+            t.verify(&db, &t.code_at(now).unwrap(), now).await.unwrap(),
+            Verification::Locked
+        );
+    }
+}
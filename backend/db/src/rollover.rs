@@ -0,0 +1,214 @@
+//! Semester rollover: setting up a module's next offering from the current one.
+//!
+//! The new module gets the old one's metadata, module config and staff (lecturers, assistant
+//! lecturers and tutors; students enrol afresh). Assignments are copied separately, through the
+//! export bundle in the API; once everything is in place the old module's assignments are
+//! archived with [`archive_assignments`].
+
+use crate::models::{
+    assignment::{self, Status},
+    module,
+    user::{self, Model as UserModel},
+    user_module_role::{self, Role},
+};
+use crate::soft_delete::SoftDelete;
+use chrono::{DateTime, Months, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QueryOrder,
+    Set, TransactionTrait,
+};
+use std::collections::HashMap;
+use util::execution_config::ExecutionConfig;
+
+/// Roles carried over to the new module.
+pub const STAFF_ROLES: [Role; 3] = [Role::Lecturer, Role::AssistantLecturer, Role::Tutor];
+
+/// The module's staff with their roles, by role then username.
+pub async fn staff(
+    db: &DatabaseConnection,
+    module_id: i64,
+) -> Result<Vec<(UserModel, Role)>, DbErr> {
+    let roles = user_module_role::Entity::find()
+        .filter(user_module_role::Column::ModuleId.eq(module_id))
+        .filter(user_module_role::Column::Role.is_in(STAFF_ROLES))
+        .all(db)
+        .await?;
+    let mut users: HashMap<i64, UserModel> = user::Entity::find()
+        .filter(user::Column::Id.is_in(roles.iter().map(|r| r.user_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|u| (u.id, u))
+        .collect();
+    let mut staff: Vec<(UserModel, Role)> = roles
+        .into_iter()
+        .filter_map(|r| users.remove(&r.user_id).map(|u| (u, r.role)))
+        .collect();
+    let rank = |r: &Role| STAFF_ROLES.iter().position(|s| s == r);
+    staff.sort_by(|(a, ra), (b, rb)| {
+        rank(ra)
+            .cmp(&rank(rb))
+            .then_with(|| a.username.cmp(&b.username))
+    });
+    Ok(staff)
+}
+
+/// `date` moved by `years` whole years (Feb 29 becomes Feb 28).
+pub fn shift_years(date: DateTime<Utc>, years: i32) -> DateTime<Utc> {
+    let months = Months::new(years.unsigned_abs() * 12);
+    let shifted = if years >= 0 {
+        date.checked_add_months(months)
+    } else {
+        date.checked_sub_months(months)
+    };
+    shifted.unwrap_or(date)
+}
+
+/// Creates the new offering of `source` as `code`/`year`, with the source's credits and
+/// module config, and (if `with_staff`) its staff in the same roles.
+pub async fn create_module(
+    db: &DatabaseConnection,
+    source: &module::Model,
+    code: &str,
+    year: i32,
+    description: Option<&str>,
+    with_staff: bool,
+) -> Result<module::Model, DbErr> {
+    let txn = db.begin().await?;
+    let created = module::Model::create(&txn, code, year, description, source.credits).await?;
+    if with_staff {
+        for role in user_module_role::Entity::find()
+            .filter(user_module_role::Column::ModuleId.eq(source.id))
+            .filter(user_module_role::Column::Role.is_in(STAFF_ROLES))
+            .all(&txn)
+            .await?
+        {
+            user_module_role::ActiveModel {
+                user_id: Set(role.user_id),
+                module_id: Set(created.id),
+                role: Set(role.role),
+            }
+            .insert(&txn)
+            .await?;
+        }
+    }
+    txn.commit().await?;
+
+    if let Some(config) = ExecutionConfig::module_config_json(source.id).map_err(DbErr::Custom)? {
+        ExecutionConfig::save_module_config(created.id, &config).map_err(DbErr::Custom)?;
+    }
+    Ok(created)
+}
+
+/// Live assignments of the module that are not archived yet.
+pub async fn archivable_assignments(
+    db: &DatabaseConnection,
+    module_id: i64,
+) -> Result<Vec<assignment::Model>, DbErr> {
+    assignment::Entity::find_active()
+        .filter(assignment::Column::ModuleId.eq(module_id))
+        .filter(assignment::Column::Status.ne(Status::Archived))
+        .order_by_asc(assignment::Column::Id)
+        .all(db)
+        .await
+}
+
+/// Archives every live assignment of the module; returns how many changed.
+pub async fn archive_assignments(db: &DatabaseConnection, module_id: i64) -> Result<u64, DbErr> {
+    let res = assignment::Entity::update_many()
+        .col_expr(assignment::Column::Status, Expr::value(Status::Archived))
+        .col_expr(assignment::Column::UpdatedAt, Expr::value(Utc::now()))
+        .filter(assignment::Column::ModuleId.eq(module_id))
+        .filter(assignment::Column::DeletedAt.is_null())
+        .filter(assignment::Column::Status.ne(Status::Archived))
+        .exec(db)
+        .await?;
+    Ok(res.rows_affected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::assignment::AssignmentType;
+    use crate::test_utils::setup_test_db;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn rollover_copies_staff_and_archives_assignments() {
+        let db = setup_test_db().await;
+        let source = module::Model::create(&db, "COS301", 2025, Some("SE"), 16)
+            .await
+            .unwrap();
+        for (name, role) in [
+            ("tutor", Role::Tutor),
+            ("lecturer", Role::Lecturer),
+            ("student", Role::Student),
+        ] {
+            let u = UserModel::create(&db, name, &format!("{name}@test.com"), "pw", false)
+                .await
+                .unwrap();
+            user_module_role::Model::assign_user_to_module(&db, u.id, source.id, role)
+                .await
+                .unwrap();
+        }
+        let a = assignment::Model::create(
+            &db,
+            source.id,
+            "A1",
+            None,
+            AssignmentType::Assignment,
+            Utc::now(),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+        let staff_roles: Vec<Role> = staff(&db, source.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, r)| r)
+            .collect();
+        assert_eq!(staff_roles, [Role::Lecturer, Role::Tutor]);
+
+        let next = create_module(&db, &source, "COS301", 2026, Some("SE"), true)
+            .await
+            .unwrap();
+        assert_eq!(next.credits, 16);
+        assert_eq!(staff(&db, next.id).await.unwrap().len(), 2);
+        assert!(
+            user_module_role::Model::get_users_by_module_role(&db, next.id, Role::Student)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(
+            archivable_assignments(&db, source.id).await.unwrap().len(),
+            1
+        );
+        assert_eq!(archive_assignments(&db, source.id).await.unwrap(), 1);
+        assert_eq!(archive_assignments(&db, source.id).await.unwrap(), 0);
+        let a = assignment::Entity::find_by_id(a.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(a.status, Status::Archived);
+    }
+
+    #[test]
+    fn shift_years_keeps_the_calendar_date() {
+        let d = Utc.with_ymd_and_hms(2025, 3, 1, 8, 0, 0).unwrap();
+        assert_eq!(
+            shift_years(d, 1),
+            Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap()
+        );
+        let leap = Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap();
+        assert_eq!(
+            shift_years(leap, 1),
+            Utc.with_ymd_and_hms(2025, 2, 28, 0, 0, 0).unwrap()
+        );
+    }
+}
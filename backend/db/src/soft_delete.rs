@@ -0,0 +1,207 @@
+//! Soft deletion and the retention sweep.
+//!
+//! Assignments, submissions, announcements and tickets are not removed when deleted; their
+//! `deleted_at` is set instead and they drop out of every [`SoftDelete::find_active`] query.
+//! They can be restored until [`purge_deleted_before`] hard-deletes them once the retention
+//! period (`SOFT_DELETE_RETENTION_DAYS`) has passed.
+
+use crate::models::{announcements, assignment, assignment_submission, content_blob, tickets};
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter, Select, UpdateMany,
+};
+use serde::Serialize;
+
+/// An entity with a nullable `deleted_at` column.
+pub trait SoftDelete: EntityTrait {
+    /// The entity's `deleted_at` column.
+    fn deleted_at_column() -> Self::Column;
+
+    /// Rows that have not been deleted. Use this instead of `find()` for anything user-facing.
+    fn find_active() -> Select<Self> {
+        Self::find().filter(Self::deleted_at_column().is_null())
+    }
+
+    /// Rows that have been deleted and are waiting to be restored or purged.
+    fn find_deleted() -> Select<Self> {
+        Self::find().filter(Self::deleted_at_column().is_not_null())
+    }
+
+    /// Marks the filtered rows as deleted now.
+    fn mark_deleted() -> UpdateMany<Self> {
+        Self::update_many()
+            .col_expr(Self::deleted_at_column(), Expr::value(Utc::now()))
+            .filter(Self::deleted_at_column().is_null())
+    }
+
+    /// Clears `deleted_at` on the filtered rows.
+    fn mark_restored() -> UpdateMany<Self> {
+        Self::update_many()
+            .col_expr(
+                Self::deleted_at_column(),
+                Expr::value(Option::<DateTime<Utc>>::None),
+            )
+            .filter(Self::deleted_at_column().is_not_null())
+    }
+}
+
+impl SoftDelete for assignment::Entity {
+    fn deleted_at_column() -> Self::Column {
+        assignment::Column::DeletedAt
+    }
+}
+
+impl SoftDelete for assignment_submission::Entity {
+    fn deleted_at_column() -> Self::Column {
+        assignment_submission::Column::DeletedAt
+    }
+}
+
+impl SoftDelete for announcements::Entity {
+    fn deleted_at_column() -> Self::Column {
+        announcements::Column::DeletedAt
+    }
+}
+
+impl SoftDelete for tickets::Entity {
+    fn deleted_at_column() -> Self::Column {
+        tickets::Column::DeletedAt
+    }
+}
+
+/// Rows hard-deleted by one retention sweep.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct SweepReport {
+    pub assignments: u64,
+    pub submissions: u64,
+    pub announcements: u64,
+    pub tickets: u64,
+}
+
+impl SweepReport {
+    pub fn total(&self) -> u64 {
+        self.assignments + self.submissions + self.announcements + self.tickets
+    }
+}
+
+/// The cut-off for a retention period of `days`: rows deleted before it are due for purging.
+pub fn retention_cutoff(days: u64) -> DateTime<Utc> {
+    Utc::now() - Duration::days(days.min(i64::MAX as u64 / 86_400) as i64)
+}
+
+/// Hard-deletes every row soft deleted before `cutoff`, along with its stored files.
+///
+/// Assignments go last so their submissions and tickets are counted here rather than
+/// disappearing in the cascade.
+pub async fn purge_deleted_before(
+    db: &DatabaseConnection,
+    cutoff: DateTime<Utc>,
+) -> Result<SweepReport, DbErr> {
+    let mut report = SweepReport::default();
+
+    let submissions = assignment_submission::Entity::find_deleted()
+        .filter(assignment_submission::Column::DeletedAt.lt(cutoff))
+        .all(db)
+        .await?;
+    for submission in submissions {
+        submission.purge(db).await?;
+        report.submissions += 1;
+    }
+
+    report.tickets = tickets::Entity::delete_many()
+        .filter(tickets::Column::DeletedAt.lt(cutoff))
+        .exec(db)
+        .await?
+        .rows_affected;
+
+    report.announcements = announcements::Entity::delete_many()
+        .filter(announcements::Column::DeletedAt.lt(cutoff))
+        .exec(db)
+        .await?
+        .rows_affected;
+
+    let assignments = assignment::Entity::find_deleted()
+        .filter(assignment::Column::DeletedAt.lt(cutoff))
+        .all(db)
+        .await?;
+    for assignment in assignments {
+        assignment.purge(db).await?;
+        report.assignments += 1;
+    }
+
+    if report.submissions > 0 || report.assignments > 0 {
+        // Purged files and submissions may have been the last users of some blobs.
+        content_blob::Model::prune(db).await?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{module, user};
+    use crate::test_utils::setup_test_db;
+    use sea_orm::{ActiveModelTrait, IntoActiveModel, Set};
+
+    #[tokio::test]
+    async fn purges_only_rows_deleted_before_the_cutoff() {
+        let db = setup_test_db().await;
+        let module = module::Model::create(&db, "COS301", 2025, None, 16)
+            .await
+            .unwrap();
+        let author = user::Model::create(&db, "lecturer", "l@test.com", "pw", false)
+            .await
+            .unwrap();
+
+        let old = announcements::Model::create(&db, module.id, author.id, "Old", "b", false)
+            .await
+            .unwrap();
+        let recent = announcements::Model::create(&db, module.id, author.id, "New", "b", false)
+            .await
+            .unwrap();
+        let live = announcements::Model::create(&db, module.id, author.id, "Live", "b", false)
+            .await
+            .unwrap();
+
+        announcements::Model::delete(&db, module.id, old.id)
+            .await
+            .unwrap();
+        announcements::Model::delete(&db, module.id, recent.id)
+            .await
+            .unwrap();
+        let mut old = announcements::Entity::find_by_id(old.id)
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap()
+            .into_active_model();
+        old.deleted_at = Set(Some(Utc::now() - Duration::days(40)));
+        old.update(&db).await.unwrap();
+
+        let active = announcements::Entity::find_active().all(&db).await.unwrap();
+        assert_eq!(active.iter().map(|a| a.id).collect::<Vec<_>>(), [live.id]);
+
+        let report = purge_deleted_before(&db, retention_cutoff(30)).await.unwrap();
+        assert_eq!(report.announcements, 1);
+        assert_eq!(report.total(), 1);
+
+        let remaining = announcements::Entity::find().all(&db).await.unwrap();
+        let mut ids: Vec<_> = remaining.iter().map(|a| a.id).collect();
+        ids.sort();
+        assert_eq!(ids, [recent.id, live.id]);
+
+        announcements::Model::restore(&db, module.id, recent.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            announcements::Entity::find_active()
+                .all(&db)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+}
@@ -1,15 +1,218 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
 use migration::Migrator;
-use sea_orm::{Database, DatabaseConnection};
+use sea_orm::{
+    ConnectOptions, ConnectionTrait, Database, DatabaseConnection, DatabaseTransaction, DbErr,
+    TransactionTrait,
+};
 use sea_orm_migration::MigratorTrait;
 
+/// Env var naming a PostgreSQL database (e.g. `postgres://postgres@localhost:5432/postgres`) to
+/// run tests against instead of in-memory SQLite. Every test gets its own `fitchfork_test_*`
+/// schema there, which is left behind, so point it at a throwaway database.
+pub const TEST_DATABASE_URL: &str = "TEST_DATABASE_URL";
+
+/// Fresh in-memory SQLite database with every migration applied.
+///
+/// Each call gets its own private database (a `sqlite::memory:` pool holds a single
+/// connection), so tests using it never see each other's rows and can run in parallel.
+/// With [`TEST_DATABASE_URL`] set, a fresh PostgreSQL database is used instead.
 pub async fn setup_test_db() -> DatabaseConnection {
+    if let Ok(server) = std::env::var(TEST_DATABASE_URL) {
+        return setup_postgres_test_db(&server, 4).await;
+    }
+
     let db = Database::connect("sqlite::memory:")
         .await
         .expect("Failed to connect to in-memory db");
 
-    Migrator::up(&db, None)
+    apply_migrations(&db)
+        .await
+        .expect("Failed to run migrations");
+
+    db
+}
+
+/// Like [`setup_test_db`], but backed by a uniquely named shared-cache in-memory database so
+/// the pool can hand out up to `max_connections` connections that all see the same data.
+///
+/// Use this for code under test that queries from several tasks at once (e.g. the submission
+/// pipeline), which would otherwise queue behind the single `sqlite::memory:` connection.
+pub async fn setup_pooled_test_db(max_connections: u32) -> DatabaseConnection {
+    if let Ok(server) = std::env::var(TEST_DATABASE_URL) {
+        return setup_postgres_test_db(&server, max_connections).await;
+    }
+
+    let url = format!(
+        "sqlite:file:fitchfork_test_{}?mode=memory&cache=shared",
+        uuid::Uuid::new_v4().simple()
+    );
+
+    // A named in-memory database is dropped once its last connection closes,
+    // so keep one connection around for the lifetime of the pool.
+    let mut opts = ConnectOptions::new(url);
+    opts.max_connections(max_connections.max(1))
+        .min_connections(1)
+        .idle_timeout(Duration::from_secs(24 * 60 * 60))
+        .max_lifetime(Duration::from_secs(24 * 60 * 60))
+        .sqlx_logging(false);
+
+    let db = Database::connect(opts)
+        .await
+        .expect("Failed to connect to pooled in-memory db");
+
+    apply_migrations(&db)
+        .await
+        .expect("Failed to run migrations");
+
+    db
+}
+
+/// Creates a uniquely named schema in the PostgreSQL database at `url` and migrates it.
+///
+/// Schemas rather than databases keep this cheap: an empty database costs megabytes of catalog.
+async fn setup_postgres_test_db(url: &str, max_connections: u32) -> DatabaseConnection {
+    let schema = format!("fitchfork_test_{}", uuid::Uuid::new_v4().simple());
+    let admin = Database::connect(url)
+        .await
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    admin
+        .execute_unprepared(&format!("CREATE SCHEMA \"{schema}\""))
+        .await
+        .expect("Failed to create test schema");
+    let _ = admin.close().await;
+
+    let mut opts = ConnectOptions::new(url);
+    opts.max_connections(max_connections.max(1))
+        .set_schema_search_path(schema)
+        .sqlx_logging(false);
+    let db = Database::connect(opts)
+        .await
+        .expect("Failed to connect to test schema");
+
+    apply_migrations(&db)
         .await
         .expect("Failed to run migrations");
 
     db
 }
+
+/// Applies all pending migrations from the `migration` crate.
+pub async fn apply_migrations(db: &DatabaseConnection) -> Result<(), DbErr> {
+    Migrator::up(db, None).await
+}
+
+/// Drops every table and re-applies all migrations, leaving an empty schema.
+pub async fn reset_test_db(db: &DatabaseConnection) -> Result<(), DbErr> {
+    Migrator::fresh(db).await
+}
+
+/// Begins a transaction that is meant to never be committed.
+///
+/// Anything written through it is rolled back when it is dropped (or on an explicit
+/// `rollback()`), so several test cases can seed data on top of one migrated database.
+pub async fn begin_rollback_txn(db: &DatabaseConnection) -> DatabaseTransaction {
+    db.begin().await.expect("Failed to begin test transaction")
+}
+
+/// Runs `f` inside a transaction that is always rolled back afterwards and returns
+/// whatever `f` produced.
+///
+/// ```ignore
+/// let count = with_rollback(&db, |txn| Box::pin(async move {
+///     UserModel::create(txn, "u1", "u1@test.com", "pw", false).await.unwrap();
+///     user::Entity::find().count(txn).await.unwrap()
+/// }))
+/// .await;
+/// ```
+pub async fn with_rollback<F, T>(db: &DatabaseConnection, f: F) -> T
+where
+    F: for<'c> FnOnce(&'c DatabaseTransaction) -> Pin<Box<dyn Future<Output = T> + Send + 'c>>,
+{
+    let txn = begin_rollback_txn(db).await;
+    let out = f(&txn).await;
+    txn.rollback()
+        .await
+        .expect("Failed to roll back test transaction");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user;
+    use chrono::Utc;
+    use sea_orm::{ActiveModelTrait, EntityTrait, PaginatorTrait, Set};
+
+    async fn insert_user(db: &impl sea_orm::ConnectionTrait, username: &str) {
+        user::ActiveModel {
+            username: Set(username.to_string()),
+            email: Set(format!("{username}@test.com")),
+            password_hash: Set("x".into()),
+            admin: Set(false),
+            created_at: Set(Utc::now()),
+            updated_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn pooled_dbs_are_isolated_from_each_other() {
+        let a = setup_pooled_test_db(4).await;
+        let b = setup_pooled_test_db(4).await;
+
+        insert_user(&a, "only_in_a").await;
+
+        assert_eq!(user::Entity::find().count(&a).await.unwrap(), 1);
+        assert_eq!(user::Entity::find().count(&b).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn pooled_db_is_shared_across_concurrent_tasks() {
+        let db = setup_pooled_test_db(4).await;
+
+        let mut handles = Vec::new();
+        for i in 0..8 {
+            let db = db.clone();
+            handles.push(tokio::spawn(async move {
+                insert_user(&db, &format!("user_{i}")).await;
+            }));
+        }
+        for h in handles {
+            h.await.unwrap();
+        }
+
+        assert_eq!(user::Entity::find().count(&db).await.unwrap(), 8);
+    }
+
+    #[tokio::test]
+    async fn with_rollback_discards_writes() {
+        let db = setup_test_db().await;
+
+        let seen = with_rollback(&db, |txn| {
+            Box::pin(async move {
+                insert_user(txn, "temp").await;
+                user::Entity::find().count(txn).await.unwrap()
+            })
+        })
+        .await;
+
+        assert_eq!(seen, 1);
+        assert_eq!(user::Entity::find().count(&db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn reset_test_db_empties_tables() {
+        let db = setup_test_db().await;
+        insert_user(&db, "gone").await;
+
+        reset_test_db(&db).await.unwrap();
+
+        assert_eq!(user::Entity::find().count(&db).await.unwrap(), 0);
+    }
+}
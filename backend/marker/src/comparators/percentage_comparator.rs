@@ -111,6 +111,10 @@ mod tests {
             value,
             regex: None,
             feedback: None,
+            memory_thresholds: None,
+            comparator: None,
+            weight: None,
+            subsections: None,
         }
     }
 
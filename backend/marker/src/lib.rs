@@ -20,7 +20,9 @@ pub mod traits;
 pub mod types;
 pub mod utilities;
 
+use crate::comparators::exact_comparator::ExactComparator;
 use crate::comparators::percentage_comparator::PercentageComparator;
+use crate::comparators::regex_comparator::RegexComparator;
 use crate::error::MarkerError;
 use crate::feedback::auto_feedback::AutoFeedback;
 use crate::report::MarkReportResponse;
@@ -36,6 +38,7 @@ use util::code_coverage_report::CoverageReport;
 use util::execution_config::ExecutionConfig;
 use util::execution_config::MarkingScheme;
 use util::mark_allocator;
+use util::massif_report::MassifReport;
 use util::valgrind_report::ValgrindReport;
 
 /// Represents a marking job for a single student submission.
@@ -49,6 +52,7 @@ use util::valgrind_report::ValgrindReport;
 /// - `allocator`: **Allocator object** describing the task/subtask structure and scoring.
 /// - `coverage_report`: Optional path to a code coverage report.
 /// - `valgrind_report`: Optional path to a valgrind memory leak report.
+/// - `massif_report`: Optional path to a massif peak heap usage report.
 /// - `comparator`: Strategy for comparing outputs (e.g., percentage, exact).
 /// - `feedback`: Automated feedback generation for each subtask.
 pub struct MarkingJob<'a> {
@@ -57,6 +61,7 @@ pub struct MarkingJob<'a> {
     allocator: mark_allocator::MarkAllocator,
     coverage_report: Option<PathBuf>,
     valgrind_report: Option<PathBuf>,
+    massif_report: Option<PathBuf>,
     comparator: Box<dyn OutputComparator + Send + Sync + 'a>,
     feedback: Box<dyn Feedback + Send + Sync + 'a>,
     config: ExecutionConfig,
@@ -91,6 +96,7 @@ impl<'a> MarkingJob<'a> {
             allocator,
             coverage_report: None,
             valgrind_report: None,
+            massif_report: None,
             comparator: Box::new(PercentageComparator),
             feedback: Box::new(AutoFeedback),
             config,
@@ -115,6 +121,15 @@ impl<'a> MarkingJob<'a> {
         self
     }
 
+    /// Attach a massif heap usage report to the marking job.
+    ///
+    /// # Arguments
+    /// * `report` - Path to the massif report file.
+    pub fn with_massif(mut self, report: PathBuf) -> Self {
+        self.massif_report = Some(report);
+        self
+    }
+
     /// Set a custom output comparator strategy for this marking job.
     ///
     /// # Arguments
@@ -197,13 +212,28 @@ impl<'a> MarkingJob<'a> {
             None => None,
         };
 
+        let massif_report: Option<MassifReport> = match &self.massif_report {
+            Some(path) => {
+                let s = fs::read_to_string(path).map_err(|e| {
+                    MarkerError::InputMismatch(format!(
+                        "Failed to read massif file {:?}: {e}",
+                        path
+                    ))
+                })?;
+                let report: MassifReport = serde_json::from_str(&s)
+                    .map_err(|e| MarkerError::InvalidJson(format!("Invalid massif JSON: {e}")))?;
+                Some(report)
+            }
+            None => None,
+        };
+
         let allocator = self.allocator;
 
         let expected_counts: Vec<usize> = allocator
             .tasks
             .iter()
             .filter(|t| !t.code_coverage.unwrap_or(false))
-            .map(|task| task.subsections.len())
+            .map(|task| task.leaf_subsections().len())
             .collect();
 
         // Parse outputs
@@ -241,7 +271,13 @@ impl<'a> MarkingJob<'a> {
             let mut task_results: Vec<TaskResult> = Vec::new();
 
             if let Some(task_output) = submission_task {
-                for (sub_index, subsection) in task_entry.subsections.iter().enumerate() {
+                for (sub_index, subsection) in task_entry.leaf_subsections().iter().enumerate() {
+                    // A subsection may override the assignment's marking scheme.
+                    let scheme = subsection
+                        .comparator
+                        .as_ref()
+                        .unwrap_or(&self.config.marking.marking_scheme);
+
                     let mut student_lines = task_output
                         .student_output
                         .subtasks
@@ -249,8 +285,7 @@ impl<'a> MarkingJob<'a> {
                         .map(|s| s.lines.clone())
                         .unwrap_or_default();
 
-                    let memo_or_regex_lines: Vec<String> = match self.config.marking.marking_scheme
-                    {
+                    let memo_or_regex_lines: Vec<String> = match scheme {
                         MarkingScheme::Regex => match subsection.regex.clone() {
                             Some(patterns) => patterns,
                             None => {
@@ -269,7 +304,7 @@ impl<'a> MarkingJob<'a> {
                     };
 
                     if self.config.marking.reorder_by_memo
-                        && !matches!(self.config.marking.marking_scheme, MarkingScheme::Regex)
+                        && !matches!(scheme, MarkingScheme::Regex)
                     {
                         student_lines =
                             crate::utilities::line_normalization::reorder_student_by_memo(
@@ -300,11 +335,14 @@ impl<'a> MarkingJob<'a> {
                         }
                     } else {
                         // No errors detected, proceed with normal comparison
-                        let mut comparison_result = self.comparator.compare(
-                            subsection,
-                            &memo_or_regex_lines,
-                            &student_lines,
-                        );
+                        let comparator: &dyn OutputComparator = match &subsection.comparator {
+                            Some(MarkingScheme::Exact) => &ExactComparator,
+                            Some(MarkingScheme::Percentage) => &PercentageComparator,
+                            Some(MarkingScheme::Regex) => &RegexComparator,
+                            None => self.comparator.as_ref(),
+                        };
+                        let mut comparison_result =
+                            comparator.compare(subsection, &memo_or_regex_lines, &student_lines);
                         comparison_result.stderr = task_output.stderr.clone();
                         comparison_result.return_code = task_output.return_code;
                         comparison_result
@@ -324,53 +362,64 @@ impl<'a> MarkingJob<'a> {
                                     task_output.return_code.unwrap_or(0)
                                 )
                             });
+                    } else if subsection.memory_thresholds.is_some() {
+                        // Marked on the task's peak heap usage instead of its output
+                        let peak = massif_report
+                            .as_ref()
+                            .and_then(|r| r.task(task_entry.task_number))
+                            .map(|t| t.peak_heap_bytes);
+                        match peak.and_then(|p| subsection.memory_percent(p).map(|pct| (p, pct))) {
+                            Some((peak, percent)) => {
+                                result.awarded = subsection.value * percent / 100.0;
+                                section_feedback = format!(
+                                    "Peak heap usage: {peak} bytes ({percent}% of the memory usage marks)."
+                                );
+                            }
+                            None => {
+                                result.awarded = 0.0;
+                                section_feedback = "No heap profile found for this task. Run it under `valgrind --tool=massif` and print the profile with `ms_print`.".to_string();
+                            }
+                        }
                     } else if task_entry.valgrind.unwrap_or(false) {
-                        // Only check for memory leaks if there are no compilation/runtime errors
-                        let is_memory_leak_section =
-                            subsection.name.to_lowercase().contains("memory leak")
-                                || subsection
-                                    .feedback
-                                    .as_ref()
-                                    .map(|f| f.to_lowercase().contains("valgrind"))
-                                    .unwrap_or(false);
-
-                        if is_memory_leak_section {
-                            if let Some(valgrind_report_ref) = valgrind_report.as_ref() {
-                                let task_has_leaks = valgrind_report_ref
-                                    .tasks
-                                    .iter()
-                                    .find(|t| t.task_number == task_entry.task_number)
-                                    .map(|t| t.leaked)
-                                    .unwrap_or(false);
-
-                                if task_has_leaks {
-                                    result.awarded = 0.0;
-                                    let leaked_bytes = valgrind_report_ref
-                                        .tasks
-                                        .iter()
-                                        .find(|t| t.task_number == task_entry.task_number)
-                                        .map(|t| t.bytes_leaked)
-                                        .unwrap_or(0);
-                                    section_feedback = format!(
-                                        "Memory leaks detected: {} bytes leaked. Fix memory leaks to earn points.",
-                                        leaked_bytes
-                                    );
-                                } else {
-                                    result.awarded = subsection.value;
-                                    section_feedback =
-                                        "No memory leaks detected. Well done!".to_string();
-                                }
+                        // Only check for memory problems if there are no compilation/runtime errors
+                        if subsection.is_memory_leak_section()
+                            && let Some(valgrind_report_ref) = valgrind_report.as_ref()
+                        {
+                            let valgrind_task = valgrind_report_ref
+                                .tasks
+                                .iter()
+                                .find(|t| t.task_number == task_entry.task_number);
+                            let problems = valgrind_task.map(|t| t.problems()).unwrap_or_default();
+
+                            if problems.is_empty() {
+                                result.awarded = subsection.value;
+                                section_feedback =
+                                    "No memory leaks or memory errors detected. Well done!"
+                                        .to_string();
+                            } else {
+                                let fraction = valgrind_task
+                                    .map(|t| t.marked_fraction(&self.config.valgrind))
+                                    .unwrap_or(0.0);
+                                result.awarded = subsection.value * fraction;
+                                section_feedback = format!(
+                                    "Memory problems detected: {}. Fix them to earn full points.",
+                                    problems.join(", ")
+                                );
                             }
                         }
                     }
 
+                    if let Some(weight) = subsection.weight {
+                        result.awarded *= weight;
+                        result.possible *= weight;
+                    }
                     result.awarded = round2(result.awarded);
                     task_earned += result.awarded;
 
                     subsections.push(crate::report::ReportSubsection {
                         label: subsection.name.clone(),
                         earned: result.awarded,
-                        total: round2(subsection.value),
+                        total: round2(result.possible),
                         feedback: section_feedback,
                     });
                     task_results.push(result.clone());
@@ -429,8 +478,12 @@ impl<'a> MarkingJob<'a> {
 
         let mut coverage_total_earned: f64 = 0.0;
         let mut coverage_total_possible: f64 = 0.0;
+        let mut coverage_marked_percent: f64 = 0.0;
         if let Some(coverage_report_ref) = coverage_report.as_ref() {
-            let bucket_percent: f64 = match coverage_report_ref.summary.coverage_percent {
+            coverage_marked_percent = coverage_report_ref
+                .summary
+                .marked_percent(&self.config.code_coverage);
+            let bucket_percent: f64 = match coverage_marked_percent {
                 p if p < 5.0 => 0.0,
                 p if p < 20.0 => 20.0,
                 p if p < 40.0 => 40.0,
@@ -469,6 +522,9 @@ impl<'a> MarkingJob<'a> {
                         total_lines: coverage_report_ref.summary.total_lines,
                         covered_lines: coverage_report_ref.summary.covered_lines,
                         coverage_percent: coverage_report_ref.summary.coverage_percent,
+                        branch_percent: coverage_report_ref.summary.branch_percent,
+                        function_percent: coverage_report_ref.summary.function_percent,
+                        marked_percent: coverage_marked_percent,
                     }),
                     files: coverage_report_ref
                         .files
@@ -490,10 +546,18 @@ impl<'a> MarkingJob<'a> {
                 .filter(|t| t.leaked)
                 .count();
 
+            let tasks_with_errors = valgrind_report_ref
+                .tasks
+                .iter()
+                .filter(|t| !t.problems().is_empty())
+                .count();
+
             report.valgrind = Some(crate::report::ValgrindReport {
                 summary: Some(crate::report::ValgrindSummary {
                     total_leaks: valgrind_report_ref.total_leaks,
                     tasks_with_leaks,
+                    total_errors: valgrind_report_ref.total_errors,
+                    tasks_with_errors,
                     total_tasks: valgrind_report_ref.total_tasks,
                 }),
                 tasks: valgrind_report_ref
@@ -503,6 +567,12 @@ impl<'a> MarkingJob<'a> {
                         task_number: t.task_number,
                         has_leaks: t.leaked,
                         bytes_leaked: t.bytes_leaked,
+                        bytes_indirectly_lost: t.bytes_indirectly_lost,
+                        bytes_possibly_lost: t.bytes_possibly_lost,
+                        invalid_reads: t.invalid_reads,
+                        invalid_writes: t.invalid_writes,
+                        uninitialised_values: t.uninitialised_values,
+                        earned_fraction: t.marked_fraction(&self.config.valgrind),
                     })
                     .collect(),
             });
@@ -983,6 +1053,54 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_nested_subsections_use_their_comparator_and_weight() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let memo1 = tmp.path().join("memo1.txt");
+        let student1 = tmp.path().join("student1.txt");
+        std::fs::write(&memo1, "Task\n###A\nx\ny\n###B\nz\n").unwrap();
+        std::fs::write(&student1, "Task\n###A\nx\nwrong\n###B\nz\n").unwrap();
+
+        let allocator: mark_allocator::MarkAllocator = serde_json::from_value(serde_json::json!({
+            "generated_at": "2025-01-01T00:00:00Z",
+            "total_value": 4.0,
+            "tasks": [{
+                "task_number": 1,
+                "name": "Nested",
+                "value": 4.0,
+                "valgrind": false,
+                "subsections": [{
+                    "name": "Group",
+                    "value": 4.0,
+                    "subsections": [
+                        { "name": "A", "value": 2.0, "comparator": "percentage" },
+                        { "name": "B", "value": 1.0, "weight": 2.0 }
+                    ]
+                }]
+            }]
+        }))
+        .unwrap();
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.marking.marking_scheme = MarkingScheme::Exact;
+
+        let job = MarkingJob::new(vec![memo1], vec![student1], allocator, cfg)
+            .with_comparator(ExactComparator);
+        let report = job.mark().await.expect("mark should succeed").data;
+
+        // A: half its lines under the percentage override; B: exact match, doubled.
+        let subsections = &report.tasks[0].subsections;
+        let scores: Vec<_> = subsections
+            .iter()
+            .map(|s| (s.label.as_str(), s.earned, s.total))
+            .collect();
+        assert_eq!(
+            scores,
+            vec![("Group / A", 1.0, 2.0), ("Group / B", 2.0, 2.0)]
+        );
+        assert_eq!(report.mark.earned, 3.0);
+        assert_eq!(report.mark.total, 4.0);
+    }
+
     #[tokio::test]
     async fn test_regex_scheme_ignores_reorder_flag() {
         let dir = "src/test_files/marker/case3"; // any valid fixture where content exists
@@ -1032,4 +1150,77 @@ mod tests {
             "Regex scheme should ignore reordering"
         );
     }
+
+    #[tokio::test]
+    async fn test_memory_thresholds_mark_peak_heap_usage() {
+        use std::fs;
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let dir = tmp.path();
+
+        let output = "make task1\n&-=-&Output\nA\nB\n";
+        let memo1 = dir.join("memo1.txt");
+        let student1 = dir.join("student1.txt");
+        fs::write(&memo1, output).unwrap();
+        fs::write(&student1, output).unwrap();
+
+        let allocator: mark_allocator::MarkAllocator = serde_json::from_value(serde_json::json!({
+            "generated_at": "2025-01-01T00:00:00Z",
+            "total_value": 6.0,
+            "tasks": [{
+                "task_number": 1,
+                "name": "Heap",
+                "value": 6.0,
+                "valgrind": true,
+                "subsections": [
+                    { "name": "Output", "value": 2.0 },
+                    {
+                        "name": "Memory usage",
+                        "value": 4.0,
+                        "memory_thresholds": [
+                            { "max_peak_bytes": 4096, "percent": 50.0 },
+                            { "max_peak_bytes": 1024, "percent": 100.0 }
+                        ]
+                    }
+                ]
+            }]
+        }))
+        .unwrap();
+
+        let massif = dir.join("massif_report.json");
+        fs::write(
+            &massif,
+            serde_json::json!({
+                "generated_at": "2025-01-01T00:00:00Z",
+                "total_tasks": 1,
+                "tasks": [{ "task_number": 1, "peak_heap_bytes": 2000, "snapshots": 12 }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let job = MarkingJob::new(
+            vec![memo1.clone()],
+            vec![student1.clone()],
+            allocator.clone(),
+            ExecutionConfig::default_config(),
+        )
+        .with_massif(massif);
+        let report = job.mark().await.expect("mark should succeed").data;
+
+        let memory = &report.tasks[0].subsections[1];
+        assert_eq!(memory.earned, 2.0, "2000 bytes falls in the 50% band");
+        assert!(memory.feedback.contains("2000 bytes"));
+        assert_eq!(report.mark.earned, 4.0);
+
+        // Without a heap profile the memory usage marks are lost.
+        let job = MarkingJob::new(
+            vec![memo1],
+            vec![student1],
+            allocator,
+            ExecutionConfig::default_config(),
+        );
+        let report = job.mark().await.expect("mark should succeed").data;
+        assert_eq!(report.tasks[0].subsections[1].earned, 0.0);
+        assert_eq!(report.mark.earned, 2.0);
+    }
 }
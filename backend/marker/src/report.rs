@@ -88,6 +88,14 @@ pub struct CoverageSummary {
     pub covered_lines: u64,
     /// Overall coverage percentage (0.0 - 100.0).
     pub coverage_percent: f64,
+    /// Branch coverage percentage, if the coverage tool reports branches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch_percent: Option<f64>,
+    /// Function coverage percentage, if the coverage tool reports functions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function_percent: Option<f64>,
+    /// Percentage the mark is based on, per the configured coverage metric.
+    pub marked_percent: f64,
 }
 
 /// Represents a subsection of a grading task, such as a subtask or rubric item.
@@ -141,6 +149,10 @@ pub struct ValgrindSummary {
     pub total_leaks: u64,
     /// Number of tasks with leaks.
     pub tasks_with_leaks: usize,
+    /// Invalid reads, invalid writes and uninitialised value errors across all tasks.
+    pub total_errors: u64,
+    /// Number of tasks with any leak or memory error.
+    pub tasks_with_errors: usize,
     /// Total number of tasks analyzed.
     pub total_tasks: usize,
 }
@@ -152,8 +164,20 @@ pub struct ValgrindTaskReport {
     pub task_number: i64,
     /// Whether this task has memory leaks.
     pub has_leaks: bool,
-    /// Number of bytes leaked in this task.
+    /// Number of bytes definitely lost in this task.
     pub bytes_leaked: u64,
+    /// Number of bytes indirectly lost.
+    pub bytes_indirectly_lost: u64,
+    /// Number of bytes possibly lost.
+    pub bytes_possibly_lost: u64,
+    /// Number of invalid read errors.
+    pub invalid_reads: u64,
+    /// Number of invalid write errors.
+    pub invalid_writes: u64,
+    /// Number of uninitialised value errors.
+    pub uninitialised_values: u64,
+    /// Fraction (0 to 1) of the memory check's marks earned, per the configured weights.
+    pub earned_fraction: f64,
 }
 
 /// Represents code coverage information for a single file.
@@ -349,6 +373,9 @@ mod tests {
                 total_lines: 100,
                 covered_lines: 50,
                 coverage_percent: 50.0,
+                branch_percent: None,
+                function_percent: None,
+                marked_percent: 50.0,
             }),
             files: vec![CoverageFile {
                 path: "src/lib.rs".to_string(),
@@ -18,7 +18,7 @@ impl MigrationTrait for Migration {
                     .if_not_exists()
                     .col(
                         ColumnDef::new(Alias::new("id"))
-                            .integer()
+                            .big_integer()
                             .not_null()
                             .auto_increment()
                             .primary_key(),
@@ -34,13 +34,13 @@ impl MigrationTrait for Migration {
                     )
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -16,21 +16,17 @@ impl MigrationTrait for Migration {
                 Table::create()
                     .table(Alias::new("user_module_roles"))
                     .if_not_exists()
-                    .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
-                    .col(ColumnDef::new(Alias::new("module_id")).integer().not_null())
                     .col(
-                        ColumnDef::new(Alias::new("role"))
-                            .enumeration(
-                                Alias::new("user_module_role_type"),
-                                vec![
-                                    Alias::new("lecturer"),
-                                    Alias::new("assistant_lecturer"),
-                                    Alias::new("tutor"),
-                                    Alias::new("student"),
-                                ],
-                            )
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
                             .not_null(),
                     )
+                    .col(
+                        ColumnDef::new(Alias::new("module_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("role")).text().not_null())
                     .primary_key(
                         Index::create()
                             .col(Alias::new("user_id"))
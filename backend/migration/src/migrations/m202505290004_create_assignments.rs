@@ -18,56 +18,48 @@ impl MigrationTrait for Migration {
                     .if_not_exists()
                     .col(
                         ColumnDef::new(Alias::new("id"))
-                            .integer()
+                            .big_integer()
                             .not_null()
                             .auto_increment()
                             .primary_key(),
                     )
-                    .col(ColumnDef::new(Alias::new("module_id")).integer().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("module_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
                     .col(ColumnDef::new(Alias::new("name")).string().not_null())
                     .col(ColumnDef::new(Alias::new("description")).string().null())
                     .col(
                         ColumnDef::new(Alias::new("assignment_type"))
-                            .enumeration(
-                                Alias::new("assignment_type_enum"),
-                                vec![Alias::new("assignment"), Alias::new("practical")],
-                            )
+                            .text()
                             .not_null(),
                     )
                     .col(
                         ColumnDef::new(Alias::new("status"))
-                            .enumeration(
-                                Alias::new("status_enum"),
-                                vec![
-                                    Alias::new("setup"),
-                                    Alias::new("ready"),
-                                    Alias::new("open"),
-                                    Alias::new("closed"),
-                                    Alias::new("archived"),
-                                ],
-                            )
+                            .text()
                             .not_null()
                             .default("setup"),
                     )
                     .col(
                         ColumnDef::new(Alias::new("available_from"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null(),
                     )
                     .col(
                         ColumnDef::new(Alias::new("due_date"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null(),
                     )
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -18,42 +18,28 @@ impl MigrationTrait for Migration {
                     .if_not_exists()
                     .col(
                         ColumnDef::new(Alias::new("id"))
-                            .integer()
+                            .big_integer()
                             .not_null()
                             .auto_increment()
                             .primary_key(),
                     )
                     .col(
                         ColumnDef::new(Alias::new("assignment_id"))
-                            .integer()
+                            .big_integer()
                             .not_null(),
                     )
                     .col(ColumnDef::new(Alias::new("filename")).string().not_null())
                     .col(ColumnDef::new(Alias::new("path")).string().not_null())
-                    .col(
-                        ColumnDef::new(Alias::new("file_type"))
-                            .enumeration(
-                                Alias::new("assignment_file_type"),
-                                vec![
-                                    Alias::new("spec"),
-                                    Alias::new("main"),
-                                    Alias::new("memo"),
-                                    Alias::new("markallocator"),
-                                    Alias::new("makefile"),
-                                    Alias::new("config"),
-                                ],
-                            )
-                            .not_null(),
-                    )
+                    .col(ColumnDef::new(Alias::new("file_type")).text().not_null())
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -18,18 +18,26 @@ impl MigrationTrait for Migration {
                     .if_not_exists()
                     .col(
                         ColumnDef::new(Alias::new("id"))
-                            .integer()
+                            .big_integer()
                             .not_null()
                             .auto_increment()
                             .primary_key(),
                     )
                     .col(
                         ColumnDef::new(Alias::new("assignment_id"))
-                            .integer()
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("attempt"))
+                            .big_integer()
                             .not_null(),
                     )
-                    .col(ColumnDef::new(Alias::new("user_id")).integer().not_null())
-                    .col(ColumnDef::new(Alias::new("attempt")).integer().not_null())
                     .col(ColumnDef::new(Alias::new("filename")).string().not_null())
                     .col(ColumnDef::new(Alias::new("file_hash")).string().not_null())
                     .col(ColumnDef::new(Alias::new("path")).string().not_null())
@@ -38,8 +46,8 @@ impl MigrationTrait for Migration {
                             .boolean()
                             .not_null(),
                     )
-                    .col(ColumnDef::new(Alias::new("earned")).float().not_null())
-                    .col(ColumnDef::new(Alias::new("total")).float().not_null())
+                    .col(ColumnDef::new(Alias::new("earned")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("total")).double().not_null())
                     .col(
                         ColumnDef::new(Alias::new("ignored"))
                             .boolean()
@@ -48,33 +56,19 @@ impl MigrationTrait for Migration {
                     )
                     .col(
                         ColumnDef::new(Alias::new("status"))
-                            .enumeration(
-                                Alias::new("submission_status_enum"),
-                                vec![
-                                    Alias::new("queued"),
-                                    Alias::new("running"),
-                                    Alias::new("grading"),
-                                    Alias::new("graded"),
-                                    Alias::new("failed_upload"),
-                                    Alias::new("failed_compile"),
-                                    Alias::new("failed_execution"),
-                                    Alias::new("failed_grading"),
-                                    Alias::new("failed_internal"),
-                                    Alias::new("failed_disallowed_code"),
-                                ],
-                            )
+                            .text()
                             .not_null()
                             .default("queued"),
                     )
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -42,26 +42,19 @@ impl MigrationTrait for Migration {
                     .col(ColumnDef::new(Alias::new("command")).string().not_null())
                     .col(
                         ColumnDef::new(Alias::new("task_type"))
-                            .enumeration(
-                                Alias::new("task_type_enum"),
-                                vec![
-                                    Alias::new("normal"),
-                                    Alias::new("coverage"),
-                                    Alias::new("valgrind"),
-                                ],
-                            )
+                            .text()
                             .not_null()
                             .default("normal"),
                     )
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -37,22 +37,19 @@ impl MigrationTrait for Migration {
                     .col(ColumnDef::new(Alias::new("description")).text().not_null())
                     .col(
                         ColumnDef::new(Alias::new("status"))
-                            .enumeration(
-                                Alias::new("ticket_status"),
-                                vec![Alias::new("open"), Alias::new("closed")],
-                            )
+                            .text()
                             .not_null()
                             .default("open"),
                     )
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -18,14 +18,14 @@ impl MigrationTrait for Migration {
                     .if_not_exists()
                     .col(
                         ColumnDef::new(Alias::new("id"))
-                            .integer()
+                            .big_integer()
                             .not_null()
                             .auto_increment()
                             .primary_key(),
                     )
                     .col(
                         ColumnDef::new(Alias::new("assignment_id"))
-                            .integer()
+                            .big_integer()
                             .not_null(),
                     )
                     .col(ColumnDef::new(Alias::new("filename")).string().not_null())
@@ -33,13 +33,13 @@ impl MigrationTrait for Migration {
                     .col(ColumnDef::new(Alias::new("command")).string().not_null())
                     .col(
                         ColumnDef::new(Alias::new("created_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
                     .col(
                         ColumnDef::new(Alias::new("updated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
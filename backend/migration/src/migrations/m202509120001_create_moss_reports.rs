@@ -31,7 +31,7 @@ impl MigrationTrait for Migration {
                     .col(ColumnDef::new(Alias::new("report_url")).text().not_null())
                     .col(
                         ColumnDef::new(Alias::new("generated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .not_null()
                             .default(Expr::cust("CURRENT_TIMESTAMP")),
                     )
@@ -46,7 +46,7 @@ impl MigrationTrait for Migration {
                     )
                     .col(
                         ColumnDef::new(Alias::new("archive_generated_at"))
-                            .timestamp()
+                            .timestamp_with_time_zone()
                             .null(),
                     )
                     // generation filter metadata
@@ -0,0 +1,43 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160002_add_submission_output_metrics"
+    }
+}
+
+const COLUMNS: [&str; 3] = ["wall_time_ms", "cpu_time_ms", "max_rss_bytes"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // SQLite only supports one column per ALTER TABLE.
+        for column in COLUMNS {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("assignment_submission_outputs"))
+                        .add_column(ColumnDef::new(Alias::new(column)).big_integer().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in COLUMNS.iter().rev() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("assignment_submission_outputs"))
+                        .drop_column(Alias::new(*column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
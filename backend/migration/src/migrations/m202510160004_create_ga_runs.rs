@@ -0,0 +1,183 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160004_create_ga_runs"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // ga_runs: one row per GATLAM / code coverage run of a submission
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("ga_runs"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("submission_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("mode")).text().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("status"))
+                            .text()
+                            .not_null()
+                            .default("running"),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("generations"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("population_size"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("best_fitness")).double().null())
+                    .col(ColumnDef::new(Alias::new("best_payload")).text().null())
+                    .col(ColumnDef::new(Alias::new("error")).text().null())
+                    .col(
+                        ColumnDef::new(Alias::new("started_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("finished_at"))
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ga_runs_assignment")
+                            .from(Alias::new("ga_runs"), Alias::new("assignment_id"))
+                            .to(Alias::new("assignments"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ga_runs_submission")
+                            .from(Alias::new("ga_runs"), Alias::new("submission_id"))
+                            .to(Alias::new("assignment_submissions"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // ga_generations: per-generation stats and the evaluated population of a run
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("ga_generations"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("run_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("generation"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("best_fitness"))
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("mean_fitness"))
+                            .double()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("coverage_percent"))
+                            .double()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("best_payload")).text().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("population"))
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("evaluated"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("interpreter_runs"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("cache_hits"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_ga_generations_run")
+                            .from(Alias::new("ga_generations"), Alias::new("run_id"))
+                            .to(Alias::new("ga_runs"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_ga_generations_run_generation")
+                    .table(Alias::new("ga_generations"))
+                    .col(Alias::new("run_id"))
+                    .col(Alias::new("generation"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("ga_generations")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("ga_runs")).to_owned())
+            .await
+    }
+}
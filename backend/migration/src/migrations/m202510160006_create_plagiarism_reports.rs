@@ -0,0 +1,159 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160006_create_plagiarism_reports"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // plagiarism_reports: the parsed contents of a moss_report (one per report)
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("plagiarism_reports"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("moss_report_id"))
+                            .big_integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("title")).text().null())
+                    .col(
+                        ColumnDef::new(Alias::new("pair_count"))
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_plagiarism_reports_moss_report")
+                            .from(
+                                Alias::new("plagiarism_reports"),
+                                Alias::new("moss_report_id"),
+                            )
+                            .to(Alias::new("moss_reports"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_plagiarism_reports_assignment")
+                            .from(
+                                Alias::new("plagiarism_reports"),
+                                Alias::new("assignment_id"),
+                            )
+                            .to(Alias::new("assignments"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // plagiarism_matches: one row per user pair of a parsed report
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("plagiarism_matches"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("report_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("user_a")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("user_b")).text().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("submission_id_a"))
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("submission_id_b"))
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("total_percent")).double().null())
+                    .col(
+                        ColumnDef::new(Alias::new("lines_matched"))
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(ColumnDef::new(Alias::new("files")).json_binary().null())
+                    .col(ColumnDef::new(Alias::new("case_id")).big_integer().null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_plagiarism_matches_report")
+                            .from(Alias::new("plagiarism_matches"), Alias::new("report_id"))
+                            .to(Alias::new("plagiarism_reports"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_plagiarism_matches_case")
+                            .from(Alias::new("plagiarism_matches"), Alias::new("case_id"))
+                            .to(Alias::new("plagiarism_cases"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_plagiarism_matches_report")
+                    .table(Alias::new("plagiarism_matches"))
+                    .col(Alias::new("report_id"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("plagiarism_matches"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("plagiarism_reports"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160009_add_submission_run_state"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("assignment_submissions"))
+                    .add_column(
+                        ColumnDef::new(Alias::new("submission_run_state"))
+                            .text()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("assignment_submissions"))
+                    .drop_column(Alias::new("submission_run_state"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+/// Tables whose rows are soft deleted and later purged by the retention sweep.
+const TABLES: [&str; 4] = [
+    "assignments",
+    "assignment_submissions",
+    "announcements",
+    "tickets",
+];
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160010_add_soft_delete"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column(
+                            ColumnDef::new(Alias::new("deleted_at"))
+                                .timestamp_with_time_zone()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .create_index(
+                    Index::create()
+                        .name(format!("idx_{table}_deleted_at"))
+                        .table(Alias::new(table))
+                        .col(Alias::new("deleted_at"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in TABLES {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name(format!("idx_{table}_deleted_at"))
+                        .table(Alias::new(table))
+                        .to_owned(),
+                )
+                .await?;
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_column(Alias::new("deleted_at"))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160012_create_groups"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // groups: teams of students that submit together for one assignment
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("groups"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("updated_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_groups_assignment")
+                            .from(Alias::new("groups"), Alias::new("assignment_id"))
+                            .to(Alias::new("assignments"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_groups_assignment_name")
+                    .table(Alias::new("groups"))
+                    .col(Alias::new("assignment_id"))
+                    .col(Alias::new("name"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // group_members: `assignment_id` is denormalised so a student can only be in one
+        // group per assignment
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("group_members"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("group_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(Alias::new("group_id"))
+                            .col(Alias::new("user_id")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_group_members_group")
+                            .from(Alias::new("group_members"), Alias::new("group_id"))
+                            .to(Alias::new("groups"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_group_members_user")
+                            .from(Alias::new("group_members"), Alias::new("user_id"))
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_group_members_assignment_user")
+                    .table(Alias::new("group_members"))
+                    .col(Alias::new("assignment_id"))
+                    .col(Alias::new("user_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // The group a submission was made for, if any
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("assignment_submissions"))
+                    .add_column(ColumnDef::new(Alias::new("group_id")).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_assignment_submissions_group_id")
+                    .table(Alias::new("assignment_submissions"))
+                    .col(Alias::new("group_id"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_assignment_submissions_group_id")
+                    .table(Alias::new("assignment_submissions"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("assignment_submissions"))
+                    .drop_column(Alias::new("group_id"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("group_members")).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("groups")).to_owned())
+            .await
+    }
+}
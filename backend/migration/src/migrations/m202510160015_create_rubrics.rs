@@ -0,0 +1,189 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160015_create_rubrics"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // rubrics: at most one manual-marking rubric per assignment; `weight` is the
+        // percentage of the final grade it contributes
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("rubrics"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Alias::new("title")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("description")).text().null())
+                    .col(
+                        ColumnDef::new(Alias::new("weight"))
+                            .double()
+                            .not_null()
+                            .default(0.0),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("updated_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rubrics_assignment")
+                            .from(Alias::new("rubrics"), Alias::new("assignment_id"))
+                            .to(Alias::new("assignments"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // rubric_criteria: the rubric's rows, in `position` order
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("rubric_criteria"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("rubric_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("description")).text().null())
+                    .col(ColumnDef::new(Alias::new("max_score")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("position")).integer().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rubric_criteria_rubric")
+                            .from(Alias::new("rubric_criteria"), Alias::new("rubric_id"))
+                            .to(Alias::new("rubrics"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // rubric_scores: a marker's score for one criterion of one submission
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("rubric_scores"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("criterion_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("submission_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("score")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("comment")).text().null())
+                    .col(ColumnDef::new(Alias::new("marked_by")).big_integer().null())
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("updated_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rubric_scores_criterion")
+                            .from(Alias::new("rubric_scores"), Alias::new("criterion_id"))
+                            .to(Alias::new("rubric_criteria"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rubric_scores_submission")
+                            .from(Alias::new("rubric_scores"), Alias::new("submission_id"))
+                            .to(Alias::new("assignment_submissions"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_rubric_scores_marked_by")
+                            .from(Alias::new("rubric_scores"), Alias::new("marked_by"))
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_rubric_scores_criterion_submission")
+                    .table(Alias::new("rubric_scores"))
+                    .col(Alias::new("criterion_id"))
+                    .col(Alias::new("submission_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("rubric_scores")).to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("rubric_criteria"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("rubrics")).to_owned())
+            .await
+    }
+}
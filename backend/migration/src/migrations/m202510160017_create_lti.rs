@@ -0,0 +1,298 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160017_create_lti"
+    }
+}
+
+fn id_col() -> ColumnDef {
+    ColumnDef::new(Alias::new("id"))
+        .big_integer()
+        .not_null()
+        .auto_increment()
+        .primary_key()
+        .to_owned()
+}
+
+fn timestamp_col(name: &str) -> ColumnDef {
+    ColumnDef::new(Alias::new(name))
+        .timestamp_with_time_zone()
+        .not_null()
+        .default(Expr::cust("CURRENT_TIMESTAMP"))
+        .to_owned()
+}
+
+fn platform_fk(table: &str) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .name(format!("fk_{table}_platform"))
+        .from(Alias::new(table), Alias::new("platform_id"))
+        .to(Alias::new("lti_platforms"), Alias::new("id"))
+        .on_delete(ForeignKeyAction::Cascade)
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // lti_platforms: LMS registrations; one per issuer and client id the LMS issued us
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_platforms"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(ColumnDef::new(Alias::new("name")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("issuer")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("client_id")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("auth_login_url"))
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("auth_token_url"))
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("keyset_url")).string().not_null())
+                    .col(timestamp_col("created_at"))
+                    .col(timestamp_col("updated_at"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_lti_platforms_issuer_client")
+                    .table(Alias::new("lti_platforms"))
+                    .col(Alias::new("issuer"))
+                    .col(Alias::new("client_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // lti_deployments: the deployment ids of a platform that may launch us
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_deployments"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("platform_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("deployment_id"))
+                            .string()
+                            .not_null(),
+                    )
+                    .col(timestamp_col("created_at"))
+                    .foreign_key(&mut platform_fk("lti_deployments"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_lti_deployments_platform_deployment")
+                    .table(Alias::new("lti_deployments"))
+                    .col(Alias::new("platform_id"))
+                    .col(Alias::new("deployment_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // lti_keys: the tool's RSA signing keys, published on the JWKS endpoint; the newest
+        // active key signs
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_keys"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("kid"))
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("private_key_pem"))
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("active"))
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .col(timestamp_col("created_at"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // lti_launch_states: OIDC login state and nonce, consumed by the launch
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_launch_states"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("state"))
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Alias::new("nonce")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("platform_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("expires_at"))
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(&mut platform_fk("lti_launch_states"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // lti_user_links: the FitchFork user behind a platform's `sub`
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_user_links"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("platform_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("subject")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(timestamp_col("created_at"))
+                    .foreign_key(&mut platform_fk("lti_user_links"))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_lti_user_links_user")
+                            .from(Alias::new("lti_user_links"), Alias::new("user_id"))
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_lti_user_links_platform_subject")
+                    .table(Alias::new("lti_user_links"))
+                    .col(Alias::new("platform_id"))
+                    .col(Alias::new("subject"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // lti_resource_links: an LMS placement and the module/assignment it opens; `lineitem`
+        // is the AGS line item grades are posted to
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_resource_links"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("platform_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("resource_link_id"))
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("context_id")).string().null())
+                    .col(
+                        ColumnDef::new(Alias::new("module_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("lineitem")).text().null())
+                    .col(timestamp_col("created_at"))
+                    .col(timestamp_col("updated_at"))
+                    .foreign_key(&mut platform_fk("lti_resource_links"))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_lti_resource_links_module")
+                            .from(Alias::new("lti_resource_links"), Alias::new("module_id"))
+                            .to(Alias::new("modules"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_lti_resource_links_assignment")
+                            .from(
+                                Alias::new("lti_resource_links"),
+                                Alias::new("assignment_id"),
+                            )
+                            .to(Alias::new("assignments"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_lti_resource_links_platform_link")
+                    .table(Alias::new("lti_resource_links"))
+                    .col(Alias::new("platform_id"))
+                    .col(Alias::new("resource_link_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            "lti_resource_links",
+            "lti_user_links",
+            "lti_launch_states",
+            "lti_keys",
+            "lti_deployments",
+            "lti_platforms",
+        ] {
+            manager
+                .drop_table(Table::drop().table(Alias::new(table)).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
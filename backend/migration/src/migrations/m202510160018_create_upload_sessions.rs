@@ -0,0 +1,136 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160018_create_upload_sessions"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // upload_sessions: a file sent in parts; `sha256` is the whole file's checksum, checked
+        // when the parts are assembled
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("upload_sessions"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("filename")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("size")).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("chunk_size"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("sha256")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("completed_at"))
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("expires_at"))
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_upload_sessions_user")
+                            .from(Alias::new("upload_sessions"), Alias::new("user_id"))
+                            .to(Alias::new("users"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // upload_parts: the parts received so far, so an interrupted upload resumes where it
+        // stopped
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("upload_parts"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("upload_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("part_number"))
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("size")).big_integer().not_null())
+                    .col(ColumnDef::new(Alias::new("sha256")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_upload_parts_upload")
+                            .from(Alias::new("upload_parts"), Alias::new("upload_id"))
+                            .to(Alias::new("upload_sessions"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_upload_parts_upload_part")
+                    .table(Alias::new("upload_parts"))
+                    .col(Alias::new("upload_id"))
+                    .col(Alias::new("part_number"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("upload_parts")).to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("upload_sessions"))
+                    .to_owned(),
+            )
+            .await
+    }
+}
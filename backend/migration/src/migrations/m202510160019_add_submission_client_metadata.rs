@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160019_add_submission_client_metadata"
+    }
+}
+
+/// Columns added, one ALTER each (SQLite can't add several at once).
+fn columns() -> [ColumnDef; 3] {
+    [
+        ColumnDef::new(Alias::new("client_ip"))
+            .string()
+            .null()
+            .to_owned(),
+        ColumnDef::new(Alias::new("user_agent"))
+            .text()
+            .null()
+            .to_owned(),
+        ColumnDef::new(Alias::new("upload_duration_ms"))
+            .big_integer()
+            .null()
+            .to_owned(),
+    ]
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // where and how each submission was uploaded, for spotting shared machines
+        for mut column in columns() {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("assignment_submissions"))
+                        .add_column(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_assignment_submissions_client_ip")
+                    .table(Alias::new("assignment_submissions"))
+                    .col(Alias::new("assignment_id"))
+                    .col(Alias::new("client_ip"))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_assignment_submissions_client_ip")
+                    .table(Alias::new("assignment_submissions"))
+                    .to_owned(),
+            )
+            .await?;
+        for column in ["client_ip", "user_agent", "upload_duration_ms"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("assignment_submissions"))
+                        .drop_column(Alias::new(column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160021_create_moderation"
+    }
+}
+
+fn id_col() -> ColumnDef {
+    ColumnDef::new(Alias::new("id"))
+        .big_integer()
+        .not_null()
+        .auto_increment()
+        .primary_key()
+        .to_owned()
+}
+
+fn timestamp_col(name: &str) -> ColumnDef {
+    ColumnDef::new(Alias::new(name))
+        .timestamp_with_time_zone()
+        .not_null()
+        .default(Expr::cust("CURRENT_TIMESTAMP"))
+        .to_owned()
+}
+
+fn fk(
+    table: &str,
+    column: &str,
+    to_table: &str,
+    on_delete: ForeignKeyAction,
+) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .name(format!("fk_{table}_{column}"))
+        .from(Alias::new(table), Alias::new(column))
+        .to(Alias::new(to_table), Alias::new("id"))
+        .on_delete(on_delete)
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // moderations: an assignment's moderation sign-off, which locks its grades
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("moderations"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Alias::new("signed_off_by")).big_integer())
+                    .col(ColumnDef::new(Alias::new("signed_off_at")).timestamp_with_time_zone())
+                    .col(timestamp_col("created_at"))
+                    .col(timestamp_col("updated_at"))
+                    .foreign_key(&mut fk(
+                        "moderations",
+                        "assignment_id",
+                        "assignments",
+                        ForeignKeyAction::Cascade,
+                    ))
+                    .foreign_key(&mut fk(
+                        "moderations",
+                        "signed_off_by",
+                        "users",
+                        ForeignKeyAction::SetNull,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        // moderation_samples: the counted submissions picked for second marking, with the
+        // first mark at the time and the second marker's independent mark
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("moderation_samples"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("assignment_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("submission_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("marker_id")).big_integer())
+                    .col(ColumnDef::new(Alias::new("first_mark")).double().not_null())
+                    .col(ColumnDef::new(Alias::new("second_mark")).double())
+                    .col(ColumnDef::new(Alias::new("comment")).text())
+                    .col(ColumnDef::new(Alias::new("marked_at")).timestamp_with_time_zone())
+                    .col(timestamp_col("created_at"))
+                    .foreign_key(&mut fk(
+                        "moderation_samples",
+                        "assignment_id",
+                        "assignments",
+                        ForeignKeyAction::Cascade,
+                    ))
+                    .foreign_key(&mut fk(
+                        "moderation_samples",
+                        "user_id",
+                        "users",
+                        ForeignKeyAction::Cascade,
+                    ))
+                    .foreign_key(&mut fk(
+                        "moderation_samples",
+                        "submission_id",
+                        "assignment_submissions",
+                        ForeignKeyAction::Cascade,
+                    ))
+                    .foreign_key(&mut fk(
+                        "moderation_samples",
+                        "marker_id",
+                        "users",
+                        ForeignKeyAction::SetNull,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        // A student is sampled at most once per assignment
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_moderation_samples_assignment_user")
+                    .table(Alias::new("moderation_samples"))
+                    .col(Alias::new("assignment_id"))
+                    .col(Alias::new("user_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("moderation_samples"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Alias::new("moderations")).to_owned())
+            .await
+    }
+}
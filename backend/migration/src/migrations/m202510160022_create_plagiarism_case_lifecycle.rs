@@ -0,0 +1,157 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160022_create_plagiarism_case_lifecycle"
+    }
+}
+
+fn id_col() -> ColumnDef {
+    ColumnDef::new(Alias::new("id"))
+        .big_integer()
+        .not_null()
+        .auto_increment()
+        .primary_key()
+        .to_owned()
+}
+
+fn timestamp_col(name: &str) -> ColumnDef {
+    ColumnDef::new(Alias::new(name))
+        .timestamp_with_time_zone()
+        .not_null()
+        .default(Expr::cust("CURRENT_TIMESTAMP"))
+        .to_owned()
+}
+
+fn fk(
+    table: &str,
+    column: &str,
+    to_table: &str,
+    on_delete: ForeignKeyAction,
+) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .name(format!("fk_{table}_{column}"))
+        .from(Alias::new(table), Alias::new(column))
+        .to(Alias::new(to_table), Alias::new("id"))
+        .on_delete(on_delete)
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The case's stage in the misconduct process (NULL until it is reported) and, once
+        // resolved, its outcome
+        for column in ["stage", "outcome"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("plagiarism_cases"))
+                        .add_column(ColumnDef::new(Alias::new(column)).text())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // plagiarism_case_evidence: files attached to a case, tagged with the stage the case
+        // was at when they were uploaded
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("plagiarism_case_evidence"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("case_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("stage")).text())
+                    .col(ColumnDef::new(Alias::new("filename")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("path")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("size")).big_integer().not_null())
+                    .col(ColumnDef::new(Alias::new("uploaded_by")).big_integer())
+                    .col(timestamp_col("created_at"))
+                    .foreign_key(&mut fk(
+                        "plagiarism_case_evidence",
+                        "case_id",
+                        "plagiarism_cases",
+                        ForeignKeyAction::Cascade,
+                    ))
+                    .foreign_key(&mut fk(
+                        "plagiarism_case_evidence",
+                        "uploaded_by",
+                        "users",
+                        ForeignKeyAction::SetNull,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        // plagiarism_case_decisions: one record per stage change, with who made it and why
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("plagiarism_case_decisions"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("case_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("from_stage")).text())
+                    .col(ColumnDef::new(Alias::new("to_stage")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("decision")).text().not_null())
+                    .col(ColumnDef::new(Alias::new("outcome")).text())
+                    .col(ColumnDef::new(Alias::new("decided_by")).big_integer())
+                    .col(timestamp_col("created_at"))
+                    .foreign_key(&mut fk(
+                        "plagiarism_case_decisions",
+                        "case_id",
+                        "plagiarism_cases",
+                        ForeignKeyAction::Cascade,
+                    ))
+                    .foreign_key(&mut fk(
+                        "plagiarism_case_decisions",
+                        "decided_by",
+                        "users",
+                        ForeignKeyAction::SetNull,
+                    ))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("plagiarism_case_decisions"))
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(Alias::new("plagiarism_case_evidence"))
+                    .to_owned(),
+            )
+            .await?;
+        for column in ["outcome", "stage"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("plagiarism_cases"))
+                        .drop_column(Alias::new(column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160023_add_ticket_assignment"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The staff member handling the ticket, if it has been assigned
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("tickets"))
+                    .add_column(
+                        ColumnDef::new(Alias::new("assignee_id"))
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Priority drives the SLA deadline a staff reply is due by
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("tickets"))
+                    .add_column(
+                        ColumnDef::new(Alias::new("priority"))
+                            .text()
+                            .not_null()
+                            .default("normal"),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // SLA deadline, first staff reply, and when the ticket was escalated to the lecturers
+        for column in ["sla_due_at", "first_response_at", "escalated_at"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("tickets"))
+                        .add_column(
+                            ColumnDef::new(Alias::new(column))
+                                .timestamp_with_time_zone()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_tickets_assignee_id")
+                    .table(Alias::new("tickets"))
+                    .col(Alias::new("assignee_id"))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_tickets_assignee_id")
+                    .table(Alias::new("tickets"))
+                    .to_owned(),
+            )
+            .await?;
+        for column in [
+            "escalated_at",
+            "first_response_at",
+            "sla_due_at",
+            "priority",
+            "assignee_id",
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("tickets"))
+                        .drop_column(Alias::new(column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
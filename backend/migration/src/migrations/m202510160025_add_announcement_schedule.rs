@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160025_add_announcement_schedule"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // When the announcement goes live (NULL for straight away) and when it comes down again,
+        // plus when members were told about it
+        for column in ["publish_at", "expires_at", "published_at"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("announcements"))
+                        .add_column(
+                            ColumnDef::new(Alias::new(column))
+                                .timestamp_with_time_zone()
+                                .null(),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_announcements_publish_at")
+                    .table(Alias::new("announcements"))
+                    .col(Alias::new("publish_at"))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_announcements_publish_at")
+                    .table(Alias::new("announcements"))
+                    .to_owned(),
+            )
+            .await?;
+        for column in ["published_at", "expires_at", "publish_at"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("announcements"))
+                        .drop_column(Alias::new(column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
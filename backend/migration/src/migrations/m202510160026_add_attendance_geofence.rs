@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160026_add_attendance_geofence"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Optional geofence: students must mark from within `geo_radius_m` metres of the venue
+        for column in ["geo_latitude", "geo_longitude"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("attendance_sessions"))
+                        .add_column(ColumnDef::new(Alias::new(column)).double().null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Alias::new("attendance_sessions"))
+                    .add_column(ColumnDef::new(Alias::new("geo_radius_m")).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in ["geo_radius_m", "geo_longitude", "geo_latitude"] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new("attendance_sessions"))
+                        .drop_column(Alias::new(column))
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160028_create_sso"
+    }
+}
+
+fn id_col() -> ColumnDef {
+    ColumnDef::new(Alias::new("id"))
+        .big_integer()
+        .not_null()
+        .auto_increment()
+        .primary_key()
+        .to_owned()
+}
+
+fn user_fk(table: &str, column: &str) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .name(format!("fk_{table}_user"))
+        .from(Alias::new(table), Alias::new(column))
+        .to(Alias::new("users"), Alias::new("id"))
+        .on_delete(ForeignKeyAction::Cascade)
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // sso_identities: the FitchFork user behind an OIDC provider's `sub`
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("sso_identities"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(ColumnDef::new(Alias::new("issuer")).string().not_null())
+                    .col(ColumnDef::new(Alias::new("subject")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(&mut user_fk("sso_identities", "user_id"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_sso_identities_issuer_subject")
+                    .table(Alias::new("sso_identities"))
+                    .col(Alias::new("issuer"))
+                    .col(Alias::new("subject"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // sso_login_states: state, nonce and PKCE verifier of a sign-in in progress, consumed by
+        // the callback; `link_user_id` is set when a signed-in user is linking their account
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("sso_login_states"))
+                    .if_not_exists()
+                    .col(id_col())
+                    .col(
+                        ColumnDef::new(Alias::new("state"))
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(Alias::new("nonce")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("code_verifier"))
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("link_user_id"))
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("redirect")).string().null())
+                    .col(
+                        ColumnDef::new(Alias::new("expires_at"))
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(&mut user_fk("sso_login_states", "link_user_id"))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in ["sso_login_states", "sso_identities"] {
+            manager
+                .drop_table(Table::drop().table(Alias::new(table)).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,157 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160029_create_two_factor"
+    }
+}
+
+fn user_fk(table: &str) -> ForeignKeyCreateStatement {
+    ForeignKey::create()
+        .name(format!("fk_{table}_user"))
+        .from(Alias::new(table), Alias::new("user_id"))
+        .to(Alias::new("users"), Alias::new("id"))
+        .on_delete(ForeignKeyAction::Cascade)
+        .to_owned()
+}
+
+fn created_at() -> ColumnDef {
+    ColumnDef::new(Alias::new("created_at"))
+        .timestamp_with_time_zone()
+        .not_null()
+        .default(Expr::cust("CURRENT_TIMESTAMP"))
+        .to_owned()
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // user_two_factors: a user's TOTP secret; `enabled` once they have proven a code from it.
+        // `last_used_step` stops a code being replayed, and failed attempts lock verification
+        // for a while
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("user_two_factors"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Alias::new("secret")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("enabled"))
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("last_used_step"))
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("failed_attempts"))
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("locked_until"))
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(created_at())
+                    .col(
+                        ColumnDef::new(Alias::new("enabled_at"))
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .foreign_key(&mut user_fk("user_two_factors"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // two_factor_backup_codes: single-use recovery codes, stored as SHA-256 hashes
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("two_factor_backup_codes"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("user_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("code_hash")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("used_at"))
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .col(created_at())
+                    .foreign_key(&mut user_fk("two_factor_backup_codes"))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_two_factor_backup_codes_user")
+                    .table(Alias::new("two_factor_backup_codes"))
+                    .col(Alias::new("user_id"))
+                    .to_owned(),
+            )
+            .await?;
+
+        // system_settings: instance-wide switches admins can flip at runtime
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("system_settings"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("key"))
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Alias::new("value")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("updated_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            "system_settings",
+            "two_factor_backup_codes",
+            "user_two_factors",
+        ] {
+            manager
+                .drop_table(Table::drop().table(Alias::new(table)).to_owned())
+                .await?;
+        }
+        Ok(())
+    }
+}
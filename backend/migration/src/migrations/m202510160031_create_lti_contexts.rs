@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+pub struct Migration;
+
+impl MigrationName for Migration {
+    fn name(&self) -> &str {
+        "m202510160031_create_lti_contexts"
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // lti_contexts: an LMS course (context) an admin has linked to a module; new placements
+        // in the course open that module
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alias::new("lti_contexts"))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alias::new("id"))
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("platform_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(Alias::new("context_id")).string().not_null())
+                    .col(
+                        ColumnDef::new(Alias::new("module_id"))
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Alias::new("created_at"))
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::cust("CURRENT_TIMESTAMP")),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_lti_contexts_platform")
+                            .from(Alias::new("lti_contexts"), Alias::new("platform_id"))
+                            .to(Alias::new("lti_platforms"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_lti_contexts_module")
+                            .from(Alias::new("lti_contexts"), Alias::new("module_id"))
+                            .to(Alias::new("modules"), Alias::new("id"))
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("ux_lti_contexts_platform_context")
+                    .table(Alias::new("lti_contexts"))
+                    .col(Alias::new("platform_id"))
+                    .col(Alias::new("context_id"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alias::new("lti_contexts")).to_owned())
+            .await
+    }
+}
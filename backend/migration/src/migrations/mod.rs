@@ -17,3 +17,34 @@ pub mod m202509080001_create_attendance;
 pub mod m202509120001_create_moss_reports;
 pub mod m202509120002_create_plagiarism_cases;
 pub mod m202509150003_create_system_metrics;
+pub mod m202510160001_add_submission_output_sizes;
+pub mod m202510160002_add_submission_output_metrics;
+pub mod m202510160003_add_task_artifact_patterns;
+pub mod m202510160004_create_ga_runs;
+pub mod m202510160005_add_plagiarism_case_historical;
+pub mod m202510160006_create_plagiarism_reports;
+pub mod m202510160007_create_content_blobs;
+pub mod m202510160008_add_system_metric_queue;
+pub mod m202510160009_add_submission_run_state;
+pub mod m202510160010_add_soft_delete;
+pub mod m202510160011_create_notifications;
+pub mod m202510160012_create_groups;
+pub mod m202510160013_create_assignment_extensions;
+pub mod m202510160014_create_regrade_requests;
+pub mod m202510160015_create_rubrics;
+pub mod m202510160016_create_grade_export_templates;
+pub mod m202510160017_create_lti;
+pub mod m202510160018_create_upload_sessions;
+pub mod m202510160019_add_submission_client_metadata;
+pub mod m202510160020_create_exam_sessions;
+pub mod m202510160021_create_moderation;
+pub mod m202510160022_create_plagiarism_case_lifecycle;
+pub mod m202510160023_add_ticket_assignment;
+pub mod m202510160024_create_email_deliveries;
+pub mod m202510160025_add_announcement_schedule;
+pub mod m202510160026_add_attendance_geofence;
+pub mod m202510160027_create_calendar_feed_tokens;
+pub mod m202510160028_create_sso;
+pub mod m202510160029_create_two_factor;
+pub mod m202510160030_create_api_tokens;
+pub mod m202510160031_create_lti_contexts;
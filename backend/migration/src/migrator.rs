@@ -27,6 +27,37 @@ impl MigratorTrait for Migrator {
             Box::new(migrations::m202509120001_create_moss_reports::Migration),
             Box::new(migrations::m202509120002_create_plagiarism_cases::Migration),
             Box::new(migrations::m202509150003_create_system_metrics::Migration),
+            Box::new(migrations::m202510160001_add_submission_output_sizes::Migration),
+            Box::new(migrations::m202510160002_add_submission_output_metrics::Migration),
+            Box::new(migrations::m202510160003_add_task_artifact_patterns::Migration),
+            Box::new(migrations::m202510160004_create_ga_runs::Migration),
+            Box::new(migrations::m202510160005_add_plagiarism_case_historical::Migration),
+            Box::new(migrations::m202510160006_create_plagiarism_reports::Migration),
+            Box::new(migrations::m202510160007_create_content_blobs::Migration),
+            Box::new(migrations::m202510160008_add_system_metric_queue::Migration),
+            Box::new(migrations::m202510160009_add_submission_run_state::Migration),
+            Box::new(migrations::m202510160010_add_soft_delete::Migration),
+            Box::new(migrations::m202510160011_create_notifications::Migration),
+            Box::new(migrations::m202510160012_create_groups::Migration),
+            Box::new(migrations::m202510160013_create_assignment_extensions::Migration),
+            Box::new(migrations::m202510160014_create_regrade_requests::Migration),
+            Box::new(migrations::m202510160015_create_rubrics::Migration),
+            Box::new(migrations::m202510160016_create_grade_export_templates::Migration),
+            Box::new(migrations::m202510160017_create_lti::Migration),
+            Box::new(migrations::m202510160018_create_upload_sessions::Migration),
+            Box::new(migrations::m202510160019_add_submission_client_metadata::Migration),
+            Box::new(migrations::m202510160020_create_exam_sessions::Migration),
+            Box::new(migrations::m202510160021_create_moderation::Migration),
+            Box::new(migrations::m202510160022_create_plagiarism_case_lifecycle::Migration),
+            Box::new(migrations::m202510160023_add_ticket_assignment::Migration),
+            Box::new(migrations::m202510160024_create_email_deliveries::Migration),
+            Box::new(migrations::m202510160025_add_announcement_schedule::Migration),
+            Box::new(migrations::m202510160026_add_attendance_geofence::Migration),
+            Box::new(migrations::m202510160027_create_calendar_feed_tokens::Migration),
+            Box::new(migrations::m202510160028_create_sso::Migration),
+            Box::new(migrations::m202510160029_create_two_factor::Migration),
+            Box::new(migrations::m202510160030_create_api_tokens::Migration),
+            Box::new(migrations::m202510160031_create_lti_contexts::Migration),
         ]
     }
 }
@@ -19,6 +19,33 @@ pub async fn run_all_migrations(url: &str) {
     }
 }
 
+/// Drops every table of a PostgreSQL database by recreating its current schema.
+pub async fn drop_all_tables(url: &str) {
+    let db = sea_orm::Database::connect(url)
+        .await
+        .expect("DB connection failed");
+    if db.get_database_backend() != sea_orm::DatabaseBackend::Postgres {
+        panic!("Only PostgreSQL URLs are supported; remove SQLite databases by path");
+    }
+
+    let row = db
+        .query_one(sea_orm::Statement::from_string(
+            sea_orm::DatabaseBackend::Postgres,
+            "SELECT current_schema() AS name",
+        ))
+        .await
+        .expect("Failed to read current schema")
+        .expect("No current schema");
+    let schema: String = row.try_get("", "name").expect("Invalid schema name");
+
+    db.execute_unprepared(&format!(
+        "DROP SCHEMA \"{schema}\" CASCADE; CREATE SCHEMA \"{schema}\";"
+    ))
+    .await
+    .expect("Failed to drop tables");
+    println!("Dropped all tables in schema: {schema}");
+}
+
 async fn run_migration(schema_manager: &SchemaManager<'_>, migration: Box<dyn MigrationTrait>) {
     let name_str = format!("Applying {}", migration.name().bold());
     let dots = ".".repeat(STATUS_COLUMN.saturating_sub(name_str.len()));
@@ -0,0 +1,372 @@
+//! Local copies of MOSS match pages.
+//!
+//! MOSS deletes a report 14 days after the run. When `ParseOptions::archive_dir` is set,
+//! `parse_moss` saves every kept match page (`matchN.html`) and the frames it loads
+//! (`matchN-top.html`, `matchN-0.html`, `matchN-1.html`) into that directory, flat. Links
+//! between saved pages are rewritten to the local file names, so the pages work from wherever
+//! the directory is served; any other relative link (MOSS's bitmaps) is made absolute.
+//!
+//! The top frame lists the matched line ranges of both files, which are kept as
+//! `MatchRegion`s for each match page.
+
+use crate::MatchRegion;
+use crate::fetch::Fetcher;
+use anyhow::{Context, Result};
+use regex::{Captures, Regex};
+use reqwest::Url;
+use scraper::{Html, Selector};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use tokio::task::JoinSet;
+
+/// Pages fetched at the same time (their starts are still paced by the `Fetcher`).
+const CONCURRENCY: usize = 8;
+
+/// What `archive_match_pages` saved.
+#[derive(Debug, Default)]
+pub(crate) struct ArchivedPages {
+    /// Absolute page URL (without fragment) → local file name.
+    local_names: BTreeMap<String, String>,
+    /// Absolute match page URL → line ranges from its top frame.
+    regions: BTreeMap<String, Vec<MatchRegion>>,
+    /// One line per page that could not be fetched.
+    pub errors: Vec<String>,
+}
+
+impl ArchivedPages {
+    /// The local name for `href` (as found in the report at `report_url`), keeping its
+    /// fragment; `None` if that page was not saved.
+    pub fn local_href(&self, report_url: &str, href: &str) -> Option<String> {
+        let base = Url::parse(report_url).ok()?;
+        localize(href, &base, &self.local_names)
+    }
+
+    /// The matched regions of the match page `href` (`a` = the pair's first file); empty if
+    /// its top frame was not saved.
+    pub fn regions(&self, report_url: &str, href: &str) -> Vec<MatchRegion> {
+        Url::parse(report_url)
+            .ok()
+            .and_then(|base| page_url(&base, href))
+            .and_then(|url| self.regions.get(url.as_str()).cloned())
+            .unwrap_or_default()
+    }
+}
+
+/// Downloads the match pages `hrefs` (relative to `report_url`) and their frames into `dir`.
+///
+/// # Errors
+/// Returns an error if `report_url` is not a URL or `dir` cannot be written. Pages that still
+/// fail after the fetcher's retries are skipped and listed in `ArchivedPages::errors`.
+pub(crate) async fn archive_match_pages(
+    fetcher: &Fetcher,
+    report_url: &str,
+    hrefs: &[&str],
+    dir: &Path,
+) -> Result<ArchivedPages> {
+    let base =
+        Url::parse(report_url).with_context(|| format!("invalid report URL {report_url}"))?;
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("creating {}", dir.display()))?;
+
+    let mut errors = Vec::new();
+    let match_urls: BTreeSet<Url> = hrefs.iter().filter_map(|h| page_url(&base, h)).collect();
+    let mut pages = fetch_all(fetcher, match_urls, &mut errors).await;
+
+    let frames_of: BTreeMap<Url, Vec<Url>> = pages
+        .iter()
+        .map(|(url, html)| (url.clone(), frame_urls(url, html)))
+        .collect();
+    let frames: BTreeSet<Url> = frames_of
+        .values()
+        .flatten()
+        .filter(|url| !pages.contains_key(*url))
+        .cloned()
+        .collect();
+    pages.extend(fetch_all(fetcher, frames, &mut errors).await);
+
+    let mut regions = BTreeMap::new();
+    for (page, frames) in &frames_of {
+        let top = frames
+            .iter()
+            .find(|f| f.path().ends_with("-top.html"))
+            .and_then(|f| pages.get(f));
+        if let Some(html) = top {
+            regions.insert(page.to_string(), top_frame_regions(html));
+        }
+    }
+
+    let mut local_names = BTreeMap::new();
+    let mut taken = BTreeSet::new();
+    for url in pages.keys() {
+        let mut name = local_name(url);
+        if !taken.insert(name.clone()) {
+            name = format!("{}_{}", taken.len(), name);
+            taken.insert(name.clone());
+        }
+        local_names.insert(url.to_string(), name);
+    }
+
+    for (url, html) in &pages {
+        let path = dir.join(&local_names[url.as_str()]);
+        tokio::fs::write(&path, rewrite_links(html, url, &local_names))
+            .await
+            .with_context(|| format!("writing {}", path.display()))?;
+    }
+
+    Ok(ArchivedPages {
+        local_names,
+        regions,
+        errors,
+    })
+}
+
+/* --------------------- Internal helpers (crate-private) -------------------- */
+
+async fn fetch_all(
+    fetcher: &Fetcher,
+    urls: BTreeSet<Url>,
+    errors: &mut Vec<String>,
+) -> BTreeMap<Url, String> {
+    let urls: Vec<Url> = urls.into_iter().collect();
+    let mut pages = BTreeMap::new();
+
+    for chunk in urls.chunks(CONCURRENCY) {
+        let mut set = JoinSet::new();
+        for url in chunk.iter().cloned() {
+            let fetcher = fetcher.clone();
+            set.spawn(async move {
+                let res = fetcher.fetch_html(url.as_str()).await;
+                (url, res)
+            });
+        }
+        while let Some(joined) = set.join_next().await {
+            match joined {
+                Ok((url, Ok(html))) => {
+                    pages.insert(url, html);
+                }
+                Ok((url, Err(e))) => errors.push(format!("{url}: {e:#}")),
+                Err(e) => errors.push(format!("archive task failed: {e}")),
+            }
+        }
+    }
+    pages
+}
+
+/// Line ranges from a MOSS top frame: one table row per region, whose first two `N-M` cells
+/// are the ranges in the left and right file.
+fn top_frame_regions(html: &str) -> Vec<MatchRegion> {
+    let range = Regex::new(r"^(\d+)-(\d+)$").unwrap();
+    let doc = Html::parse_document(html);
+    let tr_sel = Selector::parse("tr").unwrap();
+    let td_sel = Selector::parse("td").unwrap();
+
+    doc.select(&tr_sel)
+        .filter_map(|tr| {
+            let mut ranges = tr.select(&td_sel).filter_map(|td| {
+                let text = td.text().collect::<String>();
+                let c = range.captures(text.trim())?;
+                Some((c[1].parse::<i64>().ok()?, c[2].parse::<i64>().ok()?))
+            });
+            let ((a_start, a_end), (b_start, b_end)) = (ranges.next()?, ranges.next()?);
+            Some(MatchRegion {
+                a_start,
+                a_end,
+                b_start,
+                b_end,
+            })
+        })
+        .collect()
+}
+
+/// `href` resolved against `base`, without its fragment.
+fn page_url(base: &Url, href: &str) -> Option<Url> {
+    if href.trim().is_empty() {
+        return None;
+    }
+    let mut url = base.join(href.trim()).ok()?;
+    url.set_fragment(None);
+    Some(url)
+}
+
+/// Frame sources of a match page.
+fn frame_urls(page: &Url, html: &str) -> Vec<Url> {
+    let doc = Html::parse_document(html);
+    let sel = Selector::parse("frame[src], iframe[src]").unwrap();
+    doc.select(&sel)
+        .filter_map(|f| f.value().attr("src"))
+        .filter_map(|src| page_url(page, src))
+        .collect()
+}
+
+/// A flat, served-safe `.html` file name for a page.
+fn local_name(url: &Url) -> String {
+    let last = url
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .unwrap_or_default();
+    let mut name: String = last
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name = name.trim_start_matches('.').to_string();
+    if name.is_empty() {
+        name = "page".to_string();
+    }
+    if !name.ends_with(".html") {
+        name.push_str(".html");
+    }
+    name
+}
+
+/// The local name (plus fragment) for `raw` if it points at a saved page.
+fn localize(raw: &str, page: &Url, local_names: &BTreeMap<String, String>) -> Option<String> {
+    let url = page_url(page, raw)?;
+    let name = local_names.get(url.as_str())?;
+    let fragment = page.join(raw.trim()).ok()?.fragment().map(str::to_string);
+    Some(match fragment {
+        Some(f) => format!("{name}#{f}"),
+        None => name.clone(),
+    })
+}
+
+/// Points `src` / `href` attributes at saved pages where possible and makes the rest absolute.
+fn rewrite_links(html: &str, page: &Url, local_names: &BTreeMap<String, String>) -> String {
+    let re = Regex::new(r#"(?i)\b(src|href)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>"']+))"#).unwrap();
+    re.replace_all(html, |c: &Captures| {
+        let attr = &c[1];
+        let raw = c
+            .get(2)
+            .or_else(|| c.get(3))
+            .or_else(|| c.get(4))
+            .map_or("", |m| m.as_str());
+
+        let target = if raw.is_empty() || raw.starts_with('#') {
+            raw.to_string()
+        } else if let Some(local) = localize(raw, page, local_names) {
+            local
+        } else {
+            page.join(raw)
+                .map(|u| u.to_string())
+                .unwrap_or_else(|_| raw.to_string())
+        };
+        format!("{attr}=\"{target}\"")
+    })
+    .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MATCH_PAGE: &str = r#"<HTML><FRAMESET ROWS="150,*">
+<FRAME SRC="match0-top.html" NAME="top">
+<FRAMESET COLS="50%,50%"><FRAME SRC="match0-0.html" NAME="0"><FRAME SRC=match0-1.html NAME="1">
+</FRAMESET></FRAMESET></HTML>"#;
+
+    fn report() -> Url {
+        Url::parse("http://moss.stanford.edu/results/5/123456789/").unwrap()
+    }
+
+    #[test]
+    fn finds_frames_and_names_them_flat() {
+        let page = report().join("match0.html").unwrap();
+        let frames: Vec<String> = frame_urls(&page, MATCH_PAGE)
+            .iter()
+            .map(Url::to_string)
+            .collect();
+        assert_eq!(
+            frames,
+            [
+                "http://moss.stanford.edu/results/5/123456789/match0-top.html",
+                "http://moss.stanford.edu/results/5/123456789/match0-0.html",
+                "http://moss.stanford.edu/results/5/123456789/match0-1.html",
+            ]
+        );
+
+        assert_eq!(local_name(&page), "match0.html");
+        assert_eq!(local_name(&report()), "page.html");
+        assert_eq!(
+            local_name(&Url::parse("http://x/a/..%2Fsecret").unwrap()),
+            "_2Fsecret.html"
+        );
+        assert_eq!(
+            page_url(&report(), "match0.html#3").unwrap(),
+            report().join("match0.html").unwrap()
+        );
+        assert_eq!(page_url(&report(), "  "), None);
+    }
+
+    #[test]
+    fn rewrites_saved_pages_to_local_names_and_the_rest_to_absolute() {
+        let base = report();
+        let top = base.join("match0-top.html").unwrap();
+        let local_names: BTreeMap<String, String> = ["match0.html", "match0-0.html"]
+            .iter()
+            .map(|n| (base.join(n).unwrap().to_string(), n.to_string()))
+            .collect();
+
+        let html = r##"<A HREF="match0-0.html#2" TARGET="0"><IMG SRC="../../bitmaps/tm_0_5.gif"></A>
+<a href='http://moss.stanford.edu/results/5/123456789/match0.html'>back</a>
+<a href=match0-1.html#1>missing</a> <a href="#top">top</a>"##;
+
+        assert_eq!(
+            rewrite_links(html, &top, &local_names),
+            r##"<A HREF="match0-0.html#2" TARGET="0"><IMG SRC="http://moss.stanford.edu/results/bitmaps/tm_0_5.gif"></A>
+<a href="match0.html">back</a>
+<a href="http://moss.stanford.edu/results/5/123456789/match0-1.html#1">missing</a> <a href="#top">top</a>"##
+        );
+
+        let archived = ArchivedPages {
+            local_names,
+            regions: BTreeMap::new(),
+            errors: vec![],
+        };
+        assert_eq!(
+            archived.local_href(base.as_str(), "match0.html"),
+            Some("match0.html".to_string())
+        );
+        assert_eq!(archived.local_href(base.as_str(), "match7.html"), None);
+    }
+
+    #[test]
+    fn reads_line_ranges_from_the_top_frame() {
+        let top = r##"<HTML><BODY><CENTER><TABLE BORDER="1" CELLSPACING="0" BGCOLOR="#d0d0d0">
+<TR><TH>bob_7/main.cpp (52%)<TH><IMG SRC="../../bitmaps/tm_0_52.gif" ALT="Other" BORDER="0" ALIGN=left VSPACE="0"><TH>alice_3/main.cpp (60%)<TH><IMG SRC="../../bitmaps/tm_0_60.gif">
+<TR><TD><A HREF="match0-0.html#0" NAME="0" TARGET="0">26-41</A>
+<TD><A HREF="match0-0.html#0" NAME="0" TARGET="0"><IMG SRC="../../bitmaps/tm_0_5.gif" ALT="other" BORDER="0" ALIGN=left></A>
+<TD><A HREF="match0-1.html#0" NAME="0" TARGET="1">23-38</A>
+<TD><A HREF="match0-1.html#0" NAME="0" TARGET="1"><IMG SRC="../../bitmaps/tm_0_5.gif"></A>
+<TR><TD><A HREF="match0-0.html#1" NAME="1" TARGET="0">3-9</A>
+<TD><A HREF="match0-0.html#1" NAME="1" TARGET="0"><IMG></A>
+<TD><A HREF="match0-1.html#1" NAME="1" TARGET="1">1-7</A>
+</TABLE></CENTER></BODY></HTML>"##;
+
+        let regions = top_frame_regions(top);
+        assert_eq!(
+            regions,
+            [
+                MatchRegion {
+                    a_start: 26,
+                    a_end: 41,
+                    b_start: 23,
+                    b_end: 38
+                },
+                MatchRegion {
+                    a_start: 3,
+                    a_end: 9,
+                    b_start: 1,
+                    b_end: 7
+                },
+            ]
+        );
+        assert_eq!(regions[1].swapped().a_start, 1);
+        assert!(top_frame_regions("<TABLE><TR><TD>nothing</TABLE>").is_empty());
+    }
+}
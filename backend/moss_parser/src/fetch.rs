@@ -0,0 +1,254 @@
+//! HTTP fetching for MOSS pages.
+//!
+//! MOSS is a single, slow server that drops connections under load, so every request goes
+//! through a [`Fetcher`]: requests are paced (`FetchOptions::request_interval` between the
+//! starts of two requests, across all concurrent tasks) and failed requests are retried with
+//! exponential backoff. Only failures that may go away are retried: connection errors,
+//! timeouts, `429 Too Many Requests` and `5xx` responses.
+
+use anyhow::{Context, Result, anyhow};
+use reqwest::{Client, StatusCode, header, redirect};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Upper bound for a single backoff delay (including one asked for by `Retry-After`).
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How MOSS pages are fetched.
+#[derive(Clone, Debug)]
+pub struct FetchOptions {
+    /// Timeout for a single request.
+    pub timeout: Duration,
+    /// Extra attempts after a request fails with a retryable error.
+    pub retries: u32,
+    /// Delay before the first retry; doubled for every further one.
+    pub backoff: Duration,
+    /// Minimum time between the starts of two requests; zero disables pacing.
+    pub request_interval: Duration,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(20),
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            request_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// A shared HTTP client plus the pacing state; cheap to clone into tasks.
+#[derive(Clone)]
+pub(crate) struct Fetcher {
+    client: Client,
+    opts: FetchOptions,
+    /// Earliest start of the next request.
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+/// A failed attempt and whether trying again may help.
+struct Failure {
+    error: anyhow::Error,
+    retryable: bool,
+    retry_after: Option<Duration>,
+}
+
+impl Fetcher {
+    pub fn new(opts: FetchOptions) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(concat!(
+                "moss-scrape/0.1 (+https://example.invalid) ",
+                "reqwest/"
+            ))
+            .gzip(true)
+            .brotli(true)
+            .deflate(true)
+            .http1_only()
+            .redirect(redirect::Policy::limited(10))
+            .build()
+            .context("building HTTP client")?;
+        Ok(Self {
+            client,
+            opts,
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+        })
+    }
+
+    /// GETs `url` as text, retrying as configured.
+    ///
+    /// # Errors
+    /// Returns the last error once the request fails with a non-retryable error or the
+    /// retries are used up.
+    pub async fn fetch_html(&self, url: &str) -> Result<String> {
+        let mut attempt = 0;
+        loop {
+            self.pace().await;
+            let failure = match self.try_fetch(url).await {
+                Ok(html) => return Ok(html),
+                Err(f) => f,
+            };
+            if !failure.retryable || attempt >= self.opts.retries {
+                let error = failure.error.context(format!("GET {url}"));
+                return Err(if attempt > 0 {
+                    error.context(format!("giving up after {} attempts", attempt + 1))
+                } else {
+                    error
+                });
+            }
+            let delay = failure
+                .retry_after
+                .unwrap_or_else(|| backoff_delay(self.opts.backoff, attempt));
+            tokio::time::sleep(delay.min(MAX_BACKOFF)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Waits for this request's slot so that starts are at least `request_interval` apart.
+    async fn pace(&self) {
+        if self.opts.request_interval.is_zero() {
+            return;
+        }
+        let start = {
+            let mut next = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let start = (*next).max(Instant::now());
+            *next = start + self.opts.request_interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+
+    async fn try_fetch(&self, url: &str) -> Result<String, Failure> {
+        let resp = self
+            .client
+            .get(url)
+            .timeout(self.opts.timeout)
+            .send()
+            .await
+            .map_err(|e| Failure {
+                retryable: e.is_timeout() || e.is_connect() || e.is_request(),
+                error: e.into(),
+                retry_after: None,
+            })?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(Failure {
+                retryable: status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+                retry_after: retry_after(&resp),
+                error: anyhow!("non-success status {status}"),
+            });
+        }
+
+        let bytes = resp.bytes().await.map_err(|e| Failure {
+            retryable: true,
+            error: anyhow::Error::from(e).context("reading body"),
+            retry_after: None,
+        })?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// `base * 2^attempt`, saturating at `MAX_BACKOFF`.
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    2u32.checked_pow(attempt)
+        .and_then(|factor| base.checked_mul(factor))
+        .unwrap_or(MAX_BACKOFF)
+        .min(MAX_BACKOFF)
+}
+
+/// A `Retry-After` header given in seconds.
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    let secs = resp.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+    secs.trim().parse().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Serves one canned response per connection (the last one repeats); returns the URL and
+    /// a counter of requests served.
+    fn serve(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/results/1", listener.local_addr().unwrap());
+        let served = Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let (status, body) = responses[n.min(responses.len() - 1)]
+                    .split_once('|')
+                    .unwrap();
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        (url, served)
+    }
+
+    fn quick(retries: u32) -> FetchOptions {
+        FetchOptions {
+            timeout: Duration::from_secs(5),
+            retries,
+            backoff: Duration::from_millis(1),
+            request_interval: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_the_page_loads() {
+        let (url, served) = serve(vec![
+            "503 Service Unavailable|busy",
+            "502 Bad Gateway|busy",
+            "200 OK|<html>ok</html>",
+        ]);
+        let html = Fetcher::new(quick(3))
+            .unwrap()
+            .fetch_html(&url)
+            .await
+            .unwrap();
+        assert_eq!(html, "<html>ok</html>");
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_configured_retries_and_on_client_errors() {
+        let (url, served) = serve(vec!["500 Internal Server Error|down"]);
+        let err = Fetcher::new(quick(2))
+            .unwrap()
+            .fetch_html(&url)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("giving up after 3 attempts"));
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+
+        let (url, served) = serve(vec!["404 Not Found|gone"]);
+        assert!(
+            Fetcher::new(quick(2))
+                .unwrap()
+                .fetch_html(&url)
+                .await
+                .is_err()
+        );
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn backoff_doubles_and_is_capped() {
+        let base = Duration::from_millis(500);
+        assert_eq!(backoff_delay(base, 0), base);
+        assert_eq!(backoff_delay(base, 2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, 40), MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,315 @@
+//! User-pair graph export: DOT (Graphviz), GraphML (Gephi, yEd) and an edge-list CSV.
+//!
+//! Nodes are usernames and every edge is one user pair, weighted by its similarity percentage
+//! (`weight` in all three formats, which is what Gephi picks up as the edge weight). Edges come
+//! either straight from `parse_moss` / `parse_jplag_dir` reports or from stored plagiarism
+//! cases, and can be thresholded before export.
+
+use crate::UserPairReport;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// One edge of the user-pair graph.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// Similarity in percent (0–100), if known.
+    pub similarity: Option<f64>,
+    pub lines_matched: i64,
+    /// The plagiarism case behind the edge, when exported from stored cases.
+    pub case_id: Option<i64>,
+    pub status: Option<String>,
+}
+
+impl From<&UserPairReport> for GraphEdge {
+    fn from(r: &UserPairReport) -> Self {
+        Self {
+            source: r.user_a.clone(),
+            target: r.user_b.clone(),
+            similarity: r.total_percent,
+            lines_matched: r.total_lines_matched,
+            case_id: None,
+            status: None,
+        }
+    }
+}
+
+/// Output format for `export_graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+    Csv,
+}
+
+impl GraphFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "text/vnd.graphviz; charset=utf-8",
+            GraphFormat::GraphMl => "application/graphml+xml; charset=utf-8",
+            GraphFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::GraphMl => "graphml",
+            GraphFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dot" | "gv" => Ok(GraphFormat::Dot),
+            "graphml" => Ok(GraphFormat::GraphMl),
+            "csv" => Ok(GraphFormat::Csv),
+            other => Err(format!("Unknown graph format '{other}'")),
+        }
+    }
+}
+
+/// Which edges to keep. Edges without a similarity fail any similarity bound.
+#[derive(Debug, Clone, Default)]
+pub struct GraphExportOptions {
+    /// Keep edges with at least this similarity (inclusive).
+    pub min_similarity: Option<f64>,
+    /// Keep edges with at most this similarity (inclusive).
+    pub max_similarity: Option<f64>,
+    /// Keep edges with at least this many matched lines.
+    pub min_lines: i64,
+}
+
+impl GraphExportOptions {
+    fn keeps(&self, e: &GraphEdge) -> bool {
+        let within = |bound: Option<f64>, ok: fn(f64, f64) -> bool| match bound {
+            None => true,
+            Some(b) => e.similarity.is_some_and(|s| ok(s, b)),
+        };
+        e.lines_matched >= self.min_lines
+            && within(self.min_similarity, |s, b| s >= b)
+            && within(self.max_similarity, |s, b| s <= b)
+    }
+}
+
+/// Renders the edges that pass `opts` in `format`.
+///
+/// Nodes are the usernames on the kept edges, sorted. Edges keep their input order.
+pub fn export_graph(edges: &[GraphEdge], format: GraphFormat, opts: &GraphExportOptions) -> String {
+    let edges: Vec<&GraphEdge> = edges.iter().filter(|e| opts.keeps(e)).collect();
+    let nodes: BTreeSet<&str> = edges
+        .iter()
+        .flat_map(|e| [e.source.as_str(), e.target.as_str()])
+        .collect();
+
+    match format {
+        GraphFormat::Dot => to_dot(&nodes, &edges),
+        GraphFormat::GraphMl => to_graphml(&nodes, &edges),
+        GraphFormat::Csv => to_csv(&edges),
+    }
+}
+
+/* --------------------- Internal helpers (crate-private) -------------------- */
+
+fn to_dot(nodes: &BTreeSet<&str>, edges: &[&GraphEdge]) -> String {
+    let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+
+    let mut out = String::from("graph plagiarism {\n");
+    for n in nodes {
+        let _ = writeln!(out, "  {};", quote(n));
+    }
+    for e in edges {
+        let mut attrs = Vec::new();
+        if let Some(s) = e.similarity {
+            attrs.push(format!("weight={s}"));
+            attrs.push(format!("label=\"{s}%\""));
+        }
+        attrs.push(format!("lines_matched={}", e.lines_matched));
+        if let Some(id) = e.case_id {
+            attrs.push(format!("case_id={id}"));
+        }
+        if let Some(status) = &e.status {
+            attrs.push(format!("status={}", quote(status)));
+        }
+        let _ = writeln!(
+            out,
+            "  {} -- {} [{}];",
+            quote(&e.source),
+            quote(&e.target),
+            attrs.join(", ")
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn to_graphml(nodes: &BTreeSet<&str>, edges: &[&GraphEdge]) -> String {
+    let mut out = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="lines_matched" for="edge" attr.name="lines_matched" attr.type="long"/>
+  <key id="case_id" for="edge" attr.name="case_id" attr.type="long"/>
+  <key id="status" for="edge" attr.name="status" attr.type="string"/>
+  <graph id="plagiarism" edgedefault="undirected">
+"#,
+    );
+    for n in nodes {
+        let _ = writeln!(out, "    <node id=\"{}\"/>", xml_escape(n));
+    }
+    for (i, e) in edges.iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "    <edge id=\"e{i}\" source=\"{}\" target=\"{}\">",
+            xml_escape(&e.source),
+            xml_escape(&e.target)
+        );
+        if let Some(s) = e.similarity {
+            let _ = writeln!(out, "      <data key=\"weight\">{s}</data>");
+        }
+        let _ = writeln!(
+            out,
+            "      <data key=\"lines_matched\">{}</data>",
+            e.lines_matched
+        );
+        if let Some(id) = e.case_id {
+            let _ = writeln!(out, "      <data key=\"case_id\">{id}</data>");
+        }
+        if let Some(status) = &e.status {
+            let _ = writeln!(
+                out,
+                "      <data key=\"status\">{}</data>",
+                xml_escape(status)
+            );
+        }
+        out.push_str("    </edge>\n");
+    }
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn to_csv(edges: &[&GraphEdge]) -> String {
+    let mut out = String::from("source,target,weight,lines_matched,case_id,status\n");
+    for e in edges {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{}",
+            csv_field(&e.source),
+            csv_field(&e.target),
+            e.similarity.map(|s| s.to_string()).unwrap_or_default(),
+            e.lines_matched,
+            e.case_id.map(|id| id.to_string()).unwrap_or_default(),
+            csv_field(e.status.as_deref().unwrap_or_default())
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edges() -> Vec<GraphEdge> {
+        vec![
+            GraphEdge {
+                source: "bob".into(),
+                target: "alice".into(),
+                similarity: Some(83.5),
+                lines_matched: 40,
+                case_id: Some(7),
+                status: Some("flagged".into()),
+            },
+            GraphEdge {
+                source: "carol \"c\" <3".into(),
+                target: "dave, jr".into(),
+                similarity: Some(20.0),
+                lines_matched: 4,
+                case_id: None,
+                status: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn exports_all_three_formats() {
+        let all = GraphExportOptions::default();
+
+        assert_eq!(
+            export_graph(&edges(), GraphFormat::Dot, &all),
+            "graph plagiarism {\n  \"alice\";\n  \"bob\";\n  \"carol \\\"c\\\" <3\";\n  \"dave, jr\";\n  \"bob\" -- \"alice\" [weight=83.5, label=\"83.5%\", lines_matched=40, case_id=7, status=\"flagged\"];\n  \"carol \\\"c\\\" <3\" -- \"dave, jr\" [weight=20, label=\"20%\", lines_matched=4];\n}\n"
+        );
+
+        assert_eq!(
+            export_graph(&edges(), GraphFormat::Csv, &all),
+            "source,target,weight,lines_matched,case_id,status\nbob,alice,83.5,40,7,flagged\n\"carol \"\"c\"\" <3\",\"dave, jr\",20,4,,\n"
+        );
+
+        let graphml = export_graph(&edges(), GraphFormat::GraphMl, &all);
+        assert!(graphml.contains("<node id=\"carol &quot;c&quot; &lt;3\"/>"));
+        assert!(graphml.contains(
+            "<edge id=\"e0\" source=\"bob\" target=\"alice\">\n      <data key=\"weight\">83.5</data>\n      <data key=\"lines_matched\">40</data>\n      <data key=\"case_id\">7</data>\n      <data key=\"status\">flagged</data>\n    </edge>"
+        ));
+        assert_eq!(graphml.matches("<node ").count(), 4);
+        assert!(graphml.ends_with("</graph>\n</graphml>\n"));
+    }
+
+    #[test]
+    fn thresholds_drop_edges_and_their_nodes() {
+        let mut unknown = edges();
+        unknown[1].similarity = None;
+
+        let strong = GraphExportOptions {
+            min_similarity: Some(50.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            export_graph(&unknown, GraphFormat::Csv, &strong),
+            "source,target,weight,lines_matched,case_id,status\nbob,alice,83.5,40,7,flagged\n"
+        );
+
+        let weak = GraphExportOptions {
+            max_similarity: Some(50.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            export_graph(&edges(), GraphFormat::Csv, &weak)
+                .lines()
+                .count(),
+            2
+        );
+
+        let long = GraphExportOptions {
+            min_lines: 10,
+            ..Default::default()
+        };
+        let dot = export_graph(&edges(), GraphFormat::Dot, &long);
+        assert!(!dot.contains("dave"));
+
+        assert_eq!("GraphML".parse::<GraphFormat>(), Ok(GraphFormat::GraphMl));
+        assert_eq!("gv".parse::<GraphFormat>(), Ok(GraphFormat::Dot));
+        assert!("png".parse::<GraphFormat>().is_err());
+    }
+}
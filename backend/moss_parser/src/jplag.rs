@@ -0,0 +1,363 @@
+//! JPlag results → the same `Output` / `UserPairReport` shape `parse_moss` produces.
+//!
+//! JPlag is run on one directory per submission, named `<username>_<submission_id>` like the
+//! directories uploaded to MOSS. Its report (the unzipped `results.zip`) holds one JSON file per
+//! compared pair with both submission names, their similarity (0.0–1.0) and the matched
+//! regions; `overview.json` only supplies the title. Field names changed between JPlag
+//! releases, so both the `id1` / `start1` style and the `first_submission_id` /
+//! `start_in_first` style are read.
+
+use crate::{FileMatchRow, MatchRegion, Output, ParseOptions, UserPairReport};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// One compared pair from a JPlag comparison file.
+#[derive(Debug, Clone)]
+struct Comparison {
+    first: String,
+    second: String,
+    similarity: f64,
+    matches: Vec<Match>,
+    file_name: String,
+}
+
+#[derive(Debug, Clone)]
+struct Match {
+    file1: String,
+    file2: String,
+    lines: i64,
+    /// Both line ranges, when the second side's are given too.
+    region: Option<MatchRegion>,
+}
+
+/// Main JPlag entrypoint: read an unzipped JPlag report directory and assemble `Output`.
+///
+/// # Arguments
+/// * `dir` - Directory the JPlag `results.zip` was extracted to.
+/// * `opts` - Controls filtering and whether to include detailed matches (`archive_dir` is
+///   ignored; the JPlag report is already local).
+///
+/// # Errors
+/// Returns an error if the directory cannot be read. JSON files that are not comparisons
+/// (or not JSON at all) are skipped.
+pub fn parse_jplag_dir(dir: &Path, opts: ParseOptions) -> Result<Output> {
+    let mut files = Vec::new();
+    collect_json_files(dir, &mut files)?;
+    files.sort();
+
+    let mut title = None;
+    let mut comparisons = Vec::new();
+    for path in files {
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        let Ok(value) = serde_json::from_str::<Value>(&text) else {
+            continue;
+        };
+        let file_name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        if file_name == "overview.json" {
+            title = overview_title(&value);
+        } else if let Some(c) = parse_comparison(&value, &file_name) {
+            comparisons.push(c);
+        }
+    }
+
+    Ok(Output {
+        title,
+        reports: build_reports(comparisons, &opts),
+        archive_errors: Vec::new(),
+    })
+}
+
+/* --------------------- Internal helpers (crate-private) -------------------- */
+
+fn collect_json_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_json_files(&path, out)?;
+        } else if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// First of `keys` present on `v`.
+fn field<'a>(v: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    keys.iter().find_map(|k| v.get(*k))
+}
+
+/// A line number, given either directly or as `{ "line": n }`.
+fn line_of(v: &Value) -> Option<i64> {
+    v.as_i64().or_else(|| v.get("line").and_then(Value::as_i64))
+}
+
+fn overview_title(v: &Value) -> Option<String> {
+    let language = field(v, &["language", "language_name"])?.as_str()?;
+    Some(format!("JPlag results ({language})"))
+}
+
+fn parse_comparison(v: &Value, file_name: &str) -> Option<Comparison> {
+    let first = field(v, &["id1", "first_submission_id", "firstSubmissionId"])?.as_str()?;
+    let second = field(v, &["id2", "second_submission_id", "secondSubmissionId"])?.as_str()?;
+    let similarity = field(v, &["similarities"])
+        .and_then(|s| field(s, &["AVG", "avg"]))
+        .or_else(|| field(v, &["similarity"]))
+        .and_then(Value::as_f64)
+        .unwrap_or(0.0);
+
+    let matches = field(v, &["matches"])
+        .and_then(Value::as_array)
+        .map(|list| {
+            list.iter()
+                .filter_map(|m| {
+                    let file1 = field(m, &["file1", "first_file_name", "firstFileName"])?;
+                    let file2 = field(m, &["file2", "second_file_name", "secondFileName"])?;
+                    let start = field(m, &["start1", "start_in_first", "startInFirst"])
+                        .and_then(line_of)?;
+                    let end =
+                        field(m, &["end1", "end_in_first", "endInFirst"]).and_then(line_of)?;
+                    let start2 =
+                        field(m, &["start2", "start_in_second", "startInSecond"]).and_then(line_of);
+                    let end2 =
+                        field(m, &["end2", "end_in_second", "endInSecond"]).and_then(line_of);
+                    Some(Match {
+                        file1: strip_submission(file1.as_str()?, first),
+                        file2: strip_submission(file2.as_str()?, second),
+                        lines: (end - start + 1).max(0),
+                        region: start2.zip(end2).map(|(b_start, b_end)| MatchRegion {
+                            a_start: start,
+                            a_end: end,
+                            b_start,
+                            b_end,
+                        }),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(Comparison {
+        first: first.to_string(),
+        second: second.to_string(),
+        similarity,
+        matches,
+        file_name: file_name.to_string(),
+    })
+}
+
+/// File path relative to its submission directory.
+fn strip_submission(path: &str, submission: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.strip_prefix(&format!("{submission}/"))
+        .unwrap_or(&path)
+        .to_string()
+}
+
+/// Splits a `<username>_<submission_id>` directory name.
+fn parse_submission_name(s: &str) -> (String, Option<i64>) {
+    match s.rsplit_once('_') {
+        Some((user, id)) if !user.is_empty() => match id.parse::<i64>() {
+            Ok(id) => (user.to_string(), Some(id)),
+            Err(_) => (s.to_string(), None),
+        },
+        _ => (s.to_string(), None),
+    }
+}
+
+fn build_reports(comparisons: Vec<Comparison>, opts: &ParseOptions) -> Vec<UserPairReport> {
+    let mut by_users: HashMap<(String, String), UserPairReport> = HashMap::new();
+
+    for c in comparisons {
+        let (user_1, id_1) = parse_submission_name(&c.first);
+        let (user_2, id_2) = parse_submission_name(&c.second);
+        if user_1 == user_2 {
+            continue;
+        }
+        let swap = user_1 > user_2;
+
+        // Lines (counted on the first submission's side) and regions per file pair
+        let mut per_file: BTreeMap<(String, String), (i64, Vec<MatchRegion>)> = BTreeMap::new();
+        for m in &c.matches {
+            let (key, region) = if swap {
+                (
+                    (m.file2.clone(), m.file1.clone()),
+                    m.region.map(MatchRegion::swapped),
+                )
+            } else {
+                ((m.file1.clone(), m.file2.clone()), m.region)
+            };
+            let entry = per_file.entry(key).or_default();
+            entry.0 += m.lines;
+            entry.1.extend(region);
+        }
+        let total_lines_matched: i64 = per_file.values().map(|(lines, _)| lines).sum();
+        if opts.min_lines > 0 && total_lines_matched < opts.min_lines {
+            continue;
+        }
+
+        let matches = opts.include_matches.then(|| {
+            let mut rows: Vec<FileMatchRow> = per_file
+                .into_iter()
+                .map(
+                    |((a_filename, b_filename), (lines_matched, regions))| FileMatchRow {
+                        a_filename,
+                        b_filename,
+                        percent: None,
+                        lines_matched,
+                        match_href: c.file_name.clone(),
+                        regions,
+                    },
+                )
+                .collect();
+            rows.sort_by(|x, y| {
+                y.lines_matched
+                    .cmp(&x.lines_matched)
+                    .then(x.a_filename.cmp(&y.a_filename))
+                    .then(x.b_filename.cmp(&y.b_filename))
+            });
+            rows
+        });
+
+        let (user_a, submission_id_a, user_b, submission_id_b) = if swap {
+            (user_2, id_2, user_1, id_1)
+        } else {
+            (user_1, id_1, user_2, id_2)
+        };
+        let report = UserPairReport {
+            user_a: user_a.clone(),
+            user_b: user_b.clone(),
+            submission_id_a,
+            submission_id_b,
+            total_lines_matched,
+            total_percent: Some((c.similarity * 1000.0).round() / 10.0),
+            matches,
+        };
+
+        // Keep the strongest comparison per user pair
+        match by_users.get(&(user_a.clone(), user_b.clone())) {
+            Some(existing) if existing.total_percent >= report.total_percent => {}
+            _ => {
+                by_users.insert((user_a, user_b), report);
+            }
+        }
+    }
+
+    let mut reports: Vec<UserPairReport> = by_users.into_values().collect();
+    reports.sort_by(|a, b| {
+        b.total_lines_matched
+            .cmp(&a.total_lines_matched)
+            .then(a.user_a.cmp(&b.user_a))
+            .then(a.user_b.cmp(&b.user_b))
+    });
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn comparison(v: Value) -> Comparison {
+        parse_comparison(&v, "bob_7-alice_3.json").unwrap()
+    }
+
+    #[test]
+    fn reads_both_comparison_formats() {
+        let old = comparison(json!({
+            "id1": "bob_7",
+            "id2": "alice_3",
+            "similarities": { "AVG": 0.8123, "MAX": 0.9 },
+            "matches": [
+                { "file1": "bob_7/main.cpp", "file2": "alice_3\\main.cpp",
+                  "start1": 10, "end1": 19, "start2": 1, "end2": 10, "tokens": 40 }
+            ]
+        }));
+        let new = comparison(json!({
+            "first_submission_id": "bob_7",
+            "second_submission_id": "alice_3",
+            "similarity": 0.8123,
+            "matches": [
+                { "first_file_name": "main.cpp", "second_file_name": "main.cpp",
+                  "start_in_first": { "line": 10 }, "end_in_first": { "line": 19 } }
+            ]
+        }));
+
+        for c in [old, new] {
+            assert_eq!((c.first.as_str(), c.second.as_str()), ("bob_7", "alice_3"));
+            assert!((c.similarity - 0.8123).abs() < 1e-9);
+            assert_eq!(c.matches.len(), 1);
+            assert_eq!(c.matches[0].file1, "main.cpp");
+            assert_eq!(c.matches[0].file2, "main.cpp");
+            assert_eq!(c.matches[0].lines, 10);
+        }
+        assert!(parse_comparison(&json!({ "submission_folder_path": [] }), "x.json").is_none());
+    }
+
+    #[test]
+    fn builds_user_pair_reports_like_moss() {
+        let comparisons = vec![
+            comparison(json!({
+                "id1": "bob_7", "id2": "alice_3", "similarity": 0.5,
+                "matches": [
+                    { "file1": "a.cpp", "file2": "x.cpp", "start1": 1, "end1": 5, "start2": 11, "end2": 15 },
+                    { "file1": "a.cpp", "file2": "x.cpp", "start1": 20, "end1": 24 },
+                    { "file1": "b.cpp", "file2": "y.cpp", "start1": 1, "end1": 3 }
+                ]
+            })),
+            // Same user on both sides (e.g. a resubmission) is not a case
+            comparison(json!({ "id1": "bob_7", "id2": "bob_8", "similarity": 1.0, "matches": [] })),
+            comparison(json!({
+                "id1": "carol_9", "id2": "dave", "similarity": 0.25,
+                "matches": [{ "file1": "m.cpp", "file2": "m.cpp", "start1": 1, "end1": 2 }]
+            })),
+        ];
+
+        let reports = build_reports(comparisons.clone(), &ParseOptions::default());
+        assert_eq!(reports.len(), 2);
+
+        let r = &reports[0];
+        assert_eq!((r.user_a.as_str(), r.user_b.as_str()), ("alice", "bob"));
+        assert_eq!((r.submission_id_a, r.submission_id_b), (Some(3), Some(7)));
+        assert_eq!(r.total_lines_matched, 13);
+        assert_eq!(r.total_percent, Some(50.0));
+        let rows = r.matches.as_ref().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            (rows[0].a_filename.as_str(), rows[0].b_filename.as_str()),
+            ("x.cpp", "a.cpp")
+        );
+        assert_eq!(rows[0].lines_matched, 10);
+        assert_eq!(
+            rows[0].regions,
+            [MatchRegion {
+                a_start: 11,
+                a_end: 15,
+                b_start: 1,
+                b_end: 5
+            }]
+        );
+        assert!(rows[1].regions.is_empty());
+
+        assert_eq!(reports[1].submission_id_b, None);
+
+        let filtered = build_reports(
+            comparisons,
+            &ParseOptions {
+                min_lines: 5,
+                include_matches: false,
+                ..ParseOptions::default()
+            },
+        );
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].matches.is_none());
+    }
+}
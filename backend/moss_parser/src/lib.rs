@@ -1,9 +1,16 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use regex::Regex;
-use reqwest::{Client, redirect};
 use scraper::{Html, Selector};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
+
+mod archive;
+mod fetch;
+pub mod graph;
+pub mod jplag;
+
+pub use fetch::FetchOptions;
 
 /// Public API: control how the report is produced.
 #[derive(Clone, Debug)]
@@ -12,6 +19,11 @@ pub struct ParseOptions {
     pub min_lines: i64,
     /// Include per-file match details in each user-pair report.
     pub include_matches: bool,
+    /// Save each match page (and its frames) here, with links rewritten; `match_href`s in the
+    /// output then name the local copies. MOSS only keeps reports for 14 days.
+    pub archive_dir: Option<PathBuf>,
+    /// Timeouts, retries and pacing for the report page and archived match pages.
+    pub fetch: FetchOptions,
 }
 
 impl Default for ParseOptions {
@@ -19,6 +31,8 @@ impl Default for ParseOptions {
         Self {
             min_lines: 0,
             include_matches: true,
+            archive_dir: None,
+            fetch: FetchOptions::default(),
         }
     }
 }
@@ -39,15 +53,43 @@ pub struct MossPair {
     pub file2: PairRef,
     pub lines_matched: i64,
     pub match_href: String,
+    /// Matched regions (`a` = `file1`), read from the archived match page's top frame.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<MatchRegion>,
 }
 
-#[derive(Debug, Serialize)]
+/// One matched region of a file pair: 1-based, inclusive line ranges on both sides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchRegion {
+    pub a_start: i64,
+    pub a_end: i64,
+    pub b_start: i64,
+    pub b_end: i64,
+}
+
+impl MatchRegion {
+    /// The same region seen from the other file.
+    pub fn swapped(self) -> Self {
+        Self {
+            a_start: self.b_start,
+            a_end: self.b_end,
+            b_start: self.a_start,
+            b_end: self.a_end,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FileMatchRow {
     pub a_filename: String,
     pub b_filename: String,
     pub percent: Option<u32>,
     pub lines_matched: i64,
     pub match_href: String,
+    /// Where the files match; empty when the engine did not report line ranges (MOSS
+    /// reports parsed without `archive_dir`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<MatchRegion>,
 }
 
 #[derive(Debug, Serialize)]
@@ -66,6 +108,10 @@ pub struct UserPairReport {
 pub struct Output {
     pub title: Option<String>,
     pub reports: Vec<UserPairReport>,
+    /// Match pages that could not be archived even after retrying (their `match_href` stays
+    /// remote and their pairs have no `regions`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub archive_errors: Vec<String>,
 }
 
 /// Main library entrypoint: fetch MOSS HTML, parse, and assemble `Output`.
@@ -78,13 +124,19 @@ pub struct Output {
 /// * `Output` - Title + grouped per-user reports (optionally including matches).
 ///
 /// # Errors
-/// Returns an error if fetching/parsing fails.
+/// Returns an error if fetching/parsing the report page fails (after `opts.fetch.retries`
+/// retries). Failing to archive some match pages is not an error: the result is returned
+/// without them and they are listed in `Output::archive_errors`.
 pub async fn parse_moss(url: &str, opts: ParseOptions) -> Result<Output> {
-    let html = fetch_html(url).await?;
-    let doc = Html::parse_document(&html);
+    let fetcher = fetch::Fetcher::new(opts.fetch.clone())?;
+    let html = fetcher.fetch_html(url).await?;
 
-    // Extract raw pairs from the HTML table.
-    let mut pairs = extract_pairs(&doc);
+    // Extract title and raw pairs from the HTML table (the parsed document is not `Send`, so
+    // it must not live across the archiving awaits below).
+    let (title, mut pairs) = {
+        let doc = Html::parse_document(&html);
+        (extract_title(&doc), extract_pairs(&doc))
+    };
 
     // Filter out rows where both sides resolve to same username (self matches).
     pairs.retain(|p| p.file1.username.as_deref() != p.file2.username.as_deref());
@@ -95,44 +147,35 @@ pub async fn parse_moss(url: &str, opts: ParseOptions) -> Result<Output> {
     }
 
     // Keep best per file-pair, then group by user pair.
-    let pairs = dedupe_pairs_keep_best(pairs);
+    let mut pairs = dedupe_pairs_keep_best(pairs);
+
+    // Archive the kept match pages and point the pairs at the local copies.
+    let mut archive_errors = Vec::new();
+    if let Some(dir) = &opts.archive_dir {
+        let hrefs: Vec<&str> = pairs.iter().map(|p| p.match_href.as_str()).collect();
+        let archived = archive::archive_match_pages(&fetcher, url, &hrefs, dir).await?;
+        for p in &mut pairs {
+            p.regions = archived.regions(url, &p.match_href);
+            for href in [&mut p.match_href, &mut p.file1.href, &mut p.file2.href] {
+                if let Some(local) = archived.local_href(url, href) {
+                    *href = local;
+                }
+            }
+        }
+        archive_errors = archived.errors;
+    }
+
     let reports = group_by_user_pair(pairs, opts.include_matches);
 
     Ok(Output {
-        title: extract_title(&doc),
+        title,
         reports,
+        archive_errors,
     })
 }
 
 /* --------------------- Internal helpers (crate-private) -------------------- */
 
-async fn fetch_html(url: &str) -> Result<String> {
-    let client = Client::builder()
-        .user_agent(concat!(
-            "moss-scrape/0.1 (+https://example.invalid) ",
-            "reqwest/"
-        ))
-        .gzip(true)
-        .brotli(true)
-        .deflate(true)
-        .http1_only()
-        .redirect(redirect::Policy::limited(10))
-        .build()
-        .context("building HTTP client")?;
-
-    let resp = client
-        .get(url)
-        .timeout(std::time::Duration::from_secs(20))
-        .send()
-        .await
-        .with_context(|| format!("GET {url}"))?
-        .error_for_status()
-        .context("non-success status")?;
-
-    let bytes = resp.bytes().await.context("reading body")?;
-    Ok(String::from_utf8_lossy(&bytes).into_owned())
-}
-
 fn extract_title(doc: &Html) -> Option<String> {
     let sel = Selector::parse("title").unwrap();
     doc.select(&sel)
@@ -198,6 +241,7 @@ fn extract_pairs(doc: &Html) -> Vec<MossPair> {
             },
             lines_matched,
             match_href: href1,
+            regions: Vec::new(),
         });
     }
     out
@@ -301,6 +345,7 @@ fn group_by_user_pair(pairs: Vec<MossPair>, include_matches: bool) -> Vec<UserPa
                     file2: p.file1.clone(),
                     lines_matched: p.lines_matched,
                     match_href: p.match_href.clone(),
+                    regions: p.regions.iter().map(|r| r.swapped()).collect(),
                 },
             )
         };
@@ -356,6 +401,7 @@ fn group_by_user_pair(pairs: Vec<MossPair>, include_matches: bool) -> Vec<UserPa
                     percent,
                     lines_matched: p.lines_matched,
                     match_href: p.match_href,
+                    regions: p.regions,
                 });
             }
         }
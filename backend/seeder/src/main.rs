@@ -51,4 +51,9 @@ async fn main() {
     ] {
         run_seeder(&*seeder, name, &db).await;
     }
+
+    // Some seeds use fixed ids, which PostgreSQL sequences don't see.
+    db::sync_id_sequences(&db)
+        .await
+        .expect("Failed to sync id sequences");
 }
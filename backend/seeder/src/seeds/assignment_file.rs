@@ -238,7 +238,7 @@ task4: compile
   "marking": {
     "marking_scheme": "exact",
     "feedback_scheme": "auto",
-    "deliminator": "###",
+    "delimiter": "###",
     "grading_policy": "last",
     "max_attempts": 10,
     "limit_attempts": false,
@@ -545,7 +545,7 @@ task4: main
   "marking": {
     "marking_scheme": "exact",
     "feedback_scheme": "auto",
-    "deliminator": "###",
+    "delimiter": "###",
     "grading_policy": "last",
     "max_attempts": 10,
     "limit_attempts": false,
@@ -761,7 +761,7 @@ task4: main
         "marking": {
             "marking_scheme": "exact",
             "feedback_scheme": "auto",
-            "deliminator": "###"
+            "delimiter": "###"
         },
         "project": {
             "language": "cpp"
@@ -940,7 +940,7 @@ task4: main
         //     "verbose": false
         //   },
         //   "marking": {
-        //     "deliminator": "###",
+        //     "delimiter": "###",
         //     "feedback_scheme": "auto",
         //     "marking_scheme": "exact"
         //   },
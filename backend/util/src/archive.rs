@@ -0,0 +1,399 @@
+//! Rewriting uploaded archives into a normalized zip.
+//!
+//! Students upload zips made by Finder, tarballs, 7z and rar files, often wrapped in one or more
+//! folders named after the project. [`normalize_archive`] turns all of them into the same shape
+//! so downstream code (extraction in code_manager, the code scanner) only ever sees a clean zip:
+//!
+//! - tar, tar.gz, gz, 7z and rar inputs are converted to zip;
+//! - macOS metadata (`__MACOSX/`, `.DS_Store`, `._*` AppleDouble files) is dropped, as are
+//!   directory entries and links;
+//! - single top-level folders are unwrapped until the archive root has more than one entry;
+//! - absolute and `..` paths are rejected, and the file count and total uncompressed size are
+//!   capped while reading, so an archive bomb is never fully expanded.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use tar::Archive;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::execution_config::ExecutionConfig;
+use crate::rar;
+use crate::scan_code_content::{ArchiveFormat, detect_archive_format};
+
+/// Caps applied while reading an archive.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    /// Maximum combined uncompressed size of all kept files, in bytes.
+    pub max_total_size: u64,
+    /// Maximum number of kept files.
+    pub max_files: usize,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_total_size: 100_000_000,
+            max_files: 10_000,
+        }
+    }
+}
+
+impl From<&ExecutionConfig> for ArchiveLimits {
+    /// Caps the total size at the assignment's `execution.max_uncompressed_size`, the same
+    /// limit code_manager applies when extracting.
+    fn from(config: &ExecutionConfig) -> Self {
+        Self {
+            max_total_size: config.execution.max_uncompressed_size,
+            ..Self::default()
+        }
+    }
+}
+
+/// Archive extensions recognised by [`zip_name`], longest first.
+const ARCHIVE_EXTENSIONS: &[&str] = &[".tar.gz", ".tgz", ".tar", ".gz", ".zip", ".7z", ".rar"];
+
+/// The name a normalized archive should be stored under: `file_name` with its archive
+/// extension replaced by `.zip` (e.g. `project.tar.gz` -> `project.zip`).
+pub fn zip_name(file_name: &str) -> String {
+    let lower = file_name.to_ascii_lowercase();
+    let stem = ARCHIVE_EXTENSIONS
+        .iter()
+        .find(|ext| lower.ends_with(*ext) && lower.len() > ext.len())
+        .map(|ext| &file_name[..file_name.len() - ext.len()])
+        .unwrap_or(file_name);
+    format!("{stem}.zip")
+}
+
+/// Reads the archive in `bytes` (any format `scan_code_content` detects) and returns it
+/// rewritten as a normalized zip. See the module docs for what is normalized.
+pub fn normalize_archive(bytes: &[u8], limits: &ArchiveLimits) -> Result<Vec<u8>, String> {
+    let mut files = Collector::new(limits);
+    match detect_archive_format(bytes)? {
+        ArchiveFormat::Zip => read_zip(bytes, &mut files)?,
+        ArchiveFormat::Tar => read_tar(Archive::new(Cursor::new(bytes)), &mut files)?,
+        ArchiveFormat::TarGz => {
+            read_tar(Archive::new(GzDecoder::new(Cursor::new(bytes))), &mut files)?
+        }
+        ArchiveFormat::Gz => read_gz(bytes, &mut files)?,
+        ArchiveFormat::SevenZ => read_7z(bytes, &mut files)?,
+        ArchiveFormat::Rar => {
+            let dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {e}"))?;
+            rar::extract_rar(bytes, dir.path())?;
+            read_dir(dir.path(), dir.path(), &mut files)?;
+        }
+    }
+
+    let mut files = files.files;
+    if files.is_empty() {
+        return Err("Archive contains no files".to_string());
+    }
+    while let Some(prefix) = wrapper_folder(&files) {
+        files = files
+            .into_iter()
+            .map(|(path, contents)| (path[prefix.len()..].to_string(), contents))
+            .collect();
+    }
+    write_zip(&files)
+}
+
+/// Accumulates the kept files, enforcing [`ArchiveLimits`].
+struct Collector<'a> {
+    limits: &'a ArchiveLimits,
+    total_size: u64,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl<'a> Collector<'a> {
+    fn new(limits: &'a ArchiveLimits) -> Self {
+        Self {
+            limits,
+            total_size: 0,
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Reads one regular file stored at `raw_path`, unless it is metadata to drop.
+    fn add<R: Read>(&mut self, raw_path: &str, reader: R) -> Result<(), String> {
+        let Some(path) = clean_path(raw_path)? else {
+            return Ok(());
+        };
+        if self.files.len() >= self.limits.max_files {
+            return Err(format!(
+                "Archive contains more than {} files",
+                self.limits.max_files
+            ));
+        }
+
+        let remaining = self.limits.max_total_size - self.total_size;
+        let mut contents = Vec::new();
+        reader
+            .take(remaining + 1)
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {path} from archive: {e}"))?;
+        if contents.len() as u64 > remaining {
+            return Err(format!(
+                "Archive expands to more than {} bytes",
+                self.limits.max_total_size
+            ));
+        }
+
+        self.total_size += contents.len() as u64;
+        self.files.insert(path, contents);
+        Ok(())
+    }
+}
+
+/// Normalizes an entry path to `a/b/c` form. `Ok(None)` for macOS metadata, an error for paths
+/// that would escape the extraction directory.
+fn clean_path(raw_path: &str) -> Result<Option<String>, String> {
+    let unsafe_path = || format!("Unsafe path in archive: {raw_path}");
+    let raw = raw_path.replace('\\', "/");
+    if raw.starts_with('/') {
+        return Err(unsafe_path());
+    }
+
+    let mut parts = Vec::new();
+    for part in raw.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => return Err(unsafe_path()),
+            _ if part.contains(':') => return Err(unsafe_path()),
+            _ => parts.push(part),
+        }
+    }
+
+    let is_metadata = parts.contains(&"__MACOSX")
+        || parts
+            .last()
+            .is_some_and(|name| *name == ".DS_Store" || name.starts_with("._"));
+    if parts.is_empty() || is_metadata {
+        return Ok(None);
+    }
+    Ok(Some(parts.join("/")))
+}
+
+/// The `folder/` prefix shared by every file, if they are all inside the same top-level folder.
+fn wrapper_folder(files: &BTreeMap<String, Vec<u8>>) -> Option<String> {
+    let (first, _) = files.keys().next()?.split_once('/')?;
+    let prefix = format!("{first}/");
+    files
+        .keys()
+        .all(|path| path.starts_with(&prefix))
+        .then_some(prefix)
+}
+
+fn read_zip(bytes: &[u8], files: &mut Collector) -> Result<(), String> {
+    let mut archive =
+        ZipArchive::new(Cursor::new(bytes)).map_err(|e| format!("Failed to read zip: {e}"))?;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read zip entry: {e}"))?;
+        if !entry.is_file() || entry.is_symlink() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        files.add(&name, entry)?;
+    }
+    Ok(())
+}
+
+fn read_tar<R: Read>(mut archive: Archive<R>, files: &mut Collector) -> Result<(), String> {
+    let entries = archive
+        .entries()
+        .map_err(|e| format!("Failed to read tar: {e}"))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read tar entry: {e}"))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .map_err(|e| format!("Invalid path in tar: {e}"))?
+            .to_string_lossy()
+            .into_owned();
+        files.add(&path, entry)?;
+    }
+    Ok(())
+}
+
+fn read_gz(bytes: &[u8], files: &mut Collector) -> Result<(), String> {
+    let decoder = GzDecoder::new(Cursor::new(bytes));
+    let name = decoder
+        .header()
+        .and_then(|h| h.filename())
+        .map(|n| String::from_utf8_lossy(n).into_owned())
+        .unwrap_or_else(|| "file".to_string());
+    files.add(&name, decoder)
+}
+
+fn read_7z(bytes: &[u8], files: &mut Collector) -> Result<(), String> {
+    let mut archive = sevenz_rust::SevenZReader::new(
+        Cursor::new(bytes),
+        bytes.len() as u64,
+        sevenz_rust::Password::empty(),
+    )
+    .map_err(|e| format!("Failed to read 7z archive: {e}"))?;
+
+    archive
+        .for_each_entries(|entry, reader| {
+            if !entry.is_directory() {
+                files
+                    .add(entry.name(), reader)
+                    .map_err(sevenz_rust::Error::other)?;
+            }
+            Ok(true)
+        })
+        .map_err(|e| format!("Failed to read 7z entry: {e}"))
+}
+
+fn read_dir(root: &Path, dir: &Path, files: &mut Collector) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read extracted files: {e}"))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read extracted files: {e}"))?
+            .path();
+        let file_type = fs::symlink_metadata(&path)
+            .map_err(|e| format!("Failed to read extracted files: {e}"))?
+            .file_type();
+        if file_type.is_dir() {
+            read_dir(root, &path, files)?;
+        } else if file_type.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            let file =
+                fs::File::open(&path).map_err(|e| format!("Failed to read extracted file: {e}"))?;
+            files.add(&relative.to_string_lossy(), file)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_zip(files: &BTreeMap<String, Vec<u8>>) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    // A fixed timestamp keeps the output identical for identical contents.
+    let options = SimpleFileOptions::default().last_modified_time(zip::DateTime::default());
+
+    for (path, contents) in files {
+        zip.start_file(path.as_str(), options)
+            .map_err(|e| format!("Failed to add {path} to zip: {e}"))?;
+        zip.write_all(contents)
+            .map_err(|e| format!("Failed to write {path} to zip: {e}"))?;
+    }
+
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish zip: {e}"))?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    fn make_zip(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            if name.ends_with('/') {
+                zip.add_directory(*name, SimpleFileOptions::default())
+                    .unwrap();
+            } else {
+                zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+                zip.write_all(contents.as_bytes()).unwrap();
+            }
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn read_names(bytes: &[u8]) -> Vec<String> {
+        let archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn strips_macos_metadata_and_wrapper_folders() {
+        let bytes = make_zip(&[
+            ("project/", ""),
+            ("project/project/Makefile", "all:"),
+            ("project/project/src/main.c", "int main() {}"),
+            ("project/project/.DS_Store", "junk"),
+            ("__MACOSX/project/project/._Makefile", "junk"),
+        ]);
+
+        let normalized = normalize_archive(&bytes, &ArchiveLimits::default()).unwrap();
+        assert_eq!(read_names(&normalized), ["Makefile", "src/main.c"]);
+
+        // Normalizing is idempotent and deterministic.
+        let again = normalize_archive(&normalized, &ArchiveLimits::default()).unwrap();
+        assert_eq!(again, normalized);
+
+        // A single folder next to a root file is not a wrapper.
+        let bytes = make_zip(&[("src/main.c", ""), ("Makefile", "")]);
+        let normalized = normalize_archive(&bytes, &ArchiveLimits::default()).unwrap();
+        assert_eq!(read_names(&normalized), ["Makefile", "src/main.c"]);
+    }
+
+    #[test]
+    fn converts_tar_gz_to_zip() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, contents) in [("sub/main.py", "print(1)"), ("sub/util.py", "x = 1")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, name, contents.as_bytes())
+                .unwrap();
+        }
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+
+        let normalized = normalize_archive(&bytes, &ArchiveLimits::default()).unwrap();
+        assert_eq!(read_names(&normalized), ["main.py", "util.py"]);
+        let mut archive = ZipArchive::new(Cursor::new(normalized)).unwrap();
+        let mut contents = String::new();
+        archive
+            .by_name("main.py")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "print(1)");
+    }
+
+    #[test]
+    fn rejects_unsafe_paths_and_oversized_archives() {
+        let escape = make_zip(&[("../evil.sh", "rm -rf /"), ("ok.c", "")]);
+        assert!(normalize_archive(&escape, &ArchiveLimits::default()).is_err());
+
+        let big = make_zip(&[("a.txt", "0123456789"), ("b.txt", "0123456789")]);
+        let limits = ArchiveLimits {
+            max_total_size: 15,
+            max_files: 10,
+        };
+        assert!(normalize_archive(&big, &limits).is_err());
+        let limits = ArchiveLimits {
+            max_total_size: 100,
+            max_files: 1,
+        };
+        assert!(normalize_archive(&big, &limits).is_err());
+
+        let only_metadata = make_zip(&[("__MACOSX/._a.c", ""), (".DS_Store", "")]);
+        assert!(normalize_archive(&only_metadata, &ArchiveLimits::default()).is_err());
+    }
+
+    #[test]
+    fn zip_name_replaces_archive_extensions() {
+        assert_eq!(zip_name("project.tar.gz"), "project.zip");
+        assert_eq!(zip_name("project.TGZ"), "project.zip");
+        assert_eq!(zip_name("project.rar"), "project.zip");
+        assert_eq!(zip_name("project.zip"), "project.zip");
+        assert_eq!(zip_name("project"), "project.zip");
+    }
+}
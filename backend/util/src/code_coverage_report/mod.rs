@@ -1,22 +1,49 @@
+use crate::execution_config::{CodeCoverage, CoverageMetric};
 use crate::languages::Language;
 use chrono::Utc;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Totals of a report. Branch and function counts are zero when the tool does not report
+/// them; their percentages are then `None`.
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CoverageSummary {
     pub total_files: u64,
     pub total_lines: u64,
     pub covered_lines: u64,
+    /// Line coverage.
     pub coverage_percent: f64,
+    #[serde(default)]
+    pub total_branches: u64,
+    /// Branches taken at least once.
+    #[serde(default)]
+    pub covered_branches: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_percent: Option<f64>,
+    #[serde(default)]
+    pub total_functions: u64,
+    /// Functions entered at least once.
+    #[serde(default)]
+    pub covered_functions: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_percent: Option<f64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct CoverageFile {
     pub path: String,
     pub total_lines: u64,
     pub covered_lines: u64,
     pub coverage_percent: f64,
+    #[serde(default)]
+    pub total_branches: u64,
+    #[serde(default)]
+    pub covered_branches: u64,
+    #[serde(default)]
+    pub total_functions: u64,
+    #[serde(default)]
+    pub covered_functions: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,6 +53,32 @@ pub struct CoverageReport {
     pub files: Vec<CoverageFile>,
 }
 
+impl CoverageSummary {
+    /// The percentage the coverage mark is based on, per `config.metric`. A metric the report
+    /// has no data for (e.g. branches from a tool that only counts lines) falls back to line
+    /// coverage, and is left out of a weighted blend.
+    pub fn marked_percent(&self, config: &CodeCoverage) -> f64 {
+        let line = self.coverage_percent;
+        match config.metric {
+            CoverageMetric::Line => line,
+            CoverageMetric::Branch => self.branch_percent.unwrap_or(line),
+            CoverageMetric::Function => self.function_percent.unwrap_or(line),
+            CoverageMetric::Weighted => {
+                let w = &config.weights;
+                let (sum, weight) = [
+                    (w.line, Some(line)),
+                    (w.branch, self.branch_percent),
+                    (w.function, self.function_percent),
+                ]
+                .into_iter()
+                .filter_map(|(w, p)| p.map(|p| (w * p, w)))
+                .fold((0.0, 0.0), |(sum, total), (wp, w)| (sum + wp, total + w));
+                if weight > 0.0 { sum / weight } else { line }
+            }
+        }
+    }
+}
+
 pub struct CoverageProcessor;
 
 impl CoverageProcessor {
@@ -34,9 +87,20 @@ impl CoverageProcessor {
         content: &str,
         whitelist: &[String],
     ) -> Result<String, String> {
+        // Standard formats any toolchain can emit are recognised whatever the language.
+        if is_lcov(content) {
+            return Self::parse_lcov_report(content, whitelist);
+        }
+        if is_cobertura(content) {
+            return Self::report_json(whitelisted(Self::cobertura_files(content), whitelist));
+        }
+
         match language {
             Language::Cpp => Self::parse_cpp_report(content, whitelist),
             Language::Java => Self::parse_java_report(content, whitelist),
+            Language::Rust => Self::parse_rust_report(content, whitelist),
+            Language::Go => Self::parse_go_report(content, whitelist),
+            Language::Python => Self::parse_python_report(content, whitelist),
             other => Err(format!(
                 "Code coverage parsing not supported for {:?}",
                 other
@@ -44,66 +108,79 @@ impl CoverageProcessor {
         }
     }
 
+    /// Parses gcov's summary output. With `-b` a file's `Taken at least once:X% of N` line
+    /// gives its branch coverage; with `-f` every function gets a `Function '...'` block before
+    /// the file summaries, and a function counts as covered if any of its lines ran. Function
+    /// blocks are credited to the file summary that follows them.
     fn parse_cpp_report(content: &str, whitelist: &[String]) -> Result<String, String> {
         let re_file = Regex::new(r"File '([^']+)'").unwrap();
+        let re_function = Regex::new(r"^Function '").unwrap();
         let re_lines = Regex::new(r"Lines executed:([0-9.]+)% of (\d+)").unwrap();
+        let re_branches = Regex::new(r"Taken at least once:([0-9.]+)% of (\d+)").unwrap();
 
-        let mut files = Vec::new();
-        let mut total_lines: u64 = 0;
-        let mut total_covered: u64 = 0;
+        let mut files: Vec<CoverageFile> = Vec::new();
         let mut current_file: Option<String> = None;
+        let mut in_function = false;
+        // Functions seen since the last file summary: (total, covered)
+        let mut functions: (u64, u64) = (0, 0);
+        // The file summary further lines (branches) belong to
+        let mut last: Option<usize> = None;
 
         for line in content.lines() {
-            if let Some(cap) = re_file.captures(line) {
+            if re_function.is_match(line) {
+                in_function = true;
+                current_file = None;
+                last = None;
+                functions.0 += 1;
+            } else if let Some(cap) = re_file.captures(line) {
+                in_function = false;
                 current_file = Some(cap[1].to_string());
+                last = None;
             } else if let Some(cap) = re_lines.captures(line) {
-                if let Some(file) = &current_file {
+                let percent: f64 = cap[1].parse().unwrap_or(0.0);
+                if in_function {
+                    if percent > 0.0 {
+                        functions.1 += 1;
+                    }
+                    in_function = false;
+                } else if let Some(file) = current_file.take() {
                     // Only include file if it's in the whitelist or whitelist is empty
-                    if whitelist.is_empty() || whitelist.contains(file) {
-                        let percent: f64 = cap[1].parse().unwrap_or(0.0);
+                    if whitelist.is_empty() || whitelist.contains(&file) {
                         let lines: u64 = cap[2].parse().unwrap_or(0);
                         let covered = ((percent / 100.0) * (lines as f64)).round() as u64;
 
-                        total_lines += lines;
-                        total_covered += covered;
-
                         files.push(CoverageFile {
-                            path: file.clone(),
+                            path: file,
                             total_lines: lines,
                             covered_lines: covered,
                             coverage_percent: percent,
+                            total_functions: functions.0,
+                            covered_functions: functions.1,
+                            ..Default::default()
                         });
+                        last = Some(files.len() - 1);
                     }
-                    current_file = None;
+                    functions = (0, 0);
                 }
+            } else if let Some(cap) = re_branches.captures(line)
+                && let Some(file) = last.map(|i| &mut files[i])
+            {
+                let percent: f64 = cap[1].parse().unwrap_or(0.0);
+                file.total_branches = cap[2].parse().unwrap_or(0);
+                file.covered_branches =
+                    ((percent / 100.0) * (file.total_branches as f64)).round() as u64;
             }
         }
 
-        let summary = CoverageSummary {
-            total_files: files.len() as u64,
-            total_lines,
-            covered_lines: total_covered,
-            coverage_percent: if total_lines > 0 {
-                (total_covered as f64 / total_lines as f64) * 100.0
-            } else {
-                0.0
-            },
-        };
-
-        let report = CoverageReport {
-            generated_at: Utc::now().to_rfc3339(),
-            summary,
-            files,
-        };
-
-        serde_json::to_string_pretty(&report)
-            .map_err(|e| format!("Failed to serialize coverage report: {}", e))
+        Self::report_json(files)
     }
 
+    /// Parses JaCoCo's CSV report (`GROUP,PACKAGE,CLASS,INSTRUCTION_MISSED,INSTRUCTION_COVERED,
+    /// BRANCH_MISSED,BRANCH_COVERED,LINE_MISSED,LINE_COVERED,COMPLEXITY_MISSED,
+    /// COMPLEXITY_COVERED,METHOD_MISSED,METHOD_COVERED`), one row per class; methods count as
+    /// functions.
     fn parse_java_report(content: &str, whitelist: &[String]) -> Result<String, String> {
         let mut files = Vec::new();
-        let mut total_lines: u64 = 0;
-        let mut total_covered: u64 = 0;
 
         for line in content.lines() {
             if line.starts_with("GROUP,") || line.trim().is_empty() {
@@ -122,48 +199,406 @@ impl CoverageProcessor {
                 continue;
             }
 
-            let line_missed: u64 = cols[7].trim().parse().unwrap_or(0);
-            let line_covered: u64 = cols[8].trim().parse().unwrap_or(0);
-            let total = line_missed + line_covered;
-            let percent = if total > 0 {
-                (line_covered as f64 / total as f64) * 100.0
-            } else {
-                0.0
-            };
-
-            total_lines += total;
-            total_covered += line_covered;
+            let col =
+                |i: usize| -> u64 { cols.get(i).and_then(|c| c.trim().parse().ok()).unwrap_or(0) };
+            let (line_missed, line_covered) = (col(7), col(8));
+            let (branch_missed, branch_covered) = (col(5), col(6));
+            let (method_missed, method_covered) = (col(11), col(12));
 
             files.push(CoverageFile {
                 path: file_name, // now includes .java
-                total_lines: total,
+                total_lines: line_missed + line_covered,
                 covered_lines: line_covered,
-                coverage_percent: percent,
+                coverage_percent: percent(line_covered, line_missed + line_covered),
+                total_branches: branch_missed + branch_covered,
+                covered_branches: branch_covered,
+                total_functions: method_missed + method_covered,
+                covered_functions: method_covered,
             });
         }
 
-        let summary = CoverageSummary {
-            total_files: files.len() as u64,
-            total_lines,
-            covered_lines: total_covered,
-            coverage_percent: if total_lines > 0 {
-                (total_covered as f64 / total_lines as f64) * 100.0
-            } else {
-                0.0
-            },
+        Self::report_json(files)
+    }
+
+    /// Parses the table `llvm-cov report <binary> -instr-profile=<profdata>` prints for a
+    /// binary built with `-C instrument-coverage`:
+    ///
+    /// ```text
+    /// Filename  Regions  Missed Regions  Cover  Functions  Missed Functions  Executed  Lines  Missed Lines  Cover ...
+    /// ---------------------------------------------------------------------------------------------------------
+    /// /code/linked_list.rs  40  6  85.00%  12  1  91.67%  96  9  90.62% ...
+    /// ---------------------------------------------------------------------------------------------------------
+    /// TOTAL  ...
+    /// ```
+    fn parse_rust_report(content: &str, whitelist: &[String]) -> Result<String, String> {
+        let mut files = Vec::new();
+
+        for line in content.lines() {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            if cols.len() < 10 || cols[0] == "Filename" || cols[0] == "TOTAL" {
+                continue;
+            }
+            let (Ok(lines), Ok(missed)) = (cols[7].parse::<u64>(), cols[8].parse::<u64>()) else {
+                continue;
+            };
+            if !is_whitelisted(cols[0], whitelist) {
+                continue;
+            }
+
+            let count = |i: usize| cols.get(i).and_then(|c| c.parse::<u64>().ok()).unwrap_or(0);
+            let (functions, missed_functions) = (count(4), count(5));
+            // Branch columns are only there for builds with branch coverage
+            let (branches, missed_branches) = (count(10), count(11));
+
+            let covered = lines.saturating_sub(missed);
+            files.push(CoverageFile {
+                path: cols[0].to_string(),
+                total_lines: lines,
+                covered_lines: covered,
+                coverage_percent: percent(covered, lines),
+                total_branches: branches,
+                covered_branches: branches.saturating_sub(missed_branches),
+                total_functions: functions,
+                covered_functions: functions.saturating_sub(missed_functions),
+            });
+        }
+
+        Self::report_json(files)
+    }
+
+    /// Parses Go coverage. Prefers a cover profile (`go test -coverprofile=...`, or
+    /// `go tool covdata textfmt` for binaries built with `go build -cover`), counting
+    /// statements as lines:
+    ///
+    /// ```text
+    /// mode: set
+    /// linkedlist/linked_list.go:12.34,14.2 2 1
+    /// ```
+    ///
+    /// Without one, falls back to the per-package `coverage: 85.7% of statements` lines that
+    /// `go test -cover` and `go tool covdata percent` print; those carry no line counts.
+    fn parse_go_report(content: &str, whitelist: &[String]) -> Result<String, String> {
+        let re_block = Regex::new(r"^(.+\.go):(\d+\.\d+,\d+\.\d+) (\d+) (\d+)$").unwrap();
+        let re_summary =
+            Regex::new(r"^(?:ok\s+)?(\S+)?.*coverage: ([0-9.]+)% of statements").unwrap();
+
+        // The same block shows up once per profile that was merged in; it is covered if any
+        // run hit it.
+        let mut blocks: BTreeMap<(String, String), (u64, bool)> = BTreeMap::new();
+        let mut packages = Vec::new();
+
+        for line in content.lines().map(str::trim) {
+            if let Some(cap) = re_block.captures(line) {
+                let statements: u64 = cap[3].parse().unwrap_or(0);
+                let hit = cap[4].parse::<u64>().unwrap_or(0) > 0;
+                let entry = blocks
+                    .entry((cap[1].to_string(), cap[2].to_string()))
+                    .or_insert((statements, false));
+                entry.1 |= hit;
+            } else if let Some(cap) = re_summary.captures(line) {
+                let package = cap.get(1).map_or("", |m| m.as_str());
+                if is_whitelisted(package, whitelist) {
+                    packages.push(CoverageFile {
+                        path: package.to_string(),
+                        total_lines: 0,
+                        covered_lines: 0,
+                        coverage_percent: cap[2].parse().unwrap_or(0.0),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        if blocks.is_empty() {
+            return Self::report_json(packages);
+        }
+
+        let mut per_file: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+        for ((file, _), (statements, hit)) in blocks {
+            let entry = per_file.entry(file).or_default();
+            entry.0 += statements;
+            if hit {
+                entry.1 += statements;
+            }
+        }
+
+        let files = per_file
+            .into_iter()
+            .filter(|(file, _)| is_whitelisted(file, whitelist))
+            .map(|(path, (total, covered))| CoverageFile {
+                path,
+                total_lines: total,
+                covered_lines: covered,
+                coverage_percent: percent(covered, total),
+                ..Default::default()
+            })
+            .collect();
+
+        Self::report_json(files)
+    }
+
+    /// Parses a coverage.py report printed to stdout, either `coverage xml -o -` (Cobertura,
+    /// see [`Self::cobertura_files`]) or `coverage json -o -` (`files.<path>.summary` holds
+    /// `num_statements` and `covered_lines`). Statements count as lines.
+    fn parse_python_report(content: &str, whitelist: &[String]) -> Result<String, String> {
+        let files = if content.contains("<coverage") {
+            Self::cobertura_files(content)
+        } else {
+            Self::python_json_files(content)?
         };
 
+        Self::report_json(whitelisted(files, whitelist))
+    }
+
+    /// Parses an LCOV tracefile (`lcov.info`, `llvm-cov export -format=lcov`, `c8`, ...):
+    ///
+    /// ```text
+    /// SF:src/list.c
+    /// DA:3,1
+    /// DA:4,0
+    /// LF:2
+    /// LH:1
+    /// end_of_record
+    /// ```
+    ///
+    /// Lines come from the `DA:<line>,<hits>` entries; a record without any uses its `LF`/`LH`
+    /// totals. Branches and functions come from `BRF`/`BRH` and `FNF`/`FNH`. Records for the
+    /// same file (e.g. from several test runs) are merged; their branch and function totals
+    /// are added up.
+    fn parse_lcov_report(content: &str, whitelist: &[String]) -> Result<String, String> {
+        #[derive(Default)]
+        struct LcovFile {
+            lines: BTreeMap<u64, bool>,
+            found: u64,
+            hit: u64,
+            branches: (u64, u64),
+            functions: (u64, u64),
+        }
+
+        let mut per_file: BTreeMap<String, LcovFile> = BTreeMap::new();
+        let mut current: Option<String> = None;
+
+        for line in content.lines().map(str::trim) {
+            if let Some(path) = line.strip_prefix("SF:") {
+                current = Some(path.to_string());
+                per_file.entry(path.to_string()).or_default();
+                continue;
+            }
+            if line == "end_of_record" {
+                current = None;
+                continue;
+            }
+            let Some(file) = current.as_ref().and_then(|f| per_file.get_mut(f)) else {
+                continue;
+            };
+            if let Some(data) = line.strip_prefix("DA:") {
+                let mut parts = data.split(',');
+                let (Some(Ok(number)), Some(Ok(hits))) = (
+                    parts.next().map(str::parse::<u64>),
+                    parts.next().map(str::parse::<u64>),
+                ) else {
+                    continue;
+                };
+                *file.lines.entry(number).or_default() |= hits > 0;
+            } else if let Some(found) = line.strip_prefix("LF:") {
+                file.found += found.parse().unwrap_or(0);
+            } else if let Some(hit) = line.strip_prefix("LH:") {
+                file.hit += hit.parse().unwrap_or(0);
+            } else if let Some(found) = line.strip_prefix("BRF:") {
+                file.branches.0 += found.parse().unwrap_or(0);
+            } else if let Some(hit) = line.strip_prefix("BRH:") {
+                file.branches.1 += hit.parse().unwrap_or(0);
+            } else if let Some(found) = line.strip_prefix("FNF:") {
+                file.functions.0 += found.parse().unwrap_or(0);
+            } else if let Some(hit) = line.strip_prefix("FNH:") {
+                file.functions.1 += hit.parse().unwrap_or(0);
+            }
+        }
+
+        let files = per_file
+            .into_iter()
+            .map(|(path, file)| {
+                let (total, covered) = if file.lines.is_empty() {
+                    (file.found, file.hit.min(file.found))
+                } else {
+                    let covered = file.lines.values().filter(|hit| **hit).count() as u64;
+                    (file.lines.len() as u64, covered)
+                };
+                CoverageFile {
+                    path,
+                    total_lines: total,
+                    covered_lines: covered,
+                    coverage_percent: percent(covered, total),
+                    total_branches: file.branches.0,
+                    covered_branches: file.branches.1.min(file.branches.0),
+                    total_functions: file.functions.0,
+                    covered_functions: file.functions.1.min(file.functions.0),
+                }
+            })
+            .collect();
+
+        Self::report_json(whitelisted(files, whitelist))
+    }
+
+    /// Files of a Cobertura XML report (coverage.py, gcovr `--cobertura`, `cargo tarpaulin`,
+    /// ...): every `<class filename=...>` lists its statements as
+    /// `<line number=... hits=...>`. Lines are counted once per file, even if the file has
+    /// several classes or a method repeats the line.
+    fn cobertura_files(content: &str) -> Vec<CoverageFile> {
+        let re_tag = Regex::new(r#"<class\b[^>]*>|</class>|<line\b[^>]*>"#).unwrap();
+        let re_filename = Regex::new(r#"\bfilename="([^"]+)""#).unwrap();
+        let re_number = Regex::new(r#"\bnumber="(\d+)""#).unwrap();
+        let re_hits = Regex::new(r#"\bhits="(\d+)""#).unwrap();
+
+        let mut per_file: BTreeMap<String, BTreeMap<u64, bool>> = BTreeMap::new();
+        let mut current: Option<String> = None;
+
+        for tag in re_tag.find_iter(content).map(|m| m.as_str()) {
+            if tag.starts_with("<class") {
+                current = re_filename.captures(tag).map(|cap| cap[1].to_string());
+                if let Some(file) = &current {
+                    per_file.entry(file.clone()).or_default();
+                }
+            } else if tag == "</class>" {
+                current = None;
+            } else if let (Some(file), Some(number), Some(hits)) = (
+                &current,
+                re_number
+                    .captures(tag)
+                    .and_then(|cap| cap[1].parse::<u64>().ok()),
+                re_hits
+                    .captures(tag)
+                    .and_then(|cap| cap[1].parse::<u64>().ok()),
+            ) {
+                *per_file
+                    .entry(file.clone())
+                    .or_default()
+                    .entry(number)
+                    .or_default() |= hits > 0;
+            }
+        }
+
+        per_file
+            .into_iter()
+            .map(|(path, lines)| {
+                let total = lines.len() as u64;
+                let covered = lines.values().filter(|hit| **hit).count() as u64;
+                CoverageFile {
+                    path,
+                    total_lines: total,
+                    covered_lines: covered,
+                    coverage_percent: percent(covered, total),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    fn python_json_files(content: &str) -> Result<Vec<CoverageFile>, String> {
+        // Task output may have other lines around the report; take the first JSON object
+        // that has `files`.
+        let report = content
+            .match_indices('{')
+            .filter_map(|(i, _)| {
+                serde_json::Deserializer::from_str(&content[i..])
+                    .into_iter::<serde_json::Value>()
+                    .next()?
+                    .ok()
+            })
+            .find(|v| v.get("files").is_some_and(|f| f.is_object()))
+            .ok_or_else(|| "No coverage.py XML or JSON report found in output".to_string())?;
+
+        let files = report["files"].as_object().into_iter().flatten();
+        Ok(files
+            .map(|(path, file)| {
+                let summary = &file["summary"];
+                let total = summary["num_statements"].as_u64().unwrap_or(0);
+                let covered = summary["covered_lines"].as_u64().unwrap_or(0);
+                CoverageFile {
+                    path: path.clone(),
+                    total_lines: total,
+                    covered_lines: covered,
+                    coverage_percent: percent(covered, total),
+                    ..Default::default()
+                }
+            })
+            .collect())
+    }
+
+    /// Summarises `files` into the JSON stored as `coverage_report.json`. Reports without line
+    /// counts (e.g. Go package percentages) are summarised by their mean percentage.
+    fn report_json(files: Vec<CoverageFile>) -> Result<String, String> {
+        let total_lines: u64 = files.iter().map(|f| f.total_lines).sum();
+        let covered_lines: u64 = files.iter().map(|f| f.covered_lines).sum();
+        let coverage_percent = if total_lines > 0 {
+            percent(covered_lines, total_lines)
+        } else if !files.is_empty() {
+            files.iter().map(|f| f.coverage_percent).sum::<f64>() / files.len() as f64
+        } else {
+            0.0
+        };
+
+        let total_branches: u64 = files.iter().map(|f| f.total_branches).sum();
+        let covered_branches: u64 = files.iter().map(|f| f.covered_branches).sum();
+        let total_functions: u64 = files.iter().map(|f| f.total_functions).sum();
+        let covered_functions: u64 = files.iter().map(|f| f.covered_functions).sum();
+
         let report = CoverageReport {
             generated_at: Utc::now().to_rfc3339(),
-            summary,
+            summary: CoverageSummary {
+                total_files: files.len() as u64,
+                total_lines,
+                covered_lines,
+                coverage_percent,
+                total_branches,
+                covered_branches,
+                branch_percent: (total_branches > 0)
+                    .then(|| percent(covered_branches, total_branches)),
+                total_functions,
+                covered_functions,
+                function_percent: (total_functions > 0)
+                    .then(|| percent(covered_functions, total_functions)),
+            },
             files,
         };
 
         serde_json::to_string_pretty(&report)
-            .map_err(|e| format!("Failed to serialize Java coverage report: {}", e))
+            .map_err(|e| format!("Failed to serialize coverage report: {}", e))
+    }
+}
+
+fn percent(covered: u64, total: u64) -> f64 {
+    if total > 0 {
+        (covered as f64 / total as f64) * 100.0
+    } else {
+        0.0
     }
 }
 
+/// An LCOV tracefile: has `SF:` and `end_of_record` lines.
+fn is_lcov(content: &str) -> bool {
+    let mut lines = content.lines().map(str::trim);
+    lines.any(|l| l.starts_with("SF:")) && lines.any(|l| l == "end_of_record")
+}
+
+/// A Cobertura XML report: a `<coverage>` root with `<class>` elements.
+fn is_cobertura(content: &str) -> bool {
+    content.contains("<coverage") && content.contains("<class")
+}
+
+fn whitelisted(files: Vec<CoverageFile>, whitelist: &[String]) -> Vec<CoverageFile> {
+    files
+        .into_iter()
+        .filter(|f| is_whitelisted(&f.path, whitelist))
+        .collect()
+}
+
+/// Whether `path` passes the whitelist, given either as full paths or as file names.
+fn is_whitelisted(path: &str, whitelist: &[String]) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    whitelist.is_empty() || whitelist.iter().any(|w| w == path || w == name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,4 +617,299 @@ mod tests {
             }
         }
     }
+
+    fn summary(json: &str) -> CoverageSummary {
+        serde_json::from_str::<CoverageReport>(json)
+            .unwrap()
+            .summary
+    }
+
+    #[test]
+    fn parses_llvm_cov_report_tables() {
+        let content = "\
+Filename                      Regions    Missed Regions     Cover   Functions  Missed Functions  Executed       Lines      Missed Lines     Cover    Branches   Missed Branches     Cover
+------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------
+/code/linked_list.rs               40                 6    85.00%          12                 1    91.67%          96                 9    90.62%           0                 0         -
+/code/main.rs                      10                 0   100.00%           2                 0   100.00%           4                 0   100.00%           0                 0         -
+------------------------------------------------------------------------------------------------------------------------------------------------------------------------------------
+TOTAL                              50                 6    88.00%          14                 1    92.86%         100                 9    91.00%           0                 0         -";
+
+        let all =
+            summary(&CoverageProcessor::process_report(Language::Rust, content, &[]).unwrap());
+        assert_eq!(
+            (all.total_files, all.total_lines, all.covered_lines),
+            (2, 100, 91)
+        );
+
+        let whitelist = vec!["linked_list.rs".to_string()];
+        let json = CoverageProcessor::process_report(Language::Rust, content, &whitelist).unwrap();
+        let report: CoverageReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].path, "/code/linked_list.rs");
+        assert_eq!(report.files[0].covered_lines, 87);
+    }
+
+    #[test]
+    fn parses_gcov_branch_and_function_coverage() {
+        // `gcov -b -f -o . LinkedList.cpp main.cpp`
+        let content = "\
+Function '_ZN10LinkedList4pushEi'
+Lines executed:100.00% of 4
+No branches
+Calls executed:100.00% of 1
+
+Function '_ZN10LinkedList5eraseEi'
+Lines executed:0.00% of 6
+Branches executed:0.00% of 4
+Taken at least once:0.00% of 4
+No calls
+
+File 'LinkedList.cpp'
+Lines executed:40.00% of 10
+Branches executed:50.00% of 8
+Taken at least once:25.00% of 8
+Calls executed:50.00% of 2
+Creating 'LinkedList.cpp.gcov'
+
+Function 'main'
+Lines executed:100.00% of 5
+No branches
+Calls executed:100.00% of 3
+
+File 'main.cpp'
+Lines executed:100.00% of 5
+No branches
+Calls executed:100.00% of 3
+Creating 'main.cpp.gcov'";
+
+        let json = CoverageProcessor::process_report(Language::Cpp, content, &[]).unwrap();
+        let report: CoverageReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.files.len(), 2);
+        assert_eq!(
+            (
+                report.files[0].total_branches,
+                report.files[0].covered_branches
+            ),
+            (8, 2)
+        );
+        assert_eq!(
+            (
+                report.files[0].total_functions,
+                report.files[0].covered_functions
+            ),
+            (2, 1)
+        );
+
+        let s = report.summary;
+        assert_eq!((s.total_lines, s.covered_lines), (15, 9));
+        assert_eq!(s.branch_percent, Some(25.0));
+        assert!((s.function_percent.unwrap() - 66.666).abs() < 0.01);
+    }
+
+    #[test]
+    fn parses_jacoco_branch_and_method_coverage() {
+        let content = "\
+GROUP,PACKAGE,CLASS,INSTRUCTION_MISSED,INSTRUCTION_COVERED,BRANCH_MISSED,BRANCH_COVERED,LINE_MISSED,LINE_COVERED,COMPLEXITY_MISSED,COMPLEXITY_COVERED,METHOD_MISSED,METHOD_COVERED
+jacoco-report,,LinkedList,12,88,3,5,2,18,3,9,1,7
+jacoco-report,,Main,0,40,0,0,0,10,0,2,0,2";
+
+        let s = summary(&CoverageProcessor::process_report(Language::Java, content, &[]).unwrap());
+        assert_eq!((s.total_lines, s.covered_lines), (30, 28));
+        assert_eq!((s.total_branches, s.covered_branches), (8, 5));
+        assert_eq!((s.total_functions, s.covered_functions), (10, 9));
+        assert_eq!(s.branch_percent, Some(62.5));
+    }
+
+    #[test]
+    fn marked_percent_follows_the_configured_metric() {
+        let s = CoverageSummary {
+            coverage_percent: 80.0,
+            branch_percent: Some(50.0),
+            function_percent: Some(100.0),
+            ..Default::default()
+        };
+        let mut config = CodeCoverage::default();
+        assert_eq!(s.marked_percent(&config), 80.0);
+
+        config.metric = CoverageMetric::Branch;
+        assert_eq!(s.marked_percent(&config), 50.0);
+
+        config.metric = CoverageMetric::Weighted;
+        config.weights.line = 2.0;
+        config.weights.branch = 1.0;
+        config.weights.function = 1.0;
+        assert_eq!(s.marked_percent(&config), 77.5);
+
+        // A tool without branch or function data: only line coverage counts.
+        let lines_only = CoverageSummary {
+            coverage_percent: 80.0,
+            ..Default::default()
+        };
+        assert_eq!(lines_only.marked_percent(&config), 80.0);
+        config.metric = CoverageMetric::Function;
+        assert_eq!(lines_only.marked_percent(&config), 80.0);
+    }
+
+    #[test]
+    fn detects_lcov_tracefiles_for_any_language() {
+        // `llvm-cov export -format=lcov`, two test runs appended
+        let content = "\
+TN:
+SF:/code/src/list.rs
+FN:3,list::push
+FNDA:2,list::push
+DA:3,2
+DA:4,2
+DA:7,0
+LF:3
+LH:2
+end_of_record
+SF:/code/src/main.rs
+LF:4
+LH:4
+end_of_record
+TN:
+SF:/code/src/list.rs
+DA:3,1
+DA:7,1
+end_of_record";
+
+        for language in [Language::Rust, Language::C, Language::JavaScript] {
+            let all = summary(&CoverageProcessor::process_report(language, content, &[]).unwrap());
+            assert_eq!(
+                (all.total_files, all.total_lines, all.covered_lines),
+                (2, 7, 7)
+            );
+        }
+
+        let whitelist = vec!["list.rs".to_string()];
+        let json = CoverageProcessor::process_report(Language::C, content, &whitelist).unwrap();
+        let report: CoverageReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].path, "/code/src/list.rs");
+        assert_eq!(report.files[0].total_lines, 3);
+    }
+
+    #[test]
+    fn detects_cobertura_reports_for_any_language() {
+        // gcovr --cobertura, on a single line, with method lines repeating class lines
+        let content = r#"<?xml version='1.0' encoding='UTF-8'?><!DOCTYPE coverage SYSTEM 'http://cobertura.sourceforge.net/xml/coverage-04.dtd'><coverage line-rate="0.75" branch-rate="0" lines-covered="3" lines-valid="4" version="gcovr 7.2"><sources><source>/code</source></sources><packages><package name="" line-rate="0.75"><classes><class name="LinkedList_cpp" filename="LinkedList.cpp" line-rate="0.75"><methods><method name="push" signature="" line-rate="1"><lines><line number="4" hits="3"/></lines></method></methods><lines><line number="4" hits="3"/><line number="5" hits="3"/><line number="9" hits="1" branch="false"/><line number="12" hits="0"/></lines></class></classes></package></packages></coverage>"#;
+
+        let cpp = summary(&CoverageProcessor::process_report(Language::Cpp, content, &[]).unwrap());
+        assert_eq!(
+            (cpp.total_files, cpp.total_lines, cpp.covered_lines),
+            (1, 4, 3)
+        );
+
+        let other = summary(
+            &CoverageProcessor::process_report(Language::Haskell, content, &["Other.cpp".into()])
+                .unwrap(),
+        );
+        assert_eq!(other.total_files, 0);
+    }
+
+    #[test]
+    fn parses_coverage_py_xml_reports() {
+        // `coverage xml -o -` (coverage.py 7.4)
+        let content = r#"make: Entering directory '/code'
+<?xml version="1.0" ?>
+<coverage version="7.4.4" timestamp="1717000000000" lines-valid="9" lines-covered="7" line-rate="0.7778" branches-covered="0" branches-valid="0" branch-rate="0" complexity="0">
+	<!-- Generated by coverage.py: https://coverage.readthedocs.io/en/7.4.4 -->
+	<sources>
+		<source>/code</source>
+	</sources>
+	<packages>
+		<package name="." line-rate="0.7778" branch-rate="0" complexity="0">
+			<classes>
+				<class name="LinkedList.py" filename="LinkedList.py" complexity="0" line-rate="0.6667" branch-rate="0">
+					<methods/>
+					<lines>
+						<line number="1" hits="1"/>
+						<line number="2" hits="1"/>
+						<line number="3" hits="1"/>
+						<line number="5" hits="1"/>
+						<line number="6" hits="0"/>
+						<line number="8" hits="0"/>
+					</lines>
+				</class>
+				<class name="main.py" filename="main.py" complexity="0" line-rate="1" branch-rate="0">
+					<methods/>
+					<lines>
+						<line number="1" hits="1"/>
+						<line number="3" hits="1"/>
+						<line number="4" hits="1"/>
+					</lines>
+				</class>
+			</classes>
+		</package>
+	</packages>
+</coverage>"#;
+
+        let all =
+            summary(&CoverageProcessor::process_report(Language::Python, content, &[]).unwrap());
+        assert_eq!(
+            (all.total_files, all.total_lines, all.covered_lines),
+            (2, 9, 7)
+        );
+
+        let whitelist = vec!["LinkedList.py".to_string()];
+        let json =
+            CoverageProcessor::process_report(Language::Python, content, &whitelist).unwrap();
+        let report: CoverageReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.files.len(), 1);
+        assert_eq!(report.files[0].covered_lines, 4);
+        assert!((report.files[0].coverage_percent - 66.666).abs() < 0.01);
+    }
+
+    #[test]
+    fn parses_coverage_py_json_reports() {
+        // `coverage json -o -` (coverage.py 7.4), after the task's own output
+        let content = r#"### task1
+[1 2 3] size=3
+{"meta": {"format": 2, "version": "7.4.4", "timestamp": "2024-05-29T10:00:00.000000", "branch_coverage": false, "show_contexts": false}, "files": {"LinkedList.py": {"executed_lines": [1, 2, 3, 5], "summary": {"covered_lines": 4, "num_statements": 6, "percent_covered": 66.66666666666667, "percent_covered_display": "67", "missing_lines": 2, "excluded_lines": 0}, "missing_lines": [6, 8], "excluded_lines": []}, "main.py": {"executed_lines": [1, 3, 4], "summary": {"covered_lines": 3, "num_statements": 3, "percent_covered": 100.0, "percent_covered_display": "100", "missing_lines": 0, "excluded_lines": 0}, "missing_lines": [], "excluded_lines": []}}, "totals": {"covered_lines": 7, "num_statements": 9, "percent_covered": 77.77777777777777, "percent_covered_display": "78", "missing_lines": 2, "excluded_lines": 0}}"#;
+
+        let all =
+            summary(&CoverageProcessor::process_report(Language::Python, content, &[]).unwrap());
+        assert_eq!(
+            (all.total_files, all.total_lines, all.covered_lines),
+            (2, 9, 7)
+        );
+
+        let whitelist = vec!["main.py".to_string()];
+        let only_main = summary(
+            &CoverageProcessor::process_report(Language::Python, content, &whitelist).unwrap(),
+        );
+        assert_eq!((only_main.total_files, only_main.covered_lines), (1, 3));
+
+        assert!(CoverageProcessor::process_report(Language::Python, "no report", &[]).is_err());
+    }
+
+    #[test]
+    fn parses_go_cover_profiles_and_summaries() {
+        // Two runs merged: the second block is only hit by the second run.
+        let profile = "\
+mode: set
+linkedlist/linked_list.go:10.30,12.2 2 1
+linkedlist/linked_list.go:14.30,17.2 3 0
+linkedlist/main.go:5.13,7.2 1 1
+linkedlist/linked_list.go:10.30,12.2 2 1
+linkedlist/linked_list.go:14.30,17.2 3 1";
+        let all = summary(&CoverageProcessor::process_report(Language::Go, profile, &[]).unwrap());
+        assert_eq!(
+            (all.total_files, all.total_lines, all.covered_lines),
+            (2, 6, 6)
+        );
+
+        let whitelist = vec!["main.go".to_string()];
+        let only_main =
+            summary(&CoverageProcessor::process_report(Language::Go, profile, &whitelist).unwrap());
+        assert_eq!((only_main.total_files, only_main.total_lines), (1, 1));
+
+        let output = "ok  \tlinkedlist\t0.002s\tcoverage: 80.0% of statements\n\
+                      ok  \tlinkedlist/util\t0.001s\tcoverage: 60.0% of statements";
+        let packages =
+            summary(&CoverageProcessor::process_report(Language::Go, output, &[]).unwrap());
+        assert_eq!(packages.total_files, 2);
+        assert!((packages.coverage_percent - 70.0).abs() < 1e-9);
+    }
 }
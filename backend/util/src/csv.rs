@@ -0,0 +1,91 @@
+//! Reading and writing the CSV files users upload and download (attendance registers, class
+//! lists, exports).
+//!
+//! [`parse`] splits text into records, [`column`] finds a column by its header and [`escape`]
+//! quotes a field on the way out.
+
+/// Splits CSV text into records of fields, each with the 1-based line it starts on. Quoted
+/// fields may contain commas, doubled quotes and line breaks; blank lines are dropped.
+pub fn parse(text: &str) -> Vec<(usize, Vec<String>)> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' => {
+                line += 1;
+                if in_quotes {
+                    field.push('\n');
+                    continue;
+                }
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|f| !f.trim().is_empty()) {
+                    records.push((start, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                start = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    if fields.iter().any(|f| !f.trim().is_empty()) {
+        records.push((start, fields));
+    }
+    records
+}
+
+/// Index of the first header matching any of `names`, ignoring case and surrounding spaces.
+pub fn column(header: &[String], names: &[&str]) -> Option<usize> {
+    header
+        .iter()
+        .position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+}
+
+/// Quotes a field when it needs it.
+pub fn escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoted_fields_and_tracks_lines() {
+        let text =
+            "\u{feff}Username,Note\r\nu1,\"late, \"\"sick\"\"\"\r\n\r\nu2,\"two\nlines\"\nu3,\n";
+        let records = parse(text);
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0], (1, vec!["Username".into(), "Note".into()]));
+        assert_eq!(records[1], (2, vec!["u1".into(), "late, \"sick\"".into()]));
+        assert_eq!(records[2], (4, vec!["u2".into(), "two\nlines".into()]));
+        assert_eq!(records[3], (6, vec!["u3".into(), "".into()]));
+        assert_eq!(column(&records[0].1, &["email", "username"]), Some(0));
+        assert_eq!(column(&records[0].1, &["email"]), None);
+    }
+
+    #[test]
+    fn escaped_fields_round_trip() {
+        let fields = ["plain", "a,b", "say \"hi\"", "two\nlines"];
+        let line = fields.map(escape).join(",");
+        assert_eq!(parse(&line), vec![(1, fields.map(String::from).to_vec())]);
+    }
+}
@@ -0,0 +1,186 @@
+//! Module-level default configs.
+//!
+//! A module may have a `config.json` of its own (see [`module_config_path`]) holding only the
+//! fields its lecturers want shared, e.g. the security and limit sections. An assignment's
+//! config is then resolved in three layers: the built-in defaults, the module config, and the
+//! assignment's own `config.json`. Objects are merged field by field; any other value (arrays
+//! included) replaces the one below it.
+//!
+//! While a module config exists, assignment configs are saved as overrides: only the fields
+//! that differ from what the module config resolves to, so later changes to the module config
+//! reach every assignment that did not change that field itself. Assignment configs saved as
+//! full files keep working; every field in them simply counts as an override.
+
+use serde_json::{Map, Value};
+use std::fs;
+
+use super::{CONFIG_VERSION, ExecutionConfig, migrate};
+use crate::paths::module_config_path;
+
+impl ExecutionConfig {
+    /// The module's stored config (upgraded to [`CONFIG_VERSION`]), or `None` if the module
+    /// has none.
+    pub fn module_config_json(module_id: i64) -> Result<Option<Value>, String> {
+        let path = module_config_path(module_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)
+            .map_err(|_| format!("Failed to read module config at {path:?}"))?;
+        let mut value: Value = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid module config JSON format: {e}"))?;
+        migrate(&mut value)?;
+        Ok(Some(value))
+    }
+
+    /// Stores `value` (already validated) as the module's config.
+    pub fn save_module_config(module_id: i64, value: &Value) -> Result<(), String> {
+        let path = module_config_path(module_id);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create module directory: {e:?}"))?;
+        }
+        let json = serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize config to JSON: {e}"))?;
+        fs::write(&path, json).map_err(|e| format!("Failed to write module config: {e:?}"))
+    }
+
+    /// Removes the module's config; returns whether there was one.
+    pub fn delete_module_config(module_id: i64) -> Result<bool, String> {
+        let path = module_config_path(module_id);
+        if !path.exists() {
+            return Ok(false);
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to delete module config: {e:?}"))?;
+        Ok(true)
+    }
+
+    /// What an assignment config without overrides resolves to: the built-in defaults with
+    /// the module config applied.
+    pub fn module_defaults(module_id: i64) -> Result<Self, String> {
+        match Self::module_config_json(module_id)? {
+            Some(value) => serde_json::from_value(value)
+                .map_err(|e| format!("Invalid module config format: {e}")),
+            None => Ok(Self::default_config()),
+        }
+    }
+
+    /// Layers the assignment config JSON `overrides` (upgraded first) over the module config.
+    /// Fields set in neither are left out and come from the defaults when parsed.
+    pub fn inherit(module_id: i64, overrides: &Value) -> Result<Value, String> {
+        let mut overrides = overrides.clone();
+        migrate(&mut overrides)?;
+        let Some(mut base) = Self::module_config_json(module_id)? else {
+            return Ok(overrides);
+        };
+        merge(&mut base, overrides);
+        Ok(base)
+    }
+
+    /// The JSON to store for this assignment config: the fields that differ from
+    /// [`Self::module_defaults`], or the whole config if the module has no config.
+    pub fn overrides(&self, module_id: i64) -> Result<Value, String> {
+        let full = serde_json::to_value(self)
+            .map_err(|e| format!("Failed to serialize config to JSON: {e}"))?;
+        if Self::module_config_json(module_id)?.is_none() {
+            return Ok(full);
+        }
+        let base = serde_json::to_value(Self::module_defaults(module_id)?)
+            .map_err(|e| format!("Failed to serialize config to JSON: {e}"))?;
+
+        let mut overrides = match diff(&full, &base) {
+            Some(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        overrides.insert("config_version".into(), CONFIG_VERSION.into());
+        Ok(Value::Object(overrides))
+    }
+}
+
+/// Applies `top` over `base`: objects are merged recursively, anything else replaces.
+fn merge(base: &mut Value, top: Value) {
+    match (base, top) {
+        (Value::Object(base), Value::Object(top)) => {
+            for (key, value) in top {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, top) => *base = top,
+    }
+}
+
+/// The part of `value` that differs from `base`, such that `merge(base, diff)` gives `value`
+/// back; `None` if they are equal.
+fn diff(value: &Value, base: &Value) -> Option<Value> {
+    if value == base {
+        return None;
+    }
+    match (value, base) {
+        (Value::Object(value), Value::Object(base)) => {
+            let fields: Map<String, Value> = value
+                .iter()
+                .filter_map(|(key, v)| match base.get(key) {
+                    Some(b) => diff(v, b).map(|d| (key.clone(), d)),
+                    None => Some((key.clone(), v.clone())),
+                })
+                .collect();
+            (!fields.is_empty()).then_some(Value::Object(fields))
+        }
+        _ => Some(value.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merge_layers_objects_and_replaces_everything_else() {
+        let mut base = json!({
+            "execution": { "timeout_secs": 30, "max_memory": 1024 },
+            "security": { "allowed_cidrs": ["10.0.0.0/8"] }
+        });
+        merge(
+            &mut base,
+            json!({
+                "execution": { "timeout_secs": 60 },
+                "security": { "allowed_cidrs": [] },
+                "marking": { "pass_mark": 40 }
+            }),
+        );
+        assert_eq!(
+            base,
+            json!({
+                "execution": { "timeout_secs": 60, "max_memory": 1024 },
+                "security": { "allowed_cidrs": [] },
+                "marking": { "pass_mark": 40 }
+            })
+        );
+    }
+
+    #[test]
+    fn diff_keeps_only_changed_fields_and_round_trips() {
+        let base = json!({
+            "execution": { "timeout_secs": 30, "max_memory": 1024 },
+            "marking": { "pass_mark": 50, "delimiter": "&-=-&" }
+        });
+        let value = json!({
+            "execution": { "timeout_secs": 60, "max_memory": 1024 },
+            "marking": { "pass_mark": 50, "delimiter": "&-=-&" }
+        });
+
+        let overrides = diff(&value, &base).unwrap();
+        assert_eq!(overrides, json!({ "execution": { "timeout_secs": 60 } }));
+        assert_eq!(diff(&base, &base), None);
+
+        let mut merged = base.clone();
+        merge(&mut merged, overrides);
+        assert_eq!(merged, value);
+    }
+}
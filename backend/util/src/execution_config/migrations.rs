@@ -0,0 +1,102 @@
+//! Upgrades of older `config.json` formats.
+//!
+//! Every config carries a `config_version`; files written before versioning have none and count
+//! as version 1. When the format changes, bump [`CONFIG_VERSION`] and add a step to
+//! [`MIGRATIONS`] that rewrites the JSON of the previous version. Sections and fields that are
+//! only added need no step: they are filled from their defaults when the config is parsed, and
+//! written out when the upgraded config is saved.
+
+use serde_json::{Map, Value};
+
+/// Format version written by this build.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Version of a config without `config_version`.
+const UNVERSIONED: u32 = 1;
+
+/// Rewrites the top-level object of a config from one version to the next.
+type Step = fn(&mut Map<String, Value>);
+
+/// `(from, step)`: `step` turns a version-`from` config into a version-`from + 1` one.
+const MIGRATIONS: &[(u32, Step)] = &[(1, rename_deliminator)];
+
+/// Upgrades the config JSON `value` in place to [`CONFIG_VERSION`] and returns the version it
+/// had. Anything but an object is left alone (parsing it will fail anyway).
+///
+/// # Errors
+/// Returns an error if the config is from a newer build or its `config_version` is not a
+/// positive integer.
+pub fn migrate(value: &mut Value) -> Result<u32, String> {
+    let Some(fields) = value.as_object_mut() else {
+        return Ok(CONFIG_VERSION);
+    };
+
+    let from = match fields.get("config_version") {
+        None | Some(Value::Null) => UNVERSIONED,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| format!("Invalid config_version: {v}"))?,
+    };
+    if from > CONFIG_VERSION {
+        return Err(format!(
+            "Config version {from} is newer than this server supports ({CONFIG_VERSION})"
+        ));
+    }
+
+    for (version, step) in MIGRATIONS {
+        if *version >= from {
+            step(fields);
+        }
+    }
+    fields.insert("config_version".into(), CONFIG_VERSION.into());
+    Ok(from)
+}
+
+/// 1 → 2: `marking.deliminator` is spelled `marking.delimiter`.
+fn rename_deliminator(config: &mut Map<String, Value>) {
+    let Some(Value::Object(marking)) = config.get_mut("marking") else {
+        return;
+    };
+    if let Some(delimiter) = marking.remove("deliminator") {
+        marking.entry("delimiter").or_insert(delimiter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn upgrades_unversioned_configs() {
+        let mut value = json!({
+            "marking": { "deliminator": "&&&", "pass_mark": 40 },
+            "execution": { "timeout_secs": 5 }
+        });
+        assert_eq!(migrate(&mut value), Ok(1));
+        assert_eq!(
+            value,
+            json!({
+                "config_version": CONFIG_VERSION,
+                "marking": { "delimiter": "&&&", "pass_mark": 40 },
+                "execution": { "timeout_secs": 5 }
+            })
+        );
+
+        // Already current: nothing to do.
+        let before = value.clone();
+        assert_eq!(migrate(&mut value), Ok(CONFIG_VERSION));
+        assert_eq!(value, before);
+    }
+
+    #[test]
+    fn rejects_configs_from_newer_builds() {
+        let mut value = json!({ "config_version": CONFIG_VERSION + 1 });
+        assert!(migrate(&mut value).unwrap_err().contains("newer"));
+
+        let mut value = json!({ "config_version": "two" });
+        assert!(migrate(&mut value).is_err());
+    }
+}
@@ -1,9 +1,17 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, de::Error as _};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
 
-use crate::{languages::Language, paths::config_dir, system_health};
+use crate::{config, languages::Language, paths::config_dir, system_health};
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+mod inheritance;
+mod migrations;
+mod validation;
+
+pub use migrations::{CONFIG_VERSION, migrate};
+pub use validation::ConfigError;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MarkingScheme {
     Exact,
@@ -29,10 +37,12 @@ pub enum SubmissionMode {
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Copy, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum GradingPolicy {
-    Best, // highest score across submissions
-    Last, // the most recent submission
+    Best,        // highest score across submissions
+    Last,        // the most recent submission
+    BestOfLastN, // highest score among the `best_of_last_n` most recent submissions
+    Weighted,    // average of all submissions, later ones weighing more
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -51,6 +61,10 @@ pub struct ExecutionLimits {
 
     #[serde(default = "default_max_processes")]
     pub max_processes: u32,
+
+    /// Bytes `/code` and `/output` may use together while a command runs; 0 disables the cap.
+    #[serde(default = "default_max_disk_bytes")]
+    pub max_disk_bytes: u64,
 }
 
 impl Default for ExecutionLimits {
@@ -61,6 +75,7 @@ impl Default for ExecutionLimits {
             max_cpus: default_max_cpus(),
             max_uncompressed_size: default_max_uncompressed_size(),
             max_processes: default_max_processes(),
+            max_disk_bytes: default_max_disk_bytes(),
         }
     }
 }
@@ -90,12 +105,22 @@ pub struct MarkingOptions {
     #[serde(default = "default_feedback_scheme")]
     pub feedback_scheme: FeedbackScheme,
 
-    #[serde(default = "default_deliminator")]
-    pub deliminator: String,
+    /// Marks the lines of program output that start a new subsection (e.g. `###`).
+    #[serde(default = "default_delimiter")]
+    pub delimiter: String,
 
     #[serde(default = "default_grading_policy")]
     pub grading_policy: GradingPolicy,
 
+    /// How many of the most recent attempts `best_of_last_n` picks the best from. Default: 3
+    #[serde(default = "default_best_of_last_n")]
+    pub best_of_last_n: u32,
+
+    /// Under `weighted`, the highest percentage any attempt after the first can contribute,
+    /// so resubmitting after seeing feedback is worth less. Default: 100 (no cap)
+    #[serde(default = "default_later_attempt_cap")]
+    pub later_attempt_cap: f64,
+
     /// Maximum number of attempts (only enforced if `limit_attempts = true`).
     #[serde(default = "default_max_attempts")]
     pub max_attempts: u32,
@@ -113,6 +138,12 @@ pub struct MarkingOptions {
     /// Default: false
     #[serde(default = "default_allow_practice_submissions")]
     pub allow_practice_submissions: bool,
+
+    /// If true, students see marks but not the per-task feedback on their practice submissions,
+    /// so practice can't be used to read off the memo output.
+    #[serde(default)]
+    pub reduced_practice_feedback: bool,
+
     #[serde(default)]
     pub dissalowed_code: Vec<String>,
 
@@ -122,6 +153,11 @@ pub struct MarkingOptions {
     /// If true, reorder test cases by memoization (to group similar test cases together).
     #[serde(default)]
     pub reorder_by_memo: bool,
+
+    /// If true, a submission by a member of one of the assignment's groups counts for the whole
+    /// group: every member sees it, and its grade, as their own.
+    #[serde(default)]
+    pub group_submissions: bool,
 }
 
 fn default_late_policy() -> LatePolicy {
@@ -137,15 +173,62 @@ impl Default for MarkingOptions {
         Self {
             marking_scheme: default_marking_scheme(),
             feedback_scheme: default_feedback_scheme(),
-            deliminator: default_deliminator(),
+            delimiter: default_delimiter(),
             grading_policy: default_grading_policy(),
+            best_of_last_n: default_best_of_last_n(),
+            later_attempt_cap: default_later_attempt_cap(),
             max_attempts: default_max_attempts(),
             limit_attempts: default_limit_attempts(),
             pass_mark: default_pass_mark(),
             allow_practice_submissions: default_allow_practice_submissions(),
+            reduced_practice_feedback: false,
             dissalowed_code: vec![],
             late: default_late_policy(),
             reorder_by_memo: false,
+            group_submissions: false,
+        }
+    }
+}
+
+impl MarkingOptions {
+    /// Applies the grading policy to a student's attempt percentages, newest first. Returns the
+    /// index of the attempt the grade is credited to and the percentage that counts, or `None`
+    /// if there are no attempts.
+    ///
+    /// Ties go to the newer attempt. `weighted` credits the newest attempt with the average of
+    /// all of them, the k-th oldest weighing k and each after the first capped at
+    /// `later_attempt_cap`.
+    pub fn policy_mark(&self, marks: &[f64]) -> Option<(usize, f64)> {
+        let best_of = |n: usize| {
+            marks.iter().take(n).copied().enumerate().fold(
+                None,
+                |best: Option<(usize, f64)>, (i, m)| match best {
+                    Some((_, b)) if b >= m => best,
+                    _ => Some((i, m)),
+                },
+            )
+        };
+        match self.grading_policy {
+            GradingPolicy::Last => marks.first().map(|&m| (0, m)),
+            GradingPolicy::Best => best_of(marks.len()),
+            GradingPolicy::BestOfLastN => best_of(self.best_of_last_n.max(1) as usize),
+            GradingPolicy::Weighted => {
+                if marks.is_empty() {
+                    return None;
+                }
+                let (mut sum, mut weights) = (0.0, 0.0);
+                for (k, &m) in marks.iter().rev().enumerate() {
+                    let weight = (k + 1) as f64;
+                    let m = if k == 0 {
+                        m
+                    } else {
+                        m.min(self.later_attempt_cap)
+                    };
+                    sum += weight * m;
+                    weights += weight;
+                }
+                Some((0, sum / weights))
+            }
         }
     }
 }
@@ -156,6 +239,15 @@ pub struct ProjectSetup {
     pub language: Language,
     #[serde(default = "default_submission_mode")]
     pub submission_mode: SubmissionMode,
+    /// Command that compiles the project once before any task runs (e.g. `make build`).
+    /// When set, the compiled output is reattached to every non-coverage task instead of
+    /// rebuilding from source. None = each task builds on its own.
+    #[serde(default)]
+    pub build_command: Option<String>,
+    /// Docker image the tasks run in (e.g. `gcc:12` or `registry.example.com/cos212/runner`).
+    /// None = the deployment's image for `language` (`RUNNER_IMAGES`), else `RUNNER_IMAGE`.
+    #[serde(default)]
+    pub image: Option<String>,
 }
 
 impl Default for ProjectSetup {
@@ -163,6 +255,8 @@ impl Default for ProjectSetup {
         Self {
             language: default_language(),
             submission_mode: default_submission_mode(),
+            build_command: None,
+            image: None,
         }
     }
 }
@@ -174,6 +268,14 @@ pub struct CodeCoverage {
 
     #[serde(default)]
     pub whitelist: Vec<String>,
+
+    /// Which coverage the coverage mark is based on.
+    #[serde(default)]
+    pub metric: CoverageMetric,
+
+    /// Relative weights of the metrics when `metric` is `weighted`.
+    #[serde(default)]
+    pub weights: CoverageWeights,
 }
 
 impl Default for CodeCoverage {
@@ -181,10 +283,97 @@ impl Default for CodeCoverage {
         Self {
             code_coverage_weight: default_code_coverage_weight(),
             whitelist: default_code_coverage_whitelist(),
+            metric: CoverageMetric::default(),
+            weights: CoverageWeights::default(),
+        }
+    }
+}
+
+/// Coverage metric behind the coverage mark. Branch and function coverage fall back to line
+/// coverage for tools that do not report them.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageMetric {
+    #[default]
+    Line,
+    Branch,
+    Function,
+    /// Weighted blend of the three, per `CodeCoverage::weights`.
+    Weighted,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CoverageWeights {
+    #[serde(default = "default_line_weight")]
+    pub line: f64,
+    #[serde(default = "default_branch_weight")]
+    pub branch: f64,
+    #[serde(default = "default_function_weight")]
+    pub function: f64,
+}
+
+impl Default for CoverageWeights {
+    fn default() -> Self {
+        Self {
+            line: default_line_weight(),
+            branch: default_branch_weight(),
+            function: default_function_weight(),
+        }
+    }
+}
+
+/// How valgrind tasks are marked.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ValgrindOptions {
+    /// Share of a memory check's marks each kind of problem is worth; a task earns a
+    /// category's share only if valgrind reported none of it.
+    #[serde(default)]
+    pub weights: ValgrindWeights,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ValgrindWeights {
+    #[serde(default = "default_definitely_lost_weight")]
+    pub definitely_lost: f64,
+    #[serde(default = "default_indirectly_lost_weight")]
+    pub indirectly_lost: f64,
+    #[serde(default = "default_possibly_lost_weight")]
+    pub possibly_lost: f64,
+    #[serde(default = "default_invalid_reads_weight")]
+    pub invalid_reads: f64,
+    #[serde(default = "default_invalid_writes_weight")]
+    pub invalid_writes: f64,
+    #[serde(default = "default_uninitialised_weight")]
+    pub uninitialised: f64,
+}
+
+impl Default for ValgrindWeights {
+    fn default() -> Self {
+        Self {
+            definitely_lost: default_definitely_lost_weight(),
+            indirectly_lost: default_indirectly_lost_weight(),
+            possibly_lost: default_possibly_lost_weight(),
+            invalid_reads: default_invalid_reads_weight(),
+            invalid_writes: default_invalid_writes_weight(),
+            uninitialised: default_uninitialised_weight(),
         }
     }
 }
 
+impl ValgrindWeights {
+    /// The weights by field name, in declaration order.
+    pub fn named(&self) -> [(&'static str, f64); 6] {
+        [
+            ("definitely_lost", self.definitely_lost),
+            ("indirectly_lost", self.indirectly_lost),
+            ("possibly_lost", self.possibly_lost),
+            ("invalid_reads", self.invalid_reads),
+            ("invalid_writes", self.invalid_writes),
+            ("uninitialised", self.uninitialised),
+        ]
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CrossoverType {
@@ -193,6 +382,46 @@ pub enum CrossoverType {
     Uniform,
 }
 
+/// How parents are picked for the next generation.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionType {
+    /// Chance proportional to fitness.
+    #[default]
+    Roulette,
+    /// Best of `tournament_size` random picks.
+    Tournament,
+    /// Chance proportional to the position in the fitness ranking.
+    Rank,
+}
+
+/// What the GATLAM GA optimizes.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GaObjective {
+    /// LTL property violations and task failures only (the omega-weighted components).
+    #[default]
+    Properties,
+    /// `coverage_weight` of the fitness from code coverage, the rest from the property score.
+    WeightedSum,
+    /// Pareto ranking on (coverage, property score), archiving the non-dominated payloads.
+    Pareto,
+}
+
+/// How GATLAM searches the gene space. The non-GA strategies are baselines to compare
+/// GATLAM against, using the same interpreter and fitness plumbing.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategyKind {
+    /// The genetic algorithm.
+    #[default]
+    Genetic,
+    /// Simulated annealing from the best random start, `population_size` neighbours per step.
+    SimulatedAnnealing,
+    /// `population_size` fresh random candidates per step.
+    RandomSearch,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MutationType {
@@ -201,10 +430,130 @@ pub enum MutationType {
     Scramble,
 }
 
+/// What a GA gene turns into in the interpreter payload.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GeneKind {
+    /// An integer in `min_value..=max_value`.
+    #[default]
+    Integer,
+    /// One of `values`, e.g. a command name or a flag.
+    Categorical,
+    /// `min_length..=max_length` characters from `alphabet`.
+    String,
+}
+
+/// What the GA does with a gene that decodes outside its range or to an invalid value.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GeneRepair {
+    /// Leave it; the value is passed to the interpreter as decoded.
+    #[default]
+    None,
+    /// Replace it with a random valid value.
+    Resample,
+    /// Replace it with the nearest valid value.
+    Clamp,
+    /// Leave it, but subtract `penalty` from the chromosome's fitness.
+    Penalty,
+}
+
+/// Longest `string` gene allowed; every character is its own slot in the chromosome.
+pub const MAX_STRING_GENE_LENGTH: usize = 64;
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GeneConfig {
+    #[serde(default)]
+    pub kind: GeneKind,
+    #[serde(default)]
     pub min_value: i32,
+    #[serde(default)]
     pub max_value: i32,
+    /// Choices of a `categorical` gene.
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// Length bounds of a `string` gene.
+    #[serde(default)]
+    pub min_length: usize,
+    #[serde(default)]
+    pub max_length: usize,
+    /// Characters of a `string` gene; empty means `a`-`z`.
+    #[serde(default)]
+    pub alphabet: String,
+    /// Values an `integer` gene must not take.
+    #[serde(default)]
+    pub invalid_values: Vec<i32>,
+    /// How chromosomes with this gene out of range (or invalid) are handled.
+    #[serde(default)]
+    pub repair: GeneRepair,
+    /// Fitness subtracted per invalid gene when `repair` is `penalty`.
+    #[serde(default = "default_gene_penalty")]
+    pub penalty: f64,
+}
+
+impl GeneConfig {
+    /// An integer gene in `min_value..=max_value`.
+    pub fn integer(min_value: i32, max_value: i32) -> Self {
+        Self {
+            kind: GeneKind::Integer,
+            min_value,
+            max_value,
+            values: Vec::new(),
+            min_length: 0,
+            max_length: 0,
+            alphabet: String::new(),
+            invalid_values: Vec::new(),
+            repair: GeneRepair::None,
+            penalty: default_gene_penalty(),
+        }
+    }
+
+    /// Characters a `string` gene is built from.
+    pub fn alphabet_chars(&self) -> Vec<char> {
+        if self.alphabet.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            self.alphabet.chars().collect()
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.kind {
+            GeneKind::Integer if self.min_value > self.max_value => Err(format!(
+                "min_value {} is greater than max_value {}",
+                self.min_value, self.max_value
+            )),
+            GeneKind::Categorical if self.values.is_empty() => {
+                Err("categorical gene has no values".to_string())
+            }
+            GeneKind::String if self.min_length > self.max_length => Err(format!(
+                "min_length {} is greater than max_length {}",
+                self.min_length, self.max_length
+            )),
+            GeneKind::String if !(1..=MAX_STRING_GENE_LENGTH).contains(&self.max_length) => {
+                Err(format!(
+                    "max_length must be between 1 and {}",
+                    MAX_STRING_GENE_LENGTH
+                ))
+            }
+            GeneKind::Integer if self.invalid_values_cover_range() => Err(format!(
+                "every value in {}..={} is invalid",
+                self.min_value, self.max_value
+            )),
+            _ if self.penalty < 0.0 => Err("penalty must not be negative".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    fn invalid_values_cover_range(&self) -> bool {
+        let invalid: HashSet<i32> = self
+            .invalid_values
+            .iter()
+            .copied()
+            .filter(|v| (self.min_value..=self.max_value).contains(v))
+            .collect();
+        invalid.len() as i64 > self.max_value as i64 - self.min_value as i64
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -215,6 +564,116 @@ pub struct TaskSpecConfig {
     pub max_runtime_ms: Option<u64>,
     #[serde(default)]
     pub forbidden_outputs: Vec<String>,
+    /// Lecturer-defined properties checked on top of the built-in ones.
+    #[serde(default)]
+    pub rules: Vec<PropertyRule>,
+}
+
+/// `gatlam.task_spec`: one spec shared by every task, or a map of task number to spec, e.g.
+/// `{"1": {"valid_return_codes": [0]}, "2": {"max_runtime_ms": 500}}`. Tasks missing from the
+/// map use the default spec.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum TaskSpecs {
+    Shared(TaskSpecConfig),
+    PerTask(BTreeMap<i64, TaskSpecConfig>),
+}
+
+impl Default for TaskSpecs {
+    fn default() -> Self {
+        TaskSpecs::Shared(TaskSpecConfig::default())
+    }
+}
+
+// A non-empty object whose keys are all integers is a per-task map; anything else is a shared
+// spec (whose fields all have defaults, so it would otherwise swallow a map).
+impl<'de> Deserialize<'de> for TaskSpecs {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let per_task = value.as_object().is_some_and(|map| {
+            !map.is_empty() && map.keys().all(|k| k.trim().parse::<i64>().is_ok())
+        });
+        if per_task {
+            serde_json::from_value(value).map(TaskSpecs::PerTask)
+        } else {
+            serde_json::from_value(value).map(TaskSpecs::Shared)
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+impl TaskSpecs {
+    /// The spec of the task with `task_number` (`None` if unknown).
+    pub fn for_task(&self, task_number: Option<i64>) -> TaskSpecConfig {
+        match self {
+            TaskSpecs::Shared(spec) => spec.clone(),
+            TaskSpecs::PerTask(specs) => task_number
+                .and_then(|n| specs.get(&n))
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Every configured spec, with its task number when given per task.
+    pub fn specs(&self) -> Vec<(Option<i64>, &TaskSpecConfig)> {
+        match self {
+            TaskSpecs::Shared(spec) => vec![(None, spec)],
+            TaskSpecs::PerTask(specs) => specs.iter().map(|(n, spec)| (Some(*n), spec)).collect(),
+        }
+    }
+}
+
+/// A lecturer-defined property the output of a task must satisfy; each violated rule counts
+/// as a violated LTL property in the GATLAM fitness.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct PropertyRule {
+    /// Task the rule applies to, by task number; `None` applies it to every task.
+    #[serde(default)]
+    pub task_number: Option<i64>,
+    #[serde(flatten)]
+    pub check: PropertyCheck,
+}
+
+/// What a `PropertyRule` checks. Output checks only apply once the task has terminated and
+/// compare trimmed, non-empty stdout lines.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PropertyCheck {
+    /// Some output line contains `value`.
+    Contains { value: String },
+    /// Lines containing each of `values` appear in this order.
+    Ordering { values: Vec<String> },
+    /// `values` never appear as consecutive output lines, in this order.
+    ForbiddenSequence { values: Vec<String> },
+    /// The task finishes within `ms` milliseconds.
+    MaxRuntime { ms: u64 },
+}
+
+impl PropertyRule {
+    /// Whether the rule applies to the task with `task_number` (`None` if unknown).
+    pub fn applies_to(&self, task_number: Option<i64>) -> bool {
+        self.task_number.is_none() || self.task_number == task_number
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match &self.check {
+            PropertyCheck::Contains { value } if value.trim().is_empty() => {
+                Err("value must not be empty".to_string())
+            }
+            PropertyCheck::Ordering { values } if values.len() < 2 => {
+                Err("an ordering needs at least 2 values".to_string())
+            }
+            PropertyCheck::ForbiddenSequence { values } if values.is_empty() => {
+                Err("a forbidden sequence needs at least 1 value".to_string())
+            }
+            PropertyCheck::Ordering { values } | PropertyCheck::ForbiddenSequence { values }
+                if values.iter().any(|v| v.trim().is_empty()) =>
+            {
+                Err("values must not be empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 fn default_valid_return_codes() -> Vec<i32> {
@@ -242,6 +701,25 @@ pub struct GATLAM {
     pub crossover_type: CrossoverType,
     #[serde(default = "default_mutation_type")]
     pub mutation_type: MutationType,
+    #[serde(default)]
+    pub selection_type: SelectionType,
+    /// Candidates per tournament when `selection_type` is `tournament`.
+    #[serde(default = "default_tournament_size")]
+    pub tournament_size: usize,
+    /// Fittest chromosomes copied unchanged into the next generation (per island).
+    #[serde(default)]
+    pub elitism_count: usize,
+
+    // ---- Island model ----
+    /// Sub-populations the population is split into; 1 evolves a single population.
+    #[serde(default = "default_islands")]
+    pub islands: usize,
+    /// Generations between migrations; 0 keeps the islands isolated.
+    #[serde(default = "default_migration_interval")]
+    pub migration_interval: usize,
+    /// Fittest chromosomes each island sends to the next one per migration.
+    #[serde(default = "default_migration_count")]
+    pub migration_count: usize,
 
     // ---- Components ----
     #[serde(default = "default_omega1")]
@@ -251,9 +729,36 @@ pub struct GATLAM {
     #[serde(default = "default_omega3")]
     pub omega3: f64,
 
+    // ---- Objectives ----
+    /// Optimize properties alone, or together with code coverage in the same run.
+    #[serde(default)]
+    pub objective: GaObjective,
+    /// Share of the fitness given to coverage when `objective` is `weighted_sum`.
+    #[serde(default = "default_coverage_weight")]
+    pub coverage_weight: f64,
+
+    // ---- Search strategy ----
+    /// Search run over the genes; `number_of_generations` is its iteration count.
+    #[serde(default)]
+    pub search_strategy: SearchStrategyKind,
+    /// Starting temperature of simulated annealing.
+    #[serde(default = "default_initial_temperature")]
+    pub initial_temperature: f64,
+    /// Factor the simulated annealing temperature is multiplied by after every step.
+    #[serde(default = "default_cooling_rate")]
+    pub cooling_rate: f64,
+
     // ---- TaskSpec ----
     #[serde(default)]
-    pub task_spec: TaskSpecConfig,
+    pub task_spec: TaskSpecs,
+
+    // ---- Interpreter cache ----
+    /// Generated sources kept across runs so repeated payloads skip the interpreter; 0 disables.
+    #[serde(default = "default_interpreter_cache_size")]
+    pub interpreter_cache_size: usize,
+    /// Seconds a cached source stays valid.
+    #[serde(default = "default_interpreter_cache_ttl_secs")]
+    pub interpreter_cache_ttl_secs: u64,
 
     // ---- Optional runtime flags ----
     #[serde(default = "default_max_parallel_chromosomes")]
@@ -274,10 +779,23 @@ impl Default for GATLAM {
             genes: default_genes(),
             crossover_type: default_crossover_type(),
             mutation_type: default_mutation_type(),
+            selection_type: SelectionType::default(),
+            tournament_size: default_tournament_size(),
+            elitism_count: 0,
+            islands: default_islands(),
+            migration_interval: default_migration_interval(),
+            migration_count: default_migration_count(),
             omega1: default_omega1(),
             omega2: default_omega2(),
             omega3: default_omega3(),
-            task_spec: TaskSpecConfig::default(),
+            objective: GaObjective::default(),
+            coverage_weight: default_coverage_weight(),
+            search_strategy: SearchStrategyKind::default(),
+            initial_temperature: default_initial_temperature(),
+            cooling_rate: default_cooling_rate(),
+            task_spec: TaskSpecs::default(),
+            interpreter_cache_size: default_interpreter_cache_size(),
+            interpreter_cache_ttl_secs: default_interpreter_cache_ttl_secs(),
             max_parallel_chromosomes: default_max_parallel_chromosomes(),
             verbose: false,
         }
@@ -290,12 +808,24 @@ impl Default for TaskSpecConfig {
             valid_return_codes: default_valid_return_codes(),
             max_runtime_ms: None,
             forbidden_outputs: vec![],
+            rules: vec![],
         }
     }
 }
 
 // ---------------- Security Options ----------------
 
+/// Seccomp filter applied to task containers.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SeccompProfile {
+    /// code_manager's own profile (or `SECCOMP_PROFILE`), stricter than Docker's.
+    #[default]
+    Strict,
+    /// Docker's built-in default profile, for tools the strict one blocks.
+    Docker,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SecurityOptions {
     /// If true, students must unlock the assignment once per device/session.
@@ -318,6 +848,15 @@ pub struct SecurityOptions {
     /// Empty => no IP restriction.
     #[serde(default = "default_allowed_cidrs")]
     pub allowed_cidrs: Vec<String>,
+
+    /// Seccomp profile task containers run under.
+    #[serde(default)]
+    pub seccomp_profile: SeccompProfile,
+
+    /// AppArmor profile for task containers, which must be loaded on the code_manager host.
+    /// None = `APPARMOR_PROFILE`, or Docker's default when that is unset too.
+    #[serde(default)]
+    pub apparmor_profile: Option<String>,
 }
 
 impl Default for SecurityOptions {
@@ -328,13 +867,126 @@ impl Default for SecurityOptions {
             cookie_ttl_minutes: default_cookie_ttl_minutes(),
             bind_cookie_to_user: default_bind_cookie_to_user(),
             allowed_cidrs: default_allowed_cidrs(),
+            seccomp_profile: SeccompProfile::default(),
+            apparmor_profile: None,
+        }
+    }
+}
+
+// ---------------- Exam Options ----------------
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExamOptions {
+    /// If true, the assignment is a timed exam: each student starts it when ready and may only
+    /// submit for `duration_minutes` afterwards (and never past their due date).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Minutes a student has from starting the exam. Default: 2h.
+    #[serde(default = "default_exam_duration_minutes")]
+    pub duration_minutes: u32,
+
+    /// If true, students see marks but not the per-task feedback (which can quote the memo
+    /// output) until the assignment's due date has passed.
+    #[serde(default = "default_exam_hide_feedback")]
+    pub hide_feedback: bool,
+}
+
+impl Default for ExamOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration_minutes: default_exam_duration_minutes(),
+            hide_feedback: default_exam_hide_feedback(),
+        }
+    }
+}
+
+impl ExamOptions {
+    /// Whether students' feedback is withheld while the exam is running.
+    pub fn hides_feedback(&self) -> bool {
+        self.enabled && self.hide_feedback
+    }
+}
+
+// ---------------- Plagiarism Options ----------------
+
+/// A stage of a plagiarism case's misconduct process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlagiarismStage {
+    Reported,
+    Interviewed,
+    Escalated,
+    Resolved,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlagiarismOptions {
+    /// Stages at which the students involved in a case are notified that it reached them.
+    /// Default: `reported` and `resolved`.
+    #[serde(default = "default_plagiarism_notify_students_at")]
+    pub notify_students_at: Vec<PlagiarismStage>,
+
+    /// Similarity (%) at or above which a MOSS/JPlag run opens a case for a user pair.
+    #[serde(default)]
+    pub case_min_similarity: Option<f64>,
+
+    /// Matched lines at or above which a MOSS/JPlag run opens a case for a user pair.
+    #[serde(default)]
+    pub case_min_lines: Option<i64>,
+}
+
+impl Default for PlagiarismOptions {
+    fn default() -> Self {
+        Self {
+            notify_students_at: default_plagiarism_notify_students_at(),
+            case_min_similarity: None,
+            case_min_lines: None,
+        }
+    }
+}
+
+impl PlagiarismOptions {
+    /// Whether a run opens a case for a pair: one reaching either threshold, or every pair when
+    /// neither is set. Pairs below are still listed in the stored report.
+    pub fn opens_case(&self, similarity: f64, lines_matched: i64) -> bool {
+        match (self.case_min_similarity, self.case_min_lines) {
+            (None, None) => true,
+            (min_similarity, min_lines) => {
+                min_similarity.is_some_and(|min| similarity >= min)
+                    || min_lines.is_some_and(|min| lines_matched >= min)
+            }
+        }
+    }
+}
+
+// ---------------- Output Options ----------------
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExecutionOutputOptions {
+    /// Maximum bytes of task output stored per task; anything beyond is cut off and
+    /// replaced with a truncation marker. 0 = unlimited.
+    #[serde(default = "default_max_output_bytes")]
+    pub max_output_bytes: u64,
+    /// Maximum total bytes of `/output` files kept per task when the task has artifact
+    /// patterns; files past the cap are skipped. 0 = unlimited.
+    #[serde(default = "default_max_artifact_bytes")]
+    pub max_artifact_bytes: u64,
+}
+
+impl Default for ExecutionOutputOptions {
+    fn default() -> Self {
+        Self {
+            max_output_bytes: default_max_output_bytes(),
+            max_artifact_bytes: default_max_artifact_bytes(),
         }
     }
 }
 
 impl ExecutionLimits {
     pub fn sanitize(mut self) -> Self {
-        let sys = system_health::sample_system_metrics();
+        let sys = system_health::host_capacity();
         let mem_total_bytes = sys.mem_total * 1024;
         let cpu_count = sys.cpu_cores as u32;
 
@@ -350,6 +1002,10 @@ impl ExecutionLimits {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ExecutionConfig {
+    /// Format version (see [`CONFIG_VERSION`]); older files are upgraded when loaded.
+    #[serde(default = "default_config_version")]
+    pub config_version: u32,
+
     #[serde(default)]
     pub execution: ExecutionLimits,
 
@@ -367,11 +1023,28 @@ pub struct ExecutionConfig {
 
     #[serde(default)]
     pub code_coverage: CodeCoverage,
+
+    #[serde(default)]
+    pub valgrind: ValgrindOptions,
+
+    #[serde(default)]
+    pub output: ExecutionOutputOptions,
+
+    #[serde(default)]
+    pub exam: ExamOptions,
+
+    #[serde(default)]
+    pub plagiarism: PlagiarismOptions,
+
+    /// Extra environment variables exported in the container for every task command
+    /// (e.g. `LC_ALL`, `JAVA_TOOL_OPTIONS`).
+    #[serde(default)]
+    pub environment: HashMap<String, String>,
 }
 
 impl ExecutionConfig {
     pub fn sanitize(mut self) -> Self {
-        let sys = system_health::sample_system_metrics();
+        let sys = system_health::host_capacity();
         let mem_total_bytes = sys.mem_total * 1024;
         let cpu_count = sys.cpu_cores as u32;
 
@@ -386,41 +1059,161 @@ impl ExecutionConfig {
 
     pub fn default_config() -> Self {
         ExecutionConfig {
+            config_version: CONFIG_VERSION,
             execution: ExecutionLimits::default(),
             marking: MarkingOptions::default(),
             project: ProjectSetup::default(),
             gatlam: GATLAM::default(),
             security: SecurityOptions::default(),
             code_coverage: CodeCoverage::default(),
+            valgrind: ValgrindOptions::default(),
+            output: ExecutionOutputOptions::default(),
+            exam: ExamOptions::default(),
+            plagiarism: PlagiarismOptions::default(),
+            environment: HashMap::new(),
+        }
+    }
+
+    /// Checks that every `environment` key is a valid shell variable name.
+    pub fn validate_environment(&self) -> Result<(), String> {
+        let mut invalid: Vec<&str> = self
+            .environment
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !is_valid_env_name(name))
+            .collect();
+        if invalid.is_empty() {
+            return Ok(());
+        }
+        invalid.sort_unstable();
+        Err(format!(
+            "Invalid environment variable name(s): {}",
+            invalid.join(", ")
+        ))
+    }
+
+    /// Checks that `project.image`, if set, is a plain image reference.
+    pub fn validate_image(&self) -> Result<(), String> {
+        match &self.project.image {
+            Some(image) if !is_valid_image(image) => {
+                Err(format!("Invalid Docker image: {:?}", image))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that every GATLAM gene has a usable range, value list or length bounds.
+    pub fn validate_genes(&self) -> Result<(), String> {
+        for (i, gene) in self.gatlam.genes.iter().enumerate() {
+            gene.validate()
+                .map_err(|e| format!("Invalid gene {}: {}", i + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every GATLAM `task_spec` rule has something to check and that per-task specs
+    /// are keyed by valid task numbers.
+    pub fn validate_property_rules(&self) -> Result<(), String> {
+        for (task_number, spec) in self.gatlam.task_spec.specs() {
+            let task = match task_number {
+                Some(n) if n < 1 => {
+                    return Err(format!(
+                        "Invalid task_spec: task number {} must be at least 1",
+                        n
+                    ));
+                }
+                Some(n) => format!(" of task {}", n),
+                None => String::new(),
+            };
+            for (i, rule) in spec.rules.iter().enumerate() {
+                rule.validate()
+                    .map_err(|e| format!("Invalid property rule {}{}: {}", i + 1, task, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `security.apparmor_profile`, if set, names a confining profile.
+    pub fn validate_sandbox(&self) -> Result<(), String> {
+        match &self.security.apparmor_profile {
+            Some(profile) if !is_valid_apparmor_profile(profile) || profile == "unconfined" => {
+                Err(format!("Invalid AppArmor profile: {:?}", profile))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// AppArmor profile for this assignment's containers: `security.apparmor_profile`, else
+    /// the deployment's `APPARMOR_PROFILE`. None leaves it to Docker.
+    pub fn apparmor_profile(&self) -> Option<String> {
+        self.security
+            .apparmor_profile
+            .clone()
+            .or_else(config::apparmor_profile)
+    }
+
+    /// Image to run this assignment's tasks in: `project.image`, else the deployment's image
+    /// for the language, else the default runner image.
+    pub fn runner_image(&self) -> String {
+        if let Some(image) = &self.project.image {
+            return image.clone();
         }
+        config::runner_images()
+            .remove(&self.project.language)
+            .unwrap_or_else(config::runner_image)
+    }
+
+    /// `environment` as sorted `KEY=VALUE` pairs, skipping invalid names.
+    pub fn environment_pairs(&self) -> Vec<String> {
+        let mut pairs: Vec<String> = self
+            .environment
+            .iter()
+            .filter(|(name, _)| is_valid_env_name(name))
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        pairs.sort();
+        pairs
     }
 
+    /// Loads the assignment's config, upgrading it (and rewriting the file) if it was written
+    /// in an older format, and layers it over the module config (see `inheritance.rs`).
     pub fn get_execution_config(module_id: i64, assignment_id: i64) -> Result<Self, String> {
         let cfg_dir = config_dir(module_id, assignment_id);
 
         let canonical = cfg_dir.join("config.json");
-        let file_contents = if canonical.exists() {
-            fs::read_to_string(&canonical)
-                .map_err(|_| format!("Failed to read config file at {canonical:?}"))?
+        let config_path = if canonical.exists() {
+            canonical
         } else {
             let entries = fs::read_dir(&cfg_dir)
                 .map_err(|_| format!("Failed to read config dir at {cfg_dir:?}"))?;
-            let config_path = entries
+            entries
                 .filter_map(|e| e.ok())
                 .map(|e| e.path())
                 .find(|p| p.extension().and_then(|s| s.to_str()) == Some("json"))
-                .ok_or_else(|| format!("No config json file found in config dir {cfg_dir:?}"))?;
-            fs::read_to_string(&config_path)
-                .map_err(|_| format!("Failed to read config file at {config_path:?}"))?
+                .ok_or_else(|| format!("No config json file found in config dir {cfg_dir:?}"))?
         };
+        let file_contents = fs::read_to_string(&config_path)
+            .map_err(|_| format!("Failed to read config file at {config_path:?}"))?;
+
+        let mut value: serde_json::Value = serde_json::from_str(&file_contents)
+            .map_err(|e| format!("Invalid config JSON format: {e}"))?;
+        let from_version = migrate(&mut value)?;
+
+        // Best effort: an upgraded config that cannot be written is upgraded again next time.
+        if from_version < CONFIG_VERSION
+            && let Ok(json) = serde_json::to_string_pretty(&value)
+        {
+            let _ = fs::write(&config_path, json);
+        }
 
-        let mut cfg: ExecutionConfig = serde_json::from_str(&file_contents)
-            .map_err(|_| "Invalid config JSON format".to_string())?;
-
+        let mut cfg: ExecutionConfig = serde_json::from_value(Self::inherit(module_id, &value)?)
+            .map_err(|e| format!("Invalid config JSON format: {e}"))?;
         cfg.execution = cfg.execution.clone().sanitize();
         Ok(cfg)
     }
 
+    /// Writes the assignment's `config.json`; only the overrides of the module config if the
+    /// module has one.
     pub fn save(&self, module_id: i64, assignment_id: i64) -> Result<(), String> {
         let cfg_dir = config_dir(module_id, assignment_id);
 
@@ -430,7 +1223,7 @@ impl ExecutionConfig {
         }
 
         let config_path = cfg_dir.join("config.json");
-        let json = serde_json::to_string_pretty(self)
+        let json = serde_json::to_string_pretty(&self.overrides(module_id)?)
             .map_err(|e| format!("Failed to serialize config to JSON: {e}"))?;
 
         fs::write(&config_path, json)
@@ -440,8 +1233,38 @@ impl ExecutionConfig {
     }
 }
 
+/// `[A-Za-z_][A-Za-z0-9_]*`, the names `sh` can export.
+fn is_valid_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Image reference such as `gcc:12`, `python:3.12-slim` or `ghcr.io/org/runner@sha256:...`.
+///
+/// Only the characters Docker allows in a reference, and nothing docker could read as a flag.
+pub fn is_valid_image(image: &str) -> bool {
+    let mut chars = image.chars();
+    image.len() <= 255
+        && matches!(chars.next(), Some(c) if c.is_ascii_alphanumeric())
+        && chars
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '/' | ':' | '@'))
+}
+
+/// AppArmor profile name such as `fitchfork-runner` or `docker-default`.
+pub fn is_valid_apparmor_profile(name: &str) -> bool {
+    let mut chars = name.chars();
+    name.len() <= 128
+        && matches!(chars.next(), Some(c) if c.is_ascii_alphanumeric())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
 //Default Functions
 
+fn default_config_version() -> u32 {
+    CONFIG_VERSION
+}
+
 fn default_timeout_secs() -> u64 {
     30
 }
@@ -462,6 +1285,30 @@ fn default_max_processes() -> u32 {
     256
 }
 
+fn default_max_disk_bytes() -> u64 {
+    1_073_741_824
+}
+
+fn default_max_output_bytes() -> u64 {
+    4_194_304
+}
+
+fn default_max_artifact_bytes() -> u64 {
+    20_971_520
+}
+
+fn default_exam_duration_minutes() -> u32 {
+    120
+}
+
+fn default_exam_hide_feedback() -> bool {
+    true
+}
+
+fn default_plagiarism_notify_students_at() -> Vec<PlagiarismStage> {
+    vec![PlagiarismStage::Reported, PlagiarismStage::Resolved]
+}
+
 fn default_marking_scheme() -> MarkingScheme {
     MarkingScheme::Exact
 }
@@ -470,7 +1317,7 @@ fn default_feedback_scheme() -> FeedbackScheme {
     FeedbackScheme::Auto
 }
 
-fn default_deliminator() -> String {
+fn default_delimiter() -> String {
     "###".to_string()
 }
 
@@ -478,6 +1325,14 @@ fn default_grading_policy() -> GradingPolicy {
     GradingPolicy::Last
 }
 
+fn default_best_of_last_n() -> u32 {
+    3
+}
+
+fn default_later_attempt_cap() -> f64 {
+    100.0
+}
+
 fn default_limit_attempts() -> bool {
     true
 }
@@ -542,6 +1397,10 @@ fn default_mutation_type() -> MutationType {
     MutationType::BitFlip
 }
 
+fn default_tournament_size() -> usize {
+    3
+}
+
 fn default_omega1() -> f64 {
     0.5
 }
@@ -554,21 +1413,48 @@ fn default_omega3() -> f64 {
     0.2
 }
 
+fn default_gene_penalty() -> f64 {
+    0.5
+}
+
+fn default_coverage_weight() -> f64 {
+    0.5
+}
+
+fn default_islands() -> usize {
+    1
+}
+
+fn default_migration_interval() -> usize {
+    5
+}
+
+fn default_migration_count() -> usize {
+    2
+}
+
+fn default_initial_temperature() -> f64 {
+    1.0
+}
+
+fn default_cooling_rate() -> f64 {
+    0.95
+}
+
+fn default_interpreter_cache_size() -> usize {
+    256
+}
+
+fn default_interpreter_cache_ttl_secs() -> u64 {
+    3600
+}
+
 fn default_max_parallel_chromosomes() -> usize {
     4
 }
 
 fn default_genes() -> Vec<GeneConfig> {
-    vec![
-        GeneConfig {
-            min_value: -5,
-            max_value: 5,
-        },
-        GeneConfig {
-            min_value: -4,
-            max_value: 9,
-        },
-    ]
+    vec![GeneConfig::integer(-5, 5), GeneConfig::integer(-4, 9)]
 }
 
 fn default_submission_mode() -> SubmissionMode {
@@ -597,3 +1483,288 @@ fn default_code_coverage_weight() -> f32 {
 fn default_code_coverage_whitelist() -> Vec<String> {
     Vec::new()
 }
+
+fn default_line_weight() -> f64 {
+    0.5
+}
+
+fn default_branch_weight() -> f64 {
+    0.3
+}
+
+fn default_function_weight() -> f64 {
+    0.2
+}
+
+fn default_definitely_lost_weight() -> f64 {
+    0.4
+}
+
+fn default_indirectly_lost_weight() -> f64 {
+    0.15
+}
+
+fn default_possibly_lost_weight() -> f64 {
+    0.05
+}
+
+fn default_invalid_reads_weight() -> f64 {
+    0.15
+}
+
+fn default_invalid_writes_weight() -> f64 {
+    0.15
+}
+
+fn default_uninitialised_weight() -> f64 {
+    0.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_defaults_to_empty_and_parses() {
+        let cfg: ExecutionConfig = serde_json::from_str("{}").unwrap();
+        assert!(cfg.environment.is_empty());
+
+        let cfg: ExecutionConfig =
+            serde_json::from_str(r#"{"environment": {"LC_ALL": "C.UTF-8", "A": "x=y"}}"#).unwrap();
+        assert!(cfg.validate_environment().is_ok());
+        assert_eq!(cfg.environment_pairs(), vec!["A=x=y", "LC_ALL=C.UTF-8"]);
+    }
+
+    #[test]
+    fn invalid_environment_names_are_rejected() {
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.environment.insert("1BAD".into(), "x".into());
+        cfg.environment.insert("NO SPACES".into(), "x".into());
+        cfg.environment.insert("_OK".into(), "x".into());
+
+        let err = cfg.validate_environment().unwrap_err();
+        assert_eq!(err, "Invalid environment variable name(s): 1BAD, NO SPACES");
+        assert_eq!(cfg.environment_pairs(), vec!["_OK=x"]);
+    }
+
+    #[test]
+    fn image_override_is_validated_and_wins() {
+        let cfg: ExecutionConfig =
+            serde_json::from_str(r#"{"project": {"language": "c", "image": "gcc:12"}}"#).unwrap();
+        assert!(cfg.validate_image().is_ok());
+        assert_eq!(cfg.runner_image(), "gcc:12");
+
+        for image in ["--privileged", "gcc 12", "", "img;rm"] {
+            let mut cfg = ExecutionConfig::default_config();
+            cfg.project.image = Some(image.to_string());
+            assert!(cfg.validate_image().is_err(), "{image:?} was accepted");
+        }
+        assert!(is_valid_image(
+            "registry.example.com:5000/cos212/runner@sha256:abc"
+        ));
+    }
+
+    #[test]
+    fn sandbox_defaults_to_strict_and_validates_apparmor() {
+        let cfg = ExecutionConfig::default_config();
+        assert_eq!(cfg.security.seccomp_profile, SeccompProfile::Strict);
+        assert!(cfg.validate_sandbox().is_ok());
+
+        let cfg: ExecutionConfig = serde_json::from_str(
+            r#"{"security": {"seccomp_profile": "docker", "apparmor_profile": "fitchfork-runner"}}"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.security.seccomp_profile, SeccompProfile::Docker);
+        assert!(cfg.validate_sandbox().is_ok());
+        assert_eq!(cfg.apparmor_profile().as_deref(), Some("fitchfork-runner"));
+
+        for profile in ["unconfined", "", "a b", "--privileged", "x,apparmor=y"] {
+            let mut cfg = ExecutionConfig::default_config();
+            cfg.security.apparmor_profile = Some(profile.to_string());
+            assert!(cfg.validate_sandbox().is_err(), "{profile:?} was accepted");
+        }
+    }
+
+    #[test]
+    fn gatlam_objective_defaults_to_properties_and_parses() {
+        let cfg = ExecutionConfig::default_config();
+        assert_eq!(cfg.gatlam.objective, GaObjective::Properties);
+        assert_eq!(cfg.gatlam.coverage_weight, 0.5);
+
+        let cfg: ExecutionConfig = serde_json::from_str(
+            r#"{"gatlam": {"objective": "weighted_sum", "coverage_weight": 0.25}}"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.gatlam.objective, GaObjective::WeightedSum);
+        assert_eq!(cfg.gatlam.coverage_weight, 0.25);
+    }
+
+    #[test]
+    fn genes_default_to_integer_and_validate_by_kind() {
+        let cfg: ExecutionConfig = serde_json::from_str(
+            r#"{"gatlam": {"genes": [
+                {"min_value": -2, "max_value": 2},
+                {"kind": "categorical", "values": ["push", "pop"]},
+                {"kind": "string", "min_length": 1, "max_length": 4, "alphabet": "ab"}
+            ]}}"#,
+        )
+        .unwrap();
+        let kinds: Vec<GeneKind> = cfg.gatlam.genes.iter().map(|g| g.kind).collect();
+        assert_eq!(
+            kinds,
+            [GeneKind::Integer, GeneKind::Categorical, GeneKind::String]
+        );
+        assert_eq!(cfg.gatlam.genes[2].alphabet_chars(), ['a', 'b']);
+        assert!(cfg.validate_genes().is_ok());
+
+        let mut cfg = ExecutionConfig::default_config();
+        assert_eq!(cfg.gatlam.genes[0].alphabet_chars().len(), 26);
+        cfg.gatlam.genes.push(GeneConfig {
+            kind: GeneKind::Categorical,
+            ..GeneConfig::integer(0, 0)
+        });
+        assert_eq!(
+            cfg.validate_genes().unwrap_err(),
+            "Invalid gene 3: categorical gene has no values"
+        );
+    }
+
+    #[test]
+    fn gene_repair_defaults_to_none_and_rejects_unsatisfiable_genes() {
+        let gene: GeneConfig = serde_json::from_str(r#"{"min_value": 0, "max_value": 3}"#).unwrap();
+        assert_eq!(gene.repair, GeneRepair::None);
+        assert_eq!(gene.penalty, 0.5);
+
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.gatlam.genes = vec![GeneConfig {
+            invalid_values: vec![0, 1, 2, 9],
+            repair: GeneRepair::Resample,
+            ..GeneConfig::integer(0, 3)
+        }];
+        assert!(cfg.validate_genes().is_ok());
+
+        cfg.gatlam.genes[0].invalid_values.push(3);
+        assert_eq!(
+            cfg.validate_genes().unwrap_err(),
+            "Invalid gene 1: every value in 0..=3 is invalid"
+        );
+
+        cfg.gatlam.genes[0] = GeneConfig {
+            penalty: -1.0,
+            ..GeneConfig::integer(0, 3)
+        };
+        assert_eq!(
+            cfg.validate_genes().unwrap_err(),
+            "Invalid gene 1: penalty must not be negative"
+        );
+    }
+
+    #[test]
+    fn property_rules_parse_by_type_and_reject_empty_checks() {
+        let cfg: ExecutionConfig = serde_json::from_str(
+            r#"{"gatlam": {"task_spec": {"rules": [
+                {"type": "contains", "value": "done"},
+                {"type": "ordering", "values": ["push", "pop"], "task_number": 2},
+                {"type": "max_runtime", "ms": 500}
+            ]}}}"#,
+        )
+        .unwrap();
+        let rules = &cfg.gatlam.task_spec.for_task(None).rules;
+        assert_eq!(rules.len(), 3);
+        assert_eq!(
+            rules[1].check,
+            PropertyCheck::Ordering {
+                values: vec!["push".into(), "pop".into()]
+            }
+        );
+        assert!(rules[0].applies_to(Some(2)) && rules[0].applies_to(None));
+        assert!(rules[1].applies_to(Some(2)) && !rules[1].applies_to(Some(1)));
+        assert!(cfg.validate_property_rules().is_ok());
+
+        let mut cfg = ExecutionConfig::default_config();
+        cfg.gatlam.task_spec = TaskSpecs::Shared(TaskSpecConfig {
+            rules: vec![PropertyRule {
+                task_number: None,
+                check: PropertyCheck::Ordering {
+                    values: vec!["only".into()],
+                },
+            }],
+            ..TaskSpecConfig::default()
+        });
+        assert_eq!(
+            cfg.validate_property_rules().unwrap_err(),
+            "Invalid property rule 1: an ordering needs at least 2 values"
+        );
+    }
+
+    #[test]
+    fn task_spec_is_shared_or_keyed_by_task_number() {
+        let cfg: ExecutionConfig =
+            serde_json::from_str(r#"{"gatlam": {"task_spec": {"max_runtime_ms": 100}}}"#).unwrap();
+        assert!(matches!(cfg.gatlam.task_spec, TaskSpecs::Shared(_)));
+        assert_eq!(
+            cfg.gatlam.task_spec.for_task(Some(7)).max_runtime_ms,
+            Some(100)
+        );
+
+        let cfg: ExecutionConfig = serde_json::from_str(
+            r#"{"gatlam": {"task_spec": {
+                "1": {"valid_return_codes": [0, 1]},
+                "2": {"max_runtime_ms": 500, "forbidden_outputs": ["BAD"]}
+            }}}"#,
+        )
+        .unwrap();
+        let spec = &cfg.gatlam.task_spec;
+        assert_eq!(spec.for_task(Some(1)).valid_return_codes, [0, 1]);
+        assert_eq!(spec.for_task(Some(2)).max_runtime_ms, Some(500));
+        assert_eq!(spec.for_task(Some(3)).valid_return_codes, [0]);
+        assert_eq!(spec.for_task(None).forbidden_outputs, Vec::<String>::new());
+
+        let json = serde_json::to_value(&cfg.gatlam.task_spec).unwrap();
+        assert_eq!(json["2"]["max_runtime_ms"], 500);
+
+        let cfg: ExecutionConfig =
+            serde_json::from_str(r#"{"gatlam": {"task_spec": {"0": {}}}}"#).unwrap();
+        assert_eq!(
+            cfg.validate_property_rules().unwrap_err(),
+            "Invalid task_spec: task number 0 must be at least 1"
+        );
+    }
+
+    #[test]
+    fn grading_policies_pick_from_newest_first_marks() {
+        let marks = [40.0, 90.0, 70.0, 90.0];
+        let with = |policy: &str, extra: &str| -> MarkingOptions {
+            serde_json::from_str(&format!(r#"{{"grading_policy": "{policy}"{extra}}}"#)).unwrap()
+        };
+
+        assert_eq!(with("last", "").policy_mark(&marks), Some((0, 40.0)));
+        // ties go to the newer attempt
+        assert_eq!(with("best", "").policy_mark(&marks), Some((1, 90.0)));
+        assert_eq!(
+            with("best_of_last_n", r#", "best_of_last_n": 1"#).policy_mark(&marks),
+            Some((0, 40.0))
+        );
+
+        // oldest to newest: 90 x1, 70 x2, 90 x3, 40 x4, later ones capped at 80
+        let weighted = with("weighted", r#", "later_attempt_cap": 80"#);
+        assert_eq!(weighted.policy_mark(&marks), Some((0, 63.0)));
+        assert_eq!(weighted.policy_mark(&[100.0]), Some((0, 100.0)));
+        assert_eq!(weighted.policy_mark(&[]), None);
+    }
+
+    #[test]
+    fn plagiarism_runs_open_cases_for_pairs_reaching_either_threshold() {
+        let options = |json: &str| -> PlagiarismOptions { serde_json::from_str(json).unwrap() };
+
+        assert!(options("{}").opens_case(0.0, 0));
+
+        let thresholds = options(r#"{"case_min_similarity": 60, "case_min_lines": 40}"#);
+        assert!(thresholds.opens_case(60.0, 0));
+        assert!(thresholds.opens_case(10.0, 40));
+        assert!(!thresholds.opens_case(59.9, 39));
+
+        let lines_only = options(r#"{"case_min_lines": 40}"#);
+        assert!(!lines_only.opens_case(100.0, 10));
+    }
+}
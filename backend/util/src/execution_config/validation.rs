@@ -0,0 +1,492 @@
+//! Whole-config validation for lecturer-supplied [`ExecutionConfig`] JSON.
+//!
+//! Deserializing alone drops unknown fields and accepts any value of the right type, so a typo
+//! or an out-of-range value only shows up when a submission misbehaves. The checks here
+//! collect every problem at once, each tagged with the path of the field it concerns.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+
+use super::{CoverageMetric, ExecutionConfig, GradingPolicy, SelectionType, migrate};
+
+/// Slack allowed when checking that the omegas sum to 1.
+const OMEGA_SUM_TOLERANCE: f64 = 1e-6;
+
+/// One problem in a config.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigError {
+    /// Path of the field, e.g. `gatlam.omega1` or `gatlam.genes[1]`; empty for the whole
+    /// config.
+    pub path: String,
+    /// What is wrong, readable on its own.
+    pub message: String,
+}
+
+impl ConfigError {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl ExecutionConfig {
+    /// Upgrades `value` to the current format (see [`migrate`]), parses it as a config and runs
+    /// every check on it.
+    ///
+    /// # Errors
+    /// Returns all problems found: unknown fields, a field of the wrong type (only the first,
+    /// as parsing stops there) and everything [`ExecutionConfig::validate`] reports.
+    pub fn from_json_checked(value: &Value) -> Result<Self, Vec<ConfigError>> {
+        let mut value = value.clone();
+        if let Err(e) = migrate(&mut value) {
+            return Err(vec![ConfigError::new("config_version", e)]);
+        }
+
+        let mut errors = Vec::new();
+        if let Ok(reference) = serde_json::to_value(Self::default_config()) {
+            unknown_fields(&value, &reference, "", &mut errors);
+            errors.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+
+        match serde_path_to_error::deserialize::<_, Self>(&value) {
+            Ok(cfg) => {
+                errors.extend(cfg.validate());
+                if errors.is_empty() {
+                    Ok(cfg)
+                } else {
+                    Err(errors)
+                }
+            }
+            Err(e) => {
+                let path = match e.path().to_string() {
+                    p if p == "." => String::new(),
+                    p => p,
+                };
+                errors.push(ConfigError::new(
+                    path,
+                    format!("Invalid config format: {}", e.inner()),
+                ));
+                Err(errors)
+            }
+        }
+    }
+
+    /// Range and consistency checks on a parsed config; empty if it is usable.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, path: &str, message: String| {
+            if !ok {
+                errors.push(ConfigError::new(path, message));
+            }
+        };
+
+        // ---- execution ----
+        let limits = &self.execution;
+        for (name, value) in [
+            ("timeout_secs", limits.timeout_secs),
+            ("max_memory", limits.max_memory),
+            ("max_cpus", limits.max_cpus as u64),
+            ("max_processes", limits.max_processes as u64),
+        ] {
+            check(
+                value > 0,
+                &format!("execution.{name}"),
+                format!("execution.{name} must be at least 1"),
+            );
+        }
+
+        // ---- marking ----
+        let marking = &self.marking;
+        check(
+            marking.pass_mark <= 100,
+            "marking.pass_mark",
+            format!(
+                "marking.pass_mark must be between 0 and 100 (got {})",
+                marking.pass_mark
+            ),
+        );
+        for (i, entry) in marking.dissalowed_code.iter().enumerate() {
+            if let Err(e) = crate::scan_code_content::validate_pattern(entry) {
+                check(false, &format!("marking.dissalowed_code[{i}]"), e);
+            }
+        }
+        check(
+            !marking.delimiter.is_empty(),
+            "marking.delimiter",
+            "marking.delimiter must not be empty".to_string(),
+        );
+        check(
+            !marking.limit_attempts || marking.max_attempts > 0,
+            "marking.max_attempts",
+            "marking.max_attempts must be at least 1 when limit_attempts is true".to_string(),
+        );
+        check(
+            marking.grading_policy != GradingPolicy::BestOfLastN || marking.best_of_last_n > 0,
+            "marking.best_of_last_n",
+            "marking.best_of_last_n must be at least 1 when grading_policy is best_of_last_n"
+                .to_string(),
+        );
+        check(
+            in_range(marking.later_attempt_cap, 0.0, 100.0),
+            "marking.later_attempt_cap",
+            format!(
+                "marking.later_attempt_cap must be between 0 and 100 (got {})",
+                marking.later_attempt_cap
+            ),
+        );
+        check(
+            in_range(marking.late.late_max_percent, 0.0, 100.0),
+            "marking.late.late_max_percent",
+            format!(
+                "marking.late.late_max_percent must be between 0 and 100 (got {})",
+                marking.late.late_max_percent
+            ),
+        );
+
+        // ---- code coverage ----
+        let weight = self.code_coverage.code_coverage_weight as f64;
+        check(
+            in_range(weight, 0.0, 100.0),
+            "code_coverage.code_coverage_weight",
+            format!("code_coverage.code_coverage_weight must be between 0 and 100 (got {weight})"),
+        );
+        let weights = &self.code_coverage.weights;
+        for (name, value) in [
+            ("line", weights.line),
+            ("branch", weights.branch),
+            ("function", weights.function),
+        ] {
+            check(
+                value >= 0.0,
+                &format!("code_coverage.weights.{name}"),
+                format!("code_coverage.weights.{name} must not be negative (got {value})"),
+            );
+        }
+        check(
+            self.code_coverage.metric != CoverageMetric::Weighted
+                || weights.line + weights.branch + weights.function > 0.0,
+            "code_coverage.weights",
+            "code_coverage.weights must not all be 0 when metric is weighted".to_string(),
+        );
+
+        // ---- valgrind ----
+        let weights = &self.valgrind.weights;
+        for (name, value) in weights.named() {
+            check(
+                value >= 0.0,
+                &format!("valgrind.weights.{name}"),
+                format!("valgrind.weights.{name} must not be negative (got {value})"),
+            );
+        }
+        check(
+            weights.named().iter().map(|(_, w)| w).sum::<f64>() > 0.0,
+            "valgrind.weights",
+            "valgrind.weights must not all be 0".to_string(),
+        );
+
+        // ---- gatlam ----
+        let ga = &self.gatlam;
+        for (name, value) in [
+            ("reproduction_probability", ga.reproduction_probability),
+            ("crossover_probability", ga.crossover_probability),
+            ("mutation_probability", ga.mutation_probability),
+            ("coverage_weight", ga.coverage_weight),
+        ] {
+            check(
+                in_range(value, 0.0, 1.0),
+                &format!("gatlam.{name}"),
+                format!("gatlam.{name} must be between 0 and 1 (got {value})"),
+            );
+        }
+        for (name, value) in [
+            ("omega1", ga.omega1),
+            ("omega2", ga.omega2),
+            ("omega3", ga.omega3),
+        ] {
+            check(
+                value >= 0.0,
+                &format!("gatlam.{name}"),
+                format!("gatlam.{name} must not be negative (got {value})"),
+            );
+        }
+        let omega_sum = ga.omega1 + ga.omega2 + ga.omega3;
+        check(
+            (omega_sum - 1.0).abs() <= OMEGA_SUM_TOLERANCE,
+            "gatlam.omega1",
+            format!("gatlam.omega1 + omega2 + omega3 must sum to 1 (got {omega_sum})"),
+        );
+        check(
+            ga.population_size > 0,
+            "gatlam.population_size",
+            "gatlam.population_size must be at least 1".to_string(),
+        );
+        check(
+            ga.elitism_count < ga.population_size.max(1),
+            "gatlam.elitism_count",
+            format!(
+                "gatlam.elitism_count ({}) must be less than population_size ({})",
+                ga.elitism_count, ga.population_size
+            ),
+        );
+        check(
+            (1..=ga.population_size.max(1)).contains(&ga.islands),
+            "gatlam.islands",
+            format!(
+                "gatlam.islands must be between 1 and population_size ({}) (got {})",
+                ga.population_size, ga.islands
+            ),
+        );
+        check(
+            ga.selection_type != SelectionType::Tournament || ga.tournament_size > 0,
+            "gatlam.tournament_size",
+            "gatlam.tournament_size must be at least 1 for tournament selection".to_string(),
+        );
+        check(
+            ga.initial_temperature > 0.0,
+            "gatlam.initial_temperature",
+            format!(
+                "gatlam.initial_temperature must be positive (got {})",
+                ga.initial_temperature
+            ),
+        );
+        check(
+            ga.cooling_rate > 0.0 && ga.cooling_rate <= 1.0,
+            "gatlam.cooling_rate",
+            format!(
+                "gatlam.cooling_rate must be greater than 0 and at most 1 (got {})",
+                ga.cooling_rate
+            ),
+        );
+
+        // ---- security ----
+        let security = &self.security;
+        check(
+            !security.password_enabled
+                || security
+                    .password_pin
+                    .as_deref()
+                    .is_some_and(|pin| !pin.trim().is_empty()),
+            "security.password_pin",
+            "security.password_enabled requires a password_pin".to_string(),
+        );
+        check(
+            security.cookie_ttl_minutes > 0,
+            "security.cookie_ttl_minutes",
+            "security.cookie_ttl_minutes must be at least 1".to_string(),
+        );
+
+        // ---- exam ----
+        check(
+            !self.exam.enabled || self.exam.duration_minutes > 0,
+            "exam.duration_minutes",
+            "exam.duration_minutes must be at least 1".to_string(),
+        );
+
+        // ---- plagiarism ----
+        check(
+            self.plagiarism
+                .case_min_similarity
+                .is_none_or(|min| (0.0..=100.0).contains(&min)),
+            "plagiarism.case_min_similarity",
+            "plagiarism.case_min_similarity must be between 0 and 100".to_string(),
+        );
+        check(
+            self.plagiarism.case_min_lines.is_none_or(|min| min >= 1),
+            "plagiarism.case_min_lines",
+            "plagiarism.case_min_lines must be at least 1".to_string(),
+        );
+
+        // ---- checks shared with other callers ----
+        if let Err(e) = self.validate_environment() {
+            errors.push(ConfigError::new("environment", e));
+        }
+        if let Err(e) = self.validate_image() {
+            errors.push(ConfigError::new("project.image", e));
+        }
+        if let Err(e) = self.validate_sandbox() {
+            errors.push(ConfigError::new("security.apparmor_profile", e));
+        }
+        for (i, gene) in self.gatlam.genes.iter().enumerate() {
+            if let Err(e) = gene.validate() {
+                errors.push(ConfigError::new(
+                    format!("gatlam.genes[{i}]"),
+                    format!("Invalid gene {}: {}", i + 1, e),
+                ));
+            }
+        }
+        if let Err(e) = self.validate_property_rules() {
+            errors.push(ConfigError::new("gatlam.task_spec", e));
+        }
+
+        errors
+    }
+}
+
+/// `lo <= value <= hi`, and not NaN.
+fn in_range(value: f64, lo: f64, hi: f64) -> bool {
+    (lo..=hi).contains(&value)
+}
+
+/// Fields of `value` that `reference` (the default config, serialized) does not have. Free-form
+/// maps (`environment`, per-task `task_spec`) are only descended into where their values have
+/// a known shape.
+fn unknown_fields(value: &Value, reference: &Value, path: &str, errors: &mut Vec<ConfigError>) {
+    match (value, reference) {
+        (Value::Object(fields), Value::Object(known)) => {
+            if path == "environment" {
+                return;
+            }
+            if path == "gatlam.task_spec" && is_per_task(fields) {
+                for (task, spec) in fields {
+                    unknown_fields(spec, reference, &format!("{path}.{task}"), errors);
+                }
+                return;
+            }
+            for (name, field) in fields {
+                let field_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                match known.get(name) {
+                    Some(reference) => unknown_fields(field, reference, &field_path, errors),
+                    None => errors.push(ConfigError::new(
+                        field_path.clone(),
+                        format!("Unknown field {field_path}"),
+                    )),
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(known)) => {
+            if let Some(reference) = known.first() {
+                for (i, item) in items.iter().enumerate() {
+                    unknown_fields(item, reference, &format!("{path}[{i}]"), errors);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Same rule as `TaskSpecs`' deserializer: a non-empty object keyed by integers.
+fn is_per_task(fields: &Map<String, Value>) -> bool {
+    !fields.is_empty() && fields.keys().all(|k| k.trim().parse::<i64>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(errors: &[ConfigError]) -> Vec<&str> {
+        errors.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    #[test]
+    fn the_default_config_is_valid() {
+        let value = serde_json::to_value(ExecutionConfig::default_config()).unwrap();
+        assert!(ExecutionConfig::from_json_checked(&value).is_ok());
+        assert!(ExecutionConfig::from_json_checked(&json!({})).is_ok());
+    }
+
+    #[test]
+    fn reports_unknown_fields_with_their_paths() {
+        let errors = ExecutionConfig::from_json_checked(&json!({
+            "marking": { "pass_mrak": 50, "late": { "grace": 5 } },
+            "gatlam": {
+                "genes": [{ "kind": "integer", "min_value": 0, "max_value": 3, "step": 1 }],
+                "task_spec": { "2": { "max_runtime_ms": 100, "timeout": 5 } }
+            },
+            "environment": { "ANY_NAME": "ok" },
+            "extras": true
+        }))
+        .unwrap_err();
+
+        assert_eq!(
+            paths(&errors),
+            [
+                "extras",
+                "gatlam.genes[0].step",
+                "gatlam.task_spec.2.timeout",
+                "marking.late.grace",
+                "marking.pass_mrak",
+            ]
+        );
+        assert_eq!(errors[0].message, "Unknown field extras");
+    }
+
+    #[test]
+    fn reports_where_a_value_has_the_wrong_type() {
+        let errors = ExecutionConfig::from_json_checked(&json!({
+            "execution": { "timeout_secs": "ten" }
+        }))
+        .unwrap_err();
+        assert_eq!(paths(&errors), ["execution.timeout_secs"]);
+        assert!(errors[0].message.starts_with("Invalid config format: "));
+    }
+
+    #[test]
+    fn collects_every_range_and_conflict_error() {
+        let errors = ExecutionConfig::from_json_checked(&json!({
+            "marking": {
+                "pass_mark": 120, "limit_attempts": true, "max_attempts": 0,
+                "dissalowed_code": ["system(", "code:re:exec("]
+            },
+            "code_coverage": { "metric": "weighted", "weights": { "line": -1, "branch": 0, "function": 0 } },
+            "valgrind": { "weights": { "invalid_reads": -0.5 } },
+            "gatlam": {
+                "omega1": 0.5, "omega2": 0.5, "omega3": 0.5,
+                "mutation_probability": 1.5,
+                "genes": [{ "kind": "categorical" }]
+            },
+            "security": { "password_enabled": true }
+        }))
+        .unwrap_err();
+
+        assert_eq!(
+            paths(&errors),
+            [
+                "marking.pass_mark",
+                "marking.dissalowed_code[1]",
+                "marking.max_attempts",
+                "code_coverage.weights.line",
+                "code_coverage.weights",
+                "valgrind.weights.invalid_reads",
+                "gatlam.mutation_probability",
+                "gatlam.omega1",
+                "security.password_pin",
+                "gatlam.genes[0]",
+            ]
+        );
+        assert_eq!(
+            errors[0].message,
+            "marking.pass_mark must be between 0 and 100 (got 120)"
+        );
+        assert!(
+            errors[1]
+                .message
+                .starts_with("Invalid pattern 'code:re:exec(':")
+        );
+        assert_eq!(
+            errors[5].message,
+            "valgrind.weights.invalid_reads must not be negative (got -0.5)"
+        );
+        assert_eq!(
+            errors[7].message,
+            "gatlam.omega1 + omega2 + omega3 must sum to 1 (got 1.5)"
+        );
+        assert_eq!(
+            errors[9].message,
+            "Invalid gene 1: categorical gene has no values"
+        );
+    }
+}
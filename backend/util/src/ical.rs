@@ -0,0 +1,131 @@
+//! iCalendar (RFC 5545) feeds.
+//!
+//! [`render`] writes a `VCALENDAR` of [`CalendarEvent`]s that calendar apps can subscribe to:
+//! times in UTC, text escaped and lines folded at 75 octets.
+
+use chrono::{DateTime, Utc};
+
+/// One `VEVENT`. Events without an `end` are instants (a deadline, say).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// Globally unique and stable across fetches, so apps update the event rather than adding
+    /// another.
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: Option<DateTime<Utc>>,
+    /// When the event last changed.
+    pub stamp: DateTime<Utc>,
+}
+
+/// The calendar named `name` holding `events`, with CRLF line endings.
+pub fn render(name: &str, events: &[CalendarEvent]) -> String {
+    let mut out = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//FitchFork//Calendar//EN",
+        "CALSCALE:GREGORIAN",
+        "METHOD:PUBLISH",
+    ] {
+        push_line(&mut out, line);
+    }
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(name)));
+
+    for event in events {
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", escape(&event.uid)));
+        push_line(&mut out, &format!("DTSTAMP:{}", timestamp(event.stamp)));
+        push_line(&mut out, &format!("DTSTART:{}", timestamp(event.start)));
+        if let Some(end) = event.end {
+            push_line(&mut out, &format!("DTEND:{}", timestamp(end)));
+        }
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&event.summary)));
+        if let Some(description) = &event.description {
+            push_line(&mut out, &format!("DESCRIPTION:{}", escape(description)));
+        }
+        if let Some(url) = &event.url {
+            push_line(&mut out, &format!("URL:{url}"));
+        }
+        push_line(&mut out, "END:VEVENT");
+    }
+
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes a TEXT value: backslashes, semicolons, commas and line breaks.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Appends a content line, folded so no physical line exceeds 75 octets.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn renders_escaped_events() {
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 21, 59, 0).unwrap();
+        let ics = render(
+            "COS301",
+            &[CalendarEvent {
+                uid: "assignment-12@fitchfork".into(),
+                summary: "COS301: Practical 1, part A; due".into(),
+                description: Some("Line one\nLine two".into()),
+                url: Some("https://ff.example/modules/1/assignments/12".into()),
+                start: at,
+                end: None,
+                stamp: at,
+            }],
+        );
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(ics.contains("DTSTART:20250301T215900Z\r\n"));
+        assert!(!ics.contains("DTEND"));
+        assert!(ics.contains("SUMMARY:COS301: Practical 1\\, part A\\; due\r\n"));
+        assert!(ics.contains("DESCRIPTION:Line one\\nLine two\r\n"));
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let mut out = String::new();
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        push_line(&mut out, &line);
+        for physical in out.trim_end_matches("\r\n").split("\r\n") {
+            assert!(physical.len() <= 75);
+        }
+        assert_eq!(out.replace("\r\n ", ""), format!("{line}\r\n"));
+    }
+}
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 /// All MOSS-supported languages with cleaner Rust-y names.
 /// Serialized/deserialized in `lowercase` for config JSON.
 /// Common aliases are accepted (e.g., "cc", "c++", "js", "c#").
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Language {
     /// Not supported by MOSS, but supported in our runtime and starters.
@@ -76,6 +76,23 @@ impl Language {
             Language::PlSql => "plsql",
         }
     }
+
+    /// JPlag language identifier (`-l`); languages JPlag has no frontend for are compared as
+    /// plain text.
+    pub fn to_jplag(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::Go => "go",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Java => "java",
+            Language::Python => "python3",
+            Language::Scheme => "scheme",
+            Language::CSharp => "csharp",
+            Language::JavaScript => "javascript",
+            _ => "text",
+        }
+    }
 }
 
 pub trait LanguageExt {
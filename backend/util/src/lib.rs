@@ -1,12 +1,21 @@
+pub mod archive;
 pub mod code_coverage_report;
 pub mod config;
+pub mod csv;
 pub mod execution_config;
 pub mod http;
+pub mod ical;
 pub mod languages;
+pub mod mail;
 pub mod mark_allocator;
+pub mod massif_report;
 pub mod paths;
+pub mod rate_limit;
+pub mod rar;
 pub mod scan_code_content;
+pub mod source_files;
 pub mod state;
+pub mod storage;
 pub mod system_health;
 pub mod test_helpers;
 pub mod valgrind_report;
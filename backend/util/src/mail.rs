@@ -0,0 +1,171 @@
+//! Outbound mail.
+//!
+//! [`send`] delivers an [`OutboundEmail`] through the SMTP relay at `SMTP_HOST`:`SMTP_PORT`
+//! (STARTTLS), authenticating as `GMAIL_USERNAME`. [`markdown_to_html`] renders user-written
+//! markdown (announcement bodies, ticket replies) for an email's HTML part.
+
+use crate::config;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{
+    AsyncTransport, Tokio1Executor,
+    message::{Message, MultiPart},
+    transport::smtp::{AsyncSmtpTransport, authentication::Credentials},
+};
+use once_cell::sync::Lazy;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, html};
+use std::fmt;
+
+/// Shared SMTP client, built on first use from the mail settings.
+static SMTP_CLIENT: Lazy<AsyncSmtpTransport<Tokio1Executor>> = Lazy::new(|| {
+    let host = config::smtp_host();
+    let tls_parameters = TlsParameters::new(host.clone()).expect("Failed to create TLS parameters");
+
+    AsyncSmtpTransport::<Tokio1Executor>::relay(&host)
+        .expect("Failed to create SMTP transport")
+        .port(config::smtp_port())
+        .tls(Tls::Required(tls_parameters))
+        .credentials(Credentials::new(
+            config::gmail_username(),
+            config::gmail_app_password(),
+        ))
+        .build()
+});
+
+/// A plain-text and HTML email to one recipient, sent from `EMAIL_FROM_NAME <GMAIL_USERNAME>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboundEmail {
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    /// The sender or recipient address could not be parsed.
+    Address(String),
+    /// The message could not be built.
+    Message(String),
+    /// The SMTP relay refused or failed to take the message.
+    Transport(String),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MailError::Address(e) => write!(f, "invalid address: {e}"),
+            MailError::Message(e) => write!(f, "invalid message: {e}"),
+            MailError::Transport(e) => write!(f, "SMTP error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MailError {}
+
+/// Sends `email` as a `multipart/alternative` message.
+pub async fn send(email: &OutboundEmail) -> Result<(), MailError> {
+    let from = format!(
+        "{} <{}>",
+        config::email_from_name(),
+        config::gmail_username()
+    );
+    let message = Message::builder()
+        .from(
+            from.parse()
+                .map_err(|e| MailError::Address(format!("{e}")))?,
+        )
+        .to(email
+            .to
+            .parse()
+            .map_err(|e| MailError::Address(format!("{e}")))?)
+        .subject(&email.subject)
+        .multipart(MultiPart::alternative_plain_html(
+            email.text.clone(),
+            email.html.clone(),
+        ))
+        .map_err(|e| MailError::Message(e.to_string()))?;
+    send_message(message).await
+}
+
+/// Sends an already built message.
+pub async fn send_message(message: Message) -> Result<(), MailError> {
+    SMTP_CLIENT
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| MailError::Transport(e.to_string()))
+}
+
+/// Renders markdown to an HTML fragment for an email body.
+///
+/// Raw HTML in the source is shown as text rather than passed through, and links other than
+/// `http(s)`, `mailto` and relative ones are dropped to `#`.
+pub fn markdown_to_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url),
+            title,
+            id,
+        }),
+        other => other,
+    });
+
+    let mut out = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
+
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    let lower = url.trim_start().to_ascii_lowercase();
+    let scheme = lower.split_once(':').map(|(scheme, _)| scheme);
+    match scheme {
+        // No scheme (relative), or the colon comes after a path/query/fragment starts
+        None => url,
+        Some(s) if s.contains(['/', '?', '#']) => url,
+        Some("http" | "https" | "mailto") => url,
+        Some(_) => CowStr::Borrowed("#"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_renders_to_html() {
+        let html = markdown_to_html("The exam is on **Friday**.\n\n- bring ID\n- bring a pen");
+        assert!(html.contains("<strong>Friday</strong>"));
+        assert!(html.contains("<li>bring ID</li>"));
+    }
+
+    #[test]
+    fn markdown_html_and_script_links_are_neutralised() {
+        let html = markdown_to_html(
+            "<script>alert(1)</script>\n\n[click](javascript:alert(1)) [ok](https://x.co) [rel](/a:b)",
+        );
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains("<a href=\"#\">click</a>"));
+        assert!(html.contains("<a href=\"https://x.co\">ok</a>"));
+        assert!(html.contains("<a href=\"/a:b\">rel</a>"));
+    }
+}
@@ -1,10 +1,21 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::{fs, io::Write, path::PathBuf};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use crate::execution_config::{ExecutionConfig, MarkingScheme};
 use crate::paths::{mark_allocator_dir, mark_allocator_path};
 
+mod validation;
+
+pub use validation::{AllocatorMismatch, validate_against_outputs};
+
+/// Allocator file names, in lookup order. The format follows the extension.
+const ALLOCATOR_FILE_NAMES: &[&str] = &["allocator.json", "allocator.yaml", "allocator.yml"];
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MarkAllocator {
     pub generated_at: DateTime<Utc>,
@@ -31,6 +42,105 @@ pub struct Subsection {
     pub regex: Option<Vec<String>>,
     #[serde(default)]
     pub feedback: Option<String>,
+    /// Marks this subsection against the task's peak heap usage (from a massif profile)
+    /// instead of its output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_thresholds: Option<Vec<MemoryThreshold>>,
+    /// Marking scheme for this subsection, overriding the assignment's
+    /// `marking.marking_scheme`. Nested subsections inherit it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comparator: Option<MarkingScheme>,
+    /// Multiplier applied to `value` when marking (defaults to 1).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    /// Nested subsections. A subsection with children is a group: only its leaves are marked,
+    /// and their marks must add up to the group's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subsections: Option<Vec<Subsection>>,
+}
+
+/// A peak heap usage band: a task whose peak is at most `max_peak_bytes` earns `percent` of
+/// the subsection's value. The smallest band the peak fits in applies; a peak above every
+/// band earns nothing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MemoryThreshold {
+    pub max_peak_bytes: u64,
+    pub percent: f64,
+}
+
+impl Subsection {
+    /// Marks this subsection is worth: `value` scaled by `weight`.
+    pub fn marks(&self) -> f64 {
+        self.value * self.weight.unwrap_or(1.0)
+    }
+
+    /// The nested subsections, if this is a non-empty group.
+    pub fn children(&self) -> Option<&[Subsection]> {
+        self.subsections.as_deref().filter(|c| !c.is_empty())
+    }
+
+    /// Appends this subsection's leaves to `out`, named `"Parent / Child"`, with the
+    /// comparator inherited and the weights multiplied along the way.
+    fn push_leaves(&self, out: &mut Vec<Subsection>) {
+        let Some(children) = self.children() else {
+            out.push(self.clone());
+            return;
+        };
+        for child in children {
+            let start = out.len();
+            child.push_leaves(out);
+            for leaf in &mut out[start..] {
+                leaf.name = format!("{} / {}", self.name, leaf.name);
+                if leaf.comparator.is_none() {
+                    leaf.comparator = self.comparator.clone();
+                }
+                if let Some(w) = self.weight {
+                    leaf.weight = Some(leaf.weight.unwrap_or(1.0) * w);
+                }
+            }
+        }
+    }
+
+    /// Whether this is the valgrind "Memory Leaks" subsection of a valgrind task.
+    pub fn is_memory_leak_section(&self) -> bool {
+        self.name.to_lowercase().contains("memory leak")
+            || self
+                .feedback
+                .as_ref()
+                .is_some_and(|f| f.to_lowercase().contains("valgrind"))
+    }
+
+    /// Whether this subsection is marked against a section of the task's output, rather than
+    /// its memory usage or (in a valgrind task) its valgrind report.
+    pub fn marks_output(&self, valgrind_task: bool) -> bool {
+        self.memory_thresholds.is_none() && !(valgrind_task && self.is_memory_leak_section())
+    }
+
+    /// Percentage of this subsection's value earned for a peak heap of `peak_bytes`, or
+    /// `None` if the subsection is not marked on memory usage.
+    pub fn memory_percent(&self, peak_bytes: u64) -> Option<f64> {
+        let thresholds = self.memory_thresholds.as_ref()?;
+        Some(
+            thresholds
+                .iter()
+                .filter(|t| peak_bytes <= t.max_peak_bytes)
+                .min_by_key(|t| t.max_peak_bytes)
+                .map(|t| t.percent)
+                .unwrap_or(0.0),
+        )
+    }
+}
+
+impl Task {
+    /// The subsections that are actually marked, in order: nested groups are flattened into
+    /// their leaves (see [`Subsection::subsections`]).
+    pub fn leaf_subsections(&self) -> Vec<Subsection> {
+        let mut out = Vec::with_capacity(self.subsections.len());
+        for sub in &self.subsections {
+            sub.push_leaves(&mut out);
+        }
+        out
+    }
 }
 
 impl MarkAllocator {
@@ -49,11 +159,57 @@ impl MarkAllocator {
     }
 }
 
-/// Read allocator.json as **normalized**.
+/// On-disk format of an allocator file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocatorFormat {
+    Json,
+    Yaml,
+}
+
+impl AllocatorFormat {
+    /// Format implied by the file extension (`.yaml`/`.yml`, anything else is JSON).
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") => {
+                Self::Yaml
+            }
+            _ => Self::Json,
+        }
+    }
+
+    pub fn parse(self, s: &str) -> Result<MarkAllocator, String> {
+        match self {
+            Self::Json => serde_json::from_str::<MarkAllocator>(s)
+                .map_err(|_| "Invalid allocator JSON (normalized expected)".to_string()),
+            Self::Yaml => serde_yaml::from_str::<MarkAllocator>(s)
+                .map_err(|_| "Invalid allocator YAML (normalized expected)".to_string()),
+        }
+    }
+
+    pub fn serialize(self, alloc: &MarkAllocator) -> Result<String, String> {
+        let out = match self {
+            Self::Json => serde_json::to_string_pretty(alloc).ok(),
+            Self::Yaml => serde_yaml::to_string(alloc).ok(),
+        };
+        out.ok_or_else(|| "Failed to serialize allocator".to_string())
+    }
+}
+
+/// The assignment's allocator file (`allocator.json`, `.yaml` or `.yml`), if one exists.
+pub fn allocator_file(module_id: i64, assignment_id: i64) -> Option<PathBuf> {
+    let dir = mark_allocator_dir(module_id, assignment_id);
+    ALLOCATOR_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|p| p.is_file())
+}
+
+/// Read the allocator (JSON or YAML, by extension) as **normalized**.
 pub fn load_allocator(module_id: i64, assignment_id: i64) -> Result<MarkAllocator, String> {
     use std::io::ErrorKind;
 
-    let path = mark_allocator_path(module_id, assignment_id);
+    let path = allocator_file(module_id, assignment_id)
+        .unwrap_or_else(|| mark_allocator_path(module_id, assignment_id));
 
     // Short, standardized I/O errors
     let s = match fs::read_to_string(&path) {
@@ -72,11 +228,11 @@ pub fn load_allocator(module_id: i64, assignment_id: i64) -> Result<MarkAllocato
     };
 
     // Short parse error
-    serde_json::from_str::<MarkAllocator>(&s)
-        .map_err(|_| "Invalid allocator JSON (normalized expected)".to_string())
+    AllocatorFormat::from_path(&path).parse(&s)
 }
 
-/// Save allocator.json as **normalized** (atomic-ish write).
+/// Save the allocator as **normalized** (atomic-ish write), keeping the format of an existing
+/// allocator file; new allocators are written as allocator.json.
 pub fn save_allocator(
     module_id: i64,
     assignment_id: i64,
@@ -90,9 +246,9 @@ pub fn save_allocator(
         _ => "Failed to prepare allocator directory".to_string(),
     })?;
 
-    let path = mark_allocator_path(module_id, assignment_id);
-    let pretty = serde_json::to_string_pretty(alloc)
-        .map_err(|_| "Failed to serialize allocator".to_string())?;
+    let path = allocator_file(module_id, assignment_id)
+        .unwrap_or_else(|| mark_allocator_path(module_id, assignment_id));
+    let pretty = AllocatorFormat::from_path(&path).serialize(alloc)?;
 
     let tmp = temp_path(&path);
     {
@@ -109,8 +265,8 @@ pub fn save_allocator(
     Ok(())
 }
 
-fn temp_path(final_path: &PathBuf) -> PathBuf {
-    let mut tmp = final_path.clone();
+fn temp_path(final_path: &Path) -> PathBuf {
+    let mut tmp = final_path.to_path_buf();
     let fname = final_path
         .file_name()
         .and_then(|s| s.to_str())
@@ -119,6 +275,30 @@ fn temp_path(final_path: &PathBuf) -> PathBuf {
     tmp
 }
 
+/// Splits memo output into its delimited sections: `(name, non-empty line count)` for every
+/// line containing `separator`, named by the text after it (`"Section {n}"` if blank). Lines
+/// before the first delimiter are not part of any section.
+pub(crate) fn memo_sections(content: &str, separator: &str) -> Vec<(String, usize)> {
+    let mut sections: Vec<(String, usize)> = Vec::new();
+    for line in content.lines() {
+        let split: Vec<_> = line.split(separator).collect();
+        if split.len() > 1 {
+            let name = split.last().unwrap().trim();
+            let name = if name.is_empty() {
+                format!("Section {}", sections.len() + 1)
+            } else {
+                name.to_string()
+            };
+            sections.push((name, 0));
+        } else if !line.trim().is_empty()
+            && let Some((_, count)) = sections.last_mut()
+        {
+            *count += 1;
+        }
+    }
+    sections
+}
+
 // Carry-over type used by generators
 #[derive(Debug, Clone)]
 pub struct TaskInfo {
@@ -152,7 +332,7 @@ pub async fn generate_allocator(
                 // Clamp to sane range [0.0, 0.95] to avoid division blow-ups (you can choose a different cap)
                 let w = w.clamp(0.0, 0.95);
 
-                (cfg.marking.deliminator, want_regex, w)
+                (cfg.marking.delimiter, want_regex, w)
             }
             Err(_) => ("###".to_string(), false, 0.10f64), // fallback: 10%
         };
@@ -175,53 +355,19 @@ pub async fn generate_allocator(
             let content = fs::read_to_string(maybe_path)
                 .map_err(|e| format!("Failed reading {:?}: {}", maybe_path, e))?;
 
-            let mut current_section = String::new();
-            let mut mark_counter: f64 = 0.0;
-
-            let mut section_counter = 0;
-
-            for line in content.lines() {
-                let split: Vec<_> = line.split(&separator).collect();
-                if split.len() > 1 {
-                    if !current_section.is_empty() {
-                        subsections.push(Subsection {
-                            name: current_section.clone(),
-                            value: mark_counter,
-                            regex: if want_regex {
-                                Some(vec![String::new(); mark_counter.max(0.0f64) as usize])
-                            } else {
-                                None
-                            },
-                            feedback: None,
-                        });
-                        task_value += mark_counter;
-                    }
-                    section_counter += 1;
-                    let name = split.last().unwrap().trim();
-                    current_section = if name.is_empty() {
-                        format!("Section {}", section_counter)
-                    } else {
-                        name.to_string()
-                    };
-                    mark_counter = 0.0f64;
-                } else if !line.trim().is_empty() {
-                    mark_counter += 1.0;
-                }
-            }
-
-            if !current_section.is_empty() {
-                let take_count = mark_counter.max(0.0).round() as usize;
+            for (name, lines) in memo_sections(&content, &separator) {
+                let value = lines as f64;
                 subsections.push(Subsection {
-                    name: current_section,
-                    value: mark_counter,
-                    regex: if want_regex {
-                        Some(std::iter::repeat(String::new()).take(take_count).collect())
-                    } else {
-                        None
-                    },
+                    name,
+                    value,
+                    regex: want_regex.then(|| vec![String::new(); lines]),
                     feedback: None,
+                    memory_thresholds: None,
+                    comparator: None,
+                    weight: None,
+                    subsections: None,
                 });
-                task_value += mark_counter;
+                task_value += value;
             }
 
             if info.valgrind {
@@ -240,6 +386,10 @@ pub async fn generate_allocator(
                 value: default_valgrind_mark_value,
                 regex: None,
                 feedback: Some("Check for memory leaks with Valgrind".to_string()),
+                memory_thresholds: None,
+                comparator: None,
+                weight: None,
+                subsections: None,
             });
         }
 
@@ -289,3 +439,103 @@ pub async fn generate_allocator(
     alloc.recompute_total();
     Ok(alloc)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(name: &str, value: f64) -> Subsection {
+        Subsection {
+            name: name.to_string(),
+            value,
+            regex: None,
+            feedback: None,
+            memory_thresholds: None,
+            comparator: None,
+            weight: None,
+            subsections: None,
+        }
+    }
+
+    #[test]
+    fn nested_subsections_flatten_into_weighted_leaves() {
+        let group = Subsection {
+            comparator: Some(MarkingScheme::Regex),
+            weight: Some(2.0),
+            subsections: Some(vec![
+                leaf("a", 1.0),
+                Subsection {
+                    comparator: Some(MarkingScheme::Exact),
+                    weight: Some(0.5),
+                    ..leaf("b", 2.0)
+                },
+            ]),
+            ..leaf("group", 2.0)
+        };
+        let task = Task {
+            task_number: 1,
+            name: "Task 1".into(),
+            value: 5.0,
+            code_coverage: None,
+            valgrind: None,
+            subsections: vec![leaf("plain", 1.0), group],
+        };
+
+        let leaves = task.leaf_subsections();
+        let summary: Vec<_> = leaves
+            .iter()
+            .map(|s| (s.name.as_str(), s.comparator.clone(), s.marks()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("plain", None, 1.0),
+                ("group / a", Some(MarkingScheme::Regex), 2.0),
+                ("group / b", Some(MarkingScheme::Exact), 2.0),
+            ]
+        );
+        assert_eq!(task.subsections.iter().map(|s| s.marks()).sum::<f64>(), 5.0);
+    }
+
+    #[test]
+    fn yaml_allocators_round_trip_and_old_json_still_parses() {
+        let yaml = "\
+generated_at: 2025-01-01T00:00:00Z
+total_value: 3
+tasks:
+  - task_number: 1
+    name: Task 1
+    value: 3
+    valgrind: false
+    subsections:
+      - name: Output
+        value: 3
+        comparator: percentage
+        subsections:
+          - { name: first, value: 1 }
+          - { name: second, value: 1, weight: 2 }
+";
+        let format = AllocatorFormat::from_path(Path::new("allocator.YML"));
+        assert_eq!(format, AllocatorFormat::Yaml);
+        let alloc = format.parse(yaml).unwrap();
+        let output = &alloc.tasks[0].subsections[0];
+        assert_eq!(output.comparator, Some(MarkingScheme::Percentage));
+        assert_eq!(output.children().unwrap()[1].marks(), 2.0);
+        assert_eq!(
+            format.parse(&format.serialize(&alloc).unwrap()).unwrap(),
+            alloc
+        );
+
+        // JSON written before these fields existed still loads, and is written back unchanged.
+        let json = r#"{"generated_at":"2025-01-01T00:00:00Z","total_value":1,"tasks":[
+            {"task_number":1,"name":"T","value":1,"valgrind":null,
+             "subsections":[{"name":"s","value":1,"regex":null,"feedback":null}]}]}"#;
+        let format = AllocatorFormat::from_path(Path::new("allocator.json"));
+        let alloc = format.parse(json).unwrap();
+        assert_eq!(
+            alloc.tasks[0].leaf_subsections(),
+            alloc.tasks[0].subsections
+        );
+        assert!(!format.serialize(&alloc).unwrap().contains("weight"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Checks an allocator against the memo output it will be marked with.
+//!
+//! The marker pairs the `n`-th subsection of a task with the `n`-th delimited section of its
+//! memo output and does not complain when the two drift apart (a regenerated memo, a
+//! hand-edited allocator); it just marks against the wrong or empty lines. These checks
+//! surface that up front.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::{MarkAllocator, memo_sections};
+
+/// One way an allocator disagrees with the memo output or with itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AllocatorMismatch {
+    /// The allocator file exists but could not be read or parsed.
+    InvalidAllocator { message: String },
+    /// A task marked on output has no (readable) memo output.
+    MissingMemoOutput { task_number: i64 },
+    /// There is memo output for a task the allocator does not list.
+    TaskNotInAllocator { task_number: i64 },
+    /// The memo output has a different number of sections than the task has subsections
+    /// marked on output (counting nested subsections by their leaves).
+    SubsectionCount {
+        task_number: i64,
+        allocator: usize,
+        memo: usize,
+    },
+    /// The task's subsection marks do not add up to its value.
+    TaskValue {
+        task_number: i64,
+        subsections: f64,
+        task: f64,
+    },
+    /// The task values do not add up to `total_value`.
+    TotalValue { tasks: f64, total: f64 },
+}
+
+/// Compares `alloc` with the memo output files, given as `(task_number, path)` pairs, split
+/// into sections on `delimiter`. Returns every mismatch found; empty if they agree.
+pub fn validate_against_outputs(
+    alloc: &MarkAllocator,
+    memo_outputs: &[(i64, PathBuf)],
+    delimiter: &str,
+) -> Vec<AllocatorMismatch> {
+    let mut mismatches = Vec::new();
+    let outputs: HashMap<i64, &PathBuf> = memo_outputs.iter().map(|(n, p)| (*n, p)).collect();
+
+    for task in &alloc.tasks {
+        if task.code_coverage.unwrap_or(false) {
+            continue;
+        }
+        let task_number = task.task_number;
+
+        let leaves = task.leaf_subsections();
+        let marks: f64 = task.subsections.iter().map(|s| s.marks()).sum();
+        if (marks - task.value).abs() > 1e-6 {
+            mismatches.push(AllocatorMismatch::TaskValue {
+                task_number,
+                subsections: marks,
+                task: task.value,
+            });
+        }
+
+        let Some(content) = outputs
+            .get(&task_number)
+            .and_then(|p| fs::read_to_string(p).ok())
+        else {
+            mismatches.push(AllocatorMismatch::MissingMemoOutput { task_number });
+            continue;
+        };
+        let sections = memo_sections(&content, delimiter).len();
+
+        // Subsections not marked on output (memory usage, valgrind leaks) have no section,
+        // which is fine as long as none of the output ones come after them.
+        let valgrind = task.valgrind.unwrap_or(false);
+        let needed = leaves
+            .iter()
+            .rposition(|s| s.marks_output(valgrind))
+            .map_or(0, |i| i + 1);
+        if sections < needed || sections > leaves.len() {
+            mismatches.push(AllocatorMismatch::SubsectionCount {
+                task_number,
+                allocator: if sections < needed {
+                    needed
+                } else {
+                    leaves.len()
+                },
+                memo: sections,
+            });
+        }
+    }
+
+    let mut extra: Vec<i64> = memo_outputs
+        .iter()
+        .map(|(n, _)| *n)
+        .filter(|n| !alloc.tasks.iter().any(|t| t.task_number == *n))
+        .collect();
+    extra.sort_unstable();
+    extra.dedup();
+    mismatches.extend(
+        extra
+            .into_iter()
+            .map(|task_number| AllocatorMismatch::TaskNotInAllocator { task_number }),
+    );
+
+    let tasks: f64 = alloc.tasks.iter().map(|t| t.value).sum();
+    if (tasks - alloc.total_value).abs() > 1e-6 {
+        mismatches.push(AllocatorMismatch::TotalValue {
+            tasks,
+            total: alloc.total_value,
+        });
+    }
+
+    mismatches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mark_allocator::{Subsection, Task};
+
+    fn sub(name: &str, value: f64) -> Subsection {
+        Subsection {
+            name: name.to_string(),
+            value,
+            regex: None,
+            feedback: None,
+            memory_thresholds: None,
+            comparator: None,
+            weight: None,
+            subsections: None,
+        }
+    }
+
+    fn task(task_number: i64, valgrind: bool, subsections: Vec<Subsection>) -> Task {
+        Task {
+            task_number,
+            name: format!("Task {task_number}"),
+            value: subsections.iter().map(|s| s.marks()).sum(),
+            code_coverage: Some(false),
+            valgrind: Some(valgrind),
+            subsections,
+        }
+    }
+
+    #[test]
+    fn reports_count_value_and_task_mismatches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let memo = |name: &str, body: &str| {
+            let path = tmp.path().join(name);
+            fs::write(&path, body).unwrap();
+            path
+        };
+        let two_sections = memo("1.txt", "Task 1\n###A\na\n###B\nb\n");
+
+        let leak = Subsection {
+            feedback: Some("Check for memory leaks with Valgrind".into()),
+            ..sub("Memory Leaks", 5.0)
+        };
+        let mut off_value = task(3, false, vec![sub("A", 1.0), sub("B", 1.0)]);
+        off_value.value = 3.0;
+        let mut alloc = MarkAllocator::new_now(vec![
+            // Matches: the trailing leak subsection needs no section.
+            task(1, true, vec![sub("A", 1.0), sub("B", 1.0), leak]),
+            // One section short.
+            task(2, false, vec![sub("A", 1.0), sub("B", 1.0), sub("C", 1.0)]),
+            off_value,
+            task(4, false, vec![sub("A", 1.0)]),
+        ]);
+        alloc.total_value += 1.0;
+
+        let outputs = vec![
+            (1, two_sections.clone()),
+            (2, two_sections.clone()),
+            (3, two_sections.clone()),
+            (5, two_sections),
+        ];
+        assert_eq!(
+            validate_against_outputs(&alloc, &outputs, "###"),
+            vec![
+                AllocatorMismatch::SubsectionCount {
+                    task_number: 2,
+                    allocator: 3,
+                    memo: 2,
+                },
+                AllocatorMismatch::TaskValue {
+                    task_number: 3,
+                    subsections: 2.0,
+                    task: 3.0,
+                },
+                AllocatorMismatch::MissingMemoOutput { task_number: 4 },
+                AllocatorMismatch::TaskNotInAllocator { task_number: 5 },
+                AllocatorMismatch::TotalValue {
+                    tasks: 14.0,
+                    total: 15.0,
+                },
+            ]
+        );
+    }
+}
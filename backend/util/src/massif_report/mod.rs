@@ -0,0 +1,175 @@
+use chrono::Utc;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MassifTask {
+    pub task_number: i64,
+    /// Largest heap size (useful plus allocator overhead) over all snapshots, in bytes.
+    pub peak_heap_bytes: u64,
+    /// Number of snapshots the peak was taken from.
+    pub snapshots: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MassifReport {
+    pub generated_at: String,
+    pub total_tasks: usize,
+    pub tasks: Vec<MassifTask>,
+}
+
+impl MassifReport {
+    pub fn task(&self, task_number: i64) -> Option<&MassifTask> {
+        self.tasks.iter().find(|t| t.task_number == task_number)
+    }
+}
+
+pub struct MassifProcessor;
+
+impl MassifProcessor {
+    /// Takes an array of full task outputs, each associated with a task number, and returns
+    /// the report of the tasks whose output holds massif data, or `None` if none does.
+    ///
+    /// Massif writes its profile to `massif.out.<pid>` rather than to the output, so the task
+    /// command has to print it, either raw (`cat massif.out.*`) or through `ms_print`; both
+    /// are understood.
+    pub fn process_report(task_contents: &[(i64, String)]) -> Result<Option<String>, String> {
+        // Raw massif.out snapshots: `mem_heap_B=...` followed by `mem_heap_extra_B=...`.
+        let re_heap = Regex::new(r"(?m)^\s*mem_heap_B=(\d+)\s*$").map_err(|e| e.to_string())?;
+        let re_heap_extra =
+            Regex::new(r"(?m)^\s*mem_heap_extra_B=(\d+)\s*$").map_err(|e| e.to_string())?;
+        // ms_print snapshot rows: n, time, total, useful-heap, extra-heap, stacks.
+        let re_ms_print_row =
+            Regex::new(r"(?m)^\s*\d+\s+[0-9,]+\s+[0-9,]+\s+([0-9,]+)\s+([0-9,]+)\s+[0-9,]+\s*$")
+                .map_err(|e| e.to_string())?;
+
+        let mut tasks: Vec<MassifTask> = Vec::new();
+
+        for (task_number, content) in task_contents.iter() {
+            let mut heaps: Vec<u64> = re_heap
+                .captures_iter(content)
+                .zip(re_heap_extra.captures_iter(content))
+                .map(|(heap, extra)| number(&heap[1]) + number(&extra[1]))
+                .collect();
+
+            if heaps.is_empty()
+                && let Some(table) = content.find("useful-heap(B)")
+            {
+                heaps = re_ms_print_row
+                    .captures_iter(&content[table..])
+                    .map(|row| number(&row[1]) + number(&row[2]))
+                    .collect();
+            }
+
+            if let Some(peak) = heaps.iter().max() {
+                tasks.push(MassifTask {
+                    task_number: *task_number,
+                    peak_heap_bytes: *peak,
+                    snapshots: heaps.len(),
+                });
+            }
+        }
+
+        if tasks.is_empty() {
+            return Ok(None);
+        }
+
+        let report = MassifReport {
+            generated_at: Utc::now().to_rfc3339(),
+            total_tasks: tasks.len(),
+            tasks,
+        };
+
+        serde_json::to_string_pretty(&report)
+            .map(Some)
+            .map_err(|e| format!("Failed to serialize Massif report: {}", e))
+    }
+}
+
+/// Parses a byte count as printed by massif or `ms_print` (with `,` separators).
+fn number(s: &str) -> u64 {
+    s.replace(',', "").parse().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(outputs: Vec<(i64, &str)>) -> Option<MassifReport> {
+        let outputs: Vec<(i64, String)> = outputs
+            .into_iter()
+            .map(|(n, s)| (n, s.to_string()))
+            .collect();
+        MassifProcessor::process_report(&outputs)
+            .unwrap()
+            .map(|json| serde_json::from_str(&json).unwrap())
+    }
+
+    #[test]
+    fn takes_the_peak_of_raw_massif_snapshots() {
+        let report = parse(vec![(
+            2,
+            r#"desc: --time-unit=B
+cmd: ./main task2
+time_unit: B
+#-----------
+snapshot=0
+#-----------
+time=0
+mem_heap_B=0
+mem_heap_extra_B=0
+mem_stacks_B=0
+heap_tree=empty
+#-----------
+snapshot=1
+#-----------
+time=74752
+mem_heap_B=72704
+mem_heap_extra_B=8
+mem_stacks_B=0
+heap_tree=peak
+#-----------
+snapshot=2
+#-----------
+time=75776
+mem_heap_B=1024
+mem_heap_extra_B=8
+mem_stacks_B=0
+heap_tree=empty
+"#,
+        )])
+        .unwrap();
+
+        let task = report.task(2).unwrap();
+        assert_eq!(task.peak_heap_bytes, 72712);
+        assert_eq!(task.snapshots, 3);
+    }
+
+    #[test]
+    fn reads_ms_print_tables_and_skips_tasks_without_a_profile() {
+        let report = parse(vec![
+            (
+                1,
+                r#"--------------------------------------------------------------------------------
+Command:            ./main task1
+--------------------------------------------------------------------------------
+--------------------------------------------------------------------------------
+  n        time(i)         total(B)   useful-heap(B) extra-heap(B)    stacks(B)
+--------------------------------------------------------------------------------
+  0              0                0                0             0            0
+  1        184,502           73,736           72,704         1,032            0
+  2      1,203,990          107,560          104,000         3,560            0
+98.44% (72,704B) (heap allocation functions) malloc/new/new[], --alloc-fns, etc.
+"#,
+            ),
+            (2, "###Task2Subtask1\nHello\n"),
+        ])
+        .unwrap();
+
+        assert_eq!(report.total_tasks, 1);
+        assert_eq!(report.task(1).unwrap().peak_heap_bytes, 107_560);
+        assert!(report.task(2).is_none());
+
+        assert!(parse(vec![(3, "no profile here")]).is_none());
+    }
+}
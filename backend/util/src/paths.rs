@@ -50,6 +50,11 @@ pub fn module_dir(module_id: i64) -> PathBuf {
     storage_root().join(format!("module_{module_id}"))
 }
 
+/// Path to a module's default assignment config:  {STORAGE_ROOT}/module_{module_id}/config.json
+pub fn module_config_path(module_id: i64) -> PathBuf {
+    module_dir(module_id).join("config.json")
+}
+
 /// Path to a user's folder:  {STORAGE_ROOT}/users/user_{user_id}
 pub fn user_dir(user_id: i64) -> PathBuf {
     storage_root().join("users").join(format!("user_{user_id}"))
@@ -66,6 +71,23 @@ pub fn user_profile_path(user_id: i64, filename: &str) -> PathBuf {
     user_profile_dir(user_id).join(filename)
 }
 
+/// A chunked upload's folder:  {STORAGE_ROOT}/uploads/upload_{upload_id}
+pub fn upload_dir(upload_id: i64) -> PathBuf {
+    storage_root()
+        .join("uploads")
+        .join(format!("upload_{upload_id}"))
+}
+
+/// One received part of a chunked upload:  .../uploads/upload_{id}/part_{part_number}
+pub fn upload_part_path(upload_id: i64, part_number: i32) -> PathBuf {
+    upload_dir(upload_id).join(format!("part_{part_number}"))
+}
+
+/// The assembled file of a completed chunked upload:  .../uploads/upload_{id}/file
+pub fn upload_file_path(upload_id: i64) -> PathBuf {
+    upload_dir(upload_id).join("file")
+}
+
 // ─── Directory helpers for assignments ──────────────────────────────
 
 // Top-level:  {STORAGE_ROOT}/module_{module_id}/assignment_{assignment_id}
@@ -153,6 +175,23 @@ pub fn moss_archive_zip_path(module_id: i64, assignment_id: i64, archive_id: &st
     moss_archive_dir(module_id, assignment_id, archive_id).join("archive.zip")
 }
 
+// Local copies of a report's MOSS match pages: .../moss_archives/{archive_id}/matches
+pub fn moss_matches_dir(module_id: i64, assignment_id: i64, archive_id: &str) -> PathBuf {
+    moss_archive_dir(module_id, assignment_id, archive_id).join("matches")
+}
+
+// Starter/skeleton code excluded from plagiarism runs: .../plagiarism_base
+pub fn plagiarism_base_dir(module_id: i64, assignment_id: i64) -> PathBuf {
+    assignment_dir(module_id, assignment_id).join("plagiarism_base")
+}
+
+// Evidence attached to a plagiarism case: .../plagiarism_evidence/case_{case_id}
+pub fn plagiarism_evidence_dir(module_id: i64, assignment_id: i64, case_id: i64) -> PathBuf {
+    assignment_dir(module_id, assignment_id)
+        .join("plagiarism_evidence")
+        .join(format!("case_{case_id}"))
+}
+
 // Overwrite files
 pub fn overwrite_files_dir(module_id: i64, assignment_id: i64) -> PathBuf {
     assignment_dir(module_id, assignment_id).join("overwrite_files")
@@ -231,6 +270,39 @@ pub fn submission_output_path(
     submission_output_dir(module_id, assignment_id, user_id, attempt).join(filename)
 }
 
+/// `/output` files kept from the attempt's task runs: `.../attempt_{n}/artifacts`
+pub fn submission_artifacts_dir(
+    module_id: i64,
+    assignment_id: i64,
+    user_id: i64,
+    attempt: i64,
+) -> PathBuf {
+    attempt_dir(module_id, assignment_id, user_id, attempt).join("artifacts")
+}
+/// Files one task wrote to `/output` that matched its artifact patterns:
+/// `.../attempt_{n}/artifacts/task_{task_number}`
+pub fn submission_task_artifacts_dir(
+    module_id: i64,
+    assignment_id: i64,
+    user_id: i64,
+    attempt: i64,
+    task_number: i64,
+) -> PathBuf {
+    submission_artifacts_dir(module_id, assignment_id, user_id, attempt)
+        .join(format!("task_{task_number}"))
+}
+
+// Content-addressed blobs
+pub fn content_blobs_dir() -> PathBuf {
+    storage_root().join("blobs")
+}
+/// Shared copy of a deduplicated file: `blobs/{first two hex chars}/{sha256}`
+pub fn content_blob_path(hash: &str) -> PathBuf {
+    content_blobs_dir()
+        .join(&hash[..hash.len().min(2)])
+        .join(hash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
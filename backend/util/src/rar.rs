@@ -0,0 +1,67 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Leading bytes of every RAR archive (v1.5 through v5).
+const RAR_MAGIC: &[u8] = b"Rar!\x1a\x07";
+
+/// Returns true if `bytes` start with the RAR signature.
+pub fn is_rar(bytes: &[u8]) -> bool {
+    bytes.starts_with(RAR_MAGIC)
+}
+
+/// Extracts a RAR archive into `destination_dir`.
+///
+/// There is no maintained pure-Rust RAR decoder, so this pipes the archive through libarchive's
+/// `bsdtar`, which must be on the `PATH`. bsdtar refuses absolute paths and `..` entries by
+/// default, so nothing is written outside `destination_dir`.
+pub fn extract_rar(bytes: &[u8], destination_dir: &Path) -> Result<(), String> {
+    let mut child = Command::new("bsdtar")
+        .arg("-xf")
+        .arg("-")
+        .arg("-C")
+        .arg(destination_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run bsdtar (required for .rar archives): {e}"))?;
+
+    // Feed stdin from another thread so a chatty stderr can't deadlock us.
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = bytes.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to wait for bsdtar: {e}"))?;
+    // bsdtar may stop reading early on a corrupt archive; its exit status says why.
+    let _ = writer.join();
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract rar archive: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_rar_signature() {
+        assert!(is_rar(b"Rar!\x1a\x07\x00rest"));
+        assert!(is_rar(b"Rar!\x1a\x07\x01\x00rest"));
+        assert!(!is_rar(b"PK\x03\x04"));
+        assert!(!is_rar(b"Rar"));
+    }
+
+    #[test]
+    fn corrupt_rar_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(extract_rar(b"Rar!\x1a\x07\x00garbage", dir.path()).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+//! In-memory token bucket rate limiting.
+//!
+//! Each key (a user id or an IP address) gets a bucket holding up to `capacity` tokens that
+//! refills at a steady rate; a request takes one token, and is refused while the bucket is
+//! empty. Buckets live in this process only, so each API instance limits independently.
+//!
+//! Buckets that have refilled are dropped every [`PRUNE_INTERVAL`], and at most [`MAX_BUCKETS`]
+//! are kept, so a flood from many addresses can't grow the map without bound.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How often buckets that have refilled are dropped.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Buckets kept at most; past this, the least recently used half is dropped.
+const MAX_BUCKETS: usize = 100_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    pruned: Instant,
+}
+
+/// A token bucket per key.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    /// Tokens added per second.
+    refill_per_sec: f64,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Allows `per_minute` requests a minute per key, in bursts of up to `per_minute`.
+    pub fn per_minute(per_minute: u32) -> Self {
+        let per_minute = per_minute.max(1) as f64;
+        Self {
+            capacity: per_minute,
+            refill_per_sec: per_minute / 60.0,
+            buckets: Mutex::new(Buckets {
+                by_key: HashMap::new(),
+                pruned: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token from `key`'s bucket, or returns how long until one is available.
+    pub fn check(&self, key: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if now.saturating_duration_since(buckets.pruned) >= PRUNE_INTERVAL {
+            self.prune(&mut buckets.by_key, now);
+            buckets.pruned = now;
+        }
+        if buckets.by_key.len() >= MAX_BUCKETS && !buckets.by_key.contains_key(key) {
+            evict_least_recent(&mut buckets.by_key);
+        }
+
+        let bucket = buckets.by_key.entry(key.to_string()).or_insert(Bucket {
+            tokens: self.capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let wait = (1.0 - bucket.tokens) / self.refill_per_sec;
+            Err(Duration::from_secs_f64(wait))
+        }
+    }
+
+    /// Drops the buckets that would be full by now; they behave the same as no bucket.
+    fn prune(&self, buckets: &mut HashMap<String, Bucket>, now: Instant) {
+        let full_after = self.capacity / self.refill_per_sec;
+        buckets.retain(|_, b| now.saturating_duration_since(b.updated).as_secs_f64() < full_after);
+    }
+}
+
+/// Drops the least recently used half of `buckets`. Their keys start over with a full bucket,
+/// which only lets the oldest callers through sooner than they should be.
+fn evict_least_recent(buckets: &mut HashMap<String, Bucket>) {
+    let mut updated: Vec<Instant> = buckets.values().map(|b| b.updated).collect();
+    let mid = updated.len() / 2;
+    let (_, cutoff, _) = updated.select_nth_unstable(mid);
+    let cutoff = *cutoff;
+    buckets.retain(|_, b| b.updated >= cutoff);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_up_to_capacity_then_refills() {
+        let limiter = RateLimiter::per_minute(3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.check("u1", start).is_ok());
+        }
+        let wait = limiter.check("u1", start).unwrap_err();
+        assert_eq!(wait.as_secs(), 20);
+
+        // Other keys have their own bucket
+        assert!(limiter.check("u2", start).is_ok());
+
+        assert!(
+            limiter
+                .check("u1", start + Duration::from_secs(19))
+                .is_err()
+        );
+        assert!(limiter.check("u1", start + Duration::from_secs(21)).is_ok());
+        assert!(
+            limiter
+                .check("u1", start + Duration::from_secs(21))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn prune_keeps_partly_drained_buckets() {
+        let limiter = RateLimiter::per_minute(60);
+        let start = Instant::now();
+        limiter.check("idle", start).unwrap();
+        limiter
+            .check("busy", start + Duration::from_secs(120))
+            .unwrap();
+
+        let mut buckets = limiter.buckets.lock().unwrap();
+        limiter.prune(&mut buckets.by_key, start + Duration::from_secs(120));
+        assert!(!buckets.by_key.contains_key("idle"));
+        assert!(buckets.by_key.contains_key("busy"));
+    }
+
+    #[test]
+    fn prunes_on_a_timer_and_caps_the_map() {
+        let limiter = RateLimiter::per_minute(60);
+        let start = Instant::now();
+        limiter.check("idle", start).unwrap();
+        limiter
+            .check("busy", start + Duration::from_secs(120))
+            .unwrap();
+        assert!(!limiter.buckets.lock().unwrap().by_key.contains_key("idle"));
+
+        let mut buckets: HashMap<String, Bucket> = (0..10)
+            .map(|i| {
+                let bucket = Bucket {
+                    tokens: 0.0,
+                    updated: start + Duration::from_secs(i),
+                };
+                (format!("ip:{i}"), bucket)
+            })
+            .collect();
+        evict_least_recent(&mut buckets);
+        assert_eq!(buckets.len(), 5);
+        assert!(buckets.contains_key("ip:9"));
+        assert!(!buckets.contains_key("ip:0"));
+    }
+}
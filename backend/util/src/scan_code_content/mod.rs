@@ -1,37 +1,85 @@
+mod patterns;
+
 use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::fs;
 use std::io::{Cursor, Read};
+use std::path::Path;
 use tar::Archive;
 use zip::ZipArchive;
 
 use crate::execution_config::ExecutionConfig;
+use crate::rar;
+use crate::source_files::is_source_file;
+use patterns::{Rule, first_match};
+
+/// Where a `dissalowed_code` entry matched.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisallowedMatch {
+    /// Path of the file inside the archive (or the uploaded file's name).
+    pub file: String,
+    /// 1-based line number.
+    pub line: usize,
+    /// The entry that matched, as written in the config.
+    pub pattern: String,
+}
 
 #[derive(Debug, PartialEq)]
-enum ArchiveFormat {
+pub(crate) enum ArchiveFormat {
     Zip,
     Tar,
     TarGz,
     Gz,
+    SevenZ,
+    Rar,
+}
+
+/// Leading bytes of a 7z archive.
+const SEVEN_Z_MAGIC: &[u8] = &[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C];
+
+/// Bytes checked for NULs when deciding whether a file is binary.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+/// Checks that a `dissalowed_code` entry is usable (e.g. that a `re:` pattern compiles).
+pub fn validate_pattern(entry: &str) -> Result<(), String> {
+    Rule::parse(entry).map(|_| ())
 }
 
-fn reader_contains_disallowed<R: Read>(
+fn compile_rules(config: &ExecutionConfig) -> Result<Vec<Rule>, String> {
+    let mut rules = Vec::new();
+    for entry in &config.marking.dissalowed_code {
+        rules.extend(Rule::parse(entry)?);
+    }
+    Ok(rules)
+}
+
+/// Scans one file. Binary files (a NUL in the first few KB) are skipped; other non-UTF-8
+/// bytes are decoded lossily.
+fn scan_reader<R: Read>(
     mut reader: R,
-    config: &ExecutionConfig,
-) -> Result<bool, String> {
+    name: &str,
+    rules: &[Rule],
+) -> Result<Option<DisallowedMatch>, String> {
     let mut buf = Vec::new();
     reader
         .read_to_end(&mut buf)
         .map_err(|e| format!("Failed to read file contents: {e}"))?;
 
-    let text = String::from_utf8_lossy(&buf);
-    for dis in &config.marking.dissalowed_code {
-        if !dis.is_empty() && text.contains(dis) {
-            return Ok(true);
-        }
+    if buf[..buf.len().min(BINARY_SNIFF_LEN)].contains(&0) {
+        return Ok(None);
     }
-    Ok(false)
+
+    let text = String::from_utf8_lossy(&buf);
+    Ok(
+        first_match(name, &text, rules).map(|(line, rule)| DisallowedMatch {
+            file: name.to_string(),
+            line,
+            pattern: rule.source.clone(),
+        }),
+    )
 }
 
-fn detect_archive_format(bytes: &[u8]) -> Result<ArchiveFormat, String> {
+pub(crate) fn detect_archive_format(bytes: &[u8]) -> Result<ArchiveFormat, String> {
     if bytes.len() < 4 {
         return Err("File too small to determine format".to_string());
     }
@@ -40,10 +88,16 @@ fn detect_archive_format(bytes: &[u8]) -> Result<ArchiveFormat, String> {
         return Ok(ArchiveFormat::Zip);
     }
 
-    if bytes.len() > 262 {
-        if &bytes[257..262] == b"ustar" {
-            return Ok(ArchiveFormat::Tar);
-        }
+    if bytes.starts_with(SEVEN_Z_MAGIC) {
+        return Ok(ArchiveFormat::SevenZ);
+    }
+
+    if rar::is_rar(bytes) {
+        return Ok(ArchiveFormat::Rar);
+    }
+
+    if bytes.len() > 262 && &bytes[257..262] == b"ustar" {
+        return Ok(ArchiveFormat::Tar);
     }
 
     if bytes[0] == 0x1F && bytes[1] == 0x8B {
@@ -51,10 +105,11 @@ fn detect_archive_format(bytes: &[u8]) -> Result<ArchiveFormat, String> {
         let mut decoder = GzDecoder::new(cursor);
         let mut decompressed = Vec::new();
 
-        if decoder.read_to_end(&mut decompressed).is_ok() && decompressed.len() > 262 {
-            if &decompressed[257..262] == b"ustar" {
-                return Ok(ArchiveFormat::TarGz);
-            }
+        if decoder.read_to_end(&mut decompressed).is_ok()
+            && decompressed.len() > 262
+            && &decompressed[257..262] == b"ustar"
+        {
+            return Ok(ArchiveFormat::TarGz);
         }
 
         return Ok(ArchiveFormat::Gz);
@@ -63,7 +118,9 @@ fn detect_archive_format(bytes: &[u8]) -> Result<ArchiveFormat, String> {
     Err("Unsupported archive format".to_string())
 }
 
-fn scan_zip_archive(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, String> {
+type ScanResult = Result<Option<DisallowedMatch>, String>;
+
+fn scan_zip_archive(bytes: &[u8], rules: &[Rule]) -> ScanResult {
     let cursor = Cursor::new(bytes);
     let mut archive =
         ZipArchive::new(cursor).map_err(|e| format!("Failed to read zip archive: {e}"))?;
@@ -77,65 +134,103 @@ fn scan_zip_archive(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, Stri
             continue;
         }
 
-        // read raw bytes, lossy decode
-        if reader_contains_disallowed(&mut file, config)? {
-            return Ok(true);
+        let name = file.name().to_string();
+        if let Some(found) = scan_reader(&mut file, &name, rules)? {
+            return Ok(Some(found));
         }
     }
-    Ok(false)
+    Ok(None)
 }
 
-fn scan_tar_archive(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, String> {
-    let cursor = Cursor::new(bytes);
-    let mut archive = Archive::new(cursor);
-
+fn scan_tar_entries<R: Read>(mut archive: Archive<R>, rules: &[Rule], kind: &str) -> ScanResult {
     for entry in archive
         .entries()
-        .map_err(|e| format!("Failed to read tar entries: {e}"))?
+        .map_err(|e| format!("Failed to read {kind} entries: {e}"))?
     {
-        let mut entry = entry.map_err(|e| format!("Failed to read tar entry: {e}"))?;
+        let mut entry = entry.map_err(|e| format!("Failed to read {kind} entry: {e}"))?;
         if entry.header().entry_type().is_dir() {
             continue;
         }
 
-        if reader_contains_disallowed(&mut entry, config)? {
-            return Ok(true);
+        let name = entry
+            .path()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if let Some(found) = scan_reader(&mut entry, &name, rules)? {
+            return Ok(Some(found));
         }
     }
-    Ok(false)
+    Ok(None)
 }
 
-fn scan_tar_gz_archive(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, String> {
-    let cursor = Cursor::new(bytes);
-    let decoder = GzDecoder::new(cursor);
-    let mut archive = Archive::new(decoder);
+fn scan_tar_archive(bytes: &[u8], rules: &[Rule]) -> ScanResult {
+    scan_tar_entries(Archive::new(Cursor::new(bytes)), rules, "tar")
+}
 
-    for entry in archive
-        .entries()
-        .map_err(|e| format!("Failed to read tar.gz entries: {e}"))?
-    {
-        let mut entry = entry.map_err(|e| format!("Failed to read tar.gz entry: {e}"))?;
-        if entry.header().entry_type().is_dir() {
-            continue;
-        }
+fn scan_tar_gz_archive(bytes: &[u8], rules: &[Rule]) -> ScanResult {
+    let decoder = GzDecoder::new(Cursor::new(bytes));
+    scan_tar_entries(Archive::new(decoder), rules, "tar.gz")
+}
 
-        if reader_contains_disallowed(&mut entry, config)? {
-            return Ok(true);
-        }
-    }
-    Ok(false)
+fn scan_gz_file(bytes: &[u8], rules: &[Rule]) -> ScanResult {
+    let mut decoder = GzDecoder::new(Cursor::new(bytes));
+    let name = decoder
+        .header()
+        .and_then(|h| h.filename())
+        .map(|n| String::from_utf8_lossy(n).into_owned())
+        .unwrap_or_else(|| "file".to_string());
+    scan_reader(&mut decoder, &name, rules)
 }
 
-fn scan_gz_file(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, String> {
-    let cursor = Cursor::new(bytes);
-    let mut decoder = GzDecoder::new(cursor);
-    if reader_contains_disallowed(&mut decoder, config)? {
-        return Ok(true);
+fn scan_7z_archive(bytes: &[u8], rules: &[Rule]) -> ScanResult {
+    let mut archive = sevenz_rust::SevenZReader::new(
+        Cursor::new(bytes),
+        bytes.len() as u64,
+        sevenz_rust::Password::empty(),
+    )
+    .map_err(|e| format!("Failed to read 7z archive: {e}"))?;
+
+    let mut found = None;
+    archive
+        .for_each_entries(|entry, reader| {
+            if found.is_some() || entry.is_directory() {
+                return Ok(found.is_none());
+            }
+            found = scan_reader(reader, entry.name(), rules).map_err(sevenz_rust::Error::other)?;
+            Ok(found.is_none())
+        })
+        .map_err(|e| format!("Failed to read 7z entry: {e}"))?;
+    Ok(found)
+}
+
+fn scan_rar_archive(bytes: &[u8], rules: &[Rule]) -> ScanResult {
+    let dir = tempfile::tempdir().map_err(|e| format!("Failed to create temp dir: {e}"))?;
+    rar::extract_rar(bytes, dir.path())?;
+    scan_dir(dir.path(), dir.path(), rules)
+}
+
+fn scan_dir(root: &Path, dir: &Path, rules: &[Rule]) -> ScanResult {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read extracted files: {e}"))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read extracted files: {e}"))?
+            .path();
+        let found = if path.is_dir() {
+            scan_dir(root, &path, rules)?
+        } else {
+            let file =
+                fs::File::open(&path).map_err(|e| format!("Failed to open extracted file: {e}"))?;
+            let name = path.strip_prefix(root).unwrap_or(&path).to_string_lossy();
+            scan_reader(file, &name, rules)?
+        };
+        if found.is_some() {
+            return Ok(found);
+        }
     }
-    Ok(false)
+    Ok(None)
 }
 
-/// Scans an archive (ZIP, TAR, TGZ, or GZ) for any disallowed code patterns.
+/// Scans an archive (ZIP, TAR, TGZ, GZ, 7Z, or RAR) for any disallowed code patterns.
 ///
 /// # Arguments
 ///
@@ -144,9 +239,9 @@ fn scan_gz_file(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, String>
 ///
 /// # Returns
 ///
-/// * `Ok(true)` if any file in the archive contains one of the `dissalowed_code` strings.
-/// * `Ok(false)` if none of the files contain disallowed code.
-/// * `Err(String)` if the archive data could not be read or parsed.
+/// * `Ok(Some(match))` with the file, line and entry of the first match.
+/// * `Ok(None)` if none of the files contain disallowed code.
+/// * `Err(String)` if the archive data could not be read or parsed, or an entry is invalid.
 ///
 /// # Supported Formats
 ///
@@ -154,29 +249,45 @@ fn scan_gz_file(bytes: &[u8], config: &ExecutionConfig) -> Result<bool, String>
 /// - TAR archives (.tar)
 /// - Compressed TAR archives (.tar.gz, .tgz)
 /// - GZIP compressed files (.gz)
+/// - 7-Zip archives (.7z)
+/// - RAR archives (.rar), extracted with `bsdtar`
 ///
 /// # Behavior
 ///
 /// - Automatically detects archive format using magic bytes
 /// - Iterates over all entries in the archive
-/// - Skips directories, only inspects files
-/// - Reads file contents as UTF-8 text
-/// - Stops scanning and returns `true` immediately on the first match
+/// - Skips directories and binary files, only inspects text files
+/// - Entries are substrings, `re:` regexes, and/or `code:` (ignore comments and strings);
+///   see [`patterns`]
+/// - Stops scanning and returns the first match
 ///
-pub fn contains_dissalowed_code(
-    archive_bytes: &[u8],
-    config: &ExecutionConfig,
-) -> Result<bool, String> {
+pub fn find_dissalowed_code(archive_bytes: &[u8], config: &ExecutionConfig) -> ScanResult {
+    let rules = compile_rules(config)?;
     let format = detect_archive_format(archive_bytes)?;
 
     match format {
-        ArchiveFormat::Zip => scan_zip_archive(archive_bytes, config),
-        ArchiveFormat::Tar => scan_tar_archive(archive_bytes, config),
-        ArchiveFormat::TarGz => scan_tar_gz_archive(archive_bytes, config),
-        ArchiveFormat::Gz => scan_gz_file(archive_bytes, config),
+        ArchiveFormat::Zip => scan_zip_archive(archive_bytes, &rules),
+        ArchiveFormat::Tar => scan_tar_archive(archive_bytes, &rules),
+        ArchiveFormat::TarGz => scan_tar_gz_archive(archive_bytes, &rules),
+        ArchiveFormat::Gz => scan_gz_file(archive_bytes, &rules),
+        ArchiveFormat::SevenZ => scan_7z_archive(archive_bytes, &rules),
+        ArchiveFormat::Rar => scan_rar_archive(archive_bytes, &rules),
     }
 }
 
+/// Like [`find_dissalowed_code`], but also accepts a plain source file (e.g. `main.py`),
+/// which is scanned as-is instead of being unpacked.
+pub fn find_dissalowed_code_in_file(
+    file_name: &str,
+    bytes: &[u8],
+    config: &ExecutionConfig,
+) -> ScanResult {
+    if is_source_file(file_name) {
+        return scan_reader(bytes, file_name, &compile_rules(config)?);
+    }
+    find_dissalowed_code(bytes, config)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,8 +366,10 @@ mod tests {
         config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
 
         let zip_bytes = std::fs::read(&zip_path).unwrap();
-        let result = contains_dissalowed_code(&zip_bytes, &config).unwrap();
-        assert!(result, "Should detect dissalowed code in the zip");
+        let result = find_dissalowed_code(&zip_bytes, &config).unwrap();
+        assert!(result.is_some(), "Should detect dissalowed code in the zip");
+        let found = result.unwrap();
+        assert_eq!((found.file.as_str(), found.line), ("file2.rs", 1));
     }
 
     #[test]
@@ -276,8 +389,11 @@ mod tests {
         config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
 
         let zip_bytes = std::fs::read(&zip_path).unwrap();
-        let result = contains_dissalowed_code(&zip_bytes, &config).unwrap();
-        assert!(!result, "Should not detect dissalowed code in the zip");
+        let result = find_dissalowed_code(&zip_bytes, &config).unwrap();
+        assert!(
+            result.is_none(),
+            "Should not detect dissalowed code in the zip"
+        );
     }
 
     #[test]
@@ -297,8 +413,8 @@ mod tests {
         config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
 
         let tar_bytes = std::fs::read(&tar_path).unwrap();
-        let result = contains_dissalowed_code(&tar_bytes, &config).unwrap();
-        assert!(result, "Should detect dissalowed code in the tar");
+        let result = find_dissalowed_code(&tar_bytes, &config).unwrap();
+        assert!(result.is_some(), "Should detect dissalowed code in the tar");
     }
 
     #[test]
@@ -318,8 +434,11 @@ mod tests {
         config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
 
         let tar_gz_bytes = std::fs::read(&tar_gz_path).unwrap();
-        let result = contains_dissalowed_code(&tar_gz_bytes, &config).unwrap();
-        assert!(result, "Should detect dissalowed code in the tar.gz");
+        let result = find_dissalowed_code(&tar_gz_bytes, &config).unwrap();
+        assert!(
+            result.is_some(),
+            "Should detect dissalowed code in the tar.gz"
+        );
     }
 
     #[test]
@@ -333,8 +452,11 @@ mod tests {
         config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
 
         let gz_bytes = std::fs::read(&gz_path).unwrap();
-        let result = contains_dissalowed_code(&gz_bytes, &config).unwrap();
-        assert!(result, "Should detect dissalowed code in the gz file");
+        let result = find_dissalowed_code(&gz_bytes, &config).unwrap();
+        assert!(
+            result.is_some(),
+            "Should detect dissalowed code in the gz file"
+        );
     }
 
     #[test]
@@ -348,8 +470,11 @@ mod tests {
         config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
 
         let gz_bytes = std::fs::read(&gz_path).unwrap();
-        let result = contains_dissalowed_code(&gz_bytes, &config).unwrap();
-        assert!(!result, "Should not detect dissalowed code in the gz file");
+        let result = find_dissalowed_code(&gz_bytes, &config).unwrap();
+        assert!(
+            result.is_none(),
+            "Should not detect dissalowed code in the gz file"
+        );
     }
 
     #[test]
@@ -362,9 +487,9 @@ mod tests {
         let config = ExecutionConfig::default_config(); // dissalowed_code is empty
 
         let zip_bytes = std::fs::read(&zip_path).unwrap();
-        let result = contains_dissalowed_code(&zip_bytes, &config).unwrap();
+        let result = find_dissalowed_code(&zip_bytes, &config).unwrap();
         assert!(
-            !result,
+            result.is_none(),
             "Should not detect anything when dissalowed_code is empty"
         );
     }
@@ -382,6 +507,17 @@ mod tests {
         let gz_bytes = [0x1F, 0x8B, 0x08, 0x00]; // GZIP magic bytes with no TAR content
         assert_eq!(detect_archive_format(&gz_bytes).unwrap(), ArchiveFormat::Gz);
 
+        // Test 7z and RAR detection
+        let seven_z_bytes = [0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C, 0x00, 0x04];
+        assert_eq!(
+            detect_archive_format(&seven_z_bytes).unwrap(),
+            ArchiveFormat::SevenZ
+        );
+        assert_eq!(
+            detect_archive_format(b"Rar!\x1a\x07\x00").unwrap(),
+            ArchiveFormat::Rar
+        );
+
         // Test unsupported format
         let unknown_bytes = [0xFF, 0xFF, 0xFF, 0xFF];
         assert!(detect_archive_format(&unknown_bytes).is_err());
@@ -390,4 +526,66 @@ mod tests {
         let tiny_bytes = [0x50, 0x4B];
         assert!(detect_archive_format(&tiny_bytes).is_err());
     }
+
+    #[test]
+    fn test_contains_disallowed_code_7z_found() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("file1.rs"), "fn main() {}").unwrap();
+        std::fs::write(src.join("file2.rs"), "use forbidden_code;").unwrap();
+
+        let archive = sevenz_rust::compress(&src, Cursor::new(Vec::new())).unwrap();
+        let bytes = archive.into_inner();
+
+        let mut config = ExecutionConfig::default_config();
+        config.marking.dissalowed_code = vec!["forbidden_code".to_string()];
+        assert!(find_dissalowed_code(&bytes, &config).unwrap().is_some());
+
+        config.marking.dissalowed_code = vec!["not_present".to_string()];
+        assert!(find_dissalowed_code(&bytes, &config).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_contains_disallowed_code_plain_source_file() {
+        let mut config = ExecutionConfig::default_config();
+        config.marking.dissalowed_code = vec!["import os".to_string()];
+
+        let found = find_dissalowed_code_in_file("main.py", b"print(1)\nimport os", &config);
+        assert_eq!(
+            found.unwrap(),
+            Some(DisallowedMatch {
+                file: "main.py".to_string(),
+                line: 2,
+                pattern: "import os".to_string(),
+            })
+        );
+
+        let clean = find_dissalowed_code_in_file("main.py", b"print(1)", &config);
+        assert!(clean.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_binary_files_are_skipped_and_code_rules_ignore_comments() {
+        let mut bytes = Cursor::new(Vec::new());
+        {
+            let mut zip = zip::ZipWriter::new(&mut bytes);
+            let options: FileOptions<'_, ()> = FileOptions::default();
+            zip.start_file("lib.o", options).unwrap();
+            zip.write_all(b"\x7fELF\x00\xff\xfesystem(").unwrap();
+            zip.start_file("main.c", options).unwrap();
+            zip.write_all(b"// no system( here\nint main() { return 0; }\n")
+                .unwrap();
+            zip.finish().unwrap();
+        }
+        let bytes = bytes.into_inner();
+
+        let mut config = ExecutionConfig::default_config();
+        config.marking.dissalowed_code = vec!["code:system(".to_string()];
+        assert!(find_dissalowed_code(&bytes, &config).unwrap().is_none());
+
+        config.marking.dissalowed_code = vec!["system(".to_string()];
+        let found = find_dissalowed_code(&bytes, &config).unwrap().unwrap();
+        assert_eq!((found.file.as_str(), found.line), ("main.c", 1));
+    }
 }
@@ -0,0 +1,276 @@
+//! Parsing of `marking.dissalowed_code` entries and matching them against source text.
+//!
+//! An entry is a plain substring unless prefixed:
+//! - `re:` — the rest is a regular expression (e.g. `re:\bsystem\s*\(`).
+//! - `code:` — only match in code, ignoring comments and string literals of the file's
+//!   language. Combines with `re:` as `code:re:...`.
+
+use regex::Regex;
+use std::path::Path;
+
+const CODE_PREFIX: &str = "code:";
+const REGEX_PREFIX: &str = "re:";
+
+enum Matcher {
+    Text(String),
+    Regex(Regex),
+}
+
+/// One compiled `dissalowed_code` entry.
+pub(super) struct Rule {
+    /// The entry as written in the config.
+    pub source: String,
+    matcher: Matcher,
+    code_only: bool,
+}
+
+impl Rule {
+    /// Compiles an entry; `Ok(None)` for entries with nothing to match.
+    pub fn parse(entry: &str) -> Result<Option<Self>, String> {
+        let (code_only, rest) = match entry.strip_prefix(CODE_PREFIX) {
+            Some(rest) => (true, rest),
+            None => (false, entry),
+        };
+        let matcher = match rest.strip_prefix(REGEX_PREFIX) {
+            Some(pattern) if !pattern.is_empty() => Matcher::Regex(
+                Regex::new(pattern).map_err(|e| format!("Invalid pattern '{entry}': {e}"))?,
+            ),
+            Some(_) => return Ok(None),
+            None if rest.is_empty() => return Ok(None),
+            None => Matcher::Text(rest.to_string()),
+        };
+        Ok(Some(Self {
+            source: entry.to_string(),
+            matcher,
+            code_only,
+        }))
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        match &self.matcher {
+            Matcher::Text(text) => line.contains(text.as_str()),
+            Matcher::Regex(re) => re.is_match(line),
+        }
+    }
+}
+
+/// First line (1-based) of `text` matched by any rule, with that rule.
+pub(super) fn first_match<'r>(
+    file_name: &str,
+    text: &str,
+    rules: &'r [Rule],
+) -> Option<(usize, &'r Rule)> {
+    let code = rules
+        .iter()
+        .any(|r| r.code_only)
+        .then(|| code_only(file_name, text));
+
+    let raw_lines: Vec<&str> = text.lines().collect();
+    let code_lines: Vec<&str> = code
+        .as_deref()
+        .map(|c| c.lines().collect())
+        .unwrap_or_default();
+
+    for (index, raw) in raw_lines.iter().enumerate() {
+        for rule in rules {
+            let line = if rule.code_only {
+                code_lines.get(index).copied().unwrap_or("")
+            } else {
+                raw
+            };
+            if rule.is_match(line) {
+                return Some((index + 1, rule));
+            }
+        }
+    }
+    None
+}
+
+/// Comment and string syntax of a language family.
+struct Syntax {
+    line_comment: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    /// Python-style `"""`/`'''` strings.
+    triple_quotes: bool,
+    /// `'` delimits single-character literals rather than strings.
+    char_literals: bool,
+}
+
+const C_LIKE: Syntax = Syntax {
+    line_comment: &["//"],
+    block_comment: Some(("/*", "*/")),
+    triple_quotes: false,
+    char_literals: true,
+};
+const HASH: Syntax = Syntax {
+    line_comment: &["#"],
+    block_comment: None,
+    triple_quotes: false,
+    char_literals: false,
+};
+const PYTHON: Syntax = Syntax {
+    line_comment: &["#"],
+    block_comment: None,
+    triple_quotes: true,
+    char_literals: false,
+};
+const SQL: Syntax = Syntax {
+    line_comment: &["--"],
+    block_comment: Some(("/*", "*/")),
+    triple_quotes: false,
+    char_literals: false,
+};
+const HASKELL: Syntax = Syntax {
+    line_comment: &["--"],
+    block_comment: Some(("{-", "-}")),
+    triple_quotes: false,
+    char_literals: false,
+};
+
+fn syntax_for(file_name: &str) -> Option<&'static Syntax> {
+    let ext = Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())?
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "c" | "cc" | "cpp" | "cxx" | "h" | "hpp" | "java" | "rs" | "go" | "js" | "ts" | "cs"
+        | "kt" | "scala" | "swift" => Some(&C_LIKE),
+        "py" => Some(&PYTHON),
+        "sh" | "rb" | "pl" | "r" => Some(&HASH),
+        "sql" => Some(&SQL),
+        "hs" => Some(&HASKELL),
+        _ => None,
+    }
+}
+
+/// `text` with comments and string/char literals blanked out (newlines kept, so line numbers
+/// still line up). Files of unknown languages are returned unchanged.
+pub(super) fn code_only(file_name: &str, text: &str) -> String {
+    let Some(syntax) = syntax_for(file_name) else {
+        return text.to_string();
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let blank = |s: &str, out: &mut String| {
+        out.extend(s.chars().map(|c| if c == '\n' { '\n' } else { ' ' }));
+    };
+
+    while let Some(c) = rest.chars().next() {
+        if syntax.line_comment.iter().any(|m| rest.starts_with(m)) {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            blank(&rest[..end], &mut out);
+            rest = &rest[end..];
+            continue;
+        }
+        if let Some((open, close)) = syntax.block_comment
+            && rest.starts_with(open)
+        {
+            let end = rest[open.len()..]
+                .find(close)
+                .map(|i| open.len() + i + close.len())
+                .unwrap_or(rest.len());
+            blank(&rest[..end], &mut out);
+            rest = &rest[end..];
+            continue;
+        }
+        if syntax.triple_quotes
+            && let Some(quote) = ["\"\"\"", "'''"].into_iter().find(|q| rest.starts_with(q))
+        {
+            let end = rest[3..]
+                .find(quote)
+                .map(|i| 3 + i + 3)
+                .unwrap_or(rest.len());
+            blank(&rest[..end], &mut out);
+            rest = &rest[end..];
+            continue;
+        }
+        if c == '"' || c == '\'' {
+            let end = string_end(rest, c);
+            // With char literals, `'` only opens a short closed one ('a', '\n', '\u{1F600}');
+            // anything else is e.g. a Rust lifetime.
+            let is_literal = c == '"'
+                || !syntax.char_literals
+                || (end > 1 && end <= 12 && rest[..end].ends_with('\''));
+            if is_literal {
+                blank(&rest[..end], &mut out);
+                rest = &rest[end..];
+                continue;
+            }
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    out
+}
+
+/// Byte length of the literal opened by `quote` at the start of `s`: up to the closing quote,
+/// honouring backslash escapes, or to the end of the line if it is unterminated.
+fn string_end(s: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        match c {
+            '\n' => return i,
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            _ if c == quote => return i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    s.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(entries: &[&str]) -> Vec<Rule> {
+        entries
+            .iter()
+            .filter_map(|e| Rule::parse(e).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn code_rules_ignore_comments_and_strings() {
+        let src =
+            "// system(\"ls\") is banned\nputs(\"system(\");\n/* system( */\n  system(\"ls\");\n";
+        let code = rules(&["code:system("]);
+        let (line, rule) = first_match("main.c", src, &code).unwrap();
+        assert_eq!((line, rule.source.as_str()), (4, "code:system("));
+
+        // A plain entry still matches the comment on line 1.
+        assert_eq!(
+            first_match("main.c", src, &rules(&["system("])).unwrap().0,
+            1
+        );
+
+        // Lifetimes are not char literals.
+        let rs = "fn f<'a>(s: &'a str) { system(s) }";
+        assert_eq!(first_match("a.rs", rs, &code).unwrap().0, 1);
+
+        let py = "# import os\ns = '''\nimport os\n'''\nimport os\n";
+        assert_eq!(
+            first_match("a.py", py, &rules(&["code:import os"]))
+                .unwrap()
+                .0,
+            5
+        );
+    }
+
+    #[test]
+    fn regex_entries_are_compiled_and_validated() {
+        let re = rules(&[r"code:re:\bexec[lv]p?\s*\("]);
+        assert_eq!(
+            first_match("a.c", "int x;\nexecvp (argv[0]);", &re)
+                .unwrap()
+                .0,
+            2
+        );
+        assert!(first_match("a.c", "my_execvp(argv[0]);", &re).is_none());
+
+        assert!(Rule::parse("re:(").is_err());
+        assert!(Rule::parse("re:").unwrap().is_none());
+        assert!(Rule::parse("").unwrap().is_none());
+    }
+}
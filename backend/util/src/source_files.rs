@@ -0,0 +1,90 @@
+use std::io::{Cursor, Write};
+use std::path::Path;
+
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+/// Extensions accepted for plain (non-archived) source file submissions.
+pub const SOURCE_EXTENSIONS: &[&str] = &[
+    "c", "cc", "cpp", "cxx", "h", "hpp", "java", "py", "rs", "go", "js", "ts", "cs", "hs", "pl",
+    "ml", "m", "pas", "f90", "vhd", "asm", "s", "sql",
+];
+
+/// Returns true if `file_name` has one of the [`SOURCE_EXTENSIONS`] (case-insensitive).
+pub fn is_source_file(file_name: &str) -> bool {
+    Path::new(file_name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| SOURCE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Packs `files` into an in-memory zip, each at the archive root under its given name.
+///
+/// Lets a plain-file submission go through the same extraction path as an uploaded archive.
+pub fn zip_source_files(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    if files.is_empty() {
+        return Err("No source files to package".to_string());
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+
+    for (name, contents) in files {
+        let name = Path::new(name)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| format!("Invalid source file name: {}", name))?;
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to add {} to zip: {}", name, e))?;
+        zip.write_all(contents)
+            .map_err(|e| format!("Failed to write {} to zip: {}", name, e))?;
+    }
+
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish zip: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    #[test]
+    fn recognises_source_extensions() {
+        assert!(is_source_file("main.py"));
+        assert!(is_source_file("Main.JAVA"));
+        assert!(!is_source_file("submission.zip"));
+        assert!(!is_source_file("coverage_report.json"));
+        assert!(!is_source_file("Makefile"));
+    }
+
+    #[test]
+    fn zips_files_at_the_archive_root() {
+        let bytes = zip_source_files(&[
+            ("main.py".to_string(), b"print('hi')".to_vec()),
+            ("nested/util.py".to_string(), b"x = 1".to_vec()),
+        ])
+        .unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        assert_eq!(archive.len(), 2);
+
+        let mut contents = String::new();
+        archive
+            .by_name("main.py")
+            .unwrap()
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents, "print('hi')");
+        assert!(archive.by_name("util.py").is_ok());
+    }
+
+    #[test]
+    fn empty_file_list_is_an_error() {
+        assert!(zip_source_files(&[]).is_err());
+    }
+}
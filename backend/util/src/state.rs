@@ -10,10 +10,12 @@ use sea_orm::DatabaseConnection;
 ///
 /// This includes:
 /// - A cloned, thread-safe database connection for use with SeaORM.
+/// - A second connection for read-heavy queries, pointing at a read replica if one is configured.
 /// - A global `WebSocketManager` for broadcasting and subscribing to topics.
 #[derive(Clone)]
 pub struct AppState {
     db: DatabaseConnection,
+    db_read: DatabaseConnection,
     ws: WebSocketManager,
 }
 
@@ -24,8 +26,20 @@ impl AppState {
     ///
     /// * `db` - A SeaORM `DatabaseConnection`, typically cloned from the main pool.
     /// * `ws` - A `WebSocketManager` responsible for managing topic-based communication.
+    ///
+    /// Reads go to the same connection until [`AppState::with_read_replica`] is called.
     pub fn new(db: DatabaseConnection, ws: WebSocketManager) -> Self {
-        Self { db, ws }
+        Self {
+            db_read: db.clone(),
+            db,
+            ws,
+        }
+    }
+
+    /// Sends [`AppState::db_read`] queries to `replica` instead of the primary connection.
+    pub fn with_read_replica(mut self, replica: DatabaseConnection) -> Self {
+        self.db_read = replica;
+        self
     }
 
     /// Returns a shared reference to the internal `DatabaseConnection`.
@@ -35,6 +49,14 @@ impl AppState {
         &self.db
     }
 
+    /// Connection for read-heavy queries (grade lists, statistics) that can tolerate replica lag.
+    ///
+    /// This is the read replica when one is configured, otherwise the primary. Never write
+    /// through it, and don't use it to read back something the same request just wrote.
+    pub fn db_read(&self) -> &DatabaseConnection {
+        &self.db_read
+    }
+
     /// Returns a shared reference to the internal `WebSocketManager`.
     pub fn ws(&self) -> &WebSocketManager {
         &self.ws
@@ -0,0 +1,90 @@
+use async_trait::async_trait;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::{StorageBackend, key_for, local_file};
+
+/// Files under `STORAGE_ROOT` on this machine.
+pub struct LocalStorage;
+
+#[async_trait]
+impl StorageBackend for LocalStorage {
+    async fn read(&self, key: &str) -> io::Result<Vec<u8>> {
+        fs::read(local_file(key))
+    }
+
+    async fn write(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        write_file(&local_file(key), bytes)
+    }
+
+    async fn link(&self, from: &str, to: &str) -> io::Result<()> {
+        link_file(&local_file(from), &local_file(to))
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        fs::remove_file(local_file(key))
+    }
+
+    async fn exists(&self, key: &str) -> io::Result<bool> {
+        Ok(local_file(key).is_file())
+    }
+
+    async fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut pending = vec![local_file(prefix)];
+        while let Some(dir) = pending.pop() {
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for entry in entries {
+                let path = entry?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    keys.push(key_for(&path));
+                }
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn local_path(&self, key: &str) -> io::Result<PathBuf> {
+        Ok(local_file(key))
+    }
+}
+
+/// Writes `bytes` to `path`, creating its parent directories.
+///
+/// The bytes go to a temporary file that then replaces `path`, so an existing file is never
+/// truncated (it may be a hard link to a shared blob) and concurrent readers and linkers always
+/// find a complete file.
+pub(super) fn write_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let mut tmp = tempfile::NamedTempFile::new_in(parent)?;
+    tmp.write_all(bytes)?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Points `to` at the same file as `from`, copying where hard links are unsupported.
+pub(super) fn link_file(from: &Path, to: &Path) -> io::Result<()> {
+    prepare_target(to)?;
+    if fs::hard_link(from, to).is_err() {
+        fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+fn prepare_target(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
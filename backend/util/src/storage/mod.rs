@@ -0,0 +1,214 @@
+//! Where stored files live.
+//!
+//! Everything the app stores (assignment files, submissions, outputs, ...) is addressed by a
+//! key: its `/`-separated path relative to the storage root, as built by [`crate::paths`] and
+//! kept in the `path` column of the file models. A [`StorageBackend`] maps keys to bytes;
+//! [`storage`] returns the one picked by `STORAGE_BACKEND`:
+//!
+//! - [`LocalStorage`] (default) keeps files under `STORAGE_ROOT`, exactly where the path
+//!   helpers point.
+//! - [`S3Storage`] keeps them in an S3 bucket (AWS or MinIO), so several API machines can
+//!   share one store. `STORAGE_ROOT` is then a local cache: writes land in both, reads come
+//!   from the bucket, and [`StorageBackend::local_path`] downloads an object for code that
+//!   needs a real file (archives handed to containers, plagiarism tools).
+
+mod local;
+mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+use async_trait::async_trait;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::{self, StorageBackendKind};
+use crate::paths::storage_root;
+
+#[async_trait]
+pub trait StorageBackend: Send + Sync {
+    /// The object's bytes; fails with [`io::ErrorKind::NotFound`] if there is none.
+    async fn read(&self, key: &str) -> io::Result<Vec<u8>>;
+
+    /// Creates or replaces the object.
+    async fn write(&self, key: &str, bytes: &[u8]) -> io::Result<()>;
+
+    /// Makes `to` an object with the same contents as `from`, replacing any existing `to`.
+    /// Identical files take up space once: local storage hard-links the two, S3 stores `to` as
+    /// a pointer to `from`, so `from` must outlive it there.
+    async fn link(&self, from: &str, to: &str) -> io::Result<()>;
+
+    /// Removes the object; fails with [`io::ErrorKind::NotFound`] if there is none.
+    async fn delete(&self, key: &str) -> io::Result<()>;
+
+    async fn exists(&self, key: &str) -> io::Result<bool>;
+
+    /// Keys of all objects under `prefix` (a directory key), in no particular order.
+    async fn list(&self, prefix: &str) -> io::Result<Vec<String>>;
+
+    /// A local file with the object's current contents.
+    async fn local_path(&self, key: &str) -> io::Result<PathBuf>;
+}
+
+/// The configured backend.
+///
+/// # Panics
+/// Panics if `STORAGE_BACKEND` is `s3` and the S3 client cannot be built (e.g. no bucket).
+pub fn storage() -> &'static dyn StorageBackend {
+    static S3: OnceLock<S3Storage> = OnceLock::new();
+    match config::storage_backend() {
+        StorageBackendKind::Local => &LocalStorage,
+        StorageBackendKind::S3 => S3.get_or_init(|| {
+            S3Storage::from_config().unwrap_or_else(|e| panic!("invalid S3 storage: {e}"))
+        }),
+    }
+}
+
+/// Removes every object under `prefix` (a directory key), then whatever is left of the
+/// directory locally.
+pub async fn delete_all(prefix: &str) -> io::Result<()> {
+    let store = storage();
+    for key in store.list(prefix).await? {
+        match store.delete(&key).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    match std::fs::remove_dir_all(local_file(prefix)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// The name of the object `key` points at (its last path segment).
+pub fn file_name(key: &str) -> &str {
+    key.rsplit('/').next().unwrap_or(key)
+}
+
+/// The key of a path under the storage root. Paths outside it (absolute paths stored by some
+/// test setups) are returned whole.
+pub fn key_for(path: &Path) -> String {
+    match path.strip_prefix(storage_root()) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.to_string_lossy().into_owned(),
+    }
+}
+
+/// Where `key` lives under the storage root (or the key itself if it is absolute).
+pub(crate) fn local_file(key: &str) -> PathBuf {
+    storage_root().join(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::setup_test_storage_root;
+    use serial_test::serial;
+
+    #[tokio::test]
+    #[serial]
+    async fn local_storage_round_trips_under_the_storage_root() {
+        let _tmp = setup_test_storage_root();
+        let store = LocalStorage;
+        let key = "module_1/assignment_2/memo/3.zip";
+
+        assert!(!store.exists(key).await.unwrap());
+        store.write(key, b"memo").await.unwrap();
+        assert_eq!(store.read(key).await.unwrap(), b"memo");
+        assert_eq!(store.local_path(key).await.unwrap(), local_file(key));
+        assert!(local_file(key).is_file());
+
+        store
+            .link(key, "module_1/assignment_2/main/5.zip")
+            .await
+            .unwrap();
+        assert_eq!(
+            store
+                .read("module_1/assignment_2/main/5.zip")
+                .await
+                .unwrap(),
+            b"memo"
+        );
+        // Replacing a linked file must not change the file it was linked from.
+        store
+            .write("module_1/assignment_2/main/5.zip", b"main")
+            .await
+            .unwrap();
+        assert_eq!(store.read(key).await.unwrap(), b"memo");
+        store
+            .delete("module_1/assignment_2/main/5.zip")
+            .await
+            .unwrap();
+
+        store
+            .write("module_1/assignment_2/spec/4.zip", b"spec")
+            .await
+            .unwrap();
+        let mut keys = store.list("module_1/assignment_2").await.unwrap();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "module_1/assignment_2/memo/3.zip",
+                "module_1/assignment_2/spec/4.zip"
+            ]
+        );
+
+        store.delete(key).await.unwrap();
+        assert!(!store.exists(key).await.unwrap());
+        assert_eq!(
+            store.read(key).await.unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn delete_all_removes_everything_under_a_prefix() {
+        let _tmp = setup_test_storage_root();
+        let store = storage();
+        store
+            .write("module_1/moss_archives/3/archive.zip", b"zip")
+            .await
+            .unwrap();
+        store
+            .write("module_1/moss_archives/3/matches/match0.html", b"page")
+            .await
+            .unwrap();
+        store
+            .write("module_1/moss_archives/4/archive.zip", b"zip")
+            .await
+            .unwrap();
+
+        delete_all("module_1/moss_archives/3").await.unwrap();
+        assert!(
+            store
+                .list("module_1/moss_archives/3")
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(!local_file("module_1/moss_archives/3").exists());
+        assert!(
+            store
+                .exists("module_1/moss_archives/4/archive.zip")
+                .await
+                .unwrap()
+        );
+        // Nothing to delete is not an error
+        delete_all("module_1/moss_archives/3").await.unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn keys_are_relative_to_the_storage_root() {
+        let _tmp = setup_test_storage_root();
+        let path = storage_root().join("module_1").join("config.json");
+        assert_eq!(key_for(&path), "module_1/config.json");
+    }
+}
@@ -0,0 +1,176 @@
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path as ObjectPath;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions, PutPayload};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use super::local::{link_file, write_file};
+use super::{StorageBackend, local_file};
+use crate::config;
+
+/// Objects in an S3 bucket, cached under `STORAGE_ROOT`.
+///
+/// The bucket is the source of truth: reads and [`StorageBackend::local_path`] always fetch
+/// the object (refreshing the cached copy), since another machine may have replaced it.
+///
+/// S3 has no links, so [`StorageBackend::link`] writes an empty object whose
+/// [`LINK_METADATA`] names the target key, and reads follow it.
+pub struct S3Storage {
+    store: AmazonS3,
+    /// Prepended to every key; empty or ending in `/`.
+    prefix: String,
+}
+
+impl S3Storage {
+    /// Builds the client from the `S3_*` settings, falling back to the standard `AWS_*`
+    /// variables for anything unset.
+    pub fn from_config() -> Result<Self, String> {
+        let mut builder = AmazonS3Builder::from_env().with_bucket_name(config::s3_bucket());
+        if let Some(region) = config::s3_region() {
+            builder = builder.with_region(region);
+        }
+        if let Some(endpoint) = config::s3_endpoint() {
+            builder = builder
+                .with_allow_http(endpoint.starts_with("http://"))
+                .with_endpoint(endpoint);
+        }
+        if let Some(key) = config::s3_access_key_id() {
+            builder = builder.with_access_key_id(key);
+        }
+        if let Some(secret) = config::s3_secret_access_key() {
+            builder = builder.with_secret_access_key(secret);
+        }
+        let store = builder
+            .build()
+            .map_err(|e| format!("Failed to build S3 client: {e}"))?;
+
+        let prefix = config::s3_prefix()
+            .map(|p| p.trim_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .map(|p| format!("{p}/"))
+            .unwrap_or_default();
+        Ok(Self { store, prefix })
+    }
+
+    fn object(&self, key: &str) -> ObjectPath {
+        ObjectPath::from(format!("{}{}", self.prefix, key.trim_start_matches('/')))
+    }
+
+    /// The object's bytes, following a link object to its target.
+    async fn fetch(&self, key: &str) -> io::Result<Vec<u8>> {
+        let mut result = self.store.get(&self.object(key)).await.map_err(to_io)?;
+        let link = Attribute::Metadata(LINK_METADATA.into());
+        if let Some(target) = result.attributes.get(&link) {
+            result = self.store.get(&self.object(target)).await.map_err(to_io)?;
+        }
+        Ok(result.bytes().await.map_err(to_io)?.to_vec())
+    }
+}
+
+/// User metadata (`x-amz-meta-fitchfork-link`) marking a link object and naming its target.
+const LINK_METADATA: &str = "fitchfork-link";
+
+fn to_io(e: object_store::Error) -> io::Error {
+    match e {
+        object_store::Error::NotFound { .. } => io::Error::new(io::ErrorKind::NotFound, e),
+        other => io::Error::other(other),
+    }
+}
+
+#[async_trait]
+impl StorageBackend for S3Storage {
+    async fn read(&self, key: &str) -> io::Result<Vec<u8>> {
+        let bytes = self.fetch(key).await?;
+        // Best effort: `local_path` refreshes the cache itself before handing it out.
+        let _ = write_file(&local_file(key), &bytes);
+        Ok(bytes)
+    }
+
+    async fn write(&self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        self.store
+            .put(&self.object(key), PutPayload::from(bytes.to_vec()))
+            .await
+            .map_err(to_io)?;
+        write_file(&local_file(key), bytes)
+    }
+
+    async fn link(&self, from: &str, to: &str) -> io::Result<()> {
+        let mut attributes = Attributes::new();
+        attributes.insert(
+            Attribute::Metadata(LINK_METADATA.into()),
+            from.to_string().into(),
+        );
+        let opts = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+        self.store
+            .put_opts(&self.object(to), PutPayload::new(), opts)
+            .await
+            .map_err(to_io)?;
+        let cached = local_file(from);
+        if cached.is_file() {
+            link_file(&cached, &local_file(to))
+        } else {
+            match fs::remove_file(local_file(to)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    async fn delete(&self, key: &str) -> io::Result<()> {
+        if !self.exists(key).await? {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no stored object {key}"),
+            ));
+        }
+        self.store.delete(&self.object(key)).await.map_err(to_io)?;
+        match fs::remove_file(local_file(key)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> io::Result<bool> {
+        match self.store.head(&self.object(key)).await {
+            Ok(_) => Ok(true),
+            Err(object_store::Error::NotFound { .. }) => Ok(false),
+            Err(e) => Err(to_io(e)),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> io::Result<Vec<String>> {
+        let objects: Vec<_> = self
+            .store
+            .list(Some(&self.object(prefix)))
+            .try_collect()
+            .await
+            .map_err(to_io)?;
+        Ok(objects
+            .into_iter()
+            .map(|meta| {
+                let location = meta.location.to_string();
+                location
+                    .strip_prefix(&self.prefix)
+                    .map(str::to_string)
+                    .unwrap_or(location)
+            })
+            .collect())
+    }
+
+    async fn local_path(&self, key: &str) -> io::Result<PathBuf> {
+        // The cached copy may be another machine's old version of the object, so it is only
+        // handed out once it matches the bucket.
+        let bytes = self.fetch(key).await?;
+        let path = local_file(key);
+        if fs::read(&path).ok().as_deref() != Some(bytes.as_slice()) {
+            write_file(&path, &bytes)?;
+        }
+        Ok(path)
+    }
+}
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
 use std::time::Duration;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use sysinfo::{CpuRefreshKind, Disks, System};
 
+/// Docker label code_manager puts on run containers, holding the run's job id
+/// (e.g. `submission-42`).
+pub const JOB_LABEL: &str = "fitchfork.job";
+
 #[derive(Debug, Serialize, Clone)]
 pub struct DiskSummary {
     pub name: String,
@@ -12,6 +18,28 @@ pub struct DiskSummary {
     pub mount_point: String,
 }
 
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct GpuSummary {
+    pub index: u32,
+    pub name: String,
+    /// Percent of the last sample period the GPU was busy.
+    pub utilization: f32,
+    pub mem_total: u64,
+    pub mem_used: u64,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ContainerSummary {
+    pub id: String,
+    pub name: String,
+    /// Percent of one core, so a container can exceed 100 on a multi-core host.
+    pub cpu_usage: f32,
+    pub mem_used: u64,
+    pub mem_limit: u64,
+    /// The run's job id from [`JOB_LABEL`], for containers code_manager started for one.
+    pub job: Option<String>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct SystemMetrics {
     pub load_one: f64,
@@ -26,6 +54,28 @@ pub struct SystemMetrics {
     pub swap_used: u64,
     pub disks: Vec<DiskSummary>,
     pub uptime_seconds: u64,
+    /// NVIDIA GPUs reported by `nvidia-smi`; empty when there are none (or no driver).
+    pub gpus: Vec<GpuSummary>,
+    /// Running Docker containers; empty when Docker is not reachable.
+    pub containers: Vec<ContainerSummary>,
+}
+
+/// Memory and cores of the host, for capping configured limits.
+#[derive(Debug, Clone, Copy)]
+pub struct HostCapacity {
+    pub mem_total: u64,
+    pub cpu_cores: usize,
+}
+
+/// Reads [`HostCapacity`] without sampling usage.
+pub fn host_capacity() -> HostCapacity {
+    let mut sys = System::new();
+    sys.refresh_cpu_list(CpuRefreshKind::nothing());
+    sys.refresh_memory();
+    HostCapacity {
+        mem_total: sys.total_memory(),
+        cpu_cores: sys.cpus().len(),
+    }
 }
 
 /// De-duplicate disks across all OSes by (name, total, fs).
@@ -58,7 +108,148 @@ fn dedupe_disks(disks: Vec<DiskSummary>) -> Vec<DiskSummary> {
     pick.into_values().collect()
 }
 
-/// Samples current system metrics using sysinfo in a portable way compatible with v0.30.
+/// Runs `program` with `args` and returns its stdout, or `None` if it is missing or fails.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parses `nvidia-smi --query-gpu=index,name,utilization.gpu,memory.used,memory.total
+/// --format=csv,noheader,nounits` (memory in MiB). Lines it cannot read are skipped.
+fn parse_nvidia_smi(text: &str) -> Vec<GpuSummary> {
+    const MIB: u64 = 1024 * 1024;
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [index, name, utilization, mem_used, mem_total] = fields[..] else {
+                return None;
+            };
+            Some(GpuSummary {
+                index: index.parse().ok()?,
+                name: name.to_string(),
+                // "[N/A]" on GPUs that don't report it.
+                utilization: utilization.parse().unwrap_or(0.0),
+                mem_total: mem_total.parse::<u64>().ok()? * MIB,
+                mem_used: mem_used.parse::<u64>().ok()? * MIB,
+            })
+        })
+        .collect()
+}
+
+fn sample_gpus() -> Vec<GpuSummary> {
+    command_output(
+        "nvidia-smi",
+        &[
+            "--query-gpu=index,name,utilization.gpu,memory.used,memory.total",
+            "--format=csv,noheader,nounits",
+        ],
+    )
+    .map(|out| parse_nvidia_smi(&out))
+    .unwrap_or_default()
+}
+
+/// Bytes in a Docker size such as `12.5MiB`, `1.2GB` or `0B`.
+fn parse_docker_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim() {
+        "B" | "" => 1.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// One line of `docker stats --no-stream --no-trunc --format '{{json .}}'`.
+#[derive(Deserialize)]
+struct DockerStatsLine {
+    #[serde(rename = "ID")]
+    id: String,
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "CPUPerc")]
+    cpu_perc: String,
+    /// e.g. `"12.5MiB / 1GiB"`.
+    #[serde(rename = "MemUsage")]
+    mem_usage: String,
+}
+
+/// Parses `docker stats` JSON lines, attaching job ids from `jobs` (container id → job).
+fn parse_docker_stats(text: &str, jobs: &HashMap<String, String>) -> Vec<ContainerSummary> {
+    text.lines()
+        .filter_map(|line| serde_json::from_str::<DockerStatsLine>(line).ok())
+        .map(|s| {
+            let (used, limit) = s.mem_usage.split_once('/').unwrap_or((&s.mem_usage, ""));
+            ContainerSummary {
+                job: jobs.get(&s.id).cloned(),
+                cpu_usage: s.cpu_perc.trim_end_matches('%').parse().unwrap_or(0.0),
+                mem_used: parse_docker_size(used).unwrap_or(0),
+                mem_limit: parse_docker_size(limit).unwrap_or(0),
+                id: s.id,
+                name: s.name,
+            }
+        })
+        .collect()
+}
+
+/// CPU and memory of every running container, from the Docker CLI. Takes about a second:
+/// `docker stats` measures CPU over an interval.
+fn sample_containers() -> Vec<ContainerSummary> {
+    let label_format = format!("{{{{.ID}}}}\t{{{{.Label \"{JOB_LABEL}\"}}}}");
+    let jobs: HashMap<String, String> = command_output(
+        "docker",
+        &[
+            "ps",
+            "--no-trunc",
+            "--filter",
+            &format!("label={JOB_LABEL}"),
+            "--format",
+            &label_format,
+        ],
+    )
+    .unwrap_or_default()
+    .lines()
+    .filter_map(|line| line.split_once('\t'))
+    .map(|(id, job)| (id.to_string(), job.to_string()))
+    .collect();
+
+    command_output(
+        "docker",
+        &[
+            "stats",
+            "--no-stream",
+            "--no-trunc",
+            "--format",
+            "{{json .}}",
+        ],
+    )
+    .map(|out| parse_docker_stats(&out, &jobs))
+    .unwrap_or_default()
+}
+
+/// Samples current system metrics using sysinfo in a portable way compatible with v0.30,
+/// plus GPUs (`nvidia-smi`) and containers (Docker) when the host has them.
+///
+/// Blocks for a second or more; call it off the async runtime.
 pub fn sample_system_metrics() -> SystemMetrics {
     let mut sys = System::new();
 
@@ -108,5 +299,59 @@ pub fn sample_system_metrics() -> SystemMetrics {
         swap_used: sys.used_swap(),
         disks: disk_summaries,
         uptime_seconds,
+        gpus: sample_gpus(),
+        containers: sample_containers(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_nvidia_smi_rows() {
+        let out = "0, NVIDIA A100-SXM4-40GB, 35, 1024, 40960\n1, Tesla T4, [N/A], 0, 15360\nbad\n";
+        let gpus = parse_nvidia_smi(out);
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA A100-SXM4-40GB");
+        assert_eq!(gpus[0].utilization, 35.0);
+        assert_eq!(gpus[0].mem_used, 1024 * 1024 * 1024);
+        assert_eq!(gpus[1].utilization, 0.0);
+    }
+
+    #[test]
+    fn parses_docker_stats_with_job_labels() {
+        let out = concat!(
+            r#"{"BlockIO":"0B / 0B","CPUPerc":"150.25%","Container":"abc","ID":"abc","MemPerc":"1.2%","MemUsage":"12.5MiB / 1GiB","Name":"fitchfork-code1-0","NetIO":"0B / 0B","PIDs":"3"}"#,
+            "\n",
+            r#"{"CPUPerc":"0.00%","ID":"def","MemUsage":"2kB / 8GB","Name":"postgres"}"#,
+            "\n",
+        );
+        let jobs = HashMap::from([("abc".to_string(), "submission-7".to_string())]);
+
+        let containers = parse_docker_stats(out, &jobs);
+        assert_eq!(
+            containers,
+            vec![
+                ContainerSummary {
+                    id: "abc".into(),
+                    name: "fitchfork-code1-0".into(),
+                    cpu_usage: 150.25,
+                    mem_used: 12 * 1024 * 1024 + 512 * 1024,
+                    mem_limit: 1024 * 1024 * 1024,
+                    job: Some("submission-7".into()),
+                },
+                ContainerSummary {
+                    id: "def".into(),
+                    name: "postgres".into(),
+                    cpu_usage: 0.0,
+                    mem_used: 2000,
+                    mem_limit: 8_000_000_000,
+                    job: None,
+                },
+            ]
+        );
+        assert_eq!(parse_docker_size("0B"), Some(0));
+        assert_eq!(parse_docker_size("1.5 furlongs"), None);
     }
 }
@@ -2,18 +2,82 @@ use chrono::Utc;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use crate::execution_config::ValgrindOptions;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValgrindTask {
     pub task_number: i64,
     pub leaked: bool,
+    /// Bytes definitely lost.
     pub bytes_leaked: u64,
+    #[serde(default)]
+    pub bytes_indirectly_lost: u64,
+    #[serde(default)]
+    pub bytes_possibly_lost: u64,
+    /// Number of "Invalid read" errors reported.
+    #[serde(default)]
+    pub invalid_reads: u64,
+    /// Number of "Invalid write" errors reported.
+    #[serde(default)]
+    pub invalid_writes: u64,
+    /// Number of errors about uninitialised values (conditional jumps, uses, syscall params).
+    #[serde(default)]
+    pub uninitialised_values: u64,
+}
+
+impl ValgrindTask {
+    /// The count behind each category, in the order of [`ValgrindWeights::named`], with how
+    /// to describe a non-zero count.
+    ///
+    /// [`ValgrindWeights::named`]: crate::execution_config::ValgrindWeights::named
+    fn categories(&self) -> [(u64, &'static str); 6] {
+        [
+            (self.bytes_leaked, "bytes definitely lost"),
+            (self.bytes_indirectly_lost, "bytes indirectly lost"),
+            (self.bytes_possibly_lost, "bytes possibly lost"),
+            (self.invalid_reads, "invalid reads"),
+            (self.invalid_writes, "invalid writes"),
+            (self.uninitialised_values, "uses of uninitialised values"),
+        ]
+    }
+
+    /// What valgrind found, e.g. `["100 bytes definitely lost", "50 invalid reads"]`; empty
+    /// if the task is clean.
+    pub fn problems(&self) -> Vec<String> {
+        self.categories()
+            .into_iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, what)| format!("{count} {what}"))
+            .collect()
+    }
+
+    /// Fraction (0 to 1) of the memory check's marks this task earns: the summed weights of
+    /// the categories valgrind reported nothing for, over the summed weights of all of them.
+    pub fn marked_fraction(&self, options: &ValgrindOptions) -> f64 {
+        let weights = options.weights.named();
+        let total: f64 = weights.iter().map(|(_, w)| w).sum();
+        if total <= 0.0 {
+            return if self.problems().is_empty() { 1.0 } else { 0.0 };
+        }
+        let clean: f64 = weights
+            .iter()
+            .zip(self.categories())
+            .filter(|(_, (count, _))| *count == 0)
+            .map(|((_, w), _)| w)
+            .sum();
+        clean / total
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValgrindReport {
     pub generated_at: String,
     pub total_tasks: usize,
+    /// Bytes definitely lost across all tasks.
     pub total_leaks: u64,
+    /// Invalid reads, invalid writes and uninitialised value errors across all tasks.
+    #[serde(default)]
+    pub total_errors: u64,
     pub tasks: Vec<ValgrindTask>,
 }
 
@@ -24,27 +88,41 @@ impl ValgrindProcessor {
     pub fn process_report(task_contents: &[(i64, String)]) -> Result<String, String> {
         let mut tasks: Vec<ValgrindTask> = Vec::new();
         let mut total_leaks: u64 = 0;
+        let mut total_errors: u64 = 0;
 
-        let re_definitely_lost =
-            Regex::new(r"definitely lost:\s*([0-9,]+)\s*bytes").map_err(|e| e.to_string())?;
+        let lost = |kind: &str| {
+            Regex::new(&format!(r"{kind} lost:\s*([0-9,]+)\s*bytes")).map_err(|e| e.to_string())
+        };
+        let re_definitely_lost = lost("definitely")?;
+        let re_indirectly_lost = lost("indirectly")?;
+        let re_possibly_lost = lost("possibly")?;
+        let re_invalid_read =
+            Regex::new(r"==\d+==\s+Invalid read of size").map_err(|e| e.to_string())?;
+        let re_invalid_write =
+            Regex::new(r"==\d+==\s+Invalid write of size").map_err(|e| e.to_string())?;
+        let re_uninitialised = Regex::new(
+            r"==\d+==\s+(?:Conditional jump or move depends on uninitialised value|Use of uninitialised value of size|Syscall param \S+ (?:contains|points to) uninitialised byte)",
+        )
+        .map_err(|e| e.to_string())?;
 
         for (task_number, content) in task_contents.iter() {
-            let mut leaked_bytes: u64 = 0;
-
-            for cap in re_definitely_lost.captures_iter(content) {
-                let s = cap[1].replace(',', "");
-                if let Ok(val) = s.parse::<u64>() {
-                    leaked_bytes = val;
-                    break; // take the first match
-                }
-            }
+            let leaked_bytes = first_bytes(&re_definitely_lost, content);
+            let invalid_reads = re_invalid_read.find_iter(content).count() as u64;
+            let invalid_writes = re_invalid_write.find_iter(content).count() as u64;
+            let uninitialised_values = re_uninitialised.find_iter(content).count() as u64;
 
             total_leaks += leaked_bytes;
+            total_errors += invalid_reads + invalid_writes + uninitialised_values;
 
             tasks.push(ValgrindTask {
                 task_number: *task_number,
                 leaked: leaked_bytes > 0,
                 bytes_leaked: leaked_bytes,
+                bytes_indirectly_lost: first_bytes(&re_indirectly_lost, content),
+                bytes_possibly_lost: first_bytes(&re_possibly_lost, content),
+                invalid_reads,
+                invalid_writes,
+                uninitialised_values,
             });
         }
 
@@ -52,6 +130,7 @@ impl ValgrindProcessor {
             generated_at: Utc::now().to_rfc3339(),
             total_tasks: tasks.len(),
             total_leaks,
+            total_errors,
             tasks,
         };
 
@@ -60,6 +139,13 @@ impl ValgrindProcessor {
     }
 }
 
+/// The byte count of the first leak summary line `re` matches (0 if there is none).
+fn first_bytes(re: &Regex, content: &str) -> u64 {
+    re.captures_iter(content)
+        .find_map(|cap| cap[1].replace(',', "").parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,4 +269,59 @@ Retcode: 0
         assert!(!task3.leaked);
         assert_eq!(task3.bytes_leaked, 0);
     }
+
+    #[test]
+    fn counts_memory_errors_and_scores_them_by_category() {
+        let output = r#"
+==31== Memcheck, a memory error detector
+==31== Invalid read of size 4
+==31==    at 0x109196: List::get(int) (List.cpp:12)
+==31==  Address 0x4a8b044 is 0 bytes after a block of size 4 alloc'd
+==31== Invalid read of size 4
+==31==    at 0x1091A2: List::get(int) (List.cpp:12)
+==31== Invalid write of size 8
+==31==    at 0x1091C0: List::push(int) (List.cpp:20)
+==31== Conditional jump or move depends on uninitialised value(s)
+==31==    at 0x109210: main (Main.cpp:8)
+==31== Syscall param write(buf) points to uninitialised byte(s)
+==31==    at 0x4B2A1E4: write (write.c:26)
+==31== Uninitialised value was created by a heap allocation
+==31== 
+==31== LEAK SUMMARY:
+==31==    definitely lost: 0 bytes in 0 blocks
+==31==    indirectly lost: 0 bytes in 0 blocks
+==31==      possibly lost: 1,024 bytes in 2 blocks
+==31==    still reachable: 0 bytes in 0 blocks
+==31== ERROR SUMMARY: 5 errors from 5 contexts (suppressed: 0 from 0)
+"#;
+        let json = ValgrindProcessor::process_report(&[(1, output.to_string())]).unwrap();
+        let report: ValgrindReport = serde_json::from_str(&json).unwrap();
+        let task = &report.tasks[0];
+
+        assert!(!task.leaked);
+        assert_eq!(task.bytes_possibly_lost, 1024);
+        assert_eq!(task.invalid_reads, 2);
+        assert_eq!(task.invalid_writes, 1);
+        assert_eq!(task.uninitialised_values, 2);
+        assert_eq!(report.total_errors, 5);
+        assert_eq!(
+            task.problems(),
+            [
+                "1024 bytes possibly lost",
+                "2 invalid reads",
+                "1 invalid writes",
+                "2 uses of uninitialised values"
+            ]
+        );
+
+        // No leaks, but only the definitely/indirectly lost shares are earned.
+        let options = ValgrindOptions::default();
+        assert!((task.marked_fraction(&options) - 0.55).abs() < 1e-9);
+
+        // Reports written before these fields existed still parse, as clean of them.
+        let old: ValgrindTask =
+            serde_json::from_str(r#"{ "task_number": 2, "leaked": false, "bytes_leaked": 0 }"#)
+                .unwrap();
+        assert_eq!(old.marked_fraction(&options), 1.0);
+    }
 }
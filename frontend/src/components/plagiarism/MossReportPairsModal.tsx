@@ -0,0 +1,180 @@
+import { useCallback, useEffect, useState } from 'react';
+import { Modal, Table, Space, Input, InputNumber, Button, Tag, Typography } from 'antd';
+import type { ColumnsType } from 'antd/es/table';
+import { listMossReportPairs } from '@/services/modules/assignments/plagiarism/get';
+import { createPlagiarismCaseFromPair } from '@/services/modules/assignments/plagiarism/post';
+import type { PlagiarismReportPair } from '@/types/modules/assignments/plagiarism';
+import { message } from '@/utils/message';
+
+type Props = {
+  open: boolean;
+  onClose: () => void;
+  moduleId: number;
+  assignmentId: number;
+  reportId: number | null;
+  onCaseCreated?: () => void;
+};
+
+const PAGE_SIZE = 20;
+
+const MossReportPairsModal: React.FC<Props> = ({
+  open,
+  onClose,
+  moduleId,
+  assignmentId,
+  reportId,
+  onCaseCreated,
+}) => {
+  const [pairs, setPairs] = useState<PlagiarismReportPair[]>([]);
+  const [total, setTotal] = useState(0);
+  const [page, setPage] = useState(1);
+  const [loading, setLoading] = useState(false);
+  const [notParsed, setNotParsed] = useState(false);
+  const [minPercent, setMinPercent] = useState<number | null>(null);
+  const [minLines, setMinLines] = useState<number | null>(null);
+  const [user, setUser] = useState('');
+  const [creatingId, setCreatingId] = useState<number | null>(null);
+
+  const load = useCallback(async () => {
+    if (reportId == null) return;
+    setLoading(true);
+    try {
+      const res = await listMossReportPairs(moduleId, assignmentId, reportId, {
+        page,
+        per_page: PAGE_SIZE,
+        min_percent: minPercent ?? undefined,
+        min_lines: minLines ?? undefined,
+        user: user.trim() || undefined,
+      });
+      if (res.success) {
+        setPairs(res.data.pairs);
+        setTotal(res.data.total);
+        setNotParsed(false);
+      } else {
+        setPairs([]);
+        setTotal(0);
+        setNotParsed(true);
+      }
+    } catch {
+      message.error('Failed to load report pairs');
+    } finally {
+      setLoading(false);
+    }
+  }, [moduleId, assignmentId, reportId, page, minPercent, minLines, user]);
+
+  useEffect(() => {
+    if (open) load();
+  }, [open, load]);
+
+  useEffect(() => {
+    setPage(1);
+  }, [reportId, minPercent, minLines, user]);
+
+  const createCase = async (pair: PlagiarismReportPair) => {
+    if (reportId == null) return;
+    setCreatingId(pair.id);
+    try {
+      const res = await createPlagiarismCaseFromPair(moduleId, assignmentId, reportId, pair.id);
+      if (res.success) {
+        message.success(`Created case for ${pair.user_a} and ${pair.user_b}`);
+        await load();
+        onCaseCreated?.();
+      } else {
+        message.error(res.message || 'Failed to create case');
+      }
+    } catch {
+      message.error('Failed to create case');
+    } finally {
+      setCreatingId(null);
+    }
+  };
+
+  const columns: ColumnsType<PlagiarismReportPair> = [
+    { title: 'User A', dataIndex: 'user_a', key: 'user_a' },
+    { title: 'User B', dataIndex: 'user_b', key: 'user_b' },
+    {
+      title: 'Similarity',
+      dataIndex: 'total_percent',
+      key: 'total_percent',
+      render: (v: number | null) => (v == null ? '—' : `${v.toFixed(1)}%`),
+    },
+    { title: 'Lines', dataIndex: 'lines_matched', key: 'lines_matched' },
+    {
+      title: 'Files',
+      key: 'files',
+      render: (_, p) => p.files?.length ?? '—',
+    },
+    {
+      title: '',
+      key: 'action',
+      align: 'right',
+      render: (_, p) =>
+        p.case_id != null ? (
+          <Tag color="blue">Case #{p.case_id}</Tag>
+        ) : (
+          <Button
+            size="small"
+            loading={creatingId === p.id}
+            disabled={p.submission_id_a == null || p.submission_id_b == null}
+            onClick={() => createCase(p)}
+          >
+            Create case
+          </Button>
+        ),
+    },
+  ];
+
+  return (
+    <Modal
+      title={reportId != null ? `Report #${reportId} — pairs` : 'Report pairs'}
+      open={open}
+      onCancel={onClose}
+      width={900}
+      footer={<Button onClick={onClose}>Close</Button>}
+    >
+      <Space wrap className="mb-3">
+        <InputNumber
+          min={0}
+          max={100}
+          value={minPercent}
+          onChange={(v) => setMinPercent(v)}
+          placeholder="Min %"
+          addonAfter="%"
+        />
+        <InputNumber
+          min={0}
+          value={minLines}
+          onChange={(v) => setMinLines(v)}
+          placeholder="Min lines"
+        />
+        <Input.Search
+          allowClear
+          placeholder="Search username"
+          onSearch={(v) => setUser(v)}
+          className="w-56"
+        />
+      </Space>
+
+      {notParsed ? (
+        <Typography.Text type="secondary">This report has not been parsed.</Typography.Text>
+      ) : (
+        <Table<PlagiarismReportPair>
+          rowKey="id"
+          size="small"
+          loading={loading}
+          columns={columns}
+          dataSource={pairs}
+          pagination={{
+            current: page,
+            pageSize: PAGE_SIZE,
+            total,
+            showSizeChanger: false,
+            onChange: setPage,
+          }}
+        />
+      )}
+    </Modal>
+  );
+};
+
+export default MossReportPairsModal;
@@ -19,11 +19,13 @@ import {
   DeleteOutlined,
   EyeOutlined,
   MoreOutlined,
+  UnorderedListOutlined,
 } from '@ant-design/icons';
 import { deleteMossReport } from '@/services/modules/assignments/plagiarism/delete';
 import { downloadMossArchiveByReport } from '@/services/modules/assignments/plagiarism/get';
 import type { MossReport } from '@/types/modules/assignments/plagiarism';
 import { message } from '@/utils/message';
+import MossReportPairsModal from './MossReportPairsModal';
 
 type Props = {
   moduleId: number;
@@ -32,6 +34,7 @@ type Props = {
   loading?: boolean;
   onOpenRunMoss: () => void;
   onRefresh?: () => void;
+  onCaseCreated?: () => void;
 };
 
 const MossReportsCard: React.FC<Props> = ({
@@ -41,12 +44,14 @@ const MossReportsCard: React.FC<Props> = ({
   loading,
   onOpenRunMoss,
   onRefresh,
+  onCaseCreated,
 }) => {
   const latest = useMemo(() => reports?.[0], [reports]);
   const hasArchive = Boolean(latest?.has_archive);
 
   const [detailsOpen, setDetailsOpen] = useState(false);
   const [selected, setSelected] = useState<MossReport | null>(null);
+  const [pairsReportId, setPairsReportId] = useState<number | null>(null);
 
   const openDetails = (r: MossReport) => {
     setSelected(r);
@@ -143,6 +148,14 @@ const MossReportsCard: React.FC<Props> = ({
                   </span>
                 ),
               },
+              {
+                key: 'pairs',
+                label: (
+                  <span>
+                    <UnorderedListOutlined /> Browse pairs
+                  </span>
+                ),
+              },
               {
                 key: 'download',
                 disabled: !r.has_archive,
@@ -183,6 +196,9 @@ const MossReportsCard: React.FC<Props> = ({
                 case 'view':
                   openDetails(r);
                   break;
+                case 'pairs':
+                  setPairsReportId(r.id);
+                  break;
                 case 'download':
                   if (r.has_archive) {
                     await downloadMossArchiveByReport(moduleId, assignmentId, r.id);
@@ -256,6 +272,15 @@ const MossReportsCard: React.FC<Props> = ({
           </Descriptions>
         )}
       </Modal>
+
+      <MossReportPairsModal
+        open={pairsReportId != null}
+        onClose={() => setPairsReportId(null)}
+        moduleId={moduleId}
+        assignmentId={assignmentId}
+        reportId={pairsReportId}
+        onCaseCreated={onCaseCreated}
+      />
     </>
   );
 };
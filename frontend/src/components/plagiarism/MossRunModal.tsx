@@ -1,8 +1,33 @@
-import { useState, useMemo } from 'react';
-import { Modal, Space, Typography, Radio, Select, Tooltip, Input, Alert, Divider } from 'antd';
-import { InfoCircleOutlined } from '@ant-design/icons';
-import { runMossCheck } from '@/services/modules/assignments/plagiarism';
-import { MOSS_FILTER_MODES, type MossFilterMode } from '@/types/modules/assignments/plagiarism';
+import { useState, useMemo, useEffect, useCallback } from 'react';
+import {
+  Modal,
+  Space,
+  Typography,
+  Radio,
+  Select,
+  Tooltip,
+  Input,
+  Alert,
+  Divider,
+  Upload,
+  Button,
+  Checkbox,
+  Tag,
+} from 'antd';
+import { InfoCircleOutlined, UploadOutlined, DeleteOutlined } from '@ant-design/icons';
+import {
+  runMossCheck,
+  listPlagiarismBaseFiles,
+  uploadPlagiarismBaseFile,
+  clearPlagiarismBaseFiles,
+  type PlagiarismEngine,
+  type RunMossPayload,
+} from '@/services/modules/assignments/plagiarism';
+import {
+  MOSS_FILTER_MODES,
+  type MossFilterMode,
+  type PlagiarismBaseFile,
+} from '@/types/modules/assignments/plagiarism';
 import { message } from '@/utils/message';
 
 type Props = {
@@ -19,10 +44,58 @@ type Props = {
 };
 
 const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId, onRan }) => {
+  const [engine, setEngine] = useState<PlagiarismEngine>('moss');
   const [filterMode, setFilterMode] = useState<MossFilterMode>('all');
   const [filterPatterns, setFilterPatterns] = useState<string[]>([]);
   const [description, setDescription] = useState<string>('');
   const [running, setRunning] = useState(false);
+  const [baseFiles, setBaseFiles] = useState<PlagiarismBaseFile[]>([]);
+  const [useBaseFiles, setUseBaseFiles] = useState(true);
+  const [archiveIds, setArchiveIds] = useState<string[]>([]);
+  const [uploading, setUploading] = useState(false);
+
+  const loadBaseFiles = useCallback(async () => {
+    try {
+      const res = await listPlagiarismBaseFiles(moduleId, assignmentId);
+      if (res.success) setBaseFiles(res.data.files);
+    } catch {
+      // non-fatal: the run still excludes whatever is stored
+    }
+  }, [moduleId, assignmentId]);
+
+  useEffect(() => {
+    if (open) loadBaseFiles();
+  }, [open, loadBaseFiles]);
+
+  const uploadBaseFile = async (file: File) => {
+    setUploading(true);
+    try {
+      const res = await uploadPlagiarismBaseFile(moduleId, assignmentId, file);
+      if (res.success) {
+        message.success(`Uploaded ${res.data.filename}`);
+        await loadBaseFiles();
+      } else {
+        message.error(res.message || 'Failed to upload starter code');
+      }
+    } catch {
+      message.error('Failed to upload starter code');
+    } finally {
+      setUploading(false);
+    }
+  };
+
+  const clearBaseFiles = async () => {
+    try {
+      const res = await clearPlagiarismBaseFiles(moduleId, assignmentId);
+      if (res.success) {
+        setBaseFiles([]);
+      } else {
+        message.error(res.message || 'Failed to remove starter code');
+      }
+    } catch {
+      message.error('Failed to remove starter code');
+    }
+  };
 
   const isValid = useMemo(() => {
     if (!description.trim()) return false;
@@ -35,6 +108,8 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
     return true;
   }, [description, filterMode, filterPatterns]);
 
+  const engineLabel = engine === 'jplag' ? 'JPlag' : 'MOSS';
+
   const doRun = async () => {
     // mirror backend rules
     if (!description.trim()) {
@@ -52,26 +127,25 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
 
     setRunning(true);
     try {
-      const payload: {
-        description: string;
-        filter_mode: MossFilterMode;
-        filter_patterns?: string[];
-      } = {
+      const payload: RunMossPayload = {
         description: description.trim(),
+        engine,
         filter_mode: filterMode,
+        use_base_files: useBaseFiles,
       };
       if (filterMode !== 'all') payload.filter_patterns = filterPatterns;
+      if (archiveIds.length) payload.archive_assignment_ids = archiveIds.map(Number);
 
       const res = await runMossCheck(moduleId, assignmentId, payload);
       if (res.success) {
-        message.success(res.message || 'Started MOSS job');
+        message.success(res.message || `Started ${engineLabel} job`);
         onClose();
         onRan?.();
       } else {
-        message.error(res.message || 'Failed to start MOSS job');
+        message.error(res.message || `Failed to start ${engineLabel} job`);
       }
     } catch {
-      message.error('Failed to start MOSS job');
+      message.error(`Failed to start ${engineLabel} job`);
     } finally {
       setRunning(false);
     }
@@ -79,12 +153,12 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
 
   return (
     <Modal
-      title="Run MOSS on Latest Submissions"
+      title={`Run ${engineLabel} on Latest Submissions`}
       open={open}
       onCancel={onClose}
       width={650}
       onOk={doRun}
-      okText="Run MOSS"
+      okText={`Run ${engineLabel}`}
       confirmLoading={running}
       okButtonProps={{ disabled: !isValid }}
       getContainer={false}
@@ -98,7 +172,7 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
         <Alert
           type="warning"
           showIcon
-          message="MOSS uses the language from Assignment Config"
+          message={`${engineLabel} uses the language from Assignment Config`}
           description={<span>Ensure the correct language is set before running.</span>}
         />
 
@@ -124,6 +198,27 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
 
         <Divider className="!my-2" />
 
+        <div className="inline-flex items-center gap-1">
+          <Typography.Text strong className="!mb-0">
+            Engine
+          </Typography.Text>
+          <Tooltip title="JPlag runs on the server instead of the MOSS service; use it when MOSS is unavailable.">
+            <InfoCircleOutlined className="text-gray-400 align-middle cursor-help" />
+          </Tooltip>
+        </div>
+        <Radio.Group
+          value={engine}
+          onChange={(e) => setEngine(e.target.value)}
+          options={[
+            { label: 'MOSS', value: 'moss' },
+            { label: 'JPlag (local)', value: 'jplag' },
+          ]}
+          optionType="button"
+          buttonStyle="solid"
+        />
+
+        <Divider className="!my-2" />
+
         <div className="inline-flex items-center gap-1">
           <Typography.Text strong className="!mb-0">
             Report Description <span className="text-red-500">*</span>
@@ -151,7 +246,7 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
           <Tooltip
             title={
               <div>
-                Choose which files MOSS should consider:
+                Choose which files should be compared:
                 <ul className="list-disc ml-4 mt-1">
                   <li>
                     <b>All</b>: compare every file.
@@ -205,6 +300,68 @@ const MossRunModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId,
           )}
         </div>
 
+        <Divider className="!my-2" />
+
+        <div className="inline-flex items-center gap-1">
+          <Typography.Text strong className="!mb-0">
+            Starter code
+          </Typography.Text>
+          <Tooltip title="Code every student was given (a skeleton .zip or source files). It is excluded from matches, together with the assignment’s spec files.">
+            <InfoCircleOutlined className="text-gray-400 align-middle cursor-help" />
+          </Tooltip>
+        </div>
+        <div className="flex flex-wrap items-center gap-2">
+          {baseFiles.length ? (
+            baseFiles.map((f) => <Tag key={f.filename}>{f.filename}</Tag>)
+          ) : (
+            <Typography.Text type="secondary">No starter code uploaded</Typography.Text>
+          )}
+        </div>
+        <Space>
+          <Upload
+            showUploadList={false}
+            beforeUpload={(file) => {
+              uploadBaseFile(file);
+              return false;
+            }}
+          >
+            <Button icon={<UploadOutlined />} loading={uploading}>
+              Upload starter code
+            </Button>
+          </Upload>
+          {baseFiles.length > 0 && (
+            <Button icon={<DeleteOutlined />} danger onClick={clearBaseFiles}>
+              Remove all
+            </Button>
+          )}
+        </Space>
+        <Checkbox
+          checked={useBaseFiles}
+          onChange={(e) => setUseBaseFiles(e.target.checked)}
+          disabled={!baseFiles.length}
+        >
+          Exclude uploaded starter code from this run
+        </Checkbox>
+
+        <Divider className="!my-2" />
+
+        <div className="inline-flex items-center gap-1">
+          <Typography.Text strong className="!mb-0">
+            Compare against earlier assignments
+          </Typography.Text>
+          <Tooltip title="Assignment IDs from previous semesters or other assignments. Their submissions are included as an archive; matches against them are marked historical. You need lecturer access to those modules.">
+            <InfoCircleOutlined className="text-gray-400 align-middle cursor-help" />
+          </Tooltip>
+        </div>
+        <Select
+          mode="tags"
+          value={archiveIds}
+          onChange={(vals) => setArchiveIds((vals as string[]).filter((v) => /^\d+$/.test(v)))}
+          placeholder="Add assignment IDs, e.g. 12, 34"
+          className="w-full"
+          tokenSeparators={[',', ' ']}
+        />
+
         {/* Removed the “latest report generated / archived” footer lines */}
       </Space>
     </Modal>
@@ -0,0 +1,145 @@
+import { useEffect, useMemo, useState } from 'react';
+import { Modal, Select, Empty, Spin, Typography, Tag, Alert } from 'antd';
+import { getPlagiarismCaseDiff } from '@/services/modules/assignments/plagiarism/get';
+import type {
+  PlagiarismCaseDiff,
+  PlagiarismDiffFile,
+} from '@/types/modules/assignments/plagiarism';
+import { message } from '@/utils/message';
+
+type Props = {
+  open: boolean;
+  onClose: () => void;
+  moduleId: number;
+  assignmentId: number;
+  caseId: number | null;
+};
+
+// Alternating highlight colours so neighbouring regions stay distinguishable
+const REGION_COLORS = ['bg-red-100 dark:bg-red-900/40', 'bg-amber-100 dark:bg-amber-900/40'];
+
+type Range = { start: number; end: number };
+
+const CodePane: React.FC<{ title: string; file: PlagiarismDiffFile; ranges: Range[] }> = ({
+  title,
+  file,
+  ranges,
+}) => {
+  const regionOf = (line: number) => ranges.findIndex((r) => line >= r.start && line <= r.end);
+
+  return (
+    <div className="min-w-0 flex-1 border rounded">
+      <div className="px-2 py-1 border-b text-xs">
+        <Typography.Text strong>{title}</Typography.Text>{' '}
+        <Typography.Text type="secondary">{file.name}</Typography.Text>
+      </div>
+      {file.content == null ? (
+        <div className="p-3">
+          <Typography.Text type="secondary">File not available in the submission.</Typography.Text>
+        </div>
+      ) : (
+        <pre className="m-0 max-h-[60vh] overflow-auto text-xs leading-5">
+          {file.content.split('\n').map((text, i) => {
+            const region = regionOf(i + 1);
+            return (
+              <div
+                key={i}
+                className={`flex ${region >= 0 ? REGION_COLORS[region % REGION_COLORS.length] : ''}`}
+              >
+                <span className="w-10 shrink-0 pr-2 text-right text-gray-400 select-none">
+                  {i + 1}
+                </span>
+                <code className="whitespace-pre">{text}</code>
+              </div>
+            );
+          })}
+        </pre>
+      )}
+    </div>
+  );
+};
+
+const PlagiarismDiffModal: React.FC<Props> = ({ open, onClose, moduleId, assignmentId, caseId }) => {
+  const [diff, setDiff] = useState<PlagiarismCaseDiff | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [fileIndex, setFileIndex] = useState(0);
+
+  useEffect(() => {
+    if (!open || caseId == null) return;
+    setLoading(true);
+    setError(null);
+    setFileIndex(0);
+    getPlagiarismCaseDiff(moduleId, assignmentId, caseId)
+      .then((res) => {
+        if (res.success) {
+          setDiff(res.data);
+        } else {
+          setDiff(null);
+          setError(res.message || 'No matched code available for this case');
+        }
+      })
+      .catch(() => message.error('Failed to load matched code'))
+      .finally(() => setLoading(false));
+  }, [open, moduleId, assignmentId, caseId]);
+
+  const pair = diff?.files[fileIndex];
+  const ranges = useMemo(
+    () => ({
+      left: pair?.regions.map((r) => ({ start: r.start_1, end: r.end_1 })) ?? [],
+      right: pair?.regions.map((r) => ({ start: r.start_2, end: r.end_2 })) ?? [],
+    }),
+    [pair],
+  );
+
+  return (
+    <Modal
+      title={caseId != null ? `Case #${caseId} — matched code` : 'Matched code'}
+      open={open}
+      onCancel={onClose}
+      footer={null}
+      width="90vw"
+      destroyOnClose
+    >
+      {loading ? (
+        <div className="flex justify-center p-8">
+          <Spin />
+        </div>
+      ) : error ? (
+        <Alert type="info" showIcon message={error} />
+      ) : !diff || !pair ? (
+        <Empty description="No matched files" />
+      ) : (
+        <div className="flex flex-col gap-3">
+          <div className="flex flex-wrap items-center gap-2">
+            <Select
+              className="min-w-[320px]"
+              value={fileIndex}
+              onChange={setFileIndex}
+              options={diff.files.map((f, i) => ({
+                value: i,
+                label: `${f.file_1.name} ↔ ${f.file_2.name} (${f.lines_matched} lines)`,
+              }))}
+            />
+            <Tag>{pair.regions.length} matched region(s)</Tag>
+            {!pair.regions.length && (
+              <Typography.Text type="secondary">
+                The report did not include line ranges for this file pair.
+              </Typography.Text>
+            )}
+          </div>
+          <div className="flex gap-3">
+            <CodePane title={diff.submission_1.username} file={pair.file_1} ranges={ranges.left} />
+            <CodePane
+              title={diff.submission_2.username}
+              file={pair.file_2}
+              ranges={ranges.right}
+            />
+          </div>
+        </div>
+      )}
+    </Modal>
+  );
+};
+
+export default PlagiarismDiffModal;
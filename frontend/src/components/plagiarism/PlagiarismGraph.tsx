@@ -26,12 +26,22 @@ import {
   SearchOutlined,
   ReloadOutlined,
   DeploymentUnitOutlined,
+  DownloadOutlined,
 } from '@ant-design/icons';
 import * as THREE from 'three';
 import { useTheme } from '@/context/ThemeContext';
 import { scaleColor } from '@/utils/color';
-import { getPlagiarismGraph, listMossReports } from '@/services/modules/assignments/plagiarism/get';
-import type { MossReport, PlagiarismGraphLink } from '@/types/modules/assignments/plagiarism';
+import {
+  exportPlagiarismGraph,
+  getPlagiarismGraph,
+  listMossReports,
+} from '@/services/modules/assignments/plagiarism/get';
+import type {
+  MossReport,
+  PlagiarismGraphExportFormat,
+  PlagiarismGraphLink,
+} from '@/types/modules/assignments/plagiarism';
+import { message } from '@/utils/message';
 
 // ---------- small tips ----------
 const Tips: React.FC = () => (
@@ -268,19 +278,28 @@ const PlagiarismGraph: React.FC<PlagiarismGraphProps> = ({
     return () => cancelAnimationFrame(raf);
   }, [open, mode]);
 
+  // current filters as query params
+  const filterParams = useCallback(
+    (overrides?: { user?: string }) => {
+      const params: any = {};
+      if (status !== 'all') params.status = status;
+      params.min_similarity = simRange[0];
+      params.max_similarity = simRange[1];
+      if (reportId !== 'all') params.report_id = reportId;
+      const userParam = overrides?.user ?? username.trim();
+      if (userParam) params.user = userParam;
+      return params;
+    },
+    [status, simRange, username, reportId],
+  );
+
   // fetch graph
   const fetchGraph = useCallback(
     async (overrides?: { user?: string }) => {
       setLoading(true);
       setError(null);
       try {
-        const params: any = {};
-        if (status !== 'all') params.status = status;
-        params.min_similarity = simRange[0];
-        params.max_similarity = simRange[1];
-        if (reportId !== 'all') params.report_id = reportId;
-        const userParam = overrides?.user ?? username.trim();
-        if (userParam) params.user = userParam;
+        const params = filterParams(overrides);
 
         const res = await getPlagiarismGraph(moduleId, assignmentId, params);
         if (res.success) {
@@ -299,7 +318,19 @@ const PlagiarismGraph: React.FC<PlagiarismGraphProps> = ({
         setLoading(false);
       }
     },
-    [moduleId, assignmentId, status, simRange, username, reportId, applyNodeFilter],
+    [moduleId, assignmentId, filterParams, applyNodeFilter],
+  );
+
+  // download the filtered graph for Gephi / yEd / Graphviz
+  const exportGraph = useCallback(
+    async (format: PlagiarismGraphExportFormat) => {
+      try {
+        await exportPlagiarismGraph(moduleId, assignmentId, format, filterParams());
+      } catch {
+        message.error('Failed to export graph');
+      }
+    },
+    [moduleId, assignmentId, filterParams],
   );
 
   // canvas size
@@ -684,6 +715,18 @@ const PlagiarismGraph: React.FC<PlagiarismGraphProps> = ({
                     >
                       Reset fields
                     </Button>
+                    <Dropdown
+                      menu={{
+                        items: [
+                          { key: 'graphml', label: 'GraphML (Gephi, yEd)' },
+                          { key: 'dot', label: 'DOT (Graphviz)' },
+                          { key: 'csv', label: 'CSV edge list' },
+                        ],
+                        onClick: ({ key }) => exportGraph(key as PlagiarismGraphExportFormat),
+                      }}
+                    >
+                      <Button icon={<DownloadOutlined />}>Export</Button>
+                    </Dropdown>
                     <Button
                       type="primary"
                       loading={loading}
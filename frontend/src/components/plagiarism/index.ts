@@ -6,4 +6,4 @@ export { default as PlagiarismGraph } from "./PlagiarismGraph";
 export { default as MossRunModal } from "./MossRunModal";
 export { default as MossReportsCard } from "./MossReportsCard";
 export { default as PlagiarismCasesPanel } from './PlagiarismCasesPanel';
-export { default as HashScanModal } from "./HashScanModal";
\ No newline at end of file
+export { default as HashScanModal } from "./HashScanModal";export { default as PlagiarismDiffModal } from "./PlagiarismDiffModal";
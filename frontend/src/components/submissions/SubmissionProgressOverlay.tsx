@@ -14,7 +14,13 @@ import {
   SUBMISSION_STATUSES,
   type SubmissionStatus,
 } from '@/types/modules/assignments/submissions';
-import { useWs, useWsEvents, Topics, type SubmissionStatusPayload } from '@/ws';
+import {
+  useWs,
+  useWsEvents,
+  Topics,
+  type GaProgressPayload,
+  type SubmissionStatusPayload,
+} from '@/ws';
 
 const { Title } = Typography;
 
@@ -71,6 +77,16 @@ function statusTitle(status: SubmissionStatus | 'queued' | 'connecting') {
   }
 }
 
+function gaSubtitle(ga: GaProgressPayload) {
+  const parts = [
+    `Generation ${ga.generation + 1} of ${ga.generations}`,
+    `best fitness ${ga.best_fitness.toFixed(3)}`,
+  ];
+  if (ga.coverage_percent != null) parts.push(`${ga.coverage_percent.toFixed(1)}% coverage`);
+  parts.push(`${ga.evaluated} chromosomes evaluated`);
+  return parts.join(' · ');
+}
+
 function statusSubtitle(status: SubmissionStatus | 'queued' | 'connecting') {
   switch (status) {
     case 'connecting':
@@ -162,6 +178,15 @@ export default function SubmissionProgressOverlay({
   const progress = latest ?? null;
   const activeSubmissionId = submissionId ?? progress?.submissionId ?? null;
 
+  // Per-generation progress of GATLAM / code coverage runs
+  const [ga, setGa] = useState<GaProgressPayload | null>(null);
+  useWsEvents(activeSubmissionId != null ? [Topics.gaRun(activeSubmissionId)] : [], {
+    'ga.progress': (p: GaProgressPayload) => {
+      if (p.submission_id !== activeSubmissionId) return; // safety
+      setGa(p);
+    },
+  });
+
   const isSubmissionStatus = (v: unknown): v is SubmissionStatus =>
     typeof v === 'string' && (SUBMISSION_STATUSES as readonly string[]).includes(v);
 
@@ -174,12 +199,20 @@ export default function SubmissionProgressOverlay({
 
   const isFailed = rawStatus.startsWith('failed_');
   const isGraded = rawStatus === 'graded';
+  // While a GA runs, fill the running → grading span by generations finished
+  const gaRunning = rawStatus === 'running' && ga != null && ga.generations > 0;
   const linearPercent =
     rawStatus === 'connecting'
       ? 5
-      : isSubmissionStatus(rawStatus)
-        ? STATUS_PROGRESS[rawStatus]
-        : 10;
+      : gaRunning
+        ? Math.round(
+            STATUS_PROGRESS.running +
+              ((STATUS_PROGRESS.grading - STATUS_PROGRESS.running) * (ga.generation + 1)) /
+                ga.generations,
+          )
+        : isSubmissionStatus(rawStatus)
+          ? STATUS_PROGRESS[rawStatus]
+          : 10;
 
   const mark = progress?.mark ?? undefined;
   const pct = mark && mark.total > 0 ? Math.round((mark.earned / mark.total) * 100) : null;
@@ -276,7 +309,7 @@ export default function SubmissionProgressOverlay({
         <Result
           icon={<LoadingOutlined style={{ fontSize: 40 }} spin />}
           title={statusTitle(rawStatus)}
-          subTitle={statusSubtitle(rawStatus)}
+          subTitle={gaRunning ? gaSubtitle(ga) : statusSubtitle(rawStatus)}
         />
       )}
 
@@ -22,13 +22,17 @@ type Props = {
 
 const { Text, Title } = Typography;
 
+/** Archives plus plain source files (zipped server-side before running). */
+export const SUBMISSION_ACCEPT =
+  '.zip,.tar,.gz,.tgz,.7z,.rar,.c,.cc,.cpp,.cxx,.h,.hpp,.java,.py,.rs,.go,.js,.ts,.cs,.hs,.pl,.ml,.m,.pas,.f90,.vhd,.asm,.s,.sql';
+
 const SubmitAssignmentModal = ({
   open,
   loading = false,
   onClose,
   onSubmit,
   title = 'Submit Assignment',
-  accept = '.zip,.tar,.gz,.tgz',
+  accept = SUBMISSION_ACCEPT,
   maxSizeMB = 50,
   defaultIsPractice = false,
   allowPractice = true,
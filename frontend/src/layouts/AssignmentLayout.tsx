@@ -746,7 +746,6 @@ const AssignmentLayout = () => {
           onSubmit={handleSubmitAssignment}
           loading={loading}
           title={`Submit: ${assignment.name}`}
-          accept=".zip,.tar,.gz,.tgz"
           maxSizeMB={50}
           defaultIsPractice={false}
           allowPractice={policy?.allow_practice_submissions && !auth.isStaff(module.id)}
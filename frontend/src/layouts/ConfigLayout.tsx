@@ -1,5 +1,5 @@
 import { useMemo, useState } from 'react';
-import { Typography, Menu, Button, Upload, Space, Tooltip } from 'antd';
+import { Typography, Menu, Button, Upload, Space, Tooltip, Modal } from 'antd';
 import { Link, Outlet, useLocation } from 'react-router-dom';
 import { UploadOutlined, DownloadOutlined } from '@ant-design/icons';
 import { useAssignment } from '@/context/AssignmentContext';
@@ -8,7 +8,10 @@ import { listAssignmentFiles, downloadAssignmentFile } from '@/services/modules/
 import { getAssignmentConfig, setAssignmentConfig } from '@/services/modules/assignments/config';
 import Tip from '@/components/common/Tip';
 import { requiresMainForMode, requiresInterpreterForMode } from '@/policies/submission';
-import type { SubmissionMode } from '@/types/modules/assignments/config';
+import type {
+  ConfigValidationErrors,
+  SubmissionMode,
+} from '@/types/modules/assignments/config';
 
 type MenuKey =
   | 'assignment'
@@ -111,6 +114,24 @@ const ConfigLayout = () => {
           throw new Error('Config JSON must be an object');
         }
         const res = await setAssignmentConfig(moduleId, assignmentId, parsed);
+        const problems = (res?.data as ConfigValidationErrors | undefined)?.errors;
+        if (!res?.success && problems?.length) {
+          Modal.error({
+            title: 'Config not imported',
+            width: 640,
+            content: (
+              <ul className="pl-4 mb-0">
+                {problems.map((p, i) => (
+                  <li key={i}>
+                    {p.path && <Typography.Text code>{p.path}</Typography.Text>} {p.message}
+                  </li>
+                ))}
+              </ul>
+            ),
+          });
+          onError?.(new Error(res.message));
+          return;
+        }
         if (!res?.success) throw new Error(res?.message || 'Failed to save config');
         message.success('Config imported and saved.');
         await refreshAssignment?.();
@@ -21,7 +21,8 @@ const DEFAULTS_JSON = `{
     "max_memory": 8589934592,
     "max_cpus": 2,
     "max_uncompressed_size": 100000000,
-    "max_processes": 256
+    "max_processes": 256,
+    "max_disk_bytes": 1073741824
   }
 }`;
 
@@ -78,6 +79,13 @@ const limitRows = [
     options: 'Integer ≥ 1',
     def: '256',
   },
+  {
+    key: 'disk',
+    setting: 'Max disk usage',
+    meaning: 'Space the run may use in /code and /output. Going over stops the command.',
+    options: 'Number (bytes), 0 = no limit',
+    def: '≈ 1 GiB',
+  },
 ];
 
 export default function ExecutionHelp() {
@@ -194,7 +202,7 @@ export default function ExecutionHelp() {
         type="warning"
         showIcon
         message="Remember"
-        description="If a run hits any limit (time, memory, processes), that task attempt fails and is reported in the results."
+        description="If a run hits any limit (time, memory, processes, disk), that task attempt fails and is reported in the results."
       />
 
       <section id="json" className="scroll-mt-24" />
@@ -208,7 +216,8 @@ export default function ExecutionHelp() {
         <Text code>max_memory</Text> → Memory limit,&nbsp;
         <Text code>max_cpus</Text> → CPU cores,&nbsp;
         <Text code>max_uncompressed_size</Text> → Max extracted size,&nbsp;
-        <Text code>max_processes</Text> → Max processes/threads.
+        <Text code>max_processes</Text> → Max processes/threads,&nbsp;
+        <Text code>max_disk_bytes</Text> → Max disk usage.
       </Paragraph>
       <Card>
         <Paragraph className="mb-2">Defaults:</Paragraph>
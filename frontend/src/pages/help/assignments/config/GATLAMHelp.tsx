@@ -30,14 +30,28 @@ const DEFAULTS_JSON = `{
     ],
     "crossover_type": "onepoint",
     "mutation_type": "bitflip",
+    "selection_type": "roulette",
+    "tournament_size": 3,
+    "elitism_count": 0,
+    "islands": 1,
+    "migration_interval": 5,
+    "migration_count": 2,
     "omega1": 0.5,
     "omega2": 0.3,
     "omega3": 0.2,
+    "objective": "properties",
+    "coverage_weight": 0.5,
+    "search_strategy": "genetic",
+    "initial_temperature": 1.0,
+    "cooling_rate": 0.95,
     "task_spec": {
       "valid_return_codes": [0],
       "max_runtime_ms": null,
-      "forbidden_outputs": []
+      "forbidden_outputs": [],
+      "rules": []
     },
+    "interpreter_cache_size": 256,
+    "interpreter_cache_ttl_secs": 3600,
     "max_parallel_chromosomes": 4,
     "verbose": false
   }
@@ -52,19 +66,39 @@ const EXAMPLE_JSON = `{
     "crossover_probability": 0.9,
     "mutation_probability": 0.02,
     "genes": [
-      { "min_value": -10, "max_value": 10 },
-      { "min_value": 0, "max_value": 20 }
+      { "min_value": -10, "max_value": 10, "invalid_values": [0], "repair": "resample" },
+      { "kind": "categorical", "values": ["push", "pop", "peek"] },
+      { "kind": "string", "min_length": 1, "max_length": 8, "alphabet": "abc123" }
     ],
     "crossover_type": "uniform",
     "mutation_type": "scramble",
+    "selection_type": "tournament",
+    "tournament_size": 4,
+    "elitism_count": 2,
+    "islands": 3,
+    "migration_interval": 5,
+    "migration_count": 2,
     "omega1": 0.5,
     "omega2": 0.3,
     "omega3": 0.2,
+    "objective": "weighted_sum",
+    "coverage_weight": 0.4,
+    "search_strategy": "genetic",
+    "initial_temperature": 1.0,
+    "cooling_rate": 0.95,
     "task_spec": {
       "valid_return_codes": [0],
       "max_runtime_ms": 2000,
-      "forbidden_outputs": ["forbidden", "BAD"]
+      "forbidden_outputs": ["forbidden", "BAD"],
+      "rules": [
+        { "type": "contains", "value": "done" },
+        { "type": "ordering", "values": ["push", "pop"], "task_number": 2 },
+        { "type": "forbidden_sequence", "values": ["pop", "pop"] },
+        { "type": "max_runtime", "ms": 500, "task_number": 3 }
+      ]
     },
+    "interpreter_cache_size": 512,
+    "interpreter_cache_ttl_secs": 7200,
     "max_parallel_chromosomes": 6,
     "verbose": false
   }
@@ -128,13 +162,100 @@ const optionRows = [
     options: 'Bit-flip / Swap / Scramble',
     def: 'Bit-flip',
   },
+  {
+    key: 'selop',
+    setting: 'Selection type',
+    meaning:
+      'How parents are picked: by fitness share (roulette), best of a random group (tournament) or by fitness ranking (rank).',
+    options: 'Roulette / Tournament / Rank',
+    def: 'Roulette',
+  },
+  {
+    key: 'tsize',
+    setting: 'Tournament size',
+    meaning: 'Candidates per tournament; larger means stronger selection pressure.',
+    options: 'Integer ≥ 1',
+    def: '3',
+  },
+  {
+    key: 'elite',
+    setting: 'Elitism',
+    meaning: 'Fittest chromosomes copied unchanged into the next generation (per island).',
+    options: 'Integer (≤ population)',
+    def: '0',
+  },
+  {
+    key: 'islands',
+    setting: 'Islands & migration',
+    meaning:
+      'Splits the population into islands that evolve independently; every migration_interval generations each island sends its migration_count fittest chromosomes to the next island. Helps keep diversity on hard assignments.',
+    options: 'islands ≥ 1; migration_interval ≥ 0 (0 = never); migration_count ≥ 0',
+    def: '1 / 5 / 2',
+  },
+  {
+    key: 'objective',
+    setting: 'Objective',
+    meaning:
+      'Optimize the weighted components alone, or together with code coverage in one run: as a weighted sum, or by Pareto ranking (non-dominated chromosomes score highest).',
+    options: 'Properties / Weighted sum / Pareto',
+    def: 'Properties',
+  },
+  {
+    key: 'covw',
+    setting: 'Coverage weight',
+    meaning: 'Share of the fitness given to coverage when the objective is a weighted sum.',
+    options: '0.0 – 1.0',
+    def: '0.5',
+  },
+  {
+    key: 'strategy',
+    setting: 'Search strategy',
+    meaning:
+      'Searches the genes with the genetic algorithm, or with simulated annealing / random search as baselines to compare it against (same candidates per generation).',
+    options: 'genetic / simulated_annealing / random_search',
+    def: 'genetic',
+  },
+  {
+    key: 'anneal',
+    setting: 'Annealing temperature',
+    meaning:
+      'Simulated annealing only: starting temperature, and the factor it is multiplied by after every generation.',
+    options: 'initial_temperature ≥ 0; cooling_rate 0.0 – 1.0',
+    def: '1.0 / 0.95',
+  },
   {
     key: 'genes',
     setting: 'Genes (search ranges)',
-    meaning: 'Bounds for each tunable gene.',
-    options: 'List of {min_value, max_value}',
+    meaning:
+      'Each tunable gene: an integer range, a categorical list of values (commands, flags, …) or a bounded string. Genes are comma-separated in the interpreter payload.',
+    options: (
+      <span>
+        integer {'{min_value, max_value}'} / categorical {'{values}'} / string{' '}
+        {'{min_length, max_length, alphabet}'}
+      </span>
+    ),
     def: '[{-5..5}, {-4..9}]',
   },
+  {
+    key: 'gene_repair',
+    setting: 'Gene repair',
+    meaning:
+      'Per gene: what happens when a gene decodes out of range or to one of its invalid_values. Resample draws a new valid value, clamp moves to the nearest valid one, penalty keeps it but subtracts penalty from the fitness.',
+    options: (
+      <span>
+        repair: none / resample / clamp / penalty; penalty ≥ 0; invalid_values (integer genes)
+      </span>
+    ),
+    def: 'none (penalty 0.5)',
+  },
+  {
+    key: 'task_per',
+    setting: 'Per-task specs',
+    meaning:
+      'Give tasks different return codes, time bounds, forbidden outputs and rules by keying task_spec by task number. Tasks without an entry use the defaults.',
+    options: '{ "1": { ...spec }, "2": { ...spec } } or a single spec',
+    def: 'Single spec',
+  },
   {
     key: 'task_ret',
     setting: 'Valid return codes',
@@ -156,6 +277,23 @@ const optionRows = [
     options: 'List of strings',
     def: '[]',
   },
+  {
+    key: 'task_rules',
+    setting: 'Property rules',
+    meaning:
+      'Extra properties per task, each counted like a built-in LTL property: output contains a substring, substrings appear in order, lines never appear in sequence, or runtime stays under a bound. Leave the task empty to apply a rule to every task.',
+    options:
+      'List of { type: contains | ordering | forbidden_sequence | max_runtime, value / values / ms, task_number? }',
+    def: '[]',
+  },
+  {
+    key: 'icache',
+    setting: 'Interpreter cache',
+    meaning:
+      'Sources the interpreter generated for a payload are reused when the same payload comes up again (in GA and RNG runs), skipping the interpreter run.',
+    options: 'interpreter_cache_size ≥ 0 (0 disables); interpreter_cache_ttl_secs ≥ 1',
+    def: '256 / 3600',
+  },
   {
     key: 'par',
     setting: 'Parallel chromosomes',
@@ -121,7 +121,7 @@ const DEFAULTS_JSON = `{
   "marking": {
     "marking_scheme": "exact",
     "feedback_scheme": "auto",
-    "deliminator": "###",
+    "delimiter": "###",
     "grading_policy": "last",
     "max_attempts": 10,
     "limit_attempts": false,
@@ -135,7 +135,7 @@ const COMMON_VARIANT_JSON = `{
   "marking": {
     "marking_scheme": "percentage",
     "feedback_scheme": "manual",
-    "deliminator": "###",
+    "delimiter": "###",
     "grading_policy": "best",
     "max_attempts": 3,
     "limit_attempts": true,
@@ -288,9 +288,23 @@ export default function MarkingHelp() {
             <Text code>Runtime.getRuntime()</Text>
           </li>
         </ul>
+        <Paragraph className="mt-3 mb-1">
+          Entries are plain text by default. Two prefixes make them stricter:
+        </Paragraph>
+        <ul className="list-disc pl-5">
+          <li>
+            <Text code>re:</Text> — the rest is a regular expression, e.g.{' '}
+            <Text code>{'re:\\bsystem\\s*\\('}</Text>.
+          </li>
+          <li>
+            <Text code>code:</Text> — only match in code, ignoring comments and string literals,
+            e.g. <Text code>code:system(</Text>. Combine as <Text code>code:re:...</Text>.
+          </li>
+        </ul>
         <Paragraph className="mt-3 mb-0">
-          Tips: keep patterns precise (avoid broad words), prefer API/package names, and review
-          flags to tune the list.
+          Binary files are skipped. A flagged submission shows the file, line, and entry that
+          matched. Tips: keep patterns precise (avoid broad words), prefer API/package names, and
+          review flags to tune the list.
         </Paragraph>
       </Card>
 
@@ -320,7 +334,7 @@ export default function MarkingHelp() {
         <Text code className="ml-1">
           marking_scheme
         </Text>
-        , <Text code>feedback_scheme</Text>, <Text code>deliminator</Text>,{' '}
+        , <Text code>feedback_scheme</Text>, <Text code>delimiter</Text>,{' '}
         <Text code>grading_policy</Text>, <Text code>limit_attempts</Text>,{' '}
         <Text code>max_attempts</Text>, <Text code>pass_mark</Text>,{' '}
         <Text code>allow_practice_submissions</Text>, <Text code>dissalowed_code</Text>.
@@ -410,6 +424,10 @@ export default function MarkingHelp() {
                   Add precise patterns (e.g., <Text code>{'import java.net.*'}</Text>), then re-run.
                 </li>
                 <li>Confirm students didn’t obfuscate imports/includes.</li>
+                <li>
+                  A <Text code>code:</Text> entry never matches inside comments or strings; drop the
+                  prefix to match those too.
+                </li>
               </ul>
             ),
           },
@@ -152,7 +152,19 @@ export default function ConceptCodeCoverage() {
             <Text code>lcov</Text>.
           </li>
           <li>
-            <b>Rust</b>: consider <Text code>cargo llvm-cov</Text> or per-task coverage command.
+            <b>Python</b>: run the tasks under <Text code>coverage run -a</Text>, then print{' '}
+            <Text code>coverage xml -o -</Text> or <Text code>coverage json -o -</Text>; statements
+            count as lines.
+          </li>
+          <li>
+            <b>Rust</b>: build with <Text code>-C instrument-coverage</Text>, merge the profiles
+            with <Text code>llvm-profdata</Text> and print <Text code>llvm-cov report</Text>; the
+            report table is parsed per file.
+          </li>
+          <li>
+            <b>Go</b>: build with <Text code>go build -cover</Text> (or use{' '}
+            <Text code>go test -coverprofile</Text>) and print the cover profile; statements count
+            as lines. Plain <Text code>coverage: N% of statements</Text> lines are accepted too.
           </li>
           <li>
             <b>Java</b>: tooling like JaCoCo can be invoked in the task command. Normalization to
@@ -160,6 +172,19 @@ export default function ConceptCodeCoverage() {
           </li>
         </ul>
       </Card>
+      <Alert
+        className="!mt-2"
+        type="info"
+        showIcon
+        message="Standard formats"
+        description={
+          <>
+            Whatever the language, a task that prints an LCOV tracefile (<Text code>lcov.info</Text>)
+            or a Cobertura XML report is recognised automatically, so any toolchain that can emit
+            one of those works without a language-specific parser.
+          </>
+        }
+      />
       <Alert
         className="!mt-2"
         type="info"
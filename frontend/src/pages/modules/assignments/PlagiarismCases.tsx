@@ -18,6 +18,7 @@ import { listMossReports } from '@/services/modules/assignments/plagiarism/get';
 import { getSubmissions } from '@/services/modules/assignments/submissions';
 import type { Submission } from '@/types/modules/assignments/submissions';
 import {
+  CodeOutlined,
   DeleteOutlined,
   DeploymentUnitOutlined,
   EditOutlined,
@@ -27,7 +28,7 @@ import {
 } from '@ant-design/icons';
 import { EntityList, type EntityListHandle, type EntityListProps } from '@/components/EntityList';
 import { message } from '@/utils/message';
-import { Typography, type TreeSelectProps } from 'antd';
+import { Tag, Typography, type TreeSelectProps } from 'antd';
 import { useModule } from '@/context/ModuleContext';
 import { useAssignment } from '@/context/AssignmentContext';
 import { useViewSlot } from '@/context/ViewSlotContext';
@@ -39,6 +40,7 @@ import {
   MossReportsCard,
   PlagiarismGraph,
   HashScanModal,
+  PlagiarismDiffModal,
 } from '@/components/plagiarism';
 import PlagiarismStatusTag from '@/components/plagiarism/PlagiarismStatusTag';
 import { formatModuleCode } from '@/utils/modules';
@@ -196,6 +198,7 @@ const PlagiarismCases = () => {
   const [editingItem, setEditingItem] = useState<PlagiarismCaseItem | null>(null);
 
   const [graphOpen, setGraphOpen] = useState(false);
+  const [diffCaseId, setDiffCaseId] = useState<number | null>(null);
 
   // Run MOSS modal
   const [mossOpen, setMossOpen] = useState(false);
@@ -415,6 +418,13 @@ const PlagiarismCases = () => {
         },
       };
 
+      const compareAction = {
+        key: 'compare',
+        label: 'Compare Code',
+        icon: <CodeOutlined />,
+        handler: () => setDiffCaseId(entity.id),
+      };
+
       const deleteAction = {
         key: 'delete',
         label: 'Delete',
@@ -460,6 +470,7 @@ const PlagiarismCases = () => {
       const result = [];
       if (primaryAction) result.push(primaryAction);
       if (putEditInDropdown) result.push(editAction);
+      result.push(compareAction);
       result.push(deleteAction);
 
       return result;
@@ -554,27 +565,30 @@ const PlagiarismCases = () => {
                   const stopRow = (e: React.SyntheticEvent) => {
                     e.stopPropagation();
                   };
-                  // stopPropagation so clicking the link doesn't open the case row route
-                  return (
-                    // wrapper also stops bubbling just in case
-                    <span onClick={stopRow} onMouseDown={stopRow} onKeyDown={stopRow}>
+                  // archive submissions (historical cases) live in another assignment
+                  const side = (sub: PlagiarismCaseItem['submission_1'], path: string) =>
+                    sub.assignment_id === assignmentId ? (
                       <Link
-                        to={s1Path}
+                        to={path}
                         onClick={stopRow}
                         onMouseDown={stopRow}
                         className="font-medium"
                       >
-                        {c.submission_1.user.username}
-                      </Link>{' '}
-                      vs{' '}
-                      <Link
-                        to={s2Path}
-                        onClick={stopRow}
-                        onMouseDown={stopRow}
-                        className="font-medium"
-                      >
-                        {c.submission_2.user.username}
+                        {sub.user.username}
                       </Link>
+                    ) : (
+                      <span className="font-medium">{sub.user.username}</span>
+                    );
+                  // stopPropagation so clicking the link doesn't open the case row route
+                  return (
+                    // wrapper also stops bubbling just in case
+                    <span onClick={stopRow} onMouseDown={stopRow} onKeyDown={stopRow}>
+                      {side(c.submission_1, s1Path)} vs {side(c.submission_2, s2Path)}
+                      {c.historical && (
+                        <Tag color="purple" className="!ml-2">
+                          Historical
+                        </Tag>
+                      )}
                     </span>
                   );
                 },
@@ -680,6 +694,7 @@ const PlagiarismCases = () => {
               loading={reportsLoading}
               onOpenRunMoss={() => setMossOpen(true)}
               onRefresh={loadReports}
+              onCaseCreated={() => listRef.current?.refresh()}
             />
           </aside>
         )}
@@ -753,6 +768,15 @@ const PlagiarismCases = () => {
         title={`Plagiarism Graph (${formatModuleCode(moduleDetails.code)} • ${assignment.name})`}
       />
 
+      {/* Side-by-side matched code */}
+      <PlagiarismDiffModal
+        open={diffCaseId != null}
+        onClose={() => setDiffCaseId(null)}
+        moduleId={moduleId}
+        assignmentId={assignmentId}
+        caseId={diffCaseId}
+      />
+
       {/* Run MOSS modal */}
       <MossRunModal
         open={mossOpen}
@@ -4,7 +4,11 @@ import SettingsGroup from '@/components/SettingsGroup';
 import { useViewSlot } from '@/context/ViewSlotContext';
 import { useAssignment } from '@/context/AssignmentContext';
 import { message } from '@/utils/message';
-import type { AssignmentConfig } from '@/types/modules/assignments/config';
+import {
+  COVERAGE_METRIC_OPTIONS,
+  type AssignmentConfig,
+  type CoverageMetric,
+} from '@/types/modules/assignments/config';
 import AssignmentConfigActions from '@/components/assignments/AssignmentConfigActions';
 import Tip from '@/components/common/Tip';
 import { useBreadcrumbContext } from '@/context/BreadcrumbContext';
@@ -13,6 +17,10 @@ import useConfigBackTo from '@/hooks/useConfigBackTo';
 type FormShape = {
   code_coverage_weight: number;
   code_coverage_whitelist: string[];
+  metric: CoverageMetric;
+  weight_line: number;
+  weight_branch: number;
+  weight_function: number;
 };
 
 export default function CodeCoveragePage() {
@@ -22,6 +30,7 @@ export default function CodeCoveragePage() {
   const { setBreadcrumbLabel } = useBreadcrumbContext();
 
   const [form] = Form.useForm<FormShape>();
+  const metric = Form.useWatch('metric', form);
 
   useEffect(() => {
     setBreadcrumbLabel(
@@ -51,6 +60,10 @@ export default function CodeCoveragePage() {
         typeof cc.code_coverage_weight === 'number' ? cc.code_coverage_weight : 10,
       // Whitelist is a simple list of file names to count toward coverage.
       code_coverage_whitelist: Array.isArray(cc.whitelist) ? cc.whitelist : [],
+      metric: cc.metric ?? 'line',
+      weight_line: cc.weights?.line ?? 0.5,
+      weight_branch: cc.weights?.branch ?? 0.3,
+      weight_function: cc.weights?.function ?? 0.2,
     });
   }, [config?.code_coverage, form]);
 
@@ -64,6 +77,12 @@ export default function CodeCoveragePage() {
           whitelist: (values.code_coverage_whitelist || [])
             .map((s) => String(s).trim())
             .filter(Boolean),
+          metric: values.metric,
+          weights: {
+            line: values.weight_line,
+            branch: values.weight_branch,
+            function: values.weight_function,
+          },
         },
       };
       await updateConfig(patch);
@@ -94,6 +113,38 @@ export default function CodeCoveragePage() {
           <InputNumber className="w-full" min={0} max={100} precision={2} step={0.25} />
         </Form.Item>
 
+        <Form.Item
+          name="metric"
+          label="Coverage Metric"
+          className="w-full max-w-xs"
+          tooltip="Branch and function coverage fall back to line coverage when the coverage tool does not report them."
+        >
+          <Select options={COVERAGE_METRIC_OPTIONS} />
+        </Form.Item>
+
+        {metric === 'weighted' && (
+          <Space size="middle" wrap>
+            <Form.Item name="weight_line" label="Line weight" rules={[{ type: 'number', min: 0 }]}>
+              <InputNumber min={0} step={0.1} />
+            </Form.Item>
+            <Form.Item
+              name="weight_branch"
+              label="Branch weight"
+              rules={[{ type: 'number', min: 0 }]}
+            >
+              <InputNumber min={0} step={0.1} />
+            </Form.Item>
+            <Form.Item
+              name="weight_function"
+              label="Function weight"
+              rules={[{ type: 'number', min: 0 }]}
+              extra="Weights are relative; they need not sum to 1."
+            >
+              <InputNumber min={0} step={0.1} />
+            </Form.Item>
+          </Space>
+        )}
+
         <Form.Item
           name="code_coverage_whitelist"
           label="Coverage File Whitelist"
@@ -37,6 +37,7 @@ export default function ExecutionPage() {
       ...exec,
       max_memory: toMB(exec.max_memory),
       max_uncompressed_size: toMB(exec.max_uncompressed_size),
+      max_disk_bytes: toMB(exec.max_disk_bytes),
     });
   }, [config?.execution, form]);
 
@@ -49,6 +50,7 @@ export default function ExecutionPage() {
           ...values,
           max_memory: toBytes(values.max_memory),
           max_uncompressed_size: toBytes(values.max_uncompressed_size),
+          max_disk_bytes: toBytes(values.max_disk_bytes),
         },
       });
       message.success('Execution config saved');
@@ -112,6 +114,16 @@ export default function ExecutionPage() {
             <InputNumber min={1} className="w-full" />
           </Form.Item>
 
+          <Form.Item
+            name="max_disk_bytes"
+            label="Max Disk Usage"
+            className={fieldWidth}
+            rules={[{ required: true }]}
+            tooltip="Space /code and /output may use while a command runs. 0 disables the limit."
+          >
+            <InputNumber min={0} className="w-full" addonAfter="MB" />
+          </Form.Item>
+
           <div className="pt-2">
             <AssignmentConfigActions
               primaryText="Save Execution Config"
@@ -21,8 +21,16 @@ import { message } from '@/utils/message';
 
 import {
   CROSSOVER_TYPE_OPTIONS,
+  GA_OBJECTIVE_OPTIONS,
+  GENE_KIND_OPTIONS,
+  GENE_REPAIR_OPTIONS,
   MUTATION_TYPE_OPTIONS,
+  PROPERTY_RULE_TYPE_OPTIONS,
+  SEARCH_STRATEGY_OPTIONS,
+  SELECTION_TYPE_OPTIONS,
+  isPerTaskSpec,
   type GatlamConfig,
+  type TaskSpecConfig,
 } from '@/types/modules/assignments/config';
 
 import AssignmentConfigActions from '@/components/assignments/AssignmentConfigActions';
@@ -33,12 +41,41 @@ import useConfigBackTo from '@/hooks/useConfigBackTo';
 const clamp01 = (n: number) => Math.max(0, Math.min(1, n));
 const round2 = (n: number) => Math.round(n * 100) / 100;
 
+// A per-task `task_spec` is edited as a list of specs, each carrying its task number
+type TaskSpecEntry = TaskSpecConfig & { task_number: number };
+type GatlamFormValues = GatlamConfig & { per_task_spec?: boolean; task_specs?: TaskSpecEntry[] };
+
+const toFormValues = (gatlam: GatlamConfig): GatlamFormValues => {
+  if (!isPerTaskSpec(gatlam.task_spec)) {
+    return { ...gatlam, per_task_spec: false, task_specs: [] };
+  }
+  const task_specs = Object.entries(gatlam.task_spec).map(([n, spec]) => ({
+    ...spec,
+    task_number: Number(n),
+  }));
+  // Shared inputs start from the defaults if the lecturer switches back
+  const task_spec = { valid_return_codes: [0], forbidden_outputs: [], rules: [] };
+  return { ...gatlam, task_spec, per_task_spec: true, task_specs };
+};
+
+const fromFormValues = ({
+  per_task_spec,
+  task_specs,
+  ...gatlam
+}: GatlamFormValues): GatlamConfig => {
+  if (!per_task_spec) return gatlam;
+  const task_spec = Object.fromEntries(
+    (task_specs ?? []).map(({ task_number, ...spec }) => [task_number, spec]),
+  );
+  return { ...gatlam, task_spec };
+};
+
 export default function GatlamPage() {
   useConfigBackTo();
   const { isSm } = useUI();
   const { setValue } = useViewSlot();
   const { config, updateConfig } = useAssignment();
-  const [form] = Form.useForm<GatlamConfig>();
+  const [form] = Form.useForm<GatlamFormValues>();
 
   useEffect(() => {
     setValue(
@@ -54,7 +91,7 @@ export default function GatlamPage() {
   // Seed form from context whenever GATLAM changes
   useEffect(() => {
     if (!config?.gatlam) return;
-    form.setFieldsValue(config.gatlam);
+    form.setFieldsValue(toFormValues(config.gatlam));
   }, [config?.gatlam, form]);
 
   // ---- Live sum & helpers for weights --------------------------------------
@@ -63,6 +100,11 @@ export default function GatlamPage() {
   const w3 = Form.useWatch('omega3', form) ?? 0;
 
   const sum = useMemo(() => w1 + w2 + w3, [w1, w2, w3]);
+  const selectionType = Form.useWatch('selection_type', form);
+  const objective = Form.useWatch('objective', form);
+  const searchStrategy = Form.useWatch('search_strategy', form);
+  const islands = Form.useWatch('islands', form) ?? 1;
+  const perTaskSpec = Form.useWatch('per_task_spec', form) ?? false;
 
   const setWeightsNormalized = (key: 'omega1' | 'omega2' | 'omega3', nextVal: number | null) => {
     const v = clamp01(Number(nextVal ?? 0));
@@ -117,7 +159,7 @@ export default function GatlamPage() {
       message.error('No configuration loaded yet.');
       return;
     }
-    const values = await form.validateFields(); // GatlamConfig
+    const values = fromFormValues(await form.validateFields());
     const S = values.omega1 + values.omega2 + values.omega3;
     if (Math.abs(S - 1) > 1e-6) {
       values.omega1 = values.omega1 / S;
@@ -136,6 +178,173 @@ export default function GatlamPage() {
   const fieldWidth = 'w-full sm:max-w-xs';
   const disabled = !config;
 
+  // Inputs of one gene, depending on its kind (integer range, categorical values, string bounds),
+  // followed by how invalid values are repaired
+  const renderGeneInputs = (name: number, compact: boolean) => {
+    const itemProps = compact ? { noStyle: true } : { className: '!mb-0' };
+    const repairInputs = () => (
+      <>
+        <Form.Item {...itemProps} name={[name, 'repair']} initialValue="none">
+          <Select className="!min-w-28" options={GENE_REPAIR_OPTIONS} />
+        </Form.Item>
+        {form.getFieldValue(['genes', name, 'repair']) === 'penalty' && (
+          <Form.Item {...itemProps} name={[name, 'penalty']} initialValue={0.5}>
+            <InputNumber min={0} step={0.05} className="w-full" placeholder="Penalty" />
+          </Form.Item>
+        )}
+      </>
+    );
+    return (
+      <Form.Item
+        noStyle
+        dependencies={[
+          ['genes', name, 'kind'],
+          ['genes', name, 'repair'],
+        ]}
+      >
+        {() => {
+          const kind = form.getFieldValue(['genes', name, 'kind']) ?? 'integer';
+          if (kind === 'categorical') {
+            return (
+              <>
+                <Form.Item {...itemProps} name={[name, 'values']} rules={[{ required: true }]}>
+                  <Select
+                    mode="tags"
+                    className="w-full"
+                    placeholder="Values"
+                    tokenSeparators={[',']}
+                    open={false}
+                  />
+                </Form.Item>
+                {repairInputs()}
+              </>
+            );
+          }
+          if (kind === 'string') {
+            return (
+              <>
+                <Form.Item {...itemProps} name={[name, 'min_length']} rules={[{ required: true }]}>
+                  <InputNumber min={0} max={64} className="w-full" placeholder="Min length" />
+                </Form.Item>
+                <Form.Item {...itemProps} name={[name, 'max_length']} rules={[{ required: true }]}>
+                  <InputNumber min={1} max={64} className="w-full" placeholder="Max length" />
+                </Form.Item>
+                <Form.Item {...itemProps} name={[name, 'alphabet']}>
+                  <Input className="w-full" placeholder="Alphabet (a-z)" />
+                </Form.Item>
+                {repairInputs()}
+              </>
+            );
+          }
+          return (
+            <>
+              <Form.Item {...itemProps} name={[name, 'min_value']} rules={[{ required: true }]}>
+                <InputNumber className="w-full" placeholder="Min" />
+              </Form.Item>
+              <Form.Item {...itemProps} name={[name, 'max_value']} rules={[{ required: true }]}>
+                <InputNumber className="w-full" placeholder="Max" />
+              </Form.Item>
+              <Form.Item
+                {...itemProps}
+                name={[name, 'invalid_values']}
+                getValueFromEvent={(vals: string[]) =>
+                  vals.map(Number).filter((v) => Number.isInteger(v))
+                }
+              >
+                <Select
+                  mode="tags"
+                  className="w-full"
+                  placeholder="Invalid values"
+                  tokenSeparators={[',']}
+                  open={false}
+                />
+              </Form.Item>
+              {repairInputs()}
+            </>
+          );
+        }}
+      </Form.Item>
+    );
+  };
+
+  // Inputs of one task property rule, depending on its type, followed by the task it targets;
+  // `rulesPath` is the full path of the rules list the rule belongs to
+  const renderRuleInputs = (rulesPath: (string | number)[], name: number, compact: boolean) => {
+    const itemProps = compact ? { noStyle: true } : { className: '!mb-0' };
+    return (
+      <Form.Item noStyle dependencies={[[...rulesPath, name, 'type']]}>
+        {() => {
+          const type = form.getFieldValue([...rulesPath, name, 'type']);
+          return (
+            <>
+              {type === 'contains' && (
+                <Form.Item {...itemProps} name={[name, 'value']} rules={[{ required: true }]}>
+                  <Input className="w-full" placeholder="Substring" />
+                </Form.Item>
+              )}
+              {(type === 'ordering' || type === 'forbidden_sequence') && (
+                <Form.Item {...itemProps} name={[name, 'values']} rules={[{ required: true }]}>
+                  <Select
+                    mode="tags"
+                    className="w-full"
+                    placeholder={type === 'ordering' ? 'Substrings, in order' : 'Lines, in order'}
+                    tokenSeparators={[',']}
+                    open={false}
+                  />
+                </Form.Item>
+              )}
+              {type === 'max_runtime' && (
+                <Form.Item {...itemProps} name={[name, 'ms']} rules={[{ required: true }]}>
+                  <InputNumber min={0} step={100} className="w-full" placeholder="ms" />
+                </Form.Item>
+              )}
+              <Form.Item {...itemProps} name={[name, 'task_number']}>
+                <InputNumber min={1} precision={0} className="w-full" placeholder="Task (all)" />
+              </Form.Item>
+            </>
+          );
+        }}
+      </Form.Item>
+    );
+  };
+
+  // Editable list of property rules; `listName` is relative to an enclosing Form.List (if any)
+  // and `rulesPath` the list's full path
+  const renderRulesList = (listName: (string | number)[], rulesPath: (string | number)[]) => (
+    <Form.List name={listName}>
+      {(fields, { add, remove }) => (
+        <Space direction="vertical" className="w-full">
+          {fields.map((field) => (
+            <div key={field.key} className="w-full">
+              {isSm ? (
+                <Space.Compact className="w-full">
+                  <Form.Item name={[field.name, 'type']} noStyle initialValue="contains">
+                    <Select className="!w-48" options={PROPERTY_RULE_TYPE_OPTIONS} />
+                  </Form.Item>
+                  {renderRuleInputs(rulesPath, field.name, true)}
+                  <Button icon={<DeleteOutlined />} onClick={() => remove(field.name)} danger />
+                </Space.Compact>
+              ) : (
+                <Space direction="vertical" className="w-full">
+                  <Form.Item name={[field.name, 'type']} initialValue="contains" className="!mb-0">
+                    <Select className="w-full" options={PROPERTY_RULE_TYPE_OPTIONS} />
+                  </Form.Item>
+                  {renderRuleInputs(rulesPath, field.name, false)}
+                  <div>
+                    <Button icon={<DeleteOutlined />} onClick={() => remove(field.name)} danger />
+                  </div>
+                </Space>
+              )}
+            </div>
+          ))}
+          <Button onClick={() => add({ type: 'contains' })} icon={<PlusOutlined />}>
+            Add Property Rule
+          </Button>
+        </Space>
+      )}
+    </Form.List>
+  );
+
   return (
     <div className="flex flex-col gap-4">
       <Form form={form} layout="vertical" disabled={disabled}>
@@ -216,12 +425,75 @@ export default function GatlamPage() {
             >
               <Select className="w-full" options={MUTATION_TYPE_OPTIONS} />
             </Form.Item>
+
+            <Form.Item
+              name="selection_type"
+              label="Selection Type"
+              className={fieldWidth}
+              rules={[{ required: true }]}
+            >
+              <Select className="w-full" options={SELECTION_TYPE_OPTIONS} />
+            </Form.Item>
+
+            {selectionType === 'tournament' && (
+              <Form.Item
+                name="tournament_size"
+                label="Tournament Size"
+                className={fieldWidth}
+                rules={[{ required: true }]}
+              >
+                <InputNumber min={1} step={1} precision={0} className="w-full" />
+              </Form.Item>
+            )}
+
+            <Form.Item
+              name="elitism_count"
+              label="Elitism"
+              tooltip="Fittest chromosomes copied unchanged into the next generation (per island)."
+              className={fieldWidth}
+              rules={[{ required: true }]}
+            >
+              <InputNumber min={0} step={1} precision={0} className="w-full" />
+            </Form.Item>
+
+            <Form.Item
+              name="islands"
+              label="Islands"
+              tooltip="Sub-populations that evolve independently and exchange their fittest chromosomes. 1 evolves a single population."
+              className={fieldWidth}
+              rules={[{ required: true }]}
+            >
+              <InputNumber min={1} step={1} precision={0} className="w-full" />
+            </Form.Item>
+
+            {islands > 1 && (
+              <>
+                <Form.Item
+                  name="migration_interval"
+                  label="Migration Interval"
+                  tooltip="Generations between migrations. 0 keeps the islands isolated."
+                  className={fieldWidth}
+                  rules={[{ required: true }]}
+                >
+                  <InputNumber min={0} step={1} precision={0} className="w-full" />
+                </Form.Item>
+                <Form.Item
+                  name="migration_count"
+                  label="Migrants"
+                  tooltip="Fittest chromosomes each island sends to the next one per migration."
+                  className={fieldWidth}
+                  rules={[{ required: true }]}
+                >
+                  <InputNumber min={0} step={1} precision={0} className="w-full" />
+                </Form.Item>
+              </>
+            )}
           </SettingsGroup>
 
           {/* ---- Genes ---- */}
           <SettingsGroup
             title="Genes"
-            description="Define the value ranges for each gene in a chromosome."
+            description="Define each gene of a chromosome: an integer range, a list of values (e.g. commands or flags) or a bounded string."
           >
             <Form.List name="genes">
               {(fields, { add, remove }) => (
@@ -231,20 +503,10 @@ export default function GatlamPage() {
                       {isSm ? (
                         // >= sm: Compact row
                         <Space.Compact className="w-full">
-                          <Form.Item
-                            name={[field.name, 'min_value']}
-                            noStyle
-                            rules={[{ required: true }]}
-                          >
-                            <InputNumber className="w-full" placeholder="Min" />
-                          </Form.Item>
-                          <Form.Item
-                            name={[field.name, 'max_value']}
-                            noStyle
-                            rules={[{ required: true }]}
-                          >
-                            <InputNumber className="w-full" placeholder="Max" />
+                          <Form.Item name={[field.name, 'kind']} noStyle initialValue="integer">
+                            <Select className="!w-40" options={GENE_KIND_OPTIONS} />
                           </Form.Item>
+                          {renderGeneInputs(field.name, true)}
                           <Button
                             icon={<DeleteOutlined />}
                             onClick={() => remove(field.name)}
@@ -255,19 +517,13 @@ export default function GatlamPage() {
                         // < sm: stacked
                         <Space direction="vertical" className="w-full">
                           <Form.Item
-                            name={[field.name, 'min_value']}
-                            rules={[{ required: true }]}
-                            className="!mb-0"
-                          >
-                            <InputNumber className="w-full" placeholder="Min" />
-                          </Form.Item>
-                          <Form.Item
-                            name={[field.name, 'max_value']}
-                            rules={[{ required: true }]}
+                            name={[field.name, 'kind']}
+                            initialValue="integer"
                             className="!mb-0"
                           >
-                            <InputNumber className="w-full" placeholder="Max" />
+                            <Select className="w-full" options={GENE_KIND_OPTIONS} />
                           </Form.Item>
+                          {renderGeneInputs(field.name, false)}
                           <div>
                             <Button
                               icon={<DeleteOutlined />}
@@ -279,7 +535,7 @@ export default function GatlamPage() {
                       )}
                     </div>
                   ))}
-                  <Button onClick={() => add()} icon={<PlusOutlined />}>
+                  <Button onClick={() => add({ kind: 'integer' })} icon={<PlusOutlined />}>
                     Add Gene
                   </Button>
                 </Space>
@@ -369,127 +625,297 @@ export default function GatlamPage() {
             </Space>
           </SettingsGroup>
 
+          {/* ---- Objectives ---- */}
+          <SettingsGroup
+            title="Objectives"
+            description="Optimize the weighted components alone, or together with code coverage in the same run."
+          >
+            <Form.Item
+              name="objective"
+              label="Objective"
+              className="w-full sm:max-w-xs"
+              rules={[{ required: true }]}
+            >
+              <Select className="w-full" options={GA_OBJECTIVE_OPTIONS} />
+            </Form.Item>
+
+            {objective === 'weighted_sum' && (
+              <Form.Item
+                name="coverage_weight"
+                label="Coverage Weight"
+                tooltip="Share of the fitness given to coverage; the rest comes from the component score."
+                className="w-full sm:max-w-xs"
+                rules={[{ required: true }]}
+              >
+                <InputNumber min={0} max={1} step={0.05} className="w-full" />
+              </Form.Item>
+            )}
+          </SettingsGroup>
+
+          {/* ---- Search Strategy ---- */}
+          <SettingsGroup
+            title="Search Strategy"
+            description="Run the genetic algorithm, or a baseline to benchmark it against with the same budget."
+          >
+            <Form.Item
+              name="search_strategy"
+              label="Strategy"
+              className="w-full sm:max-w-xs"
+              rules={[{ required: true }]}
+            >
+              <Select className="w-full" options={SEARCH_STRATEGY_OPTIONS} />
+            </Form.Item>
+
+            {searchStrategy === 'simulated_annealing' && (
+              <>
+                <Form.Item
+                  name="initial_temperature"
+                  label="Initial Temperature"
+                  tooltip="Higher temperatures accept worse neighbours more often early on."
+                  className="w-full sm:max-w-xs"
+                  rules={[{ required: true }]}
+                >
+                  <InputNumber min={0} step={0.1} className="w-full" />
+                </Form.Item>
+                <Form.Item
+                  name="cooling_rate"
+                  label="Cooling Rate"
+                  tooltip="Factor the temperature is multiplied by after every step."
+                  className="w-full sm:max-w-xs"
+                  rules={[{ required: true }]}
+                >
+                  <InputNumber min={0} max={1} step={0.01} className="w-full" />
+                </Form.Item>
+              </>
+            )}
+          </SettingsGroup>
+
           {/* ---- TaskSpec ---- */}
           <SettingsGroup
             title="Task Specification"
             description="Runtime and validation rules for executing chromosomes."
           >
             <Form.Item
-              name={['task_spec', 'max_runtime_ms']}
-              label="Max Runtime"
-              tooltip="Optional hard cap in milliseconds"
+              name="per_task_spec"
+              label="Per-task Specs"
+              tooltip="Give each task (by task number) its own spec; tasks without one use the defaults"
+              valuePropName="checked"
               className={fieldWidth}
             >
-              <InputNumber
-                min={0}
-                max={Number.MAX_VALUE}
-                step={100}
-                className="w-full"
-                addonAfter="ms"
-              />
+              <Switch />
             </Form.Item>
 
-            <Divider className="my-2" />
-
-            <Typography.Text className="block mb-2">Valid Return Codes</Typography.Text>
-            <Form.List name={['task_spec', 'valid_return_codes']}>
-              {(fields, { add, remove }) => (
-                <Space direction="vertical" className="w-full">
-                  {fields.map((field) => (
-                    <div key={field.key} className="w-full">
-                      {isSm ? (
-                        <Space.Compact className="w-full">
-                          <Form.Item name={[field.name]} noStyle rules={[{ required: true }]}>
-                            <InputNumber className="w-full" placeholder="Code" />
-                          </Form.Item>
-                          <Button
-                            icon={<DeleteOutlined />}
-                            onClick={() => remove(field.name)}
-                            danger
-                          />
-                        </Space.Compact>
-                      ) : (
+            {perTaskSpec ? (
+              <Form.List name="task_specs">
+                {(fields, { add, remove }) => (
+                  <Space direction="vertical" className="w-full">
+                    {fields.map((field) => (
+                      <div
+                        key={field.key}
+                        className="w-full rounded-md border border-gray-200 dark:border-gray-800 p-3"
+                      >
                         <Space direction="vertical" className="w-full">
-                          <Form.Item
-                            name={[field.name]}
-                            rules={[{ required: true }]}
-                            className="!mb-0"
-                          >
-                            <InputNumber className="w-full" placeholder="Code" />
-                          </Form.Item>
-                          <div>
+                          <Space.Compact className="w-full">
+                            <Form.Item
+                              name={[field.name, 'task_number']}
+                              noStyle
+                              rules={[{ required: true }]}
+                            >
+                              <InputNumber
+                                min={1}
+                                precision={0}
+                                className="w-full"
+                                addonBefore="Task"
+                              />
+                            </Form.Item>
+                            <Form.Item name={[field.name, 'max_runtime_ms']} noStyle>
+                              <InputNumber
+                                min={0}
+                                step={100}
+                                className="w-full"
+                                placeholder="Max runtime"
+                                addonAfter="ms"
+                              />
+                            </Form.Item>
                             <Button
                               icon={<DeleteOutlined />}
                               onClick={() => remove(field.name)}
                               danger
                             />
-                          </div>
-                        </Space>
-                      )}
-                    </div>
-                  ))}
-                  <Button onClick={() => add()} icon={<PlusOutlined />}>
-                    Add Return Code
-                  </Button>
-                </Space>
-              )}
-            </Form.List>
-
-            <Divider className="my-2" />
-
-            <Typography.Text className="block mb-2">Forbidden Outputs</Typography.Text>
-            <Form.List name={['task_spec', 'forbidden_outputs']}>
-              {(fields, { add, remove }) => (
-                <Space direction="vertical" className="w-full">
-                  {fields.map((field) => (
-                    <div key={field.key} className="w-full">
-                      {isSm ? (
-                        <Space.Compact className="w-full">
-                          <Form.Item name={[field.name]} noStyle rules={[{ required: true }]}>
-                            <Input
+                          </Space.Compact>
+                          <Form.Item
+                            name={[field.name, 'valid_return_codes']}
+                            initialValue={[0]}
+                            getValueFromEvent={(vals: string[]) =>
+                              vals.map(Number).filter((v) => Number.isInteger(v))
+                            }
+                            className="!mb-0"
+                          >
+                            <Select
+                              mode="tags"
                               className="w-full"
-                              placeholder="Substring to disallow in output"
+                              placeholder="Valid return codes"
+                              tokenSeparators={[',']}
+                              open={false}
                             />
                           </Form.Item>
-                          <Button
-                            icon={<DeleteOutlined />}
-                            onClick={() => remove(field.name)}
-                            danger
-                          />
-                        </Space.Compact>
-                      ) : (
-                        <Space direction="vertical" className="w-full">
                           <Form.Item
-                            name={[field.name]}
-                            rules={[{ required: true }]}
+                            name={[field.name, 'forbidden_outputs']}
+                            initialValue={[]}
                             className="!mb-0"
                           >
-                            <Input
+                            <Select
+                              mode="tags"
                               className="w-full"
-                              placeholder="Substring to disallow in output"
+                              placeholder="Forbidden outputs"
+                              tokenSeparators={[',']}
+                              open={false}
                             />
                           </Form.Item>
-                          <div>
-                            <Button
-                              icon={<DeleteOutlined />}
-                              onClick={() => remove(field.name)}
-                              danger
-                            />
-                          </div>
+                          {renderRulesList(
+                            [field.name, 'rules'],
+                            ['task_specs', field.name, 'rules'],
+                          )}
                         </Space>
-                      )}
-                    </div>
-                  ))}
-                  <Button onClick={() => add()} icon={<PlusOutlined />}>
-                    Add Forbidden Output
-                  </Button>
-                </Space>
-              )}
-            </Form.List>
+                      </div>
+                    ))}
+                    <Button
+                      onClick={() => add({ task_number: fields.length + 1 })}
+                      icon={<PlusOutlined />}
+                    >
+                      Add Task Spec
+                    </Button>
+                  </Space>
+                )}
+              </Form.List>
+            ) : (
+              <>
+                <Form.Item
+                  name={['task_spec', 'max_runtime_ms']}
+                  label="Max Runtime"
+                  tooltip="Optional hard cap in milliseconds"
+                  className={fieldWidth}
+                >
+                  <InputNumber
+                    min={0}
+                    max={Number.MAX_VALUE}
+                    step={100}
+                    className="w-full"
+                    addonAfter="ms"
+                  />
+                </Form.Item>
+
+                <Divider className="my-2" />
+
+                <Typography.Text className="block mb-2">Valid Return Codes</Typography.Text>
+                <Form.List name={['task_spec', 'valid_return_codes']}>
+                  {(fields, { add, remove }) => (
+                    <Space direction="vertical" className="w-full">
+                      {fields.map((field) => (
+                        <div key={field.key} className="w-full">
+                          {isSm ? (
+                            <Space.Compact className="w-full">
+                              <Form.Item name={[field.name]} noStyle rules={[{ required: true }]}>
+                                <InputNumber className="w-full" placeholder="Code" />
+                              </Form.Item>
+                              <Button
+                                icon={<DeleteOutlined />}
+                                onClick={() => remove(field.name)}
+                                danger
+                              />
+                            </Space.Compact>
+                          ) : (
+                            <Space direction="vertical" className="w-full">
+                              <Form.Item
+                                name={[field.name]}
+                                rules={[{ required: true }]}
+                                className="!mb-0"
+                              >
+                                <InputNumber className="w-full" placeholder="Code" />
+                              </Form.Item>
+                              <div>
+                                <Button
+                                  icon={<DeleteOutlined />}
+                                  onClick={() => remove(field.name)}
+                                  danger
+                                />
+                              </div>
+                            </Space>
+                          )}
+                        </div>
+                      ))}
+                      <Button onClick={() => add()} icon={<PlusOutlined />}>
+                        Add Return Code
+                      </Button>
+                    </Space>
+                  )}
+                </Form.List>
+
+                <Divider className="my-2" />
+
+                <Typography.Text className="block mb-2">Forbidden Outputs</Typography.Text>
+                <Form.List name={['task_spec', 'forbidden_outputs']}>
+                  {(fields, { add, remove }) => (
+                    <Space direction="vertical" className="w-full">
+                      {fields.map((field) => (
+                        <div key={field.key} className="w-full">
+                          {isSm ? (
+                            <Space.Compact className="w-full">
+                              <Form.Item name={[field.name]} noStyle rules={[{ required: true }]}>
+                                <Input
+                                  className="w-full"
+                                  placeholder="Substring to disallow in output"
+                                />
+                              </Form.Item>
+                              <Button
+                                icon={<DeleteOutlined />}
+                                onClick={() => remove(field.name)}
+                                danger
+                              />
+                            </Space.Compact>
+                          ) : (
+                            <Space direction="vertical" className="w-full">
+                              <Form.Item
+                                name={[field.name]}
+                                rules={[{ required: true }]}
+                                className="!mb-0"
+                              >
+                                <Input
+                                  className="w-full"
+                                  placeholder="Substring to disallow in output"
+                                />
+                              </Form.Item>
+                              <div>
+                                <Button
+                                  icon={<DeleteOutlined />}
+                                  onClick={() => remove(field.name)}
+                                  danger
+                                />
+                              </div>
+                            </Space>
+                          )}
+                        </div>
+                      ))}
+                      <Button onClick={() => add()} icon={<PlusOutlined />}>
+                        Add Forbidden Output
+                      </Button>
+                    </Space>
+                  )}
+                </Form.List>
+
+                <Divider className="my-2" />
+
+                <Typography.Text className="block mb-2">Property Rules</Typography.Text>
+                {renderRulesList(['task_spec', 'rules'], ['task_spec', 'rules'])}
+              </>
+            )}
           </SettingsGroup>
 
           {/* ---- Runtime Flags ---- */}
-          <SettingsGroup title="Runtime" description="Parallelism and verbosity for GA execution.">
+          <SettingsGroup
+            title="Runtime"
+            description="Parallelism, interpreter caching and verbosity for GA execution."
+          >
             <Form.Item
               name="max_parallel_chromosomes"
               label="Max Parallel Chromosomes"
@@ -499,6 +925,25 @@ export default function GatlamPage() {
               <InputNumber min={1} step={1} precision={0} className="w-full" />
             </Form.Item>
 
+            <Form.Item
+              name="interpreter_cache_size"
+              label="Interpreter Cache Size"
+              tooltip="Generated sources kept so repeated payloads skip the interpreter. 0 disables the cache."
+              className={fieldWidth}
+              rules={[{ required: true }]}
+            >
+              <InputNumber min={0} step={1} precision={0} className="w-full" />
+            </Form.Item>
+
+            <Form.Item
+              name="interpreter_cache_ttl_secs"
+              label="Interpreter Cache TTL (s)"
+              className={fieldWidth}
+              rules={[{ required: true }]}
+            >
+              <InputNumber min={1} step={60} precision={0} className="w-full" />
+            </Form.Item>
+
             <Form.Item
               name="verbose"
               label="Verbose"
@@ -56,7 +56,7 @@ export default function MarkingPage() {
         marking_scheme: values.marking_scheme,
         feedback_scheme: values.feedback_scheme,
         grading_policy: values.grading_policy,
-        deliminator: values.deliminator,
+        delimiter: values.delimiter,
         max_attempts: values.max_attempts,
         limit_attempts: values.limit_attempts,
         pass_mark: values.pass_mark,
@@ -173,7 +173,7 @@ export default function MarkingPage() {
         </Form.Item>
 
         <Form.Item
-          name="deliminator" // matches backend field name intentionally
+          name="delimiter"
           label="Delimiter String"
           className={textFieldWidth}
           rules={[{ required: true, message: 'Enter a delimiter string' }]}
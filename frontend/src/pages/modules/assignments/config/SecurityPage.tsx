@@ -138,6 +138,8 @@ export default function SecurityPage() {
       cookie_ttl_minutes: values.cookie_ttl_minutes,
       bind_cookie_to_user: values.bind_cookie_to_user,
       allowed_cidrs: values.allowed_cidrs ?? [],
+      seccomp_profile: config.security?.seccomp_profile,
+      apparmor_profile: config.security?.apparmor_profile,
     };
 
     try {
@@ -77,7 +77,7 @@ const StepConfig = () => {
       marking_scheme: c.marking.marking_scheme,
       feedback_scheme: c.marking.feedback_scheme,
       grading_policy: c.marking.grading_policy,
-      deliminator: c.marking.deliminator,
+      delimiter: c.marking.delimiter,
       max_attempts: c.marking.max_attempts,
       limit_attempts: c.marking.limit_attempts,
       pass_mark: c.marking.pass_mark,
@@ -168,7 +168,7 @@ const StepConfig = () => {
             marking_scheme: v.marking_scheme,
             feedback_scheme: v.feedback_scheme,
             grading_policy: v.grading_policy,
-            deliminator: v.deliminator,
+            delimiter: v.delimiter,
             max_attempts: v.max_attempts,
             limit_attempts: v.limit_attempts,
             pass_mark: v.pass_mark,
@@ -358,7 +358,7 @@ const StepConfig = () => {
 
             <Divider />
             <Space wrap size="large">
-              <Form.Item name="deliminator" label="Output Delimiter" rules={[{ required: true }]}>
+              <Form.Item name="delimiter" label="Output Delimiter" rules={[{ required: true }]}>
                 <Input placeholder="###" />
               </Form.Item>
               <Form.Item name="pass_mark" label="Pass Mark (%)" rules={[{ required: true }]}>
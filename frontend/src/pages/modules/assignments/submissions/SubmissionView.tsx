@@ -343,7 +343,7 @@ const SubmissionView = () => {
                 )}
                 {status === 'failed_upload' && (
                   <li>
-                    Upload the file again. Ensure it’s a supported archive (.zip/.tgz/.gz/.tar).
+                    Upload the file again. Ensure it’s a supported archive (.zip/.tgz/.gz/.tar/.7z/.rar).
                   </li>
                 )}
                 {status === 'failed_disallowed_code' && (
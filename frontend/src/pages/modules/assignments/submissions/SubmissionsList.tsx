@@ -773,7 +773,6 @@ export default function SubmissionsList() {
         onClose={() => setSubmitModalOpen(false)}
         onSubmit={handleSubmitAssignment}
         title="Submit Assignment"
-        accept=".zip,.tar,.gz,.tgz"
         maxSizeMB={50}
         defaultIsPractice={false}
         allowPractice={policy?.allow_practice_submissions && !auth.isStaff(module.id)}
@@ -2,7 +2,7 @@
 import type { Language } from '@/types/modules/assignments/config';
 import type { TaskType } from '@/types/modules/assignments/tasks';
 
-export const COVERAGE_LANGS: Readonly<Language[]> = ['cpp', 'java'] as const;
+export const COVERAGE_LANGS: Readonly<Language[]> = ['c', 'cpp', 'java', 'python', 'rust', 'go'] as const;
 export const VALGRIND_LANGS: Readonly<Language[]> = ['cpp'] as const;
 
 export const isCoverageSupported = (lang?: Language | null) =>
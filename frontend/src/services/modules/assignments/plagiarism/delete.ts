@@ -35,3 +35,13 @@ export const bulkDeletePlagiarismCases = async (
     { case_ids: caseIds }
   );
 };
+
+/** Remove all starter code uploaded for plagiarism runs. */
+export const clearPlagiarismBaseFiles = async (
+  moduleId: number,
+  assignmentId: number
+): Promise<ApiResponse<null>> => {
+  return api.delete(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/base-files`
+  );
+};
@@ -1,9 +1,13 @@
 import type { PaginationRequest } from "@/types/common";
 import type {
   GetListPlagiarismCasesResponse,
+  GetPlagiarismCaseDiffResponse,
   GetPlagiarismGraphResponse,
+  PlagiarismGraphExportFormat,
   MossReportListResponse,
+  PlagiarismBaseFileListResponse,
   PlagiarismCaseStatus,
+  PlagiarismReportPairListResponse,
 } from "@/types/modules/assignments/plagiarism";
 import { api, apiDownload } from "@/utils/api";
 
@@ -17,6 +21,33 @@ export const listMossReports = async (
   );
 };
 
+// --- parsed user pairs of a report ---
+export const listMossReportPairs = async (
+  moduleId: number,
+  assignmentId: number,
+  reportId: number,
+  params?: {
+    min_percent?: number;
+    min_lines?: number;
+    user?: string;
+  } & PaginationRequest
+): Promise<PlagiarismReportPairListResponse> => {
+  return api.get(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/moss/reports/${reportId}/pairs`,
+    params
+  );
+};
+
+// --- starter code excluded from runs ---
+export const listPlagiarismBaseFiles = async (
+  moduleId: number,
+  assignmentId: number
+): Promise<PlagiarismBaseFileListResponse> => {
+  return api.get(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/base-files`
+  );
+};
+
 export const listPlagiarismCases = async (
   moduleId: number,
   assignmentId: number,
@@ -31,6 +62,17 @@ export const listPlagiarismCases = async (
   );
 };
 
+// --- side-by-side matched code of a case ---
+export const getPlagiarismCaseDiff = async (
+  moduleId: number,
+  assignmentId: number,
+  caseId: number
+): Promise<GetPlagiarismCaseDiffResponse> => {
+  return api.get(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/${caseId}/diff`
+  );
+};
+
 export const getPlagiarismGraph = async (
   moduleId: number,
   assignmentId: number,
@@ -38,6 +80,7 @@ export const getPlagiarismGraph = async (
     status?: PlagiarismCaseStatus;
     min_similarity?: number;
     max_similarity?: number;
+    min_lines?: number;
     user?: string;
     report_id?: number;
   }
@@ -48,6 +91,29 @@ export const getPlagiarismGraph = async (
   );
 };
 
+// Downloads the graph as a DOT / GraphML / CSV file (same filters as getPlagiarismGraph).
+export const exportPlagiarismGraph = async (
+  moduleId: number,
+  assignmentId: number,
+  format: PlagiarismGraphExportFormat,
+  params?: {
+    status?: PlagiarismCaseStatus;
+    min_similarity?: number;
+    max_similarity?: number;
+    min_lines?: number;
+    user?: string;
+    report_id?: number;
+  }
+): Promise<void> => {
+  const query = new URLSearchParams({ format });
+  Object.entries(params ?? {}).forEach(([k, v]) => {
+    if (v !== undefined && v !== null && v !== "") query.set(k, String(v));
+  });
+  return apiDownload(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/graph?${query.toString()}`
+  );
+};
+
 export const downloadMossArchiveByReport = async (
   moduleId: number,
   assignmentId: number,
@@ -1,6 +1,11 @@
 import type { ApiResponse } from "@/types/common";
-import type { PlagiarismCase, MossFilterMode, HashScanData } from "@/types/modules/assignments/plagiarism";
-import { api } from "@/utils/api";
+import type {
+  PlagiarismCase,
+  MossFilterMode,
+  HashScanData,
+  PlagiarismBaseFile,
+} from "@/types/modules/assignments/plagiarism";
+import { api, apiUpload } from "@/utils/api";
 
 export const createPlagiarismCase = async (
   moduleId: number,
@@ -19,12 +24,30 @@ export const createPlagiarismCase = async (
   );
 };
 
+// Open a case from a parsed report pair
+export const createPlagiarismCaseFromPair = async (
+  moduleId: number,
+  assignmentId: number,
+  reportId: number,
+  matchId: number
+) => {
+  return api.post<PlagiarismCase>(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/moss/reports/${reportId}/pairs/${matchId}/case`
+  );
+};
+
 // ---- MOSS run (async job w/ options) ----
+export type PlagiarismEngine = 'moss' | 'jplag';
+
 export type RunMossPayload = {
   description: string;
+  engine?: PlagiarismEngine;
   experimental?: boolean;
   max_matches?: number;
   show_limit?: number;
+  min_tokens?: number; // JPlag only
+  use_base_files?: boolean; // exclude uploaded starter code (default true)
+  archive_assignment_ids?: number[]; // earlier assignments/semesters to compare against
   filter_mode?: MossFilterMode;
   filter_patterns?: string[];
 };
@@ -46,6 +69,20 @@ export const runMossCheck = async (
   );
 };
 
+// Starter code (source file or .zip) excluded from MOSS/JPlag runs
+export const uploadPlagiarismBaseFile = async (
+  moduleId: number,
+  assignmentId: number,
+  file: File
+): Promise<ApiResponse<PlagiarismBaseFile>> => {
+  const form = new FormData();
+  form.append("file", file, file.name);
+  return apiUpload<PlagiarismBaseFile>(
+    `/modules/${moduleId}/assignments/${assignmentId}/plagiarism/base-files`,
+    form
+  );
+};
+
 // Manual archive
 export const archiveMossReport = async (
   moduleId: number,
@@ -1,5 +1,5 @@
 import type { ApiResponse } from "@/types/common";
-import type { PostSubmitAssignmentResponse, RemarkResponse, ResubmitRequest, ResubmitResponse } from "@/types/modules/assignments/submissions";
+import type { DryRunResponse, PostSubmitAssignmentResponse, RemarkResponse, ResubmitRequest, ResubmitResponse } from "@/types/modules/assignments/submissions";
 import type { RemarkRequest } from "@/types/modules/assignments/submissions/requests";
 import { api, apiUpload } from "@/utils/api";
 
@@ -41,3 +41,14 @@ export const resubmitSubmissions = async (
 ): Promise<ApiResponse<ResubmitResponse>> => {
   return api.post(`/modules/${moduleId}/assignments/${assignmentId}/submissions/resubmit`, payload);
 };
+
+// Runs every task against an existing submission without saving outputs or re-marking
+export const dryRunSubmission = async (
+  moduleId: number,
+  assignmentId: number,
+  submissionId: number
+): Promise<ApiResponse<DryRunResponse>> => {
+  return api.post(
+    `/modules/${moduleId}/assignments/${assignmentId}/submissions/${submissionId}/dry_run`
+  );
+};
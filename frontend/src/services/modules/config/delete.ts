@@ -0,0 +1,5 @@
+import { api } from "@/utils/api";
+
+export async function deleteModuleConfig(moduleId: number) {
+  return await api.delete<null>(`/modules/${moduleId}/config`);
+}
@@ -0,0 +1,6 @@
+import { api } from "@/utils/api";
+import type { ModuleConfig } from "@/types/modules/assignments/config";
+
+export async function getModuleConfig(moduleId: number) {
+  return await api.get<ModuleConfig>(`/modules/${moduleId}/config`);
+}
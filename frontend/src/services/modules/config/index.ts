@@ -0,0 +1,3 @@
+export * from "./delete"
+export * from "./get"
+export * from "./put"
\ No newline at end of file
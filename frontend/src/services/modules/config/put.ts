@@ -0,0 +1,6 @@
+import { api } from "@/utils/api";
+import type { ModuleConfig } from "@/types/modules/assignments/config";
+
+export async function setModuleConfig(moduleId: number, config: ModuleConfig) {
+  return await api.put<ModuleConfig>(`/modules/${moduleId}/config`, config);
+}
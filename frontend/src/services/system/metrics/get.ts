@@ -1,20 +1,33 @@
 import { api, apiDownload, buildQuery } from '@/utils/api';
 
 export type MetricsBucket = 'day' | 'week' | 'month' | 'year';
+export type MetricsInterval = '1m' | '5m' | '1h';
 
 export interface MetricsPoint {
   ts: string;
+  /** Samples averaged into this point; 0 for zero-filled buckets. */
+  samples: number;
   cpu_avg: number;
   mem_pct: number;
+  cm_running: number;
+  cm_waiting: number;
+  cm_waiting_max: number;
+}
+
+export interface MetricsParams {
+  start?: string;
+  end?: string;
+  bucket?: MetricsBucket;
+  interval?: MetricsInterval;
 }
 
 export interface MetricsResponse { points: MetricsPoint[] }
 
-export function getSystemMetrics(params: { start?: string; end?: string; bucket?: MetricsBucket }) {
+export function getSystemMetrics(params: MetricsParams) {
   return api.get<MetricsResponse>('/system/metrics', params);
 }
 
-export function exportSystemMetrics(params: { start?: string; end?: string; bucket?: MetricsBucket }) {
+export function exportSystemMetrics(params: MetricsParams) {
   const query = params ? buildQuery(params) : '';
   const endpoint = query ? `/system/metrics/export?${query}` : '/system/metrics/export';
   return apiDownload(endpoint);
@@ -19,9 +19,27 @@ export const SUBMISSION_MODES = ['manual', 'gatlam', 'rng', 'codecoverage'] as c
 /** Grading policies (mirrors Rust enum) */
 export const GRADING_POLICIES = ['best', 'last'] as const;
 
-/** GA: crossover & mutation types */
+/** GA: crossover, mutation & selection types */
 export const CROSSOVER_TYPES = ['onepoint', 'twopoint', 'uniform'] as const;
 export const MUTATION_TYPES = ['bitflip', 'swap', 'scramble'] as const;
+export const SELECTION_TYPES = ['roulette', 'tournament', 'rank'] as const;
+/** GA: gene kinds (what a gene turns into in the interpreter payload) */
+export const GENE_KINDS = ['integer', 'categorical', 'string'] as const;
+/** GA: what happens to genes that decode out of range or to invalid values */
+export const GENE_REPAIRS = ['none', 'resample', 'clamp', 'penalty'] as const;
+/** GA: what GATLAM optimizes (properties alone, or together with code coverage) */
+export const GA_OBJECTIVES = ['properties', 'weighted_sum', 'pareto'] as const;
+/** GATLAM: how the gene space is searched (the GA, or a baseline to compare it against) */
+export const SEARCH_STRATEGIES = ['genetic', 'simulated_annealing', 'random_search'] as const;
+/** GATLAM: kinds of lecturer-defined task property rules */
+export const PROPERTY_RULE_TYPES = [
+  'contains',
+  'ordering',
+  'forbidden_sequence',
+  'max_runtime',
+] as const;
+/** Code coverage: which metric the coverage mark is based on */
+export const COVERAGE_METRICS = ['line', 'branch', 'function', 'weighted'] as const;
 
 /** Select options */
 export const MARKING_SCHEME_OPTIONS = MARKING_SCHEMES.map((val) => ({
@@ -63,6 +81,56 @@ export const MUTATION_TYPE_OPTIONS = MUTATION_TYPES.map((val) => ({
   label: val.charAt(0).toUpperCase() + val.slice(1),
   value: val,
 }));
+export const SELECTION_TYPE_OPTIONS = SELECTION_TYPES.map((val) => ({
+  label: val.charAt(0).toUpperCase() + val.slice(1),
+  value: val,
+}));
+export const GENE_KIND_OPTIONS = GENE_KINDS.map((val) => ({
+  label: val.charAt(0).toUpperCase() + val.slice(1),
+  value: val,
+}));
+export const GENE_REPAIR_OPTIONS = GENE_REPAIRS.map((val) => ({
+  label: val.charAt(0).toUpperCase() + val.slice(1),
+  value: val,
+}));
+export const GA_OBJECTIVE_LABELS: Record<(typeof GA_OBJECTIVES)[number], string> = {
+  properties: 'Properties only',
+  weighted_sum: 'Properties + coverage (weighted sum)',
+  pareto: 'Properties + coverage (Pareto)',
+};
+export const GA_OBJECTIVE_OPTIONS = GA_OBJECTIVES.map((val) => ({
+  label: GA_OBJECTIVE_LABELS[val],
+  value: val,
+}));
+export const SEARCH_STRATEGY_LABELS: Record<(typeof SEARCH_STRATEGIES)[number], string> = {
+  genetic: 'Genetic algorithm',
+  simulated_annealing: 'Simulated annealing (baseline)',
+  random_search: 'Random search (baseline)',
+};
+export const SEARCH_STRATEGY_OPTIONS = SEARCH_STRATEGIES.map((val) => ({
+  label: SEARCH_STRATEGY_LABELS[val],
+  value: val,
+}));
+export const PROPERTY_RULE_TYPE_LABELS: Record<(typeof PROPERTY_RULE_TYPES)[number], string> = {
+  contains: 'Output contains',
+  ordering: 'Output ordering',
+  forbidden_sequence: 'Forbidden sequence',
+  max_runtime: 'Max runtime',
+};
+export const PROPERTY_RULE_TYPE_OPTIONS = PROPERTY_RULE_TYPES.map((val) => ({
+  label: PROPERTY_RULE_TYPE_LABELS[val],
+  value: val,
+}));
+export const COVERAGE_METRIC_LABELS: Record<(typeof COVERAGE_METRICS)[number], string> = {
+  line: 'Line coverage',
+  branch: 'Branch coverage',
+  function: 'Function coverage',
+  weighted: 'Weighted blend',
+};
+export const COVERAGE_METRIC_OPTIONS = COVERAGE_METRICS.map((val) => ({
+  label: COVERAGE_METRIC_LABELS[val],
+  value: val,
+}));
 
 /**
  * ---- Type unions from const arrays ----
@@ -74,6 +142,13 @@ export type SubmissionMode = (typeof SUBMISSION_MODES)[number];
 export type GradingPolicy = (typeof GRADING_POLICIES)[number];
 export type CrossoverType = (typeof CROSSOVER_TYPES)[number];
 export type MutationType = (typeof MUTATION_TYPES)[number];
+export type SelectionType = (typeof SELECTION_TYPES)[number];
+export type GaObjective = (typeof GA_OBJECTIVES)[number];
+export type SearchStrategyKind = (typeof SEARCH_STRATEGIES)[number];
+export type GeneKind = (typeof GENE_KINDS)[number];
+export type GeneRepair = (typeof GENE_REPAIRS)[number];
+export type PropertyRuleType = (typeof PROPERTY_RULE_TYPES)[number];
+export type CoverageMetric = (typeof COVERAGE_METRICS)[number];
 
 /**
  * ---- Top-level config sections (mirrors Rust structs) ----
@@ -85,6 +160,10 @@ export interface AssignmentProjectConfig {
   language: Language;
   /** How submissions are generated (manual, gatlam, rng, codecoverage). */
   submission_mode: SubmissionMode;
+  /** Optional command that compiles once; its output is reused by every task. */
+  build_command?: string | null;
+  /** Optional Docker image for this assignment (e.g. gcc:12); defaults to the deployment's image for the language. */
+  image?: string | null;
 }
 
 /** Resource constraints for running a submission (ExecutionLimits). */
@@ -99,6 +178,8 @@ export interface AssignmentExecutionConfig {
   max_uncompressed_size: number;
   /** Max number of processes allowed. */
   max_processes: number;
+  /** Max bytes written to /code and /output per run; 0 disables the quota. */
+  max_disk_bytes: number;
 }
 
 /** Late submission policy (new in ExecutionConfig.marking.late). */
@@ -128,11 +209,8 @@ export interface AssignmentMarkingConfig {
   /** Policy for selecting final grade across submissions. */
   grading_policy: GradingPolicy;
 
-  /**
-   * String delimiter used for splitting output sections.
-   * NOTE: spelling matches backend field (`deliminator`) for compatibility.
-   */
-  deliminator: string;
+  /** String delimiter used for splitting output sections (was `deliminator` before config v2). */
+  delimiter: string;
 
   /** Maximum number of attempts (only enforced if `limit_attempts` is true). */
   max_attempts: number;
@@ -149,7 +227,10 @@ export interface AssignmentMarkingConfig {
    */
   allow_practice_submissions: boolean;
 
-  /** Substrings to flag as disallowed in source files (serialized as `dissalowed_code`). */
+  /**
+   * Patterns to flag as disallowed in source files (serialized as `dissalowed_code`).
+   * Plain substrings, or prefixed with `re:` (regex) and/or `code:` (ignore comments/strings).
+   */
   dissalowed_code: string[];
 
   /** late submission policy. */
@@ -160,8 +241,24 @@ export interface AssignmentMarkingConfig {
  * ---- GATLAM-related config (mirrors Rust GATLAM & TaskSpecConfig) ----
  */
 export interface GeneConfig {
-  min_value: number;
-  max_value: number;
+  /** How the gene is rendered in the payload; missing means 'integer'. */
+  kind?: GeneKind;
+  /** Range of an integer gene. */
+  min_value?: number;
+  max_value?: number;
+  /** Choices of a categorical gene (e.g. command names or flags). */
+  values?: string[];
+  /** Length bounds of a string gene (max 64). */
+  min_length?: number;
+  max_length?: number;
+  /** Characters of a string gene; empty means a-z. */
+  alphabet?: string;
+  /** Values an integer gene must not take. */
+  invalid_values?: number[];
+  /** Handling of genes that decode out of range or to invalid values; missing means 'none'. */
+  repair?: GeneRepair;
+  /** Fitness subtracted per invalid gene when `repair` is 'penalty'. */
+  penalty?: number;
 }
 
 export interface TaskSpecConfig {
@@ -171,6 +268,32 @@ export interface TaskSpecConfig {
   max_runtime_ms?: number;
   /** Disallowed substrings in outputs. */
   forbidden_outputs: string[];
+  /** Lecturer-defined properties, each counted as an LTL property in the fitness. */
+  rules: PropertyRule[];
+}
+
+/** `gatlam.task_spec`: one spec for every task, or specs keyed by task number (TaskSpecs in Rust).
+ *  Tasks missing from the map use the default spec. */
+export type TaskSpecs = TaskSpecConfig | Record<number, TaskSpecConfig>;
+
+/** True if `spec` is keyed by task number rather than shared by every task. */
+export const isPerTaskSpec = (spec: TaskSpecs): spec is Record<number, TaskSpecConfig> => {
+  const keys = Object.keys(spec);
+  return keys.length > 0 && keys.every((k) => /^-?\d+$/.test(k.trim()));
+};
+
+/** A lecturer-defined task property (PropertyRule in Rust); fields depend on `type`. */
+export interface PropertyRule {
+  type: PropertyRuleType;
+  /** Task the rule applies to; missing means every task. */
+  task_number?: number;
+  /** 'contains': substring some output line must contain. */
+  value?: string;
+  /** 'ordering': substrings whose lines must appear in this order;
+   *  'forbidden_sequence': lines that must never appear consecutively. */
+  values?: string[];
+  /** 'max_runtime': bound in milliseconds. */
+  ms?: number;
 }
 
 /** Security options (SecurityOptions in Rust). */
@@ -185,6 +308,10 @@ export interface AssignmentSecurityConfig {
   bind_cookie_to_user: boolean;
   /** Optional CIDR allowlist; empty => allow all. */
   allowed_cidrs: string[];
+  /** Seccomp profile for task containers; 'docker' uses Docker's default. */
+  seccomp_profile?: 'strict' | 'docker';
+  /** AppArmor profile loaded on the runner host; null => deployment default. */
+  apparmor_profile?: string | null;
 }
 
 export interface GatlamConfig {
@@ -198,14 +325,47 @@ export interface GatlamConfig {
   genes: GeneConfig[];
   crossover_type: CrossoverType;
   mutation_type: MutationType;
+  selection_type: SelectionType;
+  /** Candidates per tournament when `selection_type` is `tournament`. */
+  tournament_size: number;
+  /** Fittest chromosomes copied unchanged into the next generation (per island). */
+  elitism_count: number;
+
+  // ---- Island model ----
+  /** Sub-populations the population is split into; 1 evolves a single population. */
+  islands: number;
+  /** Generations between migrations; 0 keeps the islands isolated. */
+  migration_interval: number;
+  /** Fittest chromosomes each island sends to the next one per migration. */
+  migration_count: number;
 
   // ---- Components ----
   omega1: number;
   omega2: number;
   omega3: number;
 
+  // ---- Objectives ----
+  /** Optimize properties alone, or together with code coverage in the same run. */
+  objective: GaObjective;
+  /** Share of the fitness given to coverage when `objective` is `weighted_sum`. */
+  coverage_weight: number;
+
+  // ---- Search strategy ----
+  /** Search run over the genes; `number_of_generations` is its iteration count. */
+  search_strategy: SearchStrategyKind;
+  /** Starting temperature of simulated annealing. */
+  initial_temperature: number;
+  /** Factor the simulated annealing temperature is multiplied by after every step. */
+  cooling_rate: number;
+
   // ---- TaskSpec ----
-  task_spec: TaskSpecConfig;
+  task_spec: TaskSpecs;
+
+  // ---- Interpreter cache ----
+  /** Generated sources kept across runs so repeated payloads skip the interpreter; 0 disables. */
+  interpreter_cache_size: number;
+  /** Seconds a cached source stays valid. */
+  interpreter_cache_ttl_secs: number;
 
   // ---- Optional runtime flags ----
   max_parallel_chromosomes: number;
@@ -215,16 +375,78 @@ export interface GatlamConfig {
 export interface CodeCoverage {
   code_coverage_weight: number;
   whitelist: string[];
+  /** Metric the coverage mark is based on; branch/function fall back to line coverage for tools that do not report them. */
+  metric: CoverageMetric;
+  /** Relative weights of the metrics when `metric` is `weighted`. */
+  weights: CoverageWeights;
+}
+
+export interface CoverageWeights {
+  line: number;
+  branch: number;
+  function: number;
+}
+
+export interface AssignmentValgrindConfig {
+  /** Share of a memory check's marks each kind of problem is worth; a task earns a share only if valgrind reported none of it. */
+  weights: ValgrindWeights;
+}
+
+export interface ValgrindWeights {
+  definitely_lost: number;
+  indirectly_lost: number;
+  possibly_lost: number;
+  invalid_reads: number;
+  invalid_writes: number;
+  uninitialised: number;
+}
+
+export interface AssignmentOutputConfig {
+  /** Max bytes of output stored per task; 0 = unlimited. */
+  max_output_bytes: number;
+  /** Max total bytes of `/output` artifacts kept per task; 0 = unlimited. */
+  max_artifact_bytes: number;
 }
 
 /**
  * Top-level assignment configuration (ExecutionConfig in Rust).
  */
 export interface AssignmentConfig {
+  /** Config format version; older configs are upgraded by the backend when loaded. */
+  config_version: number;
   execution: AssignmentExecutionConfig;
   marking: AssignmentMarkingConfig;     // ← includes .late now
   project: AssignmentProjectConfig;
   gatlam: GatlamConfig;
   security: AssignmentSecurityConfig;
   code_coverage: CodeCoverage;
+  valgrind: AssignmentValgrindConfig;
+  output: AssignmentOutputConfig;
+  /** Extra environment variables exported for every task command. */
+  environment: Record<string, string>;
+}
+
+/**
+ * Module-level default config (`/modules/{id}/config`): only the sections and fields that every
+ * assignment in the module inherits unless its own config overrides them.
+ */
+export type ModuleConfig = {
+  config_version?: number;
+} & {
+  [K in Exclude<keyof AssignmentConfig, "config_version">]?: Partial<AssignmentConfig[K]>;
+};
+
+/**
+ * ---- Validation (400 from saving a config) ----
+ */
+
+/** One problem in a rejected config, with the path of its field (e.g. `gatlam.omega1`). */
+export interface ConfigValidationError {
+  path: string;
+  message: string;
+}
+
+/** `data` of a rejected config save. */
+export interface ConfigValidationErrors {
+  errors: ConfigValidationError[];
 }
@@ -1,3 +1,5 @@
+import type { MarkingScheme } from "@/types/modules/assignments/config";
+
 export interface MarkAllocatorSubsection {
   name: string;
   value: number;
@@ -12,6 +14,34 @@ export interface MarkAllocatorSubsection {
    * Otherwise it may be undefined.
    */
   regex?: string[];
+  /**
+   * Optional peak heap usage bands. When present, the subsection is marked
+   * against the task's massif profile instead of its output.
+   */
+  memory_thresholds?: MemoryThreshold[];
+  /**
+   * Optional marking scheme for this subsection, overriding the assignment's.
+   * Nested subsections inherit it.
+   */
+  comparator?: MarkingScheme;
+  /**
+   * Optional multiplier applied to `value` when marking (defaults to 1).
+   */
+  weight?: number;
+  /**
+   * Optional nested subsections. Only the leaves are marked, and their
+   * marks (`value * weight`) must add up to this subsection's `value`.
+   */
+  subsections?: MarkAllocatorSubsection[];
+}
+
+export interface MemoryThreshold {
+  /**
+   * A peak heap usage (bytes) at or below this earns `percent` of the subsection.
+   * The smallest band the peak fits in applies; above every band earns nothing.
+   */
+  max_peak_bytes: number;
+  percent: number;
 }
 
 export interface MarkAllocatorTask {
@@ -25,6 +25,7 @@ export interface UserLite {
 
 export interface SubmissionLite {
   id: number;
+  assignment_id: number;
   filename: string;
   created_at: string;
   user: UserLite;
@@ -37,6 +38,7 @@ export interface PlagiarismCaseItem {
   similarity: number;
   lines_matched: number;
   report_id: number | null;
+  historical: boolean; // one side is an archive submission (earlier assignment/semester)
   created_at: string;
   updated_at: string;
   submission_1: SubmissionLite;
@@ -58,8 +60,11 @@ export interface PlagiarismGraphLink {
   similarity: number;
   lines_matched: number;
   status: PlagiarismCaseStatus;
+  historical: boolean;
 }
 
+export type PlagiarismGraphExportFormat = "dot" | "graphml" | "csv";
+
 export type GetPlagiarismGraphResponse = ApiResponse<{
   links: PlagiarismGraphLink[];
 }>;
@@ -84,6 +89,87 @@ export type MossReportListResponse = ApiResponse<{
   reports: MossReport[];
 }>;
 
+// ------------ parsed report pairs ------------
+export interface PlagiarismFileMatch {
+  a_filename: string;
+  b_filename: string;
+  percent: number | null;
+  lines_matched: number;
+  match_href: string;
+}
+
+export interface PlagiarismReportPair {
+  id: number;
+  user_a: string;
+  user_b: string;
+  submission_id_a: number | null;
+  submission_id_b: number | null;
+  total_percent: number | null;
+  lines_matched: number;
+  files: PlagiarismFileMatch[] | null;
+  case_id: number | null; // case created from this pair, if any
+}
+
+export interface ParsedPlagiarismReport {
+  id: number;
+  moss_report_id: number;
+  title: string | null;
+  pair_count: number;
+  created_at: string; // RFC 3339
+}
+
+export interface PlagiarismReportPairListData extends PaginationResponse {
+  report: ParsedPlagiarismReport;
+  pairs: PlagiarismReportPair[];
+}
+
+export type PlagiarismReportPairListResponse = ApiResponse<PlagiarismReportPairListData>;
+
+// ------------ side-by-side matched code ------------
+export interface PlagiarismDiffFile {
+  name: string;
+  content: string | null; // null if missing from the submission or too large
+  line_count: number | null;
+}
+
+export interface PlagiarismDiffRegion {
+  start_1: number;
+  end_1: number;
+  start_2: number;
+  end_2: number;
+}
+
+export interface PlagiarismDiffFilePair {
+  file_1: PlagiarismDiffFile;
+  file_2: PlagiarismDiffFile;
+  lines_matched: number;
+  percent: number | null;
+  regions: PlagiarismDiffRegion[];
+}
+
+export interface PlagiarismCaseDiff {
+  case_id: number;
+  report_id: number | null;
+  similarity: number;
+  lines_matched: number;
+  submission_1: { submission_id: number; username: string };
+  submission_2: { submission_id: number; username: string };
+  files: PlagiarismDiffFilePair[];
+}
+
+export type GetPlagiarismCaseDiffResponse = ApiResponse<PlagiarismCaseDiff>;
+
+// ------------ base (starter) files excluded from runs ------------
+export interface PlagiarismBaseFile {
+  filename: string;
+  size: number;
+  uploaded_at: string | null; // RFC 3339
+}
+
+export type PlagiarismBaseFileListResponse = ApiResponse<{
+  files: PlagiarismBaseFile[];
+}>;
+
 export interface HashScanPayload {
   /** When true, auto-create plagiarism cases for each unique pair in a collision group. */
   create_cases?: boolean;
@@ -51,9 +51,19 @@ export interface AssignmentReadiness {
   makefile_present: boolean;
   memo_output_present: boolean;
   mark_allocator_present: boolean;
+  /** Where the mark allocator disagrees with the memo output (advisory). */
+  mark_allocator_issues: MarkAllocatorMismatch[];
   is_ready: boolean;
 }
 
+export type MarkAllocatorMismatch =
+  | { kind: 'invalid_allocator'; message: string }
+  | { kind: 'missing_memo_output'; task_number: number }
+  | { kind: 'task_not_in_allocator'; task_number: number }
+  | { kind: 'subsection_count'; task_number: number; allocator: number; memo: number }
+  | { kind: 'task_value'; task_number: number; subsections: number; task: number }
+  | { kind: 'total_value'; tasks: number; total: number };
+
 export interface BestMark {
   earned: number;
   total: number;
@@ -29,6 +29,13 @@ export interface PlagiarismInfo {
   description: string;
 }
 
+/** Where disallowed code was found (set for `failed_disallowed_code` submissions). */
+export interface DisallowedMatch {
+  file: string;
+  line: number;
+  pattern: string;
+}
+
 export interface Submission extends Timestamp {
   id: number;
   attempt: number;
@@ -44,6 +51,7 @@ export interface Submission extends Timestamp {
   code_coverage?: CodeCoverage;
   user?: SubmissionUserInfo;
   plagiarism?: PlagiarismInfo;
+  disallowed_code?: DisallowedMatch;
 }
 
 export interface SubmissionMark {
@@ -76,6 +84,10 @@ export interface CodeCoverageSummary {
   total_lines: number;
   covered_lines: number;
   coverage_percent: number;
+  branch_percent?: number;
+  function_percent?: number;
+  /** Percentage the coverage mark is based on (per the configured metric). */
+  marked_percent?: number;
 }
 
 export interface CodeCoverageFile {
@@ -117,3 +129,24 @@ export interface ResubmitResponse {
   skipped_in_progress: number;
   failed: FailedResubmission[];
 }
+
+export interface CommandMetrics {
+  wall_time_ms: number;
+  cpu_time_ms: number | null;
+  max_rss_bytes: number | null;
+}
+
+// One task's output from a dry run (nothing is saved)
+export interface DryRunTaskOutput {
+  task_id: number;
+  task_number: number;
+  output: string;
+  full_size_bytes: number;
+  truncated: boolean;
+  metrics: CommandMetrics | null;
+}
+
+export interface DryRunResponse {
+  submission_id: number;
+  outputs: DryRunTaskOutput[];
+}
@@ -5,4 +5,6 @@ export type PostTaskRequest = {
   name: string;
   command: string;
   task_type: TaskType;
+  /** Globs of files under `/output` to keep from each run, e.g. `*.png`. */
+  artifact_patterns?: string[];
 };
\ No newline at end of file
@@ -10,6 +10,8 @@ export interface Task extends Timestamp {
   name: string;
   task_type: TaskType;
   command: string;
+  /** Globs of files under `/output` kept from each run. */
+  artifact_patterns: string[];
 }
 
 export interface SubsectionDetail {
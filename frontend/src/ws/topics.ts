@@ -13,6 +13,7 @@ export const Topics = {
   assignmentSubmissionsOwner(assignment_id: number, user_id: number): ClientTopic {
     return { kind: 'assignment_submissions_owner', assignment_id, user_id };
   },
+  gaRun(submission_id: number): ClientTopic { return { kind: 'ga_run', submission_id }; },
 } as const;
 
 // Derive the exact server path string (must match backend `ClientTopic::path()`)
@@ -24,5 +25,6 @@ export function topicPath(t: ClientTopic): string {
     case 'ticket_chat': return `tickets:${t.ticket_id}`;
     case 'assignment_submissions_staff': return `assignment:${t.assignment_id}.submissions:staff`;
     case 'assignment_submissions_owner': return `assignment:${t.assignment_id}.submissions:user:${t.user_id}`;
+    case 'ga_run': return `ga:${t.submission_id}`;
   }
 }
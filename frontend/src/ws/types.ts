@@ -10,7 +10,8 @@ export type ClientTopic =
   | { kind: 'attendance_session'; session_id: number }
   | { kind: 'ticket_chat'; ticket_id: number }
   | { kind: 'assignment_submissions_staff'; assignment_id: number }
-  | { kind: 'assignment_submissions_owner'; assignment_id: number; user_id: number };
+  | { kind: 'assignment_submissions_owner'; assignment_id: number; user_id: number }
+  | { kind: 'ga_run'; submission_id: number };
 
 // ---------- Frames we SEND ----------
 export type WsIn =
@@ -68,6 +69,16 @@ export type SystemHealthAdminPayload = {
   cpu: { cores: number; avg_usage: number; per_core: number[] };
   memory: { total: number; used: number; swap_total: number; swap_used: number };
   disks: Array<{ name: string; total: number; available: number; file_system: string; mount_point: string }>;
+  gpus: Array<{ index: number; name: string; utilization: number; mem_total: number; mem_used: number }>;
+  // `job` is the code_manager job id (e.g. "submission-42") for run containers
+  containers: Array<{
+    id: string;
+    name: string;
+    cpu_usage: number;
+    mem_used: number;
+    mem_limit: number;
+    job?: string | null;
+  }>;
   code_manager: { running: number; waiting: number; max_concurrent?: number | null };
 };
 
@@ -93,6 +104,21 @@ export type SubmissionNewPayload = {
   created_at: string;
 };
 
+// Sent after every GA generation of a GATLAM / code coverage run
+export type GaProgressPayload = {
+  module_id: number;
+  assignment_id: number;
+  submission_id: number;
+  generation: number; // zero-based
+  generations: number;
+  best_fitness: number;
+  mean_fitness: number;
+  coverage_percent?: number | null;
+  evaluated: number;
+  interpreter_runs: number;
+  cache_hits: number;
+};
+
 export type AttendanceSessionUpdated = {
   session_id: number;
   active: boolean;
@@ -129,6 +155,8 @@ export interface EventPayloadMap {
   'submission.status': SubmissionStatusPayload;
   'submission.new_submission': SubmissionNewPayload;
 
+  'ga.progress': GaProgressPayload;
+
   'attendance.session_updated': AttendanceSessionUpdated;
   'attendance.marked': AttendanceMarked;
   'attendance.session_deleted': AttendanceSessionDeleted;